@@ -0,0 +1,369 @@
+//! Optional help from a language model with writing rules.
+//!
+//! When an endpoint is configured, RuleWeaver can ask the model to draft a rule from a
+//! description, condense a rule that has grown too long, or merge near-duplicate rules into
+//! one. Every operation returns an [`AiRuleProposal`] with a line diff against the current
+//! text; nothing is written until the user saves the proposal as an ordinary rule change.
+//! Providers sit behind [`AiProvider`] so operations can be tested without a network and
+//! other APIs can be added next to [`openai`]. Rule search can also rank rules by meaning
+//! with an [`EmbeddingProvider`], which may use its own endpoint, such as a local model
+//! server, rather than the one that writes rules.
+
+pub mod openai;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{AiRuleProposal, AiTask, Rule};
+use crate::text_diff::{line_diff, summarize};
+
+/// Base URL of an OpenAI-compatible API, e.g. `https://api.openai.com/v1`.
+pub const AI_ENDPOINT_KEY: &str = "ai_endpoint";
+pub const AI_MODEL_KEY: &str = "ai_model";
+/// Name of the vault secret holding the API key; unset for endpoints without auth.
+pub const AI_API_KEY_SECRET_KEY: &str = "ai_api_key_secret";
+/// Base URL of the OpenAI-compatible API computing embeddings; the AI endpoint if unset.
+pub const EMBEDDING_ENDPOINT_KEY: &str = "embedding_endpoint";
+pub const EMBEDDING_MODEL_KEY: &str = "embedding_model";
+/// Vault secret holding the embedding endpoint's API key, when it has its own endpoint.
+pub const EMBEDDING_API_KEY_SECRET_KEY: &str = "embedding_api_key_secret";
+/// The secret names chosen for the settings above in this app, as a JSON array. It is
+/// never exported or synced, so a secret name that arrived with an import or from another
+/// machine is not sent to an endpoint until the user picks it here.
+pub const APPROVED_SECRETS_KEY: &str = "ai_approved_secrets";
+
+pub type Completion<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// A model that answers a prompt under a system instruction.
+pub trait AiProvider: Send + Sync {
+    fn complete<'a>(&'a self, system: &'a str, prompt: &'a str) -> Completion<'a>;
+}
+
+pub type Embeddings<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>>> + Send + 'a>>;
+
+/// A model that maps texts to vectors, close together when the texts mean similar things.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifies the model, so vectors from different models are never compared.
+    fn model(&self) -> &str;
+    /// One vector per text, in order.
+    fn embed<'a>(&'a self, texts: &'a [String]) -> Embeddings<'a>;
+}
+
+const SYSTEM_PROMPT: &str = "You write rules: Markdown instructions that AI coding assistants \
+follow in a project. Reply with the rule's Markdown only, without commentary and without \
+wrapping it in a code block.";
+
+/// The provider configured in settings.
+pub async fn provider(db: &Database) -> Result<Box<dyn AiProvider>> {
+    let settings = crate::settings::load(db).await?;
+    let (Some(endpoint), Some(model)) = (settings.ai_endpoint, settings.ai_model) else {
+        return Err(AppError::InvalidInput {
+            message: "AI assistance is not configured: set an endpoint and a model".to_string(),
+        });
+    };
+    let api_key = api_key(db, settings.ai_api_key_secret).await?;
+    Ok(Box::new(openai::OpenAiCompatible::new(
+        endpoint, model, api_key,
+    )?))
+}
+
+async fn approved_secrets(db: &Database) -> Result<Vec<String>> {
+    Ok(db
+        .get_setting(APPROVED_SECRETS_KEY)
+        .await?
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+/// Records that the user chose `name` as an API key secret on this machine.
+pub async fn approve_secret(db: &Database, name: &str) -> Result<()> {
+    let mut approved = approved_secrets(db).await?;
+    if !approved.iter().any(|approved| approved == name) {
+        approved.push(name.to_string());
+        db.set_setting(APPROVED_SECRETS_KEY, &serde_json::to_string(&approved)?)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn api_key(db: &Database, secret_name: Option<String>) -> Result<Option<String>> {
+    if let Some(name) = &secret_name {
+        if !approved_secrets(db).await?.contains(name) {
+            return Err(AppError::InvalidInput {
+                message: format!(
+                    "The API key secret '{}' was not chosen in this app's settings; choose it \
+                     again in Settings to use it",
+                    name
+                ),
+            });
+        }
+    }
+    Ok(match secret_name {
+        Some(name) => crate::secrets::resolve_secrets(db, &[name])
+            .await?
+            .pop()
+            .map(|(_, value)| value),
+        None => None,
+    })
+}
+
+/// The embedding model configured in settings. Without an embedding endpoint of its own it
+/// is served by the AI endpoint, with that endpoint's API key.
+pub async fn embedding_provider(db: &Database) -> Result<Box<dyn EmbeddingProvider>> {
+    let settings = crate::settings::load(db).await?;
+    let Some(model) = settings.embedding_model else {
+        return Err(AppError::InvalidInput {
+            message: "Semantic search is not configured: set an embedding model".to_string(),
+        });
+    };
+    let (endpoint, secret_name) = match settings.embedding_endpoint {
+        Some(endpoint) => (endpoint, settings.embedding_api_key_secret),
+        None => match settings.ai_endpoint {
+            Some(endpoint) => (endpoint, settings.ai_api_key_secret),
+            None => {
+                return Err(AppError::InvalidInput {
+                    message: "Semantic search is not configured: set an embedding endpoint"
+                        .to_string(),
+                })
+            }
+        },
+    };
+    let api_key = api_key(db, secret_name).await?;
+    Ok(Box::new(openai::OpenAiCompatible::new(
+        endpoint, model, api_key,
+    )?))
+}
+
+/// The reply without surrounding whitespace or a code block wrapped around all of it.
+fn strip_fence(reply: &str) -> &str {
+    let reply = reply.trim();
+    let Some(inner) = reply
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    else {
+        return reply;
+    };
+    // Drop the info string, e.g. "markdown", on the opening line.
+    inner
+        .split_once('\n')
+        .map_or(inner, |(_, body)| body)
+        .trim()
+}
+
+fn proposal(
+    task: AiTask,
+    source_rule_ids: Vec<String>,
+    name: String,
+    original: String,
+    reply: &str,
+) -> Result<AiRuleProposal> {
+    let proposed = strip_fence(reply).to_string();
+    if proposed.is_empty() {
+        return Err(AppError::Internal {
+            message: "The model returned an empty rule".to_string(),
+        });
+    }
+    let diff = line_diff(&original, &proposed);
+    Ok(AiRuleProposal {
+        task,
+        source_rule_ids,
+        name,
+        original,
+        summary: summarize(&diff),
+        diff,
+        proposed,
+    })
+}
+
+/// Drafts a new rule from a description. A leading `# Title` in the reply names it.
+pub async fn draft_rule(provider: &dyn AiProvider, description: &str) -> Result<AiRuleProposal> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err(AppError::Validation(
+            "Describe the rule to draft".to_string(),
+        ));
+    }
+    let prompt = format!(
+        "Write a rule for the following. Start with a level-one heading naming the rule.\n\n{}",
+        description
+    );
+    let reply = provider.complete(SYSTEM_PROMPT, &prompt).await?;
+    let mut drafted = proposal(
+        AiTask::Draft,
+        Vec::new(),
+        String::new(),
+        String::new(),
+        &reply,
+    )?;
+    drafted.name = drafted
+        .proposed
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .unwrap_or_else(|| "Drafted rule".to_string());
+    Ok(drafted)
+}
+
+/// Rewrites `rule` to fit in `max_chars` characters while keeping its instructions.
+pub async fn condense_rule(
+    provider: &dyn AiProvider,
+    rule: &Rule,
+    max_chars: usize,
+) -> Result<AiRuleProposal> {
+    let length = rule.content.chars().count();
+    if length <= max_chars {
+        return Err(AppError::Validation(format!(
+            "Rule '{}' is already {} characters, within {}",
+            rule.name, length, max_chars
+        )));
+    }
+    let prompt = format!(
+        "Condense this rule to at most {} characters. Keep every instruction; drop \
+         repetition, examples and filler.\n\n{}",
+        max_chars, rule.content
+    );
+    let reply = provider.complete(SYSTEM_PROMPT, &prompt).await?;
+    proposal(
+        AiTask::Condense,
+        vec![rule.id.clone()],
+        rule.name.clone(),
+        rule.content.clone(),
+        &reply,
+    )
+}
+
+/// Merges near-duplicate `rules` into one. The diff is against their contents joined in
+/// order, and the merged rule takes the first rule's name.
+pub async fn merge_rules(provider: &dyn AiProvider, rules: &[Rule]) -> Result<AiRuleProposal> {
+    if rules.len() < 2 {
+        return Err(AppError::Validation(
+            "Select at least two rules to merge".to_string(),
+        ));
+    }
+    let sections: Vec<String> = rules
+        .iter()
+        .map(|rule| format!("## {}\n\n{}", rule.name, rule.content))
+        .collect();
+    let prompt = format!(
+        "These rules overlap. Merge them into one rule that keeps every distinct \
+         instruction once and resolves contradictions in favour of the first rule.\n\n{}",
+        sections.join("\n\n")
+    );
+    let reply = provider.complete(SYSTEM_PROMPT, &prompt).await?;
+    let original: Vec<&str> = rules.iter().map(|rule| rule.content.as_str()).collect();
+    proposal(
+        AiTask::Merge,
+        rules.iter().map(|rule| rule.id.clone()).collect(),
+        rules[0].name.clone(),
+        original.join("\n\n"),
+        &reply,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, DiffLineKind, Metadata, Scope};
+    use chrono::Utc;
+
+    /// Replies with a fixed text and checks the prompt mentions `expect`.
+    struct Canned {
+        reply: &'static str,
+        expect: &'static str,
+    }
+
+    impl AiProvider for Canned {
+        fn complete<'a>(&'a self, _system: &'a str, prompt: &'a str) -> Completion<'a> {
+            Box::pin(async move {
+                assert!(prompt.contains(self.expect), "{}", prompt);
+                Ok(self.reply.to_string())
+            })
+        }
+    }
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule {
+            id: format!("id-{}", name.to_lowercase()),
+            name: name.to_string(),
+            description: String::new(),
+            content: content.to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Metadata::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_draft_names_the_rule_and_strips_the_fence() {
+        let provider = Canned {
+            reply: "```markdown\n# Error handling\n\nReturn `Result`s.\n```\n",
+            expect: "errors",
+        };
+        let proposal = draft_rule(&provider, "How we handle errors").await.unwrap();
+
+        assert_eq!(proposal.name, "Error handling");
+        assert_eq!(proposal.proposed, "# Error handling\n\nReturn `Result`s.");
+        assert_eq!(proposal.summary.added, 3);
+        assert!(draft_rule(&provider, "  ").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_condense_and_merge_diff_against_the_originals() {
+        let long = rule("Style", "Use tabs.\nReally, always use tabs.\nNo spaces.");
+        let provider = Canned {
+            reply: "Use tabs.",
+            expect: "at most 20 characters",
+        };
+        let proposal = condense_rule(&provider, &long, 20).await.unwrap();
+        assert_eq!(proposal.source_rule_ids, vec!["id-style"]);
+        assert_eq!(proposal.diff[0].kind, DiffLineKind::Context);
+        assert_eq!(proposal.summary.removed, 2);
+        assert!(condense_rule(&provider, &long, 1000).await.is_err());
+
+        let provider = Canned {
+            reply: "Use tabs.\nUse snake_case.",
+            expect: "## Naming",
+        };
+        let rules = [long, rule("Naming", "Use snake_case.")];
+        let proposal = merge_rules(&provider, &rules).await.unwrap();
+        assert_eq!(proposal.task, AiTask::Merge);
+        assert_eq!(proposal.name, "Style");
+        assert!(proposal.original.ends_with("No spaces.\n\nUse snake_case."));
+        assert!(merge_rules(&provider, &rules[..1]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_secret_names_not_chosen_here_are_refused() {
+        let db = Database::new_in_memory().await.unwrap();
+        // As an import from before these settings were kept local could have left them
+        db.set_setting(AI_ENDPOINT_KEY, "https://example.com/v1")
+            .await
+            .unwrap();
+        db.set_setting(AI_MODEL_KEY, "model").await.unwrap();
+        db.set_setting(AI_API_KEY_SECRET_KEY, "github_token")
+            .await
+            .unwrap();
+        match provider(&db).await {
+            Err(e) => assert!(e.to_string().contains("github_token"), "{}", e),
+            Ok(_) => panic!("an unapproved secret was used"),
+        }
+        assert!(!crate::models::is_portable_setting(AI_API_KEY_SECRET_KEY));
+        assert!(!crate::models::is_portable_setting(APPROVED_SECRETS_KEY));
+
+        crate::settings::update(&db, serde_json::json!({ "aiModel": "other" }))
+            .await
+            .unwrap();
+        assert!(approved_secrets(&db).await.unwrap().is_empty());
+        crate::settings::update(&db, serde_json::json!({ "aiApiKeySecret": "github_token" }))
+            .await
+            .unwrap();
+        assert_eq!(approved_secrets(&db).await.unwrap(), vec!["github_token"]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Provider for APIs that speak the OpenAI chat completions and embeddings protocols, which
+//! most hosted and local model servers offer.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::{AiProvider, Completion, EmbeddingProvider, Embeddings};
+use crate::error::{AppError, Result};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct OpenAiCompatible {
+    client: reqwest::Client,
+    /// Base URL without the trailing `/chat/completions` or `/embeddings`
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiCompatible {
+    pub fn new(endpoint: String, model: String, api_key: Option<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal {
+                message: format!("Failed to create HTTP client: {}", e),
+            })?;
+        Ok(Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model,
+            api_key,
+        })
+    }
+
+    async fn chat(&self, system: &str, prompt: &str) -> Result<String> {
+        let body = json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": prompt },
+            ],
+        });
+        let text = self.post("chat/completions", &body).await?;
+        reply_content(&text)
+    }
+
+    async fn embeddings(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = json!({ "model": self.model, "input": texts });
+        let text = self.post("embeddings", &body).await?;
+        embedding_vectors(&text, texts.len())
+    }
+
+    /// The body of a successful response to `body` posted to `path` under the endpoint.
+    async fn post(&self, path: &str, body: &Value) -> Result<String> {
+        let mut request = self
+            .client
+            .post(format!("{}/{}", self.endpoint, path))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await.map_err(|e| AppError::InvalidInput {
+            message: format!("Failed to reach the AI endpoint: {}", e),
+        })?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| AppError::InvalidInput {
+            message: format!("Failed to read the AI endpoint's response: {}", e),
+        })?;
+        if !status.is_success() {
+            return Err(AppError::InvalidInput {
+                message: format!("AI endpoint returned {}: {}", status, text.trim()),
+            });
+        }
+        Ok(text)
+    }
+}
+
+/// The first choice's message in a chat completions response.
+fn reply_content(body: &str) -> Result<String> {
+    let response: Value = serde_json::from_str(body)?;
+    response["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Internal {
+            message: "The AI endpoint's response has no message content".to_string(),
+        })
+}
+
+/// The vectors of an embeddings response, in the order of the inputs.
+fn embedding_vectors(body: &str, count: usize) -> Result<Vec<Vec<f32>>> {
+    let response: Value = serde_json::from_str(body)?;
+    let missing = || AppError::Internal {
+        message: "The embedding endpoint's response has no embeddings".to_string(),
+    };
+    let mut vectors = vec![None; count];
+    for (position, item) in response["data"]
+        .as_array()
+        .ok_or_else(missing)?
+        .iter()
+        .enumerate()
+    {
+        let index = item["index"]
+            .as_u64()
+            .map_or(position, |index| index as usize);
+        let vector: Vec<f32> = item["embedding"]
+            .as_array()
+            .ok_or_else(missing)?
+            .iter()
+            .filter_map(|x| x.as_f64().map(|x| x as f32))
+            .collect();
+        if let Some(slot) = vectors.get_mut(index) {
+            *slot = Some(vector);
+        }
+    }
+    vectors.into_iter().map(|v| v.ok_or_else(missing)).collect()
+}
+
+impl AiProvider for OpenAiCompatible {
+    fn complete<'a>(&'a self, system: &'a str, prompt: &'a str) -> Completion<'a> {
+        Box::pin(self.chat(system, prompt))
+    }
+}
+
+impl EmbeddingProvider for OpenAiCompatible {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> Embeddings<'a> {
+        Box::pin(self.embeddings(texts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_vectors_follow_input_order() {
+        let body = r#"{"data": [
+            {"index": 1, "embedding": [0.5, 1]},
+            {"index": 0, "embedding": [-1, 0]}
+        ]}"#;
+        assert_eq!(
+            embedding_vectors(body, 2).unwrap(),
+            vec![vec![-1.0, 0.0], vec![0.5, 1.0]]
+        );
+        assert!(embedding_vectors(body, 3).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! In-memory rolling store of backend log lines, queried by the diagnostics console.
+//!
+//! [`init`] installs a logger that keeps printing to stderr as `RUST_LOG` asks and also
+//! captures RuleWeaver's own info-and-above lines (warnings and errors from dependencies)
+//! into a bounded buffer. Components with their own log views, like the MCP server, write
+//! into the same store under a fixed module name.
+
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use parking_lot::Mutex;
+
+use crate::constants::limits::{APP_LOG_DEFAULT_PAGE, APP_LOG_LIMIT, APP_LOG_MAX_PAGE};
+use crate::models::{AppLogEntry, AppLogLevel, AppLogPage, AppLogQuery};
+
+/// Module name for lines written by the MCP server manager.
+pub const MCP_MODULE: &str = "ruleweaver_lib::mcp";
+
+static STORE: LazyLock<Mutex<LogStore>> =
+    LazyLock::new(|| Mutex::new(LogStore::new(APP_LOG_LIMIT)));
+
+pub struct LogStore {
+    entries: VecDeque<AppLogEntry>,
+    capacity: usize,
+    next_seq: u64,
+}
+
+impl LogStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+            next_seq: 1,
+        }
+    }
+
+    pub fn push(&mut self, level: AppLogLevel, module: &str, message: String) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(AppLogEntry {
+            seq: self.next_seq,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level,
+            module: module.to_string(),
+            message,
+        });
+        self.next_seq += 1;
+    }
+
+    pub fn query(&self, query: &AppLogQuery) -> AppLogPage {
+        let search = query.search.as_deref().map(str::to_lowercase);
+        let matching = self.entries.iter().rev().filter(|entry| {
+            query.level.is_none_or(|level| entry.level <= level)
+                && query
+                    .module
+                    .as_deref()
+                    .is_none_or(|module| entry.module.starts_with(module))
+                && query.since.is_none_or(|since| entry.timestamp >= since)
+                && query.before_seq.is_none_or(|seq| entry.seq < seq)
+                && search
+                    .as_deref()
+                    .is_none_or(|needle| entry.message.to_lowercase().contains(needle))
+        });
+
+        let offset = query.offset.unwrap_or(0) as usize;
+        let limit = query
+            .limit
+            .unwrap_or(APP_LOG_DEFAULT_PAGE)
+            .min(APP_LOG_MAX_PAGE) as usize;
+        let mut total = 0u32;
+        let mut entries = Vec::new();
+        for (index, entry) in matching.enumerate() {
+            total += 1;
+            if index >= offset && entries.len() < limit {
+                entries.push(entry.clone());
+            }
+        }
+        AppLogPage { entries, total }
+    }
+}
+
+/// Adds a line to the store without going through the `log` facade.
+pub fn record(level: AppLogLevel, module: &str, message: String) {
+    STORE.lock().push(level, module, message);
+}
+
+pub fn query(query: &AppLogQuery) -> AppLogPage {
+    STORE.lock().query(query)
+}
+
+struct AppLogger {
+    stderr: env_logger::Logger,
+}
+
+impl AppLogger {
+    fn captures(metadata: &Metadata<'_>) -> bool {
+        match metadata.level() {
+            Level::Error | Level::Warn => true,
+            Level::Info => metadata.target().starts_with("ruleweaver"),
+            Level::Debug | Level::Trace => false,
+        }
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        Self::captures(metadata) || self.stderr.enabled(metadata)
+    }
+
+    fn log(&self, line: &Record<'_>) {
+        let printed = self.stderr.matches(line);
+        if printed {
+            self.stderr.log(line);
+        }
+        if printed || Self::captures(line.metadata()) {
+            record(line.level().into(), line.target(), line.args().to_string());
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+/// Installs the capturing logger in place of `env_logger::init()`.
+pub fn init() {
+    let stderr = env_logger::Builder::from_default_env().build();
+    let max_level = stderr.filter().max(LevelFilter::Info);
+    if log::set_boxed_logger(Box::new(AppLogger { stderr })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled_store() -> LogStore {
+        let mut store = LogStore::new(10);
+        store.push(
+            AppLogLevel::Info,
+            "ruleweaver_lib::sync",
+            "Synced 3 files".into(),
+        );
+        store.push(
+            AppLogLevel::Warn,
+            "ruleweaver_lib::mcp",
+            "Rate limited".into(),
+        );
+        store.push(
+            AppLogLevel::Error,
+            "ruleweaver_lib::sync",
+            "Permission denied".into(),
+        );
+        store.push(AppLogLevel::Debug, "reqwest::connect", "connecting".into());
+        store
+    }
+
+    #[test]
+    fn test_query_filters_by_level_module_and_search() {
+        let store = filled_store();
+
+        let page = store.query(&AppLogQuery {
+            level: Some(AppLogLevel::Warn),
+            ..Default::default()
+        });
+        assert_eq!(page.total, 2);
+        assert_eq!(page.entries[0].message, "Permission denied");
+
+        let page = store.query(&AppLogQuery {
+            module: Some("ruleweaver_lib::sync".into()),
+            ..Default::default()
+        });
+        assert_eq!(page.total, 2);
+
+        let page = store.query(&AppLogQuery {
+            search: Some("RATE".into()),
+            ..Default::default()
+        });
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].module, "ruleweaver_lib::mcp");
+    }
+
+    #[test]
+    fn test_query_pages_newest_first() {
+        let store = filled_store();
+
+        let first = store.query(&AppLogQuery {
+            limit: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(first.total, 4);
+        assert_eq!(first.entries.len(), 2);
+        assert_eq!(first.entries[0].seq, 4);
+        assert_eq!(first.entries[1].seq, 3);
+
+        let second = store.query(&AppLogQuery {
+            limit: Some(2),
+            before_seq: Some(first.entries[1].seq),
+            ..Default::default()
+        });
+        assert_eq!(
+            second.entries.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        let offset = store.query(&AppLogQuery {
+            offset: Some(3),
+            ..Default::default()
+        });
+        assert_eq!(offset.entries.len(), 1);
+        assert_eq!(offset.entries[0].seq, 1);
+    }
+
+    #[test]
+    fn test_store_drops_oldest_lines_beyond_capacity() {
+        let mut store = LogStore::new(3);
+        for i in 0..5 {
+            store.push(AppLogLevel::Info, "ruleweaver_lib", format!("line {}", i));
+        }
+
+        let page = store.query(&AppLogQuery::default());
+        assert_eq!(page.total, 3);
+        assert_eq!(page.entries[0].message, "line 4");
+        assert_eq!(page.entries[2].message, "line 2");
+        assert_eq!(page.entries[2].seq, 3);
+    }
+}
@@ -0,0 +1,354 @@
+//! Files kept from command runs.
+//!
+//! Commands list glob patterns relative to their working directory. After a run the
+//! matching files are copied to `<app data>/artifacts/<execution log id>/`, keeping their
+//! relative paths, and the log entry records that directory. Copies are deleted once they
+//! are older than the artifact retention setting or their log entry is removed.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+use glob::{MatchOptions, Pattern};
+use walkdir::WalkDir;
+
+use crate::constants::limits::{
+    DEFAULT_ARTIFACT_RETENTION_MAX_AGE_DAYS, MAX_ARTIFACT_FILES, MAX_ARTIFACT_TOTAL_BYTES,
+};
+use crate::database::{default_app_data_dir, Database};
+use crate::error::{AppError, Result};
+use crate::log_retention::retention_setting;
+use crate::models::ExecutionArtifact;
+
+pub const ARTIFACT_RETENTION_MAX_AGE_DAYS_KEY: &str = "artifact_retention_max_age_days";
+
+/// Directories this recent may belong to a capture still in progress.
+const ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+pub fn validate_artifact_globs(globs: &[String]) -> Result<()> {
+    for glob in globs {
+        let trimmed = glob.trim();
+        let path = Path::new(trimmed);
+        if trimmed.is_empty()
+            || path.is_absolute()
+            || path.components().any(|c| matches!(c, Component::ParentDir))
+        {
+            return Err(AppError::Validation(format!(
+                "Artifact globs must be relative paths inside the working directory: '{}'",
+                glob
+            )));
+        }
+        Pattern::new(trimmed).map_err(|e| {
+            AppError::Validation(format!("Invalid artifact glob '{}': {}", glob, e))
+        })?;
+    }
+    Ok(())
+}
+
+pub fn artifacts_root() -> Result<PathBuf> {
+    Ok(default_app_data_dir()?.join("artifacts"))
+}
+
+/// The leading components of `glob` without wildcards; only that subtree is searched.
+fn literal_prefix(glob: &str) -> PathBuf {
+    glob.split('/')
+        .take_while(|part| !part.contains(['*', '?', '[']))
+        .collect()
+}
+
+/// `relative` with `/` separators, the form globs are matched against.
+fn slash_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Copies the regular files under `base` matching any of `globs` into `dest`. Symlinks
+/// and anything resolving outside `base` are skipped, as are files past the size caps.
+fn copy_matching(base: &Path, globs: &[String], dest: &Path) -> Result<Vec<ExecutionArtifact>> {
+    let base = base.canonicalize()?;
+    let mut seen = HashSet::new();
+    let mut artifacts = Vec::new();
+    let mut total_bytes = 0u64;
+    let mut skipped = 0usize;
+
+    for glob in globs.iter().map(|g| g.trim()) {
+        let Ok(pattern) = Pattern::new(glob) else {
+            continue;
+        };
+        let Ok(root) = base.join(literal_prefix(glob)).canonicalize() else {
+            continue;
+        };
+        if !root.starts_with(&base) {
+            continue;
+        }
+
+        for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(&base) else {
+                continue;
+            };
+            let name = slash_path(relative);
+            if !pattern.matches_with(&name, MATCH_OPTIONS) || !seen.insert(name.clone()) {
+                continue;
+            }
+
+            let size_bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if artifacts.len() >= MAX_ARTIFACT_FILES
+                || total_bytes + size_bytes > MAX_ARTIFACT_TOTAL_BYTES
+            {
+                skipped += 1;
+                continue;
+            }
+
+            let target = dest.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(entry.path(), &target)?;
+            total_bytes += size_bytes;
+            artifacts.push(ExecutionArtifact {
+                name,
+                path: target.to_string_lossy().to_string(),
+                size_bytes,
+            });
+        }
+    }
+
+    if skipped > 0 {
+        log::warn!(
+            "Skipped {} artifact files over the limit of {} files or {} bytes",
+            skipped,
+            MAX_ARTIFACT_FILES,
+            MAX_ARTIFACT_TOTAL_BYTES
+        );
+    }
+    Ok(artifacts)
+}
+
+/// Copies the files matching `globs` under `base` (the app's cwd when `None`) and links
+/// them to the execution log entry `log_id`. Returns how many files were kept.
+pub async fn capture(
+    db: &Database,
+    log_id: &str,
+    base: Option<&Path>,
+    globs: &[String],
+) -> Result<usize> {
+    if globs.is_empty() {
+        return Ok(0);
+    }
+    let base = match base {
+        Some(base) => base.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let dest = artifacts_root()?.join(log_id);
+
+    let globs = globs.to_vec();
+    let copy_dest = dest.clone();
+    let artifacts = tokio::task::spawn_blocking(move || {
+        let copied = copy_matching(&base, &globs, &copy_dest);
+        if !matches!(&copied, Ok(artifacts) if !artifacts.is_empty()) {
+            let _ = fs::remove_dir_all(&copy_dest);
+        }
+        copied
+    })
+    .await
+    .map_err(|e| AppError::Internal {
+        message: e.to_string(),
+    })??;
+
+    if !artifacts.is_empty() {
+        db.set_execution_artifacts_dir(log_id, &dest.to_string_lossy())
+            .await?;
+    }
+    Ok(artifacts.len())
+}
+
+/// Lists the files kept for an execution log entry.
+pub async fn get_artifacts(db: &Database, log_id: &str) -> Result<Vec<ExecutionArtifact>> {
+    let Some(dir) = db.get_execution_artifacts_dir(log_id).await? else {
+        return Ok(Vec::new());
+    };
+    let dir = PathBuf::from(dir);
+
+    let mut artifacts: Vec<ExecutionArtifact> = WalkDir::new(&dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(&dir).ok()?;
+            Some(ExecutionArtifact {
+                name: slash_path(relative),
+                path: entry.path().to_string_lossy().to_string(),
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(artifacts)
+}
+
+/// Deletes artifacts past the retention age and directories whose log entry is gone.
+/// Returns how many execution directories were removed.
+pub async fn prune_artifacts(db: &Database, root: &Path) -> Result<usize> {
+    let mut removed = 0;
+
+    let max_age_days = retention_setting(
+        db,
+        ARTIFACT_RETENTION_MAX_AGE_DAYS_KEY,
+        DEFAULT_ARTIFACT_RETENTION_MAX_AGE_DAYS,
+    )
+    .await;
+    if let Some(days) = max_age_days {
+        let cutoff = Utc::now().timestamp() - (days as i64) * 24 * 60 * 60;
+        for dir in db.take_expired_artifact_dirs(cutoff).await? {
+            if fs::remove_dir_all(&dir).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+
+    let live = db.get_execution_ids_with_artifacts().await?;
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(removed);
+    };
+    let grace_cutoff = SystemTime::now() - ORPHAN_GRACE_PERIOD;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let settled = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < grace_cutoff);
+        if entry.path().is_dir()
+            && settled
+            && !live.contains(&name)
+            && fs::remove_dir_all(entry.path()).is_ok()
+        {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn globs_must_stay_inside_the_working_directory() {
+        assert!(validate_artifact_globs(&["coverage/**".to_string()]).is_ok());
+        assert!(validate_artifact_globs(&["../outside/*".to_string()]).is_err());
+        assert!(validate_artifact_globs(&["/etc/passwd".to_string()]).is_err());
+        assert!(validate_artifact_globs(&["dist/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn matching_files_are_copied_with_their_relative_paths() {
+        let work = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        write(&work.path().join("coverage/lcov.info"), "lcov");
+        write(&work.path().join("coverage/html/index.html"), "<html>");
+        write(&work.path().join("dist/report.html"), "report");
+        write(&work.path().join("dist/app.js"), "js");
+
+        let globs = vec!["coverage/**".to_string(), "dist/report.html".to_string()];
+        let mut artifacts = copy_matching(work.path(), &globs, dest.path()).unwrap();
+        artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let names: Vec<&str> = artifacts.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "coverage/html/index.html",
+                "coverage/lcov.info",
+                "dist/report.html"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dest.path().join("dist/report.html")).unwrap(),
+            "report"
+        );
+        assert!(!dest.path().join("dist/app.js").exists());
+    }
+
+    #[tokio::test]
+    async fn artifacts_are_listed_for_their_log_entry() {
+        let db = Database::new_in_memory().await.unwrap();
+        let log_id = db
+            .add_execution_log(&crate::database::ExecutionLogInput {
+                command_id: "cmd",
+                command_name: "Build",
+                arguments_json: "{}",
+                stdout: "",
+                stderr: "",
+                exit_code: 0,
+                duration_ms: 1,
+                triggered_by: "test",
+                failure_class: None,
+                adapter_context: None,
+                is_redacted: false,
+                attempt_number: 1,
+                structured_output: None,
+                environment: None,
+            })
+            .await
+            .unwrap();
+        assert!(get_artifacts(&db, &log_id).await.unwrap().is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("dist/report.html"), "report");
+        db.set_execution_artifacts_dir(&log_id, &dir.path().to_string_lossy())
+            .await
+            .unwrap();
+
+        let artifacts = get_artifacts(&db, &log_id).await.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].name, "dist/report.html");
+        assert_eq!(artifacts[0].size_bytes, 6);
+
+        let expired = db
+            .take_expired_artifact_dirs(Utc::now().timestamp() + 60)
+            .await
+            .unwrap();
+        assert_eq!(expired, [dir.path().to_string_lossy().to_string()]);
+        assert!(get_artifacts(&db, &log_id).await.unwrap().is_empty());
+        assert!(get_artifacts(&db, "missing").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_not_followed() {
+        let work = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        write(&outside.path().join("secret.txt"), "secret");
+        std::os::unix::fs::symlink(outside.path(), work.path().join("linked")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            work.path().join("secret.txt"),
+        )
+        .unwrap();
+
+        let globs = vec!["linked/**".to_string(), "*.txt".to_string()];
+        assert!(copy_matching(work.path(), &globs, dest.path())
+            .unwrap()
+            .is_empty());
+    }
+}
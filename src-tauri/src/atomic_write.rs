@@ -0,0 +1,321 @@
+//! Crash-safe file replacement for everything RuleWeaver generates.
+//!
+//! Content goes to a temp file next to the target, is flushed to disk and then renamed over
+//! the target, so a crash mid-sync leaves either the old file or the new one, never half of
+//! each. Keeping the temp file in the target's directory keeps the rename on one volume,
+//! which is what makes it atomic on Windows as well as on Unix. [`WriteBatch`] extends this
+//! to several files that must change together, such as all of one tool's files in a sync.
+//! A target that is a symlink, such as a tool file kept in a dotfiles repository, has the
+//! file it points to replaced, so the link survives.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, Result};
+
+/// How often a rename is retried on Windows while another process holds the target open.
+#[cfg(windows)]
+const RENAME_ATTEMPTS: u32 = 5;
+#[cfg(windows)]
+const RENAME_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+
+fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| AppError::InvalidInput {
+        message: format!("Not a file path: {}", path.display()),
+    })?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+    Ok(path.with_file_name(temp_name))
+}
+
+/// The file `path` stands for: the file a symlink points to, followed through any chain of
+/// links, or `path` itself. A dangling link resolves to where it points, so the write
+/// creates that file.
+fn resolve_target(path: &Path) -> PathBuf {
+    let is_link = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    if !is_link {
+        return path.to_path_buf();
+    }
+    if let Ok(resolved) = fs::canonicalize(path) {
+        return resolved;
+    }
+    match fs::read_link(path) {
+        Ok(link) => path
+            .parent()
+            .map_or(link.clone(), |parent| parent.join(link)),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Windows refuses to replace a file that an editor, indexer or antivirus scanner has open
+/// without delete sharing; those handles are usually short-lived.
+#[cfg(windows)]
+fn rename_replacing(from: &Path, to: &Path) -> io::Result<()> {
+    let mut attempt = 1;
+    loop {
+        match fs::rename(from, to) {
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied && attempt < RENAME_ATTEMPTS => {
+                std::thread::sleep(RENAME_RETRY_DELAY * attempt);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn rename_replacing(from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to)
+}
+
+/// Makes the rename itself durable. Directories cannot be opened as files on Windows, where
+/// the rename is already journaled with the file.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// Atomically replaces `path` with `content`, keeping the permissions of the file it
+/// replaces. The parent directory must already exist.
+pub fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    let path = &resolve_target(path);
+    let temp_path = temp_path_for(path)?;
+
+    let written = (|| -> io::Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(content.as_ref())?;
+        file.sync_all()?;
+        if let Ok(existing) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, existing.permissions())?;
+        }
+        rename_replacing(&temp_path, path)
+    })();
+
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(AppError::Io(e));
+    }
+
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// A file staged by a [`WriteBatch`], with what it replaces.
+struct StagedFile {
+    temp: PathBuf,
+    target: PathBuf,
+    /// Content of the target before the batch, `None` when it did not exist
+    previous: Option<Vec<u8>>,
+}
+
+/// Files replaced together. Each is written to a temp file next to its target when staged;
+/// [`commit`](WriteBatch::commit) renames them all into place, and if one rename fails it
+/// puts back the targets already replaced. Temp files of a batch that is dropped without
+/// committing are removed, so a failure while staging leaves every target untouched.
+#[derive(Default)]
+pub struct WriteBatch {
+    staged: Vec<StagedFile>,
+}
+
+impl WriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes `content` to a temp file next to `path`, keeping the permissions of the file
+    /// it will replace. The parent directory must already exist.
+    pub fn stage(&mut self, path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+        let path = &resolve_target(path);
+        let previous = match fs::read(path) {
+            Ok(previous) => Some(previous),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(AppError::Io(e)),
+        };
+        let temp_path = temp_path_for(path)?;
+        let written = (|| -> io::Result<()> {
+            let mut file = File::create(&temp_path)?;
+            file.write_all(content.as_ref())?;
+            file.sync_all()?;
+            if let Ok(existing) = fs::metadata(path) {
+                fs::set_permissions(&temp_path, existing.permissions())?;
+            }
+            Ok(())
+        })();
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(AppError::Io(e));
+        }
+        self.staged.push(StagedFile {
+            temp: temp_path,
+            target: path.to_path_buf(),
+            previous,
+        });
+        Ok(())
+    }
+
+    /// Moves every staged file into place, or none: when a rename fails, the targets
+    /// already replaced get their previous content back and the error is returned.
+    pub fn commit(mut self) -> Result<()> {
+        let staged = std::mem::take(&mut self.staged);
+        for (i, file) in staged.iter().enumerate() {
+            if let Err(e) = rename_replacing(&file.temp, &file.target) {
+                for done in staged[..i].iter().rev() {
+                    restore(done);
+                }
+                for pending in &staged[i..] {
+                    let _ = fs::remove_file(&pending.temp);
+                }
+                return Err(AppError::Io(io::Error::new(
+                    e.kind(),
+                    format!("Failed to replace {}: {}", file.target.display(), e),
+                )));
+            }
+            sync_parent_dir(&file.target);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WriteBatch {
+    fn drop(&mut self) {
+        for file in &self.staged {
+            let _ = fs::remove_file(&file.temp);
+        }
+    }
+}
+
+/// Puts back what a committed file replaced.
+fn restore(file: &StagedFile) {
+    let restored = match &file.previous {
+        Some(previous) => write_atomic(&file.target, previous),
+        None => fs::remove_file(&file.target).map_err(AppError::Io),
+    };
+    if let Err(e) = restored {
+        log::error!(
+            "Failed to roll back {}; it keeps the new content: {}",
+            file.target.display(),
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_content_and_leaves_no_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CLAUDE.md");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, b"second".as_slice()).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        let entries: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_write_atomic_failure_keeps_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory in the way makes the rename fail after the temp file was written.
+        let path = dir.path().join("blocked");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep.md"), "kept").unwrap();
+
+        assert!(write_atomic(&path, "new").is_err());
+        assert_eq!(fs::read_to_string(path.join("keep.md")).unwrap(), "kept");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        fs::write(&path, "echo old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        write_atomic(&path, "echo new").unwrap();
+
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+    }
+
+    #[test]
+    fn test_write_batch_rolls_back_when_a_rename_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("AGENTS.md");
+        let created = dir.path().join("GEMINI.md");
+        let blocked = dir.path().join("CLAUDE.md");
+        fs::write(&existing, "old").unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch.stage(&existing, "new").unwrap();
+        batch.stage(&created, "new").unwrap();
+        batch.stage(&blocked, "new").unwrap();
+        // A directory that appears after staging makes the last rename fail.
+        fs::create_dir(&blocked).unwrap();
+        fs::write(blocked.join("keep.md"), "kept").unwrap();
+
+        assert!(batch.commit().is_err());
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "old");
+        assert!(!created.exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_write_batch_dropped_before_commit_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("AGENTS.md");
+        fs::write(&path, "old").unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch.stage(&path, "new").unwrap();
+        drop(batch);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let mut batch = WriteBatch::new();
+        batch.stage(&path, "new").unwrap();
+        batch.commit().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_targets_keep_their_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let dotfiles = dir.path().join("dotfiles");
+        fs::create_dir(&dotfiles).unwrap();
+        let real = dotfiles.join("CLAUDE.md");
+        fs::write(&real, "old").unwrap();
+        let link = dir.path().join("CLAUDE.md");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write_atomic(&link, "first").unwrap();
+        assert!(link.is_symlink());
+        assert_eq!(fs::read_to_string(&real).unwrap(), "first");
+
+        let mut batch = WriteBatch::new();
+        batch.stage(&link, "second").unwrap();
+        batch.commit().unwrap();
+        assert!(link.is_symlink());
+        assert_eq!(fs::read_to_string(&real).unwrap(), "second");
+        assert_eq!(fs::read_dir(&dotfiles).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,78 @@
+//! Activity timeline of every change to rules, commands, skills and workflows, and of every
+//! import, sync and reconcile run.
+//!
+//! The actor is carried as a task-local so the database can attribute a change without
+//! every call site passing it along. Work runs as the UI unless an entry point says
+//! otherwise with [`scope`]: the MCP server, the rule file watcher, the CLI and the
+//! background jobs that change data each wrap their work. Tasks spawned inside a scope do
+//! not inherit it and need their own.
+
+use std::future::Future;
+
+use crate::database::Database;
+use crate::models::{AuditAction, AuditActor, AuditActorKind};
+
+tokio::task_local! {
+    static ACTOR: AuditActor;
+}
+
+/// The actor for work on the current task.
+pub fn current_actor() -> AuditActor {
+    ACTOR
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| AuditActor::new(AuditActorKind::Ui))
+}
+
+/// Runs `future` with changes attributed to `actor`.
+pub async fn scope<F: Future>(actor: AuditActor, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+/// Records a run that is not tied to one artifact, such as a sync. Failing to record is
+/// logged rather than failing the run.
+pub async fn record_run(db: &Database, action: AuditAction, summary: String) {
+    if let Err(e) = db
+        .add_audit_entry(current_actor(), action, None, summary)
+        .await
+    {
+        log::warn!(
+            "Failed to record {} in the audit log: {}",
+            action.as_str(),
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AuditLogQuery;
+
+    #[tokio::test]
+    async fn test_scope_sets_the_actor() {
+        assert_eq!(current_actor().kind, AuditActorKind::Ui);
+        let actor = scope(
+            AuditActor::with_detail(AuditActorKind::Mcp, "claude-code"),
+            async { current_actor() },
+        )
+        .await;
+        assert_eq!(actor.kind, AuditActorKind::Mcp);
+        assert_eq!(actor.detail.as_deref(), Some("claude-code"));
+    }
+
+    #[tokio::test]
+    async fn test_record_run_uses_the_current_actor() {
+        let db = Database::new_in_memory().await.unwrap();
+        scope(
+            AuditActor::new(AuditActorKind::Cli),
+            record_run(&db, AuditAction::Sync, "3 files written".to_string()),
+        )
+        .await;
+
+        let page = db.query_audit_log(AuditLogQuery::default()).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].actor.kind, AuditActorKind::Cli);
+        assert_eq!(page.entries[0].action, AuditAction::Sync);
+        assert!(page.entries[0].target.is_none());
+    }
+}
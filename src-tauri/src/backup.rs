@@ -0,0 +1,305 @@
+//! Database backups.
+//!
+//! Manual backups and restores use SQLite's online backup API through [`Database`], so
+//! they are safe while the app is running. Optional automatic backups are written daily
+//! into `<app data>/backups` and rotated down to a configurable retention count.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::limits::DEFAULT_BACKUP_RETENTION;
+use crate::constants::timing::{AUTO_BACKUP_CHECK_INTERVAL, AUTO_BACKUP_INTERVAL};
+use crate::database::Database;
+use crate::error::Result;
+
+pub const AUTO_BACKUP_ENABLED_KEY: &str = "auto_backup_enabled";
+pub const AUTO_BACKUP_RETENTION_KEY: &str = "auto_backup_retention";
+pub const AUTO_BACKUP_LAST_AT_KEY: &str = "auto_backup_last_at";
+
+const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_FILE_PREFIX: &str = "ruleweaver-backup-";
+const PRE_MIGRATION_FILE_PREFIX: &str = "ruleweaver-pre-migration-";
+const BACKUP_FILE_EXTENSION: &str = "db";
+
+/// A backup or export snapshot file on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl BackupInfo {
+    pub(crate) fn from_path(path: &Path) -> Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let created_at = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        Ok(Self {
+            path: path.to_string_lossy().to_string(),
+            size_bytes: metadata.len(),
+            created_at,
+        })
+    }
+}
+
+pub fn backup_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(BACKUP_DIR_NAME)
+}
+
+fn is_timestamped_file(path: &Path, prefix: &str, extension: &str) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    name.starts_with(prefix) && path.extension().and_then(|e| e.to_str()) == Some(extension)
+}
+
+/// Builds a `<prefix><UTC timestamp>.<extension>` file name that sorts chronologically.
+pub(crate) fn timestamped_file_name(prefix: &str, extension: &str) -> String {
+    format!(
+        "{}{}.{}",
+        prefix,
+        Utc::now().format("%Y%m%d-%H%M%S%3f"),
+        extension
+    )
+}
+
+/// Lists files named by [`timestamped_file_name`] in `dir`, newest first.
+pub(crate) fn list_timestamped_files(
+    dir: &Path,
+    prefix: &str,
+    extension: &str,
+) -> Result<Vec<BackupInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_timestamped_file(path, prefix, extension))
+        .collect();
+    // Timestamped file names sort chronologically.
+    paths.sort();
+    paths.reverse();
+
+    paths
+        .iter()
+        .map(|path| BackupInfo::from_path(path))
+        .collect()
+}
+
+/// Deletes all but the `keep` newest timestamped files in `dir`, returning how many were
+/// removed.
+pub(crate) fn prune_timestamped_files(
+    dir: &Path,
+    prefix: &str,
+    extension: &str,
+    keep: usize,
+) -> Result<usize> {
+    let files = list_timestamped_files(dir, prefix, extension)?;
+    let mut removed = 0;
+    for file in files.iter().skip(keep) {
+        match std::fs::remove_file(&file.path) {
+            Ok(()) => removed += 1,
+            Err(e) => log::warn!("Failed to prune {}: {}", file.path, e),
+        }
+    }
+    Ok(removed)
+}
+
+/// Writes a timestamped backup into `dir`.
+pub async fn create_backup(db: &Database, dir: &Path) -> Result<BackupInfo> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(timestamped_file_name(
+        BACKUP_FILE_PREFIX,
+        BACKUP_FILE_EXTENSION,
+    ));
+    db.backup_to(path.clone()).await?;
+    BackupInfo::from_path(&path)
+}
+
+/// Lists backups in `dir`, newest first, including those taken before schema upgrades.
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>> {
+    let mut backups = list_timestamped_files(dir, BACKUP_FILE_PREFIX, BACKUP_FILE_EXTENSION)?;
+    backups.extend(list_timestamped_files(
+        dir,
+        PRE_MIGRATION_FILE_PREFIX,
+        BACKUP_FILE_EXTENSION,
+    )?);
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Path for a backup of a schema-`from_version` database about to be upgraded. These are
+/// kept apart from regular backups so auto-backup rotation never removes them.
+pub(crate) fn pre_migration_backup_path(dir: &Path, from_version: i32) -> PathBuf {
+    dir.join(timestamped_file_name(
+        PRE_MIGRATION_FILE_PREFIX,
+        &format!("v{}.{}", from_version, BACKUP_FILE_EXTENSION),
+    ))
+}
+
+/// Deletes all but the `keep` newest pre-migration backups in `dir`.
+pub(crate) fn prune_pre_migration_backups(dir: &Path, keep: usize) -> Result<usize> {
+    prune_timestamped_files(dir, PRE_MIGRATION_FILE_PREFIX, BACKUP_FILE_EXTENSION, keep)
+}
+
+/// Deletes all but the `keep` newest backups in `dir`, returning how many were removed.
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<usize> {
+    prune_timestamped_files(dir, BACKUP_FILE_PREFIX, BACKUP_FILE_EXTENSION, keep)
+}
+
+async fn retention(db: &Database) -> usize {
+    db.get_setting(AUTO_BACKUP_RETENTION_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_BACKUP_RETENTION)
+}
+
+/// Takes an automatic backup when enabled and the last one is older than a day.
+pub async fn run_auto_backup_if_due(db: &Database, dir: &Path) -> Result<Option<BackupInfo>> {
+    if !crate::settings::load(db).await?.auto_backup_enabled {
+        return Ok(None);
+    }
+
+    let now = Utc::now().timestamp();
+    let last = db
+        .get_setting(AUTO_BACKUP_LAST_AT_KEY)
+        .await?
+        .and_then(|v| v.parse::<i64>().ok());
+    if let Some(last) = last {
+        if now - last < AUTO_BACKUP_INTERVAL.as_secs() as i64 {
+            return Ok(None);
+        }
+    }
+
+    let info = create_backup(db, dir).await?;
+    db.set_setting(AUTO_BACKUP_LAST_AT_KEY, &now.to_string())
+        .await?;
+
+    let removed = prune_backups(dir, retention(db).await)?;
+    log::info!(
+        "Automatic database backup written to {} ({} old backup(s) pruned)",
+        info.path,
+        removed
+    );
+    Ok(Some(info))
+}
+
+/// Background loop that checks hourly whether an automatic backup is due.
+pub async fn run_auto_backup_loop(db: Arc<Database>, dir: PathBuf) {
+    loop {
+        if let Err(e) = run_auto_backup_if_due(&db, &dir).await {
+            log::error!("Automatic database backup failed: {}", e);
+        }
+        tokio::time::sleep(AUTO_BACKUP_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, Scope};
+
+    fn rule_input(name: &str) -> CreateRuleInput {
+        CreateRuleInput {
+            id: None,
+            name: name.to_string(),
+            description: String::new(),
+            content: "content".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = Database::new_in_memory().await.unwrap();
+        source.create_rule(rule_input("Backed up")).await.unwrap();
+
+        let info = create_backup(&source, dir.path()).await.unwrap();
+        assert!(info.size_bytes > 0);
+
+        let target = Database::new_in_memory().await.unwrap();
+        target.create_rule(rule_input("Replaced")).await.unwrap();
+        target
+            .restore_from(PathBuf::from(&info.path))
+            .await
+            .unwrap();
+
+        let rules = target.get_all_rules().await.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name, "Backed up");
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_non_database_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let bogus = dir.path().join("not-a-db.db");
+        std::fs::write(&bogus, "definitely not sqlite").unwrap();
+
+        let db = Database::new_in_memory().await.unwrap();
+        db.create_rule(rule_input("Keep me")).await.unwrap();
+        assert!(db.restore_from(bogus).await.is_err());
+        assert_eq!(db.get_all_rules().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_prune_backups_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for stamp in [
+            "20260101-000000000",
+            "20260102-000000000",
+            "20260103-000000000",
+        ] {
+            let name = format!("{}{}.{}", BACKUP_FILE_PREFIX, stamp, BACKUP_FILE_EXTENSION);
+            std::fs::write(dir.path().join(name), "x").unwrap();
+        }
+        std::fs::write(dir.path().join("unrelated.db"), "x").unwrap();
+
+        assert_eq!(prune_backups(dir.path(), 2).unwrap(), 1);
+        let remaining = list_backups(dir.path()).unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining[0].path.contains("20260103"));
+        assert!(dir.path().join("unrelated.db").exists());
+    }
+
+    #[tokio::test]
+    async fn test_auto_backup_respects_setting_and_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_in_memory().await.unwrap();
+
+        assert!(run_auto_backup_if_due(&db, dir.path())
+            .await
+            .unwrap()
+            .is_none());
+
+        db.set_setting(AUTO_BACKUP_ENABLED_KEY, "true")
+            .await
+            .unwrap();
+        assert!(run_auto_backup_if_due(&db, dir.path())
+            .await
+            .unwrap()
+            .is_some());
+        // A second check inside the interval is a no-op.
+        assert!(run_auto_backup_if_due(&db, dir.path())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(list_backups(dir.path()).unwrap().len(), 1);
+    }
+}
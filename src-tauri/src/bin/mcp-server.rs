@@ -7,13 +7,13 @@ struct Args {
     #[arg(short, long, default_value_t = 8080)]
     port: u16,
 
-    /// API token for authentication (optional, generated if not provided)
+    /// API token for authentication (optional, defaults to the token stored by the app)
     #[arg(short, long)]
     token: Option<String>,
 }
 
 fn main() {
-    env_logger::init();
+    ruleweaver_lib::init_logging();
     log::info!("MCP Server starting up");
 
     let args = Args::parse();
@@ -0,0 +1,142 @@
+//! Change events for rules, commands, skills and workflows.
+//!
+//! Every change the database makes to one of them is published here, whichever path made
+//! it: a Tauri command, the file watcher, the MCP server, an import or cloud sync. The app
+//! forwards each to the webview as `<artifact>-<change>`, such as `rule-updated` or
+//! `command-deleted`, with a [`ChangeEvent`] payload:
+//!
+//! ```json
+//! { "version": 1, "artifact": "rule", "change": "updated", "id": "…" }
+//! ```
+//!
+//! so lists can update the one item instead of fetching everything again. `version` is
+//! [`CONTRACT_VERSION`]; fields may be added without bumping it, but renaming or removing
+//! one, or changing what an event means, bumps it. Listeners that fall too far behind miss
+//! events and get [`CHANGES_DROPPED_EVENT`] instead, after which they should reload.
+
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use tokio::sync::broadcast;
+
+use crate::models::{AuditAction, AuditTargetType};
+
+pub const CONTRACT_VERSION: u32 = 1;
+
+/// Emitted when change events were dropped because the webview fell behind.
+pub const CHANGES_DROPPED_EVENT: &str = "artifact-changes-dropped";
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+static CHANNEL: LazyLock<broadcast::Sender<ChangeEvent>> =
+    LazyLock::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    /// The change an audited action made, if it changed an artifact.
+    pub fn of_action(action: AuditAction) -> Option<Self> {
+        match action {
+            AuditAction::Create => Some(Self::Created),
+            AuditAction::Update | AuditAction::Toggle => Some(Self::Updated),
+            AuditAction::Delete => Some(Self::Deleted),
+            AuditAction::Import | AuditAction::Sync | AuditAction::Reconcile => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    pub version: u32,
+    pub artifact: AuditTargetType,
+    pub change: ChangeKind,
+    pub id: String,
+}
+
+impl ChangeEvent {
+    pub fn new(artifact: AuditTargetType, change: ChangeKind, id: &str) -> Self {
+        Self {
+            version: CONTRACT_VERSION,
+            artifact,
+            change,
+            id: id.to_string(),
+        }
+    }
+
+    /// The name the webview listens for, such as `rule-updated`.
+    pub fn name(&self) -> String {
+        format!("{}-{}", self.artifact.as_str(), self.change.as_str())
+    }
+}
+
+/// Publishes `event` to every subscriber. Nobody listening, as in the CLI, is not an error.
+pub fn publish(event: ChangeEvent) {
+    let _ = CHANNEL.send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<ChangeEvent> {
+    CHANNEL.subscribe()
+}
+
+/// Forwards change events to the webview for as long as the app runs.
+pub async fn forward_to_webview(app: tauri::AppHandle) {
+    let mut events = subscribe();
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                let _ = app.emit(&event.name(), &event);
+            }
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                log::warn!("Dropped {} change event(s) for the webview", missed);
+                let _ = app.emit(CHANGES_DROPPED_EVENT, missed);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_shape_is_stable() {
+        let event = ChangeEvent::new(AuditTargetType::Command, ChangeKind::Deleted, "cmd-1");
+        assert_eq!(event.name(), "command-deleted");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "version": 1, "artifact": "command", "change": "deleted", "id": "cmd-1" })
+        );
+        let parsed: ChangeEvent = serde_json::from_value(
+            json!({ "version": 1, "artifact": "rule", "change": "created", "id": "r" }),
+        )
+        .unwrap();
+        assert_eq!(parsed.name(), "rule-created");
+    }
+
+    #[test]
+    fn test_only_artifact_changes_are_published() {
+        assert_eq!(
+            ChangeKind::of_action(AuditAction::Toggle),
+            Some(ChangeKind::Updated)
+        );
+        assert_eq!(ChangeKind::of_action(AuditAction::Sync), None);
+    }
+}
@@ -0,0 +1,1052 @@
+//! Headless subcommands of the `ruleweaver` binary, for scripts and CI.
+//!
+//! `ruleweaver rules list|create|update|delete`, `ruleweaver sync`, `ruleweaver reconcile`,
+//! `ruleweaver import` and `ruleweaver export` run against the same database as the GUI and
+//! go through the same code paths as its commands. With `--json` results are printed as JSON, including errors, which are printed
+//! as `{"error": "..."}`.
+//!
+//! Exit codes: 0 on success, 1 when the operation failed, 2 for invalid arguments and 3 when
+//! a sync, reconcile or import finished but reported errors or conflicts.
+
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+
+use crate::commands::{
+    apply_configuration, create_rule_and_sync, delete_rule_and_sync, fetch_rules,
+    read_configuration_file, update_rule_and_sync, write_configuration_file,
+};
+use crate::database::Database;
+use crate::doctor::{self, DoctorContext, DoctorStatus};
+use crate::error::{AppError, Result};
+use crate::git_hooks::{self, HookAction};
+use crate::models::{
+    AdapterType, AuditActor, AuditActorKind, CreateRuleInput, ExportFilter, ExportFormat,
+    ExportIntegrity, ImportConflictMode, ImportExecutionOptions, ImportExecutionResult, ImportMode,
+    Rule, Scope, UpdateRuleInput,
+};
+use crate::reconciliation::lock::{self, LockDriftKind};
+use crate::reconciliation::{repo_roots, ReconcileFilter, ReconciliationEngine};
+use crate::rule_import::{self, archive, mapping};
+use crate::rule_order;
+use crate::sync::SyncEngine;
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_INCOMPLETE: i32 = 3;
+
+/// First arguments that select the CLI instead of the GUI.
+const CLI_COMMANDS: &[&str] = &[
+    "rules",
+    "sync",
+    "reconcile",
+    "import",
+    "export",
+    "doctor",
+    "hooks",
+    "lock",
+    "verify",
+    "help",
+    "--help",
+    "-h",
+    "--version",
+    "-V",
+];
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "ruleweaver",
+    version,
+    about = "Manage RuleWeaver rules and sync them to AI tools without the GUI"
+)]
+struct Cli {
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// List, create, update or delete rules
+    Rules {
+        #[command(subcommand)]
+        action: RulesCommand,
+    },
+    /// Write all rules to the files of their enabled AI tools
+    Sync {
+        /// Report what would be written without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Create, update and remove generated files so they match the database
+    Reconcile {
+        /// Report what would change without touching any file
+        #[arg(long)]
+        dry_run: bool,
+        /// Only reconcile this registered repository's generated files
+        #[arg(long, value_name = "PATH")]
+        repo: Option<PathBuf>,
+        /// Like --dry-run, but exit with 3 when any file is out of date
+        #[arg(long, conflicts_with = "dry_run")]
+        check: bool,
+    },
+    /// Import rules, commands and skills from a file, directory, zip bundle or URL
+    Import(ImportArgs),
+    /// Export the configuration as JSON, YAML or a directory tree
+    Export(ExportArgs),
+    /// Check the database, tool paths, MCP port and file watcher for problems
+    Doctor,
+    /// Record a repository's generated files in `.ruleweaver/lock.json` for `verify`
+    Lock {
+        /// Registered repository root
+        #[arg(long, value_name = "PATH")]
+        repo: PathBuf,
+    },
+    /// Check a repository's generated files against its committed lock; exits with 3 on drift
+    Verify {
+        /// Repository root
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        repo: PathBuf,
+    },
+    /// Install or remove git hooks that keep a repository's generated files up to date
+    Hooks {
+        #[command(subcommand)]
+        action: HooksCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HooksCommand {
+    /// Add pre-commit and post-merge hooks that check generated files
+    Install {
+        /// Repository root
+        repo: PathBuf,
+        /// Regenerate stale files instead of only reporting them
+        #[arg(long)]
+        regenerate: bool,
+    },
+    /// Remove RuleWeaver's hooks
+    Uninstall {
+        /// Repository root
+        repo: PathBuf,
+    },
+    /// Show which hooks are installed
+    Status {
+        /// Repository root
+        repo: PathBuf,
+    },
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    /// File to write, or directory for `--format tree`; prints JSON to stdout when omitted
+    #[arg(long, short, value_name = "PATH")]
+    output: Option<String>,
+
+    /// `json`, `yaml` or `tree`; defaults to the extension of the output path
+    #[arg(long, value_parser = parse_export_format, requires = "output")]
+    format: Option<ExportFormat>,
+
+    /// Only the rule, command or skill with this id; repeat for several
+    #[arg(long = "id", value_name = "ID")]
+    ids: Vec<String>,
+
+    /// Only artifacts with this tag; repeat for several
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+
+    /// Only rules of this scope
+    #[arg(long, value_parser = parse_scope)]
+    scope: Option<Scope>,
+
+    /// Only rules enabled for this AI tool; repeat for several
+    #[arg(long = "adapter", value_name = "ADAPTER", value_parser = parse_adapter)]
+    adapters: Vec<AdapterType>,
+
+    /// Leave settings out
+    #[arg(long)]
+    no_settings: bool,
+
+    /// Add sync hashes and the rule file index so a restored machine recognizes its files
+    #[arg(long)]
+    with_state: bool,
+}
+
+impl ExportArgs {
+    fn filter(&self) -> ExportFilter {
+        ExportFilter {
+            ids: self.ids.clone(),
+            tags: self.tags.clone(),
+            scope: self.scope,
+            adapters: self.adapters.clone(),
+            include_settings: !self.no_settings,
+            include_state: self.with_state,
+            ..ExportFilter::default()
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct ImportArgs {
+    /// File, directory, `.zip` bundle, `ruleweaver export` output or http(s) URL
+    source: String,
+
+    /// What to do with items whose name is already taken: `skip`, `rename` or `replace`
+    #[arg(long, default_value = "skip", value_parser = parse_conflict_mode)]
+    conflict: ImportConflictMode,
+
+    /// Scope for imported items that do not specify one
+    #[arg(long, value_parser = parse_scope)]
+    scope: Option<Scope>,
+
+    /// AI tool for imported rules that do not specify any; repeat for several
+    #[arg(long = "adapter", value_name = "ADAPTER", value_parser = parse_adapter)]
+    adapters: Vec<AdapterType>,
+
+    /// Import items even when they closely match existing content
+    #[arg(long)]
+    allow_near_duplicates: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum RulesCommand {
+    /// List all rules
+    List,
+    /// Create a rule
+    Create(CreateArgs),
+    /// Update the given fields of a rule
+    Update(UpdateArgs),
+    /// Delete a rule and its generated files
+    Delete {
+        /// Rule id
+        id: String,
+    },
+    /// Report cycles and unmet requirements in the rules' ordering constraints
+    Lint,
+}
+
+#[derive(Args, Debug)]
+struct ContentArgs {
+    /// Rule content
+    #[arg(long, conflicts_with = "content_file")]
+    content: Option<String>,
+
+    /// Read the rule content from a file, or from stdin with `-`
+    #[arg(long, value_name = "PATH")]
+    content_file: Option<PathBuf>,
+}
+
+impl ContentArgs {
+    fn read(&self) -> Result<Option<String>> {
+        if let Some(content) = &self.content {
+            return Ok(Some(content.clone()));
+        }
+        match &self.content_file {
+            Some(path) if path.as_os_str() == "-" => {
+                let mut content = String::new();
+                std::io::stdin().read_to_string(&mut content)?;
+                Ok(Some(content))
+            }
+            Some(path) => Ok(Some(std::fs::read_to_string(path)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct CreateArgs {
+    #[arg(long)]
+    name: String,
+
+    #[arg(long, default_value = "")]
+    description: String,
+
+    #[command(flatten)]
+    content: ContentArgs,
+
+    /// `global` or `local`
+    #[arg(long, default_value = "global", value_parser = parse_scope)]
+    scope: Scope,
+
+    /// Repository a local rule applies to; repeat for several
+    #[arg(long = "target-path", value_name = "PATH")]
+    target_paths: Vec<String>,
+
+    /// AI tool to write the rule to, such as `claude-code` or `cursor`; repeat for several
+    #[arg(long = "adapter", value_name = "ADAPTER", required = true, value_parser = parse_adapter)]
+    adapters: Vec<AdapterType>,
+
+    /// Create the rule disabled
+    #[arg(long)]
+    disabled: bool,
+}
+
+#[derive(Args, Debug)]
+struct UpdateArgs {
+    /// Rule id
+    id: String,
+
+    #[arg(long)]
+    name: Option<String>,
+
+    #[arg(long)]
+    description: Option<String>,
+
+    #[command(flatten)]
+    content: ContentArgs,
+
+    /// `global` or `local`
+    #[arg(long, value_parser = parse_scope)]
+    scope: Option<Scope>,
+
+    /// Replaces the rule's repositories; repeat for several
+    #[arg(long = "target-path", value_name = "PATH")]
+    target_paths: Vec<String>,
+
+    /// Replaces the rule's AI tools; repeat for several
+    #[arg(long = "adapter", value_name = "ADAPTER", value_parser = parse_adapter)]
+    adapters: Vec<AdapterType>,
+
+    #[arg(long, conflicts_with = "disable")]
+    enable: bool,
+
+    #[arg(long)]
+    disable: bool,
+}
+
+fn parse_scope(value: &str) -> std::result::Result<Scope, String> {
+    Scope::from_str(value).map_err(|_| format!("unknown scope '{}'", value))
+}
+
+fn parse_conflict_mode(value: &str) -> std::result::Result<ImportConflictMode, String> {
+    match value {
+        "skip" => Ok(ImportConflictMode::Skip),
+        "rename" => Ok(ImportConflictMode::Rename),
+        "replace" => Ok(ImportConflictMode::Replace),
+        _ => Err(format!("unknown conflict mode '{}'", value)),
+    }
+}
+
+fn parse_export_format(value: &str) -> std::result::Result<ExportFormat, String> {
+    match value {
+        "json" => Ok(ExportFormat::Json),
+        "yaml" => Ok(ExportFormat::Yaml),
+        "tree" => Ok(ExportFormat::Tree),
+        _ => Err(format!("unknown format '{}'", value)),
+    }
+}
+
+fn parse_adapter(value: &str) -> std::result::Result<AdapterType, String> {
+    AdapterType::from_str(value).map_err(|_| format!("unknown adapter '{}'", value))
+}
+
+/// Whether `args` (without the program name) should run the CLI rather than open the GUI.
+pub fn is_cli_invocation(args: &[OsString]) -> bool {
+    args.first()
+        .and_then(|arg| arg.to_str())
+        .is_some_and(|arg| CLI_COMMANDS.contains(&arg))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_rules(rules: &[Rule]) {
+    for rule in rules {
+        println!(
+            "{}\t{}\t{}\t{}",
+            rule.id,
+            if rule.enabled { "enabled" } else { "disabled" },
+            rule.scope.as_str(),
+            rule.name
+        );
+    }
+}
+
+async fn run_rules(db: Arc<Database>, action: RulesCommand, json: bool) -> Result<i32> {
+    match action {
+        RulesCommand::List => {
+            let rules = fetch_rules(&db, None).await?;
+            if json {
+                print_json(&rules)?;
+            } else {
+                print_rules(&rules);
+            }
+        }
+        RulesCommand::Create(args) => {
+            let content = args.content.read()?.ok_or_else(|| AppError::InvalidInput {
+                message: "Either --content or --content-file is required".to_string(),
+            })?;
+            let rule = create_rule_and_sync(
+                &db,
+                CreateRuleInput {
+                    id: None,
+                    name: args.name,
+                    description: args.description,
+                    content,
+                    scope: args.scope,
+                    target_paths: (!args.target_paths.is_empty()).then_some(args.target_paths),
+                    enabled_adapters: args.adapters,
+                    enabled: !args.disabled,
+                    metadata: Default::default(),
+                },
+            )
+            .await?;
+            if json {
+                print_json(&rule)?;
+            } else {
+                println!("Created rule {} ({})", rule.name, rule.id);
+            }
+        }
+        RulesCommand::Update(args) => {
+            let input = UpdateRuleInput {
+                name: args.name,
+                description: args.description,
+                content: args.content.read()?,
+                scope: args.scope,
+                target_paths: (!args.target_paths.is_empty()).then_some(args.target_paths),
+                enabled_adapters: (!args.adapters.is_empty()).then_some(args.adapters),
+                enabled: match (args.enable, args.disable) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                },
+                metadata: None,
+            };
+            let rule = update_rule_and_sync(&db, &args.id, input).await?;
+            if json {
+                print_json(&rule)?;
+            } else {
+                println!("Updated rule {} ({})", rule.name, rule.id);
+            }
+        }
+        RulesCommand::Delete { id } => {
+            delete_rule_and_sync(db, &id).await?;
+            if json {
+                print_json(&serde_json::json!({ "deleted": id }))?;
+            } else {
+                println!("Deleted rule {}", id);
+            }
+        }
+        RulesCommand::Lint => {
+            let issues = rule_order::lint(&fetch_rules(&db, None).await?);
+            if json {
+                print_json(&issues)?;
+            } else {
+                for issue in &issues {
+                    println!("{}\t{}", issue.rule_name, issue.message);
+                }
+                println!("{} issue(s)", issues.len());
+            }
+            if !issues.is_empty() {
+                return Ok(EXIT_INCOMPLETE);
+            }
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+async fn run_sync(db: &Database, dry_run: bool, json: bool) -> Result<i32> {
+    let rules = db.get_all_rules().await?;
+    let engine = SyncEngine::new(db);
+    let result = if dry_run {
+        engine.preview(rules).await
+    } else {
+        engine.sync_all(rules).await
+    };
+
+    if json {
+        print_json(&result)?;
+    } else {
+        let verb = if dry_run { "Would write" } else { "Wrote" };
+        for file in &result.files_written {
+            println!("{} {}", verb, file);
+        }
+        for conflict in &result.conflicts {
+            eprintln!(
+                "Conflict: {} was changed outside RuleWeaver",
+                conflict.file_path
+            );
+        }
+        for error in &result.errors {
+            eprintln!("Error: {}: {}", error.file_path, error.message);
+        }
+    }
+
+    Ok(if result.errors.is_empty() && result.conflicts.is_empty() {
+        EXIT_SUCCESS
+    } else {
+        EXIT_INCOMPLETE
+    })
+}
+
+async fn run_reconcile(
+    db: Arc<Database>,
+    dry_run: bool,
+    repo: Option<PathBuf>,
+    check: bool,
+    json: bool,
+) -> Result<i32> {
+    let dry_run = dry_run || check;
+    let filter = ReconcileFilter {
+        repo_root: repo,
+        ..Default::default()
+    };
+    let result = ReconciliationEngine::new(db)?
+        .with_filter(filter)
+        .reconcile(dry_run, None)
+        .await?;
+    let out_of_date = result.created + result.updated + result.removed > 0;
+
+    if json {
+        print_json(&result)?;
+    } else {
+        let prefix = if dry_run { "Would have " } else { "" };
+        println!(
+            "{}created {}, updated {}, removed {}; {} unchanged",
+            prefix, result.created, result.updated, result.removed, result.unchanged
+        );
+        for warning in &result.warnings {
+            eprintln!("Warning: {}", warning);
+        }
+        for error in &result.errors {
+            eprintln!("Error: {}", error);
+        }
+    }
+
+    Ok(
+        if result.success && result.errors.is_empty() && !(check && out_of_date) {
+            EXIT_SUCCESS
+        } else {
+            EXIT_INCOMPLETE
+        },
+    )
+}
+
+async fn run_lock(db: Arc<Database>, repo: PathBuf, json: bool) -> Result<i32> {
+    let root = repo_roots::registered_repo_root(&db, &repo).await?;
+    let engine = ReconciliationEngine::new(db)?.with_filter(ReconcileFilter {
+        repo_root: Some(root.clone()),
+        ..Default::default()
+    });
+    let lock = engine.lock_repository(&root).await?;
+    let path = lock::write_lock(&root, &lock)?;
+
+    if json {
+        print_json(&serde_json::json!({ "lockFile": path, "files": lock.files.len() }))?;
+    } else {
+        println!("Locked {} files in {}", lock.files.len(), path.display());
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+/// Runs without a database, so it works on CI runners that only have the checkout.
+fn run_verify(repo: &std::path::Path, json: bool) -> Result<i32> {
+    let report = lock::verify_repository(repo)?;
+
+    if json {
+        print_json(&report)?;
+    } else {
+        // GitHub Actions turns these lines into annotations on the offending files.
+        let annotate = std::env::var_os("GITHUB_ACTIONS").is_some();
+        for drift in &report.drift {
+            let reason = match drift.kind {
+                LockDriftKind::Missing => "is missing",
+                LockDriftKind::Modified => "differs from what RuleWeaver generates",
+                LockDriftKind::Unexpected => "is generated by RuleWeaver but not in the lock",
+            };
+            if annotate {
+                println!("::error file={}::{} {}", drift.path, drift.path, reason);
+            } else {
+                println!("{} {}", drift.path, reason);
+            }
+        }
+        println!(
+            "Checked {} locked files: {}",
+            report.checked,
+            if report.drift.is_empty() {
+                "up to date".to_string()
+            } else {
+                format!("{} out of date", report.drift.len())
+            }
+        );
+    }
+
+    Ok(if report.drift.is_empty() {
+        EXIT_SUCCESS
+    } else {
+        EXIT_INCOMPLETE
+    })
+}
+
+async fn run_hooks(db: &Database, action: HooksCommand, json: bool) -> Result<i32> {
+    let status = match action {
+        HooksCommand::Install { repo, regenerate } => {
+            let repo = repo_roots::registered_repo_root(db, &repo).await?;
+            let action = if regenerate {
+                HookAction::Regenerate
+            } else {
+                HookAction::Verify
+            };
+            git_hooks::install_hooks(&repo, &std::env::current_exe()?, action)?
+        }
+        HooksCommand::Uninstall { repo } => git_hooks::uninstall_hooks(&repo)?,
+        HooksCommand::Status { repo } => git_hooks::hook_status(&repo)?,
+    };
+
+    if json {
+        print_json(&status)?;
+    } else {
+        let list = |hooks: &[String]| {
+            if hooks.is_empty() {
+                "none".to_string()
+            } else {
+                hooks.join(", ")
+            }
+        };
+        println!("Hooks in {}", status.hooks_dir);
+        println!("RuleWeaver: {}", list(&status.installed));
+        if !status.foreign.is_empty() {
+            println!("Other tools: {}", list(&status.foreign));
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+fn has_extension(path: &std::path::Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+/// Files and trees written by `ruleweaver export` are applied as a whole configuration;
+/// replacing maps to overwriting and renaming has no equivalent there.
+async fn import_configuration_file(
+    db: Arc<Database>,
+    path: String,
+    conflict: ImportConflictMode,
+    json: bool,
+) -> Result<i32> {
+    let mode = match conflict {
+        ImportConflictMode::Skip => ImportMode::Skip,
+        ImportConflictMode::Replace => ImportMode::Overwrite,
+        ImportConflictMode::Rename => return Err(AppError::InvalidInput {
+            message:
+                "--conflict rename is not supported for configuration files; use skip or replace"
+                    .to_string(),
+        }),
+    };
+    let (config, verification) = read_configuration_file(&db, path).await?;
+    match verification.integrity {
+        ExportIntegrity::Unverified => {
+            eprintln!("Warning: the configuration file has no manifest and cannot be verified")
+        }
+        ExportIntegrity::UnknownSigner => eprintln!(
+            "Warning: the configuration file is signed by an untrusted key {}",
+            verification.signer.unwrap_or_default()
+        ),
+        _ => {}
+    }
+    let counts = serde_json::json!({
+        "rules": config.rules.len(),
+        "commands": config.commands.len(),
+        "skills": config.skills.len(),
+    });
+    apply_configuration(db, config, mode).await?;
+
+    if json {
+        print_json(&serde_json::json!({ "configuration": counts }))?;
+    } else {
+        println!(
+            "Applied configuration with {} rules, {} commands and {} skills",
+            counts["rules"], counts["commands"], counts["skills"]
+        );
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+async fn run_import(db: Arc<Database>, args: ImportArgs, json: bool) -> Result<i32> {
+    let options = ImportExecutionOptions {
+        conflict_mode: args.conflict.clone(),
+        default_scope: args.scope,
+        default_adapters: (!args.adapters.is_empty()).then_some(args.adapters),
+        allow_near_duplicates: args.allow_near_duplicates,
+        ..Default::default()
+    };
+    let max_size = rule_import::resolve_max_size(&options);
+
+    let result = if is_url(&args.source) {
+        let scan = rule_import::scan_url_to_candidates(&args.source, max_size).await?;
+        rule_import::execute_import(db, scan, options).await?
+    } else {
+        let path = std::fs::canonicalize(&args.source).map_err(|e| AppError::InvalidInput {
+            message: format!("Invalid path '{}': {}", args.source, e),
+        })?;
+        if path.join(crate::export_tree::INDEX_FILE).is_file() {
+            return import_configuration_file(db, args.source, args.conflict, json).await;
+        } else if path.is_dir() {
+            let rules = mapping::list_mapping_rules(&db).await;
+            let scan = rule_import::scan_directory_with_mapping(&path, max_size, None, &rules);
+            rule_import::execute_import(db, scan, options).await?
+        } else if has_extension(&path, &["zip"]) {
+            archive::import_zip(db, &path, options).await?
+        } else if has_extension(&path, &["json", "yaml", "yml"]) {
+            return import_configuration_file(db, args.source, args.conflict, json).await;
+        } else {
+            let scan = rule_import::scan_file_to_candidates(&path, max_size);
+            rule_import::execute_import(db, scan, options).await?
+        }
+    };
+
+    if json {
+        print_json(&result)?;
+    } else {
+        print_import_result(&result);
+    }
+    Ok(if result.errors.is_empty() {
+        EXIT_SUCCESS
+    } else {
+        EXIT_INCOMPLETE
+    })
+}
+
+fn print_import_result(result: &ImportExecutionResult) {
+    println!(
+        "Imported {} rules, {} commands and {} skills; skipped {}",
+        result.imported_rules.len(),
+        result.imported_commands.len(),
+        result.imported_skills.len(),
+        result.skipped.len()
+    );
+    for skip in &result.skipped {
+        println!("Skipped {}: {}", skip.name, skip.reason);
+    }
+    for error in &result.errors {
+        eprintln!("Error: {}", error);
+    }
+}
+
+async fn run_export(db: &Database, args: ExportArgs, json: bool) -> Result<i32> {
+    let filter = args.filter();
+    match args.output {
+        None => {
+            let mut config = db.export_configuration().await?;
+            if filter.include_state {
+                config.state = Some(db.export_state().await?);
+            }
+            filter.apply(&mut config);
+            print_json(&config)?
+        }
+        Some(path) => {
+            write_configuration_file(db, path.clone(), &filter, args.format).await?;
+            if json {
+                print_json(&serde_json::json!({ "output": path }))?;
+            } else {
+                println!("Exported configuration to {}", path);
+            }
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+async fn run_doctor(db: &Database, json: bool) -> Result<i32> {
+    let report = doctor::run_doctor(db, DoctorContext::default()).await;
+
+    if json {
+        print_json(&report)?;
+    } else {
+        for finding in &report.findings {
+            let label = match finding.status {
+                DoctorStatus::Ok => "ok",
+                DoctorStatus::Warning => "warning",
+                DoctorStatus::Error => "error",
+                DoctorStatus::Skipped => "skipped",
+            };
+            println!("[{}] {}: {}", label, finding.check, finding.message);
+            if let Some(fix) = &finding.fix {
+                println!("    fix: {}", fix);
+            }
+        }
+        println!("{} errors, {} warnings", report.errors, report.warnings);
+    }
+
+    Ok(if report.errors == 0 {
+        EXIT_SUCCESS
+    } else {
+        EXIT_INCOMPLETE
+    })
+}
+
+async fn execute(db: Arc<Database>, command: CliCommand, json: bool) -> Result<i32> {
+    match command {
+        CliCommand::Rules { action } => run_rules(db, action, json).await,
+        CliCommand::Sync { dry_run } => run_sync(&db, dry_run, json).await,
+        CliCommand::Reconcile {
+            dry_run,
+            repo,
+            check,
+        } => run_reconcile(db, dry_run, repo, check, json).await,
+        CliCommand::Import(args) => run_import(db, args, json).await,
+        CliCommand::Export(args) => run_export(&db, args, json).await,
+        CliCommand::Doctor => run_doctor(&db, json).await,
+        CliCommand::Hooks { action } => run_hooks(&db, action, json).await,
+        CliCommand::Lock { repo } => run_lock(db, repo, json).await,
+        CliCommand::Verify { repo } => run_verify(&repo, json),
+    }
+}
+
+/// Runs the CLI for `args` (without the program name) and returns the process exit code.
+pub fn run_cli(args: Vec<OsString>) -> i32 {
+    let cli = match Cli::try_parse_from(std::iter::once(OsString::from("ruleweaver")).chain(args)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                EXIT_USAGE
+            } else {
+                EXIT_SUCCESS
+            };
+        }
+    };
+    let json = cli.json;
+
+    let outcome = tokio::runtime::Runtime::new()
+        .map_err(AppError::from)
+        .and_then(|rt| {
+            rt.block_on(async {
+                if let CliCommand::Verify { repo } = &cli.command {
+                    return run_verify(repo, json);
+                }
+                let db = Arc::new(Database::new_for_cli().await?);
+                let code = crate::audit::scope(
+                    AuditActor::new(AuditActorKind::Cli),
+                    execute(db, cli.command, json),
+                )
+                .await;
+                // Webhooks raised by this run would die with the runtime.
+                crate::webhooks::flush().await;
+                code
+            })
+        });
+
+    match outcome {
+        Ok(code) => code,
+        Err(e) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": e.to_string() }));
+            } else {
+                eprintln!("ruleweaver: {}", e);
+            }
+            EXIT_FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> std::result::Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("ruleweaver").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_cli_invocation_is_detected_only_for_known_commands() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+        assert!(is_cli_invocation(&args(&["rules", "list"])));
+        assert!(is_cli_invocation(&args(&["--version"])));
+        assert!(!is_cli_invocation(&args(&[])));
+        assert!(!is_cli_invocation(&args(&["-psn_0_12345"])));
+    }
+
+    #[test]
+    fn test_cli_parses_rule_arguments() {
+        let cli = parse(&[
+            "rules",
+            "create",
+            "--name",
+            "Style",
+            "--content",
+            "Use tabs",
+            "--adapter",
+            "claude-code",
+            "--adapter",
+            "cursor",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        let CliCommand::Rules {
+            action: RulesCommand::Create(args),
+        } = cli.command
+        else {
+            panic!("expected rules create");
+        };
+        assert_eq!(args.scope, Scope::Global);
+        assert_eq!(
+            args.adapters,
+            vec![AdapterType::ClaudeCode, AdapterType::Cursor]
+        );
+
+        assert!(parse(&["rules", "create", "--name", "x", "--content", "y"]).is_err());
+        assert!(parse(&["rules", "create", "--name", "x", "--adapter", "vim"]).is_err());
+        assert!(parse(&["rules", "update", "id", "--enable", "--disable"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cli_rule_lifecycle() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let run = |args: &[&str]| {
+            let cli = parse(args).unwrap();
+            execute(Arc::clone(&db), cli.command, true)
+        };
+
+        assert_eq!(
+            run(&[
+                "rules",
+                "create",
+                "--name",
+                "Style",
+                "--content",
+                "Use tabs",
+                "--adapter",
+                "claude-code",
+                "--disabled",
+            ])
+            .await
+            .unwrap(),
+            EXIT_SUCCESS
+        );
+        let rule = db.get_all_rules().await.unwrap().remove(0);
+        assert!(!rule.enabled);
+
+        run(&[
+            "rules",
+            "update",
+            &rule.id,
+            "--enable",
+            "--content",
+            "Use spaces",
+        ])
+        .await
+        .unwrap();
+        let updated = db.get_rule_by_id(&rule.id).await.unwrap();
+        assert!(updated.enabled);
+        assert_eq!(updated.content, "Use spaces");
+
+        assert!(run(&["rules", "update", "missing", "--enable"])
+            .await
+            .is_err());
+
+        run(&["rules", "delete", &rule.id]).await.unwrap();
+        assert!(db.get_all_rules().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cli_reconcile_check_reports_stale_repo_files() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path().to_str().unwrap();
+        crate::commands::register_local_paths(&db, &[root.to_string()])
+            .await
+            .unwrap();
+        let run = |args: &[&str]| {
+            let cli = parse(args).unwrap();
+            execute(Arc::clone(&db), cli.command, true)
+        };
+
+        // Created directly: the CLI only accepts repositories under the home directory.
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Repo".to_string(),
+            description: String::new(),
+            content: "Use tabs".to_string(),
+            scope: Scope::Local,
+            target_paths: Some(vec![root.to_string()]),
+            enabled_adapters: vec![AdapterType::ClaudeCode],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+        let generated = repo.path().join(".claude/CLAUDE.md");
+        let check = ["reconcile", "--repo", root, "--check"];
+        assert_eq!(run(&check).await.unwrap(), EXIT_INCOMPLETE);
+        assert!(!generated.exists());
+
+        assert_eq!(
+            run(&["reconcile", "--repo", root]).await.unwrap(),
+            EXIT_SUCCESS
+        );
+        assert!(generated.exists());
+        assert_eq!(run(&check).await.unwrap(), EXIT_SUCCESS);
+
+        std::fs::write(&generated, "stale").unwrap();
+        assert_eq!(run(&check).await.unwrap(), EXIT_INCOMPLETE);
+        assert_eq!(std::fs::read_to_string(&generated).unwrap(), "stale");
+
+        assert_eq!(
+            run(&["reconcile", "--repo", root]).await.unwrap(),
+            EXIT_SUCCESS
+        );
+        assert_eq!(run(&check).await.unwrap(), EXIT_SUCCESS);
+        assert!(parse(&["reconcile", "--check", "--dry-run"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cli_export_then_import_restores_rules() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("config.json");
+        let output = output.to_str().unwrap();
+        let run = |args: &[&str]| {
+            let cli = parse(args).unwrap();
+            execute(Arc::clone(&db), cli.command, true)
+        };
+
+        run(&[
+            "rules",
+            "create",
+            "--name",
+            "Shared",
+            "--content",
+            "Team rule",
+            "--adapter",
+            "claude-code",
+        ])
+        .await
+        .unwrap();
+        run(&["export", "--output", output]).await.unwrap();
+        let rule = db.get_all_rules().await.unwrap().remove(0);
+        db.delete_rule(&rule.id).await.unwrap();
+
+        assert!(run(&["import", output, "--conflict", "rename"])
+            .await
+            .is_err());
+        assert_eq!(
+            run(&["import", output, "--conflict", "replace"])
+                .await
+                .unwrap(),
+            EXIT_SUCCESS
+        );
+        let restored = db.get_all_rules().await.unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].content, "Team rule");
+
+        assert!(parse(&["import", output, "--conflict", "merge"]).is_err());
+    }
+}
@@ -0,0 +1,852 @@
+//! Cloud sync: keeps several machines on the same library through one shared document.
+//!
+//! The document is a configuration export stored on WebDAV, S3 or Dropbox. Each sync reads
+//! it, merges it with the local library and writes the result back, conditional on the
+//! document not having changed in between. The merge is three-way per item (rule, command,
+//! skill, collection, collection membership or portable setting), against the fingerprints
+//! recorded at this machine's previous sync:
+//!
+//! - changed on one side only: that side wins, including deletions;
+//! - changed on both sides: the most recent edit wins (last writer wins), and the item is
+//!   reported as a conflict;
+//! - deleted on one side and edited on the other: the edit wins.
+
+mod store;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::Digest;
+
+use self::store::{Expect, PutOutcome, Store};
+use crate::constants::timing::{CLOUD_SYNC_CHECK_INTERVAL, DEFAULT_CLOUD_SYNC_INTERVAL_MINUTES};
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    is_portable_setting, CloudSync, CloudSyncBackend, CloudSyncDocument, CloudSyncFingerprint,
+    CloudSyncResult, Collection, CollectionItem, Command, ConfigureCloudSyncInput,
+    ExportConfiguration, ImportMode, Metadata, Rule, Skill,
+};
+use crate::policy;
+use crate::secrets;
+use crate::sync::SyncEngine;
+
+pub(crate) const CLOUD_SYNC_KEY: &str = "cloud_sync";
+const DOCUMENT_FORMAT: u32 = 1;
+/// Attempts when another machine writes the document while this one is merging.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Serializes syncs and configuration changes, which share the merge base.
+static CLOUD_SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub async fn get_cloud_sync(db: &Database) -> Option<CloudSync> {
+    let encoded = db.get_setting(CLOUD_SYNC_KEY).await.ok().flatten()?;
+    serde_json::from_str(&encoded).ok()
+}
+
+async fn save_cloud_sync(db: &Database, cloud: &CloudSync) -> Result<()> {
+    let encoded = serde_json::to_string(cloud)?;
+    db.set_setting(CLOUD_SYNC_KEY, &encoded).await
+}
+
+async fn require_cloud_sync(db: &Database) -> Result<CloudSync> {
+    get_cloud_sync(db)
+        .await
+        .ok_or_else(|| AppError::InvalidInput {
+            message: "Cloud sync is not configured".to_string(),
+        })
+}
+
+fn require_field(value: &str, field: &str) -> Result<()> {
+    if value.trim().is_empty() {
+        return Err(AppError::InvalidInput {
+            message: format!("{} is required", field),
+        });
+    }
+    Ok(())
+}
+
+fn validate_http_url(raw: &str, what: &str) -> Result<()> {
+    let parsed = url::Url::parse(raw.trim()).map_err(|e| AppError::InvalidInput {
+        message: format!("Invalid {}: {}", what, e),
+    })?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return Err(AppError::InvalidInput {
+            message: format!("{} must be an http or https URL", what),
+        });
+    }
+    Ok(())
+}
+
+fn validate_backend(backend: &CloudSyncBackend) -> Result<()> {
+    match backend {
+        CloudSyncBackend::WebDav {
+            url,
+            password_secret,
+            ..
+        } => {
+            validate_http_url(url, "WebDAV URL")?;
+            if let Some(name) = password_secret {
+                secrets::validate_secret_name(name)?;
+            }
+        }
+        CloudSyncBackend::S3 {
+            endpoint,
+            region,
+            bucket,
+            key,
+            access_key_id,
+            secret_access_key_secret,
+        } => {
+            if let Some(endpoint) = endpoint {
+                validate_http_url(endpoint, "S3 endpoint")?;
+            }
+            require_field(region, "Region")?;
+            require_field(bucket, "Bucket")?;
+            require_field(key, "Object key")?;
+            require_field(access_key_id, "Access key ID")?;
+            secrets::validate_secret_name(secret_access_key_secret)?;
+            store::s3_object_url(endpoint.as_deref(), region, bucket, key)?;
+        }
+        CloudSyncBackend::Dropbox { path, token_secret } => {
+            if !path.starts_with('/') || path.ends_with('/') {
+                return Err(AppError::InvalidInput {
+                    message:
+                        "Dropbox path must be an absolute file path, like /RuleWeaver/sync.json"
+                            .to_string(),
+                });
+            }
+            secrets::validate_secret_name(token_secret)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sets up cloud sync, or changes its settings. Pointing it at a different location starts
+/// over, so the first sync there merges everything as new.
+pub async fn configure_cloud_sync(
+    db: &Database,
+    input: ConfigureCloudSyncInput,
+) -> Result<CloudSync> {
+    validate_backend(&input.backend)?;
+    let interval_minutes = input
+        .interval_minutes
+        .unwrap_or(DEFAULT_CLOUD_SYNC_INTERVAL_MINUTES);
+    if interval_minutes == 0 {
+        return Err(AppError::InvalidInput {
+            message: "Sync interval must be at least one minute".to_string(),
+        });
+    }
+
+    let _guard = CLOUD_SYNC_LOCK.lock().await;
+    let cloud = match get_cloud_sync(db).await {
+        Some(existing) if existing.backend == input.backend => CloudSync {
+            interval_minutes,
+            ..existing
+        },
+        existing => CloudSync {
+            backend: input.backend,
+            device_id: existing
+                .map(|cloud| cloud.device_id)
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            interval_minutes,
+            enabled: true,
+            last_synced_at: None,
+            last_error: None,
+            base: BTreeMap::new(),
+            created_at: Utc::now(),
+        },
+    };
+    save_cloud_sync(db, &cloud).await?;
+    Ok(cloud)
+}
+
+pub async fn remove_cloud_sync(db: &Database) -> Result<()> {
+    let _guard = CLOUD_SYNC_LOCK.lock().await;
+    db.delete_setting(CLOUD_SYNC_KEY).await
+}
+
+pub async fn set_cloud_sync_enabled(db: &Database, enabled: bool) -> Result<CloudSync> {
+    let _guard = CLOUD_SYNC_LOCK.lock().await;
+    let mut cloud = require_cloud_sync(db).await?;
+    cloud.enabled = enabled;
+    save_cloud_sync(db, &cloud).await?;
+    Ok(cloud)
+}
+
+/// One item of the shared library.
+#[derive(Debug, Clone)]
+enum Item {
+    Rule(Rule),
+    Command(Command),
+    Skill(Skill),
+    Collection(Collection),
+    Membership(CollectionItem),
+    Setting(String, String),
+}
+
+impl Item {
+    fn key(&self) -> String {
+        match self {
+            Self::Rule(rule) => format!("rule:{}", rule.id),
+            Self::Command(command) => format!("command:{}", command.id),
+            Self::Skill(skill) => format!("skill:{}", skill.id),
+            Self::Collection(collection) => format!("collection:{}", collection.id),
+            // An artifact belongs to at most one collection
+            Self::Membership(item) => {
+                format!("membership:{}:{}", item.item_type.as_str(), item.item_id)
+            }
+            Self::Setting(key, _) => format!("setting:{}", key),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Rule(rule) => format!("rule \"{}\"", rule.name),
+            Self::Command(command) => format!("command \"{}\"", command.name),
+            Self::Skill(skill) => format!("skill \"{}\"", skill.name),
+            Self::Collection(collection) => format!("collection \"{}\"", collection.name),
+            Self::Membership(item) => {
+                format!("collection of {} {}", item.item_type.as_str(), item.item_id)
+            }
+            Self::Setting(key, _) => format!("setting {}", key),
+        }
+    }
+
+    fn modified(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Rule(rule) => Some(rule.updated_at),
+            Self::Command(command) => Some(command.updated_at),
+            Self::Skill(skill) => Some(skill.updated_at),
+            Self::Collection(collection) => Some(collection.updated_at),
+            Self::Membership(_) | Self::Setting(..) => None,
+        }
+    }
+
+    /// Hash of the item's content. `updatedAt` is left out because importing an item
+    /// stamps it with the time of the import.
+    fn fingerprint(&self) -> String {
+        fn of<T: Serialize>(value: &T) -> String {
+            let mut value = serde_json::to_value(value).unwrap_or_default();
+            if let Some(object) = value.as_object_mut() {
+                object.remove("updatedAt");
+            }
+            format!("{:x}", sha2::Sha256::digest(value.to_string().as_bytes()))
+        }
+        match self {
+            Self::Rule(rule) => of(rule),
+            Self::Command(command) => of(command),
+            Self::Skill(skill) => of(skill),
+            Self::Collection(collection) => of(collection),
+            Self::Membership(item) => of(item),
+            Self::Setting(key, value) => of(&(key, value)),
+        }
+    }
+
+    /// Order in which items are written locally; deletions run in reverse.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Collection(_) => 0,
+            Self::Rule(_) => 1,
+            Self::Command(_) => 2,
+            Self::Skill(_) => 3,
+            Self::Membership(_) => 4,
+            Self::Setting(..) => 5,
+        }
+    }
+}
+
+fn items(config: ExportConfiguration) -> BTreeMap<String, Item> {
+    let all = config
+        .rules
+        .into_iter()
+        .map(Item::Rule)
+        .chain(config.commands.into_iter().map(Item::Command))
+        .chain(config.skills.into_iter().map(Item::Skill))
+        .chain(config.collections.into_iter().map(Item::Collection))
+        .chain(config.collection_items.into_iter().map(Item::Membership))
+        .chain(
+            config
+                .settings
+                .into_iter()
+                .filter(|(key, _)| is_portable_setting(key))
+                .map(|(key, value)| Item::Setting(key, value)),
+        );
+    all.map(|item| (item.key(), item)).collect()
+}
+
+fn to_config(items: impl IntoIterator<Item = Item>) -> ExportConfiguration {
+    let mut config = ExportConfiguration::new(Vec::new(), Vec::new(), Vec::new());
+    for item in items {
+        match item {
+            Item::Rule(rule) => config.rules.push(rule),
+            Item::Command(command) => config.commands.push(command),
+            Item::Skill(skill) => config.skills.push(skill),
+            Item::Collection(collection) => config.collections.push(collection),
+            Item::Membership(item) => config.collection_items.push(item),
+            Item::Setting(key, value) => {
+                config.settings.insert(key, value);
+            }
+        }
+    }
+    config
+}
+
+/// The outcome of merging the local library with the remote document.
+#[derive(Default)]
+struct Plan {
+    /// The library both sides end up with
+    merged: BTreeMap<String, Item>,
+    /// Remote items to write locally
+    apply: Vec<Item>,
+    /// Local items to delete
+    delete: Vec<Item>,
+    pushed: Vec<String>,
+    conflicts: Vec<String>,
+}
+
+fn plan(
+    mut local: BTreeMap<String, Item>,
+    mut remote: BTreeMap<String, Item>,
+    base: &BTreeMap<String, CloudSyncFingerprint>,
+) -> Plan {
+    let keys: BTreeSet<String> = local.keys().chain(remote.keys()).cloned().collect();
+    let mut plan = Plan::default();
+
+    for key in keys {
+        let base = base.get(&key);
+        match (local.remove(&key), remote.remove(&key)) {
+            (Some(l), Some(r)) => {
+                let (fl, fr) = (l.fingerprint(), r.fingerprint());
+                let local_changed = base.is_none_or(|b| b.local != fl);
+                let remote_changed = base.is_none_or(|b| b.remote != fr);
+                if fl == fr || (!local_changed && !remote_changed) {
+                    plan.merged.insert(key, r);
+                } else if !remote_changed {
+                    plan.pushed.push(l.label());
+                    plan.merged.insert(key, l);
+                } else if !local_changed {
+                    plan.apply.push(r.clone());
+                    plan.merged.insert(key, r);
+                } else {
+                    plan.conflicts.push(l.label());
+                    if r.modified() > l.modified() {
+                        plan.apply.push(r.clone());
+                        plan.merged.insert(key, r);
+                    } else {
+                        plan.pushed.push(l.label());
+                        plan.merged.insert(key, l);
+                    }
+                }
+            }
+            (Some(l), None) => {
+                if base.is_some_and(|b| b.local == l.fingerprint()) {
+                    plan.delete.push(l);
+                } else {
+                    plan.pushed.push(l.label());
+                    plan.merged.insert(key, l);
+                }
+            }
+            (None, Some(r)) => {
+                if base.is_some_and(|b| b.remote == r.fingerprint()) {
+                    plan.pushed.push(format!("deleted {}", r.label()));
+                } else {
+                    plan.apply.push(r.clone());
+                    plan.merged.insert(key, r);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    plan
+}
+
+fn parse_document(body: &[u8]) -> Result<CloudSyncDocument> {
+    let document: CloudSyncDocument =
+        serde_json::from_slice(body).map_err(|e| AppError::InvalidInput {
+            message: format!("The sync document is not valid: {}", e),
+        })?;
+    if document.format != DOCUMENT_FORMAT {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "The sync document has format {}, written by a newer RuleWeaver",
+                document.format
+            ),
+        });
+    }
+    Ok(document)
+}
+
+/// A merge ready to be written remotely, then locally.
+struct Prepared {
+    plan: Plan,
+    /// The new remote document, when the remote copy has to change
+    upload: Option<CloudSyncDocument>,
+    revision: u64,
+}
+
+async fn prepare(
+    db: &Database,
+    cloud: &CloudSync,
+    remote: Option<CloudSyncDocument>,
+) -> Result<Prepared> {
+    let local = items(db.export_configuration().await?);
+    let exists = remote.is_some();
+    let (remote_items, revision) = match remote {
+        Some(document) => (items(document.config), document.revision),
+        None => (BTreeMap::new(), 0),
+    };
+    let plan = plan(local, remote_items, &cloud.base);
+
+    if exists && plan.pushed.is_empty() {
+        return Ok(Prepared {
+            plan,
+            upload: None,
+            revision,
+        });
+    }
+    let upload = CloudSyncDocument {
+        format: DOCUMENT_FORMAT,
+        device_id: cloud.device_id.clone(),
+        revision: revision + 1,
+        config: to_config(plan.merged.values().cloned()),
+    };
+    Ok(Prepared {
+        plan,
+        upload: Some(upload),
+        revision: revision + 1,
+    })
+}
+
+/// The local copy of an artifact `item` would replace or remove: its kind, name and
+/// metadata. Collections, memberships and settings are not managed.
+async fn local_artifact(db: &Database, item: &Item) -> Option<(&'static str, String, Metadata)> {
+    match item {
+        Item::Rule(rule) => db
+            .get_rule_by_id(&rule.id)
+            .await
+            .ok()
+            .map(|r| ("Rule", r.name, r.metadata)),
+        Item::Command(command) => db
+            .get_command_by_id(&command.id)
+            .await
+            .ok()
+            .map(|c| ("Command", c.name, c.metadata)),
+        Item::Skill(skill) => db
+            .get_skill_by_id(&skill.id)
+            .await
+            .ok()
+            .map(|s| ("Skill", s.name, s.metadata)),
+        Item::Collection(_) | Item::Membership(_) | Item::Setting(..) => None,
+    }
+}
+
+async fn write_item(db: &Database, item: Item) -> Result<()> {
+    if let Some((kind, name, metadata)) = local_artifact(db, &item).await {
+        policy::ensure_replaceable(db, kind, &name, &metadata).await?;
+    }
+    match item {
+        Item::Rule(rule) => db.import_rule(rule, ImportMode::Overwrite).await,
+        Item::Command(command) => db.import_command(command, ImportMode::Overwrite).await,
+        Item::Skill(skill) => db.import_skill(skill, ImportMode::Overwrite).await,
+        Item::Collection(collection) => {
+            db.import_collection(collection, ImportMode::Overwrite)
+                .await
+        }
+        Item::Membership(item) => db.import_collection_item(item, ImportMode::Overwrite).await,
+        Item::Setting(key, value) => db.set_setting(&key, &value).await,
+    }
+}
+
+async fn delete_item(db: &Database, item: &Item) -> Result<()> {
+    if let Some((kind, name, metadata)) = local_artifact(db, item).await {
+        policy::ensure_deletable(db, kind, &name, &metadata).await?;
+    }
+    match item {
+        Item::Rule(rule) => db.delete_rule(&rule.id).await,
+        Item::Command(command) => db.delete_command(&command.id).await,
+        Item::Skill(skill) => db.delete_skill(&skill.id).await,
+        Item::Collection(collection) => db.delete_collection(&collection.id).await,
+        Item::Membership(item) => {
+            db.set_item_collection(item.item_type, &item.item_id, None)
+                .await
+        }
+        Item::Setting(key, _) => db.delete_setting(key).await,
+    }
+}
+
+/// Applies the remote side of the merge locally and records the new merge base.
+async fn finish(db: &Database, cloud: &mut CloudSync, prepared: Prepared) -> CloudSyncResult {
+    let Prepared {
+        mut plan,
+        upload,
+        revision,
+    } = prepared;
+    let mut result = CloudSyncResult {
+        pushed: plan.pushed,
+        conflicts: plan.conflicts,
+        uploaded: upload.is_some(),
+        revision,
+        ..Default::default()
+    };
+
+    // Parents are written before their children so every parent_id resolves.
+    let depths = crate::models::nesting_depths(plan.apply.iter().filter_map(|item| match item {
+        Item::Collection(collection) => Some(collection),
+        _ => None,
+    }));
+    plan.apply.sort_by_key(|item| match item {
+        Item::Collection(collection) => (item.rank(), depths[&collection.id]),
+        _ => (item.rank(), 0),
+    });
+    plan.delete
+        .sort_by_key(|item| std::cmp::Reverse(item.rank()));
+    for item in plan.delete {
+        match delete_item(db, &item).await {
+            Ok(()) => result.deleted.push(item.label()),
+            Err(e) => result
+                .errors
+                .push(format!("Failed to delete {}: {}", item.label(), e)),
+        }
+    }
+    for item in plan.apply {
+        let label = item.label();
+        match write_item(db, item).await {
+            Ok(()) => result.pulled.push(label),
+            Err(e) => result
+                .errors
+                .push(format!("Failed to update {}: {}", label, e)),
+        }
+    }
+
+    // Items that failed to apply stay out of the base and are merged again next time
+    match db.export_configuration().await {
+        Ok(config) => {
+            let local = items(config);
+            cloud.base = plan
+                .merged
+                .iter()
+                .filter_map(|(key, remote)| {
+                    local.get(key).map(|local| {
+                        let fingerprint = CloudSyncFingerprint {
+                            local: local.fingerprint(),
+                            remote: remote.fingerprint(),
+                        };
+                        (key.clone(), fingerprint)
+                    })
+                })
+                .collect();
+        }
+        Err(e) => result
+            .errors
+            .push(format!("Failed to record the sync state: {}", e)),
+    }
+    result
+}
+
+/// Rewrites the AI tools' files after the library changed underneath them.
+async fn resync_tools(db: Arc<Database>, result: &mut CloudSyncResult) {
+    match db.get_all_rules().await {
+        Ok(rules) => {
+            for err in SyncEngine::new(&db).sync_all(rules).await.errors {
+                result.errors.push(format!(
+                    "Sync error for {}: {}",
+                    err.adapter_name, err.message
+                ));
+            }
+        }
+        Err(e) => result.errors.push(e.to_string()),
+    }
+    crate::commands::reconcile_after_mutation(db).await;
+}
+
+async fn sync_with(db: Arc<Database>, cloud: &mut CloudSync) -> Result<CloudSyncResult> {
+    let store = Store::open(&db, &cloud.backend).await?;
+    for _ in 0..MAX_ATTEMPTS {
+        let (document, version) = match store.get().await? {
+            Some(remote) => (Some(parse_document(&remote.body)?), remote.version),
+            None => (None, None),
+        };
+        let expect = match (&document, version.as_deref()) {
+            (None, _) => Expect::Absent,
+            (Some(_), Some(version)) => Expect::Version(version),
+            (Some(_), None) => Expect::Any,
+        };
+
+        let prepared = prepare(&db, cloud, document).await?;
+        if let Some(upload) = &prepared.upload {
+            let body = serde_json::to_vec_pretty(upload)?;
+            if let PutOutcome::Stale = store.put(body, expect).await? {
+                continue;
+            }
+        }
+        let mut result = finish(&db, cloud, prepared).await;
+        if !result.pulled.is_empty() || !result.deleted.is_empty() {
+            resync_tools(db, &mut result).await;
+        }
+        return Ok(result);
+    }
+    Err(AppError::Internal {
+        message: "The sync document kept changing during the sync; try again".to_string(),
+    })
+}
+
+/// Merges the local library with the remote document now.
+pub async fn sync_cloud_now(db: Arc<Database>) -> Result<CloudSyncResult> {
+    let _guard = CLOUD_SYNC_LOCK.lock().await;
+    let mut cloud = require_cloud_sync(&db).await?;
+    let outcome = sync_with(db.clone(), &mut cloud).await;
+    cloud.last_synced_at = Some(Utc::now());
+    cloud.last_error = outcome.as_ref().err().map(|e| e.to_string());
+    save_cloud_sync(&db, &cloud).await?;
+    outcome
+}
+
+fn is_due(cloud: &CloudSync) -> bool {
+    cloud.enabled
+        && cloud.last_synced_at.is_none_or(|last| {
+            Utc::now() - last >= chrono::Duration::minutes(i64::from(cloud.interval_minutes))
+        })
+}
+
+/// Background loop that syncs whenever the configured interval has elapsed.
+pub async fn run_cloud_sync_loop(db: Arc<Database>) {
+    loop {
+        if get_cloud_sync(&db)
+            .await
+            .is_some_and(|cloud| is_due(&cloud))
+        {
+            match sync_cloud_now(db.clone()).await {
+                Ok(result) if !result.conflicts.is_empty() => log::info!(
+                    "Cloud sync settled {} conflict(s) by keeping the latest edit",
+                    result.conflicts.len()
+                ),
+                Ok(_) => {}
+                Err(e) => log::warn!("Cloud sync failed: {}", e),
+            }
+        }
+        tokio::time::sleep(CLOUD_SYNC_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, Scope, UpdateRuleInput};
+
+    fn device(id: &str) -> CloudSync {
+        CloudSync {
+            backend: CloudSyncBackend::Dropbox {
+                path: "/RuleWeaver/sync.json".to_string(),
+                token_secret: "DROPBOX_TOKEN".to_string(),
+            },
+            device_id: id.to_string(),
+            interval_minutes: DEFAULT_CLOUD_SYNC_INTERVAL_MINUTES,
+            enabled: true,
+            last_synced_at: None,
+            last_error: None,
+            base: BTreeMap::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// One sync against `slot`, which stands in for the remote file.
+    async fn sync_via(
+        db: &Database,
+        cloud: &mut CloudSync,
+        slot: &mut Option<CloudSyncDocument>,
+    ) -> CloudSyncResult {
+        let prepared = prepare(db, cloud, slot.clone()).await.unwrap();
+        if let Some(upload) = &prepared.upload {
+            *slot = Some(upload.clone());
+        }
+        finish(db, cloud, prepared).await
+    }
+
+    async fn create_rule(db: &Database, name: &str, content: &str) -> Rule {
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: name.to_string(),
+            description: String::new(),
+            content: content.to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::ClaudeCode],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn edit_rule(db: &Database, id: &str, content: &str) {
+        db.update_rule(
+            id,
+            UpdateRuleInput {
+                content: Some(content.to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_devices_converge_on_edits_and_deletions() {
+        let desktop = Database::new_in_memory().await.unwrap();
+        let laptop = Database::new_in_memory().await.unwrap();
+        let (mut desktop_sync, mut laptop_sync) = (device("desktop"), device("laptop"));
+        let mut remote = None;
+
+        let rule = create_rule(&desktop, "Style", "Use tabs").await;
+        desktop.set_setting("theme", "dark").await.unwrap();
+        let first = sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        assert!(first.uploaded);
+        assert_eq!(first.revision, 1);
+        let pulled = sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        assert!(pulled.pulled.contains(&"rule \"Style\"".to_string()));
+        assert_eq!(
+            laptop.get_rule_by_id(&rule.id).await.unwrap().content,
+            "Use tabs"
+        );
+        assert_eq!(
+            laptop.get_setting("theme").await.unwrap().as_deref(),
+            Some("dark")
+        );
+
+        // An edit on one machine reaches the other, then both are settled
+        edit_rule(&laptop, &rule.id, "Use spaces").await;
+        let pushed = sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        assert_eq!(pushed.pushed, vec!["rule \"Style\""]);
+        let pulled = sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        assert_eq!(pulled.pulled, vec!["rule \"Style\""]);
+        assert_eq!(
+            desktop.get_rule_by_id(&rule.id).await.unwrap().content,
+            "Use spaces"
+        );
+        for (db, cloud) in [(&desktop, &mut desktop_sync), (&laptop, &mut laptop_sync)] {
+            let idle = sync_via(db, cloud, &mut remote).await;
+            assert!(!idle.uploaded && idle.pulled.is_empty() && idle.pushed.is_empty());
+        }
+
+        // A deletion propagates instead of the rule coming back
+        desktop.delete_rule(&rule.id).await.unwrap();
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        let deleted = sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        assert_eq!(deleted.deleted, vec!["rule \"Style\""]);
+        assert!(laptop.get_all_rules().await.unwrap().is_empty());
+        assert!(remote.as_ref().unwrap().config.rules.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_leaves_managed_artifacts_alone() {
+        let desktop = Database::new_in_memory().await.unwrap();
+        let laptop = Database::new_in_memory().await.unwrap();
+        let (mut desktop_sync, mut laptop_sync) = (device("desktop"), device("laptop"));
+        let mut remote = None;
+        let source = "https://github.com/acme/rules.git";
+        let rule = create_rule(&desktop, "Security", "Never commit secrets.").await;
+        let mut metadata = Metadata::new();
+        metadata.insert(policy::MANAGED_METADATA_KEY, source);
+        desktop
+            .update_rule(
+                &rule.id,
+                UpdateRuleInput {
+                    metadata: Some(metadata),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        policy::save_org_policy(
+            &laptop,
+            crate::models::OrgPolicy {
+                sources: vec![source.to_string()],
+                allowed_overrides: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        edit_rule(&desktop, &rule.id, "Commit whatever.").await;
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        let refused = sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        assert!(refused.pulled.is_empty());
+        assert!(refused.errors[0].contains("managed by your organization"));
+        assert_eq!(
+            laptop.get_rule_by_id(&rule.id).await.unwrap().content,
+            "Never commit secrets."
+        );
+
+        desktop.delete_rule(&rule.id).await.unwrap();
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        let refused = sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        assert!(refused.deleted.is_empty());
+        assert!(laptop.get_rule_by_id(&rule.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_edits_keep_the_latest() {
+        let desktop = Database::new_in_memory().await.unwrap();
+        let laptop = Database::new_in_memory().await.unwrap();
+        let (mut desktop_sync, mut laptop_sync) = (device("desktop"), device("laptop"));
+        let mut remote = None;
+        let rule = create_rule(&desktop, "Style", "Use tabs").await;
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+
+        edit_rule(&desktop, &rule.id, "Desktop edit").await;
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        edit_rule(&laptop, &rule.id, "Laptop edit").await;
+        let settled = sync_via(&laptop, &mut laptop_sync, &mut remote).await;
+        assert_eq!(settled.conflicts, vec!["rule \"Style\""]);
+        assert!(settled.uploaded);
+
+        sync_via(&desktop, &mut desktop_sync, &mut remote).await;
+        for db in [&desktop, &laptop] {
+            assert_eq!(
+                db.get_rule_by_id(&rule.id).await.unwrap().content,
+                "Laptop edit"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configuration_is_validated_and_kept_local() {
+        let db = Database::new_in_memory().await.unwrap();
+        let bad = ConfigureCloudSyncInput {
+            backend: CloudSyncBackend::WebDav {
+                url: "ftp://example.com/sync.json".to_string(),
+                username: None,
+                password_secret: None,
+            },
+            interval_minutes: None,
+        };
+        assert!(configure_cloud_sync(&db, bad).await.is_err());
+
+        let input = ConfigureCloudSyncInput {
+            backend: device("x").backend,
+            interval_minutes: None,
+        };
+        let cloud = configure_cloud_sync(&db, input.clone()).await.unwrap();
+        assert_eq!(cloud.interval_minutes, DEFAULT_CLOUD_SYNC_INTERVAL_MINUTES);
+        let again = configure_cloud_sync(&db, input).await.unwrap();
+        assert_eq!(again.device_id, cloud.device_id);
+        assert!(!db
+            .export_configuration()
+            .await
+            .unwrap()
+            .settings
+            .contains_key(CLOUD_SYNC_KEY));
+
+        let document = serde_json::json!({
+            "format": 2, "deviceId": "x", "revision": 1,
+            "config": to_config(Vec::new()),
+        });
+        assert!(parse_document(document.to_string().as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::ai;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{AiRuleProposal, Rule};
+
+use super::fetch_rules;
+
+/// The rules with `ids`, in the order given.
+async fn rules_by_id(db: &Database, ids: &[String]) -> Result<Vec<Rule>> {
+    let rules = fetch_rules(db, None).await?;
+    ids.iter()
+        .map(|id| {
+            rules
+                .iter()
+                .find(|rule| &rule.id == id)
+                .cloned()
+                .ok_or_else(|| AppError::RuleNotFound { id: id.clone() })
+        })
+        .collect()
+}
+
+/// Asks the configured model to draft a rule. The proposal is not saved.
+#[tauri::command]
+pub async fn ai_draft_rule(
+    description: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<AiRuleProposal> {
+    let provider = ai::provider(&db).await?;
+    ai::draft_rule(provider.as_ref(), &description).await
+}
+
+/// Asks the configured model to shorten a rule to `max_chars`. The rule is not changed.
+#[tauri::command]
+pub async fn ai_condense_rule(
+    id: String,
+    max_chars: usize,
+    db: State<'_, Arc<Database>>,
+) -> Result<AiRuleProposal> {
+    let rules = rules_by_id(&db, &[id]).await?;
+    let provider = ai::provider(&db).await?;
+    ai::condense_rule(provider.as_ref(), &rules[0], max_chars).await
+}
+
+/// Asks the configured model to merge overlapping rules. The rules are not changed.
+#[tauri::command]
+pub async fn ai_merge_rules(
+    ids: Vec<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<AiRuleProposal> {
+    let rules = rules_by_id(&db, &ids).await?;
+    let provider = ai::provider(&db).await?;
+    ai::merge_rules(provider.as_ref(), &rules).await
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Manager, State};
+
+use crate::backup::{self, BackupInfo};
+use crate::database::{get_app_data_path, Database};
+use crate::error::{AppError, Result};
+use crate::snapshot;
+use crate::sync::SyncEngine;
+
+use super::{reconcile_after_mutation, validate_path};
+
+/// Resolves a backup destination whose file may not exist yet by validating its parent.
+fn validate_backup_destination(path: &str) -> Result<PathBuf> {
+    let requested = PathBuf::from(path);
+    let file_name = requested
+        .file_name()
+        .ok_or_else(|| AppError::InvalidInput {
+            message: "Backup path must include a file name".to_string(),
+        })?
+        .to_owned();
+    let parent = requested
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .ok_or_else(|| AppError::InvalidInput {
+            message: "Backup path must include a directory".to_string(),
+        })?;
+    let parent = validate_path(&parent.to_string_lossy())?;
+    Ok(parent.join(file_name))
+}
+
+#[tauri::command]
+pub async fn backup_database(path: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    let dest = validate_backup_destination(&path)?;
+    db.backup_to(dest).await
+}
+
+#[tauri::command]
+pub async fn restore_database(
+    path: String,
+    db: State<'_, Arc<Database>>,
+    app: tauri::AppHandle,
+) -> Result<()> {
+    let src = validate_path(&path)?;
+
+    // Keep a copy of the current state so a bad restore can be undone.
+    let safety = backup::create_backup(&db, &backup::backup_dir(&get_app_data_path(&app)?)).await?;
+    log::info!("Pre-restore backup written to {}", safety.path);
+
+    db.restore_from(src).await?;
+
+    {
+        if let Some(s) = app.try_state::<crate::GlobalStatus>() {
+            *s.sync_status.lock() = "Syncing...".to_string();
+            s.update_tray();
+        }
+    }
+
+    let engine = SyncEngine::new(&db);
+    let rules = db.get_all_rules().await?;
+    let sync_result = engine.sync_all(rules).await;
+    if !sync_result.errors.is_empty() || !sync_result.conflicts.is_empty() {
+        log::warn!(
+            "Post-restore AI tool sync completed with issues. Errors: {:?}, Conflicts: {:?}",
+            sync_result.errors,
+            sync_result.conflicts
+        );
+    }
+
+    reconcile_after_mutation(db.inner().clone()).await;
+
+    {
+        if let Some(s) = app.try_state::<crate::GlobalStatus>() {
+            *s.sync_status.lock() = "Idle".to_string();
+            s.update_tray();
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_database_backups(app: tauri::AppHandle) -> Result<Vec<BackupInfo>> {
+    backup::list_backups(&backup::backup_dir(&get_app_data_path(&app)?))
+}
+
+/// Writes an export snapshot to the configured snapshot directory immediately.
+#[tauri::command]
+pub async fn create_export_snapshot(db: State<'_, Arc<Database>>) -> Result<BackupInfo> {
+    let dir = snapshot::snapshot_dir(&db)
+        .await?
+        .ok_or_else(|| AppError::InvalidInput {
+            message: "No export snapshot directory is configured".to_string(),
+        })?;
+    snapshot::create_snapshot(&db, &dir).await
+}
+
+#[tauri::command]
+pub async fn list_export_snapshots(db: State<'_, Arc<Database>>) -> Result<Vec<BackupInfo>> {
+    match snapshot::snapshot_dir(&db).await? {
+        Some(dir) => snapshot::list_snapshots(&dir),
+        None => Ok(Vec::new()),
+    }
+}
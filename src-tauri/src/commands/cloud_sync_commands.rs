@@ -0,0 +1,38 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::cloud_sync;
+use crate::database::Database;
+use crate::error::Result;
+use crate::models::{CloudSync, CloudSyncResult, ConfigureCloudSyncInput};
+
+#[tauri::command]
+pub async fn get_cloud_sync(db: State<'_, Arc<Database>>) -> Result<Option<CloudSync>> {
+    Ok(cloud_sync::get_cloud_sync(db.inner()).await)
+}
+
+#[tauri::command]
+pub async fn configure_cloud_sync(
+    input: ConfigureCloudSyncInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<CloudSync> {
+    cloud_sync::configure_cloud_sync(db.inner(), input).await
+}
+
+#[tauri::command]
+pub async fn remove_cloud_sync(db: State<'_, Arc<Database>>) -> Result<()> {
+    cloud_sync::remove_cloud_sync(db.inner()).await
+}
+
+#[tauri::command]
+pub async fn set_cloud_sync_enabled(
+    enabled: bool,
+    db: State<'_, Arc<Database>>,
+) -> Result<CloudSync> {
+    cloud_sync::set_cloud_sync_enabled(db.inner(), enabled).await
+}
+
+#[tauri::command]
+pub async fn sync_cloud_now(db: State<'_, Arc<Database>>) -> Result<CloudSyncResult> {
+    cloud_sync::sync_cloud_now(db.inner().clone()).await
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::constants::limits::MAX_COLLECTION_NAME_LENGTH;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    Collection, CollectionItem, CollectionItemRef, CollectionItemType, CreateCollectionInput,
+};
+
+fn validate_collection_name(name: &str) -> Result<()> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::Validation(
+            "Collection name cannot be empty".to_string(),
+        ));
+    }
+    if trimmed.len() > MAX_COLLECTION_NAME_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Collection name too long (max {} characters)",
+            MAX_COLLECTION_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_all_collections(db: State<'_, Arc<Database>>) -> Result<Vec<Collection>> {
+    db.get_all_collections().await
+}
+
+#[tauri::command]
+pub async fn get_collection_items(db: State<'_, Arc<Database>>) -> Result<Vec<CollectionItem>> {
+    db.get_all_collection_items().await
+}
+
+#[tauri::command]
+pub async fn create_collection(
+    input: CreateCollectionInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<Collection> {
+    validate_collection_name(&input.name)?;
+    db.create_collection(input).await
+}
+
+#[tauri::command]
+pub async fn rename_collection(
+    id: String,
+    name: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Collection> {
+    validate_collection_name(&name)?;
+    db.rename_collection(&id, &name).await
+}
+
+#[tauri::command]
+pub async fn move_collection(
+    id: String,
+    parent_id: Option<String>,
+    position: Option<usize>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Collection> {
+    db.move_collection(&id, parent_id.as_deref(), position)
+        .await
+}
+
+#[tauri::command]
+pub async fn reorder_collections(
+    parent_id: Option<String>,
+    ordered_ids: Vec<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    db.reorder_collections(parent_id.as_deref(), &ordered_ids)
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_collection(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    db.delete_collection(&id).await
+}
+
+#[tauri::command]
+pub async fn move_to_collection(
+    item_type: CollectionItemType,
+    item_id: String,
+    collection_id: Option<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    db.set_item_collection(item_type, &item_id, collection_id.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn reorder_collection_items(
+    collection_id: String,
+    items: Vec<CollectionItemRef>,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    db.reorder_collection_items(&collection_id, &items).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_collection_name() {
+        assert!(validate_collection_name("Frontend").is_ok());
+        assert!(validate_collection_name("   ").is_err());
+        assert!(validate_collection_name(&"x".repeat(MAX_COLLECTION_NAME_LENGTH + 1)).is_err());
+    }
+}
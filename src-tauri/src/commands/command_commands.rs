@@ -3,35 +3,62 @@ use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 
+use crate::artifacts::validate_artifact_globs;
 use crate::commands::{RUNNING_TESTS, TEST_INVOCATION_TIMESTAMPS};
 use crate::constants::limits::TEST_CMD_RATE_LIMIT_MAX;
 use crate::constants::timing::{TEST_CMD_RATE_LIMIT_WINDOW, TEST_CMD_TIMEOUT};
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::execution::{
-    argument_env_var_name, execute_and_log, replace_template_with_env_ref, sanitize_argument_value,
-    validate_enum_argument, ExecuteAndLogInput,
+    execute_and_log, preview_command, register_execution, render_command_invocation,
+    resolve_working_dir, validate_stdin, ExecuteAndLogInput, OutputSink,
 };
 use crate::mcp::McpManager;
 use crate::models::{
-    Command, CreateCommandInput, SyncError, SyncResult, TestCommandResult, UpdateCommandInput,
+    ArtifactListQuery, ArtifactPage, Command, CommandOutputEvent, CommandPreview,
+    CreateCommandInput, MetadataQuery, SyncError, SyncResult, TestCommandResult,
+    UpdateCommandInput,
+};
+use crate::output_parser::validate_parser;
+use crate::policy;
+use crate::sandbox::validate_profile;
+use crate::scheduler::validate_schedule;
+use crate::secrets::{resolve_secrets, validate_secret_names};
+use crate::slash_commands::{
+    local_slash_roots, normalize_command_namespace, validate_slash_frontmatter,
+    SlashCommandSyncEngine,
 };
-use crate::slash_commands::SlashCommandSyncEngine;
 
 use crate::templates::commands::{get_bundled_command_templates, TemplateCommand};
 use std::time::Instant;
 
 use super::{
     command_file_targets, command_file_targets_for_root, reconcile_after_mutation,
-    register_local_paths, validate_command_arguments, validate_command_input, validate_path,
-    validate_paths_within_registered_roots,
+    register_local_paths, validate_command_arguments, validate_command_input, validate_metadata,
+    validate_path, validate_paths_within_registered_roots, validate_stdin_parameter,
 };
 
 #[tauri::command]
-pub async fn get_all_commands(db: State<'_, Arc<Database>>) -> Result<Vec<Command>> {
-    db.get_all_commands().await
+pub async fn get_all_commands(
+    metadata_query: Option<MetadataQuery>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Command>> {
+    let commands = db.get_all_commands().await?;
+    Ok(match metadata_query {
+        Some(query) => query.apply(commands),
+        None => commands,
+    })
+}
+
+/// One page of commands, filtered and sorted, for lists too long to load at once.
+#[tauri::command]
+pub async fn list_commands(
+    query: ArtifactListQuery,
+    db: State<'_, Arc<Database>>,
+) -> Result<ArtifactPage<Command>> {
+    query.apply(db.get_all_commands().await?)
 }
 
 #[tauri::command]
@@ -47,6 +74,19 @@ pub async fn create_command(
 ) -> Result<Command> {
     validate_command_input(&input.name, &input.script)?;
     validate_command_arguments(&input.arguments)?;
+    validate_stdin_parameter(&input.arguments, input.accepts_stdin)?;
+    validate_secret_names(&input.required_secrets)?;
+    validate_schedule(input.schedule.as_deref(), &input.arguments)?;
+    normalize_command_namespace(input.slash_namespace.as_deref())?;
+    validate_slash_frontmatter(&input.slash_frontmatter)?;
+    if let Some(sandbox) = &input.sandbox {
+        validate_profile(sandbox)?;
+    }
+    validate_artifact_globs(&input.artifact_globs)?;
+    if let Some(parser) = &input.output_parser {
+        validate_parser(parser)?;
+    }
+    validate_metadata(&input.metadata)?;
     for path in &input.target_paths {
         validate_path(path)?;
     }
@@ -58,13 +98,22 @@ pub async fn create_command(
     // Autosync slash commands on save when the command opts in to slash generation.
     if created.generate_slash_commands && !created.slash_command_adapters.is_empty() {
         let engine = SlashCommandSyncEngine::new(Arc::clone(&db));
-        // Sync global and local (per target_paths) slash files; errors are non-fatal.
+        // Sync global and local (per repository root) slash files; errors are non-fatal.
         if let Err(e) = engine.sync_command(&created, true) {
-            log::warn!("Failed to sync global slash command for '{}': {}", created.name, e);
+            log::warn!(
+                "Failed to sync global slash command for '{}': {}",
+                created.name,
+                e
+            );
         }
-        if !created.target_paths.is_empty() {
-            if let Err(e) = engine.sync_command(&created, false) {
-                log::warn!("Failed to sync local slash command for '{}': {}", created.name, e);
+        let roots = local_slash_roots(&db, &created).await?;
+        if !roots.is_empty() {
+            if let Err(e) = engine.sync_command_to_roots(&created, false, &roots) {
+                log::warn!(
+                    "Failed to sync local slash command for '{}': {}",
+                    created.name,
+                    e
+                );
             }
         }
     }
@@ -75,7 +124,7 @@ pub async fn create_command(
 #[tauri::command]
 pub async fn update_command(
     id: String,
-    input: UpdateCommandInput,
+    mut input: UpdateCommandInput,
     db: State<'_, Arc<Database>>,
     mcp: State<'_, McpManager>,
 ) -> Result<Command> {
@@ -83,6 +132,9 @@ pub async fn update_command(
     // renames and adapter deselections that would leave orphan slash files.
     let existing = db.get_command_by_id(&id).await?;
 
+    policy::keep_marker(&existing.metadata, input.metadata.as_mut());
+    policy::ensure_editable(&db, "Command", &input, &existing).await?;
+
     if let Some(name) = &input.name {
         if let Some(script) = &input.script {
             validate_command_input(name, script)?;
@@ -97,6 +149,46 @@ pub async fn update_command(
         validate_command_arguments(args)?;
     }
 
+    validate_stdin_parameter(
+        input.arguments.as_deref().unwrap_or(&existing.arguments),
+        input.accepts_stdin.unwrap_or(existing.accepts_stdin),
+    )?;
+
+    if let Some(secrets) = &input.required_secrets {
+        validate_secret_names(secrets)?;
+    }
+
+    if input.schedule.is_some() || input.arguments.is_some() {
+        validate_schedule(
+            input.schedule.as_deref().or(existing.schedule.as_deref()),
+            input.arguments.as_deref().unwrap_or(&existing.arguments),
+        )?;
+    }
+
+    if let Some(namespace) = &input.slash_namespace {
+        normalize_command_namespace(Some(namespace))?;
+    }
+
+    if let Some(frontmatter) = &input.slash_frontmatter {
+        validate_slash_frontmatter(frontmatter)?;
+    }
+
+    if let Some(sandbox) = &input.sandbox {
+        validate_profile(sandbox)?;
+    }
+
+    if let Some(globs) = &input.artifact_globs {
+        validate_artifact_globs(globs)?;
+    }
+
+    if let Some(parser) = &input.output_parser {
+        validate_parser(parser)?;
+    }
+
+    if let Some(metadata) = &input.metadata {
+        validate_metadata(metadata)?;
+    }
+
     if let Some(paths) = &input.target_paths {
         for path in paths {
             validate_path(path)?;
@@ -109,6 +201,7 @@ pub async fn update_command(
     mcp.refresh_commands(&db).await?;
 
     let engine = SlashCommandSyncEngine::new(Arc::clone(&db));
+    let existing_roots = local_slash_roots(&db, &existing).await?;
 
     // Orphan prevention: if slash command generation was disabled, remove all existing files.
     if existing.generate_slash_commands
@@ -117,28 +210,34 @@ pub async fn update_command(
     {
         if let Err(e) = engine.remove_command(
             &existing.name,
+            existing.slash_namespace.as_deref(),
             &existing.slash_command_adapters,
-            &existing.target_paths,
+            &existing_roots,
         ) {
             log::warn!(
                 "Failed to remove slash command files for '{}': {}",
-                existing.name, e
+                existing.name,
+                e
             );
         }
     }
 
-    // Orphan prevention: if the command was renamed, remove stale slash files for
-    // the old name across all adapters and all target paths.
-    let name_changed = existing.name != updated.name;
+    // Orphan prevention: if the command was renamed, moved to another namespace or changed
+    // scope, remove stale slash files for the old location across all adapters and roots.
+    let name_changed = existing.name != updated.name
+        || existing.slash_namespace != updated.slash_namespace
+        || existing.slash_scope != updated.slash_scope;
     if name_changed && !existing.slash_command_adapters.is_empty() {
         if let Err(e) = engine.remove_command(
             &existing.name,
+            existing.slash_namespace.as_deref(),
             &existing.slash_command_adapters,
-            &existing.target_paths,
+            &existing_roots,
         ) {
             log::warn!(
                 "Failed to remove stale slash files for renamed command '{}': {}",
-                existing.name, e
+                existing.name,
+                e
             );
         }
     } else {
@@ -152,10 +251,16 @@ pub async fn update_command(
             .cloned()
             .collect();
         if !deselected.is_empty() {
-            if let Err(e) = engine.remove_command(&existing.name, &deselected, &existing.target_paths) {
+            if let Err(e) = engine.remove_command(
+                &existing.name,
+                existing.slash_namespace.as_deref(),
+                &deselected,
+                &existing_roots,
+            ) {
                 log::warn!(
                     "Failed to remove deselected adapter slash files for '{}': {}",
-                    existing.name, e
+                    existing.name,
+                    e
                 );
             }
         }
@@ -164,11 +269,20 @@ pub async fn update_command(
     // Autosync slash commands on save when the command opts in to slash generation.
     if updated.generate_slash_commands && !updated.slash_command_adapters.is_empty() {
         if let Err(e) = engine.sync_command(&updated, true) {
-            log::warn!("Failed to sync global slash command for '{}': {}", updated.name, e);
+            log::warn!(
+                "Failed to sync global slash command for '{}': {}",
+                updated.name,
+                e
+            );
         }
-        if !updated.target_paths.is_empty() {
-            if let Err(e) = engine.sync_command(&updated, false) {
-                log::warn!("Failed to sync local slash command for '{}': {}", updated.name, e);
+        let roots = local_slash_roots(&db, &updated).await?;
+        if !roots.is_empty() {
+            if let Err(e) = engine.sync_command_to_roots(&updated, false, &roots) {
+                log::warn!(
+                    "Failed to sync local slash command for '{}': {}",
+                    updated.name,
+                    e
+                );
             }
         }
     }
@@ -184,6 +298,7 @@ pub async fn delete_command(
 ) -> Result<()> {
     // Read before deleting so we have adapter and target_path info for cleanup.
     let command = db.get_command_by_id(&id).await?;
+    policy::ensure_deletable(&db, "Command", &command.name, &command.metadata).await?;
 
     db.delete_command(&id).await?;
     mcp.refresh_commands(&db).await?;
@@ -191,14 +306,17 @@ pub async fn delete_command(
     // Remove slash command files for this command across all adapters and repo roots.
     if !command.slash_command_adapters.is_empty() {
         let engine = SlashCommandSyncEngine::new(Arc::clone(&db));
+        let roots = local_slash_roots(&db, &command).await?;
         if let Err(e) = engine.remove_command(
             &command.name,
+            command.slash_namespace.as_deref(),
             &command.slash_command_adapters,
-            &command.target_paths,
+            &roots,
         ) {
             log::warn!(
                 "Failed to remove slash command files for deleted command '{}': {}",
-                command.name, e
+                command.name,
+                e
             );
         }
     }
@@ -211,9 +329,47 @@ pub async fn delete_command(
 
 #[tauri::command]
 pub async fn test_command(
+    app: tauri::AppHandle,
     id: String,
     args: HashMap<String, String>,
+    execution_id: Option<String>,
+    stdin: Option<String>,
     db: State<'_, Arc<Database>>,
+) -> Result<TestCommandResult> {
+    run_test(&app, id, args, stdin, execution_id, "test", &db).await
+}
+
+/// Runs a logged execution's command again with the arguments it was given. Like
+/// `test_command`, the result is logged and output streams as `command-output` events.
+#[tauri::command]
+pub async fn rerun_execution(
+    app: tauri::AppHandle,
+    execution_log_id: String,
+    execution_id: Option<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<TestCommandResult> {
+    let log = db.get_execution_log(&execution_log_id).await?;
+    let args: HashMap<String, String> = if log.arguments.trim().is_empty() {
+        HashMap::new()
+    } else {
+        serde_json::from_str(&log.arguments).map_err(|e| AppError::InvalidInput {
+            message: format!(
+                "Arguments of execution {} cannot be replayed: {}",
+                execution_log_id, e
+            ),
+        })?
+    };
+    run_test(&app, log.command_id, args, None, execution_id, "rerun", &db).await
+}
+
+async fn run_test(
+    app: &tauri::AppHandle,
+    id: String,
+    args: HashMap<String, String>,
+    stdin: Option<String>,
+    execution_id: Option<String>,
+    triggered_by: &str,
+    db: &State<'_, Arc<Database>>,
 ) -> Result<TestCommandResult> {
     // 1. Global Rate Limiting
     {
@@ -253,7 +409,11 @@ pub async fn test_command(
         running.insert(id.clone());
     }
 
-    let result = test_command_internal(&id, args, &db).await;
+    // The caller may choose the execution id so it can cancel before any output arrives.
+    let execution_id = execution_id
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let result = test_command_internal(app, &id, args, stdin, execution_id, triggered_by, db).await;
 
     // Clean up regardless of success or failure
     {
@@ -265,33 +425,44 @@ pub async fn test_command(
 }
 
 async fn test_command_internal(
+    app: &tauri::AppHandle,
     id: &str,
     args: HashMap<String, String>,
+    stdin: Option<String>,
+    execution_id: String,
+    triggered_by: &str,
     db: &State<'_, Arc<Database>>,
 ) -> Result<TestCommandResult> {
     let cmd = db.get_command_by_id(id).await?;
-    let mut script = cmd.script.clone();
-    let mut envs: Vec<(String, String)> = Vec::new();
-
-    for arg in &cmd.arguments {
-        script = replace_template_with_env_ref(&script, &arg.name);
-
-        let raw_value = args
-            .get(&arg.name)
-            .cloned()
-            .or_else(|| arg.default_value.clone())
-            .unwrap_or_default();
-        let safe_value = sanitize_argument_value(&raw_value)?;
-
-        // Enum validation (shared helper)
-        if matches!(arg.arg_type, crate::models::ArgumentType::Enum) {
-            validate_enum_argument(&arg.name, &raw_value, &arg.options)?;
-        }
-
-        envs.push((argument_env_var_name(&arg.name), safe_value));
-    }
+    validate_stdin(&cmd, stdin.as_deref())?;
+    let (script, envs) = render_command_invocation(&cmd, &args)?;
 
     let args_json = serde_json::to_string(&args).map_err(AppError::Serialization)?;
+    let secrets = resolve_secrets(db, &cmd.required_secrets).await?;
+    // A local-scoped command runs against its first target path as the repo root.
+    let working_dir = resolve_working_dir(
+        cmd.working_dir.as_deref(),
+        cmd.target_paths.first().map(String::as_str),
+    )?;
+
+    // Stream output to the UI as `command-output` events while the command runs.
+    let (_registration, cancel) = register_execution(&execution_id);
+    let on_output: OutputSink = {
+        let app = app.clone();
+        let execution_id = execution_id.clone();
+        let command_id = cmd.id.clone();
+        Arc::new(move |stream, line| {
+            let _ = app.emit(
+                "command-output",
+                CommandOutputEvent {
+                    execution_id: execution_id.clone(),
+                    command_id: command_id.clone(),
+                    stream,
+                    line: line.to_string(),
+                },
+            );
+        })
+    };
 
     let (exit_code, stdout, stderr, duration_ms) = execute_and_log(ExecuteAndLogInput {
         db: Some(db),
@@ -301,15 +472,25 @@ async fn test_command_internal(
         timeout_dur: TEST_CMD_TIMEOUT,
         envs: &envs,
         arguments_json: &args_json,
-        triggered_by: "test",
+        triggered_by,
         max_retries: cmd.max_retries,
         adapter_context: None,
+        on_output: Some(on_output),
+        cancel: Some(cancel),
+        secrets: &secrets,
+        working_dir,
+        shell: cmd.shell,
+        sandbox: cmd.sandbox.as_ref(),
+        artifacts: &cmd.artifact_globs,
+        output_parser: cmd.output_parser.as_ref(),
+        stdin: stdin.as_deref(),
     })
     .await?;
 
     let success = exit_code == 0;
 
     Ok(TestCommandResult {
+        execution_id,
         success,
         stdout,
         stderr,
@@ -318,6 +499,29 @@ async fn test_command_internal(
     })
 }
 
+/// Shows what `test_command` would run with these arguments without executing anything.
+#[tauri::command]
+pub async fn preview_command_execution(
+    id: String,
+    args: HashMap<String, String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<CommandPreview> {
+    let cmd = db.get_command_by_id(&id).await?;
+    preview_command(&cmd, &args, TEST_CMD_TIMEOUT)
+}
+
+/// Stops a running command test, killing every process it started.
+#[tauri::command]
+pub async fn cancel_execution(execution_id: String) -> Result<()> {
+    if crate::execution::cancel_execution(&execution_id) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput {
+            message: format!("No running execution with id {}", execution_id),
+        })
+    }
+}
+
 #[tauri::command]
 pub async fn sync_commands(db: State<'_, Arc<Database>>) -> Result<SyncResult> {
     // ... existing sync code ...
@@ -403,6 +607,8 @@ pub async fn sync_commands(db: State<'_, Arc<Database>>) -> Result<SyncResult> {
         files_written,
         errors,
         conflicts: Vec::new(),
+        uninstalled_tools: Vec::new(),
+        rolled_back_adapters: Vec::new(),
     })
 }
 
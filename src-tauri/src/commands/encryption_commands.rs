@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::database::{Database, DatabaseKey};
+use crate::error::Result;
+use crate::keychain;
+
+#[tauri::command]
+pub fn get_database_encryption_status(db: State<'_, Arc<Database>>) -> bool {
+    db.is_encrypted()
+}
+
+/// Generates a key, stores it in the OS keychain and encrypts the existing plaintext
+/// database with it.
+#[tauri::command]
+pub async fn enable_database_encryption(db: State<'_, Arc<Database>>) -> Result<()> {
+    let key = DatabaseKey::generate()?;
+    let stored = key.clone();
+    keychain::run_blocking(move || keychain::store_database_key(&stored)).await?;
+
+    if let Err(e) = db.enable_encryption(key).await {
+        if let Err(cleanup) = keychain::run_blocking(keychain::delete_database_key).await {
+            log::warn!("Failed to remove unused database key: {}", cleanup);
+        }
+        return Err(e);
+    }
+
+    log::info!("Database encryption enabled");
+    Ok(())
+}
+
+/// Decrypts the database back to a plaintext file and removes the key from the keychain.
+#[tauri::command]
+pub async fn disable_database_encryption(db: State<'_, Arc<Database>>) -> Result<()> {
+    db.disable_encryption().await?;
+    if let Err(e) = keychain::run_blocking(keychain::delete_database_key).await {
+        log::warn!("Failed to remove database key from keychain: {}", e);
+    }
+
+    log::info!("Database encryption disabled");
+    Ok(())
+}
@@ -5,11 +5,29 @@ use tauri::State;
 use crate::database::Database;
 use crate::error::Result;
 use crate::models::{
-    ImportExecutionOptions, ImportExecutionResult, ImportHistoryEntry, ImportScanResult,
+    BundleSelection, CatalogEntry, CatalogInstall, ConfigureTeamRepoInput,
+    CreateImportSubscriptionInput, ImportCandidatePreview, ImportExecutionOptions,
+    ImportExecutionResult, ImportHistoryEntry, ImportMappingRule, ImportScanResult,
+    ImportSubscription, PeerShareInfo, ShareLinkInfo, ShareLinkSelection, SubscriptionCheckResult,
+    TeamRepo, TeamRepoChange, TeamRepoPullResult, TeamRepoPushInput, TeamRepoPushResult,
 };
 use crate::rule_import;
+use crate::rule_import::peer::{self, PeerShareManager};
+use crate::rule_import::share_link::{self, ShareLinkManager};
+use crate::rule_import::{archive, catalog, mapping, subscriptions, team_repo};
 
-use super::validate_path;
+use super::{validate_export_path, validate_path};
+
+/// Scans a directory for one artifact type and applies the stored path mapping rules.
+async fn scan_directory(
+    db: &Database,
+    path: &std::path::Path,
+    max_size: u64,
+    artifact_type: crate::models::ImportArtifactType,
+) -> ImportScanResult {
+    let rules = mapping::list_mapping_rules(db).await;
+    rule_import::scan_directory_with_mapping(path, max_size, Some(artifact_type), &rules)
+}
 
 #[tauri::command]
 pub async fn scan_ai_tool_import_candidates(
@@ -81,7 +99,10 @@ pub fn scan_rule_file_import(
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    Ok(rule_import::scan_file_to_candidates(&validated_path, max_size))
+    Ok(rule_import::scan_file_to_candidates(
+        &validated_path,
+        max_size,
+    ))
 }
 
 #[tauri::command]
@@ -93,27 +114,32 @@ pub async fn import_rules_from_directory(
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    let scan = rule_import::scan_directory_to_candidates(
+    let scan = scan_directory(
+        &db,
         &validated_path,
         max_size,
-        Some(crate::models::ImportArtifactType::Rule),
-    );
+        crate::models::ImportArtifactType::Rule,
+    )
+    .await;
     rule_import::execute_import(db.inner().clone(), scan, opts).await
 }
 
 #[tauri::command]
-pub fn scan_rule_directory_import(
+pub async fn scan_rule_directory_import(
     path: String,
     options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
 ) -> Result<ImportScanResult> {
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    Ok(rule_import::scan_directory_to_candidates(
+    Ok(scan_directory(
+        &db,
         &validated_path,
         max_size,
-        Some(crate::models::ImportArtifactType::Rule),
-    ))
+        crate::models::ImportArtifactType::Rule,
+    )
+    .await)
 }
 
 #[tauri::command]
@@ -162,6 +188,89 @@ pub fn scan_rule_clipboard_import(
     rule_import::scan_clipboard_to_candidates(&content, name.as_deref(), max_size)
 }
 
+#[tauri::command]
+pub async fn start_peer_share(
+    db: State<'_, Arc<Database>>,
+    share: State<'_, PeerShareManager>,
+) -> Result<PeerShareInfo> {
+    share.start(db.inner().clone()).await
+}
+
+#[tauri::command]
+pub fn stop_peer_share(share: State<'_, PeerShareManager>) {
+    share.stop();
+}
+
+#[tauri::command]
+pub fn get_peer_share(share: State<'_, PeerShareManager>) -> Option<PeerShareInfo> {
+    share.current()
+}
+
+/// Opens a read-only link to a snapshot of the selected rules and commands.
+#[tauri::command]
+pub async fn start_share_link(
+    selection: Option<ShareLinkSelection>,
+    db: State<'_, Arc<Database>>,
+    link: State<'_, ShareLinkManager>,
+) -> Result<ShareLinkInfo> {
+    link.start(&db, &selection.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub fn stop_share_link(link: State<'_, ShareLinkManager>) {
+    link.stop();
+}
+
+#[tauri::command]
+pub fn get_share_link(link: State<'_, ShareLinkManager>) -> Option<ShareLinkInfo> {
+    link.current()
+}
+
+/// Writes the pages a share link would serve into the directory `path` as a static site.
+#[tauri::command]
+pub async fn export_share_site(
+    path: String,
+    selection: Option<ShareLinkSelection>,
+    db: State<'_, Arc<Database>>,
+) -> Result<usize> {
+    let target = validate_export_path(&path)?;
+    share_link::export_site(&db, &target, &selection.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn scan_peer_import(
+    address: String,
+    code: String,
+    options: Option<ImportExecutionOptions>,
+) -> Result<ImportScanResult> {
+    let opts = options.unwrap_or_default();
+    let max_size = rule_import::resolve_max_size(&opts);
+    peer::scan_peer_to_candidates(&address, &code, max_size).await
+}
+
+#[tauri::command]
+pub async fn import_from_peer(
+    address: String,
+    code: String,
+    options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportExecutionResult> {
+    let opts = options.unwrap_or_default();
+    let max_size = rule_import::resolve_max_size(&opts);
+    let scan = peer::scan_peer_to_candidates(&address, &code, max_size).await?;
+    rule_import::execute_import(db.inner().clone(), scan, opts).await
+}
+
+#[tauri::command]
+pub async fn preview_import_changes(
+    scan: ImportScanResult,
+    options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ImportCandidatePreview>> {
+    let opts = options.unwrap_or_default();
+    rule_import::preview::preview_import(db.inner().clone(), &scan, &opts).await
+}
+
 #[tauri::command]
 pub async fn get_rule_import_history(
     db: State<'_, Arc<Database>>,
@@ -169,6 +278,21 @@ pub async fn get_rule_import_history(
     Ok(rule_import::read_import_history(db.inner().clone()).await)
 }
 
+#[tauri::command]
+pub async fn get_import_mapping_rules(
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ImportMappingRule>> {
+    Ok(mapping::list_mapping_rules(db.inner()).await)
+}
+
+#[tauri::command]
+pub async fn set_import_mapping_rules(
+    rules: Vec<ImportMappingRule>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ImportMappingRule>> {
+    mapping::save_mapping_rules(db.inner(), rules).await
+}
+
 #[tauri::command]
 pub async fn import_commands_from_directory(
     path: String,
@@ -178,27 +302,32 @@ pub async fn import_commands_from_directory(
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    let scan = rule_import::scan_directory_to_candidates(
+    let scan = scan_directory(
+        &db,
         &validated_path,
         max_size,
-        Some(crate::models::ImportArtifactType::SlashCommand),
-    );
+        crate::models::ImportArtifactType::SlashCommand,
+    )
+    .await;
     rule_import::execute_import(db.inner().clone(), scan, opts).await
 }
 
 #[tauri::command]
-pub fn scan_command_directory_import(
+pub async fn scan_command_directory_import(
     path: String,
     options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
 ) -> Result<ImportScanResult> {
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    Ok(rule_import::scan_directory_to_candidates(
+    Ok(scan_directory(
+        &db,
         &validated_path,
         max_size,
-        Some(crate::models::ImportArtifactType::SlashCommand),
-    ))
+        crate::models::ImportArtifactType::SlashCommand,
+    )
+    .await)
 }
 
 #[tauri::command]
@@ -210,27 +339,233 @@ pub async fn import_skills_from_directory(
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    let scan = rule_import::scan_directory_to_candidates(
+    let scan = scan_directory(
+        &db,
         &validated_path,
         max_size,
-        Some(crate::models::ImportArtifactType::Skill),
-    );
+        crate::models::ImportArtifactType::Skill,
+    )
+    .await;
     rule_import::execute_import(db.inner().clone(), scan, opts).await
 }
 
 #[tauri::command]
-pub fn scan_skill_directory_import(
+pub async fn scan_skill_directory_import(
     path: String,
     options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
 ) -> Result<ImportScanResult> {
     let validated_path = validate_path(&path)?;
     let opts = options.unwrap_or_default();
     let max_size = rule_import::resolve_max_size(&opts);
-    Ok(rule_import::scan_directory_to_candidates(
+    Ok(scan_directory(
+        &db,
         &validated_path,
         max_size,
-        Some(crate::models::ImportArtifactType::Skill),
-    ))
+        crate::models::ImportArtifactType::Skill,
+    )
+    .await)
+}
+
+#[tauri::command]
+pub fn scan_zip_import(
+    path: String,
+    options: Option<ImportExecutionOptions>,
+) -> Result<ImportScanResult> {
+    let validated_path = validate_path(&path)?;
+    let opts = options.unwrap_or_default();
+    let max_size = rule_import::resolve_max_size(&opts);
+    archive::scan_zip_to_candidates(&validated_path, max_size)
+}
+
+#[tauri::command]
+pub async fn import_from_zip(
+    path: String,
+    options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportExecutionResult> {
+    let validated_path = validate_path(&path)?;
+    let opts = options.unwrap_or_default();
+    archive::import_zip(db.inner().clone(), &validated_path, opts).await
+}
+
+#[tauri::command]
+pub async fn export_bundle(
+    path: String,
+    selection: BundleSelection,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    archive::export_bundle(db.inner(), &validate_export_path(&path)?, selection).await
+}
+
+/// Exports one skill as a `.skillpack` and returns the path written.
+#[tauri::command]
+pub async fn export_skill_pack(
+    id: String,
+    path: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<String> {
+    let written =
+        archive::export_skill_pack(db.inner(), &id, &validate_export_path(&path)?).await?;
+    Ok(written.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn scan_skill_pack_import(
+    path: String,
+    options: Option<ImportExecutionOptions>,
+) -> Result<ImportScanResult> {
+    let validated_path = validate_path(&path)?;
+    let opts = options.unwrap_or_default();
+    let max_size = rule_import::resolve_max_size(&opts);
+    archive::scan_skill_pack(&validated_path, max_size)
+}
+
+#[tauri::command]
+pub async fn import_skill_pack(
+    path: String,
+    options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportExecutionResult> {
+    let validated_path = validate_path(&path)?;
+    let opts = options.unwrap_or_default();
+    archive::import_skill_pack(db.inner().clone(), &validated_path, opts).await
+}
+
+#[tauri::command]
+pub async fn browse_rule_catalog(
+    query: Option<String>,
+    tag: Option<String>,
+    refresh: Option<bool>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<CatalogEntry>> {
+    catalog::browse(
+        db.inner(),
+        query.as_deref(),
+        tag.as_deref(),
+        refresh.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn preview_catalog_entry(
+    entry_id: String,
+    options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportScanResult> {
+    let opts = options.unwrap_or_default();
+    let max_size = rule_import::resolve_max_size(&opts);
+    catalog::preview(db.inner(), &entry_id, max_size).await
+}
+
+#[tauri::command]
+pub async fn install_catalog_entries(
+    entry_ids: Vec<String>,
+    options: Option<ImportExecutionOptions>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportExecutionResult> {
+    catalog::install(db.inner().clone(), &entry_ids, options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_catalog_installs(db: State<'_, Arc<Database>>) -> Result<Vec<CatalogInstall>> {
+    Ok(catalog::list_installs(db.inner()).await)
+}
+
+#[tauri::command]
+pub async fn list_import_subscriptions(
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ImportSubscription>> {
+    Ok(subscriptions::list_subscriptions(db.inner()).await)
+}
+
+#[tauri::command]
+pub async fn add_import_subscription(
+    input: CreateImportSubscriptionInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportSubscription> {
+    subscriptions::add_subscription(db.inner(), input).await
+}
+
+#[tauri::command]
+pub async fn remove_import_subscription(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    subscriptions::remove_subscription(db.inner(), &id).await
+}
+
+#[tauri::command]
+pub async fn set_import_subscription_enabled(
+    id: String,
+    enabled: bool,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportSubscription> {
+    subscriptions::set_subscription_enabled(db.inner(), &id, enabled).await
+}
+
+#[tauri::command]
+pub async fn check_import_subscription(
+    id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<SubscriptionCheckResult> {
+    subscriptions::check_subscription(db.inner().clone(), &id).await
+}
+
+#[tauri::command]
+pub async fn approve_subscription_updates(
+    id: String,
+    candidate_ids: Option<Vec<String>>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ImportExecutionResult> {
+    subscriptions::approve_pending(db.inner().clone(), &id, candidate_ids).await
+}
+
+#[tauri::command]
+pub async fn dismiss_subscription_updates(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    subscriptions::dismiss_pending(db.inner(), &id).await
+}
+
+#[tauri::command]
+pub async fn get_team_repo(db: State<'_, Arc<Database>>) -> Result<Option<TeamRepo>> {
+    Ok(team_repo::get_team_repo(db.inner()).await)
+}
+
+#[tauri::command]
+pub async fn configure_team_repo(
+    input: ConfigureTeamRepoInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<TeamRepo> {
+    team_repo::configure_team_repo(db.inner(), input).await
+}
+
+#[tauri::command]
+pub async fn remove_team_repo(db: State<'_, Arc<Database>>) -> Result<()> {
+    team_repo::remove_team_repo(db.inner()).await
+}
+
+#[tauri::command]
+pub async fn set_team_repo_enabled(
+    enabled: bool,
+    db: State<'_, Arc<Database>>,
+) -> Result<TeamRepo> {
+    team_repo::set_team_repo_enabled(db.inner(), enabled).await
+}
+
+#[tauri::command]
+pub async fn pull_team_repo(db: State<'_, Arc<Database>>) -> Result<TeamRepoPullResult> {
+    team_repo::pull_team_repo(db.inner().clone()).await
+}
+
+#[tauri::command]
+pub async fn get_team_repo_changes(db: State<'_, Arc<Database>>) -> Result<Vec<TeamRepoChange>> {
+    team_repo::outgoing_changes(db.inner()).await
+}
+
+#[tauri::command]
+pub async fn push_team_repo(
+    input: TeamRepoPushInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<TeamRepoPushResult> {
+    team_repo::push_team_repo(db.inner().clone(), input).await
 }
 
 #[cfg(test)]
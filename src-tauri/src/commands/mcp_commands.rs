@@ -3,7 +3,13 @@ use tauri::State;
 
 use crate::database::Database;
 use crate::error::Result;
+use crate::external_mcp;
+use crate::mcp::tokens::{self, CreatedMcpToken, McpClientToken};
 use crate::mcp::{McpConnectionInstructions, McpManager, McpStatus};
+use crate::models::{
+    ApprovalAuditEntry, CreateExternalMcpServerInput, ExternalMcpServer, McpServerCandidate,
+    McpServerScanResult, McpServerSyncResult, PendingApproval, UpdateExternalMcpServerInput,
+};
 
 #[tauri::command]
 pub async fn get_mcp_status(mcp: State<'_, McpManager>) -> Result<McpStatus> {
@@ -81,3 +87,120 @@ pub async fn get_mcp_connection_instructions(
 pub async fn get_mcp_logs(limit: Option<u32>, mcp: State<'_, McpManager>) -> Result<Vec<String>> {
     mcp.logs(limit.unwrap_or(50) as usize).await
 }
+
+#[tauri::command]
+pub async fn list_mcp_tokens(db: State<'_, Arc<Database>>) -> Result<Vec<McpClientToken>> {
+    tokens::list(&db).await
+}
+
+/// Creates a named token for another MCP client. The token is only returned here.
+#[tauri::command]
+pub async fn create_mcp_token(
+    name: String,
+    read_only: bool,
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+) -> Result<CreatedMcpToken> {
+    let created = tokens::create(&db, &name, read_only).await?;
+    mcp.reload_client_tokens(&db).await?;
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn revoke_mcp_token(
+    name: String,
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+) -> Result<()> {
+    tokens::revoke(&db, &name).await?;
+    mcp.reload_client_tokens(&db).await
+}
+
+async fn sync_external_mcp_after_mutation(db: &Database) {
+    match external_mcp::sync_to_tools(db).await {
+        Ok(result) if !result.errors.is_empty() => {
+            log::warn!(
+                "External MCP sync completed with errors: {:?}",
+                result.errors
+            );
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("External MCP sync failed: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_external_mcp_servers(
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ExternalMcpServer>> {
+    db.get_all_external_mcp_servers().await
+}
+
+#[tauri::command]
+pub async fn create_external_mcp_server(
+    input: CreateExternalMcpServerInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<ExternalMcpServer> {
+    let server = db.create_external_mcp_server(input).await?;
+    sync_external_mcp_after_mutation(&db).await;
+    Ok(server)
+}
+
+#[tauri::command]
+pub async fn update_external_mcp_server(
+    id: String,
+    input: UpdateExternalMcpServerInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<ExternalMcpServer> {
+    let server = db.update_external_mcp_server(&id, input).await?;
+    sync_external_mcp_after_mutation(&db).await;
+    Ok(server)
+}
+
+#[tauri::command]
+pub async fn delete_external_mcp_server(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    db.delete_external_mcp_server(&id).await?;
+    sync_external_mcp_after_mutation(&db).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn scan_tool_mcp_servers(db: State<'_, Arc<Database>>) -> Result<McpServerScanResult> {
+    external_mcp::scan_tool_configs(&db).await
+}
+
+#[tauri::command]
+pub async fn import_tool_mcp_servers(
+    candidates: Vec<McpServerCandidate>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ExternalMcpServer>> {
+    let imported = external_mcp::import_candidates(&db, candidates).await?;
+    sync_external_mcp_after_mutation(&db).await;
+    Ok(imported)
+}
+
+#[tauri::command]
+pub async fn sync_external_mcp_servers(
+    db: State<'_, Arc<Database>>,
+) -> Result<McpServerSyncResult> {
+    external_mcp::sync_to_tools(&db).await
+}
+
+#[tauri::command]
+pub fn get_pending_approvals(mcp: State<'_, McpManager>) -> Vec<PendingApproval> {
+    mcp.pending_approvals()
+}
+
+/// Approves or denies an MCP call that is waiting on a command whose MCP policy requires approval.
+#[tauri::command]
+pub fn respond_to_approval(id: String, approved: bool, mcp: State<'_, McpManager>) -> Result<()> {
+    mcp.respond_to_approval(&id, approved)
+}
+
+#[tauri::command]
+pub async fn get_approval_audit(
+    limit: Option<u32>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ApprovalAuditEntry>> {
+    db.get_approval_audit(limit.unwrap_or(100)).await
+}
@@ -4,15 +4,22 @@ use tauri::{Manager, State};
 use crate::database::Database;
 use crate::error::Result;
 use crate::file_storage;
+use crate::models::ExportFormat;
 
 use crate::sync::SyncEngine;
 
 use super::{reconcile_after_mutation, validate_path};
 
+/// Migrates rules to file storage. With `dual_write_days`, both stores are kept in sync for
+/// that many days before the cutover instead of switching immediately.
 #[tauri::command]
 pub async fn migrate_to_file_storage(
+    dual_write_days: Option<u32>,
     db: State<'_, Arc<Database>>,
 ) -> Result<file_storage::MigrationResult> {
+    if let Some(days) = dual_write_days {
+        return file_storage::start_dual_write(&db, days).await;
+    }
     let result = file_storage::migrate_to_file_storage(&db).await?;
     if result.success {
         db.set_storage_mode("file").await?;
@@ -40,6 +47,34 @@ pub async fn verify_file_migration(
     file_storage::verify_migration(&db).await
 }
 
+#[tauri::command]
+pub async fn get_dual_write_status(
+    db: State<'_, Arc<Database>>,
+) -> Result<file_storage::DualWriteStatus> {
+    file_storage::get_dual_write_status(&db).await
+}
+
+#[tauri::command]
+pub async fn complete_file_storage_cutover(
+    force: bool,
+    db: State<'_, Arc<Database>>,
+) -> Result<file_storage::VerificationResult> {
+    file_storage::complete_cutover(&db, force).await
+}
+
+#[tauri::command]
+pub async fn rollback_dual_write(db: State<'_, Arc<Database>>) -> Result<u32> {
+    file_storage::rollback_dual_write(&db).await
+}
+
+/// Rescans rule files and adapter outputs and repairs the index tables that track them.
+#[tauri::command]
+pub async fn rebuild_file_index(
+    db: State<'_, Arc<Database>>,
+) -> Result<file_storage::FileIndexReport> {
+    file_storage::rebuild_file_index(&db).await
+}
+
 #[tauri::command]
 pub fn get_file_migration_progress() -> file_storage::MigrationProgress {
     file_storage::get_migration_progress()
@@ -66,7 +101,7 @@ pub async fn resolve_conflict(
             .map_err(|e| crate::error::AppError::InvalidInput {
                 message: e.to_string(),
             })??;
-            let hash = crate::sync::compute_content_hash_public(&content);
+            let hash = crate::sync::file_content_hash(&content);
             db.set_file_hash(&file_path, &hash).await?;
         }
         _ => {
@@ -100,24 +135,41 @@ pub async fn get_storage_mode(db: State<'_, Arc<Database>>) -> Result<String> {
     db.get_storage_mode().await
 }
 
-#[tauri::command]
-pub async fn export_configuration(path: String, db: State<'_, Arc<Database>>) -> Result<()> {
-    let rules = db.get_all_rules().await?;
-    let commands = db.get_all_commands().await?;
-    let skills = db.get_all_skills().await?;
-
-    let config = crate::models::ExportConfiguration::new(rules, commands, skills);
+/// Serializes the part of the configuration `filter` selects to `path` in `format`, by
+/// default YAML for `.yaml`/`.yml` files and JSON otherwise.
+pub async fn write_configuration_file(
+    db: &Database,
+    path: String,
+    filter: &crate::models::ExportFilter,
+    format: Option<ExportFormat>,
+) -> Result<()> {
+    let mut config = db.export_configuration().await?;
+    if filter.include_state {
+        config.state = Some(db.export_state().await?);
+    }
+    filter.apply(&mut config);
+    crate::export_manifest::seal(db, &mut config).await?;
 
-    let content = if path.ends_with(".yaml") || path.ends_with(".yml") {
-        serde_yaml::to_string(&config).map_err(|e| crate::error::AppError::InvalidInput {
-            message: e.to_string(),
-        })?
-    } else {
-        serde_json::to_string_pretty(&config)?
+    let content = match format.unwrap_or_else(|| ExportFormat::for_path(&path)) {
+        ExportFormat::Yaml => {
+            serde_yaml::to_string(&config).map_err(|e| crate::error::AppError::InvalidInput {
+                message: e.to_string(),
+            })?
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(&config)?,
+        ExportFormat::Tree => {
+            return tokio::task::spawn_blocking(move || {
+                crate::export_tree::write(std::path::Path::new(&path), &config)
+            })
+            .await
+            .map_err(|e| crate::error::AppError::InvalidInput {
+                message: e.to_string(),
+            })?;
+        }
     };
 
     tokio::task::spawn_blocking(move || {
-        std::fs::write(path, content).map_err(crate::error::AppError::Io)
+        crate::atomic_write::write_atomic(std::path::Path::new(&path), content)
     })
     .await
     .map_err(|e| crate::error::AppError::InvalidInput {
@@ -127,6 +179,81 @@ pub async fn export_configuration(path: String, db: State<'_, Arc<Database>>) ->
     Ok(())
 }
 
+/// Reads and validates a configuration file or tree written by [`write_configuration_file`]
+/// and checks it against its manifest. Files modified since they were exported are
+/// rejected.
+pub async fn read_configuration_file(
+    db: &Database,
+    path: String,
+) -> Result<(
+    crate::models::ExportConfiguration,
+    crate::models::ExportVerification,
+)> {
+    let path_clone = path.clone();
+    let config = tokio::task::spawn_blocking(move || {
+        let path = std::path::Path::new(&path_clone);
+        if path.is_dir() {
+            return crate::export_tree::read(path);
+        }
+        let content = std::fs::read_to_string(path).map_err(crate::error::AppError::Io)?;
+        let config: crate::models::ExportConfiguration = match ExportFormat::for_path(&path_clone) {
+            ExportFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| {
+                crate::error::AppError::InvalidInput {
+                    message: e.to_string(),
+                }
+            })?,
+            _ => serde_json::from_str(&content)?,
+        };
+        Ok(config)
+    })
+    .await
+    .map_err(|e| crate::error::AppError::InvalidInput {
+        message: e.to_string(),
+    })??;
+
+    validate_config_version(&config)?;
+    let verification = crate::export_manifest::check(db, &config).await?;
+    validate_config_data(&config)?;
+
+    Ok((config, verification))
+}
+
+/// Imports a configuration, then syncs AI tools and cleans up orphaned artifacts.
+pub async fn apply_configuration(
+    db: Arc<Database>,
+    config: crate::models::ExportConfiguration,
+    mode: crate::models::ImportMode,
+) -> Result<()> {
+    db.import_configuration(config, mode).await?;
+
+    let engine = SyncEngine::new(&db);
+    let rules = db.get_all_rules().await?;
+    let sync_result = engine.sync_all(rules).await;
+    if !sync_result.errors.is_empty() || !sync_result.conflicts.is_empty() {
+        log::warn!(
+            "Post-import AI tool sync completed with issues. Errors: {:?}, Conflicts: {:?}",
+            sync_result.errors,
+            sync_result.conflicts
+        );
+    }
+
+    // Run reconciliation to clean up any orphaned artifacts from the import
+    reconcile_after_mutation(db).await;
+
+    Ok(())
+}
+
+/// Exports the configuration, or only the part `filter` selects.
+#[tauri::command]
+pub async fn export_configuration(
+    path: String,
+    filter: Option<crate::models::ExportFilter>,
+    format: Option<ExportFormat>,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    write_configuration_file(&db, path, &filter.unwrap_or_default(), format).await
+}
+
 fn validate_config_version(config: &crate::models::ExportConfiguration) -> Result<()> {
     if config.version != "1.0" {
         return Err(crate::error::AppError::InvalidInput {
@@ -193,66 +320,55 @@ fn validate_config_data(config: &crate::models::ExportConfiguration) -> Result<(
         }
     }
 
+    for collection in &config.collections {
+        if collection.name.trim().is_empty() {
+            return Err(crate::error::AppError::Validation(
+                "Imported collection name cannot be empty".to_string(),
+            ));
+        }
+    }
+
     Ok(())
 }
 
+/// What importing `path` would apply, narrowed to `filter` when given.
 #[tauri::command]
-pub async fn preview_import(path: String) -> Result<crate::models::ExportConfiguration> {
-    let path_clone = path.clone();
-    let content = tokio::task::spawn_blocking(move || {
-        std::fs::read_to_string(path_clone).map_err(crate::error::AppError::Io)
+pub async fn preview_import(
+    path: String,
+    filter: Option<crate::models::ExportFilter>,
+    db: State<'_, Arc<Database>>,
+) -> Result<crate::models::ImportPreview> {
+    let (mut configuration, verification) = read_configuration_file(&db, path).await?;
+    if let Some(filter) = filter {
+        filter.apply(&mut configuration);
+    }
+    Ok(crate::models::ImportPreview {
+        configuration,
+        verification,
     })
-    .await
-    .map_err(|e| crate::error::AppError::InvalidInput {
-        message: e.to_string(),
-    })??;
-
-    let config: crate::models::ExportConfiguration =
-        if path.ends_with(".yaml") || path.ends_with(".yml") {
-            serde_yaml::from_str(&content).map_err(|e| crate::error::AppError::InvalidInput {
-                message: e.to_string(),
-            })?
-        } else {
-            serde_json::from_str(&content)?
-        };
-
-    validate_config_version(&config)?;
-    validate_config_data(&config)?;
+}
 
-    Ok(config)
+/// The public key this machine signs exports with, created on first use.
+#[tauri::command]
+pub async fn get_export_public_key() -> Result<String> {
+    crate::export_manifest::public_key().await
 }
 
+/// Imports `path`, or only the part `filter` selects. The whole file is verified against
+/// its manifest first.
 #[tauri::command]
 pub async fn import_configuration(
     path: String,
     mode: crate::models::ImportMode,
+    filter: Option<crate::models::ExportFilter>,
     db: State<'_, Arc<Database>>,
     _status: State<'_, crate::GlobalStatus>,
     app: tauri::AppHandle,
 ) -> Result<()> {
-    let path_clone = path.clone();
-    let content = tokio::task::spawn_blocking(move || {
-        std::fs::read_to_string(path_clone).map_err(crate::error::AppError::Io)
-    })
-    .await
-    .map_err(|e| crate::error::AppError::InvalidInput {
-        message: e.to_string(),
-    })??;
-
-    let config: crate::models::ExportConfiguration =
-        if path.ends_with(".yaml") || path.ends_with(".yml") {
-            serde_yaml::from_str(&content).map_err(|e| crate::error::AppError::InvalidInput {
-                message: e.to_string(),
-            })?
-        } else {
-            serde_json::from_str(&content)?
-        };
-
-    validate_config_version(&config)?;
-    validate_config_data(&config)?;
-
-    // DB operations are now async, so we can await them directly
-    db.import_configuration(config, mode).await?;
+    let (mut config, _) = read_configuration_file(&db, path).await?;
+    if let Some(filter) = filter {
+        filter.apply(&mut config);
+    }
 
     // Trigger sync after import
     {
@@ -262,19 +378,7 @@ pub async fn import_configuration(
         }
     }
 
-    let engine = SyncEngine::new(&db);
-    let rules = db.get_all_rules().await?;
-    let sync_result = engine.sync_all(rules).await;
-    if !sync_result.errors.is_empty() || !sync_result.conflicts.is_empty() {
-        log::warn!(
-            "Post-import AI tool sync completed with issues. Errors: {:?}, Conflicts: {:?}",
-            sync_result.errors,
-            sync_result.conflicts
-        );
-    }
-
-    // Run reconciliation to clean up any orphaned artifacts from the import
-    reconcile_after_mutation(db.inner().clone()).await;
+    let result = apply_configuration(db.inner().clone(), config, mode).await;
 
     {
         if let Some(s) = app.try_state::<crate::GlobalStatus>() {
@@ -283,5 +387,5 @@ pub async fn import_configuration(
         }
     }
 
-    Ok(())
+    result
 }
@@ -1,27 +1,49 @@
 pub mod adapters;
+pub mod ai_commands;
+pub mod backup_commands;
+pub mod cloud_sync_commands;
+pub mod collection_commands;
 pub mod command_commands;
+pub mod encryption_commands;
 pub mod import_commands;
 pub mod mcp_commands;
 pub mod migration_commands;
+pub mod policy_commands;
 pub mod reconciliation_commands;
 pub mod registry_commands;
 pub mod rule_commands;
+pub mod secret_commands;
 pub mod skill_commands;
+pub mod snippet_commands;
 pub mod system_commands;
+pub mod webhook_commands;
+pub mod workflow_commands;
+pub mod workspace_commands;
 
 use adapters::{
     ClaudeAdapter, CommandAdapter, CursorAdapter, GeminiAdapter, KiloAdapter, OpenCodeAdapter,
     RooCodeAdapter, WindsurfAdapter,
 };
+pub use ai_commands::*;
+pub use backup_commands::*;
+pub use cloud_sync_commands::*;
+pub use collection_commands::*;
 pub use command_commands::*;
+pub use encryption_commands::*;
 pub use import_commands::*;
 pub use mcp_commands::*;
 pub use migration_commands::*;
+pub use policy_commands::*;
 pub use reconciliation_commands::*;
 pub use registry_commands::*;
 pub use rule_commands::*;
+pub use secret_commands::*;
 pub use skill_commands::*;
+pub use snippet_commands::*;
 pub use system_commands::*;
+pub use webhook_commands::*;
+pub use workflow_commands::*;
+pub use workspace_commands::*;
 
 use parking_lot::Mutex;
 use std::collections::{HashSet, VecDeque};
@@ -31,8 +53,8 @@ use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 
 use crate::constants::limits::{
-    MAX_COMMAND_NAME_LENGTH, MAX_COMMAND_SCRIPT_LENGTH, MAX_RULE_CONTENT_LENGTH,
-    MAX_RULE_NAME_LENGTH,
+    MAX_COMMAND_NAME_LENGTH, MAX_COMMAND_SCRIPT_LENGTH, MAX_METADATA_JSON_LENGTH,
+    MAX_METADATA_KEYS, MAX_METADATA_KEY_LENGTH, MAX_RULE_CONTENT_LENGTH, MAX_RULE_NAME_LENGTH,
 };
 use crate::constants::{
     NEW_CURSOR_DIR, NEW_GEMINI_DIR, NEW_KILO_DIR, NEW_ROO_CODE_DIR, NEW_WINDSURF_DIR,
@@ -40,7 +62,7 @@ use crate::constants::{
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::file_storage;
-use crate::models::Rule;
+use crate::models::{Metadata, Rule};
 
 pub static RUNNING_TESTS: LazyLock<Mutex<HashSet<String>>> =
     LazyLock::new(|| Mutex::new(HashSet::new()));
@@ -74,6 +96,22 @@ pub fn validate_path(path: &str) -> Result<PathBuf> {
     Ok(canonical_path)
 }
 
+/// Validates the directory of an export target; the file itself need not exist yet.
+pub fn validate_export_path(path: &str) -> Result<PathBuf> {
+    let target = PathBuf::from(path);
+    let parent = target
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .ok_or_else(|| AppError::InvalidInput {
+            message: "Export path must include a directory".to_string(),
+        })?;
+    let file_name = target.file_name().ok_or_else(|| AppError::InvalidInput {
+        message: "Export path must include a file name".to_string(),
+    })?;
+    let validated_dir = validate_path(&parent.to_string_lossy())?;
+    Ok(validated_dir.join(file_name))
+}
+
 pub fn validate_rule_input(name: &str, content: &str) -> Result<()> {
     let trimmed_name = name.trim();
     if trimmed_name.is_empty() {
@@ -123,6 +161,36 @@ pub fn validate_command_input(name: &str, script: &str) -> Result<()> {
     Ok(())
 }
 
+pub fn validate_metadata(metadata: &Metadata) -> Result<()> {
+    if metadata.len() > MAX_METADATA_KEYS {
+        return Err(AppError::Validation(format!(
+            "Too many metadata fields (max {})",
+            MAX_METADATA_KEYS
+        )));
+    }
+    for (key, _) in metadata.iter() {
+        let trimmed = key.trim();
+        if trimmed.is_empty() || trimmed != key {
+            return Err(AppError::Validation(
+                "Metadata keys cannot be empty or padded with whitespace".to_string(),
+            ));
+        }
+        if key.len() > MAX_METADATA_KEY_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Metadata key '{}' too long (max {} characters)",
+                key, MAX_METADATA_KEY_LENGTH
+            )));
+        }
+    }
+    if metadata.to_json().len() > MAX_METADATA_JSON_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Metadata too large (max {} bytes)",
+            MAX_METADATA_JSON_LENGTH
+        )));
+    }
+    crate::localization::validate_variants(metadata)
+}
+
 pub fn validate_command_arguments(args: &[crate::models::CommandArgument]) -> Result<()> {
     for arg in args {
         if arg.name.trim().is_empty() {
@@ -164,6 +232,45 @@ pub fn validate_command_arguments(args: &[crate::models::CommandArgument]) -> Re
                 }
             }
         }
+
+        if let Some(pattern) = &arg.pattern {
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(AppError::Validation(format!(
+                    "Argument '{}' has an invalid pattern: {}",
+                    arg.name, e
+                )));
+            }
+        }
+
+        if let (Some(min), Some(max)) = (arg.min, arg.max) {
+            if min > max {
+                return Err(AppError::Validation(format!(
+                    "Argument '{}' has a minimum greater than its maximum",
+                    arg.name
+                )));
+            }
+        }
+
+        if let Some(default) = arg.default_value.as_deref().filter(|d| !d.is_empty()) {
+            crate::execution::validate_argument_value(arg, default)
+                .map_err(|e| AppError::Validation(format!("Invalid default value: {}", e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// A command taking stdin over MCP receives it as an extra tool parameter, so no argument
+/// may share that parameter's name.
+pub fn validate_stdin_parameter(
+    args: &[crate::models::CommandArgument],
+    accepts_stdin: bool,
+) -> Result<()> {
+    let stdin = crate::mcp::STDIN_PARAMETER;
+    if accepts_stdin && args.iter().any(|arg| arg.name == stdin) {
+        return Err(AppError::Validation(format!(
+            "Commands that accept stdin cannot have an argument named '{}'",
+            stdin
+        )));
     }
     Ok(())
 }
@@ -216,7 +323,16 @@ pub fn command_file_targets() -> Result<Vec<(String, Arc<dyn CommandAdapter>)>>
     Ok(targets)
 }
 
+/// Whether rule changes are written to file storage: in file mode and while dual-writing.
 pub async fn use_file_storage(db: &Database) -> bool {
+    db.get_storage_mode()
+        .await
+        .map(|mode| mode == "file" || mode == file_storage::DUAL_WRITE_MODE)
+        .unwrap_or(false)
+}
+
+/// Whether rules are read from file storage. While dual-writing SQLite stays authoritative.
+pub async fn read_from_file_storage(db: &Database) -> bool {
     db.get_storage_mode()
         .await
         .map(|mode| mode == "file")
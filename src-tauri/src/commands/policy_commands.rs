@@ -0,0 +1,21 @@
+use std::sync::Arc;
+use tauri::State;
+
+use super::reconcile_after_mutation;
+use crate::database::Database;
+use crate::error::Result;
+use crate::models::OrgPolicy;
+use crate::policy;
+
+#[tauri::command]
+pub async fn get_org_policy(db: State<'_, Arc<Database>>) -> Result<OrgPolicy> {
+    Ok(policy::get_org_policy(&db).await)
+}
+
+/// Replaces the organization policy and reconciles, so newly managed files are written.
+#[tauri::command]
+pub async fn set_org_policy(policy: OrgPolicy, db: State<'_, Arc<Database>>) -> Result<OrgPolicy> {
+    let saved = policy::save_org_policy(&db, policy).await?;
+    reconcile_after_mutation(db.inner().clone()).await;
+    Ok(saved)
+}
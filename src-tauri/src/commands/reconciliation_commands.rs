@@ -1,24 +1,67 @@
+use std::path::Path;
 use std::sync::Arc;
 use tauri::State;
 
 use crate::database::{Database, ReconciliationLogEntry};
 use crate::error::Result;
-use crate::reconciliation::{FoundArtifact, ReconcilePlan, ReconcileResult, ReconciliationEngine};
+use crate::git_hooks::{self, GitHookStatus, HookAction};
+use crate::models::{ImportExecutionResult, ProjectConfig};
+use crate::project_config;
+use crate::reconciliation::review::{
+    PreparedReconcilePlan, ReconcilePlanSelection, ReconcilePlanStore,
+};
+use crate::reconciliation::{protection, repo_roots};
+use crate::reconciliation::{
+    FoundArtifact, ReconcileFilter, ReconcilePlan, ReconcileResult, ReconciliationEngine,
+};
 
 #[tauri::command]
-pub async fn reconcile_all(db: State<'_, Arc<Database>>, dry_run: bool) -> Result<ReconcileResult> {
-    let engine = ReconciliationEngine::new(db.inner().clone())?;
+pub async fn reconcile_all(
+    db: State<'_, Arc<Database>>,
+    dry_run: bool,
+    filter: Option<ReconcileFilter>,
+) -> Result<ReconcileResult> {
+    let engine =
+        ReconciliationEngine::new(db.inner().clone())?.with_filter(filter.unwrap_or_default());
     engine.reconcile(dry_run, None).await
 }
 
 #[tauri::command]
-pub async fn reconcile_preview(db: State<'_, Arc<Database>>) -> Result<ReconcilePlan> {
-    let engine = ReconciliationEngine::new(db.inner().clone())?;
+pub async fn reconcile_preview(
+    db: State<'_, Arc<Database>>,
+    filter: Option<ReconcileFilter>,
+) -> Result<ReconcilePlan> {
+    let engine =
+        ReconciliationEngine::new(db.inner().clone())?.with_filter(filter.unwrap_or_default());
     let desired = engine.compute_desired_state().await?;
     let actual = engine.scan_actual_state().await?;
     Ok(engine.plan(&desired, &actual))
 }
 
+/// Dry-runs a reconciliation and keeps the plan so it can be approved with
+/// [`apply_reconcile_plan`].
+#[tauri::command]
+pub async fn get_reconcile_plan(
+    db: State<'_, Arc<Database>>,
+    store: State<'_, ReconcilePlanStore>,
+    filter: Option<ReconcileFilter>,
+) -> Result<PreparedReconcilePlan> {
+    let engine =
+        ReconciliationEngine::new(db.inner().clone())?.with_filter(filter.unwrap_or_default());
+    engine.prepare_plan(&store).await
+}
+
+#[tauri::command]
+pub async fn apply_reconcile_plan(
+    db: State<'_, Arc<Database>>,
+    store: State<'_, ReconcilePlanStore>,
+    plan_id: String,
+    selections: ReconcilePlanSelection,
+) -> Result<ReconcileResult> {
+    let engine = ReconciliationEngine::new(db.inner().clone())?;
+    engine.apply_plan(&store, &plan_id, &selections).await
+}
+
 #[tauri::command]
 pub async fn reconcile_repair(
     db: State<'_, Arc<Database>>,
@@ -34,12 +77,121 @@ pub async fn needs_reconciliation(db: State<'_, Arc<Database>>) -> Result<bool>
     engine.needs_reconciliation().await
 }
 
+/// The plan prepared by the startup drift check, if it found anything. Returned once.
+#[tauri::command]
+pub fn get_startup_drift_report(
+    report: State<'_, crate::drift::StartupDriftReport>,
+) -> Option<PreparedReconcilePlan> {
+    report.take()
+}
+
+/// Applies a full reconciliation and clears the drift shown in the tray.
+#[tauri::command]
+pub async fn reconcile_drift(
+    app: tauri::AppHandle,
+    db: State<'_, Arc<Database>>,
+) -> Result<ReconcileResult> {
+    crate::drift::reconcile_now(db.inner().clone(), &app).await
+}
+
 #[tauri::command]
 pub async fn get_stale_paths(db: State<'_, Arc<Database>>) -> Result<Vec<FoundArtifact>> {
     let engine = ReconciliationEngine::new(db.inner().clone())?;
     engine.get_stale_paths().await
 }
 
+/// Registered repository roots whose directory no longer exists.
+#[tauri::command]
+pub async fn get_missing_repo_roots(db: State<'_, Arc<Database>>) -> Result<Vec<String>> {
+    Ok(repo_roots::missing_repo_roots(&db)
+        .await?
+        .into_iter()
+        .map(|root| root.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Deregisters missing repository roots and purges their rule file index entries.
+#[tauri::command]
+pub async fn remove_missing_repo_roots(
+    db: State<'_, Arc<Database>>,
+    roots: Vec<String>,
+) -> Result<Vec<String>> {
+    let removed = repo_roots::remove_missing_repo_roots(&db, &roots).await?;
+    if !removed.is_empty() {
+        // Local-scoped slash commands follow the registered repository roots.
+        super::reconcile_after_mutation(db.inner().clone()).await;
+    }
+    Ok(removed)
+}
+
+/// Installs pre-commit and post-merge hooks that check, or with `regenerate` rewrite, the
+/// repository's generated files through the headless CLI.
+#[tauri::command]
+pub async fn install_git_hooks(
+    db: State<'_, Arc<Database>>,
+    repo_root: String,
+    regenerate: bool,
+) -> Result<GitHookStatus> {
+    let root = repo_roots::registered_repo_root(&db, Path::new(&repo_root)).await?;
+    let action = if regenerate {
+        HookAction::Regenerate
+    } else {
+        HookAction::Verify
+    };
+    git_hooks::install_hooks(&root, &std::env::current_exe()?, action)
+}
+
+#[tauri::command]
+pub async fn uninstall_git_hooks(
+    db: State<'_, Arc<Database>>,
+    repo_root: String,
+) -> Result<GitHookStatus> {
+    let root = repo_roots::registered_repo_root(&db, Path::new(&repo_root)).await?;
+    git_hooks::uninstall_hooks(&root)
+}
+
+#[tauri::command]
+pub async fn get_git_hook_status(
+    db: State<'_, Arc<Database>>,
+    repo_root: String,
+) -> Result<GitHookStatus> {
+    let root = repo_roots::registered_repo_root(&db, Path::new(&repo_root)).await?;
+    git_hooks::hook_status(&root)
+}
+
+/// The repository's `.ruleweaver.toml`, or `None` without one. Fails when it is invalid.
+#[tauri::command]
+pub async fn get_project_config(
+    db: State<'_, Arc<Database>>,
+    repo_root: String,
+) -> Result<Option<ProjectConfig>> {
+    let root = repo_roots::registered_repo_root(&db, Path::new(&repo_root)).await?;
+    project_config::load(&root)
+}
+
+/// Imports orphaned generated files as rules, commands or skills instead of removing them.
+#[tauri::command]
+pub async fn adopt_orphaned_artifacts(
+    db: State<'_, Arc<Database>>,
+    paths: Vec<String>,
+) -> Result<ImportExecutionResult> {
+    let engine = ReconciliationEngine::new(db.inner().clone())?;
+    engine.adopt_orphans(&paths).await
+}
+
+#[tauri::command]
+pub async fn get_reconcile_protected_paths(db: State<'_, Arc<Database>>) -> Result<Vec<String>> {
+    Ok(protection::list_protected_paths(db.inner()).await)
+}
+
+#[tauri::command]
+pub async fn set_reconcile_protected_paths(
+    db: State<'_, Arc<Database>>,
+    patterns: Vec<String>,
+) -> Result<Vec<String>> {
+    protection::save_protected_paths(db.inner(), patterns).await
+}
+
 #[tauri::command]
 pub async fn get_reconciliation_logs(
     db: State<'_, Arc<Database>>,
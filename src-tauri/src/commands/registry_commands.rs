@@ -1,7 +1,26 @@
 use crate::error::Result;
-use crate::models::registry::{ToolEntry, REGISTRY};
+use crate::models::registry::{InstalledTools, RegisteredTool, REGISTRY};
+use crate::tool_detection;
 
 #[tauri::command]
-pub fn get_tool_registry() -> Result<Vec<ToolEntry>> {
-    Ok(REGISTRY.all().into_iter().cloned().collect())
+pub fn get_tool_registry() -> Result<Vec<RegisteredTool>> {
+    let detected = tool_detection::detect_installed_tools();
+    Ok(REGISTRY
+        .all()
+        .into_iter()
+        .map(|entry| RegisteredTool {
+            installed: detected.is_installed(entry.id),
+            entry: entry.clone(),
+        })
+        .collect())
+}
+
+/// Which AI tools are installed, from the cache unless `refresh` is set.
+#[tauri::command]
+pub fn detect_installed_tools(refresh: Option<bool>) -> InstalledTools {
+    if refresh.unwrap_or(false) {
+        tool_detection::refresh()
+    } else {
+        tool_detection::detect_installed_tools()
+    }
 }
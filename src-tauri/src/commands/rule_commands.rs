@@ -1,17 +1,27 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
 
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::file_storage;
-use crate::models::{CreateRuleInput, Rule, SyncResult, UpdateRuleInput};
+use crate::markdown::{self, MarkdownIssue};
+use crate::models::{
+    AdapterType, ArtifactListQuery, ArtifactPage, BulkRuleChanges, CommandArgument,
+    CreateRuleInput, CreateRuleTemplateInput, MetadataQuery, Rule, RuleFeedback, RuleFeedbackInput,
+    RuleStats, RuleStatsSort, RuleTemplate, SyncResult, UpdateRuleInput, UpdateRuleTemplateInput,
+};
+use crate::policy;
+use crate::rule_feedback;
+use crate::rule_order::{self, RuleOrderIssue};
 
 use crate::sync::SyncEngine;
-use crate::templates::rules::{get_bundled_rule_templates, TemplateRule};
+use crate::templates::rules::{get_bundled_rule_templates, render_rule_template, TemplateRule};
 
 use super::{
-    get_local_rule_roots, reconcile_after_mutation, register_local_rule_paths,
-    storage_location_for_rule, use_file_storage, validate_local_rule_paths, validate_rule_input,
+    get_local_rule_roots, read_from_file_storage, reconcile_after_mutation,
+    register_local_rule_paths, storage_location_for_rule, use_file_storage,
+    validate_command_arguments, validate_local_rule_paths, validate_metadata, validate_rule_input,
 };
 
 /// Helper function to sync all rules to AI tool locations.
@@ -52,113 +62,174 @@ async fn delete_rule_from_all_locations(id: &str, db: &Database) -> Result<()> {
     Ok(())
 }
 
-#[tauri::command]
-pub async fn get_all_rules(db: State<'_, Arc<Database>>) -> Result<Vec<Rule>> {
-    if use_file_storage(&db).await {
-        let local_roots = get_local_rule_roots(&db).await?;
-        let loaded = file_storage::load_rules_from_locations(&local_roots)?;
-        Ok(loaded.rules)
+/// Rules from whichever store is authoritative, optionally filtered by metadata.
+pub async fn fetch_rules(
+    db: &Database,
+    metadata_query: Option<MetadataQuery>,
+) -> Result<Vec<Rule>> {
+    let rules = if read_from_file_storage(db).await {
+        let local_roots = get_local_rule_roots(db).await?;
+        file_storage::load_rules_from_locations(&local_roots)?.rules
     } else {
-        db.get_all_rules().await
-    }
-}
+        db.get_all_rules().await?
+    };
 
-#[tauri::command]
-pub async fn get_rule_by_id(id: String, db: State<'_, Arc<Database>>) -> Result<Rule> {
-    if use_file_storage(&db).await {
-        let local_roots = get_local_rule_roots(&db).await?;
-        let loaded = file_storage::load_rules_from_locations(&local_roots)?;
-        loaded
-            .rules
-            .into_iter()
-            .find(|r| r.id == id)
-            .ok_or_else(|| AppError::RuleNotFound { id })
-    } else {
-        db.get_rule_by_id(&id).await
-    }
+    Ok(match metadata_query {
+        Some(query) => query.apply(rules),
+        None => rules,
+    })
 }
 
-#[tauri::command]
-pub async fn create_rule(input: CreateRuleInput, db: State<'_, Arc<Database>>) -> Result<Rule> {
+/// Validates and creates a rule, writes its rule file in file storage and syncs adapters.
+pub async fn create_rule_and_sync(db: &Database, input: CreateRuleInput) -> Result<Rule> {
     validate_rule_input(&input.name, &input.content)?;
-    validate_local_rule_paths(&db, None, Some(input.scope), &input.target_paths).await?;
+    validate_metadata(&input.metadata)?;
+    validate_local_rule_paths(db, None, Some(input.scope), &input.target_paths).await?;
 
     let created = db.create_rule(input).await?;
 
-    if use_file_storage(&db).await {
+    if use_file_storage(db).await {
         let location = storage_location_for_rule(&created);
         file_storage::save_rule_to_disk(&created, &location)?;
         db.update_rule_file_index(&created.id, &location).await?;
-        register_local_rule_paths(&db, &created).await?;
+        register_local_rule_paths(db, &created).await?;
     }
 
     // Sync to AI tool locations
-    sync_to_ai_tools(&db).await;
+    sync_to_ai_tools(db).await;
 
     Ok(created)
 }
 
-#[tauri::command]
-pub async fn update_rule(
-    id: String,
-    input: UpdateRuleInput,
-    db: State<'_, Arc<Database>>,
+/// Validates and applies `input` to a rule, rewrites its rule file and syncs adapters.
+pub async fn update_rule_and_sync(
+    db: &Database,
+    id: &str,
+    mut input: UpdateRuleInput,
 ) -> Result<Rule> {
+    if let Ok(existing) = db.get_rule_by_id(id).await {
+        policy::keep_marker(&existing.metadata, input.metadata.as_mut());
+        policy::ensure_editable(db, "Rule", &input, &existing).await?;
+    }
+
     if let Some(ref name) = input.name {
         if let Some(ref content) = input.content {
             validate_rule_input(name, content)?;
         } else {
-            let existing = db.get_rule_by_id(&id).await?;
+            let existing = db.get_rule_by_id(id).await?;
             validate_rule_input(name, &existing.content)?;
         }
     } else if let Some(ref content) = input.content {
-        let existing = db.get_rule_by_id(&id).await?;
+        let existing = db.get_rule_by_id(id).await?;
         validate_rule_input(&existing.name, content)?;
     }
+    if let Some(ref metadata) = input.metadata {
+        validate_metadata(metadata)?;
+    }
 
-    validate_local_rule_paths(&db, Some(&id), input.scope, &input.target_paths).await?;
+    validate_local_rule_paths(db, Some(id), input.scope, &input.target_paths).await?;
 
-    let updated = db.update_rule(&id, input).await?;
+    let updated = db.update_rule(id, input).await?;
 
-    if use_file_storage(&db).await {
+    if use_file_storage(db).await {
         let location = storage_location_for_rule(&updated);
         file_storage::save_rule_to_disk(&updated, &location)?;
         db.update_rule_file_index(&updated.id, &location).await?;
-        register_local_rule_paths(&db, &updated).await?;
+        register_local_rule_paths(db, &updated).await?;
     }
 
     // Sync to AI tool locations
-    sync_to_ai_tools(&db).await;
+    sync_to_ai_tools(db).await;
 
     Ok(updated)
 }
 
-#[tauri::command]
-pub async fn delete_rule(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+/// Deletes a rule and its rule file, then syncs adapters and removes orphaned artifacts.
+pub async fn delete_rule_and_sync(db: Arc<Database>, id: &str) -> Result<()> {
+    if let Ok(existing) = db.get_rule_by_id(id).await {
+        policy::ensure_deletable(&db, "Rule", &existing.name, &existing.metadata).await?;
+    }
     if use_file_storage(&db).await {
         // Try to get the rule from DB to determine storage location
-        if let Ok(existing) = db.get_rule_by_id(&id).await {
+        if let Ok(existing) = db.get_rule_by_id(id).await {
             let location = storage_location_for_rule(&existing);
-            file_storage::delete_rule_file(&id, &location, Some(&db)).await?;
-            db.remove_rule_file_index(&id).await?;
+            file_storage::delete_rule_file(id, &location, Some(&db)).await?;
+            db.remove_rule_file_index(id).await?;
         } else {
             // Rule not in DB but might exist as file - try to delete from all locations
-            delete_rule_from_all_locations(&id, &db).await?;
+            delete_rule_from_all_locations(id, &db).await?;
         }
     }
-    db.delete_rule(&id).await?;
+    db.delete_rule(id).await?;
 
     // Sync to AI tool locations to remove deleted rule from adapters
     sync_to_ai_tools(&db).await;
 
     // Run reconciliation to clean up any orphaned artifacts
-    reconcile_after_mutation(db.inner().clone()).await;
+    reconcile_after_mutation(db).await;
 
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_all_rules(
+    metadata_query: Option<MetadataQuery>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Rule>> {
+    fetch_rules(&db, metadata_query).await
+}
+
+/// One page of rules, filtered and sorted, for lists too long to load at once.
+#[tauri::command]
+pub async fn list_rules(
+    query: ArtifactListQuery,
+    db: State<'_, Arc<Database>>,
+) -> Result<ArtifactPage<Rule>> {
+    query.apply(fetch_rules(&db, None).await?)
+}
+
+#[tauri::command]
+pub async fn get_rule_by_id(id: String, db: State<'_, Arc<Database>>) -> Result<Rule> {
+    if read_from_file_storage(&db).await {
+        let local_roots = get_local_rule_roots(&db).await?;
+        let loaded = file_storage::load_rules_from_locations(&local_roots)?;
+        loaded
+            .rules
+            .into_iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| AppError::RuleNotFound { id })
+    } else {
+        db.get_rule_by_id(&id).await
+    }
+}
+
+#[tauri::command]
+pub async fn create_rule(input: CreateRuleInput, db: State<'_, Arc<Database>>) -> Result<Rule> {
+    create_rule_and_sync(&db, input).await
+}
+
+#[tauri::command]
+pub async fn update_rule(
+    id: String,
+    input: UpdateRuleInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<Rule> {
+    update_rule_and_sync(&db, &id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_rule(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    delete_rule_and_sync(db.inner().clone(), &id).await
+}
+
 #[tauri::command]
 pub async fn bulk_delete_rules(ids: Vec<String>, db: State<'_, Arc<Database>>) -> Result<()> {
+    for id in &ids {
+        if let Ok(existing) = db.get_rule_by_id(id).await {
+            policy::ensure_deletable(&db, "Rule", &existing.name, &existing.metadata).await?;
+        }
+    }
+
     let use_fs = use_file_storage(&db).await;
 
     for id in ids {
@@ -185,8 +256,93 @@ pub async fn bulk_delete_rules(ids: Vec<String>, db: State<'_, Arc<Database>>) -
     Ok(())
 }
 
+/// Applies `changes` to every rule in `ids` in one database transaction, rewrites their
+/// rule files, then syncs adapters and reconciles once. Nothing is written when any rule is
+/// missing or a change is refused.
+pub async fn bulk_update_rules_and_sync(
+    db: Arc<Database>,
+    ids: Vec<String>,
+    changes: BulkRuleChanges,
+) -> Result<Vec<Rule>> {
+    let mut updates: Vec<(String, UpdateRuleInput)> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if updates.iter().any(|(seen, _)| *seen == id) {
+            continue;
+        }
+        let existing = db.get_rule_by_id(&id).await?;
+        let mut input = changes.to_update(&existing);
+        policy::keep_marker(&existing.metadata, input.metadata.as_mut());
+        policy::ensure_editable(&db, "Rule", &input, &existing).await?;
+        if let Some(ref metadata) = input.metadata {
+            validate_metadata(metadata)?;
+        }
+        updates.push((id, input));
+    }
+    if updates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let updated = db.update_rules(updates).await?;
+
+    if use_file_storage(&db).await {
+        for rule in &updated {
+            let location = storage_location_for_rule(rule);
+            file_storage::save_rule_to_disk(rule, &location)?;
+            db.update_rule_file_index(&rule.id, &location).await?;
+        }
+    }
+
+    sync_to_ai_tools(&db).await;
+    reconcile_after_mutation(db).await;
+
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn bulk_update_rules(
+    ids: Vec<String>,
+    changes: BulkRuleChanges,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Rule>> {
+    bulk_update_rules_and_sync(db.inner().clone(), ids, changes).await
+}
+
+#[tauri::command]
+pub async fn bulk_toggle_rules(
+    ids: Vec<String>,
+    enabled: bool,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Rule>> {
+    let changes = BulkRuleChanges {
+        enabled: Some(enabled),
+        ..Default::default()
+    };
+    bulk_update_rules_and_sync(db.inner().clone(), ids, changes).await
+}
+
+#[tauri::command]
+pub async fn bulk_set_adapters(
+    ids: Vec<String>,
+    adapters: Vec<AdapterType>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Rule>> {
+    let changes = BulkRuleChanges {
+        enabled_adapters: Some(adapters),
+        ..Default::default()
+    };
+    bulk_update_rules_and_sync(db.inner().clone(), ids, changes).await
+}
+
 #[tauri::command]
 pub async fn toggle_rule(id: String, enabled: bool, db: State<'_, Arc<Database>>) -> Result<Rule> {
+    if !enabled {
+        let existing = db.get_rule_by_id(&id).await?;
+        let input = UpdateRuleInput {
+            enabled: Some(false),
+            ..Default::default()
+        };
+        policy::ensure_editable(&db, "Rule", &input, &existing).await?;
+    }
     let toggled = db.toggle_rule(&id, enabled).await?;
 
     if use_file_storage(&db).await {
@@ -216,6 +372,50 @@ pub async fn preview_sync(db: State<'_, Arc<Database>>) -> Result<SyncResult> {
     Ok(engine.preview(rules).await)
 }
 
+/// Returns usage statistics for every rule, by name or by `sort_by`. With `stale_days`,
+/// only rules that have not been synced within that many days (or never) are returned.
+#[tauri::command]
+pub async fn get_rule_stats(
+    stale_days: Option<u32>,
+    sort_by: Option<RuleStatsSort>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<RuleStats>> {
+    let stats = db.get_rule_stats().await?;
+    let mut stats: Vec<RuleStats> = match stale_days {
+        Some(days) => {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(days));
+            stats
+                .into_iter()
+                .filter(|s| s.last_synced_at.is_none_or(|at| at < cutoff))
+                .collect()
+        }
+        None => stats,
+    };
+    rule_feedback::sort_stats(&mut stats, sort_by.unwrap_or_default());
+    Ok(stats)
+}
+
+/// Records whether a rule was helpful, ignored or harmful in a session. `rule_id` may
+/// also be the rule's name.
+#[tauri::command]
+pub async fn report_rule_feedback(
+    input: RuleFeedbackInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<RuleFeedback> {
+    let rules = fetch_rules(&db, None).await?;
+    rule_feedback::report(&db, &rules, input).await
+}
+
+/// The most recent feedback reports on a rule, newest first.
+#[tauri::command]
+pub async fn get_rule_feedback(
+    rule_id: String,
+    limit: Option<u32>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<RuleFeedback>> {
+    db.get_rule_feedback(&rule_id, limit.unwrap_or(50)).await
+}
+
 #[tauri::command]
 pub fn get_rule_templates() -> Result<Vec<TemplateRule>> {
     Ok(get_bundled_rule_templates())
@@ -278,3 +478,107 @@ pub async fn install_rule_template(
 
     Ok(created)
 }
+
+/// Checks a user template before it is saved: its placeholders must be valid and uniquely
+/// named, and its skeleton must be a valid rule.
+fn validate_rule_template(
+    name: &str,
+    placeholders: &[CommandArgument],
+    rule: &CreateRuleInput,
+) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Template name cannot be empty".to_string(),
+        ));
+    }
+    validate_command_arguments(placeholders)?;
+    let mut seen = std::collections::HashSet::new();
+    if let Some(duplicate) = placeholders.iter().find(|p| !seen.insert(p.name.as_str())) {
+        return Err(AppError::Validation(format!(
+            "Duplicate placeholder: {}",
+            duplicate.name
+        )));
+    }
+    validate_rule_input(&rule.name, &rule.content)?;
+    validate_metadata(&rule.metadata)
+}
+
+#[tauri::command]
+pub async fn get_custom_rule_templates(db: State<'_, Arc<Database>>) -> Result<Vec<RuleTemplate>> {
+    db.get_all_rule_templates().await
+}
+
+#[tauri::command]
+pub async fn create_rule_template(
+    mut input: CreateRuleTemplateInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<RuleTemplate> {
+    input.name = input.name.trim().to_string();
+    validate_rule_template(&input.name, &input.placeholders, &input.rule)?;
+    db.create_rule_template(input).await
+}
+
+#[tauri::command]
+pub async fn update_rule_template(
+    id: String,
+    mut input: UpdateRuleTemplateInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<RuleTemplate> {
+    let existing = db.get_rule_template_by_id(&id).await?;
+    if let Some(name) = &mut input.name {
+        *name = name.trim().to_string();
+    }
+    validate_rule_template(
+        input.name.as_deref().unwrap_or(&existing.name),
+        input
+            .placeholders
+            .as_deref()
+            .unwrap_or(&existing.placeholders),
+        input.rule.as_ref().unwrap_or(&existing.rule),
+    )?;
+    db.update_rule_template(&id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_rule_template(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    db.delete_rule_template(&id).await
+}
+
+/// The rule a template would create with `values`, for the creation wizard to show before
+/// anything is saved.
+#[tauri::command]
+pub async fn preview_rule_from_template(
+    template_id: String,
+    values: HashMap<String, String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<CreateRuleInput> {
+    let template = db.get_rule_template_by_id(&template_id).await?;
+    let input = render_rule_template(&template, &values)?;
+    validate_rule_input(&input.name, &input.content)?;
+    Ok(input)
+}
+
+/// Fills in a template's placeholders and creates the resulting rule.
+#[tauri::command]
+pub async fn create_rule_from_template(
+    template_id: String,
+    values: HashMap<String, String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Rule> {
+    let template = db.get_rule_template_by_id(&template_id).await?;
+    let input = render_rule_template(&template, &values)?;
+    create_rule_and_sync(&db, input).await
+}
+
+/// Heading structure problems in rule content.
+#[tauri::command]
+pub fn validate_rule_markdown(content: String) -> Vec<MarkdownIssue> {
+    markdown::validate(&content, None)
+}
+
+/// Cycles, unknown rules and unmet requirements in the rules' `after` and `requires`
+/// metadata.
+#[tauri::command]
+pub async fn lint_rule_order(db: State<'_, Arc<Database>>) -> Result<Vec<RuleOrderIssue>> {
+    Ok(rule_order::lint(&fetch_rules(&db, None).await?))
+}
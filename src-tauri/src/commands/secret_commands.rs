@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::mcp::credentials;
+use crate::models::{SecretInfo, SensitiveFinding};
+use crate::{secrets, sensitive};
+
+/// Lists stored secret names; values never leave the backend.
+#[tauri::command]
+pub async fn get_secrets(db: State<'_, Arc<Database>>) -> Result<Vec<SecretInfo>> {
+    db.get_all_secrets().await
+}
+
+/// Creates or replaces a secret in the vault.
+#[tauri::command]
+pub async fn set_secret(name: String, value: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    secrets::set_secret(&db, &name, &value).await
+}
+
+#[tauri::command]
+pub async fn delete_secret(name: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    db.delete_secret(&name).await
+}
+
+/// Stores a secret for MCP skills in the OS keychain.
+#[tauri::command]
+pub async fn set_mcp_secret(name: String, value: String) -> Result<()> {
+    credentials::set_skill_secret(&name, &value).await
+}
+
+#[tauri::command]
+pub async fn delete_mcp_secret(name: String) -> Result<()> {
+    credentials::delete_skill_secret(&name).await
+}
+
+/// Likely secrets and internal endpoints in rules and commands, for a warning before they
+/// are synced or exported. Allowlisted findings are left out.
+#[tauri::command]
+pub async fn scan_sensitive_content(db: State<'_, Arc<Database>>) -> Result<Vec<SensitiveFinding>> {
+    sensitive::scan_library(&db).await
+}
+
+#[tauri::command]
+pub async fn allow_sensitive_finding(
+    fingerprint: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    sensitive::allow(&db, &fingerprint).await
+}
+
+#[tauri::command]
+pub async fn disallow_sensitive_finding(
+    fingerprint: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    sensitive::disallow(&db, &fingerprint).await
+}
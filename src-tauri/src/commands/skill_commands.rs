@@ -1,17 +1,41 @@
 use std::sync::Arc;
 use tauri::State;
 
-use super::reconcile_after_mutation;
+use super::{reconcile_after_mutation, validate_metadata};
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::file_storage::skills::{delete_skill_from_disk, save_skill_to_disk};
+use crate::markdown::{self, MarkdownIssue};
 use crate::models::registry::{ArtifactType, REGISTRY};
-use crate::models::{AdapterType, CreateSkillInput, Scope, Skill, UpdateSkillInput};
-use crate::templates::skills::{get_bundled_skill_templates, TemplateSkill};
+use crate::models::{
+    AdapterType, ArtifactListQuery, ArtifactPage, CreateSkillInput, MetadataQuery, Scope, Skill,
+    SkillVersion, UpdateSkillInput,
+};
+use crate::policy;
+use crate::templates::marketplace;
+use crate::templates::skills::{
+    get_bundled_skill_templates, SkillTemplateInstall, TemplateSkill, TemplateSource,
+};
 
 #[tauri::command]
-pub async fn get_all_skills(db: State<'_, Arc<Database>>) -> Result<Vec<Skill>> {
-    db.get_all_skills().await
+pub async fn get_all_skills(
+    metadata_query: Option<MetadataQuery>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Skill>> {
+    let skills = db.get_all_skills().await?;
+    Ok(match metadata_query {
+        Some(query) => query.apply(skills),
+        None => skills,
+    })
+}
+
+/// One page of skills, filtered and sorted, for lists too long to load at once.
+#[tauri::command]
+pub async fn list_skills(
+    query: ArtifactListQuery,
+    db: State<'_, Arc<Database>>,
+) -> Result<ArtifactPage<Skill>> {
+    query.apply(db.get_all_skills().await?)
 }
 
 #[tauri::command]
@@ -19,12 +43,31 @@ pub async fn get_skill_by_id(id: String, db: State<'_, Arc<Database>>) -> Result
     db.get_skill_by_id(&id).await
 }
 
+/// Heading structure problems in a skill's instructions, and links to files missing from
+/// its directory.
+#[tauri::command]
+pub async fn validate_skill_markdown(
+    id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<MarkdownIssue>> {
+    let skill = db.get_skill_by_id(&id).await?;
+    let directory = std::path::Path::new(&skill.directory_path);
+    let asset_root = (!skill.directory_path.is_empty() && directory.is_dir()).then_some(directory);
+    Ok(markdown::validate(&skill.instructions, asset_root))
+}
+
 #[tauri::command]
 pub async fn create_skill(input: CreateSkillInput, db: State<'_, Arc<Database>>) -> Result<Skill> {
     crate::models::validate_skill_input(&input.name, &input.instructions)?;
     crate::models::validate_skill_schema(&input.input_schema)?;
     crate::models::validate_skill_entry_point(&input.entry_point)?;
     crate::models::validate_skill_target_adapters(&input.target_adapters)?;
+    crate::models::validate_skill_bundle_files(&input.bundle_files)?;
+    crate::models::validate_skill_frontmatter(&input.allowed_tools, input.license.as_deref())?;
+    if let Some(ref version) = input.version {
+        crate::models::parse_skill_version(version)?;
+    }
+    validate_metadata(&input.metadata)?;
 
     // Create in DB first
     let created = db.create_skill(input).await?;
@@ -59,9 +102,14 @@ pub async fn create_skill(input: CreateSkillInput, db: State<'_, Arc<Database>>)
 #[tauri::command]
 pub async fn update_skill(
     id: String,
-    input: UpdateSkillInput,
+    mut input: UpdateSkillInput,
     db: State<'_, Arc<Database>>,
 ) -> Result<Skill> {
+    if let Ok(existing) = db.get_skill_by_id(&id).await {
+        policy::keep_marker(&existing.metadata, input.metadata.as_mut());
+        policy::ensure_editable(&db, "Skill", &input, &existing).await?;
+    }
+
     if let Some(ref name) = input.name {
         if let Some(ref instructions) = input.instructions {
             crate::models::validate_skill_input(name, instructions)?;
@@ -86,6 +134,35 @@ pub async fn update_skill(
         crate::models::validate_skill_target_adapters(adapters)?;
     }
 
+    if let Some(ref files) = input.bundle_files {
+        crate::models::validate_skill_bundle_files(files)?;
+    }
+
+    if input.allowed_tools.is_some() || input.license.is_some() {
+        crate::models::validate_skill_frontmatter(
+            input.allowed_tools.as_deref().unwrap_or_default(),
+            input.license.as_deref(),
+        )?;
+    }
+
+    if let Some(ref pins) = input.version_pins {
+        crate::models::validate_skill_version_pins(pins)?;
+        let versions = db.get_skill_versions(&id).await?;
+        if let Some(pin) = pins
+            .iter()
+            .find(|pin| !versions.iter().any(|v| v.version == pin.version))
+        {
+            return Err(AppError::Validation(format!(
+                "Cannot pin version {}: it is not in this skill's history",
+                pin.version
+            )));
+        }
+    }
+
+    if let Some(ref metadata) = input.metadata {
+        validate_metadata(metadata)?;
+    }
+
     let updated = db.update_skill(&id, input).await?;
     save_skill_to_disk(&updated)?;
     // Run reconciliation so adapter files are updated immediately.
@@ -96,6 +173,7 @@ pub async fn update_skill(
 #[tauri::command]
 pub async fn delete_skill(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
     if let Ok(existing) = db.get_skill_by_id(&id).await {
+        policy::ensure_deletable(&db, "Skill", &existing.name, &existing.metadata).await?;
         let _ = delete_skill_from_disk(&existing);
     }
     db.delete_skill(&id).await?;
@@ -107,8 +185,74 @@ pub async fn delete_skill(id: String, db: State<'_, Arc<Database>>) -> Result<()
 }
 
 #[tauri::command]
-pub fn get_skill_templates() -> Result<Vec<TemplateSkill>> {
-    Ok(get_bundled_skill_templates())
+pub async fn get_skill_versions(
+    id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<SkillVersion>> {
+    db.get_skill_versions(&id).await
+}
+
+/// Restores the content of an earlier version as a new version, so the rollback itself
+/// shows up in the history.
+#[tauri::command]
+pub async fn rollback_skill(
+    id: String,
+    version: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Skill> {
+    let target = db.get_skill_version(&id, &version).await?;
+    let content = target.content;
+    let updated = db
+        .update_skill(
+            &id,
+            UpdateSkillInput {
+                name: Some(content.name),
+                description: Some(content.description),
+                instructions: Some(content.instructions),
+                input_schema: Some(content.input_schema),
+                entry_point: Some(content.entry_point),
+                allowed_tools: Some(content.allowed_tools),
+                license: Some(content.license.unwrap_or_default()),
+                changelog: Some(format!("Rolled back to {}", version)),
+                ..Default::default()
+            },
+        )
+        .await?;
+    save_skill_to_disk(&updated)?;
+    reconcile_after_mutation(db.inner().clone()).await;
+    Ok(updated)
+}
+
+/// Bundled templates plus those from the remote template index, optionally filtered by
+/// `query`. An unreachable index only hides the remote templates.
+#[tauri::command]
+pub async fn get_skill_templates(
+    query: Option<String>,
+    refresh: Option<bool>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<TemplateSkill>> {
+    let mut templates =
+        marketplace::filter_templates(get_bundled_skill_templates(), query.as_deref());
+    match marketplace::browse(&db, query.as_deref(), refresh.unwrap_or(false)).await {
+        Ok(remote) => {
+            let bundled_ids: Vec<String> =
+                templates.iter().map(|t| t.template_id.clone()).collect();
+            templates.extend(
+                remote
+                    .into_iter()
+                    .filter(|t| !bundled_ids.contains(&t.template_id)),
+            );
+        }
+        Err(e) => log::warn!("Skill template index unavailable: {}", e),
+    }
+    Ok(templates)
+}
+
+#[tauri::command]
+pub async fn get_skill_template_installs(
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<SkillTemplateInstall>> {
+    Ok(marketplace::list_installs(&db).await)
 }
 
 #[tauri::command]
@@ -132,12 +276,16 @@ pub async fn install_skill_template(
         return Ok(existing);
     }
 
-    // 2. Find template
-    let templates = get_bundled_skill_templates();
-    let template = templates
+    // 2. Find template, preferring bundled ones over the remote index
+    let bundled = get_bundled_skill_templates()
         .into_iter()
-        .find(|t| t.template_id == template_id)
-        .ok_or_else(|| AppError::Validation(format!("Template '{}' not found", template_id)))?;
+        .find(|t| t.template_id == template_id);
+    let template = match bundled {
+        Some(template) => template,
+        None => marketplace::find_template(&db, &template_id)
+            .await?
+            .ok_or_else(|| AppError::Validation(format!("Template '{}' not found", template_id)))?,
+    };
 
     // 3. Check for name collisions
     if db.skill_exists_with_name(&template.metadata.name).await? {
@@ -147,9 +295,13 @@ pub async fn install_skill_template(
         )));
     }
 
+    // Download remote assets before anything is written so a failed fetch leaves no trace
+    let assets = marketplace::fetch_assets(&template).await?;
+
     // 4. Ensure the metadata uses our specific template ID
     let mut metadata = template.metadata.clone();
     metadata.id = Some(template_id.clone());
+    metadata.bundle_files = Vec::new();
 
     // 4. Create in DB first so it generates default timestamps etc (using our prescribed ID)
     let created = db.create_skill(metadata).await?;
@@ -184,9 +336,28 @@ pub async fn install_skill_template(
         }
     }
 
+    // Write the asset bundle; paths were validated when the index was parsed
+    for (relative, bytes) in &assets {
+        let file_path = path.join(relative);
+        let written = file_path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&file_path, bytes));
+        if let Err(e) = written {
+            rollback(created.id.clone(), path.clone()).await;
+            return Err(AppError::Io(e));
+        }
+    }
+
     // 6. Update the DB with the absolute directory path that save_skill_to_disk determined
     let update = UpdateSkillInput {
         directory_path: Some(path.to_string_lossy().to_string()),
+        bundle_files: (!assets.is_empty()).then(|| {
+            assets
+                .iter()
+                .map(|(relative, _)| relative.clone())
+                .collect()
+        }),
         ..Default::default()
     };
 
@@ -195,6 +366,20 @@ pub async fn install_skill_template(
         return Err(e);
     }
 
+    // 7. Record where remote templates came from so newer index versions can be offered
+    if template.source == TemplateSource::Remote {
+        let install = SkillTemplateInstall {
+            template_id: template_id.clone(),
+            index_url: marketplace::index_url(&db).await,
+            version: template.metadata.version.clone(),
+            installed_at: chrono::Utc::now(),
+        };
+        if let Err(e) = marketplace::record_install(&db, install).await {
+            rollback(created.id.clone(), path.clone()).await;
+            return Err(e);
+        }
+    }
+
     // Return the latest from DB
     db.get_skill_by_id(&template_id).await
 }
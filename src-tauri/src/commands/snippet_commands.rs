@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{CreateSnippetInput, Snippet, SnippetUsage, UpdateSnippetInput};
+use crate::snippets;
+use crate::sync::SyncEngine;
+
+use super::fetch_rules;
+
+async fn snippet_usages(db: &Database, name: &str) -> Result<Vec<SnippetUsage>> {
+    Ok(snippets::usages(&fetch_rules(db, None).await?, name))
+}
+
+async fn ensure_unique_name(db: &Database, name: &str, id: Option<&str>) -> Result<()> {
+    let taken = db
+        .get_all_snippets()
+        .await?
+        .iter()
+        .any(|snippet| snippet.name == name && Some(snippet.id.as_str()) != id);
+    if taken {
+        return Err(AppError::Validation(format!(
+            "A snippet named '{}' already exists",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Rewrites the tools' files so rules pick up the changed snippet.
+async fn resync(db: &Database) {
+    match db.get_all_rules().await {
+        Ok(rules) => {
+            let result = SyncEngine::new(db).sync_all(rules).await;
+            if !result.errors.is_empty() {
+                log::error!("Sync after a snippet change failed: {:?}", result.errors);
+            }
+        }
+        Err(e) => log::error!("Failed to get rules for sync: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_snippets(db: State<'_, Arc<Database>>) -> Result<Vec<Snippet>> {
+    db.get_all_snippets().await
+}
+
+#[tauri::command]
+pub async fn create_snippet(
+    mut input: CreateSnippetInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<Snippet> {
+    input.name = input.name.trim().to_string();
+    snippets::validate_name(&input.name)?;
+    ensure_unique_name(&db, &input.name, None).await?;
+    let created = db.create_snippet(input).await?;
+    // Rules may already reference the name.
+    if !snippet_usages(&db, &created.name).await?.is_empty() {
+        resync(&db).await;
+    }
+    Ok(created)
+}
+
+/// Updates a snippet. Renaming one that rules reference would leave their tokens dangling,
+/// so it is refused unless `force` is set.
+#[tauri::command]
+pub async fn update_snippet(
+    id: String,
+    mut input: UpdateSnippetInput,
+    force: Option<bool>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Snippet> {
+    let existing = db.get_snippet_by_id(&id).await?;
+    let mut usages = snippet_usages(&db, &existing.name).await?;
+    if let Some(name) = &mut input.name {
+        *name = name.trim().to_string();
+        if *name != existing.name {
+            snippets::validate_name(name)?;
+            ensure_unique_name(&db, name, Some(&id)).await?;
+            snippets::ensure_unused(&existing, &usages, force.unwrap_or(false))?;
+            usages.extend(snippet_usages(&db, name).await?);
+        }
+    }
+    let updated = db.update_snippet(&id, input).await?;
+    if !usages.is_empty() {
+        resync(&db).await;
+    }
+    Ok(updated)
+}
+
+/// Deletes a snippet, refusing while rules reference it unless `force` is set.
+#[tauri::command]
+pub async fn delete_snippet(
+    id: String,
+    force: Option<bool>,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    let existing = db.get_snippet_by_id(&id).await?;
+    let usages = snippet_usages(&db, &existing.name).await?;
+    snippets::ensure_unused(&existing, &usages, force.unwrap_or(false))?;
+    db.delete_snippet(&id).await?;
+    if !usages.is_empty() {
+        resync(&db).await;
+    }
+    Ok(())
+}
+
+/// The rules that reference a snippet.
+#[tauri::command]
+pub async fn get_snippet_usages(
+    id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<SnippetUsage>> {
+    let snippet = db.get_snippet_by_id(&id).await?;
+    snippet_usages(&db, &snippet.name).await
+}
@@ -1,13 +1,25 @@
 use std::collections::HashMap;
 use std::fs;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 
+use crate::app_log;
 use crate::database::{get_app_data_path, Database};
-use crate::error::Result;
-use crate::models::{ExecutionLog, SyncHistoryEntry};
+use crate::doctor::{DoctorContext, DoctorReport};
+use crate::error::{AppError, Result};
+use crate::jobs;
+use crate::log_retention::{LogPurgeResult, LogTable};
+use crate::mcp::McpManager;
+use crate::metrics;
+use crate::models::{
+    AppLogPage, AppLogQuery, AppSettings, AuditLogPage, AuditLogQuery, ExecutionArtifact,
+    ExecutionLog, JobRecord, JobResolution, MetricsSnapshot, SettingsChanged, SyncHistoryEntry,
+    SyncReport, SyncReportFormat,
+};
+use crate::settings::{self, SETTINGS_CHANGED_EVENT};
+use crate::sync::sync_report_to_markdown;
 
-use super::validate_path;
+use super::{reconcile_after_mutation, validate_export_path, validate_path, LOCAL_RULE_PATHS_KEY};
 
 #[tauri::command]
 pub async fn get_execution_history(
@@ -34,6 +46,37 @@ pub async fn get_execution_history_filtered(
     .await
 }
 
+/// Files kept from an execution by its command's artifact globs.
+#[tauri::command]
+pub async fn get_execution_artifacts(
+    execution_log_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ExecutionArtifact>> {
+    crate::artifacts::get_artifacts(&db, &execution_log_id).await
+}
+
+/// Deletes log entries from the selected tables (all tables when none are given). With
+/// `older_than_days`, only entries older than that many days are removed.
+#[tauri::command]
+pub async fn purge_logs(
+    tables: Option<Vec<LogTable>>,
+    older_than_days: Option<u32>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<LogPurgeResult>> {
+    let older_than =
+        older_than_days.map(|days| chrono::Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60);
+    let tables = tables
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(LogTable::all);
+
+    let mut results = Vec::with_capacity(tables.len());
+    for table in tables {
+        let deleted = db.purge_log_table(table, older_than).await?;
+        results.push(LogPurgeResult { table, deleted });
+    }
+    Ok(results)
+}
+
 #[tauri::command]
 pub async fn get_sync_history(
     limit: Option<u32>,
@@ -42,6 +85,70 @@ pub async fn get_sync_history(
     db.get_sync_history(limit.unwrap_or(50)).await
 }
 
+/// Returns captured backend log lines, newest first, for the diagnostics console.
+#[tauri::command]
+pub fn query_app_logs(query: Option<AppLogQuery>) -> AppLogPage {
+    app_log::query(&query.unwrap_or_default())
+}
+
+/// Recorded changes and runs, newest first, optionally filtered by actor, action or target.
+#[tauri::command]
+pub async fn query_audit_log(
+    query: Option<AuditLogQuery>,
+    db: State<'_, Arc<Database>>,
+) -> Result<AuditLogPage> {
+    db.query_audit_log(query.unwrap_or_default()).await
+}
+
+/// Imports, migrations and reconciliations that were cut short and await a decision.
+#[tauri::command]
+pub async fn get_pending_jobs(db: State<'_, Arc<Database>>) -> Result<Vec<JobRecord>> {
+    db.get_pending_jobs().await
+}
+
+#[tauri::command]
+pub async fn resolve_pending_job(
+    id: String,
+    resolution: JobResolution,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    jobs::resolve(db.inner().clone(), &id, resolution).await
+}
+
+/// Counters and timing histograms collected since the app started.
+#[tauri::command]
+pub fn get_metrics() -> MetricsSnapshot {
+    metrics::snapshot()
+}
+
+#[tauri::command]
+pub async fn get_sync_report(sync_id: String, db: State<'_, Arc<Database>>) -> Result<SyncReport> {
+    db.get_sync_report(&sync_id)
+        .await?
+        .ok_or(AppError::SyncReportNotFound { id: sync_id })
+}
+
+/// Writes a sync report to `path` as JSON or Markdown.
+#[tauri::command]
+pub async fn export_sync_report(
+    sync_id: String,
+    format: SyncReportFormat,
+    path: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    let target = validate_export_path(&path)?;
+    let report = db
+        .get_sync_report(&sync_id)
+        .await?
+        .ok_or(AppError::SyncReportNotFound { id: sync_id })?;
+    let content = match format {
+        SyncReportFormat::Json => serde_json::to_string_pretty(&report)?,
+        SyncReportFormat::Markdown => sync_report_to_markdown(&report),
+    };
+    fs::write(target, content)?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn read_file_content(path: String) -> Result<String> {
     let validated_path = validate_path(&path)?;
@@ -65,9 +172,53 @@ pub async fn get_setting(key: String, db: State<'_, Arc<Database>>) -> Result<Op
     db.get_setting(&key).await
 }
 
+/// Sets one setting from its stored form. Only the settings in [`AppSettings`] can be set.
+#[tauri::command]
+pub async fn set_setting(
+    key: String,
+    value: String,
+    app: tauri::AppHandle,
+    db: State<'_, Arc<Database>>,
+) -> Result<()> {
+    let (settings, changed) = settings::set(&db, &key, &value).await?;
+    settings_changed(&app, db.inner(), settings, changed).await;
+    Ok(())
+}
+
 #[tauri::command]
-pub async fn set_setting(key: String, value: String, db: State<'_, Arc<Database>>) -> Result<()> {
-    db.set_setting(&key, &value).await
+pub async fn get_app_settings(db: State<'_, Arc<Database>>) -> Result<AppSettings> {
+    settings::load(&db).await
+}
+
+/// Applies a partial [`AppSettings`] object and returns the result.
+#[tauri::command]
+pub async fn update_app_settings(
+    patch: serde_json::Value,
+    app: tauri::AppHandle,
+    db: State<'_, Arc<Database>>,
+) -> Result<AppSettings> {
+    let (settings, changed) = settings::update(&db, patch).await?;
+    settings_changed(&app, db.inner(), settings.clone(), changed).await;
+    Ok(settings)
+}
+
+async fn settings_changed(
+    app: &tauri::AppHandle,
+    db: &Arc<Database>,
+    settings: AppSettings,
+    changed: Vec<String>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    // Local-scoped slash commands follow the registered repository roots.
+    if changed.iter().any(|key| key == LOCAL_RULE_PATHS_KEY) {
+        reconcile_after_mutation(db.clone()).await;
+    }
+    let _ = app.emit(
+        SETTINGS_CHANGED_EVENT,
+        SettingsChanged { settings, changed },
+    );
 }
 
 #[tauri::command]
@@ -75,6 +226,20 @@ pub async fn get_all_settings(db: State<'_, Arc<Database>>) -> Result<HashMap<St
     db.get_all_settings().await
 }
 
+#[tauri::command]
+pub async fn run_doctor(
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+    watcher: State<'_, crate::WatcherState>,
+) -> Result<DoctorReport> {
+    let status = mcp.status().await?;
+    let context = DoctorContext {
+        mcp_port: status.running.then_some(status.port),
+        watcher: Some(watcher.0.clone()),
+    };
+    Ok(crate::doctor::run_doctor(&db, context).await)
+}
+
 #[tauri::command]
 pub fn get_app_data_path_cmd(app: tauri::AppHandle) -> Result<String> {
     let path = get_app_data_path(&app)?;
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::models::{CreateWebhookInput, UpdateWebhookInput, Webhook, WebhookDelivery};
+use crate::webhooks;
+
+#[tauri::command]
+pub async fn get_webhooks(db: State<'_, Arc<Database>>) -> Result<Vec<Webhook>> {
+    db.get_all_webhooks().await
+}
+
+#[tauri::command]
+pub async fn create_webhook(
+    mut input: CreateWebhookInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<Webhook> {
+    input.url = input.url.trim().to_string();
+    input.secret_name = input.secret_name.filter(|name| !name.is_empty());
+    webhooks::validate(&input.url, &input.events, input.secret_name.as_deref())?;
+    db.create_webhook(input).await
+}
+
+#[tauri::command]
+pub async fn update_webhook(
+    id: String,
+    mut input: UpdateWebhookInput,
+    db: State<'_, Arc<Database>>,
+) -> Result<Webhook> {
+    let existing = db.get_webhook_by_id(&id).await?;
+    if let Some(url) = &mut input.url {
+        *url = url.trim().to_string();
+    }
+    webhooks::validate(
+        input.url.as_deref().unwrap_or(&existing.url),
+        input.events.as_deref().unwrap_or(&existing.events),
+        input
+            .secret_name
+            .as_deref()
+            .or(existing.secret_name.as_deref()),
+    )?;
+    db.update_webhook(&id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_webhook(id: String, db: State<'_, Arc<Database>>) -> Result<()> {
+    db.delete_webhook(&id).await
+}
+
+/// Sends a `webhook.test` event now and reports the response, retries included.
+#[tauri::command]
+pub async fn test_webhook(id: String, db: State<'_, Arc<Database>>) -> Result<WebhookDelivery> {
+    webhooks::send_test(&db, &id).await
+}
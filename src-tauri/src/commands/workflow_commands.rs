@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{Emitter, State};
+
+use crate::database::Database;
+use crate::error::Result;
+use crate::execution::{register_execution, OutputSink};
+use crate::mcp::McpManager;
+use crate::models::{
+    CommandOutputEvent, CreateWorkflowInput, UpdateWorkflowInput, Workflow, WorkflowRunResult,
+    WorkflowStepEvent,
+};
+use crate::slash_commands::SlashCommandSyncEngine;
+use crate::workflow::{
+    run_workflow as run_workflow_steps, validate_workflow, workflow_slash_command, StepSink,
+    WorkflowRunOptions,
+};
+
+use super::validate_command_arguments;
+
+/// Writes the workflow's global slash command files; errors are non-fatal.
+async fn sync_workflow_slash_commands(db: &Arc<Database>, workflow: &Workflow) -> Result<()> {
+    if !workflow.generate_slash_commands || workflow.slash_command_adapters.is_empty() {
+        return Ok(());
+    }
+    let commands = db.get_all_commands().await?;
+    let engine = SlashCommandSyncEngine::new(Arc::clone(db));
+    if let Err(e) = engine.sync_command(&workflow_slash_command(workflow, &commands), true) {
+        log::warn!(
+            "Failed to sync slash command for workflow '{}': {}",
+            workflow.name,
+            e
+        );
+    }
+    Ok(())
+}
+
+fn remove_workflow_slash_commands(db: &Arc<Database>, name: &str, adapters: &[String]) {
+    if adapters.is_empty() {
+        return;
+    }
+    let engine = SlashCommandSyncEngine::new(Arc::clone(db));
+    if let Err(e) = engine.remove_command(name, None, adapters, &[]) {
+        log::warn!(
+            "Failed to remove slash command files for workflow '{}': {}",
+            name,
+            e
+        );
+    }
+}
+
+#[tauri::command]
+pub async fn get_all_workflows(db: State<'_, Arc<Database>>) -> Result<Vec<Workflow>> {
+    db.get_all_workflows().await
+}
+
+#[tauri::command]
+pub async fn get_workflow_by_id(id: String, db: State<'_, Arc<Database>>) -> Result<Workflow> {
+    db.get_workflow_by_id(&id).await
+}
+
+#[tauri::command]
+pub async fn create_workflow(
+    input: CreateWorkflowInput,
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+) -> Result<Workflow> {
+    let commands = db.get_all_commands().await?;
+    validate_workflow(&input.name, &input.steps, &commands)?;
+    validate_command_arguments(&input.arguments)?;
+
+    let created = db.create_workflow(input).await?;
+    mcp.refresh_commands(&db).await?;
+    sync_workflow_slash_commands(&db, &created).await?;
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_workflow(
+    id: String,
+    input: UpdateWorkflowInput,
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+) -> Result<Workflow> {
+    let existing = db.get_workflow_by_id(&id).await?;
+
+    let commands = db.get_all_commands().await?;
+    validate_workflow(
+        input.name.as_deref().unwrap_or(&existing.name),
+        input.steps.as_deref().unwrap_or(&existing.steps),
+        &commands,
+    )?;
+    if let Some(args) = &input.arguments {
+        validate_command_arguments(args)?;
+    }
+
+    let updated = db.update_workflow(&id, input).await?;
+    mcp.refresh_commands(&db).await?;
+
+    // Orphan prevention: drop every old file on rename or when generation is turned off,
+    // otherwise only the files of deselected adapters.
+    if existing.generate_slash_commands {
+        if existing.name != updated.name || !updated.generate_slash_commands {
+            remove_workflow_slash_commands(&db, &existing.name, &existing.slash_command_adapters);
+        } else {
+            let deselected: Vec<String> = existing
+                .slash_command_adapters
+                .iter()
+                .filter(|a| !updated.slash_command_adapters.contains(a))
+                .cloned()
+                .collect();
+            remove_workflow_slash_commands(&db, &existing.name, &deselected);
+        }
+    }
+    sync_workflow_slash_commands(&db, &updated).await?;
+
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn delete_workflow(
+    id: String,
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+) -> Result<()> {
+    let workflow = db.get_workflow_by_id(&id).await?;
+
+    db.delete_workflow(&id).await?;
+    mcp.refresh_commands(&db).await?;
+
+    if workflow.generate_slash_commands {
+        remove_workflow_slash_commands(&db, &workflow.name, &workflow.slash_command_adapters);
+    }
+
+    Ok(())
+}
+
+/// Runs a workflow, emitting `workflow-step` as each step finishes and `command-output`
+/// for every line of output. Cancel it with `cancel_execution` and the same id.
+#[tauri::command]
+pub async fn run_workflow(
+    app: tauri::AppHandle,
+    id: String,
+    args: HashMap<String, String>,
+    execution_id: Option<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<WorkflowRunResult> {
+    let workflow = db.get_workflow_by_id(&id).await?;
+    let execution_id = execution_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let (_registration, cancel) = register_execution(&execution_id);
+
+    let on_step: StepSink = {
+        let app = app.clone();
+        let execution_id = execution_id.clone();
+        let workflow_id = workflow.id.clone();
+        Arc::new(move |result| {
+            let _ = app.emit(
+                "workflow-step",
+                WorkflowStepEvent {
+                    execution_id: execution_id.clone(),
+                    workflow_id: workflow_id.clone(),
+                    result: result.clone(),
+                },
+            );
+        })
+    };
+    let on_output: OutputSink = {
+        let app = app.clone();
+        let execution_id = execution_id.clone();
+        let workflow_id = workflow.id.clone();
+        Arc::new(move |stream, line| {
+            let _ = app.emit(
+                "command-output",
+                CommandOutputEvent {
+                    execution_id: execution_id.clone(),
+                    command_id: workflow_id.clone(),
+                    stream,
+                    line: line.to_string(),
+                },
+            );
+        })
+    };
+
+    run_workflow_steps(
+        &db,
+        &workflow,
+        &args,
+        WorkflowRunOptions {
+            triggered_by: "workflow",
+            adapter_context: None,
+            on_step: Some(on_step),
+            on_output: Some(on_output),
+            cancel: Some(cancel),
+        },
+    )
+    .await
+}
@@ -0,0 +1,45 @@
+use tauri::Manager;
+
+use crate::error::Result;
+use crate::models::{Workspace, WorkspaceList};
+use crate::workspace;
+use crate::GlobalStatus;
+
+fn refresh_tray(app: &tauri::AppHandle) {
+    if let Some(status) = app.try_state::<GlobalStatus>() {
+        status.update_workspace_menu(app);
+    }
+}
+
+#[tauri::command]
+pub fn list_workspaces() -> Result<WorkspaceList> {
+    workspace::list()
+}
+
+#[tauri::command]
+pub fn create_workspace(name: String, app: tauri::AppHandle) -> Result<Workspace> {
+    let created = workspace::create(&name)?;
+    refresh_tray(&app);
+    Ok(created)
+}
+
+#[tauri::command]
+pub fn rename_workspace(id: String, name: String, app: tauri::AppHandle) -> Result<Workspace> {
+    let renamed = workspace::rename(&id, &name)?;
+    refresh_tray(&app);
+    Ok(renamed)
+}
+
+/// Removes a workspace from the list; its files are kept.
+#[tauri::command]
+pub fn delete_workspace(id: String, app: tauri::AppHandle) -> Result<()> {
+    workspace::remove(&id)?;
+    refresh_tray(&app);
+    Ok(())
+}
+
+/// Restarts the app into the workspace `id`.
+#[tauri::command]
+pub fn switch_workspace(id: String, app: tauri::AppHandle) -> Result<()> {
+    workspace::switch(&app, &id)
+}
@@ -10,13 +10,43 @@ pub mod timing {
     pub const MCP_SERVER_BACKOFF_INITIAL_MS: u64 = 100;
     pub const WATCHER_DEBOUNCE: Duration = Duration::from_millis(500);
     pub const WATCHER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+    pub const AUTO_BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+    pub const AUTO_BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+    pub const EXPORT_SNAPSHOT_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+    pub const DEFAULT_EXPORT_SNAPSHOT_INTERVAL_HOURS: u64 = 24;
+    pub const LOG_PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+    pub const SUBSCRIPTION_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+    pub const SUBSCRIPTION_FETCH_TIMEOUT: Duration = Duration::from_secs(120);
+    pub const DEFAULT_SUBSCRIPTION_INTERVAL_HOURS: u32 = 24;
+    pub const TEAM_REPO_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+    pub const TEAM_REPO_GIT_TIMEOUT: Duration = Duration::from_secs(120);
+    pub const DEFAULT_TEAM_REPO_INTERVAL_HOURS: u32 = 1;
+    pub const CLOUD_SYNC_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+    pub const CLOUD_SYNC_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+    pub const DEFAULT_CLOUD_SYNC_INTERVAL_MINUTES: u32 = 15;
+    pub const CATALOG_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+    pub const SKILL_TEMPLATE_INDEX_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+    pub const PEER_SHARE_TTL: Duration = Duration::from_secs(10 * 60);
+    pub const PEER_FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+    pub const SHARE_LINK_TTL: Duration = Duration::from_secs(60 * 60);
+    pub const MCP_APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+    pub const MCP_CLIENT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+    pub const TOOL_DETECTION_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+    pub const SCHEDULER_TICK_INTERVAL: Duration = Duration::from_secs(30);
+    pub const DRIFT_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+    pub const RECONCILE_PLAN_TTL: Duration = Duration::from_secs(10 * 60);
 }
 
 pub mod limits {
     pub const MAX_ARG_LENGTH: usize = 2000;
     pub const MAX_SCRIPT_LENGTH: usize = 20000;
+    pub const MAX_STDIN_LENGTH: usize = 1024 * 1024; // 1MB
     pub const MAX_OUTPUT_SIZE: usize = 10 * 1024 * 1024; // 10MB
-    pub const LOG_LIMIT: usize = 500;
+    pub const APP_LOG_LIMIT: usize = 5000;
+    pub const APP_LOG_DEFAULT_PAGE: u32 = 200;
+    pub const APP_LOG_MAX_PAGE: u32 = 1000;
+    pub const AUDIT_LOG_DEFAULT_PAGE: u32 = 100;
+    pub const AUDIT_LOG_MAX_PAGE: u32 = 1000;
     pub const MCP_RATE_LIMIT_MAX_CALLS: usize = 10;
     pub const TEST_CMD_RATE_LIMIT_MAX: usize = 5;
     pub const MAX_RULE_NAME_LENGTH: usize = 200;
@@ -24,9 +54,37 @@ pub mod limits {
     pub const MAX_COMMAND_NAME_LENGTH: usize = 120;
     pub const MAX_COMMAND_SCRIPT_LENGTH: usize = 10_000;
     pub const MAX_SKILL_NAME_LENGTH: usize = 160;
+    pub const MAX_COLLECTION_NAME_LENGTH: usize = 120;
     pub const MAX_SKILL_INSTRUCTIONS_LENGTH: usize = 200_000;
+    pub const MAX_SKILL_LICENSE_LENGTH: usize = 200;
     pub const MAX_SKILL_OUTPUT_PER_STREAM: usize = 1024 * 1024; // 1MB per step stream
     pub const MCP_SERVER_RETRY_COUNT: u32 = 5;
+    pub const DEFAULT_BACKUP_RETENTION: usize = 7;
+    pub const DEFAULT_EXPORT_SNAPSHOT_RETENTION: usize = 10;
+    pub const DEFAULT_LOG_RETENTION_MAX_ROWS: u64 = 10_000;
+    pub const DEFAULT_LOG_RETENTION_MAX_AGE_DAYS: u64 = 90;
+    pub const DEFAULT_ARTIFACT_RETENTION_MAX_AGE_DAYS: u64 = 14;
+    /// Per-execution caps on captured artifacts; files beyond them are skipped.
+    pub const MAX_ARTIFACT_FILES: usize = 500;
+    pub const MAX_ARTIFACT_TOTAL_BYTES: u64 = 100 * 1024 * 1024;
+    pub const MAX_METADATA_KEYS: usize = 50;
+    pub const MAX_METADATA_KEY_LENGTH: usize = 64;
+    pub const MAX_METADATA_JSON_LENGTH: usize = 64 * 1024;
+    pub const MAX_ARCHIVE_ENTRIES: usize = 5_000;
+    pub const MAX_ARCHIVE_UNCOMPRESSED_BYTES: u64 = 200 * 1024 * 1024;
+    pub const PEER_SHARE_MAX_FAILED_ATTEMPTS: usize = 5;
+    pub const PEER_SHARE_CODE_LENGTH: usize = 8;
+    pub const SHARE_LINK_TOKEN_BYTES: usize = 16;
+}
+
+pub mod database {
+    use super::*;
+    pub const DB_READ_POOL_SIZE: usize = 4;
+    pub const DB_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+    /// Schema version written by this build. Databases stamped with a higher version come
+    /// from a newer release and are refused rather than silently downgraded.
+    pub const SCHEMA_VERSION: i32 = 47;
+    pub const PRE_MIGRATION_BACKUP_RETENTION: usize = 5;
 }
 
 pub mod skills {
@@ -42,6 +100,10 @@ pub const DEFAULT_MCP_PORT: u16 = 8080;
 pub const SKILLS_DIR_NAME: &str = "skills";
 pub const SKILL_METADATA_FILE: &str = "skill.json";
 pub const SKILL_INSTRUCTIONS_FILE: &str = "SKILL.md";
+/// Directories next to SKILL.md whose files are copied along with a skill.
+pub const SKILL_BUNDLE_DIRS: &[&str] = &["scripts", "resources"];
+/// File extension of single-skill archives.
+pub const SKILL_PACK_EXTENSION: &str = "skillpack";
 
 pub const ANTIGRAVITY_FILENAME: &str = "GEMINI.md";
 pub const GEMINI_FILENAME: &str = "GEMINI.md";
@@ -49,6 +111,7 @@ pub const OPENCODE_FILENAME: &str = "AGENTS.md";
 
 pub const LEGACY_ANTIGRAVITY_DIR: &str = ".antigravity";
 pub const LEGACY_OPENCODE_DIR: &str = ".opencode";
+pub const LEGACY_WINDSURF_FILENAME: &str = ".windsurfrules";
 
 pub const NEW_GEMINI_DIR: &str = ".gemini";
 pub const NEW_OPENCODE_DIR: &str = ".config/opencode";
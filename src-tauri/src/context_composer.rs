@@ -0,0 +1,313 @@
+//! Assembling the rules an agent needs for a task within a token budget.
+//!
+//! The `compose_context` MCP tool takes a description of the task and a token budget and
+//! returns one Markdown block of rules, so an agent can load what applies to the work at
+//! hand instead of every rule file. Enabled rules are picked in this order:
+//!
+//! 1. rules marked `alwaysApply` in their metadata;
+//! 2. rules whose `globs` match one of the files the agent is working on, or whose `tags`
+//!    include one it asked for;
+//! 3. rules that match the task description, best match first.
+//!
+//! Within a group, better search matches come first. Rules are added in that order while
+//! they fit; one that does not fit is skipped and reported, and smaller rules after it may
+//! still be added. Tokens are estimated at four characters each, which is close for English
+//! prose and code with common tokenizers but is not exact.
+
+use glob::{MatchOptions, Pattern};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{Rule, TAGS_METADATA_KEY};
+use crate::rule_search::{self, SearchMode};
+
+const GLOBS_METADATA_KEY: &str = "globs";
+const ALWAYS_APPLY_METADATA_KEY: &str = "alwaysApply";
+
+const CHARS_PER_TOKEN: usize = 4;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// What an agent is working on.
+#[derive(Debug, Clone, Default)]
+pub struct ContextRequest {
+    pub task_description: String,
+    pub max_tokens: u32,
+    /// Paths relative to the repository root, matched against rules' `globs`
+    pub files: Vec<String>,
+    pub tags: Vec<String>,
+    pub mode: SearchMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionReason {
+    AlwaysApply,
+    MatchesFiles,
+    MatchesTags,
+    MatchesTask,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposedRule {
+    pub id: String,
+    pub name: String,
+    pub reason: SelectionReason,
+    pub tokens: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposedContext {
+    /// The selected rules as one Markdown block
+    pub content: String,
+    pub tokens: u32,
+    pub rules: Vec<ComposedRule>,
+    /// Relevant rules left out because they did not fit in the budget
+    pub omitted: Vec<ComposedRule>,
+}
+
+/// Estimated tokens in `text`.
+pub fn estimate_tokens(text: &str) -> u32 {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) as u32
+}
+
+/// Values of a metadata list, which imports store either as an array or as one
+/// comma-separated string.
+fn metadata_list<'a>(rule: &'a Rule, key: &str) -> Vec<&'a str> {
+    let values: Vec<&str> = match rule.metadata.get(key) {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        Some(Value::String(value)) => value.split(',').collect(),
+        _ => Vec::new(),
+    };
+    values
+        .into_iter()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Whether a glob from a rule matches `file`. Globs without a `/` match the file name in
+/// any directory, as in Cursor rules.
+fn glob_matches(glob: &str, file: &str) -> bool {
+    let Ok(pattern) = Pattern::new(glob) else {
+        return false;
+    };
+    let file = file.replace('\\', "/");
+    let file = file.trim_start_matches("./");
+    pattern.matches_with(file, MATCH_OPTIONS)
+        || (!glob.contains('/')
+            && file
+                .rsplit('/')
+                .next()
+                .is_some_and(|name| pattern.matches_with(name, MATCH_OPTIONS)))
+}
+
+fn selection_reason(
+    rule: &Rule,
+    request: &ContextRequest,
+    matches_task: bool,
+) -> Option<SelectionReason> {
+    if rule.metadata.get_bool(ALWAYS_APPLY_METADATA_KEY) == Some(true) {
+        return Some(SelectionReason::AlwaysApply);
+    }
+    let globs = metadata_list(rule, GLOBS_METADATA_KEY);
+    if request
+        .files
+        .iter()
+        .any(|file| globs.iter().any(|glob| glob_matches(glob, file)))
+    {
+        return Some(SelectionReason::MatchesFiles);
+    }
+    if metadata_list(rule, TAGS_METADATA_KEY).iter().any(|tag| {
+        request
+            .tags
+            .iter()
+            .any(|wanted| wanted.trim().eq_ignore_ascii_case(tag))
+    }) {
+        return Some(SelectionReason::MatchesTags);
+    }
+    matches_task.then_some(SelectionReason::MatchesTask)
+}
+
+fn section(rule: &Rule) -> String {
+    format!("## {}\n\n{}", rule.name, rule.content.trim())
+}
+
+/// Picks from `rules` for `request`. `scores` holds the search score of the rules matching
+/// the task description, by id; higher is better.
+pub fn compose(
+    rules: &[Rule],
+    scores: &std::collections::HashMap<String, f64>,
+    request: &ContextRequest,
+) -> ComposedContext {
+    let mut candidates: Vec<(SelectionReason, f64, &Rule)> = rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| {
+            let score = scores.get(&rule.id).copied();
+            let reason = selection_reason(rule, request, score.is_some())?;
+            Some((reason, score.unwrap_or(f64::NEG_INFINITY), rule))
+        })
+        .collect();
+    candidates.sort_by(|(reason_a, score_a, a), (reason_b, score_b, b)| {
+        reason_a
+            .cmp(reason_b)
+            .then(score_b.total_cmp(score_a))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    // Sections are joined by a blank line, which counts against the budget too.
+    let separator_tokens = estimate_tokens("\n\n");
+    let mut sections = Vec::new();
+    let mut tokens = 0u32;
+    let mut selected = Vec::new();
+    let mut omitted = Vec::new();
+    for (reason, _, rule) in candidates {
+        let text = section(rule);
+        let cost = estimate_tokens(&text);
+        let composed = ComposedRule {
+            id: rule.id.clone(),
+            name: rule.name.clone(),
+            reason,
+            tokens: cost,
+        };
+        let needed = cost
+            + if sections.is_empty() {
+                0
+            } else {
+                separator_tokens
+            };
+        if tokens + needed <= request.max_tokens {
+            tokens += needed;
+            sections.push(text);
+            selected.push(composed);
+        } else {
+            omitted.push(composed);
+        }
+    }
+    ComposedContext {
+        content: sections.join("\n\n"),
+        tokens,
+        rules: selected,
+        omitted,
+    }
+}
+
+/// The rules for `request`, with snippets expanded as sync writes them.
+pub async fn compose_for_task(db: &Database, request: &ContextRequest) -> Result<ComposedContext> {
+    if request.max_tokens == 0 {
+        return Err(AppError::InvalidInput {
+            message: "max_tokens must be at least 1".to_string(),
+        });
+    }
+    let scores = if request.task_description.trim().is_empty() {
+        Default::default()
+    } else {
+        rule_search::search(
+            db,
+            &request.task_description,
+            request.mode,
+            rule_search::MAX_LIMIT,
+        )
+        .await?
+        .into_iter()
+        .map(|hit| (hit.id, hit.score))
+        .collect()
+    };
+    let rules = crate::snippets::expand_rules(db, db.get_all_rules().await?).await;
+    Ok(compose(&rules, &scores, request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Scope;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn rule(name: &str, content: &str, metadata: Value) -> Rule {
+        let mut rule = Rule::new(
+            name.to_string(),
+            String::new(),
+            content.to_string(),
+            Scope::Global,
+        );
+        rule.id = name.to_lowercase();
+        if let Value::Object(fields) = metadata {
+            for (key, value) in fields {
+                rule.metadata.insert(key, value);
+            }
+        }
+        rule
+    }
+
+    fn names(rules: &[ComposedRule]) -> Vec<&str> {
+        rules.iter().map(|rule| rule.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_compose_orders_by_reason_then_score() {
+        let rules = vec![
+            rule("Logging", "Log errors.", json!({})),
+            rule(
+                "Security",
+                "Never log secrets.",
+                json!({ "alwaysApply": true }),
+            ),
+            rule("React", "Use hooks.", json!({ "globs": "*.tsx, *.jsx" })),
+            rule("Docs", "Document APIs.", json!({ "tags": ["docs"] })),
+            rule("Errors", "Wrap errors.", json!({})),
+            rule("Unrelated", "Anything.", json!({})),
+        ];
+        let scores = HashMap::from([("logging".to_string(), 1.0), ("errors".to_string(), 2.0)]);
+        let request = ContextRequest {
+            task_description: "error handling".to_string(),
+            max_tokens: 1000,
+            files: vec!["./src/components/App.tsx".to_string()],
+            tags: vec!["Docs".to_string()],
+            ..Default::default()
+        };
+
+        let composed = compose(&rules, &scores, &request);
+        assert_eq!(
+            names(&composed.rules),
+            vec!["Security", "React", "Docs", "Errors", "Logging"]
+        );
+        assert!(composed
+            .content
+            .starts_with("## Security\n\nNever log secrets.\n\n## React"));
+    }
+
+    #[test]
+    fn test_compose_skips_rules_that_do_not_fit() {
+        let rules = vec![
+            rule("Long", &"x".repeat(400), json!({ "alwaysApply": true })),
+            rule("Short", "Be brief.", json!({ "alwaysApply": true })),
+        ];
+        let request = ContextRequest {
+            max_tokens: 20,
+            ..Default::default()
+        };
+
+        let composed = compose(&rules, &HashMap::new(), &request);
+        assert_eq!(names(&composed.rules), vec!["Short"]);
+        assert_eq!(names(&composed.omitted), vec!["Long"]);
+        assert!(composed.tokens <= 20);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("src/**/*.rs", "src/sync/mod.rs"));
+        assert!(glob_matches("*.rs", "src/sync/mod.rs"));
+        assert!(!glob_matches("src/*.rs", "src/sync/mod.rs"));
+        assert!(glob_matches("src/*.rs", "src\\lib.rs"));
+    }
+}
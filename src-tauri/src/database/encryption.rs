@@ -0,0 +1,212 @@
+//! At-rest encryption for the database file.
+//!
+//! An encrypted database never touches disk in plaintext: the decrypted image is loaded
+//! into an in-memory SQLite connection, and after every write the whole database is
+//! serialized, sealed with AES-256-GCM and atomically written to `ruleweaver.db.enc`.
+
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use rusqlite::serialize::OwnedData;
+use rusqlite::{Connection, DatabaseName};
+
+use crate::error::{AppError, Result};
+
+const FILE_MAGIC: &[u8] = b"RWENC\x01";
+const KEY_LEN: usize = 32;
+
+/// A 256-bit database encryption key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey([u8; KEY_LEN]);
+
+impl std::fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DatabaseKey(..)")
+    }
+}
+
+impl DatabaseKey {
+    pub fn generate() -> Result<Self> {
+        let mut bytes = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| encryption_error("failed to generate a random key"))?;
+        Ok(Self(bytes))
+    }
+
+    pub fn to_hex(&self) -> String {
+        crate::util::hex(&self.0)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        crate::util::unhex(hex.trim())
+            .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+            .map(Self)
+            .ok_or_else(|| encryption_error("stored key has an invalid format"))
+    }
+
+    fn aead_key(&self) -> Result<LessSafeKey> {
+        let unbound =
+            UnboundKey::new(&AES_256_GCM, &self.0).map_err(|_| encryption_error("invalid key"))?;
+        Ok(LessSafeKey::new(unbound))
+    }
+}
+
+fn encryption_error(message: &str) -> AppError {
+    AppError::Encryption {
+        message: message.to_string(),
+    }
+}
+
+/// Path of the encrypted image that replaces `db_path` once encryption is enabled.
+pub fn encrypted_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(".enc");
+    PathBuf::from(name)
+}
+
+/// Seals `plaintext` as `magic || nonce || ciphertext+tag`.
+pub fn encrypt(key: &DatabaseKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce_bytes)
+        .map_err(|_| encryption_error("failed to generate a nonce"))?;
+
+    let mut in_out = plaintext.to_vec();
+    key.aead_key()?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce_bytes),
+            Aad::from(FILE_MAGIC),
+            &mut in_out,
+        )
+        .map_err(|_| encryption_error("failed to encrypt database"))?;
+
+    let mut out = Vec::with_capacity(FILE_MAGIC.len() + NONCE_LEN + in_out.len());
+    out.extend_from_slice(FILE_MAGIC);
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&in_out);
+    Ok(out)
+}
+
+/// Opens data produced by [`encrypt`], failing on a wrong key or any tampering.
+pub fn decrypt(key: &DatabaseKey, data: &[u8]) -> Result<Vec<u8>> {
+    let body = data
+        .strip_prefix(FILE_MAGIC)
+        .ok_or_else(|| encryption_error("not a RuleWeaver encrypted database"))?;
+    if body.len() < NONCE_LEN {
+        return Err(encryption_error("encrypted database is truncated"));
+    }
+    let (nonce_bytes, ciphertext) = body.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+        .map_err(|_| encryption_error("encrypted database is truncated"))?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .aead_key()?
+        .open_in_place(nonce, Aad::from(FILE_MAGIC), &mut in_out)
+        .map_err(|_| encryption_error("wrong key or corrupted encrypted database"))?;
+    Ok(plaintext.to_vec())
+}
+
+/// The encrypted file backing an in-memory database connection.
+pub(crate) struct EncryptedFile {
+    db_path: PathBuf,
+    key: DatabaseKey,
+}
+
+impl EncryptedFile {
+    pub(crate) fn new(db_path: PathBuf, key: DatabaseKey) -> Self {
+        Self { db_path, key }
+    }
+
+    /// The plaintext database path this file stands in for.
+    pub(crate) fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    pub(crate) fn path(&self) -> PathBuf {
+        encrypted_path(&self.db_path)
+    }
+
+    /// Serializes and encrypts the database behind `conn`.
+    pub(crate) fn seal(&self, conn: &Connection) -> Result<Vec<u8>> {
+        let image = conn.serialize(DatabaseName::Main)?;
+        encrypt(&self.key, &image)
+    }
+
+    /// Decrypts `data` into a fresh in-memory connection.
+    pub(crate) fn open_image(&self, data: &[u8]) -> Result<Connection> {
+        let image = decrypt(&self.key, data)?;
+        let mut conn = Connection::open_in_memory()?;
+        conn.deserialize(DatabaseName::Main, sqlite_owned_copy(&image)?, false)?;
+        Ok(conn)
+    }
+
+    /// Encrypts `conn` and atomically replaces the encrypted file.
+    pub(crate) fn persist(&self, conn: &Connection) -> Result<()> {
+        let sealed = self.seal(conn)?;
+        crate::atomic_write::write_atomic(&self.path(), sealed)
+    }
+
+    /// Decrypts the encrypted file into a fresh in-memory connection.
+    pub(crate) fn load(&self) -> Result<Connection> {
+        self.open_image(&std::fs::read(self.path())?)
+    }
+}
+
+/// Whether `path` starts with the encrypted database header.
+pub fn is_encrypted_file(path: &Path) -> Result<bool> {
+    use std::io::Read;
+
+    let mut header = [0u8; FILE_MAGIC.len()];
+    let mut file = std::fs::File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(header == FILE_MAGIC),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Copies `bytes` into a buffer allocated by SQLite, as `deserialize` requires.
+fn sqlite_owned_copy(bytes: &[u8]) -> Result<OwnedData> {
+    // SAFETY: the buffer comes from sqlite3_malloc64 with room for `bytes.len()` bytes and
+    // is fully initialized before ownership passes to OwnedData, which frees it with
+    // sqlite3_free.
+    unsafe {
+        let ptr = rusqlite::ffi::sqlite3_malloc64(bytes.len().max(1) as u64) as *mut u8;
+        let ptr = NonNull::new(ptr).ok_or_else(|| encryption_error("out of memory"))?;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr.as_ptr(), bytes.len());
+        Ok(OwnedData::from_raw_nonnull(ptr, bytes.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip_and_tamper_detection() {
+        let key = DatabaseKey::generate().unwrap();
+        let sealed = encrypt(&key, b"secret rules").unwrap();
+        assert!(!sealed.windows(6).any(|w| w == b"secret"));
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"secret rules");
+
+        let other = DatabaseKey::generate().unwrap();
+        assert!(decrypt(&other, &sealed).is_err());
+
+        let mut tampered = sealed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert!(decrypt(&key, &tampered).is_err());
+        assert!(decrypt(&key, b"SQLite format 3\0").is_err());
+    }
+
+    #[test]
+    fn test_key_hex_roundtrip() {
+        let key = DatabaseKey::generate().unwrap();
+        assert_eq!(DatabaseKey::from_hex(&key.to_hex()).unwrap(), key);
+        assert!(DatabaseKey::from_hex("zz").is_err());
+    }
+}
@@ -1,19 +1,76 @@
-use std::path::PathBuf;
+mod encryption;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use tokio::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
 
 use chrono::{DateTime, TimeZone, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::backup::{Backup, Progress};
+use rusqlite::{params, Connection, DatabaseName, OpenFlags, OptionalExtension};
 use tauri::Manager;
 
+use crate::change_events::{self, ChangeEvent, ChangeKind};
+use crate::constants::database::{
+    DB_BUSY_TIMEOUT, DB_READ_POOL_SIZE, PRE_MIGRATION_BACKUP_RETENTION, SCHEMA_VERSION,
+};
+use crate::constants::limits::{AUDIT_LOG_DEFAULT_PAGE, AUDIT_LOG_MAX_PAGE};
 use crate::error::{AppError, Result};
 use crate::file_storage::StorageLocation;
+use crate::log_retention::LogTable;
+use encryption::EncryptedFile;
+pub use encryption::{decrypt, encrypt, encrypted_path, DatabaseKey};
+
 use crate::models::{
-    AdapterType, Command, CommandArgument, CreateCommandInput, CreateRuleInput, CreateSkillInput,
-    ExecutionLog, ReconcileOperation, ReconcileResultType, Rule, Scope, Skill, SyncHistoryEntry,
-    UpdateCommandInput, UpdateRuleInput, UpdateSkillInput,
+    AdapterType, ApprovalAuditEntry, ApprovalDecision, AuditAction, AuditActor, AuditActorKind,
+    AuditLogEntry, AuditLogPage, AuditLogQuery, AuditTarget, AuditTargetType, Collection,
+    CollectionItem, CollectionItemRef, CollectionItemType, Command, CommandArgument, CommandShell,
+    CreateCollectionInput, CreateCommandInput, CreateExternalMcpServerInput, CreateRuleInput,
+    CreateRuleTemplateInput, CreateSkillInput, CreateSnippetInput, CreateWebhookInput,
+    CreateWorkflowInput, EnvironmentSnapshot, ExecutionLog, ExternalMcpServer, JobKind, JobRecord,
+    JobStatus, McpPolicy, McpServerSpec, McpTransport, Metadata, OutputParser, ReconcileOperation,
+    ReconcileResultType, Rule, RuleFeedback, RuleFeedbackInput, RuleFeedbackSummary,
+    RuleFeedbackVerdict, RuleFileStat, RuleStats, RuleTemplate, Scope, SecretInfo, Skill,
+    SkillVersion, SkillVersionContent, Snippet, SyncHistoryEntry, SyncReport, UpdateCommandInput,
+    UpdateExternalMcpServerInput, UpdateRuleInput, UpdateRuleTemplateInput, UpdateSkillInput,
+    UpdateSnippetInput, UpdateWebhookInput, UpdateWorkflowInput, Webhook, Workflow,
 };
 
+fn row_exists(conn: &Connection, table: &str, id: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table),
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+/// Publishes the change an import made, if it wrote the row rather than skipping it.
+fn publish_import(artifact: AuditTargetType, id: &str, existed: bool, written: bool) {
+    if written {
+        let change = if existed {
+            ChangeKind::Updated
+        } else {
+            ChangeKind::Created
+        };
+        change_events::publish(ChangeEvent::new(artifact, change, id));
+    }
+}
+
+/// A vector as little-endian `f32`s, as rule embeddings are stored.
+fn encode_embedding(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
 fn parse_timestamp_or_now(timestamp: i64) -> DateTime<Utc> {
     chrono::Utc
         .timestamp_opt(timestamp, 0)
@@ -21,7 +78,27 @@ fn parse_timestamp_or_now(timestamp: i64) -> DateTime<Utc> {
         .unwrap_or_else(chrono::Utc::now)
 }
 
-pub struct Database(Mutex<Connection>);
+/// Handle to the RuleWeaver SQLite database.
+///
+/// File-backed databases run in WAL mode with a single writer connection and a small
+/// pool of read-only connections, so long scans and imports don't block the quick reads
+/// the tray and MCP server depend on. Every rusqlite call runs on the blocking thread
+/// pool via `spawn_blocking`. In-memory databases have no readers and route everything
+/// through the writer.
+///
+/// When at-rest encryption is enabled the database lives in an in-memory writer
+/// connection backed by an encrypted image on disk (see [`encryption`]); reads then go
+/// through the writer as well.
+pub struct Database {
+    writer: Arc<Mutex<Connection>>,
+    readers: Vec<Arc<Mutex<Connection>>>,
+    next_reader: AtomicUsize,
+    encryption: Arc<Mutex<Option<Arc<EncryptedFile>>>>,
+    /// Writes waiting on the writer lock; the last of a burst persists the encrypted image.
+    queued_writes: Arc<AtomicUsize>,
+    /// Set when the writer has changes the encrypted image does not have yet.
+    unsaved: Arc<AtomicBool>,
+}
 
 impl std::fmt::Debug for Database {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -42,6 +119,38 @@ pub struct ExecutionLogInput<'a> {
     pub adapter_context: Option<&'a str>,
     pub is_redacted: bool,
     pub attempt_number: u8,
+    pub structured_output: Option<&'a serde_json::Value>,
+    pub environment: Option<&'a EnvironmentSnapshot>,
+}
+
+const EXECUTION_LOG_COLUMNS: &str = "id, command_id, command_name, arguments, stdout, stderr, exit_code, duration_ms, executed_at, triggered_by, failure_class, adapter_context, is_redacted, attempt_number, artifacts_dir, structured_output, environment_snapshot";
+
+/// Maps a row selected with [`EXECUTION_LOG_COLUMNS`].
+fn execution_log_from_row(row: &rusqlite::Row) -> rusqlite::Result<ExecutionLog> {
+    let timestamp: i64 = row.get(8)?;
+    Ok(ExecutionLog {
+        id: row.get(0)?,
+        command_id: row.get(1)?,
+        command_name: row.get(2)?,
+        arguments: row.get(3)?,
+        stdout: row.get(4)?,
+        stderr: row.get(5)?,
+        exit_code: row.get(6)?,
+        duration_ms: row.get::<_, i64>(7)? as u64,
+        executed_at: parse_timestamp_or_now(timestamp),
+        triggered_by: row.get(9)?,
+        failure_class: row.get(10)?,
+        adapter_context: row.get(11)?,
+        is_redacted: row.get::<_, i32>(12)? != 0,
+        attempt_number: row.get::<_, i32>(13)? as u8,
+        artifacts_dir: row.get(14)?,
+        structured_output: row
+            .get::<_, Option<String>>(15)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        environment: row
+            .get::<_, Option<String>>(16)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+    })
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -59,29 +168,80 @@ pub struct ReconciliationLogEntry {
     pub error_message: Option<String>,
 }
 
+/// A file's hash as of its last reconciliation scan, valid while its size and mtime match.
+/// `content_hash` is `None` for files RuleWeaver does not manage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanCacheEntry {
+    pub file_path: String,
+    pub file_size: u64,
+    pub modified_ns: i64,
+    pub content_hash: Option<String>,
+}
+
+/// A row of `rule_file_index`: where a rule's file lives and what it hashed to when indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleFileIndexEntry {
+    pub rule_id: String,
+    pub file_path: String,
+    pub content_hash: Option<String>,
+    pub last_modified: Option<i64>,
+}
+
 impl Database {
+    fn from_connections(writer: Connection, readers: Vec<Connection>) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(writer)),
+            readers: readers
+                .into_iter()
+                .map(|conn| Arc::new(Mutex::new(conn)))
+                .collect(),
+            next_reader: AtomicUsize::new(0),
+            encryption: Arc::new(Mutex::new(None)),
+            queued_writes: Arc::new(AtomicUsize::new(0)),
+            unsaved: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
     async fn new_with_db_path(db_path: PathBuf) -> Result<Self> {
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
+        if encrypted_path(&db_path).exists() {
+            let key = run_blocking(crate::keychain::load_database_key).await?;
+            return Self::open_encrypted(db_path, key).await;
+        }
+
         // Connection::open is blocking, so we wrap it in spawn_blocking
-        let conn = tokio::task::spawn_blocking(move || -> Result<Connection> {
-            let mut conn = Connection::open(&db_path)?;
-            run_migrations(&mut conn)?;
-            Ok(conn)
+        let (writer, readers) = run_blocking(move || open_file_connections(&db_path)).await?;
+
+        Ok(Self::from_connections(writer, readers))
+    }
+
+    /// Opens the encrypted image for `db_path` into memory. Placeholder readers are kept
+    /// so the pool can be restored if encryption is later disabled.
+    async fn open_encrypted(db_path: PathBuf, key: DatabaseKey) -> Result<Self> {
+        let file = Arc::new(EncryptedFile::new(db_path, key));
+        let file_for_open = Arc::clone(&file);
+        let (writer, readers) = run_blocking(move || {
+            let mut writer = file_for_open.load()?;
+            backup_before_migration(&writer, file_for_open.db_path(), Some(&file_for_open))?;
+            run_migrations(&mut writer)?;
+            file_for_open.persist(&writer)?;
+            let readers = (0..DB_READ_POOL_SIZE)
+                .map(|_| Connection::open_in_memory())
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok((writer, readers))
         })
-        .await
-        .map_err(|e| AppError::Database(rusqlite::Error::ToSqlConversionFailure(Box::new(e))))??;
+        .await?;
 
-        Ok(Self(Mutex::new(conn)))
+        let db = Self::from_connections(writer, readers);
+        *db.encryption.lock() = Some(file);
+        Ok(db)
     }
 
     pub async fn new(app_handle: &tauri::AppHandle) -> Result<Self> {
-        let app_data_dir = app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| AppError::Path(e.to_string()))?;
+        let app_data_dir = get_app_data_path(app_handle)?;
         let db_path = app_data_dir.join("ruleweaver.db");
         Self::new_with_db_path(db_path).await
     }
@@ -94,829 +254,1593 @@ impl Database {
 
     #[cfg(any(test, feature = "test-helpers"))]
     pub async fn new_in_memory() -> Result<Self> {
-        let conn = tokio::task::spawn_blocking(move || -> Result<Connection> {
+        let conn = run_blocking(move || -> Result<Connection> {
             let mut conn = Connection::open_in_memory()?;
             run_migrations(&mut conn)?;
             Ok(conn)
         })
-        .await
-        .map_err(|e| AppError::Database(rusqlite::Error::ToSqlConversionFailure(Box::new(e))))??;
+        .await?;
 
-        Ok(Self(Mutex::new(conn)))
+        Ok(Self::from_connections(conn, Vec::new()))
     }
 
-    /// Re-establishes the database connection and runs migrations.
+    /// Re-establishes the database connections and runs migrations.
     /// Useful for recovering from disk disconnections or handling external database modifications.
     #[allow(dead_code)]
     pub async fn reconnect(&self) -> Result<()> {
-        let db_path = {
-            let conn = self.0.lock().await;
-            let path: String = conn.query_row("PRAGMA database_list", [], |row| row.get(2))?;
-            PathBuf::from(path)
-        };
+        let encryption = self.encryption.lock().clone();
+        if let Some(file) = encryption {
+            let writer = run_blocking(move || file.load()).await?;
+            *self.writer.lock() = writer;
+            return Ok(());
+        }
 
-        let new_conn = tokio::task::spawn_blocking(move || -> Result<Connection> {
-            let mut conn = Connection::open(&db_path)?;
-            run_migrations(&mut conn)?;
-            Ok(conn)
+        let db_path = PathBuf::from(self.get_database_path().await?);
+
+        let (writer, readers) = run_blocking(move || open_file_connections(&db_path)).await?;
+
+        *self.writer.lock() = writer;
+        for (slot, conn) in self.readers.iter().zip(readers) {
+            *slot.lock() = conn;
+        }
+        Ok(())
+    }
+
+    /// Runs `f` in a transaction against the writer connection on the blocking thread pool,
+    /// rolling it back if `f` fails.
+    async fn write<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.run_write(true, f).await
+    }
+
+    /// Like [`Self::write`] but without the surrounding transaction, for work SQLite cannot
+    /// do inside one such as restoring a backup.
+    async fn write_outside_transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.run_write(false, f).await
+    }
+
+    /// With encryption enabled, the encrypted image is rewritten before the writer lock is
+    /// released, unless another write is already waiting for it; a burst of writes is then
+    /// sealed once by its last write instead of once per write.
+    async fn run_write<T, F>(&self, transactional: bool, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let writer = Arc::clone(&self.writer);
+        let encryption = Arc::clone(&self.encryption);
+        let queued = Arc::clone(&self.queued_writes);
+        let unsaved = Arc::clone(&self.unsaved);
+        run_blocking(move || {
+            queued.fetch_add(1, Ordering::SeqCst);
+            let mut conn = writer.lock();
+            queued.fetch_sub(1, Ordering::SeqCst);
+
+            let changes_before = conn.total_changes();
+            let result = if transactional {
+                in_transaction(&mut conn, f)
+            } else {
+                f(&mut conn)
+            };
+            if !transactional || conn.total_changes() != changes_before {
+                unsaved.store(true, Ordering::SeqCst);
+            }
+
+            let file = encryption.lock().clone();
+            if let Some(file) = file {
+                if queued.load(Ordering::SeqCst) == 0 && unsaved.swap(false, Ordering::SeqCst) {
+                    if let Err(e) = file.persist(&conn) {
+                        unsaved.store(true, Ordering::SeqCst);
+                        return Err(e);
+                    }
+                }
+            }
+            result
         })
         .await
-        .map_err(|e| AppError::Database(rusqlite::Error::ToSqlConversionFailure(Box::new(e))))??;
+    }
 
-        let mut guard = self.0.lock().await;
-        *guard = new_conn;
-        Ok(())
+    /// Runs `f` against a read connection on the blocking thread pool, preferring an idle
+    /// reader and falling back to round-robin when all are busy.
+    async fn read<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let reader = self.pick_reader();
+        let writer = Arc::clone(&self.writer);
+        let encryption = Arc::clone(&self.encryption);
+        run_blocking(move || {
+            let conn = reader.lock();
+            // Encryption may have closed the read pool since the reader was picked.
+            if !Arc::ptr_eq(&reader, &writer) && encryption.lock().is_some() {
+                drop(conn);
+                return f(&writer.lock());
+            }
+            f(&conn)
+        })
+        .await
     }
 
-    pub async fn get_all_rules(&self) -> Result<Vec<Rule>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at 
-             FROM rules 
-             ORDER BY updated_at DESC"
-        )?;
+    fn pick_reader(&self) -> Arc<Mutex<Connection>> {
+        if self.readers.is_empty() || self.is_encrypted() {
+            return Arc::clone(&self.writer);
+        }
+        if let Some(idle) = self.readers.iter().find(|r| !r.is_locked()) {
+            return Arc::clone(idle);
+        }
+        let index = self.next_reader.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        Arc::clone(&self.readers[index])
+    }
 
-        let rules = stmt
-            .query_map([], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let description: String = row.get(2)?;
-                let content: String = row.get(3)?;
-                let scope_str: String = row.get(4)?;
-                let target_paths_json: Option<String> = row.get(5)?;
-                let enabled_adapters_json: String = row.get(6)?;
-                let enabled: bool = row.get(7)?;
-                let created_at: i64 = row.get(8)?;
-                let updated_at: i64 = row.get(9)?;
-
-                let scope = Scope::from_str(&scope_str).map_err(|_| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        4,
-                        rusqlite::types::Type::Text,
-                        Box::new(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            format!("Invalid scope for rule {}: {}", id, scope_str),
-                        )),
-                    )
-                })?;
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.lock().is_some()
+    }
 
-                let target_paths: Option<Vec<String>> = match target_paths_json {
-                    Some(j) => Some(serde_json::from_str(&j).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            4,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?),
-                    None => None,
-                };
+    /// Encrypts the database at rest: writes the encrypted image, switches to an in-memory
+    /// connection backed by it and deletes the plaintext database files. Existing backups
+    /// are sealed with the same key and plaintext export snapshots are deleted.
+    pub async fn enable_encryption(&self, key: DatabaseKey) -> Result<()> {
+        if self.is_encrypted() {
+            return Err(AppError::InvalidInput {
+                message: "Database is already encrypted".to_string(),
+            });
+        }
+        let db_path = PathBuf::from(self.get_database_path().await?);
+        if db_path.as_os_str().is_empty() {
+            return Err(AppError::InvalidInput {
+                message: "Only file-backed databases can be encrypted".to_string(),
+            });
+        }
+
+        let snapshot_dir = crate::snapshot::snapshot_dir(self).await?;
+
+        let writer = Arc::clone(&self.writer);
+        let readers = self.readers.clone();
+        let encryption = Arc::clone(&self.encryption);
+        run_blocking(move || {
+            let mut conn = writer.lock();
+            // Reads go to the writer, which is held until this finishes, from here on; a
+            // read that already picked a pooled connection checks the marker again once it
+            // holds it.
+            let file = Arc::new(EncryptedFile::new(db_path.clone(), key));
+            *encryption.lock() = Some(Arc::clone(&file));
+            // Close the read pool and leave WAL mode so the serialized image is
+            // self-contained.
+            for reader in &readers {
+                *reader.lock() = Connection::open_in_memory()?;
+            }
+            let encrypted = (|| {
+                conn.pragma_update(None, "journal_mode", "DELETE")?;
+                file.persist(&conn)?;
+                // Loading the image back proves it decrypts before the plaintext goes away.
+                file.load()
+            })();
+
+            match encrypted {
+                Ok(in_memory) => {
+                    *conn = in_memory;
+                    remove_database_files(&db_path)?;
+                    seal_plaintext_backups(&file)?;
+                    if let Some(dir) = snapshot_dir {
+                        for snapshot in crate::snapshot::list_snapshots(&dir)? {
+                            std::fs::remove_file(&snapshot.path)?;
+                        }
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    *encryption.lock() = None;
+                    let (file_writer, file_readers) = open_file_connections(&db_path)?;
+                    *conn = file_writer;
+                    for (slot, reader) in readers.iter().zip(file_readers) {
+                        *slot.lock() = reader;
+                    }
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+
+    /// Writes the database back to its plaintext file, restores the read pool and deletes
+    /// the encrypted image.
+    pub async fn disable_encryption(&self) -> Result<()> {
+        let Some(file) = self.encryption.lock().clone() else {
+            return Err(AppError::InvalidInput {
+                message: "Database is not encrypted".to_string(),
+            });
+        };
+
+        let writer = Arc::clone(&self.writer);
+        let readers = self.readers.clone();
+        let encryption = Arc::clone(&self.encryption);
+        run_blocking(move || {
+            let mut conn = writer.lock();
+            let db_path = file.db_path().to_path_buf();
+            remove_database_files(&db_path)?;
+            conn.backup(DatabaseName::Main, &db_path, None)?;
+
+            let (file_writer, file_readers) = open_file_connections(&db_path)?;
+            *conn = file_writer;
+            for (slot, reader) in readers.iter().zip(file_readers) {
+                *slot.lock() = reader;
+            }
+            *encryption.lock() = None;
+            std::fs::remove_file(file.path())?;
+            Ok(())
+        })
+        .await
+    }
 
-                let enabled_adapters: Vec<AdapterType> =
-                    serde_json::from_str(&enabled_adapters_json).map_err(|e| {
+    pub async fn get_all_rules(&self) -> Result<Vec<Rule>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at, metadata
+                 FROM rules 
+                 ORDER BY updated_at DESC"
+            )?;
+
+            let rules = stmt
+                .query_map([], |row| {
+                    let id: String = row.get(0)?;
+                    let name: String = row.get(1)?;
+                    let description: String = row.get(2)?;
+                    let content: String = row.get(3)?;
+                    let scope_str: String = row.get(4)?;
+                    let target_paths_json: Option<String> = row.get(5)?;
+                    let enabled_adapters_json: String = row.get(6)?;
+                    let enabled: bool = row.get(7)?;
+                    let created_at: i64 = row.get(8)?;
+                    let updated_at: i64 = row.get(9)?;
+                    let metadata_json: String = row.get(10)?;
+
+                    let scope = Scope::from_str(&scope_str).map_err(|_| {
                         rusqlite::Error::FromSqlConversionFailure(
-                            5,
+                            4,
                             rusqlite::types::Type::Text,
-                            Box::new(e),
+                            Box::new(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                format!("Invalid scope for rule {}: {}", id, scope_str),
+                            )),
                         )
                     })?;
 
-                Ok(Rule {
-                    id,
-                    name,
-                    description,
-                    content,
-                    scope,
-                    target_paths,
-                    enabled_adapters,
-                    enabled,
-                    created_at: parse_timestamp_or_now(created_at),
-                    updated_at: parse_timestamp_or_now(updated_at),
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+                    let target_paths: Option<Vec<String>> = match target_paths_json {
+                        Some(j) => Some(serde_json::from_str(&j).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                4,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?),
+                        None => None,
+                    };
 
-        Ok(rules)
+                    let enabled_adapters: Vec<AdapterType> =
+                        serde_json::from_str(&enabled_adapters_json).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                5,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
+
+                    Ok(Rule {
+                        id,
+                        name,
+                        description,
+                        content,
+                        scope,
+                        target_paths,
+                        enabled_adapters,
+                        enabled,
+                        metadata: Metadata::from_json(&metadata_json),
+                        created_at: parse_timestamp_or_now(created_at),
+                        updated_at: parse_timestamp_or_now(updated_at),
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(rules)
+        })
+        .await
     }
 
     pub async fn get_rule_by_id(&self, id: &str) -> Result<Rule> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at 
-             FROM rules 
-             WHERE id = ?"
-        )?;
-
-        let rule = stmt
-            .query_row(params![id], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let description: String = row.get(2)?;
-                let content: String = row.get(3)?;
-                let scope_str: String = row.get(4)?;
-                let target_paths_json: Option<String> = row.get(5)?;
-                let enabled_adapters_json: String = row.get(6)?;
-                let enabled: bool = row.get(7)?;
-                let created_at: i64 = row.get(8)?;
-                let updated_at: i64 = row.get(9)?;
-
-                let scope = Scope::from_str(&scope_str).map_err(|_| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        4,
-                        rusqlite::types::Type::Text,
-                        Box::new(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
-                            format!("Invalid scope for rule {}: {}", id, scope_str),
-                        )),
-                    )
-                })?;
+        let id = id.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at, metadata
+                 FROM rules 
+                 WHERE id = ?"
+            )?;
 
-                let target_paths: Option<Vec<String>> = match target_paths_json {
-                    Some(j) => Some(serde_json::from_str(&j).map_err(|e| {
+            let rule = stmt
+                .query_row(params![id], |row| {
+                    let id: String = row.get(0)?;
+                    let name: String = row.get(1)?;
+                    let description: String = row.get(2)?;
+                    let content: String = row.get(3)?;
+                    let scope_str: String = row.get(4)?;
+                    let target_paths_json: Option<String> = row.get(5)?;
+                    let enabled_adapters_json: String = row.get(6)?;
+                    let enabled: bool = row.get(7)?;
+                    let created_at: i64 = row.get(8)?;
+                    let updated_at: i64 = row.get(9)?;
+                    let metadata_json: String = row.get(10)?;
+
+                    let scope = Scope::from_str(&scope_str).map_err(|_| {
                         rusqlite::Error::FromSqlConversionFailure(
                             4,
                             rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?),
-                    None => None,
-                };
-                let enabled_adapters: Vec<AdapterType> =
-                    serde_json::from_str(&enabled_adapters_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            5,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
+                            Box::new(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                format!("Invalid scope for rule {}: {}", id, scope_str),
+                            )),
                         )
                     })?;
 
-                Ok(Rule {
-                    id,
-                    name,
-                    description,
-                    content,
-                    scope,
-                    target_paths,
-                    enabled_adapters,
-                    enabled,
-                    created_at: parse_timestamp_or_now(created_at),
-                    updated_at: parse_timestamp_or_now(updated_at),
+                    let target_paths: Option<Vec<String>> = match target_paths_json {
+                        Some(j) => Some(serde_json::from_str(&j).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                4,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?),
+                        None => None,
+                    };
+                    let enabled_adapters: Vec<AdapterType> =
+                        serde_json::from_str(&enabled_adapters_json).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                5,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
+
+                    Ok(Rule {
+                        id,
+                        name,
+                        description,
+                        content,
+                        scope,
+                        target_paths,
+                        enabled_adapters,
+                        enabled,
+                        metadata: Metadata::from_json(&metadata_json),
+                        created_at: parse_timestamp_or_now(created_at),
+                        updated_at: parse_timestamp_or_now(updated_at),
+                    })
                 })
-            })
-            .map_err(|e| match e {
-                rusqlite::Error::QueryReturnedNoRows => {
-                    AppError::RuleNotFound { id: id.to_string() }
-                }
-                _ => AppError::Database(e),
-            })?;
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => {
+                        AppError::RuleNotFound { id: id.to_string() }
+                    }
+                    _ => AppError::Database(e),
+                })?;
 
-        Ok(rule)
+            Ok(rule)
+        })
+        .await
     }
 
     pub async fn create_rule(&self, input: CreateRuleInput) -> Result<Rule> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
-        let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-
-        let target_paths_json = input
-            .target_paths
-            .as_ref()
-            .map(|p| serde_json::to_string(p).unwrap_or_default());
-
-        let enabled_adapters_json = serde_json::to_string(&input.enabled_adapters)?;
-
-        conn.execute(
-            "INSERT INTO rules (id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                id,
-                input.name,
-                input.description,
-                input.content,
-                input.scope.as_str(),
-                target_paths_json,
-                enabled_adapters_json,
-                input.enabled,
-                now,
-                now
-            ],
-        )?;
+        let id = self
+            .write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+            let target_paths_json = input
+                .target_paths
+                .as_ref()
+                .map(|p| serde_json::to_string(p).unwrap_or_default());
+
+            let enabled_adapters_json = serde_json::to_string(&input.enabled_adapters)?;
+
+            conn.execute(
+                "INSERT INTO rules (id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at, metadata)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    id,
+                    input.name,
+                    input.description,
+                    input.content,
+                    input.scope.as_str(),
+                    target_paths_json,
+                    enabled_adapters_json,
+                    input.enabled,
+                    now,
+                    now,
+                    input.metadata.to_json()
+                ],
+            )?;
 
-        drop(conn);
-        self.get_rule_by_id(&id).await
+                Ok(id)
+            })
+            .await?;
+
+        let created = self.get_rule_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Create,
+            AuditTarget::new(AuditTargetType::Rule, &created.id, &created.name),
+            format!("Created rule '{}'", created.name),
+        )
+        .await;
+        Ok(created)
     }
 
     pub async fn update_rule(&self, id: &str, input: UpdateRuleInput) -> Result<Rule> {
         let existing = self.get_rule_by_id(id).await?;
-        let conn = self.0.lock().await;
-
-        let name = input.name.unwrap_or(existing.name);
-        let description = input.description.unwrap_or(existing.description);
-        let content = input.content.unwrap_or(existing.content);
-        let scope = input.scope.unwrap_or(existing.scope);
-        let target_paths = input.target_paths.or(existing.target_paths);
-        let enabled_adapters = input.enabled_adapters.unwrap_or(existing.enabled_adapters);
-        let enabled = input.enabled.unwrap_or(existing.enabled);
-        let now = chrono::Utc::now().timestamp();
-
-        let target_paths_json = target_paths
-            .as_ref()
-            .map(|p| serde_json::to_string(p).unwrap_or_default());
-
-        let enabled_adapters_json = serde_json::to_string(&enabled_adapters)?;
-
-        conn.execute(
-            "UPDATE rules SET name = ?, description = ?, content = ?, scope = ?, target_paths = ?, enabled_adapters = ?, enabled = ?, updated_at = ?
-             WHERE id = ?",
-            params![
-                name,
-                description,
-                content,
-                scope.as_str(),
-                target_paths_json,
-                enabled_adapters_json,
-                enabled,
-                now,
-                id
-            ],
-        )?;
+        let previous = existing.clone();
+        let rule = apply_rule_update(existing, input);
+        let id = self
+            .write(move |conn| {
+                write_rule(conn, &rule, chrono::Utc::now().timestamp())?;
+                Ok(rule.id)
+            })
+            .await?;
+
+        let updated = self.get_rule_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Update,
+            AuditTarget::new(AuditTargetType::Rule, &updated.id, &updated.name),
+            rule_update_summary(&previous, &updated),
+        )
+        .await;
+        Ok(updated)
+    }
 
-        drop(conn);
-        self.get_rule_by_id(id).await
+    /// Applies each update in one transaction: either every rule is updated or, when one
+    /// is missing or a write fails, none is.
+    pub async fn update_rules(&self, updates: Vec<(String, UpdateRuleInput)>) -> Result<Vec<Rule>> {
+        let mut previous = Vec::with_capacity(updates.len());
+        let mut rules = Vec::with_capacity(updates.len());
+        for (id, input) in updates {
+            let existing = self.get_rule_by_id(&id).await?;
+            previous.push(existing.clone());
+            rules.push(apply_rule_update(existing, input));
+        }
+        let ids: Vec<String> = rules.iter().map(|rule| rule.id.clone()).collect();
+        self.write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            let tx = conn.savepoint()?;
+            for rule in &rules {
+                write_rule(&tx, rule, now)?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?;
+
+        let mut updated = Vec::with_capacity(ids.len());
+        for (id, previous) in ids.iter().zip(&previous) {
+            let rule = self.get_rule_by_id(id).await?;
+            self.audit_change(
+                AuditAction::Update,
+                AuditTarget::new(AuditTargetType::Rule, &rule.id, &rule.name),
+                rule_update_summary(previous, &rule),
+            )
+            .await;
+            updated.push(rule);
+        }
+        Ok(updated)
     }
 
     pub async fn delete_rule(&self, id: &str) -> Result<()> {
-        let conn = self.0.lock().await;
-        conn.execute("DELETE FROM rules WHERE id = ?", params![id])?;
+        let existing = self.get_rule_by_id(id).await.ok();
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM rules WHERE id = ?", params![id])?;
+            conn.execute(
+                "DELETE FROM collection_items WHERE item_type = 'rule' AND item_id = ?",
+                params![id],
+            )?;
+            conn.execute("DELETE FROM rule_sync_stats WHERE rule_id = ?", params![id])?;
+            conn.execute("DELETE FROM rule_feedback WHERE rule_id = ?", params![id])?;
+            conn.execute("DELETE FROM rule_embeddings WHERE rule_id = ?", params![id])?;
+            Ok(())
+        })
+        .await?;
+
+        if let Some(existing) = existing {
+            self.audit_change(
+                AuditAction::Delete,
+                AuditTarget::new(AuditTargetType::Rule, &existing.id, &existing.name),
+                format!("Deleted rule '{}'", existing.name),
+            )
+            .await;
+        }
         Ok(())
     }
 
     pub async fn toggle_rule(&self, id: &str, enabled: bool) -> Result<Rule> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+                let now = chrono::Utc::now().timestamp();
 
-        conn.execute(
-            "UPDATE rules SET enabled = ?, updated_at = ? WHERE id = ?",
-            params![enabled, now, id],
-        )?;
+                conn.execute(
+                    "UPDATE rules SET enabled = ?, updated_at = ? WHERE id = ?",
+                    params![enabled, now, id],
+                )?;
 
-        drop(conn);
-        self.get_rule_by_id(id).await
+                Ok(id)
+            })
+            .await?;
+
+        let toggled = self.get_rule_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Toggle,
+            AuditTarget::new(AuditTargetType::Rule, &toggled.id, &toggled.name),
+            format!(
+                "{} rule '{}'",
+                if enabled { "Enabled" } else { "Disabled" },
+                toggled.name
+            ),
+        )
+        .await;
+        Ok(toggled)
     }
 
     pub async fn get_all_commands(&self) -> Result<Vec<Command>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, timeout_ms, max_retries, base_path
-             FROM commands
-             ORDER BY updated_at DESC",
-        )?;
-
-        let commands = stmt
-            .query_map([], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let description: String = row.get(2)?;
-                let script: String = row.get(3)?;
-                let arguments_json: String = row.get(4)?;
-                let expose_via_mcp: bool = row.get(5)?;
-                let is_placeholder: bool = row.get(6)?;
-                let generate_slash_commands: bool = row.get(7)?;
-                let slash_adapters_json: String = row.get(8)?;
-                let target_paths_json: String = row.get(9)?;
-                let created_at: i64 = row.get(10)?;
-                let updated_at: i64 = row.get(11)?;
-                let timeout_ms: Option<i64> = row.get(12)?;
-                let max_retries: Option<i32> = row.get(13)?;
-                let base_path: Option<String> = row.get(14)?;
-
-                let arguments: Vec<CommandArgument> = serde_json::from_str(&arguments_json)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            4,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
-
-                let slash_command_adapters: Vec<String> =
-                    serde_json::from_str(&slash_adapters_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            7,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, timeout_ms, max_retries, base_path, metadata, required_secrets, working_dir, shell, requires_approval, schedule, sandbox, artifact_globs, output_parser, accepts_stdin, slash_namespace, slash_frontmatter, slash_scope, mcp_policy
+                 FROM commands
+                 ORDER BY updated_at DESC",
+            )?;
 
-                let target_paths: Vec<String> =
-                    serde_json::from_str(&target_paths_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            8,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
+            let commands = stmt
+                .query_map([], |row| {
+                    let id: String = row.get(0)?;
+                    let name: String = row.get(1)?;
+                    let description: String = row.get(2)?;
+                    let script: String = row.get(3)?;
+                    let arguments_json: String = row.get(4)?;
+                    let expose_via_mcp: bool = row.get(5)?;
+                    let is_placeholder: bool = row.get(6)?;
+                    let generate_slash_commands: bool = row.get(7)?;
+                    let slash_adapters_json: String = row.get(8)?;
+                    let target_paths_json: String = row.get(9)?;
+                    let created_at: i64 = row.get(10)?;
+                    let updated_at: i64 = row.get(11)?;
+                    let timeout_ms: Option<i64> = row.get(12)?;
+                    let max_retries: Option<i32> = row.get(13)?;
+                    let base_path: Option<String> = row.get(14)?;
+                    let metadata_json: String = row.get(15)?;
+                    let required_secrets_json: String = row.get(16)?;
+                    let working_dir: Option<String> = row.get(17)?;
+                    let shell: Option<String> = row.get(18)?;
+                    let requires_approval: bool = row.get(19)?;
+                    let schedule: Option<String> = row.get(20)?;
+                    let sandbox_json: Option<String> = row.get(21)?;
+                    let artifact_globs_json: String = row.get(22)?;
+                    let output_parser_json: Option<String> = row.get(23)?;
+                    let accepts_stdin: bool = row.get(24)?;
+                    let slash_namespace: Option<String> = row.get(25)?;
+                    let slash_frontmatter_json: Option<String> = row.get(26)?;
+                    let slash_scope: String = row.get(27)?;
+                    let mcp_policy_json: Option<String> = row.get(28)?;
+
+                    let arguments: Vec<CommandArgument> = serde_json::from_str(&arguments_json)
+                        .map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                4,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
 
-                Ok(Command {
-                    id,
-                    name,
-                    description,
-                    script,
-                    arguments,
-                    expose_via_mcp,
-                    is_placeholder,
-                    generate_slash_commands,
-                    slash_command_adapters,
-                    target_paths,
-                    base_path,
-                    timeout_ms: timeout_ms.map(|t| t as u64),
-                    max_retries: max_retries.map(|r| r as u8),
-                    created_at: parse_timestamp_or_now(created_at),
-                    updated_at: parse_timestamp_or_now(updated_at),
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+                    let slash_command_adapters: Vec<String> =
+                        serde_json::from_str(&slash_adapters_json).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                7,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
 
-        Ok(commands)
+                    let target_paths: Vec<String> =
+                        serde_json::from_str(&target_paths_json).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                8,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
+
+                    Ok(Command {
+                        id,
+                        name,
+                        description,
+                        script,
+                        arguments,
+                        mcp_policy: mcp_policy_json
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_else(|| {
+                                McpPolicy::from_legacy(expose_via_mcp, requires_approval)
+                            }),
+                        is_placeholder,
+                        generate_slash_commands,
+                        slash_command_adapters,
+                        slash_namespace,
+                        slash_frontmatter: slash_frontmatter_json
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_default(),
+                        slash_scope: Scope::from_str(&slash_scope).unwrap_or_default(),
+                        target_paths,
+                        base_path,
+                        timeout_ms: timeout_ms.map(|t| t as u64),
+                        max_retries: max_retries.map(|r| r as u8),
+                        required_secrets: serde_json::from_str(&required_secrets_json)
+                            .unwrap_or_default(),
+                        working_dir,
+                        shell: shell.and_then(|s| CommandShell::from_str(&s).ok()),
+                        schedule,
+                        sandbox: sandbox_json.and_then(|json| serde_json::from_str(&json).ok()),
+                        artifact_globs: serde_json::from_str(&artifact_globs_json)
+                            .unwrap_or_default(),
+                        output_parser: output_parser_json
+                            .and_then(|json| serde_json::from_str(&json).ok()),
+                        accepts_stdin,
+                        metadata: Metadata::from_json(&metadata_json),
+                        created_at: parse_timestamp_or_now(created_at),
+                        updated_at: parse_timestamp_or_now(updated_at),
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(commands)
+        })
+        .await
     }
 
     pub async fn get_command_by_id(&self, id: &str) -> Result<Command> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, timeout_ms, max_retries, base_path
-             FROM commands
-             WHERE id = ?",
-        )?;
+        let id = id.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, timeout_ms, max_retries, base_path, metadata, required_secrets, working_dir, shell, requires_approval, schedule, sandbox, artifact_globs, output_parser, accepts_stdin, slash_namespace, slash_frontmatter, slash_scope, mcp_policy
+                 FROM commands
+                 WHERE id = ?",
+            )?;
 
-        let command = stmt
-            .query_row(params![id], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let description: String = row.get(2)?;
-                let script: String = row.get(3)?;
-                let arguments_json: String = row.get(4)?;
-                let expose_via_mcp: bool = row.get(5)?;
-                let is_placeholder: bool = row.get(6)?;
-                let generate_slash_commands: bool = row.get(7)?;
-                let slash_adapters_json: String = row.get(8)?;
-                let target_paths_json: String = row.get(9)?;
-                let created_at: i64 = row.get(10)?;
-                let updated_at: i64 = row.get(11)?;
-                let timeout_ms: Option<i64> = row.get(12)?;
-                let max_retries: Option<i32> = row.get(13)?;
-                let base_path: Option<String> = row.get(14)?;
-
-                let arguments: Vec<CommandArgument> = serde_json::from_str(&arguments_json)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            4,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
+            let command = stmt
+                .query_row(params![id], |row| {
+                    let id: String = row.get(0)?;
+                    let name: String = row.get(1)?;
+                    let description: String = row.get(2)?;
+                    let script: String = row.get(3)?;
+                    let arguments_json: String = row.get(4)?;
+                    let expose_via_mcp: bool = row.get(5)?;
+                    let is_placeholder: bool = row.get(6)?;
+                    let generate_slash_commands: bool = row.get(7)?;
+                    let slash_adapters_json: String = row.get(8)?;
+                    let target_paths_json: String = row.get(9)?;
+                    let created_at: i64 = row.get(10)?;
+                    let updated_at: i64 = row.get(11)?;
+                    let timeout_ms: Option<i64> = row.get(12)?;
+                    let max_retries: Option<i32> = row.get(13)?;
+                    let base_path: Option<String> = row.get(14)?;
+                    let metadata_json: String = row.get(15)?;
+                    let required_secrets_json: String = row.get(16)?;
+                    let working_dir: Option<String> = row.get(17)?;
+                    let shell: Option<String> = row.get(18)?;
+                    let requires_approval: bool = row.get(19)?;
+                    let schedule: Option<String> = row.get(20)?;
+                    let sandbox_json: Option<String> = row.get(21)?;
+                    let artifact_globs_json: String = row.get(22)?;
+                    let output_parser_json: Option<String> = row.get(23)?;
+                    let accepts_stdin: bool = row.get(24)?;
+                    let slash_namespace: Option<String> = row.get(25)?;
+                    let slash_frontmatter_json: Option<String> = row.get(26)?;
+                    let slash_scope: String = row.get(27)?;
+                    let mcp_policy_json: Option<String> = row.get(28)?;
+
+                    let arguments: Vec<CommandArgument> = serde_json::from_str(&arguments_json)
+                        .map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                4,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
 
-                let slash_command_adapters: Vec<String> =
-                    serde_json::from_str(&slash_adapters_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            7,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
+                    let slash_command_adapters: Vec<String> =
+                        serde_json::from_str(&slash_adapters_json).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                7,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
 
-                let target_paths: Vec<String> =
-                    serde_json::from_str(&target_paths_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            8,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
+                    let target_paths: Vec<String> =
+                        serde_json::from_str(&target_paths_json).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                8,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?;
+
+                    Ok(Command {
+                        id,
+                        name,
+                        description,
+                        script,
+                        arguments,
+                        mcp_policy: mcp_policy_json
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_else(|| {
+                                McpPolicy::from_legacy(expose_via_mcp, requires_approval)
+                            }),
+                        is_placeholder,
+                        generate_slash_commands,
+                        slash_command_adapters,
+                        slash_namespace,
+                        slash_frontmatter: slash_frontmatter_json
+                            .and_then(|json| serde_json::from_str(&json).ok())
+                            .unwrap_or_default(),
+                        slash_scope: Scope::from_str(&slash_scope).unwrap_or_default(),
+                        target_paths,
+                        base_path,
+                        timeout_ms: timeout_ms.map(|t| t as u64),
+                        max_retries: max_retries.map(|r| r as u8),
+                        required_secrets: serde_json::from_str(&required_secrets_json)
+                            .unwrap_or_default(),
+                        working_dir,
+                        shell: shell.and_then(|s| CommandShell::from_str(&s).ok()),
+                        schedule,
+                        sandbox: sandbox_json.and_then(|json| serde_json::from_str(&json).ok()),
+                        artifact_globs: serde_json::from_str(&artifact_globs_json)
+                            .unwrap_or_default(),
+                        output_parser: output_parser_json
+                            .and_then(|json| serde_json::from_str(&json).ok()),
+                        accepts_stdin,
+                        metadata: Metadata::from_json(&metadata_json),
+                        created_at: parse_timestamp_or_now(created_at),
+                        updated_at: parse_timestamp_or_now(updated_at),
+                    })
+                })
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => {
+                        AppError::CommandNotFound { id: id.to_string() }
+                    }
+                    _ => AppError::Database(e),
+                })?;
+
+            Ok(command)
+        })
+        .await
+    }
 
-                Ok(Command {
+    pub async fn create_command(&self, input: CreateCommandInput) -> Result<Command> {
+        let id = self
+            .write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            let arguments_json = serde_json::to_string(&input.arguments)?;
+            let slash_adapters_json = serde_json::to_string(&input.slash_command_adapters)?;
+            let target_paths_json = serde_json::to_string(&input.target_paths)?;
+            let required_secrets_json = serde_json::to_string(&input.required_secrets)?;
+            let sandbox_json = input
+                .sandbox
+                .filter(|s| !s.is_unrestricted())
+                .map(|s| serde_json::to_string(&s))
+                .transpose()?;
+            let artifact_globs_json = serde_json::to_string(&input.artifact_globs)?;
+            let output_parser_json = input
+                .output_parser
+                .filter(|p| *p != OutputParser::Text)
+                .map(|p| serde_json::to_string(&p))
+                .transpose()?;
+            let slash_frontmatter_json = (!input.slash_frontmatter.is_empty())
+                .then(|| serde_json::to_string(&input.slash_frontmatter))
+                .transpose()?;
+            let mcp_policy_json = serde_json::to_string(&input.mcp_policy)?;
+
+            conn.execute(
+                "INSERT INTO commands (id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, timeout_ms, max_retries, base_path, metadata, required_secrets, working_dir, shell, requires_approval, schedule, sandbox, artifact_globs, output_parser, accepts_stdin, slash_namespace, slash_frontmatter, slash_scope, mcp_policy)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
                     id,
+                    input.name,
+                    input.description,
+                    input.script,
+                    arguments_json,
+                    input.mcp_policy.is_exposed(),
+                    input.is_placeholder,
+                    input.generate_slash_commands,
+                    slash_adapters_json,
+                    target_paths_json,
+                    now,
+                    now,
+                    input.timeout_ms.map(|t| t as i64),
+                    input.max_retries.map(|r| r as i32),
+                    input.base_path,
+                    input.metadata.to_json(),
+                    required_secrets_json,
+                    input.working_dir.filter(|d| !d.trim().is_empty()),
+                    input.shell.map(|s| s.as_str()),
+                    input.mcp_policy.requires_approval(),
+                    input.schedule.filter(|s| !s.trim().is_empty()),
+                    sandbox_json,
+                    artifact_globs_json,
+                    output_parser_json,
+                    input.accepts_stdin,
+                    input.slash_namespace.filter(|n| !n.trim().is_empty()),
+                    slash_frontmatter_json,
+                    input.slash_scope.as_str(),
+                    mcp_policy_json
+                ],
+            )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        let created = self.get_command_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Create,
+            AuditTarget::new(AuditTargetType::Command, &created.id, &created.name),
+            format!("Created command '{}'", created.name),
+        )
+        .await;
+        Ok(created)
+    }
+
+    pub async fn update_command(&self, id: &str, input: UpdateCommandInput) -> Result<Command> {
+        let existing = self.get_command_by_id(id).await?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+
+            let name = input.name.unwrap_or(existing.name);
+            let description = input.description.unwrap_or(existing.description);
+            let script = input.script.unwrap_or(existing.script);
+            let arguments = input.arguments.unwrap_or(existing.arguments);
+            let mcp_policy = input.mcp_policy.unwrap_or(existing.mcp_policy);
+            let is_placeholder = input.is_placeholder.unwrap_or(existing.is_placeholder);
+            let generate_slash_commands = input
+                .generate_slash_commands
+                .unwrap_or(existing.generate_slash_commands);
+            let slash_command_adapters = input
+                .slash_command_adapters
+                .unwrap_or(existing.slash_command_adapters);
+            let target_paths = input.target_paths.unwrap_or(existing.target_paths);
+            let base_path = input.base_path.or(existing.base_path);
+            let timeout_ms = input.timeout_ms.or(existing.timeout_ms);
+            let max_retries = input.max_retries.or(existing.max_retries);
+            let metadata = input.metadata.unwrap_or(existing.metadata);
+            let required_secrets = input.required_secrets.unwrap_or(existing.required_secrets);
+            let working_dir = input
+                .working_dir
+                .or(existing.working_dir)
+                .filter(|d| !d.trim().is_empty());
+            let shell = input.shell.or(existing.shell);
+            let accepts_stdin = input.accepts_stdin.unwrap_or(existing.accepts_stdin);
+            let slash_namespace = input
+                .slash_namespace
+                .or(existing.slash_namespace)
+                .filter(|n| !n.trim().is_empty());
+            let schedule = input
+                .schedule
+                .or(existing.schedule)
+                .filter(|s| !s.trim().is_empty());
+            let sandbox_json = input
+                .sandbox
+                .or(existing.sandbox)
+                .filter(|s| !s.is_unrestricted())
+                .map(|s| serde_json::to_string(&s))
+                .transpose()?;
+            let now = chrono::Utc::now().timestamp();
+            let arguments_json = serde_json::to_string(&arguments)?;
+            let slash_adapters_json = serde_json::to_string(&slash_command_adapters)?;
+            let target_paths_json = serde_json::to_string(&target_paths)?;
+            let required_secrets_json = serde_json::to_string(&required_secrets)?;
+            let artifact_globs_json = serde_json::to_string(
+                &input.artifact_globs.unwrap_or(existing.artifact_globs),
+            )?;
+            let output_parser_json = input
+                .output_parser
+                .or(existing.output_parser)
+                .filter(|p| *p != OutputParser::Text)
+                .map(|p| serde_json::to_string(&p))
+                .transpose()?;
+            let slash_frontmatter = input
+                .slash_frontmatter
+                .unwrap_or(existing.slash_frontmatter);
+            let slash_scope = input.slash_scope.unwrap_or(existing.slash_scope);
+            let slash_frontmatter_json = (!slash_frontmatter.is_empty())
+                .then(|| serde_json::to_string(&slash_frontmatter))
+                .transpose()?;
+            let mcp_policy_json = serde_json::to_string(&mcp_policy)?;
+
+            conn.execute(
+                "UPDATE commands SET name = ?, description = ?, script = ?, arguments = ?, expose_via_mcp = ?, is_placeholder = ?, generate_slash_commands = ?, slash_command_adapters = ?, target_paths = ?, updated_at = ?, timeout_ms = ?, max_retries = ?, base_path = ?, metadata = ?, required_secrets = ?, working_dir = ?, shell = ?, requires_approval = ?, schedule = ?, sandbox = ?, artifact_globs = ?, output_parser = ?, accepts_stdin = ?, slash_namespace = ?, slash_frontmatter = ?, slash_scope = ?, mcp_policy = ?
+                 WHERE id = ?",
+                params![
                     name,
                     description,
                     script,
-                    arguments,
-                    expose_via_mcp,
+                    arguments_json,
+                    mcp_policy.is_exposed(),
                     is_placeholder,
                     generate_slash_commands,
-                    slash_command_adapters,
-                    target_paths,
+                    slash_adapters_json,
+                    target_paths_json,
+                    now,
+                    timeout_ms.map(|t| t as i64),
+                    max_retries.map(|r| r as i32),
                     base_path,
-                    timeout_ms: timeout_ms.map(|t| t as u64),
-                    max_retries: max_retries.map(|r| r as u8),
-                    created_at: parse_timestamp_or_now(created_at),
-                    updated_at: parse_timestamp_or_now(updated_at),
-                })
+                    metadata.to_json(),
+                    required_secrets_json,
+                    working_dir,
+                    shell.map(|s| s.as_str()),
+                    mcp_policy.requires_approval(),
+                    schedule,
+                    sandbox_json,
+                    artifact_globs_json,
+                    output_parser_json,
+                    accepts_stdin,
+                    slash_namespace,
+                    slash_frontmatter_json,
+                    slash_scope.as_str(),
+                    mcp_policy_json,
+                    id
+                ],
+            )?;
+
+                Ok(id)
             })
-            .map_err(|e| match e {
-                rusqlite::Error::QueryReturnedNoRows => {
-                    AppError::CommandNotFound { id: id.to_string() }
-                }
-                _ => AppError::Database(e),
-            })?;
-
-        Ok(command)
-    }
-
-    pub async fn create_command(&self, input: CreateCommandInput) -> Result<Command> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
-        let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-        let arguments_json = serde_json::to_string(&input.arguments)?;
-        let slash_adapters_json = serde_json::to_string(&input.slash_command_adapters)?;
-        let target_paths_json = serde_json::to_string(&input.target_paths)?;
-
-        conn.execute(
-            "INSERT INTO commands (id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, timeout_ms, max_retries, base_path)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                id,
-                input.name,
-                input.description,
-                input.script,
-                arguments_json,
-                input.expose_via_mcp,
-                input.is_placeholder,
-                input.generate_slash_commands,
-                slash_adapters_json,
-                target_paths_json,
-                now,
-                now,
-                input.timeout_ms.map(|t| t as i64),
-                input.max_retries.map(|r| r as i32),
-                input.base_path
-            ],
-        )?;
-
-        drop(conn);
-        self.get_command_by_id(&id).await
-    }
-
-    pub async fn update_command(&self, id: &str, input: UpdateCommandInput) -> Result<Command> {
-        let existing = self.get_command_by_id(id).await?;
-        let conn = self.0.lock().await;
-
-        let name = input.name.unwrap_or(existing.name);
-        let description = input.description.unwrap_or(existing.description);
-        let script = input.script.unwrap_or(existing.script);
-        let arguments = input.arguments.unwrap_or(existing.arguments);
-        let expose_via_mcp = input.expose_via_mcp.unwrap_or(existing.expose_via_mcp);
-        let is_placeholder = input.is_placeholder.unwrap_or(existing.is_placeholder);
-        let generate_slash_commands = input
-            .generate_slash_commands
-            .unwrap_or(existing.generate_slash_commands);
-        let slash_command_adapters = input
-            .slash_command_adapters
-            .unwrap_or(existing.slash_command_adapters);
-        let target_paths = input.target_paths.unwrap_or(existing.target_paths);
-        let base_path = input.base_path.or(existing.base_path);
-        let timeout_ms = input.timeout_ms.or(existing.timeout_ms);
-        let max_retries = input.max_retries.or(existing.max_retries);
-        let now = chrono::Utc::now().timestamp();
-        let arguments_json = serde_json::to_string(&arguments)?;
-        let slash_adapters_json = serde_json::to_string(&slash_command_adapters)?;
-        let target_paths_json = serde_json::to_string(&target_paths)?;
-
-        conn.execute(
-            "UPDATE commands SET name = ?, description = ?, script = ?, arguments = ?, expose_via_mcp = ?, is_placeholder = ?, generate_slash_commands = ?, slash_command_adapters = ?, target_paths = ?, updated_at = ?, timeout_ms = ?, max_retries = ?, base_path = ?
-             WHERE id = ?",
-            params![
-                name,
-                description,
-                script,
-                arguments_json,
-                expose_via_mcp,
-                is_placeholder,
-                generate_slash_commands,
-                slash_adapters_json,
-                target_paths_json,
-                now,
-                timeout_ms.map(|t| t as i64),
-                max_retries.map(|r| r as i32),
-                base_path,
-                id
-            ],
-        )?;
-
-        drop(conn);
-        self.get_command_by_id(id).await
+            .await?;
+
+        let updated = self.get_command_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Update,
+            AuditTarget::new(AuditTargetType::Command, &updated.id, &updated.name),
+            format!("Updated command '{}'", updated.name),
+        )
+        .await;
+        Ok(updated)
     }
 
     pub async fn delete_command(&self, id: &str) -> Result<()> {
-        let conn = self.0.lock().await;
-        conn.execute("DELETE FROM commands WHERE id = ?", params![id])?;
+        let existing = self.get_command_by_id(id).await.ok();
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM commands WHERE id = ?", params![id])?;
+            conn.execute(
+                "DELETE FROM collection_items WHERE item_type = 'command' AND item_id = ?",
+                params![id],
+            )?;
+            Ok(())
+        })
+        .await?;
+
+        if let Some(existing) = existing {
+            self.audit_change(
+                AuditAction::Delete,
+                AuditTarget::new(AuditTargetType::Command, &existing.id, &existing.name),
+                format!("Deleted command '{}'", existing.name),
+            )
+            .await;
+        }
         Ok(())
     }
 
     pub async fn get_all_skills(&self) -> Result<Vec<Skill>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, instructions, input_schema, enabled, created_at, updated_at, directory_path, entry_point, scope, target_adapters, target_paths, base_path
-             FROM skills
-             ORDER BY updated_at DESC",
-        )?;
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, instructions, input_schema, enabled, created_at, updated_at, directory_path, entry_point, scope, target_adapters, target_paths, base_path, metadata, bundle_files, allowed_tools, license, version, version_pins
+                 FROM skills
+                 ORDER BY updated_at DESC",
+            )?;
 
-        let skills = stmt
-            .query_map([], |row| {
-                Ok(Skill {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    instructions: row.get(3)?,
-                    input_schema: {
-                        let raw: String = row.get(4)?;
-                        serde_json::from_str(&raw).map_err(|e| {
+            let skills = stmt
+                .query_map([], |row| {
+                    Ok(Skill {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        description: row.get(2)?,
+                        instructions: row.get(3)?,
+                        input_schema: {
+                            let raw: String = row.get(4)?;
+                            serde_json::from_str(&raw).map_err(|e| {
+                                rusqlite::Error::FromSqlConversionFailure(
+                                    4,
+                                    rusqlite::types::Type::Text,
+                                    Box::new(e),
+                                )
+                            })?
+                        },
+                        enabled: row.get(5)?,
+                        created_at: parse_timestamp_or_now(row.get(6)?),
+                        updated_at: parse_timestamp_or_now(row.get(7)?),
+                        directory_path: row.get(8)?,
+                        entry_point: row.get(9)?,
+                        scope: Scope::from_str(&row.get::<_, String>(10)?).map_err(|_| {
                             rusqlite::Error::FromSqlConversionFailure(
-                                4,
+                                10,
                                 rusqlite::types::Type::Text,
-                                Box::new(e),
+                                Box::new(std::io::Error::new(
+                                    std::io::ErrorKind::InvalidData,
+                                    "Invalid skill scope",
+                                )),
                             )
-                        })?
-                    },
-                    enabled: row.get(5)?,
-                    created_at: parse_timestamp_or_now(row.get(6)?),
-                    updated_at: parse_timestamp_or_now(row.get(7)?),
-                    directory_path: row.get(8)?,
-                    entry_point: row.get(9)?,
-                    scope: Scope::from_str(&row.get::<_, String>(10)?).map_err(|_| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            10,
-                            rusqlite::types::Type::Text,
-                            Box::new(std::io::Error::new(
-                                std::io::ErrorKind::InvalidData,
-                                "Invalid skill scope",
-                            )),
-                        )
-                    })?,
-                    target_adapters: {
-                        let raw: String = row.get(11)?;
-                        serde_json::from_str(&raw).unwrap_or_else(|e| {
-                            log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
-                            Vec::new()
-                        })
-                    },
-                    target_paths: {
-                        let raw: String = row.get(12)?;
-                        serde_json::from_str(&raw).unwrap_or_else(|e| {
-                            log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
-                            Vec::new()
-                        })
-                    },
-                    base_path: row.get(13)?,
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
-
-        Ok(skills)
+                        })?,
+                        target_adapters: {
+                            let raw: String = row.get(11)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        target_paths: {
+                            let raw: String = row.get(12)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        base_path: row.get(13)?,
+                        metadata: Metadata::from_json(&row.get::<_, String>(14)?),
+                        bundle_files: {
+                            let raw: String = row.get(15)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        allowed_tools: {
+                            let raw: String = row.get(16)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        license: row.get(17)?,
+                        version: row.get(18)?,
+                        version_pins: {
+                            let raw: String = row.get(19)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(skills)
+        })
+        .await
     }
 
     pub async fn get_skill_by_id(&self, id: &str) -> Result<Skill> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, instructions, input_schema, enabled, created_at, updated_at, directory_path, entry_point, scope, target_adapters, target_paths, base_path
-             FROM skills WHERE id = ?",
-        )?;
+        let id = id.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, instructions, input_schema, enabled, created_at, updated_at, directory_path, entry_point, scope, target_adapters, target_paths, base_path, metadata, bundle_files, allowed_tools, license, version, version_pins
+                 FROM skills WHERE id = ?",
+            )?;
 
-        let skill = stmt
-            .query_row(params![id], |row| {
-                Ok(Skill {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    instructions: row.get(3)?,
-                    input_schema: {
-                        let raw: String = row.get(4)?;
-                        serde_json::from_str(&raw).map_err(|e| {
+            let skill = stmt
+                .query_row(params![id], |row| {
+                    Ok(Skill {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        description: row.get(2)?,
+                        instructions: row.get(3)?,
+                        input_schema: {
+                            let raw: String = row.get(4)?;
+                            serde_json::from_str(&raw).map_err(|e| {
+                                rusqlite::Error::FromSqlConversionFailure(
+                                    4,
+                                    rusqlite::types::Type::Text,
+                                    Box::new(e),
+                                )
+                            })?
+                        },
+                        enabled: row.get(5)?,
+                        created_at: parse_timestamp_or_now(row.get(6)?),
+                        updated_at: parse_timestamp_or_now(row.get(7)?),
+                        directory_path: row.get(8)?,
+                        entry_point: row.get(9)?,
+                        scope: Scope::from_str(&row.get::<_, String>(10)?).map_err(|_| {
                             rusqlite::Error::FromSqlConversionFailure(
-                                4,
+                                10,
                                 rusqlite::types::Type::Text,
-                                Box::new(e),
+                                Box::new(std::io::Error::new(
+                                    std::io::ErrorKind::InvalidData,
+                                    format!("Invalid scope for skill: {}", id),
+                                )),
                             )
-                        })?
-                    },
-                    enabled: row.get(5)?,
-                    created_at: parse_timestamp_or_now(row.get(6)?),
-                    updated_at: parse_timestamp_or_now(row.get(7)?),
-                    directory_path: row.get(8)?,
-                    entry_point: row.get(9)?,
-                    scope: Scope::from_str(&row.get::<_, String>(10)?).map_err(|_| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            10,
-                            rusqlite::types::Type::Text,
-                            Box::new(std::io::Error::new(
-                                std::io::ErrorKind::InvalidData,
-                                format!("Invalid scope for skill: {}", id),
-                            )),
-                        )
-                    })?,
-                    target_adapters: {
-                        let raw: String = row.get(11)?;
-                        serde_json::from_str(&raw).unwrap_or_else(|e| {
-                            log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
-                            Vec::new()
-                        })
-                    },
-                    target_paths: {
-                        let raw: String = row.get(12)?;
-                        serde_json::from_str(&raw).unwrap_or_else(|e| {
-                            log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
-                            Vec::new()
-                        })
-                    },
-                    base_path: row.get(13)?,
+                        })?,
+                        target_adapters: {
+                            let raw: String = row.get(11)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        target_paths: {
+                            let raw: String = row.get(12)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        base_path: row.get(13)?,
+                        metadata: Metadata::from_json(&row.get::<_, String>(14)?),
+                        bundle_files: {
+                            let raw: String = row.get(15)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        allowed_tools: {
+                            let raw: String = row.get(16)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                        license: row.get(17)?,
+                        version: row.get(18)?,
+                        version_pins: {
+                            let raw: String = row.get(19)?;
+                            serde_json::from_str(&raw).unwrap_or_else(|e| {
+                                log::warn!("Failed to parse skill JSON: {}. Falling back to empty.", e);
+                                Vec::new()
+                            })
+                        },
+                    })
                 })
-            })
-            .map_err(|e| match e {
-                rusqlite::Error::QueryReturnedNoRows => {
-                    AppError::SkillNotFound { id: id.to_string() }
-                }
-                _ => AppError::Database(e),
-            })?;
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => {
+                        AppError::SkillNotFound { id: id.to_string() }
+                    }
+                    _ => AppError::Database(e),
+                })?;
 
-        Ok(skill)
+            Ok(skill)
+        })
+        .await
     }
 
     pub async fn create_skill(&self, input: CreateSkillInput) -> Result<Skill> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
-        let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-        let input_schema_json = serde_json::to_string(&input.input_schema)?;
-        let target_adapters_json = serde_json::to_string(&input.target_adapters)?;
-        let target_paths_json = serde_json::to_string(&input.target_paths)?;
-
-        conn.execute(
-            "INSERT INTO skills (id, name, description, instructions, input_schema, enabled, directory_path, entry_point, scope, target_adapters, target_paths, created_at, updated_at, base_path)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                &id,
-                &input.name,
-                &input.description,
-                &input.instructions,
-                &input_schema_json,
-                &input.enabled,
-                &input.directory_path,
-                &input.entry_point,
-                &input.scope.as_str(),
-                &target_adapters_json,
-                &target_paths_json,
-                &now,
-                &now,
-                &input.base_path
-            ],
-        )?;
+        let id = self
+            .write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            let input_schema_json = serde_json::to_string(&input.input_schema)?;
+            let target_adapters_json = serde_json::to_string(&input.target_adapters)?;
+            let target_paths_json = serde_json::to_string(&input.target_paths)?;
+            let bundle_files_json = serde_json::to_string(&input.bundle_files)?;
+            let allowed_tools_json = serde_json::to_string(&input.allowed_tools)?;
+            let version = match &input.version {
+                Some(v) => crate::models::parse_skill_version(v)?.to_string(),
+                None => crate::models::default_skill_version(),
+            };
+            let snapshot = SkillVersionContent {
+                name: input.name.clone(),
+                description: input.description.clone(),
+                instructions: input.instructions.clone(),
+                input_schema: input.input_schema.clone(),
+                entry_point: input.entry_point.clone(),
+                allowed_tools: input.allowed_tools.clone(),
+                license: input.license.clone(),
+            };
+
+            let tx = conn.savepoint()?;
+            tx.execute(
+                "INSERT INTO skills (id, name, description, instructions, input_schema, enabled, directory_path, entry_point, scope, target_adapters, target_paths, created_at, updated_at, base_path, metadata, bundle_files, allowed_tools, license, version)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    &id,
+                    &input.name,
+                    &input.description,
+                    &input.instructions,
+                    &input_schema_json,
+                    &input.enabled,
+                    &input.directory_path,
+                    &input.entry_point,
+                    &input.scope.as_str(),
+                    &target_adapters_json,
+                    &target_paths_json,
+                    &now,
+                    &now,
+                    &input.base_path,
+                    &input.metadata.to_json(),
+                    &bundle_files_json,
+                    &allowed_tools_json,
+                    &input.license,
+                    &version
+                ],
+            )?;
+            insert_skill_version(&tx, &id, &version, "Created", &snapshot, now)?;
+            tx.commit()?;
 
-        drop(conn);
-        self.get_skill_by_id(&id).await
+                Ok(id)
+            })
+            .await?;
+
+        let created = self.get_skill_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Create,
+            AuditTarget::new(AuditTargetType::Skill, &created.id, &created.name),
+            format!("Created skill '{}'", created.name),
+        )
+        .await;
+        Ok(created)
     }
 
     pub async fn update_skill(&self, id: &str, input: UpdateSkillInput) -> Result<Skill> {
         let existing = self.get_skill_by_id(id).await?;
-        let conn = self.0.lock().await;
-
-        let name = input.name.unwrap_or(existing.name);
-        let description = input.description.unwrap_or(existing.description);
-        let instructions = input.instructions.unwrap_or(existing.instructions);
-        let input_schema = input.input_schema.unwrap_or(existing.input_schema);
-        let enabled = input.enabled.unwrap_or(existing.enabled);
-        let directory_path = input.directory_path.unwrap_or(existing.directory_path);
-        let entry_point = input.entry_point.unwrap_or(existing.entry_point);
-        let scope = input.scope.unwrap_or(existing.scope);
-        let target_adapters = input.target_adapters.unwrap_or(existing.target_adapters);
-        let target_paths = input.target_paths.unwrap_or(existing.target_paths);
-        let base_path = input.base_path.or(existing.base_path);
-        let now = chrono::Utc::now().timestamp();
-        let input_schema_json = serde_json::to_string(&input_schema)?;
-        let target_adapters_json = serde_json::to_string(&target_adapters)?;
-        let target_paths_json = serde_json::to_string(&target_paths)?;
-
-        conn.execute(
-            "UPDATE skills SET name = ?, description = ?, instructions = ?, input_schema = ?, enabled = ?, directory_path = ?, entry_point = ?, scope = ?, target_adapters = ?, target_paths = ?, updated_at = ?, base_path = ? WHERE id = ?",
-            params![
-                &name,
-                &description,
-                &instructions,
-                &input_schema_json,
-                &enabled,
-                &directory_path,
-                &entry_point,
-                &scope.as_str(),
-                &target_adapters_json,
-                &target_paths_json,
-                &now,
-                &base_path,
-                &id
-            ],
-        )?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+
+            let previous_content = SkillVersionContent::of(&existing);
+            let previous_version = existing.version.clone();
+            let name = input.name.unwrap_or(existing.name);
+            let description = input.description.unwrap_or(existing.description);
+            let instructions = input.instructions.unwrap_or(existing.instructions);
+            let input_schema = input.input_schema.unwrap_or(existing.input_schema);
+            let enabled = input.enabled.unwrap_or(existing.enabled);
+            let directory_path = input.directory_path.unwrap_or(existing.directory_path);
+            let entry_point = input.entry_point.unwrap_or(existing.entry_point);
+            let scope = input.scope.unwrap_or(existing.scope);
+            let target_adapters = input.target_adapters.unwrap_or(existing.target_adapters);
+            let target_paths = input.target_paths.unwrap_or(existing.target_paths);
+            let base_path = input.base_path.or(existing.base_path);
+            let metadata = input.metadata.unwrap_or(existing.metadata);
+            let bundle_files = input.bundle_files.unwrap_or(existing.bundle_files);
+            let allowed_tools = input.allowed_tools.unwrap_or(existing.allowed_tools);
+            let license = match input.license {
+                Some(license) => Some(license).filter(|l| !l.trim().is_empty()),
+                None => existing.license,
+            };
+            let version_pins = input.version_pins.unwrap_or(existing.version_pins);
+            let content = SkillVersionContent {
+                name: name.clone(),
+                description: description.clone(),
+                instructions: instructions.clone(),
+                input_schema: input_schema.clone(),
+                entry_point: entry_point.clone(),
+                allowed_tools: allowed_tools.clone(),
+                license: license.clone(),
+            };
+            let new_version = next_skill_version(
+                &previous_version,
+                input.version.as_deref(),
+                content != previous_content,
+            )?;
+            let version = new_version.clone().unwrap_or(previous_version);
+            let now = chrono::Utc::now().timestamp();
+            let input_schema_json = serde_json::to_string(&input_schema)?;
+            let target_adapters_json = serde_json::to_string(&target_adapters)?;
+            let target_paths_json = serde_json::to_string(&target_paths)?;
+            let bundle_files_json = serde_json::to_string(&bundle_files)?;
+            let allowed_tools_json = serde_json::to_string(&allowed_tools)?;
+            let version_pins_json = serde_json::to_string(&version_pins)?;
+
+            let tx = conn.savepoint()?;
+            tx.execute(
+                "UPDATE skills SET name = ?, description = ?, instructions = ?, input_schema = ?, enabled = ?, directory_path = ?, entry_point = ?, scope = ?, target_adapters = ?, target_paths = ?, updated_at = ?, base_path = ?, metadata = ?, bundle_files = ?, allowed_tools = ?, license = ?, version = ?, version_pins = ? WHERE id = ?",
+                params![
+                    &name,
+                    &description,
+                    &instructions,
+                    &input_schema_json,
+                    &enabled,
+                    &directory_path,
+                    &entry_point,
+                    &scope.as_str(),
+                    &target_adapters_json,
+                    &target_paths_json,
+                    &now,
+                    &base_path,
+                    &metadata.to_json(),
+                    &bundle_files_json,
+                    &allowed_tools_json,
+                    &license,
+                    &version,
+                    &version_pins_json,
+                    &id
+                ],
+            )?;
+            if let Some(version) = &new_version {
+                let changelog = input.changelog.as_deref().unwrap_or("").trim();
+                insert_skill_version(&tx, &id, version, changelog, &content, now)?;
+            }
+            tx.commit()?;
 
-        drop(conn);
-        self.get_skill_by_id(id).await
+                Ok(id)
+            })
+            .await?;
+
+        let updated = self.get_skill_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Update,
+            AuditTarget::new(AuditTargetType::Skill, &updated.id, &updated.name),
+            format!("Updated skill '{}'", updated.name),
+        )
+        .await;
+        Ok(updated)
     }
 
     pub async fn delete_skill(&self, id: &str) -> Result<()> {
-        let conn = self.0.lock().await;
-        conn.execute("DELETE FROM skills WHERE id = ?", params![id])?;
+        let existing = self.get_skill_by_id(id).await.ok();
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM skills WHERE id = ?", params![id])?;
+            conn.execute("DELETE FROM skill_versions WHERE skill_id = ?", params![id])?;
+            conn.execute(
+                "DELETE FROM collection_items WHERE item_type = 'skill' AND item_id = ?",
+                params![id],
+            )?;
+            Ok(())
+        })
+        .await?;
+
+        if let Some(existing) = existing {
+            self.audit_change(
+                AuditAction::Delete,
+                AuditTarget::new(AuditTargetType::Skill, &existing.id, &existing.name),
+                format!("Deleted skill '{}'", existing.name),
+            )
+            .await;
+        }
         Ok(())
     }
 
-    pub async fn get_mcp_data(&self) -> Result<(Vec<Command>, Vec<Skill>)> {
+    /// Recorded versions of a skill, newest first.
+    pub async fn get_skill_versions(&self, skill_id: &str) -> Result<Vec<SkillVersion>> {
+        let skill_id = skill_id.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT skill_id, version, changelog, snapshot, created_at
+                 FROM skill_versions WHERE skill_id = ?",
+            )?;
+            let mut versions = stmt
+                .query_map(params![skill_id], |row| {
+                    let raw: String = row.get(3)?;
+                    Ok(SkillVersion {
+                        skill_id: row.get(0)?,
+                        version: row.get(1)?,
+                        changelog: row.get(2)?,
+                        content: serde_json::from_str(&raw).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                3,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })?,
+                        created_at: parse_timestamp_or_now(row.get(4)?),
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            versions.sort_by(|a, b| {
+                let a = semver::Version::parse(&a.version).ok();
+                let b = semver::Version::parse(&b.version).ok();
+                b.cmp(&a)
+            });
+            Ok(versions)
+        })
+        .await
+    }
+
+    pub async fn get_skill_version(&self, skill_id: &str, version: &str) -> Result<SkillVersion> {
+        self.get_skill_versions(skill_id)
+            .await?
+            .into_iter()
+            .find(|v| v.version == version)
+            .ok_or_else(|| AppError::InvalidInput {
+                message: format!("Skill {} has no version {}", skill_id, version),
+            })
+    }
+
+    pub async fn get_mcp_data(&self) -> Result<(Vec<Command>, Vec<Skill>, Vec<Workflow>)> {
         let commands = self.get_all_commands().await?;
         let skills = self.get_all_skills().await?;
-        Ok((commands, skills))
+        let workflows = self.get_all_workflows().await?;
+        Ok((commands, skills, workflows))
     }
 
     pub async fn rule_exists_with_name(&self, name: &str) -> Result<bool> {
-        let conn = self.0.lock().await;
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM rules WHERE name = ? COLLATE NOCASE",
-            params![name],
-            |row| row.get(0),
-        )?;
-        Ok(count > 0)
+        let name = name.to_string();
+        self.read(move |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM rules WHERE name = ? COLLATE NOCASE",
+                params![name],
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        })
+        .await
     }
 
     pub async fn command_exists_with_name(&self, name: &str) -> Result<bool> {
-        let conn = self.0.lock().await;
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM commands WHERE name = ? COLLATE NOCASE",
-            params![name],
-            |row| row.get(0),
-        )?;
-        Ok(count > 0)
+        let name = name.to_string();
+        self.read(move |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM commands WHERE name = ? COLLATE NOCASE",
+                params![name],
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        })
+        .await
     }
 
     pub async fn skill_exists_with_name(&self, name: &str) -> Result<bool> {
-        let conn = self.0.lock().await;
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM skills WHERE name = ? COLLATE NOCASE",
-            params![name],
-            |row| row.get(0),
-        )?;
-        Ok(count > 0)
+        let name = name.to_string();
+        self.read(move |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM skills WHERE name = ? COLLATE NOCASE",
+                params![name],
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        })
+        .await
     }
 
-    pub async fn add_execution_log(&self, input: &ExecutionLogInput<'_>) -> Result<()> {
-        let conn = self.0.lock().await;
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp();
+    /// Records one execution attempt and returns the new entry's id.
+    pub async fn add_execution_log(&self, input: &ExecutionLogInput<'_>) -> Result<String> {
+        let command_id = input.command_id.to_string();
+        let command_name = input.command_name.to_string();
+        let arguments_json = input.arguments_json.to_string();
+        let stdout = input.stdout.to_string();
+        let stderr = input.stderr.to_string();
+        let exit_code = input.exit_code;
+        let duration_ms = input.duration_ms as i64;
+        let triggered_by = input.triggered_by.to_string();
+        let failure_class = input.failure_class.map(str::to_string);
+        let adapter_context = input.adapter_context.map(str::to_string);
+        let is_redacted = input.is_redacted as i32;
+        let attempt_number = input.attempt_number as i32;
+        let structured_output = input
+            .structured_output
+            .map(serde_json::to_string)
+            .transpose()?;
+        let environment = input.environment.map(serde_json::to_string).transpose()?;
+
+        self.write(move |conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = chrono::Utc::now().timestamp();
+
+            conn.execute(
+                "INSERT INTO execution_logs (id, command_id, command_name, arguments, stdout, stderr, exit_code, duration_ms, executed_at, triggered_by, failure_class, adapter_context, is_redacted, attempt_number, structured_output, environment_snapshot)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    id,
+                    command_id,
+                    command_name,
+                    arguments_json,
+                    stdout,
+                    stderr,
+                    exit_code,
+                    duration_ms,
+                    now,
+                    triggered_by,
+                    failure_class,
+                    adapter_context,
+                    is_redacted,
+                    attempt_number,
+                    structured_output,
+                    environment
+                ],
+            )?;
 
-        conn.execute(
-            "INSERT INTO execution_logs (id, command_id, command_name, arguments, stdout, stderr, exit_code, duration_ms, executed_at, triggered_by, failure_class, adapter_context, is_redacted, attempt_number)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                id,
-                input.command_id,
-                input.command_name,
-                input.arguments_json,
-                input.stdout,
-                input.stderr,
-                input.exit_code,
-                input.duration_ms as i64,
-                now,
-                input.triggered_by,
-                input.failure_class,
-                input.adapter_context,
-                input.is_redacted as i32,
-                input.attempt_number as i32
-            ],
-        )?;
+            Ok(id)
+        })
+        .await
+    }
 
-        Ok(())
+    pub async fn get_execution_log(&self, id: &str) -> Result<ExecutionLog> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                &format!(
+                    "SELECT {} FROM execution_logs WHERE id = ?",
+                    EXECUTION_LOG_COLUMNS
+                ),
+                params![id],
+                execution_log_from_row,
+            )
+            .optional()?
+            .ok_or(AppError::ExecutionLogNotFound { id })
+        })
+        .await
+    }
+
+    pub async fn set_execution_artifacts_dir(&self, log_id: &str, dir: &str) -> Result<()> {
+        let log_id = log_id.to_string();
+        let dir = dir.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "UPDATE execution_logs SET artifacts_dir = ? WHERE id = ?",
+                params![dir, log_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_execution_artifacts_dir(&self, log_id: &str) -> Result<Option<String>> {
+        let log_id = log_id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT artifacts_dir FROM execution_logs WHERE id = ?",
+                params![log_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or(AppError::ExecutionLogNotFound { id: log_id })
+        })
+        .await
+    }
+
+    /// Unlinks artifacts from entries older than `older_than` (unix seconds) and returns
+    /// their directories so the caller can delete them.
+    pub async fn take_expired_artifact_dirs(&self, older_than: i64) -> Result<Vec<String>> {
+        self.write(move |conn| {
+            let dirs = conn
+                .prepare(
+                    "SELECT artifacts_dir FROM execution_logs
+                     WHERE artifacts_dir IS NOT NULL AND executed_at < ?",
+                )?
+                .query_map(params![older_than], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<String>, _>>()?;
+            conn.execute(
+                "UPDATE execution_logs SET artifacts_dir = NULL
+                 WHERE artifacts_dir IS NOT NULL AND executed_at < ?",
+                params![older_than],
+            )?;
+            Ok(dirs)
+        })
+        .await
+    }
+
+    /// Ids of the execution log entries that still have artifacts.
+    pub async fn get_execution_ids_with_artifacts(&self) -> Result<HashSet<String>> {
+        self.read(move |conn| {
+            let ids = conn
+                .prepare("SELECT id FROM execution_logs WHERE artifacts_dir IS NOT NULL")?
+                .query_map([], |row| row.get(0))?
+                .collect::<std::result::Result<HashSet<String>, _>>()?;
+            Ok(ids)
+        })
+        .await
     }
 
     pub async fn get_execution_history(&self, limit: u32) -> Result<Vec<ExecutionLog>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, command_id, command_name, arguments, stdout, stderr, exit_code, duration_ms, executed_at, triggered_by, failure_class, adapter_context, is_redacted, attempt_number
-             FROM execution_logs
-             ORDER BY executed_at DESC
-             LIMIT ?",
-        )?;
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM execution_logs ORDER BY executed_at DESC LIMIT ?",
+                EXECUTION_LOG_COLUMNS
+            ))?;
 
-        let rows = stmt
-            .query_map(params![limit], |row| {
-                let timestamp: i64 = row.get(8)?;
-                Ok(ExecutionLog {
-                    id: row.get(0)?,
-                    command_id: row.get(1)?,
-                    command_name: row.get(2)?,
-                    arguments: row.get(3)?,
-                    stdout: row.get(4)?,
-                    stderr: row.get(5)?,
-                    exit_code: row.get(6)?,
-                    duration_ms: row.get::<_, i64>(7)? as u64,
-                    executed_at: parse_timestamp_or_now(timestamp),
-                    triggered_by: row.get(9)?,
-                    failure_class: row.get(10)?,
-                    adapter_context: row.get(11)?,
-                    is_redacted: row.get::<_, i32>(12)? != 0,
-                    attempt_number: row.get::<_, i32>(13)? as u8,
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+            let rows = stmt
+                .query_map(params![limit], execution_log_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(rows)
+            Ok(rows)
+        })
+        .await
     }
 
     pub async fn get_execution_history_filtered(
@@ -926,86 +1850,109 @@ impl Database {
         limit: u32,
         offset: u32,
     ) -> Result<Vec<ExecutionLog>> {
-        let conn = self.0.lock().await;
-
-        let (sql, params) = {
-            let mut where_clauses = Vec::new();
-            let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let command_id = command_id.map(str::to_string);
+        let failure_class = failure_class.map(str::to_string);
+        self.read(move |conn| {
+            let (sql, params) = {
+                let mut where_clauses = Vec::new();
+                let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+                if let Some(cid) = command_id {
+                    where_clauses.push("command_id = ?");
+                    params.push(Box::new(cid.to_string()));
+                }
 
-            if let Some(cid) = command_id {
-                where_clauses.push("command_id = ?");
-                params.push(Box::new(cid.to_string()));
-            }
+                if let Some(fc) = failure_class {
+                    where_clauses.push("failure_class = ?");
+                    params.push(Box::new(fc.to_string()));
+                }
 
-            if let Some(fc) = failure_class {
-                where_clauses.push("failure_class = ?");
-                params.push(Box::new(fc.to_string()));
-            }
+                let mut sql = format!("SELECT {} FROM execution_logs", EXECUTION_LOG_COLUMNS);
 
-            let mut sql = "SELECT id, command_id, command_name, arguments, stdout, stderr, exit_code, duration_ms, executed_at, triggered_by, failure_class, adapter_context, is_redacted, attempt_number FROM execution_logs".to_string();
+                if !where_clauses.is_empty() {
+                    sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
+                }
 
-            if !where_clauses.is_empty() {
-                sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
-            }
+                sql.push_str(" ORDER BY executed_at DESC LIMIT ? OFFSET ?");
+                params.push(Box::new(limit as i64));
+                params.push(Box::new(offset as i64));
+                (sql, params)
+            };
 
-            sql.push_str(" ORDER BY executed_at DESC LIMIT ? OFFSET ?");
-            params.push(Box::new(limit as i64));
-            params.push(Box::new(offset as i64));
-            (sql, params)
-        };
+            let params_refs: Vec<&dyn rusqlite::ToSql> =
+                params.iter().map(|p| p.as_ref()).collect();
+            let mut stmt = conn.prepare(&sql)?;
 
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let mut stmt = conn.prepare(&sql)?;
-
-        let rows = stmt
-            .query_map(params_refs.as_slice(), |row| {
-                let timestamp: i64 = row.get(8)?;
-                Ok(ExecutionLog {
-                    id: row.get(0)?,
-                    command_id: row.get(1)?,
-                    command_name: row.get(2)?,
-                    arguments: row.get(3)?,
-                    stdout: row.get(4)?,
-                    stderr: row.get(5)?,
-                    exit_code: row.get(6)?,
-                    duration_ms: row.get::<_, i64>(7)? as u64,
-                    executed_at: parse_timestamp_or_now(timestamp),
-                    triggered_by: row.get(9)?,
-                    failure_class: row.get(10)?,
-                    adapter_context: row.get(11)?,
-                    is_redacted: row.get::<_, i32>(12)? != 0,
-                    attempt_number: row.get::<_, i32>(13)? as u8,
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+            let rows = stmt
+                .query_map(params_refs.as_slice(), execution_log_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(rows)
+            Ok(rows)
+        })
+        .await
     }
 
     pub async fn get_file_hash(&self, file_path: &str) -> Result<Option<String>> {
-        let conn = self.0.lock().await;
-        let result: Option<String> = conn
-            .query_row(
-                "SELECT content_hash FROM sync_history WHERE file_path = ?",
-                params![file_path],
-                |row| row.get(0),
-            )
-            .optional()?;
-
-        Ok(result)
+        let file_path = file_path.to_string();
+        self.read(move |conn| {
+            let result: Option<String> = conn
+                .query_row(
+                    "SELECT content_hash FROM sync_history WHERE file_path = ?",
+                    params![file_path],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(result)
+        })
+        .await
     }
 
     pub async fn set_file_hash(&self, file_path: &str, hash: &str) -> Result<()> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
+        let file_path = file_path.to_string();
+        let hash = hash.to_string();
+        self.write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+
+            conn.execute(
+                "INSERT OR REPLACE INTO sync_history (file_path, content_hash, last_sync_at)
+                 VALUES (?, ?, ?)",
+                params![file_path, hash, now],
+            )?;
 
-        conn.execute(
-            "INSERT OR REPLACE INTO sync_history (file_path, content_hash, last_sync_at)
-             VALUES (?, ?, ?)",
-            params![file_path, hash, now],
-        )?;
+            Ok(())
+        })
+        .await
+    }
 
-        Ok(())
+    /// Every stored sync hash as `(file_path, content_hash)`.
+    pub async fn get_file_hashes(&self) -> Result<Vec<(String, String)>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare("SELECT file_path, content_hash FROM sync_history")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+    }
+
+    pub async fn remove_file_hashes(&self, file_paths: Vec<String>) -> Result<()> {
+        if file_paths.is_empty() {
+            return Ok(());
+        }
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            for path in &file_paths {
+                tx.execute(
+                    "DELETE FROM sync_history WHERE file_path = ?",
+                    params![path],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn add_sync_log(
@@ -1014,69 +1961,139 @@ impl Database {
         status: &str,
         triggered_by: &str,
     ) -> Result<()> {
-        let conn = self.0.lock().await;
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp();
-
-        conn.execute(
-            "INSERT INTO sync_logs (id, timestamp, files_written, status, triggered_by)
-             VALUES (?, ?, ?, ?, ?)",
-            params![id, now, files_written, status, triggered_by],
-        )?;
+        let status = status.to_string();
+        let triggered_by = triggered_by.to_string();
+        self.write(move |conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = chrono::Utc::now().timestamp();
+
+            conn.execute(
+                "INSERT INTO sync_logs (id, timestamp, files_written, status, triggered_by)
+                 VALUES (?, ?, ?, ?, ?)",
+                params![id, now, files_written, status, triggered_by],
+            )?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
-    pub async fn get_sync_history(&self, limit: u32) -> Result<Vec<SyncHistoryEntry>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, timestamp, files_written, status, triggered_by 
-             FROM sync_logs 
-             ORDER BY timestamp DESC 
-             LIMIT ?",
-        )?;
-
-        let entries = stmt
-            .query_map(params![limit], |row| {
-                let id: String = row.get(0)?;
-                let timestamp: i64 = row.get(1)?;
-                let files_written: u32 = row.get(2)?;
-                let status: String = row.get(3)?;
-                let triggered_by: String = row.get(4)?;
-
-                Ok(SyncHistoryEntry {
+    /// Records a sync run in the history together with its full report.
+    pub async fn add_sync_report(&self, report: &SyncReport) -> Result<()> {
+        let report_json = serde_json::to_string(report)?;
+        let id = report.id.clone();
+        let timestamp = report.timestamp.timestamp();
+        let files_written = report.adapters.iter().map(|a| a.files.len()).sum::<usize>() as u32;
+        let status = report.status.clone();
+        let triggered_by = report.triggered_by.clone();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO sync_logs (id, timestamp, files_written, status, triggered_by, report)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![
                     id,
-                    timestamp: parse_timestamp_or_now(timestamp),
+                    timestamp,
                     files_written,
                     status,
                     triggered_by,
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+                    report_json
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
 
-        Ok(entries)
+    /// Returns the report of a sync run, or `None` for unknown ids and runs recorded before
+    /// reports existed.
+    pub async fn get_sync_report(&self, id: &str) -> Result<Option<SyncReport>> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            let report: Option<String> = conn
+                .query_row(
+                    "SELECT report FROM sync_logs WHERE id = ?",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .flatten();
+            report
+                .map(|json| serde_json::from_str(&json).map_err(Into::into))
+                .transpose()
+        })
+        .await
     }
 
-    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
-        let conn = self.0.lock().await;
-        let result: Option<String> = conn
-            .query_row(
-                "SELECT value FROM settings WHERE key = ?",
-                params![key],
-                |row| row.get(0),
-            )
-            .optional()?;
+    pub async fn get_sync_history(&self, limit: u32) -> Result<Vec<SyncHistoryEntry>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, timestamp, files_written, status, triggered_by, report IS NOT NULL
+                 FROM sync_logs 
+                 ORDER BY timestamp DESC 
+                 LIMIT ?",
+            )?;
+
+            let entries = stmt
+                .query_map(params![limit], |row| {
+                    let id: String = row.get(0)?;
+                    let timestamp: i64 = row.get(1)?;
+                    let files_written: u32 = row.get(2)?;
+                    let status: String = row.get(3)?;
+                    let triggered_by: String = row.get(4)?;
+                    let has_report: bool = row.get(5)?;
+
+                    Ok(SyncHistoryEntry {
+                        id,
+                        timestamp: parse_timestamp_or_now(timestamp),
+                        files_written,
+                        status,
+                        triggered_by,
+                        has_report,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(entries)
+        })
+        .await
+    }
 
-        Ok(result)
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let key = key.to_string();
+        self.read(move |conn| {
+            let result: Option<String> = conn
+                .query_row(
+                    "SELECT value FROM settings WHERE key = ?",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(result)
+        })
+        .await
     }
 
     pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
-        let conn = self.0.lock().await;
-        conn.execute(
-            "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
-            params![key, value],
-        )?;
-        Ok(())
+        let key = key.to_string();
+        let value = value.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+                params![key, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn delete_setting(&self, key: &str) -> Result<()> {
+        let key = key.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM settings WHERE key = ?", params![key])?;
+            Ok(())
+        })
+        .await
     }
 
     pub async fn merge_setting_string_array_unique(
@@ -1084,55 +2101,99 @@ impl Database {
         key: &str,
         values: &[String],
     ) -> Result<()> {
-        let conn = self.0.lock().await;
-        let current: Option<String> = conn
-            .query_row(
-                "SELECT value FROM settings WHERE key = ?",
-                params![key],
-                |row| row.get(0),
-            )
-            .optional()?;
+        let key = key.to_string();
+        let values = values.to_vec();
+        self.write(move |conn| {
+            let current: Option<String> = conn
+                .query_row(
+                    "SELECT value FROM settings WHERE key = ?",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let mut merged: std::collections::HashSet<String> = match current {
+                Some(raw) => serde_json::from_str::<Vec<String>>(&raw)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect(),
+                None => std::collections::HashSet::new(),
+            };
+
+            for value in values {
+                merged.insert(value.clone());
+            }
 
-        let mut merged: std::collections::HashSet<String> = match current {
-            Some(raw) => serde_json::from_str::<Vec<String>>(&raw)
-                .unwrap_or_default()
-                .into_iter()
-                .collect(),
-            None => std::collections::HashSet::new(),
-        };
+            let encoded = serde_json::to_string(&merged.into_iter().collect::<Vec<_>>())?;
+            conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+                params![key, encoded],
+            )?;
 
-        for value in values {
-            merged.insert(value.clone());
-        }
+            Ok(())
+        })
+        .await
+    }
 
-        let encoded = serde_json::to_string(&merged.into_iter().collect::<Vec<_>>())?;
-        conn.execute(
-            "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
-            params![key, encoded],
-        )?;
+    /// Removes `values` from a setting holding a JSON string array, keeping the rest in order.
+    pub async fn remove_setting_string_array_values(
+        &self,
+        key: &str,
+        values: &[String],
+    ) -> Result<()> {
+        let key = key.to_string();
+        let values = values.to_vec();
+        self.write(move |conn| {
+            let current: Option<String> = conn
+                .query_row(
+                    "SELECT value FROM settings WHERE key = ?",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(raw) = current else {
+                return Ok(());
+            };
+
+            let mut remaining = serde_json::from_str::<Vec<String>>(&raw).unwrap_or_default();
+            remaining.retain(|value| !values.contains(value));
+            conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+                params![key, serde_json::to_string(&remaining)?],
+            )?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     pub async fn get_all_settings(&self) -> Result<std::collections::HashMap<String, String>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
-
-        let settings = stmt
-            .query_map([], |row| {
-                let key: String = row.get(0)?;
-                let value: String = row.get(1)?;
-                Ok((key, value))
-            })?
-            .collect::<std::result::Result<std::collections::HashMap<String, String>, _>>()?;
-
-        Ok(settings)
+        self.read(move |conn| {
+            let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+
+            let settings = stmt
+                .query_map([], |row| {
+                    let key: String = row.get(0)?;
+                    let value: String = row.get(1)?;
+                    Ok((key, value))
+                })?
+                .collect::<std::result::Result<std::collections::HashMap<String, String>, _>>()?;
+
+            Ok(settings)
+        })
+        .await
     }
 
     pub async fn get_database_path(&self) -> Result<String> {
-        let conn = self.0.lock().await;
-        let path: String = conn.query_row("PRAGMA database_list", [], |row| row.get(2))?;
-        Ok(path)
+        let encryption = self.encryption.lock().clone();
+        if let Some(file) = encryption {
+            return Ok(file.path().to_string_lossy().to_string());
+        }
+        self.read(move |conn| {
+            let path: String = conn.query_row("PRAGMA database_list", [], |row| row.get(2))?;
+            Ok(path)
+        })
+        .await
     }
 
     pub async fn update_rule_file_index(
@@ -1140,7 +2201,7 @@ impl Database {
         rule_id: &str,
         location: &StorageLocation,
     ) -> Result<()> {
-        let conn = self.0.lock().await;
+        let rule_id = rule_id.to_string();
         let file_path = match location {
             StorageLocation::Global => crate::file_storage::get_global_rules_dir()?
                 .to_string_lossy()
@@ -1148,74 +2209,216 @@ impl Database {
             StorageLocation::Local(path) => path.to_string_lossy().to_string(),
         };
 
-        conn.execute(
-            "INSERT OR REPLACE INTO rule_file_index (rule_id, file_path) VALUES (?, ?)",
-            params![rule_id, file_path],
-        )?;
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO rule_file_index (rule_id, file_path) VALUES (?, ?)",
+                params![rule_id, file_path],
+            )?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     pub async fn get_rule_file_path(&self, rule_id: &str) -> Result<Option<String>> {
-        let conn = self.0.lock().await;
-        let result: Option<String> = conn
-            .query_row(
-                "SELECT file_path FROM rule_file_index WHERE rule_id = ?",
-                params![rule_id],
-                |row| row.get(0),
-            )
-            .optional()?;
-
-        Ok(result)
+        let rule_id = rule_id.to_string();
+        self.read(move |conn| {
+            let result: Option<String> = conn
+                .query_row(
+                    "SELECT file_path FROM rule_file_index WHERE rule_id = ?",
+                    params![rule_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            Ok(result)
+        })
+        .await
     }
 
     pub async fn remove_rule_file_index(&self, rule_id: &str) -> Result<()> {
-        let conn = self.0.lock().await;
-        conn.execute(
-            "DELETE FROM rule_file_index WHERE rule_id = ?",
-            params![rule_id],
-        )?;
-        Ok(())
+        let rule_id = rule_id.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "DELETE FROM rule_file_index WHERE rule_id = ?",
+                params![rule_id],
+            )?;
+            Ok(())
+        })
+        .await
     }
 
-    pub async fn import_rule(&self, rule: Rule, mode: crate::models::ImportMode) -> Result<()> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
+    pub async fn get_scan_cache(&self) -> Result<Vec<ScanCacheEntry>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT file_path, file_size, modified_ns, content_hash FROM scan_cache",
+            )?;
+            let entries = stmt
+                .query_map([], |row| {
+                    Ok(ScanCacheEntry {
+                        file_path: row.get(0)?,
+                        file_size: row.get::<_, i64>(1)? as u64,
+                        modified_ns: row.get(2)?,
+                        content_hash: row.get(3)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+        .await
+    }
 
-        let target_paths_json = rule
-            .target_paths
-            .as_ref()
-            .map(|p| serde_json::to_string(p).unwrap_or_default());
+    /// Stores changed scan cache entries and drops the ones for `removed` paths.
+    pub async fn update_scan_cache(
+        &self,
+        changed: Vec<ScanCacheEntry>,
+        removed: Vec<String>,
+    ) -> Result<()> {
+        if changed.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            for entry in &changed {
+                tx.execute(
+                    "INSERT OR REPLACE INTO scan_cache (file_path, file_size, modified_ns, content_hash)
+                     VALUES (?, ?, ?, ?)",
+                    params![
+                        entry.file_path,
+                        entry.file_size as i64,
+                        entry.modified_ns,
+                        entry.content_hash
+                    ],
+                )?;
+            }
+            for path in &removed {
+                tx.execute("DELETE FROM scan_cache WHERE file_path = ?", params![path])?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
 
-        let enabled_adapters_json = serde_json::to_string(&rule.enabled_adapters)?;
+    pub async fn get_rule_file_index(&self) -> Result<Vec<RuleFileIndexEntry>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT rule_id, file_path, content_hash, last_modified FROM rule_file_index",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok(RuleFileIndexEntry {
+                        rule_id: row.get(0)?,
+                        file_path: row.get(1)?,
+                        content_hash: row.get(2)?,
+                        last_modified: row.get(3)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+    }
 
-        let sql = match mode {
-            crate::models::ImportMode::Overwrite => {
-                log::info!("Import: Overwriting rule {}", rule.id);
-                "INSERT OR REPLACE INTO rules (id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    /// Replaces the whole rule file index with `entries` in one transaction.
+    pub async fn replace_rule_file_index(&self, entries: Vec<RuleFileIndexEntry>) -> Result<()> {
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            tx.execute("DELETE FROM rule_file_index", [])?;
+            for entry in &entries {
+                tx.execute(
+                    "INSERT OR REPLACE INTO rule_file_index (rule_id, file_path, content_hash, last_modified)
+                     VALUES (?, ?, ?, ?)",
+                    params![
+                        entry.rule_id,
+                        entry.file_path,
+                        entry.content_hash,
+                        entry.last_modified
+                    ],
+                )?;
             }
-            crate::models::ImportMode::Skip => {
-                "INSERT OR IGNORE INTO rules (id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Drops rule file index entries stored at or below `root`. Returns how many were removed.
+    pub async fn remove_rule_file_index_under(&self, root: &Path) -> Result<usize> {
+        let root = root.to_path_buf();
+        self.write(move |conn| {
+            let rule_ids: Vec<String> = {
+                let mut stmt = conn.prepare("SELECT rule_id, file_path FROM rule_file_index")?;
+                let rows = stmt
+                    .query_map([], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                rows.into_iter()
+                    .filter(|(_, file_path)| Path::new(file_path).starts_with(&root))
+                    .map(|(rule_id, _)| rule_id)
+                    .collect()
+            };
+
+            let tx = conn.savepoint()?;
+            for rule_id in &rule_ids {
+                tx.execute(
+                    "DELETE FROM rule_file_index WHERE rule_id = ?",
+                    params![rule_id],
+                )?;
             }
-        };
+            tx.commit()?;
+            Ok(rule_ids.len())
+        })
+        .await
+    }
 
-        conn.execute(
-            sql,
-            params![
-                rule.id,
-                rule.name,
-                rule.description,
-                rule.content,
-                rule.scope.as_str(),
-                target_paths_json,
-                enabled_adapters_json,
-                rule.enabled,
-                rule.created_at.timestamp(),
-                now
-            ],
-        )?;
+    pub async fn import_rule(&self, rule: Rule, mode: crate::models::ImportMode) -> Result<()> {
+        let id = rule.id.clone();
+        let (existed, written) = self
+            .write(move |conn| {
+                let existed = row_exists(conn, "rules", &rule.id)?;
+                let now = chrono::Utc::now().timestamp();
+
+                let target_paths_json = rule
+                    .target_paths
+                    .as_ref()
+                    .map(|p| serde_json::to_string(p).unwrap_or_default());
+
+                let enabled_adapters_json = serde_json::to_string(&rule.enabled_adapters)?;
+
+                let sql = match mode {
+                    crate::models::ImportMode::Overwrite => {
+                        log::info!("Import: Overwriting rule {}", rule.id);
+                        "INSERT OR REPLACE INTO rules (id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at, metadata)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    }
+                    crate::models::ImportMode::Skip => {
+                        "INSERT OR IGNORE INTO rules (id, name, description, content, scope, target_paths, enabled_adapters, enabled, created_at, updated_at, metadata)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    }
+                };
+
+                let written = conn.execute(
+                    sql,
+                    params![
+                        rule.id,
+                        rule.name,
+                        rule.description,
+                        rule.content,
+                        rule.scope.as_str(),
+                        target_paths_json,
+                        enabled_adapters_json,
+                        rule.enabled,
+                        rule.created_at.timestamp(),
+                        now,
+                        rule.metadata.to_json()
+                    ],
+                )? > 0;
+                Ok((existed, written))
+            })
+            .await?;
+        publish_import(AuditTargetType::Rule, &id, existed, written);
         Ok(())
     }
 
@@ -1224,80 +2427,116 @@ impl Database {
         command: Command,
         mode: crate::models::ImportMode,
     ) -> Result<()> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
-        let arguments_json = serde_json::to_string(&command.arguments)?;
-        let slash_adapters_json = serde_json::to_string(&command.slash_command_adapters)?;
-        let target_paths_json = serde_json::to_string(&command.target_paths)?;
-
-        let sql = match mode {
-            crate::models::ImportMode::Overwrite => {
-                log::info!("Import: Overwriting command {}", command.id);
-                "INSERT OR REPLACE INTO commands (id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-            }
-            crate::models::ImportMode::Skip => {
-                "INSERT OR IGNORE INTO commands (id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-            }
-        };
+        let id = command.id.clone();
+        let (existed, written) = self
+            .write(move |conn| {
+                let existed = row_exists(conn, "commands", &command.id)?;
+                let now = chrono::Utc::now().timestamp();
+                let arguments_json = serde_json::to_string(&command.arguments)?;
+                let slash_adapters_json = serde_json::to_string(&command.slash_command_adapters)?;
+                let target_paths_json = serde_json::to_string(&command.target_paths)?;
+                let mcp_policy_json = serde_json::to_string(&command.mcp_policy)?;
+
+                let sql = match mode {
+                    crate::models::ImportMode::Overwrite => {
+                        log::info!("Import: Overwriting command {}", command.id);
+                        "INSERT OR REPLACE INTO commands (id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, metadata, slash_namespace, requires_approval, mcp_policy)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    }
+                    crate::models::ImportMode::Skip => {
+                        "INSERT OR IGNORE INTO commands (id, name, description, script, arguments, expose_via_mcp, is_placeholder, generate_slash_commands, slash_command_adapters, target_paths, created_at, updated_at, metadata, slash_namespace, requires_approval, mcp_policy)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    }
+                };
 
-        conn.execute(
-            sql,
-            params![
-                command.id,
-                command.name,
-                command.description,
-                command.script,
-                arguments_json,
-                command.expose_via_mcp,
-                command.is_placeholder,
-                command.generate_slash_commands,
-                slash_adapters_json,
-                target_paths_json,
-                command.created_at.timestamp(),
-                now
-            ],
-        )?;
+                let written = conn.execute(
+                    sql,
+                    params![
+                        command.id,
+                        command.name,
+                        command.description,
+                        command.script,
+                        arguments_json,
+                        command.mcp_policy.is_exposed(),
+                        command.is_placeholder,
+                        command.generate_slash_commands,
+                        slash_adapters_json,
+                        target_paths_json,
+                        command.created_at.timestamp(),
+                        now,
+                        command.metadata.to_json(),
+                        command.slash_namespace,
+                        command.mcp_policy.requires_approval(),
+                        mcp_policy_json
+                    ],
+                )? > 0;
+                Ok((existed, written))
+            })
+            .await?;
+        publish_import(AuditTargetType::Command, &id, existed, written);
         Ok(())
     }
 
     pub async fn import_skill(&self, skill: Skill, mode: crate::models::ImportMode) -> Result<()> {
-        let conn = self.0.lock().await;
-        let now = chrono::Utc::now().timestamp();
-        let input_schema_json = serde_json::to_string(&skill.input_schema)?;
-        let target_adapters_json = serde_json::to_string(&skill.target_adapters)?;
-        let target_paths_json = serde_json::to_string(&skill.target_paths)?;
-
-        let sql = match mode {
-            crate::models::ImportMode::Overwrite => {
-                log::info!("Import: Overwriting skill {}", skill.id);
-                "INSERT OR REPLACE INTO skills (id, name, description, instructions, input_schema, enabled, directory_path, entry_point, target_adapters, target_paths, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-            }
-            crate::models::ImportMode::Skip => {
-                "INSERT OR IGNORE INTO skills (id, name, description, instructions, input_schema, enabled, directory_path, entry_point, target_adapters, target_paths, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-            }
-        };
+        let id = skill.id.clone();
+        let (existed, written) = self
+            .write(move |conn| {
+                let existed = row_exists(conn, "skills", &skill.id)?;
+                let now = chrono::Utc::now().timestamp();
+                let input_schema_json = serde_json::to_string(&skill.input_schema)?;
+                let target_adapters_json = serde_json::to_string(&skill.target_adapters)?;
+                let target_paths_json = serde_json::to_string(&skill.target_paths)?;
+                let bundle_files_json = serde_json::to_string(&skill.bundle_files)?;
+                let allowed_tools_json = serde_json::to_string(&skill.allowed_tools)?;
+                let version_pins_json = serde_json::to_string(&skill.version_pins)?;
+
+                let sql = match mode {
+                    crate::models::ImportMode::Overwrite => {
+                        log::info!("Import: Overwriting skill {}", skill.id);
+                        "INSERT OR REPLACE INTO skills (id, name, description, instructions, input_schema, enabled, directory_path, entry_point, target_adapters, target_paths, created_at, updated_at, metadata, bundle_files, allowed_tools, license, version, version_pins)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    }
+                    crate::models::ImportMode::Skip => {
+                        "INSERT OR IGNORE INTO skills (id, name, description, instructions, input_schema, enabled, directory_path, entry_point, target_adapters, target_paths, created_at, updated_at, metadata, bundle_files, allowed_tools, license, version, version_pins)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                    }
+                };
 
-        conn.execute(
-            sql,
-            params![
-                skill.id,
-                skill.name,
-                skill.description,
-                skill.instructions,
-                input_schema_json,
-                skill.enabled,
-                skill.directory_path,
-                skill.entry_point,
-                target_adapters_json,
-                target_paths_json,
-                skill.created_at.timestamp(),
-                now
-            ],
-        )?;
+                let written = conn.execute(
+                    sql,
+                    params![
+                        skill.id,
+                        skill.name,
+                        skill.description,
+                        skill.instructions,
+                        input_schema_json,
+                        skill.enabled,
+                        skill.directory_path,
+                        skill.entry_point,
+                        target_adapters_json,
+                        target_paths_json,
+                        skill.created_at.timestamp(),
+                        now,
+                        skill.metadata.to_json(),
+                        bundle_files_json,
+                        allowed_tools_json,
+                        skill.license,
+                        skill.version,
+                        version_pins_json
+                    ],
+                )? > 0;
+                insert_skill_version(
+                    conn,
+                    &skill.id,
+                    &skill.version,
+                    "Imported",
+                    &SkillVersionContent::of(&skill),
+                    now,
+                )?;
+                Ok((existed, written))
+            })
+            .await?;
+        publish_import(AuditTargetType::Skill, &id, existed, written);
         Ok(())
     }
 
@@ -1317,9 +2556,144 @@ impl Database {
         for skill in config.skills {
             self.import_skill(skill, mode).await?;
         }
+
+        let depths = crate::models::nesting_depths(&config.collections);
+        let mut collections = config.collections;
+        collections.sort_by_key(|c| depths[&c.id]);
+        for collection in collections {
+            self.import_collection(collection, mode).await?;
+        }
+
+        for item in config.collection_items {
+            self.import_collection_item(item, mode).await?;
+        }
+
+        for (key, value) in config.settings {
+            if crate::models::is_portable_setting(&key) {
+                self.import_setting(key, value, mode).await?;
+            }
+        }
+
+        if let Some(state) = config.state {
+            self.import_state(state, mode).await?;
+        }
+        Ok(())
+    }
+
+    /// The sync and reconciliation bookkeeping an export carries when asked to.
+    pub async fn export_state(&self) -> Result<crate::models::ExportState> {
+        let sync_hashes = self.get_file_hashes().await?.into_iter().collect();
+        let rule_files = self
+            .get_rule_file_index()
+            .await?
+            .into_iter()
+            .map(|entry| crate::models::RuleFileState {
+                rule_id: entry.rule_id,
+                file_path: entry.file_path,
+                content_hash: entry.content_hash,
+                last_modified: entry.last_modified,
+            })
+            .collect();
+        let import_source_map = self
+            .get_setting(crate::rule_import::IMPORT_SOURCE_MAP_KEY)
+            .await?;
+        Ok(crate::models::ExportState {
+            sync_hashes,
+            rule_files,
+            import_source_map,
+        })
+    }
+
+    async fn import_state(
+        &self,
+        state: crate::models::ExportState,
+        mode: crate::models::ImportMode,
+    ) -> Result<()> {
+        let (hash_sql, index_sql) = match mode {
+            crate::models::ImportMode::Overwrite => (
+                "INSERT OR REPLACE INTO sync_history (file_path, content_hash, last_sync_at)
+                 VALUES (?, ?, ?)",
+                "INSERT OR REPLACE INTO rule_file_index (rule_id, file_path, content_hash, last_modified)
+                 VALUES (?, ?, ?, ?)",
+            ),
+            crate::models::ImportMode::Skip => (
+                "INSERT OR IGNORE INTO sync_history (file_path, content_hash, last_sync_at)
+                 VALUES (?, ?, ?)",
+                "INSERT OR IGNORE INTO rule_file_index (rule_id, file_path, content_hash, last_modified)
+                 VALUES (?, ?, ?, ?)",
+            ),
+        };
+        let (sync_hashes, rule_files) = (state.sync_hashes, state.rule_files);
+        self.write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            let tx = conn.savepoint()?;
+            for (path, hash) in &sync_hashes {
+                tx.execute(hash_sql, params![path, hash, now])?;
+            }
+            for file in &rule_files {
+                tx.execute(
+                    index_sql,
+                    params![
+                        file.rule_id,
+                        file.file_path,
+                        file.content_hash,
+                        file.last_modified
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?;
+
+        if let Some(map) = state.import_source_map {
+            self.import_setting(
+                crate::rule_import::IMPORT_SOURCE_MAP_KEY.to_string(),
+                map,
+                mode,
+            )
+            .await?;
+        }
         Ok(())
     }
 
+    /// Builds a full configuration export from the current database contents.
+    pub async fn export_configuration(&self) -> Result<crate::models::ExportConfiguration> {
+        let rules = self.get_all_rules().await?;
+        let commands = self.get_all_commands().await?;
+        let skills = self.get_all_skills().await?;
+        let collections = self.get_all_collections().await?;
+        let collection_items = self.get_all_collection_items().await?;
+        let settings = self.get_all_settings().await?;
+
+        Ok(
+            crate::models::ExportConfiguration::new(rules, commands, skills)
+                .with_collections(collections, collection_items)
+                .with_settings(settings),
+        )
+    }
+
+    async fn import_setting(
+        &self,
+        key: String,
+        value: String,
+        mode: crate::models::ImportMode,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            let sql = match mode {
+                crate::models::ImportMode::Overwrite => {
+                    "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)"
+                }
+                crate::models::ImportMode::Skip => {
+                    "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)"
+                }
+            };
+            conn.execute(sql, params![key, value])?;
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn get_storage_mode(&self) -> Result<String> {
         let mode = self.get_setting("storage_mode").await?;
         Ok(mode.unwrap_or_else(|| "sqlite".to_string()))
@@ -1340,566 +2714,4329 @@ impl Database {
         result: ReconcileResultType,
         error_message: Option<&str>,
     ) -> Result<()> {
-        let conn = self.0.lock().await;
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp();
-
-        conn.execute(
-            "INSERT INTO reconciliation_logs (id, timestamp, operation, artifact_type, adapter, scope, path, result, error_message)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            rusqlite::params![
-                id,
-                now,
-                operation.as_str(),
-                artifact_type,
-                adapter.map(|a| a.as_str()),
-                scope.map(|s| s.as_str()),
-                path,
-                result.as_str(),
-                error_message
-            ],
-        )?;
+        let artifact_type = artifact_type.map(str::to_string);
+        let path = path.to_string();
+        let error_message = error_message.map(str::to_string);
+        self.write(move |conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = chrono::Utc::now().timestamp();
+
+            conn.execute(
+                "INSERT INTO reconciliation_logs (id, timestamp, operation, artifact_type, adapter, scope, path, result, error_message)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    id,
+                    now,
+                    operation.as_str(),
+                    artifact_type,
+                    adapter.map(|a| a.as_str()),
+                    scope.map(|s| s.as_str()),
+                    path,
+                    result.as_str(),
+                    error_message
+                ],
+            )?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     pub async fn get_reconciliation_logs(&self, limit: i64) -> Result<Vec<ReconciliationLogEntry>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT id, timestamp, operation, artifact_type, adapter, scope, path, result, error_message
-             FROM reconciliation_logs
-             ORDER BY timestamp DESC
-             LIMIT ?",
-        )?;
-
-        let logs = stmt
-            .query_map(rusqlite::params![limit], |row| {
-                let op_str: String = row.get(2)?;
-                let operation =
-                    ReconcileOperation::from_str(&op_str).unwrap_or(ReconcileOperation::Check);
-
-                let adapter_str: Option<String> = row.get(4)?;
-                let adapter = adapter_str.and_then(|s| AdapterType::from_str(&s).ok());
-
-                let scope_str: Option<String> = row.get(5)?;
-                let scope = scope_str.and_then(|s| Scope::from_str(&s).ok());
-
-                let res_str: String = row.get(7)?;
-                let result =
-                    ReconcileResultType::from_str(&res_str).unwrap_or(ReconcileResultType::Failed);
-
-                Ok(ReconciliationLogEntry {
-                    id: row.get(0)?,
-                    timestamp: parse_timestamp_or_now(row.get(1)?),
-                    operation,
-                    artifact_type: row.get(3)?,
-                    adapter,
-                    scope,
-                    path: row.get(6)?,
-                    result,
-                    error_message: row.get(8)?,
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, timestamp, operation, artifact_type, adapter, scope, path, result, error_message
+                 FROM reconciliation_logs
+                 ORDER BY timestamp DESC
+                 LIMIT ?",
+            )?;
 
-        Ok(logs)
+            let logs = stmt
+                .query_map(rusqlite::params![limit], |row| {
+                    let op_str: String = row.get(2)?;
+                    let operation =
+                        ReconcileOperation::from_str(&op_str).unwrap_or(ReconcileOperation::Check);
+
+                    let adapter_str: Option<String> = row.get(4)?;
+                    let adapter = adapter_str.and_then(|s| AdapterType::from_str(&s).ok());
+
+                    let scope_str: Option<String> = row.get(5)?;
+                    let scope = scope_str.and_then(|s| Scope::from_str(&s).ok());
+
+                    let res_str: String = row.get(7)?;
+                    let result =
+                        ReconcileResultType::from_str(&res_str).unwrap_or(ReconcileResultType::Failed);
+
+                    Ok(ReconciliationLogEntry {
+                        id: row.get(0)?,
+                        timestamp: parse_timestamp_or_now(row.get(1)?),
+                        operation,
+                        artifact_type: row.get(3)?,
+                        adapter,
+                        scope,
+                        path: row.get(6)?,
+                        result,
+                        error_message: row.get(8)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(logs)
+        })
+        .await
     }
 
     pub async fn get_last_reconciliation_op_per_path(
         &self,
     ) -> Result<std::collections::HashMap<String, (String, DateTime<Utc>)>> {
-        let conn = self.0.lock().await;
-        let mut stmt = conn.prepare(
-            "SELECT path, operation, timestamp 
-             FROM reconciliation_logs 
-             WHERE id IN (SELECT MAX(id) FROM reconciliation_logs GROUP BY path)",
-        )?;
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT path, operation, timestamp 
+                 FROM reconciliation_logs 
+                 WHERE id IN (SELECT MAX(id) FROM reconciliation_logs GROUP BY path)",
+            )?;
 
-        let rows = stmt.query_map([], |row| {
-            let path: String = row.get(0)?;
-            let operation: String = row.get(1)?;
-            let timestamp: DateTime<Utc> = parse_timestamp_or_now(row.get(2)?);
-            Ok((path, operation, timestamp))
-        })?;
+            let rows = stmt.query_map([], |row| {
+                let path: String = row.get(0)?;
+                let operation: String = row.get(1)?;
+                let timestamp: DateTime<Utc> = parse_timestamp_or_now(row.get(2)?);
+                Ok((path, operation, timestamp))
+            })?;
 
-        let mut ops = std::collections::HashMap::new();
-        for (path, operation, timestamp) in rows.flatten() {
-            ops.insert(path, (operation, timestamp));
-        }
+            let mut ops = std::collections::HashMap::new();
+            for (path, operation, timestamp) in rows.flatten() {
+                ops.insert(path, (operation, timestamp));
+            }
 
-        Ok(ops)
+            Ok(ops)
+        })
+        .await
     }
 
     pub async fn clear_reconciliation_logs(&self) -> Result<()> {
-        let conn = self.0.lock().await;
-        conn.execute("DELETE FROM reconciliation_logs", [])?;
-        Ok(())
+        self.write(move |conn| {
+            conn.execute("DELETE FROM reconciliation_logs", [])?;
+            Ok(())
+        })
+        .await
     }
-}
 
-fn run_migrations(conn: &mut Connection) -> Result<()> {
-    let transaction = conn.transaction()?;
+    /// Deletes entries older than `older_than` (unix seconds) and then all but the newest
+    /// `max_rows`, returning how many rows were removed.
+    pub async fn prune_log_table(
+        &self,
+        table: LogTable,
+        max_rows: Option<u64>,
+        older_than: Option<i64>,
+    ) -> Result<u64> {
+        self.write(move |conn| {
+            let (name, ts) = (table.table_name(), table.timestamp_column());
+            let mut deleted = 0;
+            if let Some(cutoff) = older_than {
+                deleted += conn.execute(
+                    &format!("DELETE FROM {} WHERE {} < ?", name, ts),
+                    params![cutoff],
+                )?;
+            }
+            if let Some(max_rows) = max_rows {
+                deleted += conn.execute(
+                    &format!(
+                        "DELETE FROM {name} WHERE rowid NOT IN
+                         (SELECT rowid FROM {name} ORDER BY {ts} DESC, rowid DESC LIMIT ?)"
+                    ),
+                    params![max_rows as i64],
+                )?;
+            }
+            Ok(deleted as u64)
+        })
+        .await
+    }
 
-    let current_version: i32 = transaction
-        .query_row("PRAGMA user_version", [], |row| row.get(0))
-        .unwrap_or(0);
+    /// Deletes every entry in `table`, or only those older than `older_than` when given.
+    pub async fn purge_log_table(&self, table: LogTable, older_than: Option<i64>) -> Result<u64> {
+        self.write(move |conn| {
+            let deleted = match older_than {
+                Some(cutoff) => conn.execute(
+                    &format!(
+                        "DELETE FROM {} WHERE {} < ?",
+                        table.table_name(),
+                        table.timestamp_column()
+                    ),
+                    params![cutoff],
+                )?,
+                None => conn.execute(&format!("DELETE FROM {}", table.table_name()), [])?,
+            };
+            Ok(deleted as u64)
+        })
+        .await
+    }
 
-    if current_version < 1 {
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS rules (
-                id TEXT PRIMARY KEY NOT NULL,
-                name TEXT NOT NULL,
-                content TEXT NOT NULL,
-                scope TEXT NOT NULL,
-                target_paths TEXT,
-                enabled_adapters TEXT NOT NULL,
-                enabled INTEGER NOT NULL DEFAULT 1,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
-            )",
-            [],
-        )?;
+    /// Copies the live database to `dest` using SQLite's online backup API, so a
+    /// consistent snapshot is taken without closing the app.
+    pub async fn backup_to(&self, dest: PathBuf) -> Result<()> {
+        let encryption = self.encryption.lock().clone();
+        self.read(move |conn| {
+            match encryption {
+                // Backups of an encrypted database are sealed with the same key.
+                Some(file) => crate::atomic_write::write_atomic(&dest, file.seal(conn)?)?,
+                None => conn.backup(DatabaseName::Main, &dest, None)?,
+            }
+            Ok(())
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS sync_history (
-                file_path TEXT PRIMARY KEY NOT NULL,
-                content_hash TEXT NOT NULL,
-                last_sync_at INTEGER NOT NULL
-            )",
-            [],
-        )?;
+    /// Problems reported by SQLite's `quick_check`; empty when the database is intact.
+    pub async fn integrity_problems(&self) -> Result<Vec<String>> {
+        self.read(|conn| {
+            let mut stmt = conn.prepare("PRAGMA quick_check")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            let problems = rows
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .into_iter()
+                .filter(|line| line != "ok")
+                .collect();
+            Ok(problems)
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS sync_logs (
-                id TEXT PRIMARY KEY NOT NULL,
-                timestamp INTEGER NOT NULL,
-                files_written INTEGER NOT NULL,
-                status TEXT NOT NULL,
-                triggered_by TEXT NOT NULL
-            )",
-            [],
-        )?;
+    /// Replaces the live database contents with the snapshot at `src`. Migrations are
+    /// re-run afterwards so backups taken by older versions come up on the current schema.
+    pub async fn restore_from(&self, src: PathBuf) -> Result<()> {
+        let encryption = self.encryption.lock().clone();
+        self.write_outside_transaction(move |conn| {
+            if src.is_file() && encryption::is_encrypted_file(&src)? {
+                let file = encryption.ok_or_else(|| AppError::InvalidInput {
+                    message: "Encrypted backups can only be restored while database encryption is enabled"
+                        .to_string(),
+                })?;
+                let source = file.open_image(&std::fs::read(&src)?)?;
+                validate_backup_connection(&source, &src)?;
+                Backup::new(&source, conn)?.run_to_completion(
+                    100,
+                    std::time::Duration::ZERO,
+                    None,
+                )?;
+            } else {
+                validate_backup_file(&src)?;
+                conn.restore(DatabaseName::Main, &src, None::<fn(Progress)>)?;
+            }
+            run_migrations(conn)?;
+            Ok(())
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS settings (
-                key TEXT PRIMARY KEY NOT NULL,
-                value TEXT NOT NULL
-            )",
-            [],
-        )?;
+    /// Records which rules were just written to `file_path` by `adapter`, replacing the
+    /// previous entries for that file so rules removed from it stop counting.
+    pub async fn record_rule_sync(
+        &self,
+        adapter: AdapterType,
+        file_path: &str,
+        rule_bytes: Vec<(String, u64)>,
+    ) -> Result<()> {
+        let file_path = file_path.to_string();
+        self.write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            let tx = conn.savepoint()?;
+            tx.execute(
+                "DELETE FROM rule_sync_stats WHERE adapter = ? AND file_path = ?",
+                params![adapter.as_str(), file_path],
+            )?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR REPLACE INTO rule_sync_stats (rule_id, adapter, file_path, bytes, last_synced_at)
+                     VALUES (?, ?, ?, ?, ?)",
+                )?;
+                for (rule_id, bytes) in &rule_bytes {
+                    stmt.execute(params![rule_id, adapter.as_str(), file_path, *bytes as i64, now])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_rules_scope ON rules(scope)",
-            [],
-        )?;
+    /// Usage statistics for every rule, including rules that have never been synced.
+    pub async fn get_rule_stats(&self) -> Result<Vec<RuleStats>> {
+        self.read(move |conn| {
+            let mut files_by_rule: std::collections::HashMap<String, Vec<RuleFileStat>> =
+                std::collections::HashMap::new();
+            {
+                let mut stmt = conn.prepare(
+                    "SELECT rule_id, adapter, file_path, bytes, last_synced_at
+                     FROM rule_sync_stats
+                     ORDER BY last_synced_at DESC, file_path ASC",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    let rule_id: String = row.get(0)?;
+                    let adapter: String = row.get(1)?;
+                    let file_path: String = row.get(2)?;
+                    let bytes: i64 = row.get(3)?;
+                    let last_synced_at: i64 = row.get(4)?;
+                    Ok((rule_id, adapter, file_path, bytes, last_synced_at))
+                })?;
+                for row in rows {
+                    let (rule_id, adapter, file_path, bytes, last_synced_at) = row?;
+                    // Rows for adapters this build no longer knows about are ignored.
+                    let Ok(adapter) = AdapterType::from_str(&adapter) else {
+                        continue;
+                    };
+                    files_by_rule
+                        .entry(rule_id)
+                        .or_default()
+                        .push(RuleFileStat {
+                            adapter,
+                            file_path,
+                            bytes: bytes.max(0) as u64,
+                            last_synced_at: parse_timestamp_or_now(last_synced_at),
+                        });
+                }
+            }
+
+            let mut feedback_by_rule: std::collections::HashMap<String, RuleFeedbackSummary> =
+                std::collections::HashMap::new();
+            {
+                let mut stmt = conn.prepare(
+                    "SELECT rule_id, verdict, COUNT(*), MAX(created_at)
+                     FROM rule_feedback
+                     GROUP BY rule_id, verdict",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                })?;
+                for row in rows {
+                    let (rule_id, verdict, count, last_reported_at) = row?;
+                    let Ok(verdict) = RuleFeedbackVerdict::from_str(&verdict) else {
+                        continue;
+                    };
+                    let summary = feedback_by_rule.entry(rule_id).or_default();
+                    let count = count.max(0) as u32;
+                    match verdict {
+                        RuleFeedbackVerdict::Helpful => summary.helpful = count,
+                        RuleFeedbackVerdict::Ignored => summary.ignored = count,
+                        RuleFeedbackVerdict::Harmful => summary.harmful = count,
+                    }
+                    let last_reported_at = parse_timestamp_or_now(last_reported_at);
+                    if summary
+                        .last_reported_at
+                        .is_none_or(|at| at < last_reported_at)
+                    {
+                        summary.last_reported_at = Some(last_reported_at);
+                    }
+                }
+            }
+
+            let mut stmt = conn
+                .prepare("SELECT id, name, enabled FROM rules ORDER BY name COLLATE NOCASE ASC")?;
+            let rules = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, bool>(2)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(rules
+                .into_iter()
+                .map(|(rule_id, rule_name, enabled)| {
+                    let files = files_by_rule.remove(&rule_id).unwrap_or_default();
+                    let feedback = feedback_by_rule.remove(&rule_id).unwrap_or_default();
+                    let mut adapters: Vec<AdapterType> = Vec::new();
+                    for file in &files {
+                        if !adapters.contains(&file.adapter) {
+                            adapters.push(file.adapter);
+                        }
+                    }
+                    RuleStats {
+                        rule_id,
+                        rule_name,
+                        enabled,
+                        last_synced_at: files.iter().map(|f| f.last_synced_at).max(),
+                        adapters,
+                        total_bytes: files.iter().map(|f| f.bytes).sum(),
+                        files,
+                        feedback,
+                    }
+                })
+                .collect())
+        })
+        .await
     }
 
-    if current_version < 2 {
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_sync_logs_timestamp ON sync_logs(timestamp)",
-            [],
-        )?;
+    /// Records a feedback report on a rule on behalf of the current audit actor.
+    pub async fn add_rule_feedback(&self, input: RuleFeedbackInput) -> Result<RuleFeedback> {
+        let feedback = RuleFeedback {
+            id: uuid::Uuid::new_v4().to_string(),
+            rule_id: input.rule_id,
+            verdict: input.verdict,
+            session_id: input.session_id,
+            note: input.note,
+            reporter: crate::audit::current_actor(),
+            created_at: chrono::Utc::now(),
+        };
+        let row = feedback.clone();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO rule_feedback (id, rule_id, verdict, session_id, note, reporter, reporter_detail, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    row.id,
+                    row.rule_id,
+                    row.verdict.as_str(),
+                    row.session_id,
+                    row.note,
+                    row.reporter.kind.as_str(),
+                    row.reporter.detail,
+                    row.created_at.timestamp()
+                ],
+            )?;
+            Ok(())
+        })
+        .await?;
+        Ok(feedback)
     }
 
-    if current_version < 3 {
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS rule_file_index (
-                rule_id TEXT PRIMARY KEY NOT NULL,
-                file_path TEXT NOT NULL,
-                content_hash TEXT,
-                last_modified INTEGER
-            )",
-            [],
-        )?;
+    /// The most recent feedback reports on a rule, newest first.
+    pub async fn get_rule_feedback(&self, rule_id: &str, limit: u32) -> Result<Vec<RuleFeedback>> {
+        let rule_id = rule_id.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, rule_id, verdict, session_id, note, reporter, reporter_detail, created_at
+                 FROM rule_feedback
+                 WHERE rule_id = ?
+                 ORDER BY created_at DESC, rowid DESC
+                 LIMIT ?",
+            )?;
+            let rows = stmt
+                .query_map(params![rule_id, limit], rule_feedback_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows.into_iter().flatten().collect())
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_rule_file_index_path ON rule_file_index(file_path)",
-            [],
-        )?;
+    /// Ids of the enabled rules matching the FTS5 `query`, best first, with their BM25
+    /// score, where lower is better. Matches in the name count most, then the description.
+    pub async fn search_rules_fts(&self, query: &str, limit: u32) -> Result<Vec<(String, f64)>> {
+        let query = query.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT rules.id, bm25(rules_fts, 0.0, 10.0, 4.0, 1.0) AS score
+                 FROM rules_fts
+                 JOIN rules ON rules.id = rules_fts.rule_id
+                 WHERE rules_fts MATCH ? AND rules.enabled = 1
+                 ORDER BY score
+                 LIMIT ?",
+            )?;
+            let rows = stmt
+                .query_map(params![query, limit], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
     }
 
-    if current_version < 4 {
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS commands (
-                id TEXT PRIMARY KEY NOT NULL,
-                name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                script TEXT NOT NULL,
-                arguments TEXT NOT NULL,
-                expose_via_mcp INTEGER NOT NULL DEFAULT 1,
-                target_paths TEXT NOT NULL DEFAULT '[]',
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
-            )",
-            [],
-        )?;
+    /// Cached embeddings of rules by `model`, as rule id to the hash of the embedded text
+    /// and the vector.
+    pub async fn get_rule_embeddings(
+        &self,
+        model: &str,
+    ) -> Result<std::collections::HashMap<String, (String, Vec<f32>)>> {
+        let model = model.to_string();
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT rule_id, content_hash, embedding FROM rule_embeddings WHERE model = ?",
+            )?;
+            let rows = stmt
+                .query_map(params![model], |row| {
+                    let bytes: Vec<u8> = row.get(2)?;
+                    Ok((row.get(0)?, (row.get(1)?, decode_embedding(&bytes))))
+                })?
+                .collect::<std::result::Result<std::collections::HashMap<_, _>, _>>()?;
+            Ok(rows)
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_commands_updated_at ON commands(updated_at)",
-            [],
-        )?;
-
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS execution_logs (
-                id TEXT PRIMARY KEY NOT NULL,
-                command_id TEXT NOT NULL,
-                command_name TEXT NOT NULL,
-                arguments TEXT NOT NULL,
-                stdout TEXT NOT NULL,
-                stderr TEXT NOT NULL,
-                exit_code INTEGER NOT NULL,
-                duration_ms INTEGER NOT NULL,
-                executed_at INTEGER NOT NULL,
-                triggered_by TEXT NOT NULL
-            )",
-            [],
-        )?;
+    /// Stores rule embeddings by `model` as (rule id, text hash, vector), replacing any
+    /// earlier embedding of those rules.
+    pub async fn save_rule_embeddings(
+        &self,
+        model: &str,
+        embeddings: Vec<(String, String, Vec<f32>)>,
+    ) -> Result<()> {
+        let model = model.to_string();
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            for (rule_id, content_hash, vector) in &embeddings {
+                tx.execute(
+                    "INSERT OR REPLACE INTO rule_embeddings (rule_id, model, content_hash, embedding)
+                     VALUES (?, ?, ?, ?)",
+                    params![rule_id, model, content_hash, encode_embedding(vector)],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
 
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_execution_logs_executed_at ON execution_logs(executed_at)",
-            [],
-        )?;
+    /// Most recent write and number of files written, per adapter that has synced rules.
+    pub async fn get_adapter_sync_times(&self) -> Result<Vec<(AdapterType, DateTime<Utc>, u32)>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT adapter, MAX(last_synced_at), COUNT(DISTINCT file_path)
+                 FROM rule_sync_stats
+                 GROUP BY adapter",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, u32>(2)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows
+                .into_iter()
+                .filter_map(|(adapter, last_synced_at, files)| {
+                    let adapter = AdapterType::from_str(&adapter).ok()?;
+                    Some((adapter, parse_timestamp_or_now(last_synced_at), files))
+                })
+                .collect())
+        })
+        .await
     }
 
-    if current_version < 5 {
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS skills (
-                id TEXT PRIMARY KEY NOT NULL,
-                name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                instructions TEXT NOT NULL,
-                enabled INTEGER NOT NULL DEFAULT 1,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
-            )",
-            [],
-        )?;
+    pub async fn get_all_collections(&self) -> Result<Vec<Collection>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, parent_id, position, created_at, updated_at
+                 FROM collections
+                 ORDER BY position ASC, name COLLATE NOCASE ASC",
+            )?;
 
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_skills_updated_at ON skills(updated_at)",
-            [],
-        )?;
+            let collections = stmt
+                .query_map([], collection_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(collections)
+        })
+        .await
     }
 
-    if current_version < 6 {
-        let mut stmt = transaction.prepare("PRAGMA table_info(skills)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+    pub async fn get_collection_by_id(&self, id: &str) -> Result<Collection> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT id, name, parent_id, position, created_at, updated_at
+                 FROM collections
+                 WHERE id = ?",
+                params![id],
+                collection_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::CollectionNotFound { id: id.to_string() }
+                }
+                _ => AppError::Database(e),
+            })
+        })
+        .await
+    }
 
-        if !cols.iter().any(|c| c == "input_schema") {
-            transaction.execute(
-                "ALTER TABLE skills ADD COLUMN input_schema TEXT NOT NULL DEFAULT '[]'",
-                [],
+    pub async fn create_collection(&self, input: CreateCollectionInput) -> Result<Collection> {
+        let id = self
+            .write(move |conn| {
+                if let Some(ref parent_id) = input.parent_id {
+                    ensure_collection_exists(conn, parent_id)?;
+                }
+
+                let now = chrono::Utc::now().timestamp();
+                let id = input.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+                let position: i64 = conn.query_row(
+                    "SELECT COALESCE(MAX(position) + 1, 0) FROM collections WHERE parent_id IS ?",
+                    params![input.parent_id],
+                    |row| row.get(0),
+                )?;
+
+                conn.execute(
+                "INSERT INTO collections (id, name, parent_id, position, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![id, input.name, input.parent_id, position, now, now],
             )?;
-        }
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_collection_by_id(&id).await
     }
 
-    if current_version < 7 {
-        let mut stmt = transaction.prepare("PRAGMA table_info(skills)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+    pub async fn rename_collection(&self, id: &str, name: &str) -> Result<Collection> {
+        let id = id.to_string();
+        let name = name.to_string();
+        let id = self
+            .write(move |conn| {
+                let now = chrono::Utc::now().timestamp();
+                let updated = conn.execute(
+                    "UPDATE collections SET name = ?, updated_at = ? WHERE id = ?",
+                    params![name, now, id],
+                )?;
+                if updated == 0 {
+                    return Err(AppError::CollectionNotFound { id: id.to_string() });
+                }
 
-        if !cols.iter().any(|c| c == "directory_path") {
-            transaction.execute(
-                "ALTER TABLE skills ADD COLUMN directory_path TEXT NOT NULL DEFAULT ''",
-                [],
+                Ok(id)
+            })
+            .await?;
+
+        self.get_collection_by_id(&id).await
+    }
+
+    /// Moves a collection under `parent_id` (or to the top level) and inserts it at
+    /// `position` among its new siblings, appending when no position is given.
+    pub async fn move_collection(
+        &self,
+        id: &str,
+        parent_id: Option<&str>,
+        position: Option<usize>,
+    ) -> Result<Collection> {
+        let id = id.to_string();
+        let parent_id = parent_id.map(str::to_string);
+        let id = self
+            .write(move |conn| {
+                let tx = conn.savepoint()?;
+                ensure_collection_exists(&tx, &id)?;
+
+                if let Some(ref parent) = parent_id {
+                    ensure_collection_exists(&tx, parent)?;
+                    if collection_is_within(&tx, parent, &id)? {
+                        return Err(AppError::Validation(
+                            "Cannot move a collection into itself or one of its descendants"
+                                .to_string(),
+                        ));
+                    }
+                }
+
+                let mut siblings: Vec<String> = {
+                    let mut stmt = tx.prepare(
+                        "SELECT id FROM collections WHERE parent_id IS ? AND id != ? ORDER BY position ASC",
+                    )?;
+                    let ids = stmt
+                        .query_map(params![parent_id, id], |row| row.get(0))?
+                        .collect::<std::result::Result<Vec<String>, _>>()?;
+                    ids
+                };
+                let index = position.unwrap_or(siblings.len()).min(siblings.len());
+                siblings.insert(index, id.clone());
+
+                let now = chrono::Utc::now().timestamp();
+                tx.execute(
+                    "UPDATE collections SET parent_id = ?, updated_at = ? WHERE id = ?",
+                    params![parent_id, now, id],
+                )?;
+                for (pos, sibling_id) in siblings.iter().enumerate() {
+                    tx.execute(
+                        "UPDATE collections SET position = ? WHERE id = ?",
+                        params![pos as i64, sibling_id],
+                    )?;
+                }
+                tx.commit()?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_collection_by_id(&id).await
+    }
+
+    /// Rewrites sibling order under `parent_id` to match `ordered_ids`.
+    pub async fn reorder_collections(
+        &self,
+        parent_id: Option<&str>,
+        ordered_ids: &[String],
+    ) -> Result<()> {
+        let parent_id = parent_id.map(str::to_string);
+        let ordered_ids = ordered_ids.to_vec();
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            for (pos, id) in ordered_ids.iter().enumerate() {
+                let updated = tx.execute(
+                    "UPDATE collections SET position = ? WHERE id = ? AND parent_id IS ?",
+                    params![pos as i64, id, parent_id],
+                )?;
+                if updated == 0 {
+                    return Err(AppError::Validation(format!(
+                        "Collection '{}' is not a child of the given parent",
+                        id
+                    )));
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Deletes a collection. Child collections and items move up to the deleted
+    /// collection's parent so no artifact is ever removed by deleting a folder.
+    pub async fn delete_collection(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            let parent_id: Option<String> = tx
+                .query_row(
+                    "SELECT parent_id FROM collections WHERE id = ?",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => {
+                        AppError::CollectionNotFound { id: id.to_string() }
+                    }
+                    _ => AppError::Database(e),
+                })?;
+
+            let collection_offset: i64 = tx.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM collections WHERE parent_id IS ?",
+                params![parent_id],
+                |row| row.get(0),
             )?;
-        }
-        if !cols.iter().any(|c| c == "entry_point") {
-            transaction.execute(
-                "ALTER TABLE skills ADD COLUMN entry_point TEXT NOT NULL DEFAULT ''",
-                [],
+            tx.execute(
+                "UPDATE collections SET parent_id = ?, position = position + ? WHERE parent_id = ?",
+                params![parent_id, collection_offset, id],
             )?;
-        }
-    }
 
-    if current_version < 8 {
-        let mut stmt = transaction.prepare("PRAGMA table_info(skills)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+            match parent_id {
+                Some(ref parent) => {
+                    let item_offset: i64 = tx.query_row(
+                        "SELECT COALESCE(MAX(position) + 1, 0) FROM collection_items WHERE collection_id = ?",
+                        params![parent],
+                        |row| row.get(0),
+                    )?;
+                    tx.execute(
+                        "UPDATE collection_items SET collection_id = ?, position = position + ? WHERE collection_id = ?",
+                        params![parent, item_offset, id],
+                    )?;
+                }
+                None => {
+                    tx.execute(
+                        "DELETE FROM collection_items WHERE collection_id = ?",
+                        params![id],
+                    )?;
+                }
+            }
 
-        if !cols.iter().any(|c| c == "scope") {
-            transaction.execute(
-                "ALTER TABLE skills ADD COLUMN scope TEXT NOT NULL DEFAULT 'global'",
-                [],
+            tx.execute("DELETE FROM collections WHERE id = ?", params![id])?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_all_collection_items(&self) -> Result<Vec<CollectionItem>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT collection_id, item_type, item_id, position
+                 FROM collection_items
+                 ORDER BY collection_id, position ASC",
             )?;
-        }
+
+            let items = stmt
+                .query_map([], collection_item_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(items)
+        })
+        .await
     }
 
-    if current_version < 9 {
-        // Add slash command support columns to commands table
-        let mut stmt = transaction.prepare("PRAGMA table_info(commands)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+    /// Files an artifact into `collection_id`, appending it to the end of that collection.
+    /// Passing `None` returns the artifact to the root.
+    pub async fn set_item_collection(
+        &self,
+        item_type: CollectionItemType,
+        item_id: &str,
+        collection_id: Option<&str>,
+    ) -> Result<()> {
+        let item_id = item_id.to_string();
+        let collection_id = collection_id.map(str::to_string);
+        self.write(move |conn| {
+            let Some(collection_id) = collection_id else {
+                conn.execute(
+                    "DELETE FROM collection_items WHERE item_type = ? AND item_id = ?",
+                    params![item_type.as_str(), item_id],
+                )?;
+                return Ok(());
+            };
+
+            ensure_collection_exists(conn, &collection_id)?;
+
+            let (table, not_found) = match item_type {
+                CollectionItemType::Rule => (
+                    "rules",
+                    AppError::RuleNotFound {
+                        id: item_id.to_string(),
+                    },
+                ),
+                CollectionItemType::Command => (
+                    "commands",
+                    AppError::CommandNotFound {
+                        id: item_id.to_string(),
+                    },
+                ),
+                CollectionItemType::Skill => (
+                    "skills",
+                    AppError::SkillNotFound {
+                        id: item_id.to_string(),
+                    },
+                ),
+            };
+            let count: i64 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE id = ?", table),
+                params![item_id],
+                |row| row.get(0),
+            )?;
+            if count == 0 {
+                return Err(not_found);
+            }
 
-        if !cols.iter().any(|c| c == "generate_slash_commands") {
-            transaction.execute(
-                "ALTER TABLE commands ADD COLUMN generate_slash_commands INTEGER NOT NULL DEFAULT 0",
-                [],
+            let position: i64 = conn.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM collection_items WHERE collection_id = ?",
+                params![collection_id],
+                |row| row.get(0),
             )?;
-        }
-        if !cols.iter().any(|c| c == "slash_command_adapters") {
-            transaction.execute(
-                "ALTER TABLE commands ADD COLUMN slash_command_adapters TEXT NOT NULL DEFAULT '[]'",
-                [],
+            conn.execute(
+                "INSERT OR REPLACE INTO collection_items (item_type, item_id, collection_id, position)
+                 VALUES (?, ?, ?, ?)",
+                params![item_type.as_str(), item_id, collection_id, position],
             )?;
-        }
+
+            Ok(())
+        })
+        .await
     }
 
-    if current_version < 10 {
-        let mut stmt = transaction.prepare("PRAGMA table_info(commands)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+    /// Rewrites item order inside `collection_id` to match `ordered`.
+    pub async fn reorder_collection_items(
+        &self,
+        collection_id: &str,
+        ordered: &[CollectionItemRef],
+    ) -> Result<()> {
+        let collection_id = collection_id.to_string();
+        let ordered = ordered.to_vec();
+        self.write(move |conn| {
+            let tx = conn.savepoint()?;
+            for (pos, item) in ordered.iter().enumerate() {
+                let updated = tx.execute(
+                    "UPDATE collection_items SET position = ?
+                     WHERE collection_id = ? AND item_type = ? AND item_id = ?",
+                    params![
+                        pos as i64,
+                        collection_id,
+                        item.item_type.as_str(),
+                        item.item_id
+                    ],
+                )?;
+                if updated == 0 {
+                    return Err(AppError::Validation(format!(
+                        "{} '{}' is not in collection '{}'",
+                        item.item_type.as_str(),
+                        item.item_id,
+                        collection_id
+                    )));
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
 
-        if !cols.iter().any(|c| c == "target_paths") {
-            transaction.execute(
-                "ALTER TABLE commands ADD COLUMN target_paths TEXT NOT NULL DEFAULT '[]'",
-                [],
+    pub async fn import_collection(
+        &self,
+        collection: Collection,
+        mode: crate::models::ImportMode,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            if mode == crate::models::ImportMode::Skip
+                && row_exists(conn, "collections", &collection.id)?
+            {
+                return Ok(());
+            }
+            // Like moving a collection, the parent must exist and must not sit below it.
+            if let Some(ref parent) = collection.parent_id {
+                ensure_collection_exists(conn, parent)?;
+                if collection_is_within(conn, parent, &collection.id)? {
+                    return Err(AppError::Validation(format!(
+                        "Collection '{}' cannot be nested inside itself or one of its descendants",
+                        collection.name
+                    )));
+                }
+            }
+
+            let now = chrono::Utc::now().timestamp();
+
+            let sql = match mode {
+                crate::models::ImportMode::Overwrite => {
+                    "INSERT OR REPLACE INTO collections (id, name, parent_id, position, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?)"
+                }
+                crate::models::ImportMode::Skip => {
+                    "INSERT OR IGNORE INTO collections (id, name, parent_id, position, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?)"
+                }
+            };
+
+            conn.execute(
+                sql,
+                params![
+                    collection.id,
+                    collection.name,
+                    collection.parent_id,
+                    collection.position,
+                    collection.created_at.timestamp(),
+                    now
+                ],
             )?;
-        }
+            Ok(())
+        })
+        .await
     }
 
-    if current_version < 11 {
-        let mut stmt = transaction.prepare("PRAGMA table_info(rules)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+    pub async fn import_collection_item(
+        &self,
+        item: CollectionItem,
+        mode: crate::models::ImportMode,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            let sql = match mode {
+                crate::models::ImportMode::Overwrite => {
+                    "INSERT OR REPLACE INTO collection_items (item_type, item_id, collection_id, position)
+                     VALUES (?, ?, ?, ?)"
+                }
+                crate::models::ImportMode::Skip => {
+                    "INSERT OR IGNORE INTO collection_items (item_type, item_id, collection_id, position)
+                     VALUES (?, ?, ?, ?)"
+                }
+            };
+
+            conn.execute(
+                sql,
+                params![
+                    item.item_type.as_str(),
+                    item.item_id,
+                    item.collection_id,
+                    item.position
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
 
-        if !cols.iter().any(|c| c == "description") {
-            transaction.execute(
-                "ALTER TABLE rules ADD COLUMN description TEXT NOT NULL DEFAULT ''",
-                [],
+    pub async fn get_all_external_mcp_servers(&self) -> Result<Vec<ExternalMcpServer>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, transport, command, args, env, url, headers, enabled_adapters, enabled, created_at, updated_at
+                 FROM external_mcp_servers
+                 ORDER BY name COLLATE NOCASE ASC",
             )?;
-        }
 
-        let mut stmt = transaction.prepare("PRAGMA table_info(commands)")?;
-        let cols: Vec<String> = stmt
-            .query_map([], |row| row.get(1))?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
+            let servers = stmt
+                .query_map([], external_mcp_server_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        if !cols.iter().any(|c| c == "is_placeholder") {
-            transaction.execute(
-                "ALTER TABLE commands ADD COLUMN is_placeholder INTEGER NOT NULL DEFAULT 0",
-                [],
-            )?;
-        }
+            Ok(servers)
+        })
+        .await
     }
 
-    if current_version < 12 {
-        transaction.execute(
-            "CREATE TABLE IF NOT EXISTS reconciliation_logs (
-                id TEXT PRIMARY KEY NOT NULL,
-                timestamp INTEGER NOT NULL,
-                operation TEXT NOT NULL,
-                artifact_type TEXT,
-                adapter TEXT,
-                scope TEXT,
-                path TEXT NOT NULL,
-                result TEXT NOT NULL,
-                error_message TEXT
-            )",
-            [],
-        )?;
-
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_reconciliation_logs_timestamp ON reconciliation_logs(timestamp)",
-            [],
-        )?;
+    pub async fn get_external_mcp_server_by_id(&self, id: &str) -> Result<ExternalMcpServer> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT id, name, transport, command, args, env, url, headers, enabled_adapters, enabled, created_at, updated_at
+                 FROM external_mcp_servers
+                 WHERE id = ?",
+                params![id],
+                external_mcp_server_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::McpServerNotFound { id: id.to_string() }
+                }
+                _ => AppError::Database(e),
+            })
+        })
+        .await
     }
 
-    if current_version < 13 {
-        add_column_if_missing(
-            &transaction,
-            "skills",
-            "target_adapters",
-            "TEXT NOT NULL DEFAULT '[]'",
-        )?;
-        add_column_if_missing(
-            &transaction,
-            "skills",
-            "target_paths",
-            "TEXT NOT NULL DEFAULT '[]'",
+    pub async fn create_external_mcp_server(
+        &self,
+        input: CreateExternalMcpServerInput,
+    ) -> Result<ExternalMcpServer> {
+        let id = self
+            .write(move |conn| {
+                ensure_mcp_server_name_free(conn, &input.name, None)?;
+
+                let now = chrono::Utc::now().timestamp();
+                let id = uuid::Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO external_mcp_servers (id, name, transport, command, args, env, url, headers, enabled_adapters, enabled, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        input.name,
+                        input.spec.transport.as_str(),
+                        input.spec.command,
+                        serde_json::to_string(&input.spec.args)?,
+                        serde_json::to_string(&input.spec.env)?,
+                        input.spec.url,
+                        serde_json::to_string(&input.spec.headers)?,
+                        serde_json::to_string(&input.enabled_adapters)?,
+                        input.enabled,
+                        now,
+                        now
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_external_mcp_server_by_id(&id).await
+    }
+
+    pub async fn update_external_mcp_server(
+        &self,
+        id: &str,
+        input: UpdateExternalMcpServerInput,
+    ) -> Result<ExternalMcpServer> {
+        let existing = self.get_external_mcp_server_by_id(id).await?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+                let name = input.name.unwrap_or(existing.name);
+                ensure_mcp_server_name_free(conn, &name, Some(&id))?;
+                let spec = input.spec.unwrap_or(existing.spec);
+                let enabled_adapters = input.enabled_adapters.unwrap_or(existing.enabled_adapters);
+                let enabled = input.enabled.unwrap_or(existing.enabled);
+                let now = chrono::Utc::now().timestamp();
+
+                conn.execute(
+                    "UPDATE external_mcp_servers
+                     SET name = ?, transport = ?, command = ?, args = ?, env = ?, url = ?, headers = ?, enabled_adapters = ?, enabled = ?, updated_at = ?
+                     WHERE id = ?",
+                    params![
+                        name,
+                        spec.transport.as_str(),
+                        spec.command,
+                        serde_json::to_string(&spec.args)?,
+                        serde_json::to_string(&spec.env)?,
+                        spec.url,
+                        serde_json::to_string(&spec.headers)?,
+                        serde_json::to_string(&enabled_adapters)?,
+                        enabled,
+                        now,
+                        id
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_external_mcp_server_by_id(&id).await
+    }
+
+    pub async fn delete_external_mcp_server(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM external_mcp_servers WHERE id = ?", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_all_webhooks(&self) -> Result<Vec<Webhook>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, events, secret_name, enabled, created_at, updated_at
+                 FROM webhooks
+                 ORDER BY created_at ASC",
+            )?;
+
+            let webhooks = stmt
+                .query_map([], webhook_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(webhooks)
+        })
+        .await
+    }
+
+    pub async fn get_webhook_by_id(&self, id: &str) -> Result<Webhook> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT id, url, events, secret_name, enabled, created_at, updated_at
+                 FROM webhooks
+                 WHERE id = ?",
+                params![id],
+                webhook_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::WebhookNotFound { id: id.clone() }
+                }
+                _ => AppError::Database(e),
+            })
+        })
+        .await
+    }
+
+    pub async fn create_webhook(&self, input: CreateWebhookInput) -> Result<Webhook> {
+        let id = self
+            .write(move |conn| {
+                let now = chrono::Utc::now().timestamp();
+                let id = uuid::Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO webhooks (id, url, events, secret_name, enabled, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        input.url,
+                        serde_json::to_string(&input.events)?,
+                        input.secret_name,
+                        input.enabled,
+                        now,
+                        now
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_webhook_by_id(&id).await
+    }
+
+    pub async fn update_webhook(&self, id: &str, input: UpdateWebhookInput) -> Result<Webhook> {
+        let existing = self.get_webhook_by_id(id).await?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+                let url = input.url.unwrap_or(existing.url);
+                let events = input.events.unwrap_or(existing.events);
+                let secret_name = match input.secret_name {
+                    Some(name) if name.is_empty() => None,
+                    Some(name) => Some(name),
+                    None => existing.secret_name,
+                };
+                let enabled = input.enabled.unwrap_or(existing.enabled);
+                let now = chrono::Utc::now().timestamp();
+
+                conn.execute(
+                    "UPDATE webhooks
+                     SET url = ?, events = ?, secret_name = ?, enabled = ?, updated_at = ?
+                     WHERE id = ?",
+                    params![
+                        url,
+                        serde_json::to_string(&events)?,
+                        secret_name,
+                        enabled,
+                        now,
+                        id
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_webhook_by_id(&id).await
+    }
+
+    pub async fn delete_webhook(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM webhooks WHERE id = ?", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_all_rule_templates(&self) -> Result<Vec<RuleTemplate>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, placeholders, rule, created_at, updated_at
+                 FROM rule_templates
+                 ORDER BY name COLLATE NOCASE ASC",
+            )?;
+
+            let templates = stmt
+                .query_map([], rule_template_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(templates.into_iter().flatten().collect())
+        })
+        .await
+    }
+
+    pub async fn get_rule_template_by_id(&self, id: &str) -> Result<RuleTemplate> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT id, name, description, placeholders, rule, created_at, updated_at
+                 FROM rule_templates
+                 WHERE id = ?",
+                params![id],
+                rule_template_from_row,
+            )
+            .optional()?
+            .flatten()
+            .ok_or_else(|| AppError::RuleTemplateNotFound { id: id.clone() })
+        })
+        .await
+    }
+
+    pub async fn create_rule_template(
+        &self,
+        input: CreateRuleTemplateInput,
+    ) -> Result<RuleTemplate> {
+        let id = self
+            .write(move |conn| {
+                let now = chrono::Utc::now().timestamp();
+                let id = uuid::Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO rule_templates (id, name, description, placeholders, rule, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        input.name,
+                        input.description,
+                        serde_json::to_string(&input.placeholders)?,
+                        serde_json::to_string(&input.rule)?,
+                        now,
+                        now
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_rule_template_by_id(&id).await
+    }
+
+    pub async fn update_rule_template(
+        &self,
+        id: &str,
+        input: UpdateRuleTemplateInput,
+    ) -> Result<RuleTemplate> {
+        let existing = self.get_rule_template_by_id(id).await?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+                let name = input.name.unwrap_or(existing.name);
+                let description = input.description.unwrap_or(existing.description);
+                let placeholders = input.placeholders.unwrap_or(existing.placeholders);
+                let rule = input.rule.unwrap_or(existing.rule);
+                let now = chrono::Utc::now().timestamp();
+
+                conn.execute(
+                    "UPDATE rule_templates
+                     SET name = ?, description = ?, placeholders = ?, rule = ?, updated_at = ?
+                     WHERE id = ?",
+                    params![
+                        name,
+                        description,
+                        serde_json::to_string(&placeholders)?,
+                        serde_json::to_string(&rule)?,
+                        now,
+                        id
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_rule_template_by_id(&id).await
+    }
+
+    pub async fn delete_rule_template(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM rule_templates WHERE id = ?", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_all_snippets(&self) -> Result<Vec<Snippet>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, content, created_at, updated_at
+                 FROM snippets
+                 ORDER BY name ASC",
+            )?;
+
+            let snippets = stmt
+                .query_map([], snippet_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(snippets)
+        })
+        .await
+    }
+
+    pub async fn get_snippet_by_id(&self, id: &str) -> Result<Snippet> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT id, name, description, content, created_at, updated_at
+                 FROM snippets
+                 WHERE id = ?",
+                params![id],
+                snippet_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::SnippetNotFound { id: id.clone() }
+                }
+                _ => AppError::Database(e),
+            })
+        })
+        .await
+    }
+
+    pub async fn create_snippet(&self, input: CreateSnippetInput) -> Result<Snippet> {
+        let id = self
+            .write(move |conn| {
+                let now = chrono::Utc::now().timestamp();
+                let id = uuid::Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO snippets (id, name, description, content, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                    params![id, input.name, input.description, input.content, now, now],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_snippet_by_id(&id).await
+    }
+
+    pub async fn update_snippet(&self, id: &str, input: UpdateSnippetInput) -> Result<Snippet> {
+        let existing = self.get_snippet_by_id(id).await?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+                let name = input.name.unwrap_or(existing.name);
+                let description = input.description.unwrap_or(existing.description);
+                let content = input.content.unwrap_or(existing.content);
+                let now = chrono::Utc::now().timestamp();
+
+                conn.execute(
+                    "UPDATE snippets
+                     SET name = ?, description = ?, content = ?, updated_at = ?
+                     WHERE id = ?",
+                    params![name, description, content, now, id],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        self.get_snippet_by_id(&id).await
+    }
+
+    pub async fn delete_snippet(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM snippets WHERE id = ?", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn add_approval_audit(&self, entry: &ApprovalAuditEntry) -> Result<()> {
+        let entry = entry.clone();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO approval_audit (id, command_id, command_name, arguments_json, decision, requested_at, decided_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    entry.id,
+                    entry.command_id,
+                    entry.command_name,
+                    entry.arguments_json,
+                    entry.decision.as_str(),
+                    entry.requested_at.timestamp(),
+                    entry.decided_at.timestamp()
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_approval_audit(&self, limit: u32) -> Result<Vec<ApprovalAuditEntry>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, command_id, command_name, arguments_json, decision, requested_at, decided_at
+                 FROM approval_audit
+                 ORDER BY decided_at DESC
+                 LIMIT ?",
+            )?;
+
+            let entries = stmt
+                .query_map(params![limit], |row| {
+                    let decision: String = row.get(4)?;
+                    Ok(ApprovalAuditEntry {
+                        id: row.get(0)?,
+                        command_id: row.get(1)?,
+                        command_name: row.get(2)?,
+                        arguments_json: row.get(3)?,
+                        decision: ApprovalDecision::from_str(&decision)
+                            .unwrap_or(ApprovalDecision::Denied),
+                        requested_at: parse_timestamp_or_now(row.get(5)?),
+                        decided_at: parse_timestamp_or_now(row.get(6)?),
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(entries)
+        })
+        .await
+    }
+
+    pub async fn add_audit_entry(
+        &self,
+        actor: AuditActor,
+        action: AuditAction,
+        target: Option<AuditTarget>,
+        summary: String,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO audit_log (id, timestamp, actor, actor_detail, action, target_type, target_id, target_name, summary)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    chrono::Utc::now().timestamp(),
+                    actor.kind.as_str(),
+                    actor.detail,
+                    action.as_str(),
+                    target.as_ref().map(|t| t.target_type.as_str()),
+                    target.as_ref().map(|t| t.id.clone()),
+                    target.as_ref().map(|t| t.name.clone()),
+                    summary
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Records a change made through this database on behalf of the current audit actor,
+    /// and publishes it as a change event. The change has already been written, so failing
+    /// to record it is only logged.
+    async fn audit_change(&self, action: AuditAction, target: AuditTarget, summary: String) {
+        if let Some(change) = ChangeKind::of_action(action) {
+            change_events::publish(ChangeEvent::new(target.target_type, change, &target.id));
+        }
+        let actor = crate::audit::current_actor();
+        if let Err(e) = self
+            .add_audit_entry(actor, action, Some(target), summary)
+            .await
+        {
+            log::warn!(
+                "Failed to record {} in the audit log: {}",
+                action.as_str(),
+                e
+            );
+        }
+    }
+
+    pub async fn query_audit_log(&self, query: AuditLogQuery) -> Result<AuditLogPage> {
+        self.read(move |conn| {
+            let mut where_clauses = Vec::new();
+            let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+            if let Some(actor) = query.actor {
+                where_clauses.push("actor = ?");
+                params.push(Box::new(actor.as_str()));
+            }
+            if let Some(action) = query.action {
+                where_clauses.push("action = ?");
+                params.push(Box::new(action.as_str()));
+            }
+            if let Some(target_type) = query.target_type {
+                where_clauses.push("target_type = ?");
+                params.push(Box::new(target_type.as_str()));
+            }
+            if let Some(target_id) = query.target_id {
+                where_clauses.push("target_id = ?");
+                params.push(Box::new(target_id));
+            }
+            if let Some(search) = query.search.filter(|s| !s.trim().is_empty()) {
+                where_clauses.push("(summary LIKE ? OR target_name LIKE ?)");
+                let pattern = format!("%{}%", search.trim());
+                params.push(Box::new(pattern.clone()));
+                params.push(Box::new(pattern));
+            }
+            if let Some(since) = query.since {
+                where_clauses.push("timestamp >= ?");
+                params.push(Box::new(since));
+            }
+            if let Some(until) = query.until {
+                where_clauses.push("timestamp < ?");
+                params.push(Box::new(until));
+            }
+
+            let where_sql = if where_clauses.is_empty() {
+                String::new()
+            } else {
+                format!(" WHERE {}", where_clauses.join(" AND "))
+            };
+            let params_refs: Vec<&dyn rusqlite::ToSql> =
+                params.iter().map(|p| p.as_ref()).collect();
+
+            let total: u32 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM audit_log{}", where_sql),
+                params_refs.as_slice(),
+                |row| row.get(0),
+            )?;
+
+            let limit = query
+                .limit
+                .unwrap_or(AUDIT_LOG_DEFAULT_PAGE)
+                .min(AUDIT_LOG_MAX_PAGE);
+            let offset = query.offset.unwrap_or(0);
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, timestamp, actor, actor_detail, action, target_type, target_id, target_name, summary
+                 FROM audit_log{}
+                 ORDER BY timestamp DESC, rowid DESC
+                 LIMIT {} OFFSET {}",
+                where_sql, limit, offset
+            ))?;
+            let entries = stmt
+                .query_map(params_refs.as_slice(), |row| {
+                    let actor: String = row.get(2)?;
+                    let action: String = row.get(4)?;
+                    let target_type: Option<String> = row.get(5)?;
+                    let target_id: Option<String> = row.get(6)?;
+                    let target_name: Option<String> = row.get(7)?;
+                    let target = match (target_type, target_id) {
+                        (Some(target_type), Some(id)) => AuditTargetType::from_str(&target_type)
+                            .ok()
+                            .map(|target_type| AuditTarget {
+                                target_type,
+                                id,
+                                name: target_name.unwrap_or_default(),
+                            }),
+                        _ => None,
+                    };
+                    Ok(AuditLogEntry {
+                        id: row.get(0)?,
+                        timestamp: parse_timestamp_or_now(row.get(1)?),
+                        actor: AuditActor {
+                            kind: AuditActorKind::from_str(&actor).unwrap_or(AuditActorKind::Ui),
+                            detail: row.get(3)?,
+                        },
+                        action: AuditAction::from_str(&action).unwrap_or(AuditAction::Update),
+                        target,
+                        summary: row.get(8)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(AuditLogPage { entries, total })
+        })
+        .await
+    }
+
+    /// Adds a running job to the journal and returns its id.
+    pub async fn start_job(
+        &self,
+        kind: JobKind,
+        description: String,
+        total: u32,
+        payload: serde_json::Value,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let job_id = id.clone();
+        self.write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            conn.execute(
+                "INSERT INTO job_journal (id, kind, description, status, completed, total, payload, started_at, updated_at)
+                 VALUES (?, ?, ?, ?, 0, ?, ?, ?, ?)",
+                params![
+                    job_id,
+                    kind.as_str(),
+                    description,
+                    JobStatus::Running.as_str(),
+                    total,
+                    payload.to_string(),
+                    now,
+                    now
+                ],
+            )?;
+            Ok(())
+        })
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn checkpoint_job(
+        &self,
+        id: &str,
+        completed: u32,
+        checkpoint: serde_json::Value,
+    ) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "UPDATE job_journal SET completed = ?, checkpoint = ?, updated_at = ? WHERE id = ?",
+                params![
+                    completed,
+                    checkpoint.to_string(),
+                    chrono::Utc::now().timestamp(),
+                    id
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Removes a job from the journal once it has finished or been resolved.
+    pub async fn finish_job(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM job_journal WHERE id = ?", params![id])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Marks a job that stopped with an error as interrupted, so it can be resolved like one
+    /// cut short by a crash.
+    pub async fn mark_job_interrupted(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "UPDATE job_journal SET status = ?, updated_at = ? WHERE id = ?",
+                params![
+                    JobStatus::Interrupted.as_str(),
+                    chrono::Utc::now().timestamp(),
+                    id
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Marks every job still running as interrupted. Called once at startup, before any
+    /// new job can begin, so a running entry can only be left over from an earlier run.
+    pub async fn mark_running_jobs_interrupted(&self) -> Result<u32> {
+        self.write(move |conn| {
+            let changed = conn.execute(
+                "UPDATE job_journal SET status = ? WHERE status = ?",
+                params![JobStatus::Interrupted.as_str(), JobStatus::Running.as_str()],
+            )?;
+            Ok(changed as u32)
+        })
+        .await
+    }
+
+    /// Interrupted jobs, oldest first.
+    pub async fn get_pending_jobs(&self) -> Result<Vec<JobRecord>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, kind, description, status, completed, total, payload, checkpoint, started_at, updated_at
+                 FROM job_journal
+                 WHERE status = ?
+                 ORDER BY started_at ASC, rowid ASC",
+            )?;
+            let jobs = stmt
+                .query_map(params![JobStatus::Interrupted.as_str()], job_from_row)?
+                .filter_map(|row| row.ok().flatten())
+                .collect();
+            Ok(jobs)
+        })
+        .await
+    }
+
+    pub async fn get_job(&self, id: &str) -> Result<Option<JobRecord>> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            let job = conn
+                .query_row(
+                    "SELECT id, kind, description, status, completed, total, payload, checkpoint, started_at, updated_at
+                     FROM job_journal
+                     WHERE id = ?",
+                    params![id],
+                    job_from_row,
+                )
+                .optional()?;
+            Ok(job.flatten())
+        })
+        .await
+    }
+
+    pub async fn get_all_workflows(&self) -> Result<Vec<Workflow>> {
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, steps, arguments, expose_via_mcp, generate_slash_commands, slash_command_adapters, created_at, updated_at
+                 FROM workflows
+                 ORDER BY name COLLATE NOCASE ASC",
+            )?;
+
+            let workflows = stmt
+                .query_map([], workflow_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(workflows)
+        })
+        .await
+    }
+
+    pub async fn get_workflow_by_id(&self, id: &str) -> Result<Workflow> {
+        let id = id.to_string();
+        self.read(move |conn| {
+            conn.query_row(
+                "SELECT id, name, description, steps, arguments, expose_via_mcp, generate_slash_commands, slash_command_adapters, created_at, updated_at
+                 FROM workflows
+                 WHERE id = ?",
+                params![id],
+                workflow_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::WorkflowNotFound { id: id.to_string() }
+                }
+                _ => AppError::Database(e),
+            })
+        })
+        .await
+    }
+
+    pub async fn create_workflow(&self, input: CreateWorkflowInput) -> Result<Workflow> {
+        let id = self
+            .write(move |conn| {
+                let now = chrono::Utc::now().timestamp();
+                let id = uuid::Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO workflows (id, name, description, steps, arguments, expose_via_mcp, generate_slash_commands, slash_command_adapters, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        input.name,
+                        input.description,
+                        serde_json::to_string(&input.steps)?,
+                        serde_json::to_string(&input.arguments)?,
+                        input.expose_via_mcp,
+                        input.generate_slash_commands,
+                        serde_json::to_string(&input.slash_command_adapters)?,
+                        now,
+                        now
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        let created = self.get_workflow_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Create,
+            AuditTarget::new(AuditTargetType::Workflow, &created.id, &created.name),
+            format!("Created workflow '{}'", created.name),
+        )
+        .await;
+        Ok(created)
+    }
+
+    pub async fn update_workflow(&self, id: &str, input: UpdateWorkflowInput) -> Result<Workflow> {
+        let existing = self.get_workflow_by_id(id).await?;
+        let id = id.to_string();
+        let id = self
+            .write(move |conn| {
+                let name = input.name.unwrap_or(existing.name);
+                let description = input.description.unwrap_or(existing.description);
+                let steps = input.steps.unwrap_or(existing.steps);
+                let arguments = input.arguments.unwrap_or(existing.arguments);
+                let expose_via_mcp = input.expose_via_mcp.unwrap_or(existing.expose_via_mcp);
+                let generate_slash_commands = input
+                    .generate_slash_commands
+                    .unwrap_or(existing.generate_slash_commands);
+                let slash_command_adapters = input
+                    .slash_command_adapters
+                    .unwrap_or(existing.slash_command_adapters);
+                let now = chrono::Utc::now().timestamp();
+
+                conn.execute(
+                    "UPDATE workflows
+                     SET name = ?, description = ?, steps = ?, arguments = ?, expose_via_mcp = ?, generate_slash_commands = ?, slash_command_adapters = ?, updated_at = ?
+                     WHERE id = ?",
+                    params![
+                        name,
+                        description,
+                        serde_json::to_string(&steps)?,
+                        serde_json::to_string(&arguments)?,
+                        expose_via_mcp,
+                        generate_slash_commands,
+                        serde_json::to_string(&slash_command_adapters)?,
+                        now,
+                        id
+                    ],
+                )?;
+
+                Ok(id)
+            })
+            .await?;
+
+        let updated = self.get_workflow_by_id(&id).await?;
+        self.audit_change(
+            AuditAction::Update,
+            AuditTarget::new(AuditTargetType::Workflow, &updated.id, &updated.name),
+            format!("Updated workflow '{}'", updated.name),
+        )
+        .await;
+        Ok(updated)
+    }
+
+    pub async fn delete_workflow(&self, id: &str) -> Result<()> {
+        let existing = self.get_workflow_by_id(id).await.ok();
+        let id = id.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM workflows WHERE id = ?", params![id])?;
+            Ok(())
+        })
+        .await?;
+
+        if let Some(existing) = existing {
+            self.audit_change(
+                AuditAction::Delete,
+                AuditTarget::new(AuditTargetType::Workflow, &existing.id, &existing.name),
+                format!("Deleted workflow '{}'", existing.name),
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Lists stored secrets without their values.
+    pub async fn get_all_secrets(&self) -> Result<Vec<SecretInfo>> {
+        self.read(move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT name, created_at, updated_at FROM secrets ORDER BY name ASC")?;
+
+            let secrets = stmt
+                .query_map([], |row| {
+                    Ok(SecretInfo {
+                        name: row.get(0)?,
+                        created_at: parse_timestamp_or_now(row.get(1)?),
+                        updated_at: parse_timestamp_or_now(row.get(2)?),
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            Ok(secrets)
+        })
+        .await
+    }
+
+    /// Returns the encrypted value of a secret, if it exists.
+    pub async fn get_secret_ciphertext(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let name = name.to_string();
+        self.read(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT value FROM secrets WHERE name = ?",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    pub async fn upsert_secret(&self, name: &str, ciphertext: Vec<u8>) -> Result<()> {
+        let name = name.to_string();
+        self.write(move |conn| {
+            let now = chrono::Utc::now().timestamp();
+            conn.execute(
+                "INSERT INTO secrets (name, value, created_at, updated_at) VALUES (?, ?, ?, ?)
+                 ON CONFLICT(name) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+                params![name, ciphertext, now, now],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn delete_secret(&self, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.write(move |conn| {
+            conn.execute("DELETE FROM secrets WHERE name = ?", params![name])?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Database(rusqlite::Error::ToSqlConversionFailure(Box::new(e))))?
+}
+
+/// Opens the writer (running migrations and switching to WAL) followed by the read pool.
+fn open_file_connections(db_path: &Path) -> Result<(Connection, Vec<Connection>)> {
+    let mut writer = Connection::open(db_path)?;
+    writer.busy_timeout(DB_BUSY_TIMEOUT)?;
+    writer.pragma_update(None, "journal_mode", "WAL")?;
+    writer.pragma_update(None, "synchronous", "NORMAL")?;
+    backup_before_migration(&writer, db_path, None)?;
+    run_migrations(&mut writer)?;
+
+    let mut readers = Vec::with_capacity(DB_READ_POOL_SIZE);
+    for _ in 0..DB_READ_POOL_SIZE {
+        let reader = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX
+                | OpenFlags::SQLITE_OPEN_URI,
         )?;
+        reader.busy_timeout(DB_BUSY_TIMEOUT)?;
+        readers.push(reader);
+    }
+
+    Ok((writer, readers))
+}
+
+/// Rejects files that are not intact RuleWeaver databases before they replace live data.
+fn validate_backup_file(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Err(AppError::InvalidInput {
+            message: format!("Backup file not found: {}", path.display()),
+        });
+    }
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    validate_backup_connection(&conn, path)
+}
+
+/// Copies a database that is about to be upgraded into the backups directory, so it can
+/// be restored if the user goes back to an older release.
+fn backup_before_migration(
+    conn: &Connection,
+    db_path: &Path,
+    encryption: Option<&EncryptedFile>,
+) -> Result<()> {
+    let version = schema_version(conn)?;
+    if version == 0 || version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let dir = crate::backup::backup_dir(db_path.parent().unwrap_or_else(|| Path::new(".")));
+    std::fs::create_dir_all(&dir)?;
+    let dest = crate::backup::pre_migration_backup_path(&dir, version);
+    match encryption {
+        Some(file) => crate::atomic_write::write_atomic(&dest, file.seal(conn)?)?,
+        None => conn.backup(DatabaseName::Main, &dest, None)?,
+    }
+    log::info!(
+        "Backed up schema v{} database to {} before upgrading to v{}",
+        version,
+        dest.display(),
+        SCHEMA_VERSION
+    );
+
+    if let Err(e) = crate::backup::prune_pre_migration_backups(&dir, PRE_MIGRATION_BACKUP_RETENTION)
+    {
+        log::warn!("Failed to prune pre-migration backups: {}", e);
+    }
+    Ok(())
+}
+
+fn schema_version(conn: &Connection) -> Result<i32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Refuses databases written by a newer release instead of re-stamping them with an
+/// older version and running with a schema this build does not understand.
+fn check_schema_compatibility(version: i32) -> Result<()> {
+    if version > SCHEMA_VERSION {
+        return Err(AppError::Migration {
+            message: format!(
+                "database schema v{} was created by a newer RuleWeaver release (this build supports up to v{}). \
+                 Update RuleWeaver, or restore a pre-migration backup from the backups folder.",
+                version, SCHEMA_VERSION
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn validate_backup_connection(conn: &Connection, path: &Path) -> Result<()> {
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|_| AppError::InvalidInput {
+            message: format!("{} is not a valid SQLite database", path.display()),
+        })?;
+    if check != "ok" {
+        return Err(AppError::InvalidInput {
+            message: format!("Backup integrity check failed: {}", check),
+        });
+    }
+
+    let has_rules: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'rules'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_rules == 0 {
+        return Err(AppError::InvalidInput {
+            message: format!("{} is not a RuleWeaver database", path.display()),
+        });
+    }
+
+    check_schema_compatibility(schema_version(conn)?)
+}
+
+/// Runs `f` between `BEGIN` and `COMMIT`, rolling back when it fails.
+fn in_transaction<T>(
+    conn: &mut Connection,
+    f: impl FnOnce(&mut Connection) -> Result<T>,
+) -> Result<T> {
+    // A write that panicked can leave its transaction open.
+    if !conn.is_autocommit() {
+        conn.execute_batch("ROLLBACK")?;
+    }
+    conn.execute_batch("BEGIN")?;
+    match f(conn) {
+        Ok(value) => {
+            conn.execute_batch("COMMIT")?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(rollback) = conn.execute_batch("ROLLBACK") {
+                log::error!("Failed to roll back a database write: {}", rollback);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Re-seals plaintext backups next to the database with `file`'s key, so enabling
+/// encryption leaves no readable copy of the database behind.
+fn seal_plaintext_backups(file: &EncryptedFile) -> Result<()> {
+    let Some(app_data_dir) = file.db_path().parent() else {
+        return Ok(());
+    };
+    for backup in crate::backup::list_backups(&crate::backup::backup_dir(app_data_dir))? {
+        let path = PathBuf::from(&backup.path);
+        if encryption::is_encrypted_file(&path)? {
+            continue;
+        }
+        let sealed = {
+            // Like the live database, leave WAL mode so the sealed image is self-contained.
+            let source = Connection::open(&path)?;
+            source.pragma_update(None, "journal_mode", "DELETE")?;
+            file.seal(&source)?
+        };
+        crate::atomic_write::write_atomic(&path, sealed)?;
+    }
+    Ok(())
+}
+
+/// Removes a database file together with its WAL and shared-memory siblings.
+fn remove_database_files(db_path: &Path) -> Result<()> {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(suffix);
+        match std::fs::remove_file(PathBuf::from(path)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+fn collection_from_row(row: &rusqlite::Row) -> rusqlite::Result<Collection> {
+    Ok(Collection {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        parent_id: row.get(2)?,
+        position: row.get(3)?,
+        created_at: parse_timestamp_or_now(row.get(4)?),
+        updated_at: parse_timestamp_or_now(row.get(5)?),
+    })
+}
+
+fn webhook_from_row(row: &rusqlite::Row) -> rusqlite::Result<Webhook> {
+    let events: String = row.get(2)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        events: serde_json::from_str(&events).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to parse webhook events: {}. Falling back to none.",
+                e
+            );
+            Vec::new()
+        }),
+        secret_name: row.get(3)?,
+        enabled: row.get(4)?,
+        created_at: parse_timestamp_or_now(row.get(5)?),
+        updated_at: parse_timestamp_or_now(row.get(6)?),
+    })
+}
+
+fn snippet_from_row(row: &rusqlite::Row) -> rusqlite::Result<Snippet> {
+    Ok(Snippet {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        content: row.get(3)?,
+        created_at: parse_timestamp_or_now(row.get(4)?),
+        updated_at: parse_timestamp_or_now(row.get(5)?),
+    })
+}
+
+/// Reads a template row, skipping one whose rule no longer parses.
+fn rule_template_from_row(row: &rusqlite::Row) -> rusqlite::Result<Option<RuleTemplate>> {
+    let id: String = row.get(0)?;
+    let placeholders: String = row.get(3)?;
+    let rule: String = row.get(4)?;
+    let rule = match serde_json::from_str(&rule) {
+        Ok(rule) => rule,
+        Err(e) => {
+            log::warn!(
+                "Skipping rule template {} with an unreadable rule: {}",
+                id,
+                e
+            );
+            return Ok(None);
+        }
+    };
+    Ok(Some(RuleTemplate {
+        id,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        placeholders: serde_json::from_str(&placeholders).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to parse rule template placeholders: {}. Falling back to none.",
+                e
+            );
+            Vec::new()
+        }),
+        rule,
+        created_at: parse_timestamp_or_now(row.get(5)?),
+        updated_at: parse_timestamp_or_now(row.get(6)?),
+    }))
+}
+
+fn external_mcp_server_from_row(row: &rusqlite::Row) -> rusqlite::Result<ExternalMcpServer> {
+    fn json_column<T: serde::de::DeserializeOwned + Default>(raw: String) -> T {
+        serde_json::from_str(&raw).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to parse MCP server JSON: {}. Falling back to empty.",
+                e
+            );
+            T::default()
+        })
+    }
+
+    let transport: String = row.get(2)?;
+    let transport = McpTransport::from_str(&transport).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(ExternalMcpServer {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        spec: McpServerSpec {
+            transport,
+            command: row.get(3)?,
+            args: json_column(row.get(4)?),
+            env: json_column(row.get(5)?),
+            url: row.get(6)?,
+            headers: json_column(row.get(7)?),
+        },
+        enabled_adapters: json_column(row.get(8)?),
+        enabled: row.get(9)?,
+        created_at: parse_timestamp_or_now(row.get(10)?),
+        updated_at: parse_timestamp_or_now(row.get(11)?),
+    })
+}
+
+fn workflow_from_row(row: &rusqlite::Row) -> rusqlite::Result<Workflow> {
+    fn json_column<T: serde::de::DeserializeOwned>(
+        row: &rusqlite::Row,
+        index: usize,
+    ) -> rusqlite::Result<T> {
+        let raw: String = row.get(index)?;
+        serde_json::from_str(&raw).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                index,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+            )
+        })
+    }
+
+    Ok(Workflow {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        steps: json_column(row, 3)?,
+        arguments: json_column(row, 4)?,
+        expose_via_mcp: row.get(5)?,
+        generate_slash_commands: row.get(6)?,
+        slash_command_adapters: json_column(row, 7)?,
+        created_at: parse_timestamp_or_now(row.get(8)?),
+        updated_at: parse_timestamp_or_now(row.get(9)?),
+    })
+}
+
+/// Tool configs key MCP servers by name, so names must be unique ignoring case.
+fn ensure_mcp_server_name_free(
+    conn: &Connection,
+    name: &str,
+    except_id: Option<&str>,
+) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput {
+            message: "MCP server name cannot be empty".to_string(),
+        });
+    }
+    let taken: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM external_mcp_servers WHERE name = ? COLLATE NOCASE AND id IS NOT ?",
+        params![name, except_id],
+        |row| row.get(0),
+    )?;
+    if taken > 0 {
+        return Err(AppError::InvalidInput {
+            message: format!("An MCP server named '{}' already exists", name),
+        });
+    }
+    Ok(())
+}
+
+fn collection_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<CollectionItem> {
+    let item_type_str: String = row.get(1)?;
+    let item_type = CollectionItemType::from_str(&item_type_str).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(CollectionItem {
+        collection_id: row.get(0)?,
+        item_type,
+        item_id: row.get(2)?,
+        position: row.get(3)?,
+    })
+}
+
+fn ensure_collection_exists(conn: &Connection, id: &str) -> Result<()> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM collections WHERE id = ?",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if count == 0 {
+        return Err(AppError::CollectionNotFound { id: id.to_string() });
+    }
+    Ok(())
+}
+
+/// Returns true when `candidate` is `ancestor` or sits somewhere below it.
+fn collection_is_within(conn: &Connection, candidate: &str, ancestor: &str) -> Result<bool> {
+    let mut current = Some(candidate.to_string());
+    let mut visited = std::collections::HashSet::new();
+    while let Some(id) = current {
+        if id == ancestor {
+            return Ok(true);
+        }
+        if !visited.insert(id.clone()) {
+            break;
+        }
+        current = conn
+            .query_row(
+                "SELECT parent_id FROM collections WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+    }
+    Ok(false)
+}
+
+fn run_migrations(conn: &mut Connection) -> Result<()> {
+    let transaction = conn.transaction()?;
+
+    let current_version: i32 = transaction
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap_or(0);
+    check_schema_compatibility(current_version)?;
+
+    if current_version < 1 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS rules (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                scope TEXT NOT NULL,
+                target_paths TEXT,
+                enabled_adapters TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS sync_history (
+                file_path TEXT PRIMARY KEY NOT NULL,
+                content_hash TEXT NOT NULL,
+                last_sync_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS sync_logs (
+                id TEXT PRIMARY KEY NOT NULL,
+                timestamp INTEGER NOT NULL,
+                files_written INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                triggered_by TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY NOT NULL,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_rules_scope ON rules(scope)",
+            [],
+        )?;
+    }
+
+    if current_version < 2 {
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sync_logs_timestamp ON sync_logs(timestamp)",
+            [],
+        )?;
+    }
+
+    if current_version < 3 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS rule_file_index (
+                rule_id TEXT PRIMARY KEY NOT NULL,
+                file_path TEXT NOT NULL,
+                content_hash TEXT,
+                last_modified INTEGER
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_rule_file_index_path ON rule_file_index(file_path)",
+            [],
+        )?;
+    }
+
+    if current_version < 4 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS commands (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                script TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                expose_via_mcp INTEGER NOT NULL DEFAULT 1,
+                target_paths TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_commands_updated_at ON commands(updated_at)",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS execution_logs (
+                id TEXT PRIMARY KEY NOT NULL,
+                command_id TEXT NOT NULL,
+                command_name TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                stdout TEXT NOT NULL,
+                stderr TEXT NOT NULL,
+                exit_code INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                executed_at INTEGER NOT NULL,
+                triggered_by TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_execution_logs_executed_at ON execution_logs(executed_at)",
+            [],
+        )?;
+    }
+
+    if current_version < 5 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS skills (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                instructions TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_skills_updated_at ON skills(updated_at)",
+            [],
+        )?;
+    }
+
+    if current_version < 6 {
+        let mut stmt = transaction.prepare("PRAGMA table_info(skills)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "input_schema") {
+            transaction.execute(
+                "ALTER TABLE skills ADD COLUMN input_schema TEXT NOT NULL DEFAULT '[]'",
+                [],
+            )?;
+        }
+    }
+
+    if current_version < 7 {
+        let mut stmt = transaction.prepare("PRAGMA table_info(skills)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "directory_path") {
+            transaction.execute(
+                "ALTER TABLE skills ADD COLUMN directory_path TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+        if !cols.iter().any(|c| c == "entry_point") {
+            transaction.execute(
+                "ALTER TABLE skills ADD COLUMN entry_point TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+    }
+
+    if current_version < 8 {
+        let mut stmt = transaction.prepare("PRAGMA table_info(skills)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "scope") {
+            transaction.execute(
+                "ALTER TABLE skills ADD COLUMN scope TEXT NOT NULL DEFAULT 'global'",
+                [],
+            )?;
+        }
+    }
+
+    if current_version < 9 {
+        // Add slash command support columns to commands table
+        let mut stmt = transaction.prepare("PRAGMA table_info(commands)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "generate_slash_commands") {
+            transaction.execute(
+                "ALTER TABLE commands ADD COLUMN generate_slash_commands INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+        if !cols.iter().any(|c| c == "slash_command_adapters") {
+            transaction.execute(
+                "ALTER TABLE commands ADD COLUMN slash_command_adapters TEXT NOT NULL DEFAULT '[]'",
+                [],
+            )?;
+        }
+    }
+
+    if current_version < 10 {
+        let mut stmt = transaction.prepare("PRAGMA table_info(commands)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "target_paths") {
+            transaction.execute(
+                "ALTER TABLE commands ADD COLUMN target_paths TEXT NOT NULL DEFAULT '[]'",
+                [],
+            )?;
+        }
+    }
+
+    if current_version < 11 {
+        let mut stmt = transaction.prepare("PRAGMA table_info(rules)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "description") {
+            transaction.execute(
+                "ALTER TABLE rules ADD COLUMN description TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+
+        let mut stmt = transaction.prepare("PRAGMA table_info(commands)")?;
+        let cols: Vec<String> = stmt
+            .query_map([], |row| row.get(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !cols.iter().any(|c| c == "is_placeholder") {
+            transaction.execute(
+                "ALTER TABLE commands ADD COLUMN is_placeholder INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+    }
+
+    if current_version < 12 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS reconciliation_logs (
+                id TEXT PRIMARY KEY NOT NULL,
+                timestamp INTEGER NOT NULL,
+                operation TEXT NOT NULL,
+                artifact_type TEXT,
+                adapter TEXT,
+                scope TEXT,
+                path TEXT NOT NULL,
+                result TEXT NOT NULL,
+                error_message TEXT
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_reconciliation_logs_timestamp ON reconciliation_logs(timestamp)",
+            [],
+        )?;
+    }
+
+    if current_version < 13 {
+        add_column_if_missing(
+            &transaction,
+            "skills",
+            "target_adapters",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+        add_column_if_missing(
+            &transaction,
+            "skills",
+            "target_paths",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+    }
+
+    if current_version < 14 {
+        add_column_if_missing(&transaction, "commands", "timeout_ms", "INTEGER")?;
+        add_column_if_missing(&transaction, "commands", "max_retries", "INTEGER")?;
+    }
+
+    if current_version < 15 {
+        add_column_if_missing(&transaction, "execution_logs", "failure_class", "TEXT")?;
+        add_column_if_missing(&transaction, "execution_logs", "adapter_context", "TEXT")?;
+        add_column_if_missing(
+            &transaction,
+            "execution_logs",
+            "is_redacted",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        add_column_if_missing(
+            &transaction,
+            "execution_logs",
+            "attempt_number",
+            "INTEGER NOT NULL DEFAULT 1",
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_execution_logs_command_id ON execution_logs(command_id)",
+            [],
+        )?;
+    }
+
+    if current_version < 16 {
+        add_column_if_missing(&transaction, "commands", "base_path", "TEXT")?;
+        add_column_if_missing(&transaction, "skills", "base_path", "TEXT")?;
+    }
+
+    if current_version < 17 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS collections (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                parent_id TEXT,
+                position INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_collections_parent_id ON collections(parent_id)",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS collection_items (
+                item_type TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                collection_id TEXT NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (item_type, item_id)
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_collection_items_collection_id ON collection_items(collection_id)",
+            [],
+        )?;
+    }
+
+    if current_version < 18 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS rule_sync_stats (
+                rule_id TEXT NOT NULL,
+                adapter TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                bytes INTEGER NOT NULL DEFAULT 0,
+                last_synced_at INTEGER NOT NULL,
+                PRIMARY KEY (rule_id, adapter, file_path)
+            )",
+            [],
+        )?;
+
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_rule_sync_stats_file ON rule_sync_stats(adapter, file_path)",
+            [],
+        )?;
+    }
+
+    if current_version < 19 {
+        for table in ["rules", "commands", "skills"] {
+            add_column_if_missing(
+                &transaction,
+                table,
+                "metadata",
+                "TEXT NOT NULL DEFAULT '{}'",
+            )?;
+        }
+    }
+
+    if current_version < 20 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS external_mcp_servers (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                transport TEXT NOT NULL DEFAULT 'stdio',
+                command TEXT,
+                args TEXT NOT NULL DEFAULT '[]',
+                env TEXT NOT NULL DEFAULT '{}',
+                url TEXT,
+                headers TEXT NOT NULL DEFAULT '{}',
+                enabled_adapters TEXT NOT NULL DEFAULT '[]',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 21 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS secrets (
+                name TEXT PRIMARY KEY NOT NULL,
+                value BLOB NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        add_column_if_missing(
+            &transaction,
+            "commands",
+            "required_secrets",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+    }
+
+    if current_version < 22 {
+        add_column_if_missing(&transaction, "commands", "working_dir", "TEXT")?;
+        add_column_if_missing(&transaction, "commands", "shell", "TEXT")?;
+    }
+
+    if current_version < 23 {
+        add_column_if_missing(
+            &transaction,
+            "commands",
+            "requires_approval",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS approval_audit (
+                id TEXT PRIMARY KEY NOT NULL,
+                command_id TEXT NOT NULL,
+                command_name TEXT NOT NULL,
+                arguments_json TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                requested_at INTEGER NOT NULL,
+                decided_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_approval_audit_decided_at ON approval_audit(decided_at)",
+            [],
+        )?;
+    }
+
+    if current_version < 24 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS workflows (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                steps TEXT NOT NULL DEFAULT '[]',
+                arguments TEXT NOT NULL DEFAULT '[]',
+                expose_via_mcp INTEGER NOT NULL DEFAULT 0,
+                generate_slash_commands INTEGER NOT NULL DEFAULT 0,
+                slash_command_adapters TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 25 {
+        add_column_if_missing(&transaction, "commands", "schedule", "TEXT")?;
+    }
+
+    if current_version < 26 {
+        add_column_if_missing(&transaction, "commands", "sandbox", "TEXT")?;
+    }
+
+    if current_version < 27 {
+        add_column_if_missing(
+            &transaction,
+            "commands",
+            "artifact_globs",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+        add_column_if_missing(&transaction, "execution_logs", "artifacts_dir", "TEXT")?;
+    }
+
+    if current_version < 28 {
+        add_column_if_missing(&transaction, "commands", "output_parser", "TEXT")?;
+        add_column_if_missing(&transaction, "execution_logs", "structured_output", "TEXT")?;
+    }
+
+    if current_version < 29 {
+        add_column_if_missing(
+            &transaction,
+            "execution_logs",
+            "environment_snapshot",
+            "TEXT",
+        )?;
+    }
+
+    if current_version < 30 {
+        add_column_if_missing(
+            &transaction,
+            "commands",
+            "accepts_stdin",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+    }
+
+    if current_version < 31 {
+        add_column_if_missing(
+            &transaction,
+            "skills",
+            "bundle_files",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+    }
+
+    if current_version < 32 {
+        add_column_if_missing(
+            &transaction,
+            "skills",
+            "allowed_tools",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+        add_column_if_missing(&transaction, "skills", "license", "TEXT")?;
+    }
+
+    if current_version < 33 {
+        add_column_if_missing(
+            &transaction,
+            "skills",
+            "version",
+            "TEXT NOT NULL DEFAULT '1.0.0'",
+        )?;
+        add_column_if_missing(
+            &transaction,
+            "skills",
+            "version_pins",
+            "TEXT NOT NULL DEFAULT '[]'",
+        )?;
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS skill_versions (
+                skill_id TEXT NOT NULL,
+                version TEXT NOT NULL,
+                changelog TEXT NOT NULL DEFAULT '',
+                snapshot TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (skill_id, version)
+            )",
+            [],
+        )?;
+
+        // Existing skills start their history at their current content.
+        let existing: Vec<(String, SkillVersionContent, i64)> = {
+            let mut stmt = transaction.prepare(
+                "SELECT id, name, description, instructions, input_schema, entry_point, allowed_tools, license, updated_at FROM skills",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    let input_schema: String = row.get(4)?;
+                    let allowed_tools: String = row.get(6)?;
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        SkillVersionContent {
+                            name: row.get(1)?,
+                            description: row.get(2)?,
+                            instructions: row.get(3)?,
+                            input_schema: serde_json::from_str(&input_schema).unwrap_or_default(),
+                            entry_point: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                            allowed_tools: serde_json::from_str(&allowed_tools).unwrap_or_default(),
+                            license: row.get(7)?,
+                        },
+                        row.get::<_, i64>(8)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+        for (skill_id, content, updated_at) in existing {
+            insert_skill_version(
+                &transaction,
+                &skill_id,
+                &crate::models::default_skill_version(),
+                "",
+                &content,
+                updated_at,
+            )?;
+        }
+    }
+
+    if current_version < 34 {
+        add_column_if_missing(&transaction, "commands", "slash_namespace", "TEXT")?;
+    }
+
+    if current_version < 35 {
+        add_column_if_missing(&transaction, "commands", "slash_frontmatter", "TEXT")?;
+    }
+
+    if current_version < 36 {
+        add_column_if_missing(
+            &transaction,
+            "commands",
+            "slash_scope",
+            "TEXT NOT NULL DEFAULT 'global'",
+        )?;
+    }
+
+    if current_version < 37 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS scan_cache (
+                file_path TEXT PRIMARY KEY NOT NULL,
+                file_size INTEGER NOT NULL,
+                modified_ns INTEGER NOT NULL,
+                content_hash TEXT
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 38 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS webhooks (
+                id TEXT PRIMARY KEY NOT NULL,
+                url TEXT NOT NULL,
+                events TEXT NOT NULL DEFAULT '[]',
+                secret_name TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 39 {
+        add_column_if_missing(&transaction, "sync_logs", "report", "TEXT")?;
+    }
+
+    if current_version < 40 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id TEXT PRIMARY KEY NOT NULL,
+                timestamp INTEGER NOT NULL,
+                actor TEXT NOT NULL,
+                actor_detail TEXT,
+                action TEXT NOT NULL,
+                target_type TEXT,
+                target_id TEXT,
+                target_name TEXT,
+                summary TEXT NOT NULL
+            )",
+            [],
+        )?;
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log(timestamp)",
+            [],
+        )?;
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_target ON audit_log(target_type, target_id)",
+            [],
+        )?;
+    }
+
+    if current_version < 41 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS job_journal (
+                id TEXT PRIMARY KEY NOT NULL,
+                kind TEXT NOT NULL,
+                description TEXT NOT NULL,
+                status TEXT NOT NULL,
+                completed INTEGER NOT NULL DEFAULT 0,
+                total INTEGER NOT NULL DEFAULT 0,
+                payload TEXT NOT NULL,
+                checkpoint TEXT,
+                started_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 42 {
+        // Boolean settings were compared with "true"; store every spelling the typed
+        // settings accept in that form.
+        for key in crate::settings::bool_keys() {
+            let value: Option<String> = transaction
+                .query_row("SELECT value FROM settings WHERE key = ?", [key], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            match value.as_deref().map(crate::settings::parse_bool) {
+                Some(Some(enabled)) => {
+                    transaction.execute(
+                        "UPDATE settings SET value = ? WHERE key = ?",
+                        params![enabled.to_string(), key],
+                    )?;
+                }
+                Some(None) => {
+                    transaction.execute("DELETE FROM settings WHERE key = ?", [key])?;
+                }
+                None => {}
+            }
+        }
+    }
+
+    if current_version < 43 {
+        // Rows without a policy fall back to `expose_via_mcp` and `requires_approval`,
+        // which are still written so older releases keep working.
+        add_column_if_missing(&transaction, "commands", "mcp_policy", "TEXT")?;
+    }
+
+    if current_version < 44 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS rule_templates (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                placeholders TEXT NOT NULL DEFAULT '[]',
+                rule TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 45 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS snippets (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT NOT NULL DEFAULT '',
+                content TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+    }
+
+    if current_version < 46 {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS rule_feedback (
+                id TEXT PRIMARY KEY NOT NULL,
+                rule_id TEXT NOT NULL,
+                verdict TEXT NOT NULL,
+                session_id TEXT,
+                note TEXT,
+                reporter TEXT NOT NULL,
+                reporter_detail TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        transaction.execute(
+            "CREATE INDEX IF NOT EXISTS idx_rule_feedback_rule ON rule_feedback(rule_id, created_at)",
+            [],
+        )?;
+    }
+
+    if current_version < 47 {
+        // Full-text index of rules, kept current by triggers so every write path updates it.
+        // The insert trigger clears the rule's old entry too, as INSERT OR REPLACE does not
+        // fire the delete trigger.
+        transaction.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS rules_fts USING fts5(
+                rule_id UNINDEXED, name, description, content,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+            CREATE TRIGGER IF NOT EXISTS rules_fts_insert AFTER INSERT ON rules BEGIN
+                DELETE FROM rules_fts WHERE rule_id = new.id;
+                INSERT INTO rules_fts (rule_id, name, description, content)
+                VALUES (new.id, new.name, new.description, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS rules_fts_update AFTER UPDATE ON rules BEGIN
+                DELETE FROM rules_fts WHERE rule_id = old.id;
+                INSERT INTO rules_fts (rule_id, name, description, content)
+                VALUES (new.id, new.name, new.description, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS rules_fts_delete AFTER DELETE ON rules BEGIN
+                DELETE FROM rules_fts WHERE rule_id = old.id;
+            END;
+            DELETE FROM rules_fts;
+            INSERT INTO rules_fts (rule_id, name, description, content)
+            SELECT id, name, description, content FROM rules;
+            CREATE TABLE IF NOT EXISTS rule_embeddings (
+                rule_id TEXT PRIMARY KEY NOT NULL,
+                model TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                embedding BLOB NOT NULL
+            );",
+        )?;
+    }
+
+    transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Describes a rule update by the fields it changed.
+/// Reads a feedback row, skipping rows written by a newer version with an unknown verdict.
+fn rule_feedback_from_row(row: &rusqlite::Row) -> rusqlite::Result<Option<RuleFeedback>> {
+    let verdict: String = row.get(2)?;
+    let Ok(verdict) = RuleFeedbackVerdict::from_str(&verdict) else {
+        return Ok(None);
+    };
+    let reporter: String = row.get(5)?;
+    Ok(Some(RuleFeedback {
+        id: row.get(0)?,
+        rule_id: row.get(1)?,
+        verdict,
+        session_id: row.get(3)?,
+        note: row.get(4)?,
+        reporter: AuditActor {
+            kind: AuditActorKind::from_str(&reporter).unwrap_or(AuditActorKind::Ui),
+            detail: row.get(6)?,
+        },
+        created_at: parse_timestamp_or_now(row.get(7)?),
+    }))
+}
+
+/// Reads a journal row, skipping rows written by a newer version with an unknown kind.
+fn job_from_row(row: &rusqlite::Row) -> rusqlite::Result<Option<JobRecord>> {
+    let kind: String = row.get(1)?;
+    let status: String = row.get(3)?;
+    let payload: String = row.get(6)?;
+    let checkpoint: Option<String> = row.get(7)?;
+    let Ok(kind) = JobKind::from_str(&kind) else {
+        return Ok(None);
+    };
+    Ok(Some(JobRecord {
+        id: row.get(0)?,
+        kind,
+        description: row.get(2)?,
+        status: JobStatus::from_str(&status).unwrap_or(JobStatus::Interrupted),
+        completed: row.get(4)?,
+        total: row.get(5)?,
+        can_roll_back: kind.can_roll_back(),
+        started_at: parse_timestamp_or_now(row.get(8)?),
+        updated_at: parse_timestamp_or_now(row.get(9)?),
+        payload: serde_json::from_str(&payload).unwrap_or_default(),
+        checkpoint: checkpoint
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default(),
+    }))
+}
+
+/// `existing` with the fields `input` sets replaced.
+fn apply_rule_update(existing: Rule, input: UpdateRuleInput) -> Rule {
+    Rule {
+        name: input.name.unwrap_or(existing.name),
+        description: input.description.unwrap_or(existing.description),
+        content: input.content.unwrap_or(existing.content),
+        scope: input.scope.unwrap_or(existing.scope),
+        target_paths: input.target_paths.or(existing.target_paths),
+        enabled_adapters: input.enabled_adapters.unwrap_or(existing.enabled_adapters),
+        enabled: input.enabled.unwrap_or(existing.enabled),
+        metadata: input.metadata.unwrap_or(existing.metadata),
+        ..existing
+    }
+}
+
+/// Stores every field of `rule` over the row with its id.
+fn write_rule(conn: &Connection, rule: &Rule, updated_at: i64) -> Result<()> {
+    let target_paths_json = rule
+        .target_paths
+        .as_ref()
+        .map(|p| serde_json::to_string(p).unwrap_or_default());
+    let enabled_adapters_json = serde_json::to_string(&rule.enabled_adapters)?;
+    conn.execute(
+        "UPDATE rules SET name = ?, description = ?, content = ?, scope = ?, target_paths = ?, enabled_adapters = ?, enabled = ?, updated_at = ?, metadata = ?
+         WHERE id = ?",
+        params![
+            rule.name,
+            rule.description,
+            rule.content,
+            rule.scope.as_str(),
+            target_paths_json,
+            enabled_adapters_json,
+            rule.enabled,
+            updated_at,
+            rule.metadata.to_json(),
+            rule.id
+        ],
+    )?;
+    Ok(())
+}
+
+fn rule_update_summary(before: &Rule, after: &Rule) -> String {
+    let changed: Vec<&str> = [
+        ("name", before.name != after.name),
+        ("description", before.description != after.description),
+        ("content", before.content != after.content),
+        ("scope", before.scope != after.scope),
+        ("target paths", before.target_paths != after.target_paths),
+        (
+            "adapters",
+            before.enabled_adapters != after.enabled_adapters,
+        ),
+        ("enabled", before.enabled != after.enabled),
+        ("metadata", before.metadata != after.metadata),
+    ]
+    .into_iter()
+    .filter_map(|(field, changed)| changed.then_some(field))
+    .collect();
+
+    if changed.is_empty() {
+        format!("Saved rule '{}' without changes", after.name)
+    } else {
+        format!("Updated rule '{}': {}", after.name, changed.join(", "))
+    }
+}
+
+fn insert_skill_version(
+    conn: &Connection,
+    skill_id: &str,
+    version: &str,
+    changelog: &str,
+    content: &SkillVersionContent,
+    created_at: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO skill_versions (skill_id, version, changelog, snapshot, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+        params![
+            skill_id,
+            version,
+            changelog,
+            serde_json::to_string(content)?,
+            created_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// The version an update records, if any: the requested one, which must be newer than
+/// `current`, or the next patch version when the content changed.
+fn next_skill_version(
+    current: &str,
+    requested: Option<&str>,
+    content_changed: bool,
+) -> Result<Option<String>> {
+    match requested.map(str::trim).filter(|v| *v != current) {
+        Some(requested) => {
+            let parsed = crate::models::parse_skill_version(requested)?;
+            if semver::Version::parse(current).is_ok_and(|current| parsed <= current) {
+                return Err(AppError::Validation(format!(
+                    "Skill version {} must be greater than the current version {}",
+                    parsed, current
+                )));
+            }
+            Ok(Some(parsed.to_string()))
+        }
+        None if content_changed => Ok(Some(crate::models::next_patch_version(current))),
+        None => Ok(None),
+    }
+}
+
+fn add_column_if_missing(
+    transaction: &rusqlite::Transaction,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let mut stmt = transaction.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|c| c.as_ref().map(|s| s == column).unwrap_or(false));
+
+    if !exists {
+        transaction.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// The app's data directory for the active workspace.
+pub fn get_app_data_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| crate::workspace::scoped_dir(&dir))
+        .map_err(|e| AppError::Path(e.to_string()))
+}
+
+/// RuleWeaver's data directory, shared by every workspace.
+pub fn base_app_data_dir() -> Result<PathBuf> {
+    let base = dirs::data_local_dir()
+        .or_else(dirs::data_dir)
+        .ok_or_else(|| AppError::Path("Could not determine data directory".to_string()))?;
+    Ok(base.join("RuleWeaver"))
+}
+
+/// RuleWeaver's data directory for the active workspace.
+pub fn default_app_data_dir() -> Result<PathBuf> {
+    Ok(crate::workspace::scoped_dir(&base_app_data_dir()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        CreateCommandInput, CreateSkillInput, SkillParameter, SkillParameterType,
+        UpdateCommandInput, UpdateSkillInput,
+    };
+
+    #[tokio::test]
+    async fn test_skill_crud() {
+        let db = Database::new_in_memory().await.unwrap();
+
+        // 1. Create
+        let input = CreateSkillInput {
+            id: None,
+            name: "Test Skill".to_string(),
+            description: "A test skill".to_string(),
+            instructions: "echo 'hello'".to_string(),
+            input_schema: vec![SkillParameter {
+                name: "param1".to_string(),
+                description: "desc".to_string(),
+                param_type: SkillParameterType::String,
+                required: true,
+                default_value: None,
+                enum_values: None,
+            }],
+            directory_path: "/test/path".to_string(),
+            entry_point: "main.sh".to_string(),
+            scope: Scope::Global,
+            enabled: true,
+            ..Default::default()
+        };
+
+        let created = db.create_skill(input.clone()).await.unwrap();
+        assert_eq!(created.name, "Test Skill");
+        assert_eq!(created.input_schema.len(), 1);
+        assert_eq!(created.directory_path, "/test/path");
+
+        // 2. Read
+        let fetched = db.get_skill_by_id(&created.id).await.unwrap();
+        assert_eq!(created.id, fetched.id);
+        assert_eq!(fetched.entry_point, "main.sh");
+
+        let all = db.get_all_skills().await.unwrap();
+        assert_eq!(all.len(), 1);
+
+        // 3. Update
+        let update_input = UpdateSkillInput {
+            name: Some("Updated Skill".to_string()),
+            ..Default::default()
+        };
+        let updated = db.update_skill(&created.id, update_input).await.unwrap();
+        assert_eq!(updated.name, "Updated Skill");
+        // Ensure other fields remain unchanged
+        assert_eq!(updated.directory_path, "/test/path");
+
+        // 4. Delete
+        db.delete_skill(&created.id).await.unwrap();
+        assert!(db.get_skill_by_id(&created.id).await.is_err());
+        assert_eq!(db.get_all_skills().await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_command_target_paths_roundtrip() {
+        let db = Database::new_in_memory().await.unwrap();
+
+        let created = db
+            .create_command(CreateCommandInput {
+                id: None,
+                name: "Build".to_string(),
+                description: "Run build".to_string(),
+                script: "npm run build".to_string(),
+                arguments: vec![],
+                mcp_policy: Default::default(),
+                is_placeholder: false,
+                generate_slash_commands: false,
+                slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
+                target_paths: vec!["C:/repo-a".to_string()],
+                base_path: None,
+                timeout_ms: None,
+                max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(created.target_paths, vec!["C:/repo-a".to_string()]);
+
+        let updated = db
+            .update_command(
+                &created.id,
+                UpdateCommandInput {
+                    target_paths: Some(vec!["C:/repo-b".to_string(), "C:/repo-c".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            updated.target_paths,
+            vec!["C:/repo-b".to_string(), "C:/repo-c".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn secrets_are_listed_without_values() {
+        let db = Database::new_in_memory().await.unwrap();
+        db.upsert_secret("API_TOKEN", vec![1, 2, 3]).await.unwrap();
+        db.upsert_secret("API_TOKEN", vec![4, 5]).await.unwrap();
+
+        let secrets = db.get_all_secrets().await.unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].name, "API_TOKEN");
+        assert_eq!(
+            db.get_secret_ciphertext("API_TOKEN").await.unwrap(),
+            Some(vec![4, 5])
+        );
+
+        db.delete_secret("API_TOKEN").await.unwrap();
+        assert_eq!(db.get_secret_ciphertext("API_TOKEN").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_file_database_uses_wal_and_read_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_with_db_path(dir.path().join("ruleweaver.db"))
+            .await
+            .unwrap();
+        assert_eq!(db.readers.len(), DB_READ_POOL_SIZE);
+
+        let journal_mode: String = db
+            .read(|conn| Ok(conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?))
+            .await
+            .unwrap();
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        let created = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "WAL rule".to_string(),
+                description: String::new(),
+                content: "content".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+
+        // Reads go through the pool and must observe the committed write.
+        let all = db.get_all_rules().await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].id, created.id);
+
+        // Read-only connections reject writes.
+        let reader = Arc::clone(&db.readers[0]);
+        assert!(reader.lock().execute("DELETE FROM rules", []).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_keeps_pre_migration_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("ruleweaver.db");
+        {
+            let (writer, _) = open_file_connections(&db_path).unwrap();
+            writer
+                .pragma_update(None, "user_version", SCHEMA_VERSION - 1)
+                .unwrap();
+        }
+
+        let (writer, _) = open_file_connections(&db_path).unwrap();
+        assert_eq!(schema_version(&writer).unwrap(), SCHEMA_VERSION);
+
+        let backups = crate::backup::list_backups(&crate::backup::backup_dir(dir.path())).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0]
+            .path
+            .ends_with(&format!(".v{}.db", SCHEMA_VERSION - 1)));
+        let backup = Connection::open(&backups[0].path).unwrap();
+        assert_eq!(schema_version(&backup).unwrap(), SCHEMA_VERSION - 1);
+
+        // Opening an up-to-date database takes no further backups.
+        drop(writer);
+        open_file_connections(&db_path).unwrap();
+        assert_eq!(
+            crate::backup::list_backups(&crate::backup::backup_dir(dir.path()))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused_not_downgraded() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("ruleweaver.db");
+        {
+            let (writer, _) = open_file_connections(&db_path).unwrap();
+            writer
+                .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+                .unwrap();
+        }
+
+        let err = open_file_connections(&db_path).unwrap_err();
+        assert!(matches!(err, AppError::Migration { .. }));
+        assert!(validate_backup_file(&db_path).is_err());
+
+        let conn = Connection::open(&db_path).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION + 1);
+    }
+
+    #[tokio::test]
+    async fn test_enable_and_disable_encryption() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("ruleweaver.db");
+        let db = Database::new_with_db_path(db_path.clone()).await.unwrap();
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Confidential rule".to_string(),
+            description: String::new(),
+            content: "internal project details".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+
+        let key = DatabaseKey::generate().unwrap();
+        db.enable_encryption(key.clone()).await.unwrap();
+        assert!(db.is_encrypted());
+        assert!(!db_path.exists());
+        let enc_path = encrypted_path(&db_path);
+        let sealed = std::fs::read(&enc_path).unwrap();
+        assert!(!sealed
+            .windows(b"internal project".len())
+            .any(|w| w == b"internal project"));
+
+        // Writes after enabling are persisted to the encrypted image.
+        db.set_setting("after_encryption", "yes").await.unwrap();
+        assert_eq!(db.get_all_rules().await.unwrap().len(), 1);
+        drop(db);
+
+        let reopened = Database::open_encrypted(db_path.clone(), key)
+            .await
+            .unwrap();
+        assert_eq!(reopened.get_all_rules().await.unwrap().len(), 1);
+        assert_eq!(
+            reopened.get_setting("after_encryption").await.unwrap(),
+            Some("yes".to_string())
+        );
+        assert!(
+            Database::open_encrypted(db_path.clone(), DatabaseKey::generate().unwrap())
+                .await
+                .is_err()
+        );
+
+        reopened.disable_encryption().await.unwrap();
+        assert!(!reopened.is_encrypted());
+        assert!(db_path.exists());
+        assert!(!enc_path.exists());
+        assert_eq!(reopened.get_all_rules().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_reads_during_enable_encryption_see_the_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(
+            Database::new_with_db_path(dir.path().join("ruleweaver.db"))
+                .await
+                .unwrap(),
+        );
+        db.set_setting("visible", "yes").await.unwrap();
+
+        let readers: Vec<_> = (0..32)
+            .map(|_| {
+                let db = Arc::clone(&db);
+                tokio::spawn(async move { db.get_setting("visible").await })
+            })
+            .collect();
+        db.enable_encryption(DatabaseKey::generate().unwrap())
+            .await
+            .unwrap();
+        for reader in readers {
+            assert_eq!(reader.await.unwrap().unwrap(), Some("yes".to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enable_encryption_seals_backups_and_purges_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("ruleweaver.db");
+        let db = Database::new_with_db_path(db_path.clone()).await.unwrap();
+        let snapshots = dir.path().join("snapshots");
+        db.set_setting(
+            crate::snapshot::EXPORT_SNAPSHOT_DIR_KEY,
+            snapshots.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+        db.set_setting("before_encryption", "yes").await.unwrap();
+        let backup = crate::backup::create_backup(&db, &crate::backup::backup_dir(dir.path()))
+            .await
+            .unwrap();
+        crate::snapshot::create_snapshot(&db, &snapshots)
+            .await
+            .unwrap();
+
+        let key = DatabaseKey::generate().unwrap();
+        db.enable_encryption(key).await.unwrap();
+
+        let backup_path = PathBuf::from(&backup.path);
+        assert!(encryption::is_encrypted_file(&backup_path).unwrap());
+        assert!(crate::snapshot::list_snapshots(&snapshots)
+            .unwrap()
+            .is_empty());
+        assert!(crate::snapshot::create_snapshot(&db, &snapshots)
+            .await
+            .is_err());
+
+        // Sealed backups still restore.
+        db.set_setting("before_encryption", "no").await.unwrap();
+        db.restore_from(backup_path).await.unwrap();
+        assert_eq!(
+            db.get_setting("before_encryption").await.unwrap(),
+            Some("yes".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_rolls_back() {
+        let db = Database::new_in_memory().await.unwrap();
+        let result: Result<()> = db
+            .write(|conn| {
+                conn.execute(
+                    "INSERT INTO settings (key, value) VALUES ('partial', 'yes')",
+                    [],
+                )?;
+                Err(AppError::Internal {
+                    message: "write failed".to_string(),
+                })
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(db.get_setting("partial").await.unwrap(), None);
+
+        db.set_setting("after_failure", "yes").await.unwrap();
+        assert_eq!(
+            db.get_setting("after_failure").await.unwrap(),
+            Some("yes".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_roundtrip_and_update() {
+        let db = Database::new_in_memory().await.unwrap();
+        let mut metadata = Metadata::new();
+        metadata.insert("owner", "platform");
+        metadata.insert("reviewDate", "2026-03-01");
+
+        let rule = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "Owned".to_string(),
+                description: String::new(),
+                content: "content".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: metadata.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(rule.metadata, metadata);
+
+        // Updates that leave metadata unset keep the stored fields.
+        let renamed = db
+            .update_rule(
+                &rule.id,
+                UpdateRuleInput {
+                    name: Some("Renamed".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(renamed.metadata.get_str("owner"), Some("platform"));
+
+        metadata.remove("reviewDate");
+        let updated = db
+            .update_rule(
+                &rule.id,
+                UpdateRuleInput {
+                    metadata: Some(metadata.clone()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.metadata, metadata);
+
+        let command = db
+            .create_command(CreateCommandInput {
+                name: "Tagged".to_string(),
+                script: "echo hi".to_string(),
+                metadata: metadata.clone(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_command_by_id(&command.id).await.unwrap().metadata,
+            metadata
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rule_stats_track_latest_sync_per_file() {
+        let db = Database::new_in_memory().await.unwrap();
+        let mut ids = Vec::new();
+        for name in ["Active", "Dropped", "Never synced"] {
+            let rule = db
+                .create_rule(CreateRuleInput {
+                    id: None,
+                    name: name.to_string(),
+                    description: String::new(),
+                    content: "content".to_string(),
+                    scope: Scope::Global,
+                    target_paths: None,
+                    enabled_adapters: vec![AdapterType::Gemini],
+                    enabled: true,
+                    metadata: Default::default(),
+                })
+                .await
+                .unwrap();
+            ids.push(rule.id);
+        }
+
+        db.record_rule_sync(
+            AdapterType::Gemini,
+            "/tmp/GEMINI.md",
+            vec![(ids[0].clone(), 100), (ids[1].clone(), 40)],
+        )
+        .await
+        .unwrap();
+        db.record_rule_sync(
+            AdapterType::Cursor,
+            "/tmp/.cursorrules",
+            vec![(ids[0].clone(), 60)],
+        )
+        .await
+        .unwrap();
+        // Re-syncing the Gemini file without the second rule drops its entry.
+        db.record_rule_sync(
+            AdapterType::Gemini,
+            "/tmp/GEMINI.md",
+            vec![(ids[0].clone(), 110)],
+        )
+        .await
+        .unwrap();
+
+        let stats = db.get_rule_stats().await.unwrap();
+        let by_id = |id: &str| stats.iter().find(|s| s.rule_id == id).unwrap();
+
+        let active = by_id(&ids[0]);
+        assert_eq!(active.total_bytes, 170);
+        assert_eq!(active.files.len(), 2);
+        assert!(active.adapters.contains(&AdapterType::Gemini));
+        assert!(active.adapters.contains(&AdapterType::Cursor));
+        assert!(active.last_synced_at.is_some());
+
+        assert!(by_id(&ids[1]).files.is_empty());
+        assert!(by_id(&ids[2]).last_synced_at.is_none());
+
+        let mut times = db.get_adapter_sync_times().await.unwrap();
+        times.sort_by_key(|(adapter, _, _)| adapter.as_str());
+        assert_eq!(times.len(), 2);
+        assert_eq!(times[0].0, AdapterType::Cursor);
+        assert_eq!(times[0].2, 1);
+        assert_eq!(times[1].0, AdapterType::Gemini);
+
+        db.delete_rule(&ids[0]).await.unwrap();
+        assert_eq!(db.get_rule_stats().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rule_feedback_is_counted_per_rule() {
+        let db = Database::new_in_memory().await.unwrap();
+        let rule = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "Reviewed".to_string(),
+                description: String::new(),
+                content: "content".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+        let report = |verdict| RuleFeedbackInput {
+            rule_id: rule.id.clone(),
+            verdict,
+            session_id: Some("session-1".to_string()),
+            note: None,
+        };
+
+        db.add_rule_feedback(report(RuleFeedbackVerdict::Helpful))
+            .await
+            .unwrap();
+        db.add_rule_feedback(report(RuleFeedbackVerdict::Helpful))
+            .await
+            .unwrap();
+        crate::audit::scope(
+            AuditActor::with_detail(AuditActorKind::Mcp, "claude-code"),
+            db.add_rule_feedback(report(RuleFeedbackVerdict::Harmful)),
+        )
+        .await
+        .unwrap();
+
+        let stats = db.get_rule_stats().await.unwrap();
+        let feedback = &stats[0].feedback;
+        assert_eq!(
+            (feedback.helpful, feedback.ignored, feedback.harmful),
+            (2, 0, 1)
+        );
+        assert!(feedback.last_reported_at.is_some());
+
+        let reports = db.get_rule_feedback(&rule.id, 2).await.unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].verdict, RuleFeedbackVerdict::Harmful);
+        assert_eq!(reports[0].reporter.kind, AuditActorKind::Mcp);
+        assert_eq!(reports[1].reporter.kind, AuditActorKind::Ui);
+
+        db.delete_rule(&rule.id).await.unwrap();
+        assert!(db.get_rule_feedback(&rule.id, 10).await.unwrap().is_empty());
     }
 
-    if current_version < 14 {
-        add_column_if_missing(&transaction, "commands", "timeout_ms", "INTEGER")?;
-        add_column_if_missing(&transaction, "commands", "max_retries", "INTEGER")?;
+    #[tokio::test]
+    async fn test_update_rules_writes_all_or_none() {
+        let db = Database::new_in_memory().await.unwrap();
+        let mut ids = Vec::new();
+        for name in ["One", "Two"] {
+            let rule = db
+                .create_rule(CreateRuleInput {
+                    id: None,
+                    name: name.to_string(),
+                    description: String::new(),
+                    content: "content".to_string(),
+                    scope: Scope::Global,
+                    target_paths: None,
+                    enabled_adapters: vec![AdapterType::Gemini],
+                    enabled: true,
+                    metadata: Default::default(),
+                })
+                .await
+                .unwrap();
+            ids.push(rule.id);
+        }
+        let disable = UpdateRuleInput {
+            enabled: Some(false),
+            ..Default::default()
+        };
+
+        let missing = vec![
+            (ids[0].clone(), disable.clone()),
+            ("missing".to_string(), disable.clone()),
+        ];
+        assert!(db.update_rules(missing).await.is_err());
+        assert!(db.get_rule_by_id(&ids[0]).await.unwrap().enabled);
+
+        let updates = ids.iter().map(|id| (id.clone(), disable.clone())).collect();
+        let updated = db.update_rules(updates).await.unwrap();
+        assert!(updated.iter().all(|rule| !rule.enabled));
+        assert_eq!(updated[1].name, "Two");
+        assert_eq!(updated[1].content, "content");
     }
 
-    if current_version < 15 {
-        add_column_if_missing(&transaction, "execution_logs", "failure_class", "TEXT")?;
-        add_column_if_missing(&transaction, "execution_logs", "adapter_context", "TEXT")?;
-        add_column_if_missing(
-            &transaction,
-            "execution_logs",
-            "is_redacted",
-            "INTEGER NOT NULL DEFAULT 0",
-        )?;
-        add_column_if_missing(
-            &transaction,
-            "execution_logs",
-            "attempt_number",
-            "INTEGER NOT NULL DEFAULT 1",
-        )?;
+    #[tokio::test]
+    async fn test_rule_changes_are_published() {
+        let db = Database::new_in_memory().await.unwrap();
+        let mut events = change_events::subscribe();
+        let rule = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "Published".to_string(),
+                description: String::new(),
+                content: "content".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+        db.toggle_rule(&rule.id, false).await.unwrap();
+        // Skipped because the rule exists, so nothing changes
+        db.import_rule(rule.clone(), crate::models::ImportMode::Skip)
+            .await
+            .unwrap();
+        db.delete_rule(&rule.id).await.unwrap();
+        db.import_rule(rule.clone(), crate::models::ImportMode::Skip)
+            .await
+            .unwrap();
 
-        transaction.execute(
-            "CREATE INDEX IF NOT EXISTS idx_execution_logs_command_id ON execution_logs(command_id)",
-            [],
-        )?;
+        // Other tests publish to the same channel
+        let mut names = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if event.id == rule.id {
+                names.push(event.name());
+            }
+        }
+        assert_eq!(
+            names,
+            vec![
+                "rule-created",
+                "rule-updated",
+                "rule-deleted",
+                "rule-created"
+            ]
+        );
     }
 
-    if current_version < 16 {
-        add_column_if_missing(&transaction, "commands", "base_path", "TEXT")?;
-        add_column_if_missing(&transaction, "skills", "base_path", "TEXT")?;
+    fn collection_input(name: &str, parent_id: Option<&str>) -> CreateCollectionInput {
+        CreateCollectionInput {
+            id: None,
+            name: name.to_string(),
+            parent_id: parent_id.map(|p| p.to_string()),
+        }
     }
 
-    transaction.execute("PRAGMA user_version = 16", [])?;
-    transaction.commit()?;
+    #[tokio::test]
+    async fn test_collection_nesting_and_cycle_prevention() {
+        let db = Database::new_in_memory().await.unwrap();
 
-    Ok(())
-}
+        let root = db
+            .create_collection(collection_input("Root", None))
+            .await
+            .unwrap();
+        let child = db
+            .create_collection(collection_input("Child", Some(&root.id)))
+            .await
+            .unwrap();
+        let grandchild = db
+            .create_collection(collection_input("Grandchild", Some(&child.id)))
+            .await
+            .unwrap();
+        assert_eq!(grandchild.parent_id.as_deref(), Some(child.id.as_str()));
 
-fn add_column_if_missing(
-    transaction: &rusqlite::Transaction,
-    table: &str,
-    column: &str,
-    definition: &str,
-) -> Result<()> {
-    let mut stmt = transaction.prepare(&format!("PRAGMA table_info({})", table))?;
-    let exists = stmt
-        .query_map([], |row| row.get::<_, String>(1))?
-        .any(|c| c.as_ref().map(|s| s == column).unwrap_or(false));
+        assert!(db
+            .move_collection(&root.id, Some(&grandchild.id), None)
+            .await
+            .is_err());
+        assert!(db
+            .move_collection(&root.id, Some(&root.id), None)
+            .await
+            .is_err());
 
-    if !exists {
-        transaction.execute(
-            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
-            [],
-        )?;
+        let moved = db
+            .move_collection(&grandchild.id, None, Some(0))
+            .await
+            .unwrap();
+        assert!(moved.parent_id.is_none());
+        assert_eq!(moved.position, 0);
+        assert_eq!(db.get_collection_by_id(&root.id).await.unwrap().position, 1);
+
+        assert!(db
+            .create_collection(collection_input("Orphan", Some("missing")))
+            .await
+            .is_err());
     }
-    Ok(())
-}
 
-pub fn get_app_data_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
-    app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| AppError::Path(e.to_string()))
-}
+    #[tokio::test]
+    async fn test_import_collection_validates_parents() {
+        use crate::models::{ExportConfiguration, ImportMode};
 
-pub fn default_app_data_dir() -> Result<PathBuf> {
-    let base = dirs::data_local_dir()
-        .or_else(dirs::data_dir)
-        .ok_or_else(|| AppError::Path("Could not determine data directory".to_string()))?;
-    Ok(base.join("RuleWeaver"))
-}
+        let db = Database::new_in_memory().await.unwrap();
+        let collection = |id: &str, parent_id: Option<&str>| Collection {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::{
-        CreateCommandInput, CreateSkillInput, SkillParameter, SkillParameterType,
-        UpdateCommandInput, UpdateSkillInput,
-    };
+        // Children listed before their parents still import.
+        let mut config = ExportConfiguration::new(Vec::new(), Vec::new(), Vec::new());
+        config.collections = vec![
+            collection("leaf", Some("middle")),
+            collection("middle", Some("top")),
+            collection("top", None),
+        ];
+        db.import_configuration(config, ImportMode::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_collection_by_id("leaf").await.unwrap().parent_id,
+            Some("middle".to_string())
+        );
+
+        assert!(db
+            .import_collection(collection("orphan", Some("missing")), ImportMode::Overwrite)
+            .await
+            .is_err());
+        assert!(db.get_collection_by_id("orphan").await.is_err());
+
+        assert!(db
+            .import_collection(collection("top", Some("leaf")), ImportMode::Overwrite)
+            .await
+            .is_err());
+        assert!(db
+            .import_collection(collection("top", Some("top")), ImportMode::Overwrite)
+            .await
+            .is_err());
+        assert!(db
+            .get_collection_by_id("top")
+            .await
+            .unwrap()
+            .parent_id
+            .is_none());
+
+        // Skipping an existing collection leaves it alone without checking the incoming parent.
+        db.import_collection(collection("top", Some("leaf")), ImportMode::Skip)
+            .await
+            .unwrap();
+    }
 
     #[tokio::test]
-    async fn test_skill_crud() {
+    async fn test_delete_collection_reparents_children_and_items() {
         let db = Database::new_in_memory().await.unwrap();
 
-        // 1. Create
-        let input = CreateSkillInput {
-            id: None,
-            name: "Test Skill".to_string(),
-            description: "A test skill".to_string(),
-            instructions: "echo 'hello'".to_string(),
-            input_schema: vec![SkillParameter {
-                name: "param1".to_string(),
-                description: "desc".to_string(),
-                param_type: SkillParameterType::String,
-                required: true,
-                default_value: None,
-                enum_values: None,
-            }],
-            directory_path: "/test/path".to_string(),
-            entry_point: "main.sh".to_string(),
-            scope: Scope::Global,
-            enabled: true,
-            ..Default::default()
-        };
+        let root = db
+            .create_collection(collection_input("Root", None))
+            .await
+            .unwrap();
+        let child = db
+            .create_collection(collection_input("Child", Some(&root.id)))
+            .await
+            .unwrap();
+        let nested = db
+            .create_collection(collection_input("Nested", Some(&child.id)))
+            .await
+            .unwrap();
 
-        let created = db.create_skill(input.clone()).await.unwrap();
-        assert_eq!(created.name, "Test Skill");
-        assert_eq!(created.input_schema.len(), 1);
-        assert_eq!(created.directory_path, "/test/path");
+        let rule = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "Filed rule".to_string(),
+                description: String::new(),
+                content: "content".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+        db.set_item_collection(CollectionItemType::Rule, &rule.id, Some(&child.id))
+            .await
+            .unwrap();
 
-        // 2. Read
-        let fetched = db.get_skill_by_id(&created.id).await.unwrap();
-        assert_eq!(created.id, fetched.id);
-        assert_eq!(fetched.entry_point, "main.sh");
+        db.delete_collection(&child.id).await.unwrap();
 
-        let all = db.get_all_skills().await.unwrap();
-        assert_eq!(all.len(), 1);
+        let nested = db.get_collection_by_id(&nested.id).await.unwrap();
+        assert_eq!(nested.parent_id.as_deref(), Some(root.id.as_str()));
+        let items = db.get_all_collection_items().await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].collection_id, root.id);
 
-        // 3. Update
-        let update_input = UpdateSkillInput {
-            name: Some("Updated Skill".to_string()),
-            ..Default::default()
+        db.delete_rule(&rule.id).await.unwrap();
+        assert!(db.get_all_collection_items().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn external_mcp_server_crud_enforces_unique_names() {
+        let db = Database::new_in_memory().await.unwrap();
+        let input = |name: &str| CreateExternalMcpServerInput {
+            name: name.to_string(),
+            spec: McpServerSpec {
+                command: Some("npx".to_string()),
+                args: vec!["-y".to_string(), "@mcp/github".to_string()],
+                ..Default::default()
+            },
+            enabled_adapters: vec![AdapterType::Cursor],
+            enabled: true,
         };
-        let updated = db.update_skill(&created.id, update_input).await.unwrap();
-        assert_eq!(updated.name, "Updated Skill");
-        // Ensure other fields remain unchanged
-        assert_eq!(updated.directory_path, "/test/path");
 
-        // 4. Delete
-        db.delete_skill(&created.id).await.unwrap();
-        assert!(db.get_skill_by_id(&created.id).await.is_err());
-        assert_eq!(db.get_all_skills().await.unwrap().len(), 0);
+        let server = db
+            .create_external_mcp_server(input("github"))
+            .await
+            .unwrap();
+        assert_eq!(server.spec.args.len(), 2);
+        assert!(db
+            .create_external_mcp_server(input("GitHub"))
+            .await
+            .is_err());
+        assert!(db.create_external_mcp_server(input("  ")).await.is_err());
+
+        let updated = db
+            .update_external_mcp_server(
+                &server.id,
+                UpdateExternalMcpServerInput {
+                    spec: Some(McpServerSpec {
+                        transport: McpTransport::Http,
+                        url: Some("https://mcp.example.com".to_string()),
+                        ..Default::default()
+                    }),
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.spec.transport, McpTransport::Http);
+        assert!(!updated.enabled);
+        assert_eq!(updated.enabled_adapters, vec![AdapterType::Cursor]);
+
+        db.delete_external_mcp_server(&server.id).await.unwrap();
+        assert!(matches!(
+            db.get_external_mcp_server_by_id(&server.id).await,
+            Err(AppError::McpServerNotFound { .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_command_target_paths_roundtrip() {
+    async fn test_sync_report_roundtrip_and_history_flag() {
+        use crate::models::{AdapterSyncReport, SyncReportFile};
+
         let db = Database::new_in_memory().await.unwrap();
+        db.add_sync_log(1, "success", "manual").await.unwrap();
+
+        let report = SyncReport {
+            id: "sync-1".to_string(),
+            timestamp: Utc::now(),
+            triggered_by: "manual".to_string(),
+            status: "success".to_string(),
+            duration_ms: 12,
+            adapters: vec![AdapterSyncReport {
+                adapter: AdapterType::Gemini,
+                adapter_name: "Gemini".to_string(),
+                duration_ms: 5,
+                files: vec![SyncReportFile {
+                    path: "/tmp/GEMINI.md".to_string(),
+                    hash: "abc".to_string(),
+                    rule_count: 1,
+                    bytes: 42,
+                    duration_ms: 5,
+                }],
+                skipped_reason: None,
+                errors: vec![],
+                installed: None,
+                rolled_back: false,
+            }],
+        };
+        db.add_sync_report(&report).await.unwrap();
+
+        let stored = db.get_sync_report("sync-1").await.unwrap().unwrap();
+        assert_eq!(stored.adapters[0].files[0].hash, "abc");
+        assert!(db.get_sync_report("missing").await.unwrap().is_none());
+
+        let history = db.get_sync_history(10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        let entry = history.iter().find(|e| e.id == "sync-1").unwrap();
+        assert!(entry.has_report);
+        assert_eq!(entry.files_written, 1);
+        assert!(history.iter().any(|e| !e.has_report));
+        assert!(db
+            .get_sync_report(&history.iter().find(|e| !e.has_report).unwrap().id)
+            .await
+            .unwrap()
+            .is_none());
+    }
 
-        let created = db
-            .create_command(CreateCommandInput {
+    #[tokio::test]
+    async fn test_audit_log_records_changes_and_filters() {
+        use crate::models::{AuditAction, AuditActorKind, AuditLogQuery, AuditTargetType};
+
+        let db = Database::new_in_memory().await.unwrap();
+        let rule = db
+            .create_rule(CreateRuleInput {
                 id: None,
-                name: "Build".to_string(),
-                description: "Run build".to_string(),
-                script: "npm run build".to_string(),
-                arguments: vec![],
-                expose_via_mcp: true,
-                is_placeholder: false,
-                generate_slash_commands: false,
-                slash_command_adapters: vec![],
-                target_paths: vec!["C:/repo-a".to_string()],
-                base_path: None,
-                timeout_ms: None,
-                max_retries: None,
+                name: "Audited".to_string(),
+                description: String::new(),
+                content: "before".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
+        db.update_rule(
+            &rule.id,
+            UpdateRuleInput {
+                content: Some("after".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        db.toggle_rule(&rule.id, false).await.unwrap();
+        db.delete_rule(&rule.id).await.unwrap();
 
-        assert_eq!(created.target_paths, vec!["C:/repo-a".to_string()]);
+        let page = db.query_audit_log(AuditLogQuery::default()).await.unwrap();
+        assert_eq!(page.total, 4);
+        let actions: Vec<AuditAction> = page.entries.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                AuditAction::Delete,
+                AuditAction::Toggle,
+                AuditAction::Update,
+                AuditAction::Create
+            ]
+        );
+        assert!(page
+            .entries
+            .iter()
+            .all(|e| e.actor.kind == AuditActorKind::Ui));
+        assert_eq!(page.entries[2].summary, "Updated rule 'Audited': content");
+        let target = page.entries[0].target.as_ref().unwrap();
+        assert_eq!(target.target_type, AuditTargetType::Rule);
+        assert_eq!(target.id, rule.id);
+
+        let updates = db
+            .query_audit_log(AuditLogQuery {
+                action: Some(AuditAction::Update),
+                target_id: Some(rule.id.clone()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(updates.total, 1);
 
-        let updated = db
-            .update_command(
-                &created.id,
-                UpdateCommandInput {
-                    target_paths: Some(vec!["C:/repo-b".to_string(), "C:/repo-c".to_string()]),
-                    ..Default::default()
-                },
-            )
+        let paged = db
+            .query_audit_log(AuditLogQuery {
+                offset: Some(1),
+                limit: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(paged.total, 4);
+        assert_eq!(paged.entries.len(), 2);
+        assert_eq!(paged.entries[0].action, AuditAction::Toggle);
+
+        let none = db
+            .query_audit_log(AuditLogQuery {
+                actor: Some(AuditActorKind::Mcp),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(none.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_export_state_restores_sync_bookkeeping() {
+        let source = Database::new_in_memory().await.unwrap();
+        source
+            .set_file_hash("/repo/GEMINI.md", "abc")
+            .await
+            .unwrap();
+        source
+            .replace_rule_file_index(vec![RuleFileIndexEntry {
+                rule_id: "rule-1".to_string(),
+                file_path: "/rules/style.md".to_string(),
+                content_hash: Some("def".to_string()),
+                last_modified: Some(42),
+            }])
+            .await
+            .unwrap();
+        source
+            .set_setting(crate::rule_import::IMPORT_SOURCE_MAP_KEY, "{}")
             .await
             .unwrap();
+        let mut config = source.export_configuration().await.unwrap();
+        assert!(config.state.is_none());
+        config.state = Some(source.export_state().await.unwrap());
 
+        let restored = Database::new_in_memory().await.unwrap();
+        restored
+            .set_file_hash("/repo/GEMINI.md", "old")
+            .await
+            .unwrap();
+        restored
+            .import_configuration(config.clone(), crate::models::ImportMode::Skip)
+            .await
+            .unwrap();
         assert_eq!(
-            updated.target_paths,
-            vec!["C:/repo-b".to_string(), "C:/repo-c".to_string()]
+            restored.get_file_hash("/repo/GEMINI.md").await.unwrap(),
+            Some("old".to_string())
+        );
+        assert_eq!(restored.get_rule_file_index().await.unwrap().len(), 1);
+
+        restored
+            .import_configuration(config, crate::models::ImportMode::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(
+            restored.get_file_hash("/repo/GEMINI.md").await.unwrap(),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            restored
+                .get_setting(crate::rule_import::IMPORT_SOURCE_MAP_KEY)
+                .await
+                .unwrap(),
+            Some("{}".to_string())
         );
     }
 }
@@ -0,0 +1,243 @@
+//! Handling for `ruleweaver://` links opened from the browser, a terminal or another app.
+//!
+//! Supported links:
+//! - `ruleweaver://rule/<id>` opens a rule in the editor
+//! - `ruleweaver://sync` runs a quick sync, like the tray menu item
+//! - `ruleweaver://import?url=<url>` imports from a URL after the user confirms
+
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_notification::NotificationExt;
+use url::Url;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::ImportExecutionOptions;
+use crate::rule_import;
+
+pub const SCHEME: &str = "ruleweaver";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    OpenRule { id: String },
+    Sync,
+    Import { url: String },
+}
+
+pub fn parse(url: &Url) -> Result<DeepLinkAction> {
+    if url.scheme() != SCHEME {
+        return Err(AppError::InvalidInput {
+            message: format!("Unsupported link scheme: {}", url.scheme()),
+        });
+    }
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    match url.host_str().unwrap_or_default() {
+        "rule" | "rules" => match segments.as_slice() {
+            [id] => Ok(DeepLinkAction::OpenRule { id: id.to_string() }),
+            _ => Err(AppError::InvalidInput {
+                message: "Rule links must look like ruleweaver://rule/<id>".to_string(),
+            }),
+        },
+        "sync" if segments.is_empty() => Ok(DeepLinkAction::Sync),
+        "import" if segments.is_empty() => {
+            let target = url
+                .query_pairs()
+                .find(|(key, _)| key == "url")
+                .map(|(_, value)| value.into_owned())
+                .ok_or_else(|| AppError::InvalidInput {
+                    message: "Import links need a url parameter".to_string(),
+                })?;
+            let parsed = Url::parse(&target).map_err(|e| AppError::InvalidInput {
+                message: format!("Invalid import URL: {}", e),
+            })?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(AppError::InvalidInput {
+                    message: "Import links only accept http and https URLs".to_string(),
+                });
+            }
+            Ok(DeepLinkAction::Import {
+                url: parsed.to_string(),
+            })
+        }
+        other => Err(AppError::InvalidInput {
+            message: format!("Unknown link action: {}", other),
+        }),
+    }
+}
+
+/// Brings the main window forward and dispatches every link. Invalid links are logged and skipped.
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        match parse(&url) {
+            Ok(action) => {
+                log::info!("Handling deep link: {:?}", action);
+                focus_main_window(app);
+                tauri::async_runtime::spawn(dispatch(app.clone(), action));
+            }
+            Err(e) => log::warn!("Ignoring deep link {}: {}", url, e),
+        }
+    }
+}
+
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+async fn dispatch(app: AppHandle, action: DeepLinkAction) {
+    match action {
+        DeepLinkAction::OpenRule { id } => {
+            let _ = app.emit("deep-link-open-rule", id);
+        }
+        DeepLinkAction::Sync => crate::quick_sync(app).await,
+        DeepLinkAction::Import { url } => {
+            if !confirm_import(&app, &url).await {
+                log::info!("Deep link import from {} cancelled", url);
+                return;
+            }
+            import_from_url(app, url).await;
+        }
+    }
+}
+
+async fn confirm_import(app: &AppHandle, url: &str) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!(
+            "A link asked RuleWeaver to import rules from:\n\n{}\n\nOnly continue if you trust this source.",
+            url
+        ))
+        .title("Import From Link")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Import".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+async fn import_from_url(app: AppHandle, url: String) {
+    let Some(db) = app
+        .try_state::<Arc<Database>>()
+        .map(|db| db.inner().clone())
+    else {
+        return;
+    };
+
+    let result = async {
+        let options = ImportExecutionOptions::default();
+        let max_size = rule_import::resolve_max_size(&options);
+        let scan = rule_import::scan_url_to_candidates(&url, max_size).await?;
+        rule_import::execute_import(db, scan, options).await
+    }
+    .await;
+
+    let (title, body) = match result {
+        Ok(result) => {
+            let imported = result.imported_rules.len()
+                + result.imported_commands.len()
+                + result.imported_skills.len();
+            let _ = app.emit("deep-link-imported", &result);
+            if result.errors.is_empty() {
+                (
+                    "Import Complete",
+                    format!(
+                        "Imported {} artifact(s), skipped {}.",
+                        imported,
+                        result.skipped.len() + result.conflicts.len()
+                    ),
+                )
+            } else {
+                (
+                    "Import Finished With Errors",
+                    format!(
+                        "Imported {} artifact(s); {} error(s) occurred.",
+                        imported,
+                        result.errors.len()
+                    ),
+                )
+            }
+        }
+        Err(e) => {
+            log::error!("Deep link import from {} failed: {}", url, e);
+            ("Import Failed", e.to_string())
+        }
+    };
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(link: &str) -> Result<DeepLinkAction> {
+        parse(&Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn test_parse_open_rule() {
+        assert_eq!(
+            parse_str("ruleweaver://rule/abc-123").unwrap(),
+            DeepLinkAction::OpenRule {
+                id: "abc-123".to_string()
+            }
+        );
+        assert_eq!(
+            parse_str("ruleweaver://rules/abc-123/").unwrap(),
+            DeepLinkAction::OpenRule {
+                id: "abc-123".to_string()
+            }
+        );
+        assert!(parse_str("ruleweaver://rule").is_err());
+        assert!(parse_str("ruleweaver://rule/a/b").is_err());
+    }
+
+    #[test]
+    fn test_parse_sync() {
+        assert_eq!(
+            parse_str("ruleweaver://sync").unwrap(),
+            DeepLinkAction::Sync
+        );
+        assert_eq!(
+            parse_str("ruleweaver://sync/").unwrap(),
+            DeepLinkAction::Sync
+        );
+    }
+
+    #[test]
+    fn test_parse_import_requires_http_url() {
+        assert_eq!(
+            parse_str("ruleweaver://import?url=https%3A%2F%2Fexample.com%2Frules.md").unwrap(),
+            DeepLinkAction::Import {
+                url: "https://example.com/rules.md".to_string()
+            }
+        );
+        assert!(parse_str("ruleweaver://import").is_err());
+        assert!(parse_str("ruleweaver://import?url=file%3A%2F%2F%2Fetc%2Fpasswd").is_err());
+        assert!(parse_str("ruleweaver://import?url=not%20a%20url").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_links() {
+        assert!(parse_str("ruleweaver://delete/abc").is_err());
+        assert!(parse_str("https://rule/abc").is_err());
+    }
+}
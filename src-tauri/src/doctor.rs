@@ -0,0 +1,354 @@
+//! Environment diagnostics behind `ruleweaver doctor` and the `run_doctor` command.
+//!
+//! Each check yields a [`DoctorFinding`] with a status, what was found and, when something is
+//! wrong, what to do about it. Checks never fail the report as a whole: a check that cannot
+//! run reports that as its finding.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::constants::DEFAULT_MCP_PORT;
+use crate::database::Database;
+use crate::file_storage::watcher::{RuleFileWatcher, WatchMode, WATCH_MODE_KEY};
+use crate::models::registry::{ArtifactType, REGISTRY};
+use crate::models::AdapterType;
+use crate::path_resolver::path_resolver;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DoctorStatus {
+    Ok,
+    Warning,
+    Error,
+    /// The check does not apply here, such as watcher health outside the app
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorFinding {
+    /// Which check produced the finding: `database`, `tools`, `adapterPaths`, `mcpPort`
+    /// or `watcher`
+    pub check: &'static str,
+    pub status: DoctorStatus,
+    pub message: String,
+    /// What to do about a warning or error
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    pub findings: Vec<DoctorFinding>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// State only the running app knows about.
+#[derive(Default)]
+pub struct DoctorContext {
+    /// Port the embedded MCP server is listening on, if it is running
+    pub mcp_port: Option<u16>,
+    pub watcher: Option<RuleFileWatcher>,
+}
+
+fn finding(check: &'static str, status: DoctorStatus, message: String) -> DoctorFinding {
+    DoctorFinding {
+        check,
+        status,
+        message,
+        fix: None,
+    }
+}
+
+impl DoctorFinding {
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+async fn check_database(db: &Database) -> DoctorFinding {
+    match db.integrity_problems().await {
+        Ok(problems) if problems.is_empty() => finding(
+            "database",
+            DoctorStatus::Ok,
+            "Database integrity check passed".to_string(),
+        ),
+        Ok(problems) => finding(
+            "database",
+            DoctorStatus::Error,
+            format!("Database is damaged: {}", problems.join("; ")),
+        )
+        .with_fix("Restore a backup from Settings > Data Management, or from the backups folder"),
+        Err(e) => finding(
+            "database",
+            DoctorStatus::Error,
+            format!("Could not check the database: {}", e),
+        )
+        .with_fix("Make sure no other process has the database locked, then retry"),
+    }
+}
+
+/// Global rule file of each adapter. A tool counts as installed when the directory holding
+/// that file exists, since every tool creates its config directory on first run.
+fn global_rule_paths() -> Vec<(AdapterType, PathBuf)> {
+    AdapterType::all()
+        .into_iter()
+        .filter_map(|adapter| {
+            path_resolver()
+                .global_path(adapter, ArtifactType::Rule)
+                .ok()
+                .map(|resolved| (adapter, resolved.path))
+        })
+        .collect()
+}
+
+fn tool_name(adapter: AdapterType) -> &'static str {
+    REGISTRY
+        .get(&adapter)
+        .map(|entry| entry.name)
+        .unwrap_or(adapter.as_str())
+}
+
+fn is_installed(rule_path: &Path) -> bool {
+    rule_path.parent().is_some_and(Path::is_dir)
+}
+
+async fn check_tools(db: &Database, paths: &[(AdapterType, PathBuf)]) -> DoctorFinding {
+    let installed: Vec<AdapterType> = paths
+        .iter()
+        .filter(|(_, path)| is_installed(path))
+        .map(|(adapter, _)| *adapter)
+        .collect();
+
+    let targeted: BTreeSet<&'static str> = match db.get_all_rules().await {
+        Ok(rules) => rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .flat_map(|rule| rule.enabled_adapters.iter().copied())
+            .filter(|adapter| !installed.contains(adapter))
+            .map(tool_name)
+            .collect(),
+        Err(_) => BTreeSet::new(),
+    };
+
+    let names: Vec<&str> = installed
+        .iter()
+        .map(|adapter| tool_name(*adapter))
+        .collect();
+    if installed.is_empty() {
+        return finding(
+            "tools",
+            DoctorStatus::Warning,
+            "No supported AI tools were found in their default locations".to_string(),
+        )
+        .with_fix("Install an AI tool, or run it once so it creates its config directory");
+    }
+    if !targeted.is_empty() {
+        let missing: Vec<&str> = targeted.into_iter().collect();
+        return finding(
+            "tools",
+            DoctorStatus::Warning,
+            format!(
+                "Rules target tools that are not installed: {} (installed: {})",
+                missing.join(", "),
+                names.join(", ")
+            ),
+        )
+        .with_fix("Install those tools or remove them from the rules' adapters");
+    }
+    finding(
+        "tools",
+        DoctorStatus::Ok,
+        format!("Installed: {}", names.join(", ")),
+    )
+}
+
+/// Creates and removes a probe file, which catches read-only mounts and ACLs that
+/// permission bits do not show.
+fn is_writable_dir(dir: &Path) -> bool {
+    let probe = dir.join(format!(
+        ".ruleweaver-doctor-{}",
+        uuid::Uuid::new_v4().simple()
+    ));
+    match fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn check_adapter_paths(paths: &[(AdapterType, PathBuf)]) -> DoctorFinding {
+    let mut unwritable = Vec::new();
+    for (adapter, path) in paths.iter().filter(|(_, path)| is_installed(path)) {
+        let file_read_only = fs::metadata(path).is_ok_and(|m| m.permissions().readonly());
+        let dir_writable = path.parent().is_some_and(is_writable_dir);
+        if file_read_only || !dir_writable {
+            unwritable.push(format!("{} ({})", tool_name(*adapter), path.display()));
+        }
+    }
+
+    if unwritable.is_empty() {
+        finding(
+            "adapterPaths",
+            DoctorStatus::Ok,
+            "Rule files of all installed tools are writable".to_string(),
+        )
+    } else {
+        finding(
+            "adapterPaths",
+            DoctorStatus::Error,
+            format!("Cannot write rule files for: {}", unwritable.join(", ")),
+        )
+        .with_fix("Fix the permissions or ownership of those files and their directories")
+    }
+}
+
+fn check_mcp_port(running_on: Option<u16>) -> DoctorFinding {
+    if let Some(port) = running_on {
+        return finding(
+            "mcpPort",
+            DoctorStatus::Ok,
+            format!("MCP server is listening on port {}", port),
+        );
+    }
+    match TcpListener::bind(("127.0.0.1", DEFAULT_MCP_PORT)) {
+        Ok(_) => finding(
+            "mcpPort",
+            DoctorStatus::Ok,
+            format!("Port {} is free for the MCP server", DEFAULT_MCP_PORT),
+        ),
+        Err(e) => finding(
+            "mcpPort",
+            DoctorStatus::Warning,
+            format!(
+                "Port {} is unavailable, so the MCP server cannot start: {}",
+                DEFAULT_MCP_PORT, e
+            ),
+        )
+        .with_fix(format!(
+            "Stop the process using port {} (it may be another RuleWeaver instance), or run ruleweaver-mcp --port <other port>",
+            DEFAULT_MCP_PORT
+        )),
+    }
+}
+
+async fn check_watcher(db: &Database, watcher: Option<&RuleFileWatcher>) -> DoctorFinding {
+    let mode = db.get_storage_mode().await.unwrap_or_default();
+    if mode != "file" {
+        return finding(
+            "watcher",
+            DoctorStatus::Skipped,
+            format!("Rule files are not watched in {} storage mode", mode),
+        );
+    }
+    let Some(watcher) = watcher else {
+        return finding(
+            "watcher",
+            DoctorStatus::Skipped,
+            "The file watcher only runs inside the app".to_string(),
+        );
+    };
+
+    if !watcher.is_running() {
+        return finding(
+            "watcher",
+            DoctorStatus::Error,
+            "The rule file watcher is not running; external edits are not picked up".to_string(),
+        )
+        .with_fix("Restart RuleWeaver");
+    }
+    let pending = watcher.pending_paths();
+    if !pending.is_empty() {
+        let paths: Vec<String> = pending.iter().map(|p| p.display().to_string()).collect();
+        return finding(
+            "watcher",
+            DoctorStatus::Warning,
+            format!(
+                "Waiting for unavailable rule directories: {}",
+                paths.join(", ")
+            ),
+        )
+        .with_fix(
+            "Reconnect the drive or let the sync client finish; watching resumes on its own",
+        );
+    }
+
+    let configured = db.get_setting(WATCH_MODE_KEY).await.ok().flatten();
+    let mut message = format!(
+        "Watching {} rule directories",
+        watcher.watched_paths().len()
+    );
+    if WatchMode::from_setting(configured.as_deref()) != watcher.mode() {
+        message.push_str("; the watch mode setting changes after a restart");
+    }
+    finding("watcher", DoctorStatus::Ok, message)
+}
+
+/// Runs every check and collects the findings.
+pub async fn run_doctor(db: &Database, context: DoctorContext) -> DoctorReport {
+    let paths = global_rule_paths();
+    let findings = vec![
+        check_database(db).await,
+        check_tools(db, &paths).await,
+        check_adapter_paths(&paths),
+        check_mcp_port(context.mcp_port),
+        check_watcher(db, context.watcher.as_ref()).await,
+    ];
+
+    let count = |status| findings.iter().filter(|f| f.status == status).count();
+    DoctorReport {
+        errors: count(DoctorStatus::Error),
+        warnings: count(DoctorStatus::Warning),
+        findings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_doctor_reports_every_check() {
+        let db = Database::new_in_memory().await.unwrap();
+        let report = run_doctor(&db, DoctorContext::default()).await;
+
+        let checks: Vec<_> = report.findings.iter().map(|f| f.check).collect();
+        assert_eq!(
+            checks,
+            vec!["database", "tools", "adapterPaths", "mcpPort", "watcher"]
+        );
+        assert_eq!(report.findings[0].status, DoctorStatus::Ok);
+        assert_eq!(report.findings[4].status, DoctorStatus::Skipped);
+        assert!(report
+            .findings
+            .iter()
+            .filter(|f| matches!(f.status, DoctorStatus::Warning | DoctorStatus::Error))
+            .all(|f| f.fix.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_doctor_flags_busy_port_and_stopped_watcher() {
+        let db = Database::new_in_memory().await.unwrap();
+        db.set_storage_mode("file").await.unwrap();
+
+        let busy = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = busy.local_addr().unwrap().port();
+        assert_eq!(check_mcp_port(Some(port)).status, DoctorStatus::Ok);
+
+        let watcher = check_watcher(&db, Some(&RuleFileWatcher::new())).await;
+        assert_eq!(watcher.status, DoctorStatus::Error);
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(is_writable_dir(dir.path()));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+        assert!(!is_writable_dir(&dir.path().join("missing")));
+    }
+}
@@ -0,0 +1,359 @@
+//! Background drift monitoring.
+//!
+//! Periodically plans a reconciliation without applying it. When generated files are missing,
+//! have been edited by hand, or are left over from deleted artifacts, the tray status shows
+//! it and a desktop notification points at the tray's "Reconcile Now" item. The frontend gets
+//! a `drift-detected` event so it can offer the same action in-app. Monitoring is on by
+//! default and can be turned off with the `drift_monitor_enabled` setting.
+//!
+//! With the opt-in `startup_drift_check` setting, the first check also prepares a reviewable
+//! plan of what changed while RuleWeaver was closed. It is kept as [`StartupDriftReport`] for
+//! the frontend to show and apply, and nothing is written until the user approves it.
+
+use std::sync::{Arc, LazyLock};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::constants::timing::DRIFT_CHECK_INTERVAL;
+use crate::database::Database;
+use crate::error::Result;
+use crate::models::WebhookEvent;
+use crate::reconciliation::review::{PreparedReconcilePlan, ReconcilePlanStore};
+use crate::reconciliation::{ReconcilePlan, ReconcileResult, ReconciliationEngine};
+use crate::settings;
+use crate::webhooks;
+use crate::GlobalStatus;
+
+pub const DRIFT_MONITOR_ENABLED_KEY: &str = "drift_monitor_enabled";
+pub const STARTUP_DRIFT_CHECK_KEY: &str = "startup_drift_check";
+pub const DRIFT_DETECTED_EVENT: &str = "drift-detected";
+pub const STARTUP_DRIFT_EVENT: &str = "startup-drift-report";
+
+const IDLE_STATUS: &str = "Idle";
+const DRIFT_STATUS_PREFIX: &str = "Drift: ";
+
+/// Drift last reported to the user, so an unchanged finding is not notified every tick.
+static LAST_REPORTED: LazyLock<Mutex<DriftSummary>> =
+    LazyLock::new(|| Mutex::new(DriftSummary::default()));
+
+/// Counts of artifacts whose files disagree with the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftSummary {
+    /// Expected files that do not exist
+    pub missing: usize,
+    /// Files whose content differs from what would be generated
+    pub modified: usize,
+    /// Generated files that no longer belong to any artifact
+    pub stale: usize,
+}
+
+impl DriftSummary {
+    pub fn from_plan(plan: &ReconcilePlan) -> Self {
+        Self {
+            missing: plan.to_create.len(),
+            modified: plan.to_update.len(),
+            stale: plan.to_remove.len(),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.missing + self.modified + self.stale
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Whether `self` contains drift that `previous` did not, i.e. something worth notifying.
+    pub fn is_new_since(&self, previous: &DriftSummary) -> bool {
+        self.missing > previous.missing
+            || self.modified > previous.modified
+            || self.stale > previous.stale
+    }
+
+    fn describe(&self) -> String {
+        [
+            (self.missing, "missing"),
+            (self.modified, "modified"),
+            (self.stale, "stale"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// Plans a reconciliation with `engine` and summarizes what it would change.
+pub async fn detect_drift(engine: &ReconciliationEngine) -> Result<DriftSummary> {
+    let desired = engine.compute_desired_state().await?;
+    let actual = engine.scan_actual_state().await?;
+    Ok(DriftSummary::from_plan(&engine.plan(&desired, &actual)))
+}
+
+/// Drift found at launch, awaiting review. Taken by the frontend once it has shown it.
+#[derive(Default)]
+pub struct StartupDriftReport(Mutex<Option<PreparedReconcilePlan>>);
+
+impl StartupDriftReport {
+    pub fn take(&self) -> Option<PreparedReconcilePlan> {
+        self.0.lock().take()
+    }
+}
+
+pub async fn is_enabled(db: &Database) -> bool {
+    settings::load_or_default(db).await.drift_monitor_enabled
+}
+
+async fn is_startup_check_enabled(db: &Database) -> bool {
+    settings::load_or_default(db).await.startup_drift_check
+}
+
+/// Shows drift in the tray status without overwriting an in-progress sync.
+fn show_drift_in_tray(app: &tauri::AppHandle, summary: &DriftSummary) {
+    let Some(status) = app.try_state::<GlobalStatus>() else {
+        return;
+    };
+    {
+        let mut current = status.sync_status.lock();
+        let owned = current.is_empty()
+            || current.as_str() == IDLE_STATUS
+            || current.starts_with(DRIFT_STATUS_PREFIX);
+        if !owned {
+            return;
+        }
+        *current = if summary.is_empty() {
+            IDLE_STATUS.to_string()
+        } else {
+            format!("{}{} artifact(s)", DRIFT_STATUS_PREFIX, summary.total())
+        };
+    }
+    status.update_tray();
+}
+
+fn set_tray_status(app: &tauri::AppHandle, text: &str) {
+    if let Some(status) = app.try_state::<GlobalStatus>() {
+        *status.sync_status.lock() = text.to_string();
+        status.update_tray();
+    }
+}
+
+async fn check_and_report(db: &Arc<Database>, app: &tauri::AppHandle) -> Result<()> {
+    let summary = if is_enabled(db).await {
+        let engine = ReconciliationEngine::new(Arc::clone(db))?;
+        detect_drift(&engine).await?
+    } else {
+        DriftSummary::default()
+    };
+
+    show_drift_in_tray(app, &summary);
+
+    let notify = {
+        let mut last = LAST_REPORTED.lock();
+        let notify = summary.is_new_since(&last);
+        *last = summary;
+        notify
+    };
+    if notify {
+        log::info!("Drift detected: {}", summary.describe());
+        app.notification()
+            .builder()
+            .title("Configuration Drift Detected")
+            .body(format!(
+                "{} generated file(s) are out of sync ({}). Choose \"Reconcile Now\" from the tray menu to repair them.",
+                summary.total(),
+                summary.describe()
+            ))
+            .show()
+            .ok();
+        let _ = app.emit(DRIFT_DETECTED_EVENT, summary);
+        notify_webhooks(db, &summary).await;
+    }
+
+    Ok(())
+}
+
+async fn notify_webhooks(db: &Database, summary: &DriftSummary) {
+    webhooks::emit(
+        db,
+        WebhookEvent::ConflictDetected,
+        &format!(
+            "RuleWeaver found {} generated file(s) out of sync ({})",
+            summary.total(),
+            summary.describe()
+        ),
+        serde_json::json!(summary),
+    )
+    .await;
+}
+
+/// Prepares a plan for the drift found at launch and reports it without applying anything.
+async fn check_at_startup(db: &Arc<Database>, app: &tauri::AppHandle) -> Result<()> {
+    let (Some(store), Some(report)) = (
+        app.try_state::<ReconcilePlanStore>(),
+        app.try_state::<StartupDriftReport>(),
+    ) else {
+        return Ok(());
+    };
+
+    let engine = ReconciliationEngine::new(Arc::clone(db))?;
+    let prepared = engine.prepare_plan(&store).await?;
+    let summary = DriftSummary::from_plan(&prepared.plan);
+    // The monitor's first tick should not notify about the same drift again.
+    *LAST_REPORTED.lock() = summary;
+    if summary.is_empty() {
+        return Ok(());
+    }
+
+    log::info!("Drift since last run: {}", summary.describe());
+    show_drift_in_tray(app, &summary);
+    app.notification()
+        .builder()
+        .title("Drift While RuleWeaver Was Closed")
+        .body(format!(
+            "{} change(s) are waiting for review ({}). Nothing has been written yet.",
+            summary.total(),
+            summary.describe()
+        ))
+        .show()
+        .ok();
+    notify_webhooks(db, &summary).await;
+    *report.0.lock() = Some(prepared.clone());
+    let _ = app.emit(STARTUP_DRIFT_EVENT, prepared);
+    Ok(())
+}
+
+/// Background loop that checks for drift on a fixed interval, starting with the opt-in
+/// startup check.
+pub async fn run_drift_monitor_loop(db: Arc<Database>, app: tauri::AppHandle) {
+    if is_startup_check_enabled(&db).await {
+        if let Err(e) = check_at_startup(&db, &app).await {
+            log::error!("Startup drift check failed: {}", e);
+        }
+        tokio::time::sleep(DRIFT_CHECK_INTERVAL).await;
+    }
+    loop {
+        if let Err(e) = check_and_report(&db, &app).await {
+            log::error!("Drift check failed: {}", e);
+        }
+        tokio::time::sleep(DRIFT_CHECK_INTERVAL).await;
+    }
+}
+
+/// Applies a full reconciliation and clears the reported drift on success.
+pub async fn reconcile_now(db: Arc<Database>, app: &tauri::AppHandle) -> Result<ReconcileResult> {
+    set_tray_status(app, "Reconciling...");
+    let result = match ReconciliationEngine::new(db) {
+        Ok(engine) => engine.reconcile(false, None).await,
+        Err(e) => Err(e),
+    };
+    set_tray_status(app, IDLE_STATUS);
+
+    if matches!(&result, Ok(r) if r.success) {
+        *LAST_REPORTED.lock() = DriftSummary::default();
+    }
+    result
+}
+
+/// Tray entry point for [`reconcile_now`], reporting the outcome as a notification.
+pub async fn reconcile_from_tray(app: tauri::AppHandle) {
+    let Some(db) = app
+        .try_state::<Arc<Database>>()
+        .map(|db| db.inner().clone())
+    else {
+        return;
+    };
+
+    let (title, body) = match reconcile_now(db, &app).await {
+        Ok(result) if result.success => (
+            "Reconcile Complete",
+            format!(
+                "Created {}, updated {} and removed {} file(s).",
+                result.created, result.updated, result.removed
+            ),
+        ),
+        Ok(result) => (
+            "Reconcile Failed",
+            format!(
+                "{} error(s) occurred while reconciling.",
+                result.errors.len()
+            ),
+        ),
+        Err(e) => {
+            log::error!("Reconcile from tray failed: {}", e);
+            ("Reconcile Failed", e.to_string())
+        }
+    };
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, Scope};
+    use crate::path_resolver::PathResolver;
+
+    #[test]
+    fn test_new_drift_only_when_a_count_grows() {
+        let previous = DriftSummary {
+            missing: 1,
+            modified: 2,
+            stale: 0,
+        };
+
+        assert!(!DriftSummary::default().is_new_since(&previous));
+        assert!(!previous.is_new_since(&previous));
+        assert!(!DriftSummary {
+            missing: 1,
+            modified: 1,
+            stale: 0
+        }
+        .is_new_since(&previous));
+        assert!(DriftSummary {
+            missing: 0,
+            modified: 0,
+            stale: 1
+        }
+        .is_new_since(&previous));
+        assert_eq!(previous.describe(), "1 missing, 2 modified");
+    }
+
+    #[tokio::test]
+    async fn test_detect_drift_reports_missing_then_clears_after_reconcile() {
+        let home = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Drifting".to_string(),
+            description: String::new(),
+            content: "content".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+
+        let engine = ReconciliationEngine::new_with_resolver(
+            Arc::clone(&db),
+            PathResolver::new_with_home(home.path().to_path_buf(), vec![]),
+        );
+        let before = detect_drift(&engine).await.unwrap();
+        assert!(before.missing >= 1);
+
+        assert!(engine.reconcile(false, None).await.unwrap().success);
+        assert!(detect_drift(&engine).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+//! The conditions a command ran under.
+//!
+//! Before the first attempt of a run the environment handed to the process, the versions
+//! of well-known tools named in the script, the git revision of the working directory and
+//! the RuleWeaver version are collected into an [`EnvironmentSnapshot`] and stored with
+//! every log entry of that run. Comparing two snapshots shows what changed between a run
+//! that worked and one that did not.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command as TokioCommand;
+use tokio::time::timeout;
+
+use crate::models::{EnvironmentSnapshot, SandboxProfile};
+use crate::redaction::{redact, redact_values};
+use crate::sandbox;
+
+/// Tools whose version is recorded when a script calls them, with the arguments that
+/// print it.
+const KNOWN_TOOLS: &[(&str, &[&str])] = &[
+    ("bun", &["--version"]),
+    ("cargo", &["--version"]),
+    ("cmake", &["--version"]),
+    ("deno", &["--version"]),
+    ("docker", &["--version"]),
+    ("dotnet", &["--version"]),
+    ("git", &["--version"]),
+    ("go", &["version"]),
+    ("gradle", &["--version"]),
+    ("java", &["-version"]),
+    ("kubectl", &["version", "--client"]),
+    ("make", &["--version"]),
+    ("mvn", &["--version"]),
+    ("node", &["--version"]),
+    ("npm", &["--version"]),
+    ("npx", &["--version"]),
+    ("php", &["--version"]),
+    ("pip", &["--version"]),
+    ("pip3", &["--version"]),
+    ("pnpm", &["--version"]),
+    ("python", &["--version"]),
+    ("python3", &["--version"]),
+    ("ruby", &["--version"]),
+    ("rustc", &["--version"]),
+    ("terraform", &["--version"]),
+    ("yarn", &["--version"]),
+];
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Substrings of variable names whose values are never recorded.
+const SENSITIVE_NAME_PARTS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Collects the snapshot for a run. `envs` are the command's own variables; `secrets` are
+/// left out by name and their values are scrubbed from everything else.
+pub async fn capture(
+    script: &str,
+    envs: &[(String, String)],
+    secrets: &[(String, String)],
+    working_dir: Option<&Path>,
+    profile: Option<&SandboxProfile>,
+) -> EnvironmentSnapshot {
+    let working_dir = working_dir
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::current_dir().ok());
+    let (git_commit, git_dirty) = match &working_dir {
+        Some(dir) => git_revision(dir).await,
+        None => (None, false),
+    };
+
+    EnvironmentSnapshot {
+        env: process_env(envs, secrets, profile),
+        tool_versions: tool_versions(script, working_dir.as_deref()).await,
+        working_dir: working_dir.map(|d| d.to_string_lossy().to_string()),
+        git_commit,
+        git_dirty,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+    }
+}
+
+/// The variables the process starts with: the inherited environment (only the allowlist
+/// when the sandbox scrubs it) overlaid with the command's own.
+fn process_env(
+    envs: &[(String, String)],
+    secrets: &[(String, String)],
+    profile: Option<&SandboxProfile>,
+) -> BTreeMap<String, String> {
+    let secret_names: BTreeSet<&str> = secrets.iter().map(|(name, _)| name.as_str()).collect();
+    let secret_values: Vec<String> = secrets.iter().map(|(_, value)| value.clone()).collect();
+
+    let inherited: Vec<(String, String)> = if profile.is_some_and(|p| p.scrub_env) {
+        sandbox::scrubbed_env()
+    } else {
+        std::env::vars().collect()
+    };
+
+    inherited
+        .into_iter()
+        .chain(envs.iter().cloned())
+        .filter(|(name, _)| !secret_names.contains(name.as_str()))
+        .map(|(name, value)| {
+            let upper = name.to_ascii_uppercase();
+            let value = if SENSITIVE_NAME_PARTS.iter().any(|part| upper.contains(part)) {
+                REDACTED.to_string()
+            } else {
+                let (value, _) = redact_values(&value, &secret_values);
+                redact(&value).0
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// The known tools named anywhere in `script`, in name order.
+fn referenced_tools(script: &str) -> Vec<(&'static str, &'static [&'static str])> {
+    let words: BTreeSet<&str> = script
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'))
+        .collect();
+    KNOWN_TOOLS
+        .iter()
+        .filter(|(tool, _)| words.contains(tool))
+        .copied()
+        .collect()
+}
+
+async fn tool_versions(script: &str, working_dir: Option<&Path>) -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::new();
+    for (tool, args) in referenced_tools(script) {
+        if let Some(version) = probe(tool, args, working_dir).await {
+            versions.insert(tool.to_string(), version);
+        }
+    }
+    versions
+}
+
+/// First non-empty line a program prints, checking stdout before stderr (some tools, such
+/// as `java -version`, report on stderr).
+async fn probe(program: &str, args: &[&str], working_dir: Option<&Path>) -> Option<String> {
+    let mut cmd = TokioCommand::new(program);
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
+    let output = timeout(PROBE_TIMEOUT, cmd.output()).await.ok()?.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    [output.stdout, output.stderr].iter().find_map(|stream| {
+        String::from_utf8_lossy(stream)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// `HEAD` of the repository containing `dir` and whether its work tree has changes.
+async fn git_revision(dir: &Path) -> (Option<String>, bool) {
+    let Some(commit) = probe("git", &["rev-parse", "HEAD"], Some(dir)).await else {
+        return (None, false);
+    };
+    let dirty = probe("git", &["status", "--porcelain"], Some(dir))
+        .await
+        .is_some();
+    (Some(commit), dirty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tools_are_detected_as_whole_words() {
+        let tools: Vec<&str> =
+            referenced_tools("npm run build && cargo-nextest run; ./node_modules/.bin/eslint")
+                .into_iter()
+                .map(|(tool, _)| tool)
+                .collect();
+        assert_eq!(tools, vec!["npm"]);
+
+        let tools: Vec<&str> = referenced_tools("git pull\npython3 -m pytest")
+            .into_iter()
+            .map(|(tool, _)| tool)
+            .collect();
+        assert_eq!(tools, vec!["git", "python3"]);
+    }
+
+    #[test]
+    fn secrets_never_reach_the_snapshot() {
+        let envs = vec![
+            ("RW_ARG_TARGET".to_string(), "prod".to_string()),
+            ("RW_ARG_NOTE".to_string(), "uses hunter2-value".to_string()),
+            ("GITHUB_TOKEN".to_string(), "plain".to_string()),
+        ];
+        let secrets = vec![("DEPLOY_KEY".to_string(), "hunter2-value".to_string())];
+        let env = process_env(&envs, &secrets, None);
+
+        assert_eq!(env["RW_ARG_TARGET"], "prod");
+        assert_eq!(env["RW_ARG_NOTE"], "uses [REDACTED]");
+        assert_eq!(env["GITHUB_TOKEN"], "[REDACTED]");
+        assert!(!env.contains_key("DEPLOY_KEY"));
+    }
+
+    #[tokio::test]
+    async fn git_revision_is_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let (commit, dirty) = git_revision(dir.path()).await;
+        assert!(commit.is_none());
+        assert!(!dirty);
+
+        let snapshot = capture("echo hi", &[], &[], Some(dir.path()), None).await;
+        assert_eq!(snapshot.app_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            snapshot.working_dir.as_deref(),
+            Some(dir.path().to_string_lossy().as_ref())
+        );
+        assert!(snapshot.tool_versions.is_empty());
+    }
+}
@@ -19,6 +19,36 @@ pub enum AppError {
     #[error("Skill not found: {id}")]
     SkillNotFound { id: String },
 
+    #[error("Collection not found: {id}")]
+    CollectionNotFound { id: String },
+
+    #[error("MCP server not found: {id}")]
+    McpServerNotFound { id: String },
+
+    #[error("Workflow not found: {id}")]
+    WorkflowNotFound { id: String },
+
+    #[error("Webhook not found: {id}")]
+    WebhookNotFound { id: String },
+
+    #[error("Execution log not found: {id}")]
+    ExecutionLogNotFound { id: String },
+
+    #[error("Sync report not found: {id}")]
+    SyncReportNotFound { id: String },
+
+    #[error("Job not found: {id}")]
+    JobNotFound { id: String },
+
+    #[error("Workspace not found: {id}")]
+    WorkspaceNotFound { id: String },
+
+    #[error("Rule template not found: {id}")]
+    RuleTemplateNotFound { id: String },
+
+    #[error("Snippet not found: {id}")]
+    SnippetNotFound { id: String },
+
     #[error("Sync conflict detected in: {file_path}")]
     #[allow(dead_code)]
     SyncConflict { file_path: String },
@@ -38,6 +68,12 @@ pub enum AppError {
     #[error("Invalid input: {message}")]
     InvalidInput { message: String },
 
+    #[error("{message}")]
+    PolicyViolation { message: String },
+
+    #[error("Execution cancelled")]
+    ExecutionCancelled,
+
     #[error("Failed to serialize data: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -54,8 +90,10 @@ pub enum AppError {
     #[allow(dead_code)]
     Yaml { message: String },
 
+    #[error("Encryption error: {message}")]
+    Encryption { message: String },
+
     #[error("Migration error: {message}")]
-    #[allow(dead_code)]
     Migration { message: String },
 
     #[error("File watcher error: {message}")]
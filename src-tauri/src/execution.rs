@@ -1,12 +1,39 @@
-use std::time::Duration;
-use tokio::process::Command as TokioCommand;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, Command as TokioCommand};
+use tokio::sync::Notify;
 use tokio::time::timeout;
 
-use crate::constants::limits::{MAX_ARG_LENGTH, MAX_SCRIPT_LENGTH};
+use crate::artifacts;
+use crate::constants::limits::{MAX_ARG_LENGTH, MAX_SCRIPT_LENGTH, MAX_STDIN_LENGTH};
+use crate::constants::timing::CMD_EXEC_TIMEOUT;
 use crate::database::{Database, ExecutionLogInput};
+use crate::env_snapshot;
 use crate::error::{AppError, Result};
-use crate::models::FailureClass;
-use crate::redaction::redact;
+use crate::metrics;
+use crate::models::{
+    ArgumentType, Command, CommandArgument, CommandPreview, CommandShell, FailureClass,
+    OutputParser, OutputStream, SandboxProfile, WebhookEvent,
+};
+use crate::output_parser;
+use crate::process_tree::{self, ProcessTree};
+use crate::redaction::Redactor;
+use crate::sandbox;
+
+/// Receives each line of output, without its line ending, while a process runs.
+pub type OutputSink = Arc<dyn Fn(OutputStream, &str) + Send + Sync>;
+
+/// Wraps a sink so secrets are redacted from each line before it is passed on.
+pub fn redacting_sink(sink: OutputSink, redactor: Arc<Redactor>) -> OutputSink {
+    Arc::new(move |stream, line| {
+        let (redacted, _) = redactor.redact(line);
+        sink(stream, &redacted)
+    })
+}
 
 pub fn template_token(arg_name: &str) -> String {
     format!("{{{{{}}}}}", arg_name)
@@ -20,19 +47,86 @@ pub fn argument_env_var_name(arg_name: &str) -> String {
     format!("RW_ARG_{}", slug.replace('-', "_").to_uppercase())
 }
 
-pub fn replace_template_with_env_ref(script: &str, arg_name: &str) -> String {
+pub fn replace_template_with_env_ref(
+    script: &str,
+    arg_name: &str,
+    shell: Option<CommandShell>,
+) -> String {
     let token = template_token(arg_name);
     let env_name = argument_env_var_name(arg_name);
 
-    #[cfg(target_os = "windows")]
-    let reference = format!("%{}%", env_name);
-
-    #[cfg(not(target_os = "windows"))]
-    let reference = format!("${}", env_name);
+    let reference = match shell {
+        Some(CommandShell::Bash | CommandShell::Zsh) => format!("${}", env_name),
+        Some(CommandShell::Pwsh) => format!("$env:{}", env_name),
+        Some(CommandShell::Cmd) => format!("%{}%", env_name),
+        #[cfg(target_os = "windows")]
+        None => format!("%{}%", env_name),
+        #[cfg(not(target_os = "windows"))]
+        None => format!("${}", env_name),
+    };
 
     script.replace(&token, &reference)
 }
 
+/// Placeholder for the repository a local-scoped command runs against.
+pub const REPO_ROOT_PLACEHOLDER: &str = "{{repo_root}}";
+
+/// Resolves a command's working directory. `{{repo_root}}` is replaced with `repo_root`,
+/// and relative paths are taken relative to it. Returns `None` to use the app's cwd.
+pub fn resolve_working_dir(
+    working_dir: Option<&str>,
+    repo_root: Option<&str>,
+) -> Result<Option<PathBuf>> {
+    let Some(working_dir) = working_dir.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+
+    let resolved = if working_dir.contains(REPO_ROOT_PLACEHOLDER) {
+        let root = repo_root.ok_or_else(|| AppError::InvalidInput {
+            message: format!(
+                "Working directory uses {} but the command has no local target path",
+                REPO_ROOT_PLACEHOLDER
+            ),
+        })?;
+        PathBuf::from(working_dir.replace(REPO_ROOT_PLACEHOLDER, root))
+    } else {
+        let path = PathBuf::from(working_dir);
+        match repo_root {
+            Some(root) if path.is_relative() => Path::new(root).join(path),
+            _ => path,
+        }
+    };
+
+    if !resolved.is_dir() {
+        return Err(AppError::InvalidInput {
+            message: format!("Working directory does not exist: {}", resolved.display()),
+        });
+    }
+    Ok(Some(resolved))
+}
+
+/// Builds the process that runs `script` with the chosen shell, inside the sandbox wrapper
+/// when the profile needs one.
+fn shell_command(
+    script: &str,
+    shell: Option<CommandShell>,
+    profile: Option<&SandboxProfile>,
+) -> Result<TokioCommand> {
+    let (program, args): (&str, &[&str]) = match shell {
+        Some(CommandShell::Bash) => ("bash", &["-c"]),
+        Some(CommandShell::Zsh) => ("zsh", &["-c"]),
+        Some(CommandShell::Pwsh) => ("pwsh", &["-NoProfile", "-NonInteractive", "-Command"]),
+        Some(CommandShell::Cmd) => ("cmd", &["/C"]),
+        #[cfg(target_os = "windows")]
+        None => ("cmd", &["/C"]),
+        #[cfg(not(target_os = "windows"))]
+        None => ("sh", &["-c"]),
+    };
+    let mut args = args.to_vec();
+    args.push(script);
+    sandbox::wrap_command(program, &args, profile)
+}
+
 pub fn slugify(input: &str) -> String {
     static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
     let re = RE.get_or_init(|| regex::Regex::new(r"[a-z0-9]+").expect("Invalid slugify regex"));
@@ -61,6 +155,57 @@ pub fn validate_enum_argument(
     Ok(())
 }
 
+/// Checks a non-empty value against its argument's type, allowed values, range and pattern.
+pub fn validate_argument_value(arg: &CommandArgument, value: &str) -> Result<()> {
+    let invalid = |reason: String| AppError::InvalidInput {
+        message: format!("Argument '{}' {}", arg.name, reason),
+    };
+
+    match arg.arg_type {
+        ArgumentType::Number => {
+            let number: f64 = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|n: &f64| n.is_finite())
+                .ok_or_else(|| invalid(format!("must be a number, got '{}'", value)))?;
+            if let Some(min) = arg.min.filter(|min| number < *min) {
+                return Err(invalid(format!("must be at least {}", min)));
+            }
+            if let Some(max) = arg.max.filter(|max| number > *max) {
+                return Err(invalid(format!("must be at most {}", max)));
+            }
+        }
+        ArgumentType::Boolean => {
+            if value != "true" && value != "false" {
+                return Err(invalid("must be true or false".to_string()));
+            }
+        }
+        ArgumentType::Enum => validate_enum_argument(&arg.name, value, &arg.options)?,
+        ArgumentType::Path => {
+            if Path::new(value)
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                return Err(invalid(
+                    "cannot contain traversal sequences (..)".to_string(),
+                ));
+            }
+        }
+        ArgumentType::String => {}
+    }
+
+    if let Some(pattern) = &arg.pattern {
+        let re = regex::Regex::new(&format!("^(?:{})$", pattern))
+            .map_err(|e| invalid(format!("has an invalid pattern: {}", e)))?;
+        if !re.is_match(value) {
+            return Err(invalid(format!("must match the pattern {}", pattern)));
+        }
+    }
+
+    Ok(())
+}
+
 pub fn sanitize_argument_value(value: &str) -> Result<String> {
     if value.contains('\n') || value.contains('\r') || value.contains('\t') {
         return Err(AppError::InvalidInput {
@@ -115,6 +260,59 @@ fn escape_cmd_argument(value: &str) -> String {
     escaped
 }
 
+/// Renders a command's script and argument environment from caller-supplied values,
+/// falling back to argument defaults. Values are checked against their argument's type and
+/// constraints, then sanitized.
+pub fn render_command_invocation(
+    cmd: &Command,
+    values: &HashMap<String, String>,
+) -> Result<(String, Vec<(String, String)>)> {
+    let mut script = cmd.script.clone();
+    let mut envs = Vec::with_capacity(cmd.arguments.len());
+
+    for arg in &cmd.arguments {
+        script = replace_template_with_env_ref(&script, &arg.name, cmd.shell);
+
+        let raw_value = values
+            .get(&arg.name)
+            .cloned()
+            .or_else(|| arg.default_value.clone())
+            .unwrap_or_default();
+        if raw_value.is_empty() {
+            if arg.required {
+                return Err(AppError::InvalidInput {
+                    message: format!("Missing required argument: {}", arg.name),
+                });
+            }
+        } else {
+            validate_argument_value(arg, &raw_value)?;
+        }
+        let safe_value = sanitize_argument_value(&raw_value)?;
+
+        envs.push((argument_env_var_name(&arg.name), safe_value));
+    }
+
+    Ok((script, envs))
+}
+
+/// Checks an input payload a caller wants piped to `cmd`.
+pub fn validate_stdin(cmd: &Command, stdin: Option<&str>) -> Result<()> {
+    let Some(stdin) = stdin else {
+        return Ok(());
+    };
+    if !cmd.accepts_stdin {
+        return Err(AppError::InvalidInput {
+            message: format!("Command '{}' does not accept stdin", cmd.name),
+        });
+    }
+    if stdin.len() > MAX_STDIN_LENGTH {
+        return Err(AppError::InvalidInput {
+            message: format!("stdin too long (max {} bytes)", MAX_STDIN_LENGTH),
+        });
+    }
+    Ok(())
+}
+
 pub fn contains_disallowed_pattern(script: &str) -> Option<String> {
     let lower = script.to_lowercase();
     let patterns: [(&str, &str, &str); 18] = [
@@ -147,19 +345,82 @@ pub fn contains_disallowed_pattern(script: &str) -> Option<String> {
     None
 }
 
-pub async fn execute_shell_with_timeout_env(
-    script: &str,
-    timeout_dur: Duration,
-    envs: &[(String, String)],
-) -> Result<(i32, String, String)> {
-    execute_shell_with_timeout_env_dir(script, timeout_dur, envs, None).await
+/// Requests that a running execution stop.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<Notify>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        // notify_one stores a permit, so a cancel that races ahead of the wait still counts.
+        self.0.notify_one();
+    }
+
+    async fn cancelled(&self) {
+        self.0.notified().await;
+    }
+}
+
+static RUNNING_EXECUTIONS: LazyLock<parking_lot::Mutex<HashMap<String, CancelToken>>> =
+    LazyLock::new(|| parking_lot::Mutex::new(HashMap::new()));
+
+/// Keeps an execution cancellable by id until dropped.
+pub struct ExecutionRegistration {
+    execution_id: String,
+}
+
+impl Drop for ExecutionRegistration {
+    fn drop(&mut self) {
+        RUNNING_EXECUTIONS.lock().remove(&self.execution_id);
+    }
+}
+
+/// Makes `execution_id` cancellable through [`cancel_execution`] for as long as the
+/// returned registration is alive.
+pub fn register_execution(execution_id: &str) -> (ExecutionRegistration, CancelToken) {
+    let token = CancelToken::default();
+    RUNNING_EXECUTIONS
+        .lock()
+        .insert(execution_id.to_string(), token.clone());
+    (
+        ExecutionRegistration {
+            execution_id: execution_id.to_string(),
+        },
+        token,
+    )
+}
+
+/// Cancels a registered execution. Returns `false` if no execution has that id.
+pub fn cancel_execution(execution_id: &str) -> bool {
+    match RUNNING_EXECUTIONS.lock().get(execution_id) {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
 }
 
-pub async fn execute_shell_with_timeout_env_dir(
+/// Where and how a script's process is started.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    pub dir: Option<PathBuf>,
+    pub shell: Option<CommandShell>,
+    pub sandbox: Option<SandboxProfile>,
+    /// Written to the process's stdin, which is then closed; stdin is empty when unset
+    pub stdin: Option<String>,
+}
+
+/// Runs a script through the platform shell, passing each line of output to `on_output`
+/// as it is produced. The full output is still returned once the process exits.
+///
+/// On timeout or cancellation the whole process tree is killed, not just the shell.
+pub async fn execute_shell_streaming(
     script: &str,
     timeout_dur: Duration,
     envs: &[(String, String)],
-    dir: Option<std::path::PathBuf>,
+    process: ProcessOptions,
+    on_output: Option<OutputSink>,
+    cancel: Option<CancelToken>,
 ) -> Result<(i32, String, String)> {
     if script.trim().is_empty() {
         return Err(AppError::InvalidInput {
@@ -177,37 +438,105 @@ pub async fn execute_shell_with_timeout_env_dir(
         return Err(AppError::Io(e));
     }
 
-    #[cfg(target_os = "windows")]
-    let mut cmd = TokioCommand::new("cmd");
-    #[cfg(target_os = "windows")]
-    cmd.args(["/C", script]);
-
-    #[cfg(not(target_os = "windows"))]
-    let mut cmd = TokioCommand::new("sh");
-    #[cfg(not(target_os = "windows"))]
-    cmd.args(["-c", script]);
-
+    let mut cmd = shell_command(script, process.shell, process.sandbox.as_ref())?;
+    sandbox::apply(&mut cmd, process.sandbox.as_ref());
     cmd.envs(envs.iter().cloned());
 
-    if let Some(d) = dir {
+    if let Some(d) = process.dir {
         cmd.current_dir(d);
     }
 
-    let future = cmd.output();
+    cmd.stdin(if process.stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    // Dropping the child on timeout must not leave the process running.
+    .kill_on_drop(true);
+    process_tree::prepare(&mut cmd);
 
-    match timeout(timeout_dur, future).await {
-        Ok(Ok(output)) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            Ok((output.status.code().unwrap_or(-1), stdout, stderr))
+    let mut child = cmd.spawn().map_err(AppError::Io)?;
+    let tree = ProcessTree::attach(&child, process.sandbox.as_ref());
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let run = async {
+        // Input is written alongside the reads so a child that fills its output pipes
+        // before consuming all of stdin cannot deadlock.
+        let (_, stdout, stderr) = tokio::join!(
+            write_stdin(stdin, process.stdin.as_deref()),
+            read_lines(stdout, OutputStream::Stdout, on_output.as_ref()),
+            read_lines(stderr, OutputStream::Stderr, on_output.as_ref()),
+        );
+        let status = child.wait().await?;
+        Ok::<_, std::io::Error>((status.code().unwrap_or(-1), stdout?, stderr?))
+    };
+
+    let cancelled = async {
+        match &cancel {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        result = timeout(timeout_dur, run) => match result {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(e)) => Err(AppError::Io(e)),
+            Err(_) => {
+                tree.kill();
+                Err(AppError::InvalidInput {
+                    message: format!("Execution timed out after {}s", timeout_dur.as_secs()),
+                })
+            }
+        },
+        _ = cancelled => {
+            tree.kill();
+            Err(AppError::ExecutionCancelled)
         }
-        Ok(Err(e)) => Err(AppError::Io(e)),
-        Err(_) => Err(AppError::InvalidInput {
-            message: format!("Execution timed out after {}s", timeout_dur.as_secs()),
-        }),
     }
 }
 
+/// Writes `input` to a child's stdin and closes it. A child that exits without reading
+/// everything is not an error.
+async fn write_stdin(stdin: Option<ChildStdin>, input: Option<&str>) {
+    let (Some(mut stdin), Some(input)) = (stdin, input) else {
+        return;
+    };
+    if let Err(e) = stdin.write_all(input.as_bytes()).await {
+        log::debug!("Process closed stdin early: {}", e);
+    }
+}
+
+/// Collects a child's output stream, forwarding each complete line to `sink`.
+async fn read_lines<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    stream: OutputStream,
+    sink: Option<&OutputSink>,
+) -> std::io::Result<String> {
+    let Some(reader) = reader else {
+        return Ok(String::new());
+    };
+    let mut reader = BufReader::new(reader);
+    let mut collected = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            break;
+        }
+        if let Some(sink) = sink {
+            let text = String::from_utf8_lossy(&line);
+            sink(stream, text.trim_end_matches(['\r', '\n']));
+        }
+        collected.extend_from_slice(&line);
+    }
+    Ok(String::from_utf8_lossy(&collected).into_owned())
+}
+
 pub fn classify_failure(exit_code: i32, stderr: &str, is_timeout: bool) -> FailureClass {
     if is_timeout {
         return FailureClass::Timeout;
@@ -258,14 +587,214 @@ pub struct ExecuteAndLogInput<'a> {
     pub triggered_by: &'a str,
     pub max_retries: Option<u8>,
     pub adapter_context: Option<&'a str>,
+    /// Receives output as it is produced; lines are redacted before delivery
+    pub on_output: Option<OutputSink>,
+    pub cancel: Option<CancelToken>,
+    /// Vault secrets added to the environment; their values never reach logs or output
+    pub secrets: &'a [(String, String)],
+    pub working_dir: Option<PathBuf>,
+    pub shell: Option<CommandShell>,
+    pub sandbox: Option<&'a SandboxProfile>,
+    /// Globs of files to keep from the final attempt; see [`crate::artifacts`]
+    pub artifacts: &'a [String],
+    /// Turns stdout into the structured results stored with each log entry
+    pub output_parser: Option<&'a OutputParser>,
+    /// Piped to the script on every attempt
+    pub stdin: Option<&'a str>,
+}
+
+/// How a saved command run through [`run_command`] is attributed and observed.
+pub struct RunCommandOptions<'a> {
+    pub triggered_by: &'a str,
+    pub adapter_context: Option<&'a str>,
+    pub on_output: Option<OutputSink>,
+    pub cancel: Option<CancelToken>,
+}
+
+/// Runs a saved command with the given argument values, resolving its secrets, working
+/// directory and timeout. Problems found before the process starts are returned as errors.
+pub async fn run_command(
+    db: &Database,
+    cmd: &Command,
+    values: &HashMap<String, String>,
+    options: RunCommandOptions<'_>,
+) -> Result<(i32, String, String, u64)> {
+    let (script, envs) = render_command_invocation(cmd, values)?;
+    let args_json = serde_json::to_string(values)?;
+    let secrets = crate::secrets::resolve_secrets(db, &cmd.required_secrets).await?;
+    let working_dir = resolve_working_dir(
+        cmd.working_dir.as_deref(),
+        cmd.target_paths.first().map(String::as_str),
+    )?;
+
+    execute_and_log(ExecuteAndLogInput {
+        db: Some(db),
+        command_id: &cmd.id,
+        command_name: &cmd.name,
+        script: &script,
+        timeout_dur: cmd
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(CMD_EXEC_TIMEOUT),
+        envs: &envs,
+        arguments_json: &args_json,
+        triggered_by: options.triggered_by,
+        max_retries: cmd.max_retries,
+        adapter_context: options.adapter_context,
+        on_output: options.on_output,
+        cancel: options.cancel,
+        secrets: &secrets,
+        working_dir,
+        shell: cmd.shell,
+        sandbox: cmd.sandbox.as_ref(),
+        artifacts: &cmd.artifact_globs,
+        output_parser: cmd.output_parser.as_ref(),
+        stdin: None,
+    })
+    .await
+}
+
+/// Resolves everything a run of `cmd` would use, the process command line included,
+/// without starting it. Secrets are listed by name only and the vault is not read.
+pub fn preview_command(
+    cmd: &Command,
+    values: &HashMap<String, String>,
+    timeout_dur: Duration,
+) -> Result<CommandPreview> {
+    let (script, envs) = render_command_invocation(cmd, values)?;
+    let working_dir = resolve_working_dir(
+        cmd.working_dir.as_deref(),
+        cmd.target_paths.first().map(String::as_str),
+    )?;
+    let process = shell_command(&script, cmd.shell, cmd.sandbox.as_ref())?;
+    let process = process.as_std();
+    let program = process.get_program().to_string_lossy().to_string();
+    let args: Vec<String> = process
+        .get_args()
+        .map(|a| a.to_string_lossy().to_string())
+        .collect();
+
+    Ok(CommandPreview {
+        command_id: cmd.id.clone(),
+        command_name: cmd.name.clone(),
+        command_line: format_command_line(&program, &args),
+        script,
+        program,
+        args,
+        env: envs.into_iter().collect(),
+        secrets: cmd.required_secrets.clone(),
+        working_dir: working_dir.map(|d| d.to_string_lossy().to_string()),
+        timeout_ms: timeout_dur.as_millis() as u64,
+        sandbox: cmd.sandbox.clone(),
+        requires_approval: cmd.mcp_policy.requires_approval(),
+    })
+}
+
+/// Joins a program and its arguments for display, single-quoting anything a shell would split.
+fn format_command_line(program: &str, args: &[String]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .map(|part| {
+            let plain = !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+            if plain {
+                part.to_string()
+            } else {
+                format!("'{}'", part.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Executes a command and logs the result.
 ///
 /// Respects the provided `max_retries`. The total number of attempts will be
-/// 1 (the initial attempt) + the number of retries.
+/// 1 (the initial attempt) + the number of retries. Logged runs raise the
+/// `command.executed` webhook event once the last attempt finishes.
 pub async fn execute_and_log(input: ExecuteAndLogInput<'_>) -> Result<(i32, String, String, u64)> {
+    let db = input.db;
+    let (command_id, command_name, triggered_by) =
+        (input.command_id, input.command_name, input.triggered_by);
+    let started = Instant::now();
+    let result = execute_attempts(input).await;
+
+    let status = match &result {
+        Ok((0, ..)) => "success",
+        Ok(_) => "failed",
+        Err(_) => "error",
+    };
+    metrics::increment(
+        metrics::EXECUTIONS,
+        &[("trigger", triggered_by), ("status", status)],
+    );
+    metrics::observe_since(
+        metrics::EXECUTION_DURATION,
+        &[("trigger", triggered_by)],
+        started,
+    );
+
+    if let Some(db) = db {
+        let (exit_code, duration_ms, error) = match &result {
+            Ok((exit_code, _, _, duration_ms)) => (Some(*exit_code), Some(*duration_ms), None),
+            Err(e) => (None, None, Some(e.to_string())),
+        };
+        let success = exit_code == Some(0);
+        let text = match exit_code {
+            Some(_) if success => format!("Command '{}' succeeded", command_name),
+            Some(code) => format!("Command '{}' failed with exit code {}", command_name, code),
+            None => format!("Command '{}' did not finish", command_name),
+        };
+        crate::webhooks::emit(
+            db,
+            WebhookEvent::CommandExecuted,
+            &text,
+            serde_json::json!({
+                "commandId": command_id,
+                "commandName": command_name,
+                "triggeredBy": triggered_by,
+                "success": success,
+                "exitCode": exit_code,
+                "durationMs": duration_ms,
+                "error": error,
+            }),
+        )
+        .await;
+    }
+
+    result
+}
+
+async fn execute_attempts(input: ExecuteAndLogInput<'_>) -> Result<(i32, String, String, u64)> {
     let max_attempts = input.max_retries.map(|r| (r as u32) + 1).unwrap_or(1);
+    let secret_values: Vec<String> = input.secrets.iter().map(|(_, v)| v.clone()).collect();
+    let envs: Vec<(String, String)> = input.envs.iter().chain(input.secrets).cloned().collect();
+    let redactor = match input.db {
+        Some(db) => Redactor::load(db, input.envs).await,
+        None => Redactor::default(),
+    };
+    let redactor = Arc::new(redactor.with_values(secret_values));
+    let on_output = input
+        .on_output
+        .clone()
+        .map(|sink| redacting_sink(sink, redactor.clone()));
+
+    // Only logged runs need a snapshot; collecting one starts a few short-lived processes.
+    let environment = match input.db {
+        Some(_) => Some(
+            env_snapshot::capture(
+                input.script,
+                input.envs,
+                input.secrets,
+                input.working_dir.as_deref(),
+                input.sandbox,
+            )
+            .await,
+        ),
+        None => None,
+    };
 
     let mut last_exit_code: i32 = 0;
     let mut last_stdout = String::new();
@@ -275,22 +804,43 @@ pub async fn execute_and_log(input: ExecuteAndLogInput<'_>) -> Result<(i32, Stri
     for attempt in 1..=max_attempts {
         let attempt_start = std::time::Instant::now();
 
-        match execute_shell_with_timeout_env(input.script, input.timeout_dur, input.envs).await {
+        match execute_shell_streaming(
+            input.script,
+            input.timeout_dur,
+            &envs,
+            ProcessOptions {
+                dir: input.working_dir.clone(),
+                shell: input.shell,
+                sandbox: input.sandbox.cloned(),
+                stdin: input.stdin.map(str::to_string),
+            },
+            on_output.clone(),
+            input.cancel.clone(),
+        )
+        .await
+        {
             Ok((exit_code, stdout, stderr)) => {
-                let (stdout_redacted, stdout_was_redacted) = redact(&stdout);
-                let (stderr_redacted, stderr_was_redacted) = redact(&stderr);
+                let (stdout_redacted, stdout_was_redacted) = redactor.redact(&stdout);
+                let (stderr_redacted, stderr_was_redacted) = redactor.redact(&stderr);
                 let is_redacted = stdout_was_redacted || stderr_was_redacted;
                 let is_timeout = false;
                 let failure_class = classify_failure(exit_code, &stderr_redacted, is_timeout);
                 let duration_ms = attempt_start.elapsed().as_millis() as u64;
+                let structured_output = input.output_parser.and_then(|parser| {
+                    output_parser::parse(parser, &stdout_redacted, input.working_dir.as_deref())
+                        .unwrap_or_else(|e| {
+                            log::warn!("Failed to parse output of '{}': {}", input.command_name, e);
+                            None
+                        })
+                });
 
                 last_exit_code = exit_code;
                 last_stdout = stdout_redacted.clone();
                 last_stderr = stderr_redacted.clone();
                 last_duration_ms = duration_ms;
 
-                if let Some(db) = input.db {
-                    let _ = db
+                let log_id = match input.db {
+                    Some(db) => db
                         .add_execution_log(&ExecutionLogInput {
                             command_id: input.command_id,
                             command_name: input.command_name,
@@ -304,16 +854,34 @@ pub async fn execute_and_log(input: ExecuteAndLogInput<'_>) -> Result<(i32, Stri
                             adapter_context: input.adapter_context,
                             is_redacted,
                             attempt_number: attempt as u8,
+                            structured_output: structured_output.as_ref(),
+                            environment: environment.as_ref(),
                         })
-                        .await;
-                }
+                        .await
+                        .ok(),
+                    None => None,
+                };
 
-                if exit_code == 0 {
-                    return Ok((exit_code, stdout_redacted, stderr_redacted, duration_ms));
-                }
-
-                let should_retry = attempt < max_attempts && failure_class.is_retryable();
+                let should_retry =
+                    exit_code != 0 && attempt < max_attempts && failure_class.is_retryable();
                 if !should_retry {
+                    // Artifacts are kept from the final attempt only, whether it passed or not.
+                    if let (Some(db), Some(log_id)) = (input.db, log_id) {
+                        if let Err(e) = artifacts::capture(
+                            db,
+                            &log_id,
+                            input.working_dir.as_deref(),
+                            input.artifacts,
+                        )
+                        .await
+                        {
+                            log::warn!(
+                                "Failed to capture artifacts for '{}': {}",
+                                input.command_name,
+                                e
+                            );
+                        }
+                    }
                     return Ok((exit_code, stdout_redacted, stderr_redacted, duration_ms));
                 }
             }
@@ -341,6 +909,8 @@ pub async fn execute_and_log(input: ExecuteAndLogInput<'_>) -> Result<(i32, Stri
                             adapter_context: input.adapter_context,
                             is_redacted: false,
                             attempt_number: attempt as u8,
+                            structured_output: None,
+                            environment: environment.as_ref(),
                         })
                         .await;
                 }
@@ -350,6 +920,30 @@ pub async fn execute_and_log(input: ExecuteAndLogInput<'_>) -> Result<(i32, Stri
                     return Err(AppError::InvalidInput { message });
                 }
             }
+            Err(AppError::ExecutionCancelled) => {
+                let message = AppError::ExecutionCancelled.to_string();
+                if let Some(db) = input.db {
+                    let _ = db
+                        .add_execution_log(&ExecutionLogInput {
+                            command_id: input.command_id,
+                            command_name: input.command_name,
+                            arguments_json: input.arguments_json,
+                            stdout: "",
+                            stderr: &message,
+                            exit_code: -1,
+                            duration_ms: attempt_start.elapsed().as_millis() as u64,
+                            triggered_by: input.triggered_by,
+                            failure_class: Some(FailureClass::Cancelled.as_str()),
+                            adapter_context: input.adapter_context,
+                            is_redacted: false,
+                            attempt_number: attempt as u8,
+                            structured_output: None,
+                            environment: environment.as_ref(),
+                        })
+                        .await;
+                }
+                return Err(AppError::ExecutionCancelled);
+            }
             Err(e) => {
                 return Err(e);
             }
@@ -363,6 +957,112 @@ pub async fn execute_and_log(input: ExecuteAndLogInput<'_>) -> Result<(i32, Stri
 mod tests {
     use super::*;
 
+    fn argument(arg_type: ArgumentType) -> CommandArgument {
+        CommandArgument {
+            name: "value".to_string(),
+            description: String::new(),
+            arg_type,
+            required: true,
+            default_value: None,
+            options: None,
+            pattern: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    #[test]
+    fn typed_arguments_are_validated() {
+        let mut count = argument(ArgumentType::Number);
+        count.min = Some(1.0);
+        count.max = Some(10.0);
+        assert!(validate_argument_value(&count, "5").is_ok());
+        assert!(validate_argument_value(&count, "2.5").is_ok());
+        assert!(validate_argument_value(&count, "11").is_err());
+        assert!(validate_argument_value(&count, "five").is_err());
+
+        let flag = argument(ArgumentType::Boolean);
+        assert!(validate_argument_value(&flag, "true").is_ok());
+        assert!(validate_argument_value(&flag, "yes").is_err());
+
+        let path = argument(ArgumentType::Path);
+        assert!(validate_argument_value(&path, "src/main.rs").is_ok());
+        assert!(validate_argument_value(&path, "../secrets").is_err());
+
+        let mut branch = argument(ArgumentType::String);
+        branch.pattern = Some("[a-z0-9/-]+".to_string());
+        assert!(validate_argument_value(&branch, "feature/login").is_ok());
+        // The pattern must match the whole value, not just part of it.
+        assert!(validate_argument_value(&branch, "main; rm -rf ~").is_err());
+    }
+
+    #[test]
+    fn rendering_enforces_required_arguments_and_defaults() {
+        let mut cmd = Command::new(
+            "bump".to_string(),
+            String::new(),
+            "npm version {{level}}".to_string(),
+            false,
+        );
+        let mut level = argument(ArgumentType::Enum);
+        level.name = "level".to_string();
+        level.options = Some(vec!["patch".to_string(), "minor".to_string()]);
+        cmd.arguments = vec![level];
+
+        let err = render_command_invocation(&cmd, &HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("Missing required argument"));
+        let values = HashMap::from([("level".to_string(), "major".to_string())]);
+        assert!(render_command_invocation(&cmd, &values).is_err());
+
+        cmd.arguments[0].default_value = Some("patch".to_string());
+        let (_, envs) = render_command_invocation(&cmd, &HashMap::new()).unwrap();
+        assert_eq!(envs[0].0, "RW_ARG_LEVEL");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn preview_resolves_the_invocation_without_running_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cmd = Command::new(
+            "deploy".to_string(),
+            String::new(),
+            "touch ran; echo {{env}}".to_string(),
+            false,
+        );
+        let mut env = argument(ArgumentType::String);
+        env.name = "env".to_string();
+        cmd.arguments = vec![env];
+        cmd.required_secrets = vec!["DEPLOY_TOKEN".to_string()];
+        cmd.working_dir = Some(dir.path().to_string_lossy().to_string());
+        cmd.shell = Some(CommandShell::Bash);
+
+        let values = HashMap::from([("env".to_string(), "prod".to_string())]);
+        let preview = preview_command(&cmd, &values, Duration::from_secs(5)).unwrap();
+
+        assert_eq!(preview.script, "touch ran; echo $RW_ARG_ENV");
+        assert_eq!(preview.program, "bash");
+        assert_eq!(
+            preview.command_line,
+            "bash -c 'touch ran; echo $RW_ARG_ENV'"
+        );
+        assert_eq!(
+            preview.env.get("RW_ARG_ENV").map(String::as_str),
+            Some("'prod'")
+        );
+        assert_eq!(preview.secrets, ["DEPLOY_TOKEN"]);
+        assert_eq!(preview.timeout_ms, 5000);
+        assert!(!dir.path().join("ran").exists());
+    }
+
+    #[test]
+    fn command_lines_quote_only_when_needed() {
+        let args = ["-c".to_string(), "echo 'hi'".to_string(), String::new()];
+        assert_eq!(
+            format_command_line("sh", &args),
+            "sh -c 'echo '\\''hi'\\''' ''"
+        );
+    }
+
     #[cfg(target_os = "windows")]
     #[test]
     fn test_escape_cmd_argument() {
@@ -393,6 +1093,348 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn streaming_delivers_lines_as_they_are_produced() {
+        let lines = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let collected = Arc::clone(&lines);
+        let sink: OutputSink = Arc::new(move |stream, line| {
+            collected.lock().push((stream, line.to_string()));
+        });
+
+        let (exit_code, stdout, stderr) = execute_shell_streaming(
+            "printf 'one\\ntwo\\n'; echo oops >&2; printf partial",
+            Duration::from_secs(10),
+            &[],
+            ProcessOptions::default(),
+            Some(sink),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(stdout, "one\ntwo\npartial");
+        assert_eq!(stderr, "oops\n");
+        let lines = lines.lock();
+        let stdout_lines: Vec<&str> = lines
+            .iter()
+            .filter(|(s, _)| *s == OutputStream::Stdout)
+            .map(|(_, l)| l.as_str())
+            .collect();
+        assert_eq!(stdout_lines, vec!["one", "two", "partial"]);
+        assert!(lines.contains(&(OutputStream::Stderr, "oops".to_string())));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn streaming_times_out_long_running_scripts() {
+        let started = std::time::Instant::now();
+        let result = execute_shell_streaming(
+            "sleep 5",
+            Duration::from_millis(200),
+            &[],
+            ProcessOptions::default(),
+            None,
+            None,
+        )
+        .await;
+        assert!(
+            matches!(result, Err(AppError::InvalidInput { message }) if message.contains("timed out"))
+        );
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn cancellation_kills_the_process_tree() {
+        let (registration, token) = register_execution("cancel-test");
+        let (pid_tx, pid_rx) = tokio::sync::oneshot::channel::<String>();
+        let pid_tx = parking_lot::Mutex::new(Some(pid_tx));
+        let sink: OutputSink = Arc::new(move |_, line| {
+            if let Some(tx) = pid_tx.lock().take() {
+                let _ = tx.send(line.to_string());
+            }
+        });
+
+        let run = tokio::spawn(execute_shell_streaming(
+            "sleep 30 & echo $!; wait",
+            Duration::from_secs(20),
+            &[],
+            ProcessOptions::default(),
+            Some(sink),
+            Some(token),
+        ));
+        let grandchild = pid_rx.await.unwrap();
+        assert!(cancel_execution("cancel-test"));
+
+        let result = run.await.unwrap();
+        assert!(matches!(result, Err(AppError::ExecutionCancelled)));
+
+        drop(registration);
+        assert!(!cancel_execution("cancel-test"));
+
+        // The background sleep is in the shell's process group, so it was killed as well.
+        // An orphan may linger briefly as a zombie until it is reaped, which counts as dead.
+        let mut alive = true;
+        for _ in 0..50 {
+            let output = std::process::Command::new("ps")
+                .args(["-o", "stat=", "-p", &grandchild])
+                .output()
+                .unwrap();
+            let state = String::from_utf8_lossy(&output.stdout);
+            if !output.status.success() || state.trim().starts_with('Z') {
+                alive = false;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(
+            !alive,
+            "background process {} survived cancellation",
+            grandchild
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn secrets_are_injected_and_redacted() {
+        let secrets = vec![("VAULT_TOKEN".to_string(), "hunter2-value".to_string())];
+        let (exit_code, stdout, _, _) = execute_and_log(ExecuteAndLogInput {
+            db: None,
+            command_id: "cmd",
+            command_name: "cmd",
+            script: "echo \"token is $VAULT_TOKEN\"",
+            timeout_dur: Duration::from_secs(10),
+            envs: &[],
+            arguments_json: "{}",
+            triggered_by: "test",
+            max_retries: None,
+            adapter_context: None,
+            on_output: None,
+            cancel: None,
+            secrets: &secrets,
+            working_dir: None,
+            shell: None,
+            sandbox: None,
+            artifacts: &[],
+            output_parser: None,
+            stdin: None,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(stdout.trim(), "token is [REDACTED]");
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn parsed_output_is_stored_with_the_log() {
+        let db = Database::new_in_memory().await.unwrap();
+        let parser = OutputParser::Regex {
+            pattern: r"(?P<passed>\d+) passed".to_string(),
+        };
+        execute_and_log(ExecuteAndLogInput {
+            db: Some(&db),
+            command_id: "cmd",
+            command_name: "cmd",
+            script: "echo '12 passed'",
+            timeout_dur: Duration::from_secs(10),
+            envs: &[],
+            arguments_json: "{}",
+            triggered_by: "test",
+            max_retries: None,
+            adapter_context: None,
+            on_output: None,
+            cancel: None,
+            secrets: &[],
+            working_dir: None,
+            shell: None,
+            sandbox: None,
+            artifacts: &[],
+            output_parser: Some(&parser),
+            stdin: None,
+        })
+        .await
+        .unwrap();
+
+        let logs = db.get_execution_history(10).await.unwrap();
+        assert_eq!(
+            logs[0].structured_output,
+            Some(serde_json::json!({ "matches": [{ "passed": "12" }] }))
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn environment_snapshot_is_logged_without_secrets() {
+        let db = Database::new_in_memory().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let envs = vec![("RW_ARG_TARGET".to_string(), "staging".to_string())];
+        let secrets = vec![("VAULT_TOKEN".to_string(), "hunter2-value".to_string())];
+        execute_and_log(ExecuteAndLogInput {
+            db: Some(&db),
+            command_id: "cmd",
+            command_name: "cmd",
+            script: "echo \"$RW_ARG_TARGET\"",
+            timeout_dur: Duration::from_secs(10),
+            envs: &envs,
+            arguments_json: "{}",
+            triggered_by: "test",
+            max_retries: None,
+            adapter_context: None,
+            on_output: None,
+            cancel: None,
+            secrets: &secrets,
+            working_dir: Some(dir.path().to_path_buf()),
+            shell: None,
+            sandbox: None,
+            artifacts: &[],
+            output_parser: None,
+            stdin: None,
+        })
+        .await
+        .unwrap();
+
+        let logs = db.get_execution_history(10).await.unwrap();
+        let snapshot = logs[0].environment.as_ref().unwrap();
+        assert_eq!(snapshot.env["RW_ARG_TARGET"], "staging");
+        assert!(!snapshot.env.contains_key("VAULT_TOKEN"));
+        assert_eq!(
+            snapshot.working_dir.as_deref(),
+            Some(dir.path().to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            db.get_execution_log(&logs[0].id).await.unwrap().environment,
+            logs[0].environment
+        );
+    }
+
+    #[test]
+    fn env_refs_follow_the_selected_shell() {
+        let script = "deploy {{env}}";
+        assert_eq!(
+            replace_template_with_env_ref(script, "env", Some(CommandShell::Bash)),
+            "deploy $RW_ARG_ENV"
+        );
+        assert_eq!(
+            replace_template_with_env_ref(script, "env", Some(CommandShell::Pwsh)),
+            "deploy $env:RW_ARG_ENV"
+        );
+        assert_eq!(
+            replace_template_with_env_ref(script, "env", Some(CommandShell::Cmd)),
+            "deploy %RW_ARG_ENV%"
+        );
+    }
+
+    #[test]
+    fn working_dir_resolves_repo_root() {
+        let repo = tempfile::tempdir().unwrap();
+        std::fs::create_dir(repo.path().join("web")).unwrap();
+        let root = repo.path().to_str().unwrap();
+
+        assert_eq!(resolve_working_dir(None, Some(root)).unwrap(), None);
+        assert_eq!(
+            resolve_working_dir(Some("{{repo_root}}/web"), Some(root)).unwrap(),
+            Some(PathBuf::from(format!("{}/web", root)))
+        );
+        assert_eq!(
+            resolve_working_dir(Some("web"), Some(root)).unwrap(),
+            Some(repo.path().join("web"))
+        );
+        assert!(resolve_working_dir(Some("{{repo_root}}"), None).is_err());
+        assert!(resolve_working_dir(Some("{{repo_root}}/missing"), Some(root)).is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn scripts_run_in_the_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let (exit_code, stdout, _) = execute_shell_streaming(
+            "pwd",
+            Duration::from_secs(10),
+            &[],
+            ProcessOptions {
+                dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        let expected = dir.path().canonicalize().unwrap();
+        assert_eq!(Path::new(stdout.trim()).canonicalize().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn stdin_is_piped_to_the_script() {
+        let input = "line one\nline two\n".repeat(20_000);
+        let (exit_code, stdout, _) = execute_shell_streaming(
+            "cat; echo done",
+            Duration::from_secs(10),
+            &[],
+            ProcessOptions {
+                stdin: Some(input.clone()),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(stdout, format!("{}done\n", input));
+    }
+
+    #[test]
+    fn stdin_is_only_accepted_when_declared() {
+        let mut cmd = Command::new(
+            "filter".to_string(),
+            String::new(),
+            "cat".to_string(),
+            false,
+        );
+        assert!(validate_stdin(&cmd, None).is_ok());
+        assert!(validate_stdin(&cmd, Some("diff")).is_err());
+
+        cmd.accepts_stdin = true;
+        assert!(validate_stdin(&cmd, Some("diff")).is_ok());
+        assert!(validate_stdin(&cmd, Some(&"x".repeat(MAX_STDIN_LENGTH + 1))).is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn sandbox_scrubs_env_and_sets_limits() {
+        let (exit_code, stdout, _) = execute_shell_streaming(
+            "echo \"${CARGO_MANIFEST_DIR:-unset} $RW_ARG_NAME\"; ulimit -t; ulimit -v",
+            Duration::from_secs(10),
+            &[("RW_ARG_NAME".to_string(), "kept".to_string())],
+            ProcessOptions {
+                sandbox: Some(SandboxProfile {
+                    scrub_env: true,
+                    cpu_time_secs: Some(30),
+                    memory_mb: Some(2048),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exit_code, 0);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines, ["unset kept", "30", "2097152"]);
+    }
+
     #[test]
     fn test_classify_failure_timeout() {
         assert_eq!(classify_failure(0, "", true), FailureClass::Timeout);
@@ -0,0 +1,328 @@
+//! Checksums and signatures for configuration exports.
+//!
+//! Every export carries a manifest with the SHA-256 of each rule, command, skill,
+//! collection and setting, and of the sync state when included, serialized as JSON so the
+//! hashes hold for both the JSON and the YAML form of the file. With the `sign_exports` setting on, the manifest is also signed
+//! with an Ed25519 key pair kept in the OS keychain. Importing a file whose contents no
+//! longer match its manifest, or whose signature does not verify, is refused; files
+//! without a manifest or signed by a key that is not trusted here import with a warning.
+
+use ring::rand::SystemRandom;
+use ring::signature::{self, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::keychain;
+use crate::models::{
+    ExportConfiguration, ExportIntegrity, ExportManifest, ExportVerification, ManifestEntry,
+    ManifestSignature,
+};
+use crate::util::{hex, unhex};
+
+pub const SIGN_EXPORTS_KEY: &str = "sign_exports";
+/// JSON array of hex public keys.
+pub const TRUSTED_EXPORT_KEYS_KEY: &str = "trusted_export_keys";
+
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// Whether `value` is a hex-encoded Ed25519 public key.
+pub fn is_public_key(value: &str) -> bool {
+    unhex(value).is_some_and(|bytes| bytes.len() == PUBLIC_KEY_LEN)
+}
+
+fn tampered(detail: impl std::fmt::Display) -> AppError {
+    AppError::Validation(format!(
+        "Configuration file was modified after it was exported: {}",
+        detail
+    ))
+}
+
+fn entry(kind: &str, id: &str, artifact: &impl Serialize) -> Result<ManifestEntry> {
+    Ok(ManifestEntry {
+        kind: kind.to_string(),
+        id: id.to_string(),
+        sha256: hex(&Sha256::digest(serde_json::to_vec(artifact)?)),
+    })
+}
+
+fn entries(config: &ExportConfiguration) -> Result<Vec<ManifestEntry>> {
+    let mut entries = Vec::new();
+    for rule in &config.rules {
+        entries.push(entry("rule", &rule.id, rule)?);
+    }
+    for command in &config.commands {
+        entries.push(entry("command", &command.id, command)?);
+    }
+    for skill in &config.skills {
+        entries.push(entry("skill", &skill.id, skill)?);
+    }
+    for collection in &config.collections {
+        entries.push(entry("collection", &collection.id, collection)?);
+    }
+    for item in &config.collection_items {
+        let id = format!("{}/{}", item.collection_id, item.item_id);
+        entries.push(entry("collection_item", &id, item)?);
+    }
+    for (key, value) in &config.settings {
+        entries.push(entry("setting", key, value)?);
+    }
+    if let Some(state) = &config.state {
+        entries.push(entry("state", "state", state)?);
+    }
+    Ok(entries)
+}
+
+/// The bytes a signature covers: the format version, the export time and the entries.
+fn signed_payload(config: &ExportConfiguration, artifacts: &[ManifestEntry]) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&(
+        &config.version,
+        &config.exported_at,
+        artifacts,
+    ))?)
+}
+
+/// Replaces the manifest of `config` with one for its current contents, signed with `key`
+/// when given.
+pub fn attach(config: &mut ExportConfiguration, key: Option<&Ed25519KeyPair>) -> Result<()> {
+    let artifacts = entries(config)?;
+    let signature = match key {
+        Some(key) => Some(ManifestSignature {
+            public_key: hex(key.public_key().as_ref()),
+            signature: hex(key.sign(&signed_payload(config, &artifacts)?).as_ref()),
+        }),
+        None => None,
+    };
+    config.manifest = Some(ExportManifest {
+        artifacts,
+        signature,
+    });
+    Ok(())
+}
+
+/// Checks `config` against its manifest. Fails when the contents or the signature do not
+/// match; otherwise reports how far the file can be trusted, given the `trusted` public
+/// keys.
+pub fn verify(config: &ExportConfiguration, trusted: &[String]) -> Result<ExportVerification> {
+    let Some(manifest) = &config.manifest else {
+        return Ok(ExportVerification {
+            integrity: ExportIntegrity::Unverified,
+            signer: None,
+        });
+    };
+
+    let actual = entries(config)?;
+    for found in &actual {
+        match manifest
+            .artifacts
+            .iter()
+            .find(|listed| listed.kind == found.kind && listed.id == found.id)
+        {
+            Some(listed) if listed.sha256 == found.sha256 => {}
+            Some(_) => return Err(tampered(format!("{} '{}' changed", found.kind, found.id))),
+            None => {
+                return Err(tampered(format!(
+                    "{} '{}' is not in the manifest",
+                    found.kind, found.id
+                )))
+            }
+        }
+    }
+    if actual.len() != manifest.artifacts.len() {
+        return Err(tampered(format!(
+            "the manifest lists {} artifacts but the file has {}",
+            manifest.artifacts.len(),
+            actual.len()
+        )));
+    }
+
+    let Some(signed) = &manifest.signature else {
+        return Ok(ExportVerification {
+            integrity: ExportIntegrity::Checksummed,
+            signer: None,
+        });
+    };
+    let (Some(public_key), Some(signature)) = (unhex(&signed.public_key), unhex(&signed.signature))
+    else {
+        return Err(tampered("the signature is malformed"));
+    };
+    UnparsedPublicKey::new(&signature::ED25519, public_key)
+        .verify(&signed_payload(config, &manifest.artifacts)?, &signature)
+        .map_err(|_| tampered("the signature does not match"))?;
+
+    let integrity = if trusted
+        .iter()
+        .any(|key| key.eq_ignore_ascii_case(&signed.public_key))
+    {
+        ExportIntegrity::Trusted
+    } else {
+        ExportIntegrity::UnknownSigner
+    };
+    Ok(ExportVerification {
+        integrity,
+        signer: Some(signed.public_key.to_lowercase()),
+    })
+}
+
+fn parse_key_pair(pkcs8_hex: &str) -> Result<Ed25519KeyPair> {
+    unhex(pkcs8_hex)
+        .and_then(|pkcs8| Ed25519KeyPair::from_pkcs8(&pkcs8).ok())
+        .ok_or_else(|| AppError::Encryption {
+            message: "Stored export signing key is invalid".to_string(),
+        })
+}
+
+/// This machine's signing key, created and stored on first use.
+async fn signing_key() -> Result<Ed25519KeyPair> {
+    if let Some(stored) = keychain::run_blocking(keychain::load_export_signing_key).await? {
+        return parse_key_pair(&stored);
+    }
+    let pkcs8 =
+        Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).map_err(|_| AppError::Encryption {
+            message: "Failed to generate an export signing key".to_string(),
+        })?;
+    let pkcs8_hex = hex(pkcs8.as_ref());
+    let stored = pkcs8_hex.clone();
+    keychain::run_blocking(move || keychain::store_export_signing_key(&stored)).await?;
+    parse_key_pair(&pkcs8_hex)
+}
+
+/// The hex public key that signs this machine's exports, for others to trust.
+pub async fn public_key() -> Result<String> {
+    Ok(hex(signing_key().await?.public_key().as_ref()))
+}
+
+/// Adds the manifest to an export about to be written, signed if the user asked for it.
+pub async fn seal(db: &Database, config: &mut ExportConfiguration) -> Result<()> {
+    let key = if crate::settings::load_or_default(db).await.sign_exports {
+        Some(signing_key().await?)
+    } else {
+        None
+    };
+    attach(config, key.as_ref())
+}
+
+/// [`verify`] against the trusted keys in settings and this machine's own key.
+pub async fn check(db: &Database, config: &ExportConfiguration) -> Result<ExportVerification> {
+    let mut trusted = crate::settings::load_or_default(db)
+        .await
+        .trusted_export_keys;
+    let signed = config
+        .manifest
+        .as_ref()
+        .is_some_and(|manifest| manifest.signature.is_some());
+    if signed {
+        match keychain::run_blocking(keychain::load_export_signing_key).await {
+            Ok(Some(stored)) => {
+                if let Ok(own) = parse_key_pair(&stored) {
+                    trusted.push(hex(own.public_key().as_ref()));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to read the export signing key: {}", e),
+        }
+    }
+    let verification = verify(config, &trusted)?;
+    match verification.integrity {
+        ExportIntegrity::Unverified => {
+            log::warn!("Importing a configuration file without a manifest")
+        }
+        ExportIntegrity::UnknownSigner => log::warn!(
+            "Importing a configuration file signed by an untrusted key: {}",
+            verification.signer.as_deref().unwrap_or_default()
+        ),
+        _ => {}
+    }
+    Ok(verification)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn export() -> ExportConfiguration {
+        let db = Database::new_in_memory().await.unwrap();
+        db.create_rule(crate::models::CreateRuleInput {
+            id: None,
+            name: "Style".to_string(),
+            description: "House style".to_string(),
+            content: "Prefer early returns.".to_string(),
+            scope: crate::models::Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![crate::models::AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+        db.set_setting("mcp_auto_start", "true").await.unwrap();
+        db.export_configuration().await.unwrap()
+    }
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_checksums_survive_yaml_and_catch_edits() {
+        let mut config = export().await;
+        assert_eq!(
+            verify(&config, &[]).unwrap().integrity,
+            ExportIntegrity::Unverified
+        );
+
+        attach(&mut config, None).unwrap();
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let mut parsed: ExportConfiguration = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            verify(&parsed, &[]).unwrap().integrity,
+            ExportIntegrity::Checksummed
+        );
+
+        parsed.rules[0].content = "Prefer deep nesting.".to_string();
+        assert!(verify(&parsed, &[]).is_err());
+
+        let mut added = config.clone();
+        added.settings.insert("sign_exports".into(), "false".into());
+        assert!(verify(&added, &[]).is_err());
+
+        let mut removed = config.clone();
+        removed.rules.clear();
+        assert!(verify(&removed, &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signatures_are_checked_and_matched_against_trusted_keys() {
+        let key = key_pair();
+        let public_key = hex(key.public_key().as_ref());
+        let mut config = export().await;
+        attach(&mut config, Some(&key)).unwrap();
+
+        let unknown = verify(&config, &[]).unwrap();
+        assert_eq!(unknown.integrity, ExportIntegrity::UnknownSigner);
+        assert_eq!(unknown.signer.as_deref(), Some(public_key.as_str()));
+        assert_eq!(
+            verify(&config, &[public_key.to_uppercase()])
+                .unwrap()
+                .integrity,
+            ExportIntegrity::Trusted
+        );
+
+        // Re-hashing edited contents does not help without the private key.
+        let mut forged = config.clone();
+        forged.rules[0].content = "Prefer deep nesting.".to_string();
+        let signature = config.manifest.as_ref().unwrap().signature.clone();
+        attach(&mut forged, None).unwrap();
+        forged.manifest.as_mut().unwrap().signature = signature;
+        assert!(verify(&forged, &[public_key]).is_err());
+    }
+
+    #[test]
+    fn test_is_public_key() {
+        assert!(is_public_key(&"ab".repeat(PUBLIC_KEY_LEN)));
+        assert!(!is_public_key("abc"));
+        assert!(!is_public_key(&"zz".repeat(PUBLIC_KEY_LEN)));
+    }
+}
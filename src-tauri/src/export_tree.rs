@@ -0,0 +1,339 @@
+//! Configuration exports as a directory tree.
+//!
+//! Instead of one large file, each rule is written as a Markdown file whose YAML
+//! frontmatter holds everything but the content, and each command and skill as a YAML file.
+//! They sit under `rules/`, `commands/` and `skills/`, in one folder per collection, so an
+//! exported configuration committed to Git shows every change as a small per-file diff.
+//! `ruleweaver-export.yaml` at the root keeps the rest of the export: its version, the
+//! collections and memberships, settings and the manifest. Reading a tree gives back the
+//! same [`ExportConfiguration`], so the manifest still verifies.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::atomic_write::write_atomic;
+use crate::error::{AppError, Result};
+use crate::execution::slugify;
+use crate::models::{Collection, CollectionItemType, Command, ExportConfiguration, Rule, Skill};
+
+pub const INDEX_FILE: &str = "ruleweaver-export.yaml";
+const RULES_DIR: &str = "rules";
+const COMMANDS_DIR: &str = "commands";
+const SKILLS_DIR: &str = "skills";
+const FRONTMATTER_DELIMITER: &str = "---\n";
+
+fn invalid(path: &Path, message: impl std::fmt::Display) -> AppError {
+    AppError::InvalidInput {
+        message: format!("Invalid export file {}: {}", path.display(), message),
+    }
+}
+
+fn to_yaml(value: &impl serde::Serialize) -> Result<String> {
+    serde_yaml::to_string(value).map_err(|e| AppError::InvalidInput {
+        message: e.to_string(),
+    })
+}
+
+/// A file name stem that is unique among `taken`, derived from `name` and falling back
+/// to `id`.
+fn unique_stem(name: &str, id: &str, taken: &mut HashSet<String>) -> String {
+    let slug = slugify(name);
+    let mut stem = if slug.is_empty() { slugify(id) } else { slug };
+    if stem.is_empty() || taken.contains(&stem) {
+        let suffix: String = id
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(8)
+            .collect();
+        stem = format!("{}-{}", stem, suffix).trim_matches('-').to_string();
+    }
+    taken.insert(stem.clone());
+    stem
+}
+
+/// The folder of every collection relative to a kind's directory, nested like the
+/// collections themselves.
+fn collection_folders(collections: &[Collection]) -> HashMap<&str, PathBuf> {
+    let mut folders: HashMap<&str, PathBuf> = HashMap::new();
+    let mut taken: HashMap<Option<&str>, HashSet<String>> = HashMap::new();
+    let mut pending: Vec<&Collection> = collections.iter().collect();
+    pending.sort_by(|a, b| (a.position, &a.name).cmp(&(b.position, &b.name)));
+    loop {
+        let before = pending.len();
+        pending.retain(|collection| {
+            let parent = match collection.parent_id.as_deref() {
+                None => PathBuf::new(),
+                Some(parent_id) => match folders.get(parent_id) {
+                    Some(folder) => folder.clone(),
+                    // Placed once the parent is; a missing parent leaves it at the top.
+                    None if collections.iter().any(|c| c.id == parent_id) => return true,
+                    None => PathBuf::new(),
+                },
+            };
+            let siblings = taken.entry(collection.parent_id.as_deref()).or_default();
+            let stem = unique_stem(&collection.name, &collection.id, siblings);
+            folders.insert(collection.id.as_str(), parent.join(stem));
+            false
+        });
+        if pending.is_empty() || pending.len() == before {
+            return folders;
+        }
+    }
+}
+
+fn rule_file(rule: &Rule) -> Result<String> {
+    let mut frontmatter = serde_yaml::to_value(rule).map_err(|e| AppError::InvalidInput {
+        message: e.to_string(),
+    })?;
+    if let Some(fields) = frontmatter.as_mapping_mut() {
+        fields.remove("content");
+    }
+    Ok(format!(
+        "{}{}{}{}",
+        FRONTMATTER_DELIMITER,
+        to_yaml(&frontmatter)?,
+        FRONTMATTER_DELIMITER,
+        rule.content
+    ))
+}
+
+fn parse_rule_file(path: &Path, raw: &str) -> Result<Rule> {
+    let rest = raw
+        .strip_prefix(FRONTMATTER_DELIMITER)
+        .ok_or_else(|| invalid(path, "missing frontmatter"))?;
+    let end = if rest.starts_with(FRONTMATTER_DELIMITER) {
+        0
+    } else {
+        rest.find("\n---\n")
+            .map(|i| i + 1)
+            .ok_or_else(|| invalid(path, "unterminated frontmatter"))?
+    };
+    let mut frontmatter: serde_yaml::Value =
+        serde_yaml::from_str(&rest[..end]).map_err(|e| invalid(path, e))?;
+    let fields = frontmatter
+        .as_mapping_mut()
+        .ok_or_else(|| invalid(path, "frontmatter is not a mapping"))?;
+    let content = &rest[end + FRONTMATTER_DELIMITER.len()..];
+    fields.insert("content".into(), content.into());
+    serde_yaml::from_value(frontmatter).map_err(|e| invalid(path, e))
+}
+
+/// Removes what an earlier export left in `dir`. Refuses to touch a non-empty directory
+/// that is not an export tree.
+fn clear(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    if !dir.join(INDEX_FILE).exists() && dir.read_dir()?.next().is_some() {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "{} is not empty and does not hold an exported configuration",
+                dir.display()
+            ),
+        });
+    }
+    for kind in [RULES_DIR, COMMANDS_DIR, SKILLS_DIR] {
+        let path = dir.join(kind);
+        if path.exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes `config` as a tree under `dir`, replacing an earlier export there.
+pub fn write(dir: &Path, config: &ExportConfiguration) -> Result<()> {
+    clear(dir)?;
+    std::fs::create_dir_all(dir)?;
+
+    let folders = collection_folders(&config.collections);
+    let folder_of: HashMap<(CollectionItemType, String), &PathBuf> = config
+        .collection_items
+        .iter()
+        .filter_map(|item| {
+            folders
+                .get(item.collection_id.as_str())
+                .map(|folder| ((item.item_type, item.item_id.clone()), folder))
+        })
+        .collect();
+    let mut taken: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    let mut path_for =
+        |kind_dir: &str, item_type: CollectionItemType, id: &str, name: &str, extension: &str| {
+            let folder = match folder_of.get(&(item_type, id.to_string())) {
+                Some(folder) => dir.join(kind_dir).join(folder),
+                None => dir.join(kind_dir),
+            };
+            let stem = unique_stem(name, id, taken.entry(folder.clone()).or_default());
+            folder.join(format!("{}.{}", stem, extension))
+        };
+
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    for rule in &config.rules {
+        let path = path_for(
+            RULES_DIR,
+            CollectionItemType::Rule,
+            &rule.id,
+            &rule.name,
+            "md",
+        );
+        files.push((path, rule_file(rule)?));
+    }
+    for command in &config.commands {
+        let path = path_for(
+            COMMANDS_DIR,
+            CollectionItemType::Command,
+            &command.id,
+            &command.name,
+            "yaml",
+        );
+        files.push((path, to_yaml(command)?));
+    }
+    for skill in &config.skills {
+        let path = path_for(
+            SKILLS_DIR,
+            CollectionItemType::Skill,
+            &skill.id,
+            &skill.name,
+            "yaml",
+        );
+        files.push((path, to_yaml(skill)?));
+    }
+
+    let mut index = config.clone();
+    index.rules.clear();
+    index.commands.clear();
+    index.skills.clear();
+    files.push((dir.join(INDEX_FILE), to_yaml(&index)?));
+
+    for (path, content) in files {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, content)?;
+    }
+    Ok(())
+}
+
+/// Files under `dir/kind` with one of `extensions`, in path order.
+fn files_in(dir: &Path, kind: &str, extensions: &[&str]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir.join(kind))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn read_yaml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    serde_yaml::from_str(&std::fs::read_to_string(path)?).map_err(|e| invalid(path, e))
+}
+
+/// Reads a tree written by [`write`].
+pub fn read(dir: &Path) -> Result<ExportConfiguration> {
+    let index_path = dir.join(INDEX_FILE);
+    if !index_path.is_file() {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "{} has no {}; it is not an exported configuration",
+                dir.display(),
+                INDEX_FILE
+            ),
+        });
+    }
+    let mut config: ExportConfiguration = read_yaml(&index_path)?;
+
+    for path in files_in(dir, RULES_DIR, &["md"]) {
+        let raw = std::fs::read_to_string(&path)?;
+        config.rules.push(parse_rule_file(&path, &raw)?);
+    }
+    for path in files_in(dir, COMMANDS_DIR, &["yaml", "yml"]) {
+        config.commands.push(read_yaml::<Command>(&path)?);
+    }
+    for path in files_in(dir, SKILLS_DIR, &["yaml", "yml"]) {
+        config.skills.push(read_yaml::<Skill>(&path)?);
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::models::{CreateCollectionInput, CreateRuleInput, Scope};
+
+    #[tokio::test]
+    async fn test_tree_round_trips_and_keeps_its_manifest_valid() {
+        let db = Database::new_in_memory().await.unwrap();
+        let team = db
+            .create_collection(CreateCollectionInput {
+                id: None,
+                name: "Team".to_string(),
+                parent_id: None,
+            })
+            .await
+            .unwrap();
+        for (name, content) in [
+            (
+                "Style",
+                "Prefer early returns.\n\n---\n\nKeep functions short.  ",
+            ),
+            ("Style!", ""),
+        ] {
+            let rule = db
+                .create_rule(CreateRuleInput {
+                    id: None,
+                    name: name.to_string(),
+                    description: "House style".to_string(),
+                    content: content.to_string(),
+                    scope: Scope::Global,
+                    target_paths: None,
+                    enabled_adapters: vec![crate::models::AdapterType::Gemini],
+                    enabled: true,
+                    metadata: Default::default(),
+                })
+                .await
+                .unwrap();
+            db.set_item_collection(CollectionItemType::Rule, &rule.id, Some(&team.id))
+                .await
+                .unwrap();
+        }
+        let mut config = db.export_configuration().await.unwrap();
+        crate::export_manifest::attach(&mut config, None).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("export");
+        write(&root, &config).unwrap();
+        assert!(root.join(RULES_DIR).join("team").join("style.md").is_file());
+        assert_eq!(files_in(&root, RULES_DIR, &["md"]).len(), 2);
+
+        let read_back = read(&root).unwrap();
+        assert_eq!(read_back.rules.len(), 2);
+        let edited = read_back.rules.iter().find(|r| r.name == "Style").unwrap();
+        assert_eq!(
+            edited.content,
+            "Prefer early returns.\n\n---\n\nKeep functions short.  "
+        );
+        crate::export_manifest::verify(&read_back, &[]).unwrap();
+
+        // Re-exporting replaces the previous tree.
+        config.rules.truncate(1);
+        crate::export_manifest::attach(&mut config, None).unwrap();
+        write(&root, &config).unwrap();
+        assert_eq!(read(&root).unwrap().rules.len(), 1);
+
+        let other = dir.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(other.join("notes.txt"), "keep me").unwrap();
+        assert!(write(&other, &config).is_err());
+        assert!(read(&other).is_err());
+    }
+}
@@ -0,0 +1,753 @@
+//! Third-party MCP servers shared across AI tools.
+//!
+//! Each supported tool keeps its MCP servers in its own config file and format. Servers
+//! found there can be imported into RuleWeaver, which then writes the managed list back
+//! into every enabled tool. Entries RuleWeaver did not write are never touched; the names
+//! it wrote to each file are remembered so servers deleted here are removed there too.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::atomic_write::write_atomic;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    AdapterType, CreateExternalMcpServerInput, ExternalMcpServer, McpServerCandidate,
+    McpServerScanResult, McpServerSpec, McpServerSyncResult, McpTransport,
+    UpdateExternalMcpServerInput,
+};
+
+pub(crate) const SYNC_STATE_KEY: &str = "external_mcp_sync_state";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    /// `{"mcpServers": {name: {command, args, env} | {<url_key>, headers}}}`
+    McpServersJson { url_key: &'static str },
+    /// OpenCode's `{"mcp": {name: {type: "local" | "remote", ...}}}`
+    OpenCodeJson,
+    /// Codex's `[mcp_servers.<name>]` tables
+    CodexToml,
+}
+
+#[derive(Debug, Clone)]
+struct ConfigTarget {
+    adapter: AdapterType,
+    path: PathBuf,
+    format: ConfigFormat,
+    /// Scanned on import only; RuleWeaver writes to the tool's primary config instead
+    scan_only: bool,
+}
+
+fn config_targets(home: &Path) -> Vec<ConfigTarget> {
+    let target = |adapter, path: PathBuf, format, scan_only| ConfigTarget {
+        adapter,
+        path,
+        format,
+        scan_only,
+    };
+    let mcp_servers = ConfigFormat::McpServersJson { url_key: "url" };
+    vec![
+        target(
+            AdapterType::ClaudeCode,
+            home.join(".claude.json"),
+            mcp_servers,
+            false,
+        ),
+        target(
+            AdapterType::ClaudeCode,
+            home.join(".claude").join("settings.json"),
+            mcp_servers,
+            true,
+        ),
+        target(
+            AdapterType::Cursor,
+            home.join(".cursor").join("mcp.json"),
+            mcp_servers,
+            false,
+        ),
+        target(
+            AdapterType::Windsurf,
+            home.join(".codeium")
+                .join("windsurf")
+                .join("mcp_config.json"),
+            ConfigFormat::McpServersJson {
+                url_key: "serverUrl",
+            },
+            false,
+        ),
+        target(
+            AdapterType::Gemini,
+            home.join(".gemini").join("settings.json"),
+            ConfigFormat::McpServersJson { url_key: "httpUrl" },
+            false,
+        ),
+        target(
+            AdapterType::Codex,
+            home.join(".codex").join("config.toml"),
+            ConfigFormat::CodexToml,
+            false,
+        ),
+        target(
+            AdapterType::OpenCode,
+            home.join(".config").join("opencode").join("opencode.json"),
+            ConfigFormat::OpenCodeJson,
+            false,
+        ),
+    ]
+}
+
+fn home_dir() -> Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| AppError::Path("Could not determine home directory".into()))
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn string_map(value: Option<&Value>) -> BTreeMap<String, String> {
+    value
+        .and_then(Value::as_object)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_mcp_servers_entry(entry: &Value) -> Option<McpServerSpec> {
+    if let Some(command) = entry.get("command").and_then(Value::as_str) {
+        return Some(McpServerSpec {
+            transport: McpTransport::Stdio,
+            command: Some(command.to_string()),
+            args: string_list(entry.get("args")),
+            env: string_map(entry.get("env")),
+            ..Default::default()
+        });
+    }
+    let url = ["url", "serverUrl", "httpUrl"]
+        .iter()
+        .find_map(|key| entry.get(*key).and_then(Value::as_str))?;
+    Some(McpServerSpec {
+        transport: McpTransport::Http,
+        url: Some(url.to_string()),
+        headers: string_map(entry.get("headers")),
+        ..Default::default()
+    })
+}
+
+fn parse_opencode_entry(entry: &Value) -> Option<McpServerSpec> {
+    match entry.get("type").and_then(Value::as_str) {
+        Some("remote") => Some(McpServerSpec {
+            transport: McpTransport::Http,
+            url: Some(entry.get("url")?.as_str()?.to_string()),
+            headers: string_map(entry.get("headers")),
+            ..Default::default()
+        }),
+        _ => {
+            let mut command = string_list(entry.get("command")).into_iter();
+            Some(McpServerSpec {
+                transport: McpTransport::Stdio,
+                command: Some(command.next()?),
+                args: command.collect(),
+                env: string_map(entry.get("environment")),
+                ..Default::default()
+            })
+        }
+    }
+}
+
+fn parse_codex_entry(entry: &toml::Value) -> Option<McpServerSpec> {
+    // Round-trip through JSON so the string helpers can be shared.
+    let entry = serde_json::to_value(entry).ok()?;
+    if let Some(command) = entry.get("command").and_then(Value::as_str) {
+        return Some(McpServerSpec {
+            transport: McpTransport::Stdio,
+            command: Some(command.to_string()),
+            args: string_list(entry.get("args")),
+            env: string_map(entry.get("env")),
+            ..Default::default()
+        });
+    }
+    Some(McpServerSpec {
+        transport: McpTransport::Http,
+        url: Some(entry.get("url")?.as_str()?.to_string()),
+        headers: string_map(entry.get("http_headers")),
+        ..Default::default()
+    })
+}
+
+fn read_json_object(path: &Path) -> Result<Map<String, Value>> {
+    let raw = fs::read_to_string(path)?;
+    if raw.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str::<Value>(&raw)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(AppError::InvalidInput {
+            message: format!("{} is not a JSON object", path.display()),
+        }),
+    }
+}
+
+fn read_toml_table(path: &Path) -> Result<toml::Table> {
+    let raw = fs::read_to_string(path)?;
+    raw.parse::<toml::Table>()
+        .map_err(|e| AppError::InvalidInput {
+            message: format!("{} is not valid TOML: {}", path.display(), e),
+        })
+}
+
+fn parse_target(target: &ConfigTarget) -> Result<Vec<(String, McpServerSpec)>> {
+    let mut servers = Vec::new();
+    match target.format {
+        ConfigFormat::McpServersJson { .. } | ConfigFormat::OpenCodeJson => {
+            let root = read_json_object(&target.path)?;
+            let (key, parse): (&str, fn(&Value) -> Option<McpServerSpec>) = match target.format {
+                ConfigFormat::OpenCodeJson => ("mcp", parse_opencode_entry),
+                _ => ("mcpServers", parse_mcp_servers_entry),
+            };
+            if let Some(entries) = root.get(key).and_then(Value::as_object) {
+                for (name, entry) in entries {
+                    if let Some(spec) = parse(entry) {
+                        servers.push((name.clone(), spec));
+                    }
+                }
+            }
+        }
+        ConfigFormat::CodexToml => {
+            let root = read_toml_table(&target.path)?;
+            if let Some(entries) = root.get("mcp_servers").and_then(toml::Value::as_table) {
+                for (name, entry) in entries {
+                    if let Some(spec) = parse_codex_entry(entry) {
+                        servers.push((name.clone(), spec));
+                    }
+                }
+            }
+        }
+    }
+    Ok(servers)
+}
+
+async fn scan_home(db: &Database, home: &Path) -> Result<McpServerScanResult> {
+    let existing = db.get_all_external_mcp_servers().await?;
+    let mut result = McpServerScanResult::default();
+
+    for target in config_targets(home) {
+        if !target.path.is_file() {
+            continue;
+        }
+        let servers = match parse_target(&target) {
+            Ok(servers) => servers,
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("{}: {}", target.path.display(), e));
+                continue;
+            }
+        };
+        for (name, spec) in servers {
+            // RuleWeaver's own server is configured separately.
+            if name.eq_ignore_ascii_case("ruleweaver") {
+                continue;
+            }
+            result.candidates.push(McpServerCandidate {
+                id: uuid::Uuid::new_v4().to_string(),
+                existing_id: existing
+                    .iter()
+                    .find(|s| s.name.eq_ignore_ascii_case(&name))
+                    .map(|s| s.id.clone()),
+                name,
+                source_tool: target.adapter,
+                source_path: target.path.to_string_lossy().to_string(),
+                spec,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Lists MCP servers configured in the supported tools' global config files.
+pub async fn scan_tool_configs(db: &Database) -> Result<McpServerScanResult> {
+    scan_home(db, &home_dir()?).await
+}
+
+/// Imports candidates into the managed list. Candidates sharing a name are merged into one
+/// server enabled for every tool it was found in; an existing server of that name has its
+/// settings replaced and the new tools added.
+pub async fn import_candidates(
+    db: &Database,
+    candidates: Vec<McpServerCandidate>,
+) -> Result<Vec<ExternalMcpServer>> {
+    let mut merged: Vec<(String, McpServerSpec, Vec<AdapterType>)> = Vec::new();
+    for candidate in candidates {
+        match merged
+            .iter_mut()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(&candidate.name))
+        {
+            Some((_, _, adapters)) => {
+                if !adapters.contains(&candidate.source_tool) {
+                    adapters.push(candidate.source_tool);
+                }
+            }
+            None => merged.push((candidate.name, candidate.spec, vec![candidate.source_tool])),
+        }
+    }
+
+    let existing = db.get_all_external_mcp_servers().await?;
+    let mut imported = Vec::new();
+    for (name, spec, adapters) in merged {
+        let server = match existing.iter().find(|s| s.name.eq_ignore_ascii_case(&name)) {
+            Some(current) => {
+                let mut enabled_adapters = current.enabled_adapters.clone();
+                for adapter in adapters {
+                    if !enabled_adapters.contains(&adapter) {
+                        enabled_adapters.push(adapter);
+                    }
+                }
+                db.update_external_mcp_server(
+                    &current.id,
+                    UpdateExternalMcpServerInput {
+                        spec: Some(spec),
+                        enabled_adapters: Some(enabled_adapters),
+                        ..Default::default()
+                    },
+                )
+                .await?
+            }
+            None => {
+                db.create_external_mcp_server(CreateExternalMcpServerInput {
+                    name,
+                    spec,
+                    enabled_adapters: adapters,
+                    enabled: true,
+                })
+                .await?
+            }
+        };
+        imported.push(server);
+    }
+    Ok(imported)
+}
+
+fn render_mcp_servers_entry(spec: &McpServerSpec, url_key: &str) -> Value {
+    match spec.transport {
+        McpTransport::Stdio => {
+            let mut entry = json!({
+                "command": spec.command.clone().unwrap_or_default(),
+                "args": spec.args,
+            });
+            if !spec.env.is_empty() {
+                entry["env"] = json!(spec.env);
+            }
+            entry
+        }
+        McpTransport::Http => {
+            let mut entry = Map::new();
+            entry.insert(
+                url_key.to_string(),
+                json!(spec.url.clone().unwrap_or_default()),
+            );
+            if !spec.headers.is_empty() {
+                entry.insert("headers".to_string(), json!(spec.headers));
+            }
+            Value::Object(entry)
+        }
+    }
+}
+
+fn render_opencode_entry(spec: &McpServerSpec) -> Value {
+    match spec.transport {
+        McpTransport::Stdio => {
+            let mut command = vec![spec.command.clone().unwrap_or_default()];
+            command.extend(spec.args.iter().cloned());
+            let mut entry = json!({ "type": "local", "command": command, "enabled": true });
+            if !spec.env.is_empty() {
+                entry["environment"] = json!(spec.env);
+            }
+            entry
+        }
+        McpTransport::Http => {
+            let mut entry = json!({
+                "type": "remote",
+                "url": spec.url.clone().unwrap_or_default(),
+                "enabled": true,
+            });
+            if !spec.headers.is_empty() {
+                entry["headers"] = json!(spec.headers);
+            }
+            entry
+        }
+    }
+}
+
+fn render_codex_entry(spec: &McpServerSpec) -> toml::Value {
+    let mut entry = toml::Table::new();
+    match spec.transport {
+        McpTransport::Stdio => {
+            entry.insert(
+                "command".to_string(),
+                toml::Value::String(spec.command.clone().unwrap_or_default()),
+            );
+            entry.insert(
+                "args".to_string(),
+                toml::Value::Array(spec.args.iter().cloned().map(toml::Value::String).collect()),
+            );
+            if !spec.env.is_empty() {
+                entry.insert(
+                    "env".to_string(),
+                    toml::Value::Table(
+                        spec.env
+                            .iter()
+                            .map(|(k, v)| (k.clone(), toml::Value::String(v.clone())))
+                            .collect(),
+                    ),
+                );
+            }
+        }
+        McpTransport::Http => {
+            entry.insert(
+                "url".to_string(),
+                toml::Value::String(spec.url.clone().unwrap_or_default()),
+            );
+            if !spec.headers.is_empty() {
+                entry.insert(
+                    "http_headers".to_string(),
+                    toml::Value::Table(
+                        spec.headers
+                            .iter()
+                            .map(|(k, v)| (k.clone(), toml::Value::String(v.clone())))
+                            .collect(),
+                    ),
+                );
+            }
+        }
+    }
+    toml::Value::Table(entry)
+}
+
+/// Rewrites the managed entries in one config file, leaving everything else in place.
+/// Returns the new file content, or `None` when nothing changed.
+fn merge_into_target(
+    target: &ConfigTarget,
+    servers: &[&ExternalMcpServer],
+    previously_synced: &[String],
+) -> Result<Option<String>> {
+    let exists = target.path.is_file();
+    match target.format {
+        ConfigFormat::McpServersJson { .. } | ConfigFormat::OpenCodeJson => {
+            let mut root = if exists {
+                read_json_object(&target.path)?
+            } else {
+                Map::new()
+            };
+            let before = Value::Object(root.clone());
+            let key = match target.format {
+                ConfigFormat::OpenCodeJson => "mcp",
+                _ => "mcpServers",
+            };
+            let container = root
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            let Value::Object(entries) = container else {
+                return Err(AppError::InvalidInput {
+                    message: format!("'{}' in {} is not an object", key, target.path.display()),
+                });
+            };
+            for name in previously_synced {
+                entries.remove(name);
+            }
+            for server in servers {
+                let entry = match target.format {
+                    ConfigFormat::McpServersJson { url_key } => {
+                        render_mcp_servers_entry(&server.spec, url_key)
+                    }
+                    _ => render_opencode_entry(&server.spec),
+                };
+                entries.insert(server.name.clone(), entry);
+            }
+            if entries.is_empty() && before.get(key).is_none() {
+                root.remove(key);
+            }
+            let after = Value::Object(root);
+            if exists && after == before {
+                return Ok(None);
+            }
+            Ok(Some(serde_json::to_string_pretty(&after)?))
+        }
+        ConfigFormat::CodexToml => {
+            let mut root = if exists {
+                read_toml_table(&target.path)?
+            } else {
+                toml::Table::new()
+            };
+            let before = root.clone();
+            let container = root
+                .entry("mcp_servers".to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            let toml::Value::Table(entries) = container else {
+                return Err(AppError::InvalidInput {
+                    message: format!("'mcp_servers' in {} is not a table", target.path.display()),
+                });
+            };
+            for name in previously_synced {
+                entries.remove(name);
+            }
+            for server in servers {
+                entries.insert(server.name.clone(), render_codex_entry(&server.spec));
+            }
+            if entries.is_empty() && !before.contains_key("mcp_servers") {
+                root.remove("mcp_servers");
+            }
+            if exists && root == before {
+                return Ok(None);
+            }
+            toml::to_string(&root)
+                .map(Some)
+                .map_err(|e| AppError::Internal {
+                    message: format!("Failed to serialize {}: {}", target.path.display(), e),
+                })
+        }
+    }
+}
+
+async fn read_sync_state(db: &Database) -> HashMap<String, Vec<String>> {
+    match db.get_setting(SYNC_STATE_KEY).await {
+        Ok(Some(v)) => serde_json::from_str(&v).unwrap_or_default(),
+        _ => HashMap::new(),
+    }
+}
+
+async fn sync_home(db: &Database, home: &Path) -> Result<McpServerSyncResult> {
+    let servers: Vec<ExternalMcpServer> = db
+        .get_all_external_mcp_servers()
+        .await?
+        .into_iter()
+        .filter(|s| s.enabled)
+        .collect();
+    let mut state = read_sync_state(db).await;
+    let mut result = McpServerSyncResult::default();
+
+    for target in config_targets(home).into_iter().filter(|t| !t.scan_only) {
+        let key = target.path.to_string_lossy().to_string();
+        let wanted: Vec<&ExternalMcpServer> = servers
+            .iter()
+            .filter(|s| s.enabled_adapters.contains(&target.adapter))
+            .collect();
+        let previous = state.get(&key).cloned().unwrap_or_default();
+        if wanted.is_empty() && previous.is_empty() {
+            continue;
+        }
+        // Only write into tools that are installed.
+        if !target.path.parent().is_some_and(Path::is_dir) {
+            continue;
+        }
+
+        match merge_into_target(&target, &wanted, &previous) {
+            Ok(content) => {
+                if let Some(content) = content {
+                    if let Err(e) = write_atomic(&target.path, &content) {
+                        result.errors.push(format!("{}: {}", key, e));
+                        continue;
+                    }
+                    result.files_written.push(key.clone());
+                }
+                if wanted.is_empty() {
+                    state.remove(&key);
+                } else {
+                    state.insert(key, wanted.iter().map(|s| s.name.clone()).collect());
+                }
+            }
+            Err(e) => result.errors.push(format!("{}: {}", key, e)),
+        }
+    }
+
+    db.set_setting(SYNC_STATE_KEY, &serde_json::to_string(&state)?)
+        .await?;
+    Ok(result)
+}
+
+/// Writes the managed servers into every enabled tool's global MCP config.
+pub async fn sync_to_tools(db: &Database) -> Result<McpServerSyncResult> {
+    sync_home(db, &home_dir()?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn scan_reads_each_tool_format() {
+        let db = Database::new_in_memory().await.unwrap();
+        let home = TempDir::new().unwrap();
+        write(
+            &home.path().join(".cursor/mcp.json"),
+            r#"{"mcpServers": {
+                "github": {"command": "npx", "args": ["-y", "@mcp/github"], "env": {"TOKEN": "x"}},
+                "ruleweaver": {"url": "http://127.0.0.1:8080"}
+            }}"#,
+        );
+        write(
+            &home.path().join(".codex/config.toml"),
+            "model = \"o3\"\n\n[mcp_servers.docs]\nurl = \"https://docs.example.com/mcp\"\n",
+        );
+        write(
+            &home.path().join(".config/opencode/opencode.json"),
+            r#"{"mcp": {"fs": {"type": "local", "command": ["mcp-fs", "--root", "/tmp"]}}}"#,
+        );
+        write(&home.path().join(".gemini/settings.json"), "not json");
+
+        let scan = scan_home(&db, home.path()).await.unwrap();
+        assert_eq!(scan.errors.len(), 1, "{:?}", scan.errors);
+
+        let find = |name: &str| scan.candidates.iter().find(|c| c.name == name).unwrap();
+        let github = find("github");
+        assert_eq!(github.source_tool, AdapterType::Cursor);
+        assert_eq!(github.spec.command.as_deref(), Some("npx"));
+        assert_eq!(github.spec.env.get("TOKEN").map(String::as_str), Some("x"));
+
+        let docs = find("docs");
+        assert_eq!(docs.source_tool, AdapterType::Codex);
+        assert_eq!(docs.spec.transport, McpTransport::Http);
+
+        let fs_server = find("fs");
+        assert_eq!(fs_server.spec.command.as_deref(), Some("mcp-fs"));
+        assert_eq!(fs_server.spec.args, vec!["--root", "/tmp"]);
+
+        assert!(scan.candidates.iter().all(|c| c.name != "ruleweaver"));
+    }
+
+    #[tokio::test]
+    async fn import_merges_same_named_servers_across_tools() {
+        let db = Database::new_in_memory().await.unwrap();
+        let candidate = |tool| McpServerCandidate {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: "github".to_string(),
+            source_tool: tool,
+            source_path: String::new(),
+            spec: McpServerSpec {
+                command: Some("npx".to_string()),
+                ..Default::default()
+            },
+            existing_id: None,
+        };
+
+        let imported = import_candidates(
+            &db,
+            vec![
+                candidate(AdapterType::Cursor),
+                candidate(AdapterType::Codex),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(
+            imported[0].enabled_adapters,
+            vec![AdapterType::Cursor, AdapterType::Codex]
+        );
+
+        let reimported = import_candidates(&db, vec![candidate(AdapterType::Gemini)])
+            .await
+            .unwrap();
+        assert_eq!(reimported[0].id, imported[0].id);
+        assert_eq!(reimported[0].enabled_adapters.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn sync_preserves_foreign_entries_and_removes_deleted_servers() {
+        let db = Database::new_in_memory().await.unwrap();
+        let home = TempDir::new().unwrap();
+        let cursor = home.path().join(".cursor/mcp.json");
+        let codex = home.path().join(".codex/config.toml");
+        write(
+            &cursor,
+            r#"{"mcpServers": {"personal": {"command": "mine"}}, "other": true}"#,
+        );
+        write(&codex, "model = \"o3\"\n");
+
+        let server = db
+            .create_external_mcp_server(CreateExternalMcpServerInput {
+                name: "github".to_string(),
+                spec: McpServerSpec {
+                    command: Some("npx".to_string()),
+                    args: vec!["-y".to_string(), "@mcp/github".to_string()],
+                    ..Default::default()
+                },
+                enabled_adapters: vec![AdapterType::Cursor, AdapterType::Codex],
+                enabled: true,
+            })
+            .await
+            .unwrap();
+
+        let result = sync_home(&db, home.path()).await.unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.files_written.len(), 2);
+
+        let cursor_json: Value =
+            serde_json::from_str(&fs::read_to_string(&cursor).unwrap()).unwrap();
+        assert_eq!(cursor_json["mcpServers"]["github"]["command"], "npx");
+        assert_eq!(cursor_json["mcpServers"]["personal"]["command"], "mine");
+        assert_eq!(cursor_json["other"], true);
+        let codex_toml = read_toml_table(&codex).unwrap();
+        assert_eq!(codex_toml["model"].as_str(), Some("o3"));
+        assert_eq!(
+            codex_toml["mcp_servers"]["github"]["command"].as_str(),
+            Some("npx")
+        );
+
+        let unchanged = sync_home(&db, home.path()).await.unwrap();
+        assert!(unchanged.files_written.is_empty());
+
+        db.delete_external_mcp_server(&server.id).await.unwrap();
+        sync_home(&db, home.path()).await.unwrap();
+        let cursor_json: Value =
+            serde_json::from_str(&fs::read_to_string(&cursor).unwrap()).unwrap();
+        assert!(cursor_json["mcpServers"].get("github").is_none());
+        assert_eq!(cursor_json["mcpServers"]["personal"]["command"], "mine");
+        assert!(read_toml_table(&codex).unwrap()["mcp_servers"]
+            .as_table()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn sync_skips_tools_that_are_not_installed() {
+        let db = Database::new_in_memory().await.unwrap();
+        let home = TempDir::new().unwrap();
+        db.create_external_mcp_server(CreateExternalMcpServerInput {
+            name: "docs".to_string(),
+            spec: McpServerSpec {
+                transport: McpTransport::Http,
+                url: Some("https://docs.example.com/mcp".to_string()),
+                ..Default::default()
+            },
+            enabled_adapters: vec![AdapterType::Windsurf],
+            enabled: true,
+        })
+        .await
+        .unwrap();
+
+        let result = sync_home(&db, home.path()).await.unwrap();
+        assert!(result.files_written.is_empty());
+        assert!(!home.path().join(".codeium").exists());
+    }
+}
@@ -0,0 +1,245 @@
+//! Transitional dual-write mode between SQLite and file storage.
+//!
+//! Instead of switching in one step, a migration can keep both stores in sync for a while:
+//! SQLite stays the source of truth for reads and every rule change is also written to the
+//! rule files. Before the final cutover, [`verify_migration`] reports whether the two stores
+//! have diverged, and the cutover is refused while they have. Rolling back is cheap because
+//! SQLite never stopped being current: the rule files are removed and the mode switches back.
+
+use std::fs;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::{delete_rule_file, verify_migration, MigrationResult, VerificationResult};
+use crate::commands::storage_location_for_rule;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+
+pub const DUAL_WRITE_MODE: &str = "dual";
+pub(crate) const DUAL_WRITE_STARTED_KEY: &str = "file_storage_dual_write_started_at";
+pub(crate) const DUAL_WRITE_UNTIL_KEY: &str = "file_storage_dual_write_until";
+pub(crate) const BACKUP_PATH_KEY: &str = "file_storage_backup_path";
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DualWriteStatus {
+    pub active: bool,
+    pub started_at: Option<DateTime<Utc>>,
+    /// End of the dual-write period; cutting over earlier has to be forced
+    pub cutover_after: Option<DateTime<Utc>>,
+    pub ready_for_cutover: bool,
+    pub divergence: Option<VerificationResult>,
+}
+
+async fn get_timestamp(db: &Database, key: &str) -> Result<Option<DateTime<Utc>>> {
+    Ok(db
+        .get_setting(key)
+        .await?
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|at| at.with_timezone(&Utc)))
+}
+
+async fn ensure_dual_write(db: &Database) -> Result<()> {
+    if db.get_storage_mode().await? != DUAL_WRITE_MODE {
+        return Err(AppError::InvalidInput {
+            message: "Storage is not in dual-write mode".to_string(),
+        });
+    }
+    Ok(())
+}
+
+async fn clear_dual_write(db: &Database, mode: &str) -> Result<()> {
+    db.set_storage_mode(mode).await?;
+    db.set_setting(DUAL_WRITE_STARTED_KEY, "").await?;
+    db.set_setting(DUAL_WRITE_UNTIL_KEY, "").await
+}
+
+/// Writes every rule to file storage and enters dual-write mode for `days` days.
+pub async fn start_dual_write(db: &Database, days: u32) -> Result<MigrationResult> {
+    if db.get_storage_mode().await? != "sqlite" {
+        return Err(AppError::InvalidInput {
+            message: "Dual-write can only be started from SQLite storage".to_string(),
+        });
+    }
+
+    let result = super::migrate_to_file_storage(db).await?;
+    if result.success {
+        let now = Utc::now();
+        let until = now + Duration::days(i64::from(days));
+        db.set_storage_mode(DUAL_WRITE_MODE).await?;
+        db.set_setting(DUAL_WRITE_STARTED_KEY, &now.to_rfc3339())
+            .await?;
+        db.set_setting(DUAL_WRITE_UNTIL_KEY, &until.to_rfc3339())
+            .await?;
+        if let Some(path) = &result.backup_path {
+            db.set_setting(BACKUP_PATH_KEY, path).await?;
+        }
+    }
+    Ok(result)
+}
+
+/// Where the dual-write period stands, with a divergence report while it is active.
+pub async fn get_dual_write_status(db: &Database) -> Result<DualWriteStatus> {
+    if db.get_storage_mode().await? != DUAL_WRITE_MODE {
+        return Ok(DualWriteStatus {
+            active: false,
+            started_at: None,
+            cutover_after: None,
+            ready_for_cutover: false,
+            divergence: None,
+        });
+    }
+
+    let started_at = get_timestamp(db, DUAL_WRITE_STARTED_KEY).await?;
+    let cutover_after = get_timestamp(db, DUAL_WRITE_UNTIL_KEY).await?;
+    let divergence = verify_migration(db).await?;
+    Ok(DualWriteStatus {
+        active: true,
+        started_at,
+        ready_for_cutover: divergence.is_valid && cutover_after.is_none_or(|at| at <= Utc::now()),
+        cutover_after,
+        divergence: Some(divergence),
+    })
+}
+
+/// Switches from dual-write to file storage. Refused while the stores diverge, and before
+/// the dual-write period has ended unless `force` is set.
+pub async fn complete_cutover(db: &Database, force: bool) -> Result<VerificationResult> {
+    ensure_dual_write(db).await?;
+
+    let divergence = verify_migration(db).await?;
+    if !divergence.is_valid {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "File storage has diverged from SQLite ({} missing, {} mismatched rules). Roll back or fix the rule files before cutting over.",
+                divergence.missing_rules.len(),
+                divergence.mismatched_rules.len()
+            ),
+        });
+    }
+    if !force {
+        if let Some(until) = get_timestamp(db, DUAL_WRITE_UNTIL_KEY).await? {
+            if until > Utc::now() {
+                return Err(AppError::InvalidInput {
+                    message: format!(
+                        "Dual-write period runs until {}",
+                        until.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                });
+            }
+        }
+    }
+
+    clear_dual_write(db, "file").await?;
+    Ok(divergence)
+}
+
+/// Leaves dual-write mode for SQLite. SQLite was kept current throughout, so nothing is
+/// restored: the rule files are removed, and so is the pre-migration backup, which is now
+/// older than the database. Returns how many rule files were removed.
+pub async fn rollback_dual_write(db: &Database) -> Result<u32> {
+    ensure_dual_write(db).await?;
+
+    let mut removed = 0u32;
+    for rule in db.get_all_rules().await? {
+        let location = storage_location_for_rule(&rule);
+        match delete_rule_file(&rule.id, &location, Some(db)).await {
+            Ok(true) => removed += 1,
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to remove rule file for {}: {}", rule.id, e),
+        }
+        db.remove_rule_file_index(&rule.id).await?;
+    }
+
+    if let Some(backup) = db.get_setting(BACKUP_PATH_KEY).await? {
+        if !backup.is_empty() {
+            fs::remove_file(&backup).ok();
+            fs::remove_file(format!("{}.checksum", backup)).ok();
+        }
+    }
+    db.set_setting(BACKUP_PATH_KEY, "").await?;
+    clear_dual_write(db, "sqlite").await?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_storage::{save_rule_to_disk, StorageLocation};
+    use crate::models::{AdapterType, CreateRuleInput, Scope, UpdateRuleInput};
+
+    #[tokio::test]
+    async fn test_cutover_waits_for_period_and_refuses_divergence_then_rollback_cleans_up() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path().to_string_lossy().to_string();
+        let rule = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "Dual".to_string(),
+                description: String::new(),
+                content: "Written to both stores".to_string(),
+                scope: Scope::Local,
+                target_paths: Some(vec![root.clone()]),
+                enabled_adapters: vec![AdapterType::ClaudeCode],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+        crate::commands::register_local_paths(&db, &[root])
+            .await
+            .unwrap();
+        let location = StorageLocation::Local(repo.path().to_path_buf());
+        save_rule_to_disk(&rule, &location).unwrap();
+        db.update_rule_file_index(&rule.id, &location)
+            .await
+            .unwrap();
+
+        db.set_storage_mode(DUAL_WRITE_MODE).await.unwrap();
+        let until = Utc::now() + Duration::days(3);
+        db.set_setting(DUAL_WRITE_UNTIL_KEY, &until.to_rfc3339())
+            .await
+            .unwrap();
+
+        let status = get_dual_write_status(&db).await.unwrap();
+        assert!(status.active);
+        assert!(status.divergence.unwrap().is_valid);
+        assert!(!status.ready_for_cutover);
+        assert!(complete_cutover(&db, false).await.is_err());
+
+        // A change that only reached SQLite is reported and blocks even a forced cutover.
+        db.update_rule(
+            &rule.id,
+            UpdateRuleInput {
+                content: Some("Changed behind the file's back".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let divergence = get_dual_write_status(&db)
+            .await
+            .unwrap()
+            .divergence
+            .unwrap();
+        assert_eq!(divergence.mismatched_rules, vec![rule.id.clone()]);
+        assert!(complete_cutover(&db, true).await.is_err());
+        assert_eq!(db.get_storage_mode().await.unwrap(), DUAL_WRITE_MODE);
+
+        assert_eq!(rollback_dual_write(&db).await.unwrap(), 1);
+        assert_eq!(db.get_storage_mode().await.unwrap(), "sqlite");
+        assert!(db.get_rule_file_path(&rule.id).await.unwrap().is_none());
+        assert!(crate::file_storage::load_rules_from_directory(
+            &crate::file_storage::get_local_rules_dir(repo.path())
+        )
+        .unwrap()
+        .0
+        .is_empty());
+        assert_eq!(
+            db.get_rule_by_id(&rule.id).await.unwrap().content,
+            "Changed behind the file's back"
+        );
+        assert!(!get_dual_write_status(&db).await.unwrap().active);
+    }
+}
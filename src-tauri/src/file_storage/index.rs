@@ -0,0 +1,301 @@
+//! Rebuilding `rule_file_index` and `sync_history` from what is actually on disk.
+//!
+//! Both tables are bookkeeping that can drift after a crash or a database restore, which
+//! shows up as rules that cannot be found on delete and as conflicts that are not real.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use walkdir::WalkDir;
+
+use super::{get_global_rules_dir, get_local_rules_dir, parse_rule_file};
+use crate::commands::{get_local_rule_roots, use_file_storage};
+use crate::database::{Database, RuleFileIndexEntry};
+use crate::error::Result;
+use crate::models::SyncHashRepair;
+use crate::sync::{compute_content_hash_public, SyncEngine};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileIndexIssueKind {
+    /// A rule file that could not be read or parsed
+    UnreadableFile,
+    /// Several rule files claim the same rule id; the first one found is indexed
+    DuplicateRuleId,
+    /// A rule file for a rule the database does not know
+    UnknownRule,
+    /// A rule in the database without a rule file, while file storage is in use
+    MissingRuleFile,
+    /// An adapter file edited outside RuleWeaver
+    ModifiedSyncedFile,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndexIssue {
+    pub kind: FileIndexIssueKind,
+    pub rule_id: Option<String>,
+    pub file_path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndexReport {
+    pub rule_files_scanned: u32,
+    pub index_entries_added: u32,
+    pub index_entries_updated: u32,
+    pub index_entries_removed: u32,
+    pub sync_hashes: SyncHashRepair,
+    pub issues: Vec<FileIndexIssue>,
+}
+
+struct ScannedRuleFiles {
+    entries: Vec<RuleFileIndexEntry>,
+    files_scanned: u32,
+    issues: Vec<FileIndexIssue>,
+}
+
+fn modified_secs(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    i64::try_from(modified.duration_since(UNIX_EPOCH).ok()?.as_secs()).ok()
+}
+
+/// Hashes every rule file under `dirs`, keeping the first file for each rule id.
+fn scan_rule_files(dirs: &[PathBuf]) -> ScannedRuleFiles {
+    let mut scanned = ScannedRuleFiles {
+        entries: Vec::new(),
+        files_scanned: 0,
+        issues: Vec::new(),
+    };
+    let mut seen: HashMap<String, String> = HashMap::new();
+
+    for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+        for entry in WalkDir::new(dir)
+            .follow_links(false)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            scanned.files_scanned += 1;
+            let file_path = path.to_string_lossy().to_string();
+
+            let parsed = fs::read_to_string(path)
+                .map_err(crate::error::AppError::from)
+                .and_then(|content| {
+                    parse_rule_file(path, &content).map(|parsed| (parsed, content))
+                });
+            let (parsed, content) = match parsed {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    scanned.issues.push(FileIndexIssue {
+                        kind: FileIndexIssueKind::UnreadableFile,
+                        rule_id: None,
+                        file_path: Some(file_path),
+                        message: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let rule_id = parsed.frontmatter.id;
+            if let Some(first) = seen.get(&rule_id) {
+                scanned.issues.push(FileIndexIssue {
+                    kind: FileIndexIssueKind::DuplicateRuleId,
+                    rule_id: Some(rule_id),
+                    message: format!("Same rule id as {}", first),
+                    file_path: Some(file_path),
+                });
+                continue;
+            }
+            seen.insert(rule_id.clone(), file_path.clone());
+            scanned.entries.push(RuleFileIndexEntry {
+                rule_id,
+                content_hash: Some(compute_content_hash_public(&content)),
+                last_modified: modified_secs(path),
+                file_path,
+            });
+        }
+    }
+
+    scanned
+}
+
+/// Rescans the global and repository rule directories, rewrites `rule_file_index` to match
+/// them, repairs stale `sync_history` hashes and reports whatever could not be repaired.
+pub async fn rebuild_file_index(db: &Database) -> Result<FileIndexReport> {
+    let mut dirs = vec![get_global_rules_dir()?];
+    dirs.extend(
+        get_local_rule_roots(db)
+            .await?
+            .iter()
+            .map(|root| get_local_rules_dir(root)),
+    );
+    let scanned = tokio::task::spawn_blocking(move || scan_rule_files(&dirs))
+        .await
+        .map_err(|e| crate::error::AppError::InvalidInput {
+            message: e.to_string(),
+        })?;
+    let mut issues = scanned.issues;
+
+    let previous: HashMap<String, String> = db
+        .get_rule_file_index()
+        .await?
+        .into_iter()
+        .map(|entry| (entry.rule_id, entry.file_path))
+        .collect();
+    let (mut added, mut updated) = (0u32, 0u32);
+    for entry in &scanned.entries {
+        match previous.get(&entry.rule_id) {
+            None => added += 1,
+            Some(path) if path != &entry.file_path => updated += 1,
+            Some(_) => {}
+        }
+    }
+    let indexed: HashSet<&str> = scanned
+        .entries
+        .iter()
+        .map(|entry| entry.rule_id.as_str())
+        .collect();
+    let removed = previous
+        .keys()
+        .filter(|rule_id| !indexed.contains(rule_id.as_str()))
+        .count() as u32;
+
+    let rules = db.get_all_rules().await?;
+    let rule_ids: HashSet<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+    for entry in &scanned.entries {
+        if !rule_ids.contains(entry.rule_id.as_str()) {
+            issues.push(FileIndexIssue {
+                kind: FileIndexIssueKind::UnknownRule,
+                rule_id: Some(entry.rule_id.clone()),
+                file_path: Some(entry.file_path.clone()),
+                message: "Rule file has no matching rule in the database".to_string(),
+            });
+        }
+    }
+    if use_file_storage(db).await {
+        for rule in rules.iter().filter(|r| !indexed.contains(r.id.as_str())) {
+            issues.push(FileIndexIssue {
+                kind: FileIndexIssueKind::MissingRuleFile,
+                rule_id: Some(rule.id.clone()),
+                file_path: None,
+                message: format!("Rule '{}' has no rule file", rule.name),
+            });
+        }
+    }
+
+    let rule_files_scanned = scanned.files_scanned;
+    db.replace_rule_file_index(scanned.entries).await?;
+
+    let sync_hashes = SyncEngine::new(db).repair_file_hashes(&rules).await?;
+    for path in &sync_hashes.modified {
+        issues.push(FileIndexIssue {
+            kind: FileIndexIssueKind::ModifiedSyncedFile,
+            rule_id: None,
+            file_path: Some(path.clone()),
+            message: "Changed outside RuleWeaver since the last sync".to_string(),
+        });
+    }
+
+    Ok(FileIndexReport {
+        rule_files_scanned,
+        index_entries_added: added,
+        index_entries_updated: updated,
+        index_entries_removed: removed,
+        sync_hashes,
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_storage::{save_rule_to_disk, StorageLocation};
+    use crate::models::{AdapterType, CreateRuleInput, Scope};
+
+    #[tokio::test]
+    async fn test_rebuild_repairs_index_and_sync_hashes_and_reports_edits() {
+        let db = Database::new_in_memory().await.unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path().to_string_lossy().to_string();
+        crate::commands::register_local_paths(&db, std::slice::from_ref(&root))
+            .await
+            .unwrap();
+
+        let mut rules = Vec::new();
+        for name in ["Indexed", "Restored"] {
+            rules.push(
+                db.create_rule(CreateRuleInput {
+                    id: None,
+                    name: name.to_string(),
+                    description: String::new(),
+                    content: format!("{} content", name),
+                    scope: Scope::Local,
+                    target_paths: Some(vec![root.clone()]),
+                    enabled_adapters: vec![AdapterType::ClaudeCode],
+                    enabled: true,
+                    metadata: Default::default(),
+                })
+                .await
+                .unwrap(),
+            );
+        }
+        let location = StorageLocation::Local(repo.path().to_path_buf());
+        let restored_file = save_rule_to_disk(&rules[1], &location).unwrap();
+        save_rule_to_disk(&rules[0], &location).unwrap();
+        // The index only knows one rule, and under a rule that no longer exists.
+        db.update_rule_file_index("deleted-rule", &location)
+            .await
+            .unwrap();
+
+        let edited = repo.path().join("CLAUDE.md");
+        fs::write(&edited, "edited by hand").unwrap();
+        let gone = repo.path().join("gone.md").to_string_lossy().to_string();
+        let edited = edited.to_string_lossy().to_string();
+        db.set_file_hash(&edited, "hash-of-what-was-synced")
+            .await
+            .unwrap();
+        db.set_file_hash(&gone, "whatever").await.unwrap();
+
+        let report = rebuild_file_index(&db).await.unwrap();
+        assert_eq!(report.rule_files_scanned, 2);
+        assert_eq!(report.index_entries_added, 2);
+        assert_eq!(report.index_entries_removed, 1);
+        assert_eq!(
+            db.get_rule_file_path(&rules[1].id).await.unwrap(),
+            Some(restored_file.to_string_lossy().to_string())
+        );
+        assert!(db
+            .get_rule_file_path("deleted-rule")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Hashes of deleted files go; a hand-edited file keeps its hash and is reported.
+        assert_eq!(report.sync_hashes.removed, vec![gone.clone()]);
+        assert!(db.get_file_hash(&gone).await.unwrap().is_none());
+        assert_eq!(report.sync_hashes.modified, vec![edited.clone()]);
+        assert_eq!(
+            db.get_file_hash(&edited).await.unwrap().as_deref(),
+            Some("hash-of-what-was-synced")
+        );
+        let kinds: Vec<_> = report.issues.iter().map(|issue| issue.kind).collect();
+        assert_eq!(kinds, vec![FileIndexIssueKind::ModifiedSyncedFile]);
+
+        // Rules deleted from the database but still on disk are reported, not indexed away.
+        db.delete_rule(&rules[0].id).await.unwrap();
+        let report = rebuild_file_index(&db).await.unwrap();
+        assert_eq!(report.index_entries_added, 0);
+        assert!(report.issues.iter().any(|issue| {
+            issue.kind == FileIndexIssueKind::UnknownRule
+                && issue.rule_id.as_deref() == Some(rules[0].id.as_str())
+        }));
+    }
+}
@@ -7,6 +7,8 @@ use std::sync::{Mutex, OnceLock};
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::file_storage::{save_rule_to_disk, StorageLocation};
+use crate::jobs::{JobHandle, MigrationJob};
+use crate::models::JobKind;
 
 static MIGRATION_PROGRESS: AtomicU32 = AtomicU32::new(0);
 static MIGRATION_TOTAL: AtomicU32 = AtomicU32::new(0);
@@ -98,6 +100,17 @@ pub async fn migrate_to_file_storage(db: &Database) -> Result<MigrationResult> {
     let storage_dir = crate::file_storage::get_global_rules_dir()?;
     fs::create_dir_all(&storage_dir)?;
 
+    let job = JobHandle::begin(
+        db,
+        JobKind::Migration,
+        format!("Move {} rule(s) to rule files", total),
+        total,
+        &MigrationJob {
+            backup_path: backup_path.clone(),
+        },
+    )
+    .await;
+
     let mut rules_migrated = 0u32;
     let mut rules_skipped = 0u32;
     let mut errors = Vec::new();
@@ -151,12 +164,14 @@ pub async fn migrate_to_file_storage(db: &Database) -> Result<MigrationResult> {
                 });
             }
         }
+        job.checkpoint(db, rules_migrated + rules_skipped, &())
+            .await;
     }
+    job.finish(db).await;
 
     let success = errors.is_empty();
     if success {
-        let local_paths_json = serde_json::to_string(&local_rule_paths)?;
-        let _ = db.set_setting("local_rule_paths", &local_paths_json).await;
+        let _ = crate::commands::register_local_paths(db, &local_rule_paths).await;
     }
     if let Ok(mut state) = migration_state().lock() {
         state.current_rule = None;
@@ -282,7 +297,8 @@ pub fn get_migration_progress() -> MigrationProgress {
 pub async fn verify_migration(db: &Database) -> Result<VerificationResult> {
     let db_rules = db.get_all_rules().await?;
 
-    let load_result = crate::file_storage::load_rules_from_disk()?;
+    let local_roots = crate::commands::get_local_rule_roots(db).await?;
+    let load_result = crate::file_storage::load_rules_from_locations(&local_roots)?;
 
     let mut missing_rules = Vec::new();
     let mut extra_rules = Vec::new();
@@ -293,7 +309,11 @@ pub async fn verify_migration(db: &Database) -> Result<VerificationResult> {
         if found.is_none() {
             missing_rules.push(db_rule.id.clone());
         } else if let Some(file_rule) = found {
-            if file_rule.name != db_rule.name || file_rule.content != db_rule.content {
+            if file_rule.name != db_rule.name
+                // Rule files store the body trimmed
+                || file_rule.content.trim() != db_rule.content.trim()
+                || file_rule.enabled != db_rule.enabled
+            {
                 mismatched_rules.push(db_rule.id.clone());
             }
         }
@@ -1,9 +1,17 @@
+mod dual_write;
+mod index;
 mod migration;
 mod parser;
 mod serializer;
 pub mod skills;
 pub mod watcher;
 
+pub use dual_write::{
+    complete_cutover, get_dual_write_status, rollback_dual_write, start_dual_write,
+    DualWriteStatus, DUAL_WRITE_MODE,
+};
+pub(crate) use dual_write::{BACKUP_PATH_KEY, DUAL_WRITE_STARTED_KEY, DUAL_WRITE_UNTIL_KEY};
+pub use index::{rebuild_file_index, FileIndexReport};
 #[allow(unused_imports)]
 pub use migration::{
     get_migration_progress, migrate_to_file_storage, rollback_migration, verify_migration,
@@ -17,11 +25,11 @@ pub use serializer::{generate_filename, generate_rule_file_path, serialize_rule_
 pub use watcher::{FileChangeEvent, RuleFileWatcher};
 
 use std::fs;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use walkdir::WalkDir;
 
+use crate::atomic_write::write_atomic;
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::models::Rule;
@@ -32,7 +40,7 @@ pub const RULEWEAVER_DIR_NAME: &str = ".ruleweaver";
 pub fn get_global_rules_dir() -> Result<PathBuf> {
     let home = dirs::home_dir()
         .ok_or_else(|| AppError::Path("Could not determine home directory".to_string()))?;
-    Ok(home.join(RULEWEAVER_DIR_NAME).join(RULES_DIR_NAME))
+    Ok(crate::workspace::scoped_dir(&home.join(RULEWEAVER_DIR_NAME)).join(RULES_DIR_NAME))
 }
 
 pub fn get_local_rules_dir(project_path: &Path) -> PathBuf {
@@ -60,10 +68,6 @@ pub enum StorageLocation {
     Local(PathBuf),
 }
 
-pub fn load_rules_from_disk() -> Result<RuleLoadResult> {
-    load_rules_from_locations(&[])
-}
-
 pub fn load_rules_from_locations(local_roots: &[PathBuf]) -> Result<RuleLoadResult> {
     let mut all_rules = Vec::new();
     let mut all_errors = Vec::new();
@@ -156,21 +160,7 @@ pub fn save_rule_to_disk(rule: &Rule, location: &StorageLocation) -> Result<Path
 
     let file_path = find_or_create_rule_file(&base_dir, rule)?;
 
-    let temp_path = file_path.with_extension(format!("md.tmp-{}", uuid::Uuid::new_v4()));
-    {
-        let mut file = fs::File::create(&temp_path)?;
-        file.write_all(file_content.as_bytes())?;
-        file.sync_all()?;
-    }
-
-    fs::rename(&temp_path, &file_path)?;
-
-    // Ensure directory metadata is also synced to disk if possible
-    if let Some(parent) = file_path.parent() {
-        if let Ok(dir) = fs::File::open(parent) {
-            let _ = dir.sync_all();
-        }
-    }
+    write_atomic(&file_path, &file_content)?;
 
     Ok(file_path)
 }
@@ -210,7 +200,8 @@ pub async fn delete_rule_file(
     if let Some(db) = db {
         if let Ok(Some(path_str)) = db.get_rule_file_path(rule_id).await {
             let path = PathBuf::from(path_str);
-            if path.exists() {
+            // The index may hold the rules directory rather than the file itself
+            if path.is_file() {
                 fs::remove_file(path)?;
                 return Ok(true);
             }
@@ -262,7 +253,7 @@ pub async fn get_rule_file_path(
     if let Some(db) = db {
         if let Ok(Some(path_str)) = db.get_rule_file_path(rule_id).await {
             let path = PathBuf::from(path_str);
-            if path.exists() {
+            if path.is_file() {
                 return Ok(Some(path));
             }
         }
@@ -469,6 +460,7 @@ mod tests {
             enabled: true,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            metadata: Default::default(),
         };
 
         let result = save_rule_to_disk(&rule, &StorageLocation::Local(temp_dir.clone()));
@@ -534,6 +526,7 @@ mod tests {
             enabled: true,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            metadata: Default::default(),
         };
 
         let filename = generate_filename(&rule);
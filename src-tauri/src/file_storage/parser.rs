@@ -6,7 +6,7 @@ use std::str::FromStr;
 use std::sync::OnceLock;
 
 use crate::error::{AppError, Result};
-use crate::models::{AdapterType, Rule, Scope};
+use crate::models::{AdapterType, Metadata, Rule, Scope};
 
 const FRONTMATTER_DELIMITER: &str = "---";
 
@@ -28,6 +28,8 @@ pub struct RuleFrontmatter {
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    #[serde(default)]
+    pub metadata: Metadata,
 }
 
 fn default_true() -> bool {
@@ -74,6 +76,7 @@ impl ParsedRuleFile {
             target_paths: self.frontmatter.target_paths.clone(),
             enabled_adapters,
             enabled: self.frontmatter.enabled,
+            metadata: self.frontmatter.metadata.clone(),
             created_at,
             updated_at,
         })
@@ -295,6 +298,29 @@ Test body.
         assert!(!rule.enabled);
     }
 
+    #[test]
+    fn test_to_rule_reads_metadata() {
+        let content = r#"---
+id: test-meta
+name: "Metadata"
+enabledAdapters: [gemini]
+createdAt: 2024-01-15T10:30:00Z
+updatedAt: 2024-01-15T10:30:00Z
+metadata:
+  owner: platform
+  ticket: 42
+---
+Content
+"#;
+
+        let rule = parse_rule_file(&create_test_path("test"), content)
+            .unwrap()
+            .to_rule()
+            .unwrap();
+        assert_eq!(rule.metadata.get_str("owner"), Some("platform"));
+        assert_eq!(rule.metadata.get_i64("ticket"), Some(42));
+    }
+
     #[test]
     fn test_to_rule_no_valid_adapters() {
         let content = r#"---
@@ -3,7 +3,7 @@ use serde::Serialize;
 use std::path::Path;
 
 use crate::error::Result;
-use crate::models::Rule;
+use crate::models::{Metadata, Rule};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RuleFrontmatter {
@@ -20,6 +20,8 @@ pub struct RuleFrontmatter {
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    #[serde(skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
 }
 
 impl From<&Rule> for RuleFrontmatter {
@@ -38,6 +40,7 @@ impl From<&Rule> for RuleFrontmatter {
             enabled: rule.enabled,
             created_at: format_datetime(rule.created_at),
             updated_at: format_datetime(rule.updated_at),
+            metadata: rule.metadata.clone(),
         }
     }
 }
@@ -121,6 +124,7 @@ mod tests {
             enabled: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            metadata: Default::default(),
         }
     }
 
@@ -4,11 +4,14 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::atomic_write::write_atomic;
 use crate::database::Database;
 use crate::error::{AppError, Result};
-use crate::models::{CreateSkillInput, Scope, Skill, SkillParameter, UpdateSkillInput};
+use crate::models::{CreateSkillInput, Metadata, Scope, Skill, SkillParameter, UpdateSkillInput};
 
-use crate::constants::{SKILLS_DIR_NAME, SKILL_INSTRUCTIONS_FILE, SKILL_METADATA_FILE};
+use crate::constants::{
+    SKILLS_DIR_NAME, SKILL_BUNDLE_DIRS, SKILL_INSTRUCTIONS_FILE, SKILL_METADATA_FILE,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SkillMetadata {
@@ -25,6 +28,14 @@ pub struct SkillMetadata {
     pub created_at: Option<String>,
     #[serde(default)]
     pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -32,10 +43,7 @@ fn default_true() -> bool {
 }
 
 pub fn get_global_skills_dir() -> Result<PathBuf> {
-    let base = dirs::data_local_dir()
-        .or_else(dirs::data_dir)
-        .ok_or_else(|| AppError::Path("Could not determine data directory".to_string()))?;
-    Ok(base.join("RuleWeaver").join(SKILLS_DIR_NAME))
+    Ok(crate::database::default_app_data_dir()?.join(SKILLS_DIR_NAME))
 }
 
 pub fn load_skills_from_disk() -> Result<Vec<Skill>> {
@@ -123,11 +131,70 @@ pub fn load_skill_from_directory(dir: &Path) -> Result<Skill> {
         target_adapters: Vec::new(),
         target_paths: Vec::new(),
         base_path: None,
+        metadata: metadata.metadata,
+        bundle_files: list_bundle_files(dir),
+        allowed_tools: metadata.allowed_tools,
+        license: metadata.license,
+        version: metadata
+            .version
+            .unwrap_or_else(crate::models::default_skill_version),
+        version_pins: Vec::new(),
         created_at,
         updated_at,
     })
 }
 
+/// Regular files under the bundle directories of `dir`, relative to it with `/`
+/// separators, in path order. Symlinks are not followed.
+pub fn list_bundle_files(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    for bundle_dir in SKILL_BUNDLE_DIRS {
+        for entry in WalkDir::new(dir.join(bundle_dir)).min_depth(1) {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if let Ok(relative) = entry.path().strip_prefix(dir) {
+                let parts: Vec<String> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect();
+                files.push(parts.join("/"));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Replaces the bundle directories in `target` with `files` copied from `source`.
+pub fn copy_bundle(source: &Path, files: &[String], target: &Path) -> Result<()> {
+    crate::models::validate_skill_bundle_files(files)?;
+    remove_bundle(target)?;
+    for file in files {
+        let from = source.join(file);
+        let to = target.join(file);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&from, &to)?;
+    }
+    Ok(())
+}
+
+/// Removes the bundle directories next to a synced SKILL.md.
+pub fn remove_bundle(target: &Path) -> Result<()> {
+    for bundle_dir in SKILL_BUNDLE_DIRS {
+        let dir = target.join(bundle_dir);
+        if dir.is_dir() && !dir.is_symlink() {
+            fs::remove_dir_all(&dir)?;
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_skill_directory_path(path: &Path) -> Result<()> {
     // 1. Ensure it's absolute
     if !path.is_absolute() {
@@ -206,7 +273,7 @@ pub fn save_skill_to_disk(skill: &Skill) -> Result<PathBuf> {
 
     // Write SKILL.md
     let instructions_path = skill_dir.join(SKILL_INSTRUCTIONS_FILE);
-    fs::write(&instructions_path, &skill.instructions)?;
+    write_atomic(&instructions_path, &skill.instructions)?;
 
     // Write skill.json
     let metadata = SkillMetadata {
@@ -219,11 +286,15 @@ pub fn save_skill_to_disk(skill: &Skill) -> Result<PathBuf> {
         enabled: skill.enabled,
         created_at: Some(skill.created_at.to_rfc3339()),
         updated_at: Some(skill.updated_at.to_rfc3339()),
+        metadata: skill.metadata.clone(),
+        allowed_tools: skill.allowed_tools.clone(),
+        license: skill.license.clone(),
+        version: Some(skill.version.clone()),
     };
 
     let metadata_path = skill_dir.join(SKILL_METADATA_FILE);
     let json_content = serde_json::to_string_pretty(&metadata)?;
-    fs::write(&metadata_path, &json_content)?;
+    write_atomic(&metadata_path, &json_content)?;
 
     Ok(skill_dir)
 }
@@ -234,7 +305,7 @@ pub async fn sync_skills_to_db(db: &Database) -> Result<u32> {
 
     for skill in skills {
         // Try to update, if not found, create
-        if db.get_skill_by_id(&skill.id).await.is_ok() {
+        if let Ok(existing) = db.get_skill_by_id(&skill.id).await {
             let update_input = UpdateSkillInput {
                 name: Some(skill.name.clone()),
                 description: Some(skill.description.clone()),
@@ -244,8 +315,19 @@ pub async fn sync_skills_to_db(db: &Database) -> Result<u32> {
                 directory_path: Some(skill.directory_path.clone()),
                 entry_point: Some(skill.entry_point.clone()),
                 enabled: Some(skill.enabled),
+                metadata: Some(skill.metadata.clone()),
+                bundle_files: Some(skill.bundle_files.clone()),
+                allowed_tools: Some(skill.allowed_tools.clone()),
+                license: Some(skill.license.clone().unwrap_or_default()),
                 ..Default::default()
             };
+            // Files edited on disk do not override what the organization manages.
+            if let Err(e) =
+                crate::policy::ensure_editable(db, "Skill", &update_input, &existing).await
+            {
+                log::warn!("Skipping skill '{}' from disk: {}", skill.name, e);
+                continue;
+            }
             db.update_skill(&skill.id, update_input).await?;
         } else {
             let create_input = CreateSkillInput {
@@ -258,6 +340,11 @@ pub async fn sync_skills_to_db(db: &Database) -> Result<u32> {
                 directory_path: skill.directory_path.clone(),
                 entry_point: skill.entry_point.clone(),
                 enabled: skill.enabled,
+                metadata: skill.metadata.clone(),
+                bundle_files: skill.bundle_files.clone(),
+                allowed_tools: skill.allowed_tools.clone(),
+                license: skill.license.clone(),
+                version: Some(skill.version.clone()),
                 ..Default::default()
             };
             db.create_skill(create_input).await?;
@@ -1,18 +1,48 @@
 //! File watcher for tracking external changes to rule files.
-//! NOTE: This module is currently not integrated into the main application loop
-//! and is intended for a future update to enable full "file-first" bidirectional sync.
+//!
+//! Native change notifications are unreliable on network drives and in folders managed by
+//! sync clients such as Dropbox or OneDrive, so those folders are polled instead, as is any
+//! path the native watcher refuses. Paths that are missing, or disappear while watched, are
+//! retried with backoff and rescanned once they come back.
 #![allow(dead_code)]
 
-use std::path::PathBuf;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, Event, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
 
 use crate::error::{AppError, Result};
 
+/// Setting that selects the [`WatchMode`]: `auto`, `native` or `poll`.
+pub const WATCH_MODE_KEY: &str = "rule_watcher_mode";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+const RETRY_ATTEMPTS: u32 = 4;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(150);
+
+/// Folder names used by sync clients, optionally followed by an account suffix such as
+/// "Dropbox (Personal)" or "OneDrive - Contoso".
+const SYNCED_FOLDER_NAMES: &[&str] = &[
+    "Dropbox",
+    "OneDrive",
+    "Google Drive",
+    "GoogleDrive",
+    "iCloud Drive",
+    "Mobile Documents",
+    "pCloudDrive",
+    "Nextcloud",
+    "ownCloud",
+];
+
 pub type FileChangeCallback = Box<dyn Fn(FileChangeEvent) + Send + 'static>;
 
 #[derive(Debug, Clone)]
@@ -22,114 +52,365 @@ pub enum FileChangeEvent {
     Deleted(PathBuf),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    /// Native notifications, polling synced folders and paths they do not work for
+    #[default]
+    Auto,
+    Native,
+    Poll,
+}
+
+impl WatchMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("native") => Self::Native,
+            Some("poll") => Self::Poll,
+            _ => Self::Auto,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchBackend {
+    Native,
+    Poll,
+}
+
+fn is_synced_folder(path: &Path) -> bool {
+    path.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        SYNCED_FOLDER_NAMES.iter().any(|synced| {
+            name.strip_prefix(synced)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '-']))
+        })
+    })
+}
+
+fn is_rule_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("md")
+}
+
+/// Runs `op` until it succeeds, sleeping with exponential backoff between attempts. Meant
+/// for filesystem calls that fail briefly while a sync client or network drive catches up.
+pub async fn retry_with_backoff<T, E: Display>(
+    what: &str,
+    mut op: impl FnMut() -> std::result::Result<T, E>,
+) -> std::result::Result<T, E> {
+    let mut delay = RETRY_BASE_DELAY;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < RETRY_ATTEMPTS => {
+                log::debug!("{} failed (attempt {}): {}", what, attempt, e);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A path waiting to become available again.
+#[derive(Debug)]
+struct PendingPath {
+    path: PathBuf,
+    delay: Duration,
+    next_attempt: Instant,
+}
+
+impl PendingPath {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            delay: MIN_RETRY_DELAY,
+            next_attempt: Instant::now() + MIN_RETRY_DELAY,
+        }
+    }
+
+    fn back_off(&mut self) {
+        self.delay = (self.delay * 2).min(MAX_RETRY_DELAY);
+        self.next_attempt = Instant::now() + self.delay;
+    }
+}
+
+#[derive(Debug, Default)]
+struct WatchSet {
+    native: Option<RecommendedWatcher>,
+    poll: Option<PollWatcher>,
+    watched: Vec<(PathBuf, WatchBackend)>,
+    pending: Vec<PendingPath>,
+    events: Option<Sender<Result<FileChangeEvent>>>,
+}
+
+fn event_handler(tx: Sender<Result<FileChangeEvent>>) -> impl Fn(notify::Result<Event>) {
+    move |res: notify::Result<Event>| {
+        let event = match res {
+            Ok(e) => e,
+            Err(e) => {
+                let _ = tx.send(Err(AppError::InvalidInput {
+                    message: format!("Watch error: {}", e),
+                }));
+                return;
+            }
+        };
+
+        for path in event.paths.iter().filter(|p| is_rule_file(p)) {
+            let file_event = if event.kind.is_create() {
+                Some(FileChangeEvent::Created(path.clone()))
+            } else if event.kind.is_modify() {
+                Some(FileChangeEvent::Modified(path.clone()))
+            } else if event.kind.is_remove() {
+                Some(FileChangeEvent::Deleted(path.clone()))
+            } else {
+                None
+            };
+
+            if let Some(fe) = file_event {
+                let _ = tx.send(Ok(fe));
+            }
+        }
+    }
+}
+
+impl WatchSet {
+    fn sender(&self) -> Result<Sender<Result<FileChangeEvent>>> {
+        self.events.clone().ok_or_else(|| AppError::InvalidInput {
+            message: "File watcher is not running".to_string(),
+        })
+    }
+
+    fn watch_with(&mut self, path: &Path, backend: WatchBackend) -> Result<()> {
+        let watch_error = |e: notify::Error| AppError::InvalidInput {
+            message: format!("Failed to watch path '{}': {}", path.display(), e),
+        };
+        let create_error = |e: notify::Error| AppError::InvalidInput {
+            message: format!("Failed to create file watcher: {}", e),
+        };
+
+        match backend {
+            WatchBackend::Native => {
+                if self.native.is_none() {
+                    let watcher = RecommendedWatcher::new(
+                        event_handler(self.sender()?),
+                        Config::default().with_compare_contents(false),
+                    )
+                    .map_err(create_error)?;
+                    self.native = Some(watcher);
+                }
+                if let Some(watcher) = self.native.as_mut() {
+                    watcher
+                        .watch(path, RecursiveMode::Recursive)
+                        .map_err(watch_error)?;
+                }
+            }
+            WatchBackend::Poll => {
+                if self.poll.is_none() {
+                    let watcher = PollWatcher::new(
+                        event_handler(self.sender()?),
+                        Config::default()
+                            .with_poll_interval(POLL_INTERVAL)
+                            .with_compare_contents(true),
+                    )
+                    .map_err(create_error)?;
+                    self.poll = Some(watcher);
+                }
+                if let Some(watcher) = self.poll.as_mut() {
+                    watcher
+                        .watch(path, RecursiveMode::Recursive)
+                        .map_err(watch_error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn unwatch(&mut self, path: &Path, backend: WatchBackend) {
+        let _ = match backend {
+            WatchBackend::Native => self.native.as_mut().map(|w| w.unwatch(path)),
+            WatchBackend::Poll => self.poll.as_mut().map(|w| w.unwatch(path)),
+        };
+    }
+
+    /// Watches `path` with the backend `mode` calls for, polling when native watching fails
+    /// unless native watching was asked for explicitly.
+    fn attach(&mut self, path: &Path, mode: WatchMode) -> Result<WatchBackend> {
+        let prefer_poll =
+            mode == WatchMode::Poll || (mode == WatchMode::Auto && is_synced_folder(path));
+        if !prefer_poll {
+            match self.watch_with(path, WatchBackend::Native) {
+                Ok(()) => return Ok(WatchBackend::Native),
+                Err(e) if mode == WatchMode::Native => return Err(e),
+                Err(e) => log::warn!(
+                    "Native watching failed for {}, polling instead: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        self.watch_with(path, WatchBackend::Poll)?;
+        Ok(WatchBackend::Poll)
+    }
+
+    /// Reports every rule file under `path` as modified, to catch up on changes made while
+    /// the path was unavailable.
+    fn rescan(&self, path: &Path) {
+        let Some(tx) = &self.events else {
+            return;
+        };
+        for entry in WalkDir::new(path)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if entry.file_type().is_file() && is_rule_file(entry.path()) {
+                let _ = tx.send(Ok(FileChangeEvent::Modified(entry.into_path())));
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RuleFileWatcher {
-    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+    state: Arc<Mutex<WatchSet>>,
     is_running: Arc<Mutex<bool>>,
-    watched_paths: Arc<Mutex<Vec<PathBuf>>>,
+    mode: Arc<Mutex<WatchMode>>,
 }
 
 impl RuleFileWatcher {
     pub fn new() -> Self {
         Self {
-            watcher: Arc::new(Mutex::new(None)),
+            state: Arc::new(Mutex::new(WatchSet::default())),
             is_running: Arc::new(Mutex::new(false)),
-            watched_paths: Arc::new(Mutex::new(Vec::new())),
+            mode: Arc::new(Mutex::new(WatchMode::default())),
         }
     }
 
-    pub fn start(&self, path: &std::path::Path, callback: FileChangeCallback) -> Result<()> {
-        let mut is_running = self.is_running.lock().map_err(|_| AppError::LockError)?;
-        if *is_running {
-            return Ok(());
+    /// Sets how paths watched from now on are watched.
+    pub fn set_mode(&self, mode: WatchMode) {
+        if let Ok(mut current) = self.mode.lock() {
+            *current = mode;
         }
+    }
 
-        let (tx, rx): (
-            Sender<Result<FileChangeEvent>>,
-            Receiver<Result<FileChangeEvent>>,
-        ) = channel();
-
-        let event_callback = callback;
-        let callback_arc = Arc::new(Mutex::new(event_callback));
+    pub fn mode(&self) -> WatchMode {
+        self.mode.lock().map(|m| *m).unwrap_or_default()
+    }
 
-        let mut watcher = RecommendedWatcher::new(
-            move |res: notify::Result<Event>| {
-                let event = match res {
-                    Ok(e) => e,
-                    Err(e) => {
-                        let _ = tx.send(Err(AppError::InvalidInput {
-                            message: format!("Watch error: {}", e),
-                        }));
-                        return;
+    /// Watches `path`, starting the watcher on the first call. Events for every watched path
+    /// go to the callback passed to that first call.
+    pub fn start(&self, path: &Path, callback: FileChangeCallback) -> Result<()> {
+        let mut is_running = self.is_running.lock().map_err(|_| AppError::LockError)?;
+        if !*is_running {
+            let (tx, rx): (
+                Sender<Result<FileChangeEvent>>,
+                Receiver<Result<FileChangeEvent>>,
+            ) = channel();
+            self.state.lock().map_err(|_| AppError::LockError)?.events = Some(tx);
+            *is_running = true;
+
+            let is_running_clone = Arc::clone(&self.is_running);
+            thread::spawn(move || {
+                while let Ok(event_result) = rx.recv() {
+                    let running = is_running_clone.lock().map(|g| *g).unwrap_or(false);
+                    if !running {
+                        break;
                     }
-                };
 
-                for path in &event.paths {
-                    if path.extension().and_then(|e| e.to_str()) != Some("md") {
-                        continue;
+                    match event_result {
+                        Ok(event) => callback(event),
+                        Err(e) => log::warn!("{}", e),
                     }
+                }
+            });
 
-                    let file_event = if event.kind.is_create() {
-                        Some(FileChangeEvent::Created(path.clone()))
-                    } else if event.kind.is_modify() {
-                        Some(FileChangeEvent::Modified(path.clone()))
-                    } else if event.kind.is_remove() {
-                        Some(FileChangeEvent::Deleted(path.clone()))
-                    } else {
-                        None
-                    };
-
-                    if let Some(fe) = file_event {
-                        let _ = tx.send(Ok(fe));
-                    }
+            let supervisor = self.clone();
+            thread::spawn(move || {
+                while supervisor.is_running() {
+                    thread::sleep(SUPERVISE_INTERVAL);
+                    supervisor.supervise();
                 }
-            },
-            Config::default()
-                .with_poll_interval(Duration::from_millis(500))
-                .with_compare_contents(false),
-        )
-        .map_err(|e| AppError::InvalidInput {
-            message: format!("Failed to create file watcher: {}", e),
-        })?;
+            });
+        }
+        drop(is_running);
 
-        watcher
-            .watch(path, RecursiveMode::Recursive)
-            .map_err(|e| AppError::InvalidInput {
-                message: format!("Failed to watch path '{}': {}", path.display(), e),
-            })?;
+        self.watch(path)
+    }
 
-        {
-            let mut w = self.watcher.lock().map_err(|_| AppError::LockError)?;
-            *w = Some(watcher);
+    /// Adds `path` to a running watcher. A path that does not exist yet is retried with
+    /// backoff until it does.
+    pub fn watch(&self, path: &Path) -> Result<()> {
+        let mut state = self.state.lock().map_err(|_| AppError::LockError)?;
+        let known = state.watched.iter().any(|(p, _)| p == path)
+            || state.pending.iter().any(|p| p.path == path);
+        if known {
+            return Ok(());
         }
 
-        {
-            let mut watched = self.watched_paths.lock().map_err(|_| AppError::LockError)?;
-            watched.push(path.to_path_buf());
+        if !path.exists() {
+            log::warn!(
+                "Rule directory {} is unavailable; retrying until it appears",
+                path.display()
+            );
+            state.pending.push(PendingPath::new(path.to_path_buf()));
+            return Ok(());
         }
 
-        *is_running = true;
-        drop(is_running);
-
-        let is_running_clone = Arc::clone(&self.is_running);
-        let callback_clone = Arc::clone(&callback_arc);
+        let backend = state.attach(path, self.mode())?;
+        log::info!("Watching {} ({:?})", path.display(), backend);
+        state.watched.push((path.to_path_buf(), backend));
+        Ok(())
+    }
 
-        thread::spawn(move || {
-            while let Ok(event_result) = rx.recv() {
-                let running = is_running_clone.lock().map(|g| *g).unwrap_or(false);
-                if !running {
-                    break;
-                }
+    /// Moves watched paths that disappeared back to pending and retries pending paths that
+    /// are due, rescanning the ones that come back.
+    fn supervise(&self) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let mode = self.mode();
+
+        let (gone, watched): (Vec<_>, Vec<_>) = std::mem::take(&mut state.watched)
+            .into_iter()
+            .partition(|(path, _)| !path.exists());
+        state.watched = watched;
+        for (path, backend) in gone {
+            log::warn!(
+                "Rule directory {} became unavailable; retrying until it returns",
+                path.display()
+            );
+            state.unwatch(&path, backend);
+            state.pending.push(PendingPath::new(path));
+        }
 
-                if let Ok(event) = event_result {
-                    if let Ok(cb) = callback_clone.lock() {
-                        cb(event);
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut state.pending)
+            .into_iter()
+            .partition(|p| p.next_attempt <= now);
+        state.pending = waiting;
+        for mut pending in due {
+            if pending.path.exists() {
+                match state.attach(&pending.path, mode) {
+                    Ok(backend) => {
+                        log::info!(
+                            "Rule directory {} is available again ({:?})",
+                            pending.path.display(),
+                            backend
+                        );
+                        state.rescan(&pending.path);
+                        state.watched.push((pending.path, backend));
+                        continue;
                     }
+                    Err(e) => log::warn!("{}", e),
                 }
             }
-        });
-
-        Ok(())
+            pending.back_off();
+            state.pending.push(pending);
+        }
     }
 
     pub fn stop(&self) -> Result<()> {
@@ -138,14 +419,11 @@ impl RuleFileWatcher {
             return Ok(());
         }
 
-        let mut watcher_guard = self.watcher.lock().map_err(|_| AppError::LockError)?;
-
-        if let Some(mut watcher) = watcher_guard.take() {
-            let watched = self.watched_paths.lock().map_err(|_| AppError::LockError)?;
-            for path in watched.iter() {
-                let _ = watcher.unwatch(path);
-            }
+        let mut state = self.state.lock().map_err(|_| AppError::LockError)?;
+        for (path, backend) in std::mem::take(&mut state.watched) {
+            state.unwatch(&path, backend);
         }
+        *state = WatchSet::default();
 
         *is_running = false;
         Ok(())
@@ -156,11 +434,28 @@ impl RuleFileWatcher {
     }
 
     pub fn watched_paths(&self) -> Vec<PathBuf> {
-        self.watched_paths
+        self.state
             .lock()
-            .map(|g| g.clone())
+            .map(|s| s.watched.iter().map(|(p, _)| p.clone()).collect())
             .unwrap_or_default()
     }
+
+    /// Paths waiting to become available.
+    pub fn pending_paths(&self) -> Vec<PathBuf> {
+        self.state
+            .lock()
+            .map(|s| s.pending.iter().map(|p| p.path.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn backend_for(&self, path: &Path) -> Option<WatchBackend> {
+        self.state.lock().ok().and_then(|s| {
+            s.watched
+                .iter()
+                .find(|(p, _)| p == path)
+                .map(|(_, backend)| *backend)
+        })
+    }
 }
 
 impl Default for RuleFileWatcher {
@@ -209,4 +504,70 @@ mod tests {
 
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn test_synced_folders_are_recognised() {
+        assert!(is_synced_folder(Path::new(
+            "/home/me/Dropbox/.ruleweaver/rules"
+        )));
+        assert!(is_synced_folder(Path::new(
+            "/Users/me/OneDrive - Contoso/.ruleweaver/rules"
+        )));
+        assert!(is_synced_folder(Path::new(
+            "/Users/me/Dropbox (Personal)/rules"
+        )));
+        assert!(!is_synced_folder(Path::new("/home/me/DropboxNotes/rules")));
+        assert!(!is_synced_folder(Path::new("/home/me/.ruleweaver/rules")));
+    }
+
+    #[test]
+    fn test_watcher_covers_every_path_and_waits_for_unavailable_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second, later) = (
+            dir.path().join("first"),
+            dir.path().join("second"),
+            dir.path().join("later"),
+        );
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+
+        let watcher = RuleFileWatcher::new();
+        watcher.set_mode(WatchMode::Poll);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = Arc::clone(&seen);
+        let callback: FileChangeCallback = Box::new(move |event: FileChangeEvent| {
+            if let FileChangeEvent::Modified(path) | FileChangeEvent::Created(path) = event {
+                seen_clone.lock().unwrap().push(path);
+            }
+        });
+
+        watcher.start(&first, callback).unwrap();
+        watcher.start(&second, Box::new(|_| {})).unwrap();
+        watcher.watch(&later).unwrap();
+        assert_eq!(watcher.watched_paths(), vec![first.clone(), second.clone()]);
+        assert_eq!(watcher.backend_for(&second), Some(WatchBackend::Poll));
+        assert_eq!(watcher.pending_paths(), vec![later.clone()]);
+
+        // The missing path is picked up and rescanned once it appears.
+        fs::create_dir_all(&later).unwrap();
+        let rule = later.join("rule.md");
+        fs::write(&rule, "---\nid: x\n---\n").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !seen.lock().unwrap().contains(&rule) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert!(seen.lock().unwrap().contains(&rule));
+        assert!(watcher.pending_paths().is_empty());
+
+        // A watched path that disappears goes back to waiting.
+        fs::remove_dir_all(&second).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !watcher.pending_paths().contains(&second) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(watcher.pending_paths(), vec![second]);
+
+        watcher.stop().unwrap();
+        assert!(watcher.watched_paths().is_empty());
+    }
 }
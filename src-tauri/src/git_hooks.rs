@@ -0,0 +1,271 @@
+//! Git hooks that keep a registered repository's generated files in step with RuleWeaver.
+//!
+//! The hooks call the headless CLI (`ruleweaver reconcile --repo <root>`): `pre-commit`
+//! refuses a commit while generated files are stale, `post-merge` reports files a merge left
+//! stale. With regeneration on, both rewrite the files instead; the commit is still stopped
+//! so the regenerated files can be reviewed and staged. Machines without RuleWeaver skip the
+//! hooks, so they never block a teammate.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::{AppError, Result};
+
+/// First lines of every hook RuleWeaver writes; hooks without it belong to someone else.
+const HOOK_MARKER: &str = "# Installed by RuleWeaver";
+
+const HOOK_NAMES: &[&str] = &["pre-commit", "post-merge"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookAction {
+    /// Fail (pre-commit) or warn (post-merge) when generated files are stale
+    Verify,
+    /// Rewrite stale generated files
+    Regenerate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHookStatus {
+    pub repo_root: String,
+    pub hooks_dir: String,
+    /// Hooks RuleWeaver installed
+    pub installed: Vec<String>,
+    /// Hooks of the same name installed by something else, which RuleWeaver leaves alone
+    pub foreign: Vec<String>,
+}
+
+/// Resolves the hooks directory, following the `gitdir:` file of worktrees and submodules.
+/// Worktrees share the hooks of the repository named in their `commondir` file.
+fn hooks_dir(repo_root: &Path) -> Result<PathBuf> {
+    let dot_git = repo_root.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else if dot_git.is_file() {
+        let content = fs::read_to_string(&dot_git)?;
+        let target = content
+            .lines()
+            .find_map(|line| line.strip_prefix("gitdir:"))
+            .map(str::trim)
+            .ok_or_else(|| AppError::InvalidInput {
+                message: format!("Unrecognized .git file in {}", repo_root.display()),
+            })?;
+        repo_root.join(target)
+    } else {
+        return Err(AppError::InvalidInput {
+            message: format!("{} is not a git repository", repo_root.display()),
+        });
+    };
+    let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim()),
+        Err(_) => git_dir,
+    };
+    Ok(common_dir.join("hooks"))
+}
+
+fn is_ours(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(HOOK_MARKER))
+}
+
+/// Quotes `value` for a POSIX shell. Git for Windows runs hooks with its bundled `sh` too.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn hook_script(hook: &str, repo_root: &Path, executable: &Path, action: HookAction) -> String {
+    let check = r#""$ruleweaver" reconcile --repo "$repo" --check >/dev/null"#;
+    let regenerate = r#""$ruleweaver" reconcile --repo "$repo" >/dev/null"#;
+    let body = match (hook, action) {
+        ("pre-commit", HookAction::Verify) => format!(
+            "if ! {check}; then\n  \
+             echo \"RuleWeaver: generated rule files are out of date.\" >&2\n  \
+             echo \"Run: ruleweaver reconcile --repo $repo\" >&2\n  \
+             exit 1\n\
+             fi\n"
+        ),
+        ("pre-commit", HookAction::Regenerate) => format!(
+            "if ! {check}; then\n  \
+             {regenerate}\n  \
+             echo \"RuleWeaver: regenerated out-of-date rule files; review and stage them, then commit again.\" >&2\n  \
+             exit 1\n\
+             fi\n"
+        ),
+        (_, HookAction::Verify) => format!(
+            "if ! {check}; then\n  \
+             echo \"RuleWeaver: the merge left generated rule files out of date.\" >&2\n  \
+             echo \"Run: ruleweaver reconcile --repo $repo\" >&2\n\
+             fi\n"
+        ),
+        (_, HookAction::Regenerate) => format!(
+            "if ! {check}; then\n  \
+             {regenerate} && echo \"RuleWeaver: regenerated out-of-date rule files.\" >&2\n\
+             fi\n"
+        ),
+    };
+
+    format!(
+        "#!/bin/sh\n\
+         {HOOK_MARKER}; `ruleweaver hooks uninstall` removes it.\n\
+         repo={repo}\n\
+         ruleweaver={exe}\n\
+         if [ ! -x \"$ruleweaver\" ]; then\n  \
+         ruleweaver=$(command -v ruleweaver) || exit 0\n\
+         fi\n\
+         {body}",
+        repo = shell_quote(&repo_root.to_string_lossy()),
+        exe = shell_quote(&executable.to_string_lossy()),
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+pub fn hook_status(repo_root: &Path) -> Result<GitHookStatus> {
+    let dir = hooks_dir(repo_root)?;
+    let mut installed = Vec::new();
+    let mut foreign = Vec::new();
+    for hook in HOOK_NAMES {
+        let path = dir.join(hook);
+        if is_ours(&path) {
+            installed.push(hook.to_string());
+        } else if path.exists() {
+            foreign.push(hook.to_string());
+        }
+    }
+    Ok(GitHookStatus {
+        repo_root: repo_root.to_string_lossy().to_string(),
+        hooks_dir: dir.to_string_lossy().to_string(),
+        installed,
+        foreign,
+    })
+}
+
+/// Installs or replaces RuleWeaver's hooks. Fails without writing anything when another
+/// tool already owns one of the hooks. `executable` is the binary the hooks call first,
+/// falling back to `ruleweaver` on `PATH`.
+pub fn install_hooks(
+    repo_root: &Path,
+    executable: &Path,
+    action: HookAction,
+) -> Result<GitHookStatus> {
+    let status = hook_status(repo_root)?;
+    if !status.foreign.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "{} already has {} hooks that RuleWeaver did not install; remove them or call ruleweaver from them",
+                repo_root.display(),
+                status.foreign.join(" and ")
+            ),
+        });
+    }
+
+    let dir = PathBuf::from(&status.hooks_dir);
+    fs::create_dir_all(&dir)?;
+    for hook in HOOK_NAMES {
+        let path = dir.join(hook);
+        crate::atomic_write::write_atomic(&path, hook_script(hook, repo_root, executable, action))?;
+        make_executable(&path)?;
+    }
+    hook_status(repo_root)
+}
+
+/// Removes RuleWeaver's hooks, leaving hooks installed by other tools in place.
+pub fn uninstall_hooks(repo_root: &Path) -> Result<GitHookStatus> {
+    let status = hook_status(repo_root)?;
+    for hook in &status.installed {
+        fs::remove_file(Path::new(&status.hooks_dir).join(hook))?;
+    }
+    hook_status(repo_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_and_uninstall_hooks() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::create_dir(repo.path().join(".git")).unwrap();
+
+        let status = install_hooks(
+            repo.path(),
+            Path::new("/opt/rule weaver"),
+            HookAction::Verify,
+        )
+        .unwrap();
+        assert_eq!(status.installed, vec!["pre-commit", "post-merge"]);
+        let script = fs::read_to_string(repo.path().join(".git/hooks/pre-commit")).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("ruleweaver='/opt/rule weaver'"));
+        assert!(script.contains("--check"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(repo.path().join(".git/hooks/pre-commit"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        // Reinstalling switches the action in place.
+        install_hooks(repo.path(), Path::new("ruleweaver"), HookAction::Regenerate).unwrap();
+        let script = fs::read_to_string(repo.path().join(".git/hooks/post-merge")).unwrap();
+        assert!(script.contains("regenerated"));
+
+        let status = uninstall_hooks(repo.path()).unwrap();
+        assert!(status.installed.is_empty());
+        assert!(!repo.path().join(".git/hooks/pre-commit").exists());
+    }
+
+    #[test]
+    fn test_install_leaves_foreign_hooks_alone() {
+        let repo = tempfile::tempdir().unwrap();
+        let hooks = repo.path().join(".git/hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(hooks.join("pre-commit"), "#!/bin/sh\nnpx lint-staged\n").unwrap();
+
+        assert!(install_hooks(repo.path(), Path::new("ruleweaver"), HookAction::Verify).is_err());
+        assert!(!hooks.join("post-merge").exists());
+        assert_eq!(
+            uninstall_hooks(repo.path()).unwrap().foreign,
+            vec!["pre-commit"]
+        );
+        assert!(hooks.join("pre-commit").exists());
+    }
+
+    #[test]
+    fn test_hooks_dir_follows_gitdir_file_and_rejects_plain_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let submodule = root.path().join("sub");
+        fs::create_dir(&submodule).unwrap();
+        fs::write(submodule.join(".git"), "gitdir: ../.git/modules/sub\n").unwrap();
+        assert_eq!(
+            hooks_dir(&submodule).unwrap(),
+            submodule.join("../.git/modules/sub/hooks")
+        );
+
+        let worktree = root.path().join("wt");
+        let worktree_git = root.path().join("main/.git/worktrees/wt");
+        fs::create_dir_all(&worktree_git).unwrap();
+        fs::create_dir(&worktree).unwrap();
+        fs::write(worktree.join(".git"), "gitdir: ../main/.git/worktrees/wt\n").unwrap();
+        fs::write(worktree_git.join("commondir"), "../..\n").unwrap();
+        assert_eq!(
+            hooks_dir(&worktree).unwrap(),
+            worktree.join("../main/.git/worktrees/wt/../../hooks")
+        );
+        assert!(hooks_dir(root.path()).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+//! Crash-safe journal for imports, file storage migrations and reconciliations.
+//!
+//! A long operation adds a journal entry when it starts, with what it needs to start again,
+//! checkpoints its progress as it goes and removes the entry when it finishes. An entry that
+//! is still running at startup was cut short, as is one whose operation stopped with an
+//! error; both are returned by `get_pending_jobs` until the user resumes, rolls back or
+//! discards them.
+//!
+//! The journal never fails the operation it tracks: journal errors are only logged.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    ImportArtifactType, ImportExecutionOptions, ImportScanResult, JobKind, JobRecord,
+    JobResolution, JobStatus,
+};
+use crate::reconciliation::{ReconcileFilter, ReconciliationEngine};
+
+/// What an import needs to run again.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJob {
+    pub scan: ImportScanResult,
+    pub options: ImportExecutionOptions,
+}
+
+/// An artifact an import created, as opposed to one it updated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedArtifact {
+    pub artifact_type: ImportArtifactType,
+    pub id: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    /// Candidates handled, whether imported, skipped or flagged as a conflict
+    pub processed: Vec<String>,
+    pub created: Vec<CreatedArtifact>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationJob {
+    /// Database copy taken before any rule file was written
+    pub backup_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileJob {
+    pub filter: ReconcileFilter,
+}
+
+/// A journal entry for an operation in progress. Without an id, because the entry could
+/// not be written, every call is a no-op.
+#[derive(Debug)]
+pub struct JobHandle {
+    id: Option<String>,
+}
+
+impl JobHandle {
+    /// A handle for a run with nothing to journal, such as a dry run.
+    pub fn untracked() -> Self {
+        Self { id: None }
+    }
+
+    pub async fn begin(
+        db: &Database,
+        kind: JobKind,
+        description: String,
+        total: u32,
+        payload: &impl Serialize,
+    ) -> Self {
+        let started = match serde_json::to_value(payload) {
+            Ok(payload) => db.start_job(kind, description, total, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        match started {
+            Ok(id) => Self { id: Some(id) },
+            Err(e) => {
+                log::warn!("Failed to journal {} job: {}", kind.as_str(), e);
+                Self { id: None }
+            }
+        }
+    }
+
+    pub async fn checkpoint(&self, db: &Database, completed: u32, progress: &impl Serialize) {
+        let Some(id) = &self.id else {
+            return;
+        };
+        let written = match serde_json::to_value(progress) {
+            Ok(progress) => db.checkpoint_job(id, completed, progress).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = written {
+            log::warn!("Failed to checkpoint job {}: {}", id, e);
+        }
+    }
+
+    /// Removes the entry once the operation has run to the end.
+    pub async fn finish(self, db: &Database) {
+        self.end(db, &Ok(())).await;
+    }
+
+    /// Removes the entry when the operation succeeded, and keeps it as interrupted when it
+    /// returned an error part way through.
+    pub async fn end<T>(self, db: &Database, outcome: &Result<T>) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let ended = match outcome {
+            Ok(_) => db.finish_job(&id).await,
+            Err(_) => db.mark_job_interrupted(&id).await,
+        };
+        if let Err(e) = ended {
+            log::warn!("Failed to close job {}: {}", id, e);
+        }
+    }
+}
+
+/// Called once at startup, before anything can start a job.
+pub async fn recover_interrupted(db: &Database) {
+    match db.mark_running_jobs_interrupted().await {
+        Ok(0) => {}
+        Ok(count) => log::warn!(
+            "{} job(s) were interrupted by the last shutdown and are waiting to be resolved",
+            count
+        ),
+        Err(e) => log::warn!("Failed to check the job journal: {}", e),
+    }
+}
+
+fn payload<T: DeserializeOwned>(job: &JobRecord) -> Result<T> {
+    serde_json::from_value(job.payload.clone()).map_err(|e| AppError::InvalidInput {
+        message: format!("Job {} cannot be restarted: {}", job.id, e),
+    })
+}
+
+fn import_progress(job: &JobRecord) -> ImportProgress {
+    serde_json::from_value(job.checkpoint.clone()).unwrap_or_default()
+}
+
+/// Resumes, rolls back or discards an interrupted job. The entry is removed once the
+/// resolution succeeds; a resumed job journals its remaining work as a new entry.
+pub async fn resolve(db: Arc<Database>, id: &str, resolution: JobResolution) -> Result<()> {
+    let job = db
+        .get_job(id)
+        .await?
+        .ok_or_else(|| AppError::JobNotFound { id: id.to_string() })?;
+    if job.status == JobStatus::Running {
+        return Err(AppError::InvalidInput {
+            message: format!("Job '{}' is still running", job.description),
+        });
+    }
+
+    match resolution {
+        JobResolution::Discard => {}
+        JobResolution::Resume => resume(Arc::clone(&db), &job).await?,
+        JobResolution::RollBack => roll_back(Arc::clone(&db), &job).await?,
+    }
+    db.finish_job(&job.id).await
+}
+
+async fn resume(db: Arc<Database>, job: &JobRecord) -> Result<()> {
+    match job.kind {
+        JobKind::Import => {
+            let ImportJob { mut scan, options } = payload(job)?;
+            let processed: HashSet<String> = import_progress(job).processed.into_iter().collect();
+            scan.candidates.retain(|c| !processed.contains(&c.id));
+            crate::rule_import::execute_import(db, scan, options).await?;
+        }
+        JobKind::Migration => {
+            crate::file_storage::migrate_to_file_storage(&db).await?;
+        }
+        JobKind::Reconcile => {
+            let ReconcileJob { filter } = payload(job)?;
+            ReconciliationEngine::new(db)?
+                .with_filter(filter)
+                .reconcile(false, None)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Undoes an interrupted job. For an import this deletes the artifacts it created; ones it
+/// updated keep their new content.
+async fn roll_back(db: Arc<Database>, job: &JobRecord) -> Result<()> {
+    match job.kind {
+        JobKind::Import => {
+            delete_created_artifacts(&db, &import_progress(job).created).await;
+            let rules = db.get_all_rules().await?;
+            crate::sync::SyncEngine::new(&db).sync_all(rules).await;
+            crate::commands::reconcile_after_mutation(db).await;
+        }
+        JobKind::Migration => {
+            let MigrationJob { backup_path } = payload(job)?;
+            crate::file_storage::rollback_migration(&backup_path, Some(&db)).await?;
+        }
+        JobKind::Reconcile => {
+            return Err(AppError::InvalidInput {
+                message: "An interrupted reconciliation cannot be rolled back; resume it instead"
+                    .to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+async fn delete_created_artifacts(db: &Database, created: &[CreatedArtifact]) {
+    let use_fs = crate::commands::use_file_storage(db).await;
+    for artifact in created {
+        if let Err(e) = ensure_artifact_deletable(db, artifact).await {
+            log::warn!("Not rolling back imported artifact {}: {}", artifact.id, e);
+            continue;
+        }
+        let deleted = match artifact.artifact_type {
+            ImportArtifactType::Rule => delete_imported_rule(db, &artifact.id, use_fs).await,
+            ImportArtifactType::SlashCommand => db.delete_command(&artifact.id).await,
+            ImportArtifactType::Skill => {
+                if let Ok(skill) = db.get_skill_by_id(&artifact.id).await {
+                    let _ = crate::file_storage::skills::delete_skill_from_disk(&skill);
+                }
+                db.delete_skill(&artifact.id).await
+            }
+        };
+        if let Err(e) = deleted {
+            log::warn!(
+                "Failed to roll back imported artifact {}: {}",
+                artifact.id,
+                e
+            );
+        }
+    }
+}
+
+/// Undoing an import must not remove what the organization manages.
+async fn ensure_artifact_deletable(db: &Database, artifact: &CreatedArtifact) -> Result<()> {
+    let (kind, name, metadata) = match artifact.artifact_type {
+        ImportArtifactType::Rule => match db.get_rule_by_id(&artifact.id).await {
+            Ok(rule) => ("Rule", rule.name, rule.metadata),
+            Err(_) => return Ok(()),
+        },
+        ImportArtifactType::SlashCommand => match db.get_command_by_id(&artifact.id).await {
+            Ok(command) => ("Command", command.name, command.metadata),
+            Err(_) => return Ok(()),
+        },
+        ImportArtifactType::Skill => match db.get_skill_by_id(&artifact.id).await {
+            Ok(skill) => ("Skill", skill.name, skill.metadata),
+            Err(_) => return Ok(()),
+        },
+    };
+    crate::policy::ensure_deletable(db, kind, &name, &metadata).await
+}
+
+async fn delete_imported_rule(db: &Database, id: &str, use_fs: bool) -> Result<()> {
+    if use_fs {
+        if let Ok(rule) = db.get_rule_by_id(id).await {
+            let location = crate::commands::storage_location_for_rule(&rule);
+            crate::file_storage::delete_rule_file(id, &location, Some(db)).await?;
+            db.remove_rule_file_index(id).await?;
+        }
+    }
+    db.delete_rule(id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, Scope};
+
+    #[tokio::test]
+    async fn test_interrupted_jobs_are_pending_until_resolved() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let job = JobHandle::begin(
+            &db,
+            JobKind::Reconcile,
+            "Reconcile".to_string(),
+            3,
+            &ReconcileJob {
+                filter: ReconcileFilter::default(),
+            },
+        )
+        .await;
+        job.checkpoint(&db, 1, &()).await;
+        assert!(db.get_pending_jobs().await.unwrap().is_empty());
+
+        recover_interrupted(&db).await;
+        let pending = db.get_pending_jobs().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].completed, 1);
+        assert_eq!(pending[0].total, 3);
+        assert!(!pending[0].can_roll_back);
+
+        let rolled_back = resolve(Arc::clone(&db), &pending[0].id, JobResolution::RollBack).await;
+        assert!(rolled_back.is_err());
+        resolve(Arc::clone(&db), &pending[0].id, JobResolution::Discard)
+            .await
+            .unwrap();
+        assert!(db.get_pending_jobs().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_is_kept_and_finished_job_removed() {
+        let db = Database::new_in_memory().await.unwrap();
+        let failed = JobHandle::begin(&db, JobKind::Migration, "m".to_string(), 1, &()).await;
+        failed
+            .end::<()>(
+                &db,
+                &Err(AppError::InvalidInput {
+                    message: "disk full".to_string(),
+                }),
+            )
+            .await;
+        let finished = JobHandle::begin(&db, JobKind::Migration, "m".to_string(), 1, &()).await;
+        finished.finish(&db).await;
+
+        assert_eq!(db.get_pending_jobs().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_progress_round_trips_and_rollback_deletes_created() {
+        let db = Database::new_in_memory().await.unwrap();
+        let rule = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "Imported".to_string(),
+                description: String::new(),
+                content: "content".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+        let job = JobHandle::begin(
+            &db,
+            JobKind::Import,
+            "Import".to_string(),
+            2,
+            &ImportJob {
+                scan: ImportScanResult::default(),
+                options: ImportExecutionOptions::default(),
+            },
+        )
+        .await;
+        job.checkpoint(
+            &db,
+            1,
+            &ImportProgress {
+                processed: vec!["candidate-1".to_string()],
+                created: vec![CreatedArtifact {
+                    artifact_type: ImportArtifactType::Rule,
+                    id: rule.id.clone(),
+                }],
+            },
+        )
+        .await;
+        recover_interrupted(&db).await;
+
+        let pending = db.get_pending_jobs().await.unwrap();
+        assert!(pending[0].can_roll_back);
+        let progress = import_progress(&pending[0]);
+        assert_eq!(progress.processed, vec!["candidate-1".to_string()]);
+        assert!(payload::<ImportJob>(&pending[0]).is_ok());
+
+        delete_created_artifacts(&db, &progress.created).await;
+        assert!(db.get_rule_by_id(&rule.id).await.is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! Storage for RuleWeaver's encryption keys, MCP token and secrets in the operating
+//! system's credential store.
+//!
+//! - macOS: the login keychain via `security`
+//! - Linux: the Secret Service (GNOME Keyring, KWallet) via `secret-tool`. Setups without a
+//!   Secret Service, such as headless machines and minimal window managers, use an
+//!   AES-256-GCM encrypted file instead, readable only by the current user.
+//! - Windows: a DPAPI-protected blob next to the database, sealed to the current user
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::database::DatabaseKey;
+use crate::error::{AppError, Result};
+
+#[cfg(not(target_os = "windows"))]
+const SERVICE: &str = "RuleWeaver";
+const DATABASE_KEY_ACCOUNT: &str = "database-encryption-key";
+const VAULT_KEY_ACCOUNT: &str = "secrets-vault-key";
+const MCP_TOKEN_ACCOUNT: &str = "mcp-api-token";
+const EXPORT_SIGNING_KEY_ACCOUNT: &str = "export-signing-key";
+/// Prefix of the accounts holding named secrets, so they cannot collide with the keys above.
+const SECRET_ACCOUNT_PREFIX: &str = "secret-";
+
+/// The account for `name` in the active workspace. On Windows the key files already live in
+/// the workspace's data directory.
+fn account(name: &str) -> String {
+    if cfg!(target_os = "windows") {
+        name.to_string()
+    } else {
+        crate::workspace::scoped_name(name)
+    }
+}
+
+fn keychain_error(message: impl std::fmt::Display) -> AppError {
+    AppError::Encryption {
+        message: format!("OS keychain: {}", message),
+    }
+}
+
+/// Runs a keychain call on the blocking pool; the platform tools can take a while to answer.
+pub async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Internal {
+            message: e.to_string(),
+        })?
+}
+
+/// Runs `cmd`, optionally feeding `stdin`, and returns trimmed stdout on success.
+fn run(mut cmd: Command, stdin: Option<&str>) -> Result<String> {
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(keychain_error)?;
+    if let Some(input) = stdin {
+        if let Some(mut pipe) = child.stdin.take() {
+            pipe.write_all(input.as_bytes()).map_err(keychain_error)?;
+        }
+    }
+    let output = child.wait_with_output().map_err(keychain_error)?;
+    if !output.status.success() {
+        return Err(keychain_error(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn store_database_key(key: &DatabaseKey) -> Result<()> {
+    store(&account(DATABASE_KEY_ACCOUNT), &key.to_hex())
+}
+
+pub fn load_database_key() -> Result<DatabaseKey> {
+    DatabaseKey::from_hex(&load(&account(DATABASE_KEY_ACCOUNT))?)
+}
+
+pub fn delete_database_key() -> Result<()> {
+    delete(&account(DATABASE_KEY_ACCOUNT))
+}
+
+/// Stores the key that encrypts values in the secrets vault.
+pub fn store_vault_key(key: &DatabaseKey) -> Result<()> {
+    store(&account(VAULT_KEY_ACCOUNT), &key.to_hex())
+}
+
+/// Loads the secrets vault key, or `None` if no secret has been stored yet.
+pub fn load_vault_key() -> Result<Option<DatabaseKey>> {
+    if !exists(&account(VAULT_KEY_ACCOUNT)) {
+        return Ok(None);
+    }
+    DatabaseKey::from_hex(&load(&account(VAULT_KEY_ACCOUNT))?).map(Some)
+}
+
+/// The MCP server's API token, or `None` before one has been stored.
+pub fn load_mcp_token() -> Result<Option<String>> {
+    load_optional(&account(MCP_TOKEN_ACCOUNT))
+}
+
+pub fn store_mcp_token(token: &str) -> Result<()> {
+    store(&account(MCP_TOKEN_ACCOUNT), token)
+}
+
+/// The PKCS#8 key pair that signs configuration exports, hex encoded, or `None` before the
+/// first signed export.
+pub fn load_export_signing_key() -> Result<Option<String>> {
+    load_optional(&account(EXPORT_SIGNING_KEY_ACCOUNT))
+}
+
+pub fn store_export_signing_key(pkcs8_hex: &str) -> Result<()> {
+    store(&account(EXPORT_SIGNING_KEY_ACCOUNT), pkcs8_hex)
+}
+
+fn secret_account(name: &str) -> String {
+    account(&format!("{}{}", SECRET_ACCOUNT_PREFIX, name))
+}
+
+/// Stores a named secret, replacing any previous value.
+pub fn store_secret(name: &str, value: &str) -> Result<()> {
+    store(&secret_account(name), value)
+}
+
+/// Loads a named secret, or `None` if it has not been stored.
+pub fn load_secret(name: &str) -> Result<Option<String>> {
+    load_optional(&secret_account(name))
+}
+
+pub fn delete_secret(name: &str) -> Result<()> {
+    delete(&secret_account(name))
+}
+
+fn load_optional(account: &str) -> Result<Option<String>> {
+    if !exists(account) {
+        return Ok(None);
+    }
+    load(account).map(Some)
+}
+
+#[cfg(target_os = "macos")]
+fn store(account: &str, value: &str) -> Result<()> {
+    let mut cmd = Command::new("security");
+    cmd.args([
+        "add-generic-password",
+        "-U",
+        "-s",
+        SERVICE,
+        "-a",
+        account,
+        // Last and without a value, so `security` prompts for the secret on stdin rather
+        // than taking it from argv, where other processes could read it.
+        "-w",
+    ]);
+    // The prompt asks for the secret twice to confirm it.
+    run(cmd, Some(&format!("{}\n{}\n", value, value))).map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn load(account: &str) -> Result<String> {
+    let mut cmd = Command::new("security");
+    cmd.args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"]);
+    run(cmd, None)
+}
+
+#[cfg(target_os = "macos")]
+fn exists(account: &str) -> bool {
+    let mut cmd = Command::new("security");
+    cmd.args(["find-generic-password", "-s", SERVICE, "-a", account]);
+    run(cmd, None).is_ok()
+}
+
+#[cfg(target_os = "macos")]
+fn delete(account: &str) -> Result<()> {
+    let mut cmd = Command::new("security");
+    cmd.args(["delete-generic-password", "-s", SERVICE, "-a", account]);
+    run(cmd, None).map(|_| ())
+}
+
+/// Whether a Secret Service answers on the session bus. `secret-tool` reports a missing
+/// entry with an empty stderr and a missing service with an error message.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn secret_service_available() -> bool {
+    static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let probe = Command::new("secret-tool")
+            .args([
+                "lookup",
+                "service",
+                SERVICE,
+                "account",
+                "availability-probe",
+            ])
+            .stdin(Stdio::null())
+            .output();
+        let available = probe.is_ok_and(|output| output.stderr.is_empty());
+        if !available {
+            log::info!("No Secret Service found; keeping credentials in an encrypted file");
+        }
+        available
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn store(account: &str, value: &str) -> Result<()> {
+    if !secret_service_available() {
+        return file_store::store(&file_store::path()?, account, value);
+    }
+    let mut cmd = Command::new("secret-tool");
+    cmd.arg("store")
+        .arg(format!("--label=RuleWeaver {}", account))
+        .args(["service", SERVICE, "account", account]);
+    run(cmd, Some(value)).map(|_| ())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn load(account: &str) -> Result<String> {
+    if !secret_service_available() {
+        return file_store::load(&file_store::path()?, account)?
+            .ok_or_else(|| keychain_error(format!("no entry for {}", account)));
+    }
+    let mut cmd = Command::new("secret-tool");
+    cmd.args(["lookup", "service", SERVICE, "account", account]);
+    run(cmd, None)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn exists(account: &str) -> bool {
+    // secret-tool exits non-zero when nothing matches.
+    load(account).is_ok_and(|value| !value.is_empty())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn delete(account: &str) -> Result<()> {
+    if !secret_service_available() {
+        return file_store::delete(&file_store::path()?, account);
+    }
+    let mut cmd = Command::new("secret-tool");
+    cmd.args(["clear", "service", SERVICE, "account", account]);
+    run(cmd, None).map(|_| ())
+}
+
+/// The fallback for Linux without a Secret Service: all entries in one JSON map, sealed
+/// with a key derived from the machine id and the user. The file is only readable by the
+/// user, and a copy taken to another machine or account cannot be opened. It is weaker
+/// than a real keychain, since anything running as the user can derive the same key.
+#[cfg(all(unix, not(target_os = "macos")))]
+mod file_store {
+    use std::collections::BTreeMap;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+
+    use sha2::{Digest, Sha256};
+
+    use crate::atomic_write::write_atomic;
+    use crate::database::{decrypt, encrypt, DatabaseKey};
+    use crate::error::Result;
+
+    const FILE_NAME: &str = "keychain.enc";
+    const MACHINE_ID_PATHS: &[&str] = &["/etc/machine-id", "/var/lib/dbus/machine-id"];
+
+    pub fn path() -> Result<PathBuf> {
+        Ok(crate::database::base_app_data_dir()?.join(FILE_NAME))
+    }
+
+    fn file_key() -> Result<DatabaseKey> {
+        let machine_id = MACHINE_ID_PATHS
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let home = dirs::home_dir().unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(b"RuleWeaver keychain file\0");
+        hasher.update(machine_id.trim().as_bytes());
+        hasher.update(b"\0");
+        hasher.update(home.to_string_lossy().as_bytes());
+        DatabaseKey::from_hex(&format!("{:x}", hasher.finalize()))
+    }
+
+    fn read(path: &Path) -> Result<BTreeMap<String, String>> {
+        match std::fs::read(path) {
+            Ok(sealed) => Ok(serde_json::from_slice(&decrypt(&file_key()?, &sealed)?)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(path: &Path, entries: &BTreeMap<String, String>) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let sealed = encrypt(&file_key()?, &serde_json::to_vec(entries)?)?;
+        write_atomic(path, sealed)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(())
+    }
+
+    pub fn store(path: &Path, account: &str, value: &str) -> Result<()> {
+        let mut entries = read(path)?;
+        entries.insert(account.to_string(), value.to_string());
+        write(path, &entries)
+    }
+
+    pub fn load(path: &Path, account: &str) -> Result<Option<String>> {
+        Ok(read(path)?.remove(account))
+    }
+
+    pub fn delete(path: &Path, account: &str) -> Result<()> {
+        let mut entries = read(path)?;
+        if entries.remove(account).is_some() {
+            write(path, &entries)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn protected_key_path(account: &str) -> Result<std::path::PathBuf> {
+    let file = match account {
+        DATABASE_KEY_ACCOUNT => "ruleweaver.db.key".to_string(),
+        other => format!("{}.key", other),
+    };
+    Ok(crate::database::default_app_data_dir()?.join(file))
+}
+
+#[cfg(target_os = "windows")]
+fn powershell(script: &str, stdin: Option<&str>) -> Result<String> {
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+    run(cmd, stdin)
+}
+
+#[cfg(target_os = "windows")]
+fn store(account: &str, value: &str) -> Result<()> {
+    let sealed = powershell(
+        "Add-Type -AssemblyName System.Security; \
+         $k = [Text.Encoding]::UTF8.GetBytes([Console]::In.ReadToEnd().Trim()); \
+         [Convert]::ToBase64String([Security.Cryptography.ProtectedData]::Protect($k, $null, 'CurrentUser'))",
+        Some(value),
+    )?;
+    let path = protected_key_path(account)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::atomic_write::write_atomic(&path, sealed)
+}
+
+#[cfg(target_os = "windows")]
+fn load(account: &str) -> Result<String> {
+    let sealed = std::fs::read_to_string(protected_key_path(account)?).map_err(keychain_error)?;
+    powershell(
+        "Add-Type -AssemblyName System.Security; \
+         $b = [Convert]::FromBase64String([Console]::In.ReadToEnd().Trim()); \
+         [Text.Encoding]::UTF8.GetString([Security.Cryptography.ProtectedData]::Unprotect($b, $null, 'CurrentUser'))",
+        Some(&sealed),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn exists(account: &str) -> bool {
+    protected_key_path(account).is_ok_and(|path| path.exists())
+}
+
+#[cfg(target_os = "windows")]
+fn delete(account: &str) -> Result<()> {
+    match std::fs::remove_file(protected_key_path(account)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keychain.enc");
+
+        assert_eq!(file_store::load(&path, "mcp-api-token").unwrap(), None);
+        file_store::store(&path, "mcp-api-token", "token-1").unwrap();
+        file_store::store(&path, "secret-api_key", "hunter2").unwrap();
+        file_store::store(&path, "mcp-api-token", "token-2").unwrap();
+
+        let sealed = std::fs::read(&path).unwrap();
+        assert!(!String::from_utf8_lossy(&sealed).contains("hunter2"));
+        assert_eq!(
+            file_store::load(&path, "mcp-api-token").unwrap().as_deref(),
+            Some("token-2")
+        );
+
+        file_store::delete(&path, "secret-api_key").unwrap();
+        assert_eq!(file_store::load(&path, "secret-api_key").unwrap(), None);
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+    }
+}
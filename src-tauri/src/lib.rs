@@ -1,30 +1,77 @@
+mod ai;
+mod app_log;
+mod artifacts;
+mod atomic_write;
+mod audit;
+mod backup;
+mod change_events;
+mod cli;
+mod cloud_sync;
 mod commands;
 mod constants;
+mod context_composer;
 pub mod database;
+mod deep_link;
+mod doctor;
+mod drift;
+mod env_snapshot;
 pub mod error;
 mod execution;
+mod export_manifest;
+mod export_tree;
+mod external_mcp;
 mod feature_flags;
 mod file_storage;
+mod git_hooks;
+mod jobs;
+mod keychain;
+mod localization;
+mod log_retention;
+mod markdown;
 mod mcp;
+mod metrics;
 pub mod models;
+mod output_parser;
 pub mod path_resolver;
+mod policy;
+mod process_tree;
+mod project_config;
 pub mod reconciliation;
 mod redaction;
+mod rule_feedback;
 pub mod rule_import;
+mod rule_order;
+mod rule_search;
+mod sandbox;
+mod scheduler;
+mod secrets;
+mod sensitive;
+mod settings;
 mod slash_commands;
+mod snapshot;
+mod snippets;
 mod status;
 mod sync;
 pub mod templates;
+mod text_diff;
+mod tool_detection;
+mod util;
+mod webhooks;
+mod workflow;
+mod workspace;
 
 use database::Database;
 use file_storage::RuleFileWatcher;
 use mcp::McpManager;
 use std::sync::Arc;
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{Emitter, Manager};
 
-const MINIMIZE_TO_TRAY_KEY: &str = "minimize_to_tray";
+pub(crate) const MCP_AUTO_START_KEY: &str = "mcp_auto_start";
+pub(crate) const MINIMIZE_TO_TRAY_KEY: &str = "minimize_to_tray";
+/// Tray menu ids of the workspace entries are this prefix followed by the workspace id.
+const WORKSPACE_MENU_PREFIX: &str = "workspace:";
 
 pub struct WatcherState(pub RuleFileWatcher);
 
@@ -33,6 +80,7 @@ pub struct GlobalStatus {
     pub sync_status: parking_lot::Mutex<String>,
     pub mcp_status: parking_lot::Mutex<String>,
     pub menu: parking_lot::Mutex<Option<tauri::menu::Menu<tauri::Wry>>>,
+    pub workspace_menu: parking_lot::Mutex<Option<tauri::menu::Submenu<tauri::Wry>>>,
 }
 
 impl GlobalStatus {
@@ -61,6 +109,39 @@ impl GlobalStatus {
         }
     }
 
+    /// Refills the tray's workspace submenu, e.g. after a workspace is created or renamed.
+    pub fn update_workspace_menu(&self, app: &tauri::AppHandle) {
+        let Some(submenu) = self.workspace_menu.lock().clone() else {
+            return;
+        };
+        let list = match workspace::list() {
+            Ok(list) => list,
+            Err(e) => {
+                log::warn!("Failed to list workspaces for the tray: {}", e);
+                return;
+            }
+        };
+        if let Ok(items) = submenu.items() {
+            for item in items {
+                let _ = submenu.remove(&item);
+            }
+        }
+        for entry in &list.workspaces {
+            let item = CheckMenuItemBuilder::with_id(
+                format!("{}{}", WORKSPACE_MENU_PREFIX, entry.id),
+                &entry.name,
+            )
+            .checked(entry.id == list.active)
+            .build(app);
+            match item {
+                Ok(item) => {
+                    let _ = submenu.append(&item);
+                }
+                Err(e) => log::warn!("Failed to add workspace '{}' to the tray: {}", entry.id, e),
+            }
+        }
+    }
+
     pub fn update_mcp_status(&self, status: &str) {
         {
             *self.mcp_status.lock() = status.to_string();
@@ -69,11 +150,82 @@ impl GlobalStatus {
     }
 }
 
+pub use app_log::init as init_logging;
+pub use cli::{is_cli_invocation, run_cli};
+
+fn background_actor(job: &str) -> models::AuditActor {
+    models::AuditActor::with_detail(models::AuditActorKind::Background, job)
+}
+
+/// Runs a full sync with tray status updates and a completion notification.
+pub(crate) async fn quick_sync(app_handle: tauri::AppHandle) {
+    if let (Some(db), Some(status)) = (
+        app_handle.try_state::<Arc<Database>>(),
+        app_handle.try_state::<GlobalStatus>(),
+    ) {
+        {
+            *status.sync_status.lock() = "Syncing...".to_string();
+            status.update_tray();
+        }
+
+        // Perform sync asynchronously
+        let result = async {
+            let engine = crate::sync::SyncEngine::new(&db);
+            let rules = db.get_all_rules().await?;
+            Ok::<_, crate::error::AppError>(engine.sync_all(rules).await)
+        }
+        .await;
+
+        {
+            *status.sync_status.lock() = "Idle".to_string();
+            status.update_tray();
+        }
+
+        match result {
+            Ok(sync_result) => {
+                use tauri_plugin_notification::NotificationExt;
+                if sync_result.success {
+                    app_handle
+                        .notification()
+                        .builder()
+                        .title("Sync Complete")
+                        .body(format!(
+                            "Successfully synced {} files.",
+                            sync_result.files_written.len()
+                        ))
+                        .show()
+                        .ok();
+                } else {
+                    app_handle
+                        .notification()
+                        .builder()
+                        .title("Sync Failed")
+                        .body("Errors occurred during quick sync.")
+                        .show()
+                        .ok();
+                }
+            }
+            Err(e) => {
+                log::error!("Sync task failed: {}", e);
+            }
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    log::info!("RuleWeaver application initializing");
+    log::info!(
+        "RuleWeaver application initializing in workspace '{}'",
+        workspace::active_id()
+    );
 
     tauri::Builder::default()
+        // Must be registered first so a second launch (e.g. from a ruleweaver:// link) is
+        // forwarded to the running instance instead of opening another window.
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            deep_link::focus_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_autostart::Builder::new().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
@@ -83,6 +235,7 @@ pub fn run() {
             // Initialize database asynchronously blocking the setup
             let db = tauri::async_runtime::block_on(async {
                 let db = Arc::new(Database::new(app.handle()).await?);
+                jobs::recover_interrupted(&db).await;
 
                 // Sync skills to database on startup
                 // Note: sync_skills_to_db likely needs to be async or internal calls do.
@@ -177,13 +330,11 @@ pub fn run() {
 
             // Need to block on getting settings for initial setup
             let (auto_start_mcp, _minimize_to_tray, storage_mode) = tauri::async_runtime::block_on(async {
-                let auto = db
-                    .get_setting("mcp_auto_start")
-                    .await
-                    .ok()
-                    .flatten()
-                    .map(|v| v == "true")
-                    .unwrap_or(false);
+                mcp_manager.set_api_token(mcp::credentials::api_token().await).await;
+                if let Err(e) = mcp::credentials::migrate_plaintext_secrets(&db).await {
+                    log::warn!("Failed to move MCP secrets into the keychain: {}", e);
+                }
+                let auto = settings::load_or_default(&db).await.mcp_auto_start;
 
                 let min = db.get_setting(MINIMIZE_TO_TRAY_KEY).await.ok().flatten();
                 if min.is_none() {
@@ -202,6 +353,43 @@ pub fn run() {
                 });
             }
 
+            // Periodic automatic backups (no-op unless enabled in settings)
+            match crate::database::get_app_data_path(app.handle()) {
+                Ok(app_data_dir) => {
+                    let db_for_backup = Arc::clone(&db);
+                    tauri::async_runtime::spawn(crate::backup::run_auto_backup_loop(
+                        db_for_backup,
+                        crate::backup::backup_dir(&app_data_dir),
+                    ));
+                }
+                Err(e) => log::error!("Failed to resolve backup directory: {}", e),
+            }
+            tauri::async_runtime::spawn(crate::snapshot::run_snapshot_loop(Arc::clone(&db)));
+            tauri::async_runtime::spawn(crate::log_retention::run_log_pruning_loop(Arc::clone(&db)));
+            tauri::async_runtime::spawn(audit::scope(
+                background_actor("subscriptions"),
+                crate::rule_import::subscriptions::run_subscription_loop(Arc::clone(&db)),
+            ));
+            tauri::async_runtime::spawn(audit::scope(
+                background_actor("team_repo"),
+                crate::rule_import::team_repo::run_team_repo_loop(Arc::clone(&db)),
+            ));
+            tauri::async_runtime::spawn(audit::scope(
+                background_actor("cloud_sync"),
+                crate::cloud_sync::run_cloud_sync_loop(Arc::clone(&db)),
+            ));
+            tauri::async_runtime::spawn(crate::scheduler::run_scheduler_loop(
+                Arc::clone(&db),
+                Some(app.handle().clone()),
+            ));
+            tauri::async_runtime::spawn(crate::drift::run_drift_monitor_loop(
+                Arc::clone(&db),
+                app.handle().clone(),
+            ));
+            tauri::async_runtime::spawn(crate::change_events::forward_to_webview(
+                app.handle().clone(),
+            ));
+
             // Start file watcher if in file storage mode
             if storage_mode == "file" {
                 let app_handle = app.handle().clone();
@@ -219,6 +407,7 @@ pub fn run() {
                 .enabled(false)
                 .build(app)?;
             let quick_sync = MenuItemBuilder::with_id("sync", "Quick Sync").build(app)?;
+            let reconcile = MenuItemBuilder::with_id("reconcile", "Reconcile Now").build(app)?;
             let mcp_info = MenuItemBuilder::with_id("mcp_info", "MCP: Disconnected")
                 .enabled(false)
                 .build(app)?;
@@ -226,11 +415,14 @@ pub fn run() {
             let show = MenuItemBuilder::with_id("show", "Show RuleWeaver").build(app)?;
             let hide = MenuItemBuilder::with_id("hide", "Hide to Tray").build(app)?;
             let quit = MenuItemBuilder::with_id("quit", "Quit RuleWeaver").build(app)?;
+            let workspace_menu = SubmenuBuilder::new(app, "Workspace").build()?;
             let tray_menu = MenuBuilder::new(app)
                 .item(&status_label)
                 .item(&mcp_info)
                 .separator()
                 .item(&quick_sync)
+                .item(&reconcile)
+                .item(&workspace_menu)
                 .separator()
                 .item(&show)
                 .item(&hide)
@@ -241,66 +433,20 @@ pub fn run() {
             let global_status = GlobalStatus::default();
             {
                 *global_status.menu.lock() = Some(tray_menu.clone());
+                *global_status.workspace_menu.lock() = Some(workspace_menu);
             }
+            global_status.update_workspace_menu(app.handle());
 
             let app_handle = app.handle().clone();
             let mut tray_builder = TrayIconBuilder::with_id("main")
                 .menu(&tray_menu)
                 .on_menu_event(move |app, event| match event.id().as_ref() {
                     "sync" => {
-                        let app_handle = app.clone();
-                        tauri::async_runtime::spawn(async move {
-                            if let (Some(db), Some(status)) = (
-                                app_handle.try_state::<Arc<Database>>(),
-                                app_handle.try_state::<GlobalStatus>(),
-                            ) {
-                                {
-                                    *status.sync_status.lock() = "Syncing...".to_string();
-                                    status.update_tray();
-                                }
-
-                                // Perform sync asynchronously
-                                let result = async {
-                                    let engine = crate::sync::SyncEngine::new(&db);
-                                    let rules = db.get_all_rules().await?;
-                                    Ok::<_, crate::error::AppError>(engine.sync_all(rules).await)
-                                }.await;
-
-                                {
-                                    *status.sync_status.lock() = "Idle".to_string();
-                                    status.update_tray();
-                                }
+                        tauri::async_runtime::spawn(crate::quick_sync(app.clone()));
+                    }
 
-                                match result {
-                                    Ok(sync_result) => {
-                                        use tauri_plugin_notification::NotificationExt;
-                                        if sync_result.success {
-                                            app_handle
-                                                .notification()
-                                                .builder()
-                                                .title("Sync Complete")
-                                                .body(format!(
-                                                    "Successfully synced {} files.",
-                                                    sync_result.files_written.len()
-                                                ))
-                                                .show()
-                                                .ok();
-                                        } else {
-                                            app_handle
-                                                .notification()
-                                                .builder()
-                                                .title("Sync Failed")
-                                                .body("Errors occurred during quick sync.")
-                                                .show()
-                                                .ok();
-                                        }
-                                    }
-                                    Err(e) => {
-                                        log::error!("Sync task failed: {}", e);
-                                    }
-                                }
-                            }
-                        });
+                    "reconcile" => {
+                        tauri::async_runtime::spawn(crate::drift::reconcile_from_tray(app.clone()));
                     }
 
                     "show" => {
@@ -327,7 +473,17 @@ pub fn run() {
                         }
                         app.exit(0);
                     }
-                    _ => {}
+                    id => {
+                        if let Some(workspace_id) = id.strip_prefix(WORKSPACE_MENU_PREFIX) {
+                            if let Err(e) = workspace::switch(app, workspace_id) {
+                                log::error!("Failed to switch workspace: {}", e);
+                            }
+                            // Keeps the check on the active workspace when nothing changed.
+                            if let Some(status) = app.try_state::<GlobalStatus>() {
+                                status.update_workspace_menu(app);
+                            }
+                        }
+                    }
                 })
                 .on_tray_icon_event(move |tray, event| {
                     if let TrayIconEvent::Click {
@@ -369,12 +525,7 @@ pub fn run() {
                         let app = app_for_events.clone();
                         tauri::async_runtime::spawn(async move {
                             let should_minimize = if let Some(db) = app.try_state::<Arc<Database>>() {
-                                db.get_setting(MINIMIZE_TO_TRAY_KEY)
-                                    .await
-                                    .ok()
-                                    .flatten()
-                                    .map(|v| v == "true")
-                                    .unwrap_or(true)
+                                settings::load_or_default(&db).await.minimize_to_tray
                             } else {
                                 true
                             };
@@ -397,21 +548,63 @@ pub fn run() {
 
             app.manage(Arc::clone(&db));
             app.manage(mcp_manager);
+            app.manage(rule_import::peer::PeerShareManager::default());
+            app.manage(rule_import::share_link::ShareLinkManager::default());
+            app.manage(reconciliation::review::ReconcilePlanStore::default());
+            app.manage(drift::StartupDriftReport::default());
             app.manage(WatcherState(watcher));
             app.manage(global_status);
+
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+
+                // Installed bundles register the scheme themselves; dev builds and AppImages
+                // on Linux/Windows need it registered at runtime.
+                #[cfg(any(windows, target_os = "linux"))]
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("Failed to register ruleweaver:// links: {}", e);
+                }
+
+                let link_handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    deep_link::handle_urls(&link_handle, event.urls());
+                });
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    deep_link::handle_urls(app.handle(), urls);
+                }
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_all_rules,
+            commands::list_rules,
             commands::get_rule_by_id,
             commands::create_rule,
             commands::update_rule,
             commands::delete_rule,
             commands::bulk_delete_rules,
+            commands::bulk_update_rules,
+            commands::bulk_toggle_rules,
+            commands::bulk_set_adapters,
             commands::toggle_rule,
             commands::sync_rules,
             commands::preview_sync,
+            commands::get_rule_stats,
+            commands::report_rule_feedback,
+            commands::get_rule_feedback,
             commands::get_sync_history,
+            commands::get_sync_report,
+            commands::export_sync_report,
+            commands::query_app_logs,
+            commands::query_audit_log,
+            commands::get_pending_jobs,
+            commands::resolve_pending_job,
+            commands::list_workspaces,
+            commands::create_workspace,
+            commands::rename_workspace,
+            commands::delete_workspace,
+            commands::switch_workspace,
+            commands::get_metrics,
             commands::get_app_data_path_cmd,
             commands::open_in_explorer,
             commands::read_file_content,
@@ -420,9 +613,16 @@ pub fn run() {
             commands::get_setting,
             commands::set_setting,
             commands::get_all_settings,
+            commands::get_app_settings,
+            commands::update_app_settings,
+            commands::run_doctor,
             commands::migrate_to_file_storage,
             commands::rollback_file_migration,
             commands::verify_file_migration,
+            commands::get_dual_write_status,
+            commands::complete_file_storage_cutover,
+            commands::rollback_dual_write,
+            commands::rebuild_file_index,
             commands::get_file_migration_progress,
             commands::get_storage_info,
             commands::get_storage_mode,
@@ -439,29 +639,134 @@ pub fn run() {
             commands::scan_rule_clipboard_import,
             commands::import_rule_from_clipboard,
             commands::get_rule_import_history,
+            commands::get_import_mapping_rules,
+            commands::set_import_mapping_rules,
+            commands::start_peer_share,
+            commands::stop_peer_share,
+            commands::get_peer_share,
+            commands::start_share_link,
+            commands::stop_share_link,
+            commands::get_share_link,
+            commands::export_share_site,
+            commands::scan_peer_import,
+            commands::import_from_peer,
+            commands::preview_import_changes,
             commands::import_commands_from_directory,
             commands::scan_command_directory_import,
             commands::import_skills_from_directory,
             commands::scan_skill_directory_import,
+            commands::scan_zip_import,
+            commands::import_from_zip,
+            commands::export_bundle,
+            commands::export_skill_pack,
+            commands::scan_skill_pack_import,
+            commands::import_skill_pack,
+            commands::browse_rule_catalog,
+            commands::preview_catalog_entry,
+            commands::install_catalog_entries,
+            commands::get_catalog_installs,
+            commands::list_import_subscriptions,
+            commands::add_import_subscription,
+            commands::remove_import_subscription,
+            commands::set_import_subscription_enabled,
+            commands::check_import_subscription,
+            commands::approve_subscription_updates,
+            commands::dismiss_subscription_updates,
+            commands::get_team_repo,
+            commands::configure_team_repo,
+            commands::remove_team_repo,
+            commands::set_team_repo_enabled,
+            commands::pull_team_repo,
+            commands::get_team_repo_changes,
+            commands::push_team_repo,
+            commands::get_cloud_sync,
+            commands::configure_cloud_sync,
+            commands::remove_cloud_sync,
+            commands::set_cloud_sync_enabled,
+            commands::sync_cloud_now,
+            commands::get_org_policy,
+            commands::set_org_policy,
             commands::export_configuration,
             commands::import_configuration,
             commands::preview_import,
+            commands::get_export_public_key,
+            commands::backup_database,
+            commands::restore_database,
+            commands::list_database_backups,
+            commands::create_export_snapshot,
+            commands::list_export_snapshots,
+            commands::get_database_encryption_status,
+            commands::enable_database_encryption,
+            commands::disable_database_encryption,
+            commands::get_secrets,
+            commands::set_secret,
+            commands::delete_secret,
+            commands::set_mcp_secret,
+            commands::delete_mcp_secret,
+            commands::scan_sensitive_content,
+            commands::allow_sensitive_finding,
+            commands::disallow_sensitive_finding,
+            commands::get_webhooks,
+            commands::create_webhook,
+            commands::update_webhook,
+            commands::delete_webhook,
+            commands::test_webhook,
+            commands::get_snippets,
+            commands::create_snippet,
+            commands::update_snippet,
+            commands::delete_snippet,
+            commands::get_snippet_usages,
+            commands::get_all_workflows,
+            commands::get_workflow_by_id,
+            commands::create_workflow,
+            commands::update_workflow,
+            commands::delete_workflow,
+            commands::run_workflow,
+            commands::get_all_collections,
+            commands::get_collection_items,
+            commands::create_collection,
+            commands::rename_collection,
+            commands::move_collection,
+            commands::reorder_collections,
+            commands::delete_collection,
+            commands::move_to_collection,
+            commands::reorder_collection_items,
             commands::get_all_commands,
+            commands::list_commands,
             commands::get_command_by_id,
             commands::create_command,
             commands::update_command,
             commands::delete_command,
             commands::test_command,
+            commands::rerun_execution,
+            commands::preview_command_execution,
+            commands::cancel_execution,
             commands::sync_commands,
             commands::get_all_skills,
+            commands::list_skills,
             commands::get_skill_by_id,
+            commands::validate_skill_markdown,
             commands::create_skill,
             commands::update_skill,
             commands::delete_skill,
             commands::get_skill_templates,
+            commands::get_skill_template_installs,
+            commands::get_skill_versions,
+            commands::rollback_skill,
             commands::install_skill_template,
             commands::get_rule_templates,
             commands::install_rule_template,
+            commands::get_custom_rule_templates,
+            commands::create_rule_template,
+            commands::update_rule_template,
+            commands::delete_rule_template,
+            commands::preview_rule_from_template,
+            commands::create_rule_from_template,
+            commands::lint_rule_order,
+            commands::validate_rule_markdown,
+            commands::ai_draft_rule,
+            commands::ai_condense_rule,
+            commands::ai_merge_rules,
             commands::get_command_templates,
             commands::install_command_template,
             commands::sync_skills,
@@ -472,8 +777,22 @@ pub fn run() {
             commands::restart_mcp_server,
             commands::get_mcp_connection_instructions,
             commands::get_mcp_logs,
+            commands::list_mcp_tokens,
+            commands::create_mcp_token,
+            commands::revoke_mcp_token,
+            commands::get_pending_approvals,
+            commands::respond_to_approval,
+            commands::get_approval_audit,
+            commands::get_external_mcp_servers,
+            commands::create_external_mcp_server,
+            commands::update_external_mcp_server,
+            commands::delete_external_mcp_server,
+            commands::scan_tool_mcp_servers,
+            commands::import_tool_mcp_servers,
+            commands::sync_external_mcp_servers,
             commands::get_execution_history,
             commands::get_execution_history_filtered,
+            commands::get_execution_artifacts,
             slash_commands::commands::sync_slash_command,
             slash_commands::commands::sync_all_slash_commands,
             slash_commands::commands::get_slash_command_status,
@@ -483,18 +802,35 @@ pub fn run() {
             slash_commands::commands::test_slash_command_generation,
             slash_commands::commands::get_slash_command_path,
             commands::get_tool_registry,
+            commands::detect_installed_tools,
             commands::reconcile_all,
             commands::reconcile_preview,
+            commands::get_reconcile_plan,
+            commands::apply_reconcile_plan,
             commands::reconcile_repair,
             commands::needs_reconciliation,
+            commands::reconcile_drift,
+            commands::get_startup_drift_report,
             commands::get_stale_paths,
+            commands::adopt_orphaned_artifacts,
+            commands::get_missing_repo_roots,
+            commands::remove_missing_repo_roots,
+            commands::install_git_hooks,
+            commands::uninstall_git_hooks,
+            commands::get_git_hook_status,
+            commands::get_project_config,
+            commands::get_reconcile_protected_paths,
+            commands::set_reconcile_protected_paths,
             commands::get_reconciliation_logs,
             commands::clear_reconciliation_logs,
+            commands::purge_logs,
             status::commands::get_artifact_status,
             status::commands::get_artifact_status_summary,
             status::commands::repair_artifact,
             status::commands::repair_all_artifacts,
             status::commands::refresh_artifact_status,
+            status::commands::get_skill_sync_status,
+            status::commands::get_unified_status,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -507,11 +843,14 @@ pub fn run_mcp_cli(port: u16, token: Option<String>) -> std::result::Result<(),
         let db = Arc::new(Database::new_for_cli().await.map_err(|e| e.to_string())?);
         let manager = McpManager::new(port);
 
-        if let Some(t) = token {
-            manager.set_api_token(t).await;
-        }
+        let token = match token {
+            Some(token) => token,
+            None => mcp::credentials::api_token().await,
+        };
+        manager.set_api_token(token).await;
 
         manager.start(&db).await.map_err(|e| e.to_string())?;
+        tokio::spawn(crate::scheduler::run_scheduler_loop(Arc::clone(&db), None));
         manager
             .wait_until_stopped()
             .await
@@ -526,10 +865,24 @@ async fn setup_watcher(
     watcher: RuleFileWatcher,
 ) -> crate::error::Result<()> {
     let global_dir = crate::file_storage::get_global_rules_dir()?;
-    if !global_dir.exists() {
-        std::fs::create_dir_all(&global_dir)?;
+    if let Err(e) = std::fs::create_dir_all(&global_dir) {
+        // A synced or network folder may simply be offline; the watcher waits for it.
+        log::warn!(
+            "Global rules dir {} is unavailable: {}",
+            global_dir.display(),
+            e
+        );
     }
 
+    let mode = db
+        .get_setting(crate::file_storage::watcher::WATCH_MODE_KEY)
+        .await
+        .ok()
+        .flatten();
+    watcher.set_mode(crate::file_storage::watcher::WatchMode::from_setting(
+        mode.as_deref(),
+    ));
+
     let app_handle_for_callback = app.clone();
     let db_for_callback = Arc::clone(&db);
 
@@ -542,7 +895,8 @@ async fn setup_watcher(
             | crate::file_storage::FileChangeEvent::Modified(path) => {
                 log::info!("File watcher detected change in: {}", path.display());
 
-                tauri::async_runtime::spawn(async move {
+                let actor = models::AuditActor::new(models::AuditActorKind::Watcher);
+                tauri::async_runtime::spawn(audit::scope(actor, async move {
                     if let Err(e) = handle_external_rule_change(&app, &db, path).await {
                         log::error!("Failed to handle external rule change: {}", e);
                         use tauri_plugin_notification::NotificationExt;
@@ -553,7 +907,7 @@ async fn setup_watcher(
                             .show()
                             .ok();
                     }
-                });
+                }));
             }
             crate::file_storage::FileChangeEvent::Deleted(path) => {
                 log::info!("File watcher detected deletion: {}", path.display());
@@ -568,7 +922,7 @@ async fn setup_watcher(
         for root in local_roots {
             let local_rules_dir = crate::file_storage::get_local_rules_dir(&root);
             if local_rules_dir.exists() {
-                if let Err(e) = watcher.start(&local_rules_dir, callback.clone()) {
+                if let Err(e) = watcher.watch(&local_rules_dir) {
                     log::error!(
                         "Failed to watch local dir {}: {}",
                         local_rules_dir.display(),
@@ -591,12 +945,33 @@ async fn handle_external_rule_change(
 
     let status = app.try_state::<GlobalStatus>();
 
-    // Canonicalize input path for reliable comparison
-    let canonical_path = std::fs::canonicalize(&path)?;
+    // Canonicalize input path for reliable comparison. Sync clients and network drives can
+    // briefly fail this while a file is being replaced, so retry before giving up.
+    let canonical_path = match crate::file_storage::watcher::retry_with_backoff(
+        "Canonicalizing changed rule file",
+        || std::fs::canonicalize(&path),
+    )
+    .await
+    {
+        Ok(canonical_path) => canonical_path,
+        Err(e) if !path.exists() => {
+            log::info!(
+                "Ignoring change to {}, which is no longer available: {}",
+                path.display(),
+                e
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
     let path_str = canonical_path.to_string_lossy().to_string();
 
     // 1. Load the rule from disk
-    let rule_from_disk = crate::file_storage::load_rule_from_file(&canonical_path)?;
+    let rule_from_disk =
+        crate::file_storage::watcher::retry_with_backoff("Loading changed rule file", || {
+            crate::file_storage::load_rule_from_file(&canonical_path)
+        })
+        .await?;
 
     // 2. Check if it exists in DB
     let existing_rule = db.get_rule_by_id(&rule_from_disk.id).await.ok();
@@ -659,6 +1034,7 @@ async fn handle_external_rule_change(
             target_paths: rule_from_disk.target_paths.clone(),
             enabled_adapters: rule_from_disk.enabled_adapters.clone(),
             enabled: rule_from_disk.enabled,
+            metadata: rule_from_disk.metadata.clone(),
         })
         .await?;
     }
@@ -0,0 +1,172 @@
+//! Language variants of a rule.
+//!
+//! A rule keeps translations of its content in its `variants` metadata, an object from a
+//! language code such as `ja` or `pt-BR` to the content in that language; the rule's own
+//! content is the default. Which variant sync writes is chosen per repository by the
+//! `language` of its `.ruleweaver.toml`, and otherwise per tool by the `adapter_languages`
+//! setting. A rule without a variant for the chosen language is written as it is.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{AdapterType, Metadata, Rule};
+
+pub const VARIANTS_METADATA_KEY: &str = "variants";
+/// Setting holding the language written for each tool as a map of adapter id to language.
+pub const ADAPTER_LANGUAGES_KEY: &str = "adapter_languages";
+
+const MAX_LANGUAGE_LENGTH: usize = 35;
+
+/// Checks that `language` looks like a BCP 47 tag: letters, then hyphen-separated
+/// alphanumeric parts.
+pub fn validate_language(language: &str) -> Result<()> {
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let valid = language.len() <= MAX_LANGUAGE_LENGTH
+        && (2..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "'{}' is not a language code such as 'en' or 'pt-BR'",
+            language
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the `variants` in `metadata`, if any: an object of language codes to text.
+pub fn validate_variants(metadata: &Metadata) -> Result<()> {
+    let Some(variants) = metadata.get(VARIANTS_METADATA_KEY) else {
+        return Ok(());
+    };
+    let Value::Object(variants) = variants else {
+        return Err(AppError::Validation(format!(
+            "Metadata '{}' must map language codes to content",
+            VARIANTS_METADATA_KEY
+        )));
+    };
+    for (language, content) in variants {
+        validate_language(language)?;
+        if !content.is_string() {
+            return Err(AppError::Validation(format!(
+                "The '{}' variant must be text",
+                language
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The content of `rule` in `language`. A variant for the exact tag is preferred, then
+/// one for its primary language, so `ja-JP` falls back to `ja`.
+pub fn variant<'a>(rule: &'a Rule, language: &str) -> Option<&'a str> {
+    let Some(Value::Object(variants)) = rule.metadata.get(VARIANTS_METADATA_KEY) else {
+        return None;
+    };
+    let find = |wanted: &str| {
+        variants
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(wanted))
+            .and_then(|(_, content)| content.as_str())
+    };
+    find(language).or_else(|| find(language.split('-').next()?))
+}
+
+/// `rule` as written in `language`. Its variants are dropped, so a rule localized for a
+/// repository is not localized again for a tool.
+pub fn localize(rule: &Rule, language: Option<&str>) -> Rule {
+    let mut localized = rule.clone();
+    if let Some(content) = language.and_then(|language| variant(rule, language)) {
+        localized.content = content.to_string();
+    }
+    localized.metadata.remove(VARIANTS_METADATA_KEY);
+    localized
+}
+
+/// Rewrites the content of each of `rule`'s variants with `f`, as sync does to its content.
+pub fn map_variants(rule: &mut Rule, f: impl Fn(&str) -> String) {
+    let Some(Value::Object(variants)) = rule.metadata.get(VARIANTS_METADATA_KEY) else {
+        return;
+    };
+    let mapped: serde_json::Map<String, Value> = variants
+        .iter()
+        .map(|(language, content)| match content.as_str() {
+            Some(text) => (language.clone(), Value::String(f(text))),
+            None => (language.clone(), content.clone()),
+        })
+        .collect();
+    rule.metadata
+        .insert(VARIANTS_METADATA_KEY, Value::Object(mapped));
+}
+
+/// The language configured for each tool. Invalid entries are logged and ignored.
+pub async fn adapter_languages(db: &Database) -> HashMap<AdapterType, String> {
+    let settings = crate::settings::load_or_default(db).await;
+    settings
+        .adapter_languages
+        .into_iter()
+        .filter_map(
+            |(adapter, language)| match AdapterType::from_str(&adapter) {
+                Ok(adapter) => Some((adapter, language)),
+                Err(_) => {
+                    log::warn!("Ignoring language for unknown adapter '{}'", adapter);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Scope;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn rule(variants: Value) -> Rule {
+        let mut metadata = Metadata::new();
+        metadata.insert(VARIANTS_METADATA_KEY, variants);
+        Rule {
+            id: "id-style".to_string(),
+            name: "Style".to_string(),
+            description: String::new(),
+            content: "Use tabs.".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_localize_picks_the_closest_variant() {
+        let style = rule(json!({ "ja": "タブを使う。", "pt-BR": "Use tabulações." }));
+
+        assert_eq!(localize(&style, Some("JA-jp")).content, "タブを使う。");
+        assert_eq!(localize(&style, Some("pt-br")).content, "Use tabulações.");
+        assert_eq!(localize(&style, Some("pt")).content, "Use tabs.");
+        let default = localize(&style, None);
+        assert_eq!(default.content, "Use tabs.");
+        assert!(default.metadata.get(VARIANTS_METADATA_KEY).is_none());
+    }
+
+    #[test]
+    fn test_validate_variants() {
+        assert!(validate_variants(&rule(json!({ "ja": "", "zh-Hant": "" })).metadata).is_ok());
+        assert!(validate_variants(&rule(json!({ "japanese!": "" })).metadata).is_err());
+        assert!(validate_variants(&rule(json!({ "ja": 1 })).metadata).is_err());
+        assert!(validate_variants(&rule(json!(["ja"])).metadata).is_err());
+        assert!(validate_language("x").is_err());
+    }
+}
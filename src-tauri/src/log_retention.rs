@@ -0,0 +1,188 @@
+//! Retention for the append-only log tables.
+//!
+//! Execution, sync, reconciliation and audit logs are pruned in the background to a maximum row
+//! count and age. Either bound can be disabled by setting it to `0`.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::limits::{
+    DEFAULT_LOG_RETENTION_MAX_AGE_DAYS, DEFAULT_LOG_RETENTION_MAX_ROWS,
+};
+use crate::constants::timing::LOG_PRUNE_INTERVAL;
+use crate::database::Database;
+use crate::error::Result;
+use crate::models::ParseEnumError;
+
+pub const LOG_RETENTION_MAX_ROWS_KEY: &str = "log_retention_max_rows";
+pub const LOG_RETENTION_MAX_AGE_DAYS_KEY: &str = "log_retention_max_age_days";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTable {
+    Execution,
+    Sync,
+    Reconciliation,
+    Audit,
+}
+
+impl LogTable {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogTable::Execution => "execution",
+            LogTable::Sync => "sync",
+            LogTable::Reconciliation => "reconciliation",
+            LogTable::Audit => "audit",
+        }
+    }
+
+    pub fn all() -> Vec<Self> {
+        vec![
+            LogTable::Execution,
+            LogTable::Sync,
+            LogTable::Reconciliation,
+            LogTable::Audit,
+        ]
+    }
+
+    pub(crate) fn table_name(&self) -> &'static str {
+        match self {
+            LogTable::Execution => "execution_logs",
+            LogTable::Sync => "sync_logs",
+            LogTable::Reconciliation => "reconciliation_logs",
+            LogTable::Audit => "audit_log",
+        }
+    }
+
+    pub(crate) fn timestamp_column(&self) -> &'static str {
+        match self {
+            LogTable::Execution => "executed_at",
+            LogTable::Sync | LogTable::Reconciliation | LogTable::Audit => "timestamp",
+        }
+    }
+}
+
+impl FromStr for LogTable {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "execution" => Ok(LogTable::Execution),
+            "sync" => Ok(LogTable::Sync),
+            "reconciliation" => Ok(LogTable::Reconciliation),
+            "audit" => Ok(LogTable::Audit),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// Rows removed from one log table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPurgeResult {
+    pub table: LogTable,
+    pub deleted: u64,
+}
+
+/// Reads a retention bound; a missing or invalid value falls back to `default` and `0`
+/// disables the bound.
+pub(crate) async fn retention_setting(db: &Database, key: &str, default: u64) -> Option<u64> {
+    let value = db
+        .get_setting(key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(default);
+    (value > 0).then_some(value)
+}
+
+/// Applies the configured retention to every log table.
+pub async fn prune_logs(db: &Database) -> Result<Vec<LogPurgeResult>> {
+    let max_rows = retention_setting(
+        db,
+        LOG_RETENTION_MAX_ROWS_KEY,
+        DEFAULT_LOG_RETENTION_MAX_ROWS,
+    )
+    .await;
+    let max_age_days = retention_setting(
+        db,
+        LOG_RETENTION_MAX_AGE_DAYS_KEY,
+        DEFAULT_LOG_RETENTION_MAX_AGE_DAYS,
+    )
+    .await;
+    let older_than = max_age_days.map(|days| Utc::now().timestamp() - (days as i64) * 24 * 60 * 60);
+
+    let mut results = Vec::new();
+    for table in LogTable::all() {
+        let deleted = db.prune_log_table(table, max_rows, older_than).await?;
+        results.push(LogPurgeResult { table, deleted });
+    }
+    Ok(results)
+}
+
+/// Background loop that periodically enforces log and artifact retention.
+pub async fn run_log_pruning_loop(db: Arc<Database>) {
+    loop {
+        match prune_logs(&db).await {
+            Ok(results) => {
+                let deleted: u64 = results.iter().map(|r| r.deleted).sum();
+                if deleted > 0 {
+                    log::info!("Pruned {} log entries", deleted);
+                }
+            }
+            Err(e) => log::error!("Log pruning failed: {}", e),
+        }
+        // Runs after log pruning so artifacts of removed entries go in the same pass.
+        let pruned_artifacts = match crate::artifacts::artifacts_root() {
+            Ok(root) => crate::artifacts::prune_artifacts(&db, &root).await,
+            Err(e) => Err(e),
+        };
+        match pruned_artifacts {
+            Ok(removed) if removed > 0 => log::info!("Pruned artifacts of {} executions", removed),
+            Ok(_) => {}
+            Err(e) => log::error!("Artifact pruning failed: {}", e),
+        }
+        tokio::time::sleep(LOG_PRUNE_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed_sync_logs(db: &Database, count: usize) {
+        for i in 0..count {
+            db.add_sync_log(i as u32, "success", "test").await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prune_logs_enforces_max_rows() {
+        let db = Database::new_in_memory().await.unwrap();
+        seed_sync_logs(&db, 5).await;
+        db.set_setting(LOG_RETENTION_MAX_ROWS_KEY, "3")
+            .await
+            .unwrap();
+
+        let results = prune_logs(&db).await.unwrap();
+        let sync = results.iter().find(|r| r.table == LogTable::Sync).unwrap();
+        assert_eq!(sync.deleted, 2);
+        assert_eq!(db.get_sync_history(100).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_zero_disables_retention() {
+        let db = Database::new_in_memory().await.unwrap();
+        seed_sync_logs(&db, 5).await;
+        db.set_setting(LOG_RETENTION_MAX_ROWS_KEY, "0")
+            .await
+            .unwrap();
+
+        prune_logs(&db).await.unwrap();
+        assert_eq!(db.get_sync_history(100).await.unwrap().len(), 5);
+    }
+}
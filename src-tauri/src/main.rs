@@ -1,7 +1,13 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
-    env_logger::init();
+    ruleweaver_lib::init_logging();
+
+    let args: Vec<std::ffi::OsString> = std::env::args_os().skip(1).collect();
+    if ruleweaver_lib::is_cli_invocation(&args) {
+        std::process::exit(ruleweaver_lib::run_cli(args));
+    }
+
     log::info!("RuleWeaver GUI starting up");
     ruleweaver_lib::run()
 }
@@ -0,0 +1,404 @@
+//! Markdown structure checks and a normalized form for comparing content.
+//!
+//! Rules and skill instructions are parsed with pulldown-cmark. [`validate`] reports headings
+//! that skip a level or have no text, and relative links that point at files missing from a
+//! skill's directory. [`normalize`] writes the parsed document back out with every block on
+//! its own line, whitespace collapsed and one spelling for each list marker, emphasis and
+//! heading style, so two texts that only differ in formatting normalize the same. Sync
+//! hashes files in this form; it is not meant to be rendered.
+
+use std::path::{Component, Path};
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkdownIssueKind {
+    /// A heading more than one level below the heading before it
+    SkippedHeadingLevel,
+    /// A heading with no text
+    EmptyHeading,
+    /// A relative link or image whose file does not exist
+    BrokenLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownIssue {
+    pub kind: MarkdownIssueKind,
+    /// 1-based line the issue starts on
+    pub line: usize,
+    pub message: String,
+}
+
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+}
+
+fn level_number(level: HeadingLevel) -> usize {
+    level as usize
+}
+
+/// The file a link points at, relative to the document, or `None` for anything else:
+/// URLs, anchors, absolute paths and paths leaving the directory.
+fn relative_target(destination: &str) -> Option<&str> {
+    let path = destination
+        .split(['#', '?'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if path.is_empty() || path.contains(':') || path.starts_with('/') || path.starts_with('\\') {
+        return None;
+    }
+    Path::new(path)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        .then_some(path)
+}
+
+/// Problems with the structure of `content`. Relative links are checked against
+/// `asset_root`, the directory the document's files live in, when one is given.
+pub fn validate(content: &str, asset_root: Option<&Path>) -> Vec<MarkdownIssue> {
+    let line_of = |offset: usize| content[..offset].matches('\n').count() + 1;
+    let mut issues = Vec::new();
+    let mut previous_level: Option<usize> = None;
+    // (level, text, line) of the heading being read
+    let mut heading: Option<(usize, String, usize)> = None;
+
+    for (event, range) in Parser::new_ext(content, options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading = Some((level_number(level), String::new(), line_of(range.start)));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, heading_text, _)) = &mut heading {
+                    heading_text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let Some((level, text, line)) = heading.take() else {
+                    continue;
+                };
+                if text.trim().is_empty() {
+                    issues.push(MarkdownIssue {
+                        kind: MarkdownIssueKind::EmptyHeading,
+                        line,
+                        message: "Heading has no text".to_string(),
+                    });
+                }
+                if let Some(previous) = previous_level {
+                    if level > previous + 1 {
+                        issues.push(MarkdownIssue {
+                            kind: MarkdownIssueKind::SkippedHeadingLevel,
+                            line,
+                            message: format!(
+                                "Level {} heading follows a level {} heading",
+                                level, previous
+                            ),
+                        });
+                    }
+                }
+                previous_level = Some(level);
+            }
+            Event::Start(Tag::Link { dest_url, .. })
+            | Event::Start(Tag::Image { dest_url, .. }) => {
+                let (Some(root), Some(target)) = (asset_root, relative_target(&dest_url)) else {
+                    continue;
+                };
+                if !root.join(target).exists() {
+                    issues.push(MarkdownIssue {
+                        kind: MarkdownIssueKind::BrokenLink,
+                        line: line_of(range.start),
+                        message: format!("Linked file '{}' does not exist", target),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    issues
+}
+
+/// A block container: its prefix on the first line written inside it, and on later ones.
+struct Container {
+    first: String,
+    rest: String,
+    used: bool,
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    inline: String,
+    containers: Vec<Container>,
+    /// Next number of each open list; `None` for bullet lists
+    lists: Vec<Option<u64>>,
+    links: Vec<String>,
+    in_code: bool,
+}
+
+impl Writer {
+    fn line(&mut self, text: &str) {
+        for container in &mut self.containers {
+            self.out.push_str(if container.used {
+                &container.rest
+            } else {
+                &container.first
+            });
+            container.used = true;
+        }
+        self.out.push_str(text.trim_end());
+        self.out.push('\n');
+    }
+
+    /// Writes the pending inline text, one line per hard break.
+    fn flush(&mut self) {
+        let inline = std::mem::take(&mut self.inline);
+        if inline.trim().is_empty() {
+            return;
+        }
+        for line in inline.trim().split('\n') {
+            self.line(line.trim());
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.in_code {
+            self.inline.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if !c.is_whitespace() {
+                self.inline.push(c);
+            } else if !self.inline.is_empty() && !self.inline.ends_with([' ', '\n']) {
+                self.inline.push(' ');
+            }
+        }
+    }
+
+    fn open(&mut self, first: String, rest: String) {
+        self.flush();
+        self.containers.push(Container {
+            first,
+            rest,
+            used: false,
+        });
+    }
+
+    fn close(&mut self) {
+        self.flush();
+        if self.containers.last().is_some_and(|c| !c.used) {
+            self.line("");
+        }
+        self.containers.pop();
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph | Tag::TableHead | Tag::TableRow => self.flush(),
+            Tag::Heading { level, .. } => {
+                self.flush();
+                self.inline = format!("{} ", "#".repeat(level_number(level)));
+            }
+            Tag::BlockQuote(_) => self.open("> ".to_string(), "> ".to_string()),
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                match kind {
+                    CodeBlockKind::Fenced(info) => self.line(&format!("```{}", info.trim())),
+                    CodeBlockKind::Indented => self.line("```"),
+                }
+                self.in_code = true;
+            }
+            Tag::HtmlBlock => self.flush(),
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{}. ", number);
+                        *number += 1;
+                        marker
+                    }
+                    _ => "- ".to_string(),
+                };
+                let indent = " ".repeat(marker.len());
+                self.open(marker, indent);
+            }
+            Tag::FootnoteDefinition(label) => {
+                self.open(format!("[^{}]: ", label), "    ".to_string())
+            }
+            Tag::Table(alignments) => {
+                self.flush();
+                let row: Vec<&str> = alignments
+                    .iter()
+                    .map(|a| match a {
+                        Alignment::None => "---",
+                        Alignment::Left => ":--",
+                        Alignment::Center => ":-:",
+                        Alignment::Right => "--:",
+                    })
+                    .collect();
+                // The alignment row is written first; column order is all that matters.
+                self.line(&format!("| {} |", row.join(" | ")));
+            }
+            Tag::TableCell => self.inline.push_str("| "),
+            Tag::Emphasis => self.inline.push('*'),
+            Tag::Strong => self.inline.push_str("**"),
+            Tag::Strikethrough => self.inline.push_str("~~"),
+            Tag::Superscript => self.inline.push('^'),
+            Tag::Subscript => self.inline.push('~'),
+            Tag::Link { dest_url, .. } => {
+                self.inline.push('[');
+                self.links.push(dest_url.to_string());
+            }
+            Tag::Image { dest_url, .. } => {
+                self.inline.push_str("![");
+                self.links.push(dest_url.to_string());
+            }
+            Tag::MetadataBlock(_) => {
+                self.flush();
+                self.line("---");
+                self.in_code = true;
+            }
+            Tag::DefinitionList | Tag::DefinitionListTitle | Tag::DefinitionListDefinition => {
+                self.flush()
+            }
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::HtmlBlock
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition => self.flush(),
+            TagEnd::TableHead | TagEnd::TableRow => {
+                self.inline.push('|');
+                self.flush();
+            }
+            TagEnd::TableCell => self.inline.push(' '),
+            TagEnd::BlockQuote(_) | TagEnd::Item | TagEnd::FootnoteDefinition => self.close(),
+            TagEnd::CodeBlock | TagEnd::MetadataBlock(_) => {
+                let code = std::mem::take(&mut self.inline);
+                for line in code.lines() {
+                    self.line(line);
+                }
+                self.line(if tag == TagEnd::CodeBlock {
+                    "```"
+                } else {
+                    "---"
+                });
+                self.in_code = false;
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+            }
+            TagEnd::Emphasis => self.inline.push('*'),
+            TagEnd::Strong => self.inline.push_str("**"),
+            TagEnd::Strikethrough => self.inline.push_str("~~"),
+            TagEnd::Superscript => self.inline.push('^'),
+            TagEnd::Subscript => self.inline.push('~'),
+            TagEnd::Link | TagEnd::Image => {
+                let destination = self.links.pop().unwrap_or_default();
+                self.inline.push_str(&format!("]({})", destination));
+            }
+            TagEnd::Table | TagEnd::DefinitionList => self.flush(),
+        }
+    }
+}
+
+/// `content` with formatting-only differences removed; see the module docs.
+pub fn normalize(content: &str) -> String {
+    let mut writer = Writer::default();
+    for event in Parser::new_ext(content, options()) {
+        match event {
+            Event::Start(tag) => writer.start(tag),
+            Event::End(tag) => writer.end(tag),
+            Event::Text(text) => writer.push_text(&text),
+            Event::Code(code) => writer.inline.push_str(&format!("`{}`", code)),
+            Event::InlineMath(math) => writer.inline.push_str(&format!("${}$", math)),
+            Event::DisplayMath(math) => writer.inline.push_str(&format!("$${}$$", math)),
+            Event::Html(html) => {
+                for line in html.lines() {
+                    writer.inline.push_str(line.trim());
+                    writer.inline.push('\n');
+                }
+            }
+            Event::InlineHtml(html) => writer.inline.push_str(&html),
+            Event::FootnoteReference(label) => writer.inline.push_str(&format!("[^{}]", label)),
+            Event::SoftBreak => writer.push_text(" "),
+            Event::HardBreak => writer.inline.push('\n'),
+            Event::Rule => {
+                writer.flush();
+                writer.line("***");
+            }
+            Event::TaskListMarker(checked) => {
+                writer
+                    .inline
+                    .push_str(if checked { "[x] " } else { "[ ] " })
+            }
+        }
+    }
+    writer.flush();
+    writer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_ignores_formatting_only_changes() {
+        let original =
+            "# Style\n\nUse *tabs* for\nindentation.\n\n* one\n* two\n\n```rust\nlet x = 1;\n```\n";
+        let reformatted = "Style\n=====\n\n\n\nUse _tabs_ for indentation.   \r\n\r\n- one\n- two\n\n```rust\nlet x = 1;\n```";
+        assert_eq!(normalize(original), normalize(reformatted));
+        assert_eq!(
+            normalize(original),
+            "# Style\nUse *tabs* for indentation.\n- one\n- two\n```rust\nlet x = 1;\n```\n"
+        );
+
+        assert_ne!(
+            normalize(original),
+            normalize(&original.replace("tabs", "spaces"))
+        );
+        assert_ne!(normalize("```\na  b\n```"), normalize("```\na b\n```"));
+        assert_eq!(
+            normalize("> 1. first\n>    second para\n>\n>    more\n"),
+            "> 1. first second para\n>    more\n"
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_heading_structure_and_broken_links() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("scripts")).unwrap();
+        std::fs::write(dir.path().join("scripts/run.sh"), "").unwrap();
+        let content = "# Skill\n\n### Deep\n\n## \n\nRun [it](scripts/run.sh), see \
+                       [docs](resources/missing.md#usage), [site](https://example.com) \
+                       and [top](#skill).\n";
+
+        let kinds: Vec<(MarkdownIssueKind, usize)> = validate(content, Some(dir.path()))
+            .into_iter()
+            .map(|issue| (issue.kind, issue.line))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (MarkdownIssueKind::SkippedHeadingLevel, 3),
+                (MarkdownIssueKind::EmptyHeading, 5),
+                (MarkdownIssueKind::BrokenLink, 7),
+            ]
+        );
+        assert_eq!(validate(content, None).len(), 2);
+    }
+}
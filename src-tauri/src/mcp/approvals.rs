@@ -0,0 +1,122 @@
+//! Approval gates for MCP-triggered commands.
+//!
+//! A call to a command whose MCP policy requires approval is parked here until the user
+//! approves or denies it in the desktop app, or until `MCP_APPROVAL_TIMEOUT` passes.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+use crate::models::{ApprovalDecision, Command, PendingApproval};
+
+/// A parked call and the channel that releases it.
+type Waiting = (PendingApproval, oneshot::Sender<bool>);
+
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalQueue {
+    pending: Arc<Mutex<HashMap<String, Waiting>>>,
+}
+
+impl ApprovalQueue {
+    /// Parks a call and returns the request plus a receiver for the user's answer.
+    pub fn request(
+        &self,
+        cmd: &Command,
+        arguments_json: &str,
+    ) -> (PendingApproval, oneshot::Receiver<bool>) {
+        let approval = PendingApproval {
+            id: uuid::Uuid::new_v4().to_string(),
+            command_id: cmd.id.clone(),
+            command_name: cmd.name.clone(),
+            script: cmd.script.clone(),
+            arguments_json: arguments_json.to_string(),
+            requested_at: chrono::Utc::now(),
+        };
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .insert(approval.id.clone(), (approval.clone(), tx));
+        (approval, rx)
+    }
+
+    pub fn pending(&self) -> Vec<PendingApproval> {
+        let mut pending: Vec<PendingApproval> = self
+            .pending
+            .lock()
+            .values()
+            .map(|(approval, _)| approval.clone())
+            .collect();
+        pending.sort_by_key(|a| a.requested_at);
+        pending
+    }
+
+    /// Delivers the user's answer. Returns `false` if the request is no longer waiting.
+    pub fn respond(&self, id: &str, approved: bool) -> bool {
+        match self.pending.lock().remove(id) {
+            Some((_, tx)) => tx.send(approved).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Drops a request that was never answered.
+    pub fn expire(&self, id: &str) {
+        self.pending.lock().remove(id);
+    }
+}
+
+/// Waits for the answer to a parked call, treating a dropped request as denied.
+pub async fn wait_for_decision(
+    queue: &ApprovalQueue,
+    approval: &PendingApproval,
+    rx: oneshot::Receiver<bool>,
+    timeout: std::time::Duration,
+) -> ApprovalDecision {
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(true)) => ApprovalDecision::Approved,
+        Ok(Ok(false)) | Ok(Err(_)) => ApprovalDecision::Denied,
+        Err(_) => {
+            queue.expire(&approval.id);
+            ApprovalDecision::TimedOut
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn command() -> Command {
+        Command::new(
+            "deploy".to_string(),
+            String::new(),
+            "make deploy".to_string(),
+            false,
+        )
+    }
+
+    #[tokio::test]
+    async fn approved_calls_proceed() {
+        let queue = ApprovalQueue::default();
+        let (approval, rx) = queue.request(&command(), "{}");
+        assert_eq!(queue.pending().len(), 1);
+
+        assert!(queue.respond(&approval.id, true));
+        let decision = wait_for_decision(&queue, &approval, rx, Duration::from_secs(5)).await;
+        assert_eq!(decision, ApprovalDecision::Approved);
+        assert!(queue.pending().is_empty());
+        assert!(!queue.respond(&approval.id, true));
+    }
+
+    #[tokio::test]
+    async fn unanswered_calls_time_out() {
+        let queue = ApprovalQueue::default();
+        let (approval, rx) = queue.request(&command(), "{}");
+
+        let decision = wait_for_decision(&queue, &approval, rx, Duration::from_millis(20)).await;
+        assert_eq!(decision, ApprovalDecision::TimedOut);
+        assert!(queue.pending().is_empty());
+    }
+}
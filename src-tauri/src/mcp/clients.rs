@@ -0,0 +1,159 @@
+//! Tracks which MCP clients are talking to the server.
+//!
+//! A client announces itself with the `clientInfo` of its `initialize` request. Later
+//! requests carry no session id, so they are attributed to the client that initialized with
+//! the same `User-Agent`. A client that has not been heard from within
+//! `MCP_CLIENT_IDLE_TIMEOUT` is no longer reported as connected.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::constants::timing::MCP_CLIENT_IDLE_TIMEOUT;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpClientInfo {
+    /// `clientInfo.name` from `initialize`, or the user agent when it was not sent
+    pub name: String,
+    pub version: Option<String>,
+    #[serde(with = "crate::models::timestamp")]
+    pub connected_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub last_seen: DateTime<Utc>,
+    /// Requests attributed to this client, including `initialize`
+    pub requests: u64,
+}
+
+#[derive(Debug)]
+struct TrackedClient {
+    info: McpClientInfo,
+    user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClientTracker {
+    clients: Arc<Mutex<Vec<TrackedClient>>>,
+}
+
+impl ClientTracker {
+    /// Records a JSON-RPC request and returns the name of the client it came from, if known.
+    /// `params` is only read for `initialize`.
+    pub fn record(
+        &self,
+        method: &str,
+        params: Option<&serde_json::Value>,
+        user_agent: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Option<String> {
+        let mut clients = self.clients.lock();
+        prune(&mut clients, now);
+
+        if method == "initialize" {
+            let client_info = params.and_then(|p| p.get("clientInfo"));
+            let name = client_info
+                .and_then(|c| c.get("name"))
+                .and_then(|n| n.as_str())
+                .or(user_agent)
+                .unwrap_or("unknown")
+                .to_string();
+            let version = client_info
+                .and_then(|c| c.get("version"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+
+            let existing = clients
+                .iter_mut()
+                .find(|c| c.info.name == name && c.user_agent.as_deref() == user_agent);
+            match existing {
+                Some(client) => {
+                    client.info.version = version;
+                    client.info.last_seen = now;
+                    client.info.requests += 1;
+                }
+                None => clients.push(TrackedClient {
+                    info: McpClientInfo {
+                        name: name.clone(),
+                        version,
+                        connected_at: now,
+                        last_seen: now,
+                        requests: 1,
+                    },
+                    user_agent: user_agent.map(str::to_string),
+                }),
+            }
+            return Some(name);
+        }
+
+        let user_agent = user_agent?;
+        let client = clients
+            .iter_mut()
+            .filter(|c| c.user_agent.as_deref() == Some(user_agent))
+            .max_by_key(|c| c.info.last_seen)?;
+        client.info.last_seen = now;
+        client.info.requests += 1;
+        Some(client.info.name.clone())
+    }
+
+    /// Clients heard from recently, most recent first.
+    pub fn connected(&self, now: DateTime<Utc>) -> Vec<McpClientInfo> {
+        let mut clients = self.clients.lock();
+        prune(&mut clients, now);
+        let mut connected: Vec<McpClientInfo> = clients.iter().map(|c| c.info.clone()).collect();
+        connected.sort_by_key(|c| std::cmp::Reverse(c.last_seen));
+        connected
+    }
+
+    pub fn clear(&self) {
+        self.clients.lock().clear();
+    }
+}
+
+fn prune(clients: &mut Vec<TrackedClient>, now: DateTime<Utc>) {
+    let timeout = chrono::Duration::from_std(MCP_CLIENT_IDLE_TIMEOUT).unwrap_or_default();
+    clients.retain(|c| now - c.info.last_seen < timeout);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_requests_are_attributed_by_user_agent() {
+        let tracker = ClientTracker::default();
+        let now = Utc::now();
+        let init = json!({ "clientInfo": { "name": "claude-code", "version": "1.2.0" } });
+
+        tracker.record("initialize", Some(&init), Some("node"), now);
+        assert_eq!(
+            tracker.record("tools/list", None, Some("node"), now),
+            Some("claude-code".to_string())
+        );
+        assert_eq!(
+            tracker.record("tools/call", None, Some("curl/8.0"), now),
+            None
+        );
+        tracker.record("initialize", None, Some("curl/8.0"), now);
+
+        let clients = tracker.connected(now);
+        assert_eq!(clients.len(), 2);
+        let claude = clients.iter().find(|c| c.name == "claude-code").unwrap();
+        assert_eq!(claude.version.as_deref(), Some("1.2.0"));
+        assert_eq!(claude.requests, 2);
+        let curl = clients.iter().find(|c| c.name == "curl/8.0").unwrap();
+        assert_eq!(curl.requests, 1);
+    }
+
+    #[test]
+    fn test_idle_clients_are_dropped() {
+        let tracker = ClientTracker::default();
+        let start = Utc::now();
+        tracker.record("initialize", None, Some("node"), start);
+
+        let later = start + chrono::Duration::from_std(MCP_CLIENT_IDLE_TIMEOUT).unwrap();
+        assert!(tracker.connected(later).is_empty());
+    }
+}
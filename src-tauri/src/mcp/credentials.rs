@@ -0,0 +1,135 @@
+//! The MCP API token and the secrets passed to skills, kept in the OS keychain.
+//!
+//! Secrets named in the `mcp_secrets_allowlist` setting used to be plain settings rows.
+//! [`migrate_plaintext_secrets`] moves them into the keychain on startup; a row that could
+//! not be moved is still honored until the next attempt succeeds. Rows the app itself owns,
+//! such as its preferences and bookkeeping, are never treated as secrets.
+
+use std::collections::HashMap;
+
+use crate::constants::skills::SKILL_SECRET_PREFIX;
+use crate::database::Database;
+use crate::error::Result;
+use crate::keychain;
+
+/// The API token MCP clients authenticate with. Created and stored on first use, so clients
+/// keep working across restarts. When the keychain is unavailable the token only lasts
+/// for this launch.
+pub async fn api_token() -> String {
+    match keychain::run_blocking(keychain::load_mcp_token).await {
+        Ok(Some(token)) if !token.is_empty() => return token,
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!("Failed to read the MCP token from the keychain: {}", e);
+            return uuid::Uuid::new_v4().to_string();
+        }
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    let stored = token.clone();
+    if let Err(e) = keychain::run_blocking(move || keychain::store_mcp_token(&stored)).await {
+        log::warn!("Failed to store the MCP token in the keychain: {}", e);
+    }
+    token
+}
+
+/// Allowlisted secret names, lowercased as they are stored in the keychain.
+async fn allowlisted(db: &Database) -> Vec<String> {
+    crate::settings::load_or_default(db)
+        .await
+        .mcp_secrets_allowlist
+        .iter()
+        .map(|name| name.to_lowercase())
+        .collect()
+}
+
+/// Settings rows holding allowlisted secrets, by their keys.
+async fn plaintext_secrets(db: &Database, names: &[String]) -> Result<HashMap<String, String>> {
+    let mut rows = db.get_all_settings().await?;
+    rows.retain(|key, _| {
+        names.contains(&key.to_lowercase()) && !crate::settings::is_app_owned(key)
+    });
+    Ok(rows)
+}
+
+/// Moves allowlisted secrets still stored as settings rows into the keychain and removes
+/// the rows once the keychain returns them. Returns how many were moved.
+pub async fn migrate_plaintext_secrets(db: &Database) -> Result<usize> {
+    let names = allowlisted(db).await;
+    if names.is_empty() {
+        return Ok(0);
+    }
+
+    let mut moved = 0;
+    for (key, value) in plaintext_secrets(db, &names).await? {
+        let name = key.to_lowercase();
+        let stored = keychain::run_blocking(move || {
+            keychain::store_secret(&name, &value)?;
+            Ok(keychain::load_secret(&name)?.is_some_and(|stored| stored == value))
+        })
+        .await?;
+        if !stored {
+            log::warn!(
+                "The keychain did not keep secret '{}'; leaving it in settings",
+                key
+            );
+            continue;
+        }
+        db.delete_setting(&key).await?;
+        moved += 1;
+    }
+    if moved > 0 {
+        log::info!(
+            "Moved {} MCP secret(s) from settings into the keychain",
+            moved
+        );
+    }
+    Ok(moved)
+}
+
+/// Stores a secret for skills in the keychain. It is passed to skills once its name is in
+/// the allowlist.
+pub async fn set_skill_secret(name: &str, value: &str) -> Result<()> {
+    crate::secrets::validate_secret_name(name)?;
+    let (name, value) = (name.to_lowercase(), value.to_string());
+    keychain::run_blocking(move || keychain::store_secret(&name, &value)).await
+}
+
+pub async fn delete_skill_secret(name: &str) -> Result<()> {
+    let name = name.to_lowercase();
+    keychain::run_blocking(move || keychain::delete_secret(&name)).await
+}
+
+/// `SKILL_SECRET_*` environment variables for the allowlisted secrets that are set.
+pub async fn skill_secret_envs(db: &Database) -> Vec<(String, String)> {
+    let names = allowlisted(db).await;
+    let plaintext = plaintext_secrets(db, &names).await.unwrap_or_default();
+
+    let mut envs = Vec::new();
+    for name in names {
+        let lookup = name.clone();
+        let value = match keychain::run_blocking(move || keychain::load_secret(&lookup)).await {
+            Ok(Some(value)) => Some(value),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!("Failed to read secret '{}' from the keychain: {}", name, e);
+                None
+            }
+        }
+        .or_else(|| {
+            plaintext
+                .iter()
+                .find(|(key, _)| key.to_lowercase() == name)
+                .map(|(_, value)| value.clone())
+        });
+        if let Some(value) = value {
+            let env_name = format!(
+                "{}{}",
+                SKILL_SECRET_PREFIX,
+                name.replace('-', "_").to_uppercase()
+            );
+            envs.push((env_name, value));
+        }
+    }
+    envs
+}
@@ -1,36 +1,62 @@
 use axum::{
     extract::State,
-    http::{HeaderMap, HeaderValue, Method, StatusCode},
-    response::{IntoResponse, Response},
-    routing::post,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::Emitter;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::task::JoinHandle;
 use tower_http::cors::CorsLayer;
 
+pub mod approvals;
+pub mod clients;
+pub mod credentials;
+pub mod tokens;
 pub mod watcher;
 
+use crate::app_log;
+use crate::audit;
 use crate::constants::{
-    limits::{LOG_LIMIT, MAX_OUTPUT_SIZE, MCP_RATE_LIMIT_MAX_CALLS, MCP_SERVER_RETRY_COUNT},
+    limits::{MAX_OUTPUT_SIZE, MCP_RATE_LIMIT_MAX_CALLS, MCP_SERVER_RETRY_COUNT},
     timing::{
-        CMD_EXEC_TIMEOUT, MCP_RATE_LIMIT_WINDOW, MCP_SERVER_BACKOFF_INITIAL_MS, SKILL_EXEC_TIMEOUT,
+        CMD_EXEC_TIMEOUT, MCP_APPROVAL_TIMEOUT, MCP_RATE_LIMIT_WINDOW,
+        MCP_SERVER_BACKOFF_INITIAL_MS, SKILL_EXEC_TIMEOUT,
     },
 };
+use crate::context_composer::{self, ContextRequest};
 use crate::database::{Database, ExecutionLogInput};
 use crate::error::{AppError, Result};
 use crate::execution::{
-    argument_env_var_name, contains_disallowed_pattern, execute_and_log,
-    execute_shell_with_timeout_env_dir, replace_template_with_env_ref, sanitize_argument_value,
-    slugify, ExecuteAndLogInput,
+    contains_disallowed_pattern, execute_and_log, execute_shell_streaming, preview_command,
+    redacting_sink, render_command_invocation, resolve_working_dir, slugify, validate_stdin,
+    ExecuteAndLogInput, OutputSink, ProcessOptions,
+};
+use crate::metrics;
+use crate::models::{
+    AppLogLevel, AppLogQuery, ApprovalAuditEntry, ApprovalDecision, ArgumentType, AuditActor,
+    AuditActorKind, Command, CommandArgument, PendingApproval, RuleFeedbackInput,
+    RuleFeedbackVerdict, Skill, SkillParameterType, Workflow, WorkflowStepStatus,
 };
-use crate::models::{Command, Skill, SkillParameterType};
+use crate::rule_feedback;
+use crate::rule_search::{self, SearchMode};
+use crate::util::constant_time_eq;
+use crate::workflow::{run_workflow, WorkflowRunOptions};
+use approvals::ApprovalQueue;
+use clients::{ClientTracker, McpClientInfo};
+use tokens::{McpCaller, McpClientToken};
 
 fn mcp_error_response(id: serde_json::Value, code: i64, message: &str) -> serde_json::Value {
     json!({
@@ -58,10 +84,37 @@ fn truncate_output_custom(s: String, max_size: usize) -> String {
     }
 }
 
+/// Strings are passed through as-is; numbers and booleans use their JSON text.
+fn json_argument_value(value: &serde_json::Value) -> String {
+    value
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| value.to_string())
+}
+
 fn truncate_output(s: String) -> String {
     truncate_output_custom(s, MAX_OUTPUT_SIZE)
 }
 
+/// The command's parsed output for a `tools/call` result. MCP requires an object, so
+/// other JSON values are wrapped as `{"value": ...}`.
+fn structured_content(
+    cmd: &Command,
+    stdout: &str,
+    working_dir: Option<&std::path::Path>,
+) -> Option<serde_json::Value> {
+    let parser = cmd.output_parser.as_ref()?;
+    match crate::output_parser::parse(parser, stdout, working_dir) {
+        Ok(Some(value)) if value.is_object() => Some(value),
+        Ok(Some(value)) => Some(json!({ "value": value })),
+        Ok(None) => None,
+        Err(e) => {
+            log::warn!("Failed to parse output of '{}': {}", cmd.name, e);
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpStatus {
@@ -86,12 +139,14 @@ pub struct McpRuntime {
     running: bool,
     port: u16,
     api_token: String,
+    /// Named tokens handed out to other clients
+    client_tokens: Vec<McpClientToken>,
     started_at: Option<Instant>,
-    logs: Vec<String>,
     stop_tx: Option<broadcast::Sender<()>>,
     task_handle: Option<JoinHandle<()>>,
     commands: Vec<Command>,
     skills: Vec<Skill>,
+    workflows: Vec<Workflow>,
     invocation_timestamps: VecDeque<Instant>,
     db: Option<Arc<Database>>,
     watcher: watcher::WatcherManager,
@@ -101,11 +156,14 @@ pub struct McpRuntime {
 #[derive(Clone, Debug)]
 pub struct McpManager {
     pub inner: Arc<Mutex<McpRuntime>>,
+    approvals: ApprovalQueue,
+    clients: ClientTracker,
 }
 
 pub struct McpSnapshot {
     pub commands: Vec<Command>,
     pub skills: Vec<Skill>,
+    pub workflows: Vec<Workflow>,
     pub db: Option<Arc<Database>>,
 }
 
@@ -126,17 +184,20 @@ impl McpManager {
                 running: false,
                 port,
                 api_token,
+                client_tokens: Vec::new(),
                 started_at: None,
-                logs: Vec::new(),
                 stop_tx: None,
                 task_handle: None,
                 commands: Vec::new(),
                 skills: Vec::new(),
+                workflows: Vec::new(),
                 invocation_timestamps: VecDeque::new(),
                 db: None,
                 watcher: watcher::WatcherManager::new(),
                 app_handle: None,
             })),
+            approvals: ApprovalQueue::default(),
+            clients: ClientTracker::default(),
         }
     }
 
@@ -145,13 +206,23 @@ impl McpManager {
         state.api_token = token;
     }
 
+    /// Reloads the named client tokens after one was created or revoked.
+    pub async fn reload_client_tokens(&self, db: &Database) -> Result<()> {
+        let client_tokens = tokens::list(db).await?;
+        self.inner.lock().await.client_tokens = client_tokens;
+        Ok(())
+    }
+
     pub async fn refresh_commands(&self, db: &Database) -> Result<()> {
-        let (commands, skills) = db.get_mcp_data().await?;
+        let (commands, skills, workflows) = db.get_mcp_data().await?;
+        let client_tokens = tokens::list(db).await?;
 
         let app_handle = {
             let mut state = self.inner.lock().await;
+            state.client_tokens = client_tokens;
             state.commands = commands;
             state.skills = skills;
+            state.workflows = workflows;
 
             if state.running {
                 let mut paths = std::collections::HashSet::new();
@@ -194,6 +265,7 @@ impl McpManager {
         Ok(McpSnapshot {
             commands: state.commands.clone(),
             skills: state.skills.clone(),
+            workflows: state.workflows.clone(),
             db: state.db.clone(),
         })
     }
@@ -212,7 +284,11 @@ impl McpManager {
 
             state.running = true;
             state.started_at = Some(Instant::now());
-            state.logs.push("Starting MCP server".to_string());
+            app_log::record(
+                AppLogLevel::Info,
+                app_log::MCP_MODULE,
+                "Starting MCP server".to_string(),
+            );
             state.db = Some(Arc::clone(db));
             state.port
         };
@@ -230,6 +306,7 @@ impl McpManager {
         let handle = tokio::spawn(async move {
             let app = Router::new()
                 .route("/", post(mcp_handler))
+                .route("/metrics", get(metrics_handler))
                 // Support root and any other path for flexibility
                 .fallback(post(mcp_handler))
                 .layer(
@@ -355,11 +432,19 @@ impl McpManager {
         })
     }
 
+    /// Clients that have made requests recently, most recent first.
+    pub fn connected_clients(&self) -> Vec<McpClientInfo> {
+        self.clients.connected(chrono::Utc::now())
+    }
+
+    /// Latest MCP lines from the app log store, oldest first.
     pub async fn logs(&self, limit: usize) -> Result<Vec<String>> {
-        let state = self.inner.lock().await;
-        let len = state.logs.len();
-        let start = len.saturating_sub(limit);
-        Ok(state.logs[start..].to_vec())
+        let page = app_log::query(&AppLogQuery {
+            module: Some(app_log::MCP_MODULE.to_string()),
+            limit: Some(limit as u32),
+            ..Default::default()
+        });
+        Ok(page.entries.into_iter().rev().map(|e| e.message).collect())
     }
 
     pub async fn instructions(&self) -> Result<McpConnectionInstructions> {
@@ -402,13 +487,92 @@ impl McpManager {
         })
     }
 
-    async fn log(&self, message: String) -> Result<()> {
-        let mut state = self.inner.lock().await;
-        state.logs.push(message);
-        if state.logs.len() > LOG_LIMIT {
-            let drain_to = state.logs.len() - LOG_LIMIT;
-            state.logs.drain(0..drain_to);
+    pub fn pending_approvals(&self) -> Vec<PendingApproval> {
+        self.approvals.pending()
+    }
+
+    /// Answers a parked call. Fails if it already timed out or was answered.
+    pub fn respond_to_approval(&self, id: &str, approved: bool) -> Result<()> {
+        if self.approvals.respond(id, approved) {
+            Ok(())
+        } else {
+            Err(AppError::InvalidInput {
+                message: format!("No pending approval with id {}", id),
+            })
         }
+    }
+
+    /// Parks a call to `cmd` until the user answers in the app, then records the outcome.
+    async fn await_approval(
+        &self,
+        cmd: &Command,
+        arguments_json: &str,
+        db: Option<&Database>,
+    ) -> ApprovalDecision {
+        let app_handle = self.inner.lock().await.app_handle.clone();
+        let (approval, rx) = self.approvals.request(cmd, arguments_json);
+
+        let decision = match app_handle {
+            Some(app) => {
+                use tauri_plugin_notification::NotificationExt;
+
+                let _ = app.emit("approval-requested", &approval);
+                app.notification()
+                    .builder()
+                    .title("Approval Required")
+                    .body(format!(
+                        "An MCP client wants to run '{}'. Open RuleWeaver to approve or deny it.",
+                        cmd.name
+                    ))
+                    .show()
+                    .ok();
+
+                let decision = approvals::wait_for_decision(
+                    &self.approvals,
+                    &approval,
+                    rx,
+                    MCP_APPROVAL_TIMEOUT,
+                )
+                .await;
+                let _ = app.emit(
+                    "approval-resolved",
+                    json!({ "id": approval.id, "decision": decision }),
+                );
+                decision
+            }
+            // Nobody can answer without the desktop app, so the call is refused.
+            None => {
+                self.approvals.expire(&approval.id);
+                ApprovalDecision::Denied
+            }
+        };
+
+        let _ = self
+            .log(format!(
+                "MCP tools/call '{}' approval: {}",
+                cmd.name,
+                decision.as_str()
+            ))
+            .await;
+        if let Some(db) = db {
+            let entry = ApprovalAuditEntry {
+                id: approval.id,
+                command_id: approval.command_id,
+                command_name: approval.command_name,
+                arguments_json: approval.arguments_json,
+                decision,
+                requested_at: approval.requested_at,
+                decided_at: chrono::Utc::now(),
+            };
+            if let Err(e) = db.add_approval_audit(&entry).await {
+                log::warn!("Failed to record approval audit entry: {}", e);
+            }
+        }
+        decision
+    }
+
+    async fn log(&self, message: String) -> Result<()> {
+        app_log::record(AppLogLevel::Info, app_log::MCP_MODULE, message);
         Ok(())
     }
 
@@ -418,6 +582,7 @@ impl McpManager {
         state.stop_tx = None;
         state.started_at = None;
         state.watcher.stop();
+        self.clients.clear();
         Ok(())
     }
 
@@ -454,25 +619,39 @@ async fn mcp_handler(
     headers: HeaderMap,
     Json(request): Json<JsonRpcRequest>,
 ) -> Response {
-    let auth_valid = {
+    let caller = {
         let state = manager.inner.lock().await;
-
-        let provided_key = headers.get("X-API-Key").and_then(|v| v.to_str().ok());
-
-        provided_key == Some(&state.api_token)
+        headers
+            .get("X-API-Key")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|key| tokens::authenticate(key, &state.api_token, &state.client_tokens))
     };
 
-    if !auth_valid {
+    let Some(caller) = caller else {
         return (
             StatusCode::UNAUTHORIZED,
             "Unauthorized: Invalid or missing X-API-Key header",
         )
             .into_response();
-    }
+    };
+
+    let client = manager.clients.record(
+        &request.method,
+        request.params.as_ref(),
+        headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok()),
+        chrono::Utc::now(),
+    );
+    let actor = match client {
+        Some(name) => AuditActor::with_detail(AuditActorKind::Mcp, name),
+        None => AuditActor::new(AuditActorKind::Mcp),
+    };
 
     let McpSnapshot {
         commands,
         skills,
+        workflows,
         db: shared_db,
     } = match manager.snapshot().await {
         Ok(s) => s,
@@ -489,17 +668,38 @@ async fn mcp_handler(
         }
     };
 
+    if request.method == "tools/call" && accepts_event_stream(&headers) {
+        if let Some(token) = progress_token(&request.params) {
+            let tools = ToolSet {
+                commands,
+                skills,
+                workflows,
+                caller,
+            };
+            return stream_tools_call(manager, request, token, tools, shared_db, actor);
+        }
+    }
+
+    let tools = ToolSet {
+        commands,
+        skills,
+        workflows,
+        caller,
+    };
     let response = match request.method.as_str() {
         "initialize" => handle_initialize(request.id),
-        "tools/list" => handle_tools_list(request.id, &commands, &skills),
+        "tools/list" => handle_tools_list(request.id, &tools),
         "tools/call" => {
-            handle_tools_call(
-                &manager,
-                request.id,
-                request.params,
-                &commands,
-                &skills,
-                &shared_db,
+            audit::scope(
+                actor,
+                handle_tools_call(
+                    &manager,
+                    request.id,
+                    request.params,
+                    &tools,
+                    &shared_db,
+                    None,
+                ),
             )
             .await
         }
@@ -516,6 +716,117 @@ async fn mcp_handler(
     Json(response).into_response()
 }
 
+/// Prometheus scrape endpoint, off unless enabled in settings. Takes the MCP token as
+/// `X-API-Key` or as a bearer token.
+async fn metrics_handler(State(manager): State<McpManager>, headers: HeaderMap) -> Response {
+    let (authorized, db) = {
+        let state = manager.inner.lock().await;
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        let api_key = headers.get("X-API-Key").and_then(|v| v.to_str().ok());
+        let token = state.api_token.as_bytes();
+        let matches = |candidate: Option<&str>| {
+            candidate.is_some_and(|c| constant_time_eq(c.as_bytes(), token))
+        };
+        (matches(bearer) || matches(api_key), state.db.clone())
+    };
+
+    let enabled = match db {
+        Some(db) => {
+            crate::settings::load_or_default(&db)
+                .await
+                .metrics_prometheus_enabled
+        }
+        None => false,
+    };
+    if !enabled {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render_prometheus(&metrics::snapshot()),
+    )
+        .into_response()
+}
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"))
+}
+
+fn progress_token(params: &Option<serde_json::Value>) -> Option<serde_json::Value> {
+    params
+        .as_ref()?
+        .get("_meta")?
+        .get("progressToken")
+        .filter(|token| token.is_string() || token.is_number())
+        .cloned()
+}
+
+/// Runs a tool call while streaming its output as `notifications/progress` messages over
+/// server-sent events, ending with the JSON-RPC response. Used when the client asks for
+/// progress and accepts an event stream.
+fn stream_tools_call(
+    manager: McpManager,
+    request: JsonRpcRequest,
+    token: serde_json::Value,
+    tools: ToolSet,
+    shared_db: Option<Arc<Database>>,
+    actor: AuditActor,
+) -> Response {
+    let (tx, rx) = mpsc::unbounded_channel::<serde_json::Value>();
+    let progress = Arc::new(AtomicU64::new(0));
+    let on_output: OutputSink = {
+        let tx = tx.clone();
+        Arc::new(move |_stream, line| {
+            let count = progress.fetch_add(1, Ordering::Relaxed) + 1;
+            let _ = tx.send(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": {
+                    "progressToken": token,
+                    "progress": count,
+                    "message": line,
+                }
+            }));
+        })
+    };
+
+    tokio::spawn(async move {
+        let response = audit::scope(
+            actor,
+            handle_tools_call(
+                &manager,
+                request.id,
+                request.params,
+                &tools,
+                &shared_db,
+                Some(on_output),
+            ),
+        )
+        .await;
+        let _ = tx.send(response);
+    });
+
+    // The stream ends once the call finishes and every sender has been dropped.
+    let events = futures_util::stream::unfold(rx, |mut rx| async move {
+        let message = rx.recv().await?;
+        Some((
+            Ok::<_, Infallible>(Event::default().data(message.to_string())),
+            rx,
+        ))
+    });
+    Sse::new(events).into_response()
+}
+
 fn handle_initialize(id: serde_json::Value) -> serde_json::Value {
     json!({
         "jsonrpc": "2.0",
@@ -535,52 +846,246 @@ struct McpToolParameter {
     required: bool,
     enum_values: Option<Vec<String>>,
     param_type: SkillParameterType,
+    pattern: Option<String>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
 }
 
-fn handle_tools_list(
-    id: serde_json::Value,
-    commands: &[Command],
-    skills: &[Skill],
-) -> serde_json::Value {
-    let mut tools: Vec<serde_json::Value> = commands
-        .iter()
-        .filter(|c| c.expose_via_mcp)
+impl McpToolParameter {
+    /// The input payload of a command that accepts stdin.
+    fn stdin() -> Self {
+        Self {
+            name: STDIN_PARAMETER.to_string(),
+            description: "Input piped to the command's stdin".to_string(),
+            required: false,
+            enum_values: None,
+            param_type: SkillParameterType::String,
+            pattern: None,
+            minimum: None,
+            maximum: None,
+        }
+    }
+
+    fn from_command_argument(arg: &CommandArgument) -> Self {
+        let enum_values = arg.options.clone().filter(|opts| !opts.is_empty());
+        let param_type = match arg.arg_type {
+            ArgumentType::Number => SkillParameterType::Number,
+            ArgumentType::Boolean => SkillParameterType::Boolean,
+            _ if enum_values.is_some() => SkillParameterType::Enum,
+            _ => SkillParameterType::String,
+        };
+        Self {
+            name: arg.name.clone(),
+            description: arg.description.clone(),
+            required: arg.required,
+            enum_values,
+            param_type,
+            // Argument patterns must match the whole value; JSON Schema patterns are unanchored.
+            pattern: arg.pattern.as_ref().map(|p| format!("^(?:{})$", p)),
+            minimum: arg.min,
+            maximum: arg.max,
+        }
+    }
+}
+
+/// Everything an MCP client can call, and the client asking.
+struct ToolSet {
+    commands: Vec<Command>,
+    skills: Vec<Skill>,
+    workflows: Vec<Workflow>,
+    caller: McpCaller,
+}
+
+impl ToolSet {
+    /// Commands whose policy admits the caller.
+    fn callable_commands(&self) -> impl Iterator<Item = &Command> {
+        self.commands.iter().filter(|c| self.caller.admits(c))
+    }
+
+    /// Skills run arbitrary scripts, so read-only callers do not get them.
+    fn callable_skills(&self) -> impl Iterator<Item = &Skill> {
+        self.skills
+            .iter()
+            .filter(|s| s.enabled && !self.caller.read_only)
+    }
+
+    /// Exposed workflows whose steps only run commands the caller passes the policy of.
+    /// Steps may run commands that are not exposed on their own.
+    fn callable_workflows(&self) -> impl Iterator<Item = &Workflow> {
+        self.workflows.iter().filter(|w| {
+            w.expose_via_mcp
+                && !self.caller.read_only
+                && w.steps.iter().all(|step| {
+                    self.commands
+                        .iter()
+                        .find(|c| c.id == step.command_id)
+                        .is_none_or(|c| self.caller.passes(&c.mcp_policy))
+                })
+        })
+    }
+}
+
+/// Tool parameter carrying the stdin payload of commands that accept one.
+pub const STDIN_PARAMETER: &str = "stdin";
+/// Comma-separated names of the secrets passed to skills as `SKILL_SECRET_*` variables.
+pub const SECRETS_ALLOWLIST_KEY: &str = "mcp_secrets_allowlist";
+
+fn command_tool_name(cmd: &Command) -> String {
+    format!("{}-{}", slugify(&cmd.name), &cmd.id[..8])
+}
+
+/// Built-in tool that shows what a command tool would run, without running it.
+const EXPLAIN_TOOL_NAME: &str = "explain_command";
+
+fn explain_tool_schema(commands: &[&Command]) -> serde_json::Value {
+    let tool_names: Vec<String> = commands.iter().map(|c| command_tool_name(c)).collect();
+    json!({
+        "name": EXPLAIN_TOOL_NAME,
+        "description": "Show the exact command line, environment and working directory a command tool would use for the given arguments, without executing it.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "tool": {
+                    "type": "string",
+                    "description": "Name of the command tool to explain",
+                    "enum": tool_names,
+                },
+                "arguments": {
+                    "type": "object",
+                    "description": "Arguments as they would be passed to the tool",
+                },
+            },
+            "required": ["tool"],
+        }
+    })
+}
+
+/// Built-in tool for agents to report how a rule did in their session.
+const FEEDBACK_TOOL_NAME: &str = "report_rule_feedback";
+
+fn feedback_tool_schema() -> serde_json::Value {
+    json!({
+        "name": FEEDBACK_TOOL_NAME,
+        "description": "Report whether a RuleWeaver rule was helpful, ignored or harmful in this session, so the user can see which rules make a difference.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "rule": {
+                    "type": "string",
+                    "description": "Id or name of the rule",
+                },
+                "verdict": {
+                    "type": "string",
+                    "enum": ["helpful", "ignored", "harmful"],
+                },
+                "sessionId": {
+                    "type": "string",
+                    "description": "Identifies the session, to group its reports",
+                },
+                "note": {
+                    "type": "string",
+                    "description": "What the rule did or failed to do",
+                },
+            },
+            "required": ["rule", "verdict"],
+        }
+    })
+}
+
+/// Built-in tool for agents to fetch only the rules relevant to their task.
+const SEARCH_TOOL_NAME: &str = "search_rules";
+
+fn search_tool_schema() -> serde_json::Value {
+    json!({
+        "name": SEARCH_TOOL_NAME,
+        "description": "Find the RuleWeaver rules relevant to a task and return their content, instead of reading whole rule files.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What you are working on or want guidance about",
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["keyword", "semantic"],
+                    "description": "keyword matches words; semantic matches meaning and needs an embedding model configured in RuleWeaver",
+                },
+                "limit": {
+                    "type": "number",
+                    "minimum": 1,
+                    "maximum": rule_search::MAX_LIMIT,
+                    "description": format!("Most rules to return, {} by default", rule_search::DEFAULT_LIMIT),
+                },
+            },
+            "required": ["query"],
+        }
+    })
+}
+
+/// Built-in tool for agents to load the rules for a task within a token budget.
+const COMPOSE_TOOL_NAME: &str = "compose_context";
+
+fn compose_tool_schema() -> serde_json::Value {
+    json!({
+        "name": COMPOSE_TOOL_NAME,
+        "description": "Assemble the RuleWeaver rules that apply to a task into one block that fits a token budget: rules that always apply, then rules for the files or tags given, then rules matching the task.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "task_description": {
+                    "type": "string",
+                    "description": "What you are working on",
+                },
+                "max_tokens": {
+                    "type": "number",
+                    "minimum": 1,
+                    "description": "Most tokens the block may take, estimated at four characters per token",
+                },
+                "files": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Paths relative to the repository root that the task touches",
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Rule tags to include",
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["keyword", "semantic"],
+                    "description": "How to match the task description; semantic needs an embedding model configured in RuleWeaver",
+                },
+            },
+            "required": ["task_description", "max_tokens"],
+        }
+    })
+}
+
+fn workflow_tool_name(workflow: &Workflow) -> String {
+    format!("workflow_{}-{}", slugify(&workflow.name), &workflow.id[..8])
+}
+
+fn handle_tools_list(id: serde_json::Value, tool_set: &ToolSet) -> serde_json::Value {
+    let mut tools: Vec<serde_json::Value> = tool_set
+        .callable_commands()
         .map(|c| {
-            let params: Vec<_> = c
+            let mut params: Vec<_> = c
                 .arguments
                 .iter()
-                .map(|a| {
-                    let p_type = if let Some(ref opts) = a.options {
-                        if !opts.is_empty() {
-                            SkillParameterType::Enum
-                        } else {
-                            SkillParameterType::String
-                        }
-                    } else {
-                        SkillParameterType::String
-                    };
-
-                    McpToolParameter {
-                        name: a.name.clone(),
-                        description: a.description.clone(),
-                        required: a.required,
-                        enum_values: a.options.clone(),
-                        param_type: p_type,
-                    }
-                })
+                .map(McpToolParameter::from_command_argument)
                 .collect();
+            if c.accepts_stdin {
+                params.push(McpToolParameter::stdin());
+            }
 
-            build_mcp_tool_schema(
-                &format!("{}-{}", slugify(&c.name), &c.id[..8]),
-                &c.description,
-                &params,
-            )
+            build_mcp_tool_schema(&command_tool_name(c), &c.description, &params)
         })
         .collect();
 
-    let skill_tools: Vec<serde_json::Value> = skills
-        .iter()
-        .filter(|s| s.enabled)
+    let skill_tools: Vec<serde_json::Value> = tool_set
+        .callable_skills()
         .map(|s| {
             let params: Vec<_> = s
                 .input_schema
@@ -591,6 +1096,9 @@ fn handle_tools_list(
                     required: p.required,
                     enum_values: p.enum_values.clone(),
                     param_type: p.param_type.clone(),
+                    pattern: None,
+                    minimum: None,
+                    maximum: None,
                 })
                 .collect();
 
@@ -602,7 +1110,31 @@ fn handle_tools_list(
         })
         .collect();
 
+    let workflow_tools: Vec<serde_json::Value> = tool_set
+        .callable_workflows()
+        .map(|w| {
+            let params: Vec<_> = w
+                .arguments
+                .iter()
+                .map(McpToolParameter::from_command_argument)
+                .collect();
+
+            build_mcp_tool_schema(&workflow_tool_name(w), &w.description, &params)
+        })
+        .collect();
+
+    let exposed_commands: Vec<&Command> = tool_set.callable_commands().collect();
+    if !exposed_commands.is_empty() {
+        tools.push(explain_tool_schema(&exposed_commands));
+    }
     tools.extend(skill_tools);
+    tools.extend(workflow_tools);
+    tools.push(search_tool_schema());
+    tools.push(compose_tool_schema());
+    // Feedback is written to the database, so read-only callers cannot report it.
+    if !tool_set.caller.read_only {
+        tools.push(feedback_tool_schema());
+    }
 
     json!({
         "jsonrpc": "2.0",
@@ -633,11 +1165,18 @@ fn build_mcp_tool_schema(
             "description": param.description,
         });
 
+        let schema = prop_schema.as_object_mut().unwrap();
         if let Some(ref enum_vals) = param.enum_values {
-            prop_schema
-                .as_object_mut()
-                .unwrap()
-                .insert("enum".to_string(), json!(enum_vals));
+            schema.insert("enum".to_string(), json!(enum_vals));
+        }
+        if let Some(ref pattern) = param.pattern {
+            schema.insert("pattern".to_string(), json!(pattern));
+        }
+        if let Some(minimum) = param.minimum {
+            schema.insert("minimum".to_string(), json!(minimum));
+        }
+        if let Some(maximum) = param.maximum {
+            schema.insert("maximum".to_string(), json!(maximum));
         }
 
         props.insert(param.name.clone(), prop_schema);
@@ -661,9 +1200,31 @@ async fn handle_tools_call(
     manager: &McpManager,
     id: serde_json::Value,
     params: Option<serde_json::Value>,
-    commands: &[Command],
-    skills: &[Skill],
+    tools: &ToolSet,
     shared_db: &Option<Arc<Database>>,
+    on_output: Option<OutputSink>,
+) -> serde_json::Value {
+    let started = Instant::now();
+    let response = dispatch_tools_call(manager, id, params, tools, shared_db, on_output).await;
+
+    let failed = response.get("error").is_some()
+        || response
+            .pointer("/result/isError")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+    let labels = [("status", if failed { "error" } else { "ok" })];
+    metrics::increment(metrics::MCP_CALLS, &labels);
+    metrics::observe_since(metrics::MCP_CALL_DURATION, &labels, started);
+    response
+}
+
+async fn dispatch_tools_call(
+    manager: &McpManager,
+    id: serde_json::Value,
+    params: Option<serde_json::Value>,
+    tools: &ToolSet,
+    shared_db: &Option<Arc<Database>>,
+    on_output: Option<OutputSink>,
 ) -> serde_json::Value {
     let allow = match manager.allow_invocation().await {
         Ok(a) => a,
@@ -703,16 +1264,40 @@ async fn handle_tools_call(
         .cloned()
         .unwrap_or_default();
 
-    if let Some(cmd) = commands
-        .iter()
-        .find(|c| format!("{}-{}", slugify(&c.name), &c.id[..8]) == name && c.expose_via_mcp)
+    if let Some(cmd) = tools
+        .callable_commands()
+        .find(|c| command_tool_name(c) == name)
     {
-        handle_command_call(manager, id, cmd, args_map, shared_db).await
-    } else if let Some(skill) = skills
-        .iter()
-        .find(|s| s.enabled && format!("skill_{}-{}", slugify(&s.name), &s.id[..8]) == name)
+        handle_command_call(manager, id, cmd, args_map, shared_db, on_output).await
+    } else if name == EXPLAIN_TOOL_NAME {
+        handle_explain_call(id, tools, &params)
+    } else if name == FEEDBACK_TOOL_NAME && tools.caller.read_only {
+        mcp_error_response(id, -32601, "Read-only tokens cannot report rule feedback")
+    } else if name == FEEDBACK_TOOL_NAME {
+        handle_feedback_call(id, args_map, shared_db).await
+    } else if name == SEARCH_TOOL_NAME {
+        handle_search_call(id, args_map, shared_db).await
+    } else if name == COMPOSE_TOOL_NAME {
+        handle_compose_call(id, args_map, shared_db).await
+    } else if let Some(skill) = tools
+        .callable_skills()
+        .find(|s| format!("skill_{}-{}", slugify(&s.name), &s.id[..8]) == name)
+    {
+        handle_skill_call(manager, id, skill, args_map, shared_db, on_output).await
+    } else if let Some(workflow) = tools
+        .callable_workflows()
+        .find(|w| workflow_tool_name(w) == name)
     {
-        handle_skill_call(manager, id, skill, args_map, shared_db).await
+        handle_workflow_call(
+            manager,
+            id,
+            workflow,
+            &tools.commands,
+            args_map,
+            shared_db,
+            on_output,
+        )
+        .await
     } else {
         json!({
             "jsonrpc": "2.0",
@@ -725,13 +1310,230 @@ async fn handle_tools_call(
     }
 }
 
+fn handle_explain_call(
+    id: serde_json::Value,
+    tools: &ToolSet,
+    params: &serde_json::Value,
+) -> serde_json::Value {
+    let arguments = params.get("arguments");
+    let tool = arguments
+        .and_then(|a| a.get("tool"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let Some(cmd) = tools
+        .callable_commands()
+        .find(|c| command_tool_name(c) == tool)
+    else {
+        return mcp_error_response(id, -32602, &format!("Unknown or disabled tool: {}", tool));
+    };
+
+    let values: HashMap<String, String> = arguments
+        .and_then(|a| a.get("arguments"))
+        .and_then(|v| v.as_object())
+        .map(|args| {
+            args.iter()
+                .map(|(k, v)| (k.clone(), json_argument_value(v)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match preview_command(cmd, &values, CMD_EXEC_TIMEOUT) {
+        Ok(preview) => {
+            let text = serde_json::to_string_pretty(&preview).unwrap_or_default();
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "content": [{ "type": "text", "text": text }],
+                    "isError": false
+                }
+            })
+        }
+        Err(e) => mcp_error_response(id, -32602, &e.to_string()),
+    }
+}
+
+async fn handle_feedback_call(
+    id: serde_json::Value,
+    args_map: serde_json::Map<String, serde_json::Value>,
+    shared_db: &Option<Arc<Database>>,
+) -> serde_json::Value {
+    let Some(db) = shared_db else {
+        return mcp_error_response(id, -32603, "Database is not available");
+    };
+    let text = |key: &str| {
+        args_map
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let Some(verdict) = text("verdict").and_then(|v| RuleFeedbackVerdict::from_str(&v).ok()) else {
+        return mcp_error_response(
+            id,
+            -32602,
+            "verdict must be one of helpful, ignored or harmful",
+        );
+    };
+    let input = RuleFeedbackInput {
+        rule_id: text("rule").unwrap_or_default(),
+        verdict,
+        session_id: text("sessionId"),
+        note: text("note"),
+    };
+
+    let recorded = match crate::commands::fetch_rules(db, None).await {
+        Ok(rules) => rule_feedback::report(db, &rules, input).await,
+        Err(e) => Err(e),
+    };
+    match recorded {
+        Ok(feedback) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "content": [{
+                    "type": "text",
+                    "text": format!("Recorded '{}' feedback on rule {}", feedback.verdict.as_str(), feedback.rule_id),
+                }],
+                "isError": false
+            }
+        }),
+        Err(e) => mcp_error_response(id, -32602, &e.to_string()),
+    }
+}
+
+async fn handle_search_call(
+    id: serde_json::Value,
+    args_map: serde_json::Map<String, serde_json::Value>,
+    shared_db: &Option<Arc<Database>>,
+) -> serde_json::Value {
+    let Some(db) = shared_db else {
+        return mcp_error_response(id, -32603, "Database is not available");
+    };
+    let query = args_map
+        .get("query")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let mode = match args_map.get("mode").and_then(|v| v.as_str()) {
+        Some(mode) => match SearchMode::from_str(mode) {
+            Ok(mode) => mode,
+            Err(_) => return mcp_error_response(id, -32602, "mode must be keyword or semantic"),
+        },
+        None => SearchMode::default(),
+    };
+    let limit = args_map
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(rule_search::DEFAULT_LIMIT, |limit| {
+            limit.min(u64::from(rule_search::MAX_LIMIT)) as u32
+        });
+
+    match rule_search::search(db, query, mode, limit).await {
+        Ok(hits) => {
+            let text = if hits.is_empty() {
+                "No rules match.".to_string()
+            } else {
+                hits.iter()
+                    .map(|hit| format!("## {}\n\n{}", hit.name, hit.content.trim()))
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            };
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "content": [{ "type": "text", "text": text }],
+                    "structuredContent": { "rules": hits },
+                    "isError": false
+                }
+            })
+        }
+        Err(e) => mcp_error_response(id, -32602, &e.to_string()),
+    }
+}
+
+fn string_list(value: Option<&serde_json::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn handle_compose_call(
+    id: serde_json::Value,
+    args_map: serde_json::Map<String, serde_json::Value>,
+    shared_db: &Option<Arc<Database>>,
+) -> serde_json::Value {
+    let Some(db) = shared_db else {
+        return mcp_error_response(id, -32603, "Database is not available");
+    };
+    let Some(max_tokens) = args_map.get("max_tokens").and_then(|v| v.as_u64()) else {
+        return mcp_error_response(id, -32602, "max_tokens must be a positive integer");
+    };
+    let mode = match args_map.get("mode").and_then(|v| v.as_str()) {
+        Some(mode) => match SearchMode::from_str(mode) {
+            Ok(mode) => mode,
+            Err(_) => return mcp_error_response(id, -32602, "mode must be keyword or semantic"),
+        },
+        None => SearchMode::default(),
+    };
+    let request = ContextRequest {
+        task_description: args_map
+            .get("task_description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        max_tokens: max_tokens.min(u64::from(u32::MAX)) as u32,
+        files: string_list(args_map.get("files")),
+        tags: string_list(args_map.get("tags")),
+        mode,
+    };
+
+    match context_composer::compose_for_task(db, &request).await {
+        Ok(composed) => {
+            let text = if composed.rules.is_empty() {
+                "No rules apply within the budget.".to_string()
+            } else {
+                composed.content.clone()
+            };
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "content": [{ "type": "text", "text": text }],
+                    "structuredContent": composed,
+                    "isError": false
+                }
+            })
+        }
+        Err(e) => mcp_error_response(id, -32602, &e.to_string()),
+    }
+}
+
 async fn handle_command_call(
     manager: &McpManager,
     id: serde_json::Value,
     cmd: &Command,
-    args_map: serde_json::Map<String, serde_json::Value>,
+    mut args_map: serde_json::Map<String, serde_json::Value>,
     shared_db: &Option<Arc<Database>>,
+    on_output: Option<OutputSink>,
 ) -> serde_json::Value {
+    let stdin = if cmd.accepts_stdin {
+        args_map
+            .remove(STDIN_PARAMETER)
+            .map(|v| json_argument_value(&v))
+    } else {
+        None
+    };
+    if let Err(e) = validate_stdin(cmd, stdin.as_deref()) {
+        return mcp_error_response(id, -32602, &e.to_string());
+    }
+
     if let Some(pattern) = contains_disallowed_pattern(&cmd.script) {
         return mcp_error_response(
             id,
@@ -766,52 +1568,16 @@ async fn handle_command_call(
         );
     }
 
-    let mut rendered = cmd.script.clone();
-    let mut envs: Vec<(String, String)> = Vec::new();
-    let mut invalid_arg_message: Option<String> = None;
-
-    for arg in &cmd.arguments {
-        rendered = replace_template_with_env_ref(&rendered, &arg.name);
-
-        let raw_value = args_map
-            .get(&arg.name)
-            .map(|v| {
-                if let Some(s) = v.as_str() {
-                    s.to_string()
-                } else {
-                    v.to_string()
-                }
-            })
-            .or_else(|| arg.default_value.clone())
-            .unwrap_or_default();
-
-        match sanitize_argument_value(&raw_value) {
-            Ok(safe_value) => {
-                // Enum validation
-                if matches!(arg.arg_type, crate::models::ArgumentType::Enum) {
-                    if let Some(ref options) = arg.options {
-                        if !options.contains(&raw_value) {
-                            invalid_arg_message = Some(format!(
-                                "Argument '{}' must be one of: {}",
-                                arg.name,
-                                options.join(", ")
-                            ));
-                            break;
-                        }
-                    }
-                }
-                envs.push((argument_env_var_name(&arg.name), safe_value));
-            }
-            Err(e) => {
-                invalid_arg_message = Some(e.to_string());
-                break;
-            }
+    let values: HashMap<String, String> = args_map
+        .iter()
+        .map(|(k, v)| (k.clone(), json_argument_value(v)))
+        .collect();
+    let (rendered, envs) = match render_command_invocation(cmd, &values) {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            return mcp_error_response(id, -32602, &format!("Invalid argument value: {}", e));
         }
-    }
-
-    if let Some(message) = invalid_arg_message {
-        return mcp_error_response(id, -32602, &format!("Invalid argument value: {}", message));
-    }
+    };
 
     let args_json = match serde_json::to_string(&args_map) {
         Ok(s) => s,
@@ -827,6 +1593,42 @@ async fn handle_command_call(
         }
     };
 
+    if cmd.mcp_policy.requires_approval() {
+        let db = shared_db.as_ref().map(|arc| arc.as_ref());
+        match manager.await_approval(cmd, &args_json, db).await {
+            ApprovalDecision::Approved => {}
+            ApprovalDecision::Denied => {
+                return mcp_error_response(id, -32603, "Execution was denied by the user");
+            }
+            ApprovalDecision::TimedOut => {
+                return mcp_error_response(id, -32603, "Execution approval timed out");
+            }
+        }
+    }
+
+    let working_dir = match resolve_working_dir(
+        cmd.working_dir.as_deref(),
+        cmd.target_paths.first().map(String::as_str),
+    ) {
+        Ok(dir) => dir,
+        Err(e) => return mcp_error_response(id, -32603, &e.to_string()),
+    };
+
+    let secrets = if cmd.required_secrets.is_empty() {
+        Vec::new()
+    } else {
+        let resolved = match shared_db {
+            Some(db) => crate::secrets::resolve_secrets(db, &cmd.required_secrets).await,
+            None => Err(AppError::Internal {
+                message: "Secrets vault unavailable".to_string(),
+            }),
+        };
+        match resolved {
+            Ok(secrets) => secrets,
+            Err(e) => return mcp_error_response(id, -32603, &e.to_string()),
+        }
+    };
+
     match execute_and_log(ExecuteAndLogInput {
         db: shared_db.as_ref().map(|arc| arc.as_ref()),
         command_id: &cmd.id,
@@ -838,6 +1640,15 @@ async fn handle_command_call(
         triggered_by: "mcp",
         max_retries: cmd.max_retries,
         adapter_context: Some("mcp"),
+        on_output,
+        cancel: None,
+        secrets: &secrets,
+        working_dir: working_dir.clone(),
+        shell: cmd.shell,
+        sandbox: cmd.sandbox.as_ref(),
+        artifacts: &cmd.artifact_globs,
+        output_parser: cmd.output_parser.as_ref(),
+        stdin: stdin.as_deref(),
     })
     .await
     {
@@ -848,16 +1659,21 @@ async fn handle_command_call(
                     cmd.name, exit_code, duration_ms
                 ))
                 .await;
+            let structured = structured_content(cmd, &stdout, working_dir.as_deref());
+            let mut result = json!({
+                "content": [{
+                    "type": "text",
+                    "text": format!("exit_code: {}\n\nstdout:\n{}\n\nstderr:\n{}", exit_code, truncate_output(stdout), truncate_output(stderr))
+                }],
+                "isError": exit_code != 0
+            });
+            if let Some(structured) = structured {
+                result["structuredContent"] = structured;
+            }
             json!({
                 "jsonrpc": "2.0",
                 "id": id,
-                "result": {
-                    "content": [{
-                        "type": "text",
-                        "text": format!("exit_code: {}\n\nstdout:\n{}\n\nstderr:\n{}", exit_code, truncate_output(stdout), truncate_output(stderr))
-                    }],
-                    "isError": exit_code != 0
-                }
+                "result": result
             })
         }
         Err(e) => json!({
@@ -874,12 +1690,135 @@ async fn handle_command_call(
     }
 }
 
+async fn handle_workflow_call(
+    manager: &McpManager,
+    id: serde_json::Value,
+    workflow: &Workflow,
+    commands: &[Command],
+    args_map: serde_json::Map<String, serde_json::Value>,
+    shared_db: &Option<Arc<Database>>,
+    on_output: Option<OutputSink>,
+) -> serde_json::Value {
+    let Some(db) = shared_db else {
+        return mcp_error_response(id, -32603, "Database unavailable");
+    };
+
+    let step_commands: Vec<&Command> = workflow
+        .steps
+        .iter()
+        .filter_map(|step| commands.iter().find(|c| c.id == step.command_id))
+        .collect();
+    for cmd in &step_commands {
+        if let Some(pattern) = contains_disallowed_pattern(&cmd.script) {
+            return mcp_error_response(
+                id,
+                -32602,
+                &format!(
+                    "Command '{}' contains a disallowed pattern: {}",
+                    cmd.name, pattern
+                ),
+            );
+        }
+    }
+
+    let args: HashMap<String, String> = args_map
+        .iter()
+        .map(|(k, v)| (k.clone(), json_argument_value(v)))
+        .collect();
+    let args_json = serde_json::to_string(&args_map).unwrap_or_default();
+
+    // Approve every gated command up front so the run is not left half finished.
+    let mut approved = std::collections::HashSet::new();
+    for cmd in step_commands
+        .iter()
+        .filter(|c| c.mcp_policy.requires_approval())
+    {
+        if !approved.insert(cmd.id.as_str()) {
+            continue;
+        }
+        match manager.await_approval(cmd, &args_json, Some(db)).await {
+            ApprovalDecision::Approved => {}
+            ApprovalDecision::Denied => {
+                return mcp_error_response(id, -32603, "Execution was denied by the user");
+            }
+            ApprovalDecision::TimedOut => {
+                return mcp_error_response(id, -32603, "Execution approval timed out");
+            }
+        }
+    }
+
+    let options = WorkflowRunOptions {
+        triggered_by: "mcp",
+        adapter_context: Some("mcp"),
+        on_step: None,
+        on_output,
+        cancel: None,
+    };
+    match run_workflow(db, workflow, &args, options).await {
+        Ok(result) => {
+            let _ = manager
+                .log(format!(
+                    "MCP tools/call workflow '{}' {} ({}ms)",
+                    workflow.name,
+                    if result.success {
+                        "succeeded"
+                    } else {
+                        "failed"
+                    },
+                    result.duration_ms
+                ))
+                .await;
+
+            let mut text = String::new();
+            for step in &result.steps {
+                text.push_str(&format!(
+                    "## {} ({}): {}\n",
+                    step.step_id,
+                    step.command_name,
+                    match step.status {
+                        WorkflowStepStatus::Succeeded => "succeeded",
+                        WorkflowStepStatus::Failed => "failed",
+                        WorkflowStepStatus::Skipped => "skipped",
+                    }
+                ));
+                if let Some(code) = step.exit_code {
+                    text.push_str(&format!("exit_code: {}\n", code));
+                }
+                if let Some(message) = &step.message {
+                    text.push_str(&format!("{}\n", message));
+                }
+                if !step.stdout.is_empty() {
+                    text.push_str(&format!("stdout:\n{}\n", step.stdout));
+                }
+                if !step.stderr.is_empty() {
+                    text.push_str(&format!("stderr:\n{}\n", step.stderr));
+                }
+                text.push('\n');
+            }
+
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "content": [{
+                        "type": "text",
+                        "text": truncate_output(text)
+                    }],
+                    "isError": !result.success
+                }
+            })
+        }
+        Err(e) => mcp_error_response(id, -32602, &e.to_string()),
+    }
+}
+
 async fn handle_skill_call(
     manager: &McpManager,
     id: serde_json::Value,
     skill: &Skill,
     args_map: serde_json::Map<String, serde_json::Value>,
     shared_db: &Option<Arc<Database>>,
+    on_output: Option<OutputSink>,
 ) -> serde_json::Value {
     let final_envs = match skill.validate_payload(&args_map) {
         Ok(envs) => envs,
@@ -904,33 +1843,15 @@ async fn handle_skill_call(
     ));
 
     // Inject filtered secrets as SKILL_SECRET_*
+    let mut redactor = crate::redaction::Redactor::default();
     if let Some(db) = shared_db {
-        let allowlist = db
-            .get_setting("mcp_secrets_allowlist")
+        let secret_envs = credentials::skill_secret_envs(db).await;
+        redactor = crate::redaction::Redactor::load(db, &final_envs)
             .await
-            .ok()
-            .flatten()
-            .unwrap_or_default();
-        let allowed_keys: Vec<String> = allowlist
-            .split(',')
-            .map(|s| s.trim().to_lowercase())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        if let Ok(settings) = db.get_all_settings().await {
-            for (k, v) in settings {
-                let low_k = k.to_lowercase();
-                if allowed_keys.contains(&low_k) {
-                    let env_name = format!(
-                        "{}{}",
-                        crate::constants::skills::SKILL_SECRET_PREFIX,
-                        k.replace('-', "_").to_uppercase()
-                    );
-                    final_envs.push((env_name, v));
-                }
-            }
-        }
+            .with_values(secret_envs.iter().map(|(_, value)| value.clone()));
+        final_envs.extend(secret_envs);
     }
+    let redactor = Arc::new(redactor);
 
     let start = Instant::now();
     let mut output = String::new();
@@ -1014,11 +1935,16 @@ async fn handle_skill_call(
         });
     }
 
-    match execute_shell_with_timeout_env_dir(
+    match execute_shell_streaming(
         &entry_point,
         SKILL_EXEC_TIMEOUT,
         &final_envs,
-        Some(dir),
+        ProcessOptions {
+            dir: Some(dir),
+            ..Default::default()
+        },
+        on_output.map(|sink| redacting_sink(sink, redactor.clone())),
+        None,
     )
     .await
     {
@@ -1047,6 +1973,7 @@ async fn handle_skill_call(
     }
 
     let duration_ms = start.elapsed().as_millis() as u64;
+    let (output, was_redacted) = redactor.redact(&output);
     let _ = manager
         .log(format!(
             "MCP tools/call '{}' skill execution {} ({}ms)",
@@ -1067,13 +1994,12 @@ async fn handle_skill_call(
             }
         };
         let skill_name = format!("skill:{}", skill.name);
-        let (stdout_redacted, was_redacted) = crate::redaction::redact(&output);
         let _ = db
             .add_execution_log(&ExecutionLogInput {
                 command_id: &skill.id,
                 command_name: &skill_name,
                 arguments_json: &args_json,
-                stdout: &stdout_redacted,
+                stdout: &output,
                 stderr: "",
                 exit_code: if is_error { 1 } else { 0 },
                 duration_ms,
@@ -1082,6 +2008,8 @@ async fn handle_skill_call(
                 adapter_context: Some("mcp-skill"),
                 is_redacted: was_redacted,
                 attempt_number: 1,
+                structured_output: None,
+                environment: None,
             })
             .await;
     }
@@ -1109,6 +2037,215 @@ mod tests {
         assert_eq!(slugify("Skill__Name"), "skill-name");
     }
 
+    #[test]
+    fn progress_token_is_read_from_request_meta() {
+        let params = Some(json!({ "name": "tool", "_meta": { "progressToken": "abc" } }));
+        assert_eq!(progress_token(&params), Some(json!("abc")));
+        assert_eq!(progress_token(&Some(json!({ "name": "tool" }))), None);
+        assert_eq!(
+            progress_token(&Some(json!({ "_meta": { "progressToken": {} } }))),
+            None
+        );
+
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_event_stream(&headers));
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json, text/event-stream"),
+        );
+        assert!(accepts_event_stream(&headers));
+    }
+
+    #[test]
+    fn command_argument_constraints_reach_the_tool_schema() {
+        let arg = CommandArgument {
+            name: "retries".to_string(),
+            description: "How many times to retry".to_string(),
+            arg_type: ArgumentType::Number,
+            required: true,
+            default_value: None,
+            options: None,
+            pattern: Some("[0-9]+".to_string()),
+            min: Some(0.0),
+            max: Some(5.0),
+        };
+        let schema = build_mcp_tool_schema(
+            "deploy",
+            "Deploy",
+            &[McpToolParameter::from_command_argument(&arg)],
+        );
+        let prop = &schema["inputSchema"]["properties"]["retries"];
+        assert_eq!(prop["type"], "number");
+        assert_eq!(prop["pattern"], "^(?:[0-9]+)$");
+        assert_eq!(prop["minimum"], 0.0);
+        assert_eq!(prop["maximum"], 5.0);
+        assert_eq!(schema["inputSchema"]["required"][0], "retries");
+    }
+
+    #[test]
+    fn stdin_commands_take_an_optional_stdin_parameter() {
+        let mut cmd = Command::new(
+            "Summarize".to_string(),
+            "Summarize a diff".to_string(),
+            "summarize".to_string(),
+            true,
+        );
+        cmd.accepts_stdin = true;
+        let tools = ToolSet {
+            commands: vec![cmd],
+            skills: Vec::new(),
+            workflows: Vec::new(),
+            caller: McpCaller::owner(),
+        };
+
+        let listed = handle_tools_list(json!(1), &tools);
+        let schema = &listed["result"]["tools"][0]["inputSchema"];
+        assert_eq!(schema["properties"][STDIN_PARAMETER]["type"], "string");
+        assert!(schema["required"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn explain_tool_previews_exposed_commands() {
+        let mut cmd = Command::new(
+            "Greet".to_string(),
+            "Say hello".to_string(),
+            "echo {{name}}".to_string(),
+            false,
+        );
+        cmd.arguments = vec![CommandArgument {
+            name: "name".to_string(),
+            description: String::new(),
+            arg_type: ArgumentType::String,
+            required: true,
+            default_value: None,
+            options: None,
+            pattern: None,
+            min: None,
+            max: None,
+        }];
+        let tool = command_tool_name(&cmd);
+        let tools = ToolSet {
+            commands: vec![cmd],
+            skills: Vec::new(),
+            workflows: Vec::new(),
+            caller: McpCaller::owner(),
+        };
+
+        let listed = handle_tools_list(json!(1), &tools);
+        let names: Vec<&str> = listed["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&EXPLAIN_TOOL_NAME));
+        assert!(names.contains(&FEEDBACK_TOOL_NAME));
+        assert!(names.contains(&SEARCH_TOOL_NAME));
+        assert!(names.contains(&COMPOSE_TOOL_NAME));
+
+        let params = json!({
+            "name": EXPLAIN_TOOL_NAME,
+            "arguments": { "tool": tool, "arguments": { "name": "world" } }
+        });
+        let response = handle_explain_call(json!(2), &tools, &params);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        let preview: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(preview["env"]["RW_ARG_NAME"], "'world'");
+        assert!(preview["commandLine"]
+            .as_str()
+            .unwrap()
+            .contains("RW_ARG_NAME"));
+
+        let params = json!({ "arguments": { "tool": "missing-00000000" } });
+        let response = handle_explain_call(json!(3), &tools, &params);
+        assert_eq!(response["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn read_only_tokens_only_see_read_only_commands() {
+        let deploy = Command::new(
+            "Deploy".to_string(),
+            String::new(),
+            "make deploy".to_string(),
+            false,
+        );
+        let mut status = Command::new(
+            "Status".to_string(),
+            String::new(),
+            "git status".to_string(),
+            false,
+        );
+        status.mcp_policy.read_only = true;
+        let mut tools = ToolSet {
+            commands: vec![deploy.clone(), status.clone()],
+            skills: Vec::new(),
+            workflows: Vec::new(),
+            caller: McpCaller {
+                token: Some("ci".to_string()),
+                read_only: true,
+            },
+        };
+
+        let listed = handle_tools_list(json!(1), &tools);
+        let names: Vec<&str> = listed["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&command_tool_name(&status).as_str()));
+        assert!(!names.contains(&command_tool_name(&deploy).as_str()));
+
+        let params = json!({ "arguments": { "tool": command_tool_name(&deploy) } });
+        let response = handle_explain_call(json!(2), &tools, &params);
+        assert_eq!(response["error"]["code"], -32602);
+
+        tools.caller = McpCaller::owner();
+        let response = handle_explain_call(json!(3), &tools, &params);
+        assert!(response.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn read_only_tokens_cannot_report_feedback() {
+        let mut tools = ToolSet {
+            commands: Vec::new(),
+            skills: Vec::new(),
+            workflows: Vec::new(),
+            caller: McpCaller {
+                token: Some("ci".to_string()),
+                read_only: true,
+            },
+        };
+        let listed = handle_tools_list(json!(1), &tools);
+        assert!(!listed["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t["name"] == FEEDBACK_TOOL_NAME));
+
+        let manager = McpManager::new(0);
+        let params = json!({
+            "name": FEEDBACK_TOOL_NAME,
+            "arguments": { "rule": "Style", "verdict": "harmful" }
+        });
+        let response = dispatch_tools_call(
+            &manager,
+            json!(2),
+            Some(params.clone()),
+            &tools,
+            &None,
+            None,
+        )
+        .await;
+        assert_eq!(response["error"]["code"], -32601);
+
+        tools.caller = McpCaller::owner();
+        let response =
+            dispatch_tools_call(&manager, json!(3), Some(params), &tools, &None, None).await;
+        // Past the check; it only fails for want of a database.
+        assert_eq!(response["error"]["code"], -32603);
+    }
+
     #[test]
     fn test_disallowed_patterns() {
         assert!(contains_disallowed_pattern("rm -rf /").is_some());
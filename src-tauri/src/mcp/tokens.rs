@@ -0,0 +1,241 @@
+//! Named API tokens for MCP clients and the checks that decide which commands a client can
+//! see and call.
+//!
+//! Besides the app's own token, which the tools configured on this machine use, the user
+//! can hand out named tokens to other agents such as CI runners or containers. A named
+//! token can be read-only. Only a SHA-256 hash of each token is stored; the token itself
+//! is shown once when it is created.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{Command, McpPolicy};
+use crate::util::constant_time_eq;
+
+/// JSON array of [`McpClientToken`].
+pub const MCP_CLIENT_TOKENS_KEY: &str = "mcp_client_tokens";
+
+const TOKEN_PREFIX: &str = "rwt_";
+const MAX_TOKEN_NAME_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpClientToken {
+    pub name: String,
+    /// Hex SHA-256 of the token
+    pub sha256: String,
+    /// Only commands whose policy is marked read-only are offered
+    pub read_only: bool,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A token that was just created. `token` is not stored and cannot be shown again.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedMcpToken {
+    #[serde(flatten)]
+    pub info: McpClientToken,
+    pub token: String,
+}
+
+/// Who sent an MCP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpCaller {
+    /// Name of the token used; `None` for the app's own token
+    pub token: Option<String>,
+    pub read_only: bool,
+}
+
+impl McpCaller {
+    pub fn owner() -> Self {
+        Self {
+            token: None,
+            read_only: false,
+        }
+    }
+
+    /// Whether the caller satisfies the client restrictions of `policy`, regardless of
+    /// whether the command is exposed at all.
+    pub fn passes(&self, policy: &McpPolicy) -> bool {
+        if self.read_only && !policy.read_only {
+            return false;
+        }
+        match &self.token {
+            None => true,
+            Some(name) => {
+                !policy.local_only && (policy.tokens.is_empty() || policy.tokens.contains(name))
+            }
+        }
+    }
+
+    /// Whether the caller may see and call `command`.
+    pub fn admits(&self, command: &Command) -> bool {
+        command.mcp_policy.is_exposed() && self.passes(&command.mcp_policy)
+    }
+}
+
+fn digest(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// The caller holding `provided`, or `None` if it matches no token.
+pub fn authenticate(
+    provided: &str,
+    owner_token: &str,
+    tokens: &[McpClientToken],
+) -> Option<McpCaller> {
+    if constant_time_eq(provided.as_bytes(), owner_token.as_bytes()) {
+        return Some(McpCaller::owner());
+    }
+    let hash = digest(provided);
+    tokens
+        .iter()
+        .find(|token| constant_time_eq(token.sha256.as_bytes(), hash.as_bytes()))
+        .map(|token| McpCaller {
+            token: Some(token.name.clone()),
+            read_only: token.read_only,
+        })
+}
+
+pub async fn list(db: &Database) -> Result<Vec<McpClientToken>> {
+    Ok(db
+        .get_setting(MCP_CLIENT_TOKENS_KEY)
+        .await?
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+async fn save(db: &Database, tokens: &[McpClientToken]) -> Result<()> {
+    db.set_setting(MCP_CLIENT_TOKENS_KEY, &serde_json::to_string(tokens)?)
+        .await
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_TOKEN_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid token name '{}': use letters, digits, '-' and '_' (max {} characters)",
+            name, MAX_TOKEN_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+pub async fn create(db: &Database, name: &str, read_only: bool) -> Result<CreatedMcpToken> {
+    validate_name(name)?;
+    let mut tokens = list(db).await?;
+    if tokens.iter().any(|token| token.name == name) {
+        return Err(AppError::Validation(format!(
+            "A token named '{}' already exists",
+            name
+        )));
+    }
+
+    let token = format!("{}{}", TOKEN_PREFIX, uuid::Uuid::new_v4().simple());
+    let info = McpClientToken {
+        name: name.to_string(),
+        sha256: digest(&token),
+        read_only,
+        created_at: Utc::now(),
+    };
+    tokens.push(info.clone());
+    save(db, &tokens).await?;
+    Ok(CreatedMcpToken { info, token })
+}
+
+pub async fn revoke(db: &Database, name: &str) -> Result<()> {
+    let mut tokens = list(db).await?;
+    let before = tokens.len();
+    tokens.retain(|token| token.name != name);
+    if tokens.len() == before {
+        return Err(AppError::InvalidInput {
+            message: format!("No MCP token named '{}'", name),
+        });
+    }
+    save(db, &tokens).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::McpAccess;
+
+    fn command(policy: McpPolicy) -> Command {
+        let mut command = Command::new(
+            "deploy".to_string(),
+            String::new(),
+            "make deploy".to_string(),
+            false,
+        );
+        command.mcp_policy = policy;
+        command
+    }
+
+    fn named(name: &str, read_only: bool) -> McpCaller {
+        McpCaller {
+            token: Some(name.to_string()),
+            read_only,
+        }
+    }
+
+    #[test]
+    fn test_policies_admit_the_right_callers() {
+        let open = command(McpPolicy::default());
+        assert!(McpCaller::owner().admits(&open));
+        assert!(named("ci", false).admits(&open));
+        assert!(!named("ci", true).admits(&open));
+
+        let hidden = command(McpPolicy::hidden());
+        assert!(!McpCaller::owner().admits(&hidden));
+
+        let local = command(McpPolicy {
+            local_only: true,
+            ..McpPolicy::default()
+        });
+        assert!(McpCaller::owner().admits(&local));
+        assert!(!named("ci", false).admits(&local));
+
+        let status = command(McpPolicy {
+            access: McpAccess::ApprovalRequired,
+            read_only: true,
+            tokens: vec!["ci".to_string()],
+            ..McpPolicy::default()
+        });
+        assert!(McpCaller::owner().admits(&status));
+        assert!(named("ci", true).admits(&status));
+        assert!(!named("agent", false).admits(&status));
+    }
+
+    #[tokio::test]
+    async fn test_created_tokens_authenticate_until_revoked() {
+        let db = Database::new_in_memory().await.unwrap();
+        let created = create(&db, "ci", true).await.unwrap();
+        assert!(create(&db, "ci", false).await.is_err());
+        assert!(create(&db, "bad name", false).await.is_err());
+
+        let tokens = list(&db).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_ne!(tokens[0].sha256, created.token);
+        assert_eq!(
+            authenticate(&created.token, "owner", &tokens),
+            Some(named("ci", true))
+        );
+        assert_eq!(
+            authenticate("owner", "owner", &tokens),
+            Some(McpCaller::owner())
+        );
+        assert_eq!(authenticate("rwt_guess", "owner", &tokens), None);
+
+        revoke(&db, "ci").await.unwrap();
+        assert!(list(&db).await.unwrap().is_empty());
+        assert!(revoke(&db, "ci").await.is_err());
+    }
+}
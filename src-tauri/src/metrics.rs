@@ -0,0 +1,329 @@
+//! Process-wide counters and timing histograms for sync, import, reconciliation, MCP calls
+//! and command executions.
+//!
+//! Values live in memory from app start. They are read by the `get_metrics` command and,
+//! when enabled, served in the Prometheus text format at `/metrics` on the MCP port.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+
+use crate::models::{CounterSample, HistogramBucket, HistogramSample, MetricsSnapshot};
+
+/// Serves `/metrics` on the MCP port when `"true"`.
+pub const PROMETHEUS_ENDPOINT_KEY: &str = "metrics_prometheus_enabled";
+
+pub const SYNC_RUNS: &str = "ruleweaver_sync_runs_total";
+pub const SYNC_FILES_WRITTEN: &str = "ruleweaver_sync_files_written_total";
+pub const SYNC_DURATION: &str = "ruleweaver_sync_duration_seconds";
+pub const IMPORT_RUNS: &str = "ruleweaver_import_runs_total";
+pub const IMPORT_ARTIFACTS: &str = "ruleweaver_import_artifacts_total";
+pub const IMPORT_DURATION: &str = "ruleweaver_import_duration_seconds";
+pub const RECONCILE_RUNS: &str = "ruleweaver_reconcile_runs_total";
+pub const RECONCILE_FILES: &str = "ruleweaver_reconcile_files_total";
+pub const RECONCILE_DURATION: &str = "ruleweaver_reconcile_duration_seconds";
+pub const MCP_CALLS: &str = "ruleweaver_mcp_tool_calls_total";
+pub const MCP_CALL_DURATION: &str = "ruleweaver_mcp_tool_call_duration_seconds";
+pub const EXECUTIONS: &str = "ruleweaver_command_executions_total";
+pub const EXECUTION_DURATION: &str = "ruleweaver_command_execution_duration_seconds";
+
+const HELP: &[(&str, &str)] = &[
+    (SYNC_RUNS, "Sync runs by trigger and outcome"),
+    (SYNC_FILES_WRITTEN, "Tool files written by sync"),
+    (SYNC_DURATION, "Wall time of sync runs"),
+    (IMPORT_RUNS, "Import runs by outcome"),
+    (IMPORT_ARTIFACTS, "Artifacts imported by type"),
+    (IMPORT_DURATION, "Wall time of import runs"),
+    (RECONCILE_RUNS, "Applied reconciliation plans by outcome"),
+    (
+        RECONCILE_FILES,
+        "Files changed by reconciliation by operation",
+    ),
+    (
+        RECONCILE_DURATION,
+        "Wall time of applying reconciliation plans",
+    ),
+    (MCP_CALLS, "MCP tool calls by outcome"),
+    (MCP_CALL_DURATION, "Wall time of MCP tool calls"),
+    (EXECUTIONS, "Command executions by trigger and outcome"),
+    (
+        EXECUTION_DURATION,
+        "Wall time of command executions, including retries",
+    ),
+];
+
+/// Upper bounds in seconds, from quick file writes to long-running commands.
+const BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0,
+];
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::new()));
+
+type Key = (&'static str, Vec<(String, String)>);
+
+struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+struct Registry {
+    since: i64,
+    counters: BTreeMap<Key, u64>,
+    histograms: BTreeMap<Key, Histogram>,
+}
+
+fn key(name: &'static str, labels: &[(&str, &str)]) -> Key {
+    let mut labels: Vec<(String, String)> = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    labels.sort();
+    (name, labels)
+}
+
+fn help(name: &str) -> String {
+    HELP.iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, h)| h.to_string())
+        .unwrap_or_default()
+}
+
+impl Registry {
+    fn new() -> Self {
+        Self {
+            since: chrono::Utc::now().timestamp(),
+            counters: BTreeMap::new(),
+            histograms: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, name: &'static str, labels: &[(&str, &str)], value: u64) {
+        *self.counters.entry(key(name, labels)).or_default() += value;
+    }
+
+    fn observe(&mut self, name: &'static str, labels: &[(&str, &str)], seconds: f64) {
+        let histogram = self
+            .histograms
+            .entry(key(name, labels))
+            .or_insert_with(|| Histogram {
+                counts: vec![0; BUCKETS.len()],
+                count: 0,
+                sum: 0.0,
+            });
+        for (bound, count) in BUCKETS.iter().zip(histogram.counts.iter_mut()) {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += seconds;
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        let counters = self
+            .counters
+            .iter()
+            .map(|((name, labels), value)| CounterSample {
+                name: name.to_string(),
+                help: help(name),
+                labels: labels.iter().cloned().collect(),
+                value: *value,
+            })
+            .collect();
+        let histograms = self
+            .histograms
+            .iter()
+            .map(|((name, labels), histogram)| HistogramSample {
+                name: name.to_string(),
+                help: help(name),
+                labels: labels.iter().cloned().collect(),
+                count: histogram.count,
+                sum: histogram.sum,
+                buckets: BUCKETS
+                    .iter()
+                    .zip(&histogram.counts)
+                    .map(|(le, count)| HistogramBucket {
+                        le: *le,
+                        count: *count,
+                    })
+                    .collect(),
+            })
+            .collect();
+        MetricsSnapshot {
+            since: self.since,
+            counters,
+            histograms,
+        }
+    }
+}
+
+pub fn increment(name: &'static str, labels: &[(&str, &str)]) {
+    add(name, labels, 1);
+}
+
+pub fn add(name: &'static str, labels: &[(&str, &str)], value: u64) {
+    REGISTRY.lock().add(name, labels, value);
+}
+
+pub fn observe(name: &'static str, labels: &[(&str, &str)], seconds: f64) {
+    REGISTRY.lock().observe(name, labels, seconds);
+}
+
+/// Records the time since `started` in a histogram.
+pub fn observe_since(name: &'static str, labels: &[(&str, &str)], started: Instant) {
+    observe(name, labels, started.elapsed().as_secs_f64());
+}
+
+pub fn snapshot() -> MetricsSnapshot {
+    REGISTRY.lock().snapshot()
+}
+
+fn label_set(labels: &BTreeMap<String, String>, extra: Option<(&str, String)>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some((k, v)) = extra {
+        parts.push(format!("{}=\"{}\"", k, v));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders a snapshot in the Prometheus text exposition format.
+pub fn render_prometheus(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    let mut last_name = "";
+    for counter in &snapshot.counters {
+        if counter.name != last_name {
+            let _ = writeln!(out, "# HELP {} {}", counter.name, counter.help);
+            let _ = writeln!(out, "# TYPE {} counter", counter.name);
+            last_name = &counter.name;
+        }
+        let _ = writeln!(
+            out,
+            "{}{} {}",
+            counter.name,
+            label_set(&counter.labels, None),
+            counter.value
+        );
+    }
+    for histogram in &snapshot.histograms {
+        if histogram.name != last_name {
+            let _ = writeln!(out, "# HELP {} {}", histogram.name, histogram.help);
+            let _ = writeln!(out, "# TYPE {} histogram", histogram.name);
+            last_name = &histogram.name;
+        }
+        for bucket in &histogram.buckets {
+            let _ = writeln!(
+                out,
+                "{}_bucket{} {}",
+                histogram.name,
+                label_set(&histogram.labels, Some(("le", bucket.le.to_string()))),
+                bucket.count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{} {}",
+            histogram.name,
+            label_set(&histogram.labels, Some(("le", "+Inf".to_string()))),
+            histogram.count
+        );
+        let labels = label_set(&histogram.labels, None);
+        let _ = writeln!(out, "{}_sum{} {}", histogram.name, labels, histogram.sum);
+        let _ = writeln!(
+            out,
+            "{}_count{} {}",
+            histogram.name, labels, histogram.count
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_are_keyed_by_sorted_labels() {
+        let mut registry = Registry::new();
+        registry.add(
+            SYNC_RUNS,
+            &[("trigger", "manual"), ("status", "success")],
+            1,
+        );
+        registry.add(
+            SYNC_RUNS,
+            &[("status", "success"), ("trigger", "manual")],
+            2,
+        );
+        registry.add(SYNC_RUNS, &[("status", "failed"), ("trigger", "auto")], 1);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.counters.len(), 2);
+        let success = snapshot
+            .counters
+            .iter()
+            .find(|c| c.labels.get("status").map(String::as_str) == Some("success"))
+            .unwrap();
+        assert_eq!(success.value, 3);
+        assert_eq!(success.help, "Sync runs by trigger and outcome");
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let mut registry = Registry::new();
+        registry.observe(SYNC_DURATION, &[], 0.02);
+        registry.observe(SYNC_DURATION, &[], 0.7);
+        registry.observe(SYNC_DURATION, &[], 1000.0);
+
+        let snapshot = registry.snapshot();
+        let histogram = &snapshot.histograms[0];
+        assert_eq!(histogram.count, 3);
+        let at = |le: f64| histogram.buckets.iter().find(|b| b.le == le).unwrap().count;
+        assert_eq!(at(0.01), 0);
+        assert_eq!(at(0.025), 1);
+        assert_eq!(at(1.0), 2);
+        assert_eq!(at(300.0), 2);
+    }
+
+    #[test]
+    fn test_render_prometheus_text_format() {
+        let mut registry = Registry::new();
+        registry.add(MCP_CALLS, &[("status", "ok")], 4);
+        registry.observe(MCP_CALL_DURATION, &[("status", "ok")], 0.2);
+
+        let text = render_prometheus(&registry.snapshot());
+        assert!(text.contains("# TYPE ruleweaver_mcp_tool_calls_total counter\n"));
+        assert!(text.contains("ruleweaver_mcp_tool_calls_total{status=\"ok\"} 4\n"));
+        assert!(text.contains("# TYPE ruleweaver_mcp_tool_call_duration_seconds histogram\n"));
+        assert!(text.contains(
+            "ruleweaver_mcp_tool_call_duration_seconds_bucket{status=\"ok\",le=\"0.25\"} 1\n"
+        ));
+        assert!(text.contains(
+            "ruleweaver_mcp_tool_call_duration_seconds_bucket{status=\"ok\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(text.contains("ruleweaver_mcp_tool_call_duration_seconds_count{status=\"ok\"} 1\n"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        let labels = BTreeMap::from([("path".to_string(), "a\"b\\c".to_string())]);
+        assert_eq!(label_set(&labels, None), "{path=\"a\\\"b\\\\c\"}");
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DiffLine, DiffSummary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AiTask {
+    /// A new rule written from a description
+    Draft,
+    /// A shorter version of one rule
+    Condense,
+    /// One rule replacing several overlapping ones
+    Merge,
+}
+
+/// Text suggested by the model, for the user to review. Nothing has been saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiRuleProposal {
+    pub task: AiTask,
+    /// The rules the proposal rewrites or merges; empty for drafts
+    pub source_rule_ids: Vec<String>,
+    /// Suggested rule name
+    pub name: String,
+    /// The current text; the merged rules' contents joined by blank lines for merges
+    pub original: String,
+    pub proposed: String,
+    pub diff: Vec<DiffLine>,
+    pub summary: DiffSummary,
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// Severity of a captured log line, ordered from most to least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppLogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<log::Level> for AppLogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => Self::Error,
+            log::Level::Warn => Self::Warn,
+            log::Level::Info => Self::Info,
+            log::Level::Debug => Self::Debug,
+            log::Level::Trace => Self::Trace,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLogEntry {
+    /// Increases by one per captured line, so pages stay stable while new lines arrive
+    pub seq: u64,
+    /// Unix milliseconds
+    pub timestamp: i64,
+    pub level: AppLogLevel,
+    /// Module path (log target) that emitted the line, e.g. `ruleweaver_lib::sync`
+    pub module: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLogQuery {
+    /// Least severe level to include; `warn` returns warnings and errors
+    pub level: Option<AppLogLevel>,
+    /// Module prefix, e.g. `ruleweaver_lib::mcp`
+    pub module: Option<String>,
+    /// Case-insensitive text to find in the message
+    pub search: Option<String>,
+    /// Unix milliseconds, inclusive
+    pub since: Option<i64>,
+    /// Only lines captured before this sequence number, for paging back from a fixed point
+    pub before_seq: Option<u64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLogPage {
+    /// Newest first
+    pub entries: Vec<AppLogEntry>,
+    /// Lines matching the filters, across all pages
+    pub total: u32,
+}
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse_error::ParseEnumError;
+
+/// Where a change came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditActorKind {
+    /// The desktop app, including the tray and deep links
+    Ui,
+    /// A tool call over the MCP server
+    Mcp,
+    /// The rule file watcher picking up an external edit
+    Watcher,
+    /// The `ruleweaver` command line
+    Cli,
+    /// A background job such as cloud sync or an import subscription
+    Background,
+}
+
+impl AuditActorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ui => "ui",
+            Self::Mcp => "mcp",
+            Self::Watcher => "watcher",
+            Self::Cli => "cli",
+            Self::Background => "background",
+        }
+    }
+}
+
+impl FromStr for AuditActorKind {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ui" => Ok(Self::Ui),
+            "mcp" => Ok(Self::Mcp),
+            "watcher" => Ok(Self::Watcher),
+            "cli" => Ok(Self::Cli),
+            "background" => Ok(Self::Background),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// The actor of a change, with an optional detail such as the MCP client name or the
+/// background job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditActor {
+    pub kind: AuditActorKind,
+    pub detail: Option<String>,
+}
+
+impl AuditActor {
+    pub fn new(kind: AuditActorKind) -> Self {
+        Self { kind, detail: None }
+    }
+
+    pub fn with_detail(kind: AuditActorKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Toggle,
+    Import,
+    Sync,
+    Reconcile,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Toggle => "toggle",
+            Self::Import => "import",
+            Self::Sync => "sync",
+            Self::Reconcile => "reconcile",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
+            "delete" => Ok(Self::Delete),
+            "toggle" => Ok(Self::Toggle),
+            "import" => Ok(Self::Import),
+            "sync" => Ok(Self::Sync),
+            "reconcile" => Ok(Self::Reconcile),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditTargetType {
+    Rule,
+    Command,
+    Skill,
+    Workflow,
+}
+
+impl AuditTargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rule => "rule",
+            Self::Command => "command",
+            Self::Skill => "skill",
+            Self::Workflow => "workflow",
+        }
+    }
+}
+
+impl FromStr for AuditTargetType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "rule" => Ok(Self::Rule),
+            "command" => Ok(Self::Command),
+            "skill" => Ok(Self::Skill),
+            "workflow" => Ok(Self::Workflow),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// The artifact a change applied to. Sync, import and reconcile runs have none.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditTarget {
+    #[serde(rename = "type")]
+    pub target_type: AuditTargetType,
+    pub id: String,
+    pub name: String,
+}
+
+impl AuditTarget {
+    pub fn new(target_type: AuditTargetType, id: &str, name: &str) -> Self {
+        Self {
+            target_type,
+            id: id.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pub id: String,
+    #[serde(with = "crate::models::timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub actor: AuditActor,
+    pub action: AuditAction,
+    pub target: Option<AuditTarget>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQuery {
+    pub actor: Option<AuditActorKind>,
+    pub action: Option<AuditAction>,
+    pub target_type: Option<AuditTargetType>,
+    pub target_id: Option<String>,
+    /// Case-insensitive text to find in the summary or target name
+    pub search: Option<String>,
+    /// Unix seconds, inclusive
+    pub since: Option<i64>,
+    /// Unix seconds, exclusive
+    pub until: Option<i64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogPage {
+    /// Newest first
+    pub entries: Vec<AuditLogEntry>,
+    /// Entries matching the filters, across all pages
+    pub total: u32,
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ExportConfiguration;
+
+/// Remote storage holding the shared configuration. Credentials are referenced by the name of
+/// a secret in the vault, never stored in the setting itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum CloudSyncBackend {
+    /// A file on a WebDAV server (Nextcloud, ownCloud, Synology, ...)
+    #[serde(rename = "webdav")]
+    WebDav {
+        /// URL of the file to read and write
+        url: String,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password_secret: Option<String>,
+    },
+    /// An object in an S3-compatible bucket
+    S3 {
+        /// Custom endpoint for S3-compatible services; AWS when unset
+        #[serde(default)]
+        endpoint: Option<String>,
+        region: String,
+        bucket: String,
+        key: String,
+        access_key_id: String,
+        secret_access_key_secret: String,
+    },
+    /// A file in a Dropbox account, accessed with an app access token
+    Dropbox { path: String, token_secret: String },
+}
+
+impl CloudSyncBackend {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::WebDav { url, .. } => url.clone(),
+            Self::S3 { bucket, key, .. } => format!("s3://{}/{}", bucket, key),
+            Self::Dropbox { path, .. } => format!("dropbox:{}", path),
+        }
+    }
+}
+
+/// Cloud sync settings and the state of the last sync on this machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSync {
+    pub backend: CloudSyncBackend,
+    /// Identifies this machine in the remote document
+    pub device_id: String,
+    pub interval_minutes: u32,
+    pub enabled: bool,
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Every item as of the last sync, keyed by `<kind>:<id>`; the common ancestor that
+    /// tells local edits apart from remote ones
+    #[serde(default)]
+    pub base: BTreeMap<String, CloudSyncFingerprint>,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Content hashes of one item after the last sync. They are kept per side because an
+/// imported item is not always stored exactly as it was exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSyncFingerprint {
+    pub local: String,
+    pub remote: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigureCloudSyncInput {
+    pub backend: CloudSyncBackend,
+    pub interval_minutes: Option<u32>,
+}
+
+/// The file stored remotely: a configuration export plus who wrote it last.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSyncDocument {
+    pub format: u32,
+    pub device_id: String,
+    /// Incremented on every upload
+    pub revision: u64,
+    pub config: ExportConfiguration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSyncResult {
+    /// Items created or updated locally from the remote copy
+    pub pulled: Vec<String>,
+    /// Items deleted locally because they were deleted remotely
+    pub deleted: Vec<String>,
+    /// Local changes sent to the remote copy
+    pub pushed: Vec<String>,
+    /// Items changed on both sides, settled by keeping the most recent edit
+    pub conflicts: Vec<String>,
+    /// Whether the remote copy was rewritten
+    pub uploaded: bool,
+    pub revision: u64,
+    pub errors: Vec<String>,
+}
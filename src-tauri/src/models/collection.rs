@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse_error::ParseEnumError;
+
+/// Kind of artifact that can be filed into a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionItemType {
+    Rule,
+    Command,
+    Skill,
+}
+
+impl CollectionItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rule => "rule",
+            Self::Command => "command",
+            Self::Skill => "skill",
+        }
+    }
+}
+
+impl FromStr for CollectionItemType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rule" => Ok(Self::Rule),
+            "command" => Ok(Self::Command),
+            "skill" => Ok(Self::Skill),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// A folder that groups rules, commands and skills. Collections nest via `parent_id`;
+/// `None` places the collection at the top level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    /// Sort order among siblings sharing the same parent
+    pub position: i64,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How many of each collection's ancestors are themselves in `collections`. Importing in
+/// ascending depth writes every parent before its children.
+pub fn nesting_depths<'a>(
+    collections: impl IntoIterator<Item = &'a Collection>,
+) -> HashMap<String, usize> {
+    let parents: HashMap<&str, Option<&str>> = collections
+        .into_iter()
+        .map(|c| (c.id.as_str(), c.parent_id.as_deref()))
+        .collect();
+    parents
+        .keys()
+        .map(|&id| {
+            let mut depth = 0;
+            let mut current = parents[id];
+            // Stop after visiting every collection once so a cycle cannot loop forever.
+            while let Some(parent) = current.filter(|p| parents.contains_key(p)) {
+                if depth == parents.len() {
+                    break;
+                }
+                depth += 1;
+                current = parents[parent];
+            }
+            (id.to_string(), depth)
+        })
+        .collect()
+}
+
+/// Membership of a single artifact in a collection. An artifact belongs to at most one
+/// collection; artifacts without a membership row are shown at the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionItem {
+    pub collection_id: String,
+    pub item_type: CollectionItemType,
+    pub item_id: String,
+    /// Sort order among items of the same collection
+    pub position: i64,
+}
+
+/// Identifies an artifact when reordering the contents of a collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionItemRef {
+    pub item_type: CollectionItemType,
+    pub item_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCollectionInput {
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collection_item_type_roundtrip() {
+        for item_type in [
+            CollectionItemType::Rule,
+            CollectionItemType::Command,
+            CollectionItemType::Skill,
+        ] {
+            assert_eq!(
+                CollectionItemType::from_str(item_type.as_str()),
+                Ok(item_type)
+            );
+        }
+        assert!(CollectionItemType::from_str("folder").is_err());
+    }
+
+    #[test]
+    fn test_create_collection_input_camel_case_from_frontend() {
+        let json = r#"{ "name": "Frontend", "parentId": "root-1" }"#;
+        let parsed: CreateCollectionInput = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.name, "Frontend");
+        assert_eq!(parsed.parent_id.as_deref(), Some("root-1"));
+        assert!(parsed.id.is_none());
+    }
+
+    #[test]
+    fn test_nesting_depths_count_listed_ancestors() {
+        let collection = |id: &str, parent_id: Option<&str>| Collection {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let collections = [
+            collection("leaf", Some("middle")),
+            collection("middle", Some("top")),
+            collection("top", Some("elsewhere")),
+            collection("a", Some("b")),
+            collection("b", Some("a")),
+        ];
+
+        let depths = nesting_depths(&collections);
+        assert_eq!(depths["top"], 0);
+        assert_eq!(depths["middle"], 1);
+        assert_eq!(depths["leaf"], 2);
+        // A cycle ends the walk instead of looping.
+        assert!(depths["a"] <= collections.len());
+    }
+}
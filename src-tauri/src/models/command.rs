@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use chrono::{DateTime, Utc};
@@ -5,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::parse_error::ParseEnumError;
+use super::{Metadata, Scope};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,13 +16,25 @@ pub struct Command {
     pub description: String,
     pub script: String,
     pub arguments: Vec<CommandArgument>,
-    pub expose_via_mcp: bool,
+    /// Which MCP clients may see and call the command
+    #[serde(flatten, with = "mcp_policy_field")]
+    pub mcp_policy: McpPolicy,
     #[serde(default)]
     pub is_placeholder: bool,
     #[serde(default)]
     pub generate_slash_commands: bool,
     #[serde(default)]
     pub slash_command_adapters: Vec<String>,
+    /// Folder-style namespace such as `git` or `git/review`; the command is invoked as
+    /// `/git/commit` where the tool supports nested commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slash_namespace: Option<String>,
+    #[serde(default)]
+    pub slash_frontmatter: SlashCommandFrontmatter,
+    /// Global slash commands go to the home directory plus any `target_paths`; local ones
+    /// only into repositories: `target_paths` when set, otherwise every registered root
+    #[serde(default)]
+    pub slash_scope: Scope,
     #[serde(default)]
     pub target_paths: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,12 +43,131 @@ pub struct Command {
     pub timeout_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_retries: Option<u8>,
+    /// Vault secrets injected as environment variables of the same name
+    #[serde(default)]
+    pub required_secrets: Vec<String>,
+    /// Directory to run in; may start with `{{repo_root}}` for local-scoped commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Shell to run the script with; the platform default when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<CommandShell>,
+    /// Cron expression (local time) the background scheduler runs the command on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Restrictions applied to every run of the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxProfile>,
+    /// Globs, relative to the working directory, of files kept after each run
+    #[serde(default)]
+    pub artifact_globs: Vec<String>,
+    /// How stdout is turned into structured results stored with each run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_parser: Option<OutputParser>,
+    /// Callers may pass an input payload that is piped to the script's stdin
+    #[serde(default)]
+    pub accepts_stdin: bool,
+    #[serde(default)]
+    pub metadata: Metadata,
     #[serde(with = "crate::models::timestamp")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "crate::models::timestamp")]
     pub updated_at: DateTime<Utc>,
 }
 
+/// How a command is offered to MCP clients.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpAccess {
+    /// Not offered to MCP clients
+    Hidden,
+    /// Callable by every client the rest of the policy admits
+    #[default]
+    Open,
+    /// Calls wait for the user to approve them in the app
+    ApprovalRequired,
+}
+
+/// Which MCP clients may see and call a command. Clients authenticate with the app's own
+/// token or with a named token; see `mcp::tokens`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpPolicy {
+    pub access: McpAccess,
+    /// The command changes nothing, so read-only tokens may call it
+    pub read_only: bool,
+    /// Only the app's own token may call it; named tokens never see it
+    pub local_only: bool,
+    /// Named tokens that may call it; empty admits every named token. The app's own token
+    /// is not restricted by this list.
+    pub tokens: Vec<String>,
+}
+
+impl McpPolicy {
+    pub fn hidden() -> Self {
+        Self {
+            access: McpAccess::Hidden,
+            ..Self::default()
+        }
+    }
+
+    /// The policy implied by the `expose_via_mcp` and `requires_approval` flags that
+    /// commands had before policies existed.
+    pub fn from_legacy(expose_via_mcp: bool, requires_approval: bool) -> Self {
+        let access = match (expose_via_mcp, requires_approval) {
+            (false, _) => McpAccess::Hidden,
+            (true, true) => McpAccess::ApprovalRequired,
+            (true, false) => McpAccess::Open,
+        };
+        Self {
+            access,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_exposed(&self) -> bool {
+        self.access != McpAccess::Hidden
+    }
+
+    pub fn requires_approval(&self) -> bool {
+        self.access == McpAccess::ApprovalRequired
+    }
+}
+
+/// Reads and writes the policy as an `mcpPolicy` field, falling back to the
+/// `exposeViaMcp` and `requiresApproval` fields of exports and synced copies from older
+/// releases.
+mod mcp_policy_field {
+    use serde::ser::SerializeMap;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::McpPolicy;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Stored {
+        mcp_policy: Option<McpPolicy>,
+        expose_via_mcp: Option<bool>,
+        requires_approval: Option<bool>,
+    }
+
+    pub fn serialize<S: Serializer>(policy: &McpPolicy, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry("mcpPolicy", policy)?;
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<McpPolicy, D::Error> {
+        let stored = Stored::deserialize(deserializer)?;
+        Ok(stored.mcp_policy.unwrap_or_else(|| {
+            McpPolicy::from_legacy(
+                stored.expose_via_mcp.unwrap_or(true),
+                stored.requires_approval.unwrap_or(false),
+            )
+        }))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandArgument {
@@ -45,6 +178,15 @@ pub struct CommandArgument {
     pub required: bool,
     pub default_value: Option<String>,
     pub options: Option<Vec<String>>,
+    /// Regular expression the whole value must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Inclusive lower bound for number arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Inclusive upper bound for number arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -54,12 +196,60 @@ pub enum ArgumentType {
     Number,
     Boolean,
     Enum,
+    /// A filesystem path; parent-directory components are rejected
+    Path,
+}
+
+impl ArgumentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArgumentType::String => "string",
+            ArgumentType::Number => "number",
+            ArgumentType::Boolean => "boolean",
+            ArgumentType::Enum => "enum",
+            ArgumentType::Path => "path",
+        }
+    }
 }
 
 fn default_arg_type() -> ArgumentType {
     ArgumentType::String
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandShell {
+    Bash,
+    Zsh,
+    Pwsh,
+    Cmd,
+}
+
+impl CommandShell {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandShell::Bash => "bash",
+            CommandShell::Zsh => "zsh",
+            CommandShell::Pwsh => "pwsh",
+            CommandShell::Cmd => "cmd",
+        }
+    }
+}
+
+impl FromStr for CommandShell {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(CommandShell::Bash),
+            "zsh" => Ok(CommandShell::Zsh),
+            "pwsh" => Ok(CommandShell::Pwsh),
+            "cmd" => Ok(CommandShell::Cmd),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum FailureClass {
@@ -69,6 +259,8 @@ pub enum FailureClass {
     PermissionDenied,
     MissingBinary,
     NonZeroExit,
+    /// Stopped by the user before it finished
+    Cancelled,
     UnknownError,
 }
 
@@ -81,6 +273,7 @@ impl FailureClass {
             FailureClass::PermissionDenied => "permission_denied",
             FailureClass::MissingBinary => "missing_binary",
             FailureClass::NonZeroExit => "non_zero_exit",
+            FailureClass::Cancelled => "cancelled",
             FailureClass::UnknownError => "unknown_error",
         }
     }
@@ -91,6 +284,7 @@ impl FailureClass {
             FailureClass::ValidationError
                 | FailureClass::MissingBinary
                 | FailureClass::PermissionDenied
+                | FailureClass::Cancelled
         )
     }
 }
@@ -106,6 +300,7 @@ impl FromStr for FailureClass {
             "permission_denied" => Ok(FailureClass::PermissionDenied),
             "missing_binary" => Ok(FailureClass::MissingBinary),
             "non_zero_exit" => Ok(FailureClass::NonZeroExit),
+            "cancelled" => Ok(FailureClass::Cancelled),
             "unknown_error" => Ok(FailureClass::UnknownError),
             _ => Err(ParseEnumError),
         }
@@ -134,6 +329,47 @@ pub struct ExecutionLog {
     pub is_redacted: bool,
     #[serde(default)]
     pub attempt_number: u8,
+    /// Directory holding the files captured by the command's artifact globs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts_dir: Option<String>,
+    /// Results extracted by the command's output parser
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_output: Option<serde_json::Value>,
+    /// What the run saw: environment, tool versions and source revision
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentSnapshot>,
+}
+
+/// The conditions a command ran under, recorded with its execution log entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentSnapshot {
+    /// Variables passed to the process. Secrets are left out and sensitive-looking
+    /// values are redacted.
+    pub env: BTreeMap<String, String>,
+    /// First line of `--version` output for the known tools the script calls
+    pub tool_versions: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// `HEAD` of the git repository containing the working directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// Whether that repository had uncommitted changes
+    #[serde(default)]
+    pub git_dirty: bool,
+    pub app_version: String,
+    pub os: String,
+}
+
+/// A file captured from an execution.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionArtifact {
+    /// Path relative to the command's working directory
+    pub name: String,
+    /// Where the copy is stored
+    pub path: String,
+    pub size_bytes: u64,
 }
 
 impl Command {
@@ -146,14 +382,26 @@ impl Command {
             description,
             script,
             arguments: Vec::new(),
-            expose_via_mcp: true,
+            mcp_policy: McpPolicy::default(),
             is_placeholder,
             generate_slash_commands: false,
             slash_command_adapters: Vec::new(),
+            slash_namespace: None,
+            slash_frontmatter: Default::default(),
+            slash_scope: Default::default(),
             target_paths: Vec::new(),
             base_path: None,
             timeout_ms: None,
             max_retries: None,
+            required_secrets: Vec::new(),
+            working_dir: None,
+            shell: None,
+            schedule: None,
+            sandbox: None,
+            artifact_globs: Vec::new(),
+            output_parser: None,
+            accepts_stdin: false,
+            metadata: Metadata::default(),
             created_at: now,
             updated_at: now,
         }
@@ -169,14 +417,20 @@ pub struct CreateCommandInput {
     pub script: String,
     #[serde(default)]
     pub arguments: Vec<CommandArgument>,
-    #[serde(default = "default_true")]
-    pub expose_via_mcp: bool,
+    #[serde(flatten, with = "mcp_policy_field")]
+    pub mcp_policy: McpPolicy,
     #[serde(default)]
     pub is_placeholder: bool,
     #[serde(default)]
     pub generate_slash_commands: bool,
     #[serde(default)]
     pub slash_command_adapters: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slash_namespace: Option<String>,
+    #[serde(default)]
+    pub slash_frontmatter: SlashCommandFrontmatter,
+    #[serde(default)]
+    pub slash_scope: Scope,
     #[serde(default)]
     pub target_paths: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -185,10 +439,24 @@ pub struct CreateCommandInput {
     pub timeout_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_retries: Option<u8>,
-}
-
-fn default_true() -> bool {
-    true
+    #[serde(default)]
+    pub required_secrets: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<CommandShell>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxProfile>,
+    #[serde(default)]
+    pub artifact_globs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_parser: Option<OutputParser>,
+    #[serde(default)]
+    pub accepts_stdin: bool,
+    #[serde(default)]
+    pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -198,19 +466,179 @@ pub struct UpdateCommandInput {
     pub description: Option<String>,
     pub script: Option<String>,
     pub arguments: Option<Vec<CommandArgument>>,
-    pub expose_via_mcp: Option<bool>,
+    pub mcp_policy: Option<McpPolicy>,
     pub is_placeholder: Option<bool>,
     pub generate_slash_commands: Option<bool>,
     pub slash_command_adapters: Option<Vec<String>>,
+    /// An empty string removes the namespace
+    pub slash_namespace: Option<String>,
+    pub slash_frontmatter: Option<SlashCommandFrontmatter>,
+    pub slash_scope: Option<Scope>,
     pub target_paths: Option<Vec<String>>,
     pub base_path: Option<String>,
     pub timeout_ms: Option<u64>,
     pub max_retries: Option<u8>,
+    pub required_secrets: Option<Vec<String>>,
+    /// An empty string clears the working directory
+    pub working_dir: Option<String>,
+    pub shell: Option<CommandShell>,
+    /// An empty string removes the schedule
+    pub schedule: Option<String>,
+    /// A profile with no restrictions removes the sandbox
+    pub sandbox: Option<SandboxProfile>,
+    pub artifact_globs: Option<Vec<String>>,
+    /// The `text` parser removes the output parser
+    pub output_parser: Option<OutputParser>,
+    pub accepts_stdin: Option<bool>,
+    pub metadata: Option<Metadata>,
+}
+
+/// Slash command frontmatter the adapters emit in their own syntax. Tools without a
+/// matching option ignore it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommandFrontmatter {
+    /// Shown while typing the command, e.g. `<file> [message]`; derived from the
+    /// arguments when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+    /// Tools the command may use without asking, e.g. `Bash(git add:*)`
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl SlashCommandFrontmatter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Limits on what a command's process may do. Each restriction is enforced where the OS
+/// allows it; a run fails rather than going ahead without a restriction it asked for.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxProfile {
+    /// Start from a minimal environment instead of inheriting the app's
+    #[serde(default)]
+    pub scrub_env: bool,
+    /// Absolute paths the command may read but not modify
+    #[serde(default)]
+    pub read_only_paths: Vec<String>,
+    #[serde(default)]
+    pub deny_network: bool,
+    /// CPU time limit in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_time_secs: Option<u64>,
+    /// Memory (address space) limit in megabytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+}
+
+impl SandboxProfile {
+    /// True when the profile restricts nothing.
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Turns a run's output into structured results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OutputParser {
+    /// Raw output only; no structured results
+    Text,
+    /// stdout is a JSON document
+    Json,
+    /// A JUnit XML report, read from stdout or from `reportPath` under the working directory
+    Junit {
+        #[serde(
+            default,
+            rename = "reportPath",
+            skip_serializing_if = "Option::is_none"
+        )]
+        report_path: Option<String>,
+    },
+    /// The named capture groups of every match of `pattern` in stdout
+    Regex { pattern: String },
+}
+
+/// An MCP call parked until the user approves or denies it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApproval {
+    pub id: String,
+    pub command_id: String,
+    pub command_name: String,
+    pub script: String,
+    pub arguments_json: String,
+    #[serde(with = "crate::models::timestamp")]
+    pub requested_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+impl ApprovalDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalDecision::Approved => "approved",
+            ApprovalDecision::Denied => "denied",
+            ApprovalDecision::TimedOut => "timed_out",
+        }
+    }
+}
+
+impl FromStr for ApprovalDecision {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "approved" => Ok(ApprovalDecision::Approved),
+            "denied" => Ok(ApprovalDecision::Denied),
+            "timed_out" => Ok(ApprovalDecision::TimedOut),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// The audit record of one approval request and its outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalAuditEntry {
+    pub id: String,
+    pub command_id: String,
+    pub command_name: String,
+    pub arguments_json: String,
+    pub decision: ApprovalDecision,
+    #[serde(with = "crate::models::timestamp")]
+    pub requested_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub decided_at: DateTime<Utc>,
+}
+
+/// A vault entry as shown to the UI; the value itself is never returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretInfo {
+    pub name: String,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestCommandResult {
+    /// Matches the `executionId` of the `command-output` events emitted while running
+    pub execution_id: String,
     pub success: bool,
     pub stdout: String,
     pub stderr: String,
@@ -218,6 +646,48 @@ pub struct TestCommandResult {
     pub duration_ms: u64,
 }
 
+/// What running a command would do, resolved without starting anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPreview {
+    pub command_id: String,
+    pub command_name: String,
+    /// The script after argument placeholders are replaced
+    pub script: String,
+    /// The process that would be started, including any sandbox wrapper
+    pub program: String,
+    pub args: Vec<String>,
+    /// `program` and `args` joined and quoted for display
+    pub command_line: String,
+    /// Argument values passed as environment variables
+    pub env: BTreeMap<String, String>,
+    /// Vault secrets that would be added to the environment; values are never shown
+    pub secrets: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    pub timeout_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxProfile>,
+    pub requires_approval: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// One line of output from a running command, emitted as the `command-output` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutputEvent {
+    pub execution_id: String,
+    pub command_id: String,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,7 +701,7 @@ mod tests {
             false,
         );
         assert_eq!(command.name, "Fmt");
-        assert!(command.expose_via_mcp);
+        assert_eq!(command.mcp_policy, McpPolicy::default());
         assert!(command.arguments.is_empty());
         assert!(!command.id.is_empty());
     }
@@ -250,15 +720,30 @@ mod tests {
                 required: false,
                 default_value: None,
                 options: None,
+                pattern: None,
+                min: None,
+                max: None,
             }],
-            expose_via_mcp: true,
+            mcp_policy: McpPolicy::default(),
             is_placeholder: false,
             generate_slash_commands: false,
             slash_command_adapters: vec![],
+            slash_namespace: None,
+            slash_frontmatter: Default::default(),
+            slash_scope: Default::default(),
             target_paths: vec![],
             base_path: None,
             timeout_ms: None,
             max_retries: None,
+            required_secrets: vec![],
+            working_dir: None,
+            shell: None,
+            schedule: None,
+            sandbox: None,
+            artifact_globs: Vec::new(),
+            output_parser: None,
+            accepts_stdin: false,
+            metadata: Default::default(),
         };
 
         let json = serde_json::to_string(&input).expect("serialize create input");
@@ -266,5 +751,30 @@ mod tests {
             serde_json::from_str(&json).expect("deserialize create input");
         assert_eq!(parsed.name, input.name);
         assert_eq!(parsed.arguments.len(), 1);
+        assert_eq!(parsed.mcp_policy, input.mcp_policy);
+    }
+
+    #[test]
+    fn test_policy_falls_back_to_legacy_flags() {
+        let legacy = |fields: &str| {
+            let json = format!(
+                r#"{{"name":"Deploy","description":"","script":"make deploy"{}}}"#,
+                fields
+            );
+            serde_json::from_str::<CreateCommandInput>(&json)
+                .unwrap()
+                .mcp_policy
+                .access
+        };
+        assert_eq!(legacy(""), McpAccess::Open);
+        assert_eq!(legacy(r#","exposeViaMcp":false"#), McpAccess::Hidden);
+        assert_eq!(
+            legacy(r#","exposeViaMcp":true,"requiresApproval":true"#),
+            McpAccess::ApprovalRequired
+        );
+        assert_eq!(
+            legacy(r#","exposeViaMcp":false,"mcpPolicy":{"access":"open","readOnly":true}"#),
+            McpAccess::Open
+        );
     }
 }
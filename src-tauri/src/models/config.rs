@@ -1,7 +1,41 @@
-use crate::models::{Command, Rule, Skill};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::{
+    AdapterType, Collection, CollectionItem, CollectionItemType, Command, Metadata, Rule, Scope,
+    Skill,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// User preferences that carry over to another machine through exports and cloud sync.
+/// Every other setting describes this machine, points it at a server or secret, or is
+/// bookkeeping, so it is left out of exports and ignored on import; a new key stays local
+/// unless it is added here.
+pub const PORTABLE_SETTINGS: &[&str] = &[
+    "theme",
+    "default_adapters",
+    "adapter_languages",
+    "mcp_auto_start",
+    "minimize_to_tray",
+    "startup_drift_check",
+    "drift_monitor_enabled",
+    "auto_backup_enabled",
+    "auto_backup_retention",
+    "log_retention_max_rows",
+    "log_retention_max_age_days",
+    "artifact_retention_max_age_days",
+    "export_snapshot_interval_hours",
+    "export_snapshot_retention",
+    "sign_exports",
+    "redact_builtin_patterns",
+    "redaction_patterns",
+    "redacted_env_vars",
+];
+
+pub fn is_portable_setting(key: &str) -> bool {
+    PORTABLE_SETTINGS.contains(&key)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportConfiguration {
@@ -10,6 +44,128 @@ pub struct ExportConfiguration {
     pub rules: Vec<Rule>,
     pub commands: Vec<Command>,
     pub skills: Vec<Skill>,
+    #[serde(default)]
+    pub collections: Vec<Collection>,
+    #[serde(default)]
+    pub collection_items: Vec<CollectionItem>,
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+    /// Sync and reconciliation bookkeeping, included on request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<ExportState>,
+    /// Checksums of the contents; absent in exports from older releases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<ExportManifest>,
+}
+
+/// What this machine knows about the files it generated. Restoring it along with the
+/// configuration lets the next sync recognize those files instead of reporting each one
+/// as a conflict.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportState {
+    /// Hash of every generated file as last written, by path
+    #[serde(default)]
+    pub sync_hashes: BTreeMap<String, String>,
+    /// Where each rule's file lives in file storage
+    #[serde(default)]
+    pub rule_files: Vec<RuleFileState>,
+    /// The stored `import_source_map` setting: where imported artifacts came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_source_map: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFileState {
+    pub rule_id: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<i64>,
+}
+
+/// Per-artifact SHA-256 hashes of an export, optionally signed by the exporting machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub artifacts: Vec<ManifestEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ManifestSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// `rule`, `command`, `skill`, `collection`, `collection_item`, `setting` or `state`
+    pub kind: String,
+    pub id: String,
+    /// Hex SHA-256 of the artifact serialized as JSON
+    pub sha256: String,
+}
+
+/// Ed25519 signature over the export time and the manifest entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSignature {
+    /// Hex public key of the signer
+    pub public_key: String,
+    /// Hex signature
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportIntegrity {
+    /// No manifest; the file cannot be checked
+    Unverified,
+    /// Checksums match but nobody signed the export
+    Checksummed,
+    /// Signed by this machine or a trusted key
+    Trusted,
+    /// Signed by a key that is not trusted here
+    UnknownSigner,
+}
+
+/// Result of checking an export before it is imported. Exports whose contents do not match
+/// their manifest are rejected outright and never get this far.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportVerification {
+    pub integrity: ExportIntegrity,
+    /// Hex public key of the signer
+    pub signer: Option<String>,
+}
+
+/// What `preview_import` returns: the export and whether it can be trusted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    #[serde(flatten)]
+    pub configuration: ExportConfiguration,
+    pub verification: ExportVerification,
+}
+
+/// How an export is written. Without an explicit format, `.yaml`/`.yml` paths get YAML and
+/// everything else JSON.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    /// A directory with one file per rule, command and skill; see [`crate::export_tree`]
+    Tree,
+}
+
+impl ExportFormat {
+    pub fn for_path(path: &str) -> Self {
+        if path.ends_with(".yaml") || path.ends_with(".yml") {
+            Self::Yaml
+        } else {
+            Self::Json
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -27,6 +183,315 @@ impl ExportConfiguration {
             rules,
             commands,
             skills,
+            collections: Vec::new(),
+            collection_items: Vec::new(),
+            settings: BTreeMap::new(),
+            state: None,
+            manifest: None,
+        }
+    }
+
+    pub fn with_collections(
+        mut self,
+        collections: Vec<Collection>,
+        collection_items: Vec<CollectionItem>,
+    ) -> Self {
+        self.collections = collections;
+        self.collection_items = collection_items;
+        self
+    }
+
+    pub fn with_settings(mut self, settings: HashMap<String, String>) -> Self {
+        self.settings = settings
+            .into_iter()
+            .filter(|(key, _)| is_portable_setting(key))
+            .collect();
+        self
+    }
+}
+
+/// Which part of the configuration an export or import covers. Empty lists mean "all"; an
+/// artifact must match every filter that is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFilter {
+    /// Rule, command and skill ids
+    #[serde(default)]
+    pub ids: Vec<String>,
+    /// Selected artifacts carry at least one of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Selected rules have this scope; commands and skills have none and are unaffected
+    #[serde(default)]
+    pub scope: Option<Scope>,
+    /// Selected rules are enabled for at least one of these adapters
+    #[serde(default)]
+    pub adapters: Vec<AdapterType>,
+    #[serde(default = "default_true")]
+    pub include_rules: bool,
+    #[serde(default = "default_true")]
+    pub include_commands: bool,
+    #[serde(default = "default_true")]
+    pub include_skills: bool,
+    #[serde(default = "default_true")]
+    pub include_settings: bool,
+    /// Add the sync and reconciliation state; see [`ExportState`]
+    #[serde(default)]
+    pub include_state: bool,
+}
+
+impl Default for ExportFilter {
+    fn default() -> Self {
+        Self {
+            ids: Vec::new(),
+            tags: Vec::new(),
+            scope: None,
+            adapters: Vec::new(),
+            include_rules: true,
+            include_commands: true,
+            include_skills: true,
+            include_settings: true,
+            include_state: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ExportFilter {
+    /// Whether every rule, command and skill passes, so only settings can be left out.
+    fn selects_every_artifact(&self) -> bool {
+        self.ids.is_empty()
+            && self.tags.is_empty()
+            && self.scope.is_none()
+            && self.adapters.is_empty()
+            && self.include_rules
+            && self.include_commands
+            && self.include_skills
+    }
+
+    fn selects(&self, id: &str, metadata: &Metadata) -> bool {
+        (self.ids.is_empty() || self.ids.iter().any(|wanted| wanted == id))
+            && (self.tags.is_empty() || metadata.has_any_tag(&self.tags))
+    }
+
+    fn selects_rule(&self, rule: &Rule) -> bool {
+        self.include_rules
+            && self.selects(&rule.id, &rule.metadata)
+            && self.scope.is_none_or(|scope| rule.scope == scope)
+            && (self.adapters.is_empty()
+                || rule
+                    .enabled_adapters
+                    .iter()
+                    .any(|adapter| self.adapters.contains(adapter)))
+    }
+
+    /// Removes everything the filter does not select from `config`. Collection memberships
+    /// of removed artifacts go with them, and only the collections still holding selected
+    /// artifacts are kept, together with their parents.
+    pub fn apply(&self, config: &mut ExportConfiguration) {
+        if !self.include_settings {
+            config.settings.clear();
+        }
+        if !self.include_state {
+            config.state = None;
+        }
+        if self.selects_every_artifact() {
+            return;
+        }
+
+        config.rules.retain(|rule| self.selects_rule(rule));
+        config.commands.retain(|command| {
+            self.include_commands && self.selects(&command.id, &command.metadata)
+        });
+        config
+            .skills
+            .retain(|skill| self.include_skills && self.selects(&skill.id, &skill.metadata));
+
+        let kept: HashSet<(CollectionItemType, &str)> = config
+            .rules
+            .iter()
+            .map(|r| (CollectionItemType::Rule, r.id.as_str()))
+            .chain(
+                config
+                    .commands
+                    .iter()
+                    .map(|c| (CollectionItemType::Command, c.id.as_str())),
+            )
+            .chain(
+                config
+                    .skills
+                    .iter()
+                    .map(|s| (CollectionItemType::Skill, s.id.as_str())),
+            )
+            .collect();
+        let items: Vec<CollectionItem> = config
+            .collection_items
+            .iter()
+            .filter(|item| kept.contains(&(item.item_type, item.item_id.as_str())))
+            .cloned()
+            .collect();
+
+        let mut used: HashSet<String> = items.iter().map(|i| i.collection_id.clone()).collect();
+        loop {
+            let parents: Vec<String> = config
+                .collections
+                .iter()
+                .filter(|c| used.contains(&c.id))
+                .filter_map(|c| c.parent_id.clone())
+                .filter(|parent| !used.contains(parent))
+                .collect();
+            if parents.is_empty() {
+                break;
+            }
+            used.extend(parents);
+        }
+        config.collections.retain(|c| used.contains(&c.id));
+        config.collection_items = items;
+
+        if let Some(state) = config.state.as_mut() {
+            let rules: HashSet<&str> = config.rules.iter().map(|r| r.id.as_str()).collect();
+            state
+                .rule_files
+                .retain(|file| rules.contains(file.rule_id.as_str()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TAGS_METADATA_KEY;
+    use chrono::Utc;
+
+    fn rule(id: &str, scope: Scope, adapter: AdapterType, tag: &str) -> Rule {
+        let mut metadata = Metadata::new();
+        metadata.insert(TAGS_METADATA_KEY, vec![tag]);
+        Rule {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            content: "Content".to_string(),
+            scope,
+            target_paths: None,
+            enabled_adapters: vec![adapter],
+            enabled: true,
+            metadata,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn collection(id: &str, parent_id: Option<&str>) -> Collection {
+        Collection {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn export() -> ExportConfiguration {
+        let command = Command::new(
+            "deploy".to_string(),
+            String::new(),
+            "make deploy".to_string(),
+            false,
+        );
+        ExportConfiguration::new(
+            vec![
+                rule("style", Scope::Global, AdapterType::ClaudeCode, "frontend"),
+                rule("api", Scope::Local, AdapterType::Gemini, "backend"),
+            ],
+            vec![command],
+            Vec::new(),
+        )
+        .with_collections(
+            vec![
+                collection("team", None),
+                collection("web", Some("team")),
+                collection("other", None),
+            ],
+            vec![
+                CollectionItem {
+                    collection_id: "web".to_string(),
+                    item_type: CollectionItemType::Rule,
+                    item_id: "style".to_string(),
+                    position: 0,
+                },
+                CollectionItem {
+                    collection_id: "other".to_string(),
+                    item_type: CollectionItemType::Rule,
+                    item_id: "api".to_string(),
+                    position: 0,
+                },
+            ],
+        )
+        .with_settings(HashMap::from([(
+            "mcp_auto_start".to_string(),
+            "true".to_string(),
+        )]))
+    }
+
+    #[test]
+    fn test_only_listed_settings_are_portable() {
+        assert!(is_portable_setting("redaction_patterns"));
+        for key in [
+            "slash_command_roots",
+            "reconcile_protected_paths",
+            "adapter_settings",
+            "mcp_secrets_allowlist",
+            "export_snapshot_enabled",
+            "rule_catalog_url",
+            "ai_api_key_secret",
+            "some_future_setting",
+        ] {
+            assert!(!is_portable_setting(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_default_filter_keeps_everything() {
+        let mut config = export();
+        ExportFilter::default().apply(&mut config);
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.commands.len(), 1);
+        assert_eq!(config.collections.len(), 3);
+        assert_eq!(config.settings.len(), 1);
+    }
+
+    #[test]
+    fn test_filter_selects_rules_and_prunes_collections() {
+        let mut config = export();
+        ExportFilter {
+            tags: vec!["Frontend".to_string()],
+            adapters: vec![AdapterType::ClaudeCode],
+            include_commands: false,
+            include_settings: false,
+            ..ExportFilter::default()
+        }
+        .apply(&mut config);
+
+        assert_eq!(config.rules.len(), 1);
+        assert_eq!(config.rules[0].id, "style");
+        assert!(config.commands.is_empty());
+        assert!(config.settings.is_empty());
+        let collections: Vec<&str> = config.collections.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(collections, vec!["team", "web"]);
+        assert_eq!(config.collection_items.len(), 1);
+
+        let mut config = export();
+        ExportFilter {
+            scope: Some(Scope::Local),
+            ..ExportFilter::default()
         }
+        .apply(&mut config);
+        assert_eq!(config.rules.len(), 1);
+        assert_eq!(config.rules[0].id, "api");
+        assert_eq!(config.commands.len(), 1);
     }
 }
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::models::{AdapterType, Command, Rule, Scope, Skill};
+use crate::models::{AdapterType, Command, DiffLine, DiffSummary, Metadata, Rule, Scope, Skill};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -11,6 +13,9 @@ pub enum ImportSourceType {
     Directory,
     Url,
     Clipboard,
+    Archive,
+    /// Another RuleWeaver instance sharing its configuration over the local network
+    Peer,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -49,6 +54,22 @@ pub struct ImportCandidate {
     pub file_size: u64,
     #[serde(default)]
     pub artifact_type: ImportArtifactType,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub metadata: Metadata,
+    /// Name of the collection the imported artifact is filed into
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// `allowed-tools` from the frontmatter of an imported SKILL.md
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// `license` from the frontmatter of an imported SKILL.md
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Adapters in `enabled_adapters` whose tool is not installed on this machine
+    #[serde(default)]
+    pub uninstalled_adapters: Vec<AdapterType>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +92,9 @@ pub struct ImportConflict {
     pub existing_id: Option<String>,
     pub existing_name: Option<String>,
     pub reason: String,
+    /// Percentage similarity when the conflict is a near-duplicate of an existing artifact
+    #[serde(default)]
+    pub similarity: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -102,6 +126,9 @@ pub struct ImportExecutionOptions {
     pub default_adapters: Option<Vec<AdapterType>>,
     pub selected_candidate_ids: Option<Vec<String>>,
     pub max_file_size_bytes: Option<u64>,
+    /// Import candidates even when they closely match existing content
+    #[serde(default)]
+    pub allow_near_duplicates: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,3 +143,373 @@ pub struct ImportHistoryEntry {
     pub conflict_count: usize,
     pub error_count: usize,
 }
+
+/// Where a subscribed rule library is fetched from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionSourceKind {
+    /// A single raw file URL
+    Url,
+    /// A Git repository, cloned shallowly on each check
+    Git,
+    /// A GitHub Gist; every supported file in it is imported
+    Gist,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionUpdateMode {
+    /// Queue upstream changes until the user approves them
+    #[default]
+    Approve,
+    /// Apply upstream changes as soon as they are fetched
+    Auto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSubscription {
+    pub id: String,
+    pub name: String,
+    pub kind: SubscriptionSourceKind,
+    pub url: String,
+    /// Branch or tag for Git sources; the remote default branch when unset.
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    /// Directory inside a Git repository to import from.
+    #[serde(default)]
+    pub subpath: Option<String>,
+    #[serde(default)]
+    pub update_mode: SubscriptionUpdateMode,
+    pub interval_hours: u32,
+    pub enabled: bool,
+    #[serde(default)]
+    pub options: ImportExecutionOptions,
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_checked_at: Option<DateTime<Utc>>,
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Upstream changes awaiting approval.
+    #[serde(default)]
+    pub pending: Vec<ImportCandidate>,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateImportSubscriptionInput {
+    pub name: Option<String>,
+    pub kind: SubscriptionSourceKind,
+    pub url: String,
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    #[serde(default)]
+    pub subpath: Option<String>,
+    #[serde(default)]
+    pub update_mode: SubscriptionUpdateMode,
+    pub interval_hours: Option<u32>,
+    #[serde(default)]
+    pub options: Option<ImportExecutionOptions>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionCheckResult {
+    pub subscription_id: String,
+    /// Upstream items with no imported artifact yet
+    pub new_count: usize,
+    /// Upstream items whose content differs from the mapped artifact
+    pub changed_count: usize,
+    pub unchanged_count: usize,
+    /// Items queued for approval after this check
+    pub pending_count: usize,
+    /// Set when changes were applied during this check
+    pub import: Option<ImportExecutionResult>,
+    pub errors: Vec<String>,
+}
+
+/// Artifacts to include in an exported zip bundle.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSelection {
+    #[serde(default)]
+    pub rule_ids: Vec<String>,
+    #[serde(default)]
+    pub command_ids: Vec<String>,
+    #[serde(default)]
+    pub skill_ids: Vec<String>,
+}
+
+/// How approved local changes reach the team repository.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamRepoPushMode {
+    /// Commit straight to the tracked branch
+    #[default]
+    Commit,
+    /// Commit to a new `ruleweaver/...` branch to open a pull request from
+    Branch,
+}
+
+/// A Git repository holding the team's canonical rules, commands and skills, in the
+/// layout of an exported bundle (`rules/`, `commands/`, `skills/<name>/`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRepo {
+    pub url: String,
+    pub branch: String,
+    /// Directory inside the repository holding the library.
+    #[serde(default)]
+    pub subpath: Option<String>,
+    #[serde(default)]
+    pub push_mode: TeamRepoPushMode,
+    pub interval_hours: u32,
+    pub enabled: bool,
+    #[serde(default)]
+    pub options: ImportExecutionOptions,
+    /// Commit of the last successful pull.
+    #[serde(default)]
+    pub last_commit: Option<String>,
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_pulled_at: Option<DateTime<Utc>>,
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Library files linked to local artifacts, keyed by path relative to the library root
+    #[serde(default)]
+    pub files: HashMap<String, TeamRepoFile>,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// The local artifact a library file was pulled into or pushed from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRepoFile {
+    pub artifact_type: ImportArtifactType,
+    pub artifact_id: String,
+    /// Content hash as last pulled or pushed; tells local edits apart from upstream ones
+    pub content_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigureTeamRepoInput {
+    pub url: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub subpath: Option<String>,
+    #[serde(default)]
+    pub push_mode: TeamRepoPushMode,
+    pub interval_hours: Option<u32>,
+    #[serde(default)]
+    pub options: Option<ImportExecutionOptions>,
+}
+
+/// A library file whose local artifact differs from the version last pulled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRepoChange {
+    pub path: String,
+    pub artifact_type: ImportArtifactType,
+    pub artifact_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRepoPullResult {
+    pub commit: Option<String>,
+    /// Library items with no local artifact yet
+    pub new_count: usize,
+    /// Library items changed upstream and updated locally
+    pub changed_count: usize,
+    pub unchanged_count: usize,
+    /// Items edited locally only; they are kept and can be pushed
+    pub local_changes: Vec<TeamRepoChange>,
+    /// Items edited both locally and upstream; neither side is overwritten
+    pub conflicts: Vec<TeamRepoChange>,
+    pub import: Option<ImportExecutionResult>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRepoPushInput {
+    #[serde(flatten)]
+    pub selection: BundleSelection,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRepoPushResult {
+    pub commit: String,
+    /// Branch the commit was pushed to
+    pub branch: String,
+    /// Library files written, relative to the library root
+    pub files: Vec<String>,
+    /// Where to open a pull request, for branch pushes to GitHub
+    pub pull_request_url: Option<String>,
+}
+
+/// One installable item listed in a rule catalog index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogEntry {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Raw content URL (or a gist/GitHub blob URL) fetched on preview and install
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub artifact_type: ImportArtifactType,
+    /// Set when this entry was installed from the current catalog
+    #[serde(default)]
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogIndex {
+    #[serde(default)]
+    pub entries: Vec<CatalogEntry>,
+}
+
+/// Provenance of artifacts installed from a catalog entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogInstall {
+    pub entry_id: String,
+    pub entry_name: String,
+    pub catalog_url: String,
+    pub source_url: String,
+    pub artifact_ids: Vec<String>,
+    #[serde(with = "crate::models::timestamp")]
+    pub installed_at: DateTime<Utc>,
+}
+
+/// What executing an import would do with a candidate.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportPreviewAction {
+    /// A new artifact is created
+    Create,
+    /// The artifact previously imported from the same source is updated
+    Update,
+    /// An existing artifact with the same name is overwritten
+    Replace,
+    /// A new artifact is created under a de-duplicated name
+    Rename,
+    /// Nothing happens; see `reason`
+    Skip,
+    /// A same-named artifact blocks the import in skip mode
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCandidatePreview {
+    pub candidate_id: String,
+    pub artifact_type: ImportArtifactType,
+    pub action: ImportPreviewAction,
+    /// Name the artifact will have after import
+    pub name: String,
+    pub existing_id: Option<String>,
+    pub existing_name: Option<String>,
+    pub reason: Option<String>,
+    /// Percentage similarity when the candidate is a near-duplicate of `existing_name`
+    pub similarity: Option<u8>,
+    /// Line diff from the existing content to the candidate content; for new artifacts
+    /// every line is an addition
+    pub diff: Vec<DiffLine>,
+    pub summary: DiffSummary,
+}
+
+/// Defaults applied to directory-scan candidates whose path, relative to the scanned
+/// directory, matches `pattern`. Rules apply in order: later matches override scope,
+/// adapters and group, while tags accumulate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMappingRule {
+    /// Glob such as `frontend/**` or `**/*.rules.md`
+    pub pattern: String,
+    #[serde(default)]
+    pub scope: Option<Scope>,
+    #[serde(default)]
+    pub adapters: Option<Vec<AdapterType>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Collection to file matching artifacts into, created on import if missing
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// An active share that lets another RuleWeaver instance on the local network pull this
+/// machine's configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerShareInfo {
+    /// `host:port` addresses the receiving machine can connect to
+    pub addresses: Vec<String>,
+    pub port: u16,
+    /// One-time code the receiver must present
+    pub code: String,
+    #[serde(with = "crate::models::timestamp")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Which rules and commands a read-only share shows. Empty lists mean "all"; an artifact
+/// must match both filters to be shown.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLinkSelection {
+    /// Shown artifacts carry at least one of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Shown artifacts are filed in one of these collections or their subcollections
+    #[serde(default)]
+    pub collection_ids: Vec<String>,
+    #[serde(default = "default_true")]
+    pub include_rules: bool,
+    #[serde(default = "default_true")]
+    pub include_commands: bool,
+}
+
+impl Default for ShareLinkSelection {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            collection_ids: Vec::new(),
+            include_rules: true,
+            include_commands: true,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// An active read-only share: a link a colleague can open in a browser.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLinkInfo {
+    /// Links to the snapshot, one per local network address
+    pub urls: Vec<String>,
+    pub port: u16,
+    pub rule_count: usize,
+    pub command_count: usize,
+    #[serde(with = "crate::models::timestamp")]
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse_error::ParseEnumError;
+
+/// A long operation that records its progress in the job journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Import,
+    /// Moving rules from the database into rule files
+    Migration,
+    Reconcile,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Import => "import",
+            Self::Migration => "migration",
+            Self::Reconcile => "reconcile",
+        }
+    }
+
+    /// Whether the work done so far can be undone. Reconciliation writes tool files from
+    /// the stored rules and keeps no earlier copies, so it can only be run again.
+    pub fn can_roll_back(&self) -> bool {
+        !matches!(self, Self::Reconcile)
+    }
+}
+
+impl FromStr for JobKind {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "import" => Ok(Self::Import),
+            "migration" => Ok(Self::Migration),
+            "reconcile" => Ok(Self::Reconcile),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    /// The app exited before the job finished
+    Interrupted,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(Self::Running),
+            "interrupted" => Ok(Self::Interrupted),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// A journal entry. Finished jobs are removed, so every entry is either running now or
+/// was cut short.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRecord {
+    pub id: String,
+    pub kind: JobKind,
+    pub description: String,
+    pub status: JobStatus,
+    /// Steps completed at the last checkpoint
+    pub completed: u32,
+    pub total: u32,
+    pub can_roll_back: bool,
+    #[serde(with = "crate::models::timestamp")]
+    pub started_at: DateTime<Utc>,
+    /// Time of the last checkpoint
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
+    /// What the job needs to start again, as written when it began
+    #[serde(skip)]
+    pub payload: serde_json::Value,
+    /// What the job had done at the last checkpoint
+    #[serde(skip)]
+    pub checkpoint: serde_json::Value,
+}
+
+/// What to do with an interrupted job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobResolution {
+    /// Run the remaining work
+    Resume,
+    /// Undo the work done before the interruption
+    RollBack,
+    /// Forget the job and keep whatever it did
+    Discard,
+}
@@ -0,0 +1,240 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Command, HasMetadata, MetadataQuery, Rule, Skill};
+use crate::error::{AppError, Result};
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Rules, commands and skills as the list views page through them.
+pub trait Listable: HasMetadata {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn created_at(&self) -> DateTime<Utc>;
+    fn updated_at(&self) -> DateTime<Utc>;
+}
+
+macro_rules! impl_listable {
+    ($($artifact:ty),*) => {$(
+        impl Listable for $artifact {
+            fn id(&self) -> &str {
+                &self.id
+            }
+            fn name(&self) -> &str {
+                &self.name
+            }
+            fn description(&self) -> &str {
+                &self.description
+            }
+            fn created_at(&self) -> DateTime<Utc> {
+                self.created_at
+            }
+            fn updated_at(&self) -> DateTime<Utc> {
+                self.updated_at
+            }
+        }
+    )*};
+}
+
+impl_listable!(Rule, Command, Skill);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactSort {
+    /// Case-insensitive
+    #[default]
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// One page of a filtered, sorted artifact list. Pages follow either `offset` or `cursor`,
+/// the `nextCursor` of the previous page; a cursor stays valid while items are added or
+/// removed in front of it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArtifactListQuery {
+    /// Case-insensitive text to find in the name or description
+    pub search: Option<String>,
+    /// Items tagged with any of these in their `tags` metadata
+    pub tags: Vec<String>,
+    pub metadata_query: Option<MetadataQuery>,
+    pub sort_by: ArtifactSort,
+    pub descending: bool,
+    pub offset: Option<u32>,
+    pub cursor: Option<String>,
+    /// Defaults to 100, at most 1000
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactPage<T> {
+    pub items: Vec<T>,
+    /// Items matching the filters, across all pages
+    pub total: u32,
+    /// Pass as `cursor` for the page after this one; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Where an item sorts: its sort value, then its id to break ties.
+type SortKey = (String, String);
+
+impl ArtifactListQuery {
+    fn matches<T: Listable>(&self, item: &T, search: Option<&str>) -> bool {
+        let found = search.is_none_or(|needle| {
+            item.name().to_lowercase().contains(needle)
+                || item.description().to_lowercase().contains(needle)
+        });
+        found && (self.tags.is_empty() || item.metadata().has_any_tag(&self.tags))
+    }
+
+    fn sort_key<T: Listable>(&self, item: &T) -> SortKey {
+        let value = match self.sort_by {
+            ArtifactSort::Name => item.name().to_lowercase(),
+            // Zero-padded so the strings order like the timestamps
+            ArtifactSort::CreatedAt => format!("{:020}", item.created_at().timestamp_millis()),
+            ArtifactSort::UpdatedAt => format!("{:020}", item.updated_at().timestamp_millis()),
+        };
+        (value, item.id().to_string())
+    }
+
+    fn compare(&self, a: &SortKey, b: &SortKey) -> Ordering {
+        let ordering = a.cmp(b);
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// The page of `items` this query asks for.
+    pub fn apply<T: Listable>(&self, items: Vec<T>) -> Result<ArtifactPage<T>> {
+        let items = match &self.metadata_query {
+            Some(query) => query.apply(items),
+            None => items,
+        };
+        let search = self
+            .search
+            .as_deref()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty());
+        let mut keyed: Vec<(SortKey, T)> = items
+            .into_iter()
+            .filter(|item| self.matches(item, search.as_deref()))
+            .map(|item| (self.sort_key(&item), item))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| self.compare(a, b));
+        let total = keyed.len() as u32;
+
+        let start = match &self.cursor {
+            Some(cursor) => {
+                let after: SortKey =
+                    serde_json::from_str(cursor).map_err(|_| AppError::InvalidInput {
+                        message: "Invalid page cursor".to_string(),
+                    })?;
+                keyed.partition_point(|(key, _)| self.compare(key, &after) != Ordering::Greater)
+            }
+            None => self.offset.unwrap_or(0) as usize,
+        };
+        let limit = self
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE) as usize;
+        let end = start.saturating_add(limit).min(keyed.len());
+
+        let next_cursor = (end < keyed.len() && start < end)
+            .then(|| serde_json::to_string(&keyed[end - 1].0).unwrap_or_default());
+        let items = keyed
+            .into_iter()
+            .skip(start)
+            .take(limit)
+            .map(|(_, item)| item)
+            .collect();
+        Ok(ArtifactPage {
+            items,
+            total,
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Scope, TAGS_METADATA_KEY};
+
+    fn rule(name: &str, description: &str, tags: &[&str]) -> Rule {
+        let mut rule = Rule::new(
+            name.to_string(),
+            description.to_string(),
+            String::new(),
+            Scope::Global,
+        );
+        rule.id = format!("id-{}", name.to_lowercase());
+        if !tags.is_empty() {
+            rule.metadata.insert(TAGS_METADATA_KEY, tags.to_vec());
+        }
+        rule
+    }
+
+    fn names(page: &ArtifactPage<Rule>) -> Vec<&str> {
+        page.items.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filters_and_pages_by_offset() {
+        let rules = vec![
+            rule("Style", "formatting", &["frontend"]),
+            rule("api", "REST conventions", &["backend"]),
+            rule("Testing", "", &["Backend", "qa"]),
+        ];
+        let query = ArtifactListQuery {
+            tags: vec!["backend".to_string()],
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let page = query.apply(rules.clone()).unwrap();
+        assert_eq!((names(&page), page.total), (vec!["Testing"], 2));
+
+        let query = ArtifactListQuery {
+            search: Some(" rest ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(names(&query.apply(rules).unwrap()), vec!["api"]);
+    }
+
+    #[test]
+    fn test_cursor_survives_removed_items() {
+        let rules: Vec<Rule> = ["A", "B", "C", "D", "E"]
+            .iter()
+            .map(|name| rule(name, "", &[]))
+            .collect();
+        let mut query = ArtifactListQuery {
+            descending: true,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first = query.apply(rules.clone()).unwrap();
+        assert_eq!(names(&first), vec!["E", "D"]);
+
+        // "D", the last item of the page, is gone before the next page is asked for.
+        let remaining: Vec<Rule> = rules.into_iter().filter(|r| r.name != "D").collect();
+        query.cursor = first.next_cursor;
+        let second = query.apply(remaining.clone()).unwrap();
+        assert_eq!(names(&second), vec!["C", "B"]);
+
+        query.cursor = second.next_cursor;
+        let last = query.apply(remaining).unwrap();
+        assert_eq!(names(&last), vec!["A"]);
+        assert!(last.next_cursor.is_none());
+
+        query.cursor = Some("not a cursor".to_string());
+        assert!(query.apply(Vec::<Rule>::new()).is_err());
+    }
+}
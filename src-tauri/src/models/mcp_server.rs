@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse_error::ParseEnumError;
+use super::AdapterType;
+
+/// How a tool reaches an MCP server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransport {
+    /// A local process started from `command` and `args`
+    #[default]
+    Stdio,
+    /// A remote server reached at `url`
+    Http,
+}
+
+impl McpTransport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stdio => "stdio",
+            Self::Http => "http",
+        }
+    }
+}
+
+impl FromStr for McpTransport {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stdio" => Ok(Self::Stdio),
+            "http" => Ok(Self::Http),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// Launch or connection settings for an MCP server, as written into tool configs.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerSpec {
+    #[serde(default)]
+    pub transport: McpTransport,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// A third-party MCP server managed by RuleWeaver and synced into each enabled tool's
+/// MCP configuration. Distinct from RuleWeaver's own MCP server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalMcpServer {
+    pub id: String,
+    /// Key the server is registered under in tool configs
+    pub name: String,
+    #[serde(flatten)]
+    pub spec: McpServerSpec,
+    pub enabled_adapters: Vec<AdapterType>,
+    pub enabled: bool,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExternalMcpServerInput {
+    pub name: String,
+    #[serde(flatten)]
+    pub spec: McpServerSpec,
+    #[serde(default)]
+    pub enabled_adapters: Vec<AdapterType>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateExternalMcpServerInput {
+    pub name: Option<String>,
+    pub spec: Option<McpServerSpec>,
+    pub enabled_adapters: Option<Vec<AdapterType>>,
+    pub enabled: Option<bool>,
+}
+
+/// An MCP server found in a tool's configuration file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerCandidate {
+    pub id: String,
+    pub name: String,
+    pub source_tool: AdapterType,
+    pub source_path: String,
+    #[serde(flatten)]
+    pub spec: McpServerSpec,
+    /// Managed server with the same name, if one exists
+    pub existing_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerScanResult {
+    pub candidates: Vec<McpServerCandidate>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerSyncResult {
+    pub files_written: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_round_trips_through_strings() {
+        for transport in [McpTransport::Stdio, McpTransport::Http] {
+            assert_eq!(McpTransport::from_str(transport.as_str()), Ok(transport));
+        }
+        assert!(McpTransport::from_str("sse").is_err());
+    }
+}
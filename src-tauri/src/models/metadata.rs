@@ -0,0 +1,341 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Command, Rule, Skill};
+
+/// Metadata key holding an artifact's tags, either an array of strings or a single string.
+pub const TAGS_METADATA_KEY: &str = "tags";
+
+/// Free-form key/value fields attached to a rule, command or skill (owner, ticket link,
+/// review date, ...). Stored as a JSON object so new fields need no schema change.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Metadata(BTreeMap<String, Value>);
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the stored JSON column, treating malformed data as empty.
+    pub fn from_json(raw: &str) -> Self {
+        serde_json::from_str(raw).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to parse metadata JSON: {}. Falling back to empty.",
+                e
+            );
+            Self::default()
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Value::as_i64)
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(Value::as_f64)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key).and_then(Value::as_bool)
+    }
+
+    /// Reads a `YYYY-MM-DD` string value, such as a review date.
+    pub fn get_date(&self, key: &str) -> Option<NaiveDate> {
+        self.get_str(key)
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.0.remove(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.0.iter()
+    }
+
+    /// The artifact's tags; a single string counts as one tag.
+    pub fn tags(&self) -> Vec<&str> {
+        match self.get(TAGS_METADATA_KEY) {
+            Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+            Some(Value::String(tag)) => vec![tag.as_str()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether any tag matches one of `wanted`, ignoring ASCII case and surrounding
+    /// whitespace.
+    pub fn has_any_tag(&self, wanted: &[String]) -> bool {
+        self.tags()
+            .iter()
+            .any(|tag| wanted.iter().any(|w| w.trim().eq_ignore_ascii_case(tag)))
+    }
+}
+
+/// Artifacts that carry [`Metadata`].
+pub trait HasMetadata {
+    fn metadata(&self) -> &Metadata;
+}
+
+impl HasMetadata for Rule {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+impl HasMetadata for Command {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+impl HasMetadata for Skill {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataFilterOp {
+    /// The key is present, whatever its value
+    #[default]
+    Exists,
+    Equals,
+    NotEquals,
+    /// Case-insensitive substring for strings, membership for arrays
+    Contains,
+    /// Strictly less than `value`; ISO dates compare chronologically
+    Before,
+    /// Strictly greater than `value`
+    After,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataFilter {
+    pub key: String,
+    #[serde(default)]
+    pub op: MetadataFilterOp,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+impl MetadataFilter {
+    fn matches(&self, metadata: &Metadata) -> bool {
+        let actual = metadata.get(&self.key);
+        match self.op {
+            MetadataFilterOp::Exists => actual.is_some(),
+            MetadataFilterOp::Equals => actual.is_some() && actual == self.value.as_ref(),
+            MetadataFilterOp::NotEquals => actual != self.value.as_ref(),
+            MetadataFilterOp::Contains => match (actual, self.value.as_ref()) {
+                (Some(Value::String(haystack)), Some(Value::String(needle))) => {
+                    haystack.to_lowercase().contains(&needle.to_lowercase())
+                }
+                (Some(Value::Array(items)), Some(needle)) => items.contains(needle),
+                _ => false,
+            },
+            MetadataFilterOp::Before => {
+                compare_values(actual, self.value.as_ref()) == Some(Ordering::Less)
+            }
+            MetadataFilterOp::After => {
+                compare_values(actual, self.value.as_ref()) == Some(Ordering::Greater)
+            }
+        }
+    }
+}
+
+/// Filters and sorts list results by metadata. All filters must match; items missing the
+/// sort key are placed last regardless of direction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataQuery {
+    #[serde(default)]
+    pub filters: Vec<MetadataFilter>,
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+impl MetadataQuery {
+    pub fn apply<T: HasMetadata>(&self, items: Vec<T>) -> Vec<T> {
+        let mut items: Vec<T> = items
+            .into_iter()
+            .filter(|item| self.filters.iter().all(|f| f.matches(item.metadata())))
+            .collect();
+
+        if let Some(key) = &self.sort_by {
+            items.sort_by(
+                |a, b| match (a.metadata().get(key), b.metadata().get(key)) {
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                    (left, right) => {
+                        let ordering = compare_values(left, right).unwrap_or(Ordering::Equal);
+                        if self.descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        }
+                    }
+                },
+            );
+        }
+
+        items
+    }
+}
+
+/// Orders two JSON values of the same kind. Numbers compare numerically and strings
+/// lexically (which also orders ISO-8601 dates); mixed kinds are incomparable.
+fn compare_values(left: Option<&Value>, right: Option<&Value>) -> Option<Ordering> {
+    match (left?, right?) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Item(Metadata);
+
+    impl HasMetadata for Item {
+        fn metadata(&self) -> &Metadata {
+            &self.0
+        }
+    }
+
+    fn item(owner: &str, review: Option<&str>) -> Item {
+        let mut metadata = Metadata::new();
+        metadata.insert("owner", owner);
+        if let Some(review) = review {
+            metadata.insert("reviewDate", review);
+        }
+        Item(metadata)
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let metadata: Metadata = serde_json::from_value(json!({
+            "owner": "platform",
+            "priority": 2,
+            "reviewDate": "2026-03-01",
+            "archived": false
+        }))
+        .unwrap();
+
+        assert_eq!(metadata.get_str("owner"), Some("platform"));
+        assert_eq!(metadata.get_i64("priority"), Some(2));
+        assert_eq!(metadata.get_bool("archived"), Some(false));
+        assert_eq!(
+            metadata.get_date("reviewDate"),
+            NaiveDate::from_ymd_opt(2026, 3, 1)
+        );
+        assert_eq!(metadata.get_date("owner"), None);
+        assert_eq!(Metadata::from_json(&metadata.to_json()), metadata);
+        assert!(Metadata::from_json("not json").is_empty());
+    }
+
+    #[test]
+    fn test_tags_accept_a_list_or_a_single_string() {
+        let list: Metadata = serde_json::from_value(json!({ "tags": ["Frontend", 3] })).unwrap();
+        assert_eq!(list.tags(), vec!["Frontend"]);
+        assert!(list.has_any_tag(&[" frontend ".to_string()]));
+        assert!(!list.has_any_tag(&[]));
+
+        let single: Metadata = serde_json::from_value(json!({ "tags": "docs" })).unwrap();
+        assert!(single.has_any_tag(&["DOCS".to_string()]));
+        assert!(Metadata::new().tags().is_empty());
+    }
+
+    #[test]
+    fn test_query_filters_and_sorts() {
+        let items = vec![
+            item("web", Some("2026-05-01")),
+            item("platform", Some("2026-01-15")),
+            item("platform", None),
+        ];
+
+        let query = MetadataQuery {
+            filters: vec![MetadataFilter {
+                key: "owner".to_string(),
+                op: MetadataFilterOp::Equals,
+                value: Some(json!("platform")),
+            }],
+            sort_by: None,
+            descending: false,
+        };
+        assert_eq!(query.apply(items).len(), 2);
+
+        let items = vec![
+            item("a", None),
+            item("b", Some("2026-05-01")),
+            item("c", Some("2026-01-15")),
+        ];
+        let query = MetadataQuery {
+            filters: Vec::new(),
+            sort_by: Some("reviewDate".to_string()),
+            descending: true,
+        };
+        let owners: Vec<_> = query
+            .apply(items)
+            .iter()
+            .map(|i| i.0.get_str("owner").unwrap().to_string())
+            .collect();
+        assert_eq!(owners, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_before_filter_finds_overdue_reviews() {
+        let items = vec![
+            item("a", Some("2026-01-15")),
+            item("b", Some("2026-09-01")),
+            item("c", None),
+        ];
+        let query = MetadataQuery {
+            filters: vec![MetadataFilter {
+                key: "reviewDate".to_string(),
+                op: MetadataFilterOp::Before,
+                value: Some(json!("2026-06-01")),
+            }],
+            ..Default::default()
+        };
+        let result = query.apply(items);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0.get_str("owner"), Some("a"));
+    }
+}
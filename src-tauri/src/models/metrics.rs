@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSnapshot {
+    /// Unix seconds when the registry started counting (app start)
+    pub since: i64,
+    pub counters: Vec<CounterSample>,
+    pub histograms: Vec<HistogramSample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterSample {
+    pub name: String,
+    pub help: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramSample {
+    pub name: String,
+    pub help: String,
+    pub labels: BTreeMap<String, String>,
+    pub count: u64,
+    /// Total of all observations, in seconds
+    pub sum: f64,
+    /// Cumulative counts per upper bound, as in Prometheus
+    pub buckets: Vec<HistogramBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBucket {
+    pub le: f64,
+    pub count: u64,
+}
@@ -1,17 +1,59 @@
+mod ai;
+mod app_log;
+mod audit;
+mod cloud_sync;
+mod collection;
 mod command;
 mod config;
 mod import;
+mod job;
+mod listing;
+mod mcp_server;
+mod metadata;
+mod metrics;
 mod parse_error;
+mod policy;
+mod project_config;
 pub mod reconciliation;
 pub mod registry;
 mod rule;
+mod rule_stats;
+mod rule_template;
+mod sensitive;
+mod settings;
 mod skill;
+mod snippet;
+mod sync_report;
 pub mod timestamp;
+mod webhook;
+mod workflow;
+mod workspace;
 
+pub use ai::*;
+pub use app_log::*;
+pub use audit::*;
+pub use cloud_sync::*;
+pub use collection::*;
 pub use command::*;
 pub use config::*;
 pub use import::*;
+pub use job::*;
+pub use listing::*;
+pub use mcp_server::*;
+pub use metadata::*;
+pub use metrics::*;
 pub use parse_error::ParseEnumError;
+pub use policy::*;
+pub use project_config::*;
 pub use reconciliation::*;
 pub use rule::*;
+pub use rule_stats::*;
+pub use rule_template::*;
+pub use sensitive::*;
+pub use settings::*;
 pub use skill::*;
+pub use snippet::*;
+pub use sync_report::*;
+pub use webhook::*;
+pub use workflow::*;
+pub use workspace::*;
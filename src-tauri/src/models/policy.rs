@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Parts of an org-managed artifact that users may still change on their machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyOverride {
+    /// Target paths and base path
+    TargetPaths,
+    /// The tools the artifact is written to
+    Adapters,
+    /// Metadata fields other than the managed marker
+    Metadata,
+}
+
+/// Import sources whose artifacts are managed by the organization. Managed artifacts can't
+/// be disabled, deleted or edited locally except for the allowed overrides, and
+/// reconciliation always writes them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrgPolicy {
+    /// URLs of managed sources, such as the team repository or a subscription. A source
+    /// also covers everything beneath it.
+    #[serde(default)]
+    pub sources: Vec<String>,
+    #[serde(default)]
+    pub allowed_overrides: Vec<PolicyOverride>,
+}
@@ -0,0 +1,28 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A repository's `.ruleweaver.toml`: which local rules are written into it, for which tools,
+/// in which language, and the values of the `{{variables}}` in their content.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub rules: ProjectRuleSelection,
+    /// Adapter ids such as `claude-code`. When set, every selected rule is written for
+    /// these tools in this repository instead of the rule's own adapters.
+    pub adapters: Option<Vec<String>>,
+    /// Language code of the rule variants written into this repository, such as `ja`.
+    /// Rules without that variant are written in their default content.
+    pub language: Option<String>,
+    pub variables: BTreeMap<String, String>,
+}
+
+/// Rules listed by name or id in `include`, or carrying one of `tags`, are selected; with
+/// neither set every rule targeting the repository is. `exclude` always wins.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectRuleSelection {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub tags: Vec<String>,
+}
@@ -69,6 +69,46 @@ pub struct ToolEntry {
     pub file_format: &'static str,
     pub slash_command_extension: Option<&'static str>,
     pub slash_command_argument_pattern: Option<&'static str>,
+    /// Whether namespaced slash commands map to nested folders (`git/commit.md`) rather than
+    /// prefixed filenames (`git-commit.md`).
+    pub slash_command_nested_dirs: bool,
+}
+
+/// A registry entry with whether the tool was detected on this machine.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredTool {
+    #[serde(flatten)]
+    pub entry: ToolEntry,
+    pub installed: bool,
+}
+
+/// Evidence that a tool is installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInstallation {
+    pub adapter: AdapterType,
+    pub installed: bool,
+    /// Config directories of the tool that exist
+    pub config_dirs: Vec<String>,
+    /// Executable of the tool found on `PATH`
+    pub binary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledTools {
+    pub tools: Vec<ToolInstallation>,
+    #[serde(with = "crate::models::timestamp")]
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl InstalledTools {
+    pub fn is_installed(&self, adapter: AdapterType) -> bool {
+        self.tools
+            .iter()
+            .any(|tool| tool.adapter == adapter && tool.installed)
+    }
 }
 
 pub struct ToolRegistry {
@@ -111,6 +151,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -136,6 +177,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("toml"),
                 slash_command_argument_pattern: Some("{{args}}"),
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -161,6 +203,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: Some("$ARGUMENTS"),
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -186,6 +229,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -211,6 +255,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: Some("$ARGUMENTS"),
+                slash_command_nested_dirs: true,
             },
         );
 
@@ -236,6 +281,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -270,6 +316,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: None,
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -302,6 +349,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -336,6 +384,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: None,
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -361,6 +410,7 @@ impl ToolRegistry {
                 file_format: "markdown",
                 slash_command_extension: Some("md"),
                 slash_command_argument_pattern: None,
+                slash_command_nested_dirs: false,
             },
         );
 
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::parse_error::ParseEnumError;
+use super::Metadata;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -114,6 +115,8 @@ pub struct Rule {
     pub target_paths: Option<Vec<String>>,
     pub enabled_adapters: Vec<AdapterType>,
     pub enabled: bool,
+    #[serde(default)]
+    pub metadata: Metadata,
     #[serde(with = "crate::models::timestamp")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "crate::models::timestamp")]
@@ -129,6 +132,9 @@ pub struct SyncHistoryEntry {
     pub files_written: u32,
     pub status: String,
     pub triggered_by: String,
+    /// Whether a structured report can be fetched with `get_sync_report`
+    #[serde(default)]
+    pub has_report: bool,
 }
 
 impl Rule {
@@ -144,6 +150,7 @@ impl Rule {
             target_paths: None,
             enabled_adapters: vec![AdapterType::Gemini, AdapterType::OpenCode],
             enabled: true,
+            metadata: Metadata::default(),
             created_at: now,
             updated_at: now,
         }
@@ -163,6 +170,8 @@ pub struct CreateRuleInput {
     pub enabled_adapters: Vec<AdapterType>,
     #[serde(default = "default_true")]
     pub enabled: bool,
+    #[serde(default)]
+    pub metadata: Metadata,
 }
 
 fn default_true() -> bool {
@@ -179,6 +188,59 @@ pub struct UpdateRuleInput {
     pub target_paths: Option<Vec<String>>,
     pub enabled_adapters: Option<Vec<AdapterType>>,
     pub enabled: Option<bool>,
+    pub metadata: Option<Metadata>,
+}
+
+/// One change applied to many rules at once. Adapters are replaced by `enabled_adapters`
+/// when set, then `add_adapters` and `remove_adapters` are applied. `metadata` is merged
+/// into each rule's metadata; a `null` value removes that key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BulkRuleChanges {
+    pub enabled: Option<bool>,
+    pub enabled_adapters: Option<Vec<AdapterType>>,
+    pub add_adapters: Vec<AdapterType>,
+    pub remove_adapters: Vec<AdapterType>,
+    pub metadata: Option<Metadata>,
+}
+
+impl BulkRuleChanges {
+    /// The update these changes make to `existing`, setting only the fields they touch.
+    pub fn to_update(&self, existing: &Rule) -> UpdateRuleInput {
+        let touches_adapters = self.enabled_adapters.is_some()
+            || !self.add_adapters.is_empty()
+            || !self.remove_adapters.is_empty();
+        let enabled_adapters = touches_adapters.then(|| {
+            let mut adapters = self
+                .enabled_adapters
+                .clone()
+                .unwrap_or_else(|| existing.enabled_adapters.clone());
+            for adapter in &self.add_adapters {
+                if !adapters.contains(adapter) {
+                    adapters.push(*adapter);
+                }
+            }
+            adapters.retain(|adapter| !self.remove_adapters.contains(adapter));
+            adapters
+        });
+        let metadata = self.metadata.as_ref().map(|patch| {
+            let mut metadata = existing.metadata.clone();
+            for (key, value) in patch.iter() {
+                if value.is_null() {
+                    metadata.remove(key);
+                } else {
+                    metadata.insert(key.clone(), value.clone());
+                }
+            }
+            metadata
+        });
+        UpdateRuleInput {
+            enabled: self.enabled,
+            enabled_adapters,
+            metadata,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -188,6 +250,25 @@ pub struct SyncResult {
     pub files_written: Vec<String>,
     pub errors: Vec<SyncError>,
     pub conflicts: Vec<Conflict>,
+    /// Tools files were written for that are not installed on this machine
+    #[serde(default)]
+    pub uninstalled_tools: Vec<AdapterType>,
+    /// Tools none of whose files were changed because one of them failed to write
+    #[serde(default)]
+    pub rolled_back_adapters: Vec<AdapterType>,
+}
+
+/// Outcome of checking stored sync hashes against the adapter files on disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHashRepair {
+    pub files_checked: u32,
+    /// Files matching what sync would write now whose stored hash was wrong or missing
+    pub repaired: Vec<String>,
+    /// Stored hashes for files that no longer exist
+    pub removed: Vec<String>,
+    /// Files changed outside RuleWeaver; their hashes are left alone so they still conflict
+    pub modified: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -210,6 +291,22 @@ pub struct DiffSummary {
     pub changed: usize,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// One line of an ordered line diff.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Conflict {
@@ -316,6 +413,7 @@ mod tests {
             target_paths: None,
             enabled_adapters: vec![AdapterType::Gemini, AdapterType::OpenCode],
             enabled: true,
+            metadata: Default::default(),
         };
 
         let json = serde_json::to_string(&input).unwrap();
@@ -389,6 +487,7 @@ mod tests {
             enabled: true,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            metadata: Default::default(),
         };
 
         let json = serde_json::to_string(&rule).unwrap();
@@ -405,4 +504,41 @@ mod tests {
         assert!(!json.contains("\"enabled_adapters\""));
         assert!(!json.contains("\"target_paths\""));
     }
+
+    #[test]
+    fn test_bulk_changes_touch_only_what_they_set() {
+        let mut rule = Rule::new(
+            "Style".to_string(),
+            String::new(),
+            String::new(),
+            Scope::Global,
+        );
+        rule.metadata.insert("owner", "web");
+        rule.metadata.insert("tags", vec!["frontend"]);
+
+        let mut patch = Metadata::new();
+        patch.insert("owner", serde_json::Value::Null);
+        patch.insert("reviewed", true);
+        let changes = BulkRuleChanges {
+            add_adapters: vec![AdapterType::Cursor, AdapterType::Gemini],
+            remove_adapters: vec![AdapterType::OpenCode],
+            metadata: Some(patch),
+            ..Default::default()
+        };
+        let update = changes.to_update(&rule);
+        assert_eq!(
+            update.enabled_adapters,
+            Some(vec![AdapterType::Gemini, AdapterType::Cursor])
+        );
+        let metadata = update.metadata.unwrap();
+        assert!(metadata.get("owner").is_none());
+        assert_eq!(metadata.get_bool("reviewed"), Some(true));
+        assert!(metadata.get("tags").is_some());
+        assert!(update.enabled.is_none() && update.content.is_none());
+
+        assert!(BulkRuleChanges::default()
+            .to_update(&rule)
+            .enabled_adapters
+            .is_none());
+    }
 }
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{AdapterType, AuditActor, ParseEnumError};
+
+/// Where a rule was last written and how much it contributed to that file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFileStat {
+    pub adapter: AdapterType,
+    pub file_path: String,
+    /// Size of the rule's formatted section in the file
+    pub bytes: u64,
+    #[serde(with = "crate::models::timestamp")]
+    pub last_synced_at: DateTime<Utc>,
+}
+
+/// Usage summary for a single rule, used to spot stale rules worth pruning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStats {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub enabled: bool,
+    /// Most recent sync across all files; `None` if the rule has never been synced
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub adapters: Vec<AdapterType>,
+    pub total_bytes: u64,
+    pub files: Vec<RuleFileStat>,
+    pub feedback: RuleFeedbackSummary,
+}
+
+/// How a rule did in a session, as reported by an agent or the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleFeedbackVerdict {
+    /// The rule shaped the work for the better
+    Helpful,
+    /// The rule was in context but made no difference
+    Ignored,
+    /// Following the rule made things worse
+    Harmful,
+}
+
+impl RuleFeedbackVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Helpful => "helpful",
+            Self::Ignored => "ignored",
+            Self::Harmful => "harmful",
+        }
+    }
+}
+
+impl FromStr for RuleFeedbackVerdict {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "helpful" => Ok(Self::Helpful),
+            "ignored" => Ok(Self::Ignored),
+            "harmful" => Ok(Self::Harmful),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFeedbackInput {
+    pub rule_id: String,
+    pub verdict: RuleFeedbackVerdict,
+    /// Groups reports from the same agent session
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// One recorded feedback report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFeedback {
+    pub id: String,
+    pub rule_id: String,
+    pub verdict: RuleFeedbackVerdict,
+    pub session_id: Option<String>,
+    pub note: Option<String>,
+    /// Who reported it: the app, the CLI or an MCP client
+    pub reporter: AuditActor,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Feedback counts for one rule.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleFeedbackSummary {
+    pub helpful: u32,
+    pub ignored: u32,
+    pub harmful: u32,
+    #[serde(default, with = "crate::models::timestamp::option")]
+    pub last_reported_at: Option<DateTime<Utc>>,
+}
+
+impl RuleFeedbackSummary {
+    pub fn count(&self, verdict: RuleFeedbackVerdict) -> u32 {
+        match verdict {
+            RuleFeedbackVerdict::Helpful => self.helpful,
+            RuleFeedbackVerdict::Ignored => self.ignored,
+            RuleFeedbackVerdict::Harmful => self.harmful,
+        }
+    }
+}
+
+/// Order of the rules returned by `get_rule_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleStatsSort {
+    #[default]
+    Name,
+    /// Most reported helpful first
+    Helpful,
+    /// Most reported ignored first
+    Ignored,
+    /// Most reported harmful first
+    Harmful,
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{CommandArgument, CreateRuleInput};
+
+/// A user-defined skeleton for new rules. `{{placeholder}}` tokens in the rule's name,
+/// description, content and target paths are filled in when a rule is created from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Declared like command arguments, with a type, a default and optional constraints
+    pub placeholders: Vec<CommandArgument>,
+    /// The rule to create, before placeholders are filled in; its `id` is ignored
+    pub rule: CreateRuleInput,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRuleTemplateInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub placeholders: Vec<CommandArgument>,
+    pub rule: CreateRuleInput,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRuleTemplateInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub placeholders: Option<Vec<CommandArgument>>,
+    pub rule: Option<CreateRuleInput>,
+}
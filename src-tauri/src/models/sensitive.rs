@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveKind {
+    /// Matches a known credential format such as a GitHub token or a private key
+    Credential,
+    /// A URL on a private network or internal host
+    PrivateUrl,
+    /// A long random-looking string
+    HighEntropy,
+}
+
+impl SensitiveKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Credential => "credential",
+            Self::PrivateUrl => "private_url",
+            Self::HighEntropy => "high_entropy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScannedArtifactType {
+    Rule,
+    Command,
+}
+
+/// Content in a rule or command that looks like it should not leave this machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveFinding {
+    /// Identifies this value in this artifact, for the allowlist
+    pub fingerprint: String,
+    pub kind: SensitiveKind,
+    /// What matched, such as "GitHub Token"
+    pub label: String,
+    pub artifact_type: ScannedArtifactType,
+    pub artifact_id: String,
+    pub artifact_name: String,
+    /// 1-based line in the scanned text
+    pub line: usize,
+    /// The match, masked unless it is a URL
+    pub excerpt: String,
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::limits::{
+    DEFAULT_ARTIFACT_RETENTION_MAX_AGE_DAYS, DEFAULT_BACKUP_RETENTION,
+    DEFAULT_EXPORT_SNAPSHOT_RETENTION, DEFAULT_LOG_RETENTION_MAX_AGE_DAYS,
+    DEFAULT_LOG_RETENTION_MAX_ROWS,
+};
+use crate::constants::timing::DEFAULT_EXPORT_SNAPSHOT_INTERVAL_HOURS;
+use crate::file_storage::watcher::WatchMode;
+
+/// User preferences. Each field is stored under the settings key of the same name in
+/// snake_case; a missing or unreadable value falls back to its default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct AppSettings {
+    /// Per-tool switches by adapter id; tools missing from the map are on
+    pub adapter_settings: BTreeMap<String, bool>,
+    /// Adapters preselected for new rules
+    pub default_adapters: Vec<String>,
+    /// Repository roots that local rules can target
+    pub local_rule_paths: Vec<String>,
+    pub mcp_auto_start: bool,
+    pub minimize_to_tray: bool,
+    pub startup_drift_check: bool,
+    pub drift_monitor_enabled: bool,
+    pub rule_watcher_mode: WatchMode,
+    pub auto_backup_enabled: bool,
+    pub auto_backup_retention: u64,
+    /// Row cap for the log tables; 0 keeps every row
+    pub log_retention_max_rows: u64,
+    /// Age cap for the log tables in days; 0 keeps every row
+    pub log_retention_max_age_days: u64,
+    pub artifact_retention_max_age_days: u64,
+    pub export_snapshot_enabled: bool,
+    pub export_snapshot_dir: Option<String>,
+    pub export_snapshot_interval_hours: u64,
+    pub export_snapshot_retention: u64,
+    pub metrics_prometheus_enabled: bool,
+    pub rule_catalog_url: Option<String>,
+    pub skill_template_index_url: Option<String>,
+    /// Settings keys passed to MCP skills as `SKILL_SECRET_*` variables
+    pub mcp_secrets_allowlist: Vec<String>,
+    /// Sign configuration exports with this machine's key
+    pub sign_exports: bool,
+    /// Hex public keys whose signed exports import without a warning
+    pub trusted_export_keys: Vec<String>,
+    /// Redact well-known credential formats from command output
+    pub redact_builtin_patterns: bool,
+    /// Regular expressions whose matches are redacted from command output
+    pub redaction_patterns: Vec<String>,
+    /// Environment variables whose values are redacted from command output
+    pub redacted_env_vars: Vec<String>,
+    /// Base URL of an OpenAI-compatible API used to draft, condense and merge rules
+    pub ai_endpoint: Option<String>,
+    pub ai_model: Option<String>,
+    /// Vault secret holding the AI endpoint's API key
+    pub ai_api_key_secret: Option<String>,
+    /// Base URL of an OpenAI-compatible API computing embeddings for semantic rule search;
+    /// the AI endpoint if unset
+    pub embedding_endpoint: Option<String>,
+    pub embedding_model: Option<String>,
+    /// Vault secret holding the embedding endpoint's API key
+    pub embedding_api_key_secret: Option<String>,
+    /// Language of the rule variants written for each tool, by adapter id
+    pub adapter_languages: BTreeMap<String, String>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            adapter_settings: BTreeMap::new(),
+            default_adapters: vec!["gemini".to_string(), "opencode".to_string()],
+            local_rule_paths: Vec::new(),
+            mcp_auto_start: false,
+            minimize_to_tray: true,
+            startup_drift_check: false,
+            drift_monitor_enabled: true,
+            rule_watcher_mode: WatchMode::default(),
+            auto_backup_enabled: false,
+            auto_backup_retention: DEFAULT_BACKUP_RETENTION as u64,
+            log_retention_max_rows: DEFAULT_LOG_RETENTION_MAX_ROWS,
+            log_retention_max_age_days: DEFAULT_LOG_RETENTION_MAX_AGE_DAYS,
+            artifact_retention_max_age_days: DEFAULT_ARTIFACT_RETENTION_MAX_AGE_DAYS,
+            export_snapshot_enabled: false,
+            export_snapshot_dir: None,
+            export_snapshot_interval_hours: DEFAULT_EXPORT_SNAPSHOT_INTERVAL_HOURS,
+            export_snapshot_retention: DEFAULT_EXPORT_SNAPSHOT_RETENTION as u64,
+            metrics_prometheus_enabled: false,
+            rule_catalog_url: None,
+            skill_template_index_url: None,
+            mcp_secrets_allowlist: Vec::new(),
+            sign_exports: false,
+            trusted_export_keys: Vec::new(),
+            redact_builtin_patterns: true,
+            redaction_patterns: Vec::new(),
+            redacted_env_vars: Vec::new(),
+            ai_endpoint: None,
+            ai_model: None,
+            ai_api_key_secret: None,
+            embedding_endpoint: None,
+            embedding_model: None,
+            embedding_api_key_secret: None,
+            adapter_languages: BTreeMap::new(),
+        }
+    }
+}
+
+/// Payload of the `settings-changed` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    pub settings: AppSettings,
+    /// Settings keys whose stored value changed
+    pub changed: Vec<String>,
+}
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use crate::error::{AppError, Result};
-use crate::models::Scope;
+use crate::models::{Metadata, Scope};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -26,13 +26,30 @@ pub struct Skill {
     pub target_paths: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_path: Option<String>,
+    #[serde(default)]
+    pub metadata: Metadata,
+    /// Files under `scripts/` and `resources/` in `directory_path`, relative to it, that
+    /// are copied next to SKILL.md wherever the skill is synced.
+    #[serde(default)]
+    pub bundle_files: Vec<String>,
+    /// Tools the skill may use without asking, written as `allowed-tools` in SKILL.md.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Semantic version of the current content; every content change records a new one.
+    #[serde(default = "default_skill_version")]
+    pub version: String,
+    /// Earlier versions to sync instead of the current one for some adapters or repos.
+    #[serde(default)]
+    pub version_pins: Vec<SkillVersionPin>,
     #[serde(with = "crate::models::timestamp")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "crate::models::timestamp")]
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SkillParameter {
     pub name: String,
@@ -202,18 +219,61 @@ pub fn validate_skill_schema(schema: &[SkillParameter]) -> Result<()> {
     Ok(())
 }
 
-/// Validate that all adapter IDs in `target_adapters` are known and support skills.
+/// Validate that all adapter IDs in `target_adapters` are known. Adapters without native
+/// skills support are allowed and receive the skill through the MCP server.
 pub fn validate_skill_target_adapters(target_adapters: &[String]) -> Result<()> {
-    use crate::models::registry::{ArtifactType, REGISTRY};
-    use crate::models::{AdapterType, Scope};
+    use crate::models::AdapterType;
     for adapter_str in target_adapters {
-        let adapter = AdapterType::from_str(adapter_str)
+        AdapterType::from_str(adapter_str)
             .map_err(|_| AppError::Validation(format!("Unknown adapter: '{}'", adapter_str)))?;
-        REGISTRY
-            .validate_support(&adapter, &Scope::Global, ArtifactType::Skill)
-            .map_err(|_| {
-                AppError::Validation(format!("Adapter '{}' does not support skills", adapter_str))
-            })?;
+    }
+    Ok(())
+}
+
+/// Bundle files must be relative paths inside one of the bundle directories.
+pub fn validate_skill_bundle_files(files: &[String]) -> Result<()> {
+    for file in files {
+        let path = std::path::Path::new(file);
+        let in_bundle_dir = path
+            .components()
+            .next()
+            .and_then(|c| c.as_os_str().to_str())
+            .is_some_and(|dir| crate::constants::SKILL_BUNDLE_DIRS.contains(&dir));
+        let plain = path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !in_bundle_dir || !plain || path.components().count() < 2 {
+            return Err(AppError::Validation(format!(
+                "Bundle file '{}' must be a relative path under scripts/ or resources/",
+                file
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Validates the `allowed-tools` and `license` fields written to SKILL.md frontmatter.
+pub fn validate_skill_frontmatter(allowed_tools: &[String], license: Option<&str>) -> Result<()> {
+    for tool in allowed_tools {
+        if tool.trim().is_empty() || tool.contains(',') || tool.contains(['\n', '\r']) {
+            return Err(AppError::Validation(format!(
+                "Invalid allowed tool '{}': must be non-empty, single-line and without commas",
+                tool
+            )));
+        }
+    }
+    if let Some(license) = license {
+        if license.len() > crate::constants::limits::MAX_SKILL_LICENSE_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Skill license too long (max {} characters)",
+                crate::constants::limits::MAX_SKILL_LICENSE_LENGTH
+            )));
+        }
+        if license.contains(['\n', '\r']) {
+            return Err(AppError::Validation(
+                "Skill license must be a single line".to_string(),
+            ));
+        }
     }
     Ok(())
 }
@@ -259,6 +319,17 @@ pub struct CreateSkillInput {
     pub target_paths: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_path: Option<String>,
+    #[serde(default)]
+    pub metadata: Metadata,
+    #[serde(default)]
+    pub bundle_files: Vec<String>,
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Starting version; defaults to 1.0.0.
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -281,4 +352,123 @@ pub struct UpdateSkillInput {
     /// Repository roots for local-scope syncing.
     pub target_paths: Option<Vec<String>>,
     pub base_path: Option<String>,
+    pub metadata: Option<Metadata>,
+    pub bundle_files: Option<Vec<String>>,
+    pub allowed_tools: Option<Vec<String>>,
+    /// None = no change; Some("") clears the license.
+    pub license: Option<String>,
+    /// Version for this change; None bumps the patch version when the content changes.
+    pub version: Option<String>,
+    /// What changed, stored with the new version.
+    pub changelog: Option<String>,
+    pub version_pins: Option<Vec<SkillVersionPin>>,
+}
+
+pub fn default_skill_version() -> String {
+    "1.0.0".to_string()
+}
+
+/// Syncs `version` of a skill instead of its current content to the matching targets. A
+/// pin naming both an adapter and a repository only applies where both match, and wins
+/// over a pin naming just one of them; a repository pin wins over an adapter pin.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillVersionPin {
+    #[serde(default)]
+    pub adapter: Option<String>,
+    #[serde(default)]
+    pub repo_path: Option<String>,
+    pub version: String,
+}
+
+/// The versioned content of a skill. Bundle files live on disk and are not versioned.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillVersionContent {
+    pub name: String,
+    pub description: String,
+    pub instructions: String,
+    #[serde(default)]
+    pub input_schema: Vec<SkillParameter>,
+    #[serde(default)]
+    pub entry_point: String,
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+impl SkillVersionContent {
+    pub fn of(skill: &Skill) -> Self {
+        Self {
+            name: skill.name.clone(),
+            description: skill.description.clone(),
+            instructions: skill.instructions.clone(),
+            input_schema: skill.input_schema.clone(),
+            entry_point: skill.entry_point.clone(),
+            allowed_tools: skill.allowed_tools.clone(),
+            license: skill.license.clone(),
+        }
+    }
+
+    /// `skill` with this content and version in place of its current ones.
+    pub fn apply_to(&self, skill: &Skill, version: &str) -> Skill {
+        Skill {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            instructions: self.instructions.clone(),
+            input_schema: self.input_schema.clone(),
+            entry_point: self.entry_point.clone(),
+            allowed_tools: self.allowed_tools.clone(),
+            license: self.license.clone(),
+            version: version.to_string(),
+            ..skill.clone()
+        }
+    }
+}
+
+/// A recorded version of a skill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillVersion {
+    pub skill_id: String,
+    pub version: String,
+    pub changelog: String,
+    pub content: SkillVersionContent,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn parse_skill_version(version: &str) -> Result<semver::Version> {
+    semver::Version::parse(version.trim()).map_err(|e| {
+        AppError::Validation(format!(
+            "Invalid skill version '{}': {} (expected MAJOR.MINOR.PATCH)",
+            version, e
+        ))
+    })
+}
+
+/// The version after `current` with the patch number incremented.
+pub fn next_patch_version(current: &str) -> String {
+    match semver::Version::parse(current.trim()) {
+        Ok(v) => semver::Version::new(v.major, v.minor, v.patch + 1).to_string(),
+        Err(_) => default_skill_version(),
+    }
+}
+
+/// Pins need a target and a valid version; adapters must be known.
+pub fn validate_skill_version_pins(pins: &[SkillVersionPin]) -> Result<()> {
+    for pin in pins {
+        if pin.adapter.is_none() && pin.repo_path.is_none() {
+            return Err(AppError::Validation(
+                "A version pin needs an adapter, a repository or both".to_string(),
+            ));
+        }
+        if let Some(adapter) = &pin.adapter {
+            crate::models::AdapterType::from_str(adapter)
+                .map_err(|_| AppError::Validation(format!("Unknown adapter: '{}'", adapter)))?;
+        }
+        parse_skill_version(&pin.version)?;
+    }
+    Ok(())
 }
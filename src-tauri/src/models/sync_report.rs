@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{AdapterType, SyncError};
+
+/// What one sync run did, adapter by adapter. Stored alongside its `sync_logs` row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// Same id as the sync history entry
+    pub id: String,
+    #[serde(with = "crate::models::timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub triggered_by: String,
+    pub status: String,
+    pub duration_ms: u64,
+    pub adapters: Vec<AdapterSyncReport>,
+}
+
+impl SyncReport {
+    pub fn files_written(&self) -> Vec<String> {
+        self.adapters
+            .iter()
+            .flat_map(|a| a.files.iter().map(|f| f.path.clone()))
+            .collect()
+    }
+
+    pub fn errors(&self) -> Vec<SyncError> {
+        self.adapters
+            .iter()
+            .flat_map(|a| a.errors.iter().cloned())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterSyncReport {
+    pub adapter: AdapterType,
+    pub adapter_name: String,
+    pub duration_ms: u64,
+    pub files: Vec<SyncReportFile>,
+    /// Why the adapter was not synced at all, e.g. disabled in settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped_reason: Option<String>,
+    pub errors: Vec<SyncError>,
+    /// Whether the tool was detected on this machine; absent in reports from older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed: Option<bool>,
+    /// A file failed to write, so none of the adapter's files were changed
+    #[serde(default)]
+    pub rolled_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReportFile {
+    pub path: String,
+    /// Hash of the content written, as stored for conflict detection
+    pub hash: String,
+    pub rule_count: u32,
+    pub bytes: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncReportFormat {
+    Json,
+    Markdown,
+}
@@ -17,3 +17,32 @@ where
         .single()
         .ok_or_else(|| serde::de::Error::custom(format!("Invalid timestamp: {}", ts)))
 }
+
+/// Same Unix-seconds encoding for optional timestamps; `None` serializes as `null`.
+pub mod option {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => super::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ts = Option::<i64>::deserialize(deserializer)?;
+        ts.map(|ts| {
+            chrono::TimeZone::timestamp_opt(&Utc, ts, 0)
+                .single()
+                .ok_or_else(|| serde::de::Error::custom(format!("Invalid timestamp: {}", ts)))
+        })
+        .transpose()
+    }
+}
@@ -0,0 +1,105 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse_error::ParseEnumError;
+
+/// Lifecycle events a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    /// Rules were written to their tools' files
+    #[serde(rename = "sync.completed")]
+    SyncCompleted,
+    /// A file was changed outside RuleWeaver since it was last written
+    #[serde(rename = "conflict.detected")]
+    ConflictDetected,
+    /// Reconciliation created, updated or removed generated files
+    #[serde(rename = "reconcile.applied")]
+    ReconcileApplied,
+    /// A command ran, from the UI, an MCP client, a schedule or a workflow
+    #[serde(rename = "command.executed")]
+    CommandExecuted,
+    /// Sent by "Send test" to check a URL and its signature verification
+    #[serde(rename = "webhook.test")]
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SyncCompleted => "sync.completed",
+            Self::ConflictDetected => "conflict.detected",
+            Self::ReconcileApplied => "reconcile.applied",
+            Self::CommandExecuted => "command.executed",
+            Self::Test => "webhook.test",
+        }
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sync.completed" => Ok(Self::SyncCompleted),
+            "conflict.detected" => Ok(Self::ConflictDetected),
+            "reconcile.applied" => Ok(Self::ReconcileApplied),
+            "command.executed" => Ok(Self::CommandExecuted),
+            "webhook.test" => Ok(Self::Test),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// A URL that receives a signed JSON POST for each subscribed event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Secrets vault entry whose value signs each payload; unsigned when `None`
+    pub secret_name: Option<String>,
+    pub enabled: bool,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookInput {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub secret_name: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookInput {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    /// An empty name stops signing
+    pub secret_name: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Outcome of one delivery, as returned by "Send test".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub delivery_id: String,
+    /// HTTP status of the last attempt, if the server answered
+    pub status: Option<u16>,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
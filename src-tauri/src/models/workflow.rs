@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse_error::ParseEnumError;
+use super::CommandArgument;
+
+/// What happens to the rest of a workflow when a step fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepFailurePolicy {
+    /// Skip every remaining step
+    #[default]
+    Stop,
+    /// Skip only the steps that depend on this one, directly or indirectly
+    SkipDependents,
+    /// Carry on as if the step had succeeded
+    Continue,
+}
+
+impl StepFailurePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stop => "stop",
+            Self::SkipDependents => "skip_dependents",
+            Self::Continue => "continue",
+        }
+    }
+}
+
+impl FromStr for StepFailurePolicy {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stop" => Ok(Self::Stop),
+            "skip_dependents" => Ok(Self::SkipDependents),
+            "continue" => Ok(Self::Continue),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+/// One command invocation in a workflow.
+///
+/// Argument values may reference `{{input.NAME}}` for a workflow argument, or
+/// `{{steps.STEP_ID.stdout}}` / `{{steps.STEP_ID.exit_code}}` for an earlier step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStep {
+    /// Identifies the step within its workflow
+    pub id: String,
+    pub command_id: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub args: HashMap<String, String>,
+    #[serde(default)]
+    pub on_failure: StepFailurePolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workflow {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<WorkflowStep>,
+    /// Inputs the whole workflow accepts, referenced from steps as `{{input.NAME}}`
+    #[serde(default)]
+    pub arguments: Vec<CommandArgument>,
+    pub expose_via_mcp: bool,
+    #[serde(default)]
+    pub generate_slash_commands: bool,
+    #[serde(default)]
+    pub slash_command_adapters: Vec<String>,
+    #[serde(with = "crate::models::timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::models::timestamp")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkflowInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    pub arguments: Vec<CommandArgument>,
+    #[serde(default)]
+    pub expose_via_mcp: bool,
+    #[serde(default)]
+    pub generate_slash_commands: bool,
+    #[serde(default)]
+    pub slash_command_adapters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkflowInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub steps: Option<Vec<WorkflowStep>>,
+    pub arguments: Option<Vec<CommandArgument>>,
+    pub expose_via_mcp: Option<bool>,
+    pub generate_slash_commands: Option<bool>,
+    pub slash_command_adapters: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowStepStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStepResult {
+    pub step_id: String,
+    pub command_id: String,
+    pub command_name: String,
+    pub status: WorkflowStepStatus,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    /// Why the step failed to start or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRunResult {
+    pub workflow_id: String,
+    /// True when no step failed
+    pub success: bool,
+    pub steps: Vec<WorkflowStepResult>,
+    pub duration_ms: u64,
+}
+
+/// A finished or skipped step, emitted as the `workflow-step` event during a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStepEvent {
+    pub execution_id: String,
+    pub workflow_id: String,
+    pub result: WorkflowStepResult,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_defaults_from_frontend() {
+        let json = r#"{ "id": "lint", "commandId": "cmd-1" }"#;
+        let step: WorkflowStep = serde_json::from_str(json).unwrap();
+        assert!(step.depends_on.is_empty());
+        assert!(step.args.is_empty());
+        assert_eq!(step.on_failure, StepFailurePolicy::Stop);
+    }
+
+    #[test]
+    fn test_failure_policy_roundtrip() {
+        for policy in [
+            StepFailurePolicy::Stop,
+            StepFailurePolicy::SkipDependents,
+            StepFailurePolicy::Continue,
+        ] {
+            assert_eq!(StepFailurePolicy::from_str(policy.as_str()), Ok(policy));
+        }
+        assert!(StepFailurePolicy::from_str("retry").is_err());
+    }
+}
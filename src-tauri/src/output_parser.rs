@@ -0,0 +1,310 @@
+//! Structured results extracted from command output.
+//!
+//! A command may name an [`OutputParser`]; after each run its stdout (or, for JUnit, a
+//! report file) is parsed and the resulting JSON is stored with the execution log entry
+//! and returned to MCP clients as structured content.
+
+use std::fs;
+use std::path::{Component, Path};
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::error::{AppError, Result};
+use crate::models::OutputParser;
+
+pub fn validate_parser(parser: &OutputParser) -> Result<()> {
+    match parser {
+        OutputParser::Text | OutputParser::Json => Ok(()),
+        OutputParser::Junit { report_path } => {
+            let Some(report_path) = report_path else {
+                return Ok(());
+            };
+            let path = Path::new(report_path.trim());
+            if report_path.trim().is_empty()
+                || path.is_absolute()
+                || path.components().any(|c| matches!(c, Component::ParentDir))
+            {
+                return Err(AppError::Validation(format!(
+                    "JUnit report path must be relative to the working directory: '{}'",
+                    report_path
+                )));
+            }
+            Ok(())
+        }
+        OutputParser::Regex { pattern } => {
+            let regex = Regex::new(pattern).map_err(|e| {
+                AppError::Validation(format!("Invalid output pattern '{}': {}", pattern, e))
+            })?;
+            if regex.capture_names().flatten().next().is_none() {
+                return Err(AppError::Validation(format!(
+                    "Output pattern '{}' has no named capture groups",
+                    pattern
+                )));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Parses a run's output. Returns `None` for the text parser.
+pub fn parse(
+    parser: &OutputParser,
+    stdout: &str,
+    working_dir: Option<&Path>,
+) -> Result<Option<Value>> {
+    match parser {
+        OutputParser::Text => Ok(None),
+        OutputParser::Json => Ok(Some(serde_json::from_str(stdout.trim())?)),
+        OutputParser::Junit { report_path } => {
+            let xml = match report_path {
+                Some(report_path) => {
+                    let base = match working_dir {
+                        Some(dir) => dir.to_path_buf(),
+                        None => std::env::current_dir()?,
+                    };
+                    fs::read_to_string(base.join(report_path.trim()))?
+                }
+                None => stdout.to_string(),
+            };
+            parse_junit(&xml).map(Some)
+        }
+        OutputParser::Regex { pattern } => {
+            let regex = Regex::new(pattern).map_err(|e| {
+                AppError::Validation(format!("Invalid output pattern '{}': {}", pattern, e))
+            })?;
+            Ok(Some(parse_regex(&regex, stdout)))
+        }
+    }
+}
+
+/// One object per match, holding the named groups that took part in it.
+fn parse_regex(regex: &Regex, text: &str) -> Value {
+    let matches: Vec<Value> = regex
+        .captures_iter(text)
+        .map(|captures| {
+            let groups: Map<String, Value> = regex
+                .capture_names()
+                .flatten()
+                .filter_map(|name| {
+                    let value = captures.name(name)?.as_str();
+                    Some((name.to_string(), Value::String(value.to_string())))
+                })
+                .collect();
+            Value::Object(groups)
+        })
+        .collect();
+    json!({ "matches": matches })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Passed,
+    Failed,
+    Errored,
+    Skipped,
+}
+
+#[derive(Default)]
+struct JunitSummary {
+    tests: u64,
+    failures: u64,
+    errors: u64,
+    skipped: u64,
+    failed: Vec<String>,
+}
+
+impl JunitSummary {
+    fn finish(&mut self, name: String, outcome: Outcome) {
+        self.tests += 1;
+        match outcome {
+            Outcome::Passed => {}
+            Outcome::Failed => self.failures += 1,
+            Outcome::Errored => self.errors += 1,
+            Outcome::Skipped => self.skipped += 1,
+        }
+        if matches!(outcome, Outcome::Failed | Outcome::Errored) {
+            self.failed.push(name);
+        }
+    }
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    let attribute = element.try_get_attribute(name).ok()??;
+    let value = attribute.normalized_value(XmlVersion::Implicit1_0).ok()?;
+    Some(value.to_string())
+}
+
+/// `classname.name` of a `<testcase>`, or just its name when it has no class.
+fn testcase_name(element: &BytesStart) -> String {
+    let name = attribute(element, "name").unwrap_or_default();
+    match attribute(element, "classname") {
+        Some(class) if !class.is_empty() => format!("{}.{}", class, name),
+        _ => name,
+    }
+}
+
+/// Counts `<testcase>` elements by outcome rather than trusting suite totals, which
+/// some reporters omit or get wrong.
+fn parse_junit(xml: &str) -> Result<Value> {
+    let mut reader = Reader::from_str(xml);
+    let mut summary = JunitSummary::default();
+    let mut current: Option<(String, Outcome)> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| AppError::InvalidInput {
+            message: format!("Invalid JUnit XML: {}", e),
+        })?;
+        let (element, self_closing) = match event {
+            Event::Start(element) => (element, false),
+            Event::Empty(element) => (element, true),
+            Event::End(element) if element.local_name().as_ref() == "testcase" => {
+                if let Some((name, outcome)) = current.take() {
+                    summary.finish(name, outcome);
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let outcome = match element.local_name().as_ref() {
+            "testcase" => {
+                let name = testcase_name(&element);
+                if self_closing {
+                    summary.finish(name, Outcome::Passed);
+                } else {
+                    current = Some((name, Outcome::Passed));
+                }
+                continue;
+            }
+            "failure" => Outcome::Failed,
+            "error" => Outcome::Errored,
+            "skipped" => Outcome::Skipped,
+            _ => continue,
+        };
+        if let Some((_, current_outcome)) = current.as_mut() {
+            if *current_outcome == Outcome::Passed {
+                *current_outcome = outcome;
+            }
+        }
+    }
+
+    Ok(json!({
+        "tests": summary.tests,
+        "passed": summary.tests - summary.failures - summary.errors - summary.skipped,
+        "failures": summary.failures,
+        "errors": summary.errors,
+        "skipped": summary.skipped,
+        "failed": summary.failed,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="api" tests="4">
+    <testcase classname="api.users" name="creates_user" time="0.1"/>
+    <testcase classname="api.users" name="rejects_&quot;bad&quot;_email">
+      <failure message="expected 400">assertion failed</failure>
+    </testcase>
+    <testcase classname="api.users" name="deletes_user">
+      <skipped/>
+    </testcase>
+    <testcase name="connects">
+      <error type="Timeout"/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+
+    #[test]
+    fn junit_reports_are_summarised() {
+        let parsed = parse(&OutputParser::Junit { report_path: None }, REPORT, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            parsed,
+            json!({
+                "tests": 4,
+                "passed": 1,
+                "failures": 1,
+                "errors": 1,
+                "skipped": 1,
+                "failed": ["api.users.rejects_\"bad\"_email", "connects"],
+            })
+        );
+    }
+
+    #[test]
+    fn junit_reports_are_read_from_the_working_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("reports")).unwrap();
+        fs::write(dir.path().join("reports/junit.xml"), REPORT).unwrap();
+
+        let parser = OutputParser::Junit {
+            report_path: Some("reports/junit.xml".to_string()),
+        };
+        let parsed = parse(&parser, "ignored", Some(dir.path()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed["tests"], 4);
+        assert!(parse(
+            &OutputParser::Junit { report_path: None },
+            "<a><b></a>",
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn regex_parser_collects_named_groups() {
+        let parser = OutputParser::Regex {
+            pattern: r"(?P<passed>\d+) passed(?:, (?P<failed>\d+) failed)?".to_string(),
+        };
+        let parsed = parse(
+            &parser,
+            "suite a: 3 passed\nsuite b: 5 passed, 2 failed",
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            parsed,
+            json!({ "matches": [{ "passed": "3" }, { "passed": "5", "failed": "2" }] })
+        );
+    }
+
+    #[test]
+    fn json_and_text_parsers() {
+        let parsed = parse(&OutputParser::Json, " {\"ok\": true}\n", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed, json!({ "ok": true }));
+        assert!(parse(&OutputParser::Json, "not json", None).is_err());
+        assert!(parse(&OutputParser::Text, "anything", None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn parsers_are_validated() {
+        let regex = |pattern: &str| OutputParser::Regex {
+            pattern: pattern.to_string(),
+        };
+        assert!(validate_parser(&regex(r"(?P<count>\d+) tests")).is_ok());
+        assert!(validate_parser(&regex(r"(\d+) tests")).is_err());
+        assert!(validate_parser(&regex(r"(?P<count>\d+")).is_err());
+
+        let junit = |path: &str| OutputParser::Junit {
+            report_path: Some(path.to_string()),
+        };
+        assert!(validate_parser(&junit("target/junit.xml")).is_ok());
+        assert!(validate_parser(&junit("../junit.xml")).is_err());
+        assert!(validate_parser(&junit("/tmp/junit.xml")).is_err());
+    }
+}
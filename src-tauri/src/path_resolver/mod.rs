@@ -45,6 +45,40 @@ fn validate_command_name(command_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validate a slash command namespace such as `git` or `git/review`.
+///
+/// Segments are separated by `/`; each one must be non-empty and free of
+/// backslashes and parent directory references.
+fn validate_command_namespace(namespace: &str) -> Result<()> {
+    let valid = namespace
+        .split('/')
+        .all(|segment| !segment.is_empty() && !segment.contains("..") && !segment.contains('\\'));
+    if !valid {
+        return Err(AppError::InvalidInput {
+            message: format!("Invalid slash command namespace: '{}'", namespace),
+        });
+    }
+    Ok(())
+}
+
+/// Relative location of a slash command inside an adapter's commands directory.
+///
+/// Namespaced commands become nested folders (`git/commit.md`) for adapters that
+/// support them and a prefixed filename (`git-commit.md`) for everything else.
+fn slash_command_relative_path(
+    namespace: Option<&str>,
+    command_name: &str,
+    extension: &str,
+    nested_dirs: bool,
+) -> PathBuf {
+    let filename = format!("{}.{}", command_name, extension);
+    match namespace {
+        Some(ns) if nested_dirs => ns.split('/').collect::<PathBuf>().join(filename),
+        Some(ns) => PathBuf::from(format!("{}-{}", ns.replace('/', "-"), filename)),
+        None => PathBuf::from(filename),
+    }
+}
+
 /// Validate a skill name for path safety.
 ///
 /// Prevents path traversal attacks by rejecting names containing:
@@ -361,8 +395,27 @@ impl PathResolver {
         adapter: AdapterType,
         command_name: &str,
         is_global: bool,
+    ) -> Result<ResolvedPath> {
+        self.namespaced_slash_command_path(adapter, None, command_name, is_global)
+    }
+
+    /// Resolve a path for a slash command, optionally inside a namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the adapter doesn't support slash commands,
+    /// or if the command name or namespace contains invalid characters.
+    pub fn namespaced_slash_command_path(
+        &self,
+        adapter: AdapterType,
+        namespace: Option<&str>,
+        command_name: &str,
+        is_global: bool,
     ) -> Result<ResolvedPath> {
         validate_command_name(command_name)?;
+        if let Some(ns) = namespace {
+            validate_command_namespace(ns)?;
+        }
 
         let entry = REGISTRY
             .get(&adapter)
@@ -401,10 +454,15 @@ impl PathResolver {
                 })?
         };
 
-        let filename = format!("{}.{}", command_name, extension);
+        let relative = slash_command_relative_path(
+            namespace,
+            command_name,
+            extension,
+            entry.slash_command_nested_dirs,
+        );
 
         let path = if is_global {
-            self.home_dir.join(dir).join(&filename)
+            self.home_dir.join(dir).join(relative)
         } else {
             // For local, we need a repo root - this is handled differently
             // The caller must provide the repo root context
@@ -442,8 +500,27 @@ impl PathResolver {
         adapter: AdapterType,
         command_name: &str,
         repo_root: &Path,
+    ) -> Result<ResolvedPath> {
+        self.local_namespaced_slash_command_path(adapter, None, command_name, repo_root)
+    }
+
+    /// Resolve a local path for a slash command in a repository, optionally inside a namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the adapter doesn't support slash commands,
+    /// or if the command name or namespace contains invalid characters.
+    pub fn local_namespaced_slash_command_path(
+        &self,
+        adapter: AdapterType,
+        namespace: Option<&str>,
+        command_name: &str,
+        repo_root: &Path,
     ) -> Result<ResolvedPath> {
         validate_command_name(command_name)?;
+        if let Some(ns) = namespace {
+            validate_command_namespace(ns)?;
+        }
 
         let entry = REGISTRY
             .get(&adapter)
@@ -470,8 +547,13 @@ impl PathResolver {
                 ),
             })?;
 
-        let filename = format!("{}.{}", command_name, extension);
-        let path = repo_root.join(dir).join(&filename);
+        let relative = slash_command_relative_path(
+            namespace,
+            command_name,
+            extension,
+            entry.slash_command_nested_dirs,
+        );
+        let path = repo_root.join(dir).join(relative);
         let exists = path.exists();
 
         Ok(ResolvedPath {
@@ -1394,6 +1476,49 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_namespaced_slash_command_path_nests_or_prefixes() {
+        let resolver = PathResolver::new().unwrap();
+        let repo_root = PathBuf::from("/test/repo");
+
+        let nested = resolver
+            .local_namespaced_slash_command_path(
+                AdapterType::ClaudeCode,
+                Some("git/review"),
+                "commit",
+                &repo_root,
+            )
+            .unwrap();
+        assert_eq!(
+            nested.path,
+            repo_root.join(".claude/commands/git/review/commit.md")
+        );
+
+        let prefixed = resolver
+            .local_namespaced_slash_command_path(
+                AdapterType::OpenCode,
+                Some("git/review"),
+                "commit",
+                &repo_root,
+            )
+            .unwrap();
+        assert_eq!(
+            prefixed.path,
+            repo_root.join(".opencode/commands/git-review-commit.md")
+        );
+
+        for namespace in ["../escape", "git//review", "git\\review", ""] {
+            assert!(resolver
+                .namespaced_slash_command_path(
+                    AdapterType::ClaudeCode,
+                    Some(namespace),
+                    "commit",
+                    true
+                )
+                .is_err());
+        }
+    }
+
     #[test]
     fn test_resolve_workspace_path() {
         #[cfg(windows)]
@@ -1431,7 +1556,10 @@ mod tests {
         assert_eq!(resolve_workspace_path(absolute_path, base), absolute_path);
 
         // Traversal prevention
-        assert_eq!(resolve_workspace_path("./../../etc/passwd", base), base_path);
+        assert_eq!(
+            resolve_workspace_path("./../../etc/passwd", base),
+            base_path
+        );
 
         // Traversal prevention with variable
         assert_eq!(
@@ -0,0 +1,299 @@
+//! Organization policy for artifacts imported from designated sources.
+//!
+//! An artifact imported from a source listed in the [`OrgPolicy`] carries the source URL under
+//! [`MANAGED_METADATA_KEY`] in its metadata. While that source stays in the policy, the
+//! artifact cannot be disabled, deleted or edited locally except for the allowed overrides,
+//! and reconciliation writes its files even where a protected path or a disabled flag would
+//! otherwise keep them out. Removing the source from the policy releases its artifacts.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{HasMetadata, ImportCandidate, Metadata, OrgPolicy, PolicyOverride};
+
+pub const ORG_POLICY_KEY: &str = "org_policy";
+pub const MANAGED_METADATA_KEY: &str = "orgManaged";
+
+pub async fn get_org_policy(db: &Database) -> OrgPolicy {
+    let encoded = match db.get_setting(ORG_POLICY_KEY).await {
+        Ok(Some(v)) => v,
+        _ => return OrgPolicy::default(),
+    };
+    serde_json::from_str(&encoded).unwrap_or_default()
+}
+
+/// Validates and stores the policy, dropping blank and duplicate sources.
+pub async fn save_org_policy(db: &Database, policy: OrgPolicy) -> Result<OrgPolicy> {
+    let mut sources: Vec<String> = Vec::new();
+    for source in policy.sources {
+        let source = source.trim().trim_end_matches('/').to_string();
+        if source.is_empty() || sources.contains(&source) {
+            continue;
+        }
+        if url::Url::parse(&source).is_err() && !std::path::Path::new(&source).is_absolute() {
+            return Err(AppError::InvalidInput {
+                message: format!(
+                    "Managed source '{}' must be a URL or an absolute path",
+                    source
+                ),
+            });
+        }
+        sources.push(source);
+    }
+    let mut allowed_overrides = policy.allowed_overrides;
+    allowed_overrides.dedup();
+    let normalized = OrgPolicy {
+        sources,
+        allowed_overrides,
+    };
+    db.set_setting(ORG_POLICY_KEY, &serde_json::to_string(&normalized)?)
+        .await?;
+    Ok(normalized)
+}
+
+impl OrgPolicy {
+    /// The managed source covering an import source path such as `<url>#rules/a.md`.
+    pub fn source_for(&self, source_path: &str) -> Option<&str> {
+        self.sources
+            .iter()
+            .find(|source| {
+                source_path
+                    .strip_prefix(source.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['#', '/', '\\']))
+            })
+            .map(String::as_str)
+    }
+
+    /// Whether `metadata` marks an artifact from a source this policy still manages.
+    pub fn manages(&self, metadata: &Metadata) -> bool {
+        metadata
+            .get_str(MANAGED_METADATA_KEY)
+            .is_some_and(|source| self.source_for(source).is_some())
+    }
+
+    pub fn allows(&self, override_kind: PolicyOverride) -> bool {
+        self.allowed_overrides.contains(&override_kind)
+    }
+
+    /// Marks candidates from managed sources so the imported artifacts are managed.
+    pub fn mark_candidates(&self, candidates: &mut [ImportCandidate]) {
+        if self.sources.is_empty() {
+            return;
+        }
+        for candidate in candidates {
+            if let Some(source) = self.source_for(&candidate.source_path) {
+                let source = source.to_string();
+                candidate.metadata.insert(MANAGED_METADATA_KEY, source);
+            }
+        }
+    }
+}
+
+/// Rejects deleting an artifact the organization manages.
+pub async fn ensure_deletable(
+    db: &Database,
+    kind: &str,
+    name: &str,
+    metadata: &Metadata,
+) -> Result<()> {
+    if get_org_policy(db).await.manages(metadata) {
+        return Err(violation(kind, name, "deleted"));
+    }
+    Ok(())
+}
+
+/// Rejects replacing a managed artifact with a copy from elsewhere, such as another
+/// device's cloud sync.
+pub async fn ensure_replaceable(
+    db: &Database,
+    kind: &str,
+    name: &str,
+    metadata: &Metadata,
+) -> Result<()> {
+    if get_org_policy(db).await.manages(metadata) {
+        return Err(violation(kind, name, "replaced"));
+    }
+    Ok(())
+}
+
+/// Carries the managed marker over into metadata that replaces `existing`, so an allowed
+/// metadata edit cannot drop it.
+pub fn keep_marker(existing: &Metadata, metadata: Option<&mut Metadata>) {
+    if let (Some(source), Some(metadata)) = (existing.get(MANAGED_METADATA_KEY), metadata) {
+        metadata.insert(MANAGED_METADATA_KEY, source.clone());
+    }
+}
+
+/// Rejects a local edit of a managed artifact that changes anything besides the allowed
+/// overrides. `input` is the update input and `existing` the stored artifact; fields are
+/// matched by their serialized names.
+pub async fn ensure_editable<I, A>(db: &Database, kind: &str, input: &I, existing: &A) -> Result<()>
+where
+    I: Serialize,
+    A: Serialize + HasMetadata,
+{
+    let policy = get_org_policy(db).await;
+    if !policy.manages(existing.metadata()) {
+        return Ok(());
+    }
+    let input = serde_json::to_value(input)?;
+    let existing = serde_json::to_value(existing)?;
+    let name = existing["name"].as_str().unwrap_or_default();
+    for field in changed_fields(&input, &existing) {
+        let allowed = match field.as_str() {
+            "enabled" if input[&field] == Value::Bool(false) => {
+                return Err(violation(kind, name, "disabled"))
+            }
+            "enabled" | "changelog" => true,
+            "targetPaths" | "basePath" => policy.allows(PolicyOverride::TargetPaths),
+            "enabledAdapters" | "targetAdapters" | "slashCommandAdapters" => {
+                policy.allows(PolicyOverride::Adapters)
+            }
+            "metadata" => policy.allows(PolicyOverride::Metadata),
+            _ => false,
+        };
+        if !allowed {
+            return Err(violation(kind, name, &format!("edited ({})", field)));
+        }
+    }
+    Ok(())
+}
+
+/// Fields set in `input` whose value differs from the same field in `existing`. An empty
+/// string counts as clearing the field; a field `existing` leaves out counts as unset.
+fn changed_fields(input: &Value, existing: &Value) -> Vec<String> {
+    let Some(fields) = input.as_object() else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .filter(|(key, value)| {
+            let current = existing.get(key).unwrap_or(&Value::Null);
+            let value = match value {
+                Value::String(s) if s.is_empty() => &Value::Null,
+                other => other,
+            };
+            value != current
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+fn violation(kind: &str, name: &str, action: &str) -> AppError {
+    AppError::PolicyViolation {
+        message: format!(
+            "{} '{}' is managed by your organization and cannot be {}",
+            kind, name, action
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, Scope, UpdateRuleInput};
+
+    const SOURCE: &str = "https://github.com/acme/rules.git";
+
+    async fn managed_rule(db: &Database) -> crate::models::Rule {
+        save_org_policy(
+            db,
+            OrgPolicy {
+                sources: vec![format!("{}/", SOURCE)],
+                allowed_overrides: vec![PolicyOverride::Adapters],
+            },
+        )
+        .await
+        .unwrap();
+        let mut metadata = Metadata::new();
+        metadata.insert(MANAGED_METADATA_KEY, SOURCE);
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Security".to_string(),
+            description: String::new(),
+            content: "Never commit secrets.".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_source_for_matches_source_and_paths_beneath_it() {
+        let policy = OrgPolicy {
+            sources: vec![SOURCE.to_string()],
+            allowed_overrides: Vec::new(),
+        };
+        assert_eq!(
+            policy.source_for(&format!("{}#rules/a.md", SOURCE)),
+            Some(SOURCE)
+        );
+        assert_eq!(policy.source_for(SOURCE), Some(SOURCE));
+        assert_eq!(policy.source_for(&format!("{}-fork#a.md", SOURCE)), None);
+    }
+
+    #[tokio::test]
+    async fn test_managed_rule_allows_only_permitted_overrides() {
+        let db = Database::new_in_memory().await.unwrap();
+        let rule = managed_rule(&db).await;
+
+        let adapters = UpdateRuleInput {
+            name: Some(rule.name.clone()),
+            enabled_adapters: Some(vec![AdapterType::ClaudeCode]),
+            ..Default::default()
+        };
+        ensure_editable(&db, "Rule", &adapters, &rule)
+            .await
+            .unwrap();
+
+        let content = UpdateRuleInput {
+            content: Some("Commit whatever.".to_string()),
+            ..Default::default()
+        };
+        let err = ensure_editable(&db, "Rule", &content, &rule)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("content"));
+
+        let disable = UpdateRuleInput {
+            enabled: Some(false),
+            ..Default::default()
+        };
+        assert!(ensure_editable(&db, "Rule", &disable, &rule).await.is_err());
+        assert!(ensure_deletable(&db, "Rule", &rule.name, &rule.metadata)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_removing_source_releases_artifacts() {
+        let db = Database::new_in_memory().await.unwrap();
+        let rule = managed_rule(&db).await;
+
+        save_org_policy(&db, OrgPolicy::default()).await.unwrap();
+
+        ensure_deletable(&db, "Rule", &rule.name, &rule.metadata)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_keep_marker_restores_dropped_marker() {
+        let mut existing = Metadata::new();
+        existing.insert(MANAGED_METADATA_KEY, SOURCE);
+        let mut replacement = Metadata::new();
+        replacement.insert("owner", "platform");
+
+        keep_marker(&existing, Some(&mut replacement));
+
+        assert_eq!(replacement.get_str(MANAGED_METADATA_KEY), Some(SOURCE));
+        assert_eq!(replacement.get_str("owner"), Some("platform"));
+    }
+}
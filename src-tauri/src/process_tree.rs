@@ -0,0 +1,142 @@
+//! Killing a spawned shell together with everything it started.
+//!
+//! Killing only the shell leaves its children (a test runner, a dev server) running. On
+//! Unix the shell is started in its own process group and the whole group is signalled; on
+//! Windows the process is placed in a Job Object, which is terminated as a unit and kills
+//! any remaining members when it is closed.
+
+use tokio::process::{Child, Command};
+
+use crate::models::SandboxProfile;
+
+/// Must be called on the command before it is spawned.
+pub fn prepare(cmd: &mut Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+pub struct ProcessTree {
+    #[cfg(unix)]
+    pgid: Option<i32>,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+impl ProcessTree {
+    /// On Windows the sandbox's CPU and memory limits are applied to the job; on Unix they
+    /// were already set with `setrlimit` before exec.
+    pub fn attach(child: &Child, sandbox: Option<&SandboxProfile>) -> Self {
+        #[cfg(unix)]
+        {
+            let _ = sandbox;
+            Self {
+                pgid: child.id().and_then(|pid| i32::try_from(pid).ok()),
+            }
+        }
+        #[cfg(windows)]
+        {
+            Self {
+                job: child
+                    .raw_handle()
+                    .and_then(|handle| job::Job::for_process(handle, sandbox)),
+            }
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            let _ = (child, sandbox);
+            Self {}
+        }
+    }
+
+    /// Forcibly stops the process and all of its descendants.
+    pub fn kill(&self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid {
+            // SAFETY: kill has no memory-safety preconditions; a negative pid addresses
+            // the process group created by `prepare`.
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+        #[cfg(windows)]
+        if let Some(job) = &self.job {
+            job.terminate();
+        }
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::os::windows::io::RawHandle;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+        JOB_OBJECT_LIMIT_PROCESS_TIME,
+    };
+
+    use crate::models::SandboxProfile;
+
+    pub struct Job(HANDLE);
+
+    // SAFETY: a job handle may be used and closed from any thread.
+    unsafe impl Send for Job {}
+    unsafe impl Sync for Job {}
+
+    impl Job {
+        pub fn for_process(process: RawHandle, sandbox: Option<&SandboxProfile>) -> Option<Self> {
+            // SAFETY: every pointer passed is either null or points at a live local, and
+            // the job handle is owned by the returned value.
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return None;
+                }
+                let job = Job(handle);
+
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                if let Some(secs) = sandbox.and_then(|s| s.cpu_time_secs) {
+                    // Job time limits are in 100-nanosecond units.
+                    info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                    info.BasicLimitInformation.PerProcessUserTimeLimit =
+                        secs.saturating_mul(10_000_000) as i64;
+                }
+                if let Some(mb) = sandbox.and_then(|s| s.memory_mb) {
+                    info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                    info.ProcessMemoryLimit = mb.saturating_mul(1024 * 1024) as usize;
+                }
+                let configured = SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const std::ffi::c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                );
+                if configured == 0 || AssignProcessToJobObject(job.0, process as HANDLE) == 0 {
+                    return None;
+                }
+                Some(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            // SAFETY: the handle stays valid until drop.
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: the handle was created by CreateJobObjectW and is closed only here.
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Per-repository project configuration read from a committed `.ruleweaver.toml`.
+//!
+//! ```toml
+//! adapters = ["claude-code", "cursor"]
+//! language = "ja"
+//!
+//! [rules]
+//! include = ["Rust Style"]
+//! tags = ["backend"]
+//! exclude = ["Legacy API"]
+//!
+//! [variables]
+//! service = "billing"
+//! ```
+//!
+//! The config decides which of the local rules targeting the repository are written into
+//! it, for which tools, in which language variant, and fills `{{name}}` placeholders in
+//! their content. It takes precedence over each rule's own adapters and the per-tool
+//! languages in settings; tools switched off in settings stay off.
+//! Global rules are not affected.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{AppError, Result};
+use crate::localization;
+use crate::models::{AdapterType, ProjectConfig, Rule, Scope};
+
+pub const PROJECT_CONFIG_FILE: &str = ".ruleweaver.toml";
+
+/// Reads the config in `root`, or `None` when the repository has none.
+pub fn load(root: &Path) -> Result<Option<ProjectConfig>> {
+    let path = root.join(PROJECT_CONFIG_FILE);
+    let encoded = match std::fs::read_to_string(&path) {
+        Ok(encoded) => encoded,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let config = parse(&encoded).map_err(|message| AppError::InvalidInput {
+        message: format!("Invalid {}: {}", path.display(), message),
+    })?;
+    Ok(Some(config))
+}
+
+fn parse(encoded: &str) -> std::result::Result<ProjectConfig, String> {
+    let config: ProjectConfig = toml::from_str(encoded).map_err(|e| e.to_string())?;
+    for adapter in config.adapters.iter().flatten() {
+        AdapterType::from_str(adapter).map_err(|_| format!("unknown adapter '{}'", adapter))?;
+    }
+    if let Some(language) = &config.language {
+        localization::validate_language(language)
+            .map_err(|_| format!("'{}' is not a language code such as 'en'", language))?;
+    }
+    Ok(config)
+}
+
+fn matches_rule(entries: &[String], rule: &Rule) -> bool {
+    entries
+        .iter()
+        .any(|entry| entry == &rule.id || entry.trim().eq_ignore_ascii_case(&rule.name))
+}
+
+fn selects(config: &ProjectConfig, rule: &Rule) -> bool {
+    let selection = &config.rules;
+    if matches_rule(&selection.exclude, rule) {
+        return false;
+    }
+    (selection.include.is_empty() && selection.tags.is_empty())
+        || matches_rule(&selection.include, rule)
+        || rule.metadata.has_any_tag(&selection.tags)
+}
+
+/// Replaces `{{name}}` and `{{ name }}` with the config's variables. Unknown placeholders
+/// are left as they are.
+fn render(config: &ProjectConfig, content: &str) -> String {
+    let mut rendered = content.to_string();
+    for (name, value) in &config.variables {
+        rendered = rendered
+            .replace(&format!("{{{{{}}}}}", name), value)
+            .replace(&format!("{{{{ {} }}}}", name), value);
+    }
+    rendered
+}
+
+/// `rule` as written into one configured repository.
+fn for_repository(config: &ProjectConfig, rule: &Rule, root: &str) -> Rule {
+    let mut scoped = rule.clone();
+    scoped.target_paths = Some(vec![root.to_string()]);
+    if let Some(adapters) = &config.adapters {
+        scoped.enabled_adapters = adapters
+            .iter()
+            .filter_map(|a| AdapterType::from_str(a).ok())
+            .collect();
+    }
+    match &config.language {
+        Some(language) => {
+            scoped = localization::localize(&scoped, Some(language));
+            scoped.content = render(config, &scoped.content);
+        }
+        None => {
+            scoped.content = render(config, &rule.content);
+            localization::map_variants(&mut scoped, |content| render(config, content));
+        }
+    }
+    scoped
+}
+
+/// Project configs by repository root, each read once.
+#[derive(Default)]
+struct ProjectConfigs {
+    loaded: HashMap<PathBuf, Option<ProjectConfig>>,
+}
+
+impl ProjectConfigs {
+    /// The config for `root`. An unreadable or invalid config is logged and ignored.
+    fn get(&mut self, root: &str) -> Option<&ProjectConfig> {
+        self.loaded
+            .entry(PathBuf::from(root))
+            .or_insert_with_key(|root| match load(root) {
+                Ok(config) => config,
+                Err(e) => {
+                    log::warn!("Ignoring project config: {}", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
+
+/// The rules as they apply in each repository. A local rule is split per configured
+/// repository it targets: kept only where that repository's config selects it, with the
+/// config's adapters and variables. Repositories without a config and global rules are
+/// left as they are.
+pub fn apply_to_rules(rules: Vec<Rule>) -> Vec<Rule> {
+    let mut configs = ProjectConfigs::default();
+    let mut applied = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let targets = match (&rule.scope, &rule.target_paths) {
+            (Scope::Local, Some(targets)) if !targets.is_empty() => targets.clone(),
+            _ => {
+                applied.push(rule);
+                continue;
+            }
+        };
+
+        let mut unconfigured = Vec::new();
+        let mut scoped = Vec::new();
+        for root in targets {
+            match configs.get(&root) {
+                None => unconfigured.push(root),
+                Some(config) if selects(config, &rule) => {
+                    scoped.push(for_repository(config, &rule, &root))
+                }
+                Some(_) => {}
+            }
+        }
+        if !unconfigured.is_empty() {
+            let mut rest = rule;
+            rest.target_paths = Some(unconfigured);
+            applied.push(rest);
+        }
+        applied.extend(scoped);
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Metadata, TAGS_METADATA_KEY};
+    use chrono::Utc;
+
+    fn rule(name: &str, tags: &[&str], targets: &[&Path]) -> Rule {
+        let mut metadata = Metadata::new();
+        metadata.insert(TAGS_METADATA_KEY, tags.to_vec());
+        Rule {
+            id: format!("id-{}", name),
+            name: name.to_string(),
+            description: String::new(),
+            content: "Service: {{ service }}, owner: {{owner}}".to_string(),
+            scope: Scope::Local,
+            target_paths: Some(
+                targets
+                    .iter()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .collect(),
+            ),
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_adapters_and_keys() {
+        assert!(parse("adapters = [\"claude-code\"]").is_ok());
+        assert!(parse("adapters = [\"vim\"]").is_err());
+        assert!(parse("[rules]\nonly = []").is_err());
+        assert!(parse("language = \"ja\"").is_ok());
+        assert!(parse("language = \"Japanese!\"").is_err());
+    }
+
+    #[test]
+    fn test_config_selects_rules_and_overrides_adapters() {
+        let configured = tempfile::tempdir().unwrap();
+        let plain = tempfile::tempdir().unwrap();
+        std::fs::write(
+            configured.path().join(PROJECT_CONFIG_FILE),
+            r#"
+adapters = ["cursor", "claude-code"]
+
+[rules]
+include = ["style"]
+tags = ["backend"]
+exclude = ["Legacy"]
+
+[variables]
+service = "billing"
+"#,
+        )
+        .unwrap();
+        let both = [configured.path(), plain.path()];
+
+        let applied = apply_to_rules(vec![
+            rule("Style", &[], &both),
+            rule("Api", &["Backend"], &[configured.path()]),
+            rule("Legacy", &["backend"], &both),
+            rule("Frontend", &["frontend"], &[configured.path()]),
+        ]);
+
+        let in_repo = |root: &Path| -> Vec<&Rule> {
+            let root = root.to_string_lossy();
+            applied
+                .iter()
+                .filter(|r| r.target_paths.as_ref().unwrap()[0] == root)
+                .collect()
+        };
+        let in_configured = in_repo(configured.path());
+        let names: Vec<&str> = in_configured.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Style", "Api"]);
+        assert_eq!(
+            in_configured[0].enabled_adapters,
+            vec![AdapterType::Cursor, AdapterType::ClaudeCode]
+        );
+        assert_eq!(
+            in_configured[0].content,
+            "Service: billing, owner: {{owner}}"
+        );
+
+        let in_plain: Vec<&str> = in_repo(plain.path())
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(in_plain, vec!["Style", "Legacy"]);
+    }
+}
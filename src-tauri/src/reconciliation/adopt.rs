@@ -0,0 +1,195 @@
+//! Adopting orphaned artifacts instead of removing them.
+//!
+//! A generated file that no longer belongs to any rule, command or skill is imported back
+//! through the regular import pipeline. Adopted slash commands are set to regenerate for the
+//! adapter and scope they were found in, so the next reconcile keeps the file instead of
+//! deleting it.
+
+use std::collections::HashSet;
+
+use super::{FoundArtifact, ReconciliationEngine, MAX_FILE_SIZE_BYTES};
+use crate::error::{AppError, Result};
+use crate::models::registry::ArtifactType;
+use crate::models::{
+    AdapterType, ImportArtifactType, ImportConflictMode, ImportExecutionOptions,
+    ImportExecutionResult, ImportScanResult, Scope, UpdateCommandInput,
+};
+use crate::rule_import::{self, adoption};
+
+fn import_artifact_type(artifact: &FoundArtifact) -> Result<ImportArtifactType> {
+    match artifact.artifact_type {
+        Some(ArtifactType::Rule) => Ok(ImportArtifactType::Rule),
+        Some(ArtifactType::SlashCommand) => Ok(ImportArtifactType::SlashCommand),
+        Some(ArtifactType::Skill) => Ok(ImportArtifactType::Skill),
+        Some(ArtifactType::CommandStub) | None => Err(AppError::InvalidInput {
+            message: format!(
+                "{} cannot be adopted: it is not a rule, slash command or skill",
+                artifact.path.display()
+            ),
+        }),
+    }
+}
+
+impl ReconciliationEngine {
+    /// Imports the given orphaned files as artifacts. Only paths the current plan would
+    /// remove can be adopted; anything else is reported as an error.
+    pub async fn adopt_orphans(&self, paths: &[String]) -> Result<ImportExecutionResult> {
+        let desired = self.compute_desired_state().await?;
+        let actual = self.scan_actual_state().await?;
+        let plan = self.plan(&desired, &actual);
+
+        let requested: HashSet<&str> = paths.iter().map(String::as_str).collect();
+        let mut errors = Vec::new();
+        let mut candidates = Vec::new();
+        // Slash command scripts with where they were found, to re-enable generation after import.
+        let mut slash_targets: Vec<(String, AdapterType, Scope)> = Vec::new();
+
+        let mut found = HashSet::new();
+        for orphan in &plan.to_remove {
+            let path = orphan.path.to_string_lossy().to_string();
+            if !requested.contains(path.as_str()) {
+                continue;
+            }
+            found.insert(path.clone());
+
+            let candidate = import_artifact_type(orphan).and_then(|artifact_type| {
+                adoption::candidate_from_orphan(
+                    &orphan.path,
+                    orphan.adapter,
+                    artifact_type,
+                    orphan.scope.unwrap_or_default(),
+                    MAX_FILE_SIZE_BYTES,
+                )
+            });
+            match candidate {
+                Ok(candidate) => {
+                    if let (ImportArtifactType::SlashCommand, Some(adapter)) =
+                        (candidate.artifact_type, orphan.adapter)
+                    {
+                        slash_targets.push((
+                            candidate.content.clone(),
+                            adapter,
+                            orphan.scope.unwrap_or_default(),
+                        ));
+                    }
+                    candidates.push(candidate);
+                }
+                Err(e) => errors.push(format!("Failed to adopt {}: {}", path, e)),
+            }
+        }
+        for path in paths.iter().filter(|p| !found.contains(p.as_str())) {
+            errors.push(format!("{} is not an orphaned artifact", path));
+        }
+
+        let mut result = if candidates.is_empty() {
+            ImportExecutionResult::default()
+        } else {
+            rule_import::execute_import(
+                self.db.clone(),
+                ImportScanResult {
+                    candidates,
+                    ..Default::default()
+                },
+                ImportExecutionOptions {
+                    conflict_mode: ImportConflictMode::Rename,
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        let mut regenerate = false;
+        for command in &mut result.imported_commands {
+            let Some((_, adapter, scope)) = slash_targets
+                .iter()
+                .find(|(script, _, _)| *script == command.script)
+            else {
+                continue;
+            };
+            *command = self
+                .db
+                .update_command(
+                    &command.id,
+                    UpdateCommandInput {
+                        generate_slash_commands: Some(true),
+                        slash_command_adapters: Some(vec![adapter.as_str().to_string()]),
+                        slash_scope: Some(*scope),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+            regenerate = true;
+        }
+        // The import's own reconcile ran before generation was enabled and removed the
+        // original files, so write them back now.
+        if regenerate {
+            let reconciled = self.reconcile(false, None).await?;
+            result.errors.extend(reconciled.errors);
+        }
+
+        result.errors.extend(errors);
+        log::info!(
+            "Adopted {} rule(s), {} command(s) and {} skill(s) from orphaned files",
+            result.imported_rules.len(),
+            result.imported_commands.len(),
+            result.imported_skills.len()
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::path_resolver::PathResolver;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_adopted_slash_command_is_kept_by_next_reconcile() {
+        let home = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let engine = ReconciliationEngine::new_with_resolver(
+            Arc::clone(&db),
+            PathResolver::new_with_home(home.path().to_path_buf(), vec![]),
+        );
+
+        let commands_dir = home.path().join(".claude").join("commands");
+        std::fs::create_dir_all(&commands_dir).unwrap();
+        let orphan = commands_dir.join("tidy.md");
+        std::fs::write(
+            &orphan,
+            "<!-- Generated by RuleWeaver - Do not edit manually -->\n---\ndescription: Tidy up\n---\n\nTidy the workspace.\n",
+        )
+        .unwrap();
+        let orphan_path = orphan.to_string_lossy().to_string();
+        assert!(engine
+            .get_stale_paths()
+            .await
+            .unwrap()
+            .iter()
+            .any(|a| a.path == orphan));
+
+        let result = engine
+            .adopt_orphans(&[orphan_path, "/not/an/orphan.md".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(result.imported_commands.len(), 1);
+        let command = &result.imported_commands[0];
+        assert_eq!(command.name, "tidy");
+        assert_eq!(command.description, "Tidy up");
+        assert!(command.generate_slash_commands);
+        assert_eq!(
+            command.slash_command_adapters,
+            vec!["claude-code".to_string()]
+        );
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.contains("/not/an/orphan.md")));
+
+        assert!(orphan.exists());
+        assert!(!engine.needs_reconciliation().await.unwrap());
+    }
+}
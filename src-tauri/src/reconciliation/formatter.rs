@@ -33,12 +33,7 @@ pub fn format_command_stub_content(adapter: &AdapterType, commands: &[Command])
                 args.insert(
                     arg.name.clone(),
                     CommandStubArg {
-                        arg_type: match arg.arg_type {
-                            crate::models::ArgumentType::String => "string".to_string(),
-                            crate::models::ArgumentType::Number => "number".to_string(),
-                            crate::models::ArgumentType::Boolean => "boolean".to_string(),
-                            crate::models::ArgumentType::Enum => "enum".to_string(),
-                        },
+                        arg_type: arg.arg_type.as_str().to_string(),
                         required: arg.required,
                     },
                 );
@@ -101,11 +96,56 @@ pub fn format_rule_content(name: &str, content: &str) -> String {
     )
 }
 
-/// Format skill content for writing to SKILL.md files.
+/// SKILL.md frontmatter per the Claude skills spec.
+#[derive(serde::Serialize)]
+struct SkillFrontmatter<'a> {
+    name: String,
+    description: &'a str,
+    #[serde(rename = "allowed-tools", skip_serializing_if = "Option::is_none")]
+    allowed_tools: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<&'a str>,
+    metadata: SkillFrontmatterMetadata<'a>,
+}
+
+#[derive(serde::Serialize)]
+struct SkillFrontmatterMetadata<'a> {
+    version: &'a str,
+}
+
+/// Longest description the skills spec accepts.
+const MAX_SKILL_DESCRIPTION_CHARS: usize = 1024;
+
+/// Format skill content for writing to SKILL.md files: YAML frontmatter, which must open
+/// the file, then the instructions.
 pub fn format_skill_content(skill: &Skill) -> String {
+    let description = if skill.description.trim().is_empty() {
+        skill.name.trim()
+    } else {
+        skill.description.trim()
+    };
+    let description = match description.char_indices().nth(MAX_SKILL_DESCRIPTION_CHARS) {
+        Some((end, _)) => &description[..end],
+        None => description,
+    };
+    let frontmatter = SkillFrontmatter {
+        name: crate::path_resolver::sanitize_skill_name(&skill.name),
+        description,
+        allowed_tools: (!skill.allowed_tools.is_empty()).then(|| skill.allowed_tools.join(", ")),
+        license: skill.license.as_deref().filter(|l| !l.trim().is_empty()),
+        metadata: SkillFrontmatterMetadata {
+            version: &skill.version,
+        },
+    };
+    let yaml = serde_yaml::to_string(&frontmatter).unwrap_or_else(|e| {
+        log::error!("Failed to serialize skill frontmatter: {}", e);
+        String::new()
+    });
+
     let mut content = format!(
-        "<!-- Generated by RuleWeaver - Do not edit manually -->\n\n# {}\n\n{}\n\n## Instructions\n\n{}\n",
-        skill.name, skill.description, skill.instructions
+        "---\n{}---\n\n<!-- Generated by RuleWeaver - Do not edit manually -->\n\n{}\n",
+        yaml,
+        skill.instructions.trim_end()
     );
 
     if !skill.input_schema.is_empty() {
@@ -128,7 +168,16 @@ pub fn format_skill_content(skill: &Skill) -> String {
         }
     }
 
-    content.push_str(&format!("\n## Entry Point\n\n`{}`\n", skill.entry_point));
+    if !skill.entry_point.is_empty() {
+        content.push_str(&format!("\n## Entry Point\n\n`{}`\n", skill.entry_point));
+    }
+
+    if !skill.bundle_files.is_empty() {
+        content.push_str("\n## Bundled Files\n\n");
+        for file in &skill.bundle_files {
+            content.push_str(&format!("- `{}`\n", file));
+        }
+    }
 
     content
 }
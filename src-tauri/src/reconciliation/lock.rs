@@ -0,0 +1,315 @@
+//! Repository lock files for CI.
+//!
+//! `ruleweaver lock` records the files RuleWeaver generates in a repository, with their content
+//! hashes, in `.ruleweaver/lock.json`. Once committed, `ruleweaver verify` checks a checkout
+//! against it without a RuleWeaver database, so a pipeline can fail when someone commits
+//! generated files that were edited by hand or synced from stale rules.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{compute_content_hash, ReconciliationEngine, RULEWEAVER_MARKER};
+use crate::atomic_write::write_atomic;
+use crate::error::{AppError, Result};
+use crate::file_storage::RULEWEAVER_DIR_NAME;
+use crate::models::registry::ArtifactType;
+use crate::models::AdapterType;
+use crate::path_resolver::path_resolver;
+
+const LOCK_FILE_NAME: &str = "lock.json";
+const LOCK_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoLock {
+    pub version: u32,
+    /// Sorted by path, so regenerating an unchanged lock leaves it byte-identical
+    pub files: Vec<LockedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedFile {
+    /// Relative to the repository root, `/`-separated
+    pub path: String,
+    pub adapter: AdapterType,
+    pub artifact_type: ArtifactType,
+    pub content_hash: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LockDriftKind {
+    Missing,
+    Modified,
+    /// A RuleWeaver-generated rule or command file the lock does not list
+    Unexpected,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockDrift {
+    pub path: String,
+    pub kind: LockDriftKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub repo_root: String,
+    pub checked: usize,
+    pub drift: Vec<LockDrift>,
+}
+
+pub fn lock_path(repo_root: &Path) -> PathBuf {
+    repo_root.join(RULEWEAVER_DIR_NAME).join(LOCK_FILE_NAME)
+}
+
+fn relative_path(repo_root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(repo_root).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// Lock entries come from a committed file, so they must stay inside the repository.
+fn resolve_locked_path(repo_root: &Path, relative: &str) -> Result<PathBuf> {
+    let path = Path::new(relative);
+    if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(AppError::InvalidInput {
+            message: format!("Lock file entry escapes the repository: {}", relative),
+        });
+    }
+    Ok(repo_root.join(path))
+}
+
+/// Hashes as generated. Checkouts with `core.autocrlf` turn the LF endings RuleWeaver writes
+/// into CRLF, which is not drift.
+fn checkout_hash(content: &str) -> String {
+    compute_content_hash(&content.replace("\r\n", "\n"))
+}
+
+impl ReconciliationEngine {
+    /// The files the desired state puts in `repo_root`. Use an engine filtered to that root
+    /// to skip computing every other repository's files.
+    pub async fn lock_repository(&self, repo_root: &Path) -> Result<RepoLock> {
+        let desired = self.compute_desired_state().await?;
+        let mut files: Vec<LockedFile> = desired
+            .expected_paths
+            .iter()
+            .filter(|(_, expected)| expected.repo_root.as_deref() == Some(repo_root))
+            .filter_map(|(path, expected)| {
+                Some(LockedFile {
+                    path: relative_path(repo_root, Path::new(path))?,
+                    adapter: expected.adapter,
+                    artifact_type: expected.artifact_type,
+                    content_hash: expected.content_hash.clone(),
+                })
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(RepoLock {
+            version: LOCK_VERSION,
+            files,
+        })
+    }
+}
+
+pub fn write_lock(repo_root: &Path, lock: &RepoLock) -> Result<PathBuf> {
+    let path = lock_path(repo_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut json = serde_json::to_string_pretty(lock)?;
+    json.push('\n');
+    write_atomic(&path, json)?;
+    Ok(path)
+}
+
+pub fn read_lock(repo_root: &Path) -> Result<RepoLock> {
+    let path = lock_path(repo_root);
+    let content = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::InvalidInput {
+            message: format!(
+                "{} not found; run `ruleweaver lock --repo <path>` and commit it",
+                path.display()
+            ),
+        },
+        _ => AppError::Io(e),
+    })?;
+    let lock: RepoLock = serde_json::from_str(&content)?;
+    if lock.version > LOCK_VERSION {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "{} has version {}; this RuleWeaver reads up to version {}",
+                path.display(),
+                lock.version,
+                LOCK_VERSION
+            ),
+        });
+    }
+    Ok(lock)
+}
+
+/// Compares `repo_root` with its committed lock. Besides the locked files, the rule and
+/// command files every adapter reads are checked for generated files the lock lacks.
+pub fn verify_repository(repo_root: &Path) -> Result<VerifyReport> {
+    let lock = read_lock(repo_root)?;
+    let mut drift = Vec::new();
+
+    for locked in &lock.files {
+        let path = resolve_locked_path(repo_root, &locked.path)?;
+        let kind = match fs::read_to_string(&path) {
+            Ok(content) if checkout_hash(&content) == locked.content_hash => continue,
+            Ok(_) => LockDriftKind::Modified,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => LockDriftKind::Missing,
+            Err(e) => return Err(AppError::Io(e)),
+        };
+        drift.push(LockDrift {
+            path: locked.path.clone(),
+            kind,
+        });
+    }
+
+    let locked: BTreeSet<&str> = lock.files.iter().map(|f| f.path.as_str()).collect();
+    let mut seen = BTreeSet::new();
+    for adapter in AdapterType::all() {
+        for artifact_type in [ArtifactType::Rule, ArtifactType::CommandStub] {
+            let Ok(resolved) = path_resolver().local_path(adapter, artifact_type, repo_root) else {
+                continue;
+            };
+            let Some(relative) = relative_path(repo_root, &resolved.path) else {
+                continue;
+            };
+            if locked.contains(relative.as_str()) || !seen.insert(relative.clone()) {
+                continue;
+            }
+            let generated = fs::read_to_string(&resolved.path)
+                .is_ok_and(|content| content.contains(RULEWEAVER_MARKER));
+            if generated {
+                drift.push(LockDrift {
+                    path: relative,
+                    kind: LockDriftKind::Unexpected,
+                });
+            }
+        }
+    }
+
+    Ok(VerifyReport {
+        repo_root: repo_root.to_string_lossy().to_string(),
+        checked: lock.files.len(),
+        drift,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::models::{CreateRuleInput, Scope};
+    use crate::reconciliation::ReconcileFilter;
+    use std::sync::Arc;
+
+    async fn locked_repo() -> (tempfile::TempDir, PathBuf) {
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path().to_path_buf();
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        crate::commands::register_local_paths(&db, &[root.to_string_lossy().to_string()])
+            .await
+            .unwrap();
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Repo".to_string(),
+            description: String::new(),
+            content: "Use tabs".to_string(),
+            scope: Scope::Local,
+            target_paths: Some(vec![root.to_string_lossy().to_string()]),
+            enabled_adapters: vec![AdapterType::ClaudeCode, AdapterType::Cursor],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+
+        let engine = ReconciliationEngine::new(db)
+            .unwrap()
+            .with_filter(ReconcileFilter {
+                repo_root: Some(root.clone()),
+                ..Default::default()
+            });
+        engine.reconcile(false, None).await.unwrap();
+        let lock = engine.lock_repository(&root).await.unwrap();
+        write_lock(&root, &lock).unwrap();
+        (repo, root)
+    }
+
+    #[tokio::test]
+    async fn test_verify_passes_for_locked_checkout() {
+        let (_repo, root) = locked_repo().await;
+
+        let lock = read_lock(&root).unwrap();
+        assert!(lock.files.iter().any(|f| f.path == ".claude/CLAUDE.md"));
+        assert!(lock.files.windows(2).all(|w| w[0].path < w[1].path));
+
+        // CRLF checkouts are not drift.
+        let claude = root.join(".claude/CLAUDE.md");
+        let content = fs::read_to_string(&claude).unwrap();
+        fs::write(&claude, content.replace('\n', "\r\n")).unwrap();
+
+        let report = verify_repository(&root).unwrap();
+        assert_eq!(report.checked, lock.files.len());
+        assert!(report.drift.is_empty(), "{:?}", report.drift);
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_missing_modified_and_unexpected_files() {
+        let (_repo, root) = locked_repo().await;
+        let lock = read_lock(&root).unwrap();
+        let cursor = lock
+            .files
+            .iter()
+            .find(|f| f.adapter == AdapterType::Cursor)
+            .unwrap();
+        fs::remove_file(root.join(&cursor.path)).unwrap();
+        fs::write(root.join(".claude/CLAUDE.md"), "hand edited").unwrap();
+        let gemini = path_resolver()
+            .local_path(AdapterType::Gemini, ArtifactType::Rule, &root)
+            .unwrap()
+            .path;
+        fs::create_dir_all(gemini.parent().unwrap()).unwrap();
+        fs::write(&gemini, format!("<!-- {} -->\nold", RULEWEAVER_MARKER)).unwrap();
+
+        let drift = verify_repository(&root).unwrap().drift;
+        let kind_of = |path: &str| drift.iter().find(|d| d.path == path).map(|d| d.kind);
+        assert_eq!(kind_of(&cursor.path), Some(LockDriftKind::Missing));
+        assert_eq!(kind_of(".claude/CLAUDE.md"), Some(LockDriftKind::Modified));
+        assert_eq!(
+            kind_of(&relative_path(&root, &gemini).unwrap()),
+            Some(LockDriftKind::Unexpected)
+        );
+    }
+
+    #[test]
+    fn test_lock_entries_cannot_escape_the_repository() {
+        let root = Path::new("/repo");
+        assert!(resolve_locked_path(root, "../etc/passwd").is_err());
+        assert!(resolve_locked_path(root, "/etc/passwd").is_err());
+        assert!(resolve_locked_path(root, "").is_err());
+        assert_eq!(
+            resolve_locked_path(root, ".claude/CLAUDE.md").unwrap(),
+            root.join(".claude/CLAUDE.md")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            verify_repository(dir.path()),
+            Err(AppError::InvalidInput { .. })
+        ));
+    }
+}
@@ -20,20 +20,30 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
 
+use crate::atomic_write::write_atomic;
+use crate::audit;
 use crate::database::Database;
 use crate::error::Result;
+use crate::jobs::{JobHandle, ReconcileJob};
+use crate::metrics;
 use crate::models::registry::{ArtifactType, REGISTRY};
-use crate::models::{AdapterType, ReconcileOperation, ReconcileResultType, Scope};
+use crate::models::{
+    AdapterType, AuditAction, JobKind, ReconcileOperation, ReconcileResultType, Scope, WebhookEvent,
+};
 use crate::path_resolver::PathResolver;
+use crate::policy;
 use crate::slash_commands::adapters::get_adapter;
+use protection::ProtectedPaths;
+use scan::{FileStamp, ScanCache};
 
 const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
 
@@ -41,7 +51,17 @@ const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
 /// Only files containing this marker should be considered for removal during reconciliation.
 const RULEWEAVER_MARKER: &str = "Generated by RuleWeaver";
 
+/// Repository roots local slash commands were last reconciled into. They stay scanned after a
+/// repository is deregistered so its copies can still be removed.
+pub(crate) const SLASH_COMMAND_ROOTS_KEY: &str = "slash_command_roots";
+
+pub mod adopt;
 pub mod formatter;
+pub mod lock;
+pub mod protection;
+pub mod repo_roots;
+pub mod review;
+mod scan;
 
 /// Represents the desired state of generated artifacts.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -50,6 +70,39 @@ pub struct DesiredState {
     /// All paths that should exist with their expected content hashes
     #[serde(default)]
     pub expected_paths: HashMap<String, ExpectedArtifact>,
+    /// Skill bundles to copy next to the SKILL.md at each path (used internally)
+    #[serde(skip)]
+    pub skill_bundles: HashMap<String, SkillBundle>,
+    /// Targeted adapters without native skills support, which reach the skill through the
+    /// MCP server instead of a SKILL.md
+    #[serde(default)]
+    pub skill_fallbacks: Vec<SkillFallback>,
+    /// User-protected globs that reconciliation must leave untouched
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
+    /// Repository roots that no longer exist, which nothing is written into
+    #[serde(default)]
+    pub missing_repo_roots: Vec<PathBuf>,
+    /// IDs of org-managed artifacts, whose files are written even under protected paths
+    #[serde(default)]
+    pub managed_ids: HashSet<String>,
+}
+
+/// A skill/adapter pair served through the MCP server rather than written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillFallback {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub adapter: AdapterType,
+    pub scope: Scope,
+}
+
+/// Files from a skill's source directory that are copied next to its SKILL.md.
+#[derive(Debug, Clone)]
+pub struct SkillBundle {
+    pub source_dir: PathBuf,
+    pub files: Vec<String>,
 }
 
 /// An artifact that should exist in the desired state.
@@ -117,6 +170,12 @@ pub struct ReconcilePlan {
     /// Paths that are unchanged
     #[serde(default)]
     pub unchanged: Vec<PathBuf>,
+    /// Paths left out because they match a protected glob
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Skill bundles for the created and updated paths (used internally)
+    #[serde(skip)]
+    pub skill_bundles: HashMap<String, SkillBundle>,
 }
 
 /// A resolved artifact in the reconciliation plan.
@@ -130,6 +189,9 @@ pub struct ResolvedArtifact {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repo_root: Option<PathBuf>,
     pub content_hash: String,
+    /// Hash of the file on disk when the plan was made (updates only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_hash: Option<String>,
     /// The actual content to write (not serialized, used internally)
     #[serde(skip)]
     pub content: Option<String>,
@@ -157,23 +219,126 @@ pub struct ReconcileResult {
     pub warnings: Vec<String>,
 }
 
+/// Narrows a reconciliation to some artifact types, adapters or one repository. Empty lists
+/// mean "all"; with `repo_root` set, only that repository's local artifacts are considered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileFilter {
+    #[serde(default)]
+    pub artifact_types: Vec<ArtifactType>,
+    #[serde(default)]
+    pub adapters: Vec<AdapterType>,
+    #[serde(default)]
+    pub repo_root: Option<PathBuf>,
+}
+
+impl ReconcileFilter {
+    pub fn is_unfiltered(&self) -> bool {
+        self.artifact_types.is_empty() && self.adapters.is_empty() && self.repo_root.is_none()
+    }
+
+    pub fn includes_type(&self, artifact_type: ArtifactType) -> bool {
+        self.artifact_types.is_empty() || self.artifact_types.contains(&artifact_type)
+    }
+
+    pub fn includes_adapter(&self, adapter: AdapterType) -> bool {
+        self.adapters.is_empty() || self.adapters.contains(&adapter)
+    }
+
+    /// Whether global (home directory) artifacts are in scope.
+    pub fn includes_global(&self) -> bool {
+        self.repo_root.is_none()
+    }
+
+    /// The subset of `roots` in scope.
+    pub fn roots<'a>(&self, roots: &'a [PathBuf]) -> Vec<&'a PathBuf> {
+        roots
+            .iter()
+            .filter(|root| self.repo_root.as_ref().is_none_or(|only| only == *root))
+            .collect()
+    }
+
+    fn includes(
+        &self,
+        adapter: AdapterType,
+        artifact_type: ArtifactType,
+        repo_root: Option<&Path>,
+    ) -> bool {
+        self.includes_adapter(adapter)
+            && self.includes_type(artifact_type)
+            && self
+                .repo_root
+                .as_ref()
+                .is_none_or(|only| repo_root == Some(only.as_path()))
+    }
+
+    /// Whether a found file is in scope. Its repository is judged by its path.
+    fn includes_found(&self, found: &FoundArtifact) -> bool {
+        found.adapter.is_none_or(|a| self.includes_adapter(a))
+            && found.artifact_type.is_none_or(|t| self.includes_type(t))
+            && self
+                .repo_root
+                .as_ref()
+                .is_none_or(|only| found.path.starts_with(only))
+    }
+}
+
+/// Drops duplicate roots and deleted repositories, which have nothing left to scan.
+fn existing_unique_roots(mut roots: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    roots.retain(|root| seen.insert(root.clone()) && root.is_dir());
+    roots
+}
+
 /// Engine for reconciling desired state with actual filesystem state.
 pub struct ReconciliationEngine {
     db: Arc<Database>,
     path_resolver: PathResolver,
+    filter: ReconcileFilter,
+    scan_cache: parking_lot::Mutex<ScanCache>,
+    /// Adapters switched off in settings, whose files are neither written nor removed.
+    /// Loaded at the start of each computation and scan.
+    disabled_adapters: parking_lot::Mutex<HashSet<AdapterType>>,
 }
 
 impl ReconciliationEngine {
     /// Create a new ReconciliationEngine.
     pub fn new(db: Arc<Database>) -> Result<Self> {
         let path_resolver = PathResolver::new()?;
-        Ok(Self { db, path_resolver })
+        Ok(Self {
+            db,
+            path_resolver,
+            filter: ReconcileFilter::default(),
+            scan_cache: Default::default(),
+            disabled_adapters: Default::default(),
+        })
+    }
+
+    /// Restricts every later computation, scan and plan to `filter`.
+    pub fn with_filter(mut self, filter: ReconcileFilter) -> Self {
+        self.filter = filter;
+        self
     }
 
     /// Create a ReconciliationEngine with an explicit PathResolver (for tests only).
     #[cfg(any(test, feature = "test-helpers"))]
     pub fn new_with_resolver(db: Arc<Database>, path_resolver: PathResolver) -> Self {
-        Self { db, path_resolver }
+        Self {
+            db,
+            path_resolver,
+            filter: ReconcileFilter::default(),
+            scan_cache: Default::default(),
+            disabled_adapters: Default::default(),
+        }
+    }
+
+    async fn load_disabled_adapters(&self) {
+        *self.disabled_adapters.lock() = crate::sync::disabled_adapters(&self.db).await;
+    }
+
+    /// Whether `adapter` is in scope: selected by the filter and not switched off.
+    fn includes_adapter(&self, adapter: AdapterType) -> bool {
+        self.filter.includes_adapter(adapter) && !self.disabled_adapters.lock().contains(&adapter)
     }
 
     /// Compute desired state from all database artifacts.
@@ -182,23 +347,53 @@ impl ReconciliationEngine {
     /// what paths should exist for each artifact type.
     pub async fn compute_desired_state(&self) -> Result<DesiredState> {
         let mut desired = DesiredState::default();
+        self.load_disabled_adapters().await;
 
-        self.compute_desired_state_rules(&mut desired).await?;
-        self.compute_desired_state_command_stubs(&mut desired)
-            .await?;
-        self.compute_desired_state_slash_commands(&mut desired)
-            .await?;
-        self.compute_desired_state_skills(&mut desired).await?;
+        if self.filter.includes_type(ArtifactType::Rule) {
+            self.compute_desired_state_rules(&mut desired).await?;
+        }
+        if self.filter.includes_type(ArtifactType::CommandStub) {
+            self.compute_desired_state_command_stubs(&mut desired)
+                .await?;
+        }
+        if self.filter.includes_type(ArtifactType::SlashCommand) {
+            self.compute_desired_state_slash_commands(&mut desired)
+                .await?;
+        }
+        if self.filter.includes_type(ArtifactType::Skill) {
+            self.compute_desired_state_skills(&mut desired).await?;
+        }
+        desired.protected_paths = protection::list_protected_paths(&self.db).await;
+        desired.missing_repo_roots = repo_roots::missing_roots_for(&self.db, &desired).await;
+
+        if !self.filter.is_unfiltered() || !self.disabled_adapters.lock().is_empty() {
+            let filter = &self.filter;
+            desired.expected_paths.retain(|_, a| {
+                self.includes_adapter(a.adapter)
+                    && filter.includes(a.adapter, a.artifact_type, a.repo_root.as_deref())
+            });
+            let expected = &desired.expected_paths;
+            desired
+                .skill_bundles
+                .retain(|path, _| expected.contains_key(path));
+            desired
+                .skill_fallbacks
+                .retain(|f| self.includes_adapter(f.adapter));
+        }
 
         Ok(desired)
     }
 
     /// Compute desired state for rules.
     async fn compute_desired_state_rules(&self, desired: &mut DesiredState) -> Result<()> {
-        let rules = self.db.get_all_rules().await?;
+        let rules = crate::snippets::expand_rules(&self.db, self.db.get_all_rules().await?).await;
+        let rules = crate::project_config::apply_to_rules(rules);
+        let org_policy = policy::get_org_policy(&self.db).await;
 
         for rule in rules {
-            if !rule.enabled {
+            if org_policy.manages(&rule.metadata) {
+                desired.managed_ids.insert(rule.id.clone());
+            } else if !rule.enabled {
                 continue;
             }
 
@@ -271,7 +466,10 @@ impl ReconciliationEngine {
     async fn compute_desired_state_command_stubs(&self, desired: &mut DesiredState) -> Result<()> {
         let commands = self.db.get_all_commands().await?;
 
-        let exposed_commands: Vec<_> = commands.into_iter().filter(|c| c.expose_via_mcp).collect();
+        let exposed_commands: Vec<_> = commands
+            .into_iter()
+            .filter(|c| c.mcp_policy.is_exposed())
+            .collect();
         if exposed_commands.is_empty() {
             return Ok(());
         }
@@ -314,11 +512,35 @@ impl ReconciliationEngine {
     /// Compute desired state for slash commands (individual command files).
     async fn compute_desired_state_slash_commands(&self, desired: &mut DesiredState) -> Result<()> {
         let commands = self.db.get_all_commands().await?;
+        let registered_roots = crate::commands::get_local_rule_roots(&self.db).await?;
+        let org_policy = policy::get_org_policy(&self.db).await;
 
         for command in commands {
             if !command.generate_slash_commands {
                 continue;
             }
+            if org_policy.manages(&command.metadata) {
+                desired.managed_ids.insert(command.id.clone());
+            }
+
+            // Explicit target paths win; local-scoped commands otherwise go to every
+            // registered repository root.
+            let local_roots: Vec<PathBuf> = if !command.target_paths.is_empty() {
+                command
+                    .target_paths
+                    .iter()
+                    .map(|p| {
+                        PathBuf::from(crate::path_resolver::resolve_workspace_path(
+                            p,
+                            command.base_path.as_deref(),
+                        ))
+                    })
+                    .collect()
+            } else if command.slash_scope == Scope::Local {
+                registered_roots.clone()
+            } else {
+                Vec::new()
+            };
 
             for adapter_name in &command.slash_command_adapters {
                 let Ok(adapter_type) = AdapterType::from_str(adapter_name) else {
@@ -342,20 +564,39 @@ impl ReconciliationEngine {
                         Ok(name) => name,
                         Err(_) => continue,
                     };
+                let namespace = match crate::slash_commands::sync::normalize_command_namespace(
+                    command.slash_namespace.as_deref(),
+                ) {
+                    Ok(ns) => ns,
+                    Err(_) => continue,
+                };
+                let qualified_name = match &namespace {
+                    Some(ns) => format!("{}/{}", ns, safe_name),
+                    None => safe_name.clone(),
+                };
 
                 let content = slash_adapter.format_command(&command);
                 let content_hash = compute_content_hash(&content);
 
-                if let Ok(resolved) =
+                let global = if command.slash_scope == Scope::Global {
                     self.path_resolver
-                        .slash_command_path(adapter_type, &safe_name, true)
-                {
+                        .namespaced_slash_command_path(
+                            adapter_type,
+                            namespace.as_deref(),
+                            &safe_name,
+                            true,
+                        )
+                        .ok()
+                } else {
+                    None
+                };
+                if let Some(resolved) = global {
                     let path_str = resolved.path.to_string_lossy().to_string();
                     desired.expected_paths.insert(
                         path_str.clone(),
                         ExpectedArtifact {
-                            id: format!("command-{}", safe_name),
-                            name: safe_name.clone(),
+                            id: format!("command-{}", qualified_name),
+                            name: qualified_name.clone(),
                             adapter: adapter_type,
                             artifact_type: ArtifactType::SlashCommand,
                             scope: Scope::Global,
@@ -366,26 +607,23 @@ impl ReconciliationEngine {
                     );
                 }
 
-                for target_path in &command.target_paths {
-                    let resolved_target = crate::path_resolver::resolve_workspace_path(
-                        target_path,
-                        command.base_path.as_deref(),
-                    );
-                    if let Ok(resolved) = self.path_resolver.local_slash_command_path(
+                for repo_root in &local_roots {
+                    if let Ok(resolved) = self.path_resolver.local_namespaced_slash_command_path(
                         adapter_type,
+                        namespace.as_deref(),
                         &safe_name,
-                        Path::new(&resolved_target),
+                        repo_root,
                     ) {
                         let path_str = resolved.path.to_string_lossy().to_string();
                         desired.expected_paths.insert(
                             path_str.clone(),
                             ExpectedArtifact {
-                                id: format!("command-{}", safe_name),
-                                name: safe_name.clone(),
+                                id: format!("command-{}", qualified_name),
+                                name: qualified_name.clone(),
                                 adapter: adapter_type,
                                 artifact_type: ArtifactType::SlashCommand,
                                 scope: Scope::Local,
-                                repo_root: Some(PathBuf::from(resolved_target)),
+                                repo_root: Some(repo_root.clone()),
                                 content_hash: content_hash.clone(),
                                 content: Some(content.clone()),
                             },
@@ -401,47 +639,74 @@ impl ReconciliationEngine {
     /// Compute desired state for skills.
     ///
     /// Respects per-skill `target_adapters`: when non-empty only those adapters
-    /// are included; unsupported adapters are silently skipped (MCP-only fallback
-    /// is handled by the MCP layer). When `target_adapters` is empty all registry-
-    /// supported adapters are used (existing behaviour).
+    /// are included, and when empty every adapter is targeted. Targeted adapters
+    /// without native skills support are recorded in `skill_fallbacks`, since they
+    /// only see the skill as an MCP tool.
     ///
     /// For local-scope skills, `target_paths` on the skill take priority over the
     /// global repository roots when non-empty.
     async fn compute_desired_state_skills(&self, desired: &mut DesiredState) -> Result<()> {
         let skills = self.db.get_all_skills().await?;
+        let org_policy = policy::get_org_policy(&self.db).await;
 
         for skill in skills {
-            if !skill.enabled {
+            if org_policy.manages(&skill.metadata) {
+                desired.managed_ids.insert(skill.id.clone());
+            } else if !skill.enabled {
                 continue;
             }
 
             // Determine which adapters to target for this skill.
-            let candidate_adapters: Vec<AdapterType> = if skill.target_adapters.is_empty() {
-                // Default: all adapters that support skills.
+            let targeted: Vec<AdapterType> = if skill.target_adapters.is_empty() {
                 AdapterType::all()
-                    .into_iter()
-                    .filter(|a| {
-                        REGISTRY
-                            .validate_support(a, &skill.scope, ArtifactType::Skill)
-                            .is_ok()
-                    })
-                    .collect()
             } else {
-                // Per-skill: only the explicitly listed adapters (skip unsupported).
                 skill
                     .target_adapters
                     .iter()
                     .filter_map(|s| crate::models::AdapterType::from_str(s).ok())
-                    .filter(|a| {
-                        REGISTRY
-                            .validate_support(a, &skill.scope, ArtifactType::Skill)
-                            .is_ok()
-                    })
                     .collect()
             };
-
-            let content = formatter::format_skill_content(&skill);
-            let content_hash = compute_content_hash(&content);
+            let (candidate_adapters, fallback_adapters): (Vec<AdapterType>, Vec<AdapterType>) =
+                targeted.into_iter().partition(|a| {
+                    REGISTRY
+                        .validate_support(a, &skill.scope, ArtifactType::Skill)
+                        .is_ok()
+                });
+            desired
+                .skill_fallbacks
+                .extend(fallback_adapters.into_iter().map(|adapter| SkillFallback {
+                    skill_id: skill.id.clone(),
+                    skill_name: skill.name.clone(),
+                    adapter,
+                    scope: skill.scope,
+                }));
+
+            let bundle =
+                (!skill.bundle_files.is_empty() && !skill.directory_path.is_empty()).then(|| {
+                    SkillBundle {
+                        source_dir: PathBuf::from(&skill.directory_path),
+                        files: skill.bundle_files.clone(),
+                    }
+                });
+            // Changing an asset changes SKILL.md, so the plan picks the skill up for update.
+            let bundle_note = bundle
+                .as_ref()
+                .map(|bundle| format!("\n<!-- Bundle: {} -->\n", bundle_digest(bundle)))
+                .unwrap_or_default();
+            let versions = if skill.version_pins.is_empty() {
+                Vec::new()
+            } else {
+                self.db.get_skill_versions(&skill.id).await?
+            };
+            let render = |adapter: AdapterType, repo_root: Option<&Path>| {
+                let mut content = match pinned_skill(&skill, &versions, adapter, repo_root) {
+                    Some(pinned) => formatter::format_skill_content(&pinned),
+                    None => formatter::format_skill_content(&skill),
+                };
+                content.push_str(&bundle_note);
+                let content_hash = compute_content_hash(&content);
+                (content, content_hash)
+            };
             let safe_name = crate::path_resolver::sanitize_skill_name(&skill.name);
 
             for adapter in candidate_adapters {
@@ -449,6 +714,12 @@ impl ReconciliationEngine {
                     Scope::Global => {
                         if let Ok(resolved) = self.path_resolver.skill_path(adapter, &safe_name) {
                             let path_str = resolved.path.to_string_lossy().to_string();
+                            let (content, content_hash) = render(adapter, None);
+                            if let Some(bundle) = &bundle {
+                                desired
+                                    .skill_bundles
+                                    .insert(path_str.clone(), bundle.clone());
+                            }
                             desired.expected_paths.insert(
                                 path_str,
                                 ExpectedArtifact {
@@ -489,6 +760,12 @@ impl ReconciliationEngine {
                                 .local_skill_path(adapter, &safe_name, repo_root)
                             {
                                 let path_str = resolved.path.to_string_lossy().to_string();
+                                let (content, content_hash) = render(adapter, Some(repo_root));
+                                if let Some(bundle) = &bundle {
+                                    desired
+                                        .skill_bundles
+                                        .insert(path_str.clone(), bundle.clone());
+                                }
                                 desired.expected_paths.insert(
                                     path_str,
                                     ExpectedArtifact {
@@ -517,18 +794,61 @@ impl ReconciliationEngine {
     /// This scans known paths for all adapters to find what artifacts currently exist.
     pub async fn scan_actual_state(&self) -> Result<ActualState> {
         let mut actual = ActualState::default();
+        *self.scan_cache.lock() = ScanCache::load(&self.db).await;
+        self.load_disabled_adapters().await;
+        let complete = self.filter.is_unfiltered() && self.disabled_adapters.lock().is_empty();
+
+        let local_roots = self.local_scan_roots().await?;
+        if self.filter.includes_type(ArtifactType::Rule) {
+            self.scan_actual_state_rules(&local_roots, &mut actual)?;
+        }
+        if self.filter.includes_type(ArtifactType::CommandStub) {
+            self.scan_actual_state_command_stubs(&local_roots, &mut actual)?;
+        }
+        if self.filter.includes_type(ArtifactType::SlashCommand) {
+            let slash_roots = self.slash_command_scan_roots(&local_roots).await;
+            self.scan_actual_state_slash_commands(&slash_roots, &mut actual)?;
+        }
+        if self.filter.includes_type(ArtifactType::Skill) {
+            self.scan_actual_state_skills(&local_roots, &mut actual)?;
+        }
 
-        self.scan_actual_state_rules(&mut actual)?;
-        self.scan_actual_state_command_stubs(&mut actual)?;
-        self.scan_actual_state_slash_commands(&mut actual)?;
-        self.scan_actual_state_skills(&mut actual)?;
+        if !complete {
+            actual.found_paths.retain(|_, found| {
+                self.filter.includes_found(found)
+                    && found.adapter.is_none_or(|a| self.includes_adapter(a))
+            });
+        }
+
+        let cache = std::mem::take(&mut *self.scan_cache.lock());
+        cache.save(&self.db, complete).await;
 
         Ok(actual)
     }
 
+    /// Adapters whose global files are scanned under the current filter.
+    fn global_scan_adapters(&self) -> Vec<AdapterType> {
+        if !self.filter.includes_global() {
+            return Vec::new();
+        }
+        self.local_scan_adapters()
+    }
+
+    /// Adapters whose repository files are scanned under the current filter.
+    fn local_scan_adapters(&self) -> Vec<AdapterType> {
+        AdapterType::all()
+            .into_iter()
+            .filter(|a| self.includes_adapter(*a))
+            .collect()
+    }
+
     /// Scan for rule artifacts.
-    fn scan_actual_state_rules(&self, actual: &mut ActualState) -> Result<()> {
-        for adapter in AdapterType::all() {
+    fn scan_actual_state_rules(
+        &self,
+        repo_roots: &[PathBuf],
+        actual: &mut ActualState,
+    ) -> Result<()> {
+        for adapter in self.global_scan_adapters() {
             if let Ok(resolved) = self.path_resolver.global_path(adapter, ArtifactType::Rule) {
                 if let Some(found) = self.scan_artifact_file(
                     &resolved.path,
@@ -543,33 +863,41 @@ impl ReconciliationEngine {
             }
         }
 
-        let repo_roots = self.path_resolver.repository_roots();
-        for repo_root in repo_roots {
-            for adapter in AdapterType::all() {
-                if let Ok(resolved) =
-                    self.path_resolver
-                        .local_path(adapter, ArtifactType::Rule, repo_root)
-                {
-                    if let Some(found) = self.scan_artifact_file(
-                        &resolved.path,
-                        Some(adapter),
-                        Some(ArtifactType::Rule),
-                        Scope::Local,
-                    )? {
-                        actual
-                            .found_paths
-                            .insert(resolved.path.to_string_lossy().to_string(), found);
+        self.scan_roots(
+            &self.filter.roots(repo_roots),
+            actual,
+            |repo_root, actual| {
+                for adapter in self.local_scan_adapters() {
+                    if let Ok(resolved) =
+                        self.path_resolver
+                            .local_path(adapter, ArtifactType::Rule, repo_root)
+                    {
+                        if let Some(found) = self.scan_artifact_file(
+                            &resolved.path,
+                            Some(adapter),
+                            Some(ArtifactType::Rule),
+                            Scope::Local,
+                        )? {
+                            actual
+                                .found_paths
+                                .insert(resolved.path.to_string_lossy().to_string(), found);
+                        }
                     }
                 }
-            }
-        }
+                Ok(())
+            },
+        )?;
 
         Ok(())
     }
 
     /// Scan for command stub artifacts (COMMANDS.md files).
-    fn scan_actual_state_command_stubs(&self, actual: &mut ActualState) -> Result<()> {
-        for adapter in AdapterType::all() {
+    fn scan_actual_state_command_stubs(
+        &self,
+        repo_roots: &[PathBuf],
+        actual: &mut ActualState,
+    ) -> Result<()> {
+        for adapter in self.global_scan_adapters() {
             if let Ok(resolved) = self
                 .path_resolver
                 .global_path(adapter, ArtifactType::CommandStub)
@@ -587,33 +915,41 @@ impl ReconciliationEngine {
             }
         }
 
-        let repo_roots = self.path_resolver.repository_roots();
-        for repo_root in repo_roots {
-            for adapter in AdapterType::all() {
-                if let Ok(resolved) =
-                    self.path_resolver
-                        .local_path(adapter, ArtifactType::CommandStub, repo_root)
-                {
-                    if let Some(found) = self.scan_artifact_file(
-                        &resolved.path,
-                        Some(adapter),
-                        Some(ArtifactType::CommandStub),
-                        Scope::Local,
-                    )? {
-                        actual
-                            .found_paths
-                            .insert(resolved.path.to_string_lossy().to_string(), found);
+        self.scan_roots(
+            &self.filter.roots(repo_roots),
+            actual,
+            |repo_root, actual| {
+                for adapter in self.local_scan_adapters() {
+                    if let Ok(resolved) =
+                        self.path_resolver
+                            .local_path(adapter, ArtifactType::CommandStub, repo_root)
+                    {
+                        if let Some(found) = self.scan_artifact_file(
+                            &resolved.path,
+                            Some(adapter),
+                            Some(ArtifactType::CommandStub),
+                            Scope::Local,
+                        )? {
+                            actual
+                                .found_paths
+                                .insert(resolved.path.to_string_lossy().to_string(), found);
+                        }
                     }
                 }
-            }
-        }
+                Ok(())
+            },
+        )?;
 
         Ok(())
     }
 
     /// Scan for slash command artifacts.
-    fn scan_actual_state_slash_commands(&self, actual: &mut ActualState) -> Result<()> {
-        for adapter in AdapterType::all() {
+    fn scan_actual_state_slash_commands(
+        &self,
+        repo_roots: &[PathBuf],
+        actual: &mut ActualState,
+    ) -> Result<()> {
+        for adapter in self.global_scan_adapters() {
             let entry = match REGISTRY.get(&adapter) {
                 Some(e) => e,
                 None => continue,
@@ -630,35 +966,81 @@ impl ReconciliationEngine {
             }
         }
 
-        let repo_roots = self.path_resolver.repository_roots();
-        for repo_root in repo_roots {
-            for adapter in AdapterType::all() {
-                let entry = match REGISTRY.get(&adapter) {
-                    Some(e) => e,
-                    None => continue,
-                };
+        self.scan_roots(
+            &self.filter.roots(repo_roots),
+            actual,
+            |repo_root, actual| {
+                for adapter in self.local_scan_adapters() {
+                    let entry = match REGISTRY.get(&adapter) {
+                        Some(e) => e,
+                        None => continue,
+                    };
 
-                let extension = match entry.slash_command_extension {
-                    Some(ext) => ext,
-                    None => continue,
-                };
+                    let extension = match entry.slash_command_extension {
+                        Some(ext) => ext,
+                        None => continue,
+                    };
 
-                if let Some(local_dir) = entry.paths.local_commands_dir {
-                    let dir_path = repo_root.join(local_dir);
-                    self.scan_command_directory(
-                        &dir_path,
-                        adapter,
-                        extension,
-                        Scope::Local,
-                        actual,
-                    )?;
+                    if let Some(local_dir) = entry.paths.local_commands_dir {
+                        let dir_path = repo_root.join(local_dir);
+                        self.scan_command_directory(
+                            &dir_path,
+                            adapter,
+                            extension,
+                            Scope::Local,
+                            actual,
+                        )?;
+                    }
                 }
-            }
-        }
+                Ok(())
+            },
+        )?;
 
         Ok(())
     }
 
+    /// Repository roots to scan for local artifacts: the resolver's roots and every
+    /// registered root.
+    async fn local_scan_roots(&self) -> Result<Vec<PathBuf>> {
+        let mut roots = self.path_resolver.repository_roots().to_vec();
+        roots.extend(crate::commands::get_local_rule_roots(&self.db).await?);
+        Ok(existing_unique_roots(roots))
+    }
+
+    /// Local scan roots plus the roots recorded by the last reconciliation, which may hold
+    /// slash commands for repositories that were deregistered since.
+    async fn slash_command_scan_roots(&self, local_roots: &[PathBuf]) -> Vec<PathBuf> {
+        let mut roots = local_roots.to_vec();
+        roots.extend(self.recorded_slash_command_roots().await);
+        existing_unique_roots(roots)
+    }
+
+    async fn recorded_slash_command_roots(&self) -> Vec<PathBuf> {
+        match self.db.get_setting(SLASH_COMMAND_ROOTS_KEY).await {
+            Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Remember where local slash commands now live. Roots are only forgotten after a
+    /// clean run, so a failed removal is retried next time.
+    async fn record_slash_command_roots(&self, desired: &DesiredState, clean: bool) -> Result<()> {
+        let mut roots: Vec<PathBuf> = desired
+            .expected_paths
+            .values()
+            .filter(|a| a.artifact_type == ArtifactType::SlashCommand)
+            .filter_map(|a| a.repo_root.clone())
+            .collect();
+        if !clean {
+            roots.extend(self.recorded_slash_command_roots().await);
+        }
+        roots.sort();
+        roots.dedup();
+        self.db
+            .set_setting(SLASH_COMMAND_ROOTS_KEY, &serde_json::to_string(&roots)?)
+            .await
+    }
+
     /// Scan a directory for command files.
     fn scan_command_directory(
         &self,
@@ -668,22 +1050,24 @@ impl ReconciliationEngine {
         scope: Scope,
         actual: &mut ActualState,
     ) -> Result<()> {
-        if !dir.exists() {
+        let Some(entries) = scan::list_dir(dir)? else {
             return Ok(());
-        }
-
-        let entries = match fs::read_dir(dir) {
-            Ok(e) => e,
-            Err(_) => return Ok(()),
         };
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        // Namespaced commands sit in nested folders only for adapters that support them;
+        // other adapters flatten namespaces into prefixed filenames.
+        let nested_dirs = REGISTRY
+            .get(&adapter)
+            .is_some_and(|entry| entry.slash_command_nested_dirs);
 
-            if path.is_file() && path.extension().map(|e| e == extension).unwrap_or(false) {
+        for entry in entries.iter() {
+            let path = &entry.path;
+
+            if nested_dirs && entry.is_dir && !entry.is_symlink {
+                self.scan_command_directory(path, adapter, extension, scope, actual)?;
+            } else if entry.is_file && path.extension().map(|e| e == extension).unwrap_or(false) {
                 if let Some(found) = self.scan_artifact_file(
-                    &path,
+                    path,
                     Some(adapter),
                     Some(ArtifactType::SlashCommand),
                     scope,
@@ -699,21 +1083,29 @@ impl ReconciliationEngine {
     }
 
     /// Scan for skill artifacts.
-    fn scan_actual_state_skills(&self, actual: &mut ActualState) -> Result<()> {
-        for adapter in AdapterType::all() {
+    fn scan_actual_state_skills(
+        &self,
+        repo_roots: &[PathBuf],
+        actual: &mut ActualState,
+    ) -> Result<()> {
+        for adapter in self.global_scan_adapters() {
             if let Ok(resolved) = self.path_resolver.skill_dir(adapter) {
                 self.scan_skill_directory(&resolved.path, adapter, Scope::Global, actual)?;
             }
         }
 
-        let repo_roots = self.path_resolver.repository_roots();
-        for repo_root in repo_roots {
-            for adapter in AdapterType::all() {
-                if let Ok(resolved) = self.path_resolver.local_skill_dir(adapter, repo_root) {
-                    self.scan_skill_directory(&resolved.path, adapter, Scope::Local, actual)?;
+        self.scan_roots(
+            &self.filter.roots(repo_roots),
+            actual,
+            |repo_root, actual| {
+                for adapter in self.local_scan_adapters() {
+                    if let Ok(resolved) = self.path_resolver.local_skill_dir(adapter, repo_root) {
+                        self.scan_skill_directory(&resolved.path, adapter, Scope::Local, actual)?;
+                    }
                 }
-            }
-        }
+                Ok(())
+            },
+        )?;
 
         Ok(())
     }
@@ -726,20 +1118,14 @@ impl ReconciliationEngine {
         scope: Scope,
         actual: &mut ActualState,
     ) -> Result<()> {
-        if !dir.exists() {
+        let Some(entries) = scan::list_dir(dir)? else {
             return Ok(());
-        }
-
-        let entries = match fs::read_dir(dir) {
-            Ok(e) => e,
-            Err(_) => return Ok(()),
         };
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        for entry in entries.iter() {
+            let path = &entry.path;
 
-            if path.is_dir() {
+            if entry.is_dir {
                 let skill_filename = REGISTRY
                     .get(&adapter)
                     .map(|e| e.paths.skill_filename)
@@ -785,19 +1171,32 @@ impl ReconciliationEngine {
             return Ok(None);
         }
 
-        let content = fs::read_to_string(path)?;
+        let key = path.to_string_lossy().to_string();
+        let stamp = FileStamp::of(&metadata);
+        let cached = stamp.and_then(|stamp| self.scan_cache.lock().lookup(&key, stamp));
+        let hash = match cached {
+            Some(hash) => hash,
+            None => {
+                let content = fs::read_to_string(path)?;
+                // Only track files that were created by RuleWeaver.
+                // User-created custom commands/rules should NOT be touched.
+                let hash = content
+                    .contains(RULEWEAVER_MARKER)
+                    .then(|| compute_content_hash(&content));
+                if let Some(stamp) = stamp {
+                    self.scan_cache.lock().record(key, stamp, hash.clone());
+                }
+                hash
+            }
+        };
 
-        // Only track files that were created by RuleWeaver.
-        // User-created custom commands/rules should NOT be touched.
-        if !content.contains(RULEWEAVER_MARKER) {
+        let Some(hash) = hash else {
             log::debug!(
                 "Skipping file not managed by RuleWeaver: {}",
                 path.display()
             );
             return Ok(None);
-        }
-
-        let hash = compute_content_hash(&content);
+        };
 
         Ok(Some(FoundArtifact {
             path: path.to_path_buf(),
@@ -826,6 +1225,7 @@ impl ReconciliationEngine {
                         scope: expected.scope,
                         repo_root: expected.repo_root.clone(),
                         content_hash: expected.content_hash.clone(),
+                        current_hash: Some(found.content_hash.clone()),
                         content: expected.content.clone(),
                     });
                 }
@@ -838,11 +1238,19 @@ impl ReconciliationEngine {
                     scope: expected.scope,
                     repo_root: expected.repo_root.clone(),
                     content_hash: expected.content_hash.clone(),
+                    current_hash: None,
                     content: expected.content.clone(),
                 });
             }
         }
 
+        for artifact in plan.to_create.iter().chain(&plan.to_update) {
+            let path_str = artifact.path.to_string_lossy().to_string();
+            if let Some(bundle) = desired.skill_bundles.get(&path_str) {
+                plan.skill_bundles.insert(path_str, bundle.clone());
+            }
+        }
+
         // Find paths that exist but shouldn't (to remove - stale artifacts)
         for found in actual.found_paths.values() {
             if !desired
@@ -853,6 +1261,43 @@ impl ReconciliationEngine {
             }
         }
 
+        let protected =
+            ProtectedPaths::new(&desired.protected_paths, self.path_resolver.home_dir());
+        if !protected.is_empty() {
+            // Org-managed files are always enforced, protected or not.
+            let managed = |path: &Path| {
+                desired
+                    .expected_paths
+                    .get(path.to_string_lossy().as_ref())
+                    .is_some_and(|a| desired.managed_ids.contains(&a.id))
+            };
+            let mut skipped = Vec::new();
+            plan.to_create.retain(|a| {
+                managed(&a.path) || keep_unprotected(&protected, &a.path, &mut skipped)
+            });
+            plan.to_update.retain(|a| {
+                managed(&a.path) || keep_unprotected(&protected, &a.path, &mut skipped)
+            });
+            plan.to_remove
+                .retain(|a| keep_unprotected(&protected, &a.path, &mut skipped));
+            plan.warnings.extend(
+                skipped
+                    .iter()
+                    .map(|path| format!("Skipped protected path {}", path.display())),
+            );
+        }
+
+        for root in &desired.missing_repo_roots {
+            let before = plan.to_create.len();
+            plan.to_create
+                .retain(|a| a.repo_root.as_ref() != Some(root));
+            plan.warnings.push(format!(
+                "Repository root {} no longer exists; skipped {} file(s) for it",
+                root.display(),
+                before - plan.to_create.len()
+            ));
+        }
+
         plan
     }
 
@@ -860,10 +1305,27 @@ impl ReconciliationEngine {
     ///
     /// If dry_run is true, no actual changes are made.
     pub async fn execute(&self, plan: &ReconcilePlan, dry_run: bool) -> Result<ReconcileResult> {
+        let started = Instant::now();
         let mut result = ReconcileResult {
             success: true,
             ..Default::default()
         };
+        let total = plan.to_create.len() + plan.to_update.len() + plan.to_remove.len();
+        let job = if dry_run || total == 0 {
+            JobHandle::untracked()
+        } else {
+            JobHandle::begin(
+                &self.db,
+                JobKind::Reconcile,
+                format!("Reconcile {} file(s)", total),
+                total as u32,
+                &ReconcileJob {
+                    filter: self.filter.clone(),
+                },
+            )
+            .await
+        };
+        let mut completed = 0u32;
 
         // Handle creates
         for artifact in &plan.to_create {
@@ -871,7 +1333,7 @@ impl ReconciliationEngine {
                 log::info!("[DRY RUN] Would create: {}", artifact.path.display());
                 result.created += 1;
             } else {
-                match self.create_artifact(artifact).await {
+                match self.create_artifact(artifact, plan).await {
                     Ok(()) => {
                         result.created += 1;
                         self.log_operation(
@@ -902,6 +1364,8 @@ impl ReconciliationEngine {
                         .await;
                     }
                 }
+                completed += 1;
+                job.checkpoint(&self.db, completed, &()).await;
             }
         }
 
@@ -911,7 +1375,7 @@ impl ReconciliationEngine {
                 log::info!("[DRY RUN] Would update: {}", artifact.path.display());
                 result.updated += 1;
             } else {
-                match self.update_artifact(artifact).await {
+                match self.update_artifact(artifact, plan).await {
                     Ok(()) => {
                         result.updated += 1;
                         self.log_operation(
@@ -942,6 +1406,8 @@ impl ReconciliationEngine {
                         .await;
                     }
                 }
+                completed += 1;
+                job.checkpoint(&self.db, completed, &()).await;
             }
         }
 
@@ -951,7 +1417,7 @@ impl ReconciliationEngine {
                 log::info!("[DRY RUN] Would remove: {}", artifact.path.display());
                 result.removed += 1;
             } else {
-                match fs::remove_file(&artifact.path) {
+                match remove_artifact(artifact) {
                     Ok(()) => {
                         result.removed += 1;
                         self.log_operation(
@@ -982,10 +1448,63 @@ impl ReconciliationEngine {
                         .await;
                     }
                 }
+                completed += 1;
+                job.checkpoint(&self.db, completed, &()).await;
             }
         }
 
         result.unchanged = plan.unchanged.len();
+        result.warnings.extend(plan.warnings.iter().cloned());
+
+        let changed = result.created + result.updated + result.removed;
+        if !dry_run && (changed > 0 || !result.errors.is_empty()) {
+            crate::webhooks::emit(
+                &self.db,
+                WebhookEvent::ReconcileApplied,
+                &format!(
+                    "RuleWeaver reconciled {} file(s): {} created, {} updated, {} removed",
+                    changed, result.created, result.updated, result.removed
+                ),
+                serde_json::json!(result),
+            )
+            .await;
+        }
+
+        if !dry_run {
+            let status = if result.errors.is_empty() {
+                "success"
+            } else {
+                "failed"
+            };
+            metrics::increment(metrics::RECONCILE_RUNS, &[("status", status)]);
+            for (operation, count) in [
+                ("create", result.created),
+                ("update", result.updated),
+                ("remove", result.removed),
+            ] {
+                metrics::add(
+                    metrics::RECONCILE_FILES,
+                    &[("operation", operation)],
+                    count as u64,
+                );
+            }
+            metrics::observe_since(metrics::RECONCILE_DURATION, &[], started);
+
+            audit::record_run(
+                &self.db,
+                AuditAction::Reconcile,
+                format!(
+                    "Reconciled {} file(s): {} created, {} updated, {} removed, {} error(s)",
+                    changed,
+                    result.created,
+                    result.updated,
+                    result.removed,
+                    result.errors.len()
+                ),
+            )
+            .await;
+        }
+        job.finish(&self.db).await;
 
         Ok(result)
     }
@@ -1007,6 +1526,7 @@ impl ReconciliationEngine {
         log::info!("Actual state: {} paths", actual.found_paths.len());
 
         let mut plan = self.plan(&desired, &actual);
+        let full_run = target_path.is_none() && self.filter.is_unfiltered();
 
         if let Some(target) = target_path {
             plan.to_create
@@ -1020,6 +1540,11 @@ impl ReconciliationEngine {
 
         let result = self.execute(&plan, dry_run).await?;
 
+        if !dry_run && full_run {
+            self.record_slash_command_roots(&desired, result.errors.is_empty())
+                .await?;
+        }
+
         log::info!(
             "Reconciliation complete: {} created, {} updated, {} removed, {} unchanged",
             result.created,
@@ -1032,7 +1557,11 @@ impl ReconciliationEngine {
     }
 
     /// Create a single artifact with atomic write safety.
-    async fn create_artifact(&self, artifact: &ResolvedArtifact) -> Result<()> {
+    async fn create_artifact(
+        &self,
+        artifact: &ResolvedArtifact,
+        plan: &ReconcilePlan,
+    ) -> Result<()> {
         if let Some(parent) = artifact.path.parent() {
             fs::create_dir_all(parent)?;
         }
@@ -1041,16 +1570,22 @@ impl ReconciliationEngine {
             generate_placeholder_content(&artifact.adapter, artifact.artifact_type, artifact.scope)
         });
 
-        write_atomic(&artifact.path, &content)
+        write_atomic(&artifact.path, &content)?;
+        write_skill_bundle(artifact, plan)
     }
 
     /// Update a single artifact with atomic write safety.
-    async fn update_artifact(&self, artifact: &ResolvedArtifact) -> Result<()> {
+    async fn update_artifact(
+        &self,
+        artifact: &ResolvedArtifact,
+        plan: &ReconcilePlan,
+    ) -> Result<()> {
         let content = artifact.content.clone().unwrap_or_else(|| {
             generate_placeholder_content(&artifact.adapter, artifact.artifact_type, artifact.scope)
         });
 
-        write_atomic(&artifact.path, &content)
+        write_atomic(&artifact.path, &content)?;
+        write_skill_bundle(artifact, plan)
     }
 
     /// Repair orphaned artifacts by removing them.
@@ -1073,7 +1608,7 @@ impl ReconciliationEngine {
                 log::info!("[DRY RUN] Would remove orphan: {}", artifact.path.display());
                 result.removed += 1;
             } else {
-                match fs::remove_file(&artifact.path) {
+                match remove_artifact(artifact) {
                     Ok(()) => {
                         result.removed += 1;
                         self.log_operation(
@@ -1155,6 +1690,15 @@ impl ReconciliationEngine {
     }
 }
 
+/// Whether `path` may be touched, recording it in `skipped` when it is protected.
+fn keep_unprotected(protected: &ProtectedPaths, path: &Path, skipped: &mut Vec<PathBuf>) -> bool {
+    if protected.is_protected(path) {
+        skipped.push(path.to_path_buf());
+        return false;
+    }
+    true
+}
+
 /// Compute a content hash.
 fn compute_content_hash(content: &str) -> String {
     use sha2::{Digest, Sha256};
@@ -1163,22 +1707,93 @@ fn compute_content_hash(content: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
-/// Write content to a file atomically using temp file + rename.
-///
-/// This ensures that:
-/// 1. Partial writes don't corrupt existing files
-/// 2. Readers never see incomplete content
-/// 3. Crashes during write leave either old or new content, never corrupted
-fn write_atomic(path: &Path, content: &str) -> Result<()> {
-    let temp_path = path.with_extension("tmp");
+/// The pinned version of `skill` for an adapter and, for local skills, a repository, or
+/// `None` when no pin applies or the pinned version is missing from the history.
+fn pinned_skill(
+    skill: &crate::models::Skill,
+    versions: &[crate::models::SkillVersion],
+    adapter: AdapterType,
+    repo_root: Option<&Path>,
+) -> Option<crate::models::Skill> {
+    let adapter_matches = |pin: &crate::models::SkillVersionPin| {
+        pin.adapter
+            .as_deref()
+            .is_none_or(|a| AdapterType::from_str(a).ok() == Some(adapter))
+    };
+    let repo_matches = |pin: &crate::models::SkillVersionPin| match &pin.repo_path {
+        None => true,
+        Some(path) => repo_root.is_some_and(|root| {
+            Path::new(&crate::path_resolver::resolve_workspace_path(
+                path,
+                skill.base_path.as_deref(),
+            )) == root
+        }),
+    };
+    let pin = skill
+        .version_pins
+        .iter()
+        .filter(|pin| adapter_matches(pin) && repo_matches(pin))
+        .max_by_key(|pin| (pin.repo_path.is_some(), pin.adapter.is_some()))?;
+
+    match versions.iter().find(|v| v.version == pin.version) {
+        // The name stays current so the frontmatter matches the skill's directory.
+        Some(version) => Some(crate::models::Skill {
+            name: skill.name.clone(),
+            ..version.content.apply_to(skill, &version.version)
+        }),
+        None => {
+            log::warn!(
+                "Skill {} is pinned to missing version {}; syncing the current version",
+                skill.id,
+                pin.version
+            );
+            None
+        }
+    }
+}
 
-    fs::write(&temp_path, content).map_err(crate::error::AppError::Io)?;
+/// Hash of a skill bundle's file names and contents. Unreadable files hash as empty;
+/// copying them reports the error.
+fn bundle_digest(bundle: &SkillBundle) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    for file in &bundle.files {
+        hasher.update(file.as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(bundle.source_dir.join(file)).unwrap_or_default());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
 
-    fs::rename(&temp_path, path).map_err(|e| {
-        let _ = fs::remove_file(&temp_path);
-        crate::error::AppError::Io(e)
-    })?;
+/// Copies the bundle planned for a skill artifact next to it, or clears a bundle left
+/// from an earlier sync when the skill no longer has one.
+fn write_skill_bundle(artifact: &ResolvedArtifact, plan: &ReconcilePlan) -> Result<()> {
+    if artifact.artifact_type != ArtifactType::Skill {
+        return Ok(());
+    }
+    let Some(target) = artifact.path.parent() else {
+        return Ok(());
+    };
+    match plan
+        .skill_bundles
+        .get(&artifact.path.to_string_lossy().to_string())
+    {
+        Some(bundle) => {
+            crate::file_storage::skills::copy_bundle(&bundle.source_dir, &bundle.files, target)
+        }
+        None => crate::file_storage::skills::remove_bundle(target),
+    }
+}
 
+/// Removes a stale artifact, along with the bundle next to a skill.
+fn remove_artifact(artifact: &FoundArtifact) -> Result<()> {
+    fs::remove_file(&artifact.path)?;
+    if artifact.artifact_type == Some(ArtifactType::Skill) {
+        if let Some(target) = artifact.path.parent() {
+            crate::file_storage::skills::remove_bundle(target)?;
+        }
+    }
     Ok(())
 }
 
@@ -1320,6 +1935,237 @@ mod tests {
         assert_eq!(plan.to_remove[0].path, PathBuf::from("/stale/path.md"));
     }
 
+    #[tokio::test]
+    async fn test_plan_skips_protected_paths_with_warning() {
+        let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let engine = ReconciliationEngine::new(db).unwrap();
+
+        let desired = DesiredState {
+            protected_paths: vec![".claude/commands/secret.md".to_string()],
+            ..Default::default()
+        };
+
+        let mut actual = ActualState::default();
+        for path in [
+            "/repo/.claude/commands/secret.md",
+            "/repo/.claude/commands/stale.md",
+        ] {
+            actual.found_paths.insert(
+                path.to_string(),
+                FoundArtifact {
+                    path: PathBuf::from(path),
+                    adapter: Some(AdapterType::ClaudeCode),
+                    artifact_type: Some(ArtifactType::SlashCommand),
+                    scope: Some(Scope::Local),
+                    content_hash: "hash".to_string(),
+                },
+            );
+        }
+
+        let plan = engine.plan(&desired, &actual);
+
+        assert_eq!(plan.to_remove.len(), 1);
+        assert_eq!(
+            plan.to_remove[0].path,
+            PathBuf::from("/repo/.claude/commands/stale.md")
+        );
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(plan.warnings[0].contains("secret.md"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_writes_org_managed_files_under_protected_paths() {
+        let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let engine = ReconciliationEngine::new(db).unwrap();
+
+        let mut desired = DesiredState {
+            protected_paths: vec!["AGENTS.md".to_string()],
+            managed_ids: HashSet::from(["managed".to_string()]),
+            ..Default::default()
+        };
+        for (path, id) in [("/a/AGENTS.md", "managed"), ("/b/AGENTS.md", "local")] {
+            desired.expected_paths.insert(
+                path.to_string(),
+                ExpectedArtifact {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    adapter: AdapterType::OpenCode,
+                    artifact_type: ArtifactType::Rule,
+                    scope: Scope::Local,
+                    repo_root: None,
+                    content_hash: "hash".to_string(),
+                    content: Some("content".to_string()),
+                },
+            );
+        }
+
+        let plan = engine.plan(&desired, &ActualState::default());
+
+        assert_eq!(plan.to_create.len(), 1);
+        assert_eq!(plan.to_create[0].path, PathBuf::from("/a/AGENTS.md"));
+        assert_eq!(plan.warnings.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_filtered_reconcile_only_touches_selected_adapters_and_types() {
+        let home = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        db.create_rule(crate::models::CreateRuleInput {
+            id: None,
+            name: "Filtered".to_string(),
+            description: String::new(),
+            content: "content".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini, AdapterType::ClaudeCode],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+        let resolver = PathResolver::new_with_home(home.path().to_path_buf(), vec![]);
+        let gemini = resolver
+            .global_path(AdapterType::Gemini, ArtifactType::Rule)
+            .unwrap()
+            .path;
+        let claude = resolver
+            .global_path(AdapterType::ClaudeCode, ArtifactType::Rule)
+            .unwrap()
+            .path;
+
+        let skills_only = ReconciliationEngine::new_with_resolver(
+            Arc::clone(&db),
+            PathResolver::new_with_home(home.path().to_path_buf(), vec![]),
+        )
+        .with_filter(ReconcileFilter {
+            artifact_types: vec![ArtifactType::Skill],
+            ..Default::default()
+        });
+        let result = skills_only.reconcile(false, None).await.unwrap();
+        assert_eq!(result.created, 0);
+
+        let gemini_only = ReconciliationEngine::new_with_resolver(Arc::clone(&db), resolver)
+            .with_filter(ReconcileFilter {
+                adapters: vec![AdapterType::Gemini],
+                ..Default::default()
+            });
+        let result = gemini_only.reconcile(false, None).await.unwrap();
+        assert_eq!(result.created, 1);
+        assert!(gemini.exists());
+        assert!(!claude.exists());
+        assert!(!gemini_only.needs_reconciliation().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_adapter_files_are_neither_written_nor_removed() {
+        let home = tempfile::tempdir().unwrap();
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        db.create_rule(crate::models::CreateRuleInput {
+            id: None,
+            name: "Switched".to_string(),
+            description: String::new(),
+            content: "content".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Codex, AdapterType::ClaudeCode],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+        db.set_setting(crate::sync::ADAPTER_SETTINGS_KEY, r#"{"codex": false}"#)
+            .await
+            .unwrap();
+        let resolver = PathResolver::new_with_home(home.path().to_path_buf(), vec![]);
+        let codex = resolver
+            .global_path(AdapterType::Codex, ArtifactType::Rule)
+            .unwrap()
+            .path;
+        let claude = resolver
+            .global_path(AdapterType::ClaudeCode, ArtifactType::Rule)
+            .unwrap()
+            .path;
+        let stale = format!("<!-- {} -->\nold", RULEWEAVER_MARKER);
+        fs::create_dir_all(codex.parent().unwrap()).unwrap();
+        fs::write(&codex, &stale).unwrap();
+
+        let engine = ReconciliationEngine::new_with_resolver(db, resolver);
+        let result = engine.reconcile(false, None).await.unwrap();
+
+        assert_eq!(result.created, 1);
+        assert!(claude.exists());
+        assert_eq!(fs::read_to_string(&codex).unwrap(), stale);
+        assert!(!engine.needs_reconciliation().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_repo_filtered_reconcile_only_removes_stale_files_in_that_repo() {
+        let home = tempfile::tempdir().unwrap();
+        let repos = tempfile::tempdir().unwrap();
+        let (selected, other) = (repos.path().join("selected"), repos.path().join("other"));
+        let resolver = || {
+            PathResolver::new_with_home(
+                home.path().to_path_buf(),
+                vec![selected.clone(), other.clone()],
+            )
+        };
+        let stale = |root: &Path| {
+            let path = resolver()
+                .local_path(AdapterType::Gemini, ArtifactType::Rule, root)
+                .unwrap()
+                .path;
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, format!("<!-- {} -->\nold", RULEWEAVER_MARKER)).unwrap();
+            path
+        };
+        let (selected_file, other_file) = (stale(&selected), stale(&other));
+
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let engine =
+            ReconciliationEngine::new_with_resolver(db, resolver()).with_filter(ReconcileFilter {
+                repo_root: Some(selected.clone()),
+                ..Default::default()
+            });
+        let result = engine.reconcile(false, None).await.unwrap();
+
+        assert_eq!(result.removed, 1);
+        assert!(!selected_file.exists());
+        assert!(other_file.exists());
+    }
+
+    #[tokio::test]
+    async fn test_plan_skips_creates_under_missing_repo_roots() {
+        let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let engine = ReconciliationEngine::new(db).unwrap();
+
+        let mut desired = DesiredState {
+            missing_repo_roots: vec![PathBuf::from("/gone")],
+            ..Default::default()
+        };
+        for (path, root) in [("/gone/AGENTS.md", "/gone"), ("/kept/AGENTS.md", "/kept")] {
+            desired.expected_paths.insert(
+                path.to_string(),
+                ExpectedArtifact {
+                    id: "rule-1".to_string(),
+                    name: "Rule 1".to_string(),
+                    adapter: AdapterType::Codex,
+                    artifact_type: ArtifactType::Rule,
+                    scope: Scope::Local,
+                    repo_root: Some(PathBuf::from(root)),
+                    content_hash: "hash".to_string(),
+                    content: Some("content".to_string()),
+                },
+            );
+        }
+
+        let plan = engine.plan(&desired, &ActualState::default());
+
+        assert_eq!(plan.to_create.len(), 1);
+        assert_eq!(plan.to_create[0].path, PathBuf::from("/kept/AGENTS.md"));
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(plan.warnings[0].contains("/gone"));
+    }
+
     #[tokio::test]
     async fn test_plan_detects_unchanged() {
         let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
@@ -1472,6 +2318,7 @@ mod tests {
             scope: Scope::Global,
             repo_root: None,
             content_hash: "hash".to_string(),
+            current_hash: None,
             content: Some("content".to_string()),
         });
 
@@ -1512,14 +2359,26 @@ mod tests {
             description: "A test command".to_string(),
             script: "echo test".to_string(),
             arguments: vec![],
-            expose_via_mcp: true,
+            mcp_policy: Default::default(),
             is_placeholder: false,
             generate_slash_commands: false,
             slash_command_adapters: vec![],
+            slash_namespace: None,
+            slash_frontmatter: Default::default(),
+            slash_scope: Default::default(),
             target_paths: vec![],
             timeout_ms: None,
             max_retries: None,
+            required_secrets: vec![],
+            working_dir: None,
+            shell: None,
+            schedule: None,
+            sandbox: None,
+            artifact_globs: Vec::new(),
+            output_parser: None,
+            accepts_stdin: false,
             base_path: None,
+            metadata: Default::default(),
         })
         .await
         .unwrap();
@@ -1547,14 +2406,26 @@ mod tests {
             description: "A test slash command".to_string(),
             script: "echo test".to_string(),
             arguments: vec![],
-            expose_via_mcp: false,
+            mcp_policy: crate::models::McpPolicy::hidden(),
             is_placeholder: false,
             generate_slash_commands: true,
             slash_command_adapters: vec!["claude-code".to_string()],
+            slash_namespace: None,
+            slash_frontmatter: Default::default(),
+            slash_scope: Default::default(),
             target_paths: vec![],
             timeout_ms: None,
             max_retries: None,
+            required_secrets: vec![],
+            working_dir: None,
+            shell: None,
+            schedule: None,
+            sandbox: None,
+            artifact_globs: Vec::new(),
+            output_parser: None,
+            accepts_stdin: false,
             base_path: None,
+            metadata: Default::default(),
         })
         .await
         .unwrap();
@@ -1976,6 +2847,7 @@ mod tests {
                 enabled_adapters: vec![AdapterType::ClaudeCode, AdapterType::OpenCode],
                 target_paths: None,
                 enabled: true,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2015,6 +2887,7 @@ mod tests {
                 enabled_adapters: vec![AdapterType::ClaudeCode],
                 target_paths: Some(vec!["/test/repo".to_string()]),
                 enabled: true,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2056,6 +2929,7 @@ mod tests {
                     enabled_adapters: vec![AdapterType::ClaudeCode],
                     target_paths: None,
                     enabled: true,
+                    metadata: Default::default(),
                 })
                 .await
                 .unwrap();
@@ -2096,14 +2970,26 @@ mod tests {
                 description: "A test command".to_string(),
                 script: "echo 'test'".to_string(),
                 arguments: vec![],
-                expose_via_mcp: false,
+                mcp_policy: crate::models::McpPolicy::hidden(),
                 is_placeholder: false,
                 generate_slash_commands: true,
                 slash_command_adapters: vec!["claude-code".to_string(), "opencode".to_string()],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2142,14 +3028,26 @@ mod tests {
                 description: "A local command".to_string(),
                 script: "echo 'local'".to_string(),
                 arguments: vec![],
-                expose_via_mcp: false,
+                mcp_policy: crate::models::McpPolicy::hidden(),
                 is_placeholder: false,
                 generate_slash_commands: true,
                 slash_command_adapters: vec!["claude-code".to_string()],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec!["/test/repo".to_string()],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2178,6 +3076,83 @@ mod tests {
         assert!(!local_entries.is_empty(), "Should have local scope entries");
     }
 
+    #[tokio::test]
+    async fn test_namespaced_slash_command_nests_or_prefixes_by_adapter() {
+        let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+
+        db.create_command(crate::models::CreateCommandInput {
+            name: "Commit".to_string(),
+            description: "Commit staged changes".to_string(),
+            script: "git commit".to_string(),
+            generate_slash_commands: true,
+            slash_command_adapters: vec!["claude-code".to_string(), "gemini".to_string()],
+            slash_namespace: Some("Git".to_string()),
+            target_paths: vec!["/test/repo".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let engine = ReconciliationEngine::new(db).unwrap();
+        let desired = engine.compute_desired_state().await.unwrap();
+
+        let local_path = |adapter: AdapterType| {
+            desired
+                .expected_paths
+                .iter()
+                .find(|(_, a)| {
+                    a.artifact_type == ArtifactType::SlashCommand
+                        && a.adapter == adapter
+                        && a.scope == Scope::Local
+                })
+                .map(|(path, a)| (PathBuf::from(path), a.name.clone()))
+                .unwrap()
+        };
+
+        let (claude_path, claude_name) = local_path(AdapterType::ClaudeCode);
+        assert!(claude_path.ends_with(".claude/commands/git/commit.md"));
+        assert_eq!(claude_name, "git/commit");
+
+        let (gemini_path, _) = local_path(AdapterType::Gemini);
+        assert!(gemini_path.ends_with(".gemini/commands/git-commit.toml"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_command_directory_descends_into_namespaces() {
+        let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let engine = ReconciliationEngine::new(db).unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        let nested = temp.path().join("git");
+        fs::create_dir_all(&nested).unwrap();
+        let content = format!("<!-- {} -->\nCommit", RULEWEAVER_MARKER);
+        fs::write(nested.join("commit.md"), &content).unwrap();
+        fs::write(temp.path().join("top.md"), &content).unwrap();
+
+        let mut actual = ActualState::default();
+        engine
+            .scan_command_directory(
+                temp.path(),
+                AdapterType::ClaudeCode,
+                "md",
+                Scope::Local,
+                &mut actual,
+            )
+            .unwrap();
+        assert_eq!(actual.found_paths.len(), 2);
+
+        let mut flat = ActualState::default();
+        engine
+            .scan_command_directory(
+                temp.path(),
+                AdapterType::Gemini,
+                "md",
+                Scope::Local,
+                &mut flat,
+            )
+            .unwrap();
+        assert_eq!(flat.found_paths.len(), 1);
+    }
+
     #[test]
     fn test_slash_command_not_generated_when_disabled() {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -2191,14 +3166,26 @@ mod tests {
                 description: "A command without slash commands".to_string(),
                 script: "echo 'no slash'".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: false,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2237,14 +3224,26 @@ mod tests {
                 description: "A command exposed via MCP".to_string(),
                 script: "echo 'mcp'".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: false,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2263,7 +3262,7 @@ mod tests {
 
         assert!(
             !stub_entries.is_empty(),
-            "Should have command stub entries when expose_via_mcp is true"
+            "Should have command stub entries for commands exposed via MCP"
         );
     }
 
@@ -2279,14 +3278,26 @@ mod tests {
                 description: "A command not exposed via MCP".to_string(),
                 script: "echo 'not mcp'".to_string(),
                 arguments: vec![],
-                expose_via_mcp: false,
+                mcp_policy: crate::models::McpPolicy::hidden(),
                 is_placeholder: false,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             })
             .await
             .unwrap();
@@ -2305,7 +3316,7 @@ mod tests {
 
         assert!(
             stub_entries.is_empty(),
-            "Should not have command stub when expose_via_mcp is false"
+            "Should not have command stub for a hidden command"
         );
     }
 
@@ -2378,7 +3389,7 @@ mod tests {
 
         let mut path_resolver = crate::path_resolver::PathResolver::new().unwrap();
         path_resolver.add_repository_root(std::path::PathBuf::from("/repo"));
-        let engine = ReconciliationEngine { db, path_resolver };
+        let engine = ReconciliationEngine::new_with_resolver(db, path_resolver);
         let desired = rt.block_on(async { engine.compute_desired_state().await.unwrap() });
 
         let skill_entries: Vec<_> = desired
@@ -2534,11 +3545,11 @@ mod tests {
     }
 
     #[test]
-    fn test_skill_unsupported_adapter_in_target_is_silently_skipped() {
+    fn test_skill_unsupported_adapter_in_target_is_recorded_as_fallback() {
         let rt = tokio::runtime::Runtime::new().unwrap();
         let db = rt.block_on(async {
             let db = std::sync::Arc::new(crate::database::Database::new_in_memory().await.unwrap());
-            // Cursor does not support skills — it should be silently skipped without panic
+            // Cursor does not support skills — it should be recorded as an MCP fallback
             db.create_skill(crate::models::CreateSkillInput {
                 id: None,
                 name: "Skip Unsupported".to_string(),
@@ -2571,6 +3582,13 @@ mod tests {
             skill_entries.is_empty(),
             "Unsupported adapter (Cursor) should produce no skill artifacts"
         );
+
+        let fallbacks: Vec<_> = desired
+            .skill_fallbacks
+            .iter()
+            .map(|f| (f.skill_name.as_str(), f.adapter))
+            .collect();
+        assert_eq!(fallbacks, vec![("Skip Unsupported", AdapterType::Cursor)]);
     }
 
     #[test]
@@ -2581,12 +3599,12 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_skill_target_adapters_rejects_unsupported() {
-        // Cursor does not support skills
+    fn test_validate_skill_target_adapters_accepts_mcp_fallback_adapter() {
+        // Cursor does not support skills natively but can still be targeted via MCP
         let result = crate::models::validate_skill_target_adapters(&["cursor".to_string()]);
         assert!(
-            result.is_err(),
-            "Adapter that doesn't support skills should fail validation"
+            result.is_ok(),
+            "Adapter without native skills should be accepted as an MCP fallback target"
         );
     }
 
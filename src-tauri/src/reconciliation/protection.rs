@@ -0,0 +1,142 @@
+//! User-protected paths that reconciliation never creates, overwrites or deletes.
+//!
+//! Patterns are globs. An absolute pattern (or one starting with `~/`) matches from the
+//! filesystem root; any other pattern matches at any depth, so `.claude/commands/secret.md`
+//! protects that file in every repository and in the home directory.
+
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+
+pub const PROTECTED_PATHS_KEY: &str = "reconcile_protected_paths";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: !cfg!(windows),
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+pub async fn list_protected_paths(db: &Database) -> Vec<String> {
+    let encoded = match db.get_setting(PROTECTED_PATHS_KEY).await {
+        Ok(Some(v)) => v,
+        _ => return Vec::new(),
+    };
+    serde_json::from_str(&encoded).unwrap_or_default()
+}
+
+/// Validates and stores the full pattern list, dropping blanks and duplicates.
+pub async fn save_protected_paths(db: &Database, patterns: Vec<String>) -> Result<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().replace('\\', "/");
+        if pattern.is_empty() || normalized.contains(&pattern) {
+            continue;
+        }
+        Pattern::new(&pattern).map_err(|e| AppError::InvalidInput {
+            message: format!("Invalid protected path pattern '{}': {}", pattern, e),
+        })?;
+        normalized.push(pattern);
+    }
+    db.set_setting(PROTECTED_PATHS_KEY, &serde_json::to_string(&normalized)?)
+        .await?;
+    Ok(normalized)
+}
+
+/// Compiled protected path patterns.
+pub struct ProtectedPaths {
+    patterns: Vec<Pattern>,
+}
+
+impl ProtectedPaths {
+    /// Compiles `patterns`, expanding `~/` against `home`. Invalid patterns are skipped.
+    pub fn new(patterns: &[String], home: &Path) -> Self {
+        let home = home.to_string_lossy().replace('\\', "/");
+        let patterns = patterns
+            .iter()
+            .filter_map(|raw| {
+                let raw = raw.trim().replace('\\', "/");
+                let anchored = if let Some(rest) = raw.strip_prefix("~/") {
+                    format!("{}/{}", home.trim_end_matches('/'), rest)
+                } else if Path::new(&raw).is_absolute() || raw.starts_with('/') {
+                    raw.clone()
+                } else {
+                    format!("**/{}", raw.trim_start_matches("./"))
+                };
+                match Pattern::new(&anchored) {
+                    Ok(pattern) => Some(pattern),
+                    Err(e) => {
+                        log::warn!("Ignoring invalid protected path '{}': {}", raw, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn is_protected(&self, path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let path = path.to_string_lossy().replace('\\', "/");
+        self.patterns
+            .iter()
+            .any(|p| p.matches_with(&path, MATCH_OPTIONS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_relative_patterns_match_at_any_depth() {
+        let home = PathBuf::from("/home/dev");
+        let protected = ProtectedPaths::new(
+            &[
+                ".claude/commands/secret.md".to_string(),
+                "~/.gemini/**".to_string(),
+                "/srv/repo/AGENTS.md".to_string(),
+            ],
+            &home,
+        );
+
+        assert!(protected.is_protected(Path::new("/work/app/.claude/commands/secret.md")));
+        assert!(protected.is_protected(Path::new("/home/dev/.claude/commands/secret.md")));
+        assert!(!protected.is_protected(Path::new("/work/app/.claude/commands/public.md")));
+        assert!(protected.is_protected(Path::new("/home/dev/.gemini/GEMINI.md")));
+        assert!(!protected.is_protected(Path::new("/work/.gemini/GEMINI.md")));
+        assert!(protected.is_protected(Path::new("/srv/repo/AGENTS.md")));
+        assert!(!protected.is_protected(Path::new("/srv/other/AGENTS.md")));
+    }
+
+    #[tokio::test]
+    async fn test_save_rejects_invalid_and_dedupes() {
+        let db = Database::new_in_memory().await.unwrap();
+
+        assert!(save_protected_paths(&db, vec!["[".to_string()])
+            .await
+            .is_err());
+
+        let saved = save_protected_paths(
+            &db,
+            vec![
+                " .claude\\commands\\secret.md ".to_string(),
+                ".claude/commands/secret.md".to_string(),
+                String::new(),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved, vec![".claude/commands/secret.md".to_string()]);
+        assert_eq!(list_protected_paths(&db).await, saved);
+    }
+}
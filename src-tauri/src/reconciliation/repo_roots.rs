@@ -0,0 +1,136 @@
+//! Registered repository roots that no longer exist.
+//!
+//! A repository that was deleted or moved stays in `local_rule_paths` until it is removed.
+//! Reconciliation reports such roots and skips writing into them, since creating files there
+//! would bring the directory back. Removing a missing root deregisters it and drops the rule
+//! file index entries stored under it.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use super::DesiredState;
+use crate::commands::{get_local_rule_roots, LOCAL_RULE_PATHS_KEY};
+use crate::database::Database;
+use crate::error::{AppError, Result};
+
+/// Registered roots that are not directories on disk.
+pub async fn missing_repo_roots(db: &Database) -> Result<Vec<PathBuf>> {
+    Ok(get_local_rule_roots(db)
+        .await?
+        .into_iter()
+        .filter(|root| !root.is_dir())
+        .collect())
+}
+
+/// Missing roots among the registered ones and those `desired` would write into.
+pub(super) async fn missing_roots_for(db: &Database, desired: &DesiredState) -> Vec<PathBuf> {
+    let registered = match get_local_rule_roots(db).await {
+        Ok(roots) => roots,
+        Err(e) => {
+            log::warn!("Failed to read repository roots: {}", e);
+            Vec::new()
+        }
+    };
+    let referenced = desired
+        .expected_paths
+        .values()
+        .filter_map(|a| a.repo_root.clone());
+
+    registered
+        .into_iter()
+        .chain(referenced)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|root| !root.is_dir())
+        .collect()
+}
+
+/// The registered repository root `path` refers to. Roots are matched as stored, since
+/// reconciliation filters on the stored form, and then by canonical path.
+pub async fn registered_repo_root(db: &Database, path: &Path) -> Result<PathBuf> {
+    let roots = get_local_rule_roots(db).await?;
+    if let Some(root) = roots.iter().find(|root| root.as_path() == path) {
+        return Ok(root.clone());
+    }
+    let canonical = path.canonicalize()?;
+    roots
+        .into_iter()
+        .find(|root| root.canonicalize().is_ok_and(|r| r == canonical))
+        .ok_or_else(|| AppError::InvalidInput {
+            message: format!(
+                "{} is not a registered repository; add it under Settings > Repository Roots",
+                path.display()
+            ),
+        })
+}
+
+/// Deregisters the given roots and purges their rule file index entries. Roots that exist
+/// again are left alone. Returns the roots that were removed.
+pub async fn remove_missing_repo_roots(db: &Database, roots: &[String]) -> Result<Vec<String>> {
+    let missing = missing_repo_roots(db).await?;
+    let removed: Vec<String> = roots
+        .iter()
+        .filter(|root| missing.iter().any(|m| m == &PathBuf::from(root.as_str())))
+        .cloned()
+        .collect();
+    if removed.is_empty() {
+        return Ok(removed);
+    }
+
+    db.remove_setting_string_array_values(LOCAL_RULE_PATHS_KEY, &removed)
+        .await?;
+    for root in &removed {
+        let purged = db
+            .remove_rule_file_index_under(&PathBuf::from(root))
+            .await?;
+        log::info!(
+            "Removed missing repository root {} and {} rule file index entries",
+            root,
+            purged
+        );
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_storage::StorageLocation;
+
+    #[tokio::test]
+    async fn test_remove_missing_root_deregisters_it_and_purges_index() {
+        let db = Database::new_in_memory().await.unwrap();
+        let existing = tempfile::tempdir().unwrap();
+        let gone = existing.path().join("deleted-repo");
+        let (existing_root, gone_root) = (
+            existing.path().to_string_lossy().to_string(),
+            gone.to_string_lossy().to_string(),
+        );
+        crate::commands::register_local_paths(&db, &[existing_root.clone(), gone_root.clone()])
+            .await
+            .unwrap();
+        db.update_rule_file_index("rule-gone", &StorageLocation::Local(gone.clone()))
+            .await
+            .unwrap();
+        db.update_rule_file_index(
+            "rule-kept",
+            &StorageLocation::Local(existing.path().to_path_buf()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(missing_repo_roots(&db).await.unwrap(), vec![gone.clone()]);
+
+        let removed = remove_missing_repo_roots(&db, &[gone_root.clone(), existing_root.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(removed, vec![gone_root]);
+        assert_eq!(
+            get_local_rule_roots(&db).await.unwrap(),
+            vec![existing.path().to_path_buf()]
+        );
+        assert!(db.get_rule_file_path("rule-gone").await.unwrap().is_none());
+        assert!(db.get_rule_file_path("rule-kept").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,274 @@
+//! Reviewed reconciliation: a plan is prepared and shown to the user, who can exclude
+//! individual paths before it is applied by id.
+//!
+//! Prepared plans are held in memory for [`RECONCILE_PLAN_TTL`] and can be applied once. A
+//! path whose file changed between preview and apply is skipped instead of being overwritten
+//! or deleted, since the user approved the state they were shown rather than the current one.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    compute_content_hash, DesiredState, ReconcileFilter, ReconcilePlan, ReconcileResult,
+    ReconciliationEngine,
+};
+use crate::constants::timing::RECONCILE_PLAN_TTL;
+use crate::error::{AppError, Result};
+
+/// A plan awaiting approval.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedReconcilePlan {
+    pub id: String,
+    pub plan: ReconcilePlan,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// The user's choices for a prepared plan. Every planned path is applied unless excluded.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcilePlanSelection {
+    #[serde(default)]
+    pub excluded_paths: Vec<String>,
+}
+
+struct StoredPlan {
+    desired: DesiredState,
+    filter: ReconcileFilter,
+    plan: ReconcilePlan,
+    expires_at: Instant,
+}
+
+/// Holds prepared plans between preview and apply.
+#[derive(Clone, Default)]
+pub struct ReconcilePlanStore {
+    plans: Arc<Mutex<HashMap<String, StoredPlan>>>,
+}
+
+impl ReconcilePlanStore {
+    fn insert(
+        &self,
+        desired: DesiredState,
+        filter: ReconcileFilter,
+        plan: ReconcilePlan,
+    ) -> PreparedReconcilePlan {
+        let id = uuid::Uuid::new_v4().to_string();
+        let prepared = PreparedReconcilePlan {
+            id: id.clone(),
+            plan: plan.clone(),
+            expires_at: Utc::now()
+                + chrono::Duration::from_std(RECONCILE_PLAN_TTL).unwrap_or_default(),
+        };
+
+        let mut plans = self.plans.lock();
+        let now = Instant::now();
+        plans.retain(|_, stored| stored.expires_at > now);
+        plans.insert(
+            id,
+            StoredPlan {
+                desired,
+                filter,
+                plan,
+                expires_at: now + RECONCILE_PLAN_TTL,
+            },
+        );
+        prepared
+    }
+
+    fn take(&self, id: &str) -> Result<StoredPlan> {
+        self.plans
+            .lock()
+            .remove(id)
+            .filter(|stored| stored.expires_at > Instant::now())
+            .ok_or_else(|| AppError::InvalidInput {
+                message: "Reconcile plan not found or expired; preview it again".to_string(),
+            })
+    }
+}
+
+/// Whether the file at `path` still has the hash it had when planned (`None` meaning absent).
+/// Records a warning when it does not.
+fn unchanged_since_plan(path: &Path, planned: Option<&str>, warnings: &mut Vec<String>) -> bool {
+    let current = fs::read_to_string(path)
+        .ok()
+        .map(|content| compute_content_hash(&content));
+    if current.as_deref() == planned {
+        return true;
+    }
+    warnings.push(format!(
+        "Skipped {}: it changed after the plan was previewed",
+        path.display()
+    ));
+    false
+}
+
+impl ReconciliationEngine {
+    /// Plans a reconciliation without applying it and keeps it in `store` for approval.
+    pub async fn prepare_plan(&self, store: &ReconcilePlanStore) -> Result<PreparedReconcilePlan> {
+        let desired = self.compute_desired_state().await?;
+        let actual = self.scan_actual_state().await?;
+        let plan = self.plan(&desired, &actual);
+        Ok(store.insert(desired, self.filter.clone(), plan))
+    }
+
+    /// Applies a prepared plan, leaving out excluded paths and any file that changed since
+    /// the plan was previewed.
+    pub async fn apply_plan(
+        &self,
+        store: &ReconcilePlanStore,
+        plan_id: &str,
+        selection: &ReconcilePlanSelection,
+    ) -> Result<ReconcileResult> {
+        let StoredPlan {
+            desired,
+            filter,
+            mut plan,
+            ..
+        } = store.take(plan_id)?;
+
+        let excluded: HashSet<&str> = selection
+            .excluded_paths
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let planned = plan.to_create.len() + plan.to_update.len() + plan.to_remove.len();
+        let mut warnings = Vec::new();
+
+        plan.to_create.retain(|a| {
+            !excluded.contains(a.path.to_string_lossy().as_ref())
+                && unchanged_since_plan(&a.path, None, &mut warnings)
+        });
+        plan.to_update.retain(|a| {
+            !excluded.contains(a.path.to_string_lossy().as_ref())
+                && unchanged_since_plan(&a.path, a.current_hash.as_deref(), &mut warnings)
+        });
+        plan.to_remove.retain(|a| {
+            !excluded.contains(a.path.to_string_lossy().as_ref())
+                && unchanged_since_plan(&a.path, Some(&a.content_hash), &mut warnings)
+        });
+        let applied = plan.to_create.len() + plan.to_update.len() + plan.to_remove.len();
+
+        let mut result = self.execute(&plan, false).await?;
+        result.warnings.extend(warnings);
+
+        // Roots are only forgotten when every planned removal actually happened, and a
+        // filtered plan never saw every root.
+        if filter.is_unfiltered() {
+            let complete = applied == planned && result.errors.is_empty();
+            self.record_slash_command_roots(&desired, complete).await?;
+        }
+
+        log::info!(
+            "Applied reviewed plan {}: {} of {} change(s) approved",
+            plan_id,
+            applied,
+            planned
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::models::{AdapterType, CreateRuleInput, Scope};
+    use crate::path_resolver::PathResolver;
+    use std::path::PathBuf;
+
+    async fn engine_with_rule(home: &Path) -> ReconciliationEngine {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Reviewed".to_string(),
+            description: String::new(),
+            content: "content".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+        ReconciliationEngine::new_with_resolver(
+            db,
+            PathResolver::new_with_home(home.to_path_buf(), vec![]),
+        )
+    }
+
+    fn planned_paths(prepared: &PreparedReconcilePlan) -> Vec<PathBuf> {
+        prepared
+            .plan
+            .to_create
+            .iter()
+            .map(|a| a.path.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_apply_plan_skips_excluded_paths_and_is_single_use() {
+        let home = tempfile::tempdir().unwrap();
+        let engine = engine_with_rule(home.path()).await;
+        let store = ReconcilePlanStore::default();
+
+        let prepared = engine.prepare_plan(&store).await.unwrap();
+        let paths = planned_paths(&prepared);
+        assert!(!paths.is_empty());
+
+        let selection = ReconcilePlanSelection {
+            excluded_paths: paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        };
+        let result = engine
+            .apply_plan(&store, &prepared.id, &selection)
+            .await
+            .unwrap();
+        assert_eq!(result.created, 0);
+        assert!(paths.iter().all(|p| !p.exists()));
+
+        assert!(engine
+            .apply_plan(&store, &prepared.id, &ReconcilePlanSelection::default())
+            .await
+            .is_err());
+
+        let prepared = engine.prepare_plan(&store).await.unwrap();
+        let result = engine
+            .apply_plan(&store, &prepared.id, &ReconcilePlanSelection::default())
+            .await
+            .unwrap();
+        assert_eq!(result.created, paths.len());
+        assert!(paths.iter().all(|p| p.exists()));
+    }
+
+    #[tokio::test]
+    async fn test_apply_plan_skips_files_changed_after_preview() {
+        let home = tempfile::tempdir().unwrap();
+        let engine = engine_with_rule(home.path()).await;
+        let store = ReconcilePlanStore::default();
+
+        let prepared = engine.prepare_plan(&store).await.unwrap();
+        let path = planned_paths(&prepared).remove(0);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "written by hand").unwrap();
+
+        let result = engine
+            .apply_plan(&store, &prepared.id, &ReconcilePlanSelection::default())
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "written by hand");
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains(&*path.to_string_lossy())));
+    }
+}
@@ -0,0 +1,317 @@
+//! Keeping repeated scans cheap enough to run on a timer.
+//!
+//! File hashes are cached in the `scan_cache` table keyed by path, size and mtime, so a file
+//! is only read and hashed again once it changes. Directory listings are kept in memory while
+//! the directory's mtime is unchanged, which skips re-listing command and skill folders nothing
+//! was added to or removed from. Anything modified within [`RACY_WINDOW_NS`] of the scan is
+//! not cached, since a coarse mtime could hide a second write in the same tick. Repository
+//! roots are scanned on scoped threads.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+
+use super::{ActualState, ReconciliationEngine};
+use crate::database::{Database, ScanCacheEntry};
+use crate::error::Result;
+
+const RACY_WINDOW_NS: i64 = 2_000_000_000;
+
+type DirListing = Arc<Vec<ListedEntry>>;
+
+/// Listings of scanned directories with the mtime they were taken at.
+static DIR_LISTINGS: LazyLock<Mutex<HashMap<PathBuf, (i64, DirListing)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn modified_ns(metadata: &fs::Metadata) -> Option<i64> {
+    let modified = metadata.modified().ok()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_nanos()).ok()
+}
+
+fn is_racy(modified_ns: i64) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|d| i64::try_from(d.as_nanos()).ok())
+        .unwrap_or(i64::MAX);
+    now.saturating_sub(modified_ns) < RACY_WINDOW_NS
+}
+
+/// The size and mtime a cached hash is valid for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FileStamp {
+    size: u64,
+    modified_ns: i64,
+}
+
+impl FileStamp {
+    pub(super) fn of(metadata: &fs::Metadata) -> Option<Self> {
+        Some(Self {
+            size: metadata.len(),
+            modified_ns: modified_ns(metadata)?,
+        })
+    }
+}
+
+/// File hashes from earlier scans, plus what this scan changed.
+#[derive(Default)]
+pub(super) struct ScanCache {
+    entries: HashMap<String, ScanCacheEntry>,
+    changed: Vec<ScanCacheEntry>,
+    seen: HashSet<String>,
+}
+
+impl ScanCache {
+    pub(super) async fn load(db: &Database) -> Self {
+        let entries = match db.get_scan_cache().await {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to load scan cache, rescanning everything: {}", e);
+                Vec::new()
+            }
+        };
+        Self {
+            entries: entries
+                .into_iter()
+                .map(|entry| (entry.file_path.clone(), entry))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// The cached hash for `path` if its stamp still matches: `Some(None)` for a file that
+    /// is not managed by RuleWeaver, `None` when it has to be read again.
+    pub(super) fn lookup(&mut self, path: &str, stamp: FileStamp) -> Option<Option<String>> {
+        self.seen.insert(path.to_string());
+        self.entries
+            .get(path)
+            .filter(|entry| entry.file_size == stamp.size && entry.modified_ns == stamp.modified_ns)
+            .map(|entry| entry.content_hash.clone())
+    }
+
+    pub(super) fn record(&mut self, path: String, stamp: FileStamp, content_hash: Option<String>) {
+        self.seen.insert(path.clone());
+        if is_racy(stamp.modified_ns) {
+            return;
+        }
+        self.changed.push(ScanCacheEntry {
+            file_path: path,
+            file_size: stamp.size,
+            modified_ns: stamp.modified_ns,
+            content_hash,
+        });
+    }
+
+    /// Persists what changed. With `prune`, entries for files this scan did not see are
+    /// dropped, which is only correct after a scan of every root.
+    pub(super) async fn save(self, db: &Database, prune: bool) {
+        let removed = if prune {
+            self.entries
+                .into_keys()
+                .filter(|path| !self.seen.contains(path))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if let Err(e) = db.update_scan_cache(self.changed, removed).await {
+            log::warn!("Failed to save scan cache: {}", e);
+        }
+    }
+}
+
+/// A directory entry with its type resolved.
+#[derive(Debug, Clone)]
+pub(super) struct ListedEntry {
+    pub path: PathBuf,
+    /// Whether the entry is a directory, following symlinks
+    pub is_dir: bool,
+    /// Whether the entry is a file, following symlinks
+    pub is_file: bool,
+    pub is_symlink: bool,
+}
+
+/// Lists `dir`, reusing the previous listing while its mtime is unchanged. Returns `None`
+/// when the directory does not exist or cannot be read.
+pub(super) fn list_dir(dir: &Path) -> Result<Option<DirListing>> {
+    let Some(stamp) = fs::metadata(dir)
+        .ok()
+        .filter(|m| m.is_dir())
+        .map(|m| modified_ns(&m))
+    else {
+        DIR_LISTINGS.lock().remove(dir);
+        return Ok(None);
+    };
+
+    if let Some(stamp) = stamp {
+        if let Some((cached, listing)) = DIR_LISTINGS.lock().get(dir) {
+            if *cached == stamp {
+                return Ok(Some(Arc::clone(listing)));
+            }
+        }
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return Ok(None),
+    };
+    let mut listing = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        listing.push(ListedEntry {
+            is_dir: path.is_dir(),
+            is_file: path.is_file(),
+            is_symlink: path.is_symlink(),
+            path,
+        });
+    }
+    let listing = Arc::new(listing);
+
+    let mut listings = DIR_LISTINGS.lock();
+    match stamp.filter(|stamp| !is_racy(*stamp)) {
+        Some(stamp) => {
+            listings.insert(dir.to_path_buf(), (stamp, Arc::clone(&listing)));
+        }
+        None => {
+            listings.remove(dir);
+        }
+    }
+    Ok(Some(listing))
+}
+
+impl ReconciliationEngine {
+    /// Runs `scan` for every root, spread over scoped threads, and merges what they find.
+    pub(super) fn scan_roots<F>(
+        &self,
+        roots: &[&PathBuf],
+        actual: &mut ActualState,
+        scan: F,
+    ) -> Result<()>
+    where
+        F: Fn(&Path, &mut ActualState) -> Result<()> + Sync,
+    {
+        let workers = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(roots.len());
+        if workers <= 1 {
+            for root in roots {
+                scan(root, actual)?;
+            }
+            return Ok(());
+        }
+
+        let scan = &scan;
+        let results: Vec<Result<ActualState>> = std::thread::scope(|s| {
+            let handles: Vec<_> = roots
+                .chunks(roots.len().div_ceil(workers))
+                .map(|chunk| {
+                    s.spawn(move || {
+                        let mut found = ActualState::default();
+                        for root in chunk {
+                            scan(root, &mut found)?;
+                        }
+                        Ok(found)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+        for found in results {
+            actual.found_paths.extend(found?.found_paths);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Scope;
+    use crate::reconciliation::RULEWEAVER_MARKER;
+    use std::time::Duration;
+
+    fn backdate(path: &Path) {
+        let old = SystemTime::now() - Duration::from_secs(60);
+        // Directories can only be opened read-only, which is enough to set times on Unix.
+        fs::File::options()
+            .write(!path.is_dir())
+            .read(path.is_dir())
+            .open(path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_file_is_served_from_cache_until_its_stamp_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rule.md");
+        fs::write(&path, format!("<!-- {} -->\nfirst", RULEWEAVER_MARKER)).unwrap();
+        backdate(&path);
+
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let engine = ReconciliationEngine::new(Arc::clone(&db)).unwrap();
+        let scan = |engine: &ReconciliationEngine| {
+            engine
+                .scan_artifact_file(&path, None, None, Scope::Global)
+                .unwrap()
+                .unwrap()
+                .content_hash
+        };
+
+        let first = scan(&engine);
+        let cache = std::mem::take(&mut *engine.scan_cache.lock());
+        cache.save(&db, true).await;
+        assert_eq!(db.get_scan_cache().await.unwrap().len(), 1);
+
+        // Cached entries are trusted without reading the file again.
+        let key = path.to_string_lossy().to_string();
+        db.update_scan_cache(
+            vec![ScanCacheEntry {
+                content_hash: Some("cached".to_string()),
+                ..db.get_scan_cache().await.unwrap().remove(0)
+            }],
+            vec![],
+        )
+        .await
+        .unwrap();
+        *engine.scan_cache.lock() = ScanCache::load(&db).await;
+        assert_eq!(scan(&engine), "cached");
+
+        // A new mtime invalidates the entry.
+        fs::write(&path, format!("<!-- {} -->\nsecond", RULEWEAVER_MARKER)).unwrap();
+        let second = scan(&engine);
+        assert_ne!(second, first);
+        assert_ne!(second, "cached");
+
+        // Pruning drops files the scan no longer saw.
+        *engine.scan_cache.lock() = ScanCache::default();
+        ScanCache::load(&db).await.save(&db, true).await;
+        assert!(db
+            .get_scan_cache()
+            .await
+            .unwrap()
+            .iter()
+            .all(|entry| entry.file_path != key));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_listing_refreshes_when_entries_change() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "a").unwrap();
+        backdate(dir.path());
+
+        assert_eq!(list_dir(dir.path()).unwrap().unwrap().len(), 1);
+        fs::write(dir.path().join("b.md"), "b").unwrap();
+        assert_eq!(list_dir(dir.path()).unwrap().unwrap().len(), 2);
+        assert!(list_dir(&dir.path().join("missing")).unwrap().is_none());
+    }
+}
@@ -1,11 +1,27 @@
 //! Secret redaction for execution logs.
 //!
 //! This module provides functionality to redact sensitive information from command output
-//! before storing in execution logs.
+//! before storing in execution logs. A [`Redactor`] combines the built-in credential
+//! patterns with the user's own regular expressions and the values of chosen environment
+//! variables; command and skill output passes through one before it is logged, streamed or
+//! returned to MCP clients.
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::database::Database;
+use crate::models::AppSettings;
+
+pub const REDACT_BUILTIN_PATTERNS_KEY: &str = "redact_builtin_patterns";
+/// JSON array of regular expressions.
+pub const REDACTION_PATTERNS_KEY: &str = "redaction_patterns";
+/// JSON array of environment variable names.
+pub const REDACTED_ENV_VARS_KEY: &str = "redacted_env_vars";
+
+/// Environment values shorter than this are left alone; replacing every `1` or `on` in
+/// the output would make it unreadable.
+const MIN_ENV_VALUE_LEN: usize = 4;
+
 static BEARER_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)bearer\s+[a-zA-Z0-9_.-]+").expect("bearer pattern"));
 
@@ -43,46 +59,46 @@ static GENERIC_SECRET_PATTERN: Lazy<Regex> = Lazy::new(|| {
 });
 
 struct RedactionPattern {
-    _name: &'static str,
+    name: &'static str,
     regex: &'static Lazy<Regex>,
 }
 
 static DEFAULT_PATTERNS: Lazy<Vec<RedactionPattern>> = Lazy::new(|| {
     vec![
         RedactionPattern {
-            _name: "Bearer Token",
+            name: "Bearer Token",
             regex: &BEARER_PATTERN,
         },
         RedactionPattern {
-            _name: "API Key",
+            name: "API Key",
             regex: &API_KEY_PATTERN,
         },
         RedactionPattern {
-            _name: "AWS Access Key",
+            name: "AWS Access Key",
             regex: &AWS_ACCESS_KEY_PATTERN,
         },
         RedactionPattern {
-            _name: "AWS Secret Key",
+            name: "AWS Secret Key",
             regex: &AWS_SECRET_KEY_PATTERN,
         },
         RedactionPattern {
-            _name: "Private Key",
+            name: "Private Key",
             regex: &PRIVATE_KEY_PATTERN,
         },
         RedactionPattern {
-            _name: "Connection String Password",
+            name: "Connection String Password",
             regex: &CONNECTION_STRING_PATTERN,
         },
         RedactionPattern {
-            _name: "GitHub Token",
+            name: "GitHub Token",
             regex: &GITHUB_TOKEN_PATTERN,
         },
         RedactionPattern {
-            _name: "Slack Token",
+            name: "Slack Token",
             regex: &SLACK_TOKEN_PATTERN,
         },
         RedactionPattern {
-            _name: "Generic Secret",
+            name: "Generic Secret",
             regex: &GENERIC_SECRET_PATTERN,
         },
     ]
@@ -107,10 +123,146 @@ pub fn redact(input: &str) -> (String, bool) {
     (result, was_redacted)
 }
 
+/// Every likely secret in `input`, as the pattern's name and the byte range it matched.
+pub fn find_secrets(input: &str) -> Vec<(&'static str, std::ops::Range<usize>)> {
+    DEFAULT_PATTERNS
+        .iter()
+        .flat_map(|pattern| {
+            pattern
+                .regex
+                .find_iter(input)
+                .map(move |m| (pattern.name, m.range()))
+        })
+        .collect()
+}
+
+/// Replaces every occurrence of the given literal values, such as injected secrets.
+pub fn redact_values(input: &str, values: &[String]) -> (String, bool) {
+    let mut result = input.to_string();
+    let mut was_redacted = false;
+
+    for value in values.iter().filter(|v| !v.is_empty()) {
+        if result.contains(value.as_str()) {
+            result = result.replace(value.as_str(), REDACTED);
+            was_redacted = true;
+        }
+    }
+
+    (result, was_redacted)
+}
+
+/// Whether `pattern` can be used as a user-defined redaction pattern.
+pub fn validate_pattern(pattern: &str) -> std::result::Result<(), String> {
+    let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+    if regex.is_match("") {
+        return Err("it matches empty text".to_string());
+    }
+    Ok(())
+}
+
+/// The redaction configured in settings for one run.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    builtin: bool,
+    patterns: Vec<Regex>,
+    /// Literal values, longest first so a value containing another is replaced whole
+    values: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            patterns: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl Redactor {
+    /// The redaction `settings` ask for, for a process that gets `envs` on top of the app's
+    /// own environment.
+    pub fn from_settings(settings: &AppSettings, envs: &[(String, String)]) -> Self {
+        let patterns = settings
+            .redaction_patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    log::warn!("Ignoring invalid redaction pattern '{}': {}", pattern, e);
+                    None
+                }
+            })
+            .collect();
+        let values = settings
+            .redacted_env_vars
+            .iter()
+            .filter_map(|name| {
+                envs.iter()
+                    .rev()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.clone())
+                    .or_else(|| std::env::var(name).ok())
+            })
+            .filter(|value| value.len() >= MIN_ENV_VALUE_LEN);
+        Self {
+            builtin: settings.redact_builtin_patterns,
+            patterns,
+            values: Vec::new(),
+        }
+        .with_values(values)
+    }
+
+    /// [`Redactor::from_settings`] with the stored settings.
+    pub async fn load(db: &Database, envs: &[(String, String)]) -> Self {
+        Self::from_settings(&crate::settings::load_or_default(db).await, envs)
+    }
+
+    /// Also replaces these literal values, such as injected secrets, whatever their length.
+    pub fn with_values(mut self, values: impl IntoIterator<Item = String>) -> Self {
+        self.values
+            .extend(values.into_iter().filter(|value| !value.is_empty()));
+        self.values
+            .sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        self.values.dedup();
+        self
+    }
+
+    /// Redacts the configured values, then the user's patterns, then the built-in ones.
+    /// Returns the redacted text and whether anything was replaced.
+    pub fn redact(&self, input: &str) -> (String, bool) {
+        let (mut result, mut was_redacted) = redact_values(input, &self.values);
+        for pattern in &self.patterns {
+            if pattern.is_match(&result) {
+                result = pattern.replace_all(&result, REDACTED).to_string();
+                was_redacted = true;
+            }
+        }
+        if self.builtin {
+            let (text, had_pattern) = redact(&result);
+            result = text;
+            was_redacted |= had_pattern;
+        }
+        (result, was_redacted)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_redact_values() {
+        let values = vec!["s3cr3t".to_string(), String::new()];
+        let (output, was_redacted) = redact_values("token=s3cr3t; again s3cr3t", &values);
+        assert!(was_redacted);
+        assert_eq!(output, "token=[REDACTED]; again [REDACTED]");
+
+        let (output, was_redacted) = redact_values("nothing here", &values);
+        assert!(!was_redacted);
+        assert_eq!(output, "nothing here");
+    }
+
     #[test]
     fn test_redact_bearer_token() {
         let input = "Authorization: Bearer abc123xyz789token";
@@ -197,4 +349,38 @@ MIIEvgIBADANBgkqhkiG9w0BAQEFAASCBKgwggSkAgEAAoIBAQC7VJTUt9Us8cKj
         assert!(!output.contains("token123"));
         assert!(!output.contains("sk_live_abcdefghijklmnop123456"));
     }
+
+    #[test]
+    fn test_redactor_applies_configured_patterns_and_env_values() {
+        let settings = AppSettings {
+            redact_builtin_patterns: false,
+            redaction_patterns: vec![r"ticket-\d+".to_string()],
+            redacted_env_vars: vec!["DEPLOY_HOST".to_string(), "DEBUG".to_string()],
+            ..AppSettings::default()
+        };
+        let envs = vec![
+            ("DEPLOY_HOST".to_string(), "db.example.net".to_string()),
+            ("DEBUG".to_string(), "1".to_string()),
+        ];
+        let redactor = Redactor::from_settings(&settings, &envs).with_values(["hunter22".into()]);
+
+        let (output, was_redacted) =
+            redactor.redact("ticket-42 on db.example.net with hunter22, Bearer abc123 x1");
+        assert!(was_redacted);
+        assert_eq!(
+            output,
+            "[REDACTED] on [REDACTED] with [REDACTED], Bearer abc123 x1"
+        );
+
+        let (output, was_redacted) = Redactor::default().redact("Bearer abc123");
+        assert!(was_redacted);
+        assert_eq!(output, REDACTED);
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        assert!(validate_pattern(r"ticket-\d+").is_ok());
+        assert!(validate_pattern("(unclosed").is_err());
+        assert!(validate_pattern("x*").is_err());
+    }
 }
@@ -0,0 +1,142 @@
+//! Feedback on whether rules make a difference.
+//!
+//! Agents report through the `report_rule_feedback` MCP tool and users through the app
+//! that a rule was helpful, ignored or harmful in a session. Reports are kept per rule,
+//! attributed to whoever made them, and counted in the rule stats so rules can be sorted
+//! by how they are doing.
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    Rule, RuleFeedback, RuleFeedbackInput, RuleFeedbackVerdict, RuleStats, RuleStatsSort,
+};
+
+const MAX_NOTE_LENGTH: usize = 2000;
+const MAX_SESSION_ID_LENGTH: usize = 200;
+
+/// The rule `reference` names: the rule with that id, or else the only one with that name.
+pub fn resolve<'a>(rules: &'a [Rule], reference: &str) -> Result<&'a Rule> {
+    let reference = reference.trim();
+    if let Some(rule) = rules.iter().find(|rule| rule.id == reference) {
+        return Ok(rule);
+    }
+    let mut named = rules
+        .iter()
+        .filter(|rule| rule.name.trim().eq_ignore_ascii_case(reference));
+    match (named.next(), named.next()) {
+        (Some(rule), None) => Ok(rule),
+        (Some(_), Some(_)) => Err(AppError::Validation(format!(
+            "Several rules are named '{}'; use the rule id",
+            reference
+        ))),
+        (None, _) => Err(AppError::RuleNotFound {
+            id: reference.to_string(),
+        }),
+    }
+}
+
+fn trimmed(value: Option<String>, field: &str, max: usize) -> Result<Option<String>> {
+    let Some(value) = value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    else {
+        return Ok(None);
+    };
+    if value.chars().count() > max {
+        return Err(AppError::Validation(format!(
+            "Feedback {} exceeds {} characters",
+            field, max
+        )));
+    }
+    Ok(Some(value))
+}
+
+/// Records feedback on the rule among `rules` that `input.rule_id` names by id or name.
+pub async fn report(
+    db: &Database,
+    rules: &[Rule],
+    mut input: RuleFeedbackInput,
+) -> Result<RuleFeedback> {
+    input.rule_id = resolve(rules, &input.rule_id)?.id.clone();
+    input.session_id = trimmed(input.session_id, "session id", MAX_SESSION_ID_LENGTH)?;
+    input.note = trimmed(input.note, "note", MAX_NOTE_LENGTH)?;
+    db.add_rule_feedback(input).await
+}
+
+/// Orders `stats` by `sort`. Feedback sorts put the most reports of that verdict first and
+/// fall back to the name order `get_rule_stats` returns.
+pub fn sort_stats(stats: &mut [RuleStats], sort: RuleStatsSort) {
+    let verdict = match sort {
+        RuleStatsSort::Name => return,
+        RuleStatsSort::Helpful => RuleFeedbackVerdict::Helpful,
+        RuleStatsSort::Ignored => RuleFeedbackVerdict::Ignored,
+        RuleStatsSort::Harmful => RuleFeedbackVerdict::Harmful,
+    };
+    stats.sort_by_key(|s| std::cmp::Reverse(s.feedback.count(verdict)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, Metadata, RuleFeedbackSummary, Scope};
+    use chrono::Utc;
+
+    fn rule(id: &str, name: &str) -> Rule {
+        Rule {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            content: String::new(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Metadata::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn stats(name: &str, helpful: u32, harmful: u32) -> RuleStats {
+        RuleStats {
+            rule_id: name.to_string(),
+            rule_name: name.to_string(),
+            enabled: true,
+            last_synced_at: None,
+            adapters: Vec::new(),
+            total_bytes: 0,
+            files: Vec::new(),
+            feedback: RuleFeedbackSummary {
+                helpful,
+                harmful,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_resolve_by_id_then_unique_name() {
+        let rules = [rule("1", "Testing"), rule("2", "Style"), rule("3", "style")];
+        assert_eq!(resolve(&rules, "2").unwrap().id, "2");
+        assert_eq!(resolve(&rules, " testing ").unwrap().id, "1");
+        assert!(matches!(
+            resolve(&rules, "Style"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            resolve(&rules, "Missing"),
+            Err(AppError::RuleNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sort_stats_by_verdict_keeps_name_order_for_ties() {
+        let mut all = vec![stats("A", 1, 0), stats("B", 3, 2), stats("C", 1, 5)];
+        sort_stats(&mut all, RuleStatsSort::Helpful);
+        let names: Vec<&str> = all.iter().map(|s| s.rule_name.as_str()).collect();
+        assert_eq!(names, vec!["B", "A", "C"]);
+
+        sort_stats(&mut all, RuleStatsSort::Harmful);
+        assert_eq!(all[0].rule_name, "C");
+    }
+}
@@ -0,0 +1,131 @@
+//! Candidates for generated files that reconciliation found orphaned.
+//!
+//! Adopting such a file imports it back as a rule, command or skill instead of deleting it.
+//! RuleWeaver's own "generated" header lines are stripped first, and for slash commands the
+//! adapter's frontmatter is unwrapped to the underlying script, since it is regenerated from
+//! the command's fields on the next sync.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{AppError, Result};
+use crate::models::{AdapterType, ImportArtifactType, ImportCandidate, ImportSourceType, Scope};
+
+use super::{candidate_from_text, infer_name, split_frontmatter};
+
+const GENERATED_MARKER: &str = "Generated by RuleWeaver";
+const SOURCE_LABEL: &str = "Reconciliation";
+
+pub fn candidate_from_orphan(
+    path: &Path,
+    adapter: Option<AdapterType>,
+    artifact_type: ImportArtifactType,
+    scope: Scope,
+    max_size: u64,
+) -> Result<ImportCandidate> {
+    let metadata = fs::metadata(path)?;
+    if metadata.len() > max_size {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "File '{}' exceeds max import size ({} bytes)",
+                path.display(),
+                max_size
+            ),
+        });
+    }
+    let content = fs::read_to_string(path)?;
+    let content = strip_generated_markers(&content);
+
+    let (content, description) = if artifact_type == ImportArtifactType::SlashCommand {
+        unwrap_slash_command(&content)
+    } else {
+        (content, String::new())
+    };
+
+    // Skills are named after their folder, not the SKILL.md inside it.
+    let name_path = match artifact_type {
+        ImportArtifactType::Skill => path.parent().unwrap_or(path),
+        _ => path,
+    };
+    let stem = name_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("adopted");
+
+    let mut candidate = candidate_from_text(
+        content,
+        &infer_name(name_path, stem, adapter),
+        ImportSourceType::File,
+        SOURCE_LABEL,
+        &path.to_string_lossy(),
+        adapter,
+        scope,
+        None,
+        artifact_type,
+    );
+    if candidate.description.is_empty() {
+        candidate.description = description;
+    }
+    Ok(candidate)
+}
+
+fn strip_generated_markers(content: &str) -> String {
+    content
+        .lines()
+        .filter(|line| {
+            let text = line.trim().trim_start_matches('#').trim();
+            !line.contains(GENERATED_MARKER) && !text.starts_with("Last synced:")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Returns the script and description wrapped in a generated slash command file.
+fn unwrap_slash_command(content: &str) -> (String, String) {
+    match split_frontmatter(content) {
+        Some((yaml, body)) => {
+            let description = serde_yaml::from_str::<serde_yaml::Mapping>(yaml)
+                .ok()
+                .and_then(|map| {
+                    map.get("description")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or_default();
+            (body, description)
+        }
+        None => (content.to_string(), String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slash_command_orphan_is_unwrapped_to_its_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("review.md");
+        fs::write(
+            &path,
+            "<!-- Generated by RuleWeaver - Do not edit manually -->\n---\ndescription: Review the diff\n---\n\nReview $ARGUMENTS carefully.\n",
+        )
+        .unwrap();
+
+        let candidate = candidate_from_orphan(
+            &path,
+            Some(AdapterType::ClaudeCode),
+            ImportArtifactType::SlashCommand,
+            Scope::Global,
+            1024,
+        )
+        .unwrap();
+
+        assert_eq!(candidate.name, "review");
+        assert_eq!(candidate.content, "Review $ARGUMENTS carefully.");
+        assert_eq!(candidate.description, "Review the diff");
+        assert_eq!(candidate.enabled_adapters, vec![AdapterType::ClaudeCode]);
+    }
+}
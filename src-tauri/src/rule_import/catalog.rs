@@ -0,0 +1,303 @@
+//! Community rule catalog.
+//!
+//! A catalog is a JSON index of installable entries, each pointing at a raw content URL.
+//! Entries are previewed and installed through the regular URL import pipeline, and every
+//! install is recorded so the catalog can show what is already present.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::Utc;
+use parking_lot::Mutex;
+
+use super::{execute_import, fetch_url_text, sanitize_rule_name, scan_url_to_candidates};
+use crate::constants::timing::CATALOG_CACHE_TTL;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    CatalogEntry, CatalogIndex, CatalogInstall, ImportExecutionOptions, ImportExecutionResult,
+    ImportScanResult,
+};
+
+pub const CATALOG_URL_KEY: &str = "rule_catalog_url";
+pub(crate) const CATALOG_INSTALLS_KEY: &str = "rule_catalog_installs";
+pub const DEFAULT_CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/ChrisUFO/RuleWeaver/main/catalog/index.json";
+const MAX_CATALOG_INDEX_SIZE: u64 = 5 * 1024 * 1024;
+
+struct CachedIndex {
+    url: String,
+    fetched_at: Instant,
+    entries: Vec<CatalogEntry>,
+}
+
+static INDEX_CACHE: Mutex<Option<CachedIndex>> = Mutex::new(None);
+
+pub async fn catalog_url(db: &Database) -> String {
+    db.get_setting(CATALOG_URL_KEY)
+        .await
+        .ok()
+        .flatten()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_CATALOG_URL.to_string())
+}
+
+fn parse_index(body: &str) -> Result<Vec<CatalogEntry>> {
+    // Accept either `{ "entries": [...] }` or a bare array of entries.
+    let entries = match serde_json::from_str::<CatalogIndex>(body) {
+        Ok(index) => index.entries,
+        Err(_) => {
+            serde_json::from_str::<Vec<CatalogEntry>>(body).map_err(|e| AppError::InvalidInput {
+                message: format!("Catalog index is not valid JSON: {}", e),
+            })?
+        }
+    };
+    Ok(entries
+        .into_iter()
+        .filter(|e| !e.id.trim().is_empty() && !e.url.trim().is_empty())
+        .map(|mut e| {
+            e.installed = false;
+            e
+        })
+        .collect())
+}
+
+async fn load_index(url: &str, refresh: bool) -> Result<Vec<CatalogEntry>> {
+    if !refresh {
+        if let Some(cached) = INDEX_CACHE.lock().as_ref() {
+            if cached.url == url && cached.fetched_at.elapsed() < CATALOG_CACHE_TTL {
+                return Ok(cached.entries.clone());
+            }
+        }
+    }
+
+    let entries = parse_index(&fetch_url_text(url, MAX_CATALOG_INDEX_SIZE).await?)?;
+    *INDEX_CACHE.lock() = Some(CachedIndex {
+        url: url.to_string(),
+        fetched_at: Instant::now(),
+        entries: entries.clone(),
+    });
+    Ok(entries)
+}
+
+fn filter_entries(
+    entries: Vec<CatalogEntry>,
+    query: Option<&str>,
+    tag: Option<&str>,
+) -> Vec<CatalogEntry> {
+    let query = query
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+    entries
+        .into_iter()
+        .filter(|e| tag.is_none_or(|t| e.tags.iter().any(|et| et.eq_ignore_ascii_case(t))))
+        .filter(|e| {
+            query.as_ref().is_none_or(|q| {
+                e.name.to_lowercase().contains(q)
+                    || e.description.to_lowercase().contains(q)
+                    || e.tags.iter().any(|t| t.to_lowercase().contains(q))
+            })
+        })
+        .collect()
+}
+
+pub async fn list_installs(db: &Database) -> Vec<CatalogInstall> {
+    let encoded = match db.get_setting(CATALOG_INSTALLS_KEY).await {
+        Ok(Some(v)) => v,
+        _ => return Vec::new(),
+    };
+    serde_json::from_str(&encoded).unwrap_or_default()
+}
+
+async fn record_install(db: &Database, install: CatalogInstall) -> Result<()> {
+    let mut installs = list_installs(db).await;
+    installs.retain(|i| !(i.entry_id == install.entry_id && i.catalog_url == install.catalog_url));
+    installs.push(install);
+    db.set_setting(CATALOG_INSTALLS_KEY, &serde_json::to_string(&installs)?)
+        .await
+}
+
+/// Lists catalog entries matching `query` (name, description or tag) and `tag`.
+pub async fn browse(
+    db: &Database,
+    query: Option<&str>,
+    tag: Option<&str>,
+    refresh: bool,
+) -> Result<Vec<CatalogEntry>> {
+    let url = catalog_url(db).await;
+    let entries = filter_entries(load_index(&url, refresh).await?, query, tag);
+    let installs = list_installs(db).await;
+    Ok(entries
+        .into_iter()
+        .map(|mut e| {
+            e.installed = installs
+                .iter()
+                .any(|i| i.catalog_url == url && i.entry_id == e.id);
+            e
+        })
+        .collect())
+}
+
+async fn find_entry(db: &Database, entry_id: &str) -> Result<CatalogEntry> {
+    let url = catalog_url(db).await;
+    load_index(&url, false)
+        .await?
+        .into_iter()
+        .find(|e| e.id == entry_id)
+        .ok_or_else(|| AppError::InvalidInput {
+            message: format!("Catalog entry not found: {}", entry_id),
+        })
+}
+
+async fn scan_entry(entry: &CatalogEntry, max_size: u64) -> Result<ImportScanResult> {
+    let mut scan = scan_url_to_candidates(&entry.url, max_size).await?;
+    let single = scan.candidates.len() == 1;
+    for candidate in &mut scan.candidates {
+        candidate.source_label = "Catalog".to_string();
+        candidate.artifact_type = entry.artifact_type;
+        if single {
+            candidate.name = sanitize_rule_name(&entry.name);
+            candidate.proposed_name = candidate.name.clone();
+        }
+    }
+    Ok(scan)
+}
+
+/// Fetches an entry's content without importing it.
+pub async fn preview(db: &Database, entry_id: &str, max_size: u64) -> Result<ImportScanResult> {
+    let entry = find_entry(db, entry_id).await?;
+    scan_entry(&entry, max_size).await
+}
+
+/// Installs the selected entries through the import pipeline and records their provenance.
+pub async fn install(
+    db: Arc<Database>,
+    entry_ids: &[String],
+    options: ImportExecutionOptions,
+) -> Result<ImportExecutionResult> {
+    let url = catalog_url(&db).await;
+    let max_size = super::resolve_max_size(&options);
+    let mut combined = ImportExecutionResult::default();
+
+    for entry_id in entry_ids {
+        let entry = match find_entry(&db, entry_id).await {
+            Ok(entry) => entry,
+            Err(e) => {
+                combined.errors.push(e.to_string());
+                continue;
+            }
+        };
+        let scan = match scan_entry(&entry, max_size).await {
+            Ok(scan) => scan,
+            Err(e) => {
+                combined.errors.push(format!("{}: {}", entry.name, e));
+                continue;
+            }
+        };
+
+        let result = execute_import(db.clone(), scan, options.clone()).await?;
+        let artifact_ids: Vec<String> = result
+            .imported_rules
+            .iter()
+            .map(|r| r.id.clone())
+            .chain(result.imported_commands.iter().map(|c| c.id.clone()))
+            .chain(result.imported_skills.iter().map(|s| s.id.clone()))
+            .collect();
+        if !artifact_ids.is_empty() {
+            record_install(
+                &db,
+                CatalogInstall {
+                    entry_id: entry.id.clone(),
+                    entry_name: entry.name.clone(),
+                    catalog_url: url.clone(),
+                    source_url: entry.url.clone(),
+                    artifact_ids,
+                    installed_at: Utc::now(),
+                },
+            )
+            .await?;
+        }
+        merge_results(&mut combined, result);
+    }
+    Ok(combined)
+}
+
+#[allow(deprecated)]
+fn merge_results(into: &mut ImportExecutionResult, from: ImportExecutionResult) {
+    into.imported.extend(from.imported);
+    into.imported_rules.extend(from.imported_rules);
+    into.imported_commands.extend(from.imported_commands);
+    into.imported_skills.extend(from.imported_skills);
+    into.skipped.extend(from.skipped);
+    into.conflicts.extend(from.conflicts);
+    into.errors.extend(from.errors);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = r#"{
+        "entries": [
+            {"id": "react", "name": "React Best Practices", "url": "https://example.com/react.md", "tags": ["frontend", "react"]},
+            {"id": "rust", "name": "Rust Style", "description": "Idiomatic Rust", "url": "https://example.com/rust.md", "tags": ["backend"]},
+            {"id": "", "name": "Broken", "url": "https://example.com/broken.md"}
+        ]
+    }"#;
+
+    #[test]
+    fn parse_index_accepts_wrapped_and_bare_lists() {
+        let entries = parse_index(INDEX).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let bare = r#"[{"id": "a", "name": "A", "url": "https://example.com/a.md"}]"#;
+        assert_eq!(parse_index(bare).unwrap().len(), 1);
+        assert!(parse_index("not json").is_err());
+    }
+
+    #[test]
+    fn filter_entries_matches_query_and_tag() {
+        let entries = parse_index(INDEX).unwrap();
+
+        let by_query = filter_entries(entries.clone(), Some("idiomatic"), None);
+        assert_eq!(by_query.len(), 1);
+        assert_eq!(by_query[0].id, "rust");
+
+        let by_tag = filter_entries(entries.clone(), None, Some("Frontend"));
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].id, "react");
+
+        assert_eq!(filter_entries(entries, Some("  "), None).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn browse_marks_installed_entries() {
+        let db = Database::new_in_memory().await.unwrap();
+        let url = "https://example.com/catalog.json";
+        db.set_setting(CATALOG_URL_KEY, url).await.unwrap();
+        *INDEX_CACHE.lock() = Some(CachedIndex {
+            url: url.to_string(),
+            fetched_at: Instant::now(),
+            entries: parse_index(INDEX).unwrap(),
+        });
+        record_install(
+            &db,
+            CatalogInstall {
+                entry_id: "rust".to_string(),
+                entry_name: "Rust Style".to_string(),
+                catalog_url: url.to_string(),
+                source_url: "https://example.com/rust.md".to_string(),
+                artifact_ids: vec!["rule-1".to_string()],
+                installed_at: Utc::now(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let entries = browse(&db, None, None, false).await.unwrap();
+        let installed: Vec<_> = entries.iter().filter(|e| e.installed).collect();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].id, "rust");
+    }
+}
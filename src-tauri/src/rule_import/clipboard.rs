@@ -0,0 +1,270 @@
+//! Format detection for clipboard imports.
+//!
+//! Pasted text may be a rule, a slash command, a skill, or a whole RuleWeaver export. The
+//! shape is inferred from the content itself: export JSON by its top-level artifact lists,
+//! and Markdown by which frontmatter keys it carries.
+
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value as YamlValue};
+
+use super::{candidate_from_text, parse_payload_adapters, sanitize_rule_name, split_frontmatter};
+use crate::models::{ImportArtifactType, ImportCandidate, ImportSourceType, Scope};
+
+const SOURCE_LABEL: &str = "Clipboard";
+const DEFAULT_NAME: &str = "clipboard-import";
+
+/// Frontmatter keys that only rules use.
+const RULE_KEYS: &[&str] = &[
+    "globs",
+    "alwaysApply",
+    "scope",
+    "enabledAdapters",
+    "adapters",
+];
+/// Frontmatter keys that only slash commands use.
+const COMMAND_KEYS: &[&str] = &["argument-hint", "argumentHint"];
+
+/// A multi-artifact document such as a configuration export.
+#[derive(Debug, Deserialize)]
+pub(super) struct ExportDocument {
+    rules: Option<Vec<ExportedArtifact>>,
+    commands: Option<Vec<ExportedArtifact>>,
+    skills: Option<Vec<ExportedArtifact>>,
+}
+
+/// The fields shared by exported rules, commands and skills.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedArtifact {
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default, alias = "script", alias = "instructions")]
+    content: String,
+    scope: Option<String>,
+    // Security: target paths are intentionally not read, as for other payloads.
+    enabled_adapters: Option<Vec<String>>,
+}
+
+/// Turns clipboard text into typed candidates. `name` overrides the inferred name of a
+/// single pasted artifact and is ignored for multi-artifact exports.
+pub(super) fn detect_clipboard_candidates(
+    content: &str,
+    name: Option<&str>,
+) -> Vec<ImportCandidate> {
+    let trimmed = content.trim();
+    if let Some(export) = parse_export(trimmed) {
+        return export_candidates(export, ImportSourceType::Clipboard, SOURCE_LABEL);
+    }
+    if let Some(candidate) = frontmatter_candidate(trimmed, name) {
+        return vec![candidate];
+    }
+    vec![candidate_from_text(
+        content.to_string(),
+        name.unwrap_or(DEFAULT_NAME),
+        ImportSourceType::Clipboard,
+        SOURCE_LABEL,
+        SOURCE_LABEL,
+        None,
+        Scope::Global,
+        None,
+        ImportArtifactType::Rule,
+    )]
+}
+
+/// Parses JSON listing `rules`, `commands` and/or `skills`. Returns `None` for anything
+/// else, including a single rule's JSON.
+pub(super) fn parse_export(content: &str) -> Option<ExportDocument> {
+    if !content.starts_with('{') {
+        return None;
+    }
+    let export: ExportDocument = serde_json::from_str(content).ok()?;
+    (export.rules.is_some() || export.commands.is_some() || export.skills.is_some())
+        .then_some(export)
+}
+
+/// Makes one typed candidate per exported artifact, skipping any without content.
+pub(super) fn export_candidates(
+    export: ExportDocument,
+    source_type: ImportSourceType,
+    source_label: &str,
+) -> Vec<ImportCandidate> {
+    let groups = [
+        (ImportArtifactType::Rule, "rules", export.rules),
+        (
+            ImportArtifactType::SlashCommand,
+            "commands",
+            export.commands,
+        ),
+        (ImportArtifactType::Skill, "skills", export.skills),
+    ];
+    let mut candidates = Vec::new();
+    for (artifact_type, kind, items) in groups {
+        for (index, item) in items.unwrap_or_default().into_iter().enumerate() {
+            if item.content.trim().is_empty() {
+                continue;
+            }
+            let scope = item
+                .scope
+                .as_deref()
+                .and_then(|s| Scope::from_str(s).ok())
+                .unwrap_or(Scope::Global);
+            // Each artifact gets its own source path so re-imports map back to it.
+            let key = item.id.clone().unwrap_or_else(|| index.to_string());
+            let source_path = format!("{}#{}/{}", source_label, kind, key);
+            let mut candidate = candidate_from_text(
+                item.content.trim().to_string(),
+                &item.name,
+                source_type.clone(),
+                source_label,
+                &source_path,
+                None,
+                scope,
+                None,
+                artifact_type,
+            );
+            let name = sanitize_rule_name(&item.name);
+            candidate.name = name.clone();
+            candidate.proposed_name = name;
+            candidate.description = item.description.trim().to_string();
+            candidate.enabled_adapters = parse_payload_adapters(item.enabled_adapters, None);
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Classifies Markdown with frontmatter as a slash command or skill. Returns `None` for
+/// rules, which the regular rule parser handles.
+fn frontmatter_candidate(content: &str, name: Option<&str>) -> Option<ImportCandidate> {
+    let (yaml, body) = split_frontmatter(content)?;
+    let frontmatter: Mapping = serde_yaml::from_str(yaml).ok()?;
+    let has_key = |key: &str| frontmatter.contains_key(key);
+    let text = |key: &str| {
+        frontmatter
+            .get(key)
+            .and_then(YamlValue::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let artifact_type = if RULE_KEYS.iter().any(|k| has_key(k)) {
+        return None;
+    } else if COMMAND_KEYS.iter().any(|k| has_key(k)) {
+        ImportArtifactType::SlashCommand
+    } else if text("name").is_some() && text("description").is_some() {
+        ImportArtifactType::Skill
+    } else if text("description").is_some() {
+        ImportArtifactType::SlashCommand
+    } else {
+        return None;
+    };
+
+    let inferred = name.or(text("name")).unwrap_or(DEFAULT_NAME);
+    let mut candidate = candidate_from_text(
+        body,
+        inferred,
+        ImportSourceType::Clipboard,
+        SOURCE_LABEL,
+        SOURCE_LABEL,
+        None,
+        Scope::Global,
+        None,
+        artifact_type,
+    );
+    let name = sanitize_rule_name(inferred);
+    candidate.name = name.clone();
+    candidate.proposed_name = name;
+    candidate.description = text("description").unwrap_or_default().to_string();
+    Some(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AdapterType;
+
+    #[test]
+    fn plain_text_is_a_rule() {
+        let candidates = detect_clipboard_candidates("Always write tests.", Some("tests"));
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].artifact_type, ImportArtifactType::Rule);
+        assert_eq!(candidates[0].name, "tests");
+    }
+
+    #[test]
+    fn rule_frontmatter_stays_a_rule() {
+        let content = "---\ndescription: React conventions\nglobs: \"*.tsx\"\n---\nUse hooks.";
+        let candidates = detect_clipboard_candidates(content, None);
+        assert_eq!(candidates[0].artifact_type, ImportArtifactType::Rule);
+        assert_eq!(candidates[0].content, "Use hooks.");
+    }
+
+    #[test]
+    fn command_frontmatter_is_a_slash_command() {
+        let content =
+            "---\ndescription: Review a PR\nargument-hint: <pr-number>\n---\nReview PR $ARGUMENTS.";
+        let candidates = detect_clipboard_candidates(content, Some("review"));
+        assert_eq!(candidates.len(), 1);
+        let command = &candidates[0];
+        assert_eq!(command.artifact_type, ImportArtifactType::SlashCommand);
+        assert_eq!(command.name, "review");
+        assert_eq!(command.description, "Review a PR");
+        assert_eq!(command.content, "Review PR $ARGUMENTS.");
+    }
+
+    #[test]
+    fn skill_frontmatter_is_a_skill() {
+        let content = "---\nname: pdf-tools\ndescription: Extract text from PDFs\n---\n# PDF tools\n\nRun the extractor.";
+        let candidates = detect_clipboard_candidates(content, None);
+        let skill = &candidates[0];
+        assert_eq!(skill.artifact_type, ImportArtifactType::Skill);
+        assert_eq!(skill.name, "pdf-tools");
+        assert_eq!(skill.description, "Extract text from PDFs");
+        assert!(skill.content.starts_with("# PDF tools"));
+    }
+
+    #[test]
+    fn export_json_yields_typed_candidates() {
+        let content = serde_json::json!({
+            "version": "1.0",
+            "rules": [{
+                "name": "style",
+                "content": "Use rustfmt.",
+                "scope": "global",
+                "enabledAdapters": ["cursor"]
+            }],
+            "commands": [{"name": "deploy", "description": "Ship it", "script": "make deploy"}],
+            "skills": [
+                {"name": "triage", "instructions": "Label new issues."},
+                {"name": "empty", "instructions": "  "}
+            ]
+        })
+        .to_string();
+
+        let candidates = detect_clipboard_candidates(&content, Some("ignored"));
+        assert_eq!(candidates.len(), 3);
+        let rule = &candidates[0];
+        assert_eq!(rule.artifact_type, ImportArtifactType::Rule);
+        assert_eq!(rule.name, "style");
+        assert_eq!(rule.enabled_adapters, vec![AdapterType::Cursor]);
+        let command = &candidates[1];
+        assert_eq!(command.artifact_type, ImportArtifactType::SlashCommand);
+        assert_eq!(command.content, "make deploy");
+        assert_eq!(command.description, "Ship it");
+        assert_eq!(candidates[2].artifact_type, ImportArtifactType::Skill);
+        assert_ne!(candidates[0].source_path, candidates[1].source_path);
+    }
+
+    #[test]
+    fn single_rule_json_is_not_an_export() {
+        let content = r#"{"name": "json-rule", "content": "Prefer iterators."}"#;
+        let candidates = detect_clipboard_candidates(content, None);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].artifact_type, ImportArtifactType::Rule);
+        assert_eq!(candidates[0].name, "json-rule");
+    }
+}
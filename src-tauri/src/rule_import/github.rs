@@ -0,0 +1,252 @@
+//! Resolution of GitHub page URLs (gists and blob views) to their raw content.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::{candidate_from_text, fetch_url_text, is_supported_import_extension};
+use crate::error::{AppError, Result};
+use crate::models::{ImportArtifactType, ImportScanResult, ImportSourceType, Scope};
+
+const GIST_HOST: &str = "gist.github.com";
+const GITHUB_HOST: &str = "github.com";
+const GITHUB_API_BASE: &str = "https://api.github.com";
+const GITHUB_RAW_BASE: &str = "https://raw.githubusercontent.com";
+
+/// A GitHub URL that has to be resolved before its content can be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GithubSource {
+    /// `gist.github.com/<user>/<id>[/<revision>]`
+    Gist {
+        id: String,
+        revision: Option<String>,
+    },
+    /// `github.com/<owner>/<repo>/blob/<ref>/<path>`
+    Blob {
+        owner: String,
+        repo: String,
+        git_ref: String,
+        path: String,
+    },
+}
+
+fn is_hex_id(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub(crate) fn parse_github_url(url: &url::Url) -> Option<GithubSource> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+
+    if host == GIST_HOST {
+        // Anonymous gists have no user segment.
+        let (id, revision) = match segments.as_slice() {
+            [id] if is_hex_id(id) => (*id, None),
+            [_, id] if is_hex_id(id) => (*id, None),
+            [_, id, revision] if is_hex_id(id) && is_hex_id(revision) => (*id, Some(*revision)),
+            _ => return None,
+        };
+        return Some(GithubSource::Gist {
+            id: id.to_string(),
+            revision: revision.map(str::to_string),
+        });
+    }
+
+    if host == GITHUB_HOST || host == "www.github.com" {
+        if let [owner, repo, "blob", git_ref, path @ ..] = segments.as_slice() {
+            if path.is_empty() {
+                return None;
+            }
+            return Some(GithubSource::Blob {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                git_ref: git_ref.to_string(),
+                path: path.join("/"),
+            });
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, Deserialize)]
+struct GistResponse {
+    files: HashMap<String, GistFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GistFile {
+    filename: String,
+    content: Option<String>,
+    #[serde(default)]
+    truncated: bool,
+    raw_url: Option<String>,
+}
+
+fn file_stem(path: &str, fallback: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+/// Source path recorded for a gist file. The gist id is stable across revisions, so
+/// re-importing a newer revision updates the artifacts created from the earlier one.
+pub(crate) fn gist_source_path(id: &str, filename: &str) -> String {
+    format!("gist:{}#{}", id, filename)
+}
+
+/// Source path recorded for a blob, keyed by repository, ref and file path.
+pub(crate) fn blob_source_path(owner: &str, repo: &str, git_ref: &str, path: &str) -> String {
+    format!("github:{}/{}@{}#{}", owner, repo, git_ref, path)
+}
+
+pub(crate) async fn scan_github_source(
+    source: GithubSource,
+    max_size: u64,
+) -> Result<ImportScanResult> {
+    match source {
+        GithubSource::Gist { id, revision } => scan_gist(&id, revision.as_deref(), max_size).await,
+        GithubSource::Blob {
+            owner,
+            repo,
+            git_ref,
+            path,
+        } => {
+            let raw_url = format!(
+                "{}/{}/{}/{}/{}",
+                GITHUB_RAW_BASE, owner, repo, git_ref, path
+            );
+            let body = fetch_url_text(&raw_url, max_size).await?;
+            let mut scan = ImportScanResult::default();
+            scan.candidates.push(candidate_from_text(
+                body,
+                &file_stem(&path, "github-rule"),
+                ImportSourceType::Url,
+                "GitHub",
+                &blob_source_path(&owner, &repo, &git_ref, &path),
+                None,
+                Scope::Global,
+                None,
+                ImportArtifactType::Rule,
+            ));
+            Ok(scan)
+        }
+    }
+}
+
+async fn scan_gist(id: &str, revision: Option<&str>, max_size: u64) -> Result<ImportScanResult> {
+    let api_url = match revision {
+        Some(revision) => format!("{}/gists/{}/{}", GITHUB_API_BASE, id, revision),
+        None => format!("{}/gists/{}", GITHUB_API_BASE, id),
+    };
+    // The API response embeds every file, so allow headroom beyond a single file's limit.
+    let body = fetch_url_text(&api_url, max_size.saturating_mul(4)).await?;
+    let gist: GistResponse = serde_json::from_str(&body).map_err(|e| AppError::InvalidInput {
+        message: format!("Unexpected gist API response: {}", e),
+    })?;
+
+    let mut files: Vec<GistFile> = gist.files.into_values().collect();
+    files.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+    let mut scan = ImportScanResult::default();
+    for file in files {
+        if !is_supported_import_extension(Path::new(&file.filename)) {
+            continue;
+        }
+        let content = match (file.content, file.truncated, file.raw_url) {
+            (Some(content), false, _) => content,
+            (_, _, Some(raw_url)) => match fetch_url_text(&raw_url, max_size).await {
+                Ok(content) => content,
+                Err(e) => {
+                    scan.errors.push(format!("{}: {}", file.filename, e));
+                    continue;
+                }
+            },
+            _ => continue,
+        };
+        if content.len() as u64 > max_size {
+            scan.errors.push(format!(
+                "{}: content exceeds max size ({} bytes)",
+                file.filename, max_size
+            ));
+            continue;
+        }
+        scan.candidates.push(candidate_from_text(
+            content,
+            &file_stem(&file.filename, "gist-rule"),
+            ImportSourceType::Url,
+            "Gist",
+            &gist_source_path(id, &file.filename),
+            None,
+            Scope::Global,
+            None,
+            ImportArtifactType::Rule,
+        ));
+    }
+
+    if scan.candidates.is_empty() && scan.errors.is_empty() {
+        scan.errors
+            .push(format!("Gist {} contains no importable files", id));
+    }
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Option<GithubSource> {
+        parse_github_url(&url::Url::parse(input).unwrap())
+    }
+
+    #[test]
+    fn parses_gist_urls() {
+        assert_eq!(
+            parse("https://gist.github.com/octocat/aa5a315d61ae9438b18d"),
+            Some(GithubSource::Gist {
+                id: "aa5a315d61ae9438b18d".to_string(),
+                revision: None,
+            })
+        );
+        assert_eq!(
+            parse("https://gist.github.com/octocat/aa5a315d61ae9438b18d/57a7f021a713b1c5a6a199b54cc514735d2d462f"),
+            Some(GithubSource::Gist {
+                id: "aa5a315d61ae9438b18d".to_string(),
+                revision: Some("57a7f021a713b1c5a6a199b54cc514735d2d462f".to_string()),
+            })
+        );
+        assert_eq!(parse("https://gist.github.com/octocat"), None);
+    }
+
+    #[test]
+    fn parses_blob_urls() {
+        assert_eq!(
+            parse("https://github.com/acme/rules/blob/main/docs/style.md"),
+            Some(GithubSource::Blob {
+                owner: "acme".to_string(),
+                repo: "rules".to_string(),
+                git_ref: "main".to_string(),
+                path: "docs/style.md".to_string(),
+            })
+        );
+        assert_eq!(parse("https://github.com/acme/rules/tree/main/docs"), None);
+        assert_eq!(
+            parse("https://raw.githubusercontent.com/acme/rules/main/style.md"),
+            None
+        );
+    }
+
+    #[test]
+    fn gist_source_path_ignores_revision() {
+        let first = parse("https://gist.github.com/octocat/aa5a315d61ae9438b18d/1111").unwrap();
+        let second = parse("https://gist.github.com/aa5a315d61ae9438b18d").unwrap();
+        let id_of = |source: GithubSource| match source {
+            GithubSource::Gist { id, .. } => gist_source_path(&id, "rules.md"),
+            GithubSource::Blob { .. } => unreachable!(),
+        };
+        assert_eq!(id_of(first), id_of(second));
+    }
+}
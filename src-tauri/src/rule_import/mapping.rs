@@ -0,0 +1,363 @@
+//! Path mapping rules for directory imports.
+//!
+//! Each rule pairs a glob with defaults (scope, adapters, tags, group) that are applied to
+//! candidates whose path, relative to the scanned directory, matches it. This lets a
+//! monorepo import send `frontend/**` to one set of tools and `backend/**` to another.
+
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    CollectionItemType, CreateCollectionInput, ImportArtifactType, ImportCandidate,
+    ImportMappingRule, TAGS_METADATA_KEY,
+};
+
+pub(crate) const MAPPING_RULES_KEY: &str = "import_mapping_rules";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+pub async fn list_mapping_rules(db: &Database) -> Vec<ImportMappingRule> {
+    let encoded = match db.get_setting(MAPPING_RULES_KEY).await {
+        Ok(Some(v)) => v,
+        _ => return Vec::new(),
+    };
+    serde_json::from_str(&encoded).unwrap_or_default()
+}
+
+/// Validates and stores the full, ordered rule list.
+pub async fn save_mapping_rules(
+    db: &Database,
+    rules: Vec<ImportMappingRule>,
+) -> Result<Vec<ImportMappingRule>> {
+    let rules = rules
+        .into_iter()
+        .map(normalize_rule)
+        .collect::<Result<Vec<_>>>()?;
+    db.set_setting(MAPPING_RULES_KEY, &serde_json::to_string(&rules)?)
+        .await?;
+    Ok(rules)
+}
+
+fn normalize_rule(rule: ImportMappingRule) -> Result<ImportMappingRule> {
+    let pattern = rule.pattern.trim().replace('\\', "/");
+    if pattern.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: "Mapping rule pattern cannot be empty".to_string(),
+        });
+    }
+    Pattern::new(&pattern).map_err(|e| AppError::InvalidInput {
+        message: format!("Invalid mapping pattern '{}': {}", pattern, e),
+    })?;
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in rule.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    Ok(ImportMappingRule {
+        pattern,
+        scope: rule.scope,
+        adapters: rule.adapters.filter(|a| !a.is_empty()),
+        tags,
+        group: rule
+            .group
+            .map(|g| g.trim().to_string())
+            .filter(|g| !g.is_empty()),
+    })
+}
+
+fn relative_path(root: &Path, source_path: &str) -> Option<String> {
+    let relative = Path::new(source_path).strip_prefix(root).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+fn merge_tags(candidate: &mut ImportCandidate, tags: &[String]) {
+    if tags.is_empty() {
+        return;
+    }
+    let mut merged: Vec<String> = candidate
+        .metadata
+        .tags()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    for tag in tags {
+        if !merged.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            merged.push(tag.clone());
+        }
+    }
+    candidate.metadata.insert(TAGS_METADATA_KEY, merged);
+}
+
+/// Applies every rule matching a candidate's path under `root`, in order.
+pub fn apply_mapping_rules(
+    candidates: &mut [ImportCandidate],
+    root: &Path,
+    rules: &[ImportMappingRule],
+) {
+    let compiled: Vec<(Pattern, &ImportMappingRule)> = rules
+        .iter()
+        .filter_map(|rule| match Pattern::new(&rule.pattern) {
+            Ok(pattern) => Some((pattern, rule)),
+            Err(e) => {
+                log::warn!("Skipping invalid mapping pattern '{}': {}", rule.pattern, e);
+                None
+            }
+        })
+        .collect();
+    if compiled.is_empty() {
+        return;
+    }
+
+    for candidate in candidates {
+        let Some(relative) = relative_path(root, &candidate.source_path) else {
+            continue;
+        };
+        for (pattern, rule) in &compiled {
+            if !pattern.matches_with(&relative, MATCH_OPTIONS) {
+                continue;
+            }
+            if let Some(scope) = rule.scope {
+                candidate.scope = scope;
+            }
+            if let Some(adapters) = &rule.adapters {
+                candidate.enabled_adapters = adapters.clone();
+            }
+            merge_tags(candidate, &rule.tags);
+            if let Some(group) = &rule.group {
+                candidate.collection = Some(group.clone());
+            }
+        }
+    }
+}
+
+fn collection_item_type(artifact_type: ImportArtifactType) -> CollectionItemType {
+    match artifact_type {
+        ImportArtifactType::Rule => CollectionItemType::Rule,
+        ImportArtifactType::SlashCommand => CollectionItemType::Command,
+        ImportArtifactType::Skill => CollectionItemType::Skill,
+    }
+}
+
+/// Files imported artifacts into top-level collections by name, creating any that are
+/// missing. Returns one message per assignment that failed.
+pub(super) async fn assign_collections(
+    db: &Database,
+    assignments: &[(ImportArtifactType, String, String)],
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    if assignments.is_empty() {
+        return errors;
+    }
+    let mut collections = match db.get_all_collections().await {
+        Ok(collections) => collections,
+        Err(e) => return vec![format!("Could not load collections: {}", e)],
+    };
+
+    for (artifact_type, artifact_id, name) in assignments {
+        let existing = collections
+            .iter()
+            .find(|c| c.parent_id.is_none() && c.name.eq_ignore_ascii_case(name))
+            .map(|c| c.id.clone());
+        let collection_id = match existing {
+            Some(id) => id,
+            None => match db
+                .create_collection(CreateCollectionInput {
+                    id: None,
+                    name: name.clone(),
+                    parent_id: None,
+                })
+                .await
+            {
+                Ok(created) => {
+                    let id = created.id.clone();
+                    collections.push(created);
+                    id
+                }
+                Err(e) => {
+                    errors.push(format!("Could not create collection '{}': {}", name, e));
+                    continue;
+                }
+            },
+        };
+        if let Err(e) = db
+            .set_item_collection(
+                collection_item_type(*artifact_type),
+                artifact_id,
+                Some(&collection_id),
+            )
+            .await
+        {
+            errors.push(format!(
+                "Could not add item to collection '{}': {}",
+                name, e
+            ));
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, Scope};
+    use crate::rule_import::{execute_import, scan_directory_with_mapping};
+    use std::fs;
+    use std::sync::Arc;
+
+    fn rule(pattern: &str) -> ImportMappingRule {
+        ImportMappingRule {
+            pattern: pattern.to_string(),
+            scope: None,
+            adapters: None,
+            tags: Vec::new(),
+            group: None,
+        }
+    }
+
+    #[test]
+    fn normalize_rejects_bad_patterns_and_cleans_fields() {
+        assert!(normalize_rule(rule("  ")).is_err());
+        assert!(normalize_rule(rule("frontend/[")).is_err());
+
+        let normalized = normalize_rule(ImportMappingRule {
+            tags: vec![" ui ".to_string(), "UI".to_string(), String::new()],
+            group: Some("  ".to_string()),
+            adapters: Some(Vec::new()),
+            ..rule("frontend\\**")
+        })
+        .unwrap();
+        assert_eq!(normalized.pattern, "frontend/**");
+        assert_eq!(normalized.tags, vec!["ui".to_string()]);
+        assert_eq!(normalized.group, None);
+        assert_eq!(normalized.adapters, None);
+    }
+
+    #[test]
+    fn directory_scan_applies_matching_rules_in_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("frontend/components")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("backend")).unwrap();
+        fs::write(
+            temp_dir.path().join("frontend/components/react.md"),
+            "Use hooks",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("backend/api.md"), "Use axum").unwrap();
+        fs::write(temp_dir.path().join("general.md"), "Be kind").unwrap();
+
+        let rules = vec![
+            ImportMappingRule {
+                scope: Some(Scope::Local),
+                adapters: Some(vec![AdapterType::Cursor, AdapterType::Windsurf]),
+                tags: vec!["frontend".to_string()],
+                group: Some("Frontend".to_string()),
+                ..rule("frontend/**")
+            },
+            ImportMappingRule {
+                adapters: Some(vec![AdapterType::ClaudeCode]),
+                ..rule("backend/**")
+            },
+            ImportMappingRule {
+                tags: vec!["react".to_string()],
+                ..rule("**/react.md")
+            },
+        ];
+
+        let scan = scan_directory_with_mapping(temp_dir.path(), 1024 * 1024, None, &rules);
+        let by_name = |name: &str| {
+            scan.candidates
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("missing candidate {}", name))
+        };
+
+        let react = by_name("react");
+        assert_eq!(react.scope, Scope::Local);
+        assert_eq!(
+            react.enabled_adapters,
+            vec![AdapterType::Cursor, AdapterType::Windsurf]
+        );
+        assert_eq!(
+            react.metadata.get(TAGS_METADATA_KEY),
+            Some(&serde_json::json!(["frontend", "react"]))
+        );
+        assert_eq!(react.collection.as_deref(), Some("Frontend"));
+
+        let api = by_name("api");
+        assert_eq!(api.enabled_adapters, vec![AdapterType::ClaudeCode]);
+        assert_eq!(api.collection, None);
+
+        let general = by_name("general");
+        assert_eq!(general.scope, Scope::Global);
+        assert!(general.metadata.get(TAGS_METADATA_KEY).is_none());
+    }
+
+    #[tokio::test]
+    async fn import_files_mapped_candidates_into_collections() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let existing = db
+            .create_collection(CreateCollectionInput {
+                id: None,
+                name: "Backend".to_string(),
+                parent_id: None,
+            })
+            .await
+            .unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("backend")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("frontend")).unwrap();
+        fs::write(temp_dir.path().join("backend/api.md"), "Use axum").unwrap();
+        fs::write(temp_dir.path().join("frontend/ui.md"), "Use hooks").unwrap();
+        let rules = vec![
+            ImportMappingRule {
+                group: Some("backend".to_string()),
+                ..rule("backend/**")
+            },
+            ImportMappingRule {
+                group: Some("Frontend".to_string()),
+                ..rule("frontend/**")
+            },
+        ];
+
+        let scan = scan_directory_with_mapping(temp_dir.path(), 1024 * 1024, None, &rules);
+        let result = execute_import(db.clone(), scan, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(result.imported_rules.len(), 2);
+
+        let collections = db.get_all_collections().await.unwrap();
+        assert_eq!(collections.len(), 2);
+        let frontend = collections.iter().find(|c| c.name == "Frontend").unwrap();
+        let items = db.get_all_collection_items().await.unwrap();
+        let collection_of = |name: &str| {
+            let rule = result
+                .imported_rules
+                .iter()
+                .find(|r| r.name == name)
+                .unwrap();
+            items
+                .iter()
+                .find(|i| i.item_id == rule.id)
+                .map(|i| i.collection_id.clone())
+        };
+        assert_eq!(collection_of("api"), Some(existing.id));
+        assert_eq!(collection_of("ui"), Some(frontend.id.clone()));
+    }
+}
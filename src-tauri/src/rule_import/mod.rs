@@ -3,32 +3,54 @@ use std::fs;
 use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
 use chrono::Utc;
+use regex::Regex;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
+use crate::audit;
 use crate::commands::{
     reconcile_after_mutation, register_local_rule_paths, storage_location_for_rule,
     use_file_storage,
 };
+use crate::constants::SKILL_INSTRUCTIONS_FILE;
 use crate::database::Database;
 use crate::error::{AppError, Result};
 use crate::file_storage;
+use crate::jobs::{CreatedArtifact, ImportJob, ImportProgress, JobHandle};
+use crate::metrics;
 use crate::models::{
-    AdapterType, Command, CreateCommandInput, CreateRuleInput, CreateSkillInput,
+    AdapterType, AuditAction, Command, CreateCommandInput, CreateRuleInput, CreateSkillInput,
     ImportArtifactType, ImportCandidate, ImportConflict, ImportConflictMode,
-    ImportExecutionOptions, ImportExecutionResult, ImportHistoryEntry, ImportScanResult,
-    ImportSkip, Rule, Scope, Skill, UpdateCommandInput, UpdateRuleInput, UpdateSkillInput,
+    ImportExecutionOptions, ImportExecutionResult, ImportHistoryEntry, ImportMappingRule,
+    ImportScanResult, ImportSkip, JobKind, Metadata, Rule, Scope, Skill, UpdateCommandInput,
+    UpdateRuleInput, UpdateSkillInput,
 };
+use crate::policy;
 use crate::sync::SyncEngine;
+use crate::tool_detection;
+
+pub mod adoption;
+pub mod archive;
+pub mod catalog;
+mod clipboard;
+mod github;
+pub mod mapping;
+pub mod peer;
+pub mod preview;
+pub mod share_link;
+mod similarity;
+pub mod subscriptions;
+pub mod team_repo;
 
 const DEFAULT_IMPORT_FILE_LIMIT: u64 = 10 * 1024 * 1024;
 const MAX_IMPORT_CANDIDATES: usize = 1000;
-const IMPORT_SOURCE_MAP_KEY: &str = "import_source_map";
-const IMPORT_HISTORY_KEY: &str = "import_history";
+pub const IMPORT_SOURCE_MAP_KEY: &str = "import_source_map";
+pub(crate) const IMPORT_HISTORY_KEY: &str = "import_history";
 const LOCAL_RULE_PATHS_KEY: &str = "local_rule_paths";
 
 #[derive(Debug, Deserialize)]
@@ -42,32 +64,52 @@ struct JsonRulePayload {
     enabled_adapters: Option<Vec<String>>,
 }
 
-pub async fn scan_url_to_candidates(url: &str, max_size: u64) -> Result<ImportScanResult> {
-    let parsed_url = validate_url_for_import(url)?;
-    let response = reqwest::get(parsed_url.clone())
-        .await
-        .map_err(|e| AppError::InvalidInput {
-            message: format!("Failed to fetch URL: {}", e),
-        })?;
-
-    validate_url_for_import(response.url().as_str())?;
+/// YAML frontmatter of a Markdown rule, covering RuleWeaver's own rule files and the
+/// Cursor-style `description`/`globs`/`alwaysApply` keys.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarkdownFrontmatter {
+    name: Option<String>,
+    description: Option<String>,
+    globs: Option<serde_yaml::Value>,
+    always_apply: Option<bool>,
+    scope: Option<String>,
+    // Security: target paths are intentionally not read, as for JSON payloads.
+    #[serde(alias = "adapters")]
+    enabled_adapters: Option<Vec<String>>,
+    #[serde(default)]
+    metadata: Metadata,
+}
 
-    if !response.status().is_success() {
-        return Err(AppError::InvalidInput {
-            message: format!("URL returned non-success status: {}", response.status()),
-        });
-    }
+/// YAML frontmatter of a SKILL.md per the Claude skills spec.
+#[derive(Debug, Deserialize)]
+struct SkillFrontmatter {
+    name: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "allowed-tools")]
+    allowed_tools: Option<serde_yaml::Value>,
+    license: Option<String>,
+}
 
-    let body = response.text().await.map_err(|e| AppError::InvalidInput {
-        message: format!("Failed to read URL response body: {}", e),
-    })?;
+/// Rule fields recovered from an imported document.
+struct RulePayload {
+    name: String,
+    content: String,
+    scope: Scope,
+    target_paths: Option<Vec<String>>,
+    adapters: Vec<AdapterType>,
+    description: String,
+    metadata: Metadata,
+}
 
-    if body.len() as u64 > max_size {
-        return Err(AppError::InvalidInput {
-            message: format!("URL content exceeds max size ({} bytes)", max_size),
-        });
+pub async fn scan_url_to_candidates(url: &str, max_size: u64) -> Result<ImportScanResult> {
+    let parsed_url = validate_url_for_import(url)?;
+    if let Some(source) = github::parse_github_url(&parsed_url) {
+        return github::scan_github_source(source, max_size).await;
     }
 
+    let body = fetch_url_text(parsed_url.as_str(), max_size).await?;
+
     let mut scan = ImportScanResult::default();
     let inferred_name = parsed_url
         .path_segments()
@@ -87,6 +129,65 @@ pub async fn scan_url_to_candidates(url: &str, max_size: u64) -> Result<ImportSc
     Ok(scan)
 }
 
+/// Sends a GET for a public http(s) URL, re-validating the final URL after redirects.
+async fn fetch_url_response(url: &str) -> Result<reqwest::Response> {
+    let parsed_url = validate_url_for_import(url)?;
+    let response = reqwest::Client::new()
+        .get(parsed_url)
+        .header(reqwest::header::USER_AGENT, "RuleWeaver")
+        .send()
+        .await
+        .map_err(|e| AppError::InvalidInput {
+            message: format!("Failed to fetch URL: {}", e),
+        })?;
+
+    validate_url_for_import(response.url().as_str())?;
+
+    if !response.status().is_success() {
+        return Err(AppError::InvalidInput {
+            message: format!("URL returned non-success status: {}", response.status()),
+        });
+    }
+    Ok(response)
+}
+
+/// Fetches a public http(s) URL as text.
+pub(crate) async fn fetch_url_text(url: &str, max_size: u64) -> Result<String> {
+    let body = fetch_url_response(url)
+        .await?
+        .text()
+        .await
+        .map_err(|e| AppError::InvalidInput {
+            message: format!("Failed to read URL response body: {}", e),
+        })?;
+
+    if body.len() as u64 > max_size {
+        return Err(AppError::InvalidInput {
+            message: format!("URL content exceeds max size ({} bytes)", max_size),
+        });
+    }
+    Ok(body)
+}
+
+/// Fetches a public http(s) URL as raw bytes, for binary assets.
+pub(crate) async fn fetch_url_bytes(url: &str, max_size: u64) -> Result<Vec<u8>> {
+    let body =
+        fetch_url_response(url)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| AppError::InvalidInput {
+                message: format!("Failed to read URL response body: {}", e),
+            })?;
+
+    if body.len() as u64 > max_size {
+        return Err(AppError::InvalidInput {
+            message: format!("URL content exceeds max size ({} bytes)", max_size),
+        });
+    }
+    Ok(body.to_vec())
+}
+
 pub fn scan_clipboard_to_candidates(
     content: &str,
     name: Option<&str>,
@@ -111,20 +212,10 @@ pub fn scan_clipboard_to_candidates(
         });
     }
 
-    let mut scan = ImportScanResult::default();
-    let inferred = name.unwrap_or("clipboard-import");
-    scan.candidates.push(candidate_from_text(
-        content.to_string(),
-        inferred,
-        crate::models::ImportSourceType::Clipboard,
-        "Clipboard",
-        "Clipboard",
-        None,
-        Scope::Global,
-        None,
-        ImportArtifactType::Rule,
-    ));
-    Ok(scan)
+    Ok(ImportScanResult {
+        candidates: clipboard::detect_clipboard_candidates(content, name),
+        ..Default::default()
+    })
 }
 
 pub fn scan_file_to_candidates(path: &Path, max_size: u64) -> ImportScanResult {
@@ -176,10 +267,12 @@ pub fn scan_directory_to_candidates(
         .into_iter()
         .filter_map(|e| e.ok())
     {
-        let item_path = entry.path();
-        if !item_path.is_file() {
+        // The entry's own type, not its target's: a scanned repository could otherwise
+        // link a rule file to anything on this machine.
+        if !entry.file_type().is_file() {
             continue;
         }
+        let item_path = entry.path();
 
         let artifact_type = detect_artifact_type_from_path(item_path);
         if let Some(filter) = artifact_filter {
@@ -224,9 +317,10 @@ pub async fn scan_ai_tool_candidates(db: Arc<Database>, max_size: u64) -> Result
     let mut scan = ImportScanResult::default();
     let home = dirs::home_dir()
         .ok_or_else(|| AppError::Path("Could not determine home directory".to_string()))?;
+    let disabled = crate::sync::disabled_adapters(&db).await;
 
     for tool_path in global_tool_paths(&home) {
-        if !tool_path.path.exists() {
+        if disabled.contains(&tool_path.adapter) || !tool_path.path.exists() {
             continue;
         }
 
@@ -268,6 +362,9 @@ pub async fn scan_ai_tool_candidates(db: Arc<Database>, max_size: u64) -> Result
 
     for local_root in get_local_rule_roots(db.clone()).await {
         for local_path in local_tool_paths() {
+            if disabled.contains(&local_path.adapter) {
+                continue;
+            }
             let path = local_root.join(local_path.relative_path);
             if !path.exists() {
                 continue;
@@ -445,12 +542,95 @@ const SLASH_COMMAND_PATTERNS: &[&str] = &[
 
 const SKILL_PATTERNS: &[&str] = &["/skills/", "\\skills\\", "documents/cline/skills"];
 
+/// Scans a directory like [`scan_directory_to_candidates`], then applies the user's path
+/// mapping rules to each candidate.
+pub fn scan_directory_with_mapping(
+    path: &Path,
+    max_size: u64,
+    artifact_filter: Option<ImportArtifactType>,
+    rules: &[ImportMappingRule],
+) -> ImportScanResult {
+    let mut scan = scan_directory_to_candidates(path, max_size, artifact_filter);
+    if let Ok(root) = path.canonicalize() {
+        mapping::apply_mapping_rules(&mut scan.candidates, &root, rules);
+    }
+    scan
+}
+
 pub async fn execute_import(
     db: Arc<Database>,
     scan_result: ImportScanResult,
     options: ImportExecutionOptions,
+) -> Result<ImportExecutionResult> {
+    let started = Instant::now();
+    let total = match &options.selected_candidate_ids {
+        Some(ids) => ids.len(),
+        None => scan_result.candidates.len(),
+    };
+    let job = JobHandle::begin(
+        &db,
+        JobKind::Import,
+        format!("Import {} candidate(s)", total),
+        total as u32,
+        &ImportJob {
+            scan: scan_result.clone(),
+            options: options.clone(),
+        },
+    )
+    .await;
+    let result = run_import(Arc::clone(&db), scan_result, options, &job).await;
+    job.end(&db, &result).await;
+
+    let status = match &result {
+        Ok(r) if r.errors.is_empty() => "success",
+        Ok(_) => "partial",
+        Err(_) => "failed",
+    };
+    metrics::increment(metrics::IMPORT_RUNS, &[("status", status)]);
+    metrics::observe_since(metrics::IMPORT_DURATION, &[], started);
+    if let Ok(r) = &result {
+        metrics::add(
+            metrics::IMPORT_ARTIFACTS,
+            &[("type", "rule")],
+            r.imported_rules.len() as u64,
+        );
+        metrics::add(
+            metrics::IMPORT_ARTIFACTS,
+            &[("type", "command")],
+            r.imported_commands.len() as u64,
+        );
+        metrics::add(
+            metrics::IMPORT_ARTIFACTS,
+            &[("type", "skill")],
+            r.imported_skills.len() as u64,
+        );
+        audit::record_run(
+            &db,
+            AuditAction::Import,
+            format!(
+                "Imported {} rule(s), {} command(s), {} skill(s); {} skipped, {} error(s)",
+                r.imported_rules.len(),
+                r.imported_commands.len(),
+                r.imported_skills.len(),
+                r.skipped.len(),
+                r.errors.len()
+            ),
+        )
+        .await;
+    }
+    result
+}
+
+async fn run_import(
+    db: Arc<Database>,
+    mut scan_result: ImportScanResult,
+    options: ImportExecutionOptions,
+    job: &JobHandle,
 ) -> Result<ImportExecutionResult> {
     let mut result = ImportExecutionResult::default();
+    policy::get_org_policy(&db)
+        .await
+        .mark_candidates(&mut scan_result.candidates);
     let history_source_type = scan_result
         .candidates
         .first()
@@ -465,6 +645,15 @@ pub async fn execute_import(
     let mut existing_commands = db.get_all_commands().await?;
     let mut existing_skills = db.get_all_skills().await?;
     let mut source_map = read_source_map(db.clone()).await;
+    let mut collection_targets = Vec::new();
+    let mut fingerprints = similarity::FingerprintCache::default();
+    let preexisting_ids: HashSet<String> = existing_rules
+        .iter()
+        .map(|r| r.id.clone())
+        .chain(existing_commands.iter().map(|c| c.id.clone()))
+        .chain(existing_skills.iter().map(|s| s.id.clone()))
+        .collect();
+    let mut processed = Vec::new();
 
     for candidate in scan_result.candidates {
         if let Some(selected) = selected_set.as_ref() {
@@ -472,8 +661,18 @@ pub async fn execute_import(
                 continue;
             }
         }
+        job.checkpoint(
+            &db,
+            processed.len() as u32,
+            &job_progress(&result, &preexisting_ids, &processed),
+        )
+        .await;
+        processed.push(candidate.id.clone());
 
         let source_key = source_identity(&candidate);
+        if let Some(collection) = candidate.collection.clone() {
+            collection_targets.push((candidate.artifact_type, source_key.clone(), collection));
+        }
         let effective_scope = options.default_scope.unwrap_or(candidate.scope);
         let effective_adapters = options
             .default_adapters
@@ -523,12 +722,15 @@ pub async fn execute_import(
                             &artifact_id,
                             UpdateRuleInput {
                                 name: Some(candidate.proposed_name.clone()),
-                                description: None,
+                                description: (!candidate.description.is_empty())
+                                    .then(|| candidate.description.clone()),
                                 content: Some(candidate.content.clone()),
                                 scope: Some(effective_scope),
                                 target_paths: None, // Security: Always strip on import
                                 enabled_adapters: Some(effective_adapters.clone()),
                                 enabled: Some(true),
+                                metadata: (!candidate.metadata.is_empty())
+                                    .then(|| candidate.metadata.clone()),
                             },
                         )
                         .await?;
@@ -566,11 +768,14 @@ pub async fn execute_import(
                             UpdateSkillInput {
                                 name: Some(candidate.proposed_name.clone()),
                                 instructions: Some(candidate.content.clone()),
+                                allowed_tools: Some(candidate.allowed_tools.clone()),
+                                license: candidate.license.clone(),
                                 ..Default::default()
                             },
                         )
                         .await?;
                     // Persistence for skills
+                    let update = import_skill_bundle(&db, update, &candidate).await?;
                     persist_skill_to_file_if_needed(db.clone(), &update).await?;
                     existing_skills.retain(|s| s.id != update.id);
                     existing_skills.push(update.clone());
@@ -580,6 +785,54 @@ pub async fn execute_import(
             continue;
         }
 
+        if !options.allow_near_duplicates {
+            let near_duplicate = match candidate.artifact_type {
+                ImportArtifactType::Rule => similarity::closest_near_duplicate(
+                    &mut fingerprints,
+                    &candidate.content,
+                    existing_rules
+                        .iter()
+                        .map(|r| (r.id.as_str(), r.name.as_str(), r.content.as_str())),
+                ),
+                ImportArtifactType::SlashCommand => similarity::closest_near_duplicate(
+                    &mut fingerprints,
+                    &candidate.content,
+                    existing_commands
+                        .iter()
+                        .map(|c| (c.id.as_str(), c.name.as_str(), c.script.as_str())),
+                ),
+                ImportArtifactType::Skill => similarity::closest_near_duplicate(
+                    &mut fingerprints,
+                    &candidate.content,
+                    existing_skills
+                        .iter()
+                        .map(|s| (s.id.as_str(), s.name.as_str(), s.instructions.as_str())),
+                ),
+            };
+            // Replacing a same-named artifact is an explicit choice, so only flag others.
+            let near_duplicate = near_duplicate.filter(|near| {
+                options.conflict_mode != ImportConflictMode::Replace
+                    || !near.name.eq_ignore_ascii_case(&candidate.proposed_name)
+            });
+            if let Some(near) = near_duplicate {
+                #[allow(deprecated)]
+                result.conflicts.push(ImportConflict {
+                    candidate_id: candidate.id.clone(),
+                    candidate_name: candidate.proposed_name.clone(),
+                    existing_rule_id: Some(near.id.clone()),
+                    existing_rule_name: Some(near.name.clone()),
+                    reason: format!(
+                        "Near-duplicate of '{}' ({}% similar)",
+                        near.name, near.percent
+                    ),
+                    existing_id: Some(near.id),
+                    existing_name: Some(near.name),
+                    similarity: Some(near.percent),
+                });
+                continue;
+            }
+        }
+
         let same_name_id = match candidate.artifact_type {
             ImportArtifactType::Rule => existing_rules
                 .iter()
@@ -619,6 +872,7 @@ pub async fn execute_import(
                         existing_id: Some(existing_id),
                         existing_name: Some(existing_name),
                         reason: "Name collision with different content".to_string(),
+                        similarity: None,
                     });
                     continue;
                 }
@@ -630,12 +884,15 @@ pub async fn execute_import(
                                     &existing_id,
                                     UpdateRuleInput {
                                         name: Some(candidate.proposed_name.clone()),
-                                        description: None,
+                                        description: (!candidate.description.is_empty())
+                                            .then(|| candidate.description.clone()),
                                         content: Some(candidate.content.clone()),
                                         scope: Some(effective_scope),
                                         target_paths: None, // Security: Always strip on import
                                         enabled_adapters: Some(effective_adapters.clone()),
                                         enabled: Some(true),
+                                        metadata: (!candidate.metadata.is_empty())
+                                            .then(|| candidate.metadata.clone()),
                                     },
                                 )
                                 .await?;
@@ -655,6 +912,8 @@ pub async fn execute_import(
                                     &existing_id,
                                     UpdateCommandInput {
                                         name: Some(candidate.proposed_name.clone()),
+                                        description: (!candidate.description.is_empty())
+                                            .then(|| candidate.description.clone()),
                                         script: Some(candidate.content.clone()),
                                         target_paths: None, // Security: Always strip on import
                                         ..Default::default()
@@ -673,11 +932,16 @@ pub async fn execute_import(
                                     &existing_id,
                                     UpdateSkillInput {
                                         name: Some(candidate.proposed_name.clone()),
+                                        description: (!candidate.description.is_empty())
+                                            .then(|| candidate.description.clone()),
                                         instructions: Some(candidate.content.clone()),
+                                        allowed_tools: Some(candidate.allowed_tools.clone()),
+                                        license: candidate.license.clone(),
                                         ..Default::default()
                                     },
                                 )
                                 .await?;
+                            let update = import_skill_bundle(&db, update, &candidate).await?;
                             persist_skill_to_file_if_needed(db.clone(), &update).await?;
                             source_map.insert(source_key, update.id.clone());
                             existing_skills.retain(|s| s.id != update.id);
@@ -718,12 +982,13 @@ pub async fn execute_import(
                                 .create_rule(CreateRuleInput {
                                     id: None,
                                     name: unique_name,
-                                    description: String::new(),
+                                    description: candidate.description.clone(),
                                     content: candidate.content.clone(),
                                     scope: effective_scope,
                                     target_paths: None, // Security: Always strip on import
                                     enabled_adapters: effective_adapters.clone(),
                                     enabled: true,
+                                    metadata: candidate.metadata.clone(),
                                 })
                                 .await?;
                             persist_rule_to_file_if_needed(db.clone(), &created).await?;
@@ -739,6 +1004,7 @@ pub async fn execute_import(
                             let created = db
                                 .create_command(CreateCommandInput {
                                     name: unique_name,
+                                    description: candidate.description.clone(),
                                     script: candidate.content.clone(),
                                     ..Default::default()
                                 })
@@ -752,10 +1018,14 @@ pub async fn execute_import(
                             let created = db
                                 .create_skill(CreateSkillInput {
                                     name: unique_name,
+                                    description: candidate.description.clone(),
                                     instructions: candidate.content.clone(),
+                                    allowed_tools: candidate.allowed_tools.clone(),
+                                    license: candidate.license.clone(),
                                     ..Default::default()
                                 })
                                 .await?;
+                            let created = import_skill_bundle(&db, created, &candidate).await?;
                             persist_skill_to_file_if_needed(db.clone(), &created).await?;
                             source_map.insert(source_key, created.id.clone());
                             existing_skills.push(created.clone());
@@ -777,12 +1047,13 @@ pub async fn execute_import(
                     .create_rule(CreateRuleInput {
                         id: None,
                         name: candidate.proposed_name.clone(),
-                        description: String::new(),
+                        description: candidate.description.clone(),
                         content: candidate.content.clone(),
                         scope: effective_scope,
                         target_paths: None, // Security: Always strip on import
                         enabled_adapters: effective_adapters,
                         enabled: true,
+                        metadata: candidate.metadata.clone(),
                     })
                     .await?;
                 persist_rule_to_file_if_needed(db.clone(), &created).await?;
@@ -799,6 +1070,7 @@ pub async fn execute_import(
                 let created = db
                     .create_command(CreateCommandInput {
                         name: candidate.proposed_name.clone(),
+                        description: candidate.description.clone(),
                         script: candidate.content.clone(),
                         ..Default::default()
                     })
@@ -812,10 +1084,14 @@ pub async fn execute_import(
                 let created = db
                     .create_skill(CreateSkillInput {
                         name: candidate.proposed_name.clone(),
+                        description: candidate.description.clone(),
                         instructions: candidate.content.clone(),
+                        allowed_tools: candidate.allowed_tools.clone(),
+                        license: candidate.license.clone(),
                         ..Default::default()
                     })
                     .await?;
+                let created = import_skill_bundle(&db, created, &candidate).await?;
                 persist_skill_to_file_if_needed(db.clone(), &created).await?;
                 source_map.insert(source_key, created.id.clone());
                 existing_skills.push(created.clone());
@@ -825,6 +1101,27 @@ pub async fn execute_import(
     }
 
     write_source_map(db.clone(), &source_map).await?;
+
+    // Only file artifacts written by this import; skipped candidates keep their collections.
+    let imported_ids: HashSet<&str> = result
+        .imported_rules
+        .iter()
+        .map(|r| r.id.as_str())
+        .chain(result.imported_commands.iter().map(|c| c.id.as_str()))
+        .chain(result.imported_skills.iter().map(|s| s.id.as_str()))
+        .collect();
+    let assignments: Vec<_> = collection_targets
+        .into_iter()
+        .filter_map(|(artifact_type, source_key, collection)| {
+            source_map
+                .get(&source_key)
+                .filter(|id| imported_ids.contains(id.as_str()))
+                .map(|id| (artifact_type, id.clone(), collection))
+        })
+        .collect();
+    let collection_errors = mapping::assign_collections(&db, &assignments).await;
+    result.errors.extend(collection_errors);
+
     append_history(
         db.clone(),
         ImportHistoryEntry {
@@ -861,6 +1158,41 @@ pub async fn execute_import(
     Ok(result)
 }
 
+/// Progress for the job journal: the candidates handled so far and the artifacts created,
+/// rather than updated, along the way.
+fn job_progress(
+    result: &ImportExecutionResult,
+    preexisting_ids: &HashSet<String>,
+    processed: &[String],
+) -> ImportProgress {
+    let created = |artifact_type: ImportArtifactType, id: &String| {
+        (!preexisting_ids.contains(id)).then(|| CreatedArtifact {
+            artifact_type,
+            id: id.clone(),
+        })
+    };
+    ImportProgress {
+        processed: processed.to_vec(),
+        created: result
+            .imported_rules
+            .iter()
+            .filter_map(|r| created(ImportArtifactType::Rule, &r.id))
+            .chain(
+                result
+                    .imported_commands
+                    .iter()
+                    .filter_map(|c| created(ImportArtifactType::SlashCommand, &c.id)),
+            )
+            .chain(
+                result
+                    .imported_skills
+                    .iter()
+                    .filter_map(|s| created(ImportArtifactType::Skill, &s.id)),
+            )
+            .collect(),
+    }
+}
+
 pub async fn read_import_history(db: Arc<Database>) -> Vec<ImportHistoryEntry> {
     let encoded = match db.get_setting(IMPORT_HISTORY_KEY).await {
         Ok(Some(v)) => v,
@@ -911,6 +1243,47 @@ async fn persist_skill_to_file_if_needed(db: Arc<Database>, skill: &Skill) -> Re
     Ok(())
 }
 
+/// Copies the `scripts/` and `resources/` next to an imported SKILL.md into the skill's
+/// own directory, so multi-file skills arrive whole.
+async fn import_skill_bundle(
+    db: &Arc<Database>,
+    skill: Skill,
+    candidate: &ImportCandidate,
+) -> Result<Skill> {
+    if !matches!(
+        candidate.source_type,
+        crate::models::ImportSourceType::AiTool
+            | crate::models::ImportSourceType::File
+            | crate::models::ImportSourceType::Directory
+    ) {
+        return Ok(skill);
+    }
+    let source = Path::new(&candidate.source_path);
+    let is_skill_file = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.eq_ignore_ascii_case(SKILL_INSTRUCTIONS_FILE));
+    let Some(source_dir) = source.parent().filter(|_| is_skill_file) else {
+        return Ok(skill);
+    };
+    let files = file_storage::skills::list_bundle_files(source_dir);
+    if files.is_empty() {
+        return Ok(skill);
+    }
+
+    let skill_dir = file_storage::skills::save_skill_to_disk(&skill)?;
+    file_storage::skills::copy_bundle(source_dir, &files, &skill_dir)?;
+    db.update_skill(
+        &skill.id,
+        UpdateSkillInput {
+            directory_path: Some(skill_dir.to_string_lossy().to_string()),
+            bundle_files: Some(files),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
 async fn get_local_rule_roots(db: Arc<Database>) -> Vec<PathBuf> {
     let roots_json = db
         .get_setting(LOCAL_RULE_PATHS_KEY)
@@ -1302,47 +1675,142 @@ fn candidate_from_text(
     target_paths: Option<Vec<String>>,
     artifact_type: ImportArtifactType,
 ) -> ImportCandidate {
-    let (name, parsed_content, parsed_scope, parsed_target_paths, parsed_adapters) =
-        if artifact_type == ImportArtifactType::Rule {
+    let mut allowed_tools = Vec::new();
+    let mut license = None;
+    let payload = match artifact_type {
+        ImportArtifactType::Rule => {
             extract_rule_payload(default_name, &content, scope, target_paths, source_tool)
-        } else {
-            (
-                default_name.to_string(),
-                content.clone(),
-                scope,
-                target_paths,
-                default_adapters(source_tool),
-            )
-        };
+        }
+        ImportArtifactType::Skill => {
+            let (payload, tools, skill_license) =
+                extract_skill_payload(default_name, &content, scope, target_paths, source_tool);
+            allowed_tools = tools;
+            license = skill_license;
+            payload
+        }
+        ImportArtifactType::SlashCommand => RulePayload {
+            name: default_name.to_string(),
+            content: content.clone(),
+            scope,
+            target_paths,
+            adapters: default_adapters(source_tool),
+            description: String::new(),
+            metadata: Metadata::new(),
+        },
+    };
 
-    let content_hash = compute_content_hash(&parsed_content);
+    let content_hash = compute_content_hash(&payload.content);
+    let uninstalled_adapters = tool_detection::uninstalled(&payload.adapters);
     ImportCandidate {
         id: uuid::Uuid::new_v4().to_string(),
         source_type,
         source_label: source_label.to_string(),
         source_path: source_path.to_string(),
         source_tool,
-        name: name.clone(),
-        proposed_name: name,
-        content: parsed_content.clone(),
-        scope: parsed_scope,
-        target_paths: parsed_target_paths,
-        enabled_adapters: parsed_adapters,
+        name: payload.name.clone(),
+        proposed_name: payload.name,
+        file_size: payload.content.len() as u64,
+        content: payload.content,
+        scope: payload.scope,
+        target_paths: payload.target_paths,
+        enabled_adapters: payload.adapters,
         content_hash,
-        file_size: parsed_content.len() as u64,
         artifact_type,
+        description: payload.description,
+        metadata: payload.metadata,
+        collection: None,
+        allowed_tools,
+        license,
+        uninstalled_adapters,
     }
 }
 
+/// Splits the spec frontmatter (`name`, `description`, `allowed-tools`, `license`) off an
+/// imported SKILL.md. Without parseable frontmatter the whole document is the
+/// instructions.
+fn extract_skill_payload(
+    fallback_name: &str,
+    content: &str,
+    scope: Scope,
+    target_paths: Option<Vec<String>>,
+    source_tool: Option<AdapterType>,
+) -> (RulePayload, Vec<String>, Option<String>) {
+    let mut payload = RulePayload {
+        name: fallback_name.to_string(),
+        content: content.to_string(),
+        scope,
+        target_paths,
+        adapters: default_adapters(source_tool),
+        description: String::new(),
+        metadata: Metadata::new(),
+    };
+    let Some((yaml, body)) = split_frontmatter(content.trim()) else {
+        return (payload, Vec::new(), None);
+    };
+    let Ok(frontmatter) = serde_yaml::from_str::<SkillFrontmatter>(yaml) else {
+        return (payload, Vec::new(), None);
+    };
+
+    if let Some(name) = frontmatter.name.filter(|n| !n.trim().is_empty()) {
+        payload.name = name.trim().to_string();
+    }
+    payload.description = frontmatter
+        .description
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    payload.content = body;
+    let allowed_tools = frontmatter
+        .allowed_tools
+        .map(parse_allowed_tools)
+        .unwrap_or_default();
+    let license = frontmatter
+        .license
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    (payload, allowed_tools, license)
+}
+
+/// Accepts `allowed-tools` as a list, a comma-separated string or, without commas, a
+/// space-separated string.
+fn parse_allowed_tools(value: serde_yaml::Value) -> Vec<String> {
+    let items: Vec<String> = match value {
+        serde_yaml::Value::String(s) if s.contains(',') => {
+            s.split(',').map(str::to_string).collect()
+        }
+        serde_yaml::Value::String(s) => s.split_whitespace().map(str::to_string).collect(),
+        serde_yaml::Value::Sequence(seq) => seq
+            .into_iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
 fn extract_rule_payload(
     fallback_name: &str,
     content: &str,
     fallback_scope: Scope,
     fallback_targets: Option<Vec<String>>,
     source_tool: Option<AdapterType>,
-) -> (String, String, Scope, Option<Vec<String>>, Vec<AdapterType>) {
+) -> RulePayload {
     let trimmed = content.trim().to_string();
 
+    if let Some(payload) = extract_frontmatter_payload(
+        fallback_name,
+        &trimmed,
+        fallback_scope,
+        fallback_targets.clone(),
+        source_tool,
+    ) {
+        return payload;
+    }
+
     let try_parse = |text: &str| -> Option<JsonRulePayload> {
         if let Ok(payload) = serde_json::from_str::<JsonRulePayload>(text) {
             return Some(payload);
@@ -1366,33 +1834,125 @@ fn extract_rule_payload(
             .scope
             .and_then(|s| Scope::from_str(&s).ok())
             .unwrap_or(fallback_scope);
-        let adapters = payload
-            .enabled_adapters
-            .unwrap_or_default()
-            .iter()
-            .filter_map(|a| AdapterType::from_str(a).ok())
-            .collect::<Vec<_>>();
-
-        return (
-            sanitize_rule_name(&name),
-            body,
+        return RulePayload {
+            name: sanitize_rule_name(&name),
+            content: body,
             scope,
-            fallback_targets, // Always use fallback targets (safe), never payload targets
-            if adapters.is_empty() {
-                default_adapters(source_tool)
-            } else {
-                adapters
-            },
-        );
+            target_paths: fallback_targets, // Always use fallback targets (safe), never payload targets
+            adapters: parse_payload_adapters(payload.enabled_adapters, source_tool),
+            description: String::new(),
+            metadata: Metadata::new(),
+        };
     }
 
-    (
-        sanitize_rule_name(fallback_name),
-        trimmed,
-        fallback_scope,
-        fallback_targets,
-        default_adapters(source_tool),
-    )
+    RulePayload {
+        name: sanitize_rule_name(fallback_name),
+        content: trimmed,
+        scope: fallback_scope,
+        target_paths: fallback_targets,
+        adapters: default_adapters(source_tool),
+        description: String::new(),
+        metadata: Metadata::new(),
+    }
+}
+
+fn parse_payload_adapters(
+    adapters: Option<Vec<String>>,
+    source_tool: Option<AdapterType>,
+) -> Vec<AdapterType> {
+    let adapters = adapters
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|a| AdapterType::from_str(a).ok())
+        .collect::<Vec<_>>();
+    if adapters.is_empty() {
+        default_adapters(source_tool)
+    } else {
+        adapters
+    }
+}
+
+/// Splits leading `---` YAML frontmatter from a Markdown document, returning the raw YAML
+/// and the trimmed body.
+fn split_frontmatter(content: &str) -> Option<(&str, String)> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"^---[ \t]*\r?\n([\s\S]*?)\r?\n---[ \t]*(?:\r?\n([\s\S]*))?$")
+            .expect("Invalid frontmatter regex")
+    });
+    let caps = re.captures(content)?;
+    let yaml = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+    let body = caps
+        .get(2)
+        .map(|m| m.as_str())
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    Some((yaml, body))
+}
+
+/// Parses a Markdown document with leading `---` YAML frontmatter, returning the rule with
+/// the frontmatter stripped from its body. Returns `None` when there is no parseable
+/// frontmatter.
+fn extract_frontmatter_payload(
+    fallback_name: &str,
+    content: &str,
+    fallback_scope: Scope,
+    fallback_targets: Option<Vec<String>>,
+    source_tool: Option<AdapterType>,
+) -> Option<RulePayload> {
+    let (yaml, body) = split_frontmatter(content)?;
+    let frontmatter: MarkdownFrontmatter = serde_yaml::from_str(yaml).ok()?;
+
+    let mut metadata = frontmatter.metadata;
+    let globs = frontmatter.globs.map(parse_globs).unwrap_or_default();
+    if !globs.is_empty() {
+        metadata.insert("globs", globs);
+    }
+    if let Some(always_apply) = frontmatter.always_apply {
+        metadata.insert("alwaysApply", always_apply);
+    }
+
+    let scope = frontmatter
+        .scope
+        .and_then(|s| Scope::from_str(&s).ok())
+        .or_else(|| (frontmatter.always_apply == Some(true)).then_some(Scope::Global))
+        .unwrap_or(fallback_scope);
+    let name = frontmatter
+        .name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| fallback_name.to_string());
+
+    Some(RulePayload {
+        name: sanitize_rule_name(&name),
+        content: body,
+        scope,
+        target_paths: fallback_targets,
+        adapters: parse_payload_adapters(frontmatter.enabled_adapters, source_tool),
+        description: frontmatter
+            .description
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        metadata,
+    })
+}
+
+/// Accepts `globs` as a list or as a comma-separated string.
+fn parse_globs(value: serde_yaml::Value) -> Vec<String> {
+    let items: Vec<String> = match value {
+        serde_yaml::Value::String(s) => s.split(',').map(str::to_string).collect(),
+        serde_yaml::Value::Sequence(seq) => seq
+            .into_iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty())
+        .collect()
 }
 
 fn infer_name(path: &Path, fallback: &str, source_tool: Option<AdapterType>) -> String {
@@ -1519,6 +2079,18 @@ fn is_disallowed_ip(ip: &IpAddr) -> bool {
     }
 }
 
+/// Replaces a throwaway extraction or checkout location with `origin` so the same file maps
+/// to the same artifact on every import.
+fn stable_source_path(origin: &str, root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{}#{}", origin, relative)
+}
+
 fn compute_content_hash(content: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
@@ -1599,6 +2171,7 @@ mod tests {
             target_paths: None,
             enabled_adapters: vec![AdapterType::Gemini],
             enabled: true,
+            metadata: Default::default(),
         })
         .await
         .expect("seed rule");
@@ -1642,6 +2215,7 @@ mod tests {
             target_paths: None,
             enabled_adapters: vec![AdapterType::Gemini],
             enabled: true,
+            metadata: Default::default(),
         })
         .await
         .expect("seed rule");
@@ -1691,6 +2265,7 @@ mod tests {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini],
                 enabled: true,
+                metadata: Default::default(),
             })
             .await
             .expect("seed rule");
@@ -1728,6 +2303,71 @@ mod tests {
         assert_eq!(result.imported_rules[0].content, "updated");
     }
 
+    #[tokio::test]
+    async fn execute_import_flags_near_duplicates_as_conflicts() {
+        let db = Arc::new(Database::new_in_memory().await.expect("in-memory db"));
+        let body = "Always write unit tests for new functions.\n\
+            Prefer small, focused modules over large files.\n\
+            Document every public API with examples.";
+        let existing = db
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "quality".to_string(),
+                description: "".to_string(),
+                content: body.to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .expect("seed rule");
+
+        let scan = || ImportScanResult {
+            candidates: vec![candidate_from_text(
+                format!("# Quality\n\n{}\n", body),
+                "quality",
+                crate::models::ImportSourceType::File,
+                "File",
+                "C:/tmp/quality.md",
+                None,
+                Scope::Global,
+                None,
+                ImportArtifactType::Rule,
+            )],
+            errors: vec![],
+        };
+        let rename = ImportExecutionOptions {
+            conflict_mode: ImportConflictMode::Rename,
+            ..Default::default()
+        };
+
+        let result = execute_import(db.clone(), scan(), rename.clone())
+            .await
+            .expect("execute import");
+        assert!(result.imported_rules.is_empty());
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(
+            result.conflicts[0].existing_id.as_deref(),
+            Some(existing.id.as_str())
+        );
+        assert!(result.conflicts[0].similarity.is_some_and(|s| s >= 80));
+
+        let result = execute_import(
+            db.clone(),
+            scan(),
+            ImportExecutionOptions {
+                allow_near_duplicates: true,
+                ..rename
+            },
+        )
+        .await
+        .expect("execute import");
+        assert_eq!(result.imported_rules.len(), 1);
+        assert_eq!(result.imported_rules[0].name, "quality-2");
+    }
+
     #[test]
     fn extract_payload_reads_json_rule_fields() {
         let json = r#"{
@@ -1738,15 +2378,14 @@ mod tests {
           "enabledAdapters": ["gemini", "opencode"]
         }"#;
 
-        let (name, content, scope, target_paths, adapters) =
-            extract_rule_payload("fallback", json, Scope::Global, None, None);
+        let payload = extract_rule_payload("fallback", json, Scope::Global, None, None);
 
-        assert_eq!(name, "json-rule");
-        assert_eq!(content, "json body");
-        assert_eq!(scope, Scope::Local);
+        assert_eq!(payload.name, "json-rule");
+        assert_eq!(payload.content, "json body");
+        assert_eq!(payload.scope, Scope::Local);
         // Security fix: verify target_paths from JSON are IGNORED
-        assert_eq!(target_paths, None);
-        assert_eq!(adapters.len(), 2);
+        assert_eq!(payload.target_paths, None);
+        assert_eq!(payload.adapters.len(), 2);
     }
 
     #[test]
@@ -1757,10 +2396,9 @@ mod tests {
           "targetPaths": ["../../../../Windows/System32"]
         }"#;
 
-        let (_, _, _, target_paths, _) =
-            extract_rule_payload("fallback", json, Scope::Global, None, None);
+        let payload = extract_rule_payload("fallback", json, Scope::Global, None, None);
 
-        assert_eq!(target_paths, None);
+        assert_eq!(payload.target_paths, None);
     }
 
     #[test]
@@ -1768,10 +2406,9 @@ mod tests {
         let json = r#"{ "name": "ok", "content": "ok" }"#;
         let fallback = Some(vec!["C:/safe/path".to_string()]);
 
-        let (_, _, _, target_paths, _) =
-            extract_rule_payload("fallback", json, Scope::Global, fallback.clone(), None);
+        let payload = extract_rule_payload("fallback", json, Scope::Global, fallback.clone(), None);
 
-        assert_eq!(target_paths, fallback);
+        assert_eq!(payload.target_paths, fallback);
     }
 
     #[test]
@@ -1784,7 +2421,7 @@ enabledAdapters:
   - cline
 "#;
 
-        let (name, content, scope, _target_paths, adapters) = extract_rule_payload(
+        let payload = extract_rule_payload(
             "fallback",
             yaml,
             Scope::Local,
@@ -1792,10 +2429,80 @@ enabledAdapters:
             None,
         );
 
-        assert_eq!(name, "yaml-rule");
-        assert_eq!(content, "yaml body");
-        assert_eq!(scope, Scope::Global);
-        assert_eq!(adapters, vec![AdapterType::Cline]);
+        assert_eq!(payload.name, "yaml-rule");
+        assert_eq!(payload.content, "yaml body");
+        assert_eq!(payload.scope, Scope::Global);
+        assert_eq!(payload.adapters, vec![AdapterType::Cline]);
+    }
+
+    #[test]
+    fn skill_import_reads_spec_frontmatter() {
+        let markdown = "---\nname: pdf-tools\ndescription: Fill PDF forms\nallowed-tools: Read, Bash(python:*)\nlicense: Apache-2.0\n---\n\nRun scripts/fill.py.\n";
+        let candidate = candidate_from_text(
+            markdown.to_string(),
+            "SKILL",
+            crate::models::ImportSourceType::File,
+            "File",
+            "/tmp/pdf/SKILL.md",
+            None,
+            Scope::Global,
+            None,
+            ImportArtifactType::Skill,
+        );
+
+        assert_eq!(candidate.name, "pdf-tools");
+        assert_eq!(candidate.description, "Fill PDF forms");
+        assert_eq!(candidate.content, "Run scripts/fill.py.");
+        assert_eq!(candidate.allowed_tools, vec!["Read", "Bash(python:*)"]);
+        assert_eq!(candidate.license.as_deref(), Some("Apache-2.0"));
+
+        assert_eq!(
+            parse_allowed_tools(serde_yaml::Value::String("Read Grep".to_string())),
+            vec!["Read", "Grep"]
+        );
+    }
+
+    #[test]
+    fn extract_payload_strips_markdown_frontmatter() {
+        let markdown = r#"---
+description: React component conventions
+globs: src/**/*.tsx, src/**/*.jsx
+alwaysApply: true
+adapters: [cursor, cline]
+targetPaths: ["../../etc"]
+---
+
+# React
+
+Prefer function components.
+"#;
+
+        let payload = extract_rule_payload("react-rules", markdown, Scope::Local, None, None);
+
+        assert_eq!(payload.name, "react-rules");
+        assert_eq!(payload.content, "# React\n\nPrefer function components.");
+        assert_eq!(payload.description, "React component conventions");
+        assert_eq!(payload.scope, Scope::Global);
+        assert_eq!(payload.target_paths, None);
+        assert_eq!(
+            payload.adapters,
+            vec![AdapterType::Cursor, AdapterType::Cline]
+        );
+        assert_eq!(
+            payload.metadata.get("globs"),
+            Some(&serde_json::json!(["src/**/*.tsx", "src/**/*.jsx"]))
+        );
+        assert_eq!(payload.metadata.get_bool("alwaysApply"), Some(true));
+    }
+
+    #[test]
+    fn extract_payload_keeps_body_when_frontmatter_is_not_yaml() {
+        let markdown = "---\n: : not yaml [\n---\nBody";
+
+        let payload = extract_rule_payload("fallback", markdown, Scope::Global, None, None);
+
+        assert_eq!(payload.content, markdown);
+        assert!(payload.description.is_empty());
     }
 
     #[tokio::test]
@@ -2082,6 +2789,25 @@ enabledAdapters:
         assert!(result.errors.is_empty());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn import_scan_skips_symlinked_files() {
+        let outside = tempfile::TempDir::new().unwrap();
+        let private = outside.path().join("id_rsa");
+        fs::write(&private, "PRIVATE KEY").unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::os::unix::fs::symlink(&private, temp_dir.path().join("CLAUDE.md")).unwrap();
+        fs::write(temp_dir.path().join("GEMINI.md"), "# Real rule").unwrap();
+
+        let result = scan_directory_to_candidates(temp_dir.path(), 1024 * 1024, None);
+
+        assert_eq!(result.candidates.len(), 1);
+        assert!(result
+            .candidates
+            .iter()
+            .all(|c| !c.content.contains("PRIVATE KEY")));
+    }
+
     #[test]
     fn import_rule_happy_path_local() {
         let temp_dir = tempfile::TempDir::new().unwrap();
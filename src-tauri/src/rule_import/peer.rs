@@ -0,0 +1,390 @@
+//! Machine-to-machine transfer over the local network.
+//!
+//! The sending instance starts a short-lived HTTP endpoint serving its configuration
+//! export, guarded by a one-time pairing code. The receiving instance pulls the export and
+//! turns it into ordinary import candidates, so conflicts are handled by the normal import
+//! pipeline. A share closes after its first successful pull, when stopped, when it expires,
+//! or after too many wrong codes.
+
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use parking_lot::Mutex;
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::sync::broadcast;
+
+use super::clipboard::{export_candidates, parse_export};
+use crate::constants::limits::{PEER_SHARE_CODE_LENGTH, PEER_SHARE_MAX_FAILED_ATTEMPTS};
+use crate::constants::timing::{PEER_FETCH_TIMEOUT, PEER_SHARE_TTL};
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{ImportScanResult, ImportSourceType, PeerShareInfo};
+use crate::util::constant_time_eq;
+
+pub const PAIRING_CODE_HEADER: &str = "X-RuleWeaver-Pairing-Code";
+const EXPORT_PATH: &str = "/export";
+const SOURCE_LABEL: &str = "RuleWeaver peer";
+/// Unambiguous characters for codes that are read aloud or typed on another machine.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+struct ActiveShare {
+    info: PeerShareInfo,
+    stop_tx: broadcast::Sender<()>,
+}
+
+struct ShareState {
+    db: Arc<Database>,
+    code: String,
+    failed_attempts: AtomicUsize,
+    /// Set once the code has been accepted; the code is good for one pull.
+    claimed: AtomicBool,
+    stop_tx: broadcast::Sender<()>,
+}
+
+/// Owns the single outgoing share this instance may have open.
+#[derive(Clone, Default)]
+pub struct PeerShareManager {
+    active: Arc<Mutex<Option<ActiveShare>>>,
+}
+
+impl PeerShareManager {
+    pub fn current(&self) -> Option<PeerShareInfo> {
+        self.active.lock().as_ref().map(|share| share.info.clone())
+    }
+
+    /// Opens a new share, replacing any share already open.
+    pub async fn start(&self, db: Arc<Database>) -> Result<PeerShareInfo> {
+        self.stop();
+
+        let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await?;
+        let port = listener.local_addr()?.port();
+        let code = generate_code()?;
+        let (stop_tx, _) = broadcast::channel(1);
+        let info = PeerShareInfo {
+            addresses: lan_addresses()
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, port).to_string())
+                .collect(),
+            port,
+            code: code.clone(),
+            expires_at: Utc::now() + chrono::Duration::from_std(PEER_SHARE_TTL).unwrap_or_default(),
+        };
+
+        let state = Arc::new(ShareState {
+            db,
+            code: code.clone(),
+            failed_attempts: AtomicUsize::new(0),
+            claimed: AtomicBool::new(false),
+            stop_tx: stop_tx.clone(),
+        });
+        let app = Router::new()
+            .route(EXPORT_PATH, get(export_handler))
+            .with_state(state);
+
+        let mut stop_rx = stop_tx.subscribe();
+        let active = Arc::clone(&self.active);
+        tokio::spawn(async move {
+            let shutdown = async move {
+                tokio::select! {
+                    _ = stop_rx.recv() => {}
+                    _ = tokio::time::sleep(PEER_SHARE_TTL) => {}
+                }
+            };
+            if let Err(e) = axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await
+            {
+                log::warn!("Peer share server error: {}", e);
+            }
+            // Clear the share unless a newer one has replaced it.
+            let mut active = active.lock();
+            if active.as_ref().is_some_and(|share| share.info.code == code) {
+                *active = None;
+            }
+        });
+
+        *self.active.lock() = Some(ActiveShare {
+            info: info.clone(),
+            stop_tx,
+        });
+        Ok(info)
+    }
+
+    pub fn stop(&self) {
+        if let Some(share) = self.active.lock().take() {
+            let _ = share.stop_tx.send(());
+        }
+    }
+}
+
+async fn export_handler(State(state): State<Arc<ShareState>>, headers: HeaderMap) -> Response {
+    let provided = headers
+        .get(PAIRING_CODE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_uppercase())
+        .unwrap_or_default();
+    if !constant_time_eq(provided.as_bytes(), state.code.as_bytes()) {
+        let failures = state.failed_attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures >= PEER_SHARE_MAX_FAILED_ATTEMPTS {
+            log::warn!(
+                "Closing peer share after {} invalid pairing codes",
+                failures
+            );
+            let _ = state.stop_tx.send(());
+        }
+        return (StatusCode::UNAUTHORIZED, "Invalid pairing code").into_response();
+    }
+    if state.claimed.swap(true, Ordering::SeqCst) {
+        return (StatusCode::GONE, "Pairing code already used").into_response();
+    }
+
+    match state.db.export_configuration().await {
+        Ok(mut export) => {
+            // Only artifacts are imported on the other side; settings stay on this machine.
+            export.settings.clear();
+            // Graceful shutdown still delivers this response before the share closes.
+            let _ = state.stop_tx.send(());
+            Json(export).into_response()
+        }
+        Err(e) => {
+            state.claimed.store(false, Ordering::SeqCst);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+fn generate_code() -> Result<String> {
+    let mut bytes = [0u8; PEER_SHARE_CODE_LENGTH];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Internal {
+            message: "Failed to generate pairing code".to_string(),
+        })?;
+    Ok(bytes
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect())
+}
+
+/// The address of the interface that carries outbound traffic. Connecting a UDP socket
+/// sends no packets; it only asks the OS to pick a route.
+pub(super) fn lan_addresses() -> Vec<IpAddr> {
+    let probe = || -> std::io::Result<IpAddr> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect("192.0.2.1:9")?;
+        Ok(socket.local_addr()?.ip())
+    };
+    match probe() {
+        Ok(ip) if !ip.is_unspecified() => vec![ip],
+        _ => Vec::new(),
+    }
+}
+
+/// Only private, loopback and link-local addresses may be pulled from, so a share can
+/// never be fetched across the internet.
+fn is_local_network_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_private() || v4.is_loopback() || v4.is_link_local(),
+        IpAddr::V6(v6) => v6.is_loopback() || v6.is_unique_local() || v6.is_unicast_link_local(),
+    }
+}
+
+fn peer_export_url(address: &str) -> Result<url::Url> {
+    let trimmed = address.trim().trim_end_matches('/');
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("http://{}", trimmed)
+    };
+    let invalid = |reason: &str| AppError::InvalidInput {
+        message: format!("Invalid peer address '{}': {}", address.trim(), reason),
+    };
+    let mut url = url::Url::parse(&with_scheme).map_err(|e| invalid(&e.to_string()))?;
+    if url.scheme() != "http" {
+        return Err(invalid("only http is supported"));
+    }
+    let ip = match url.host() {
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        _ => return Err(invalid("use the IP address shown on the sharing machine")),
+    };
+    if !is_local_network_ip(&ip) {
+        return Err(invalid("address is not on the local network"));
+    }
+    if url.port().is_none() {
+        return Err(invalid("missing port"));
+    }
+    url.set_path(EXPORT_PATH);
+    Ok(url)
+}
+
+/// Pulls another instance's export and turns it into import candidates.
+pub async fn scan_peer_to_candidates(
+    address: &str,
+    code: &str,
+    max_size: u64,
+) -> Result<ImportScanResult> {
+    let url = peer_export_url(address)?;
+    let client = reqwest::Client::builder()
+        .timeout(PEER_FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal {
+            message: format!("Failed to create HTTP client: {}", e),
+        })?;
+    let response = client
+        .get(url.clone())
+        .header(PAIRING_CODE_HEADER, code.trim())
+        .send()
+        .await
+        .map_err(|e| AppError::InvalidInput {
+            message: format!("Could not reach peer at {}: {}", address.trim(), e),
+        })?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(AppError::InvalidInput {
+            message: "The sharing machine rejected the pairing code".to_string(),
+        });
+    }
+    if response.status() == reqwest::StatusCode::GONE {
+        return Err(AppError::InvalidInput {
+            message: "This share was already pulled; start a new share on the sharing machine"
+                .to_string(),
+        });
+    }
+    if !response.status().is_success() {
+        return Err(AppError::InvalidInput {
+            message: format!("Peer returned non-success status: {}", response.status()),
+        });
+    }
+    if response.content_length().is_some_and(|len| len > max_size) {
+        return Err(AppError::InvalidInput {
+            message: format!("Peer export exceeds max size ({} bytes)", max_size),
+        });
+    }
+    let body = response.text().await.map_err(|e| AppError::InvalidInput {
+        message: format!("Failed to read peer export: {}", e),
+    })?;
+    if body.len() as u64 > max_size {
+        return Err(AppError::InvalidInput {
+            message: format!("Peer export exceeds max size ({} bytes)", max_size),
+        });
+    }
+
+    let export = parse_export(body.trim()).ok_or_else(|| AppError::InvalidInput {
+        message: "Peer did not return a RuleWeaver export".to_string(),
+    })?;
+    Ok(ImportScanResult {
+        candidates: export_candidates(export, ImportSourceType::Peer, SOURCE_LABEL),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, ImportArtifactType, Scope};
+
+    const MAX_SIZE: u64 = 1024 * 1024;
+
+    #[test]
+    fn peer_addresses_must_be_on_the_local_network() {
+        let url = peer_export_url("192.168.1.20:41234").unwrap();
+        assert_eq!(url.as_str(), "http://192.168.1.20:41234/export");
+        assert!(peer_export_url("http://[::1]:5000/").is_ok());
+
+        assert!(peer_export_url("8.8.8.8:5000").is_err());
+        assert!(peer_export_url("example.com:5000").is_err());
+        assert!(peer_export_url("https://10.0.0.2:5000").is_err());
+        assert!(peer_export_url("10.0.0.2").is_err());
+    }
+
+    #[test]
+    fn codes_use_the_unambiguous_alphabet() {
+        let code = generate_code().unwrap();
+        assert_eq!(code.len(), PEER_SHARE_CODE_LENGTH);
+        assert!(code.bytes().all(|b| CODE_ALPHABET.contains(&b)));
+    }
+
+    #[tokio::test]
+    async fn receiver_pulls_shared_configuration() {
+        let sender = Arc::new(Database::new_in_memory().await.unwrap());
+        sender
+            .create_rule(CreateRuleInput {
+                id: None,
+                name: "shared-style".to_string(),
+                description: "House style".to_string(),
+                content: "Use rustfmt.".to_string(),
+                scope: Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![AdapterType::Cursor],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+
+        let manager = PeerShareManager::default();
+        let info = manager.start(sender).await.unwrap();
+        assert_eq!(manager.current(), Some(info.clone()));
+        let address = format!("127.0.0.1:{}", info.port);
+
+        let rejected = scan_peer_to_candidates(&address, "WRONGCODE", MAX_SIZE).await;
+        assert!(rejected.is_err());
+
+        let scan = scan_peer_to_candidates(&address, &info.code.to_lowercase(), MAX_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(scan.candidates.len(), 1);
+        let rule = &scan.candidates[0];
+        assert_eq!(rule.artifact_type, ImportArtifactType::Rule);
+        assert_eq!(rule.source_type, ImportSourceType::Peer);
+        assert_eq!(rule.name, "shared-style");
+        assert_eq!(rule.description, "House style");
+        assert_eq!(rule.enabled_adapters, vec![AdapterType::Cursor]);
+
+        // The code is good for one pull.
+        for _ in 0..50 {
+            if manager.current().is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(manager.current(), None);
+        assert!(scan_peer_to_candidates(&address, &info.code, MAX_SIZE)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn share_closes_after_repeated_bad_codes() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let manager = PeerShareManager::default();
+        let info = manager.start(db).await.unwrap();
+        let address = format!("127.0.0.1:{}", info.port);
+
+        for _ in 0..PEER_SHARE_MAX_FAILED_ATTEMPTS {
+            assert!(scan_peer_to_candidates(&address, "BADCODE1", MAX_SIZE)
+                .await
+                .is_err());
+        }
+        for _ in 0..50 {
+            if manager.current().is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(manager.current(), None);
+        assert!(scan_peer_to_candidates(&address, &info.code, MAX_SIZE)
+            .await
+            .is_err());
+    }
+}
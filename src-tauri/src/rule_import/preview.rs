@@ -0,0 +1,369 @@
+//! Dry run of `execute_import`: reports what each candidate would do, with a line diff
+//! against the artifact it would update or replace.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::similarity::{closest_near_duplicate, FingerprintCache};
+use super::{compute_content_hash, make_unique_name, read_source_map, source_identity};
+use crate::database::Database;
+use crate::error::Result;
+use crate::models::{
+    ImportArtifactType, ImportCandidate, ImportCandidatePreview, ImportConflictMode,
+    ImportExecutionOptions, ImportPreviewAction, ImportScanResult,
+};
+use crate::text_diff::{line_diff, summarize};
+
+/// Existing artifact of the candidate's type, reduced to what the preview compares.
+struct Existing {
+    id: String,
+    name: String,
+    content: String,
+}
+
+fn preview(
+    candidate: &ImportCandidate,
+    action: ImportPreviewAction,
+    name: String,
+    existing: Option<&Existing>,
+    reason: Option<String>,
+) -> ImportCandidatePreview {
+    let diff = match action {
+        ImportPreviewAction::Skip | ImportPreviewAction::Conflict => Vec::new(),
+        _ => line_diff(
+            existing.map(|e| e.content.as_str()).unwrap_or(""),
+            &candidate.content,
+        ),
+    };
+    ImportCandidatePreview {
+        candidate_id: candidate.id.clone(),
+        artifact_type: candidate.artifact_type,
+        action,
+        name,
+        existing_id: existing.map(|e| e.id.clone()),
+        existing_name: existing.map(|e| e.name.clone()),
+        reason,
+        similarity: None,
+        summary: summarize(&diff),
+        diff,
+    }
+}
+
+/// Predicts the outcome of `execute_import` for every selected candidate without writing
+/// anything. Follows the same order of checks: empty content, identical content, source
+/// mapping, near-duplicates, then name collisions under the chosen conflict mode.
+pub async fn preview_import(
+    db: Arc<Database>,
+    scan_result: &ImportScanResult,
+    options: &ImportExecutionOptions,
+) -> Result<Vec<ImportCandidatePreview>> {
+    let rules: Vec<Existing> = db
+        .get_all_rules()
+        .await?
+        .into_iter()
+        .map(|r| Existing {
+            id: r.id,
+            name: r.name,
+            content: r.content,
+        })
+        .collect();
+    let commands: Vec<Existing> = db
+        .get_all_commands()
+        .await?
+        .into_iter()
+        .map(|c| Existing {
+            id: c.id,
+            name: c.name,
+            content: c.script,
+        })
+        .collect();
+    let skills: Vec<Existing> = db
+        .get_all_skills()
+        .await?
+        .into_iter()
+        .map(|s| Existing {
+            id: s.id,
+            name: s.name,
+            content: s.instructions,
+        })
+        .collect();
+    let source_map = read_source_map(db).await;
+    let selected: Option<HashSet<&String>> = options
+        .selected_candidate_ids
+        .as_ref()
+        .map(|ids| ids.iter().collect());
+
+    // Names created earlier in this import also count as taken.
+    let mut planned_names: Vec<(ImportArtifactType, String)> = Vec::new();
+    let mut fingerprints = FingerprintCache::default();
+    let mut previews = Vec::new();
+
+    for candidate in &scan_result.candidates {
+        if selected
+            .as_ref()
+            .is_some_and(|ids| !ids.contains(&candidate.id))
+        {
+            continue;
+        }
+        let existing = match candidate.artifact_type {
+            ImportArtifactType::Rule => &rules,
+            ImportArtifactType::SlashCommand => &commands,
+            ImportArtifactType::Skill => &skills,
+        };
+        let name = candidate.proposed_name.clone();
+
+        if candidate.content.trim().is_empty() {
+            previews.push(preview(
+                candidate,
+                ImportPreviewAction::Skip,
+                name,
+                None,
+                Some("Content is empty".to_string()),
+            ));
+            continue;
+        }
+
+        if let Some(duplicate) = existing
+            .iter()
+            .find(|e| compute_content_hash(&e.content) == candidate.content_hash)
+        {
+            let reason = format!("Duplicate content already exists as '{}'", duplicate.name);
+            previews.push(preview(
+                candidate,
+                ImportPreviewAction::Skip,
+                name,
+                Some(duplicate),
+                Some(reason),
+            ));
+            continue;
+        }
+
+        let mapped = source_map
+            .get(&source_identity(candidate))
+            .and_then(|id| existing.iter().find(|e| &e.id == id));
+        if let Some(mapped) = mapped {
+            previews.push(preview(
+                candidate,
+                ImportPreviewAction::Update,
+                name,
+                Some(mapped),
+                None,
+            ));
+            continue;
+        }
+
+        if !options.allow_near_duplicates {
+            let near = closest_near_duplicate(
+                &mut fingerprints,
+                &candidate.content,
+                existing
+                    .iter()
+                    .map(|e| (e.id.as_str(), e.name.as_str(), e.content.as_str())),
+            )
+            .filter(|near| {
+                options.conflict_mode != ImportConflictMode::Replace
+                    || !near.name.eq_ignore_ascii_case(&candidate.proposed_name)
+            });
+            if let Some(near) = near {
+                let near_existing = existing.iter().find(|e| e.id == near.id);
+                let mut conflict = preview(
+                    candidate,
+                    ImportPreviewAction::Conflict,
+                    name,
+                    near_existing,
+                    Some(format!(
+                        "Near-duplicate of '{}' ({}% similar)",
+                        near.name, near.percent
+                    )),
+                );
+                // Show what differs so the user can judge whether it is a real copy.
+                conflict.diff = line_diff(
+                    near_existing.map(|e| e.content.as_str()).unwrap_or(""),
+                    &candidate.content,
+                );
+                conflict.summary = summarize(&conflict.diff);
+                conflict.similarity = Some(near.percent);
+                previews.push(conflict);
+                continue;
+            }
+        }
+
+        let same_name = existing
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(&candidate.proposed_name));
+        let Some(same_name) = same_name else {
+            planned_names.push((candidate.artifact_type, name.clone()));
+            previews.push(preview(
+                candidate,
+                ImportPreviewAction::Create,
+                name,
+                None,
+                None,
+            ));
+            continue;
+        };
+
+        if same_name.content == candidate.content {
+            let reason = format!(
+                "Duplicate name and content already exists as '{}'",
+                same_name.name
+            );
+            previews.push(preview(
+                candidate,
+                ImportPreviewAction::Skip,
+                name,
+                Some(same_name),
+                Some(reason),
+            ));
+            continue;
+        }
+
+        match options.conflict_mode {
+            ImportConflictMode::Skip => previews.push(preview(
+                candidate,
+                ImportPreviewAction::Conflict,
+                name,
+                Some(same_name),
+                Some("Name collision with different content".to_string()),
+            )),
+            ImportConflictMode::Replace => previews.push(preview(
+                candidate,
+                ImportPreviewAction::Replace,
+                name,
+                Some(same_name),
+                None,
+            )),
+            ImportConflictMode::Rename => {
+                let taken: Vec<String> = existing
+                    .iter()
+                    .map(|e| e.name.clone())
+                    .chain(
+                        planned_names
+                            .iter()
+                            .filter(|(t, _)| *t == candidate.artifact_type)
+                            .map(|(_, n)| n.clone()),
+                    )
+                    .collect();
+                let unique = make_unique_name(&candidate.proposed_name, &taken);
+                planned_names.push((candidate.artifact_type, unique.clone()));
+                previews.push(preview(
+                    candidate,
+                    ImportPreviewAction::Rename,
+                    unique,
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    Ok(previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, DiffLineKind, ImportSourceType, Scope};
+    use crate::rule_import::{candidate_from_text, execute_import};
+
+    fn rule_candidate(name: &str, content: &str, source_path: &str) -> ImportCandidate {
+        candidate_from_text(
+            content.to_string(),
+            name,
+            ImportSourceType::File,
+            "File",
+            source_path,
+            None,
+            Scope::Global,
+            None,
+            ImportArtifactType::Rule,
+        )
+    }
+
+    #[tokio::test]
+    async fn preview_reports_actions_and_diffs() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "style".to_string(),
+            description: String::new(),
+            content: "use tabs\nwrap at 100".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+
+        let scan = ImportScanResult {
+            candidates: vec![
+                rule_candidate("style", "use spaces\nwrap at 100", "/tmp/style.md"),
+                rule_candidate("fresh", "brand new", "/tmp/fresh.md"),
+                rule_candidate("copy", "use tabs\nwrap at 100", "/tmp/copy.md"),
+            ],
+            errors: Vec::new(),
+        };
+
+        let skip_mode = preview_import(db.clone(), &scan, &ImportExecutionOptions::default())
+            .await
+            .unwrap();
+        let actions: Vec<_> = skip_mode.iter().map(|p| p.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ImportPreviewAction::Conflict,
+                ImportPreviewAction::Create,
+                ImportPreviewAction::Skip,
+            ]
+        );
+        assert!(skip_mode[1]
+            .diff
+            .iter()
+            .all(|l| l.kind == DiffLineKind::Added));
+
+        let replace = ImportExecutionOptions {
+            conflict_mode: ImportConflictMode::Replace,
+            ..Default::default()
+        };
+        let previews = preview_import(db.clone(), &scan, &replace).await.unwrap();
+        assert_eq!(previews[0].action, ImportPreviewAction::Replace);
+        assert_eq!(previews[0].summary.changed, 1);
+        assert_eq!(
+            previews[0].diff[0].kind,
+            DiffLineKind::Removed,
+            "old line is shown before its replacement"
+        );
+
+        let rename = ImportExecutionOptions {
+            conflict_mode: ImportConflictMode::Rename,
+            ..Default::default()
+        };
+        let previews = preview_import(db.clone(), &scan, &rename).await.unwrap();
+        assert_eq!(previews[0].action, ImportPreviewAction::Rename);
+        assert_eq!(previews[0].name, "style-2");
+    }
+
+    #[tokio::test]
+    async fn preview_reports_update_for_mapped_source() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let first = ImportScanResult {
+            candidates: vec![rule_candidate("quality", "v1", "/tmp/quality.md")],
+            errors: Vec::new(),
+        };
+        execute_import(db.clone(), first, ImportExecutionOptions::default())
+            .await
+            .unwrap();
+
+        let second = ImportScanResult {
+            candidates: vec![rule_candidate("quality", "v2", "/tmp/quality.md")],
+            errors: Vec::new(),
+        };
+        let previews = preview_import(db, &second, &ImportExecutionOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(previews[0].action, ImportPreviewAction::Update);
+        assert_eq!(previews[0].summary.changed, 1);
+    }
+}
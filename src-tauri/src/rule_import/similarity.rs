@@ -0,0 +1,147 @@
+//! Near-duplicate detection for imports.
+//!
+//! Exact hashing misses copies that differ only in whitespace, letter case or an added
+//! header line. Content is normalized to lowercase words, cut into overlapping word
+//! shingles, and compared by Jaccard similarity of the shingle sets.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use super::compute_content_hash;
+
+/// Minimum similarity at which an import candidate is reported as a near-duplicate.
+pub(super) const NEAR_DUPLICATE_THRESHOLD: f64 = 0.8;
+const SHINGLE_SIZE: usize = 3;
+
+pub(super) struct Fingerprint(HashSet<u64>);
+
+impl Fingerprint {
+    pub(super) fn new(content: &str) -> Self {
+        let words: Vec<String> = content
+            .split_whitespace()
+            .map(|w| {
+                w.trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let hash = |window: &[String]| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        };
+        if words.len() < SHINGLE_SIZE {
+            return Self(std::iter::once(hash(&words)).collect());
+        }
+        Self(words.windows(SHINGLE_SIZE).map(hash).collect())
+    }
+
+    pub(super) fn similarity(&self, other: &Fingerprint) -> f64 {
+        let union = self.0.union(&other.0).count();
+        if union == 0 {
+            return 1.0;
+        }
+        self.0.intersection(&other.0).count() as f64 / union as f64
+    }
+}
+
+/// Fingerprints keyed by content hash, so unchanged artifacts are only shingled once per
+/// import however many candidates they are compared against.
+#[derive(Default)]
+pub(super) struct FingerprintCache(HashMap<String, Fingerprint>);
+
+impl FingerprintCache {
+    fn get(&mut self, content: &str) -> &Fingerprint {
+        self.0
+            .entry(compute_content_hash(content))
+            .or_insert_with(|| Fingerprint::new(content))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct NearDuplicate {
+    pub id: String,
+    pub name: String,
+    /// Similarity as a whole percentage
+    pub percent: u8,
+}
+
+/// Returns the existing artifact most similar to `content`, if any reaches
+/// [`NEAR_DUPLICATE_THRESHOLD`]. `existing` yields `(id, name, content)` triples.
+pub(super) fn closest_near_duplicate<'a>(
+    cache: &mut FingerprintCache,
+    content: &str,
+    existing: impl Iterator<Item = (&'a str, &'a str, &'a str)>,
+) -> Option<NearDuplicate> {
+    let candidate = Fingerprint::new(content);
+    let mut best: Option<(f64, &str, &str)> = None;
+    for (id, name, existing_content) in existing {
+        let score = candidate.similarity(cache.get(existing_content));
+        if score >= NEAR_DUPLICATE_THRESHOLD && best.is_none_or(|(b, _, _)| score > b) {
+            best = Some((score, id, name));
+        }
+    }
+    best.map(|(score, id, name)| NearDuplicate {
+        id: id.to_string(),
+        name: name.to_string(),
+        percent: (score * 100.0).round() as u8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULE: &str = "Always write unit tests for new functions.\n\
+        Prefer small, focused modules over large files.\n\
+        Document every public API with examples.\n\
+        Run the linter before committing any change.";
+
+    #[test]
+    fn whitespace_and_case_changes_are_identical() {
+        let reflowed = RULE.replace('\n', "\n\n").to_uppercase();
+        let score = Fingerprint::new(RULE).similarity(&Fingerprint::new(&reflowed));
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn added_header_is_a_near_duplicate() {
+        let with_header = format!("# Quality\n\n{}", RULE);
+        let score = Fingerprint::new(RULE).similarity(&Fingerprint::new(&with_header));
+        assert!(score >= NEAR_DUPLICATE_THRESHOLD, "score was {}", score);
+    }
+
+    #[test]
+    fn unrelated_content_is_not_similar() {
+        let other = "Use tabs for indentation and wrap lines at 100 columns.";
+        let score = Fingerprint::new(RULE).similarity(&Fingerprint::new(other));
+        assert!(score < 0.2, "score was {}", score);
+    }
+
+    #[test]
+    fn closest_picks_the_best_match_above_threshold() {
+        let mut cache = FingerprintCache::default();
+        let close = format!("{}\nKeep it simple.", RULE);
+        let existing = [
+            ("a", "unrelated", "Use tabs everywhere please."),
+            ("b", "quality", RULE),
+            ("c", "quality-extended", close.as_str()),
+        ];
+
+        let found = closest_near_duplicate(
+            &mut cache,
+            &format!("## Quality\n{}", RULE),
+            existing.iter().copied(),
+        )
+        .unwrap();
+        assert_eq!(found.id, "b");
+        assert!(found.percent >= 80 && found.percent < 100);
+
+        assert_eq!(
+            closest_near_duplicate(&mut cache, "short note", existing.iter().copied()),
+            None
+        );
+    }
+}
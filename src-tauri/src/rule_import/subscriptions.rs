@@ -0,0 +1,607 @@
+//! Subscriptions to remote rule libraries.
+//!
+//! A subscription is a remote source (raw URL, Git repository or Gist) that is re-fetched
+//! on an interval. Fetched items are matched to previously imported artifacts through the
+//! import source map; new or changed items are either imported straight away or queued
+//! until the user approves them.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use super::{
+    compute_content_hash, execute_import, github, read_source_map, resolve_max_size,
+    scan_directory_to_candidates, scan_url_to_candidates, source_identity, stable_source_path,
+    validate_url_for_import,
+};
+use crate::constants::timing::{
+    DEFAULT_SUBSCRIPTION_INTERVAL_HOURS, SUBSCRIPTION_CHECK_INTERVAL, SUBSCRIPTION_FETCH_TIMEOUT,
+};
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{
+    CreateImportSubscriptionInput, ImportArtifactType, ImportCandidate, ImportExecutionResult,
+    ImportScanResult, ImportSourceType, ImportSubscription, SubscriptionCheckResult,
+    SubscriptionSourceKind, SubscriptionUpdateMode,
+};
+
+pub(crate) const SUBSCRIPTIONS_KEY: &str = "import_subscriptions";
+
+/// Serializes read-modify-write cycles on the stored subscription list.
+static SUBSCRIPTIONS_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub async fn list_subscriptions(db: &Database) -> Vec<ImportSubscription> {
+    let encoded = match db.get_setting(SUBSCRIPTIONS_KEY).await {
+        Ok(Some(v)) => v,
+        _ => return Vec::new(),
+    };
+    serde_json::from_str(&encoded).unwrap_or_default()
+}
+
+async fn write_subscriptions(db: &Database, subscriptions: &[ImportSubscription]) -> Result<()> {
+    let encoded = serde_json::to_string(subscriptions)?;
+    db.set_setting(SUBSCRIPTIONS_KEY, &encoded).await
+}
+
+async fn find_subscription(db: &Database, id: &str) -> Result<ImportSubscription> {
+    list_subscriptions(db)
+        .await
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::InvalidInput {
+            message: format!("Subscription not found: {}", id),
+        })
+}
+
+async fn save_subscription(db: &Database, subscription: ImportSubscription) -> Result<()> {
+    let mut subscriptions = list_subscriptions(db).await;
+    match subscriptions.iter_mut().find(|s| s.id == subscription.id) {
+        Some(existing) => *existing = subscription,
+        None => subscriptions.push(subscription),
+    }
+    write_subscriptions(db, &subscriptions).await
+}
+
+pub(super) fn validate_git_ref(git_ref: &str) -> Result<()> {
+    let valid = !git_ref.is_empty()
+        && !git_ref.starts_with('-')
+        && !git_ref.contains("..")
+        && git_ref
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'));
+    if !valid {
+        return Err(AppError::InvalidInput {
+            message: format!("Invalid Git branch or tag: {}", git_ref),
+        });
+    }
+    Ok(())
+}
+
+pub(super) fn validate_subpath(subpath: &str) -> Result<()> {
+    let path = Path::new(subpath);
+    let escapes = path.is_absolute()
+        || path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)));
+    if escapes {
+        return Err(AppError::InvalidInput {
+            message: "Path must be relative to the repository root".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub async fn add_subscription(
+    db: &Database,
+    input: CreateImportSubscriptionInput,
+) -> Result<ImportSubscription> {
+    let url = validate_url_for_import(input.url.trim())?;
+    if input.kind == SubscriptionSourceKind::Gist
+        && !matches!(
+            github::parse_github_url(&url),
+            Some(github::GithubSource::Gist { .. })
+        )
+    {
+        return Err(AppError::InvalidInput {
+            message: "Gist subscriptions need a gist.github.com URL".to_string(),
+        });
+    }
+    let git_ref = input
+        .git_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    let subpath = input
+        .subpath
+        .map(|p| p.trim().trim_matches('/').to_string())
+        .filter(|p| !p.is_empty());
+    if input.kind != SubscriptionSourceKind::Git && (git_ref.is_some() || subpath.is_some()) {
+        return Err(AppError::InvalidInput {
+            message: "Branch and path are only supported for Git subscriptions".to_string(),
+        });
+    }
+    if let Some(git_ref) = &git_ref {
+        validate_git_ref(git_ref)?;
+    }
+    if let Some(subpath) = &subpath {
+        validate_subpath(subpath)?;
+    }
+
+    let name = input
+        .name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| {
+            url.path_segments()
+                .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+                .unwrap_or_else(|| url.host_str().unwrap_or("subscription"))
+                .trim_end_matches(".git")
+                .to_string()
+        });
+
+    let subscription = ImportSubscription {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        kind: input.kind,
+        url: url.to_string(),
+        git_ref,
+        subpath,
+        update_mode: input.update_mode,
+        interval_hours: input
+            .interval_hours
+            .filter(|h| *h > 0)
+            .unwrap_or(DEFAULT_SUBSCRIPTION_INTERVAL_HOURS),
+        enabled: true,
+        options: input.options.unwrap_or_default(),
+        last_checked_at: None,
+        last_updated_at: None,
+        last_error: None,
+        pending: Vec::new(),
+        created_at: Utc::now(),
+    };
+
+    let _guard = SUBSCRIPTIONS_LOCK.lock().await;
+    save_subscription(db, subscription.clone()).await?;
+    Ok(subscription)
+}
+
+pub async fn remove_subscription(db: &Database, id: &str) -> Result<()> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().await;
+    let mut subscriptions = list_subscriptions(db).await;
+    let before = subscriptions.len();
+    subscriptions.retain(|s| s.id != id);
+    if subscriptions.len() == before {
+        return Err(AppError::InvalidInput {
+            message: format!("Subscription not found: {}", id),
+        });
+    }
+    write_subscriptions(db, &subscriptions).await
+}
+
+pub async fn set_subscription_enabled(
+    db: &Database,
+    id: &str,
+    enabled: bool,
+) -> Result<ImportSubscription> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().await;
+    let mut subscription = find_subscription(db, id).await?;
+    subscription.enabled = enabled;
+    save_subscription(db, subscription.clone()).await?;
+    Ok(subscription)
+}
+
+async fn fetch_git(subscription: &ImportSubscription, max_size: u64) -> Result<ImportScanResult> {
+    let checkout =
+        std::env::temp_dir().join(format!("ruleweaver-subscription-{}", uuid::Uuid::new_v4()));
+    let result = clone_and_scan(subscription, &checkout, max_size).await;
+    if checkout.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&checkout) {
+            log::warn!("Failed to remove {}: {}", checkout.display(), e);
+        }
+    }
+    result
+}
+
+async fn clone_and_scan(
+    subscription: &ImportSubscription,
+    checkout: &Path,
+    max_size: u64,
+) -> Result<ImportScanResult> {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.args(["clone", "--depth", "1", "--quiet"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true);
+    if let Some(git_ref) = &subscription.git_ref {
+        cmd.args(["--branch", git_ref]);
+    }
+    cmd.arg("--").arg(&subscription.url).arg(checkout);
+
+    let output = tokio::time::timeout(SUBSCRIPTION_FETCH_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| AppError::InvalidInput {
+            message: format!("Timed out cloning {}", subscription.url),
+        })??;
+    if !output.status.success() {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "git clone failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    let root = match &subscription.subpath {
+        Some(subpath) => checkout.join(subpath),
+        None => checkout.to_path_buf(),
+    };
+    let canonical_root = root.canonicalize()?;
+    let scan_root = canonical_root.clone();
+    let mut scan = tokio::task::spawn_blocking(move || {
+        scan_directory_to_candidates(&scan_root, max_size, None)
+    })
+    .await
+    .map_err(|e| AppError::Internal {
+        message: e.to_string(),
+    })?;
+
+    scan.candidates
+        .retain(|c| !c.source_path.contains("/.git/"));
+    for candidate in &mut scan.candidates {
+        candidate.source_path = stable_source_path(
+            &subscription.url,
+            &canonical_root,
+            Path::new(&candidate.source_path),
+        );
+        candidate.source_type = ImportSourceType::Url;
+        candidate.source_label = "Git".to_string();
+    }
+    Ok(scan)
+}
+
+async fn fetch_candidates(subscription: &ImportSubscription) -> Result<ImportScanResult> {
+    let max_size = resolve_max_size(&subscription.options);
+    match subscription.kind {
+        SubscriptionSourceKind::Url | SubscriptionSourceKind::Gist => {
+            scan_url_to_candidates(&subscription.url, max_size).await
+        }
+        SubscriptionSourceKind::Git => fetch_git(subscription, max_size).await,
+    }
+}
+
+/// Content of the artifact previously imported for `artifact_id`, if it still exists.
+pub(super) async fn mapped_content(
+    db: &Database,
+    artifact_type: ImportArtifactType,
+    artifact_id: &str,
+) -> Option<String> {
+    match artifact_type {
+        ImportArtifactType::Rule => db.get_rule_by_id(artifact_id).await.ok().map(|r| r.content),
+        ImportArtifactType::SlashCommand => db
+            .get_command_by_id(artifact_id)
+            .await
+            .ok()
+            .map(|c| c.script),
+        ImportArtifactType::Skill => db
+            .get_skill_by_id(artifact_id)
+            .await
+            .ok()
+            .map(|s| s.instructions),
+    }
+}
+
+/// Compares fetched items with the artifacts they were imported as, applying or queueing
+/// anything new or changed according to the subscription's update mode.
+async fn apply_scan(
+    db: Arc<Database>,
+    subscription: &mut ImportSubscription,
+    scan: ImportScanResult,
+) -> Result<SubscriptionCheckResult> {
+    let mut result = SubscriptionCheckResult {
+        subscription_id: subscription.id.clone(),
+        errors: scan.errors.clone(),
+        ..Default::default()
+    };
+
+    let source_map = read_source_map(db.clone()).await;
+    let mut updates: Vec<ImportCandidate> = Vec::new();
+    for candidate in scan.candidates {
+        let mapped = match source_map.get(&source_identity(&candidate)) {
+            Some(id) => mapped_content(&db, candidate.artifact_type, id).await,
+            None => None,
+        };
+        match mapped {
+            Some(content) if compute_content_hash(&content) == candidate.content_hash => {
+                result.unchanged_count += 1;
+            }
+            Some(_) => {
+                result.changed_count += 1;
+                updates.push(candidate);
+            }
+            None => {
+                result.new_count += 1;
+                updates.push(candidate);
+            }
+        }
+    }
+
+    subscription.last_checked_at = Some(Utc::now());
+    subscription.last_error = None;
+    if updates.is_empty() {
+        subscription.pending.clear();
+        return Ok(result);
+    }
+
+    match subscription.update_mode {
+        SubscriptionUpdateMode::Auto => {
+            let import = execute_import(
+                db,
+                ImportScanResult {
+                    candidates: updates,
+                    errors: Vec::new(),
+                },
+                subscription.options.clone(),
+            )
+            .await?;
+            subscription.pending.clear();
+            subscription.last_updated_at = Some(Utc::now());
+            result.import = Some(import);
+        }
+        SubscriptionUpdateMode::Approve => {
+            subscription.pending = updates;
+        }
+    }
+    result.pending_count = subscription.pending.len();
+    Ok(result)
+}
+
+/// Fetches a subscription now and applies or queues upstream changes.
+pub async fn check_subscription(db: Arc<Database>, id: &str) -> Result<SubscriptionCheckResult> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().await;
+    let mut subscription = find_subscription(&db, id).await?;
+
+    let outcome = match fetch_candidates(&subscription).await {
+        Ok(scan) => apply_scan(db.clone(), &mut subscription, scan).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &outcome {
+        subscription.last_checked_at = Some(Utc::now());
+        subscription.last_error = Some(e.to_string());
+    }
+    save_subscription(&db, subscription).await?;
+    outcome
+}
+
+/// Imports queued changes. With `candidate_ids`, only those are applied and the rest stay
+/// queued.
+pub async fn approve_pending(
+    db: Arc<Database>,
+    id: &str,
+    candidate_ids: Option<Vec<String>>,
+) -> Result<ImportExecutionResult> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().await;
+    let mut subscription = find_subscription(&db, id).await?;
+
+    let (approved, remaining): (Vec<_>, Vec<_>) = std::mem::take(&mut subscription.pending)
+        .into_iter()
+        .partition(|c| {
+            candidate_ids
+                .as_ref()
+                .map(|ids| ids.contains(&c.id))
+                .unwrap_or(true)
+        });
+    subscription.pending = remaining;
+
+    let import = execute_import(
+        db.clone(),
+        ImportScanResult {
+            candidates: approved,
+            errors: Vec::new(),
+        },
+        subscription.options.clone(),
+    )
+    .await?;
+    subscription.last_updated_at = Some(Utc::now());
+    save_subscription(&db, subscription).await?;
+    Ok(import)
+}
+
+/// Drops queued changes; they are offered again if still present on the next check.
+pub async fn dismiss_pending(db: &Database, id: &str) -> Result<()> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().await;
+    let mut subscription = find_subscription(db, id).await?;
+    subscription.pending.clear();
+    save_subscription(db, subscription).await
+}
+
+fn is_due(subscription: &ImportSubscription) -> bool {
+    subscription.enabled
+        && subscription.last_checked_at.is_none_or(|last| {
+            Utc::now() - last >= chrono::Duration::hours(i64::from(subscription.interval_hours))
+        })
+}
+
+/// Background loop that re-fetches subscriptions whose interval has elapsed.
+pub async fn run_subscription_loop(db: Arc<Database>) {
+    loop {
+        let due: Vec<String> = list_subscriptions(&db)
+            .await
+            .into_iter()
+            .filter(is_due)
+            .map(|s| s.id)
+            .collect();
+        for id in due {
+            match check_subscription(db.clone(), &id).await {
+                Ok(result) if result.pending_count > 0 => log::info!(
+                    "Subscription {} has {} update(s) awaiting approval",
+                    id,
+                    result.pending_count
+                ),
+                Ok(_) => {}
+                Err(e) => log::warn!("Subscription {} check failed: {}", id, e),
+            }
+        }
+        tokio::time::sleep(SUBSCRIPTION_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ImportExecutionOptions;
+    use crate::models::Scope;
+    use crate::rule_import::candidate_from_text;
+
+    fn url_input(url: &str, mode: SubscriptionUpdateMode) -> CreateImportSubscriptionInput {
+        CreateImportSubscriptionInput {
+            name: None,
+            kind: SubscriptionSourceKind::Url,
+            url: url.to_string(),
+            git_ref: None,
+            subpath: None,
+            update_mode: mode,
+            interval_hours: None,
+            options: None,
+        }
+    }
+
+    fn upstream(subscription: &ImportSubscription, content: &str) -> ImportScanResult {
+        ImportScanResult {
+            candidates: vec![candidate_from_text(
+                content.to_string(),
+                "team-standards",
+                ImportSourceType::Url,
+                "URL",
+                &subscription.url,
+                None,
+                Scope::Global,
+                None,
+                ImportArtifactType::Rule,
+            )],
+            errors: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_subscription_validates_source() {
+        let db = Database::new_in_memory().await.unwrap();
+
+        let created = add_subscription(
+            &db,
+            url_input(
+                "https://example.com/rules/team.md",
+                SubscriptionUpdateMode::Approve,
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(created.name, "team.md");
+        assert_eq!(created.interval_hours, DEFAULT_SUBSCRIPTION_INTERVAL_HOURS);
+        assert_eq!(list_subscriptions(&db).await.len(), 1);
+
+        assert!(add_subscription(
+            &db,
+            url_input("http://localhost/rules.md", SubscriptionUpdateMode::Auto)
+        )
+        .await
+        .is_err());
+
+        let mut git = url_input(
+            "https://example.com/org/rules.git",
+            SubscriptionUpdateMode::Auto,
+        );
+        git.kind = SubscriptionSourceKind::Git;
+        git.git_ref = Some("--upload-pack=evil".to_string());
+        assert!(add_subscription(&db, git.clone()).await.is_err());
+        git.git_ref = Some("main".to_string());
+        git.subpath = Some("../outside".to_string());
+        assert!(add_subscription(&db, git.clone()).await.is_err());
+        git.subpath = Some("rules/".to_string());
+        let git = add_subscription(&db, git).await.unwrap();
+        assert_eq!(git.name, "rules");
+        assert_eq!(git.subpath.as_deref(), Some("rules"));
+    }
+
+    #[tokio::test]
+    async fn test_approve_mode_queues_changes_until_approved() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let mut subscription = add_subscription(
+            &db,
+            url_input(
+                "https://example.com/team.md",
+                SubscriptionUpdateMode::Approve,
+            ),
+        )
+        .await
+        .unwrap();
+
+        let scan = upstream(&subscription, "version one");
+        let result = apply_scan(db.clone(), &mut subscription, scan)
+            .await
+            .unwrap();
+        assert_eq!(result.new_count, 1);
+        assert_eq!(result.pending_count, 1);
+        assert!(db.get_all_rules().await.unwrap().is_empty());
+        save_subscription(&db, subscription.clone()).await.unwrap();
+
+        let import = approve_pending(db.clone(), &subscription.id, None)
+            .await
+            .unwrap();
+        assert_eq!(import.imported_rules.len(), 1);
+        let mut subscription = find_subscription(&db, &subscription.id).await.unwrap();
+        assert!(subscription.pending.is_empty());
+
+        let scan = upstream(&subscription, "version one");
+        let result = apply_scan(db.clone(), &mut subscription, scan)
+            .await
+            .unwrap();
+        assert_eq!(result.unchanged_count, 1);
+        assert_eq!(result.pending_count, 0);
+
+        let scan = upstream(&subscription, "version two");
+        let result = apply_scan(db.clone(), &mut subscription, scan)
+            .await
+            .unwrap();
+        assert_eq!(result.changed_count, 1);
+        assert_eq!(result.pending_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_auto_mode_updates_mapped_rule() {
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let mut input = url_input("https://example.com/team.md", SubscriptionUpdateMode::Auto);
+        input.options = Some(ImportExecutionOptions::default());
+        let mut subscription = add_subscription(&db, input).await.unwrap();
+
+        let scan = upstream(&subscription, "version one");
+        apply_scan(db.clone(), &mut subscription, scan)
+            .await
+            .unwrap();
+        let rules = db.get_all_rules().await.unwrap();
+        assert_eq!(rules.len(), 1);
+
+        let scan = upstream(&subscription, "version two");
+        let result = apply_scan(db.clone(), &mut subscription, scan)
+            .await
+            .unwrap();
+        assert_eq!(result.changed_count, 1);
+        let updated = db.get_rule_by_id(&rules[0].id).await.unwrap();
+        assert_eq!(updated.content, "version two");
+        assert!(subscription.last_updated_at.is_some());
+    }
+
+    #[test]
+    fn test_git_source_paths_are_stable_across_checkouts() {
+        let first = stable_source_path(
+            "https://example.com/org/rules.git",
+            Path::new("/tmp/checkout-a"),
+            Path::new("/tmp/checkout-a/rules/style.md"),
+        );
+        let second = stable_source_path(
+            "https://example.com/org/rules.git",
+            Path::new("/tmp/checkout-b"),
+            Path::new("/tmp/checkout-b/rules/style.md"),
+        );
+        assert_eq!(first, second);
+        assert_eq!(first, "https://example.com/org/rules.git#rules/style.md");
+    }
+}
@@ -0,0 +1,1055 @@
+//! Team repository sync.
+//!
+//! The team repository is a Git remote holding the team's canonical library in bundle layout
+//! (`rules/*.json`, `commands/*.md`, `skills/<name>/SKILL.md`). RuleWeaver keeps a clone in
+//! its data directory, pulls it on an interval and imports what changed upstream, recording
+//! which local artifact each library file maps to. Approved local edits are written back to
+//! the clone and pushed as a commit on the tracked branch or on a new branch to open a pull
+//! request from.
+//!
+//! Every library file remembers the content hash it had when last pulled or pushed. On a
+//! pull, a file whose local artifact still has that hash is updated; one edited only locally
+//! is left for pushing; one edited on both sides is reported as a conflict and not touched.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use super::archive::{
+    command_entry, import_archive_scan, rule_entry, scan_extracted, skill_entries,
+    unique_entry_name, ArchiveScan, BundleEntry, BUNDLE_COMMANDS_DIR, BUNDLE_RULES_DIR,
+};
+use super::subscriptions::{mapped_content, validate_git_ref, validate_subpath};
+use super::{
+    compute_content_hash, read_source_map, resolve_max_size, source_identity, write_source_map,
+};
+use crate::constants::timing::{
+    DEFAULT_TEAM_REPO_INTERVAL_HOURS, TEAM_REPO_CHECK_INTERVAL, TEAM_REPO_GIT_TIMEOUT,
+};
+use crate::constants::{SKILLS_DIR_NAME, SKILL_INSTRUCTIONS_FILE};
+use crate::database::{default_app_data_dir, Database};
+use crate::error::{AppError, Result};
+use crate::models::{
+    ConfigureTeamRepoInput, ImportArtifactType, ImportCandidate, ImportScanResult,
+    ImportSourceType, TeamRepo, TeamRepoChange, TeamRepoFile, TeamRepoPullResult,
+    TeamRepoPushInput, TeamRepoPushMode, TeamRepoPushResult,
+};
+
+pub(crate) const TEAM_REPO_KEY: &str = "team_repo";
+const CHECKOUT_DIR_NAME: &str = "team-repo";
+const DEFAULT_BRANCH: &str = "main";
+const PUSH_BRANCH_PREFIX: &str = "ruleweaver/";
+const SOURCE_LABEL: &str = "Team Repo";
+/// Identity pushes commit as when git has none configured.
+const FALLBACK_GIT_NAME: &str = "RuleWeaver";
+const FALLBACK_GIT_EMAIL: &str = "ruleweaver@localhost";
+
+/// Serializes pulls, pushes and configuration changes, which share the clone.
+static TEAM_REPO_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub async fn get_team_repo(db: &Database) -> Option<TeamRepo> {
+    let encoded = db.get_setting(TEAM_REPO_KEY).await.ok().flatten()?;
+    serde_json::from_str(&encoded).ok()
+}
+
+async fn save_team_repo(db: &Database, repo: &TeamRepo) -> Result<()> {
+    let encoded = serde_json::to_string(repo)?;
+    db.set_setting(TEAM_REPO_KEY, &encoded).await
+}
+
+async fn require_team_repo(db: &Database) -> Result<TeamRepo> {
+    get_team_repo(db)
+        .await
+        .ok_or_else(|| AppError::InvalidInput {
+            message: "No team repository is configured".to_string(),
+        })
+}
+
+fn checkout_dir() -> Result<PathBuf> {
+    Ok(default_app_data_dir()?.join(CHECKOUT_DIR_NAME))
+}
+
+fn remove_checkout(checkout: &Path) {
+    if checkout.exists() {
+        if let Err(e) = fs::remove_dir_all(checkout) {
+            log::warn!("Failed to remove {}: {}", checkout.display(), e);
+        }
+    }
+}
+
+/// Accepts the remotes teams actually use: http(s), ssh and scp-style `user@host:path`,
+/// and `file://` for repositories on a shared mount.
+fn validate_remote(url: &str) -> Result<()> {
+    let invalid = |reason: &str| AppError::InvalidInput {
+        message: format!("Invalid team repository URL: {}", reason),
+    };
+    if url.is_empty() || url.starts_with('-') || url.chars().any(char::is_whitespace) {
+        return Err(invalid("expected a Git remote URL"));
+    }
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "https" | "http" | "ssh" | "file") => Ok(()),
+        Ok(parsed) => Err(invalid(&format!(
+            "unsupported scheme '{}'",
+            parsed.scheme()
+        ))),
+        Err(_) if url.contains('@') && url.contains(':') => Ok(()),
+        Err(e) => Err(invalid(&e.to_string())),
+    }
+}
+
+/// Sets up the team repository, or changes its settings. Pointing it at another remote,
+/// branch or directory drops the clone and the file links, so the next pull starts over.
+pub async fn configure_team_repo(db: &Database, input: ConfigureTeamRepoInput) -> Result<TeamRepo> {
+    let url = input.url.trim().to_string();
+    validate_remote(&url)?;
+    let branch = input
+        .branch
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| DEFAULT_BRANCH.to_string());
+    validate_git_ref(&branch)?;
+    let subpath = input
+        .subpath
+        .map(|p| p.trim().trim_matches('/').to_string())
+        .filter(|p| !p.is_empty());
+    if let Some(subpath) = &subpath {
+        validate_subpath(subpath)?;
+    }
+
+    let _guard = TEAM_REPO_LOCK.lock().await;
+    let existing = get_team_repo(db).await;
+    let same_source = existing
+        .as_ref()
+        .is_some_and(|r| r.url == url && r.branch == branch && r.subpath == subpath);
+    let repo = match existing {
+        Some(existing) if same_source => TeamRepo {
+            push_mode: input.push_mode,
+            interval_hours: input
+                .interval_hours
+                .filter(|h| *h > 0)
+                .unwrap_or(existing.interval_hours),
+            options: input.options.unwrap_or(existing.options.clone()),
+            ..existing
+        },
+        _ => {
+            remove_checkout(&checkout_dir()?);
+            TeamRepo {
+                url,
+                branch,
+                subpath,
+                push_mode: input.push_mode,
+                interval_hours: input
+                    .interval_hours
+                    .filter(|h| *h > 0)
+                    .unwrap_or(DEFAULT_TEAM_REPO_INTERVAL_HOURS),
+                enabled: true,
+                options: input.options.unwrap_or_default(),
+                last_commit: None,
+                last_pulled_at: None,
+                last_pushed_at: None,
+                last_error: None,
+                files: HashMap::new(),
+                created_at: Utc::now(),
+            }
+        }
+    };
+    save_team_repo(db, &repo).await?;
+    Ok(repo)
+}
+
+/// Disconnects the team repository. Artifacts pulled from it stay.
+pub async fn remove_team_repo(db: &Database) -> Result<()> {
+    let _guard = TEAM_REPO_LOCK.lock().await;
+    remove_checkout(&checkout_dir()?);
+    db.delete_setting(TEAM_REPO_KEY).await
+}
+
+pub async fn set_team_repo_enabled(db: &Database, enabled: bool) -> Result<TeamRepo> {
+    let _guard = TEAM_REPO_LOCK.lock().await;
+    let mut repo = require_team_repo(db).await?;
+    repo.enabled = enabled;
+    save_team_repo(db, &repo).await?;
+    Ok(repo)
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    git_with_config(dir, &[], args).await
+}
+
+/// Runs git with `key=value` settings passed as `-c` ahead of the subcommand.
+async fn git_with_config(dir: &Path, config: &[String], args: &[&str]) -> Result<String> {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.arg("-C").arg(dir);
+    for setting in config {
+        cmd.arg("-c").arg(setting);
+    }
+    cmd.args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(TEAM_REPO_GIT_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| AppError::InvalidInput {
+            message: format!("Timed out running git {}", args[0]),
+        })??;
+    if !output.status.success() {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Settings giving git an identity to commit as when the machine has none, which would
+/// otherwise make every push fail. A configured name or email is left alone.
+async fn fallback_identity(checkout: &Path) -> Vec<String> {
+    let mut config = Vec::new();
+    for (key, fallback) in [
+        ("user.name", FALLBACK_GIT_NAME),
+        ("user.email", FALLBACK_GIT_EMAIL),
+    ] {
+        if git(checkout, &["config", key]).await.is_err() {
+            config.push(format!("{}={}", key, fallback));
+        }
+    }
+    config
+}
+
+/// Brings the clone to the remote's tip of the tracked branch, discarding anything a failed
+/// push left behind. Returns the checked-out commit, or `None` for a repository without
+/// commits, whose first push creates the branch.
+async fn sync_checkout(repo: &TeamRepo, checkout: &Path) -> Result<Option<String>> {
+    if !checkout.join(".git").exists() {
+        remove_checkout(checkout);
+        let parent = checkout.parent().unwrap_or(checkout);
+        fs::create_dir_all(parent)?;
+        let target = checkout.to_string_lossy();
+        git(parent, &["clone", "--quiet", "--", &repo.url, &target]).await?;
+    }
+    git(checkout, &["fetch", "--quiet", "--prune", "origin"]).await?;
+
+    let remote_branch = format!("refs/remotes/origin/{}", repo.branch);
+    if git(
+        checkout,
+        &["rev-parse", "--verify", "--quiet", &remote_branch],
+    )
+    .await
+    .is_ok()
+    {
+        git(
+            checkout,
+            &[
+                "checkout",
+                "--quiet",
+                "--force",
+                "-B",
+                &repo.branch,
+                &remote_branch,
+            ],
+        )
+        .await?;
+        git(checkout, &["clean", "--quiet", "-fd"]).await?;
+        return git(checkout, &["rev-parse", "HEAD"]).await.map(Some);
+    }
+
+    let has_branches = !git(checkout, &["branch", "--remotes"]).await?.is_empty();
+    if has_branches {
+        return Err(AppError::InvalidInput {
+            message: format!("Branch '{}' not found in {}", repo.branch, repo.url),
+        });
+    }
+    let head = format!("refs/heads/{}", repo.branch);
+    git(checkout, &["symbolic-ref", "HEAD", &head]).await?;
+    Ok(None)
+}
+
+fn library_root(repo: &TeamRepo, checkout: &Path) -> PathBuf {
+    match &repo.subpath {
+        Some(subpath) => checkout.join(subpath),
+        None => checkout.to_path_buf(),
+    }
+}
+
+/// Path of a scanned candidate relative to the library root.
+fn library_path(candidate: &ImportCandidate) -> &str {
+    candidate
+        .source_path
+        .rsplit_once('#')
+        .map(|(_, path)| path)
+        .unwrap_or(&candidate.source_path)
+}
+
+fn is_library_path(path: &str) -> bool {
+    [BUNDLE_RULES_DIR, BUNDLE_COMMANDS_DIR, SKILLS_DIR_NAME]
+        .iter()
+        .any(|dir| path.starts_with(&format!("{}/", dir)))
+}
+
+/// Scans the library in the clone. Only the bundle directories count, so READMEs and CI
+/// files in the repository are not imported.
+async fn scan_library(repo: &TeamRepo, checkout: &Path) -> Result<ArchiveScan> {
+    let root = library_root(repo, checkout);
+    if !root.is_dir() {
+        return Ok(ArchiveScan {
+            scan: ImportScanResult::default(),
+            skill_dirs: HashMap::new(),
+        });
+    }
+    // A subpath that is a link in the clone would have the scan read outside it.
+    let inside_checkout = fs::canonicalize(&root)
+        .ok()
+        .zip(fs::canonicalize(checkout).ok())
+        .is_some_and(|(root, checkout)| root.starts_with(checkout));
+    if !inside_checkout {
+        return Err(AppError::InvalidInput {
+            message: "The library path leaves the repository".to_string(),
+        });
+    }
+    let origin = repo.url.clone();
+    let max_size = resolve_max_size(&repo.options);
+    let mut archive = tokio::task::spawn_blocking(move || scan_extracted(&origin, &root, max_size))
+        .await
+        .map_err(|e| AppError::Internal {
+            message: e.to_string(),
+        })?;
+
+    archive
+        .scan
+        .candidates
+        .retain(|c| is_library_path(library_path(c)));
+    for candidate in &mut archive.scan.candidates {
+        candidate.source_type = ImportSourceType::Url;
+        candidate.source_label = SOURCE_LABEL.to_string();
+    }
+    Ok(archive)
+}
+
+fn change(path: &str, file: &TeamRepoFile, name: &str) -> TeamRepoChange {
+    TeamRepoChange {
+        path: path.to_string(),
+        artifact_type: file.artifact_type,
+        artifact_id: file.artifact_id.clone(),
+        name: name.to_string(),
+    }
+}
+
+async fn pull_into(
+    db: Arc<Database>,
+    repo: &mut TeamRepo,
+    checkout: &Path,
+) -> Result<TeamRepoPullResult> {
+    let commit = sync_checkout(repo, checkout).await?;
+    let ArchiveScan { scan, skill_dirs } = scan_library(repo, checkout).await?;
+    let mut result = TeamRepoPullResult {
+        commit: commit.clone(),
+        errors: scan.errors,
+        ..Default::default()
+    };
+
+    let source_map = read_source_map(db.clone()).await;
+    let mut files = HashMap::new();
+    let mut updates = Vec::new();
+    for candidate in scan.candidates {
+        let path = library_path(&candidate).to_string();
+        let tracked = repo
+            .files
+            .get(&path)
+            .filter(|f| f.artifact_type == candidate.artifact_type)
+            .cloned()
+            .or_else(|| {
+                source_map
+                    .get(&source_identity(&candidate))
+                    .map(|id| TeamRepoFile {
+                        artifact_type: candidate.artifact_type,
+                        artifact_id: id.clone(),
+                        content_hash: String::new(),
+                    })
+            });
+        let local = match &tracked {
+            Some(file) => mapped_content(&db, file.artifact_type, &file.artifact_id).await,
+            None => None,
+        };
+        let (Some(mut file), Some(local)) = (tracked, local) else {
+            result.new_count += 1;
+            updates.push(candidate);
+            continue;
+        };
+
+        let local_hash = compute_content_hash(&local);
+        if local_hash == candidate.content_hash {
+            result.unchanged_count += 1;
+            file.content_hash = local_hash;
+        } else if local_hash == file.content_hash {
+            result.changed_count += 1;
+            updates.push(candidate);
+            continue;
+        } else if candidate.content_hash == file.content_hash {
+            result
+                .local_changes
+                .push(change(&path, &file, &candidate.proposed_name));
+        } else {
+            result
+                .conflicts
+                .push(change(&path, &file, &candidate.proposed_name));
+        }
+        files.insert(path, file);
+    }
+
+    if !updates.is_empty() {
+        let imported: Vec<(String, String, ImportArtifactType, String)> = updates
+            .iter()
+            .map(|c| {
+                (
+                    library_path(c).to_string(),
+                    source_identity(c),
+                    c.artifact_type,
+                    c.content_hash.clone(),
+                )
+            })
+            .collect();
+        let import = import_archive_scan(
+            db.clone(),
+            ArchiveScan {
+                scan: ImportScanResult {
+                    candidates: updates,
+                    errors: Vec::new(),
+                },
+                skill_dirs,
+            },
+            repo.options.clone(),
+        )
+        .await?;
+
+        let source_map = read_source_map(db.clone()).await;
+        for (path, identity, artifact_type, content_hash) in imported {
+            if let Some(artifact_id) = source_map.get(&identity) {
+                files.insert(
+                    path,
+                    TeamRepoFile {
+                        artifact_type,
+                        artifact_id: artifact_id.clone(),
+                        content_hash,
+                    },
+                );
+            }
+        }
+        result.import = Some(import);
+    }
+
+    repo.files = files;
+    repo.last_commit = commit;
+    repo.last_pulled_at = Some(Utc::now());
+    repo.last_error = None;
+    Ok(result)
+}
+
+/// Pulls the team repository now and imports what changed upstream.
+pub async fn pull_team_repo(db: Arc<Database>) -> Result<TeamRepoPullResult> {
+    let _guard = TEAM_REPO_LOCK.lock().await;
+    let mut repo = require_team_repo(&db).await?;
+    let outcome = pull_into(db.clone(), &mut repo, &checkout_dir()?).await;
+    if let Err(e) = &outcome {
+        repo.last_pulled_at = Some(Utc::now());
+        repo.last_error = Some(e.to_string());
+    }
+    save_team_repo(&db, &repo).await?;
+    outcome
+}
+
+/// Library files whose local artifact was edited since it was last pulled or pushed.
+pub async fn outgoing_changes(db: &Database) -> Result<Vec<TeamRepoChange>> {
+    let Some(repo) = get_team_repo(db).await else {
+        return Ok(Vec::new());
+    };
+    let mut changes = Vec::new();
+    for (path, file) in &repo.files {
+        let name = match file.artifact_type {
+            ImportArtifactType::Rule => db
+                .get_rule_by_id(&file.artifact_id)
+                .await
+                .ok()
+                .map(|r| (compute_content_hash(&r.content), r.name)),
+            ImportArtifactType::SlashCommand => db
+                .get_command_by_id(&file.artifact_id)
+                .await
+                .ok()
+                .map(|c| (compute_content_hash(&c.script), c.name)),
+            ImportArtifactType::Skill => db
+                .get_skill_by_id(&file.artifact_id)
+                .await
+                .ok()
+                .map(|s| (compute_content_hash(&s.instructions), s.name)),
+        };
+        if let Some((hash, name)) = name {
+            if hash != file.content_hash {
+                changes.push(change(path, file, &name));
+            }
+        }
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+/// Names already taken in a library directory, so new files do not overwrite existing ones.
+fn existing_names(dir: &Path) -> HashSet<String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            let name = if path.is_dir() {
+                path.file_name()
+            } else {
+                path.file_stem()
+            };
+            name.map(|n| n.to_string_lossy().to_lowercase())
+        })
+        .collect()
+}
+
+fn tracked_path(repo: &TeamRepo, artifact_type: ImportArtifactType, id: &str) -> Option<String> {
+    repo.files
+        .iter()
+        .find(|(_, f)| f.artifact_type == artifact_type && f.artifact_id == id)
+        .map(|(path, _)| path.clone())
+}
+
+/// The library files for the selected artifacts, at their tracked paths when they came
+/// from the library. Returns the entries and, per artifact, the path its scan is found at.
+async fn push_entries(
+    db: &Database,
+    repo: &TeamRepo,
+    root: &Path,
+    input: &TeamRepoPushInput,
+) -> Result<(Vec<BundleEntry>, Vec<(String, ImportArtifactType, String)>)> {
+    let mut entries = Vec::new();
+    let mut pushed = Vec::new();
+
+    let mut used = existing_names(&root.join(BUNDLE_RULES_DIR));
+    for id in &input.selection.rule_ids {
+        let rule = db.get_rule_by_id(id).await?;
+        let path = tracked_path(repo, ImportArtifactType::Rule, id).unwrap_or_else(|| {
+            format!(
+                "{}/{}.json",
+                BUNDLE_RULES_DIR,
+                unique_entry_name(&rule.name, &mut used)
+            )
+        });
+        // Rules the team wrote as plain markdown stay plain markdown.
+        if path.ends_with(".json") {
+            entries.push(rule_entry(path.clone(), &rule)?);
+        } else {
+            entries.push(BundleEntry {
+                path: path.clone(),
+                bytes: rule.content.into_bytes(),
+            });
+        }
+        pushed.push((path, ImportArtifactType::Rule, rule.id));
+    }
+
+    let mut used = existing_names(&root.join(BUNDLE_COMMANDS_DIR));
+    for id in &input.selection.command_ids {
+        let command = db.get_command_by_id(id).await?;
+        let path = tracked_path(repo, ImportArtifactType::SlashCommand, id).unwrap_or_else(|| {
+            format!(
+                "{}/{}.md",
+                BUNDLE_COMMANDS_DIR,
+                unique_entry_name(&command.name, &mut used)
+            )
+        });
+        entries.push(command_entry(path.clone(), &command));
+        pushed.push((path, ImportArtifactType::SlashCommand, command.id));
+    }
+
+    let mut used = existing_names(&root.join(SKILLS_DIR_NAME));
+    for id in &input.selection.skill_ids {
+        let skill = db.get_skill_by_id(id).await?;
+        let dir = tracked_path(repo, ImportArtifactType::Skill, id)
+            .and_then(|path| path.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+            .unwrap_or_else(|| {
+                format!(
+                    "{}/{}",
+                    SKILLS_DIR_NAME,
+                    unique_entry_name(&skill.name, &mut used)
+                )
+            });
+        entries.extend(skill_entries(&dir, &skill)?);
+        pushed.push((
+            format!("{}/{}", dir, SKILL_INSTRUCTIONS_FILE),
+            ImportArtifactType::Skill,
+            skill.id,
+        ));
+    }
+    Ok((entries, pushed))
+}
+
+fn write_entries(root: &Path, entries: &[BundleEntry]) -> Result<()> {
+    for entry in entries {
+        let relative = Path::new(&entry.path);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(AppError::InvalidInput {
+                message: format!("Library path escapes the repository: {}", entry.path),
+            });
+        }
+        let target = root.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Links in the clone must not redirect the write outside it.
+        let parent_inside = target
+            .parent()
+            .and_then(|parent| fs::canonicalize(parent).ok())
+            .zip(fs::canonicalize(root).ok())
+            .is_some_and(|(parent, root)| parent.starts_with(root));
+        if !parent_inside || fs::symlink_metadata(&target).is_ok_and(|m| m.is_symlink()) {
+            return Err(AppError::InvalidInput {
+                message: format!("Library path is a link in the repository: {}", entry.path),
+            });
+        }
+        fs::write(&target, &entry.bytes)?;
+    }
+    Ok(())
+}
+
+/// `https://github.com/<owner>/<repo>/compare/...` for GitHub remotes.
+fn pull_request_url(remote: &str, base: &str, branch: &str) -> Option<String> {
+    let path = remote
+        .strip_prefix("git@github.com:")
+        .or_else(|| remote.strip_prefix("https://github.com/"))
+        .or_else(|| remote.strip_prefix("ssh://git@github.com/"))?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    Some(format!(
+        "https://github.com/{}/compare/{}...{}?expand=1",
+        path, base, branch
+    ))
+}
+
+async fn push_from(
+    db: Arc<Database>,
+    repo: &mut TeamRepo,
+    checkout: &Path,
+    input: &TeamRepoPushInput,
+) -> Result<TeamRepoPushResult> {
+    sync_checkout(repo, checkout).await?;
+    let branch = match repo.push_mode {
+        TeamRepoPushMode::Commit => repo.branch.clone(),
+        TeamRepoPushMode::Branch => {
+            let branch = format!(
+                "{}{}",
+                PUSH_BRANCH_PREFIX,
+                Utc::now().format("%Y%m%d-%H%M%S")
+            );
+            git(checkout, &["checkout", "--quiet", "-b", &branch]).await?;
+            branch
+        }
+    };
+
+    let root = library_root(repo, checkout);
+    let (entries, pushed) = push_entries(&db, repo, &root, input).await?;
+    write_entries(&root, &entries)?;
+
+    let pathspec = repo.subpath.as_deref().unwrap_or(".");
+    git(checkout, &["add", "--all", "--", pathspec]).await?;
+    if git(checkout, &["diff", "--cached", "--quiet"])
+        .await
+        .is_ok()
+    {
+        return Err(AppError::InvalidInput {
+            message: "Nothing to push: the team repository already has these versions".to_string(),
+        });
+    }
+    let identity = fallback_identity(checkout).await;
+    git_with_config(
+        checkout,
+        &identity,
+        &["commit", "--quiet", "-m", input.message.trim()],
+    )
+    .await?;
+    let refspec = format!("HEAD:refs/heads/{}", branch);
+    git(checkout, &["push", "--quiet", "origin", &refspec])
+        .await
+        .map_err(|e| AppError::InvalidInput {
+            message: format!("{}; pull the team repository and try again", e),
+        })?;
+    let commit = git(checkout, &["rev-parse", "HEAD"]).await?;
+
+    // Link the pushed files to their artifacts, so pulls update them in place.
+    let ArchiveScan { scan, .. } = scan_library(repo, checkout).await?;
+    let mut source_map = read_source_map(db.clone()).await;
+    let mut files = Vec::with_capacity(pushed.len());
+    for (path, artifact_type, artifact_id) in pushed {
+        let Some(candidate) = scan
+            .candidates
+            .iter()
+            .find(|c| library_path(c) == path && c.artifact_type == artifact_type)
+        else {
+            continue;
+        };
+        source_map.insert(source_identity(candidate), artifact_id.clone());
+        // A pushed branch is not upstream until it is merged, so the pulled version stays
+        // the base until a pull sees the merge.
+        if repo.push_mode == TeamRepoPushMode::Commit {
+            repo.files.insert(
+                path.clone(),
+                TeamRepoFile {
+                    artifact_type,
+                    artifact_id,
+                    content_hash: candidate.content_hash.clone(),
+                },
+            );
+        }
+        files.push(path);
+    }
+    write_source_map(db, &source_map).await?;
+
+    if repo.push_mode == TeamRepoPushMode::Commit {
+        repo.last_commit = Some(commit.clone());
+    }
+    repo.last_pushed_at = Some(Utc::now());
+    Ok(TeamRepoPushResult {
+        pull_request_url: (repo.push_mode == TeamRepoPushMode::Branch)
+            .then(|| pull_request_url(&repo.url, &repo.branch, &branch))
+            .flatten(),
+        commit,
+        branch,
+        files,
+    })
+}
+
+/// Writes the selected artifacts to the team repository and pushes them.
+pub async fn push_team_repo(
+    db: Arc<Database>,
+    input: TeamRepoPushInput,
+) -> Result<TeamRepoPushResult> {
+    if input.message.trim().is_empty() {
+        return Err(AppError::InvalidInput {
+            message: "A commit message is required".to_string(),
+        });
+    }
+    if input.selection.rule_ids.is_empty()
+        && input.selection.command_ids.is_empty()
+        && input.selection.skill_ids.is_empty()
+    {
+        return Err(AppError::InvalidInput {
+            message: "Select at least one rule, command or skill to push".to_string(),
+        });
+    }
+
+    let _guard = TEAM_REPO_LOCK.lock().await;
+    let mut repo = require_team_repo(&db).await?;
+    let result = push_from(db.clone(), &mut repo, &checkout_dir()?, &input).await?;
+    save_team_repo(&db, &repo).await?;
+    Ok(result)
+}
+
+fn is_due(repo: &TeamRepo) -> bool {
+    repo.enabled
+        && repo.last_pulled_at.is_none_or(|last| {
+            Utc::now() - last >= chrono::Duration::hours(i64::from(repo.interval_hours))
+        })
+}
+
+/// Background loop that pulls the team repository whenever its interval has elapsed.
+pub async fn run_team_repo_loop(db: Arc<Database>) {
+    loop {
+        if get_team_repo(&db).await.is_some_and(|repo| is_due(&repo)) {
+            match pull_team_repo(db.clone()).await {
+                Ok(result) if !result.conflicts.is_empty() => log::warn!(
+                    "Team repository pull left {} conflict(s) for review",
+                    result.conflicts.len()
+                ),
+                Ok(_) => {}
+                Err(e) => log::warn!("Team repository pull failed: {}", e),
+            }
+        }
+        tokio::time::sleep(TEAM_REPO_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BundleSelection, UpdateRuleInput};
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Team", "-c", "user.email=team@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A bare remote plus a working clone standing in for a teammate.
+    fn remote_with_rule(tmp: &Path) -> (String, PathBuf) {
+        let remote = tmp.join("remote.git");
+        let teammate = tmp.join("teammate");
+        fs::create_dir_all(&remote).unwrap();
+        run_git(
+            &remote,
+            &["init", "--quiet", "--bare", "--initial-branch=main"],
+        );
+        let url = url::Url::from_file_path(&remote).unwrap().to_string();
+        run_git(tmp, &["clone", "--quiet", &url, "teammate"]);
+        fs::create_dir_all(teammate.join("rules")).unwrap();
+        fs::write(teammate.join("README.md"), "# Team rules\n").unwrap();
+        fs::write(
+            teammate.join("rules/style.json"),
+            r#"{"name": "Style", "content": "Use tabs", "scope": "global", "enabledAdapters": ["claude-code"]}"#,
+        )
+        .unwrap();
+        run_git(&teammate, &["add", "."]);
+        run_git(&teammate, &["commit", "--quiet", "-m", "Add style"]);
+        run_git(&teammate, &["push", "--quiet", "origin", "HEAD:main"]);
+        (url, teammate)
+    }
+
+    async fn configured(db: &Database, url: &str, push_mode: TeamRepoPushMode) -> TeamRepo {
+        let input = ConfigureTeamRepoInput {
+            url: url.to_string(),
+            branch: None,
+            subpath: None,
+            push_mode,
+            interval_hours: None,
+            options: None,
+        };
+        validate_remote(&input.url).unwrap();
+        let repo = get_team_repo(db).await;
+        assert!(repo.is_none());
+        TeamRepo {
+            url: input.url,
+            branch: DEFAULT_BRANCH.to_string(),
+            subpath: None,
+            push_mode,
+            interval_hours: DEFAULT_TEAM_REPO_INTERVAL_HOURS,
+            enabled: true,
+            options: Default::default(),
+            last_commit: None,
+            last_pulled_at: None,
+            last_pushed_at: None,
+            last_error: None,
+            files: HashMap::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    async fn set_rule_content(db: &Database, id: &str, content: &str) {
+        db.update_rule(
+            id,
+            UpdateRuleInput {
+                content: Some(content.to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pull_push_and_conflicts_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (url, teammate) = remote_with_rule(tmp.path());
+        let checkout = tmp.path().join("checkout");
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let mut repo = configured(&db, &url, TeamRepoPushMode::Commit).await;
+
+        let pulled = pull_into(db.clone(), &mut repo, &checkout).await.unwrap();
+        assert_eq!(pulled.new_count, 1, "README.md is not part of the library");
+        let rule_id = repo.files["rules/style.json"].artifact_id.clone();
+        assert_eq!(
+            db.get_rule_by_id(&rule_id).await.unwrap().content,
+            "Use tabs"
+        );
+        run_git(&checkout, &["config", "user.name", "Me"]);
+        run_git(&checkout, &["config", "user.email", "me@example.com"]);
+
+        // A local edit is kept by pulls and pushed back in place.
+        set_rule_content(&db, &rule_id, "Use spaces").await;
+        let pulled = pull_into(db.clone(), &mut repo, &checkout).await.unwrap();
+        assert_eq!(pulled.local_changes.len(), 1);
+        let input = TeamRepoPushInput {
+            selection: BundleSelection {
+                rule_ids: vec![rule_id.clone()],
+                ..Default::default()
+            },
+            message: "Switch to spaces".to_string(),
+        };
+        let pushed = push_from(db.clone(), &mut repo, &checkout, &input)
+            .await
+            .unwrap();
+        assert_eq!(pushed.files, vec!["rules/style.json"]);
+        assert!(pushed.pull_request_url.is_none());
+        run_git(&teammate, &["pull", "--quiet", "origin", "main"]);
+        let upstream = fs::read_to_string(teammate.join("rules/style.json")).unwrap();
+        assert!(upstream.contains("Use spaces"));
+        assert!(push_from(db.clone(), &mut repo, &checkout, &input)
+            .await
+            .is_err());
+
+        // An upstream edit updates the untouched local rule.
+        fs::write(
+            teammate.join("rules/style.json"),
+            upstream.replace("Use spaces", "Use 2 spaces"),
+        )
+        .unwrap();
+        run_git(&teammate, &["commit", "--quiet", "-am", "Two spaces"]);
+        run_git(&teammate, &["push", "--quiet", "origin", "HEAD:main"]);
+        let pulled = pull_into(db.clone(), &mut repo, &checkout).await.unwrap();
+        assert_eq!(pulled.changed_count, 1);
+        assert_eq!(
+            db.get_rule_by_id(&rule_id).await.unwrap().content,
+            "Use 2 spaces"
+        );
+        assert_eq!(db.get_all_rules().await.unwrap().len(), 1);
+
+        // Edits on both sides are reported and neither is overwritten.
+        set_rule_content(&db, &rule_id, "Use 4 spaces").await;
+        fs::write(
+            teammate.join("rules/style.json"),
+            upstream.replace("Use spaces", "Use 8 spaces"),
+        )
+        .unwrap();
+        run_git(&teammate, &["commit", "--quiet", "-am", "Eight spaces"]);
+        run_git(&teammate, &["push", "--quiet", "origin", "HEAD:main"]);
+        let pulled = pull_into(db.clone(), &mut repo, &checkout).await.unwrap();
+        assert_eq!(pulled.conflicts.len(), 1);
+        assert_eq!(
+            db.get_rule_by_id(&rule_id).await.unwrap().content,
+            "Use 4 spaces"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_branch_push_creates_new_library_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (url, _teammate) = remote_with_rule(tmp.path());
+        let checkout = tmp.path().join("checkout");
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let mut repo = configured(&db, &url, TeamRepoPushMode::Branch).await;
+        pull_into(db.clone(), &mut repo, &checkout).await.unwrap();
+        run_git(&checkout, &["config", "user.name", "Me"]);
+        run_git(&checkout, &["config", "user.email", "me@example.com"]);
+
+        let rule = db
+            .create_rule(crate::models::CreateRuleInput {
+                id: None,
+                name: "Style".to_string(),
+                description: String::new(),
+                content: "Prefer early returns".to_string(),
+                scope: crate::models::Scope::Global,
+                target_paths: None,
+                enabled_adapters: vec![crate::models::AdapterType::Cursor],
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .await
+            .unwrap();
+        let pushed = push_from(
+            db.clone(),
+            &mut repo,
+            &checkout,
+            &TeamRepoPushInput {
+                selection: BundleSelection {
+                    rule_ids: vec![rule.id.clone()],
+                    ..Default::default()
+                },
+                message: "Add early returns".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(pushed.branch.starts_with(PUSH_BRANCH_PREFIX));
+        // The existing `style.json` is not overwritten by a same-named rule.
+        assert_eq!(pushed.files, vec!["rules/style-2.json"]);
+        assert!(!repo.files.contains_key("rules/style-2.json"));
+
+        let remote = tmp.path().join("remote.git");
+        let branches = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&remote)
+            .args(["branch", "--list"])
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&branches.stdout).contains(&pushed.branch));
+    }
+
+    #[tokio::test]
+    async fn test_push_without_a_git_identity_uses_the_fallback() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (url, teammate) = remote_with_rule(tmp.path());
+        let checkout = tmp.path().join("checkout");
+        let db = Arc::new(Database::new_in_memory().await.unwrap());
+        let mut repo = configured(&db, &url, TeamRepoPushMode::Commit).await;
+        pull_into(db.clone(), &mut repo, &checkout).await.unwrap();
+
+        // Stand in for a fresh machine: no global identity, and no guessing one from the
+        // user and host names. The other tests here configure or pass their own identity.
+        let global = tmp.path().join("gitconfig");
+        fs::write(&global, "[user]\n\tuseConfigOnly = true\n").unwrap();
+        std::env::set_var("GIT_CONFIG_GLOBAL", &global);
+        std::env::set_var("GIT_CONFIG_NOSYSTEM", "1");
+
+        let rule_id = repo.files["rules/style.json"].artifact_id.clone();
+        set_rule_content(&db, &rule_id, "Use spaces").await;
+        let pushed = push_from(
+            db.clone(),
+            &mut repo,
+            &checkout,
+            &TeamRepoPushInput {
+                selection: BundleSelection {
+                    rule_ids: vec![rule_id],
+                    ..Default::default()
+                },
+                message: "Switch to spaces".to_string(),
+            },
+        )
+        .await;
+        std::env::remove_var("GIT_CONFIG_GLOBAL");
+        std::env::remove_var("GIT_CONFIG_NOSYSTEM");
+        pushed.unwrap();
+
+        run_git(&teammate, &["pull", "--quiet", "origin", "main"]);
+        let author = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&teammate)
+            .args(["log", "-1", "--format=%an <%ae>"])
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&author.stdout).trim(),
+            format!("{} <{}>", FALLBACK_GIT_NAME, FALLBACK_GIT_EMAIL)
+        );
+    }
+
+    #[test]
+    fn test_remote_validation_and_pull_request_urls() {
+        assert!(validate_remote("https://github.com/acme/rules.git").is_ok());
+        assert!(validate_remote("git@github.com:acme/rules.git").is_ok());
+        assert!(validate_remote("ssh://git@example.com/acme/rules.git").is_ok());
+        assert!(validate_remote("--upload-pack=evil").is_err());
+        assert!(validate_remote("ftp://example.com/rules").is_err());
+        assert!(validate_remote("rules repo").is_err());
+
+        assert_eq!(
+            pull_request_url("git@github.com:acme/rules.git", "main", "ruleweaver/1").as_deref(),
+            Some("https://github.com/acme/rules/compare/main...ruleweaver/1?expand=1")
+        );
+        assert_eq!(
+            pull_request_url("https://github.com/acme/rules", "main", "x").as_deref(),
+            Some("https://github.com/acme/rules/compare/main...x?expand=1")
+        );
+        assert!(pull_request_url("https://gitlab.com/acme/rules.git", "main", "x").is_none());
+    }
+}
@@ -0,0 +1,301 @@
+//! Ordering constraints between rules.
+//!
+//! A rule lists other rules, by id or name, in its `after` and `requires` metadata. Sync
+//! writes each file's rules in an order that puts every rule after the ones it names, and
+//! otherwise keeps the usual order. `after` only orders: the named rules may be absent.
+//! `requires` also asks for the named rules to be enabled and written to every file the
+//! rule is written to; [`lint`] reports where that does not hold, along with references to
+//! unknown rules and ordering cycles. Rules on a cycle keep their usual order.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{Rule, Scope};
+
+pub const AFTER_METADATA_KEY: &str = "after";
+pub const REQUIRES_METADATA_KEY: &str = "requires";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleOrderIssueKind {
+    /// A rule in `after` or `requires` matches no rule
+    UnknownRule,
+    /// A required rule is disabled while the rule requiring it is enabled
+    DisabledDependency,
+    /// A required rule is not written to every file the rule requiring it is
+    MissingFromFile,
+    /// The rule is on a cycle of ordering constraints
+    Cycle,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleOrderIssue {
+    pub kind: RuleOrderIssueKind,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub message: String,
+}
+
+/// The entries of a metadata list, given as an array or a single string.
+fn metadata_list<'a>(rule: &'a Rule, key: &str) -> Vec<&'a str> {
+    match rule.metadata.get(key) {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        Some(Value::String(value)) => vec![value.as_str()],
+        _ => Vec::new(),
+    }
+    .into_iter()
+    .map(str::trim)
+    .filter(|entry| !entry.is_empty())
+    .collect()
+}
+
+/// Indexes of the rules `entry` names: the rule with that id, or else those with that name.
+fn resolve(rules: &[Rule], entry: &str) -> Vec<usize> {
+    if let Some(index) = rules.iter().position(|rule| rule.id == entry) {
+        return vec![index];
+    }
+    (0..rules.len())
+        .filter(|&i| rules[i].name.trim().eq_ignore_ascii_case(entry))
+        .collect()
+}
+
+/// For each rule, the indexes of the rules it must come after.
+fn predecessors(rules: &[Rule]) -> Vec<BTreeSet<usize>> {
+    rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            [AFTER_METADATA_KEY, REQUIRES_METADATA_KEY]
+                .into_iter()
+                .flat_map(|key| metadata_list(rule, key))
+                .flat_map(|entry| resolve(rules, entry))
+                .filter(|&j| j != i)
+                .collect()
+        })
+        .collect()
+}
+
+/// Indexes of `rules` in write order, and the indexes of rules on a cycle.
+fn sort(rules: &[Rule]) -> (Vec<usize>, BTreeSet<usize>) {
+    let before = predecessors(rules);
+    let mut placed = vec![false; rules.len()];
+    let mut order = Vec::with_capacity(rules.len());
+    // Repeatedly place the first rule whose predecessors are all placed, which keeps the
+    // given order wherever the constraints allow.
+    while let Some(next) =
+        (0..rules.len()).find(|&i| !placed[i] && before[i].iter().all(|&j| placed[j]))
+    {
+        placed[next] = true;
+        order.push(next);
+    }
+
+    // What is left is on a cycle or waits for one. Peel off the rules nothing left
+    // waits for until only the cycles remain.
+    let mut cyclic: BTreeSet<usize> = (0..rules.len()).filter(|&i| !placed[i]).collect();
+    while let Some(free) = cyclic
+        .iter()
+        .copied()
+        .find(|&i| !cyclic.iter().any(|&j| before[j].contains(&i)))
+    {
+        cyclic.remove(&free);
+    }
+
+    order.extend((0..rules.len()).filter(|&i| !placed[i]));
+    (order, cyclic)
+}
+
+/// `rules` in the order they are written, each after the rules it names.
+pub fn order(rules: Vec<Rule>) -> Vec<Rule> {
+    let (order, _) = sort(&rules);
+    let mut slots: Vec<Option<Rule>> = rules.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// The files `rule` is written to, as (adapter, target) pairs; `None` is the global file.
+fn files(rule: &Rule) -> Vec<(&'static str, Option<&str>)> {
+    let targets: Vec<Option<&str>> = match rule.scope {
+        Scope::Global => vec![None],
+        Scope::Local => rule
+            .target_paths
+            .iter()
+            .flatten()
+            .map(|path| Some(path.as_str()))
+            .collect(),
+    };
+    rule.enabled_adapters
+        .iter()
+        .flat_map(|adapter| {
+            targets
+                .iter()
+                .map(move |target| (adapter.as_str(), *target))
+        })
+        .collect()
+}
+
+fn issue(kind: RuleOrderIssueKind, rule: &Rule, message: String) -> RuleOrderIssue {
+    RuleOrderIssue {
+        kind,
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        message,
+    }
+}
+
+/// Problems with the ordering constraints of `rules`.
+pub fn lint(rules: &[Rule]) -> Vec<RuleOrderIssue> {
+    let mut issues = Vec::new();
+    for rule in rules {
+        for key in [AFTER_METADATA_KEY, REQUIRES_METADATA_KEY] {
+            for entry in metadata_list(rule, key) {
+                let found = resolve(rules, entry);
+                if found.is_empty() {
+                    issues.push(issue(
+                        RuleOrderIssueKind::UnknownRule,
+                        rule,
+                        format!("'{}' in {} matches no rule", entry, key),
+                    ));
+                    continue;
+                }
+                if key != REQUIRES_METADATA_KEY || !rule.enabled {
+                    continue;
+                }
+                let dependencies: Vec<&Rule> = found.iter().map(|&i| &rules[i]).collect();
+                if !dependencies.iter().any(|d| d.enabled) {
+                    issues.push(issue(
+                        RuleOrderIssueKind::DisabledDependency,
+                        rule,
+                        format!("Required rule '{}' is disabled", dependencies[0].name),
+                    ));
+                    continue;
+                }
+                let covered: BTreeSet<(&str, Option<&str>)> = dependencies
+                    .iter()
+                    .filter(|d| d.enabled)
+                    .flat_map(|d| files(d))
+                    .collect();
+                let missing: Vec<String> = files(rule)
+                    .into_iter()
+                    .filter(|file| !covered.contains(file))
+                    .map(|(adapter, target)| match target {
+                        Some(target) => format!("{} in {}", adapter, target),
+                        None => format!("{} (global)", adapter),
+                    })
+                    .collect();
+                if !missing.is_empty() {
+                    issues.push(issue(
+                        RuleOrderIssueKind::MissingFromFile,
+                        rule,
+                        format!(
+                            "Required rule '{}' is not written for {}",
+                            dependencies[0].name,
+                            missing.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    let (_, cyclic) = sort(rules);
+    let names: Vec<&str> = cyclic.iter().map(|&i| rules[i].name.as_str()).collect();
+    for &i in &cyclic {
+        issues.push(issue(
+            RuleOrderIssueKind::Cycle,
+            &rules[i],
+            format!(
+                "Ordering constraints form a cycle among: {}",
+                names.join(", ")
+            ),
+        ));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, Metadata};
+    use chrono::Utc;
+
+    fn rule(name: &str, after: &[&str], requires: &[&str]) -> Rule {
+        let mut metadata = Metadata::new();
+        if !after.is_empty() {
+            metadata.insert(AFTER_METADATA_KEY, after.to_vec());
+        }
+        if !requires.is_empty() {
+            metadata.insert(REQUIRES_METADATA_KEY, requires.to_vec());
+        }
+        Rule {
+            id: format!("id-{}", name.to_lowercase()),
+            name: name.to_string(),
+            description: String::new(),
+            content: String::new(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn names(rules: &[Rule]) -> Vec<&str> {
+        rules.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_order_puts_rules_after_what_they_name_and_keeps_the_rest() {
+        let rules = vec![
+            rule("Override", &[], &["Base"]),
+            rule("Other", &[], &[]),
+            rule("Base", &["id-missing"], &[]),
+            rule("Late", &["override"], &[]),
+        ];
+        assert_eq!(
+            names(&order(rules)),
+            vec!["Other", "Base", "Override", "Late"]
+        );
+        assert!(lint(&[rule("Base", &[], &[]), rule("Override", &[], &["Base"])]).is_empty());
+    }
+
+    #[test]
+    fn test_lint_reports_cycles_and_broken_requirements() {
+        let mut disabled = rule("Disabled", &[], &[]);
+        disabled.enabled = false;
+        let mut cursor_only = rule("Cursor only", &[], &[]);
+        cursor_only.enabled_adapters = vec![AdapterType::Cursor];
+        let rules = vec![
+            rule("A", &["B"], &[]),
+            rule("B", &["A"], &[]),
+            rule("After cycle", &["A"], &[]),
+            rule("Needs", &["Nowhere"], &["Disabled"]),
+            rule("Needs cursor", &[], &["Cursor only"]),
+            disabled,
+            cursor_only,
+        ];
+
+        let issues = lint(&rules);
+        let kinds: Vec<(&str, RuleOrderIssueKind)> = issues
+            .iter()
+            .map(|issue| (issue.rule_name.as_str(), issue.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Needs", RuleOrderIssueKind::UnknownRule),
+                ("Needs", RuleOrderIssueKind::DisabledDependency),
+                ("Needs cursor", RuleOrderIssueKind::MissingFromFile),
+                ("A", RuleOrderIssueKind::Cycle),
+                ("B", RuleOrderIssueKind::Cycle),
+            ]
+        );
+        assert_eq!(
+            names(&order(rules))[..3],
+            ["Disabled", "Needs", "Cursor only"]
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Finding the rules that matter to a task.
+//!
+//! Agents call the `search_rules` MCP tool to fetch the few rules relevant to what they are
+//! doing instead of reading whole rule files. Keyword search uses the full-text index of
+//! rule names, descriptions and content. Semantic search ranks rules by how close their
+//! embedding is to the query's, so a question about reporting errors finds a rule on
+//! logging that shares none of its words; it needs an embedding model, see
+//! [`crate::ai::embedding_provider`]. Embeddings are cached under the hash of the text they
+//! were computed from, so only new and edited rules are sent to the model. Disabled rules
+//! are never returned.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::ai::EmbeddingProvider;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{ParseEnumError, Rule};
+
+pub const DEFAULT_LIMIT: u32 = 5;
+pub const MAX_LIMIT: u32 = 50;
+
+/// Rules embedded per request to the embedding model.
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    #[default]
+    Keyword,
+    Semantic,
+}
+
+impl FromStr for SearchMode {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "keyword" => Ok(Self::Keyword),
+            "semantic" => Ok(Self::Semantic),
+            _ => Err(ParseEnumError),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSearchHit {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub content: String,
+    /// Higher is more relevant; only comparable within one search
+    pub score: f64,
+}
+
+impl RuleSearchHit {
+    fn new(rule: Rule, score: f64) -> Self {
+        Self {
+            id: rule.id,
+            name: rule.name,
+            description: rule.description,
+            content: rule.content,
+            score,
+        }
+    }
+}
+
+/// An FTS5 query matching any word of `text`, each as a prefix, or `None` if it has no
+/// words. Words are quoted, so operators and punctuation in `text` are taken literally.
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// The enabled rules most relevant to `query`, best first.
+pub async fn search(
+    db: &Database,
+    query: &str,
+    mode: SearchMode,
+    limit: u32,
+) -> Result<Vec<RuleSearchHit>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: "Search query cannot be empty".to_string(),
+        });
+    }
+    let limit = limit.clamp(1, MAX_LIMIT);
+    match mode {
+        SearchMode::Keyword => keyword(db, query, limit).await,
+        SearchMode::Semantic => {
+            let provider = crate::ai::embedding_provider(db).await?;
+            semantic(db, provider.as_ref(), query, limit).await
+        }
+    }
+}
+
+async fn keyword(db: &Database, query: &str, limit: u32) -> Result<Vec<RuleSearchHit>> {
+    let Some(fts) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let mut hits = Vec::new();
+    for (id, bm25) in db.search_rules_fts(&fts, limit).await? {
+        if let Ok(rule) = db.get_rule_by_id(&id).await {
+            hits.push(RuleSearchHit::new(rule, -bm25));
+        }
+    }
+    Ok(hits)
+}
+
+fn embedding_text(rule: &Rule) -> String {
+    format!("{}\n{}\n\n{}", rule.name, rule.description, rule.content)
+}
+
+fn text_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+async fn embed(provider: &dyn EmbeddingProvider, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+    let vectors = provider.embed(texts).await?;
+    if vectors.len() != texts.len() {
+        return Err(AppError::Internal {
+            message: format!(
+                "The embedding model returned {} vectors for {} texts",
+                vectors.len(),
+                texts.len()
+            ),
+        });
+    }
+    Ok(vectors)
+}
+
+/// The enabled rules closest in meaning to `query`, embedding the rules not yet cached.
+pub async fn semantic(
+    db: &Database,
+    provider: &dyn EmbeddingProvider,
+    query: &str,
+    limit: u32,
+) -> Result<Vec<RuleSearchHit>> {
+    let rules: Vec<Rule> = db
+        .get_all_rules()
+        .await?
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .collect();
+    let mut cached = db.get_rule_embeddings(provider.model()).await?;
+
+    let mut vectors = Vec::with_capacity(rules.len());
+    let mut stale = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        let text = embedding_text(rule);
+        let hash = text_hash(&text);
+        match cached.remove(&rule.id) {
+            Some((cached_hash, vector)) if cached_hash == hash => vectors.push(vector),
+            _ => {
+                vectors.push(Vec::new());
+                stale.push((index, hash, text));
+            }
+        }
+    }
+    for batch in stale.chunks(EMBEDDING_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, _, text)| text.clone()).collect();
+        let embedded = embed(provider, &texts).await?;
+        let mut fresh = Vec::with_capacity(batch.len());
+        for ((index, hash, _), vector) in batch.iter().zip(embedded) {
+            fresh.push((rules[*index].id.clone(), hash.clone(), vector.clone()));
+            vectors[*index] = vector;
+        }
+        db.save_rule_embeddings(provider.model(), fresh).await?;
+    }
+
+    let query_vector = embed(provider, &[query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+    let mut hits: Vec<RuleSearchHit> = rules
+        .into_iter()
+        .zip(&vectors)
+        .map(|(rule, vector)| {
+            let score = cosine_similarity(&query_vector, vector);
+            RuleSearchHit::new(rule, score)
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit as usize);
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::Embeddings;
+    use crate::models::{AdapterType, CreateRuleInput, Scope, UpdateRuleInput};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Embeds a text as how often it mentions each of a few topics.
+    struct TopicEmbeddings {
+        texts_embedded: AtomicUsize,
+    }
+
+    impl EmbeddingProvider for TopicEmbeddings {
+        fn model(&self) -> &str {
+            "topics"
+        }
+
+        fn embed<'a>(&'a self, texts: &'a [String]) -> Embeddings<'a> {
+            self.texts_embedded.fetch_add(texts.len(), Ordering::SeqCst);
+            let topics = [
+                &["log", "error", "fail"][..],
+                &["test", "assert"][..],
+                &["tab", "indent"][..],
+            ];
+            let vectors = texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    topics
+                        .iter()
+                        .map(|words| {
+                            words.iter().map(|w| text.matches(w).count()).sum::<usize>() as f32
+                        })
+                        .collect()
+                })
+                .collect();
+            Box::pin(async move { Ok(vectors) })
+        }
+    }
+
+    async fn create(db: &Database, name: &str, content: &str) -> Rule {
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: name.to_string(),
+            description: String::new(),
+            content: content.to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap()
+    }
+
+    fn names(hits: &[RuleSearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fts_query_quotes_words() {
+        assert_eq!(
+            fts_query("error-handling NOT \"x\"").as_deref(),
+            Some("\"error\"* OR \"handling\"* OR \"NOT\"* OR \"x\"*")
+        );
+        assert_eq!(fts_query(" *-- "), None);
+    }
+
+    #[tokio::test]
+    async fn test_keyword_search_follows_rule_changes() {
+        let db = Database::new_in_memory().await.unwrap();
+        let logging = create(&db, "Logging", "Log every failed request.").await;
+        create(&db, "Testing", "Write a test for each bug fix.").await;
+
+        let hits = search(&db, "logging requests", SearchMode::Keyword, 5)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Logging"]);
+
+        db.update_rule(
+            &logging.id,
+            UpdateRuleInput {
+                content: Some("Report errors to the tracker.".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let hits = search(&db, "request", SearchMode::Keyword, 5)
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+
+        db.toggle_rule(&logging.id, false).await.unwrap();
+        let hits = search(&db, "tracker", SearchMode::Keyword, 5)
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+        assert!(search(&db, "  ", SearchMode::Keyword, 5).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_reuses_cached_embeddings() {
+        let db = Database::new_in_memory().await.unwrap();
+        create(&db, "Errors", "Log failures with context.").await;
+        let tabs = create(&db, "Indentation", "Indent with tabs.").await;
+        let provider = TopicEmbeddings {
+            texts_embedded: AtomicUsize::new(0),
+        };
+
+        let hits = semantic(&db, &provider, "what to do when a call fails", 1)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Errors"]);
+        assert_eq!(provider.texts_embedded.load(Ordering::SeqCst), 3);
+
+        db.update_rule(
+            &tabs.id,
+            UpdateRuleInput {
+                content: Some("Indent with tabs; assert in tests.".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let hits = semantic(&db, &provider, "test assertions", 1)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Indentation"]);
+        // The edited rule and the query; the other rule came from the cache
+        assert_eq!(provider.texts_embedded.load(Ordering::SeqCst), 5);
+    }
+}
@@ -0,0 +1,241 @@
+//! Sandbox profiles for command execution.
+//!
+//! Environment scrubbing works everywhere. CPU and memory limits use `setrlimit` on Unix
+//! and the process's Job Object on Windows. Network and filesystem restrictions wrap the
+//! shell in bubblewrap (`bwrap`) on Linux and `sandbox-exec` on macOS; elsewhere, or when
+//! the tool is missing, the run is refused instead of going ahead unrestricted.
+
+use std::path::Path;
+
+use tokio::process::Command as TokioCommand;
+
+use crate::error::{AppError, Result};
+use crate::models::SandboxProfile;
+
+/// Variables kept when a profile scrubs the environment; enough for shells and common
+/// tools to find their binaries, home directory and temp space.
+const SCRUBBED_ENV_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "LANG",
+    "LC_ALL",
+    "TERM",
+    "TMPDIR",
+    "TMP",
+    "TEMP",
+    "SYSTEMROOT",
+    "WINDIR",
+    "COMSPEC",
+    "PATHEXT",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+
+pub fn validate_profile(profile: &SandboxProfile) -> Result<()> {
+    for path in &profile.read_only_paths {
+        let p = Path::new(path);
+        if !p.is_absolute() || path.contains("..") {
+            return Err(AppError::Validation(format!(
+                "Read-only sandbox paths must be absolute without traversal: {}",
+                path
+            )));
+        }
+    }
+    if profile.cpu_time_secs == Some(0) || profile.memory_mb == Some(0) {
+        return Err(AppError::Validation(
+            "Sandbox CPU and memory limits must be greater than zero".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether the profile needs an OS sandboxing tool around the shell.
+fn needs_wrapper(profile: &SandboxProfile) -> bool {
+    profile.deny_network || !profile.read_only_paths.is_empty()
+}
+
+/// Builds the process for `program args...`, wrapped in the platform sandbox tool when the
+/// profile restricts network or filesystem access.
+pub fn wrap_command(
+    program: &str,
+    args: &[&str],
+    profile: Option<&SandboxProfile>,
+) -> Result<TokioCommand> {
+    let Some(profile) = profile.filter(|p| needs_wrapper(p)) else {
+        let mut cmd = TokioCommand::new(program);
+        cmd.args(args);
+        return Ok(cmd);
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        let bwrap = find_in_path("bwrap").ok_or_else(|| AppError::InvalidInput {
+            message: "This command's sandbox needs bubblewrap (bwrap), which was not found"
+                .to_string(),
+        })?;
+        let mut cmd = TokioCommand::new(bwrap);
+        cmd.args(bwrap_args(profile))
+            .arg("--")
+            .arg(program)
+            .args(args);
+        Ok(cmd)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut cmd = TokioCommand::new("/usr/bin/sandbox-exec");
+        cmd.arg("-p")
+            .arg(seatbelt_profile(profile))
+            .arg(program)
+            .args(args);
+        Ok(cmd)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = (program, args, profile);
+        Err(AppError::InvalidInput {
+            message: "Network and filesystem sandboxing are not supported on this platform"
+                .to_string(),
+        })
+    }
+}
+
+/// The inherited variables a process keeps when its profile scrubs the environment.
+pub fn scrubbed_env() -> Vec<(String, String)> {
+    SCRUBBED_ENV_ALLOWLIST
+        .iter()
+        .filter_map(|name| Some((name.to_string(), std::env::var(name).ok()?)))
+        .collect()
+}
+
+/// Applies environment scrubbing and, on Unix, resource limits. Must be called before the
+/// command's own variables are added.
+pub fn apply(cmd: &mut TokioCommand, profile: Option<&SandboxProfile>) {
+    let Some(profile) = profile else {
+        return;
+    };
+
+    if profile.scrub_env {
+        cmd.env_clear();
+        for name in SCRUBBED_ENV_ALLOWLIST {
+            if let Some(value) = std::env::var_os(name) {
+                cmd.env(name, value);
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        let cpu_time_secs = profile.cpu_time_secs;
+        let memory_bytes = profile.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+        if cpu_time_secs.is_some() || memory_bytes.is_some() {
+            // SAFETY: the closure runs between fork and exec and only calls setrlimit,
+            // which is async-signal-safe, on values captured by copy.
+            unsafe {
+                cmd.pre_exec(move || {
+                    if let Some(secs) = cpu_time_secs {
+                        check(libc::setrlimit(libc::RLIMIT_CPU, &rlimit(secs)))?;
+                    }
+                    if let Some(bytes) = memory_bytes {
+                        check(libc::setrlimit(libc::RLIMIT_AS, &rlimit(bytes)))?;
+                    }
+                    Ok(())
+                });
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn rlimit(value: u64) -> libc::rlimit {
+    libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    }
+}
+
+#[cfg(unix)]
+fn check(status: libc::c_int) -> std::io::Result<()> {
+    if status != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn find_in_path(name: &str) -> Option<std::path::PathBuf> {
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// bubblewrap options: the host filesystem stays visible, with the listed paths
+/// remounted read-only and, optionally, no network.
+#[cfg(target_os = "linux")]
+fn bwrap_args(profile: &SandboxProfile) -> Vec<String> {
+    let mut args: Vec<String> = ["--die-with-parent", "--dev-bind", "/", "/"]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    if profile.deny_network {
+        args.push("--unshare-net".to_string());
+    }
+    for path in &profile.read_only_paths {
+        args.extend(["--ro-bind".to_string(), path.clone(), path.clone()]);
+    }
+    args
+}
+
+#[cfg(target_os = "macos")]
+fn seatbelt_profile(profile: &SandboxProfile) -> String {
+    let mut rules = String::from("(version 1)\n(allow default)\n");
+    if profile.deny_network {
+        rules.push_str("(deny network*)\n");
+    }
+    for path in &profile.read_only_paths {
+        let escaped = path.replace('\\', "\\\\").replace('"', "\\\"");
+        rules.push_str(&format!("(deny file-write* (subpath \"{}\"))\n", escaped));
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_are_validated() {
+        let mut profile = SandboxProfile {
+            read_only_paths: vec!["relative/dir".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_profile(&profile).is_err());
+
+        profile.read_only_paths = vec![std::env::temp_dir().to_string_lossy().to_string()];
+        assert!(validate_profile(&profile).is_ok());
+
+        profile.memory_mb = Some(0);
+        assert!(validate_profile(&profile).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn bwrap_remounts_read_only_paths_and_drops_network() {
+        let profile = SandboxProfile {
+            read_only_paths: vec!["/srv/repo".to_string()],
+            deny_network: true,
+            ..Default::default()
+        };
+        let args = bwrap_args(&profile);
+        assert!(args.contains(&"--unshare-net".to_string()));
+        let ro = args.iter().position(|a| a == "--ro-bind").unwrap();
+        assert_eq!(&args[ro + 1..ro + 3], ["/srv/repo", "/srv/repo"]);
+        // The read-only bind must come after the root bind so it takes precedence.
+        assert!(ro > args.iter().position(|a| a == "--dev-bind").unwrap());
+    }
+}
@@ -0,0 +1,224 @@
+//! Runs commands on their cron schedules while the app or headless MCP server is up.
+//!
+//! Schedules use the standard five-field cron syntax (an optional leading seconds field is
+//! also accepted) and are evaluated in local time. Runs missed while the app was closed are
+//! not caught up.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+
+use chrono::{DateTime, Local, TimeZone};
+use cron::Schedule;
+use parking_lot::Mutex;
+
+use crate::constants::timing::SCHEDULER_TICK_INTERVAL;
+use crate::database::{Database, ExecutionLogInput};
+use crate::error::{AppError, Result};
+use crate::execution::{run_command, RunCommandOptions};
+use crate::models::{Command, CommandArgument, FailureClass};
+
+const TRIGGERED_BY: &str = "schedule";
+
+/// Commands whose scheduled run is still in progress; a due run is skipped until it ends.
+static RUNNING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+pub fn parse_schedule(expression: &str) -> Result<Schedule> {
+    let trimmed = expression.trim();
+    // The cron crate expects a seconds field; classic five-field expressions run on the minute.
+    let normalized = if trimmed.split_whitespace().count() == 5 {
+        format!("0 {}", trimmed)
+    } else {
+        trimmed.to_string()
+    };
+    Schedule::from_str(&normalized)
+        .map_err(|e| AppError::Validation(format!("Invalid schedule '{}': {}", expression, e)))
+}
+
+/// A scheduled run has nobody to supply arguments, so required ones need defaults.
+pub fn validate_schedule(schedule: Option<&str>, arguments: &[CommandArgument]) -> Result<()> {
+    let Some(schedule) = schedule.filter(|s| !s.trim().is_empty()) else {
+        return Ok(());
+    };
+    parse_schedule(schedule)?;
+
+    let missing: Vec<&str> = arguments
+        .iter()
+        .filter(|a| a.required && a.default_value.as_deref().unwrap_or_default().is_empty())
+        .map(|a| a.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Scheduled commands need default values for required arguments: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `schedule` fires in `(since, now]`.
+fn is_due<Tz: TimeZone>(schedule: &Schedule, since: &DateTime<Tz>, now: &DateTime<Tz>) -> bool {
+    schedule
+        .after(since)
+        .next()
+        .is_some_and(|next| next <= *now)
+}
+
+async fn run_scheduled(db: &Database, cmd: &Command, app: Option<&tauri::AppHandle>) {
+    let outcome = run_command(
+        db,
+        cmd,
+        &HashMap::new(),
+        RunCommandOptions {
+            triggered_by: TRIGGERED_BY,
+            adapter_context: Some("scheduler"),
+            on_output: None,
+            cancel: None,
+        },
+    )
+    .await;
+
+    let failure = match outcome {
+        Ok((0, _, _, duration_ms)) => {
+            log::info!(
+                "Scheduled command '{}' succeeded ({}ms)",
+                cmd.name,
+                duration_ms
+            );
+            None
+        }
+        Ok((exit_code, _, _, _)) => Some(format!("exited with code {}", exit_code)),
+        Err(e) => {
+            // The command never started, so execute_and_log wrote nothing; record why.
+            let message = e.to_string();
+            let _ = db
+                .add_execution_log(&ExecutionLogInput {
+                    command_id: &cmd.id,
+                    command_name: &cmd.name,
+                    arguments_json: "{}",
+                    stdout: "",
+                    stderr: &message,
+                    exit_code: -1,
+                    duration_ms: 0,
+                    triggered_by: TRIGGERED_BY,
+                    failure_class: Some(FailureClass::ValidationError.as_str()),
+                    adapter_context: Some("scheduler"),
+                    is_redacted: false,
+                    attempt_number: 1,
+                    structured_output: None,
+                    environment: None,
+                })
+                .await;
+            Some(message)
+        }
+    };
+
+    if let Some(reason) = failure {
+        log::warn!("Scheduled command '{}' failed: {}", cmd.name, reason);
+        if let Some(app) = app {
+            use tauri_plugin_notification::NotificationExt;
+            app.notification()
+                .builder()
+                .title("Scheduled Command Failed")
+                .body(format!("'{}' {}", cmd.name, reason))
+                .show()
+                .ok();
+        }
+    }
+}
+
+/// Background loop that starts every command whose schedule fired since the last tick.
+/// Failures raise a desktop notification when `app` is available.
+pub async fn run_scheduler_loop(db: Arc<Database>, app: Option<tauri::AppHandle>) {
+    let mut last_tick = Local::now();
+    loop {
+        tokio::time::sleep(SCHEDULER_TICK_INTERVAL).await;
+        let now = Local::now();
+
+        let commands = match db.get_all_commands().await {
+            Ok(commands) => commands,
+            Err(e) => {
+                log::error!("Scheduler failed to load commands: {}", e);
+                continue;
+            }
+        };
+
+        for cmd in commands {
+            let Some(expression) = cmd.schedule.as_deref() else {
+                continue;
+            };
+            let schedule = match parse_schedule(expression) {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    log::warn!("Skipping schedule for '{}': {}", cmd.name, e);
+                    continue;
+                }
+            };
+            if !is_due(&schedule, &last_tick, &now) {
+                continue;
+            }
+            if !RUNNING.lock().insert(cmd.id.clone()) {
+                log::info!(
+                    "Skipping scheduled run of '{}': previous run still in progress",
+                    cmd.name
+                );
+                continue;
+            }
+
+            let db = Arc::clone(&db);
+            let app = app.clone();
+            tokio::spawn(async move {
+                run_scheduled(&db, &cmd, app.as_ref()).await;
+                RUNNING.lock().remove(&cmd.id);
+            });
+        }
+
+        last_tick = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn argument(required: bool, default_value: Option<&str>) -> CommandArgument {
+        CommandArgument {
+            name: "target".to_string(),
+            description: String::new(),
+            arg_type: crate::models::ArgumentType::String,
+            required,
+            default_value: default_value.map(str::to_string),
+            options: None,
+            pattern: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    #[test]
+    fn five_field_expressions_are_accepted() {
+        assert!(parse_schedule("0 3 * * *").is_ok());
+        assert!(parse_schedule("30 0 3 * * Mon-Fri").is_ok());
+        assert!(parse_schedule("every night").is_err());
+    }
+
+    #[test]
+    fn required_arguments_need_defaults() {
+        assert!(validate_schedule(None, &[argument(true, None)]).is_ok());
+        assert!(validate_schedule(Some(""), &[argument(true, None)]).is_ok());
+        assert!(validate_schedule(Some("0 3 * * *"), &[argument(true, Some("all"))]).is_ok());
+        assert!(validate_schedule(Some("0 3 * * *"), &[argument(false, None)]).is_ok());
+        let err = validate_schedule(Some("0 3 * * *"), &[argument(true, None)]).unwrap_err();
+        assert!(err.to_string().contains("target"));
+    }
+
+    #[test]
+    fn due_only_when_a_run_falls_inside_the_window() {
+        let schedule = parse_schedule("0 3 * * *").unwrap();
+        let at = |h: u32, m: u32| Utc.with_ymd_and_hms(2026, 1, 1, h, m, 0).unwrap();
+        assert!(is_due(&schedule, &at(2, 59), &at(3, 0)));
+        assert!(!is_due(&schedule, &at(3, 0), &at(3, 1)));
+        assert!(!is_due(&schedule, &at(1, 0), &at(2, 0)));
+    }
+}
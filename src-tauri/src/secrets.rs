@@ -0,0 +1,145 @@
+//! Secrets vault for command environment variables.
+//!
+//! Values are encrypted with AES-256-GCM before they reach the database. The vault key
+//! lives in the OS keychain and is created on first use, so a copied database or backup
+//! does not expose the secrets on its own.
+
+use std::sync::LazyLock;
+
+use parking_lot::Mutex;
+
+use crate::database::{decrypt, encrypt, Database, DatabaseKey};
+use crate::error::{AppError, Result};
+use crate::keychain;
+
+const MAX_SECRET_NAME_LENGTH: usize = 128;
+const MAX_SECRET_VALUE_LENGTH: usize = 64 * 1024;
+
+/// The vault key, cached after the first keychain lookup.
+static VAULT_KEY: LazyLock<Mutex<Option<DatabaseKey>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Returns the vault key, generating and storing one when `create` is set and none exists.
+async fn vault_key(create: bool) -> Result<Option<DatabaseKey>> {
+    if let Some(key) = VAULT_KEY.lock().clone() {
+        return Ok(Some(key));
+    }
+
+    let key = match keychain::run_blocking(keychain::load_vault_key).await? {
+        Some(key) => key,
+        None if create => {
+            let key = DatabaseKey::generate()?;
+            let stored = key.clone();
+            keychain::run_blocking(move || keychain::store_vault_key(&stored)).await?;
+            key
+        }
+        None => return Ok(None),
+    };
+    *VAULT_KEY.lock() = Some(key.clone());
+    Ok(Some(key))
+}
+
+/// Secret names double as environment variable names.
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SECRET_NAME_LENGTH
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid secret name '{}': use letters, digits and underscores, not starting with a digit (max {} characters)",
+            name, MAX_SECRET_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_secret_names(names: &[String]) -> Result<()> {
+    for name in names {
+        validate_secret_name(name)?;
+    }
+    Ok(())
+}
+
+pub async fn set_secret(db: &Database, name: &str, value: &str) -> Result<()> {
+    validate_secret_name(name)?;
+    if value.is_empty() {
+        return Err(AppError::Validation(
+            "Secret value cannot be empty".to_string(),
+        ));
+    }
+    if value.len() > MAX_SECRET_VALUE_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Secret value too long (max {} bytes)",
+            MAX_SECRET_VALUE_LENGTH
+        )));
+    }
+
+    let key = vault_key(true).await?.ok_or_else(|| AppError::Internal {
+        message: "Secrets vault key unavailable".to_string(),
+    })?;
+    let ciphertext = encrypt(&key, value.as_bytes())?;
+    db.upsert_secret(name, ciphertext).await
+}
+
+/// Decrypts the named secrets as `(name, value)` environment pairs. Fails if any is unset.
+pub async fn resolve_secrets(db: &Database, names: &[String]) -> Result<Vec<(String, String)>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stored = Vec::with_capacity(names.len());
+    let mut missing = Vec::new();
+    for name in names {
+        match db.get_secret_ciphertext(name).await? {
+            Some(ciphertext) => stored.push((name.clone(), ciphertext)),
+            None => missing.push(name.as_str()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: format!("Missing secrets: {}", missing.join(", ")),
+        });
+    }
+
+    let key = vault_key(false)
+        .await?
+        .ok_or_else(|| AppError::Encryption {
+            message: "Secrets vault key not found in the OS keychain".to_string(),
+        })?;
+    stored
+        .into_iter()
+        .map(|(name, ciphertext)| {
+            let value = String::from_utf8(decrypt(&key, &ciphertext)?).map_err(|_| {
+                AppError::Encryption {
+                    message: format!("Secret '{}' is not valid UTF-8", name),
+                }
+            })?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_names_must_be_env_var_names() {
+        assert!(validate_secret_name("OPENAI_API_KEY").is_ok());
+        assert!(validate_secret_name("_token2").is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name("2FA").is_err());
+        assert!(validate_secret_name("MY-KEY").is_err());
+        assert!(validate_secret_name("KEY=1").is_err());
+    }
+
+    #[tokio::test]
+    async fn resolving_unset_secrets_lists_them() {
+        let db = Database::new_in_memory().await.unwrap();
+        let err = resolve_secrets(&db, &["GITHUB_TOKEN".to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("GITHUB_TOKEN"));
+        assert!(resolve_secrets(&db, &[]).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,474 @@
+//! Typed access to the user preferences in the settings table.
+//!
+//! Preferences are still stored one key per row so older releases, exports and cloud sync
+//! keep reading them. This module is the only place that knows how each one is encoded:
+//! [`load`] reads them into [`AppSettings`], and [`update`] and [`set`] validate a change,
+//! write the canonical encoding and report which keys changed. Keys that are not listed
+//! here are bookkeeping owned by their modules and are not writable through the settings
+//! commands.
+
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{AdapterType, AppSettings};
+
+/// Emitted with [`crate::models::SettingsChanged`] after settings are written.
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// `true` or `false`
+    Bool,
+    /// A decimal integer
+    Number,
+    /// The raw text; no row when unset
+    Text,
+    /// A JSON document
+    Json,
+    /// Comma-separated values
+    List,
+}
+
+const FIELDS: &[(&str, Encoding)] = &[
+    (crate::sync::ADAPTER_SETTINGS_KEY, Encoding::Json),
+    ("default_adapters", Encoding::Json),
+    (crate::commands::LOCAL_RULE_PATHS_KEY, Encoding::Json),
+    (crate::MCP_AUTO_START_KEY, Encoding::Bool),
+    (crate::MINIMIZE_TO_TRAY_KEY, Encoding::Bool),
+    (crate::drift::STARTUP_DRIFT_CHECK_KEY, Encoding::Bool),
+    (crate::drift::DRIFT_MONITOR_ENABLED_KEY, Encoding::Bool),
+    (crate::file_storage::watcher::WATCH_MODE_KEY, Encoding::Text),
+    (crate::backup::AUTO_BACKUP_ENABLED_KEY, Encoding::Bool),
+    (crate::backup::AUTO_BACKUP_RETENTION_KEY, Encoding::Number),
+    (
+        crate::log_retention::LOG_RETENTION_MAX_ROWS_KEY,
+        Encoding::Number,
+    ),
+    (
+        crate::log_retention::LOG_RETENTION_MAX_AGE_DAYS_KEY,
+        Encoding::Number,
+    ),
+    (
+        crate::artifacts::ARTIFACT_RETENTION_MAX_AGE_DAYS_KEY,
+        Encoding::Number,
+    ),
+    (crate::snapshot::EXPORT_SNAPSHOT_ENABLED_KEY, Encoding::Bool),
+    (crate::snapshot::EXPORT_SNAPSHOT_DIR_KEY, Encoding::Text),
+    (
+        crate::snapshot::EXPORT_SNAPSHOT_INTERVAL_HOURS_KEY,
+        Encoding::Number,
+    ),
+    (
+        crate::snapshot::EXPORT_SNAPSHOT_RETENTION_KEY,
+        Encoding::Number,
+    ),
+    (crate::metrics::PROMETHEUS_ENDPOINT_KEY, Encoding::Bool),
+    (crate::rule_import::catalog::CATALOG_URL_KEY, Encoding::Text),
+    (
+        crate::templates::marketplace::SKILL_TEMPLATE_INDEX_URL_KEY,
+        Encoding::Text,
+    ),
+    (crate::mcp::SECRETS_ALLOWLIST_KEY, Encoding::List),
+    (crate::export_manifest::SIGN_EXPORTS_KEY, Encoding::Bool),
+    (
+        crate::export_manifest::TRUSTED_EXPORT_KEYS_KEY,
+        Encoding::Json,
+    ),
+    (
+        crate::redaction::REDACT_BUILTIN_PATTERNS_KEY,
+        Encoding::Bool,
+    ),
+    (crate::redaction::REDACTION_PATTERNS_KEY, Encoding::Json),
+    (crate::redaction::REDACTED_ENV_VARS_KEY, Encoding::Json),
+    (crate::ai::AI_ENDPOINT_KEY, Encoding::Text),
+    (crate::ai::AI_MODEL_KEY, Encoding::Text),
+    (crate::ai::AI_API_KEY_SECRET_KEY, Encoding::Text),
+    (crate::ai::EMBEDDING_ENDPOINT_KEY, Encoding::Text),
+    (crate::ai::EMBEDDING_MODEL_KEY, Encoding::Text),
+    (crate::ai::EMBEDDING_API_KEY_SECRET_KEY, Encoding::Text),
+    (crate::localization::ADAPTER_LANGUAGES_KEY, Encoding::Json),
+];
+
+/// Rows other modules write for their own bookkeeping.
+const BOOKKEEPING_KEYS: &[&str] = &[
+    "storage_mode",
+    "ai_tool_import_bootstrap_done",
+    crate::ai::APPROVED_SECRETS_KEY,
+    crate::backup::AUTO_BACKUP_LAST_AT_KEY,
+    crate::cloud_sync::CLOUD_SYNC_KEY,
+    crate::external_mcp::SYNC_STATE_KEY,
+    crate::file_storage::BACKUP_PATH_KEY,
+    crate::file_storage::DUAL_WRITE_STARTED_KEY,
+    crate::file_storage::DUAL_WRITE_UNTIL_KEY,
+    crate::mcp::tokens::MCP_CLIENT_TOKENS_KEY,
+    crate::policy::ORG_POLICY_KEY,
+    crate::reconciliation::SLASH_COMMAND_ROOTS_KEY,
+    crate::reconciliation::protection::PROTECTED_PATHS_KEY,
+    crate::rule_import::IMPORT_HISTORY_KEY,
+    crate::rule_import::IMPORT_SOURCE_MAP_KEY,
+    crate::rule_import::catalog::CATALOG_INSTALLS_KEY,
+    crate::rule_import::mapping::MAPPING_RULES_KEY,
+    crate::rule_import::subscriptions::SUBSCRIPTIONS_KEY,
+    crate::rule_import::team_repo::TEAM_REPO_KEY,
+    crate::sensitive::SENSITIVE_ALLOWLIST_KEY,
+    crate::snapshot::EXPORT_SNAPSHOT_LAST_AT_KEY,
+    crate::templates::marketplace::SKILL_TEMPLATE_INSTALLS_KEY,
+];
+
+/// Whether the app itself stores `key`: a preference or another module's bookkeeping.
+/// Any other row was put there by the user, such as a secret kept as a setting.
+pub fn is_app_owned(key: &str) -> bool {
+    encoding_of(key).is_some()
+        || BOOKKEEPING_KEYS.contains(&key)
+        || crate::models::is_portable_setting(key)
+}
+
+/// Keys of the boolean settings, for the migration that normalizes their stored values.
+pub fn bool_keys() -> impl Iterator<Item = &'static str> {
+    FIELDS
+        .iter()
+        .filter(|(_, encoding)| *encoding == Encoding::Bool)
+        .map(|(key, _)| *key)
+}
+
+/// Reads a stored boolean. Accepts `true`/`false`, `1`/`0`, `yes`/`no` and `on`/`off` in
+/// any case, which older releases and hand-edited databases left behind.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// The field of [`AppSettings`] stored under `key`.
+fn field_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            name.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+fn encoding_of(key: &str) -> Option<Encoding> {
+    FIELDS
+        .iter()
+        .find(|(known, _)| *known == key)
+        .map(|(_, encoding)| *encoding)
+}
+
+fn decode(encoding: Encoding, raw: &str) -> Option<Value> {
+    match encoding {
+        Encoding::Bool => parse_bool(raw).map(Value::Bool),
+        Encoding::Number => raw.trim().parse::<u64>().ok().map(Value::from),
+        Encoding::Text => match raw.trim() {
+            "" => Some(Value::Null),
+            text => Some(Value::String(text.to_string())),
+        },
+        Encoding::Json => serde_json::from_str(raw).ok(),
+        Encoding::List => Some(Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| Value::String(v.to_string()))
+                .collect(),
+        )),
+    }
+}
+
+/// The stored form of `value`, or `None` when the row should be removed.
+fn encode(encoding: Encoding, value: &Value) -> Option<String> {
+    match (encoding, value) {
+        (_, Value::Null) => None,
+        (Encoding::Text, Value::String(text)) => Some(text.clone()),
+        (Encoding::List, Value::Array(values)) => Some(
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        (_, value) => Some(value.to_string()),
+    }
+}
+
+/// Whether `value` is a valid value for the field `name` on its own.
+fn fits_field(name: &str, value: &Value) -> bool {
+    let mut single = Map::new();
+    single.insert(name.to_string(), value.clone());
+    serde_json::from_value::<AppSettings>(Value::Object(single)).is_ok()
+}
+
+fn from_stored(stored: &std::collections::HashMap<String, String>) -> AppSettings {
+    let mut fields = Map::new();
+    for (key, encoding) in FIELDS {
+        let Some(raw) = stored.get(*key) else {
+            continue;
+        };
+        let name = field_name(key);
+        match decode(*encoding, raw) {
+            Some(value) if fits_field(&name, &value) => {
+                fields.insert(name, value);
+            }
+            _ => log::warn!("Ignoring invalid value for setting '{}': {}", key, raw),
+        }
+    }
+    serde_json::from_value(Value::Object(fields)).unwrap_or_default()
+}
+
+/// The current settings, with defaults for anything unset or unreadable.
+pub async fn load(db: &Database) -> Result<AppSettings> {
+    Ok(from_stored(&db.get_all_settings().await?))
+}
+
+/// [`load`] for callers that only need a value and carry on with the defaults when the
+/// database cannot be read.
+pub async fn load_or_default(db: &Database) -> AppSettings {
+    load(db).await.unwrap_or_else(|e| {
+        log::warn!("Failed to read settings, using defaults: {}", e);
+        AppSettings::default()
+    })
+}
+
+fn invalid(message: impl Into<String>) -> AppError {
+    AppError::InvalidInput {
+        message: message.into(),
+    }
+}
+
+fn validate_url(key: &str, url: &Option<String>) -> Result<()> {
+    match url {
+        Some(url) if !url.starts_with("https://") && !url.starts_with("http://") => {
+            Err(invalid(format!("Setting '{}' must be an http(s) URL", key)))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate(settings: &AppSettings) -> Result<()> {
+    for adapter in settings
+        .adapter_settings
+        .keys()
+        .chain(settings.default_adapters.iter())
+        .chain(settings.adapter_languages.keys())
+    {
+        // Ids come both in the `claude-code` form and as serialized, `claudecode`.
+        let known = AdapterType::from_str(adapter).is_ok()
+            || serde_json::from_value::<AdapterType>(Value::String(adapter.clone())).is_ok();
+        if !known {
+            return Err(invalid(format!("Unknown adapter '{}'", adapter)));
+        }
+    }
+    for (key, value) in [
+        ("auto_backup_retention", settings.auto_backup_retention),
+        (
+            "export_snapshot_interval_hours",
+            settings.export_snapshot_interval_hours,
+        ),
+        (
+            "export_snapshot_retention",
+            settings.export_snapshot_retention,
+        ),
+    ] {
+        if value == 0 {
+            return Err(invalid(format!("Setting '{}' must be at least 1", key)));
+        }
+    }
+    if let Some(dir) = &settings.export_snapshot_dir {
+        if !std::path::Path::new(dir).is_absolute() {
+            return Err(invalid(
+                "Export snapshot directory must be an absolute path",
+            ));
+        }
+    }
+    for key in &settings.trusted_export_keys {
+        if !crate::export_manifest::is_public_key(key) {
+            return Err(invalid(format!(
+                "'{}' is not an Ed25519 public key in hex",
+                key
+            )));
+        }
+    }
+    for pattern in &settings.redaction_patterns {
+        crate::redaction::validate_pattern(pattern)
+            .map_err(|e| invalid(format!("Invalid redaction pattern '{}': {}", pattern, e)))?;
+    }
+    validate_url("rule_catalog_url", &settings.rule_catalog_url)?;
+    validate_url(
+        "skill_template_index_url",
+        &settings.skill_template_index_url,
+    )?;
+    validate_url(crate::ai::AI_ENDPOINT_KEY, &settings.ai_endpoint)?;
+    validate_url(
+        crate::ai::EMBEDDING_ENDPOINT_KEY,
+        &settings.embedding_endpoint,
+    )?;
+    for name in [
+        &settings.ai_api_key_secret,
+        &settings.embedding_api_key_secret,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        crate::secrets::validate_secret_name(name)?;
+    }
+    for language in settings.adapter_languages.values() {
+        crate::localization::validate_language(language)?;
+    }
+    Ok(())
+}
+
+/// Applies `patch`, an object of [`AppSettings`] fields, to the stored settings. Unknown
+/// fields and invalid values are rejected before anything is written. Returns the new
+/// settings and the keys whose stored value changed.
+pub async fn update(db: &Database, patch: Value) -> Result<(AppSettings, Vec<String>)> {
+    let Value::Object(patch) = patch else {
+        return Err(invalid("Settings must be an object"));
+    };
+    // Only secret names the user picks here may be sent to an AI endpoint.
+    let chosen_secrets: Vec<String> = [
+        crate::ai::AI_API_KEY_SECRET_KEY,
+        crate::ai::EMBEDDING_API_KEY_SECRET_KEY,
+    ]
+    .iter()
+    .filter_map(|key| patch.get(&field_name(key))?.as_str().map(str::to_string))
+    .collect();
+    let current = serde_json::to_value(load(db).await?)?;
+    let mut merged = current.as_object().cloned().unwrap_or_default();
+    merged.extend(patch);
+    let settings: AppSettings = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| invalid(format!("Invalid settings: {}", e)))?;
+    validate(&settings)?;
+
+    let updated = serde_json::to_value(&settings)?;
+    let mut changed = Vec::new();
+    for (key, encoding) in FIELDS {
+        let name = field_name(key);
+        if current.get(&name) == updated.get(&name) {
+            continue;
+        }
+        match updated
+            .get(&name)
+            .and_then(|value| encode(*encoding, value))
+        {
+            Some(stored) => db.set_setting(key, &stored).await?,
+            None => db.delete_setting(key).await?,
+        }
+        changed.push(key.to_string());
+    }
+    for name in &chosen_secrets {
+        crate::ai::approve_secret(db, name.trim()).await?;
+    }
+    Ok((settings, changed))
+}
+
+/// Sets one setting from its stored form, as the string settings command receives it.
+pub async fn set(db: &Database, key: &str, value: &str) -> Result<(AppSettings, Vec<String>)> {
+    let encoding = encoding_of(key).ok_or_else(|| invalid(format!("Unknown setting '{}'", key)))?;
+    let value = decode(encoding, value)
+        .ok_or_else(|| invalid(format!("Invalid value for setting '{}': {}", key, value)))?;
+    let mut patch = Map::new();
+    patch.insert(field_name(key), value);
+    update(db, Value::Object(patch)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_storage::watcher::WatchMode;
+    use serde_json::json;
+
+    #[test]
+    fn test_every_key_maps_to_a_field() {
+        let fields = serde_json::to_value(AppSettings::default()).unwrap();
+        for (key, _) in FIELDS {
+            assert!(fields.get(field_name(key)).is_some(), "{}", key);
+        }
+        assert_eq!(fields.as_object().unwrap().len(), FIELDS.len());
+    }
+
+    #[test]
+    fn test_app_owned_keys_are_not_user_rows() {
+        assert!(is_app_owned(crate::mcp::SECRETS_ALLOWLIST_KEY));
+        assert!(is_app_owned(crate::mcp::tokens::MCP_CLIENT_TOKENS_KEY));
+        assert!(is_app_owned("theme"));
+        assert!(!is_app_owned("github_token"));
+    }
+
+    #[tokio::test]
+    async fn test_load_reads_existing_keys_and_skips_bad_values() {
+        let db = Database::new_in_memory().await.unwrap();
+        db.set_setting("mcp_auto_start", "True").await.unwrap();
+        db.set_setting("minimize_to_tray", "0").await.unwrap();
+        db.set_setting("rule_watcher_mode", "sideways")
+            .await
+            .unwrap();
+        db.set_setting("auto_backup_retention", "three")
+            .await
+            .unwrap();
+        db.set_setting("adapter_settings", r#"{"cursor":false}"#)
+            .await
+            .unwrap();
+        db.set_setting("mcp_secrets_allowlist", "api_key, token")
+            .await
+            .unwrap();
+
+        let settings = load(&db).await.unwrap();
+        assert!(settings.mcp_auto_start);
+        assert!(!settings.minimize_to_tray);
+        assert_eq!(settings.rule_watcher_mode, WatchMode::Auto);
+        assert_eq!(settings.auto_backup_retention, 7);
+        assert_eq!(settings.adapter_settings.get("cursor"), Some(&false));
+        assert_eq!(settings.mcp_secrets_allowlist, vec!["api_key", "token"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_validates_and_writes_canonical_values() {
+        let db = Database::new_in_memory().await.unwrap();
+
+        assert!(update(&db, json!({ "mcpAutoStrat": true })).await.is_err());
+        assert!(set(&db, "mcp_auto_strat", "true").await.is_err());
+        assert!(set(&db, "mcp_auto_start", "maybe").await.is_err());
+        assert!(update(&db, json!({ "exportSnapshotRetention": 0 }))
+            .await
+            .is_err());
+        assert!(update(&db, json!({ "adapterSettings": { "vim": true } }))
+            .await
+            .is_err());
+        assert_eq!(load(&db).await.unwrap(), AppSettings::default());
+
+        let (settings, changed) = update(
+            &db,
+            json!({ "mcpAutoStart": true, "minimizeToTray": true, "ruleWatcherMode": "poll" }),
+        )
+        .await
+        .unwrap();
+        assert!(settings.mcp_auto_start);
+        assert_eq!(changed, vec!["mcp_auto_start", "rule_watcher_mode"]);
+        assert_eq!(
+            db.get_setting("rule_watcher_mode")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("poll")
+        );
+
+        let (_, changed) = set(&db, "mcp_auto_start", "0").await.unwrap();
+        assert_eq!(changed, vec!["mcp_auto_start"]);
+        assert_eq!(
+            db.get_setting("mcp_auto_start").await.unwrap().as_deref(),
+            Some("false")
+        );
+    }
+}
@@ -24,6 +24,10 @@ struct StandardFrontmatter<'a> {
     argument_hint: Option<Cow<'a, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<Cow<'a, str>>>,
+    #[serde(rename = "allowed-tools", skip_serializing_if = "Option::is_none")]
+    allowed_tools: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<Cow<'a, str>>,
 }
 
 #[derive(Serialize)]
@@ -33,6 +37,41 @@ struct ArgumentEntry<'a> {
     required: bool,
 }
 
+/// The command's argument hint, falling back to `<required> [optional]` from its arguments.
+fn argument_hint(command: &Command) -> Option<Cow<'_, str>> {
+    if let Some(hint) = command.slash_frontmatter.argument_hint.as_deref() {
+        return Some(Cow::from(hint));
+    }
+    if command.arguments.is_empty() {
+        return None;
+    }
+    let hints: Vec<String> = command
+        .arguments
+        .iter()
+        .map(|arg| {
+            if arg.required {
+                format!("<{}>", arg.name)
+            } else {
+                format!("[{}]", arg.name)
+            }
+        })
+        .collect();
+    Some(Cow::from(hints.join(" ")))
+}
+
+fn allowed_tools(command: &Command) -> Option<Cow<'_, str>> {
+    let tools = &command.slash_frontmatter.allowed_tools;
+    (!tools.is_empty()).then(|| Cow::from(tools.join(", ")))
+}
+
+fn model(command: &Command) -> Option<Cow<'_, str>> {
+    command.slash_frontmatter.model.as_deref().map(Cow::from)
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
 fn format_with_frontmatter(frontmatter: &StandardFrontmatter<'_>, script: &str) -> String {
     let yaml = match serde_yaml::to_string(frontmatter) {
         Ok(y) => y,
@@ -104,6 +143,8 @@ impl SlashCommandAdapter for OpenCodeSlashAdapter {
             arguments,
             argument_hint: None,
             tools: None,
+            allowed_tools: None,
+            model: model(command),
         };
 
         format_with_frontmatter(&frontmatter, &command.script)
@@ -153,8 +194,10 @@ impl SlashCommandAdapter for ClaudeCodeSlashAdapter {
             name: Cow::from(&command.name),
             description: Cow::from(&command.description),
             arguments: None,
-            argument_hint: None,
+            argument_hint: argument_hint(command),
             tools: Some(vec![Cow::from("bash")]),
+            allowed_tools: allowed_tools(command),
+            model: model(command),
         };
 
         format_with_frontmatter(&frontmatter, &command.script)
@@ -251,6 +294,12 @@ impl SlashCommandAdapter for GeminiSlashAdapter {
         let mut output = String::new();
 
         output.push_str(&format!("description = \"{}\"\n", command.description));
+        if let Some(hint) = argument_hint(command) {
+            output.push_str(&format!("argument-hint = {}\n", toml_string(&hint)));
+        }
+        if let Some(model) = model(command) {
+            output.push_str(&format!("model = {}\n", toml_string(&model)));
+        }
         output.push_str("prompt = \"\"\"\n");
         output.push_str(&command.script);
         output.push_str("\n{{args}}\n");
@@ -347,23 +396,14 @@ impl SlashCommandAdapter for RooCodeSlashAdapter {
     }
 
     fn format_command(&self, command: &Command) -> String {
-        let argument_hint = if command.arguments.is_empty() {
-            None
-        } else {
-            let hints: Vec<String> = command
-                .arguments
-                .iter()
-                .map(|arg| format!("<{}>", arg.name))
-                .collect();
-            Some(hints.join(" "))
-        };
-
         let frontmatter = StandardFrontmatter {
             name: Cow::from(&command.name),
             description: Cow::from(&command.description),
             arguments: None,
-            argument_hint: argument_hint.map(Cow::from),
+            argument_hint: argument_hint(command),
             tools: None,
+            allowed_tools: None,
+            model: None,
         };
 
         format_with_frontmatter(&frontmatter, &command.script)
@@ -412,6 +452,8 @@ impl SlashCommandAdapter for AntigravitySlashAdapter {
             arguments: None,
             argument_hint: None,
             tools: None,
+            allowed_tools: None,
+            model: None,
         };
 
         format_with_frontmatter(&frontmatter, &command.script)
@@ -460,6 +502,8 @@ impl SlashCommandAdapter for CodexSlashAdapter {
             arguments: None,
             argument_hint: None,
             tools: None,
+            allowed_tools: None,
+            model: None,
         };
 
         format_with_frontmatter(&frontmatter, &command.script)
@@ -545,6 +589,50 @@ mod tests {
         assert!(content.contains("tools:"));
     }
 
+    fn argument(name: &str, required: bool) -> crate::models::CommandArgument {
+        crate::models::CommandArgument {
+            name: name.to_string(),
+            description: String::new(),
+            arg_type: crate::models::ArgumentType::String,
+            required,
+            default_value: None,
+            options: None,
+            pattern: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    #[test]
+    fn test_claude_adapter_emits_frontmatter_options() {
+        let mut command = create_test_command();
+        command.arguments = vec![argument("file", true), argument("message", false)];
+        let content = ClaudeCodeSlashAdapter.format_command(&command);
+        assert!(content.contains("argument-hint: <file> [message]\n"));
+        assert!(!content.contains("allowed-tools"));
+
+        command.slash_frontmatter = crate::models::SlashCommandFrontmatter {
+            argument_hint: Some("<path>".to_string()),
+            allowed_tools: vec!["Bash(git add:*)".to_string(), "Read".to_string()],
+            model: Some("claude-sonnet-4".to_string()),
+        };
+        let content = ClaudeCodeSlashAdapter.format_command(&command);
+        assert!(content.contains("argument-hint: <path>\n"));
+        assert!(content.contains("allowed-tools: Bash(git add:*), Read\n"));
+        assert!(content.contains("model: claude-sonnet-4\n"));
+    }
+
+    #[test]
+    fn test_gemini_adapter_emits_frontmatter_options_as_toml() {
+        let mut command = create_test_command();
+        command.slash_frontmatter.argument_hint = Some("<file> \"quoted\"".to_string());
+        command.slash_frontmatter.model = Some("gemini-2.5-pro".to_string());
+        let content = GeminiSlashAdapter.format_command(&command);
+        let parsed: toml::Table = content.parse().unwrap();
+        assert_eq!(parsed["argument-hint"].as_str(), Some("<file> \"quoted\""));
+        assert_eq!(parsed["model"].as_str(), Some("gemini-2.5-pro"));
+    }
+
     #[test]
     fn test_gemini_adapter_toml_format() {
         let adapter = GeminiSlashAdapter;
@@ -560,7 +648,7 @@ mod tests {
     fn test_codex_skill_structure() {
         let adapter = CodexSlashAdapter;
         let path = adapter
-            .get_command_path("my-skill", true)
+            .get_command_path(None, "my-skill", true)
             .expect("Failed to get path");
         let path_str = path.to_string_lossy();
         // Codex uses .agents/skills directory for slash commands
@@ -614,6 +702,8 @@ mod tests {
             }]),
             argument_hint: Some(Cow::from("<arg1>")),
             tools: Some(vec![Cow::from("bash")]),
+            allowed_tools: None,
+            model: None,
         };
 
         let result = format_with_frontmatter(&frontmatter, &command.script);
@@ -77,6 +77,7 @@ pub async fn cleanup_slash_commands(
 #[tauri::command]
 pub async fn remove_slash_command_files(
     command_name: String,
+    namespace: Option<String>,
     adapters: Vec<String>,
     target_paths: Vec<String>,
     database: State<'_, Arc<Database>>,
@@ -84,7 +85,12 @@ pub async fn remove_slash_command_files(
     let engine = SlashCommandSyncEngine::new(Arc::clone(&database));
 
     // Remove command files for all adapters and repo-root local paths.
-    let result = engine.remove_command(&command_name, &adapters, &target_paths)?;
+    let result = engine.remove_command(
+        &command_name,
+        namespace.as_deref(),
+        &adapters,
+        &target_paths,
+    )?;
 
     Ok(result)
 }
@@ -148,10 +154,11 @@ pub async fn test_slash_command_generation(
 pub async fn get_slash_command_path(
     adapter_name: String,
     command_name: String,
+    namespace: Option<String>,
     is_global: bool,
 ) -> Result<PathBuf> {
     use crate::slash_commands::get_adapter;
-    use crate::slash_commands::sync::validate_command_name;
+    use crate::slash_commands::sync::{normalize_command_namespace, validate_command_name};
 
     // Get the adapter
     let adapter =
@@ -159,11 +166,12 @@ pub async fn get_slash_command_path(
             message: format!("Unknown adapter: {}", adapter_name),
         })?;
 
-    // Sanitize the command name for consistent paths
+    // Sanitize the command name and namespace for consistent paths
     let safe_name = validate_command_name(&command_name)?;
+    let namespace = normalize_command_namespace(namespace.as_deref())?;
 
     // Get the path
-    let path = adapter.get_command_path(&safe_name, is_global)?;
+    let path = adapter.get_command_path(namespace.as_deref(), &safe_name, is_global)?;
 
     Ok(path)
 }
@@ -27,10 +27,15 @@ pub trait SlashCommandAdapter: Send + Sync {
         format!("{}.{}", command_name, self.file_extension())
     }
 
-    /// Returns the full path for a command
+    /// Returns the full path for a command, optionally inside a namespace such as `git`
     ///
     /// This method now uses the PathResolver for consistent path resolution.
-    fn get_command_path(&self, command_name: &str, is_global: bool) -> Result<PathBuf> {
+    fn get_command_path(
+        &self,
+        namespace: Option<&str>,
+        command_name: &str,
+        is_global: bool,
+    ) -> Result<PathBuf> {
         let resolver = path_resolver();
 
         // Get the adapter type from the adapter name
@@ -42,7 +47,8 @@ pub trait SlashCommandAdapter: Send + Sync {
 
         if is_global {
             // Use PathResolver for global paths
-            let resolved = resolver.slash_command_path(adapter, command_name, true)?;
+            let resolved =
+                resolver.namespaced_slash_command_path(adapter, namespace, command_name, true)?;
             Ok(resolved.path)
         } else {
             // For local paths without a repo root, we need the caller to provide one
@@ -58,6 +64,7 @@ pub trait SlashCommandAdapter: Send + Sync {
     /// This method now uses the PathResolver for consistent path resolution.
     fn get_command_path_for_root(
         &self,
+        namespace: Option<&str>,
         command_name: &str,
         root: &std::path::Path,
     ) -> Result<PathBuf> {
@@ -71,7 +78,8 @@ pub trait SlashCommandAdapter: Send + Sync {
         })?;
 
         // Use PathResolver for local paths with repo root
-        let resolved = resolver.local_slash_command_path(adapter, command_name, root)?;
+        let resolved =
+            resolver.local_namespaced_slash_command_path(adapter, namespace, command_name, root)?;
         Ok(resolved.path)
     }
 
@@ -6,9 +6,10 @@ use std::sync::Arc;
 use sha2::{Digest, Sha256};
 use tokio::sync::Mutex;
 
+use crate::atomic_write::write_atomic;
 use crate::database::Database;
 use crate::error::{AppError, Result};
-use crate::models::Command;
+use crate::models::{Command, Scope, SlashCommandFrontmatter};
 use crate::slash_commands::{get_adapter, SlashCommandAdapter};
 
 /// Validates a command name to prevent path traversal and other security issues
@@ -79,23 +80,69 @@ pub fn validate_command_name(name: &str) -> Result<String> {
     Ok(trimmed)
 }
 
-/// Atomically writes content to a file by writing to a temp file first
-pub fn atomic_write(path: &PathBuf, content: &str) -> Result<()> {
-    let temp_path = path.with_extension("tmp");
-
-    // Write to temp file
-    fs::write(&temp_path, content)?;
-
-    // Rename temp to target (atomic on most filesystems)
-    fs::rename(&temp_path, path).map_err(|e| {
-        // Clean up temp file on failure
-        let _ = fs::remove_file(&temp_path);
-        AppError::Io(e)
-    })?;
+/// Normalizes a slash command namespace such as `Git/Review` into `git/review`.
+///
+/// Each `/`-separated segment is slugged like a command name; an empty namespace means none.
+pub fn normalize_command_namespace(namespace: Option<&str>) -> Result<Option<String>> {
+    let namespace = match namespace.map(str::trim).filter(|ns| !ns.is_empty()) {
+        Some(ns) => ns.trim_matches('/'),
+        None => return Ok(None),
+    };
+    let segments = namespace
+        .split('/')
+        .map(validate_command_name)
+        .collect::<Result<Vec<_>>>()
+        .map_err(|_| AppError::InvalidInput {
+            message: format!(
+                "Slash command namespace '{}' must be '/'-separated names without '..' or '\\'",
+                namespace
+            ),
+        })?;
+    Ok(Some(segments.join("/")))
+}
 
+/// Validates slash command frontmatter options; every value must be a single non-empty line.
+pub fn validate_slash_frontmatter(frontmatter: &SlashCommandFrontmatter) -> Result<()> {
+    let values = frontmatter
+        .argument_hint
+        .iter()
+        .map(|v| ("argument hint", v))
+        .chain(frontmatter.model.iter().map(|v| ("model", v)))
+        .chain(
+            frontmatter
+                .allowed_tools
+                .iter()
+                .map(|v| ("allowed tool", v)),
+        );
+    for (label, value) in values {
+        if value.trim().is_empty() || value.contains(['\n', '\r']) {
+            return Err(AppError::InvalidInput {
+                message: format!("Slash command {} must be a single non-empty line", label),
+            });
+        }
+    }
+    // Claude Code reads `allowed-tools` as one comma-separated list.
+    if frontmatter.allowed_tools.iter().any(|t| t.contains(',')) {
+        return Err(AppError::InvalidInput {
+            message: "Slash command allowed tools cannot contain commas".to_string(),
+        });
+    }
     Ok(())
 }
 
+/// Repository roots a command's local slash files belong in: its `target_paths`, or every
+/// registered repository root for a local-scoped command without any.
+pub async fn local_slash_roots(db: &Database, command: &Command) -> Result<Vec<String>> {
+    if command.slash_scope == Scope::Local && command.target_paths.is_empty() {
+        return Ok(crate::commands::get_local_rule_roots(db)
+            .await?
+            .into_iter()
+            .map(|root| root.to_string_lossy().to_string())
+            .collect());
+    }
+    Ok(command.target_paths.clone())
+}
+
 /// Represents the result of a slash command sync operation
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -159,8 +206,10 @@ impl SlashCommandSyncEngine {
 
     /// Validates a command before syncing
     fn validate_command(&self, command: &Command) -> Result<String> {
-        // Validate command name
+        // Validate command name and namespace
         let safe_name = validate_command_name(&command.name)?;
+        normalize_command_namespace(command.slash_namespace.as_deref())?;
+        validate_slash_frontmatter(&command.slash_frontmatter)?;
 
         // Validate script isn't empty
         if command.script.trim().is_empty() {
@@ -177,10 +226,21 @@ impl SlashCommandSyncEngine {
         &self,
         command: &Command,
         is_global: bool,
+    ) -> Result<SlashCommandSyncResult> {
+        self.sync_command_to_roots(command, is_global, &command.target_paths)
+    }
+
+    /// Sync slash commands for a command, writing local copies into `roots` instead of the
+    /// command's own `target_paths`. Local-scoped commands never get a global file.
+    pub fn sync_command_to_roots(
+        &self,
+        command: &Command,
+        is_global: bool,
+        roots: &[String],
     ) -> Result<SlashCommandSyncResult> {
         let mut result = SlashCommandSyncResult::new();
 
-        if !command.generate_slash_commands {
+        if !command.generate_slash_commands || (is_global && command.slash_scope == Scope::Local) {
             return Ok(result);
         }
 
@@ -192,6 +252,7 @@ impl SlashCommandSyncEngine {
             ));
             return Ok(result);
         }
+        let namespace = normalize_command_namespace(command.slash_namespace.as_deref())?;
 
         for adapter_name in &command.slash_command_adapters {
             let adapter = match get_adapter(adapter_name) {
@@ -204,8 +265,8 @@ impl SlashCommandSyncEngine {
                 }
             };
 
-            if !is_global && !command.target_paths.is_empty() {
-                for root in &command.target_paths {
+            if !is_global && !roots.is_empty() {
+                for root in roots {
                     let safe_name = match validate_command_name(&command.name) {
                         Ok(v) => v,
                         Err(e) => {
@@ -217,17 +278,20 @@ impl SlashCommandSyncEngine {
                         }
                     };
 
-                    let file_path =
-                        match adapter.get_command_path_for_root(&safe_name, &PathBuf::from(root)) {
-                            Ok(p) => p,
-                            Err(e) => {
-                                result.errors.push(format!(
-                                    "Failed to resolve local path for {} in {}: {}",
-                                    adapter_name, root, e
-                                ));
-                                continue;
-                            }
-                        };
+                    let file_path = match adapter.get_command_path_for_root(
+                        namespace.as_deref(),
+                        &safe_name,
+                        &PathBuf::from(root),
+                    ) {
+                        Ok(p) => p,
+                        Err(e) => {
+                            result.errors.push(format!(
+                                "Failed to resolve local path for {} in {}: {}",
+                                adapter_name, root, e
+                            ));
+                            continue;
+                        }
+                    };
 
                     let content = adapter.format_command(command);
                     match self.sync_command_to_path(&file_path, &content) {
@@ -263,7 +327,8 @@ impl SlashCommandSyncEngine {
     ) -> Result<bool> {
         // Use safe name for file path
         let safe_name = validate_command_name(&command.name)?;
-        let file_path = adapter.get_command_path(&safe_name, is_global)?;
+        let namespace = normalize_command_namespace(command.slash_namespace.as_deref())?;
+        let file_path = adapter.get_command_path(namespace.as_deref(), &safe_name, is_global)?;
         let content = adapter.format_command(command);
         self.sync_command_to_path(&file_path, &content)
     }
@@ -288,7 +353,7 @@ impl SlashCommandSyncEngine {
         }
 
         // Write the file atomically
-        atomic_write(file_path, content)?;
+        write_atomic(file_path, content)?;
 
         Ok(true)
     }
@@ -305,7 +370,8 @@ impl SlashCommandSyncEngine {
 
         for command in commands {
             if command.generate_slash_commands {
-                let command_result = self.sync_command(&command, is_global)?;
+                let roots = local_slash_roots(&self.database, &command).await?;
+                let command_result = self.sync_command_to_roots(&command, is_global, &roots)?;
                 result.files_written += command_result.files_written;
                 result.files_removed += command_result.files_removed;
                 result.errors.extend(command_result.errors);
@@ -321,16 +387,19 @@ impl SlashCommandSyncEngine {
     /// Removes both the global home-rooted file and any per-repo-root local files
     /// for every provided adapter.  `target_paths` should contain the repository
     /// roots that the command was targeting so that local copies are also cleaned up.
+    /// `namespace` must be the namespace the files were written under.
     pub fn remove_command(
         &self,
         command_name: &str,
+        namespace: Option<&str>,
         adapters: &[String],
         target_paths: &[String],
     ) -> Result<SlashCommandSyncResult> {
         let mut result = SlashCommandSyncResult::new();
 
-        // Validate and sanitize the command name
+        // Validate and sanitize the command name and namespace
         let safe_name = validate_command_name(command_name)?;
+        let namespace = normalize_command_namespace(namespace)?;
 
         for adapter_name in adapters {
             let adapter = match get_adapter(adapter_name) {
@@ -344,7 +413,7 @@ impl SlashCommandSyncEngine {
             };
 
             // Remove the global home-rooted file via PathResolver.
-            match adapter.get_command_path(&safe_name, true) {
+            match adapter.get_command_path(namespace.as_deref(), &safe_name, true) {
                 Ok(global_path) => match remove_path_if_exists(&global_path) {
                     Ok(true) => result.files_removed += 1,
                     Ok(false) => {}
@@ -364,7 +433,11 @@ impl SlashCommandSyncEngine {
             // Remove per-repo-root local files via PathResolver.
             for root in target_paths {
                 let root_path = PathBuf::from(root);
-                match adapter.get_command_path_for_root(&safe_name, &root_path) {
+                match adapter.get_command_path_for_root(
+                    namespace.as_deref(),
+                    &safe_name,
+                    &root_path,
+                ) {
                     Ok(local_path) => match remove_path_if_exists(&local_path) {
                         Ok(true) => result.files_removed += 1,
                         Ok(false) => {}
@@ -418,10 +491,13 @@ impl SlashCommandSyncEngine {
         }
 
         // Phase 1: scan — collect candidate paths without touching any files.
+        // Namespaced commands live in nested folders, so the scan descends into them.
         let mut candidates: Vec<PathBuf> = Vec::new();
-        for entry in fs::read_dir(&dir_path)? {
-            let entry = entry?;
-            let path = entry.path();
+        for entry in walkdir::WalkDir::new(&dir_path).follow_links(false) {
+            let entry = entry.map_err(|e| AppError::Internal {
+                message: format!("Failed to scan {}: {}", dir_path.display(), e),
+            })?;
+            let path = entry.into_path();
             if path.is_file() && path.extension().unwrap_or_default() == adapter.file_extension() {
                 // Only consider files that carry the RuleWeaver marker.
                 // Read only the first few lines to avoid loading large files into memory.
@@ -456,8 +532,9 @@ impl SlashCommandSyncEngine {
     ) -> Result<HashMap<String, SyncStatus>> {
         let mut status = HashMap::new();
 
-        // Sanitize command name for consistency with sync
+        // Sanitize command name and namespace for consistency with sync
         let safe_name = validate_command_name(&command.name)?;
+        let namespace = normalize_command_namespace(command.slash_namespace.as_deref())?;
 
         for adapter_name in &command.slash_command_adapters {
             let adapter = match get_adapter(adapter_name) {
@@ -472,7 +549,8 @@ impl SlashCommandSyncEngine {
             };
 
             // Check both global and local paths
-            let global_path = match adapter.get_command_path(&safe_name, true) {
+            let global_path = match adapter.get_command_path(namespace.as_deref(), &safe_name, true)
+            {
                 Ok(p) => p,
                 Err(e) => {
                     status.insert(adapter_name.clone(), SyncStatus::Error(e.to_string()));
@@ -491,8 +569,11 @@ impl SlashCommandSyncEngine {
 
             if !command.target_paths.is_empty() {
                 for root in &command.target_paths {
-                    let local_path =
-                        adapter.get_command_path_for_root(&safe_name, &PathBuf::from(root))?;
+                    let local_path = adapter.get_command_path_for_root(
+                        namespace.as_deref(),
+                        &safe_name,
+                        &PathBuf::from(root),
+                    )?;
                     if local_path.exists() {
                         let content = fs::read_to_string(&local_path)?;
                         let expected = adapter.format_command(command);
@@ -501,13 +582,14 @@ impl SlashCommandSyncEngine {
                     }
                 }
             } else {
-                let local_path = match adapter.get_command_path(&safe_name, false) {
-                    Ok(p) => p,
-                    Err(e) => {
-                        status.insert(adapter_name.clone(), SyncStatus::Error(e.to_string()));
-                        continue;
-                    }
-                };
+                let local_path =
+                    match adapter.get_command_path(namespace.as_deref(), &safe_name, false) {
+                        Ok(p) => p,
+                        Err(e) => {
+                            status.insert(adapter_name.clone(), SyncStatus::Error(e.to_string()));
+                            continue;
+                        }
+                    };
 
                 if local_path.exists() {
                     let content = fs::read_to_string(&local_path)?;
@@ -628,6 +710,36 @@ mod tests {
         assert_eq!(result, "leading-trailing");
     }
 
+    #[test]
+    fn test_normalize_command_namespace() {
+        assert_eq!(normalize_command_namespace(None).unwrap(), None);
+        assert_eq!(normalize_command_namespace(Some("  ")).unwrap(), None);
+        assert_eq!(
+            normalize_command_namespace(Some("/Git/Code Review/")).unwrap(),
+            Some("git/code-review".to_string())
+        );
+        assert!(normalize_command_namespace(Some("git//review")).is_err());
+        assert!(normalize_command_namespace(Some("git/../etc")).is_err());
+        assert!(normalize_command_namespace(Some("git\\review")).is_err());
+    }
+
+    #[test]
+    fn test_validate_slash_frontmatter() {
+        let mut frontmatter = SlashCommandFrontmatter {
+            argument_hint: Some("<file>".to_string()),
+            allowed_tools: vec!["Bash(git add:*)".to_string()],
+            model: Some("sonnet".to_string()),
+        };
+        assert!(validate_slash_frontmatter(&frontmatter).is_ok());
+
+        frontmatter.model = Some("sonnet\nrm: -rf".to_string());
+        assert!(validate_slash_frontmatter(&frontmatter).is_err());
+
+        frontmatter.model = None;
+        frontmatter.allowed_tools = vec!["Read, Write".to_string()];
+        assert!(validate_slash_frontmatter(&frontmatter).is_err());
+    }
+
     #[test]
     fn test_cleanup_adapter_only_touches_ruleweaver_files() {
         use std::fs;
@@ -683,14 +795,14 @@ mod tests {
     }
 
     #[test]
-    fn test_atomic_write_creates_file() {
+    fn test_write_atomic_creates_file() {
         use std::fs;
         use tempfile::TempDir;
 
         let tmp = TempDir::new().expect("tempdir");
         let dest = tmp.path().join("output.md");
 
-        atomic_write(&dest, "hello world").unwrap();
+        write_atomic(&dest, "hello world").unwrap();
 
         assert!(dest.exists());
         assert_eq!(fs::read_to_string(&dest).unwrap(), "hello world");
@@ -699,7 +811,7 @@ mod tests {
     }
 
     #[test]
-    fn test_atomic_write_overwrites_existing() {
+    fn test_write_atomic_overwrites_existing() {
         use std::fs;
         use tempfile::TempDir;
 
@@ -707,7 +819,7 @@ mod tests {
         let dest = tmp.path().join("output.md");
 
         fs::write(&dest, "old content").unwrap();
-        atomic_write(&dest, "new content").unwrap();
+        write_atomic(&dest, "new content").unwrap();
 
         assert_eq!(fs::read_to_string(&dest).unwrap(), "new content");
     }
@@ -0,0 +1,235 @@
+//! Scheduled export snapshots.
+//!
+//! When enabled, a full configuration export (the same JSON produced by
+//! `export_configuration`, with the sync state included) is written to a user-chosen
+//! directory on a fixed interval. Unlike database backups these files are human-readable and
+//! can be imported on any machine.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::backup::{
+    list_timestamped_files, prune_timestamped_files, timestamped_file_name, BackupInfo,
+};
+use crate::constants::limits::DEFAULT_EXPORT_SNAPSHOT_RETENTION;
+use crate::constants::timing::{
+    DEFAULT_EXPORT_SNAPSHOT_INTERVAL_HOURS, EXPORT_SNAPSHOT_CHECK_INTERVAL,
+};
+use crate::database::Database;
+use crate::error::{AppError, Result};
+
+pub const EXPORT_SNAPSHOT_ENABLED_KEY: &str = "export_snapshot_enabled";
+pub const EXPORT_SNAPSHOT_DIR_KEY: &str = "export_snapshot_dir";
+pub const EXPORT_SNAPSHOT_INTERVAL_HOURS_KEY: &str = "export_snapshot_interval_hours";
+pub const EXPORT_SNAPSHOT_RETENTION_KEY: &str = "export_snapshot_retention";
+pub const EXPORT_SNAPSHOT_LAST_AT_KEY: &str = "export_snapshot_last_at";
+
+const SNAPSHOT_FILE_PREFIX: &str = "ruleweaver-export-";
+const SNAPSHOT_FILE_EXTENSION: &str = "json";
+
+async fn positive_setting<T: std::str::FromStr + PartialOrd + Default>(
+    db: &Database,
+    key: &str,
+    default: T,
+) -> T {
+    db.get_setting(key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<T>().ok())
+        .filter(|v| *v > T::default())
+        .unwrap_or(default)
+}
+
+/// Returns the configured snapshot directory, if any.
+pub async fn snapshot_dir(db: &Database) -> Result<Option<PathBuf>> {
+    Ok(db
+        .get_setting(EXPORT_SNAPSHOT_DIR_KEY)
+        .await?
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from))
+}
+
+/// Writes a timestamped export snapshot into `dir` and prunes old snapshots.
+pub async fn create_snapshot(db: &Database, dir: &Path) -> Result<BackupInfo> {
+    if db.is_encrypted() {
+        return Err(AppError::InvalidInput {
+            message: "Export snapshots are not written while the database is encrypted".to_string(),
+        });
+    }
+    if !dir.is_absolute() {
+        return Err(AppError::InvalidInput {
+            message: "Export snapshot directory must be an absolute path".to_string(),
+        });
+    }
+
+    let mut config = db.export_configuration().await?;
+    config.state = Some(db.export_state().await?);
+    crate::export_manifest::seal(db, &mut config).await?;
+    let content = serde_json::to_string_pretty(&config)?;
+
+    let path = dir.join(timestamped_file_name(
+        SNAPSHOT_FILE_PREFIX,
+        SNAPSHOT_FILE_EXTENSION,
+    ));
+    let write_path = path.clone();
+    let write_dir = dir.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&write_dir)?;
+        crate::atomic_write::write_atomic(&write_path, content)
+    })
+    .await
+    .map_err(|e| AppError::InvalidInput {
+        message: e.to_string(),
+    })??;
+
+    let retention = positive_setting(
+        db,
+        EXPORT_SNAPSHOT_RETENTION_KEY,
+        DEFAULT_EXPORT_SNAPSHOT_RETENTION,
+    )
+    .await;
+    prune_timestamped_files(
+        dir,
+        SNAPSHOT_FILE_PREFIX,
+        SNAPSHOT_FILE_EXTENSION,
+        retention,
+    )?;
+
+    let now = Utc::now().timestamp();
+    db.set_setting(EXPORT_SNAPSHOT_LAST_AT_KEY, &now.to_string())
+        .await?;
+
+    BackupInfo::from_path(&path)
+}
+
+/// Lists snapshots in `dir`, newest first.
+pub fn list_snapshots(dir: &Path) -> Result<Vec<BackupInfo>> {
+    list_timestamped_files(dir, SNAPSHOT_FILE_PREFIX, SNAPSHOT_FILE_EXTENSION)
+}
+
+/// Writes a snapshot when enabled, configured, and the configured interval has elapsed.
+/// Snapshots are skipped while the database is encrypted.
+pub async fn run_snapshot_if_due(db: &Database) -> Result<Option<BackupInfo>> {
+    if !crate::settings::load(db).await?.export_snapshot_enabled || db.is_encrypted() {
+        return Ok(None);
+    }
+
+    let Some(dir) = snapshot_dir(db).await? else {
+        log::warn!("Export snapshots are enabled but no directory is configured");
+        return Ok(None);
+    };
+
+    let interval_hours = positive_setting(
+        db,
+        EXPORT_SNAPSHOT_INTERVAL_HOURS_KEY,
+        DEFAULT_EXPORT_SNAPSHOT_INTERVAL_HOURS,
+    )
+    .await;
+    let last = db
+        .get_setting(EXPORT_SNAPSHOT_LAST_AT_KEY)
+        .await?
+        .and_then(|v| v.parse::<i64>().ok());
+    if let Some(last) = last {
+        let elapsed = Utc::now().timestamp() - last;
+        if elapsed < (interval_hours * 60 * 60) as i64 {
+            return Ok(None);
+        }
+    }
+
+    let info = create_snapshot(db, &dir).await?;
+    log::info!("Export snapshot written to {}", info.path);
+    Ok(Some(info))
+}
+
+/// Background loop that periodically checks whether a snapshot is due.
+pub async fn run_snapshot_loop(db: Arc<Database>) {
+    loop {
+        if let Err(e) = run_snapshot_if_due(&db).await {
+            log::error!("Export snapshot failed: {}", e);
+        }
+        tokio::time::sleep(EXPORT_SNAPSHOT_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AdapterType, CreateRuleInput, ExportConfiguration, Scope};
+
+    async fn seeded_db() -> Database {
+        let db = Database::new_in_memory().await.unwrap();
+        db.create_rule(CreateRuleInput {
+            id: None,
+            name: "Snapshot me".to_string(),
+            description: String::new(),
+            content: "content".to_string(),
+            scope: Scope::Global,
+            target_paths: None,
+            enabled_adapters: vec![AdapterType::Gemini],
+            enabled: true,
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_writes_importable_export_with_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = seeded_db().await;
+        db.set_setting("mcp_auto_start", "true").await.unwrap();
+        db.set_setting("storage_mode", "file").await.unwrap();
+
+        let info = create_snapshot(&db, dir.path()).await.unwrap();
+        let content = std::fs::read_to_string(&info.path).unwrap();
+        let config: ExportConfiguration = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(config.rules.len(), 1);
+        assert_eq!(
+            config.settings.get("mcp_auto_start").map(String::as_str),
+            Some("true")
+        );
+        assert!(!config.settings.contains_key("storage_mode"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_retention_prunes_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = seeded_db().await;
+        db.set_setting(EXPORT_SNAPSHOT_RETENTION_KEY, "2")
+            .await
+            .unwrap();
+
+        for _ in 0..3 {
+            create_snapshot(&db, dir.path()).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        assert_eq!(list_snapshots(dir.path()).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_requires_enabled_and_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = seeded_db().await;
+
+        assert!(run_snapshot_if_due(&db).await.unwrap().is_none());
+
+        db.set_setting(EXPORT_SNAPSHOT_ENABLED_KEY, "true")
+            .await
+            .unwrap();
+        assert!(run_snapshot_if_due(&db).await.unwrap().is_none());
+
+        db.set_setting(EXPORT_SNAPSHOT_DIR_KEY, &dir.path().to_string_lossy())
+            .await
+            .unwrap();
+        assert!(run_snapshot_if_due(&db).await.unwrap().is_some());
+        // Not due again until the interval has elapsed.
+        assert!(run_snapshot_if_due(&db).await.unwrap().is_none());
+    }
+}
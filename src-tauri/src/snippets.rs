@@ -0,0 +1,148 @@
+//! Snippets: small shared blocks of text that rules pull in with `{{snippet:name}}`.
+//!
+//! Tokens are expanded when rules are written to their tools' files, before project config
+//! variables are filled in, so a snippet may use those variables too. Snippets are not
+//! expanded inside other snippets, and a token naming no snippet is left as it is. Whether
+//! a snippet is in use is worked out from the rules' content, so deleting or renaming one
+//! that rules still reference can be refused.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::localization;
+use crate::models::{Rule, Snippet, SnippetUsage};
+
+const MAX_SNIPPET_NAME_LENGTH: usize = 64;
+
+static SNIPPET_TOKEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*snippet:([A-Za-z0-9_.-]+)\s*\}\}").expect("Invalid snippet token regex")
+});
+
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SNIPPET_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid snippet name '{}': use letters, digits, '-', '_' and '.' (max {} characters)",
+            name, MAX_SNIPPET_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Names of the snippets `content` references, in order of first use.
+pub fn references(content: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for captures in SNIPPET_TOKEN.captures_iter(content) {
+        let name = captures.get(1).map_or("", |m| m.as_str());
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// `content` with every token naming one of `snippets` replaced by its content.
+pub fn expand(content: &str, snippets: &HashMap<String, String>) -> String {
+    SNIPPET_TOKEN
+        .replace_all(content, |captures: &regex::Captures| {
+            match snippets.get(&captures[1]) {
+                Some(snippet) => snippet.clone(),
+                None => captures[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// `rules` with their snippet tokens expanded, as sync writes them.
+pub async fn expand_rules(db: &Database, mut rules: Vec<Rule>) -> Vec<Rule> {
+    let snippets = match db.get_all_snippets().await {
+        Ok(snippets) => snippets,
+        Err(e) => {
+            log::warn!("Failed to load snippets; writing rules unexpanded: {}", e);
+            return rules;
+        }
+    };
+    if snippets.is_empty() {
+        return rules;
+    }
+    let snippets: HashMap<String, String> = snippets
+        .into_iter()
+        .map(|snippet| (snippet.name, snippet.content))
+        .collect();
+    for rule in &mut rules {
+        rule.content = expand(&rule.content, &snippets);
+        localization::map_variants(rule, |content| expand(content, &snippets));
+    }
+    rules
+}
+
+/// The rules among `rules` that reference the snippet called `name`.
+pub fn usages(rules: &[Rule], name: &str) -> Vec<SnippetUsage> {
+    rules
+        .iter()
+        .filter(|rule| references(&rule.content).contains(&name))
+        .map(|rule| SnippetUsage {
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+        })
+        .collect()
+}
+
+/// Fails when rules still reference `snippet`, naming the first few, unless `force` is set.
+pub fn ensure_unused(snippet: &Snippet, usages: &[SnippetUsage], force: bool) -> Result<()> {
+    if force || usages.is_empty() {
+        return Ok(());
+    }
+    let mut names: Vec<&str> = usages
+        .iter()
+        .take(3)
+        .map(|u| u.rule_name.as_str())
+        .collect();
+    if usages.len() > names.len() {
+        names.push("...");
+    }
+    Err(AppError::Validation(format!(
+        "Snippet '{}' is used by {} rule(s): {}",
+        snippet.name,
+        usages.len(),
+        names.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_replaces_known_snippets_only() {
+        let snippets = HashMap::from([
+            ("license".to_string(), "MIT licensed.".to_string()),
+            ("nested".to_string(), "{{snippet:license}}".to_string()),
+        ]);
+        let content = "Intro\n{{snippet:license}}\n{{ snippet:license }} {{snippet:missing}} \
+                       {{snippet:nested}} {{service}}";
+
+        assert_eq!(
+            expand(content, &snippets),
+            "Intro\nMIT licensed.\nMIT licensed. {{snippet:missing}} \
+             {{snippet:license}} {{service}}"
+        );
+        assert_eq!(references(content), vec!["license", "missing", "nested"]);
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("house-style_v2.1").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("two words").is_err());
+        assert!(validate_name("}}").is_err());
+    }
+}
@@ -5,7 +5,13 @@ use tauri::State;
 
 use crate::database::Database;
 use crate::error::Result;
-use crate::status::{ArtifactStatusEntry, RepairResult, StatusEngine, StatusFilter, StatusSummary};
+use crate::mcp::McpManager;
+use crate::status::unified::{self, UnifiedStatus};
+use crate::status::{
+    ArtifactStatusEntry, RepairResult, SkillSyncStatusEntry, StatusEngine, StatusFilter,
+    StatusSummary,
+};
+use crate::WatcherState;
 
 #[tauri::command]
 pub async fn get_artifact_status(
@@ -55,3 +61,22 @@ pub async fn refresh_artifact_status(
     let filter = filter.unwrap_or_default();
     engine.compute_status(&filter).await
 }
+
+#[tauri::command]
+pub async fn get_skill_sync_status(
+    db: State<'_, Arc<Database>>,
+    skill_id: Option<String>,
+) -> Result<Vec<SkillSyncStatusEntry>> {
+    let engine = StatusEngine::new(db.inner().clone())?;
+    engine.compute_skill_status(skill_id.as_deref()).await
+}
+
+/// Everything the dashboard shows, computed in one pass so the sections agree.
+#[tauri::command]
+pub async fn get_unified_status(
+    db: State<'_, Arc<Database>>,
+    mcp: State<'_, McpManager>,
+    watcher: State<'_, WatcherState>,
+) -> Result<UnifiedStatus> {
+    unified::collect(db.inner().clone(), &mcp, &watcher.0).await
+}
@@ -17,6 +17,7 @@ use crate::models::{AdapterType, ParseEnumError, Scope};
 use crate::reconciliation::{FoundArtifact, ReconciliationEngine};
 
 pub mod commands;
+pub mod unified;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -26,6 +27,8 @@ pub enum ArtifactSyncStatus {
     Missing,
     Conflicted,
     Unsupported,
+    /// The adapter has no native support and reaches the artifact through the MCP server
+    McpFallback,
     Error,
 }
 
@@ -38,6 +41,7 @@ impl ArtifactSyncStatus {
             ArtifactSyncStatus::Missing => "missing",
             ArtifactSyncStatus::Conflicted => "conflicted",
             ArtifactSyncStatus::Unsupported => "unsupported",
+            ArtifactSyncStatus::McpFallback => "mcp_fallback",
             ArtifactSyncStatus::Error => "error",
         }
     }
@@ -53,6 +57,7 @@ impl FromStr for ArtifactSyncStatus {
             "missing" => Ok(ArtifactSyncStatus::Missing),
             "conflicted" => Ok(ArtifactSyncStatus::Conflicted),
             "unsupported" => Ok(ArtifactSyncStatus::Unsupported),
+            "mcp_fallback" => Ok(ArtifactSyncStatus::McpFallback),
             "error" => Ok(ArtifactSyncStatus::Error),
             _ => Err(ParseEnumError),
         }
@@ -80,6 +85,24 @@ pub struct ArtifactStatusEntry {
     pub detail: Option<String>,
 }
 
+/// How one skill reaches one adapter: a SKILL.md on disk or the MCP fallback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSyncStatusEntry {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub adapter: AdapterType,
+    pub scope: Scope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_root: Option<String>,
+    pub status: ArtifactSyncStatus,
+    /// SKILL.md location; absent for MCP fallback
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatusFilter {
@@ -114,6 +137,7 @@ pub struct StatusSummary {
     pub missing: usize,
     pub conflicted: usize,
     pub unsupported: usize,
+    pub mcp_fallback: usize,
     pub error: usize,
 }
 
@@ -126,6 +150,7 @@ impl StatusSummary {
             missing: 0,
             conflicted: 0,
             unsupported: 0,
+            mcp_fallback: 0,
             error: 0,
         };
 
@@ -136,6 +161,7 @@ impl StatusSummary {
                 ArtifactSyncStatus::Missing => summary.missing += 1,
                 ArtifactSyncStatus::Conflicted => summary.conflicted += 1,
                 ArtifactSyncStatus::Unsupported => summary.unsupported += 1,
+                ArtifactSyncStatus::McpFallback => summary.mcp_fallback += 1,
                 ArtifactSyncStatus::Error => summary.error += 1,
             }
         }
@@ -444,6 +470,77 @@ impl StatusEngine {
         Ok(results)
     }
 
+    /// Per-adapter delivery of each skill (or only `skill_id`), covering both native
+    /// SKILL.md targets and adapters that fall back to the MCP server.
+    pub async fn compute_skill_status(
+        &self,
+        skill_id: Option<&str>,
+    ) -> Result<Vec<SkillSyncStatusEntry>> {
+        let desired = self.reconciliation_engine.compute_desired_state().await?;
+        let actual = self.reconciliation_engine.scan_actual_state().await?;
+        let wanted = |id: &str| skill_id.is_none_or(|s| s == id);
+
+        let mut entries: Vec<SkillSyncStatusEntry> = desired
+            .expected_paths
+            .iter()
+            .filter(|(_, e)| e.artifact_type == ArtifactType::Skill && wanted(&e.id))
+            .map(|(path_str, expected)| {
+                let (status, detail) = match actual.found_paths.get(path_str) {
+                    Some(found) if found.content_hash == expected.content_hash => {
+                        (ArtifactSyncStatus::Synced, None)
+                    }
+                    Some(_) => (
+                        ArtifactSyncStatus::OutOfDate,
+                        Some("Content differs from expected".to_string()),
+                    ),
+                    None => (
+                        ArtifactSyncStatus::Missing,
+                        Some("File not found on disk".to_string()),
+                    ),
+                };
+                SkillSyncStatusEntry {
+                    skill_id: expected.id.clone(),
+                    skill_name: expected.name.clone(),
+                    adapter: expected.adapter,
+                    scope: expected.scope,
+                    repo_root: expected
+                        .repo_root
+                        .as_ref()
+                        .map(|p| p.to_string_lossy().to_string()),
+                    status,
+                    path: Some(path_str.clone()),
+                    detail,
+                }
+            })
+            .collect();
+
+        entries.extend(
+            desired
+                .skill_fallbacks
+                .iter()
+                .filter(|f| wanted(&f.skill_id))
+                .map(|f| SkillSyncStatusEntry {
+                    skill_id: f.skill_id.clone(),
+                    skill_name: f.skill_name.clone(),
+                    adapter: f.adapter,
+                    scope: f.scope,
+                    repo_root: None,
+                    status: ArtifactSyncStatus::McpFallback,
+                    path: None,
+                    detail: Some("No native skills support; available as an MCP tool".to_string()),
+                }),
+        );
+
+        entries.sort_by(|a, b| {
+            (&a.skill_name, a.adapter.as_str(), &a.repo_root).cmp(&(
+                &b.skill_name,
+                b.adapter.as_str(),
+                &b.repo_root,
+            ))
+        });
+        Ok(entries)
+    }
+
     pub async fn get_summary(&self, filter: &StatusFilter) -> Result<StatusSummary> {
         let entries = self.compute_status(filter).await?;
         Ok(StatusSummary::from_entries(&entries))
@@ -461,6 +558,7 @@ mod tests {
         assert_eq!(ArtifactSyncStatus::Missing.as_str(), "missing");
         assert_eq!(ArtifactSyncStatus::Conflicted.as_str(), "conflicted");
         assert_eq!(ArtifactSyncStatus::Unsupported.as_str(), "unsupported");
+        assert_eq!(ArtifactSyncStatus::McpFallback.as_str(), "mcp_fallback");
         assert_eq!(ArtifactSyncStatus::Error.as_str(), "error");
     }
 
@@ -547,6 +645,7 @@ mod tests {
         assert_eq!(summary.out_of_date, 1);
         assert_eq!(summary.conflicted, 0);
         assert_eq!(summary.unsupported, 0);
+        assert_eq!(summary.mcp_fallback, 0);
         assert_eq!(summary.error, 0);
     }
 
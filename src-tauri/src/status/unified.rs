@@ -0,0 +1,250 @@
+//! One-call overview of everything the dashboard shows: when each adapter last synced,
+//! reconciliation drift, the MCP server and its clients, the rule file watcher, and
+//! conflicts the next sync would run into.
+//!
+//! Every section is computed in the same call so the parts agree with each other. A section
+//! that cannot be computed reports its error instead of failing the whole status.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::drift::{self, DriftSummary};
+use crate::error::Result;
+use crate::file_storage::watcher::{RuleFileWatcher, WatchBackend, WatchMode};
+use crate::mcp::clients::McpClientInfo;
+use crate::mcp::{McpManager, McpStatus};
+use crate::models::{AdapterType, Conflict, SyncHistoryEntry};
+use crate::reconciliation::ReconciliationEngine;
+use crate::sync::{get_all_adapters, SyncEngine};
+use crate::tool_detection;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedStatus {
+    #[serde(with = "crate::models::timestamp")]
+    pub generated_at: DateTime<Utc>,
+    /// Most recent sync run, if any
+    pub last_sync: Option<SyncHistoryEntry>,
+    pub adapters: Vec<AdapterStatus>,
+    pub drift: DriftStatus,
+    pub mcp: McpServerState,
+    pub watcher: WatcherHealth,
+    pub conflicts: ConflictStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdapterStatus {
+    pub adapter: AdapterType,
+    pub name: String,
+    /// Whether sync writes this adapter's files, per the adapter settings
+    pub enabled: bool,
+    /// Whether the tool was detected on this machine
+    pub installed: bool,
+    /// Most recent rule file write; `None` if sync has never written for this adapter
+    #[serde(with = "crate::models::timestamp::option")]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// Files holding synced rules
+    pub files: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftStatus {
+    /// Whether the background drift monitor is on
+    pub monitor_enabled: bool,
+    /// What reconciliation would change right now
+    pub summary: Option<DriftSummary>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerState {
+    /// Server state without the API token
+    #[serde(flatten)]
+    pub status: McpStatus,
+    pub clients: Vec<McpClientInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedPath {
+    pub path: String,
+    pub backend: WatchBackend,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherHealth {
+    /// Running with every rule directory available
+    pub healthy: bool,
+    pub running: bool,
+    pub mode: WatchMode,
+    pub watched: Vec<WatchedPath>,
+    /// Rule directories waiting to become available
+    pub unavailable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictStatus {
+    /// Tool files edited outside RuleWeaver since they were last synced
+    pub pending: Vec<Conflict>,
+    pub error: Option<String>,
+}
+
+async fn adapter_statuses(db: &Database) -> Result<Vec<AdapterStatus>> {
+    let disabled = SyncEngine::new(db).get_disabled_adapters().await;
+    let sync_times = db.get_adapter_sync_times().await?;
+    let detected = tool_detection::detect_installed_tools();
+
+    Ok(get_all_adapters()
+        .iter()
+        .map(|adapter| {
+            let id = adapter.id();
+            let synced = sync_times.iter().find(|(a, _, _)| *a == id);
+            AdapterStatus {
+                adapter: id,
+                name: adapter.name().to_string(),
+                enabled: !disabled.contains(&id),
+                installed: detected.is_installed(id),
+                last_synced_at: synced.map(|(_, at, _)| *at),
+                files: synced.map(|(_, _, files)| *files).unwrap_or(0),
+            }
+        })
+        .collect())
+}
+
+async fn drift_status(db: Arc<Database>) -> DriftStatus {
+    let monitor_enabled = drift::is_enabled(&db).await;
+    let detected = match ReconciliationEngine::new(db) {
+        Ok(engine) => drift::detect_drift(&engine).await,
+        Err(e) => Err(e),
+    };
+    match detected {
+        Ok(summary) => DriftStatus {
+            monitor_enabled,
+            summary: Some(summary),
+            error: None,
+        },
+        Err(e) => DriftStatus {
+            monitor_enabled,
+            summary: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+async fn conflict_status(db: &Database) -> ConflictStatus {
+    match db.get_all_rules().await {
+        Ok(rules) => ConflictStatus {
+            pending: SyncEngine::new(db).preview(rules).await.conflicts,
+            error: None,
+        },
+        Err(e) => ConflictStatus {
+            pending: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+fn watcher_health(watcher: &RuleFileWatcher) -> WatcherHealth {
+    let running = watcher.is_running();
+    let watched: Vec<WatchedPath> = watcher
+        .watched_paths()
+        .into_iter()
+        .filter_map(|path| {
+            let backend = watcher.backend_for(&path)?;
+            Some(WatchedPath {
+                path: path.to_string_lossy().to_string(),
+                backend,
+            })
+        })
+        .collect();
+    let unavailable: Vec<String> = watcher
+        .pending_paths()
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    WatcherHealth {
+        healthy: running && unavailable.is_empty(),
+        running,
+        mode: watcher.mode(),
+        watched,
+        unavailable,
+    }
+}
+
+pub async fn collect(
+    db: Arc<Database>,
+    mcp: &McpManager,
+    watcher: &RuleFileWatcher,
+) -> Result<UnifiedStatus> {
+    let last_sync = db.get_sync_history(1).await?.into_iter().next();
+    let adapters = adapter_statuses(&db).await?;
+
+    let mut status = mcp.status().await?;
+    status.api_token = None;
+    let mcp = McpServerState {
+        status,
+        clients: mcp.connected_clients(),
+    };
+
+    let (drift, conflicts) = tokio::join!(drift_status(db.clone()), conflict_status(&db));
+
+    Ok(UnifiedStatus {
+        generated_at: Utc::now(),
+        last_sync,
+        adapters,
+        drift,
+        mcp,
+        watcher: watcher_health(watcher),
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_adapter_statuses_combine_settings_and_sync_times() {
+        let db = Database::new_in_memory().await.unwrap();
+        db.set_setting("adapter_settings", r#"{"cline": false}"#)
+            .await
+            .unwrap();
+        db.record_rule_sync(
+            AdapterType::Gemini,
+            "/tmp/GEMINI.md",
+            vec![("rule-1".to_string(), 10)],
+        )
+        .await
+        .unwrap();
+
+        let statuses = adapter_statuses(&db).await.unwrap();
+        let by_id = |id: AdapterType| statuses.iter().find(|s| s.adapter == id).unwrap();
+
+        let gemini = by_id(AdapterType::Gemini);
+        assert!(gemini.enabled);
+        assert!(gemini.last_synced_at.is_some());
+        assert_eq!(gemini.files, 1);
+
+        let cline = by_id(AdapterType::Cline);
+        assert!(!cline.enabled);
+        assert!(cline.last_synced_at.is_none());
+        assert_eq!(cline.files, 0);
+    }
+
+    #[test]
+    fn test_watcher_health_reports_stopped_watcher() {
+        let health = watcher_health(&RuleFileWatcher::new());
+        assert!(!health.running);
+        assert!(!health.healthy);
+        assert!(health.watched.is_empty());
+    }
+}
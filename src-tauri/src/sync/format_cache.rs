@@ -0,0 +1,135 @@
+//! Memoized adapter output.
+//!
+//! Previews, syncs and conflict checks format the same files over and over, and with a
+//! large library rendering every rule each time dominates. Formatted content is cached in
+//! memory under a hash of the adapter, [`FORMAT_VERSION`], the target file and the rules
+//! exactly as they are passed in, so any change to a rule, including its order or
+//! timestamps, misses the cache.
+//! Normalized file hashes are cached under the hash of the raw content for the same reason:
+//! normalizing means parsing the Markdown. Both caches keep the most recent entries only.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+use super::SyncAdapter;
+use crate::models::Rule;
+
+/// Version of the adapters' output format. Bump it when formatting changes, so content
+/// rendered by the old code is never served for the new.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAX_ENTRIES: usize = 512;
+
+/// Values by key, dropping the oldest once [`MAX_ENTRIES`] are held.
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+impl Cache {
+    fn insert(&mut self, key: String, value: String) {
+        if self.entries.contains_key(&key) {
+            return;
+        }
+        if self.order.len() >= MAX_ENTRIES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.push_back(key.clone());
+        self.entries.insert(key, value);
+    }
+}
+
+/// The value cached under `key`, or `compute`'s result, stored for next time. The lock is
+/// not held while computing, so a slow render does not block other lookups.
+fn cached(cache: &Mutex<Cache>, key: String, compute: impl FnOnce() -> String) -> String {
+    if let Some(value) = cache.lock().entries.get(&key) {
+        return value.clone();
+    }
+    let value = compute();
+    cache.lock().insert(key, value.clone());
+    value
+}
+
+static FORMATTED: LazyLock<Mutex<Cache>> = LazyLock::new(|| Mutex::new(Cache::default()));
+static FILE_HASHES: LazyLock<Mutex<Cache>> = LazyLock::new(|| Mutex::new(Cache::default()));
+
+fn rules_key(adapter: &dyn SyncAdapter, path: &Path, rules: &[Rule]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(adapter.id().as_str());
+    hasher.update(FORMAT_VERSION.to_le_bytes());
+    let path = path.to_string_lossy();
+    hasher.update((path.len() as u64).to_le_bytes());
+    hasher.update(path.as_bytes());
+    for rule in rules {
+        let encoded = serde_json::to_vec(rule).unwrap_or_default();
+        hasher.update((encoded.len() as u64).to_le_bytes());
+        hasher.update(encoded);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// `adapter.format_file(path, rules)`, rendered once per distinct input.
+pub fn format_content(adapter: &dyn SyncAdapter, path: &Path, rules: &[Rule]) -> String {
+    let key = rules_key(adapter, path, rules);
+    cached(&FORMATTED, key, || adapter.format_file(path, rules))
+}
+
+/// The hash of `content` in normalized form, computed by `compute` once per distinct content.
+pub fn normalized_hash(content: &str, compute: impl FnOnce() -> String) -> String {
+    let key = format!("{:x}", Sha256::digest(content.as_bytes()));
+    cached(&FILE_HASHES, key, compute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Scope;
+    use crate::sync::{ClaudeCodeAdapter, GeminiAdapter};
+
+    #[test]
+    fn test_cache_keys_on_adapter_and_rules() {
+        let mut rule = Rule::new(
+            "Cached".to_string(),
+            String::new(),
+            "First".to_string(),
+            Scope::Global,
+        );
+        let path = Path::new("GEMINI.md");
+        let first = format_content(&GeminiAdapter, path, &[rule.clone()]);
+        assert_eq!(first, GeminiAdapter.format_content(&[rule.clone()], true));
+        assert_eq!(format_content(&GeminiAdapter, path, &[rule.clone()]), first);
+        assert_ne!(
+            rules_key(&GeminiAdapter, path, &[rule.clone()]),
+            rules_key(&ClaudeCodeAdapter, path, &[rule.clone()])
+        );
+        assert_ne!(
+            rules_key(&GeminiAdapter, path, &[rule.clone()]),
+            rules_key(
+                &GeminiAdapter,
+                Path::new("other/GEMINI.md"),
+                &[rule.clone()]
+            )
+        );
+
+        rule.content = "Second".to_string();
+        assert!(format_content(&GeminiAdapter, path, &[rule]).contains("Second"));
+    }
+
+    #[test]
+    fn test_cache_drops_the_oldest_entries() {
+        let cache = Mutex::new(Cache::default());
+        for i in 0..=MAX_ENTRIES {
+            cached(&cache, i.to_string(), || i.to_string());
+        }
+        assert_eq!(cache.lock().entries.len(), MAX_ENTRIES);
+        assert!(!cache.lock().entries.contains_key("0"));
+        assert_eq!(cached(&cache, "1".to_string(), String::new), "1");
+    }
+}
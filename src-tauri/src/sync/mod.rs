@@ -1,19 +1,40 @@
-use std::collections::{HashMap, HashSet};
+mod format_cache;
+mod report;
+mod windsurf;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Instant;
 
 use sha2::{Digest, Sha256};
 
+use crate::atomic_write::WriteBatch;
+use crate::audit;
 use crate::constants::{
     ANTIGRAVITY_FILENAME, GEMINI_FILENAME, LEGACY_ANTIGRAVITY_DIR, LEGACY_OPENCODE_DIR,
-    NEW_GEMINI_DIR, NEW_OPENCODE_DIR, OPENCODE_FILENAME,
+    LEGACY_WINDSURF_FILENAME, NEW_GEMINI_DIR, NEW_OPENCODE_DIR, OPENCODE_FILENAME,
 };
 use crate::database::Database;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::localization;
+use crate::markdown;
+use crate::metrics;
 use crate::models::registry::{ArtifactType, REGISTRY};
-use crate::models::{AdapterType, Conflict, DiffSummary, Rule, Scope, SyncError, SyncResult};
+use crate::models::{
+    AdapterSyncReport, AdapterType, AuditAction, Conflict, DiffSummary, Rule, Scope, SyncError,
+    SyncHashRepair, SyncReport, SyncReportFile, SyncResult, WebhookEvent,
+};
 use crate::path_resolver::path_resolver;
+use crate::project_config;
+use crate::rule_order;
+use crate::snippets;
+use crate::tool_detection;
+use crate::webhooks;
+use report::SyncReportBuilder;
+
+pub use report::to_markdown as sync_report_to_markdown;
 
 fn registry_entry(adapter: &AdapterType) -> &'static crate::models::registry::ToolEntry {
     REGISTRY.get(adapter).unwrap_or_else(|| {
@@ -47,6 +68,24 @@ pub trait SyncAdapter: Send + Sync {
 
     fn format_content(&self, rules: &[Rule], enabled_rules_only: bool) -> String;
     fn format_rule(&self, rule: &Rule) -> String;
+
+    /// The files a target's rules are written to, each with the rules it holds. `path` is
+    /// the global path or `file_name` in the target directory, and tools that read one
+    /// file get just that.
+    fn files(&self, path: PathBuf, rules: Vec<Rule>) -> Vec<(PathBuf, Vec<Rule>)> {
+        vec![(path, rules)]
+    }
+
+    /// The content of `path`, one of the files from [`SyncAdapter::files`].
+    fn format_file(&self, _path: &Path, rules: &[Rule]) -> String {
+        self.format_content(rules, true)
+    }
+
+    /// Files written for the target at `path` by an earlier sync that are no longer among
+    /// `files` and should be removed.
+    fn stale_files(&self, _path: &Path, _files: &[PathBuf]) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 pub fn format_markdown_sync_helper(
@@ -410,11 +449,8 @@ impl SyncAdapter for WindsurfAdapter {
     }
 
     fn file_name(&self) -> &str {
-        let entry = registry_entry(&self.id());
-        Path::new(entry.paths.local_path_template)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("rules.md")
+        // Workspaces using the rules directory get their files from `files` instead.
+        LEGACY_WINDSURF_FILENAME
     }
 
     fn description(&self) -> &str {
@@ -433,6 +469,36 @@ impl SyncAdapter for WindsurfAdapter {
     fn format_rule(&self, rule: &Rule) -> String {
         format!("## {}\n{}", rule.name, rule.content)
     }
+
+    fn files(&self, path: PathBuf, rules: Vec<Rule>) -> Vec<(PathBuf, Vec<Rule>)> {
+        match windsurf::workspace_of(&path) {
+            Some(workspace) if windsurf::detect(workspace) == windsurf::RulesFormat::Cascade => {
+                windsurf::rule_files(workspace, rules)
+            }
+            _ => vec![(path, rules)],
+        }
+    }
+
+    fn format_file(&self, path: &Path, rules: &[Rule]) -> String {
+        let global = self.global_path().ok();
+        match rules {
+            [rule] if windsurf::is_rule_file(path) && global.as_deref() != Some(path) => {
+                windsurf::format_rule_file(rule)
+            }
+            _ => self.format_content(rules, true),
+        }
+    }
+
+    fn stale_files(&self, path: &Path, files: &[PathBuf]) -> Vec<PathBuf> {
+        let Some(workspace) = windsurf::workspace_of(path) else {
+            return Vec::new();
+        };
+        let global = self.global_path().ok();
+        windsurf::stale_files(workspace, files)
+            .into_iter()
+            .filter(|stale| global.as_ref() != Some(stale))
+            .collect()
+    }
 }
 
 pub struct RooCodeAdapter;
@@ -503,6 +569,35 @@ pub fn get_adapter(adapter_type: AdapterType) -> Option<Box<dyn SyncAdapter>> {
     }
 }
 
+/// Setting holding the per-adapter switches as a map of adapter id to enabled. Adapters
+/// missing from the map are enabled.
+pub const ADAPTER_SETTINGS_KEY: &str = "adapter_settings";
+
+/// Adapters switched off in settings. Whatever a rule's own adapters say, sync,
+/// reconciliation and import scans leave these tools alone.
+pub async fn disabled_adapters(db: &Database) -> HashSet<AdapterType> {
+    match db.get_setting(ADAPTER_SETTINGS_KEY).await {
+        Ok(Some(settings_json)) => {
+            match serde_json::from_str::<HashMap<String, bool>>(&settings_json) {
+                Ok(settings_map) => settings_map
+                    .into_iter()
+                    .filter(|(_, enabled)| !enabled)
+                    .filter_map(|(id, _)| AdapterType::from_str(&id).ok())
+                    .collect(),
+                Err(e) => {
+                    log::warn!("Failed to deserialize adapter_settings: {}", e);
+                    HashSet::new()
+                }
+            }
+        }
+        Ok(None) => HashSet::new(),
+        Err(e) => {
+            log::warn!("Failed to load adapter_settings from database: {}", e);
+            HashSet::new()
+        }
+    }
+}
+
 pub struct SyncEngine<'a> {
     db: &'a Database,
 }
@@ -512,42 +607,29 @@ impl<'a> SyncEngine<'a> {
         Self { db }
     }
 
-    async fn get_disabled_adapters(&self) -> HashSet<AdapterType> {
-        match self.db.get_setting("adapter_settings").await {
-            Ok(Some(settings_json)) => {
-                match serde_json::from_str::<HashMap<String, bool>>(&settings_json) {
-                    Ok(settings_map) => settings_map
-                        .into_iter()
-                        .filter(|(_, enabled)| !enabled)
-                        .filter_map(|(id, _)| AdapterType::from_str(&id).ok())
-                        .collect(),
-                    Err(e) => {
-                        eprintln!("Warning: Failed to deserialize adapter_settings: {}", e);
-                        HashSet::new()
-                    }
-                }
-            }
-            Ok(None) => HashSet::new(),
-            Err(e) => {
-                eprintln!(
-                    "Warning: Failed to load adapter_settings from database: {}",
-                    e
-                );
-                HashSet::new()
-            }
-        }
+    pub async fn get_disabled_adapters(&self) -> HashSet<AdapterType> {
+        disabled_adapters(self.db).await
+    }
+
+    /// The rules as they are written: snippets expanded, ordered by their constraints, then
+    /// project configs applied.
+    async fn prepare(&self, rules: Vec<Rule>) -> Vec<Rule> {
+        let rules = rule_order::order(snippets::expand_rules(self.db, rules).await);
+        project_config::apply_to_rules(rules)
     }
 
     pub async fn sync_all(&self, rules: Vec<Rule>) -> SyncResult {
-        let mut files_written = Vec::new();
-        let mut errors = Vec::new();
+        let mut report = SyncReportBuilder::new();
         let conflicts = Vec::new();
+        let rules = self.prepare(rules).await;
 
         let disabled_adapters = self.get_disabled_adapters().await;
+        let languages = localization::adapter_languages(self.db).await;
         let adapters = get_all_adapters();
 
         for adapter in &adapters {
             if disabled_adapters.contains(&adapter.id()) {
+                report.skip(adapter.as_ref(), "Disabled in settings");
                 continue;
             }
 
@@ -559,13 +641,18 @@ impl<'a> SyncEngine<'a> {
                             .validate_support(&adapter.id(), &r.scope, ArtifactType::Rule)
                             .is_ok()
                 })
-                .cloned()
+                .map(|r| {
+                    localization::localize(r, languages.get(&adapter.id()).map(String::as_str))
+                })
                 .collect();
 
             if adapter_rules.is_empty() {
                 continue;
             }
 
+            let entry = report.adapter(adapter.as_ref());
+            let mut targets: Vec<(PathBuf, Vec<Rule>)> = Vec::new();
+
             let global_rules: Vec<Rule> = adapter_rules
                 .iter()
                 .filter(|r| r.scope == Scope::Global)
@@ -573,24 +660,16 @@ impl<'a> SyncEngine<'a> {
                 .collect();
 
             if !global_rules.is_empty() {
-                let path = match adapter.global_path() {
-                    Ok(p) => p,
+                match adapter.global_path() {
+                    Ok(path) => targets.push((path, global_rules)),
                     Err(e) => {
-                        errors.push(SyncError {
+                        entry.errors.push(SyncError {
                             file_path: String::new(),
                             adapter_name: adapter.name().to_string(),
                             message: e.to_string(),
                         });
                         continue;
                     }
-                };
-                match self.sync_file(adapter.as_ref(), &global_rules, &path).await {
-                    Ok(()) => files_written.push(path.to_string_lossy().to_string()),
-                    Err(e) => errors.push(SyncError {
-                        file_path: path.to_string_lossy().to_string(),
-                        adapter_name: adapter.name().to_string(),
-                        message: e.to_string(),
-                    }),
                 }
             }
 
@@ -604,7 +683,7 @@ impl<'a> SyncEngine<'a> {
                                     map.entry(path.clone()).or_default().push(rule.clone());
                                 }
                                 Err(e) => {
-                                    errors.push(SyncError {
+                                    entry.errors.push(SyncError {
                                         file_path: path.clone(),
                                         adapter_name: adapter.name().to_string(),
                                         message: e.to_string(),
@@ -618,51 +697,29 @@ impl<'a> SyncEngine<'a> {
             };
 
             for (base_path, path_rules) in local_rules_by_path {
-                let path = PathBuf::from(&base_path).join(adapter.file_name());
-                match self.sync_file(adapter.as_ref(), &path_rules, &path).await {
-                    Ok(()) => files_written.push(path.to_string_lossy().to_string()),
-                    Err(e) => errors.push(SyncError {
-                        file_path: path.to_string_lossy().to_string(),
-                        adapter_name: adapter.name().to_string(),
-                        message: e.to_string(),
-                    }),
-                }
+                targets.push((
+                    PathBuf::from(&base_path).join(adapter.file_name()),
+                    path_rules,
+                ));
             }
-        }
-
-        let success = errors.is_empty() && conflicts.is_empty();
 
-        let status = if errors.is_empty() {
-            "success"
-        } else if !files_written.is_empty() {
-            "partial"
-        } else {
-            "failed"
-        };
-
-        let _ = self
-            .db
-            .add_sync_log(files_written.len() as u32, status, "manual")
-            .await;
-
-        SyncResult {
-            success,
-            files_written,
-            errors,
-            conflicts,
+            self.sync_adapter_files(adapter.as_ref(), &targets, entry)
+                .await;
         }
+
+        self.finish(report.finish("manual"), conflicts).await
     }
 
     pub async fn sync_rule(&self, rule: Rule) -> SyncResult {
-        let mut files_written = Vec::new();
-        let mut errors = Vec::new();
+        let mut report = SyncReportBuilder::new();
         let conflicts = Vec::new();
 
         let disabled_adapters = self.get_disabled_adapters().await;
+        let languages = localization::adapter_languages(self.db).await;
         let adapters = get_all_adapters();
 
         let all_rules = match self.db.get_all_rules().await {
-            Ok(r) => r,
+            Ok(r) => self.prepare(r).await,
             Err(e) => {
                 return SyncResult {
                     success: false,
@@ -673,28 +730,41 @@ impl<'a> SyncEngine<'a> {
                         message: format!("Failed to fetch rules: {}", e),
                     }],
                     conflicts: vec![],
+                    uninstalled_tools: vec![],
+                    rolled_back_adapters: vec![],
                 };
             }
         };
 
+        // A repository's project config can write the rule for other tools than its own.
+        let scoped_rule = project_config::apply_to_rules(vec![rule.clone()]);
         for adapter in &adapters {
-            if disabled_adapters.contains(&adapter.id())
-                || !rule.enabled_adapters.contains(&adapter.id())
+            if !scoped_rule
+                .iter()
+                .any(|r| r.enabled_adapters.contains(&adapter.id()))
                 || REGISTRY
                     .validate_support(&adapter.id(), &rule.scope, ArtifactType::Rule)
                     .is_err()
             {
                 continue;
             }
+            if disabled_adapters.contains(&adapter.id()) {
+                report.skip(adapter.as_ref(), "Disabled in settings");
+                continue;
+            }
+
+            let entry = report.adapter(adapter.as_ref());
+            let language = languages.get(&adapter.id()).map(String::as_str);
 
             // For each adapter, we need to sync the file(s) this rule belongs to.
             // This means re-collecting ALL rules for that target file to ensure its content is correct.
 
+            let mut targets: Vec<(PathBuf, Vec<Rule>)> = Vec::new();
             if rule.scope == Scope::Global {
                 let path = match adapter.global_path() {
                     Ok(p) => p,
                     Err(e) => {
-                        errors.push(SyncError {
+                        entry.errors.push(SyncError {
                             file_path: String::new(),
                             adapter_name: adapter.name().to_string(),
                             message: e.to_string(),
@@ -708,17 +778,9 @@ impl<'a> SyncEngine<'a> {
                     .filter(|r| {
                         r.scope == Scope::Global && r.enabled_adapters.contains(&adapter.id())
                     })
-                    .cloned()
+                    .map(|r| localization::localize(r, language))
                     .collect();
-
-                match self.sync_file(adapter.as_ref(), &global_rules, &path).await {
-                    Ok(()) => files_written.push(path.to_string_lossy().to_string()),
-                    Err(e) => errors.push(SyncError {
-                        file_path: path.to_string_lossy().to_string(),
-                        adapter_name: adapter.name().to_string(),
-                        message: e.to_string(),
-                    }),
-                }
+                targets.push((path, global_rules));
             } else if rule.scope == Scope::Local {
                 if let Some(paths) = &rule.target_paths {
                     for base_path in paths {
@@ -735,49 +797,113 @@ impl<'a> SyncEngine<'a> {
                                             .map(|p| p.contains(base_path))
                                             .unwrap_or(false)
                                 })
-                                .cloned()
+                                .map(|r| localization::localize(r, language))
                                 .collect();
-
-                            match self.sync_file(adapter.as_ref(), &path_rules, &path).await {
-                                Ok(()) => files_written.push(path.to_string_lossy().to_string()),
-                                Err(e) => errors.push(SyncError {
-                                    file_path: path.to_string_lossy().to_string(),
-                                    adapter_name: adapter.name().to_string(),
-                                    message: e.to_string(),
-                                }),
+                            if !path_rules.is_empty() {
+                                targets.push((path, path_rules));
                             }
                         }
                     }
                 }
             }
+
+            self.sync_adapter_files(adapter.as_ref(), &targets, entry)
+                .await;
         }
 
-        let status = if errors.is_empty() {
-            "success"
-        } else if !files_written.is_empty() {
-            "partial"
-        } else {
-            "failed"
-        };
+        self.finish(report.finish("auto"), conflicts).await
+    }
 
-        let _ = self
-            .db
-            .add_sync_log(files_written.len() as u32, status, "auto")
-            .await;
+    /// Persists the report with its history entry and notifies webhooks.
+    async fn finish(&self, report: SyncReport, conflicts: Vec<Conflict>) -> SyncResult {
+        if let Err(e) = self.db.add_sync_report(&report).await {
+            log::warn!("Failed to record sync report: {}", e);
+        }
 
-        SyncResult {
-            success: errors.is_empty(),
-            files_written,
+        let labels = [
+            ("trigger", report.triggered_by.as_str()),
+            ("status", report.status.as_str()),
+        ];
+        metrics::increment(metrics::SYNC_RUNS, &labels);
+        metrics::observe(
+            metrics::SYNC_DURATION,
+            &labels[..1],
+            report.duration_ms as f64 / 1000.0,
+        );
+
+        let errors = report.errors();
+        let result = SyncResult {
+            success: errors.is_empty() && conflicts.is_empty(),
+            files_written: report.files_written(),
             errors,
             conflicts,
-        }
+            uninstalled_tools: report
+                .adapters
+                .iter()
+                .filter(|a| !a.files.is_empty() && a.installed == Some(false))
+                .map(|a| a.adapter)
+                .collect(),
+            rolled_back_adapters: report
+                .adapters
+                .iter()
+                .filter(|a| a.rolled_back)
+                .map(|a| a.adapter)
+                .collect(),
+        };
+        metrics::add(
+            metrics::SYNC_FILES_WRITTEN,
+            &[],
+            result.files_written.len() as u64,
+        );
+        audit::record_run(
+            self.db,
+            AuditAction::Sync,
+            format!(
+                "Sync ({}) {}: {} file(s) written, {} error(s), {} conflict(s)",
+                report.triggered_by,
+                report.status,
+                result.files_written.len(),
+                result.errors.len(),
+                result.conflicts.len()
+            ),
+        )
+        .await;
+        self.notify_webhooks(&result, &report.triggered_by).await;
+        result
+    }
+
+    async fn notify_webhooks(&self, result: &SyncResult, triggered_by: &str) {
+        let text = if result.errors.is_empty() {
+            format!("RuleWeaver synced {} file(s)", result.files_written.len())
+        } else {
+            format!(
+                "RuleWeaver synced {} file(s) with {} error(s)",
+                result.files_written.len(),
+                result.errors.len()
+            )
+        };
+        webhooks::emit(
+            self.db,
+            WebhookEvent::SyncCompleted,
+            &text,
+            serde_json::json!({
+                "triggeredBy": triggered_by,
+                "success": result.success,
+                "filesWritten": result.files_written,
+                "errors": result.errors,
+            }),
+        )
+        .await;
     }
 
     pub async fn preview(&self, rules: Vec<Rule>) -> SyncResult {
         let mut files_written = Vec::new();
         let mut conflicts = Vec::new();
+        let mut uninstalled_tools = Vec::new();
+        let rules = self.prepare(rules).await;
 
         let disabled_adapters = self.get_disabled_adapters().await;
+        let languages = localization::adapter_languages(self.db).await;
         let adapters = get_all_adapters();
 
         for adapter in &adapters {
@@ -793,12 +919,17 @@ impl<'a> SyncEngine<'a> {
                             .validate_support(&adapter.id(), &r.scope, ArtifactType::Rule)
                             .is_ok()
                 })
-                .cloned()
+                .map(|r| {
+                    localization::localize(r, languages.get(&adapter.id()).map(String::as_str))
+                })
                 .collect();
 
             if adapter_rules.is_empty() {
                 continue;
             }
+            if !tool_detection::is_installed(adapter.id()) {
+                uninstalled_tools.push(adapter.id());
+            }
 
             let global_rules: Vec<Rule> = adapter_rules
                 .iter()
@@ -811,35 +942,45 @@ impl<'a> SyncEngine<'a> {
                     Ok(p) => p,
                     Err(_) => continue,
                 };
-                files_written.push(path.to_string_lossy().to_string());
-
-                // Snapshot: read file content once into memory before any hash comparison.
-                // This eliminates the race window between reading the stored hash and computing
-                // the current hash — both operations now use the same in-memory buffer.
-                if let Some(stored_hash) = self
-                    .db
-                    .get_file_hash(&path.to_string_lossy())
-                    .await
-                    .ok()
-                    .flatten()
-                {
-                    if path.exists() {
-                        if let Ok(current_content) = fs::read_to_string(&path) {
-                            let current_hash = compute_content_hash(&current_content);
-                            if stored_hash != current_hash {
-                                let expected_content = adapter.format_content(&global_rules, true);
-                                let diff_summary =
-                                    compute_diff_summary(&expected_content, &current_content);
-                                conflicts.push(Conflict {
-                                    id: uuid::Uuid::new_v4().to_string(),
-                                    file_path: path.to_string_lossy().to_string(),
-                                    adapter_name: adapter.name().to_string(),
-                                    adapter_id: Some(adapter.id()),
-                                    local_hash: stored_hash,
-                                    current_hash,
-                                    scope: Some("global".to_string()),
-                                    diff_summary: Some(diff_summary),
-                                });
+                for (path, global_rules) in adapter.files(path, global_rules) {
+                    files_written.push(path.to_string_lossy().to_string());
+
+                    // Snapshot: read file content once into memory before any hash
+                    // comparison. This eliminates the race window between reading the stored
+                    // hash and computing the current hash — both operations now use the same
+                    // in-memory buffer.
+                    if let Some(stored_hash) = self
+                        .db
+                        .get_file_hash(&path.to_string_lossy())
+                        .await
+                        .ok()
+                        .flatten()
+                    {
+                        if path.exists() {
+                            if let Ok(current_content) = fs::read_to_string(&path) {
+                                let current_hash = file_content_hash(&current_content);
+                                // Hashes stored by earlier releases are of the raw content.
+                                if stored_hash != current_hash
+                                    && stored_hash != compute_content_hash(&current_content)
+                                {
+                                    let expected_content = format_cache::format_content(
+                                        adapter.as_ref(),
+                                        path.as_path(),
+                                        &global_rules,
+                                    );
+                                    let diff_summary =
+                                        compute_diff_summary(&expected_content, &current_content);
+                                    conflicts.push(Conflict {
+                                        id: uuid::Uuid::new_v4().to_string(),
+                                        file_path: path.to_string_lossy().to_string(),
+                                        adapter_name: adapter.name().to_string(),
+                                        adapter_id: Some(adapter.id()),
+                                        local_hash: stored_hash,
+                                        current_hash,
+                                        scope: Some("global".to_string()),
+                                        diff_summary: Some(diff_summary),
+                                    });
+                                }
                             }
                         }
                     }
@@ -862,35 +1003,45 @@ impl<'a> SyncEngine<'a> {
 
             for (base_path, path_rules) in local_rules_by_path {
                 let path = PathBuf::from(&base_path).join(adapter.file_name());
-                files_written.push(path.to_string_lossy().to_string());
-
-                // Snapshot: read file content once into memory before any hash comparison.
-                // This eliminates the race window between reading the stored hash and computing
-                // the current hash — both operations now use the same in-memory buffer.
-                if let Some(stored_hash) = self
-                    .db
-                    .get_file_hash(&path.to_string_lossy())
-                    .await
-                    .ok()
-                    .flatten()
-                {
-                    if path.exists() {
-                        if let Ok(current_content) = fs::read_to_string(&path) {
-                            let current_hash = compute_content_hash(&current_content);
-                            if stored_hash != current_hash {
-                                let expected_content = adapter.format_content(&path_rules, true);
-                                let diff_summary =
-                                    compute_diff_summary(&expected_content, &current_content);
-                                conflicts.push(Conflict {
-                                    id: uuid::Uuid::new_v4().to_string(),
-                                    file_path: path.to_string_lossy().to_string(),
-                                    adapter_name: adapter.name().to_string(),
-                                    adapter_id: Some(adapter.id()),
-                                    local_hash: stored_hash,
-                                    current_hash,
-                                    scope: Some("local".to_string()),
-                                    diff_summary: Some(diff_summary),
-                                });
+                for (path, path_rules) in adapter.files(path, path_rules) {
+                    files_written.push(path.to_string_lossy().to_string());
+
+                    // Snapshot: read file content once into memory before any hash
+                    // comparison. This eliminates the race window between reading the stored
+                    // hash and computing the current hash — both operations now use the same
+                    // in-memory buffer.
+                    if let Some(stored_hash) = self
+                        .db
+                        .get_file_hash(&path.to_string_lossy())
+                        .await
+                        .ok()
+                        .flatten()
+                    {
+                        if path.exists() {
+                            if let Ok(current_content) = fs::read_to_string(&path) {
+                                let current_hash = file_content_hash(&current_content);
+                                // Hashes stored by earlier releases are of the raw content.
+                                if stored_hash != current_hash
+                                    && stored_hash != compute_content_hash(&current_content)
+                                {
+                                    let expected_content = format_cache::format_content(
+                                        adapter.as_ref(),
+                                        path.as_path(),
+                                        &path_rules,
+                                    );
+                                    let diff_summary =
+                                        compute_diff_summary(&expected_content, &current_content);
+                                    conflicts.push(Conflict {
+                                        id: uuid::Uuid::new_v4().to_string(),
+                                        file_path: path.to_string_lossy().to_string(),
+                                        adapter_name: adapter.name().to_string(),
+                                        adapter_id: Some(adapter.id()),
+                                        local_hash: stored_hash,
+                                        current_hash,
+                                        scope: Some("local".to_string()),
+                                        diff_summary: Some(diff_summary),
+                                    });
+                                }
                             }
                         }
                     }
@@ -903,50 +1054,237 @@ impl<'a> SyncEngine<'a> {
             files_written,
             errors: vec![],
             conflicts,
+            uninstalled_tools,
+            rolled_back_adapters: Vec::new(),
         }
     }
 
-    async fn sync_file(
+    /// What a full sync would write, keyed by target path.
+    async fn expected_files(&self, rules: &[Rule]) -> HashMap<String, String> {
+        let disabled_adapters = self.get_disabled_adapters().await;
+        let languages = localization::adapter_languages(self.db).await;
+        let mut expected = HashMap::new();
+
+        for adapter in get_all_adapters() {
+            if disabled_adapters.contains(&adapter.id()) {
+                continue;
+            }
+            let adapter_rules: Vec<Rule> = rules
+                .iter()
+                .filter(|r| {
+                    r.enabled_adapters.contains(&adapter.id())
+                        && REGISTRY
+                            .validate_support(&adapter.id(), &r.scope, ArtifactType::Rule)
+                            .is_ok()
+                })
+                .map(|r| {
+                    localization::localize(r, languages.get(&adapter.id()).map(String::as_str))
+                })
+                .collect();
+
+            let global_rules: Vec<Rule> = adapter_rules
+                .iter()
+                .filter(|r| r.scope == Scope::Global)
+                .cloned()
+                .collect();
+            if !global_rules.is_empty() {
+                if let Ok(path) = adapter.global_path() {
+                    for (path, rules) in adapter.files(path, global_rules) {
+                        expected.insert(
+                            path.to_string_lossy().to_string(),
+                            format_cache::format_content(adapter.as_ref(), &path, &rules),
+                        );
+                    }
+                }
+            }
+
+            let mut local_rules_by_path: HashMap<String, Vec<Rule>> = HashMap::new();
+            for rule in adapter_rules.iter().filter(|r| r.scope == Scope::Local) {
+                for base_path in rule.target_paths.iter().flatten() {
+                    if validate_target_path(base_path).is_ok() {
+                        local_rules_by_path
+                            .entry(base_path.clone())
+                            .or_default()
+                            .push(rule.clone());
+                    }
+                }
+            }
+            for (base_path, path_rules) in local_rules_by_path {
+                let path = PathBuf::from(&base_path).join(adapter.file_name());
+                for (path, rules) in adapter.files(path, path_rules) {
+                    expected.insert(
+                        path.to_string_lossy().to_string(),
+                        format_cache::format_content(adapter.as_ref(), &path, &rules),
+                    );
+                }
+            }
+        }
+
+        expected
+    }
+
+    /// Brings `sync_history` back in line with the files on disk, e.g. after a crash or a
+    /// database restore left it stale. A file whose content is exactly what sync would write
+    /// now gets its hash stored again; hashes of deleted files are dropped. Files that were
+    /// edited outside RuleWeaver keep their old hash, so they are still reported as conflicts.
+    pub async fn repair_file_hashes(&self, rules: &[Rule]) -> Result<SyncHashRepair> {
+        let expected = self
+            .expected_files(&self.prepare(rules.to_vec()).await)
+            .await;
+        let stored: HashMap<String, String> =
+            self.db.get_file_hashes().await?.into_iter().collect();
+        let paths: BTreeSet<&String> = stored.keys().chain(expected.keys()).collect();
+
+        let mut repair = SyncHashRepair::default();
+        for path in paths {
+            if !Path::new(path).exists() {
+                if stored.contains_key(path) {
+                    repair.removed.push(path.clone());
+                }
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            repair.files_checked += 1;
+
+            let current_hash = file_content_hash(&content);
+            let stored_hash = stored.get(path);
+            if stored_hash == Some(&current_hash) {
+                continue;
+            }
+            if expected.get(path).map(|c| file_content_hash(c)) == Some(current_hash.clone()) {
+                self.db.set_file_hash(path, &current_hash).await?;
+                repair.repaired.push(path.clone());
+            } else if stored_hash.is_some() {
+                repair.modified.push(path.clone());
+            }
+        }
+
+        self.db.remove_file_hashes(repair.removed.clone()).await?;
+        Ok(repair)
+    }
+
+    /// Writes one adapter's files as a batch: either all of them change or, when one of
+    /// them cannot be written, none does and the adapter is reported as rolled back. Files
+    /// an earlier sync wrote that are no longer needed are removed once the batch is in.
+    async fn sync_adapter_files(
         &self,
         adapter: &dyn SyncAdapter,
-        rules: &[Rule],
-        path: &Path,
-    ) -> Result<()> {
-        log::debug!(
-            "Syncing {} rules to {} ({}) at {}",
-            rules.len(),
-            adapter.name(),
-            adapter.description(),
-            path.display()
-        );
+        targets: &[(PathBuf, Vec<Rule>)],
+        entry: &mut AdapterSyncReport,
+    ) {
+        let error = |path: &Path, e: AppError| SyncError {
+            file_path: path.to_string_lossy().to_string(),
+            adapter_name: adapter.name().to_string(),
+            message: e.to_string(),
+        };
 
-        for rule in rules {
-            log::trace!("Rule content: {}", adapter.format_rule(rule));
+        let files: Vec<(PathBuf, Vec<Rule>)> = targets
+            .iter()
+            .flat_map(|(path, rules)| adapter.files(path.clone(), rules.clone()))
+            .collect();
+        let paths: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+        let stale: Vec<PathBuf> = targets
+            .iter()
+            .flat_map(|(path, _)| adapter.stale_files(path, &paths))
+            .collect();
+
+        let mut batch = WriteBatch::new();
+        let mut staged = Vec::with_capacity(files.len());
+        let mut failed = Vec::new();
+        for (path, rules) in &files {
+            match stage_file(&mut batch, adapter, rules, path) {
+                Ok(file) => staged.push(file),
+                Err(e) => failed.push(error(path, e)),
+            }
+        }
+        if failed.is_empty() {
+            if let Err(e) = batch.commit() {
+                failed.push(error(Path::new(""), e));
+            }
+        }
+        if !failed.is_empty() {
+            log::warn!(
+                "Sync of {} failed; none of its {} file(s) were changed",
+                adapter.name(),
+                files.len()
+            );
+            entry.errors.extend(failed);
+            entry.rolled_back = true;
+            return;
         }
 
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
+        let mut removed = Vec::with_capacity(stale.len());
+        for path in stale {
+            match fs::remove_file(&path) {
+                Ok(()) => removed.push(path.to_string_lossy().to_string()),
+                Err(e) => entry.errors.push(error(&path, e.into())),
+            }
+        }
+        if let Err(e) = self.db.remove_file_hashes(removed).await {
+            log::warn!("Failed to forget hashes of removed files: {}", e);
         }
 
-        let content = adapter.format_content(rules, true);
-        let hash = compute_content_hash(&content);
+        for file in staged {
+            let path = file.path.clone();
+            match self.record_file(adapter, file).await {
+                Ok(file) => entry.files.push(file),
+                Err(e) => entry.errors.push(error(&path, e)),
+            }
+        }
+    }
 
-        fs::write(path, &content)?;
+    /// Writes a single adapter file.
+    async fn sync_file(
+        &self,
+        adapter: &dyn SyncAdapter,
+        rules: &[Rule],
+        path: &Path,
+    ) -> Result<SyncReportFile> {
+        let mut batch = WriteBatch::new();
+        let file = stage_file(&mut batch, adapter, rules, path)?;
+        batch.commit()?;
+        self.record_file(adapter, file).await
+    }
 
-        self.db
-            .set_file_hash(&path.to_string_lossy(), &hash)
-            .await?;
+    /// Stores the hash of a written file for conflict detection and records rule usage.
+    async fn record_file(
+        &self,
+        adapter: &dyn SyncAdapter,
+        file: StagedSyncFile,
+    ) -> Result<SyncReportFile> {
+        let path = file.path.to_string_lossy().to_string();
+        self.db.set_file_hash(&path, &file.hash).await?;
 
-        Ok(())
+        if let Err(e) = self
+            .db
+            .record_rule_sync(adapter.id(), &path, file.rule_bytes)
+            .await
+        {
+            log::warn!("Failed to record rule usage for {}: {}", path, e);
+        }
+
+        Ok(SyncReportFile {
+            path,
+            hash: file.hash,
+            rule_count: file.rule_count,
+            bytes: file.bytes,
+            duration_ms: file.duration_ms,
+        })
     }
 
     pub async fn sync_file_by_path(&self, rules: &[Rule], file_path: &str) -> Result<()> {
         validate_target_path(file_path)?;
 
+        // Snippets are expanded here too, so this file matches what a full sync writes.
+        let rules = self.prepare(rules.to_vec()).await;
         let path = PathBuf::from(file_path);
         let adapters = get_all_adapters();
+        let languages = localization::adapter_languages(self.db).await;
 
         for adapter in &adapters {
+            let language = languages.get(&adapter.id()).map(String::as_str);
             if let Ok(adapter_path) = adapter.global_path() {
                 if adapter_path == path {
                     let adapter_rules: Vec<Rule> = rules
@@ -954,13 +1292,14 @@ impl<'a> SyncEngine<'a> {
                         .filter(|r| {
                             r.enabled_adapters.contains(&adapter.id()) && r.scope == Scope::Global
                         })
-                        .cloned()
+                        .map(|r| localization::localize(r, language))
                         .collect();
 
                     if !adapter_rules.is_empty() {
                         return self
                             .sync_file(adapter.as_ref(), &adapter_rules, &path)
-                            .await;
+                            .await
+                            .map(|_| ());
                     }
                 }
             }
@@ -979,11 +1318,14 @@ impl<'a> SyncEngine<'a> {
                                     .map(|paths| paths.contains(&parent_str.to_string()))
                                     .unwrap_or(false)
                         })
-                        .cloned()
+                        .map(|r| localization::localize(r, language))
                         .collect();
 
                     if !local_rules.is_empty() {
-                        return self.sync_file(adapter.as_ref(), &local_rules, &path).await;
+                        return self
+                            .sync_file(adapter.as_ref(), &local_rules, &path)
+                            .await
+                            .map(|_| ());
                     }
                 }
             }
@@ -995,6 +1337,56 @@ impl<'a> SyncEngine<'a> {
     }
 }
 
+/// An adapter file staged in a [`WriteBatch`], recorded once the batch commits.
+struct StagedSyncFile {
+    path: PathBuf,
+    hash: String,
+    rule_bytes: Vec<(String, u64)>,
+    rule_count: u32,
+    bytes: u64,
+    duration_ms: u64,
+}
+
+fn stage_file(
+    batch: &mut WriteBatch,
+    adapter: &dyn SyncAdapter,
+    rules: &[Rule],
+    path: &Path,
+) -> Result<StagedSyncFile> {
+    let started = Instant::now();
+    log::debug!(
+        "Syncing {} rules to {} ({}) at {}",
+        rules.len(),
+        adapter.name(),
+        adapter.description(),
+        path.display()
+    );
+
+    for rule in rules {
+        log::trace!("Rule content: {}", adapter.format_rule(rule));
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = format_cache::format_content(adapter, path, rules);
+    batch.stage(path, &content)?;
+
+    Ok(StagedSyncFile {
+        path: path.to_path_buf(),
+        hash: file_content_hash(&content),
+        rule_bytes: rules
+            .iter()
+            .filter(|r| r.enabled)
+            .map(|r| (r.id.clone(), adapter.format_rule(r).len() as u64))
+            .collect(),
+        rule_count: rules.iter().filter(|r| r.enabled).count() as u32,
+        bytes: content.len() as u64,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
 /// Computes a simple line-level diff summary between two content strings.
 ///
 /// Uses set difference to count lines unique to each side.  Lines present in
@@ -1036,6 +1428,14 @@ pub fn compute_content_hash_public(content: &str) -> String {
     compute_content_hash(content)
 }
 
+/// Hash recorded for a file sync writes. The file is hashed in its normalized Markdown
+/// form, so an edit in a tool that only changes formatting is not reported as a conflict.
+pub fn file_content_hash(content: &str) -> String {
+    format_cache::normalized_hash(content, || {
+        compute_content_hash(&markdown::normalize(content))
+    })
+}
+
 #[allow(dead_code)]
 fn compute_file_hash(path: &Path) -> Result<String> {
     let content = fs::read_to_string(path)?;
@@ -1112,6 +1512,7 @@ mod tests {
             enabled: true,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            metadata: Default::default(),
         }
     }
 
@@ -1174,6 +1575,19 @@ mod tests {
         assert_eq!(hash1.len(), 64);
     }
 
+    #[test]
+    fn test_file_content_hash_ignores_formatting() {
+        let written = "# Rules\n\n- Use tabs.\n- Keep lines short.\n";
+        assert_eq!(
+            file_content_hash(written),
+            file_content_hash("# Rules\r\n\r\n\r\n* Use tabs.  \r\n* Keep lines short.")
+        );
+        assert_ne!(
+            file_content_hash(written),
+            file_content_hash(&written.replace("tabs", "spaces"))
+        );
+    }
+
     #[test]
     fn test_disabled_rules_not_included() {
         let adapter = GeminiAdapter;
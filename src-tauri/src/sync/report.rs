@@ -0,0 +1,208 @@
+//! Collects the per-adapter outcome of a sync run and renders it for export.
+
+use std::fmt::Write as _;
+use std::time::Instant;
+
+use chrono::Utc;
+
+use super::SyncAdapter;
+use crate::models::{AdapterSyncReport, SyncReport};
+use crate::tool_detection;
+
+pub(super) struct SyncReportBuilder {
+    started: Instant,
+    adapters: Vec<AdapterSyncReport>,
+}
+
+impl SyncReportBuilder {
+    pub(super) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            adapters: Vec::new(),
+        }
+    }
+
+    /// Starts the section for an adapter; files and errors are pushed onto the returned entry.
+    pub(super) fn adapter(&mut self, adapter: &dyn SyncAdapter) -> &mut AdapterSyncReport {
+        self.adapters.push(AdapterSyncReport {
+            adapter: adapter.id(),
+            adapter_name: adapter.name().to_string(),
+            duration_ms: 0,
+            files: Vec::new(),
+            skipped_reason: None,
+            errors: Vec::new(),
+            installed: Some(tool_detection::is_installed(adapter.id())),
+            rolled_back: false,
+        });
+        self.adapters.last_mut().expect("entry was just pushed")
+    }
+
+    pub(super) fn skip(&mut self, adapter: &dyn SyncAdapter, reason: &str) {
+        self.adapter(adapter).skipped_reason = Some(reason.to_string());
+    }
+
+    pub(super) fn finish(mut self, triggered_by: &str) -> SyncReport {
+        for adapter in &mut self.adapters {
+            adapter.duration_ms = adapter.files.iter().map(|f| f.duration_ms).sum();
+        }
+        let written = self.adapters.iter().any(|a| !a.files.is_empty());
+        let failed = self.adapters.iter().any(|a| !a.errors.is_empty());
+        let status = if !failed {
+            "success"
+        } else if written {
+            "partial"
+        } else {
+            "failed"
+        };
+
+        SyncReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            triggered_by: triggered_by.to_string(),
+            status: status.to_string(),
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            adapters: self.adapters,
+        }
+    }
+}
+
+/// Renders a report as Markdown for pasting into an issue or chat.
+pub fn to_markdown(report: &SyncReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Sync report {}", report.id);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- Time: {}", report.timestamp.to_rfc3339());
+    let _ = writeln!(out, "- Triggered by: {}", report.triggered_by);
+    let _ = writeln!(out, "- Status: {}", report.status);
+    let _ = writeln!(out, "- Duration: {} ms", report.duration_ms);
+
+    for adapter in &report.adapters {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## {}", adapter.adapter_name);
+        let _ = writeln!(out);
+        if let Some(reason) = &adapter.skipped_reason {
+            let _ = writeln!(out, "Skipped: {}", reason);
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "{} file(s) written in {} ms, {} error(s).",
+            adapter.files.len(),
+            adapter.duration_ms,
+            adapter.errors.len()
+        );
+        if adapter.installed == Some(false) {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "The tool was not detected on this machine.");
+        }
+        if adapter.rolled_back {
+            let _ = writeln!(out);
+            let _ = writeln!(
+                out,
+                "Rolled back: a file failed to write, so none of this tool's files changed."
+            );
+        }
+        if !adapter.files.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "| File | Rules | Bytes | Duration | Hash |");
+            let _ = writeln!(out, "| --- | ---: | ---: | ---: | --- |");
+            for file in &adapter.files {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} ms | `{}` |",
+                    file.path, file.rule_count, file.bytes, file.duration_ms, file.hash
+                );
+            }
+        }
+        if !adapter.errors.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "Errors:");
+            let _ = writeln!(out);
+            for error in &adapter.errors {
+                if error.file_path.is_empty() {
+                    let _ = writeln!(out, "- {}", error.message);
+                } else {
+                    let _ = writeln!(out, "- `{}`: {}", error.file_path, error.message);
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SyncError, SyncReportFile};
+    use crate::sync::{ClineAdapter, GeminiAdapter};
+
+    fn file(path: &str, duration_ms: u64) -> SyncReportFile {
+        SyncReportFile {
+            path: path.to_string(),
+            hash: "abc".to_string(),
+            rule_count: 2,
+            bytes: 120,
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn test_finish_sums_durations_and_derives_status() {
+        let mut builder = SyncReportBuilder::new();
+        let entry = builder.adapter(&GeminiAdapter);
+        entry.files.push(file("/a/GEMINI.md", 3));
+        entry.files.push(file("/b/GEMINI.md", 4));
+        builder.skip(&ClineAdapter, "Disabled in settings");
+
+        let report = builder.finish("manual");
+        assert_eq!(report.status, "success");
+        assert_eq!(report.adapters[0].duration_ms, 7);
+        assert_eq!(report.files_written().len(), 2);
+        assert_eq!(
+            report.adapters[1].skipped_reason.as_deref(),
+            Some("Disabled in settings")
+        );
+    }
+
+    #[test]
+    fn test_status_reflects_errors() {
+        let error = SyncError {
+            file_path: "/x".to_string(),
+            adapter_name: "Gemini".to_string(),
+            message: "denied".to_string(),
+        };
+
+        let mut builder = SyncReportBuilder::new();
+        builder.adapter(&GeminiAdapter).errors.push(error.clone());
+        assert_eq!(builder.finish("auto").status, "failed");
+
+        let mut builder = SyncReportBuilder::new();
+        let entry = builder.adapter(&GeminiAdapter);
+        entry.files.push(file("/a/GEMINI.md", 1));
+        entry.errors.push(error);
+        let report = builder.finish("auto");
+        assert_eq!(report.status, "partial");
+        assert_eq!(report.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_markdown_lists_files_errors_and_skips() {
+        let mut builder = SyncReportBuilder::new();
+        let entry = builder.adapter(&GeminiAdapter);
+        entry.files.push(file("/a/GEMINI.md", 3));
+        entry.errors.push(SyncError {
+            file_path: "/b/GEMINI.md".to_string(),
+            adapter_name: "Gemini".to_string(),
+            message: "Permission denied".to_string(),
+        });
+        builder.skip(&ClineAdapter, "Disabled in settings");
+        builder.adapter(&ClineAdapter).rolled_back = true;
+
+        let markdown = to_markdown(&builder.finish("manual"));
+        assert!(markdown.contains("- Status: partial"));
+        assert!(markdown.contains("Rolled back: a file failed to write"));
+        assert!(markdown.contains("| `/a/GEMINI.md` | 2 | 120 | 3 ms | `abc` |"));
+        assert!(markdown.contains("- `/b/GEMINI.md`: Permission denied"));
+        assert!(markdown.contains("Skipped: Disabled in settings"));
+    }
+}
@@ -0,0 +1,258 @@
+//! Windsurf's two formats for workspace rules.
+//!
+//! Current Windsurf releases read a workspace's rules from `.windsurf/rules/`, one Markdown
+//! file per rule whose `trigger` frontmatter tells Cascade when to apply it. Older releases
+//! read every rule from one `.windsurfrules` file. Each workspace gets the format it already
+//! uses: the single file while it has one and no rules directory, the directory otherwise.
+//!
+//! A rule's trigger comes from its metadata, as a Cursor import sets it: `alwaysApply: true`
+//! is `always_on`, `globs` is `glob`, and `alwaysApply: false` without globs is `manual`.
+//! Rules with neither apply always, as they did in the single file. Global rules stay one
+//! file at the registry's path.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::constants::{LEGACY_WINDSURF_FILENAME, NEW_WINDSURF_DIR};
+use crate::execution::slugify;
+use crate::models::Rule;
+
+const GLOBS_METADATA_KEY: &str = "globs";
+const ALWAYS_APPLY_METADATA_KEY: &str = "alwaysApply";
+
+/// Opens every file RuleWeaver writes for Windsurf; files without it are never removed.
+const GENERATED_MARKER: &str = "<!-- Generated by RuleWeaver - Do not edit manually -->";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulesFormat {
+    /// One file per rule in `.windsurf/rules/`
+    Cascade,
+    /// Every rule in `.windsurfrules`
+    Legacy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    AlwaysOn,
+    Glob,
+    Manual,
+}
+
+#[derive(Serialize)]
+struct Frontmatter<'a> {
+    trigger: Trigger,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    description: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    globs: Option<String>,
+}
+
+fn rules_dir(workspace: &Path) -> PathBuf {
+    workspace.join(NEW_WINDSURF_DIR).join("rules")
+}
+
+/// The workspace a local sync target belongs to, or `None` for other paths such as the
+/// global rules file.
+pub fn workspace_of(target: &Path) -> Option<&Path> {
+    if target.file_name()? == LEGACY_WINDSURF_FILENAME {
+        target.parent()
+    } else {
+        None
+    }
+}
+
+/// The format Windsurf reads in `workspace`.
+pub fn detect(workspace: &Path) -> RulesFormat {
+    if workspace.join(LEGACY_WINDSURF_FILENAME).is_file() && !rules_dir(workspace).is_dir() {
+        RulesFormat::Legacy
+    } else {
+        RulesFormat::Cascade
+    }
+}
+
+/// Whether `path` is in a workspace's rules directory.
+pub fn is_rule_file(path: &Path) -> bool {
+    path.parent()
+        .is_some_and(|dir| dir.ends_with(Path::new(NEW_WINDSURF_DIR).join("rules")))
+}
+
+fn globs(rule: &Rule) -> Vec<&str> {
+    let globs: Vec<&str> = match rule.metadata.get(GLOBS_METADATA_KEY) {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        Some(Value::String(value)) => value.split(',').collect(),
+        _ => Vec::new(),
+    };
+    globs
+        .into_iter()
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+        .collect()
+}
+
+pub fn trigger(rule: &Rule) -> Trigger {
+    match rule.metadata.get_bool(ALWAYS_APPLY_METADATA_KEY) {
+        Some(true) => Trigger::AlwaysOn,
+        _ if !globs(rule).is_empty() => Trigger::Glob,
+        Some(false) => Trigger::Manual,
+        None => Trigger::AlwaysOn,
+    }
+}
+
+/// The file of one rule in the rules directory.
+pub fn format_rule_file(rule: &Rule) -> String {
+    let trigger = trigger(rule);
+    let frontmatter = Frontmatter {
+        trigger,
+        description: rule.description.trim(),
+        globs: (trigger == Trigger::Glob).then(|| globs(rule).join(", ")),
+    };
+    let yaml = serde_yaml::to_string(&frontmatter).unwrap_or_else(|e| {
+        log::error!("Failed to serialize Windsurf rule frontmatter: {}", e);
+        String::new()
+    });
+    format!(
+        "---\n{}---\n\n{}\n\n# {}\n\n{}\n",
+        yaml,
+        GENERATED_MARKER,
+        rule.name,
+        rule.content.trim_end()
+    )
+}
+
+/// A file in `workspace`'s rules directory for each enabled rule, named after the rule.
+/// Rules whose names give the same file name are told apart by their ids.
+pub fn rule_files(workspace: &Path, rules: Vec<Rule>) -> Vec<(PathBuf, Vec<Rule>)> {
+    let dir = rules_dir(workspace);
+    let mut used = HashSet::new();
+    rules
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| {
+            let mut stem = slugify(&rule.name);
+            if stem.is_empty() || used.contains(&stem) {
+                let id: String = rule.id.chars().take(8).collect();
+                stem = if stem.is_empty() {
+                    format!("rule-{}", id)
+                } else {
+                    format!("{}-{}", stem, id)
+                };
+            }
+            used.insert(stem.clone());
+            (dir.join(format!("{}.md", stem)), vec![rule])
+        })
+        .collect()
+}
+
+/// Files RuleWeaver wrote for `workspace` that are not among `files`: those of rules since
+/// renamed, disabled or deleted, and the single file once the workspace has a rules
+/// directory.
+pub fn stale_files(workspace: &Path, files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut candidates = vec![workspace.join(LEGACY_WINDSURF_FILENAME)];
+    if let Ok(entries) = fs::read_dir(rules_dir(workspace)) {
+        candidates.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "md")),
+        );
+    }
+    candidates
+        .into_iter()
+        .filter(|path| !files.contains(path))
+        .filter(|path| {
+            fs::read_to_string(path).is_ok_and(|content| content.contains(GENERATED_MARKER))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Scope;
+    use serde_json::json;
+
+    fn rule(name: &str, metadata: Value) -> Rule {
+        let mut rule = Rule::new(
+            name.to_string(),
+            String::new(),
+            format!("{} content", name),
+            Scope::Local,
+        );
+        if let Value::Object(fields) = metadata {
+            for (key, value) in fields {
+                rule.metadata.insert(key, value);
+            }
+        }
+        rule
+    }
+
+    #[test]
+    fn test_trigger_follows_metadata() {
+        assert_eq!(trigger(&rule("A", json!({}))), Trigger::AlwaysOn);
+        assert_eq!(
+            trigger(&rule(
+                "B",
+                json!({ "alwaysApply": true, "globs": ["*.rs"] })
+            )),
+            Trigger::AlwaysOn
+        );
+        assert_eq!(
+            trigger(&rule("C", json!({ "globs": "*.ts, *.tsx" }))),
+            Trigger::Glob
+        );
+        assert_eq!(
+            trigger(&rule("D", json!({ "alwaysApply": false }))),
+            Trigger::Manual
+        );
+
+        let content = format_rule_file(&rule("Frontend", json!({ "globs": ["*.ts", "*.tsx"] })));
+        assert!(content.starts_with("---\ntrigger: glob\nglobs: '*.ts, *.tsx'\n---\n"));
+        assert!(content.ends_with("# Frontend\n\nFrontend content\n"));
+    }
+
+    #[test]
+    fn test_workspace_format_is_detected() {
+        let workspace = tempfile::tempdir().unwrap();
+        assert_eq!(detect(workspace.path()), RulesFormat::Cascade);
+
+        fs::write(workspace.path().join(LEGACY_WINDSURF_FILENAME), "Be kind.").unwrap();
+        assert_eq!(detect(workspace.path()), RulesFormat::Legacy);
+
+        fs::create_dir_all(rules_dir(workspace.path())).unwrap();
+        assert_eq!(detect(workspace.path()), RulesFormat::Cascade);
+    }
+
+    #[test]
+    fn test_rule_files_and_stale_files() {
+        let workspace = tempfile::tempdir().unwrap();
+        let dir = rules_dir(workspace.path());
+        let mut disabled = rule("Old", json!({}));
+        disabled.enabled = false;
+        let rules = vec![rule("Style", json!({})), rule("style", json!({})), disabled];
+
+        let files = rule_files(workspace.path(), rules);
+        let paths: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], dir.join("style.md"));
+        assert_ne!(paths[1], paths[0]);
+        assert!(paths.iter().all(|path| is_rule_file(path)));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("old.md"),
+            format_rule_file(&rule("Old", json!({}))),
+        )
+        .unwrap();
+        fs::write(dir.join("mine.md"), "Written by hand").unwrap();
+        fs::write(&paths[0], format_rule_file(&files[0].1[0])).unwrap();
+        assert_eq!(
+            stale_files(workspace.path(), &paths),
+            vec![dir.join("old.md")]
+        );
+    }
+}
@@ -23,14 +23,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Refactors code for readability and maintainability.".to_string(),
                 script: "echo \"Refactoring selected code...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
         TemplateCommand {
@@ -42,14 +54,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Drafts a PRD outline from notes.".to_string(),
                 script: "echo \"Generating PRD...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
         TemplateCommand {
@@ -61,14 +85,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Converts feature ideas into structured user stories.".to_string(),
                 script: "echo \"Generating user story map...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
         TemplateCommand {
@@ -80,14 +116,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Generates a beat sheet for a specific chapter scenario.".to_string(),
                 script: "echo \"Brainstorming beats...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
         TemplateCommand {
@@ -99,14 +147,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Transforms long-form into social posts.".to_string(),
                 script: "echo \"Repurposing content...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
         TemplateCommand {
@@ -118,14 +178,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Executive summary of raw data.".to_string(),
                 script: "echo \"Summarizing data...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
         TemplateCommand {
@@ -137,14 +209,26 @@ pub fn get_bundled_command_templates() -> Vec<TemplateCommand> {
                 description: "Pulls actions from meeting transcripts.".to_string(),
                 script: "echo \"Extracting actions...\"".to_string(),
                 arguments: vec![],
-                expose_via_mcp: true,
+                mcp_policy: Default::default(),
                 is_placeholder: true,
                 generate_slash_commands: false,
                 slash_command_adapters: vec![],
+                slash_namespace: None,
+                slash_frontmatter: Default::default(),
+                slash_scope: Default::default(),
                 target_paths: vec![],
                 timeout_ms: None,
                 max_retries: None,
+                required_secrets: vec![],
+                working_dir: None,
+                shell: None,
+                schedule: None,
+                sandbox: None,
+                artifact_globs: Vec::new(),
+                output_parser: None,
+                accepts_stdin: false,
                 base_path: None,
+                metadata: Default::default(),
             },
         },
     ]
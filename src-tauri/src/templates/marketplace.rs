@@ -0,0 +1,267 @@
+//! Remote skill template index.
+//!
+//! The index is a JSON list of templates in the same shape as the bundled ones. Asset bundle
+//! files are listed by URL and only fetched on install, and every install is recorded so a
+//! newer template version in the index can later be offered as an update.
+
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use serde::Deserialize;
+
+use super::skills::{SkillTemplateInstall, TemplateSkill, TemplateSource};
+use crate::constants::timing::SKILL_TEMPLATE_INDEX_CACHE_TTL;
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::validate_skill_bundle_files;
+use crate::rule_import::{fetch_url_bytes, fetch_url_text};
+
+pub const SKILL_TEMPLATE_INDEX_URL_KEY: &str = "skill_template_index_url";
+pub(crate) const SKILL_TEMPLATE_INSTALLS_KEY: &str = "skill_template_installs";
+pub const DEFAULT_SKILL_TEMPLATE_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/ChrisUFO/RuleWeaver/main/templates/skills/index.json";
+const MAX_TEMPLATE_INDEX_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_TEMPLATE_ASSET_SIZE: u64 = 10 * 1024 * 1024;
+
+#[derive(Deserialize, Default)]
+struct TemplateIndex {
+    #[serde(default)]
+    templates: Vec<TemplateSkill>,
+}
+
+struct CachedIndex {
+    url: String,
+    fetched_at: Instant,
+    templates: Vec<TemplateSkill>,
+}
+
+static INDEX_CACHE: Mutex<Option<CachedIndex>> = Mutex::new(None);
+
+pub async fn index_url(db: &Database) -> String {
+    db.get_setting(SKILL_TEMPLATE_INDEX_URL_KEY)
+        .await
+        .ok()
+        .flatten()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_SKILL_TEMPLATE_INDEX_URL.to_string())
+}
+
+fn is_valid_template(template: &TemplateSkill) -> bool {
+    let asset_paths: Vec<String> = template.assets.iter().map(|a| a.path.clone()).collect();
+    !template.template_id.trim().is_empty()
+        && !template.metadata.name.trim().is_empty()
+        && template.files.iter().all(|f| {
+            !f.filename.is_empty()
+                && !f.filename.contains("..")
+                && !f.filename.contains(['/', '\\'])
+        })
+        && template.assets.iter().all(|a| !a.url.trim().is_empty())
+        && validate_skill_bundle_files(&asset_paths).is_ok()
+}
+
+fn parse_index(body: &str) -> Result<Vec<TemplateSkill>> {
+    // Accept either `{ "templates": [...] }` or a bare array of templates.
+    let templates = match serde_json::from_str::<TemplateIndex>(body) {
+        Ok(index) => index.templates,
+        Err(_) => serde_json::from_str::<Vec<TemplateSkill>>(body).map_err(|e| {
+            AppError::InvalidInput {
+                message: format!("Skill template index is not valid JSON: {}", e),
+            }
+        })?,
+    };
+    Ok(templates
+        .into_iter()
+        .filter(|t| {
+            let valid = is_valid_template(t);
+            if !valid {
+                log::warn!("Skipping invalid remote skill template '{}'", t.template_id);
+            }
+            valid
+        })
+        .map(|mut t| {
+            t.source = TemplateSource::Remote;
+            t
+        })
+        .collect())
+}
+
+async fn load_index(url: &str, refresh: bool) -> Result<Vec<TemplateSkill>> {
+    if !refresh {
+        if let Some(cached) = INDEX_CACHE.lock().as_ref() {
+            if cached.url == url && cached.fetched_at.elapsed() < SKILL_TEMPLATE_INDEX_CACHE_TTL {
+                return Ok(cached.templates.clone());
+            }
+        }
+    }
+
+    let templates = parse_index(&fetch_url_text(url, MAX_TEMPLATE_INDEX_SIZE).await?)?;
+    *INDEX_CACHE.lock() = Some(CachedIndex {
+        url: url.to_string(),
+        fetched_at: Instant::now(),
+        templates: templates.clone(),
+    });
+    Ok(templates)
+}
+
+/// Keeps templates whose id, name, description or theme contains `query`.
+pub fn filter_templates(templates: Vec<TemplateSkill>, query: Option<&str>) -> Vec<TemplateSkill> {
+    let query = query
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+    templates
+        .into_iter()
+        .filter(|t| {
+            query.as_ref().is_none_or(|q| {
+                t.template_id.to_lowercase().contains(q)
+                    || t.metadata.name.to_lowercase().contains(q)
+                    || t.metadata.description.to_lowercase().contains(q)
+                    || t.theme.to_lowercase().contains(q)
+            })
+        })
+        .collect()
+}
+
+/// Lists remote templates matching `query`, served from cache unless `refresh` is set.
+pub async fn browse(
+    db: &Database,
+    query: Option<&str>,
+    refresh: bool,
+) -> Result<Vec<TemplateSkill>> {
+    let url = index_url(db).await;
+    Ok(filter_templates(load_index(&url, refresh).await?, query))
+}
+
+pub async fn find_template(db: &Database, template_id: &str) -> Result<Option<TemplateSkill>> {
+    let url = index_url(db).await;
+    Ok(load_index(&url, false)
+        .await?
+        .into_iter()
+        .find(|t| t.template_id == template_id))
+}
+
+/// Downloads a template's asset bundle as `(relative path, bytes)` pairs.
+pub async fn fetch_assets(template: &TemplateSkill) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut assets = Vec::with_capacity(template.assets.len());
+    for asset in &template.assets {
+        let bytes = fetch_url_bytes(&asset.url, MAX_TEMPLATE_ASSET_SIZE)
+            .await
+            .map_err(|e| AppError::InvalidInput {
+                message: format!("Failed to fetch template asset '{}': {}", asset.path, e),
+            })?;
+        assets.push((asset.path.clone(), bytes));
+    }
+    Ok(assets)
+}
+
+pub async fn list_installs(db: &Database) -> Vec<SkillTemplateInstall> {
+    let encoded = match db.get_setting(SKILL_TEMPLATE_INSTALLS_KEY).await {
+        Ok(Some(v)) => v,
+        _ => return Vec::new(),
+    };
+    serde_json::from_str(&encoded).unwrap_or_default()
+}
+
+pub async fn record_install(db: &Database, install: SkillTemplateInstall) -> Result<()> {
+    let mut installs = list_installs(db).await;
+    installs.retain(|i| i.template_id != install.template_id);
+    installs.push(install);
+    db.set_setting(
+        SKILL_TEMPLATE_INSTALLS_KEY,
+        &serde_json::to_string(&installs)?,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    const INDEX: &str = r#"{
+        "templates": [
+            {
+                "templateId": "remote-lint",
+                "theme": "Engineering",
+                "metadata": {
+                    "name": "Remote Linter",
+                    "description": "Lints staged files",
+                    "instructions": "Run the linter.",
+                    "scope": "global",
+                    "directoryPath": "",
+                    "entryPoint": "sh scripts/lint.sh",
+                    "version": "2.1.0"
+                },
+                "assets": [{"path": "scripts/lint.sh", "url": "https://example.com/lint.sh"}]
+            },
+            {
+                "templateId": "escape",
+                "theme": "Engineering",
+                "metadata": {
+                    "name": "Escape",
+                    "description": "",
+                    "instructions": "",
+                    "scope": "global",
+                    "directoryPath": "",
+                    "entryPoint": ""
+                },
+                "assets": [{"path": "../outside.sh", "url": "https://example.com/x.sh"}]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn parse_index_drops_invalid_templates_and_marks_remote() {
+        let templates = parse_index(INDEX).unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].template_id, "remote-lint");
+        assert_eq!(templates[0].source, TemplateSource::Remote);
+        assert_eq!(templates[0].metadata.version.as_deref(), Some("2.1.0"));
+        assert!(parse_index("not json").is_err());
+    }
+
+    #[test]
+    fn filter_templates_matches_theme_and_description() {
+        let templates = parse_index(INDEX).unwrap();
+        assert_eq!(filter_templates(templates.clone(), Some("staged")).len(), 1);
+        assert_eq!(
+            filter_templates(templates.clone(), Some("engineering")).len(),
+            1
+        );
+        assert!(filter_templates(templates, Some("marketing")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn browse_uses_cached_index_and_installs_replace_by_template() {
+        let db = Database::new_in_memory().await.unwrap();
+        let url = "https://example.com/skill-templates.json";
+        db.set_setting(SKILL_TEMPLATE_INDEX_URL_KEY, url)
+            .await
+            .unwrap();
+        *INDEX_CACHE.lock() = Some(CachedIndex {
+            url: url.to_string(),
+            fetched_at: Instant::now(),
+            templates: parse_index(INDEX).unwrap(),
+        });
+
+        assert_eq!(browse(&db, Some("lint"), false).await.unwrap().len(), 1);
+        assert!(find_template(&db, "remote-lint").await.unwrap().is_some());
+
+        for version in ["2.0.0", "2.1.0"] {
+            record_install(
+                &db,
+                SkillTemplateInstall {
+                    template_id: "remote-lint".to_string(),
+                    index_url: url.to_string(),
+                    version: Some(version.to_string()),
+                    installed_at: Utc::now(),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        let installs = list_installs(&db).await;
+        assert_eq!(installs.len(), 1);
+        assert_eq!(installs[0].version.as_deref(), Some("2.1.0"));
+    }
+}
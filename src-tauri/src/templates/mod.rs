@@ -1,4 +1,5 @@
 pub mod commands;
+pub mod marketplace;
 pub mod rules;
 pub mod skills;
 
@@ -1,4 +1,8 @@
-use crate::models::{AdapterType, CreateRuleInput, Scope};
+use std::collections::HashMap;
+
+use crate::error::{AppError, Result};
+use crate::execution::{template_token, validate_argument_value};
+use crate::models::{AdapterType, CreateRuleInput, RuleTemplate, Scope};
 use crate::templates::{
     THEME_ADMIN, THEME_DATA, THEME_ENGINEERING, THEME_MARKETING, THEME_PM, THEME_WRITING,
 };
@@ -26,6 +30,7 @@ pub fn get_bundled_rule_templates() -> Vec<TemplateRule> {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini, AdapterType::Cursor],
                 enabled: true,
+                metadata: Default::default(),
             },
         },
         TemplateRule {
@@ -40,6 +45,7 @@ pub fn get_bundled_rule_templates() -> Vec<TemplateRule> {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini],
                 enabled: true,
+                metadata: Default::default(),
             },
         },
         TemplateRule {
@@ -54,6 +60,7 @@ pub fn get_bundled_rule_templates() -> Vec<TemplateRule> {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini],
                 enabled: true,
+                metadata: Default::default(),
             },
         },
         TemplateRule {
@@ -68,6 +75,7 @@ pub fn get_bundled_rule_templates() -> Vec<TemplateRule> {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini],
                 enabled: true,
+                metadata: Default::default(),
             },
         },
         TemplateRule {
@@ -82,6 +90,7 @@ pub fn get_bundled_rule_templates() -> Vec<TemplateRule> {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini],
                 enabled: true,
+                metadata: Default::default(),
             },
         },
         TemplateRule {
@@ -96,7 +105,137 @@ pub fn get_bundled_rule_templates() -> Vec<TemplateRule> {
                 target_paths: None,
                 enabled_adapters: vec![AdapterType::Gemini],
                 enabled: true,
+                metadata: Default::default(),
             },
         },
     ]
 }
+
+/// The rule `template` describes, with every `{{placeholder}}` replaced by its value in
+/// `values` or else its default. Fails on a missing required value, a value that does not
+/// fit its placeholder, or a value for a placeholder the template does not declare.
+pub fn render_rule_template(
+    template: &RuleTemplate,
+    values: &HashMap<String, String>,
+) -> Result<CreateRuleInput> {
+    if let Some(unknown) = values
+        .keys()
+        .find(|key| !template.placeholders.iter().any(|p| &p.name == *key))
+    {
+        return Err(AppError::InvalidInput {
+            message: format!(
+                "Template '{}' has no placeholder '{}'",
+                template.name, unknown
+            ),
+        });
+    }
+
+    let mut rule = template.rule.clone();
+    rule.id = None;
+    for placeholder in &template.placeholders {
+        let value = values
+            .get(&placeholder.name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .or(placeholder
+                .default_value
+                .as_deref()
+                .filter(|d| !d.is_empty()));
+        let value = match value {
+            Some(value) => {
+                validate_argument_value(placeholder, value)?;
+                value
+            }
+            None if placeholder.required => {
+                return Err(AppError::InvalidInput {
+                    message: format!("Placeholder '{}' is required", placeholder.name),
+                })
+            }
+            None => "",
+        };
+
+        let token = template_token(&placeholder.name);
+        for field in [&mut rule.name, &mut rule.description, &mut rule.content]
+            .into_iter()
+            .chain(rule.target_paths.iter_mut().flatten())
+        {
+            *field = field.replace(&token, value);
+        }
+    }
+    rule.name = rule.name.trim().to_string();
+    Ok(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ArgumentType, CommandArgument};
+
+    fn placeholder(name: &str, arg_type: ArgumentType, default: Option<&str>) -> CommandArgument {
+        CommandArgument {
+            name: name.to_string(),
+            description: String::new(),
+            arg_type,
+            required: default.is_none(),
+            default_value: default.map(str::to_string),
+            options: None,
+            pattern: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    #[test]
+    fn test_render_rule_template_fills_and_checks_placeholders() {
+        let template = RuleTemplate {
+            id: "t1".to_string(),
+            name: "Service".to_string(),
+            description: String::new(),
+            placeholders: vec![
+                placeholder("service", ArgumentType::String, None),
+                placeholder("coverage", ArgumentType::Number, Some("80")),
+            ],
+            rule: CreateRuleInput {
+                id: Some("ignored".to_string()),
+                name: "{{service}} conventions".to_string(),
+                description: "Rules for {{service}}".to_string(),
+                content: "Keep coverage above {{coverage}}%. Leave {{other}} alone.".to_string(),
+                scope: Scope::Local,
+                target_paths: Some(vec!["~/src/{{service}}".to_string()]),
+                enabled_adapters: vec![AdapterType::Gemini],
+                enabled: true,
+                metadata: Default::default(),
+            },
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let values = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let rule = render_rule_template(&template, &values(&[("service", "billing")])).unwrap();
+        assert_eq!(rule.id, None);
+        assert_eq!(rule.name, "billing conventions");
+        assert_eq!(rule.description, "Rules for billing");
+        assert_eq!(
+            rule.content,
+            "Keep coverage above 80%. Leave {{other}} alone."
+        );
+        assert_eq!(rule.target_paths, Some(vec!["~/src/billing".to_string()]));
+
+        assert!(render_rule_template(&template, &values(&[])).is_err());
+        assert!(render_rule_template(
+            &template,
+            &values(&[("service", "billing"), ("coverage", "high")])
+        )
+        .is_err());
+        assert!(render_rule_template(
+            &template,
+            &values(&[("service", "billing"), ("team", "payments")])
+        )
+        .is_err());
+    }
+}
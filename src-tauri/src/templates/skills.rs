@@ -1,5 +1,6 @@
 use crate::models::{CreateSkillInput, Scope, SkillParameter, SkillParameterType};
 use crate::templates::{THEME_ENGINEERING, THEME_PM, THEME_WRITING};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,13 +10,48 @@ pub struct TemplateFile {
     pub content: String,
 }
 
+/// An asset-bundle file of a remote template, fetched from `url` into `path` on install.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateAsset {
+    /// Relative path under `scripts/` or `resources/`
+    pub path: String,
+    pub url: String,
+}
+
+/// Where a skill template came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateSource {
+    #[default]
+    Bundled,
+    Remote,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TemplateSkill {
     pub template_id: String,
     pub theme: String,
     pub metadata: CreateSkillInput,
+    #[serde(default)]
     pub files: Vec<TemplateFile>,
+    #[serde(default)]
+    pub assets: Vec<TemplateAsset>,
+    #[serde(default)]
+    pub source: TemplateSource,
+}
+
+/// Provenance of a skill installed from the remote template index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillTemplateInstall {
+    pub template_id: String,
+    pub index_url: String,
+    /// Template version at install time, compared against the index to offer updates
+    pub version: Option<String>,
+    #[serde(with = "crate::models::timestamp")]
+    pub installed_at: DateTime<Utc>,
 }
 
 pub fn get_bundled_skill_templates() -> Vec<TemplateSkill> {
@@ -48,6 +84,8 @@ pub fn get_bundled_skill_templates() -> Vec<TemplateSkill> {
                 filename: "review.py".to_string(),
                 content: include_str!("review.py").to_string(),
             }],
+            assets: vec![],
+            source: TemplateSource::Bundled,
         },
         TemplateSkill {
             template_id: "book-writing-assistant".to_string(),
@@ -70,6 +108,8 @@ pub fn get_bundled_skill_templates() -> Vec<TemplateSkill> {
                 filename: "write.py".to_string(),
                 content: include_str!("write.py").to_string(),
             }],
+            assets: vec![],
+            source: TemplateSource::Bundled,
         },
         TemplateSkill {
             template_id: "project-planner".to_string(),
@@ -91,6 +131,8 @@ pub fn get_bundled_skill_templates() -> Vec<TemplateSkill> {
                 filename: "plan.py".to_string(),
                 content: include_str!("plan.py").to_string(),
             }],
+            assets: vec![],
+            source: TemplateSource::Bundled,
         },
         TemplateSkill {
             template_id: "tmpl_system_info".to_string(),
@@ -111,6 +153,8 @@ pub fn get_bundled_skill_templates() -> Vec<TemplateSkill> {
                 filename: "sysinfo.ps1".to_string(),
                 content: include_str!("sysinfo.ps1").to_string(),
             }],
+            assets: vec![],
+            source: TemplateSource::Bundled,
         },
     ]
 }
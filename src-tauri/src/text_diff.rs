@@ -0,0 +1,115 @@
+//! Ordered line diffs for previewing content changes.
+
+use crate::models::{DiffLine, DiffLineKind, DiffSummary};
+
+/// Above this many line pairs the LCS table gets too large; the diff degrades to
+/// "remove everything, add everything".
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Computes an ordered line diff from `old` to `new` using a longest common subsequence.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let line = |kind, text: &str| DiffLine {
+        kind,
+        text: text.to_string(),
+    };
+
+    if old_lines.len().saturating_mul(new_lines.len()) > MAX_DIFF_CELLS {
+        return old_lines
+            .iter()
+            .map(|l| line(DiffLineKind::Removed, l))
+            .chain(new_lines.iter().map(|l| line(DiffLineKind::Added, l)))
+            .collect();
+    }
+
+    // lcs[i][j] = LCS length of old_lines[i..] and new_lines[j..]
+    let (n, m) = (old_lines.len(), new_lines.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            out.push(line(DiffLineKind::Context, old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(line(DiffLineKind::Removed, old_lines[i]));
+            i += 1;
+        } else {
+            out.push(line(DiffLineKind::Added, new_lines[j]));
+            j += 1;
+        }
+    }
+    out.extend(
+        old_lines[i..]
+            .iter()
+            .map(|l| line(DiffLineKind::Removed, l)),
+    );
+    out.extend(new_lines[j..].iter().map(|l| line(DiffLineKind::Added, l)));
+    out
+}
+
+/// Counts a diff the same way sync conflicts are summarized: paired removals and additions
+/// count as changed lines.
+pub fn summarize(diff: &[DiffLine]) -> DiffSummary {
+    let added = diff
+        .iter()
+        .filter(|l| l.kind == DiffLineKind::Added)
+        .count();
+    let removed = diff
+        .iter()
+        .filter(|l| l.kind == DiffLineKind::Removed)
+        .count();
+    let changed = added.min(removed);
+    DiffSummary {
+        added: added - changed,
+        removed: removed - changed,
+        changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(diff: &[DiffLine]) -> Vec<DiffLineKind> {
+        diff.iter().map(|l| l.kind).collect()
+    }
+
+    #[test]
+    fn test_line_diff_orders_changes() {
+        let diff = line_diff("a\nb\nc", "a\nx\nc\nd");
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                DiffLineKind::Context,
+                DiffLineKind::Removed,
+                DiffLineKind::Added,
+                DiffLineKind::Context,
+                DiffLineKind::Added,
+            ]
+        );
+        let summary = summarize(&diff);
+        assert_eq!((summary.added, summary.removed, summary.changed), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_line_diff_identical_and_empty() {
+        assert!(line_diff("", "").is_empty());
+        assert!(line_diff("same\ntext", "same\ntext")
+            .iter()
+            .all(|l| l.kind == DiffLineKind::Context));
+        assert_eq!(kinds(&line_diff("", "new")), vec![DiffLineKind::Added]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Detects which AI tools are installed on this machine.
+//!
+//! A tool counts as installed when one of its config directories exists under the home
+//! directory or its executable is on `PATH`. Extension-only tools such as Roo Code have no
+//! executable and are found by their config directory alone. Results are cached for
+//! `TOOL_DETECTION_CACHE_TTL`; `refresh` checks again right away, e.g. after installing a
+//! tool.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use chrono::Utc;
+use parking_lot::Mutex;
+
+use crate::constants::timing::TOOL_DETECTION_CACHE_TTL;
+use crate::models::registry::{InstalledTools, ToolInstallation};
+use crate::models::AdapterType;
+use crate::path_resolver::path_resolver;
+
+static CACHE: LazyLock<Mutex<Option<InstalledTools>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Config directories relative to the home directory, and executable names.
+fn signals(adapter: AdapterType) -> (&'static [&'static str], &'static [&'static str]) {
+    match adapter {
+        AdapterType::Antigravity => (&[".gemini/antigravity"], &["antigravity"]),
+        AdapterType::Gemini => (&[".gemini"], &["gemini"]),
+        AdapterType::OpenCode => (&[".config/opencode", ".opencode"], &["opencode"]),
+        AdapterType::Cline => (&[".cline", "Documents/Cline"], &["cline"]),
+        AdapterType::ClaudeCode => (&[".claude"], &["claude"]),
+        AdapterType::Codex => (&[".codex"], &["codex"]),
+        AdapterType::Kilo => (&[".kilocode"], &["kilocode", "kilo"]),
+        AdapterType::Cursor => (&[".cursor"], &["cursor", "cursor-agent"]),
+        AdapterType::Windsurf => (&[".windsurf", ".codeium/windsurf"], &["windsurf"]),
+        AdapterType::RooCode => (&[".roo"], &[]),
+    }
+}
+
+fn find_binary(name: &str, search_path: &OsStr) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["exe", "cmd", "bat"]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(search_path).find_map(|dir| {
+        extensions.iter().find_map(|ext| {
+            let candidate = dir.join(name).with_extension(ext);
+            candidate.is_file().then_some(candidate)
+        })
+    })
+}
+
+fn detect(home: &Path, search_path: Option<&OsStr>) -> InstalledTools {
+    let tools = AdapterType::all()
+        .into_iter()
+        .map(|adapter| {
+            let (dirs, binaries) = signals(adapter);
+            let config_dirs: Vec<String> = dirs
+                .iter()
+                .map(|dir| home.join(dir))
+                .filter(|dir| dir.is_dir())
+                .map(|dir| dir.to_string_lossy().to_string())
+                .collect();
+            let binary = search_path.and_then(|search_path| {
+                binaries
+                    .iter()
+                    .find_map(|name| find_binary(name, search_path))
+                    .map(|path| path.to_string_lossy().to_string())
+            });
+            ToolInstallation {
+                adapter,
+                installed: !config_dirs.is_empty() || binary.is_some(),
+                config_dirs,
+                binary,
+            }
+        })
+        .collect();
+
+    InstalledTools {
+        tools,
+        checked_at: Utc::now(),
+    }
+}
+
+/// Checks every tool again and replaces the cached result.
+pub fn refresh() -> InstalledTools {
+    let search_path = std::env::var_os("PATH");
+    let detected = detect(path_resolver().home_dir(), search_path.as_deref());
+    *CACHE.lock() = Some(detected.clone());
+    detected
+}
+
+/// The cached detection result, checked again once it is older than the cache TTL.
+pub fn detect_installed_tools() -> InstalledTools {
+    let ttl = chrono::Duration::from_std(TOOL_DETECTION_CACHE_TTL).unwrap_or_default();
+    if let Some(cached) = CACHE.lock().as_ref() {
+        if Utc::now() - cached.checked_at < ttl {
+            return cached.clone();
+        }
+    }
+    refresh()
+}
+
+pub fn is_installed(adapter: AdapterType) -> bool {
+    detect_installed_tools().is_installed(adapter)
+}
+
+/// The adapters in `adapters` whose tool was not detected.
+pub fn uninstalled(adapters: &[AdapterType]) -> Vec<AdapterType> {
+    let detected = detect_installed_tools();
+    adapters
+        .iter()
+        .copied()
+        .filter(|adapter| !detected.is_installed(*adapter))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(detected: &InstalledTools, adapter: AdapterType) -> &ToolInstallation {
+        detected
+            .tools
+            .iter()
+            .find(|t| t.adapter == adapter)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_config_directory_marks_tool_installed() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+        std::fs::create_dir_all(home.path().join(".codeium/windsurf")).unwrap();
+
+        let detected = detect(home.path(), None);
+        assert_eq!(detected.tools.len(), AdapterType::all().len());
+        let claude = tool(&detected, AdapterType::ClaudeCode);
+        assert!(claude.installed);
+        assert_eq!(claude.config_dirs.len(), 1);
+        assert!(tool(&detected, AdapterType::Windsurf).installed);
+        assert!(!tool(&detected, AdapterType::Codex).installed);
+    }
+
+    #[test]
+    fn test_binary_on_path_marks_tool_installed() {
+        let home = tempfile::tempdir().unwrap();
+        let bin = tempfile::tempdir().unwrap();
+        let name = if cfg!(windows) { "codex.exe" } else { "codex" };
+        std::fs::write(bin.path().join(name), "").unwrap();
+
+        let detected = detect(home.path(), Some(bin.path().as_os_str()));
+        let codex = tool(&detected, AdapterType::Codex);
+        assert!(codex.installed);
+        assert!(codex.config_dirs.is_empty());
+        assert!(codex.binary.is_some());
+        assert!(!tool(&detected, AdapterType::RooCode).installed);
+    }
+}
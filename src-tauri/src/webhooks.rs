@@ -0,0 +1,302 @@
+//! Outgoing webhooks for lifecycle events.
+//!
+//! Each enabled webhook subscribed to an event receives a JSON `POST`:
+//!
+//! ```json
+//! { "event": "sync.completed", "deliveryId": "…", "timestamp": 1700000000,
+//!   "text": "RuleWeaver synced 3 file(s)", "data": { … } }
+//! ```
+//!
+//! `text` is a one-line summary, so a Slack incoming webhook can take the payload as is. With a
+//! secret configured, `X-RuleWeaver-Signature` carries `sha256=<hex>`, the HMAC-SHA256 of
+//! `<timestamp>.<body>` keyed with the secret's value, where `<timestamp>` is the
+//! `X-RuleWeaver-Timestamp` header. Deliveries run in the background and never fail the
+//! operation that raised the event.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use ring::hmac;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::models::{Webhook, WebhookDelivery, WebhookEvent};
+use crate::secrets;
+
+pub const EVENT_HEADER: &str = "X-RuleWeaver-Event";
+pub const DELIVERY_HEADER: &str = "X-RuleWeaver-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-RuleWeaver-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-RuleWeaver-Signature";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Deliveries still running, so the CLI can wait for them before exiting.
+static IN_FLIGHT: LazyLock<Mutex<Vec<JoinHandle<()>>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Rejects a webhook that could never be delivered.
+pub fn validate(url: &str, events: &[WebhookEvent], secret_name: Option<&str>) -> Result<()> {
+    let parsed = url::Url::parse(url.trim()).map_err(|e| AppError::InvalidInput {
+        message: format!("Invalid webhook URL: {}", e),
+    })?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return Err(AppError::InvalidInput {
+            message: "Webhook URL must be an http or https URL".to_string(),
+        });
+    }
+    if events.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: "Webhook must subscribe to at least one event".to_string(),
+        });
+    }
+    if let Some(name) = secret_name.filter(|name| !name.is_empty()) {
+        secrets::validate_secret_name(name)?;
+    }
+    Ok(())
+}
+
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+    format!("sha256={}", crate::util::hex(tag.as_ref()))
+}
+
+struct Payload {
+    event: WebhookEvent,
+    delivery_id: String,
+    timestamp: i64,
+    body: String,
+}
+
+fn payload(event: WebhookEvent, text: &str, data: &Value) -> Payload {
+    let delivery_id = uuid::Uuid::new_v4().to_string();
+    let timestamp = chrono::Utc::now().timestamp();
+    let body = json!({
+        "event": event.as_str(),
+        "deliveryId": delivery_id,
+        "timestamp": timestamp,
+        "text": text,
+        "data": data,
+    })
+    .to_string();
+    Payload {
+        event,
+        delivery_id,
+        timestamp,
+        body,
+    }
+}
+
+async fn signing_secret(db: &Database, webhook: &Webhook) -> Result<Option<String>> {
+    let Some(name) = &webhook.secret_name else {
+        return Ok(None);
+    };
+    let resolved = secrets::resolve_secrets(db, std::slice::from_ref(name)).await?;
+    Ok(resolved.into_iter().next().map(|(_, value)| value))
+}
+
+/// Posts `payload`, retrying network errors and 5xx answers with a growing delay.
+async fn deliver(url: &str, secret: Option<&str>, payload: &Payload) -> WebhookDelivery {
+    let mut delivery = WebhookDelivery {
+        delivery_id: payload.delivery_id.clone(),
+        status: None,
+        attempts: 0,
+        error: None,
+    };
+    let client = match reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            delivery.error = Some(format!("Failed to create HTTP client: {}", e));
+            return delivery;
+        }
+    };
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        delivery.attempts = attempt;
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::USER_AGENT, "RuleWeaver")
+            .header(EVENT_HEADER, payload.event.as_str())
+            .header(DELIVERY_HEADER, &payload.delivery_id)
+            .header(TIMESTAMP_HEADER, payload.timestamp.to_string())
+            .body(payload.body.clone());
+        if let Some(secret) = secret {
+            request = request.header(
+                SIGNATURE_HEADER,
+                sign(secret, payload.timestamp, &payload.body),
+            );
+        }
+
+        let retryable = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                delivery.status = Some(status.as_u16());
+                if status.is_success() {
+                    delivery.error = None;
+                    return delivery;
+                }
+                delivery.error = Some(format!("Server answered {}", status));
+                status.is_server_error()
+            }
+            Err(e) => {
+                delivery.error = Some(e.to_string());
+                true
+            }
+        };
+        if !retryable || attempt == MAX_ATTEMPTS {
+            break;
+        }
+        tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+    }
+    delivery
+}
+
+/// Sends `event` to every enabled webhook subscribed to it. Returns once the deliveries are
+/// queued; failures are only logged.
+pub async fn emit(db: &Database, event: WebhookEvent, text: &str, data: Value) {
+    let webhooks = match db.get_all_webhooks().await {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            log::warn!("Failed to load webhooks for {}: {}", event.as_str(), e);
+            return;
+        }
+    };
+
+    for webhook in webhooks
+        .into_iter()
+        .filter(|w| w.enabled && w.events.contains(&event))
+    {
+        let secret = match signing_secret(db, &webhook).await {
+            Ok(secret) => secret,
+            Err(e) => {
+                log::warn!("Skipping webhook {}: {}", webhook.url, e);
+                continue;
+            }
+        };
+        let payload = payload(event, text, &data);
+        let handle = tokio::spawn(async move {
+            let delivery = deliver(&webhook.url, secret.as_deref(), &payload).await;
+            if let Some(error) = delivery.error {
+                log::warn!(
+                    "Webhook delivery {} to {} failed after {} attempt(s): {}",
+                    delivery.delivery_id,
+                    webhook.url,
+                    delivery.attempts,
+                    error
+                );
+            }
+        });
+        let mut in_flight = IN_FLIGHT.lock();
+        in_flight.retain(|handle| !handle.is_finished());
+        in_flight.push(handle);
+    }
+}
+
+/// Waits for queued deliveries, for processes that exit right after raising an event.
+pub async fn flush() {
+    let pending: Vec<_> = IN_FLIGHT.lock().drain(..).collect();
+    for handle in pending {
+        let _ = handle.await;
+    }
+}
+
+/// Sends a `webhook.test` event to one webhook, enabled or not, and reports how it went.
+pub async fn send_test(db: &Database, id: &str) -> Result<WebhookDelivery> {
+    let webhook = db.get_webhook_by_id(id).await?;
+    let secret = signing_secret(db, &webhook).await?;
+    let payload = payload(
+        WebhookEvent::Test,
+        "RuleWeaver webhook test",
+        &json!({ "webhookId": webhook.id }),
+    );
+    Ok(deliver(&webhook.url, secret.as_deref(), &payload).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let signature = sign("topsecret", 1700000000, r#"{"event":"sync.completed"}"#);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(
+            signature,
+            sign("topsecret", 1700000000, r#"{"event":"sync.completed"}"#)
+        );
+        assert_ne!(
+            signature,
+            sign("topsecret", 1700000001, r#"{"event":"sync.completed"}"#)
+        );
+        assert_ne!(
+            signature,
+            sign("other", 1700000000, r#"{"event":"sync.completed"}"#)
+        );
+    }
+
+    #[test]
+    fn test_validate_and_payload_shape() {
+        let events = [WebhookEvent::SyncCompleted];
+        assert!(validate("https://hooks.slack.com/services/x", &events, Some("SLACK")).is_ok());
+        assert!(validate("http://localhost:9000/hook", &events, None).is_ok());
+        assert!(validate("ftp://example.com", &events, None).is_err());
+        assert!(validate("not a url", &events, None).is_err());
+        assert!(validate("https://example.com", &[], None).is_err());
+        assert!(validate("https://example.com", &events, Some("bad name")).is_err());
+
+        let payload = payload(
+            WebhookEvent::ConflictDetected,
+            "1 conflict",
+            &json!({ "modified": 1 }),
+        );
+        let body: Value = serde_json::from_str(&payload.body).unwrap();
+        assert_eq!(body["event"], "conflict.detected");
+        assert_eq!(body["deliveryId"], payload.delivery_id);
+        assert_eq!(body["text"], "1 conflict");
+        assert_eq!(body["data"]["modified"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_emit_skips_unsubscribed_and_disabled_webhooks() {
+        let db = Database::new_in_memory().await.unwrap();
+        let webhook = db
+            .create_webhook(crate::models::CreateWebhookInput {
+                url: "http://127.0.0.1:9/hook".to_string(),
+                events: vec![WebhookEvent::CommandExecuted],
+                secret_name: None,
+                enabled: false,
+            })
+            .await
+            .unwrap();
+        assert_eq!(webhook.events, vec![WebhookEvent::CommandExecuted]);
+
+        emit(&db, WebhookEvent::CommandExecuted, "ran", json!({})).await;
+        emit(&db, WebhookEvent::SyncCompleted, "synced", json!({})).await;
+        assert!(IN_FLIGHT.lock().is_empty());
+
+        let updated = db
+            .update_webhook(
+                &webhook.id,
+                crate::models::UpdateWebhookInput {
+                    enabled: Some(true),
+                    secret_name: Some(String::new()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(updated.enabled);
+        assert_eq!(updated.secret_name, None);
+        db.delete_webhook(&webhook.id).await.unwrap();
+        assert!(matches!(
+            db.get_webhook_by_id(&webhook.id).await,
+            Err(AppError::WebhookNotFound { .. })
+        ));
+    }
+}
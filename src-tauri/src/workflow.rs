@@ -0,0 +1,501 @@
+//! Workflows chain saved commands into a dependency graph.
+//!
+//! Steps run one at a time in dependency order. A step's argument values may reference the
+//! workflow's inputs or the output of a step it depends on, and each step decides whether
+//! its failure stops the run, skips its dependents, or is ignored.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::database::Database;
+use crate::error::{AppError, Result};
+use crate::execution::{run_command, CancelToken, OutputSink, RunCommandOptions};
+use crate::models::{
+    Command, StepFailurePolicy, Workflow, WorkflowRunResult, WorkflowStep, WorkflowStepResult,
+    WorkflowStepStatus,
+};
+
+/// Receives each step's result as soon as the step finishes or is skipped.
+pub type StepSink = Arc<dyn Fn(&WorkflowStepResult) + Send + Sync>;
+
+pub struct WorkflowRunOptions<'a> {
+    pub triggered_by: &'a str,
+    pub adapter_context: Option<&'a str>,
+    pub on_step: Option<StepSink>,
+    pub on_output: Option<OutputSink>,
+    pub cancel: Option<CancelToken>,
+}
+
+/// Checks a workflow definition against the saved commands.
+pub fn validate_workflow(name: &str, steps: &[WorkflowStep], commands: &[Command]) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Workflow name cannot be empty".to_string(),
+        ));
+    }
+    if steps.is_empty() {
+        return Err(AppError::Validation(
+            "Workflow must have at least one step".to_string(),
+        ));
+    }
+
+    let mut ids = HashSet::new();
+    for step in steps {
+        if step.id.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Workflow step id cannot be empty".to_string(),
+            ));
+        }
+        if !ids.insert(step.id.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Duplicate workflow step id: {}",
+                step.id
+            )));
+        }
+        if !commands.iter().any(|c| c.id == step.command_id) {
+            return Err(AppError::Validation(format!(
+                "Step '{}' references an unknown command: {}",
+                step.id, step.command_id
+            )));
+        }
+    }
+
+    for step in steps {
+        for dep in &step.depends_on {
+            if !ids.contains(dep.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "Step '{}' depends on an unknown step: {}",
+                    step.id, dep
+                )));
+            }
+        }
+    }
+
+    execution_order(steps).map(|_| ())
+}
+
+/// Orders steps so each runs after its dependencies, keeping the declared order where the
+/// graph allows. Fails if the dependencies form a cycle.
+pub fn execution_order(steps: &[WorkflowStep]) -> Result<Vec<usize>> {
+    let mut done: HashSet<&str> = HashSet::new();
+    let mut order = Vec::with_capacity(steps.len());
+
+    while order.len() < steps.len() {
+        let next = steps.iter().enumerate().find(|(i, step)| {
+            !order.contains(i) && step.depends_on.iter().all(|d| done.contains(d.as_str()))
+        });
+        match next {
+            Some((i, step)) => {
+                done.insert(step.id.as_str());
+                order.push(i);
+            }
+            None => {
+                let stuck: Vec<&str> = steps
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !order.contains(i))
+                    .map(|(_, step)| step.id.as_str())
+                    .collect();
+                return Err(AppError::Validation(format!(
+                    "Workflow steps have a dependency cycle: {}",
+                    stuck.join(", ")
+                )));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// Expands `{{input.NAME}}`, `{{steps.ID.stdout}}` and `{{steps.ID.exit_code}}`.
+/// Step stdout is trimmed; unknown references are left as written.
+fn render_value(
+    template: &str,
+    inputs: &HashMap<String, String>,
+    outputs: &HashMap<&str, (i32, String)>,
+) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let token = &rest[start..start + len + 2];
+        let reference = token[2..token.len() - 2].trim();
+
+        let value = if let Some(name) = reference.strip_prefix("input.") {
+            inputs.get(name).cloned()
+        } else if let Some(path) = reference.strip_prefix("steps.") {
+            path.rsplit_once('.')
+                .and_then(|(id, field)| {
+                    outputs.get(id).map(|(code, stdout)| (field, *code, stdout))
+                })
+                .and_then(|(field, code, stdout)| match field {
+                    "stdout" => Some(stdout.trim().to_string()),
+                    "exit_code" => Some(code.to_string()),
+                    _ => None,
+                })
+        } else {
+            None
+        };
+
+        rendered.push_str(value.as_deref().unwrap_or(token));
+        rest = &rest[start + len + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Fills in workflow inputs from `args` and argument defaults, rejecting missing required ones.
+fn resolve_inputs(
+    workflow: &Workflow,
+    args: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    let mut inputs = args.clone();
+    let mut missing = Vec::new();
+    for arg in &workflow.arguments {
+        if inputs.contains_key(&arg.name) {
+            continue;
+        }
+        match arg.default_value.as_ref().filter(|v| !v.is_empty()) {
+            Some(default) => {
+                inputs.insert(arg.name.clone(), default.clone());
+            }
+            None if arg.required => missing.push(arg.name.as_str()),
+            None => {}
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: format!("Missing required arguments: {}", missing.join(", ")),
+        });
+    }
+    Ok(inputs)
+}
+
+/// Runs every step of `workflow` in dependency order, applying each step's failure policy.
+pub async fn run_workflow(
+    db: &Database,
+    workflow: &Workflow,
+    args: &HashMap<String, String>,
+    options: WorkflowRunOptions<'_>,
+) -> Result<WorkflowRunResult> {
+    let commands = db.get_all_commands().await?;
+    validate_workflow(&workflow.name, &workflow.steps, &commands)?;
+    let inputs = resolve_inputs(workflow, args)?;
+    let order = execution_order(&workflow.steps)?;
+
+    let start = Instant::now();
+    let mut outputs: HashMap<&str, (i32, String)> = HashMap::new();
+    // Steps that will not run, with the reason reported for them.
+    let mut blocked: HashMap<&str, String> = HashMap::new();
+    let mut halted: Option<String> = None;
+    let mut results = Vec::with_capacity(order.len());
+
+    for index in order {
+        let step = &workflow.steps[index];
+        let cmd = commands
+            .iter()
+            .find(|c| c.id == step.command_id)
+            .ok_or_else(|| AppError::CommandNotFound {
+                id: step.command_id.clone(),
+            })?;
+
+        let skip_reason = halted.clone().or_else(|| {
+            step.depends_on
+                .iter()
+                .find_map(|dep| blocked.get(dep.as_str()))
+                .map(|_| "A step it depends on failed or was skipped".to_string())
+        });
+
+        let result = match skip_reason {
+            Some(reason) => {
+                blocked.insert(step.id.as_str(), reason.clone());
+                WorkflowStepResult {
+                    step_id: step.id.clone(),
+                    command_id: cmd.id.clone(),
+                    command_name: cmd.name.clone(),
+                    status: WorkflowStepStatus::Skipped,
+                    exit_code: None,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    duration_ms: 0,
+                    message: Some(reason),
+                }
+            }
+            None => {
+                let values: HashMap<String, String> = step
+                    .args
+                    .iter()
+                    .map(|(name, template)| {
+                        (name.clone(), render_value(template, &inputs, &outputs))
+                    })
+                    .collect();
+
+                let step_start = Instant::now();
+                let outcome = run_command(
+                    db,
+                    cmd,
+                    &values,
+                    RunCommandOptions {
+                        triggered_by: options.triggered_by,
+                        adapter_context: options.adapter_context,
+                        on_output: options.on_output.clone(),
+                        cancel: options.cancel.clone(),
+                    },
+                )
+                .await;
+                let cancelled = matches!(outcome, Err(AppError::ExecutionCancelled));
+                let result = match outcome {
+                    Ok((exit_code, stdout, stderr, duration_ms)) => WorkflowStepResult {
+                        step_id: step.id.clone(),
+                        command_id: cmd.id.clone(),
+                        command_name: cmd.name.clone(),
+                        status: if exit_code == 0 {
+                            WorkflowStepStatus::Succeeded
+                        } else {
+                            WorkflowStepStatus::Failed
+                        },
+                        exit_code: Some(exit_code),
+                        stdout,
+                        stderr,
+                        duration_ms,
+                        message: None,
+                    },
+                    Err(e) => WorkflowStepResult {
+                        step_id: step.id.clone(),
+                        command_id: cmd.id.clone(),
+                        command_name: cmd.name.clone(),
+                        status: WorkflowStepStatus::Failed,
+                        exit_code: None,
+                        stdout: String::new(),
+                        stderr: String::new(),
+                        duration_ms: step_start.elapsed().as_millis() as u64,
+                        message: Some(e.to_string()),
+                    },
+                };
+
+                if cancelled {
+                    halted = Some("The workflow was cancelled".to_string());
+                } else if result.status == WorkflowStepStatus::Failed {
+                    match step.on_failure {
+                        StepFailurePolicy::Stop => {
+                            halted = Some(format!("Step '{}' failed", step.id));
+                        }
+                        StepFailurePolicy::SkipDependents => {
+                            blocked.insert(step.id.as_str(), String::new());
+                        }
+                        StepFailurePolicy::Continue => {}
+                    }
+                }
+                outputs.insert(
+                    step.id.as_str(),
+                    (result.exit_code.unwrap_or(-1), result.stdout.clone()),
+                );
+                result
+            }
+        };
+
+        if let Some(on_step) = &options.on_step {
+            on_step(&result);
+        }
+        results.push(result);
+    }
+
+    Ok(WorkflowRunResult {
+        workflow_id: workflow.id.clone(),
+        success: results
+            .iter()
+            .all(|r| r.status == WorkflowStepStatus::Succeeded),
+        steps: results,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Builds the command that slash-command adapters write out for a workflow: a prompt
+/// listing each step's script in execution order.
+pub fn workflow_slash_command(workflow: &Workflow, commands: &[Command]) -> Command {
+    let mut script = format!(
+        "Run the \"{}\" workflow by executing these steps in order:\n",
+        workflow.name
+    );
+    let order =
+        execution_order(&workflow.steps).unwrap_or_else(|_| (0..workflow.steps.len()).collect());
+    for (position, index) in order.into_iter().enumerate() {
+        let step = &workflow.steps[index];
+        let command_script = commands
+            .iter()
+            .find(|c| c.id == step.command_id)
+            .map(|c| c.script.as_str())
+            .unwrap_or_default();
+        script.push_str(&format!(
+            "\n{}. {}\n\n```\n{}\n```\n",
+            position + 1,
+            step.id,
+            command_script.trim()
+        ));
+    }
+
+    let mut command = Command::new(
+        workflow.name.clone(),
+        workflow.description.clone(),
+        script,
+        false,
+    );
+    command.id = workflow.id.clone();
+    command.arguments = workflow.arguments.clone();
+    command.generate_slash_commands = workflow.generate_slash_commands;
+    command.slash_command_adapters = workflow.slash_command_adapters.clone();
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateCommandInput, CreateWorkflowInput};
+
+    fn step(id: &str, depends_on: &[&str]) -> WorkflowStep {
+        WorkflowStep {
+            id: id.to_string(),
+            command_id: "cmd".to_string(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            args: HashMap::new(),
+            on_failure: StepFailurePolicy::Stop,
+        }
+    }
+
+    #[test]
+    fn steps_run_after_their_dependencies() {
+        let steps = vec![
+            step("deploy", &["build", "test"]),
+            step("test", &["build"]),
+            step("build", &[]),
+        ];
+        let order: Vec<&str> = execution_order(&steps)
+            .unwrap()
+            .into_iter()
+            .map(|i| steps[i].id.as_str())
+            .collect();
+        assert_eq!(order, vec!["build", "test", "deploy"]);
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let steps = vec![step("a", &["b"]), step("b", &["a"]), step("c", &[])];
+        let err = execution_order(&steps).unwrap_err();
+        assert!(err.to_string().contains("a, b"));
+    }
+
+    #[test]
+    fn values_reference_inputs_and_earlier_steps() {
+        let inputs = HashMap::from([("env".to_string(), "prod".to_string())]);
+        let outputs = HashMap::from([("build", (0, "v1.2.3\n".to_string()))]);
+        assert_eq!(
+            render_value(
+                "{{input.env}}:{{ steps.build.stdout }}:{{steps.build.exit_code}}",
+                &inputs,
+                &outputs
+            ),
+            "prod:v1.2.3:0"
+        );
+        assert_eq!(
+            render_value("{{steps.lint.stdout}} {{other", &inputs, &outputs),
+            "{{steps.lint.stdout}} {{other"
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn failure_policies_control_later_steps() {
+        let db = Database::new_in_memory().await.unwrap();
+        let echo = db
+            .create_command(CreateCommandInput {
+                name: "echo".to_string(),
+                script: "echo {{text}}".to_string(),
+                arguments: vec![crate::models::CommandArgument {
+                    name: "text".to_string(),
+                    description: String::new(),
+                    arg_type: crate::models::ArgumentType::String,
+                    required: true,
+                    default_value: None,
+                    options: None,
+                    pattern: None,
+                    min: None,
+                    max: None,
+                }],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let fail = db
+            .create_command(CreateCommandInput {
+                name: "fail".to_string(),
+                script: "exit 3".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut first = step("first", &[]);
+        first.command_id = echo.id.clone();
+        first.args = HashMap::from([("text".to_string(), "{{input.greeting}}".to_string())]);
+        let mut broken = step("broken", &[]);
+        broken.command_id = fail.id.clone();
+        broken.on_failure = StepFailurePolicy::SkipDependents;
+        let mut after_broken = step("after-broken", &["broken"]);
+        after_broken.command_id = echo.id.clone();
+        let mut second = step("second", &["first"]);
+        second.command_id = echo.id.clone();
+        second.args = HashMap::from([("text".to_string(), "{{steps.first.stdout}}".to_string())]);
+
+        let workflow = db
+            .create_workflow(CreateWorkflowInput {
+                name: "pipeline".to_string(),
+                description: String::new(),
+                steps: vec![first, broken, after_broken, second],
+                arguments: Vec::new(),
+                expose_via_mcp: false,
+                generate_slash_commands: false,
+                slash_command_adapters: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        let args = HashMap::from([("greeting".to_string(), "hello".to_string())]);
+        let result = run_workflow(
+            &db,
+            &workflow,
+            &args,
+            WorkflowRunOptions {
+                triggered_by: "test",
+                adapter_context: None,
+                on_step: None,
+                on_output: None,
+                cancel: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let status = |id: &str| {
+            result
+                .steps
+                .iter()
+                .find(|s| s.step_id == id)
+                .map(|s| s.status)
+                .unwrap()
+        };
+        assert!(!result.success);
+        assert_eq!(status("first"), WorkflowStepStatus::Succeeded);
+        assert_eq!(status("broken"), WorkflowStepStatus::Failed);
+        assert_eq!(status("after-broken"), WorkflowStepStatus::Skipped);
+        assert_eq!(status("second"), WorkflowStepStatus::Succeeded);
+        assert!(result.steps[0].stdout.contains("hello"));
+        assert!(result.steps[3].stdout.contains("hello"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Named workspaces, such as work and personal, each with its own database, rule and skill
+//! storage and settings.
+//!
+//! The default workspace keeps the locations RuleWeaver has always used. Any other
+//! workspace stores its data in `workspaces/<id>` under the app data directories and its
+//! rule files in `~/.ruleweaver/workspaces/<id>`. The list of workspaces and the active one
+//! are kept in `workspaces.json`, outside every workspace.
+//!
+//! The active workspace is read once per process. Switching records the choice and
+//! restarts the app, so nothing loaded for one workspace is carried into the other.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use chrono::Utc;
+use tauri::Manager;
+
+use crate::atomic_write::write_atomic;
+use crate::error::{AppError, Result};
+use crate::models::{Workspace, WorkspaceList};
+
+pub const DEFAULT_WORKSPACE_ID: &str = "default";
+const WORKSPACES_FILE: &str = "workspaces.json";
+const WORKSPACES_DIR: &str = "workspaces";
+
+static ACTIVE: LazyLock<String> = LazyLock::new(|| {
+    if cfg!(test) {
+        return DEFAULT_WORKSPACE_ID.to_string();
+    }
+    match registry_path().and_then(|path| load(&path)) {
+        Ok(list) => list.active,
+        Err(e) => {
+            log::warn!("Failed to read workspaces, using the default: {}", e);
+            DEFAULT_WORKSPACE_ID.to_string()
+        }
+    }
+});
+
+/// Id of the workspace this process runs in.
+pub fn active_id() -> &'static str {
+    &ACTIVE
+}
+
+/// `base` for the default workspace, or the active workspace's directory under it.
+pub fn scoped_dir(base: &Path) -> PathBuf {
+    scoped_dir_for(base, active_id())
+}
+
+fn scoped_dir_for(base: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_WORKSPACE_ID {
+        base.to_path_buf()
+    } else {
+        base.join(WORKSPACES_DIR).join(id)
+    }
+}
+
+/// `name` qualified with the active workspace, for names shared by the whole machine such
+/// as keychain accounts. Unchanged for the default workspace.
+pub fn scoped_name(name: &str) -> String {
+    match active_id() {
+        DEFAULT_WORKSPACE_ID => name.to_string(),
+        id => format!("{}@{}", name, id),
+    }
+}
+
+fn registry_path() -> Result<PathBuf> {
+    Ok(crate::database::base_app_data_dir()?.join(WORKSPACES_FILE))
+}
+
+fn default_workspace() -> Workspace {
+    Workspace {
+        id: DEFAULT_WORKSPACE_ID.to_string(),
+        name: "Default".to_string(),
+        created_at: chrono::DateTime::UNIX_EPOCH,
+    }
+}
+
+/// Reads the workspace list. The default workspace is always listed, and an active id that
+/// no longer exists falls back to it.
+fn load(path: &Path) -> Result<WorkspaceList> {
+    let mut list = match std::fs::read_to_string(path) {
+        Ok(encoded) => serde_json::from_str(&encoded)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => WorkspaceList {
+            active: DEFAULT_WORKSPACE_ID.to_string(),
+            workspaces: Vec::new(),
+        },
+        Err(e) => return Err(e.into()),
+    };
+    if !list.workspaces.iter().any(|w| w.id == DEFAULT_WORKSPACE_ID) {
+        list.workspaces.insert(0, default_workspace());
+    }
+    if !list.workspaces.iter().any(|w| w.id == list.active) {
+        list.active = DEFAULT_WORKSPACE_ID.to_string();
+    }
+    Ok(list)
+}
+
+fn save(path: &Path, list: &WorkspaceList) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, serde_json::to_string_pretty(list)?)
+}
+
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput {
+            message: "Workspace name cannot be empty".to_string(),
+        });
+    }
+    Ok(name.to_string())
+}
+
+fn find<'a>(list: &'a mut WorkspaceList, id: &str) -> Result<&'a mut Workspace> {
+    list.workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| AppError::WorkspaceNotFound { id: id.to_string() })
+}
+
+fn create_in(path: &Path, name: &str) -> Result<Workspace> {
+    let name = validate_name(name)?;
+    let mut list = load(path)?;
+    if list
+        .workspaces
+        .iter()
+        .any(|w| w.name.eq_ignore_ascii_case(&name))
+    {
+        return Err(AppError::InvalidInput {
+            message: format!("A workspace named '{}' already exists", name),
+        });
+    }
+
+    let slug = match crate::execution::slugify(&name) {
+        slug if slug.is_empty() => "workspace".to_string(),
+        slug => slug,
+    };
+    let id = (1..)
+        .map(|n| match n {
+            1 => slug.clone(),
+            n => format!("{}-{}", slug, n),
+        })
+        .find(|id| id != DEFAULT_WORKSPACE_ID && !list.workspaces.iter().any(|w| &w.id == id))
+        .expect("unbounded range always yields a free id");
+
+    let workspace = Workspace {
+        id,
+        name,
+        created_at: Utc::now(),
+    };
+    list.workspaces.push(workspace.clone());
+    save(path, &list)?;
+    Ok(workspace)
+}
+
+fn rename_in(path: &Path, id: &str, name: &str) -> Result<Workspace> {
+    let name = validate_name(name)?;
+    let mut list = load(path)?;
+    let workspace = find(&mut list, id)?;
+    workspace.name = name;
+    let renamed = workspace.clone();
+    save(path, &list)?;
+    Ok(renamed)
+}
+
+/// Removes a workspace from the list. Its files stay on disk; creating a workspace with the
+/// same name picks them up again.
+fn remove_in(path: &Path, id: &str, active: &str) -> Result<()> {
+    if id == DEFAULT_WORKSPACE_ID || id == active {
+        return Err(AppError::InvalidInput {
+            message: "The default and the active workspace cannot be removed".to_string(),
+        });
+    }
+    let mut list = load(path)?;
+    find(&mut list, id)?;
+    list.workspaces.retain(|w| w.id != id);
+    save(path, &list)
+}
+
+fn set_active_in(path: &Path, id: &str) -> Result<()> {
+    let mut list = load(path)?;
+    find(&mut list, id)?;
+    list.active = id.to_string();
+    save(path, &list)
+}
+
+/// The workspaces, with `active` set to the one this process runs in.
+pub fn list() -> Result<WorkspaceList> {
+    let mut list = load(&registry_path()?)?;
+    list.active = active_id().to_string();
+    Ok(list)
+}
+
+pub fn create(name: &str) -> Result<Workspace> {
+    create_in(&registry_path()?, name)
+}
+
+pub fn rename(id: &str, name: &str) -> Result<Workspace> {
+    rename_in(&registry_path()?, id, name)
+}
+
+pub fn remove(id: &str) -> Result<()> {
+    remove_in(&registry_path()?, id, active_id())
+}
+
+/// Records `id` as the workspace to open on the next start.
+pub fn set_active(id: &str) -> Result<()> {
+    set_active_in(&registry_path()?, id)
+}
+
+/// Makes `id` active and restarts the app into it. Does nothing when it is already active.
+pub fn switch(app: &tauri::AppHandle, id: &str) -> Result<()> {
+    if id == active_id() {
+        return Ok(());
+    }
+    set_active(id)?;
+    log::info!("Switching to workspace '{}'", id);
+    if let Some(watcher) = app.try_state::<crate::WatcherState>() {
+        let _ = watcher.0.stop();
+    }
+    app.restart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_workspace_keeps_original_locations() {
+        let base = Path::new("/data/RuleWeaver");
+        assert_eq!(scoped_dir_for(base, DEFAULT_WORKSPACE_ID), base);
+        assert_eq!(
+            scoped_dir_for(base, "work"),
+            PathBuf::from("/data/RuleWeaver/workspaces/work")
+        );
+        assert_eq!(scoped_name("vault-key"), "vault-key");
+    }
+
+    #[test]
+    fn test_create_switch_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(WORKSPACES_FILE);
+
+        let list = load(&path).unwrap();
+        assert_eq!(list.active, DEFAULT_WORKSPACE_ID);
+        assert_eq!(list.workspaces.len(), 1);
+
+        let work = create_in(&path, "Work Stuff").unwrap();
+        assert_eq!(work.id, "work-stuff");
+        assert!(create_in(&path, "work stuff").is_err());
+        let other = create_in(&path, "Work-Stuff!").unwrap();
+        assert_eq!(other.id, "work-stuff-2");
+        assert_eq!(create_in(&path, "Default!").unwrap().id, "default-2");
+
+        set_active_in(&path, &work.id).unwrap();
+        assert_eq!(load(&path).unwrap().active, "work-stuff");
+        assert!(matches!(
+            set_active_in(&path, "missing"),
+            Err(AppError::WorkspaceNotFound { .. })
+        ));
+
+        assert!(remove_in(&path, &work.id, &work.id).is_err());
+        assert!(remove_in(&path, DEFAULT_WORKSPACE_ID, &work.id).is_err());
+        rename_in(&path, &other.id, "Personal").unwrap();
+        remove_in(&path, &other.id, &work.id).unwrap();
+
+        let list = load(&path).unwrap();
+        let names: Vec<&str> = list.workspaces.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["Default", "Work Stuff", "Default!"]);
+    }
+}
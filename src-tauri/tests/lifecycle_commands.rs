@@ -3,7 +3,7 @@ mod common;
 
 use tempfile::TempDir;
 
-use ruleweaver_lib::models::{AdapterType, CreateCommandInput};
+use ruleweaver_lib::models::{AdapterType, CreateCommandInput, McpPolicy, Scope};
 
 // ──────────────────────────────────────────────────────────────────────────────
 // Test 1: Create command → reconcile → command stub file written
@@ -19,14 +19,26 @@ async fn test_command_create_reconcile_writes_stub() {
         description: "Format the codebase".into(),
         script: "npm run format".into(),
         arguments: vec![],
-        expose_via_mcp: true,
+        mcp_policy: Default::default(),
         is_placeholder: false,
         generate_slash_commands: false,
         slash_command_adapters: vec![],
+        slash_namespace: None,
+        slash_frontmatter: Default::default(),
+        slash_scope: Default::default(),
         target_paths: vec![],
         base_path: None,
         timeout_ms: None,
         max_retries: None,
+        required_secrets: vec![],
+        working_dir: None,
+        shell: None,
+        schedule: None,
+        sandbox: None,
+        artifact_globs: Vec::new(),
+        output_parser: None,
+        accepts_stdin: false,
+        metadata: Default::default(),
     })
     .await
     .unwrap();
@@ -65,14 +77,26 @@ async fn test_command_delete_reconcile_removes_stubs() {
             description: "Deploy the app".into(),
             script: "./deploy.sh".into(),
             arguments: vec![],
-            expose_via_mcp: true,
+            mcp_policy: Default::default(),
             is_placeholder: false,
             generate_slash_commands: false,
             slash_command_adapters: vec![],
+            slash_namespace: None,
+            slash_frontmatter: Default::default(),
+            slash_scope: Default::default(),
             target_paths: vec![],
             base_path: None,
             timeout_ms: None,
             max_retries: None,
+            required_secrets: vec![],
+            working_dir: None,
+            shell: None,
+            schedule: None,
+            sandbox: None,
+            artifact_globs: Vec::new(),
+            output_parser: None,
+            accepts_stdin: false,
+            metadata: Default::default(),
         })
         .await
         .unwrap();
@@ -112,14 +136,26 @@ async fn test_cursor_gets_no_command_stub() {
         description: "Test command".into(),
         script: "echo hello".into(),
         arguments: vec![],
-        expose_via_mcp: false,
+        mcp_policy: McpPolicy::hidden(),
         is_placeholder: false,
         generate_slash_commands: false,
         slash_command_adapters: vec![],
+        slash_namespace: None,
+        slash_frontmatter: Default::default(),
+        slash_scope: Default::default(),
         target_paths: vec![],
         base_path: None,
         timeout_ms: None,
         max_retries: None,
+        required_secrets: vec![],
+        working_dir: None,
+        shell: None,
+        schedule: None,
+        sandbox: None,
+        artifact_globs: Vec::new(),
+        output_parser: None,
+        accepts_stdin: false,
+        metadata: Default::default(),
     })
     .await
     .unwrap();
@@ -160,14 +196,26 @@ async fn test_slash_command_create_produces_desired_paths() {
         description: "Review code".into(),
         script: "echo review".into(),
         arguments: vec![],
-        expose_via_mcp: false,
+        mcp_policy: McpPolicy::hidden(),
         is_placeholder: false,
         generate_slash_commands: true,
         slash_command_adapters: vec!["claude-code".into()],
+        slash_namespace: None,
+        slash_frontmatter: Default::default(),
+        slash_scope: Default::default(),
         target_paths: vec![],
         base_path: None,
         timeout_ms: None,
         max_retries: None,
+        required_secrets: vec![],
+        working_dir: None,
+        shell: None,
+        schedule: None,
+        sandbox: None,
+        artifact_globs: Vec::new(),
+        output_parser: None,
+        accepts_stdin: false,
+        metadata: Default::default(),
     })
     .await
     .unwrap();
@@ -198,3 +246,69 @@ async fn test_slash_command_create_produces_desired_paths() {
         slash_paths
     );
 }
+
+// ──────────────────────────────────────────────────────────────────────────────
+// Test 5: Local-scoped slash command → every registered root, removed on deregister
+// ──────────────────────────────────────────────────────────────────────────────
+#[tokio::test]
+async fn test_local_slash_command_follows_registered_roots() {
+    let db = common::make_db().await;
+    let home_dir = TempDir::new().unwrap();
+    let repo_a = TempDir::new().unwrap();
+    let repo_b = TempDir::new().unwrap();
+    let roots = |dirs: &[&TempDir]| {
+        serde_json::to_string(
+            &dirs
+                .iter()
+                .map(|d| d.path().to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap()
+    };
+    db.set_setting("local_rule_paths", &roots(&[&repo_a, &repo_b]))
+        .await
+        .unwrap();
+
+    db.create_command(CreateCommandInput {
+        name: "lint".into(),
+        description: "Lint the repo".into(),
+        script: "npm run lint".into(),
+        generate_slash_commands: true,
+        slash_command_adapters: vec!["claude-code".into()],
+        slash_scope: Scope::Local,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let engine = common::make_engine(db.clone(), home_dir.path());
+    let result = engine.reconcile(false, None).await.unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let file_a = repo_a.path().join(".claude/commands/lint.md");
+    let file_b = repo_b.path().join(".claude/commands/lint.md");
+    assert!(
+        file_a.exists(),
+        "Local command should be synced into repo A"
+    );
+    assert!(
+        file_b.exists(),
+        "Local command should be synced into repo B"
+    );
+    assert!(
+        !home_dir.path().join(".claude/commands/lint.md").exists(),
+        "Local-scoped commands should not get a global file"
+    );
+
+    // Deregister repo B; its copy is removed on the next reconcile.
+    db.set_setting("local_rule_paths", &roots(&[&repo_a]))
+        .await
+        .unwrap();
+    let result = engine.reconcile(false, None).await.unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(file_a.exists());
+    assert!(
+        !file_b.exists(),
+        "Deregistered repo should lose its slash command copy"
+    );
+}
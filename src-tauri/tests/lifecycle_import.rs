@@ -43,6 +43,7 @@ async fn test_import_from_file_creates_rule_in_db() {
             default_adapters: None,
             selected_candidate_ids: None,
             max_file_size_bytes: None,
+            allow_near_duplicates: false,
         },
     )
     .await
@@ -84,6 +85,7 @@ async fn test_import_duplicate_content_skipped() {
         default_adapters: None,
         selected_candidate_ids: None,
         max_file_size_bytes: None,
+        allow_near_duplicates: false,
     };
 
     // First import
@@ -150,6 +152,7 @@ async fn test_import_conflict_rename_policy() {
             default_adapters: None,
             selected_candidate_ids: None,
             max_file_size_bytes: None,
+            allow_near_duplicates: false,
         },
     )
     .await
@@ -181,6 +184,7 @@ async fn test_import_conflict_rename_policy() {
             default_adapters: None,
             selected_candidate_ids: None,
             max_file_size_bytes: None,
+            allow_near_duplicates: false,
         },
     )
     .await
@@ -227,6 +231,7 @@ async fn test_import_conflict_replace_policy() {
             default_adapters: None,
             selected_candidate_ids: None,
             max_file_size_bytes: None,
+            allow_near_duplicates: false,
         },
     )
     .await
@@ -256,6 +261,7 @@ async fn test_import_conflict_replace_policy() {
             default_adapters: None,
             selected_candidate_ids: None,
             max_file_size_bytes: None,
+            allow_near_duplicates: false,
         },
     )
     .await
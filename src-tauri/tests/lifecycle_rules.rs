@@ -37,6 +37,7 @@ async fn test_rule_create_produces_desired_state() {
         target_paths: None,
         enabled_adapters: vec![AdapterType::ClaudeCode, AdapterType::OpenCode],
         enabled: true,
+        metadata: Default::default(),
     })
     .await
     .unwrap();
@@ -75,6 +76,7 @@ async fn test_rule_create_reconcile_writes_files() {
         target_paths: None,
         enabled_adapters: vec![AdapterType::ClaudeCode],
         enabled: true,
+        metadata: Default::default(),
     })
     .await
     .unwrap();
@@ -117,6 +119,7 @@ async fn test_rule_update_reconcile_updates_file() {
             target_paths: None,
             enabled_adapters: vec![AdapterType::ClaudeCode],
             enabled: true,
+            metadata: Default::default(),
         })
         .await
         .unwrap();
@@ -166,6 +169,7 @@ async fn test_rule_delete_reconcile_removes_orphan() {
             target_paths: None,
             enabled_adapters: vec![AdapterType::ClaudeCode],
             enabled: true,
+            metadata: Default::default(),
         })
         .await
         .unwrap();
@@ -212,6 +216,7 @@ async fn test_global_rule_writes_to_global_path_only() {
         target_paths: None,
         enabled_adapters: vec![AdapterType::ClaudeCode],
         enabled: true,
+        metadata: Default::default(),
     })
     .await
     .unwrap();
@@ -3,7 +3,7 @@ mod common;
 
 use tempfile::TempDir;
 
-use ruleweaver_lib::models::{CreateSkillInput, Scope, UpdateSkillInput};
+use ruleweaver_lib::models::{CreateSkillInput, Scope, SkillVersionPin, UpdateSkillInput};
 
 // ──────────────────────────────────────────────────────────────────────────────
 // Test 1: Create skill → reconcile → SKILL.md written to supported adapter dir
@@ -26,6 +26,11 @@ async fn test_skill_create_reconcile_writes_skill_md() {
         target_adapters: vec![], // all supported
         target_paths: vec![],
         base_path: None,
+        metadata: Default::default(),
+        bundle_files: vec![],
+        allowed_tools: vec!["Read".into(), "Bash(git diff:*)".into()],
+        license: Some("MIT".into()),
+        version: None,
     })
     .await
     .unwrap();
@@ -54,6 +59,13 @@ async fn test_skill_create_reconcile_writes_skill_md() {
         content.contains("Do the thing step by step."),
         "SKILL.md should contain the skill instructions"
     );
+    assert!(
+        content.starts_with(
+            "---\nname: my-skill\ndescription: A test skill\nallowed-tools: Read, Bash(git diff:*)\nlicense: MIT\nmetadata:\n  version: 1.0.0\n---\n"
+        ),
+        "SKILL.md should open with spec frontmatter: {}",
+        content
+    );
 }
 
 // ──────────────────────────────────────────────────────────────────────────────
@@ -78,6 +90,11 @@ async fn test_skill_adapter_targeting_limits_distribution() {
         target_adapters: vec!["claude-code".into()],
         target_paths: vec![],
         base_path: None,
+        metadata: Default::default(),
+        bundle_files: vec![],
+        allowed_tools: vec![],
+        license: None,
+        version: None,
     })
     .await
     .unwrap();
@@ -132,6 +149,11 @@ async fn test_skill_delete_reconcile_removes_skill_md() {
             target_adapters: vec!["claude-code".into()],
             target_paths: vec![],
             base_path: None,
+            metadata: Default::default(),
+            bundle_files: vec![],
+            allowed_tools: vec![],
+            license: None,
+            version: None,
         })
         .await
         .unwrap();
@@ -183,6 +205,11 @@ async fn test_skill_update_reconcile_updates_content() {
             target_adapters: vec!["claude-code".into()],
             target_paths: vec![],
             base_path: None,
+            metadata: Default::default(),
+            bundle_files: vec![],
+            allowed_tools: vec![],
+            license: None,
+            version: None,
         })
         .await
         .unwrap();
@@ -242,6 +269,11 @@ async fn test_cursor_gets_no_skill_files() {
         target_adapters: vec!["cursor".into()],
         target_paths: vec![],
         base_path: None,
+        metadata: Default::default(),
+        bundle_files: vec![],
+        allowed_tools: vec![],
+        license: None,
+        version: None,
     })
     .await
     .unwrap();
@@ -285,6 +317,11 @@ async fn test_windsurf_gets_skill_files() {
         target_adapters: vec!["windsurf".into()],
         target_paths: vec![],
         base_path: None,
+        metadata: Default::default(),
+        bundle_files: vec![],
+        allowed_tools: vec![],
+        license: None,
+        version: None,
     })
     .await
     .unwrap();
@@ -311,3 +348,186 @@ async fn test_windsurf_gets_skill_files() {
         skill_paths
     );
 }
+
+// ──────────────────────────────────────────────────────────────────────────────
+// Test 7: Skill bundle → scripts/resources copied next to SKILL.md, cleared when dropped
+// ──────────────────────────────────────────────────────────────────────────────
+#[tokio::test]
+async fn test_skill_bundle_is_copied_and_cleared() {
+    let db = common::make_db().await;
+    let home_dir = TempDir::new().unwrap();
+    let source_dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(source_dir.path().join("scripts")).unwrap();
+    std::fs::create_dir_all(source_dir.path().join("resources").join("data")).unwrap();
+    std::fs::write(source_dir.path().join("scripts").join("run.sh"), "echo run").unwrap();
+    std::fs::write(
+        source_dir
+            .path()
+            .join("resources")
+            .join("data")
+            .join("table.csv"),
+        "a,b",
+    )
+    .unwrap();
+
+    let skill = db
+        .create_skill(CreateSkillInput {
+            id: None,
+            name: "bundled-skill".into(),
+            description: "Ships a script".into(),
+            instructions: "Run scripts/run.sh.".into(),
+            scope: Scope::Global,
+            input_schema: vec![],
+            directory_path: source_dir.path().to_string_lossy().to_string(),
+            entry_point: "scripts/run.sh".into(),
+            enabled: true,
+            target_adapters: vec!["claude-code".into()],
+            target_paths: vec![],
+            base_path: None,
+            metadata: Default::default(),
+            bundle_files: vec!["resources/data/table.csv".into(), "scripts/run.sh".into()],
+            allowed_tools: vec![],
+            license: None,
+            version: None,
+        })
+        .await
+        .unwrap();
+
+    let engine = common::make_engine(db.clone(), home_dir.path());
+    let result = engine.reconcile(false, None).await.unwrap();
+    assert!(
+        result.success,
+        "Reconcile must succeed: {:?}",
+        result.errors
+    );
+
+    let target = home_dir
+        .path()
+        .join(".claude")
+        .join("skills")
+        .join("bundled-skill");
+    assert_eq!(
+        std::fs::read_to_string(target.join("scripts").join("run.sh")).unwrap(),
+        "echo run"
+    );
+    assert!(target.join("resources/data/table.csv").exists());
+    let skill_md = std::fs::read_to_string(target.join("SKILL.md")).unwrap();
+    assert!(skill_md.contains("- `scripts/run.sh`"));
+
+    // Editing an asset alone re-syncs the skill.
+    std::fs::write(source_dir.path().join("scripts").join("run.sh"), "echo v2").unwrap();
+    let result = engine.reconcile(false, None).await.unwrap();
+    assert_eq!(result.updated, 1);
+    assert_eq!(
+        std::fs::read_to_string(target.join("scripts").join("run.sh")).unwrap(),
+        "echo v2"
+    );
+
+    db.update_skill(
+        &skill.id,
+        UpdateSkillInput {
+            bundle_files: Some(vec![]),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    engine.reconcile(false, None).await.unwrap();
+    assert!(target.join("SKILL.md").exists());
+    assert!(!target.join("scripts").exists());
+    assert!(!target.join("resources").exists());
+}
+
+// ──────────────────────────────────────────────────────────────────────────────
+// Test 8: Updates bump the version, and a pin keeps an adapter on the old one
+// ──────────────────────────────────────────────────────────────────────────────
+#[tokio::test]
+async fn test_skill_version_history_and_pins() {
+    let db = common::make_db().await;
+    let home_dir = TempDir::new().unwrap();
+
+    let skill = db
+        .create_skill(CreateSkillInput {
+            id: None,
+            name: "versioned-skill".into(),
+            description: "Versioned".into(),
+            instructions: "First instructions.".into(),
+            scope: Scope::Global,
+            input_schema: vec![],
+            directory_path: "".into(),
+            entry_point: "".into(),
+            enabled: true,
+            target_adapters: vec!["claude-code".into(), "windsurf".into()],
+            target_paths: vec![],
+            base_path: None,
+            metadata: Default::default(),
+            bundle_files: vec![],
+            allowed_tools: vec![],
+            license: None,
+            version: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(skill.version, "1.0.0");
+
+    let updated = db
+        .update_skill(
+            &skill.id,
+            UpdateSkillInput {
+                instructions: Some("Second instructions.".into()),
+                changelog: Some("Reworded".into()),
+                version_pins: Some(vec![SkillVersionPin {
+                    adapter: Some("claude-code".into()),
+                    repo_path: None,
+                    version: "1.0.0".into(),
+                }]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.version, "1.0.1");
+
+    let versions = db.get_skill_versions(&skill.id).await.unwrap();
+    let listed: Vec<_> = versions.iter().map(|v| v.version.as_str()).collect();
+    assert_eq!(listed, vec!["1.0.1", "1.0.0"]);
+    assert_eq!(versions[0].changelog, "Reworded");
+
+    let lower = db
+        .update_skill(
+            &skill.id,
+            UpdateSkillInput {
+                version: Some("0.9.0".into()),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(lower.is_err(), "Versions must only move forward");
+
+    let engine = common::make_engine(db, home_dir.path());
+    engine.reconcile(false, None).await.unwrap();
+
+    let claude = std::fs::read_to_string(
+        home_dir
+            .path()
+            .join(".claude")
+            .join("skills")
+            .join("versioned-skill")
+            .join("SKILL.md"),
+    )
+    .unwrap();
+    assert!(claude.contains("First instructions."));
+    assert!(claude.contains("version: 1.0.0"));
+
+    let windsurf = std::fs::read_to_string(
+        home_dir
+            .path()
+            .join(".windsurf")
+            .join("skills")
+            .join("versioned-skill")
+            .join("SKILL.md"),
+    )
+    .unwrap();
+    assert!(windsurf.contains("Second instructions."));
+    assert!(windsurf.contains("version: 1.0.1"));
+}
@@ -15,6 +15,7 @@ import { useKeyboardShortcuts, SHORTCUTS } from "./hooks/useKeyboardShortcuts";
 import { useRulesStore } from "./stores/rulesStore";
 import { motion, AnimatePresence } from "framer-motion";
 import type { Conflict } from "./types/rule";
+import type { ChangeEvent } from "./types/status";
 import { useRegistryStore } from "./stores/registryStore";
 import { getToolFileName } from "./lib/utils";
 import "./index.css";
@@ -32,7 +33,7 @@ function App() {
   };
 
   const { fetchTools } = useRegistryStore();
-  const { rules, fetchRules } = useRulesStore();
+  const { rules, fetchRules, applyChange } = useRulesStore();
   const [activeConflict, setActiveConflict] = useState<Conflict | null>(null);
   const [isConflictDialogOpen, setIsConflictDialogOpen] = useState(false);
 
@@ -57,10 +58,28 @@ function App() {
       setIsConflictDialogOpen(true);
     });
 
+    const unlistenOpenRule = listen<string>("deep-link-open-rule", (event) => {
+      setActiveView("rules");
+      setPendingId(event.payload);
+    });
+    const unlistenImported = listen("deep-link-imported", () => {
+      fetchRules();
+    });
+    const unlistenRuleChanges = ["rule-created", "rule-updated", "rule-deleted"].map((name) =>
+      listen<ChangeEvent>(name, (event) => applyChange(event.payload))
+    );
+    const unlistenDropped = listen("artifact-changes-dropped", () => {
+      fetchRules();
+    });
+
     return () => {
       unlisten.then((f) => f());
+      unlistenOpenRule.then((f) => f());
+      unlistenImported.then((f) => f());
+      unlistenRuleChanges.forEach((unlistenChange) => unlistenChange.then((f) => f()));
+      unlistenDropped.then((f) => f());
     };
-  }, [fetchRules, fetchTools]);
+  }, [fetchRules, fetchTools, applyChange]);
 
   useKeyboardShortcuts({
     shortcuts: [
@@ -53,6 +53,10 @@ const mockSkills: Skill[] = [
     enabled: true,
     targetAdapters: [],
     targetPaths: [],
+    bundleFiles: [],
+    allowedTools: [],
+    version: "1.0.0",
+    versionPins: [],
     createdAt: Date.now(),
     updatedAt: Date.now(),
   },
@@ -68,6 +72,10 @@ const mockSkills: Skill[] = [
     enabled: false,
     targetAdapters: ["claude_code"],
     targetPaths: [],
+    bundleFiles: [],
+    allowedTools: [],
+    version: "1.0.0",
+    versionPins: [],
     createdAt: Date.now(),
     updatedAt: Date.now(),
   },
@@ -119,6 +127,10 @@ describe("Skills Component", () => {
       enabled: true,
       targetAdapters: [],
       targetPaths: [],
+      bundleFiles: [],
+      allowedTools: [],
+      version: "1.0.0",
+      versionPins: [],
       createdAt: Date.now(),
       updatedAt: Date.now(),
     };
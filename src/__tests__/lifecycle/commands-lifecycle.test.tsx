@@ -54,7 +54,7 @@ const mockCommand: CommandModel = {
   description: "Deploys the application",
   script: "npm run deploy",
   arguments: [],
-  exposeViaMcp: true,
+  mcpPolicy: { access: "open", readOnly: false, localOnly: false, tokens: [] },
   isPlaceholder: false,
   createdAt: Date.now(),
   updatedAt: Date.now(),
@@ -62,15 +62,21 @@ function makeHandlers() {
     addRepositoryRoot: vi.fn().mockResolvedValue(undefined),
     removeRepositoryRoot: vi.fn().mockResolvedValue(undefined),
     saveRepositoryRoots: vi.fn().mockResolvedValue(undefined),
+    removeMissingRepositoryRoots: vi.fn().mockResolvedValue(undefined),
+    installGitHooks: vi.fn().mockResolvedValue(undefined),
     migrateToFileStorage: vi.fn(),
     rollbackMigration: vi.fn(),
     verifyMigration: vi.fn(),
+    completeCutover: vi.fn(),
+    rebuildFileIndex: vi.fn(),
     startMcp: vi.fn(),
     stopMcp: vi.fn(),
     refreshMcpStatus: vi.fn(),
     toggleMcpAutoStart: vi.fn(),
     toggleMinimizeToTray: vi.fn(),
     toggleLaunchOnStartup: vi.fn(),
+    toggleStartupDriftCheck: vi.fn(),
+    togglePollRuleFolders: vi.fn(),
     handleExport: vi.fn(),
     handleImport: vi.fn(),
     executeImport: vi.fn(),
@@ -92,14 +98,17 @@ function makeBaseState(overrides: Partial<UseSettingsStateReturn> = {}): UseSett
     hasChanges: false,
     isSaving: false,
     repositoryRoots: [],
+    missingRepositoryRoots: [],
     repoPathsDirty: false,
     isSavingRepos: false,
     storageMode: "sqlite",
+    dualWriteStatus: null,
     storageInfo: null,
     isMigratingStorage: false,
     backupPath: "",
     migrationProgress: null,
     isRollingBack: false,
+    isRebuildingIndex: false,
     isVerifyingMigration: false,
     mcpStatus: null,
     mcpInstructions: null,
@@ -107,6 +116,8 @@ function makeBaseState(overrides: Partial<UseSettingsStateReturn> = {}): UseSett
     mcpAutoStart: false,
     minimizeToTray: true,
     launchOnStartup: false,
+    startupDriftCheck: false,
+    pollRuleFolders: false,
     mcpLogs: [],
     isExporting: false,
     isImporting: false,
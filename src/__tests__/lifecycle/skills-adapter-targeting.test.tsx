@@ -54,6 +54,10 @@ const mockSkill: Skill = {
   enabled: true,
   targetAdapters: [],
   targetPaths: [],
+  bundleFiles: [],
+  allowedTools: [],
+  version: "1.0.0",
+  versionPins: [],
   createdAt: Date.now(),
   updatedAt: Date.now(),
 };
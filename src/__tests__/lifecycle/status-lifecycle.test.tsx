@@ -74,6 +74,7 @@ const mockSummary: StatusSummary = {
   missing: 1,
   conflicted: 0,
   unsupported: 0,
+  mcpFallback: 0,
   error: 0,
 };
 
@@ -21,7 +21,7 @@ import { SlashCommandsSection } from "./SlashCommandsSection";
 import { cn, resolveWorkspacePathPreview } from "@/lib/utils";
 import { useMemo } from "react";
 import { featureManager, FEATURE_FLAGS } from "@/lib/featureManager";
-import type { CommandModel, ExecutionLog } from "@/types/command";
+import type { CommandModel, ExecutionLog, McpAccess } from "@/types/command";
 import type {
   CommandFormData,
   TestOutput,
@@ -29,6 +29,12 @@ import type {
   SlashSyncStatus,
 } from "@/hooks/useCommandsState";
 
+const MCP_ACCESS_OPTIONS = [
+  { value: "open", label: "Open" },
+  { value: "approval_required", label: "Requires approval" },
+  { value: "hidden", label: "Hidden" },
+];
+
 interface CommandEditorProps {
   selected: CommandModel | null;
   form: CommandFormData;
@@ -295,18 +301,69 @@ export function CommandEditor({
           )}
         </div>
 
-        <div className="flex items-center justify-between rounded-xl border border-white/5 bg-white/5 p-4 transition-colors hover:bg-white/10">
-          <div>
-            <div className="font-semibold text-sm">Expose via MCP</div>
-            <div className="text-[10px] uppercase tracking-wider text-muted-foreground/60">
-              Enable this command in tools/list responses.
+        <div className="space-y-3 rounded-xl border border-white/5 bg-white/5 p-4 transition-colors hover:bg-white/10">
+          <div className="flex items-center justify-between gap-4">
+            <div>
+              <div className="font-semibold text-sm">MCP access</div>
+              <div className="text-[10px] uppercase tracking-wider text-muted-foreground/60">
+                Which MCP clients can see and call this command.
+              </div>
+            </div>
+            <div className="w-48">
+              <Select
+                value={form.mcpPolicy.access}
+                onChange={(value) =>
+                  onUpdateForm({
+                    mcpPolicy: { ...form.mcpPolicy, access: value as McpAccess },
+                  })
+                }
+                options={MCP_ACCESS_OPTIONS}
+                aria-label="MCP access"
+              />
             </div>
           </div>
-          <Switch
-            checked={form.exposeViaMcp}
-            onCheckedChange={(checked) => onUpdateForm({ exposeViaMcp: checked })}
-            aria-label="Expose command via MCP"
-          />
+          {form.mcpPolicy.access !== "hidden" && (
+            <>
+              <div className="flex items-center justify-between">
+                <div className="text-xs">Read-only (offered to read-only tokens)</div>
+                <Switch
+                  checked={form.mcpPolicy.readOnly}
+                  onCheckedChange={(checked) =>
+                    onUpdateForm({ mcpPolicy: { ...form.mcpPolicy, readOnly: checked } })
+                  }
+                  aria-label="Mark command read-only"
+                />
+              </div>
+              <div className="flex items-center justify-between">
+                <div className="text-xs">Local only (never callable with named tokens)</div>
+                <Switch
+                  checked={form.mcpPolicy.localOnly}
+                  onCheckedChange={(checked) =>
+                    onUpdateForm({ mcpPolicy: { ...form.mcpPolicy, localOnly: checked } })
+                  }
+                  aria-label="Restrict command to local clients"
+                />
+              </div>
+              {!form.mcpPolicy.localOnly && (
+                <Input
+                  value={form.mcpPolicy.tokens.join(", ")}
+                  onChange={(e) =>
+                    onUpdateForm({
+                      mcpPolicy: {
+                        ...form.mcpPolicy,
+                        tokens: e.target.value
+                          .split(",")
+                          .map((token) => token.trim())
+                          .filter(Boolean),
+                      },
+                    })
+                  }
+                  placeholder="Allowed tokens (empty allows all)"
+                  aria-label="Allowed MCP tokens"
+                />
+              )}
+            </>
+          )}
         </div>
 
         <SlashCommandsSection
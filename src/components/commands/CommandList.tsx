@@ -126,7 +126,7 @@ export function CommandList({
                   (cmd.targetPaths?.length || 0) > 0 && (
                     <WatchingIndicator paths={cmd.targetPaths} justRefreshed={!!mcpJustRefreshed} />
                   )}{" "}
-                {cmd.exposeViaMcp ? (
+                {cmd.mcpPolicy.access !== "hidden" ? (
                   <Badge
                     variant="default"
                     className="h-4 text-[9px] px-1.5 uppercase font-bold tracking-tighter bg-primary/20 text-primary border-primary/20"
@@ -1,6 +1,7 @@
 import * as React from "react";
 import { Sidebar } from "./Sidebar";
 import { Header } from "./Header";
+import { useDriftWatcher } from "@/hooks/useDriftWatcher";
 
 type Theme = "light" | "dark" | "system";
 
@@ -13,6 +14,7 @@ interface MainLayoutProps {
 export function MainLayout({ children, activeView, onViewChange }: MainLayoutProps) {
   const [sidebarCollapsed, setSidebarCollapsed] = React.useState(false);
   const [theme, setTheme] = React.useState<Theme>("system");
+  useDriftWatcher();
 
   React.useEffect(() => {
     const root = window.document.documentElement;
@@ -25,6 +25,7 @@ import { SyncProgress } from "@/components/sync/SyncProgress";
 import { SyncResultsDialog } from "@/components/sync/SyncResultsDialog";
 import { DashboardSkeleton } from "@/components/ui/skeleton";
 import type { SyncResult, SyncHistoryEntry } from "@/types/rule";
+import type { SensitiveFinding } from "@/types/status";
 
 const container: Variants = {
   hidden: { opacity: 0 },
@@ -55,6 +56,7 @@ export function Dashboard({ onNavigate }: { onNavigate: (view: string, id?: stri
   const [lastSync, setLastSync] = useState<string | null>(null);
 
   const [previewOpen, setPreviewOpen] = useState(false);
+  const [sensitiveFindings, setSensitiveFindings] = useState<SensitiveFinding[]>([]);
   const [previewResult, setPreviewResult] = useState<SyncResult | null>(null);
   const [isPreviewing, setIsPreviewing] = useState(false);
 
@@ -107,8 +109,12 @@ export function Dashboard({ onNavigate }: { onNavigate: (view: string, id?: stri
   const handleSyncClick = async () => {
     setIsPreviewing(true);
     try {
-      const result = await api.sync.previewSync();
+      const [result, findings] = await Promise.all([
+        api.sync.previewSync(),
+        api.secrets.scanContent().catch(() => []),
+      ]);
       setPreviewResult(result);
+      setSensitiveFindings(findings);
       setPreviewOpen(true);
     } catch (error) {
       addToast({
@@ -440,6 +446,10 @@ export function Dashboard({ onNavigate }: { onNavigate: (view: string, id?: stri
           fetchRules();
           handleSyncClick();
         }}
+        sensitiveFindings={sensitiveFindings}
+        onFindingAllowed={(fingerprint) =>
+          setSensitiveFindings((prev) => prev.filter((f) => f.fingerprint !== fingerprint))
+        }
       />
 
       <SyncProgress
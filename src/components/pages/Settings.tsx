@@ -53,21 +53,26 @@ export function Settings() {
     hasChanges,
     isSaving,
     repositoryRoots,
+    missingRepositoryRoots,
     repoPathsDirty,
     isSavingRepos,
     storageMode,
+    dualWriteStatus,
     storageInfo,
     isMigratingStorage,
     backupPath,
     migrationProgress,
     isRollingBack,
     isVerifyingMigration,
+    isRebuildingIndex,
+    pollRuleFolders,
     mcpStatus,
     mcpInstructions,
     isMcpLoading,
     mcpAutoStart,
     minimizeToTray,
     launchOnStartup,
+    startupDriftCheck,
     mcpLogs,
     isExporting,
     isImporting,
@@ -212,12 +217,15 @@ export function Settings() {
             >
               <RepositorySettingsCard
                 repositoryRoots={repositoryRoots}
+                missingRepositoryRoots={missingRepositoryRoots}
                 repoPathsDirty={repoPathsDirty}
                 isSavingRepos={isSavingRepos}
                 isLoading={isLoading}
                 onAdd={handlers.addRepositoryRoot}
                 onRemove={handlers.removeRepositoryRoot}
                 onSave={handlers.saveRepositoryRoots}
+                onRemoveMissing={handlers.removeMissingRepositoryRoots}
+                onInstallHooks={handlers.installGitHooks}
               />
             </motion.div>
           )}
@@ -239,12 +247,14 @@ export function Settings() {
                 mcpAutoStart={mcpAutoStart}
                 minimizeToTray={minimizeToTray}
                 launchOnStartup={launchOnStartup}
+                startupDriftCheck={startupDriftCheck}
                 onStart={handlers.startMcp}
                 onStop={handlers.stopMcp}
                 onRefresh={handlers.refreshMcpStatus}
                 onToggleAutoStart={handlers.toggleMcpAutoStart}
                 onToggleMinimizeToTray={handlers.toggleMinimizeToTray}
                 onToggleLaunchOnStartup={handlers.toggleLaunchOnStartup}
+                onToggleStartupDriftCheck={handlers.toggleStartupDriftCheck}
               />
 
               <AdapterSettingsCard
@@ -310,16 +320,22 @@ export function Settings() {
             >
               <StorageSettingsCard
                 storageMode={storageMode}
+                dualWriteStatus={dualWriteStatus}
                 storageInfo={storageInfo}
                 isMigratingStorage={isMigratingStorage}
                 backupPath={backupPath}
                 migrationProgress={migrationProgress}
                 isRollingBack={isRollingBack}
                 isVerifyingMigration={isVerifyingMigration}
+                isRebuildingIndex={isRebuildingIndex}
+                pollRuleFolders={pollRuleFolders}
                 isLoading={isLoading}
                 onMigrate={handlers.migrateToFileStorage}
                 onRollback={handlers.rollbackMigration}
                 onVerify={handlers.verifyMigration}
+                onCompleteCutover={handlers.completeCutover}
+                onRebuildIndex={handlers.rebuildFileIndex}
+                onTogglePollRuleFolders={handlers.togglePollRuleFolders}
               />
 
               <Card className="glass-card premium-shadow border-none overflow-hidden">
@@ -453,6 +469,15 @@ export function Settings() {
             <p className="text-[10px] text-muted-foreground font-mono px-1">
               File: {importPreview?.path.split(/[/\\]/).pop()}
             </p>
+            {importPreview && importPreview.verification.integrity !== "trusted" && (
+              <p className="text-xs text-amber-500 px-1">
+                {importPreview.verification.integrity === "unverified"
+                  ? "This file has no checksums, so changes made to it after export cannot be detected."
+                  : importPreview.verification.integrity === "checksummed"
+                    ? "This file is checksummed but not signed."
+                    : `This file is signed by a key you have not trusted: ${importPreview.verification.signer}`}
+              </p>
+            )}
             <div className="flex items-center space-x-3 pt-2 px-1">
               <Checkbox
                 id="overwrite"
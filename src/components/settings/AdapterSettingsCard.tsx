@@ -24,7 +24,8 @@ export function AdapterSettingsCard({
           Adapters
         </CardTitle>
         <CardDescription>
-          Enable or disable adapters for syncing rules to different AI tools
+          Switch a tool off to stop syncing, reconciling and import scanning for it, whatever
+          each rule targets
         </CardDescription>
       </CardHeader>
       <CardContent className="space-y-3 pt-6">
@@ -17,12 +17,14 @@ interface McpSettingsCardProps {
   mcpAutoStart: boolean;
   minimizeToTray: boolean;
   launchOnStartup: boolean;
+  startupDriftCheck: boolean;
   onStart: () => Promise<void>;
   onStop: () => Promise<void>;
   onRefresh: () => Promise<void>;
   onToggleAutoStart: (enabled: boolean) => Promise<void>;
   onToggleMinimizeToTray: (enabled: boolean) => Promise<void>;
   onToggleLaunchOnStartup: (enabled: boolean) => Promise<void>;
+  onToggleStartupDriftCheck: (enabled: boolean) => Promise<void>;
 }
 
 export function McpSettingsCard({
@@ -33,12 +35,14 @@ export function McpSettingsCard({
   mcpAutoStart,
   minimizeToTray,
   launchOnStartup,
+  startupDriftCheck,
   onStart,
   onStop,
   onRefresh,
   onToggleAutoStart,
   onToggleMinimizeToTray,
   onToggleLaunchOnStartup,
+  onToggleStartupDriftCheck,
 }: McpSettingsCardProps) {
   return (
     <Card className="glass-card premium-shadow border-none overflow-hidden">
@@ -148,6 +152,16 @@ export function McpSettingsCard({
           <Switch checked={launchOnStartup} onCheckedChange={onToggleLaunchOnStartup} />
         </div>
 
+        <div className="flex items-center justify-between rounded-md border p-3">
+          <div>
+            <div className="font-medium">Check for drift on launch</div>
+            <div className="text-xs text-muted-foreground">
+              Review changes made while RuleWeaver was closed before anything is written
+            </div>
+          </div>
+          <Switch checked={startupDriftCheck} onCheckedChange={onToggleStartupDriftCheck} />
+        </div>
+
         {mcpInstructions && (
           <div className="space-y-2">
             <code className="block rounded-md bg-muted p-2 text-xs overflow-auto">
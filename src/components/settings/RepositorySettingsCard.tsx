@@ -1,25 +1,32 @@
-import { FolderOpen } from "lucide-react";
+import { FolderOpen, GitBranch } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
 
 interface RepositorySettingsCardProps {
   repositoryRoots: readonly string[];
+  missingRepositoryRoots: readonly string[];
   repoPathsDirty: boolean;
   isSavingRepos: boolean;
   isLoading: boolean;
   onAdd: () => Promise<void>;
   onRemove: (path: string) => Promise<void>;
   onSave: () => Promise<void>;
+  onRemoveMissing: () => Promise<void>;
+  onInstallHooks: (path: string) => Promise<void>;
 }
 
 export function RepositorySettingsCard({
   repositoryRoots,
+  missingRepositoryRoots,
   repoPathsDirty,
   isSavingRepos,
   isLoading,
   onAdd,
   onRemove,
   onSave,
+  onRemoveMissing,
+  onInstallHooks,
 }: RepositorySettingsCardProps) {
   return (
     <Card className="glass-card premium-shadow border-none overflow-hidden">
@@ -39,6 +46,11 @@ export function RepositorySettingsCard({
           <Button onClick={onSave} disabled={!repoPathsDirty || isSavingRepos || isLoading}>
             {isSavingRepos ? "Saving..." : "Save Repositories"}
           </Button>
+          {missingRepositoryRoots.length > 0 && (
+            <Button variant="ghost" onClick={() => void onRemoveMissing()} disabled={isLoading}>
+              Remove {missingRepositoryRoots.length} Missing
+            </Button>
+          )}
         </div>
 
         {repositoryRoots.length === 0 ? (
@@ -47,15 +59,32 @@ export function RepositorySettingsCard({
           <div className="space-y-2">
             {repositoryRoots.map((path) => (
               <div key={path} className="flex items-center justify-between rounded-md border p-2">
-                <span className="text-xs break-all">{path}</span>
-                <Button
-                  variant="ghost"
-                  size="sm"
-                  onClick={() => void onRemove(path)}
-                  aria-label={`Remove repository ${path}`}
-                >
-                  Remove
-                </Button>
+                <span className="flex items-center gap-2 text-xs break-all">
+                  {path}
+                  {missingRepositoryRoots.includes(path) && (
+                    <Badge variant="warning">Missing</Badge>
+                  )}
+                </span>
+                <div className="flex shrink-0 items-center gap-1">
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => void onInstallHooks(path)}
+                    disabled={missingRepositoryRoots.includes(path)}
+                    title="Add pre-commit and post-merge hooks that check generated rule files"
+                    aria-label={`Install git hooks in ${path}`}
+                  >
+                    <GitBranch className="mr-1 h-3 w-3" /> Git Hooks
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => void onRemove(path)}
+                    aria-label={`Remove repository ${path}`}
+                  >
+                    Remove
+                  </Button>
+                </div>
               </div>
             ))}
           </div>
@@ -1,7 +1,21 @@
-import { ShieldCheck, RotateCcw } from "lucide-react";
+import { ShieldCheck, RotateCcw, ArrowRightLeft, Wrench } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
+import { Switch } from "@/components/ui/switch";
+import type { StorageMode } from "@/hooks/useSettingsState";
+import type { DualWriteStatus } from "@/types/status";
+
+const DUAL_WRITE_DAYS = 7;
+
+const MODE_LABELS: Record<StorageMode, { description: string; badge: string }> = {
+  sqlite: { description: "SQLite database (legacy)", badge: "SQLite" },
+  dual: {
+    description: "SQLite, mirrored to rule files until cutover",
+    badge: "Dual-Write",
+  },
+  file: { description: "File storage (.ruleweaver/rules/*.md)", badge: "File" },
+};
 
 interface MigrationProgress {
   total: number;
@@ -11,32 +25,48 @@ interface MigrationProgress {
 }
 
 interface StorageSettingsCardProps {
-  storageMode: "sqlite" | "file";
+  storageMode: StorageMode;
+  dualWriteStatus: DualWriteStatus | null;
   storageInfo: Record<string, string> | null;
   isMigratingStorage: boolean;
   backupPath: string;
   migrationProgress: MigrationProgress | null;
   isRollingBack: boolean;
   isVerifyingMigration: boolean;
+  isRebuildingIndex: boolean;
+  pollRuleFolders: boolean;
   isLoading: boolean;
-  onMigrate: () => Promise<void>;
+  onMigrate: (dualWriteDays?: number) => Promise<void>;
   onRollback: () => Promise<void>;
   onVerify: () => Promise<void>;
+  onCompleteCutover: (force: boolean) => Promise<void>;
+  onRebuildIndex: () => Promise<void>;
+  onTogglePollRuleFolders: (enabled: boolean) => Promise<void>;
 }
 
 export function StorageSettingsCard({
   storageMode,
+  dualWriteStatus,
   storageInfo,
   isMigratingStorage,
   backupPath,
   migrationProgress,
   isRollingBack,
   isVerifyingMigration,
+  isRebuildingIndex,
+  pollRuleFolders,
   isLoading,
   onMigrate,
   onRollback,
   onVerify,
+  onCompleteCutover,
+  onRebuildIndex,
+  onTogglePollRuleFolders,
 }: StorageSettingsCardProps) {
+  const divergence = dualWriteStatus?.divergence;
+  const periodOver =
+    !dualWriteStatus?.cutoverAfter || new Date(dualWriteStatus.cutoverAfter) <= new Date();
+
   return (
     <Card className="glass-card premium-shadow border-none overflow-hidden">
       <CardHeader className="bg-white/5 pb-4">
@@ -52,13 +82,11 @@ export function StorageSettingsCard({
           <div>
             <div className="font-medium">Current Mode</div>
             <div className="text-sm text-muted-foreground">
-              {storageMode === "file"
-                ? "File storage (.ruleweaver/rules/*.md)"
-                : "SQLite database (legacy)"}
+              {MODE_LABELS[storageMode].description}
             </div>
           </div>
-          <Badge variant={storageMode === "file" ? "default" : "outline"}>
-            {storageMode === "file" ? "File" : "SQLite"}
+          <Badge variant={storageMode === "sqlite" ? "outline" : "default"}>
+            {MODE_LABELS[storageMode].badge}
           </Badge>
         </div>
 
@@ -70,10 +98,51 @@ export function StorageSettingsCard({
           </div>
         )}
 
-        {storageMode !== "file" && (
-          <Button onClick={onMigrate} disabled={isMigratingStorage || isLoading}>
-            {isMigratingStorage ? "Migrating..." : "Migrate to File Storage"}
-          </Button>
+        {storageMode === "sqlite" && (
+          <div className="flex flex-wrap gap-2">
+            <Button onClick={() => onMigrate()} disabled={isMigratingStorage || isLoading}>
+              {isMigratingStorage ? "Migrating..." : "Migrate to File Storage"}
+            </Button>
+            <Button
+              variant="outline"
+              onClick={() => onMigrate(DUAL_WRITE_DAYS)}
+              disabled={isMigratingStorage || isLoading}
+            >
+              <ArrowRightLeft className="mr-2 h-4 w-4" />
+              Dual-Write for {DUAL_WRITE_DAYS} Days
+            </Button>
+          </div>
+        )}
+
+        {storageMode === "dual" && dualWriteStatus && (
+          <div className="rounded-md border p-3 space-y-2">
+            <div className="flex items-center justify-between text-sm">
+              <span className="text-muted-foreground">Divergence Report</span>
+              <Badge variant={divergence?.is_valid ? "success" : "destructive"}>
+                {divergence?.is_valid ? "In Sync" : "Diverged"}
+              </Badge>
+            </div>
+            {divergence && (
+              <div className="text-sm">
+                {divergence.db_rule_count} database rules, {divergence.file_rule_count} rule files
+                {!divergence.is_valid &&
+                  ` (${divergence.missing_rules.length} missing, ${divergence.mismatched_rules.length} mismatched)`}
+              </div>
+            )}
+            {dualWriteStatus.cutoverAfter && (
+              <div className="text-xs text-muted-foreground">
+                Dual-write period {periodOver ? "ended" : "ends"}{" "}
+                {new Date(dualWriteStatus.cutoverAfter).toLocaleString()}
+              </div>
+            )}
+            <Button
+              size="sm"
+              onClick={() => onCompleteCutover(!periodOver)}
+              disabled={isMigratingStorage || !divergence?.is_valid}
+            >
+              {periodOver ? "Complete Cutover" : "Cut Over Early"}
+            </Button>
+          </div>
         )}
 
         {migrationProgress && (
@@ -93,19 +162,46 @@ export function StorageSettingsCard({
           </div>
         )}
 
-        {storageMode === "file" && (
+        {storageMode !== "sqlite" && (
           <div className="flex flex-wrap gap-2">
             <Button variant="outline" onClick={onVerify} disabled={isVerifyingMigration}>
               <ShieldCheck className="mr-2 h-4 w-4" />
               {isVerifyingMigration ? "Verifying..." : "Verify Migration"}
             </Button>
-            <Button variant="outline" onClick={onRollback} disabled={isRollingBack || !backupPath}>
+            <Button
+              variant="outline"
+              onClick={onRollback}
+              disabled={isRollingBack || (storageMode === "file" && !backupPath)}
+            >
               <RotateCcw className="mr-2 h-4 w-4" />
               {isRollingBack ? "Rolling Back..." : "Rollback"}
             </Button>
           </div>
         )}
 
+        <div className="flex items-center justify-between rounded-md border p-3">
+          <div>
+            <div className="font-medium">File Index</div>
+            <div className="text-sm text-muted-foreground">
+              Rescan rule files and synced files to repair stale hashes after a crash or restore
+            </div>
+          </div>
+          <Button variant="outline" onClick={onRebuildIndex} disabled={isRebuildingIndex}>
+            <Wrench className="mr-2 h-4 w-4" />
+            {isRebuildingIndex ? "Rebuilding..." : "Rebuild Index"}
+          </Button>
+        </div>
+
+        <div className="flex items-center justify-between rounded-md border p-3">
+          <div>
+            <div className="font-medium">Poll rule folders</div>
+            <div className="text-sm text-muted-foreground">
+              For rules on network drives or in Dropbox-style synced folders. Applies after restart
+            </div>
+          </div>
+          <Switch checked={pollRuleFolders} onCheckedChange={onTogglePollRuleFolders} />
+        </div>
+
         {backupPath && (
           <p className="text-xs text-muted-foreground break-all">Backup: {backupPath}</p>
         )}
@@ -1,4 +1,4 @@
-import { CheckCircle, AlertTriangle, XCircle, ExternalLink } from "lucide-react";
+import { CheckCircle, AlertTriangle, XCircle, ExternalLink, ShieldAlert } from "lucide-react";
 import { useState } from "react";
 import { Button } from "@/components/ui/button";
 import {
@@ -11,6 +11,7 @@ import {
 } from "@/components/ui/dialog";
 import { Badge } from "@/components/ui/badge";
 import type { SyncResult, Conflict, Rule } from "@/types/rule";
+import type { SensitiveFinding } from "@/types/status";
 import { api } from "@/lib/tauri";
 import { ConflictResolutionDialog } from "./ConflictResolutionDialog";
 
@@ -22,6 +23,9 @@ interface SyncPreviewDialogProps {
   onConfirm: () => void;
   onCancel: () => void;
   onConflictResolved: () => void;
+  /** Likely secrets in the rules and commands about to be written */
+  sensitiveFindings?: SensitiveFinding[];
+  onFindingAllowed?: (fingerprint: string) => void;
 }
 
 export function SyncPreviewDialog({
@@ -32,6 +36,8 @@ export function SyncPreviewDialog({
   onConfirm,
   onCancel,
   onConflictResolved,
+  sensitiveFindings = [],
+  onFindingAllowed,
 }: SyncPreviewDialogProps) {
   const [selectedConflict, setSelectedConflict] = useState<Conflict | null>(null);
   const [conflictDialogOpen, setConflictDialogOpen] = useState(false);
@@ -62,6 +68,15 @@ export function SyncPreviewDialog({
     onConflictResolved();
   };
 
+  const handleAllowFinding = async (fingerprint: string) => {
+    try {
+      await api.secrets.allowFinding(fingerprint);
+      onFindingAllowed?.(fingerprint);
+    } catch {
+      console.error("Failed to allowlist finding");
+    }
+  };
+
   if (!previewResult) return null;
 
   const newFiles = previewResult.filesWritten.filter(
@@ -97,6 +112,44 @@ export function SyncPreviewDialog({
               )}
             </div>
 
+            {sensitiveFindings.length > 0 && (
+              <div className="space-y-2">
+                <h4 className="flex items-center gap-2 font-medium text-warning">
+                  <ShieldAlert className="h-4 w-4" />
+                  Possible secrets
+                </h4>
+                <p className="text-xs text-muted-foreground">
+                  These will be written into tool files that other programs can read. Remove them
+                  or allow the ones that are safe to share.
+                </p>
+                {sensitiveFindings.map((finding) => (
+                  <div
+                    key={finding.fingerprint}
+                    className="flex items-center justify-between gap-3 p-2 rounded-md border border-warning/40 bg-warning/10 text-sm"
+                  >
+                    <div className="min-w-0">
+                      <p className="font-medium">
+                        {finding.artifactName}{" "}
+                        <span className="text-xs text-muted-foreground">
+                          {finding.artifactType}, line {finding.line}
+                        </span>
+                      </p>
+                      <p className="text-xs truncate">
+                        {finding.label}: <code>{finding.excerpt}</code>
+                      </p>
+                    </div>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => handleAllowFinding(finding.fingerprint)}
+                    >
+                      Allow
+                    </Button>
+                  </div>
+                ))}
+              </div>
+            )}
+
             {previewResult.filesWritten.length === 0 ? (
               <p className="text-muted-foreground text-center py-8">
                 No files need to be synced. All rules are up to date.
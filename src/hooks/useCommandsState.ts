@@ -4,7 +4,7 @@ import { toast } from "@/lib/toast-helpers";
 import { togglePathInSet, filterByQuery } from "@/lib/collection-utils";
 import { generateDuplicateName } from "@/lib/utils";
 import type { useToast } from "@/components/ui/toast";
-import type { CommandModel, ExecutionLog, McpStatus } from "@/types/command";
+import type { CommandModel, ExecutionLog, McpPolicy, McpStatus } from "@/types/command";
 import { useMcpWatcher } from "./useMcpWatcher";
 
 export interface AdapterInfo {
@@ -16,7 +16,7 @@ export interface CommandFormData {
   name: string;
   description: string;
   script: string;
-  exposeViaMcp: boolean;
+  mcpPolicy: McpPolicy;
   generateSlashCommands: boolean;
   slashCommandAdapters: string[];
   targetPaths: string[];
@@ -79,11 +79,18 @@ export interface UseCommandsStateReturn {
   };
 }
 
+const DEFAULT_MCP_POLICY: McpPolicy = {
+  access: "open",
+  readOnly: false,
+  localOnly: false,
+  tokens: [],
+};
+
 const initialFormData: CommandFormData = {
   name: "",
   description: "",
   script: "",
-  exposeViaMcp: true,
+  mcpPolicy: DEFAULT_MCP_POLICY,
   generateSlashCommands: false,
   slashCommandAdapters: [],
   targetPaths: [],
@@ -222,7 +229,7 @@ export function useCommandsState(
       name: selected.name,
       description: selected.description,
       script: selected.script,
-      exposeViaMcp: Boolean(selected.exposeViaMcp),
+      mcpPolicy: selected.mcpPolicy ?? DEFAULT_MCP_POLICY,
       generateSlashCommands: Boolean(selected.generateSlashCommands),
       slashCommandAdapters: selected.slashCommandAdapters ?? [],
       targetPaths: selected.targetPaths ?? [],
@@ -270,7 +277,7 @@ export function useCommandsState(
         script: "echo hello",
         isPlaceholder: false,
         arguments: [],
-        exposeViaMcp: true,
+        mcpPolicy: DEFAULT_MCP_POLICY,
         targetPaths: [],
       });
       await loadCommands();
@@ -291,7 +298,7 @@ export function useCommandsState(
         name: form.name,
         description: form.description,
         script: form.script,
-        exposeViaMcp: form.exposeViaMcp,
+        mcpPolicy: form.mcpPolicy,
         generateSlashCommands: form.generateSlashCommands,
         slashCommandAdapters: form.slashCommandAdapters,
         targetPaths: form.targetPaths,
@@ -334,7 +341,7 @@ export function useCommandsState(
         const name = isSelected ? form.name : base.name;
         const description = isSelected ? form.description : base.description;
         const script = isSelected ? form.script : base.script;
-        const exposeViaMcp = isSelected ? form.exposeViaMcp : base.exposeViaMcp;
+        const mcpPolicy = isSelected ? form.mcpPolicy : base.mcpPolicy;
         const targetPaths = isSelected ? form.targetPaths : (base.targetPaths ?? []);
         const basePath = isSelected ? form.basePath : (base.basePath ?? null);
         const timeoutMs = isSelected ? form.timeoutMs : (base.timeoutMs ?? null);
@@ -355,7 +362,7 @@ export function useCommandsState(
           script,
           isPlaceholder: base.isPlaceholder,
           arguments: base.arguments,
-          exposeViaMcp,
+          mcpPolicy,
           targetPaths,
           basePath,
           timeoutMs: timeoutMs ?? undefined,
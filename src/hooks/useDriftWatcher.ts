@@ -0,0 +1,85 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useToast } from "@/components/ui/toast";
+import { toast } from "@/lib/toast-helpers";
+import { api } from "@/lib/tauri";
+import type { DriftSummary, ReconcileResult } from "@/types/status";
+
+/**
+ * Surfaces drift reported by the background monitor as a toast with a one-click reconcile,
+ * and the opt-in startup report as a toast that applies the reviewed plan.
+ */
+export function useDriftWatcher() {
+  const { addToast } = useToast();
+
+  useEffect(() => {
+    const report = (result: ReconcileResult) => {
+      if (result.success) {
+        toast.success(addToast, {
+          title: "Reconcile Complete",
+          description: `Created ${result.created}, updated ${result.updated} and removed ${result.removed} file(s)`,
+        });
+      } else {
+        toast.error(addToast, {
+          title: "Reconcile Failed",
+          description: result.errors.join("\n"),
+        });
+      }
+    };
+
+    const reconcile = async () => {
+      try {
+        report(await api.reconciliation.reconcileDrift());
+      } catch (error) {
+        toast.error(addToast, { title: "Reconcile Failed", error });
+      }
+    };
+
+    // The report is handed out once, so the mount check and the event cannot both show it.
+    const showStartupReport = async () => {
+      const prepared = await api.reconciliation.getStartupReport().catch(() => null);
+      if (!prepared) return;
+      const { toCreate, toUpdate, toRemove } = prepared.plan;
+      const total = toCreate.length + toUpdate.length + toRemove.length;
+      addToast({
+        title: "Drift While RuleWeaver Was Closed",
+        description: `${total} change(s): ${toCreate.length} to create, ${toUpdate.length} to update, ${toRemove.length} to remove. Nothing has been written yet.`,
+        variant: "warning",
+        duration: 30000,
+        action: {
+          label: "Apply Changes",
+          onClick: async () => {
+            try {
+              report(await api.reconciliation.applyPlan(prepared.id, { excludedPaths: [] }));
+            } catch (error) {
+              toast.error(addToast, { title: "Reconcile Failed", error });
+            }
+          },
+        },
+      });
+    };
+    void showStartupReport();
+    const unlistenStartup = listen("startup-drift-report", () => {
+      void showStartupReport();
+    });
+
+    const unlisten = listen<DriftSummary>("drift-detected", (event) => {
+      const { missing, modified, stale } = event.payload;
+      addToast({
+        title: "Configuration Drift Detected",
+        description: `${missing} missing, ${modified} modified, ${stale} stale file(s)`,
+        variant: "warning",
+        duration: 15000,
+        action: {
+          label: "Reconcile Now",
+          onClick: reconcile,
+        },
+      });
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+      unlistenStartup.then((f) => f());
+    };
+  }, [addToast]);
+}
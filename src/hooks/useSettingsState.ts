@@ -1,5 +1,5 @@
 import { useEffect, useState, useCallback } from "react";
-import { open, save } from "@tauri-apps/plugin-dialog";
+import { ask, open, save } from "@tauri-apps/plugin-dialog";
 import { check, type Update } from "@tauri-apps/plugin-updater";
 import { enable, disable, isEnabled } from "@tauri-apps/plugin-autostart";
 import { api } from "@/lib/tauri";
@@ -9,13 +9,18 @@ import { useRepositoryRoots } from "@/hooks/useRepositoryRoots";
 import type { AdapterType, Rule } from "@/types/rule";
 import type { CommandModel, McpStatus, McpConnectionInstructions } from "@/types/command";
 import type { Skill } from "@/types/skill";
-
-const ADAPTER_SETTINGS_KEY = "adapter_settings";
+import type { DualWriteStatus, ExportVerification } from "@/types/status";
 
 interface AdapterSettings {
   [key: string]: boolean;
 }
 
+export type StorageMode = "sqlite" | "dual" | "file";
+
+function toStorageMode(mode: string): StorageMode {
+  return mode === "file" || mode === "dual" ? mode : "sqlite";
+}
+
 interface MigrationProgress {
   total: number;
   migrated: number;
@@ -28,6 +33,7 @@ interface ImportPreview {
   rules: Rule[];
   commands: CommandModel[];
   skills: Skill[];
+  verification: ExportVerification;
 }
 
 export interface UseSettingsStateReturn {
@@ -38,21 +44,26 @@ export interface UseSettingsStateReturn {
   hasChanges: boolean;
   isSaving: boolean;
   repositoryRoots: string[];
+  missingRepositoryRoots: string[];
   repoPathsDirty: boolean;
   isSavingRepos: boolean;
-  storageMode: "sqlite" | "file";
+  storageMode: StorageMode;
+  dualWriteStatus: DualWriteStatus | null;
   storageInfo: Record<string, string> | null;
   isMigratingStorage: boolean;
   backupPath: string;
   migrationProgress: MigrationProgress | null;
   isRollingBack: boolean;
   isVerifyingMigration: boolean;
+  isRebuildingIndex: boolean;
   mcpStatus: McpStatus | null;
   mcpInstructions: McpConnectionInstructions | null;
   isMcpLoading: boolean;
   mcpAutoStart: boolean;
   minimizeToTray: boolean;
   launchOnStartup: boolean;
+  startupDriftCheck: boolean;
+  pollRuleFolders: boolean;
   mcpLogs: string[];
   isExporting: boolean;
   isImporting: boolean;
@@ -70,15 +81,21 @@ export interface UseSettingsStateReturn {
     addRepositoryRoot: () => Promise<void>;
     removeRepositoryRoot: (path: string) => Promise<void>;
     saveRepositoryRoots: () => Promise<void>;
-    migrateToFileStorage: () => Promise<void>;
+    removeMissingRepositoryRoots: () => Promise<void>;
+    installGitHooks: (path: string) => Promise<void>;
+    migrateToFileStorage: (dualWriteDays?: number) => Promise<void>;
     rollbackMigration: () => Promise<void>;
     verifyMigration: () => Promise<void>;
+    completeCutover: (force: boolean) => Promise<void>;
+    rebuildFileIndex: () => Promise<void>;
     startMcp: () => Promise<void>;
     stopMcp: () => Promise<void>;
     refreshMcpStatus: () => Promise<void>;
     toggleMcpAutoStart: (enabled: boolean) => Promise<void>;
     toggleMinimizeToTray: (enabled: boolean) => Promise<void>;
     toggleLaunchOnStartup: (enabled: boolean) => Promise<void>;
+    toggleStartupDriftCheck: (enabled: boolean) => Promise<void>;
+    togglePollRuleFolders: (enabled: boolean) => Promise<void>;
     handleExport: () => Promise<void>;
     handleImport: () => Promise<void>;
     executeImport: () => Promise<void>;
@@ -110,13 +127,17 @@ export function useSettingsState(
   const [isImportDialogOpen, setIsImportDialogOpen] = useState(false);
   const [importMode, setImportMode] = useState<"overwrite" | "skip">("overwrite");
   const [launchOnStartup, setLaunchOnStartup] = useState(false);
-  const [storageMode, setStorageMode] = useState<"sqlite" | "file">("sqlite");
+  const [startupDriftCheck, setStartupDriftCheck] = useState(false);
+  const [pollRuleFolders, setPollRuleFolders] = useState(false);
+  const [storageMode, setStorageMode] = useState<StorageMode>("sqlite");
+  const [dualWriteStatus, setDualWriteStatus] = useState<DualWriteStatus | null>(null);
   const [storageInfo, setStorageInfo] = useState<Record<string, string> | null>(null);
   const [isMigratingStorage, setIsMigratingStorage] = useState(false);
   const [backupPath, setBackupPath] = useState<string>("");
   const [migrationProgress, setMigrationProgress] = useState<MigrationProgress | null>(null);
   const [isRollingBack, setIsRollingBack] = useState(false);
   const [isVerifyingMigration, setIsVerifyingMigration] = useState(false);
+  const [isRebuildingIndex, setIsRebuildingIndex] = useState(false);
   const [mcpStatus, setMcpStatus] = useState<McpStatus | null>(null);
   const [mcpInstructions, setMcpInstructions] = useState<McpConnectionInstructions | null>(null);
   const [isMcpLoading, setIsMcpLoading] = useState(false);
@@ -125,6 +146,7 @@ export function useSettingsState(
   const [mcpLogs, setMcpLogs] = useState<string[]>([]);
   const [repoPathsDirty, setRepoPathsDirty] = useState(false);
   const [isSavingRepos, setIsSavingRepos] = useState(false);
+  const [missingRepositoryRoots, setMissingRepositoryRoots] = useState<string[]>([]);
 
   const {
     roots: repositoryRoots,
@@ -140,28 +162,24 @@ export function useSettingsState(
         const [
           path,
           version,
-          settingsJson,
           mode,
           info,
           savedBackupPath,
           progress,
           mcpStatusRes,
-          mcpAutoStartSetting,
-          minimizeToTraySetting,
+          appSettings,
           mcpLogsInitial,
           autoStartEnabled,
           tools,
         ] = await Promise.all([
           api.app.getAppDataPath(),
           api.app.getVersion(),
-          api.settings.get(ADAPTER_SETTINGS_KEY),
           api.storage.getMode(),
           api.storage.getInfo(),
           api.settings.get("file_storage_backup_path"),
           api.storage.getMigrationProgress(),
           api.mcp.getStatus(),
-          api.settings.get("mcp_auto_start"),
-          api.settings.get("minimize_to_tray"),
+          api.settings.load(),
           api.mcp.getLogs(20),
           isEnabled(),
           api.registry.getTools(),
@@ -178,29 +196,26 @@ export function useSettingsState(
         } catch {
           setAppVersion(version);
         }
-        setStorageMode(mode === "file" ? "file" : "sqlite");
+        setStorageMode(toStorageMode(mode));
         setStorageInfo(info);
         setBackupPath(savedBackupPath ?? "");
+        if (mode === "dual") {
+          setDualWriteStatus(await api.storage.getDualWriteStatus().catch(() => null));
+        }
         setMigrationProgress(progress);
         setMcpStatus(mcpStatusRes);
-        setMcpAutoStart(mcpAutoStartSetting === "true");
-        setMinimizeToTray(minimizeToTraySetting !== "false");
+        setMcpAutoStart(appSettings.mcpAutoStart);
+        setMinimizeToTray(appSettings.minimizeToTray);
+        setStartupDriftCheck(appSettings.startupDriftCheck);
+        setPollRuleFolders(appSettings.ruleWatcherMode === "poll");
         setMcpLogs(mcpLogsInitial);
         setLaunchOnStartup(autoStartEnabled);
         await refreshRepositoryRoots();
-
-        let parsedSettings: AdapterSettings = {};
-        if (settingsJson) {
-          try {
-            parsedSettings = JSON.parse(settingsJson) as AdapterSettings;
-          } catch {
-            console.error("Failed to parse adapter settings");
-          }
-        }
+        setMissingRepositoryRoots(await api.reconciliation.getMissingRepoRoots().catch(() => []));
 
         const initialSettings: AdapterSettings = {};
         tools.forEach((t) => {
-          initialSettings[t.id] = parsedSettings[t.id] ?? true;
+          initialSettings[t.id] = appSettings.adapterSettings[t.id] ?? true;
         });
         setAdapterSettings(initialSettings);
       } catch (error) {
@@ -234,7 +249,7 @@ export function useSettingsState(
   const saveSettings = useCallback(async () => {
     setIsSaving(true);
     try {
-      await api.settings.set(ADAPTER_SETTINGS_KEY, JSON.stringify(adapterSettings));
+      await api.settings.update({ adapterSettings });
       setHasChanges(false);
       toast.success(addToast, {
         title: "Settings Saved",
@@ -320,7 +335,36 @@ export function useSettingsState(
     }
   }, [repositoryRoots, saveRepositoryRootsSetting, addToast]);
 
-  const migrateToFileStorage = useCallback(async () => {
+  const removeMissingRepositoryRoots = useCallback(async () => {
+    try {
+      const removed = await api.reconciliation.removeMissingRepoRoots(missingRepositoryRoots);
+      setMissingRepositoryRoots((prev) => prev.filter((p) => !removed.includes(p)));
+      await refreshRepositoryRoots();
+      toast.success(addToast, {
+        title: "Missing Repositories Removed",
+        description: `Removed ${removed.length} repository root(s) that no longer exist`,
+      });
+    } catch (error) {
+      toast.error(addToast, { title: "Cleanup Failed", error });
+    }
+  }, [missingRepositoryRoots, refreshRepositoryRoots, addToast]);
+
+  const installGitHooks = useCallback(
+    async (path: string) => {
+      try {
+        const status = await api.reconciliation.installGitHooks(path, false);
+        toast.success(addToast, {
+          title: "Git Hooks Installed",
+          description: `${status.installed.join(" and ")} hooks now check generated rule files in ${path}`,
+        });
+      } catch (error) {
+        toast.error(addToast, { title: "Hook Install Failed", error });
+      }
+    },
+    [addToast]
+  );
+
+  const migrateToFileStorage = useCallback(async (dualWriteDays?: number) => {
     setIsMigratingStorage(true);
     let poll: ReturnType<typeof setInterval> | null = null;
     try {
@@ -333,7 +377,7 @@ export function useSettingsState(
         }
       }, 500);
 
-      const result = await api.storage.migrateToFileStorage();
+      const result = await api.storage.migrateToFileStorage(dualWriteDays);
       clearInterval(poll);
       poll = null;
 
@@ -345,14 +389,21 @@ export function useSettingsState(
 
       setBackupPath(result.backup_path ?? "");
 
-      const [mode, info] = await Promise.all([api.storage.getMode(), api.storage.getInfo()]);
-      setStorageMode(mode === "file" ? "file" : "sqlite");
+      const [mode, info, dualWrite] = await Promise.all([
+        api.storage.getMode(),
+        api.storage.getInfo(),
+        api.storage.getDualWriteStatus(),
+      ]);
+      setStorageMode(toStorageMode(mode));
       setStorageInfo(info);
+      setDualWriteStatus(dualWrite.active ? dualWrite : null);
       setMigrationProgress(await api.storage.getMigrationProgress());
 
       toast.success(addToast, {
         title: "Migration Complete",
-        description: `Migrated ${result.rules_migrated} rules to file storage.`,
+        description: dualWriteDays
+          ? `Copied ${result.rules_migrated} rules to file storage. Both stores stay in sync for ${dualWriteDays} days before cutover.`
+          : `Migrated ${result.rules_migrated} rules to file storage.`,
       });
     } catch (error) {
       toast.error(addToast, { title: "Migration Failed", error });
@@ -365,6 +416,26 @@ export function useSettingsState(
   }, [addToast]);
 
   const rollbackMigration = useCallback(async () => {
+    if (storageMode === "dual") {
+      setIsRollingBack(true);
+      try {
+        const removed = await api.storage.rollbackDualWrite();
+        setStorageMode("sqlite");
+        setDualWriteStatus(null);
+        setBackupPath("");
+        setMigrationProgress(await api.storage.getMigrationProgress());
+        toast.success(addToast, {
+          title: "Rollback Complete",
+          description: `Dual-write stopped and ${removed} rule file(s) removed. SQLite was kept up to date.`,
+        });
+      } catch (error) {
+        toast.error(addToast, { title: "Rollback Failed", error });
+      } finally {
+        setIsRollingBack(false);
+      }
+      return;
+    }
+
     if (!backupPath) {
       toast.error(addToast, {
         title: "Rollback Unavailable",
@@ -387,12 +458,15 @@ export function useSettingsState(
     } finally {
       setIsRollingBack(false);
     }
-  }, [backupPath, addToast]);
+  }, [storageMode, backupPath, addToast]);
 
   const verifyMigration = useCallback(async () => {
     setIsVerifyingMigration(true);
     try {
       const result = await api.storage.verifyMigration();
+      if (storageMode === "dual") {
+        setDualWriteStatus(await api.storage.getDualWriteStatus());
+      }
       if (result.is_valid) {
         toast.success(addToast, {
           title: "Migration Verified",
@@ -409,6 +483,56 @@ export function useSettingsState(
     } finally {
       setIsVerifyingMigration(false);
     }
+  }, [storageMode, addToast]);
+
+  const completeCutover = useCallback(
+    async (force: boolean) => {
+      setIsMigratingStorage(true);
+      try {
+        const result = await api.storage.completeCutover(force);
+        setStorageMode("file");
+        setDualWriteStatus(null);
+        toast.success(addToast, {
+          title: "Cutover Complete",
+          description: `${result.file_rule_count} rules are now read from file storage.`,
+        });
+      } catch (error) {
+        toast.error(addToast, { title: "Cutover Refused", error });
+        setDualWriteStatus(await api.storage.getDualWriteStatus().catch(() => null));
+      } finally {
+        setIsMigratingStorage(false);
+      }
+    },
+    [addToast]
+  );
+
+  const rebuildFileIndex = useCallback(async () => {
+    setIsRebuildingIndex(true);
+    try {
+      const report = await api.storage.rebuildFileIndex();
+      const fixed =
+        report.indexEntriesAdded +
+        report.indexEntriesUpdated +
+        report.indexEntriesRemoved +
+        report.syncHashes.repaired.length +
+        report.syncHashes.removed.length;
+      const description = `Scanned ${report.ruleFilesScanned} rule files and ${report.syncHashes.filesChecked} synced files, repaired ${fixed} entries.`;
+      if (report.issues.length === 0) {
+        toast.success(addToast, { title: "Index Rebuilt", description });
+      } else {
+        toast.warning(addToast, {
+          title: "Index Rebuilt With Issues",
+          description: `${description} ${report.issues.length} issue(s) need attention: ${report.issues
+            .slice(0, 3)
+            .map((issue) => `${issue.filePath ?? issue.ruleId}: ${issue.message}`)
+            .join("; ")}`,
+        });
+      }
+    } catch (error) {
+      toast.error(addToast, { title: "Index Rebuild Failed", error });
+    } finally {
+      setIsRebuildingIndex(false);
+    }
   }, [addToast]);
 
   const refreshMcpStatus = useCallback(async () => {
@@ -463,7 +587,7 @@ export function useSettingsState(
     async (enabled: boolean) => {
       setMcpAutoStart(enabled);
       try {
-        await api.settings.set("mcp_auto_start", enabled ? "true" : "false");
+        await api.settings.update({ mcpAutoStart: enabled });
         toast.success(addToast, {
           title: "MCP Setting Saved",
           description: enabled ? "MCP will auto-start on app launch" : "MCP auto-start disabled",
@@ -480,7 +604,7 @@ export function useSettingsState(
     async (enabled: boolean) => {
       setMinimizeToTray(enabled);
       try {
-        await api.settings.set("minimize_to_tray", enabled ? "true" : "false");
+        await api.settings.update({ minimizeToTray: enabled });
         toast.success(addToast, {
           title: "Window Behavior Updated",
           description: enabled
@@ -495,6 +619,44 @@ export function useSettingsState(
     [addToast]
   );
 
+  const toggleStartupDriftCheck = useCallback(
+    async (enabled: boolean) => {
+      setStartupDriftCheck(enabled);
+      try {
+        await api.settings.update({ startupDriftCheck: enabled });
+        toast.success(addToast, {
+          title: "Startup Check Updated",
+          description: enabled
+            ? "RuleWeaver will report drift found at launch before writing anything"
+            : "Startup drift check disabled",
+        });
+      } catch (error) {
+        setStartupDriftCheck(!enabled);
+        toast.error(addToast, { title: "Setting Failed", error });
+      }
+    },
+    [addToast]
+  );
+
+  const togglePollRuleFolders = useCallback(
+    async (enabled: boolean) => {
+      setPollRuleFolders(enabled);
+      try {
+        await api.settings.update({ ruleWatcherMode: enabled ? "poll" : "auto" });
+        toast.success(addToast, {
+          title: "Rule Watching Updated",
+          description: enabled
+            ? "Rule folders will be polled after the next restart"
+            : "Native file notifications will be used after the next restart",
+        });
+      } catch (error) {
+        setPollRuleFolders(!enabled);
+        toast.error(addToast, { title: "Setting Failed", error });
+      }
+    },
+    [addToast]
+  );
+
   const toggleLaunchOnStartup = useCallback(
     async (enabled: boolean) => {
       setLaunchOnStartup(enabled);
@@ -520,6 +682,20 @@ export function useSettingsState(
 
   const handleExport = useCallback(async () => {
     try {
+      const findings = await api.secrets.scanContent().catch(() => []);
+      if (findings.length > 0) {
+        const listed = findings
+          .slice(0, 5)
+          .map((f) => `${f.artifactName} (line ${f.line}): ${f.label} ${f.excerpt}`)
+          .join("\n");
+        const more = findings.length > 5 ? `\n…and ${findings.length - 5} more` : "";
+        const proceed = await ask(
+          `The export contains ${findings.length} possible secret(s):\n\n${listed}${more}\n\nExport anyway?`,
+          { title: "Possible secrets", kind: "warning" }
+        );
+        if (!proceed) return;
+      }
+
       const selected = await save({
         filters: [
           { name: "JSON", extensions: ["json"] },
@@ -559,6 +735,7 @@ export function useSettingsState(
         rules: preview.rules,
         commands: preview.commands,
         skills: preview.skills,
+        verification: preview.verification,
       });
       setIsImportDialogOpen(true);
     } catch (error) {
@@ -638,21 +815,26 @@ export function useSettingsState(
     hasChanges,
     isSaving,
     repositoryRoots,
+    missingRepositoryRoots,
     repoPathsDirty,
     isSavingRepos,
     storageMode,
+    dualWriteStatus,
     storageInfo,
     isMigratingStorage,
     backupPath,
     migrationProgress,
     isRollingBack,
     isVerifyingMigration,
+    isRebuildingIndex,
     mcpStatus,
     mcpInstructions,
     isMcpLoading,
     mcpAutoStart,
     minimizeToTray,
     launchOnStartup,
+    startupDriftCheck,
+    pollRuleFolders,
     mcpLogs,
     isExporting,
     isImporting,
@@ -670,15 +852,21 @@ export function useSettingsState(
       addRepositoryRoot,
       removeRepositoryRoot,
       saveRepositoryRoots,
+      removeMissingRepositoryRoots,
+      installGitHooks,
       migrateToFileStorage,
       rollbackMigration,
       verifyMigration,
+      completeCutover,
+      rebuildFileIndex,
       startMcp,
       stopMcp,
       refreshMcpStatus,
       toggleMcpAutoStart,
       toggleMinimizeToTray,
       toggleLaunchOnStartup,
+      toggleStartupDriftCheck,
+      togglePollRuleFolders,
       handleExport,
       handleImport,
       executeImport,
@@ -1,47 +1,176 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  AdapterType,
   Rule,
   CreateRuleInput,
   UpdateRuleInput,
+  BulkRuleChanges,
   SyncResult,
   SyncHistoryEntry,
+  SyncReport,
+  SyncReportFormat,
   Conflict,
   ImportExecutionOptions,
   ImportExecutionResult,
   ImportHistoryEntry,
   ImportScanResult,
   TemplateRule,
+  RuleTemplate,
+  CreateRuleTemplateInput,
+  UpdateRuleTemplateInput,
+  Snippet,
+  CreateSnippetInput,
+  UpdateSnippetInput,
+  SnippetUsage,
+  RuleOrderIssue,
+  MarkdownIssue,
+  RuleFeedback,
+  RuleFeedbackInput,
+  RuleStats,
+  RuleStatsSort,
+  AiRuleProposal,
   ToolEntry,
+  InstalledTools,
+  TeamRepo,
+  ConfigureTeamRepoInput,
+  TeamRepoChange,
+  TeamRepoPullResult,
+  TeamRepoPushInput,
+  TeamRepoPushResult,
+  CloudSync,
+  ConfigureCloudSyncInput,
+  CloudSyncResult,
+  OrgPolicy,
+  ShareLinkSelection,
+  ShareLinkInfo,
 } from "@/types/rule";
 import type {
   CommandModel,
   CreateCommandInput,
   UpdateCommandInput,
   TestCommandResult,
+  CommandPreview,
   McpStatus,
   McpConnectionInstructions,
+  McpClientToken,
+  CreatedMcpToken,
   ExecutionLog,
+  ExecutionArtifact,
+  PendingApproval,
+  ApprovalAuditEntry,
+  SecretInfo,
   TemplateCommand,
+  CreateWebhookInput,
+  UpdateWebhookInput,
+  Webhook,
+  WebhookDelivery,
 } from "@/types/command";
-import type { CreateSkillInput, Skill, UpdateSkillInput, TemplateSkill } from "@/types/skill";
 import type {
+  CreateSkillInput,
+  Skill,
+  SkillTemplateInstall,
+  SkillVersion,
+  UpdateSkillInput,
+  TemplateSkill,
+} from "@/types/skill";
+import type {
+  CreateWorkflowInput,
+  UpdateWorkflowInput,
+  Workflow,
+  WorkflowRunResult,
+} from "@/types/workflow";
+import type {
+  AppLogPage,
+  AppLogQuery,
+  AppSettings,
+  ArtifactListQuery,
+  ArtifactPage,
   ArtifactStatusEntry,
+  AuditLogPage,
+  AuditLogQuery,
+  DoctorReport,
+  DualWriteStatus,
+  MetricsSnapshot,
+  FileIndexReport,
+  GitHookStatus,
+  JobRecord,
+  JobResolution,
+  PreparedReconcilePlan,
+  ProjectConfig,
+  ReconcileFilter,
+  ReconcilePlan,
+  ReconcilePlanSelection,
+  ReconcileResult,
   RepairResult,
+  ExportVerification,
+  ExportFilter,
+  ExportFormat,
+  SensitiveFinding,
+  SkillSyncStatusEntry,
   StatusFilter,
   StatusSummary,
+  UnifiedStatus,
+  StorageVerification,
+  Workspace,
+  WorkspaceList,
 } from "@/types/status";
 
 export const api = {
   rules: {
     getAll: () => invoke<Rule[]>("get_all_rules"),
+    list: (query: ArtifactListQuery) => invoke<ArtifactPage<Rule>>("list_rules", { query }),
     getById: (id: string) => invoke<Rule>("get_rule_by_id", { id }),
     create: (input: CreateRuleInput) => invoke<Rule>("create_rule", { input }),
     update: (id: string, input: UpdateRuleInput) => invoke<Rule>("update_rule", { id, input }),
     delete: (id: string) => invoke<void>("delete_rule", { id }),
     bulkDelete: (ids: string[]) => invoke<void>("bulk_delete_rules", { ids }),
+    bulkUpdate: (ids: string[], changes: BulkRuleChanges) =>
+      invoke<Rule[]>("bulk_update_rules", { ids, changes }),
+    bulkToggle: (ids: string[], enabled: boolean) =>
+      invoke<Rule[]>("bulk_toggle_rules", { ids, enabled }),
+    bulkSetAdapters: (ids: string[], adapters: AdapterType[]) =>
+      invoke<Rule[]>("bulk_set_adapters", { ids, adapters }),
     toggle: (id: string, enabled: boolean) => invoke<Rule>("toggle_rule", { id, enabled }),
     getTemplates: () => invoke<TemplateRule[]>("get_rule_templates"),
     installTemplate: (templateId: string) => invoke<Rule>("install_rule_template", { templateId }),
+    getCustomTemplates: () => invoke<RuleTemplate[]>("get_custom_rule_templates"),
+    createTemplate: (input: CreateRuleTemplateInput) =>
+      invoke<RuleTemplate>("create_rule_template", { input }),
+    updateTemplate: (id: string, input: UpdateRuleTemplateInput) =>
+      invoke<RuleTemplate>("update_rule_template", { id, input }),
+    deleteTemplate: (id: string) => invoke<void>("delete_rule_template", { id }),
+    previewFromTemplate: (templateId: string, values: Record<string, string>) =>
+      invoke<CreateRuleInput>("preview_rule_from_template", { templateId, values }),
+    createFromTemplate: (templateId: string, values: Record<string, string>) =>
+      invoke<Rule>("create_rule_from_template", { templateId, values }),
+    lintOrder: () => invoke<RuleOrderIssue[]>("lint_rule_order"),
+    validateMarkdown: (content: string) =>
+      invoke<MarkdownIssue[]>("validate_rule_markdown", { content }),
+    getStats: (staleDays?: number, sortBy?: RuleStatsSort) =>
+      invoke<RuleStats[]>("get_rule_stats", { staleDays, sortBy }),
+    reportFeedback: (input: RuleFeedbackInput) =>
+      invoke<RuleFeedback>("report_rule_feedback", { input }),
+    getFeedback: (ruleId: string, limit?: number) =>
+      invoke<RuleFeedback[]>("get_rule_feedback", { ruleId, limit }),
+  },
+
+  snippets: {
+    getAll: () => invoke<Snippet[]>("get_snippets"),
+    create: (input: CreateSnippetInput) => invoke<Snippet>("create_snippet", { input }),
+    /** Renaming a snippet that rules use fails unless `force` is set */
+    update: (id: string, input: UpdateSnippetInput, force?: boolean) =>
+      invoke<Snippet>("update_snippet", { id, input, force }),
+    /** Fails while rules use the snippet unless `force` is set */
+    delete: (id: string, force?: boolean) => invoke<void>("delete_snippet", { id, force }),
+    getUsages: (id: string) => invoke<SnippetUsage[]>("get_snippet_usages", { id }),
+  },
+
+  /** Proposals from the model configured in settings; save them as ordinary rule changes */
+  ai: {
+    draftRule: (description: string) => invoke<AiRuleProposal>("ai_draft_rule", { description }),
+    condenseRule: (id: string, maxChars: number) =>
+      invoke<AiRuleProposal>("ai_condense_rule", { id, maxChars }),
+    mergeRules: (ids: string[]) => invoke<AiRuleProposal>("ai_merge_rules", { ids }),
   },
 
   ruleImport: {
@@ -80,11 +209,48 @@ export const api = {
     getHistory: () => invoke<ImportHistoryEntry[]>("get_rule_import_history"),
   },
 
+  teamRepo: {
+    get: () => invoke<TeamRepo | null>("get_team_repo"),
+    configure: (input: ConfigureTeamRepoInput) =>
+      invoke<TeamRepo>("configure_team_repo", { input }),
+    remove: () => invoke<void>("remove_team_repo"),
+    setEnabled: (enabled: boolean) => invoke<TeamRepo>("set_team_repo_enabled", { enabled }),
+    pull: () => invoke<TeamRepoPullResult>("pull_team_repo"),
+    getChanges: () => invoke<TeamRepoChange[]>("get_team_repo_changes"),
+    push: (input: TeamRepoPushInput) => invoke<TeamRepoPushResult>("push_team_repo", { input }),
+  },
+
+  cloudSync: {
+    get: () => invoke<CloudSync | null>("get_cloud_sync"),
+    configure: (input: ConfigureCloudSyncInput) =>
+      invoke<CloudSync>("configure_cloud_sync", { input }),
+    remove: () => invoke<void>("remove_cloud_sync"),
+    setEnabled: (enabled: boolean) => invoke<CloudSync>("set_cloud_sync_enabled", { enabled }),
+    syncNow: () => invoke<CloudSyncResult>("sync_cloud_now"),
+  },
+
+  shareLink: {
+    start: (selection?: ShareLinkSelection) =>
+      invoke<ShareLinkInfo>("start_share_link", { selection }),
+    stop: () => invoke<void>("stop_share_link"),
+    get: () => invoke<ShareLinkInfo | null>("get_share_link"),
+    exportSite: (path: string, selection?: ShareLinkSelection) =>
+      invoke<number>("export_share_site", { path, selection }),
+  },
+
+  orgPolicy: {
+    get: () => invoke<OrgPolicy>("get_org_policy"),
+    set: (policy: OrgPolicy) => invoke<OrgPolicy>("set_org_policy", { policy }),
+  },
+
   sync: {
     syncRules: () => invoke<SyncResult>("sync_rules"),
     previewSync: () => invoke<SyncResult>("preview_sync"),
     getHistory: (limit?: number) =>
       invoke<SyncHistoryEntry[]>("get_sync_history", { limit: limit ?? 50 }),
+    getReport: (syncId: string) => invoke<SyncReport>("get_sync_report", { syncId }),
+    exportReport: (syncId: string, format: SyncReportFormat, path: string) =>
+      invoke<void>("export_sync_report", { syncId, format, path }),
     readFileContent: (filePath: string) => invoke<string>("read_file_content", { path: filePath }),
     resolveConflict: (conflict: Conflict, resolution: "overwrite" | "keep-remote") =>
       invoke<void>("resolve_conflict", {
@@ -97,12 +263,14 @@ export const api = {
     get: (key: string) => invoke<string | null>("get_setting", { key }),
     set: (key: string, value: string) => invoke<void>("set_setting", { key, value }),
     getAll: () => invoke<Record<string, string>>("get_all_settings"),
+    load: () => invoke<AppSettings>("get_app_settings"),
+    update: (patch: Partial<AppSettings>) => invoke<AppSettings>("update_app_settings", { patch }),
   },
 
   storage: {
     getMode: () => invoke<string>("get_storage_mode"),
     getInfo: () => invoke<Record<string, string>>("get_storage_info"),
-    migrateToFileStorage: () =>
+    migrateToFileStorage: (dualWriteDays?: number) =>
       invoke<{
         success: boolean;
         rules_migrated: number;
@@ -110,19 +278,15 @@ export const api = {
         errors: Array<{ rule_id: string; rule_name: string; error: string }>;
         backup_path?: string;
         storage_dir: string;
-      }>("migrate_to_file_storage"),
+      }>("migrate_to_file_storage", { dualWriteDays }),
     rollbackMigration: (backupPath: string) =>
       invoke<void>("rollback_file_migration", { backupPath }),
-    verifyMigration: () =>
-      invoke<{
-        is_valid: boolean;
-        db_rule_count: number;
-        file_rule_count: number;
-        missing_rules: string[];
-        extra_rules: string[];
-        mismatched_rules: string[];
-        load_errors: number;
-      }>("verify_file_migration"),
+    verifyMigration: () => invoke<StorageVerification>("verify_file_migration"),
+    getDualWriteStatus: () => invoke<DualWriteStatus>("get_dual_write_status"),
+    completeCutover: (force: boolean) =>
+      invoke<StorageVerification>("complete_file_storage_cutover", { force }),
+    rollbackDualWrite: () => invoke<number>("rollback_dual_write"),
+    rebuildFileIndex: () => invoke<FileIndexReport>("rebuild_file_index"),
     getMigrationProgress: () =>
       invoke<{
         total: number;
@@ -130,28 +294,38 @@ export const api = {
         current_rule?: string;
         status: "NotStarted" | "InProgress" | "Completed" | "Failed" | "RolledBack";
       }>("get_file_migration_progress"),
-    exportConfiguration: (path: string) => invoke<void>("export_configuration", { path }),
-    importConfiguration: (path: string, mode: "overwrite" | "skip") =>
-      invoke<void>("import_configuration", { path, mode }),
-    previewImport: (path: string) =>
+    exportConfiguration: (path: string, filter?: ExportFilter, format?: ExportFormat) =>
+      invoke<void>("export_configuration", { path, filter, format }),
+    importConfiguration: (path: string, mode: "overwrite" | "skip", filter?: ExportFilter) =>
+      invoke<void>("import_configuration", { path, mode, filter }),
+    previewImport: (path: string, filter?: ExportFilter) =>
       invoke<{
         version: string;
         exported_at: string;
         rules: Rule[];
         commands: CommandModel[];
         skills: Skill[];
-      }>("preview_import", { path }),
+        verification: ExportVerification;
+      }>("preview_import", { path, filter }),
+    getExportPublicKey: () => invoke<string>("get_export_public_key"),
   },
 
   commands: {
     getAll: () => invoke<CommandModel[]>("get_all_commands"),
+    list: (query: ArtifactListQuery) =>
+      invoke<ArtifactPage<CommandModel>>("list_commands", { query }),
     getById: (id: string) => invoke<CommandModel>("get_command_by_id", { id }),
     create: (input: CreateCommandInput) => invoke<CommandModel>("create_command", { input }),
     update: (id: string, input: UpdateCommandInput) =>
       invoke<CommandModel>("update_command", { id, input }),
     delete: (id: string) => invoke<void>("delete_command", { id }),
-    test: (id: string, args: Record<string, string>) =>
-      invoke<TestCommandResult>("test_command", { id, args }),
+    test: (id: string, args: Record<string, string>, executionId?: string, stdin?: string) =>
+      invoke<TestCommandResult>("test_command", { id, args, executionId, stdin }),
+    rerunExecution: (executionLogId: string, executionId?: string) =>
+      invoke<TestCommandResult>("rerun_execution", { executionLogId, executionId }),
+    preview: (id: string, args: Record<string, string>) =>
+      invoke<CommandPreview>("preview_command_execution", { id, args }),
+    cancelExecution: (executionId: string) => invoke<void>("cancel_execution", { executionId }),
     sync: () => invoke<SyncResult>("sync_commands"),
     getTemplates: () => invoke<TemplateCommand[]>("get_command_templates"),
     installTemplate: (templateId: string) =>
@@ -160,15 +334,27 @@ export const api = {
 
   skills: {
     getAll: () => invoke<Skill[]>("get_all_skills"),
+    list: (query: ArtifactListQuery) => invoke<ArtifactPage<Skill>>("list_skills", { query }),
     getById: (id: string) => invoke<Skill>("get_skill_by_id", { id }),
+    /** Heading problems and links to files missing from the skill's directory */
+    validateMarkdown: (id: string) => invoke<MarkdownIssue[]>("validate_skill_markdown", { id }),
     create: (input: CreateSkillInput) => invoke<Skill>("create_skill", { input }),
     update: (id: string, input: UpdateSkillInput) => invoke<Skill>("update_skill", { id, input }),
     delete: (id: string) => invoke<void>("delete_skill", { id }),
     sync: () => invoke<number>("sync_skills"),
     getSupportedAdapters: () => invoke<string[]>("get_skill_supported_adapters"),
-    getTemplates: () => invoke<TemplateSkill[]>("get_skill_templates"),
+    getTemplates: (query?: string, refresh?: boolean) =>
+      invoke<TemplateSkill[]>("get_skill_templates", { query, refresh }),
+    getTemplateInstalls: () => invoke<SkillTemplateInstall[]>("get_skill_template_installs"),
     installTemplate: (templateId: string) =>
       invoke<Skill>("install_skill_template", { templateId }),
+    getVersions: (id: string) => invoke<SkillVersion[]>("get_skill_versions", { id }),
+    rollback: (id: string, version: string) => invoke<Skill>("rollback_skill", { id, version }),
+    exportPack: (id: string, path: string) => invoke<string>("export_skill_pack", { id, path }),
+    scanPack: (path: string, options?: ImportExecutionOptions) =>
+      invoke<ImportScanResult>("scan_skill_pack_import", { path, options }),
+    importPack: (path: string, options?: ImportExecutionOptions) =>
+      invoke<ImportExecutionResult>("import_skill_pack", { path, options }),
   },
 
   mcp: {
@@ -178,6 +364,50 @@ export const api = {
     restart: () => invoke<void>("restart_mcp_server"),
     getInstructions: () => invoke<McpConnectionInstructions>("get_mcp_connection_instructions"),
     getLogs: (limit?: number) => invoke<string[]>("get_mcp_logs", { limit: limit ?? 50 }),
+    getPendingApprovals: () => invoke<PendingApproval[]>("get_pending_approvals"),
+    respondToApproval: (id: string, approved: boolean) =>
+      invoke<void>("respond_to_approval", { id, approved }),
+    getApprovalAudit: (limit?: number) =>
+      invoke<ApprovalAuditEntry[]>("get_approval_audit", { limit }),
+    listTokens: () => invoke<McpClientToken[]>("list_mcp_tokens"),
+    createToken: (name: string, readOnly: boolean) =>
+      invoke<CreatedMcpToken>("create_mcp_token", { name, readOnly }),
+    revokeToken: (name: string) => invoke<void>("revoke_mcp_token", { name }),
+  },
+
+  workflows: {
+    getAll: () => invoke<Workflow[]>("get_all_workflows"),
+    getById: (id: string) => invoke<Workflow>("get_workflow_by_id", { id }),
+    create: (input: CreateWorkflowInput) => invoke<Workflow>("create_workflow", { input }),
+    update: (id: string, input: UpdateWorkflowInput) =>
+      invoke<Workflow>("update_workflow", { id, input }),
+    delete: (id: string) => invoke<void>("delete_workflow", { id }),
+    run: (id: string, args: Record<string, string>, executionId?: string) =>
+      invoke<WorkflowRunResult>("run_workflow", { id, args, executionId }),
+  },
+
+  secrets: {
+    getAll: () => invoke<SecretInfo[]>("get_secrets"),
+    set: (name: string, value: string) => invoke<void>("set_secret", { name, value }),
+    delete: (name: string) => invoke<void>("delete_secret", { name }),
+    /** Secrets for MCP skills, kept in the OS keychain and passed once allowlisted */
+    setMcp: (name: string, value: string) => invoke<void>("set_mcp_secret", { name, value }),
+    deleteMcp: (name: string) => invoke<void>("delete_mcp_secret", { name }),
+    /** Likely secrets in rules and commands, minus allowlisted findings */
+    scanContent: () => invoke<SensitiveFinding[]>("scan_sensitive_content"),
+    allowFinding: (fingerprint: string) =>
+      invoke<void>("allow_sensitive_finding", { fingerprint }),
+    disallowFinding: (fingerprint: string) =>
+      invoke<void>("disallow_sensitive_finding", { fingerprint }),
+  },
+
+  webhooks: {
+    getAll: () => invoke<Webhook[]>("get_webhooks"),
+    create: (input: CreateWebhookInput) => invoke<Webhook>("create_webhook", { input }),
+    update: (id: string, input: UpdateWebhookInput) =>
+      invoke<Webhook>("update_webhook", { id, input }),
+    delete: (id: string) => invoke<void>("delete_webhook", { id }),
+    test: (id: string) => invoke<WebhookDelivery>("test_webhook", { id }),
   },
 
   execution: {
@@ -195,16 +425,23 @@ export const api = {
         limit: limit ?? 50,
         offset: offset ?? 0,
       }),
+    getArtifacts: (executionLogId: string) =>
+      invoke<ExecutionArtifact[]>("get_execution_artifacts", { executionLogId }),
   },
 
   slashCommands: {
-    remove: (commandName: string, adapters: string[], targetPaths: string[] = []) =>
+    remove: (
+      commandName: string,
+      adapters: string[],
+      targetPaths: string[] = [],
+      namespace?: string
+    ) =>
       invoke<{
         filesWritten: number;
         filesRemoved: number;
         errors: string[];
         conflicts: Array<{ commandName: string; adapterName: string; message: string }>;
-      }>("remove_slash_command_files", { commandName, adapters, targetPaths }),
+      }>("remove_slash_command_files", { commandName, namespace, adapters, targetPaths }),
     sync: (commandId: string, isGlobal: boolean) =>
       invoke<{
         filesWritten: number;
@@ -238,18 +475,36 @@ export const api = {
       >("get_slash_command_adapters"),
     testGeneration: (adapterName: string, commandId: string) =>
       invoke<string>("test_slash_command_generation", { adapterName, commandId }),
-    getPath: (adapterName: string, commandName: string, isGlobal: boolean) =>
-      invoke<string>("get_slash_command_path", { adapterName, commandName, isGlobal }),
+    getPath: (adapterName: string, commandName: string, isGlobal: boolean, namespace?: string) =>
+      invoke<string>("get_slash_command_path", { adapterName, commandName, namespace, isGlobal }),
   },
 
   app: {
     getAppDataPath: () => invoke<string>("get_app_data_path_cmd"),
     openInExplorer: (path: string) => invoke<void>("open_in_explorer", { path }),
+    runDoctor: () => invoke<DoctorReport>("run_doctor"),
     getVersion: () => invoke<string>("get_app_version"),
+    queryLogs: (query?: AppLogQuery) => invoke<AppLogPage>("query_app_logs", { query }),
+    queryAuditLog: (query?: AuditLogQuery) => invoke<AuditLogPage>("query_audit_log", { query }),
+    getPendingJobs: () => invoke<JobRecord[]>("get_pending_jobs"),
+    resolvePendingJob: (id: string, resolution: JobResolution) =>
+      invoke<void>("resolve_pending_job", { id, resolution }),
+    getMetrics: () => invoke<MetricsSnapshot>("get_metrics"),
+  },
+
+  workspaces: {
+    list: () => invoke<WorkspaceList>("list_workspaces"),
+    create: (name: string) => invoke<Workspace>("create_workspace", { name }),
+    rename: (id: string, name: string) => invoke<Workspace>("rename_workspace", { id, name }),
+    delete: (id: string) => invoke<void>("delete_workspace", { id }),
+    /** Restarts the app into the workspace */
+    switch: (id: string) => invoke<void>("switch_workspace", { id }),
   },
 
   registry: {
     getTools: () => invoke<ToolEntry[]>("get_tool_registry"),
+    detectInstalledTools: (refresh?: boolean) =>
+      invoke<InstalledTools>("detect_installed_tools", { refresh }),
   },
 
   status: {
@@ -262,5 +517,36 @@ export const api = {
       invoke<RepairResult[]>("repair_all_artifacts", { filter }),
     refresh: (filter?: StatusFilter) =>
       invoke<ArtifactStatusEntry[]>("refresh_artifact_status", { filter }),
+    getSkillSyncStatus: (skillId?: string) =>
+      invoke<SkillSyncStatusEntry[]>("get_skill_sync_status", { skillId }),
+    getUnified: () => invoke<UnifiedStatus>("get_unified_status"),
+  },
+
+  reconciliation: {
+    reconcile: (dryRun: boolean, filter?: ReconcileFilter) =>
+      invoke<ReconcileResult>("reconcile_all", { dryRun, filter }),
+    preview: (filter?: ReconcileFilter) => invoke<ReconcilePlan>("reconcile_preview", { filter }),
+    reconcileDrift: () => invoke<ReconcileResult>("reconcile_drift"),
+    getStartupReport: () => invoke<PreparedReconcilePlan | null>("get_startup_drift_report"),
+    getPlan: (filter?: ReconcileFilter) =>
+      invoke<PreparedReconcilePlan>("get_reconcile_plan", { filter }),
+    applyPlan: (planId: string, selections: ReconcilePlanSelection) =>
+      invoke<ReconcileResult>("apply_reconcile_plan", { planId, selections }),
+    getMissingRepoRoots: () => invoke<string[]>("get_missing_repo_roots"),
+    removeMissingRepoRoots: (roots: string[]) =>
+      invoke<string[]>("remove_missing_repo_roots", { roots }),
+    installGitHooks: (repoRoot: string, regenerate: boolean) =>
+      invoke<GitHookStatus>("install_git_hooks", { repoRoot, regenerate }),
+    uninstallGitHooks: (repoRoot: string) =>
+      invoke<GitHookStatus>("uninstall_git_hooks", { repoRoot }),
+    getGitHookStatus: (repoRoot: string) =>
+      invoke<GitHookStatus>("get_git_hook_status", { repoRoot }),
+    getProjectConfig: (repoRoot: string) =>
+      invoke<ProjectConfig | null>("get_project_config", { repoRoot }),
+    adoptOrphans: (paths: string[]) =>
+      invoke<ImportExecutionResult>("adopt_orphaned_artifacts", { paths }),
+    getProtectedPaths: () => invoke<string[]>("get_reconcile_protected_paths"),
+    setProtectedPaths: (patterns: string[]) =>
+      invoke<string[]>("set_reconcile_protected_paths", { patterns }),
   },
 };
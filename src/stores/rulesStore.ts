@@ -1,5 +1,6 @@
 import { create } from "zustand";
 import type { Rule, CreateRuleInput, UpdateRuleInput } from "@/types/rule";
+import type { ChangeEvent } from "@/types/status";
 import { api } from "@/lib/tauri";
 import { generateDuplicateName } from "@/lib/utils";
 
@@ -11,6 +12,8 @@ interface RulesState {
   recentlyDeleted: Rule | null;
 
   fetchRules: () => Promise<void>;
+  /** Applies a `rule-*` change event to the loaded rules */
+  applyChange: (event: ChangeEvent) => Promise<void>;
   createRule: (input: CreateRuleInput) => Promise<Rule>;
   updateRule: (id: string, input: UpdateRuleInput) => Promise<Rule>;
   deleteRule: (id: string) => Promise<void>;
@@ -44,6 +47,27 @@ export const useRulesStore = create<RulesState>((set, get) => ({
     }
   },
 
+  applyChange: async (event: ChangeEvent) => {
+    if (event.change === "deleted") {
+      set((state) => ({
+        rules: state.rules.filter((r) => r.id !== event.id),
+        selectedRule: state.selectedRule?.id === event.id ? null : state.selectedRule,
+      }));
+      return;
+    }
+    try {
+      const rule = await api.rules.getById(event.id);
+      set((state) => ({
+        rules: state.rules.some((r) => r.id === rule.id)
+          ? state.rules.map((r) => (r.id === rule.id ? rule : r))
+          : [...state.rules, rule],
+        selectedRule: state.selectedRule?.id === rule.id ? rule : state.selectedRule,
+      }));
+    } catch {
+      // Deleted again before it could be loaded; its deleted event follows
+    }
+  },
+
   createRule: async (input: CreateRuleInput) => {
     set({ isLoading: true, error: null });
     try {
@@ -1,8 +1,66 @@
+import type { AdapterType, Scope } from "./rule";
+
+export type CommandShell = "bash" | "zsh" | "pwsh" | "cmd";
+
+export type ArgumentType = "string" | "number" | "boolean" | "enum" | "path";
+
 export interface CommandArgument {
   name: string;
   description: string;
+  argType?: ArgumentType;
   required: boolean;
   defaultValue?: string;
+  /** Allowed values for enum arguments */
+  options?: string[];
+  /** Regular expression the whole value must match */
+  pattern?: string;
+  /** Inclusive bounds for number arguments */
+  min?: number;
+  max?: number;
+}
+
+/** Limits on what a command's process may do; a run fails if a restriction can't be enforced */
+export interface SandboxProfile {
+  /** Start from a minimal environment instead of inheriting the app's */
+  scrubEnv?: boolean;
+  /** Absolute paths the command may read but not modify */
+  readOnlyPaths?: string[];
+  denyNetwork?: boolean;
+  cpuTimeSecs?: number;
+  memoryMb?: number;
+}
+
+/**
+ * How a run's output is turned into structured results. `junit` reads the report at
+ * `reportPath` (relative to the working directory) or stdout; `regex` collects the named
+ * capture groups of every match in stdout.
+ */
+export type OutputParser =
+  | { type: "text" }
+  | { type: "json" }
+  | { type: "junit"; reportPath?: string }
+  | { type: "regex"; pattern: string };
+
+/** Slash command frontmatter, emitted in each tool's own syntax where it is supported */
+export interface SlashCommandFrontmatter {
+  /** Derived from the arguments when unset, e.g. `<file> [message]` */
+  argumentHint?: string;
+  /** Tools the command may use without asking, e.g. `Bash(git add:*)` */
+  allowedTools?: string[];
+  model?: string;
+}
+
+export type McpAccess = "hidden" | "open" | "approval_required";
+
+/** Which MCP clients can see and call a command */
+export interface McpPolicy {
+  access: McpAccess;
+  /** Offered to read-only client tokens */
+  readOnly: boolean;
+  /** Only the app's own token may call it, never a named client token */
+  localOnly: boolean;
+  /** Named client tokens allowed to call it; empty allows every token */
+  tokens: string[];
 }
 
 export interface CommandModel {
@@ -11,14 +69,30 @@ export interface CommandModel {
   description: string;
   script: string;
   arguments: CommandArgument[];
-  exposeViaMcp: boolean;
+  mcpPolicy: McpPolicy;
   isPlaceholder: boolean;
   generateSlashCommands?: boolean;
   slashCommandAdapters?: string[];
+  /** Folder-style namespace such as `git` or `git/review`; invoked as `/git/commit` */
+  slashNamespace?: string;
+  slashFrontmatter?: SlashCommandFrontmatter;
+  /** `local` syncs into `targetPaths`, or every registered repository root when empty */
+  slashScope?: Scope;
   targetPaths?: string[];
   basePath?: string | null;
   timeoutMs?: number;
   maxRetries?: number;
+  requiredSecrets?: string[];
+  workingDir?: string;
+  shell?: CommandShell;
+  /** Cron expression (local time); the command runs in the background on this schedule */
+  schedule?: string;
+  sandbox?: SandboxProfile;
+  /** Globs, relative to the working directory, of files kept after each run */
+  artifactGlobs?: string[];
+  outputParser?: OutputParser;
+  /** Callers may pass an input payload that is piped to the script's stdin */
+  acceptsStdin?: boolean;
   createdAt: number;
   updatedAt: number;
 }
@@ -30,11 +104,22 @@ export interface CreateCommandInput {
   script: string;
   isPlaceholder: boolean;
   arguments?: CommandArgument[];
-  exposeViaMcp?: boolean;
+  mcpPolicy?: McpPolicy;
+  slashNamespace?: string;
+  slashFrontmatter?: SlashCommandFrontmatter;
+  slashScope?: Scope;
   targetPaths?: string[];
   basePath?: string | null;
   timeoutMs?: number;
   maxRetries?: number;
+  requiredSecrets?: string[];
+  workingDir?: string;
+  shell?: CommandShell;
+  schedule?: string;
+  sandbox?: SandboxProfile;
+  artifactGlobs?: string[];
+  outputParser?: OutputParser;
+  acceptsStdin?: boolean;
 }
 
 export interface UpdateCommandInput {
@@ -43,16 +128,99 @@ export interface UpdateCommandInput {
   script?: string;
   isPlaceholder?: boolean;
   arguments?: CommandArgument[];
-  exposeViaMcp?: boolean;
+  mcpPolicy?: McpPolicy;
   generateSlashCommands?: boolean;
   slashCommandAdapters?: string[];
+  /** An empty string removes the namespace */
+  slashNamespace?: string;
+  slashFrontmatter?: SlashCommandFrontmatter;
+  slashScope?: Scope;
   targetPaths?: string[];
   basePath?: string | null;
   timeoutMs?: number;
   maxRetries?: number;
+  requiredSecrets?: string[];
+  workingDir?: string;
+  shell?: CommandShell;
+  /** An empty string removes the schedule */
+  schedule?: string;
+  /** A profile with no restrictions removes the sandbox */
+  sandbox?: SandboxProfile;
+  artifactGlobs?: string[];
+  /** The `text` parser removes the output parser */
+  outputParser?: OutputParser;
+  acceptsStdin?: boolean;
+}
+
+export interface PendingApproval {
+  id: string;
+  commandId: string;
+  commandName: string;
+  script: string;
+  argumentsJson: string;
+  requestedAt: number;
+}
+
+export type ApprovalDecision = "approved" | "denied" | "timed_out";
+
+export interface ApprovalAuditEntry {
+  id: string;
+  commandId: string;
+  commandName: string;
+  argumentsJson: string;
+  decision: ApprovalDecision;
+  requestedAt: number;
+  decidedAt: number;
+}
+
+export interface SecretInfo {
+  name: string;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export type WebhookEvent =
+  | "sync.completed"
+  | "conflict.detected"
+  | "reconcile.applied"
+  | "command.executed"
+  | "webhook.test";
+
+export interface Webhook {
+  id: string;
+  url: string;
+  events: WebhookEvent[];
+  /** Vault secret whose value signs each payload; unsigned when null. */
+  secretName: string | null;
+  enabled: boolean;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface CreateWebhookInput {
+  url: string;
+  events: WebhookEvent[];
+  secretName?: string | null;
+  enabled?: boolean;
+}
+
+export interface UpdateWebhookInput {
+  url?: string;
+  events?: WebhookEvent[];
+  /** An empty name stops signing. */
+  secretName?: string;
+  enabled?: boolean;
+}
+
+export interface WebhookDelivery {
+  deliveryId: string;
+  status: number | null;
+  attempts: number;
+  error: string | null;
 }
 
 export interface TestCommandResult {
+  executionId: string;
   success: boolean;
   stdout: string;
   stderr: string;
@@ -60,6 +228,31 @@ export interface TestCommandResult {
   durationMs: number;
 }
 
+/** What running a command would do, resolved without executing it */
+export interface CommandPreview {
+  commandId: string;
+  commandName: string;
+  script: string;
+  program: string;
+  args: string[];
+  commandLine: string;
+  /** Argument values passed as environment variables */
+  env: Record<string, string>;
+  /** Vault secrets that would be injected; values are never included */
+  secrets: string[];
+  workingDir?: string;
+  timeoutMs: number;
+  sandbox?: SandboxProfile;
+  requiresApproval: boolean;
+}
+
+export interface CommandOutputEvent {
+  executionId: string;
+  commandId: string;
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
 export interface McpStatus {
   running: boolean;
   port: number;
@@ -68,6 +261,18 @@ export interface McpStatus {
   isWatching: boolean;
 }
 
+/** A named token for another MCP client; the token itself is only shown once */
+export interface McpClientToken {
+  name: string;
+  sha256: string;
+  readOnly: boolean;
+  createdAt: number;
+}
+
+export interface CreatedMcpToken extends McpClientToken {
+  token: string;
+}
+
 export interface McpConnectionInstructions {
   claudeCodeJson: string;
   opencodeJson: string;
@@ -75,6 +280,44 @@ export interface McpConnectionInstructions {
   apiToken: string;
 }
 
+export type McpTransport = "stdio" | "http";
+
+export interface McpServerSpec {
+  transport: McpTransport;
+  command?: string;
+  args: string[];
+  env: Record<string, string>;
+  url?: string;
+  headers: Record<string, string>;
+}
+
+export interface ExternalMcpServer extends McpServerSpec {
+  id: string;
+  name: string;
+  enabledAdapters: AdapterType[];
+  enabled: boolean;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface McpServerCandidate extends McpServerSpec {
+  id: string;
+  name: string;
+  sourceTool: AdapterType;
+  sourcePath: string;
+  existingId?: string;
+}
+
+export interface McpServerScanResult {
+  candidates: McpServerCandidate[];
+  errors: string[];
+}
+
+export interface McpServerSyncResult {
+  filesWritten: string[];
+  errors: string[];
+}
+
 export interface ExecutionLog {
   id: string;
   commandId: string;
@@ -90,6 +333,32 @@ export interface ExecutionLog {
   adapterContext?: string;
   isRedacted?: boolean;
   attemptNumber?: number;
+  /** Directory holding the files captured by the command's artifact globs */
+  artifactsDir?: string;
+  /** Results extracted by the command's output parser */
+  structuredOutput?: unknown;
+  /** What the run saw: environment, tool versions and source revision */
+  environment?: EnvironmentSnapshot;
+}
+
+export interface EnvironmentSnapshot {
+  /** Variables passed to the process; secrets are left out and sensitive values redacted */
+  env: Record<string, string>;
+  /** First line of `--version` output for the known tools the script calls */
+  toolVersions: Record<string, string>;
+  workingDir?: string;
+  gitCommit?: string;
+  gitDirty: boolean;
+  appVersion: string;
+  os: string;
+}
+
+export interface ExecutionArtifact {
+  /** Path relative to the command's working directory */
+  name: string;
+  /** Where the copy is stored */
+  path: string;
+  sizeBytes: number;
 }
 export interface TemplateCommand {
   templateId: string;
@@ -1,6 +1,7 @@
 export type Scope = "global" | "local";
-import { CommandModel } from "./command";
+import { CommandArgument, CommandModel } from "./command";
 import { Skill } from "./skill";
+import type { AuditActor } from "./status";
 
 export type AdapterType =
   | "antigravity"
@@ -48,11 +49,28 @@ export interface UpdateRuleInput {
   enabled?: boolean;
 }
 
+/**
+ * One change applied to many rules at once. `enabledAdapters` replaces the adapters, then
+ * `addAdapters` and `removeAdapters` apply; `metadata` is merged and a `null` value removes
+ * that key.
+ */
+export interface BulkRuleChanges {
+  enabled?: boolean;
+  enabledAdapters?: AdapterType[];
+  addAdapters?: AdapterType[];
+  removeAdapters?: AdapterType[];
+  metadata?: Record<string, unknown>;
+}
+
 export interface SyncResult {
   success: boolean;
   filesWritten: string[];
   errors: SyncError[];
   conflicts: Conflict[];
+  /** Tools files were written for that are not installed on this machine */
+  uninstalledTools?: AdapterType[];
+  /** Tools none of whose files were changed because one of them failed to write */
+  rolledBackAdapters?: AdapterType[];
 }
 
 export interface SyncError {
@@ -76,9 +94,49 @@ export interface SyncHistoryEntry {
   filesWritten: number;
   status: "success" | "partial" | "failed";
   triggeredBy: "manual" | "auto";
+  hasReport: boolean;
 }
 
-export type ImportSourceType = "ai_tool" | "file" | "directory" | "url" | "clipboard";
+export interface SyncReportFile {
+  path: string;
+  hash: string;
+  ruleCount: number;
+  bytes: number;
+  durationMs: number;
+}
+
+export interface AdapterSyncReport {
+  adapter: AdapterType;
+  adapterName: string;
+  durationMs: number;
+  files: SyncReportFile[];
+  skippedReason?: string;
+  errors: SyncError[];
+  /** Whether the tool was detected on this machine; absent in older reports */
+  installed?: boolean;
+  /** A file failed to write, so none of the tool's files were changed */
+  rolledBack?: boolean;
+}
+
+export interface SyncReport {
+  id: string;
+  timestamp: number;
+  triggeredBy: "manual" | "auto";
+  status: "success" | "partial" | "failed";
+  durationMs: number;
+  adapters: AdapterSyncReport[];
+}
+
+export type SyncReportFormat = "json" | "markdown";
+
+export type ImportSourceType =
+  | "ai_tool"
+  | "file"
+  | "directory"
+  | "url"
+  | "clipboard"
+  | "archive"
+  | "peer";
 export type ImportArtifactType = "rule" | "command" | "skill" | "other" | "unknown";
 export type ImportConflictMode = "skip" | "rename" | "replace";
 
@@ -98,6 +156,10 @@ export interface ImportCandidate {
   contentHash: string;
   fileSize: number;
   metadata?: string;
+  allowedTools?: string[];
+  license?: string | null;
+  /** Enabled adapters whose tool is not installed on this machine */
+  uninstalledAdapters?: AdapterType[];
 }
 
 export interface ImportScanResult {
@@ -111,6 +173,7 @@ export interface ImportExecutionOptions {
   defaultAdapters?: AdapterType[];
   selectedCandidateIds?: string[];
   maxFileSizeBytes?: number;
+  allowNearDuplicates?: boolean;
 }
 
 export interface ImportConflict {
@@ -119,6 +182,7 @@ export interface ImportConflict {
   existingRuleId?: string;
   existingRuleName?: string;
   reason: string;
+  similarity?: number;
 }
 
 export interface ImportSkip {
@@ -147,6 +211,144 @@ export interface ImportHistoryEntry {
   errorCount: number;
 }
 
+export interface PeerShareInfo {
+  addresses: string[];
+  port: number;
+  code: string;
+  expiresAt: number;
+}
+
+export interface ShareLinkSelection {
+  tags?: string[];
+  collectionIds?: string[];
+  includeRules?: boolean;
+  includeCommands?: boolean;
+}
+
+export interface ShareLinkInfo {
+  urls: string[];
+  port: number;
+  ruleCount: number;
+  commandCount: number;
+  expiresAt: number;
+}
+
+export type TeamRepoPushMode = "commit" | "branch";
+
+export interface TeamRepoFile {
+  artifactType: ImportArtifactType;
+  artifactId: string;
+  contentHash: string;
+}
+
+export interface TeamRepo {
+  url: string;
+  branch: string;
+  subpath?: string;
+  pushMode: TeamRepoPushMode;
+  intervalHours: number;
+  enabled: boolean;
+  options: ImportExecutionOptions;
+  lastCommit?: string;
+  lastPulledAt?: number;
+  lastPushedAt?: number;
+  lastError?: string;
+  files: Record<string, TeamRepoFile>;
+  createdAt: number;
+}
+
+export interface ConfigureTeamRepoInput {
+  url: string;
+  branch?: string;
+  subpath?: string;
+  pushMode?: TeamRepoPushMode;
+  intervalHours?: number;
+  options?: ImportExecutionOptions;
+}
+
+export interface TeamRepoChange {
+  path: string;
+  artifactType: ImportArtifactType;
+  artifactId: string;
+  name: string;
+}
+
+export interface TeamRepoPullResult {
+  commit?: string;
+  newCount: number;
+  changedCount: number;
+  unchangedCount: number;
+  localChanges: TeamRepoChange[];
+  conflicts: TeamRepoChange[];
+  import?: ImportExecutionResult;
+  errors: string[];
+}
+
+export interface TeamRepoPushInput {
+  ruleIds?: string[];
+  commandIds?: string[];
+  skillIds?: string[];
+  message: string;
+}
+
+export interface TeamRepoPushResult {
+  commit: string;
+  branch: string;
+  files: string[];
+  pullRequestUrl?: string;
+}
+
+export type CloudSyncBackend =
+  | { kind: "webdav"; url: string; username?: string; passwordSecret?: string }
+  | {
+      kind: "s3";
+      endpoint?: string;
+      region: string;
+      bucket: string;
+      key: string;
+      accessKeyId: string;
+      secretAccessKeySecret: string;
+    }
+  | { kind: "dropbox"; path: string; tokenSecret: string };
+
+export interface CloudSyncFingerprint {
+  local: string;
+  remote: string;
+}
+
+export interface CloudSync {
+  backend: CloudSyncBackend;
+  deviceId: string;
+  intervalMinutes: number;
+  enabled: boolean;
+  lastSyncedAt?: number;
+  lastError?: string;
+  base: Record<string, CloudSyncFingerprint>;
+  createdAt: number;
+}
+
+export interface ConfigureCloudSyncInput {
+  backend: CloudSyncBackend;
+  intervalMinutes?: number;
+}
+
+export interface CloudSyncResult {
+  pulled: string[];
+  deleted: string[];
+  pushed: string[];
+  conflicts: string[];
+  uploaded: boolean;
+  revision: number;
+  errors: string[];
+}
+
+export type PolicyOverride = "target_paths" | "adapters" | "metadata";
+
+export interface OrgPolicy {
+  sources: string[];
+  allowedOverrides: PolicyOverride[];
+}
+
 export interface ToolCapabilities {
   supportsRules: boolean;
   supportsCommandStubs: boolean;
@@ -169,6 +371,23 @@ export interface ToolEntry {
   capabilities: ToolCapabilities;
   paths: PathTemplates;
   fileFormat: string;
+  /** Whether the tool was detected on this machine */
+  installed?: boolean;
+}
+
+export interface ToolInstallation {
+  adapter: AdapterType;
+  installed: boolean;
+  /** Config directories of the tool that exist */
+  configDirs: string[];
+  /** Executable found on PATH */
+  binary?: string | null;
+}
+
+export interface InstalledTools {
+  tools: ToolInstallation[];
+  /** Unix seconds */
+  checkedAt: number;
 }
 
 export interface TemplateRule {
@@ -176,3 +395,156 @@ export interface TemplateRule {
   theme: string;
   metadata: CreateRuleInput;
 }
+
+/** A user-defined rule skeleton whose `{{placeholder}}` tokens are filled in on creation */
+export interface RuleTemplate {
+  id: string;
+  name: string;
+  description: string;
+  placeholders: CommandArgument[];
+  /** The rule before placeholders are filled in; its id is ignored */
+  rule: CreateRuleInput;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface CreateRuleTemplateInput {
+  name: string;
+  description?: string;
+  placeholders?: CommandArgument[];
+  rule: CreateRuleInput;
+}
+
+export interface UpdateRuleTemplateInput {
+  name?: string;
+  description?: string;
+  placeholders?: CommandArgument[];
+  rule?: CreateRuleInput;
+}
+
+/** A shared block of text that rules include with `{{snippet:name}}` */
+export interface Snippet {
+  id: string;
+  /** Letters, digits, '-', '_' and '.' */
+  name: string;
+  description: string;
+  content: string;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface CreateSnippetInput {
+  name: string;
+  description?: string;
+  content: string;
+}
+
+export interface UpdateSnippetInput {
+  name?: string;
+  description?: string;
+  content?: string;
+}
+
+export type RuleOrderIssueKind = "unknownRule" | "disabledDependency" | "missingFromFile" | "cycle";
+
+/** A problem with the `after` and `requires` metadata of a rule */
+export interface RuleOrderIssue {
+  kind: RuleOrderIssueKind;
+  ruleId: string;
+  ruleName: string;
+  message: string;
+}
+
+export type RuleFeedbackVerdict = "helpful" | "ignored" | "harmful";
+
+export interface RuleFeedbackInput {
+  /** Rule id, or the rule's name */
+  ruleId: string;
+  verdict: RuleFeedbackVerdict;
+  sessionId?: string | null;
+  note?: string | null;
+}
+
+export interface RuleFeedback {
+  id: string;
+  ruleId: string;
+  verdict: RuleFeedbackVerdict;
+  sessionId?: string | null;
+  note?: string | null;
+  /** The app, the CLI or an MCP client */
+  reporter: AuditActor;
+  createdAt: number;
+}
+
+export interface RuleFeedbackSummary {
+  helpful: number;
+  ignored: number;
+  harmful: number;
+  lastReportedAt?: number | null;
+}
+
+export interface RuleFileStat {
+  adapter: AdapterType;
+  filePath: string;
+  bytes: number;
+  lastSyncedAt: number;
+}
+
+/** Usage summary for a rule */
+export interface RuleStats {
+  ruleId: string;
+  ruleName: string;
+  enabled: boolean;
+  lastSyncedAt?: number | null;
+  adapters: AdapterType[];
+  totalBytes: number;
+  files: RuleFileStat[];
+  feedback: RuleFeedbackSummary;
+}
+
+/** Feedback sorts put the most reports of that verdict first */
+export type RuleStatsSort = "name" | "helpful" | "ignored" | "harmful";
+
+export type MarkdownIssueKind = "skippedHeadingLevel" | "emptyHeading" | "brokenLink";
+
+/** A structural problem found when parsing rule or skill Markdown */
+export interface MarkdownIssue {
+  kind: MarkdownIssueKind;
+  /** 1-based */
+  line: number;
+  message: string;
+}
+
+export type DiffLineKind = "context" | "added" | "removed";
+
+export interface DiffLine {
+  kind: DiffLineKind;
+  text: string;
+}
+
+/** Paired removals and additions count as changed lines */
+export interface DiffSummary {
+  added: number;
+  removed: number;
+  changed: number;
+}
+
+export type AiTask = "draft" | "condense" | "merge";
+
+/** Text suggested by the configured model for review; nothing has been saved */
+export interface AiRuleProposal {
+  task: AiTask;
+  /** Rules the proposal rewrites or merges; empty for drafts */
+  sourceRuleIds: string[];
+  name: string;
+  /** Current text; the merged rules' contents joined by blank lines for merges */
+  original: string;
+  proposed: string;
+  diff: DiffLine[];
+  summary: DiffSummary;
+}
+
+export interface SnippetUsage {
+  ruleId: string;
+  ruleName: string;
+}
@@ -33,6 +33,15 @@ export interface Skill {
   /** Repository roots for local-scope syncing. */
   targetPaths: string[];
   basePath?: string | null;
+  /** Files under scripts/ and resources/ copied next to SKILL.md on sync. */
+  bundleFiles: string[];
+  /** Tools the skill may use without asking (`allowed-tools` in SKILL.md). */
+  allowedTools: string[];
+  license?: string | null;
+  /** Semantic version of the current content. */
+  version: string;
+  /** Earlier versions synced instead of the current one for some adapters or repos. */
+  versionPins: SkillVersionPin[];
   createdAt: number;
   updatedAt: number;
 }
@@ -50,6 +59,10 @@ export interface CreateSkillInput {
   targetAdapters?: string[];
   targetPaths?: string[];
   basePath?: string | null;
+  bundleFiles?: string[];
+  allowedTools?: string[];
+  license?: string | null;
+  version?: string;
 }
 
 export interface UpdateSkillInput {
@@ -64,6 +77,37 @@ export interface UpdateSkillInput {
   targetAdapters?: string[];
   targetPaths?: string[];
   basePath?: string | null;
+  bundleFiles?: string[];
+  allowedTools?: string[];
+  license?: string | null;
+  /** Version for this change; omitted bumps the patch version when content changes. */
+  version?: string;
+  changelog?: string;
+  versionPins?: SkillVersionPin[];
+}
+
+export interface SkillVersionPin {
+  adapter?: string | null;
+  repoPath?: string | null;
+  version: string;
+}
+
+export interface SkillVersionContent {
+  name: string;
+  description: string;
+  instructions: string;
+  inputSchema: SkillParameter[];
+  entryPoint: string;
+  allowedTools: string[];
+  license?: string | null;
+}
+
+export interface SkillVersion {
+  skillId: string;
+  version: string;
+  changelog: string;
+  content: SkillVersionContent;
+  createdAt: number;
 }
 
 export interface TemplateFile {
@@ -71,9 +115,25 @@ export interface TemplateFile {
   content: string;
 }
 
+export interface TemplateAsset {
+  path: string;
+  url: string;
+}
+
+export type TemplateSource = "bundled" | "remote";
+
 export interface TemplateSkill {
   templateId: string;
   theme: string;
   metadata: CreateSkillInput;
   files: TemplateFile[];
+  assets: TemplateAsset[];
+  source: TemplateSource;
+}
+
+export interface SkillTemplateInstall {
+  templateId: string;
+  indexUrl: string;
+  version?: string;
+  installedAt: number;
 }
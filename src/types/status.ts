@@ -1,4 +1,5 @@
-import type { AdapterType, Scope } from "./rule";
+import type { McpStatus } from "./command";
+import type { AdapterType, Conflict, Scope, SyncHistoryEntry } from "./rule";
 
 export type ArtifactType = "rule" | "command_stub" | "slash_command" | "skill";
 
@@ -8,6 +9,7 @@ export type ArtifactSyncStatus =
   | "missing"
   | "conflicted"
   | "unsupported"
+  | "mcp_fallback"
   | "error";
 
 export interface ArtifactStatusEntry {
@@ -25,6 +27,17 @@ export interface ArtifactStatusEntry {
   detail?: string;
 }
 
+export interface SkillSyncStatusEntry {
+  skillId: string;
+  skillName: string;
+  adapter: AdapterType;
+  scope: Scope;
+  repoRoot?: string;
+  status: ArtifactSyncStatus;
+  path?: string;
+  detail?: string;
+}
+
 export interface StatusFilter {
   artifactType?: ArtifactType;
   adapter?: AdapterType;
@@ -40,6 +53,434 @@ export interface RepairResult {
   updatedEntry?: ArtifactStatusEntry;
 }
 
+/** Artifacts found out of sync by the background drift monitor. */
+export interface DriftSummary {
+  missing: number;
+  modified: number;
+  stale: number;
+}
+
+export interface ResolvedArtifact {
+  path: string;
+  adapter: AdapterType;
+  artifactType: ArtifactType;
+  scope: Scope;
+  repoRoot?: string;
+  contentHash: string;
+  currentHash?: string;
+}
+
+export interface FoundArtifact {
+  path: string;
+  adapter?: AdapterType;
+  artifactType?: ArtifactType;
+  scope?: Scope;
+  contentHash: string;
+}
+
+export interface ReconcilePlan {
+  toCreate: ResolvedArtifact[];
+  toUpdate: ResolvedArtifact[];
+  toRemove: FoundArtifact[];
+  unchanged: string[];
+  warnings: string[];
+}
+
+/** Narrows a reconciliation; empty lists mean all, and `repoRoot` limits it to one repository. */
+export interface ReconcileFilter {
+  artifactTypes?: ArtifactType[];
+  adapters?: AdapterType[];
+  repoRoot?: string;
+}
+
+/** Result of comparing the rules in SQLite with the rule files. */
+export interface StorageVerification {
+  is_valid: boolean;
+  db_rule_count: number;
+  file_rule_count: number;
+  missing_rules: string[];
+  extra_rules: string[];
+  mismatched_rules: string[];
+  load_errors: number;
+}
+
+/** Progress of a migration that keeps SQLite and file storage in sync before cutting over. */
+export interface DualWriteStatus {
+  active: boolean;
+  startedAt: string | null;
+  cutoverAfter: string | null;
+  readyForCutover: boolean;
+  divergence: StorageVerification | null;
+}
+
+export type FileIndexIssueKind =
+  | "unreadableFile"
+  | "duplicateRuleId"
+  | "unknownRule"
+  | "missingRuleFile"
+  | "modifiedSyncedFile";
+
+export interface FileIndexIssue {
+  kind: FileIndexIssueKind;
+  ruleId: string | null;
+  filePath: string | null;
+  message: string;
+}
+
+/** What rebuilding the rule file index and sync hashes changed and could not fix. */
+export interface FileIndexReport {
+  ruleFilesScanned: number;
+  indexEntriesAdded: number;
+  indexEntriesUpdated: number;
+  indexEntriesRemoved: number;
+  syncHashes: {
+    filesChecked: number;
+    repaired: string[];
+    removed: string[];
+    modified: string[];
+  };
+  issues: FileIndexIssue[];
+}
+
+export type DoctorStatus = "ok" | "warning" | "error" | "skipped";
+
+export interface DoctorFinding {
+  check: "database" | "tools" | "adapterPaths" | "mcpPort" | "watcher";
+  status: DoctorStatus;
+  message: string;
+  fix: string | null;
+}
+
+/** Environment diagnostics, as printed by `ruleweaver doctor --json`. */
+export interface DoctorReport {
+  findings: DoctorFinding[];
+  errors: number;
+  warnings: number;
+}
+
+export type AppLogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+/** A backend log line captured for the diagnostics console. */
+export interface AppLogEntry {
+  seq: number;
+  /** Unix milliseconds */
+  timestamp: number;
+  level: AppLogLevel;
+  module: string;
+  message: string;
+}
+
+export interface AppLogQuery {
+  /** Least severe level to include */
+  level?: AppLogLevel;
+  /** Module prefix, e.g. `ruleweaver_lib::mcp` */
+  module?: string;
+  search?: string;
+  since?: number;
+  beforeSeq?: number;
+  offset?: number;
+  limit?: number;
+}
+
+export interface AppLogPage {
+  /** Newest first */
+  entries: AppLogEntry[];
+  total: number;
+}
+
+export type AuditActorKind = "ui" | "mcp" | "watcher" | "cli" | "background";
+
+export type AuditAction =
+  | "create"
+  | "update"
+  | "delete"
+  | "toggle"
+  | "import"
+  | "sync"
+  | "reconcile";
+
+export type AuditTargetType = "rule" | "command" | "skill" | "workflow";
+
+export interface AuditActor {
+  kind: AuditActorKind;
+  /** MCP client name or background job, when known */
+  detail?: string | null;
+}
+
+/** One change in the activity timeline. */
+export interface AuditLogEntry {
+  id: string;
+  /** Unix seconds */
+  timestamp: number;
+  actor: AuditActor;
+  action: AuditAction;
+  /** Absent for sync, import and reconcile runs */
+  target?: { type: AuditTargetType; id: string; name: string } | null;
+  summary: string;
+}
+
+export interface AuditLogQuery {
+  actor?: AuditActorKind;
+  action?: AuditAction;
+  targetType?: AuditTargetType;
+  targetId?: string;
+  search?: string;
+  /** Unix seconds, inclusive */
+  since?: number;
+  /** Unix seconds, exclusive */
+  until?: number;
+  offset?: number;
+  limit?: number;
+}
+
+export interface AuditLogPage {
+  /** Newest first */
+  entries: AuditLogEntry[];
+  total: number;
+}
+
+/** Payload of `<artifact>-<change>` events such as `rule-updated` */
+export interface ChangeEvent {
+  /** Bumped when the payload changes incompatibly */
+  version: 1;
+  artifact: "rule" | "command" | "skill" | "workflow";
+  change: "created" | "updated" | "deleted";
+  id: string;
+}
+
+export type ArtifactSort = "name" | "createdAt" | "updatedAt";
+
+/** Page with either `offset` or the `nextCursor` of the previous page */
+export interface ArtifactListQuery {
+  /** Case-insensitive, in the name or description */
+  search?: string;
+  /** Items with any of these in their `tags` metadata */
+  tags?: string[];
+  sortBy?: ArtifactSort;
+  descending?: boolean;
+  offset?: number;
+  cursor?: string;
+  /** Defaults to 100, at most 1000 */
+  limit?: number;
+}
+
+export interface ArtifactPage<T> {
+  items: T[];
+  total: number;
+  /** Absent on the last page */
+  nextCursor?: string | null;
+}
+
+export type JobKind = "import" | "migration" | "reconcile";
+
+export type JobResolution = "resume" | "roll_back" | "discard";
+
+/** A long operation that was cut short by a crash or an error. */
+export interface JobRecord {
+  id: string;
+  kind: JobKind;
+  description: string;
+  status: "running" | "interrupted";
+  /** Steps completed at the last checkpoint */
+  completed: number;
+  total: number;
+  /** Reconciliations can only be resumed */
+  canRollBack: boolean;
+  /** Unix seconds */
+  startedAt: number;
+  /** Unix seconds */
+  updatedAt: number;
+}
+
+/** A named set of rules, commands, skills and settings with storage of its own. */
+export interface Workspace {
+  /** `default` for the original storage locations */
+  id: string;
+  name: string;
+  /** Unix seconds */
+  createdAt: number;
+}
+
+export interface WorkspaceList {
+  /** Id of the workspace the app is running in */
+  active: string;
+  workspaces: Workspace[];
+}
+
+export interface CounterSample {
+  name: string;
+  help: string;
+  labels: Record<string, string>;
+  value: number;
+}
+
+export interface HistogramSample {
+  name: string;
+  help: string;
+  labels: Record<string, string>;
+  count: number;
+  /** Total of all observations, in seconds */
+  sum: number;
+  /** Cumulative counts per upper bound in seconds */
+  buckets: { le: number; count: number }[];
+}
+
+/** Counters and timings collected since the app started. */
+export interface MetricsSnapshot {
+  since: number;
+  counters: CounterSample[];
+  histograms: HistogramSample[];
+}
+
+/** RuleWeaver's git hooks in a registered repository. */
+export interface GitHookStatus {
+  repoRoot: string;
+  hooksDir: string;
+  installed: string[];
+  /** Hooks of the same name owned by other tools, which RuleWeaver leaves alone. */
+  foreign: string[];
+}
+
+/** A repository's committed `.ruleweaver.toml`. */
+export interface ProjectConfig {
+  rules: {
+    /** Rule names or ids */
+    include: string[];
+    exclude: string[];
+    tags: string[];
+  };
+  /** Replaces each rule's own adapters in this repository */
+  adapters?: string[] | null;
+  /** Language of the rule variants written into this repository, e.g. `ja` */
+  language?: string | null;
+  variables: Record<string, string>;
+}
+
+/** User preferences; each field is stored under its snake_case settings key. */
+export interface AppSettings {
+  /** Per-tool switches by adapter id; tools missing from the map are on */
+  adapterSettings: Record<string, boolean>;
+  defaultAdapters: string[];
+  localRulePaths: string[];
+  mcpAutoStart: boolean;
+  minimizeToTray: boolean;
+  startupDriftCheck: boolean;
+  driftMonitorEnabled: boolean;
+  ruleWatcherMode: "auto" | "native" | "poll";
+  autoBackupEnabled: boolean;
+  autoBackupRetention: number;
+  /** 0 keeps every row */
+  logRetentionMaxRows: number;
+  /** 0 keeps every row */
+  logRetentionMaxAgeDays: number;
+  artifactRetentionMaxAgeDays: number;
+  exportSnapshotEnabled: boolean;
+  exportSnapshotDir: string | null;
+  exportSnapshotIntervalHours: number;
+  exportSnapshotRetention: number;
+  metricsPrometheusEnabled: boolean;
+  ruleCatalogUrl: string | null;
+  skillTemplateIndexUrl: string | null;
+  mcpSecretsAllowlist: string[];
+  /** Sign configuration exports with this machine's key */
+  signExports: boolean;
+  /** Hex public keys whose signed exports import without a warning */
+  trustedExportKeys: string[];
+  /** Redact well-known credential formats from command output */
+  redactBuiltinPatterns: boolean;
+  /** Regular expressions whose matches are redacted from command output */
+  redactionPatterns: string[];
+  /** Environment variables whose values are redacted from command output */
+  redactedEnvVars: string[];
+  /** Base URL of an OpenAI-compatible API used to draft, condense and merge rules */
+  aiEndpoint: string | null;
+  aiModel: string | null;
+  /** Vault secret holding the AI endpoint's API key */
+  aiApiKeySecret: string | null;
+  /** Base URL of an OpenAI-compatible API computing embeddings; the AI endpoint if unset */
+  embeddingEndpoint: string | null;
+  embeddingModel: string | null;
+  /** Vault secret holding the embedding endpoint's API key */
+  embeddingApiKeySecret: string | null;
+  /** Language of the rule variants written for each tool, by adapter id */
+  adapterLanguages: Record<string, string>;
+}
+
+/** Payload of the `settings-changed` event. */
+export interface SettingsChanged {
+  settings: AppSettings;
+  /** Settings keys whose stored value changed */
+  changed: string[];
+}
+
+export type ExportIntegrity = "unverified" | "checksummed" | "trusted" | "unknown_signer";
+
+/** Whether a configuration file can be trusted. Modified files fail to load instead. */
+export interface ExportVerification {
+  integrity: ExportIntegrity;
+  /** Hex public key of the signer */
+  signer: string | null;
+}
+
+/** `tree` writes a directory with one file per rule, command and skill */
+export type ExportFormat = "json" | "yaml" | "tree";
+
+/** Which part of the configuration an export or import covers; empty lists mean "all" */
+export interface ExportFilter {
+  /** Rule, command and skill ids */
+  ids?: string[];
+  tags?: string[];
+  /** Applies to rules only */
+  scope?: Scope | null;
+  /** Rules enabled for at least one of these adapters */
+  adapters?: AdapterType[];
+  includeRules?: boolean;
+  includeCommands?: boolean;
+  includeSkills?: boolean;
+  includeSettings?: boolean;
+  /** Sync hashes, the rule file index and import sources */
+  includeState?: boolean;
+}
+
+export type SensitiveKind = "credential" | "private_url" | "high_entropy";
+
+/** Content in a rule or command that looks like it should not leave this machine. */
+export interface SensitiveFinding {
+  /** Identifies this value in this artifact, for the allowlist */
+  fingerprint: string;
+  kind: SensitiveKind;
+  /** What matched, such as "GitHub Token" */
+  label: string;
+  artifactType: "rule" | "command";
+  artifactId: string;
+  artifactName: string;
+  line: number;
+  /** The match, masked unless it is a URL */
+  excerpt: string;
+}
+
+/** A dry-run plan held by the backend until it is applied or expires. */
+export interface PreparedReconcilePlan {
+  id: string;
+  plan: ReconcilePlan;
+  expiresAt: string;
+}
+
+/** Every planned path is applied unless listed in `excludedPaths`. */
+export interface ReconcilePlanSelection {
+  excludedPaths: string[];
+}
+
+export interface ReconcileResult {
+  success: boolean;
+  created: number;
+  updated: number;
+  removed: number;
+  unchanged: number;
+  errors: string[];
+  warnings: string[];
+}
+
 export interface StatusSummary {
   total: number;
   synced: number;
@@ -47,9 +488,55 @@ export interface StatusSummary {
   missing: number;
   conflicted: number;
   unsupported: number;
+  mcpFallback: number;
   error: number;
 }
 
+export interface AdapterStatus {
+  adapter: AdapterType;
+  name: string;
+  enabled: boolean;
+  /** Whether the tool was detected on this machine */
+  installed: boolean;
+  /** Unix seconds; absent if sync has never written for this adapter */
+  lastSyncedAt?: number;
+  files: number;
+}
+
+export interface McpClientInfo {
+  name: string;
+  version?: string;
+  /** Unix seconds */
+  connectedAt: number;
+  /** Unix seconds */
+  lastSeen: number;
+  requests: number;
+}
+
+export interface UnifiedStatus {
+  /** Unix seconds */
+  generatedAt: number;
+  lastSync?: SyncHistoryEntry;
+  adapters: AdapterStatus[];
+  drift: {
+    monitorEnabled: boolean;
+    summary?: DriftSummary;
+    error?: string;
+  };
+  mcp: Omit<McpStatus, "apiToken"> & { clients: McpClientInfo[] };
+  watcher: {
+    healthy: boolean;
+    running: boolean;
+    mode: "auto" | "native" | "poll";
+    watched: { path: string; backend: "native" | "poll" }[];
+    unavailable: string[];
+  };
+  conflicts: {
+    pending: Conflict[];
+    error?: string;
+  };
+}
+
 export const ARTIFACT_TYPE_LABELS: Record<ArtifactType, string> = {
   rule: "Rule",
   command_stub: "Command Stub",
@@ -86,6 +573,11 @@ export const SYNC_STATUS_CONFIG: Record<
     color: "text-gray-500",
     bgColor: "bg-gray-500/10 border-gray-500/20",
   },
+  mcp_fallback: {
+    label: "MCP Fallback",
+    color: "text-blue-500",
+    bgColor: "bg-blue-500/10 border-blue-500/20",
+  },
   error: {
     label: "Error",
     color: "text-red-600",
@@ -0,0 +1,72 @@
+import type { CommandArgument } from "./command";
+
+export type StepFailurePolicy = "stop" | "skip_dependents" | "continue";
+
+export interface WorkflowStep {
+  id: string;
+  commandId: string;
+  dependsOn?: string[];
+  /** Values may use {{input.NAME}}, {{steps.ID.stdout}} or {{steps.ID.exit_code}} */
+  args?: Record<string, string>;
+  onFailure?: StepFailurePolicy;
+}
+
+export interface Workflow {
+  id: string;
+  name: string;
+  description: string;
+  steps: WorkflowStep[];
+  arguments: CommandArgument[];
+  exposeViaMcp: boolean;
+  generateSlashCommands: boolean;
+  slashCommandAdapters: string[];
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface CreateWorkflowInput {
+  name: string;
+  description?: string;
+  steps: WorkflowStep[];
+  arguments?: CommandArgument[];
+  exposeViaMcp?: boolean;
+  generateSlashCommands?: boolean;
+  slashCommandAdapters?: string[];
+}
+
+export interface UpdateWorkflowInput {
+  name?: string;
+  description?: string;
+  steps?: WorkflowStep[];
+  arguments?: CommandArgument[];
+  exposeViaMcp?: boolean;
+  generateSlashCommands?: boolean;
+  slashCommandAdapters?: string[];
+}
+
+export type WorkflowStepStatus = "succeeded" | "failed" | "skipped";
+
+export interface WorkflowStepResult {
+  stepId: string;
+  commandId: string;
+  commandName: string;
+  status: WorkflowStepStatus;
+  exitCode: number | null;
+  stdout: string;
+  stderr: string;
+  durationMs: number;
+  message?: string;
+}
+
+export interface WorkflowRunResult {
+  workflowId: string;
+  success: boolean;
+  steps: WorkflowStepResult[];
+  durationMs: number;
+}
+
+export interface WorkflowStepEvent {
+  executionId: string;
+  workflowId: string;
+  result: WorkflowStepResult;
+}